@@ -17166,6 +17166,263 @@ impl Default for VkFormat {
 	}
 }
 
+impl TryFrom<u32> for VkFormat {
+	type Error = u32;
+
+	fn try_from(v: u32) -> Result<Self, u32> {
+		Ok(match v {
+			0          => Self::VK_FORMAT_UNDEFINED,
+			1          => Self::VK_FORMAT_R4G4_UNORM_PACK8,
+			2          => Self::VK_FORMAT_R4G4B4A4_UNORM_PACK16,
+			3          => Self::VK_FORMAT_B4G4R4A4_UNORM_PACK16,
+			4          => Self::VK_FORMAT_R5G6B5_UNORM_PACK16,
+			5          => Self::VK_FORMAT_B5G6R5_UNORM_PACK16,
+			6          => Self::VK_FORMAT_R5G5B5A1_UNORM_PACK16,
+			7          => Self::VK_FORMAT_B5G5R5A1_UNORM_PACK16,
+			8          => Self::VK_FORMAT_A1R5G5B5_UNORM_PACK16,
+			9          => Self::VK_FORMAT_R8_UNORM,
+			10         => Self::VK_FORMAT_R8_SNORM,
+			11         => Self::VK_FORMAT_R8_USCALED,
+			12         => Self::VK_FORMAT_R8_SSCALED,
+			13         => Self::VK_FORMAT_R8_UINT,
+			14         => Self::VK_FORMAT_R8_SINT,
+			15         => Self::VK_FORMAT_R8_SRGB,
+			16         => Self::VK_FORMAT_R8G8_UNORM,
+			17         => Self::VK_FORMAT_R8G8_SNORM,
+			18         => Self::VK_FORMAT_R8G8_USCALED,
+			19         => Self::VK_FORMAT_R8G8_SSCALED,
+			20         => Self::VK_FORMAT_R8G8_UINT,
+			21         => Self::VK_FORMAT_R8G8_SINT,
+			22         => Self::VK_FORMAT_R8G8_SRGB,
+			23         => Self::VK_FORMAT_R8G8B8_UNORM,
+			24         => Self::VK_FORMAT_R8G8B8_SNORM,
+			25         => Self::VK_FORMAT_R8G8B8_USCALED,
+			26         => Self::VK_FORMAT_R8G8B8_SSCALED,
+			27         => Self::VK_FORMAT_R8G8B8_UINT,
+			28         => Self::VK_FORMAT_R8G8B8_SINT,
+			29         => Self::VK_FORMAT_R8G8B8_SRGB,
+			30         => Self::VK_FORMAT_B8G8R8_UNORM,
+			31         => Self::VK_FORMAT_B8G8R8_SNORM,
+			32         => Self::VK_FORMAT_B8G8R8_USCALED,
+			33         => Self::VK_FORMAT_B8G8R8_SSCALED,
+			34         => Self::VK_FORMAT_B8G8R8_UINT,
+			35         => Self::VK_FORMAT_B8G8R8_SINT,
+			36         => Self::VK_FORMAT_B8G8R8_SRGB,
+			37         => Self::VK_FORMAT_R8G8B8A8_UNORM,
+			38         => Self::VK_FORMAT_R8G8B8A8_SNORM,
+			39         => Self::VK_FORMAT_R8G8B8A8_USCALED,
+			40         => Self::VK_FORMAT_R8G8B8A8_SSCALED,
+			41         => Self::VK_FORMAT_R8G8B8A8_UINT,
+			42         => Self::VK_FORMAT_R8G8B8A8_SINT,
+			43         => Self::VK_FORMAT_R8G8B8A8_SRGB,
+			44         => Self::VK_FORMAT_B8G8R8A8_UNORM,
+			45         => Self::VK_FORMAT_B8G8R8A8_SNORM,
+			46         => Self::VK_FORMAT_B8G8R8A8_USCALED,
+			47         => Self::VK_FORMAT_B8G8R8A8_SSCALED,
+			48         => Self::VK_FORMAT_B8G8R8A8_UINT,
+			49         => Self::VK_FORMAT_B8G8R8A8_SINT,
+			50         => Self::VK_FORMAT_B8G8R8A8_SRGB,
+			51         => Self::VK_FORMAT_A8B8G8R8_UNORM_PACK32,
+			52         => Self::VK_FORMAT_A8B8G8R8_SNORM_PACK32,
+			53         => Self::VK_FORMAT_A8B8G8R8_USCALED_PACK32,
+			54         => Self::VK_FORMAT_A8B8G8R8_SSCALED_PACK32,
+			55         => Self::VK_FORMAT_A8B8G8R8_UINT_PACK32,
+			56         => Self::VK_FORMAT_A8B8G8R8_SINT_PACK32,
+			57         => Self::VK_FORMAT_A8B8G8R8_SRGB_PACK32,
+			58         => Self::VK_FORMAT_A2R10G10B10_UNORM_PACK32,
+			59         => Self::VK_FORMAT_A2R10G10B10_SNORM_PACK32,
+			60         => Self::VK_FORMAT_A2R10G10B10_USCALED_PACK32,
+			61         => Self::VK_FORMAT_A2R10G10B10_SSCALED_PACK32,
+			62         => Self::VK_FORMAT_A2R10G10B10_UINT_PACK32,
+			63         => Self::VK_FORMAT_A2R10G10B10_SINT_PACK32,
+			64         => Self::VK_FORMAT_A2B10G10R10_UNORM_PACK32,
+			65         => Self::VK_FORMAT_A2B10G10R10_SNORM_PACK32,
+			66         => Self::VK_FORMAT_A2B10G10R10_USCALED_PACK32,
+			67         => Self::VK_FORMAT_A2B10G10R10_SSCALED_PACK32,
+			68         => Self::VK_FORMAT_A2B10G10R10_UINT_PACK32,
+			69         => Self::VK_FORMAT_A2B10G10R10_SINT_PACK32,
+			70         => Self::VK_FORMAT_R16_UNORM,
+			71         => Self::VK_FORMAT_R16_SNORM,
+			72         => Self::VK_FORMAT_R16_USCALED,
+			73         => Self::VK_FORMAT_R16_SSCALED,
+			74         => Self::VK_FORMAT_R16_UINT,
+			75         => Self::VK_FORMAT_R16_SINT,
+			76         => Self::VK_FORMAT_R16_SFLOAT,
+			77         => Self::VK_FORMAT_R16G16_UNORM,
+			78         => Self::VK_FORMAT_R16G16_SNORM,
+			79         => Self::VK_FORMAT_R16G16_USCALED,
+			80         => Self::VK_FORMAT_R16G16_SSCALED,
+			81         => Self::VK_FORMAT_R16G16_UINT,
+			82         => Self::VK_FORMAT_R16G16_SINT,
+			83         => Self::VK_FORMAT_R16G16_SFLOAT,
+			84         => Self::VK_FORMAT_R16G16B16_UNORM,
+			85         => Self::VK_FORMAT_R16G16B16_SNORM,
+			86         => Self::VK_FORMAT_R16G16B16_USCALED,
+			87         => Self::VK_FORMAT_R16G16B16_SSCALED,
+			88         => Self::VK_FORMAT_R16G16B16_UINT,
+			89         => Self::VK_FORMAT_R16G16B16_SINT,
+			90         => Self::VK_FORMAT_R16G16B16_SFLOAT,
+			91         => Self::VK_FORMAT_R16G16B16A16_UNORM,
+			92         => Self::VK_FORMAT_R16G16B16A16_SNORM,
+			93         => Self::VK_FORMAT_R16G16B16A16_USCALED,
+			94         => Self::VK_FORMAT_R16G16B16A16_SSCALED,
+			95         => Self::VK_FORMAT_R16G16B16A16_UINT,
+			96         => Self::VK_FORMAT_R16G16B16A16_SINT,
+			97         => Self::VK_FORMAT_R16G16B16A16_SFLOAT,
+			98         => Self::VK_FORMAT_R32_UINT,
+			99         => Self::VK_FORMAT_R32_SINT,
+			100        => Self::VK_FORMAT_R32_SFLOAT,
+			101        => Self::VK_FORMAT_R32G32_UINT,
+			102        => Self::VK_FORMAT_R32G32_SINT,
+			103        => Self::VK_FORMAT_R32G32_SFLOAT,
+			104        => Self::VK_FORMAT_R32G32B32_UINT,
+			105        => Self::VK_FORMAT_R32G32B32_SINT,
+			106        => Self::VK_FORMAT_R32G32B32_SFLOAT,
+			107        => Self::VK_FORMAT_R32G32B32A32_UINT,
+			108        => Self::VK_FORMAT_R32G32B32A32_SINT,
+			109        => Self::VK_FORMAT_R32G32B32A32_SFLOAT,
+			110        => Self::VK_FORMAT_R64_UINT,
+			111        => Self::VK_FORMAT_R64_SINT,
+			112        => Self::VK_FORMAT_R64_SFLOAT,
+			113        => Self::VK_FORMAT_R64G64_UINT,
+			114        => Self::VK_FORMAT_R64G64_SINT,
+			115        => Self::VK_FORMAT_R64G64_SFLOAT,
+			116        => Self::VK_FORMAT_R64G64B64_UINT,
+			117        => Self::VK_FORMAT_R64G64B64_SINT,
+			118        => Self::VK_FORMAT_R64G64B64_SFLOAT,
+			119        => Self::VK_FORMAT_R64G64B64A64_UINT,
+			120        => Self::VK_FORMAT_R64G64B64A64_SINT,
+			121        => Self::VK_FORMAT_R64G64B64A64_SFLOAT,
+			122        => Self::VK_FORMAT_B10G11R11_UFLOAT_PACK32,
+			123        => Self::VK_FORMAT_E5B9G9R9_UFLOAT_PACK32,
+			124        => Self::VK_FORMAT_D16_UNORM,
+			125        => Self::VK_FORMAT_X8_D24_UNORM_PACK32,
+			126        => Self::VK_FORMAT_D32_SFLOAT,
+			127        => Self::VK_FORMAT_S8_UINT,
+			128        => Self::VK_FORMAT_D16_UNORM_S8_UINT,
+			129        => Self::VK_FORMAT_D24_UNORM_S8_UINT,
+			130        => Self::VK_FORMAT_D32_SFLOAT_S8_UINT,
+			131        => Self::VK_FORMAT_BC1_RGB_UNORM_BLOCK,
+			132        => Self::VK_FORMAT_BC1_RGB_SRGB_BLOCK,
+			133        => Self::VK_FORMAT_BC1_RGBA_UNORM_BLOCK,
+			134        => Self::VK_FORMAT_BC1_RGBA_SRGB_BLOCK,
+			135        => Self::VK_FORMAT_BC2_UNORM_BLOCK,
+			136        => Self::VK_FORMAT_BC2_SRGB_BLOCK,
+			137        => Self::VK_FORMAT_BC3_UNORM_BLOCK,
+			138        => Self::VK_FORMAT_BC3_SRGB_BLOCK,
+			139        => Self::VK_FORMAT_BC4_UNORM_BLOCK,
+			140        => Self::VK_FORMAT_BC4_SNORM_BLOCK,
+			141        => Self::VK_FORMAT_BC5_UNORM_BLOCK,
+			142        => Self::VK_FORMAT_BC5_SNORM_BLOCK,
+			143        => Self::VK_FORMAT_BC6H_UFLOAT_BLOCK,
+			144        => Self::VK_FORMAT_BC6H_SFLOAT_BLOCK,
+			145        => Self::VK_FORMAT_BC7_UNORM_BLOCK,
+			146        => Self::VK_FORMAT_BC7_SRGB_BLOCK,
+			147        => Self::VK_FORMAT_ETC2_R8G8B8_UNORM_BLOCK,
+			148        => Self::VK_FORMAT_ETC2_R8G8B8_SRGB_BLOCK,
+			149        => Self::VK_FORMAT_ETC2_R8G8B8A1_UNORM_BLOCK,
+			150        => Self::VK_FORMAT_ETC2_R8G8B8A1_SRGB_BLOCK,
+			151        => Self::VK_FORMAT_ETC2_R8G8B8A8_UNORM_BLOCK,
+			152        => Self::VK_FORMAT_ETC2_R8G8B8A8_SRGB_BLOCK,
+			153        => Self::VK_FORMAT_EAC_R11_UNORM_BLOCK,
+			154        => Self::VK_FORMAT_EAC_R11_SNORM_BLOCK,
+			155        => Self::VK_FORMAT_EAC_R11G11_UNORM_BLOCK,
+			156        => Self::VK_FORMAT_EAC_R11G11_SNORM_BLOCK,
+			157        => Self::VK_FORMAT_ASTC_4x4_UNORM_BLOCK,
+			158        => Self::VK_FORMAT_ASTC_4x4_SRGB_BLOCK,
+			159        => Self::VK_FORMAT_ASTC_5x4_UNORM_BLOCK,
+			160        => Self::VK_FORMAT_ASTC_5x4_SRGB_BLOCK,
+			161        => Self::VK_FORMAT_ASTC_5x5_UNORM_BLOCK,
+			162        => Self::VK_FORMAT_ASTC_5x5_SRGB_BLOCK,
+			163        => Self::VK_FORMAT_ASTC_6x5_UNORM_BLOCK,
+			164        => Self::VK_FORMAT_ASTC_6x5_SRGB_BLOCK,
+			165        => Self::VK_FORMAT_ASTC_6x6_UNORM_BLOCK,
+			166        => Self::VK_FORMAT_ASTC_6x6_SRGB_BLOCK,
+			167        => Self::VK_FORMAT_ASTC_8x5_UNORM_BLOCK,
+			168        => Self::VK_FORMAT_ASTC_8x5_SRGB_BLOCK,
+			169        => Self::VK_FORMAT_ASTC_8x6_UNORM_BLOCK,
+			170        => Self::VK_FORMAT_ASTC_8x6_SRGB_BLOCK,
+			171        => Self::VK_FORMAT_ASTC_8x8_UNORM_BLOCK,
+			172        => Self::VK_FORMAT_ASTC_8x8_SRGB_BLOCK,
+			173        => Self::VK_FORMAT_ASTC_10x5_UNORM_BLOCK,
+			174        => Self::VK_FORMAT_ASTC_10x5_SRGB_BLOCK,
+			175        => Self::VK_FORMAT_ASTC_10x6_UNORM_BLOCK,
+			176        => Self::VK_FORMAT_ASTC_10x6_SRGB_BLOCK,
+			177        => Self::VK_FORMAT_ASTC_10x8_UNORM_BLOCK,
+			178        => Self::VK_FORMAT_ASTC_10x8_SRGB_BLOCK,
+			179        => Self::VK_FORMAT_ASTC_10x10_UNORM_BLOCK,
+			180        => Self::VK_FORMAT_ASTC_10x10_SRGB_BLOCK,
+			181        => Self::VK_FORMAT_ASTC_12x10_UNORM_BLOCK,
+			182        => Self::VK_FORMAT_ASTC_12x10_SRGB_BLOCK,
+			183        => Self::VK_FORMAT_ASTC_12x12_UNORM_BLOCK,
+			184        => Self::VK_FORMAT_ASTC_12x12_SRGB_BLOCK,
+			1000156000 => Self::VK_FORMAT_G8B8G8R8_422_UNORM,
+			1000156001 => Self::VK_FORMAT_B8G8R8G8_422_UNORM,
+			1000156002 => Self::VK_FORMAT_G8_B8_R8_3PLANE_420_UNORM,
+			1000156003 => Self::VK_FORMAT_G8_B8R8_2PLANE_420_UNORM,
+			1000156004 => Self::VK_FORMAT_G8_B8_R8_3PLANE_422_UNORM,
+			1000156005 => Self::VK_FORMAT_G8_B8R8_2PLANE_422_UNORM,
+			1000156006 => Self::VK_FORMAT_G8_B8_R8_3PLANE_444_UNORM,
+			1000156007 => Self::VK_FORMAT_R10X6_UNORM_PACK16,
+			1000156008 => Self::VK_FORMAT_R10X6G10X6_UNORM_2PACK16,
+			1000156009 => Self::VK_FORMAT_R10X6G10X6B10X6A10X6_UNORM_4PACK16,
+			1000156010 => Self::VK_FORMAT_G10X6B10X6G10X6R10X6_422_UNORM_4PACK16,
+			1000156011 => Self::VK_FORMAT_B10X6G10X6R10X6G10X6_422_UNORM_4PACK16,
+			1000156012 => Self::VK_FORMAT_G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16,
+			1000156013 => Self::VK_FORMAT_G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16,
+			1000156014 => Self::VK_FORMAT_G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16,
+			1000156015 => Self::VK_FORMAT_G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16,
+			1000156016 => Self::VK_FORMAT_G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16,
+			1000156017 => Self::VK_FORMAT_R12X4_UNORM_PACK16,
+			1000156018 => Self::VK_FORMAT_R12X4G12X4_UNORM_2PACK16,
+			1000156019 => Self::VK_FORMAT_R12X4G12X4B12X4A12X4_UNORM_4PACK16,
+			1000156020 => Self::VK_FORMAT_G12X4B12X4G12X4R12X4_422_UNORM_4PACK16,
+			1000156021 => Self::VK_FORMAT_B12X4G12X4R12X4G12X4_422_UNORM_4PACK16,
+			1000156022 => Self::VK_FORMAT_G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16,
+			1000156023 => Self::VK_FORMAT_G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16,
+			1000156024 => Self::VK_FORMAT_G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16,
+			1000156025 => Self::VK_FORMAT_G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16,
+			1000156026 => Self::VK_FORMAT_G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16,
+			1000156027 => Self::VK_FORMAT_G16B16G16R16_422_UNORM,
+			1000156028 => Self::VK_FORMAT_B16G16R16G16_422_UNORM,
+			1000156029 => Self::VK_FORMAT_G16_B16_R16_3PLANE_420_UNORM,
+			1000156030 => Self::VK_FORMAT_G16_B16R16_2PLANE_420_UNORM,
+			1000156031 => Self::VK_FORMAT_G16_B16_R16_3PLANE_422_UNORM,
+			1000156032 => Self::VK_FORMAT_G16_B16R16_2PLANE_422_UNORM,
+			1000156033 => Self::VK_FORMAT_G16_B16_R16_3PLANE_444_UNORM,
+			1000054000 => Self::VK_FORMAT_PVRTC1_2BPP_UNORM_BLOCK_IMG,
+			1000054001 => Self::VK_FORMAT_PVRTC1_4BPP_UNORM_BLOCK_IMG,
+			1000054002 => Self::VK_FORMAT_PVRTC2_2BPP_UNORM_BLOCK_IMG,
+			1000054003 => Self::VK_FORMAT_PVRTC2_4BPP_UNORM_BLOCK_IMG,
+			1000054004 => Self::VK_FORMAT_PVRTC1_2BPP_SRGB_BLOCK_IMG,
+			1000054005 => Self::VK_FORMAT_PVRTC1_4BPP_SRGB_BLOCK_IMG,
+			1000054006 => Self::VK_FORMAT_PVRTC2_2BPP_SRGB_BLOCK_IMG,
+			1000054007 => Self::VK_FORMAT_PVRTC2_4BPP_SRGB_BLOCK_IMG,
+			1000066000 => Self::VK_FORMAT_ASTC_4x4_SFLOAT_BLOCK_EXT,
+			1000066001 => Self::VK_FORMAT_ASTC_5x4_SFLOAT_BLOCK_EXT,
+			1000066002 => Self::VK_FORMAT_ASTC_5x5_SFLOAT_BLOCK_EXT,
+			1000066003 => Self::VK_FORMAT_ASTC_6x5_SFLOAT_BLOCK_EXT,
+			1000066004 => Self::VK_FORMAT_ASTC_6x6_SFLOAT_BLOCK_EXT,
+			1000066005 => Self::VK_FORMAT_ASTC_8x5_SFLOAT_BLOCK_EXT,
+			1000066006 => Self::VK_FORMAT_ASTC_8x6_SFLOAT_BLOCK_EXT,
+			1000066007 => Self::VK_FORMAT_ASTC_8x8_SFLOAT_BLOCK_EXT,
+			1000066008 => Self::VK_FORMAT_ASTC_10x5_SFLOAT_BLOCK_EXT,
+			1000066009 => Self::VK_FORMAT_ASTC_10x6_SFLOAT_BLOCK_EXT,
+			1000066010 => Self::VK_FORMAT_ASTC_10x8_SFLOAT_BLOCK_EXT,
+			1000066011 => Self::VK_FORMAT_ASTC_10x10_SFLOAT_BLOCK_EXT,
+			1000066012 => Self::VK_FORMAT_ASTC_12x10_SFLOAT_BLOCK_EXT,
+			1000066013 => Self::VK_FORMAT_ASTC_12x12_SFLOAT_BLOCK_EXT,
+			1000330000 => Self::VK_FORMAT_G8_B8R8_2PLANE_444_UNORM_EXT,
+			1000330001 => Self::VK_FORMAT_G10X6_B10X6R10X6_2PLANE_444_UNORM_3PACK16_EXT,
+			1000330002 => Self::VK_FORMAT_G12X4_B12X4R12X4_2PLANE_444_UNORM_3PACK16_EXT,
+			1000330003 => Self::VK_FORMAT_G16_B16R16_2PLANE_444_UNORM_EXT,
+			1000340000 => Self::VK_FORMAT_A4R4G4B4_UNORM_PACK16_EXT,
+			1000340001 => Self::VK_FORMAT_A4B4G4R4_UNORM_PACK16_EXT,
+			v          => return Err(v)
+		})
+	}
+}
+
 pub const VK_FORMAT_G8B8G8R8_422_UNORM_KHR                                                : VkFormat = VK_FORMAT_G8B8G8R8_422_UNORM;
 pub const VK_FORMAT_B8G8R8G8_422_UNORM_KHR                                                : VkFormat = VK_FORMAT_B8G8R8G8_422_UNORM;
 pub const VK_FORMAT_G8_B8_R8_3PLANE_420_UNORM_KHR                                         : VkFormat = VK_FORMAT_G8_B8_R8_3PLANE_420_UNORM;
@@ -27,7 +27,7 @@ use {
 	wayland::*,
 	pipewire::*,
 	vk::*,
-	engine_core::*,
+	engine_core::{*, Time},
 	math::*,
 	ecs::*,
 	scene::*,
@@ -92,12 +92,58 @@ impl XdgToplevelListener for TopLevelListener  {
 }
 
 #[derive(Clone)]
-struct FrameListener(Arc<Box<WlSurface, WlAlloc>>, Arc<Mutex<TargetRootContext<Arc<World>>>>);
+struct FrameListener(
+	Arc<Box<WlSurface, WlAlloc>>,
+	Arc<Mutex<TargetRootContext<Arc<World>>>>,
+	Arc<Mutex<GameLoop>>,
+	Arc<World>
+);
 
 impl WlCallbackListener for FrameListener {
 	fn done(&self, proxy: &mut WlCallback, callback_data: u32) {
 		self.0.frame().unwrap().set_listener(self.clone()).unwrap();
-		self.1.lock().unwrap().submit().unwrap();
+		self.2.lock().unwrap().frame(
+			std::time::Instant::now(),
+			|time| tick(&self.3, time),
+			|time| {
+				frame(&self.3, time);
+				self.1.lock().unwrap().submit().unwrap()
+			}
+		);
+	}
+}
+
+/// Publishes the current `Time` to the world, so that systems can read it like any other
+/// singleton component.
+fn update_time(world: &World, time: &Time) {
+	if let Some(resource) = world.query::<&mut Time>().iter().next() {
+		*resource = *time;
+	}
+}
+
+/// One fixed-rate simulation step.
+fn tick(world: &World, time: &Time) {
+	update_time(world, time);
+	
+	for (transform, previous) in world.query::<(&Transform, &mut PreviousTransform)>().iter() {
+		store_previous_transform(transform, previous);
+	}
+	
+	for (velocity, acceleration) in world.query::<(&mut Velocity, &Acceleration)>().iter() {
+		update_velocity(velocity, acceleration, time);
+	}
+	
+	for (transform, velocity) in world.query::<(&mut Transform, &Velocity)>().iter() {
+		update_transform(transform, velocity, time);
+	}
+}
+
+/// Per-frame updates before rendering, `time.alpha` blends between the last two ticks.
+fn frame(world: &World, time: &Time) {
+	update_time(world, time);
+	
+	for (transform, previous, local) in world.query::<(&Transform, &PreviousTransform, &mut LocalTransform)>().iter() {
+		interpolate_local_transform(transform, previous, local, time);
 	}
 }
 
@@ -152,6 +198,7 @@ async fn run() {
 	let scene = Arc::new(World::new());
 	let _singleton_entity = scene.add_entity((
 		UiDimensions { surface_size: Vec2(1920, 1080), display_size: Vec2(0, 0) },
+		Time::default(),
 		SceneOptions {
 			sdf_extent: Vec3::from(128),
 			ids_extent: Vec3::from(128),
@@ -208,9 +255,12 @@ async fn run() {
 	
 	// RENDER LOOP
 	
+	let game_loop = Arc::new(Mutex::new(GameLoop::new(60)));
+	game_loop.lock().unwrap().begin_frame(std::time::Instant::now());
+	update_time(&scene, game_loop.lock().unwrap().time());
 	target_ctx.lock().unwrap().submit().unwrap();
 	wl_surface.frame().unwrap().set_listener(FrameListener(
-		wl_surface.clone(), target_ctx.clone())).unwrap();
+		wl_surface.clone(), target_ctx.clone(), game_loop.clone(), scene.clone())).unwrap();
 	
 	while !shutdown.load(Ordering::Relaxed) {
 		display.dispatch().expect("dispatch failed");
@@ -457,7 +507,7 @@ fn render_scene() {
 		SdfSceneInstance { sdf: 1, mat: 3, trans: Mat4::from_translation(Vec3(-0.3, 0.0, -0.4)).rotate(Vec3(0.0, 1.0, 0.0), 25f32.to_radians()) },
 	]);
 	
-	scene.sdfs.push(Box::new(Sphere { radius: 0.1 }));
+	scene.sdfs.push(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 0.1)));
 	scene.sdfs.push(Box::new(Cuboid(Vec3(0.05, 0.5, 0.01))));
 	scene.materials.extend_from_slice(&[
 		(BasicMaterial::new(Vec3(0.75, 0.0, 0.0), Vec3::from(0.0), 0.5, 0.5), 1.0),
@@ -3,4 +3,11 @@ rustup --version
 rustc --version
 cargo --version
 cargo $1 --verbose --color=always --all-features --package $2  $3
-# --message-format=json | rust-ci > $JB_SPACE_FILE_SHARE_PATH/results/$JB_SPACE_EXECUTION_NUMBER/$1
\ No newline at end of file
+# --message-format=json | rust-ci > $JB_SPACE_FILE_SHARE_PATH/results/$JB_SPACE_EXECUTION_NUMBER/$1
+
+# `vk` changes the types of the file-formats crate, so it is checked with and without it
+if [ "$2" = "file-formats" ]; then
+	for features in "ktx2" "ktx2 vk"; do
+		cargo $1 --verbose --color=always --features "$features" --package $2  $3
+	done
+fi
@@ -559,6 +559,22 @@ pub fn gen(
 							writeln!(writer, "}}\n\nimpl Default for {} {{\n\tfn default() -> Self {{\n\t\tSelf::{}\n\t}}\n}}",
 									 name, default)?;
 
+							// formats are stored in files, e.g. KTX2 textures
+							if name == "VkFormat" {
+								let values = enums.iter().filter_map(|e| match e {
+									KhrEnumsVariant::Enum { value: KhrEnumValue::Alias(_), .. } => None,
+									KhrEnumsVariant::Enum { name, value, .. } => Some((name, value.to_string())),
+									_ => None
+								}).collect::<Vec<_>>();
+								let width = values.iter().map(|(_, value)| value.len()).max().unwrap_or(1);
+
+								writeln!(writer, "\nimpl TryFrom<u32> for {} {{\n\ttype Error = u32;\n\n\tfn try_from(v: u32) -> Result<Self, u32> {{\n\t\tOk(match v {{", name)?;
+								for (name, value) in &values {
+									writeln!(writer, "\t\t\t{:<width$} => Self::{},", value, name, width = width)?;
+								}
+								writeln!(writer, "\t\t\t{:<width$} => return Err(v)\n\t\t}})\n\t}}\n}}", "v", width = width)?;
+							}
+
 							let mut first = true;
 							for e in enums {
 								if let KhrEnumsVariant::Enum { name, value: KhrEnumValue::Alias(alias), comment, .. } = e {
@@ -0,0 +1,405 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+use {
+	serde::Deserialize,
+	file_formats::xml,
+	std::{io::{self, Write}, fs}
+};
+
+const HELP: &str = r#"
+mkvgen
+Usage: mkvgen <input file>... [options]
+
+Reads EBML schemas (RFC 8794), like the Matroska schema `ebml_matroska.xml`, and generates the
+element IDs and a struct for every master element that can be read as a whole.
+
+Options:
+--help, -h           - display this help page
+--out=<output file>,
+-o=<output file>     - specify the output file, if no output file is specified, the generated code will be written to `elements.rs`
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Schema {
+	#[serde(rename = "docType")]
+	doc_type: String,
+	#[serde(rename = "element", default)]
+	elements: Vec<Element>
+}
+
+#[derive(Debug, Deserialize)]
+struct Element {
+	name:       String,
+	path:       String,
+	id:         String,
+	#[serde(rename = "type")]
+	ty:         String,
+	default:    Option<String>,
+	#[serde(rename = "minOccurs")]
+	min_occurs: Option<usize>,
+	#[serde(rename = "maxOccurs")]
+	max_occurs: Option<usize>,
+	recursive:  Option<u8>,
+	#[serde(rename = "unknownsizeallowed")]
+	unknown_size_allowed: Option<u8>,
+	#[serde(default)]
+	documentation: Vec<Documentation>,
+	restriction: Option<Restriction>
+}
+
+#[derive(Debug, Deserialize)]
+struct Documentation {
+	purpose: Option<String>,
+	#[serde(rename = "$value", default)]
+	value:   String
+}
+
+#[derive(Debug, Deserialize)]
+struct Restriction {
+	#[serde(rename = "enum", default)]
+	values: Vec<Enum>
+}
+
+#[derive(Debug, Deserialize)]
+struct Enum {
+	value: String,
+	label: String
+}
+
+impl Element {
+	fn is_global(&self) -> bool {
+		self.path.contains("(")
+	}
+	
+	fn is_recursive(&self) -> bool {
+		self.recursive.unwrap_or(0) != 0
+	}
+	
+	/// Elements that may have an unknown size (i.e. `Segment` and `Cluster`) are streamed by the
+	/// demuxer instead of being read as a whole.
+	fn is_streamed(&self) -> bool {
+		self.unknown_size_allowed.unwrap_or(0) != 0
+	}
+	
+	fn parent(&self) -> &str {
+		&self.path[..self.path.rfind('\\').unwrap_or(0)]
+	}
+	
+	fn definition(&self) -> Option<&str> {
+		self.documentation.iter()
+			.find(|doc| doc.purpose.as_deref().is_none_or(|p| p == "definition"))
+			.map(|doc| doc.value.as_str())
+	}
+	
+	fn rust_type(&self) -> String {
+		match self.ty.as_str() {
+			"uinteger"         => "u64".to_string(),
+			"integer" | "date" => "i64".to_string(),
+			"float"            => "f64".to_string(),
+			"string" | "utf-8" => "String".to_string(),
+			"binary"           => "Vec<u8>".to_string(),
+			"master"           => camel_case(&self.name),
+			ty                 => panic!("unknown element type `{}`", ty)
+		}
+	}
+	
+	fn read_expr(&self) -> String {
+		match self.ty.as_str() {
+			"uinteger"         => "read_uint(&mut reader, len)?".to_string(),
+			"integer" | "date" => "read_int(&mut reader, len)?".to_string(),
+			"float"            => "read_float(&mut reader, len)?".to_string(),
+			"string" | "utf-8" => "read_string(&mut reader, len)?".to_string(),
+			"binary"           => "read_binary(&mut reader, len)?".to_string(),
+			_                  => format!("{}::read(&mut reader, len)?", camel_case(&self.name))
+		}
+	}
+	
+	fn default_expr(&self) -> Option<String> {
+		let value = self.default.as_deref()?;
+		Some(match self.ty.as_str() {
+			"uinteger" | "integer" | "date" => value.to_string(),
+			"float"                         => format!("{:?}", parse_float(value)),
+			"string" | "utf-8"              => format!("{:?}.to_string()", value),
+			ty                              => panic!("unsupported default value for type `{}`", ty)
+		})
+	}
+}
+
+fn main() {
+	let mut files = Vec::new();
+	let mut file_out = None;
+	
+	for arg in std::env::args().skip(1) {
+		if arg.starts_with('-') {
+			if arg.starts_with("-o=") || arg.starts_with("--out=") {
+				file_out = Some(arg.trim_start_matches("-o=")
+					.trim_start_matches("--out=").to_string());
+			} else if arg == "-h" || arg == "--help" {
+				println!("{}", HELP);
+				return;
+			} else {
+				println!("ignored unknown option: {}", arg)
+			}
+		} else {
+			files.push(arg);
+		}
+	}
+	
+	let mut elements = Vec::new();
+	let mut doc_types = Vec::new();
+	
+	for file in files {
+		print!("parsing file `{}` ... ", &file);
+		io::stdout().flush().unwrap();
+		let schema: Schema = xml::deserialize(fs::File::open(&file)
+			.map(io::BufReader::new)
+			.expect("failed to open file"))
+			.expect("failed to parse schema");
+		println!("\x1b[32mok\x1b[0m");
+		doc_types.push(schema.doc_type);
+		elements.extend(schema.elements);
+	}
+	
+	let mut writer = fs::OpenOptions::new()
+		.write(true)
+		.create(true)
+		.truncate(true)
+		.open(file_out.as_ref().map_or("elements.rs", String::as_str))
+		.map(io::BufWriter::new)
+		.expect("failed to open output file");
+	
+	print!("generating code ... ");
+	io::stdout().flush().unwrap();
+	gen(&mut writer, &doc_types, &elements).expect("failed to output code");
+	writer.flush().expect("failed to output code");
+	println!("\x1b[32mok\x1b[0m");
+}
+
+fn gen(writer: &mut impl io::Write, doc_types: &[String], elements: &[Element]) -> io::Result<()> {
+	writeln!(writer, "// Generated by mkvgen from the `{}` EBML schemas, do not edit.\n", doc_types.join("`, `"))?;
+	writeln!(writer, "use super::*;\n")?;
+	
+	// ids
+	
+	writeln!(writer, "pub mod id {{\n\tuse crate::ebml::ElementId;\n\t")?;
+	
+	for element in elements {
+		write_doc(writer, "\t", element.definition())?;
+		writeln!(writer, "\tpub const {}: ElementId = {};", upper_case(&element.name), element.id)?;
+	}
+	
+	writeln!(writer, "}}\n")?;
+	writeln!(writer, "/// Returns the name of the element with the given ID, as used in the schema.")?;
+	writeln!(writer, "pub fn element_name(id: ElementId) -> Option<&'static str> {{\n\tSome(match id {{")?;
+	
+	for element in elements {
+		writeln!(writer, "\t\tid::{} => {:?},", upper_case(&element.name), element.name)?;
+	}
+	
+	writeln!(writer, "\t\t_ => return None\n\t}})\n}}")?;
+	
+	// enumerations
+	
+	for element in elements {
+		let Some(restriction) = &element.restriction else { continue };
+		let ty = match element.ty.as_str() {
+			"uinteger"         => "u64",
+			"integer"          => "i64",
+			"string" | "utf-8" => "&str",
+			_                  => continue
+		};
+		
+		writeln!(writer, "\n/// The values of [`{}`](id::{}).", element.name, upper_case(&element.name))?;
+		writeln!(writer, "pub mod {} {{", snake_case(&element.name))?;
+		
+		for value in &restriction.values {
+			let value_expr = match ty {
+				"&str" => format!("{:?}", value.value),
+				_      => value.value.clone()
+			};
+			
+			writeln!(writer, "\tpub const {}: {} = {};", const_name(&value.label), ty, value_expr)?;
+		}
+		
+		writeln!(writer, "}}")?;
+	}
+	
+	// structs
+	
+	for element in elements {
+		if element.ty != "master" || element.is_global() || element.is_streamed() {
+			continue;
+		}
+		
+		let name = camel_case(&element.name);
+		let children = elements.iter()
+			.filter(|child| child.parent() == element.path || (child.path == element.path && child.is_recursive()))
+			.collect::<Vec<_>>();
+		
+		writeln!(writer)?;
+		write_doc(writer, "", element.definition())?;
+		writeln!(writer, "#[derive(Clone, Debug, Default, PartialEq)]\npub struct {} {{", name)?;
+		
+		for child in &children {
+			let ty = match (child.max_occurs, child.min_occurs.unwrap_or(0), &child.default) {
+				(Some(1), 1, _) | (Some(1), _, Some(_)) => child.rust_type(),
+				(Some(1), _, None) => format!("Option<{}>", child.rust_type()),
+				_ => format!("Vec<{}>", child.rust_type())
+			};
+			
+			write_doc(writer, "\t", child.definition())?;
+			writeln!(writer, "\tpub {}: {},", snake_case(&child.name), ty)?;
+		}
+		
+		writeln!(writer, "}}\n\nimpl Master for {} {{\n\tconst ID: ElementId = id::{};\n\t", name, upper_case(&element.name))?;
+		writeln!(writer, "\tfn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {{")?;
+		writeln!(writer, "\t\tlet mut reader = reader.take(len);")?;
+		
+		for child in &children {
+			let init = if child.max_occurs == Some(1) { "None" } else { "Vec::new()" };
+			writeln!(writer, "\t\tlet mut {} = {};", snake_case(&child.name), init)?;
+		}
+		
+		writeln!(writer, "\t\t\n\t\twhile reader.limit() > 0 {{")?;
+		writeln!(writer, "\t\t\tlet (id, len, _) = read_header(&mut reader)?;\n\t\t\tmatch id {{")?;
+		
+		for child in &children {
+			let field = snake_case(&child.name);
+			let id = upper_case(&child.name);
+			
+			if child.max_occurs == Some(1) {
+				writeln!(writer, "\t\t\t\tid::{} => {} = Some({}),", id, field, child.read_expr())?;
+			} else {
+				writeln!(writer, "\t\t\t\tid::{} => {}.push({}),", id, field, child.read_expr())?;
+			}
+		}
+		
+		writeln!(writer, "\t\t\t\t_ => skip(&mut reader, len)?\n\t\t\t}}\n\t\t}}\n\t\t")?;
+		
+		// fill in defaults and check for mandatory elements
+		let mut checks = 0;
+		for child in &children {
+			let field = snake_case(&child.name);
+			let min_occurs = child.min_occurs.unwrap_or(0);
+			
+			checks += 1;
+			match (child.max_occurs, child.default_expr()) {
+				(Some(1), Some(default)) if default.ends_with(')') =>
+					writeln!(writer, "\t\tlet {0} = {0}.unwrap_or_else(|| {1});", field, default)?,
+				(Some(1), Some(default)) =>
+					writeln!(writer, "\t\tlet {0} = {0}.unwrap_or({1});", field, default)?,
+				(Some(1), None) if min_occurs > 0 =>
+					writeln!(writer, "\t\tlet {0} = {0}.ok_or_else(|| missing({1:?}))?;", field, child.name)?,
+				(_, Some(default)) if min_occurs > 0 =>
+					writeln!(writer, "\t\tif {0}.is_empty() {{\n\t\t\t{0}.push({1});\n\t\t}}", field, default)?,
+				_ => checks -= 1
+			}
+		}
+		
+		if checks > 0 {
+			writeln!(writer, "\t\t")?;
+		}
+		
+		writeln!(writer, "\t\tOk(Self {{")?;
+		let fields = children.iter()
+			.map(|child| format!("\t\t\t{}", snake_case(&child.name)))
+			.collect::<Vec<_>>();
+		writeln!(writer, "{}", fields.join(",\n"))?;
+		writeln!(writer, "\t\t}})\n\t}}\n}}")?;
+	}
+	
+	Ok(())
+}
+
+fn write_doc(writer: &mut impl io::Write, pre: &str, doc: Option<&str>) -> io::Result<()> {
+	if let Some(doc) = doc {
+		for line in doc.lines() {
+			writeln!(writer, "{}/// {}", pre, line.trim())?;
+		}
+	}
+	
+	Ok(())
+}
+
+/// Splits a name like `EBMLMaxIDLength` or `CRC-32` into its words.
+fn words(name: &str) -> Vec<String> {
+	let mut words = Vec::new();
+	
+	for part in name.split(|ch: char| !ch.is_ascii_alphanumeric()).filter(|part| !part.is_empty()) {
+		let chars = part.chars().collect::<Vec<_>>();
+		let mut word = String::new();
+		
+		for (i, &ch) in chars.iter().enumerate() {
+			let boundary = i > 0 && ch.is_ascii_uppercase() && (!chars[i - 1].is_ascii_uppercase()
+				|| chars.get(i + 1).is_some_and(char::is_ascii_lowercase));
+			
+			if boundary {
+				words.push(std::mem::take(&mut word));
+			}
+			
+			word.push(ch);
+		}
+		
+		words.push(word);
+	}
+	
+	words
+}
+
+fn camel_case(name: &str) -> String {
+	words(name).iter()
+		.map(|word| word[..1].to_ascii_uppercase() + &word[1..].to_ascii_lowercase())
+		.collect()
+}
+
+fn snake_case(name: &str) -> String {
+	words(name).join("_").to_ascii_lowercase()
+}
+
+fn upper_case(name: &str) -> String {
+	words(name).join("_").to_ascii_uppercase()
+}
+
+fn const_name(label: &str) -> String {
+	let name = upper_case(label);
+	match name.starts_with(|ch: char| ch.is_ascii_digit()) {
+		true  => format!("_{}", name),
+		false => name
+	}
+}
+
+/// Parses a decimal or hexadecimal (`0x1.f4p+12`) floating point literal.
+fn parse_float(s: &str) -> f64 {
+	let Some(hex) = s.strip_prefix("0x") else {
+		return s.parse().expect("invalid float");
+	};
+	
+	let (mantissa, exp) = hex.split_once('p').unwrap_or((hex, "0"));
+	let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	let mut value = u64::from_str_radix(int, 16).expect("invalid float") as f64;
+	
+	for (i, digit) in frac.chars().enumerate() {
+		value += digit.to_digit(16).expect("invalid float") as f64 / 16f64.powi(i as i32 + 1);
+	}
+	
+	value * 2f64.powi(exp.parse().expect("invalid float"))
+}
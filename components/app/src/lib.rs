@@ -35,8 +35,9 @@ mod executor;
 mod logger;
 mod plugin;
 mod registry;
+mod time;
 
-pub use {asset::*, builder::*, plugin::*, registry::*};
+pub use {asset::*, builder::*, plugin::*, registry::*, time::*};
 
 pub type BoxedFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
 
@@ -20,7 +20,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::registry::Registry;
+use {
+    crate::registry::Registry,
+    std::{fmt, io},
+};
+
+/// The symbols a plugin has to export.
+pub const PLUGIN_SYMBOLS: [&str; 3] = ["plugin_name", "plugin_setup", "plugin_destroy"];
 
 pub trait Plugin {
     const NAME: &'static str;
@@ -28,7 +34,84 @@ pub trait Plugin {
     fn setup(&mut self, registry: &Registry);
 }
 
-pub fn load(path: &str) -> Result<ExternalPlugin, libloading::Error> {
+#[derive(Debug)]
+pub enum PluginError {
+    Io(io::Error),
+    /// The file can not be loaded as a plugin, e.g. because it was built for a different
+    /// architecture or does not export the plugin functions.
+    Invalid(String),
+    Library(libloading::Error),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read plugin: {}", e),
+            Self::Invalid(msg) => write!(f, "invalid plugin: {}", msg),
+            Self::Library(e) => write!(f, "failed to load plugin: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+impl From<io::Error> for PluginError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<libloading::Error> for PluginError {
+    fn from(e: libloading::Error) -> Self {
+        Self::Library(e)
+    }
+}
+
+/// Checks that the shared object was built for this platform and exports the plugin functions.
+/// This is done before the library is opened, as opening it already runs its initializers.
+#[cfg(all(unix, not(target_vendor = "apple")))]
+pub fn validate(path: &str) -> Result<(), PluginError> {
+    use file_formats::elf::*;
+
+    let invalid = |msg: &str| Err(PluginError::Invalid(format!("`{}`: {}", path, msg)));
+    let mut reader = ElfReader::new(io::BufReader::new(std::fs::File::open(path)?))?;
+    let header = reader.header;
+
+    let machine = match () {
+        _ if cfg!(target_arch = "x86_64") => Some(Machine::AMD64),
+        _ if cfg!(target_arch = "x86") => Some(Machine::x86),
+        _ if cfg!(target_arch = "aarch64") => Some(Machine::AArch64),
+        _ if cfg!(target_arch = "arm") => Some(Machine::AArch32),
+        _ if cfg!(any(target_arch = "riscv32", target_arch = "riscv64")) => Some(Machine::RiscV),
+        _ if cfg!(target_arch = "powerpc64") => Some(Machine::PPC64),
+        _ if cfg!(target_arch = "powerpc") => Some(Machine::PPC32),
+        _ => None,
+    };
+
+    if header.r#type != Type::Dyn {
+        return invalid("not a shared object");
+    } else if (header.class == Class::_64Bit) != cfg!(target_pointer_width = "64") {
+        return invalid("wrong class");
+    } else if (header.data == Data::Big) != cfg!(target_endian = "big") {
+        return invalid("wrong byte order");
+    } else if machine.is_some_and(|machine| machine != header.machine) {
+        return invalid(&format!("built for {:?}", header.machine));
+    }
+
+    let symbols = reader.symbols(SectionType::DynSym)?;
+    for name in PLUGIN_SYMBOLS {
+        if !symbols.iter().any(|symbol| symbol.name == name && symbol.is_exported()) {
+            return invalid(&format!("`{}` is not exported", name));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn load(path: &str) -> Result<ExternalPlugin, PluginError> {
+    #[cfg(all(unix, not(target_vendor = "apple")))]
+    validate(path)?;
+
     let mut plugin = ExternalPlugin {
         lib:     libloading::Library::new(path)?,
         name:    "",
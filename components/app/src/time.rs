@@ -0,0 +1,273 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::time::{Duration, Instant};
+
+/// Upper bound for the real time that is fed into the accumulator per frame. Without it, a long
+/// stall (e.g. a breakpoint or a blocked compositor) would be followed by a burst of ticks.
+pub const DEFAULT_MAX_FRAME_DELTA: Duration = Duration::from_millis(250);
+
+/// Timing information of the game loop. The render frame and the simulation tick see the same
+/// resource, `delta`/`elapsed` refer to frames, `tick_delta`/`ticks` to the fixed-rate simulation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Time {
+	/// Scaled time since the last frame, zero while paused.
+	pub delta:      Duration,
+	/// Scaled time since the loop started, does not advance while paused.
+	pub elapsed:    Duration,
+	/// Real time since the loop started, advances while paused.
+	pub real:       Duration,
+	/// Fixed duration of one simulation tick.
+	pub tick_delta: Duration,
+	/// Number of simulation ticks executed so far.
+	pub ticks:      u64,
+	/// Number of frames rendered so far.
+	pub frames:     u64,
+	/// Factor for the real time that is applied to `delta` and `elapsed`.
+	pub scale:      f32,
+	pub paused:     bool,
+	/// Interpolation factor in `[0, 1)` between the last two ticks, used when rendering.
+	pub alpha:      f32
+}
+
+impl Time {
+	pub fn new(tick_delta: Duration) -> Self {
+		Self {
+			delta:   Duration::ZERO,
+			elapsed: Duration::ZERO,
+			real:    Duration::ZERO,
+			tick_delta,
+			ticks:   0,
+			frames:  0,
+			scale:   1.0,
+			paused:  false,
+			alpha:   0.0
+		}
+	}
+	
+	pub fn delta_secs(&self) -> f32 {
+		self.delta.as_secs_f32()
+	}
+	
+	pub fn elapsed_secs(&self) -> f32 {
+		self.elapsed.as_secs_f32()
+	}
+	
+	pub fn tick_delta_secs(&self) -> f32 {
+		self.tick_delta.as_secs_f32()
+	}
+	
+	/// The tick rate in Hz.
+	pub fn tick_rate(&self) -> f32 {
+		1.0 / self.tick_delta.as_secs_f32()
+	}
+}
+
+impl Default for Time {
+	fn default() -> Self {
+		Self::new(Duration::from_secs(1) / 60)
+	}
+}
+
+/// A game loop with variable-rate render frames and a fixed-rate simulation tick. Real time is
+/// scaled and accumulated each frame, the accumulator is then drained in `tick_delta` steps.
+/// The remainder is exposed as `Time::alpha` to interpolate between the last two ticks.
+#[derive(Clone, Debug)]
+pub struct GameLoop {
+	time:            Time,
+	accumulator:     Duration,
+	last_frame:      Option<Instant>,
+	max_frame_delta: Duration,
+	max_ticks:       u32
+}
+
+impl GameLoop {
+	/// Creates a new loop with the given tick rate in Hz.
+	pub fn new(tick_rate: u32) -> Self {
+		Self::with_tick_delta(Duration::from_secs(1) / tick_rate.max(1))
+	}
+	
+	pub fn with_tick_delta(tick_delta: Duration) -> Self {
+		assert!(!tick_delta.is_zero(), "tick delta must not be zero");
+		Self {
+			time:            Time::new(tick_delta),
+			accumulator:     Duration::ZERO,
+			last_frame:      None,
+			max_frame_delta: DEFAULT_MAX_FRAME_DELTA,
+			max_ticks:       u32::MAX
+		}
+	}
+	
+	/// Sets the maximum real time that is accumulated per frame.
+	pub fn max_frame_delta(mut self, max_frame_delta: Duration) -> Self {
+		self.max_frame_delta = max_frame_delta;
+		self
+	}
+	
+	/// Sets the maximum number of ticks per frame, excess time is discarded.
+	pub fn max_ticks(mut self, max_ticks: u32) -> Self {
+		self.max_ticks = max_ticks.max(1);
+		self
+	}
+	
+	pub fn time(&self) -> &Time {
+		&self.time
+	}
+	
+	pub fn set_scale(&mut self, scale: f32) {
+		assert!(scale >= 0.0 && scale.is_finite(), "time scale must be finite and non-negative");
+		self.time.scale = scale;
+	}
+	
+	pub fn set_paused(&mut self, paused: bool) {
+		self.time.paused = paused;
+	}
+	
+	/// Begins a new frame at `now` and returns the number of ticks that have to be simulated.
+	/// `tick` must be called after each simulated tick, `Time::alpha` is valid once all ticks
+	/// of the frame were executed.
+	pub fn begin_frame(&mut self, now: Instant) -> u32 {
+		let real = match self.last_frame.replace(now) {
+			Some(last) => now.saturating_duration_since(last),
+			None       => Duration::ZERO
+		};
+		
+		self.time.real += real;
+		self.time.frames += 1;
+		self.time.delta = match self.time.paused {
+			true  => Duration::ZERO,
+			false => scale(real.min(self.max_frame_delta), self.time.scale)
+		};
+		self.time.elapsed += self.time.delta;
+		self.accumulator += self.time.delta;
+		
+		let ticks = (self.accumulator.as_nanos() / self.time.tick_delta.as_nanos())
+			.min(u32::MAX as u128) as u32;
+		
+		if ticks > self.max_ticks {
+			log::warn!("[LOOP] simulation is {} ticks behind, skipping {} ticks",
+				ticks, ticks - self.max_ticks);
+			self.accumulator -= self.time.tick_delta * (ticks - self.max_ticks);
+			self.max_ticks
+		} else {
+			ticks
+		}
+	}
+	
+	/// Marks one simulation tick as completed.
+	pub fn tick(&mut self) {
+		self.accumulator = self.accumulator.saturating_sub(self.time.tick_delta);
+		self.time.ticks += 1;
+		self.update_alpha();
+	}
+	
+	/// Runs one frame: calls `tick` for each pending simulation tick, then `render` once.
+	pub fn frame(&mut self, now: Instant, mut tick: impl FnMut(&Time), render: impl FnOnce(&Time)) {
+		for _ in 0..self.begin_frame(now) {
+			tick(&self.time);
+			self.tick();
+		}
+		
+		self.update_alpha();
+		render(&self.time);
+	}
+	
+	fn update_alpha(&mut self) {
+		self.time.alpha = (self.accumulator.as_secs_f64() / self.time.tick_delta.as_secs_f64()) as f32;
+	}
+}
+
+fn scale(duration: Duration, scale: f32) -> Duration {
+	Duration::from_nanos((duration.as_nanos() as f64 * scale as f64).round() as u64)
+}
+
+impl Default for GameLoop {
+	fn default() -> Self {
+		Self::new(60)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::time::{Duration, Instant}};
+	
+	const TICK: Duration = Duration::from_millis(10);
+	
+	#[test]
+	fn test_fixed_ticks() {
+		let start = Instant::now();
+		let mut game_loop = GameLoop::with_tick_delta(TICK);
+		
+		assert_eq!(game_loop.begin_frame(start), 0);
+		assert_eq!(game_loop.begin_frame(start + Duration::from_millis(25)), 2);
+		game_loop.tick();
+		game_loop.tick();
+		assert_eq!(game_loop.time().ticks, 2);
+		assert!((game_loop.time().alpha - 0.5).abs() < 1e-4);
+		assert_eq!(game_loop.begin_frame(start + Duration::from_millis(30)), 1);
+	}
+	
+	#[test]
+	fn test_frame() {
+		let start = Instant::now();
+		let mut game_loop = GameLoop::with_tick_delta(TICK);
+		let (mut ticks, mut frames) = (0, 0);
+		
+		for i in 0..=10 {
+			game_loop.frame(start + Duration::from_millis(i * 16), |_| ticks += 1, |_| frames += 1);
+		}
+		
+		assert_eq!(ticks, 16);
+		assert_eq!(frames, 11);
+		assert_eq!(game_loop.time().elapsed, Duration::from_millis(160));
+	}
+	
+	#[test]
+	fn test_pause_and_scale() {
+		let start = Instant::now();
+		let mut game_loop = GameLoop::with_tick_delta(TICK);
+		
+		game_loop.begin_frame(start);
+		game_loop.set_paused(true);
+		assert_eq!(game_loop.begin_frame(start + Duration::from_millis(100)), 0);
+		assert_eq!(game_loop.time().delta, Duration::ZERO);
+		assert_eq!(game_loop.time().real, Duration::from_millis(100));
+		
+		game_loop.set_paused(false);
+		game_loop.set_scale(0.5);
+		assert_eq!(game_loop.begin_frame(start + Duration::from_millis(140)), 2);
+		assert_eq!(game_loop.time().delta, Duration::from_millis(20));
+	}
+	
+	#[test]
+	fn test_max_ticks() {
+		let start = Instant::now();
+		let mut game_loop = GameLoop::with_tick_delta(TICK)
+			.max_frame_delta(Duration::from_secs(1))
+			.max_ticks(4);
+		
+		game_loop.begin_frame(start);
+		assert_eq!(game_loop.begin_frame(start + Duration::from_millis(95)), 4);
+		(0..4).for_each(|_| game_loop.tick());
+		assert!((game_loop.time().alpha - 0.5).abs() < 1e-4);
+	}
+}
@@ -20,8 +20,37 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+//! ASN.1 Basic and Distinguished Encoding Rules.
+//!
+//! [`Element`] parses BER, i.e. definite and indefinite lengths and constructed strings, everything
+//! that is written is DER. [`BerRead`] and [`BerWrite`] are simple helpers for single byte tags.
+//!
+//! With serde, booleans, integers, floats, strings, bytes and `()` map to BOOLEAN, INTEGER, REAL,
+//! UTF8String, OCTET STRING and NULL. Sequences, tuples, structs and maps are SEQUENCEs, maps are
+//! a SEQUENCE OF SEQUENCE { key, value }. `None` is omitted. Enums with only plain unit variants
+//! are ENUMERATED, the value is the variant name if it is a number or its index otherwise. All
+//! other enums are a CHOICE, a variant is encoded as its content. Use [`OctetString`] instead of
+//! `Vec<u8>` for bytes, [`Integer`], [`BitString`], [`ObjectIdentifier`], [`UtcTime`],
+//! [`GeneralizedTime`] and [`SetOf`] for the corresponding types and [`Any`] for raw elements.
+//!
+//! Tags and types are specified with the names of fields, variants and structs in ASN.1 notation,
+//! e.g. `#[serde(rename = "controls [0] IMPLICIT")]`, `#[serde(rename = "[APPLICATION 1]")]` or
+//! `#[serde(rename = "critical BOOLEAN")]`. Tags are EXPLICIT unless specified otherwise. A type
+//! changes the encoding, e.g. PrintableString instead of UTF8String or SET instead of SEQUENCE.
+//! When decoding a SEQUENCE, an element is assigned to the next field whose tag or type matches
+//! it, fields without either match any element. OPTIONAL and DEFAULT fields that are followed by
+//! other fields therefore need a tag or a type. The elements of a SET may appear in any order.
+//! A CHOICE variant is selected by its tag or type, otherwise the first variant without either is
+//! used.
+
 use {
-	std::io::*,
+	crate::utils::{SerError, DeError},
+	std::{io::{self, Read as _, Write as _}, fmt, str::FromStr, borrow::Cow, marker::PhantomData},
+	serde::{
+		Serialize, Deserialize,
+		de::{self, Visitor, DeserializeOwned, DeserializeSeed, IntoDeserializer},
+		ser
+	},
 	futures_lite::{AsyncRead, AsyncReadExt}
 };
 
@@ -35,15 +64,15 @@ pub const BER_SET:                             u8 = 0x31;
 
 pub type BerTag = u8;
 
-pub trait BerRead: Read + Sized {
-	fn read_ber(&mut self) -> Result<(BerTag, usize)> {
+pub trait BerRead: io::Read + Sized {
+	fn read_ber(&mut self) -> io::Result<(BerTag, usize)> {
 		let mut buf = [0u8; 10];
 		self.read_exact(&mut buf[..2])?;
 		
 		let len = if buf[1] & 0x80 == 0 {
 			buf[1] as _
 		} else if buf[1] & !0x80 > 8 {
-			return Err(Error::new(ErrorKind::InvalidData, "integer too long"));
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "integer too long"));
 		} else {
 			let __tmp_buf_1__ = buf[1];
 			self.read_exact(&mut buf[10 - (__tmp_buf_1__ & !0x80) as usize..])?;
@@ -53,24 +82,24 @@ pub trait BerRead: Read + Sized {
 		Ok((buf[0], len))
 	}
 	
-	fn ber_reader(mut self) -> Result<(BerTag, std::io::Take<Self>)> {
+	fn ber_reader(mut self) -> io::Result<(BerTag, io::Take<Self>)> {
 		let (tag, len) = self.read_ber()?;
 		Ok((tag, self.take(len as _)))
 	}
 	
-	fn read_ber_to_buf(&mut self, buf: &mut Vec<u8>) -> Result<(BerTag, usize)> {
+	fn read_ber_to_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<(BerTag, usize)> {
 		let (tag, len) = self.read_ber()?;
 		let off = buf.len();
-		buf.extend(std::iter::repeat(0).take(len));
+		buf.extend(std::iter::repeat_n(0, len));
 		self.read_exact(&mut buf[off..off + len])?;
 		Ok((tag, len))
 	}
 	
-	fn read_ber_data(&mut self, tag: BerTag) -> Result<Vec<u8>> {
+	fn read_ber_data(&mut self, tag: BerTag) -> io::Result<Vec<u8>> {
 		let (read_tag, len) = self.read_ber()?;
 		
 		if read_tag != tag {
-			return Err(Error::new(ErrorKind::InvalidData, "invalid tag"))
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tag"))
 		}
 		
 		let mut buf = vec![0u8; len];
@@ -78,17 +107,17 @@ pub trait BerRead: Read + Sized {
 		Ok(buf)
 	}
 	
-	fn read_ber_null(&mut self, tag: BerTag) -> Result<()> {
+	fn read_ber_null(&mut self, tag: BerTag) -> io::Result<()> {
 		if self.read_ber()? != (tag, 0) {
-			Err(Error::new(ErrorKind::InvalidData, "invalid tag or length"))
+			Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tag or length"))
 		} else {
 			Ok(())
 		}
 	}
 	
-	fn read_ber_bool(&mut self, tag: BerTag) -> Result<bool> {
+	fn read_ber_bool(&mut self, tag: BerTag) -> io::Result<bool> {
 		if self.read_ber()? != (tag, 1) {
-			Err(Error::new(ErrorKind::InvalidData, "invalid tag or length"))
+			Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tag or length"))
 		} else {
 			let mut buf = [0];
 			self.read_exact(&mut buf)?;
@@ -96,28 +125,31 @@ pub trait BerRead: Read + Sized {
 		}
 	}
 	
-	fn read_ber_int(&mut self, tag: BerTag) -> Result<i64> {
+	fn read_ber_int(&mut self, tag: BerTag) -> io::Result<i64> {
 		let (read_tag, len) = self.read_ber()?;
 		
-		if read_tag != tag || len > 8 {
-			return Err(Error::new(ErrorKind::InvalidData, "invalid tag or integer too long"));
+		if read_tag != tag || len == 0 || len > 8 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tag or integer length"));
 		}
 		
 		let mut buf = [0u8; 8];
 		self.read_exact(&mut buf[8 - len..])?;
+		if buf[8 - len] & 0x80 != 0 {
+			buf[..8 - len].fill(0xFF);
+		}
 		Ok(i64::from_be_bytes(buf))
 	}
 	
-	fn read_ber_str(&mut self, tag: BerTag) -> Result<String> {
+	fn read_ber_str(&mut self, tag: BerTag) -> io::Result<String> {
 		String::from_utf8(self.read_ber_data(tag)?)
-			.map_err(|e| Error::new(ErrorKind::InvalidData, e))
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 	}
 }
 
-impl<T: Read> BerRead for T {}
+impl<T: io::Read> BerRead for T {}
 
-pub trait BerWrite: Write {
-	fn write_ber(&mut self, tag: BerTag, len: usize) -> Result<()> {
+pub trait BerWrite: io::Write {
+	fn write_ber(&mut self, tag: BerTag, len: usize) -> io::Result<()> {
 		if len == 0 {
 			self.write_all(&[tag, 0x00])
 		} else if len < 128 {
@@ -128,7 +160,7 @@ pub trait BerWrite: Write {
 		}
 	}
 	
-	fn write_ber_data(&mut self, tag: BerTag, data: &[u8]) -> Result<()> {
+	fn write_ber_data(&mut self, tag: BerTag, data: &[u8]) -> io::Result<()> {
 		self.write_ber(tag, data.len())?;
 		
 		if !data.is_empty() {
@@ -138,24 +170,24 @@ pub trait BerWrite: Write {
 		Ok(())
 	}
 	
-	fn write_ber_null(&mut self, tag: BerTag) -> Result<()> {
+	fn write_ber_null(&mut self, tag: BerTag) -> io::Result<()> {
 		self.write_ber_data(tag, &[])
 	}
 	
-	fn write_ber_bool(&mut self, tag: BerTag, v: bool) -> Result<()> {
+	fn write_ber_bool(&mut self, tag: BerTag, v: bool) -> io::Result<()> {
 		self.write_ber_data(tag, &[if v { 0xFF } else { 0x00 }])
 	}
 	
-	fn write_ber_int(&mut self, tag: BerTag, v: i64) -> Result<()> {
-		self.write_ber_data(tag, &(v as i32).to_be_bytes())
+	fn write_ber_int(&mut self, tag: BerTag, v: i64) -> io::Result<()> {
+		self.write_ber_data(tag, minimal_int(&v.to_be_bytes()))
 	}
 	
-	fn write_ber_str(&mut self, tag: BerTag, v: &str) -> Result<()> {
+	fn write_ber_str(&mut self, tag: BerTag, v: &str) -> io::Result<()> {
 		self.write_ber_data(tag, v.as_bytes())
 	}
 }
 
-impl<T: Write> BerWrite for T {}
+impl<T: io::Write> BerWrite for T {}
 
 pub struct BerWriter<'a>(&'a mut Vec<u8>, usize);
 
@@ -180,20 +212,20 @@ impl<'a> std::ops::DerefMut for BerWriter<'a> {
 	}
 }
 
-impl<'a> Write for BerWriter<'a> {
-	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+impl<'a> io::Write for BerWriter<'a> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
 		self.0.write(buf)
 	}
 	
-	fn flush(&mut self) -> Result<()> {
+	fn flush(&mut self) -> io::Result<()> {
 		self.0.flush()
 	}
 	
-	fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+	fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
 		self.0.write_all(buf)
 	}
 	
-	fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> Result<()> {
+	fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> io::Result<()> {
 		self.0.write_fmt(fmt)
 	}
 }
@@ -206,55 +238,2637 @@ impl<'a> Drop for BerWriter<'a> {
 	}
 }
 
-pub async fn ber_read_async(mut reader: impl AsyncRead + Unpin) -> Result<(BerTag, usize)> {
-	let mut buf = [0u8; 9];
+pub async fn ber_read_async(mut reader: impl AsyncRead + Unpin) -> io::Result<(BerTag, usize)> {
+	let mut buf = [0u8; 10];
 	reader.read_exact(&mut buf[..2]).await?;
 	
 	let len = if buf[1] & 0x80 == 0 {
 		buf[1] as _
-	} else if buf[1] & 0x80 > 8 {
-		return Err(Error::new(ErrorKind::InvalidData, "integer too long"));
+	} else if buf[1] & !0x80 > 8 {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "integer too long"));
 	} else {
 		let __tmp_buf_1__ = buf[1];
-		reader.read_exact(&mut buf[9 - (__tmp_buf_1__ & 0x80) as usize..]).await?;
-		u64::from_be_bytes([buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7], buf[8]]) as _
+		reader.read_exact(&mut buf[10 - (__tmp_buf_1__ & !0x80) as usize..]).await?;
+		u64::from_be_bytes([buf[2], buf[3], buf[4], buf[5], buf[6], buf[7], buf[8], buf[9]]) as _
 	};
 	
 	Ok((buf[0], len))
 }
 
-pub async fn ber_read_to_buf_async(mut reader: impl AsyncRead + Unpin, buf: &mut Vec<u8>) -> Result<(BerTag, usize)> {
+pub async fn ber_read_to_buf_async(mut reader: impl AsyncRead + Unpin, buf: &mut Vec<u8>) -> io::Result<(BerTag, usize)> {
 	let (tag, len) = ber_read_async(&mut reader).await?;
 	let off = buf.len();
-	buf.extend(std::iter::repeat(0).take(len));
+	buf.extend(std::iter::repeat_n(0, len));
 	reader.read_exact(&mut buf[off..off + len]).await?;
 	Ok((tag, len))
 }
 
-pub fn unexp_tag_err<T>(tag: BerTag) -> Error {
-	Error::new(ErrorKind::InvalidData, format!("unexpected tag {:#X} in {}", tag, std::any::type_name::<T>()))
+pub fn unexp_tag_err<T>(tag: BerTag) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("unexpected tag {:#X} in {}", tag, std::any::type_name::<T>()))
 }
 
-pub struct BerSeqIter<F: FnMut() -> Result<T>, T>(pub F);
+pub struct BerSeqIter<F: FnMut() -> io::Result<T>, T>(pub F);
 
-impl<F: FnMut() -> Result<T>, T> BerSeqIter<F, T> {
-	pub fn next<'a>(&'a mut self) -> Result<Option<T>>  where T: 'a {
+impl<F: FnMut() -> io::Result<T>, T> BerSeqIter<F, T> {
+	pub fn next<'a>(&'a mut self) -> io::Result<Option<T>>  where T: 'a {
 		match (self.0)() {
-			Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+			Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
 			Err(e) => Err(e),
 			Ok(v)  => Ok(Some(v))
 		}
 	}
 }
 
-impl<F: FnMut() -> Result<T>, T> Iterator for BerSeqIter<F, T> {
-	type Item = Result<T>;
+impl<F: FnMut() -> io::Result<T>, T> Iterator for BerSeqIter<F, T> {
+	type Item = io::Result<T>;
 	
 	fn next(&mut self) -> Option<Self::Item> {
 		match (self.0)() {
-			Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => None,
+			Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => None,
 			Err(e) => Some(Err(e)),
 			Ok(v)  => Some(Ok(v))
 		}
 	}
-}
\ No newline at end of file
+}
+
+const INTEGER_NAME:          &str = "$__asn1_integer";
+const BIT_STRING_NAME:       &str = "$__asn1_bit_string";
+const OID_NAME:              &str = "$__asn1_oid";
+const UTC_TIME_NAME:         &str = "$__asn1_utc_time";
+const GENERALIZED_TIME_NAME: &str = "$__asn1_generalized_time";
+const SET_OF_NAME:           &str = "$__asn1_set_of";
+const ANY_NAME:              &str = "$__asn1_any";
+const MAX_DEPTH:             usize = 100;
+
+/// The universal string types, all of them decode to `str`.
+const STRING_TAGS: &[Tag] = &[
+	Tag::UTF8_STRING, Tag::PRINTABLE_STRING, Tag::IA5_STRING, Tag::VISIBLE_STRING, Tag::NUMERIC_STRING,
+	Tag::TELETEX_STRING, Tag::BMP_STRING, Tag::UNIVERSAL_STRING, Tag::UTC_TIME, Tag::GENERALIZED_TIME,
+	Tag::OID, Tag::OCTET_STRING, Tag::OBJECT_DESCRIPTOR, Tag::VIDEOTEX_STRING, Tag::GRAPHIC_STRING, Tag::GENERAL_STRING
+];
+
+pub fn serialize<W: io::Write, T: Serialize>(mut writer: W, value: &T) -> Result<(), SerError> {
+	writer.write_all(&serialize_vec(value)?)?;
+	Ok(())
+}
+
+pub fn serialize_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, SerError> {
+	value.serialize(ValueSerializer::default())?
+		.map(|v| v.to_vec())
+		.ok_or_else(|| SerError::Custom("the top-level value cannot be `None`".to_string()))
+}
+
+/// Reads a single element and deserializes it, the reader is not read beyond the element.
+pub fn deserialize<T: DeserializeOwned, R: io::Read>(reader: R) -> Result<T, DeError> {
+	deserialize_slice(&read_element(reader)?)
+}
+
+pub fn deserialize_slice<'de, T: Deserialize<'de>>(data: &'de [u8]) -> Result<T, DeError> {
+	let (element, rest) = Element::parse(data)?;
+	if !rest.is_empty() {
+		return Err(DeError::ParseError(format!("{} bytes of trailing data", rest.len())));
+	}
+	T::deserialize(Deserializer::new(element))
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Class {
+	#[default]
+	Universal   = 0,
+	Application = 1,
+	Context     = 2,
+	Private     = 3
+}
+
+/// The identifier of an element.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Tag {
+	pub class:       Class,
+	pub constructed: bool,
+	pub number:      u32
+}
+
+impl Tag {
+	pub const EOC:              Self = Self::universal(0);
+	pub const BOOLEAN:          Self = Self::universal(1);
+	pub const INTEGER:          Self = Self::universal(2);
+	pub const BIT_STRING:       Self = Self::universal(3);
+	pub const OCTET_STRING:     Self = Self::universal(4);
+	pub const NULL:             Self = Self::universal(5);
+	pub const OID:              Self = Self::universal(6);
+	pub const OBJECT_DESCRIPTOR: Self = Self::universal(7);
+	pub const REAL:             Self = Self::universal(9);
+	pub const ENUMERATED:       Self = Self::universal(10);
+	pub const UTF8_STRING:      Self = Self::universal(12);
+	pub const SEQUENCE:         Self = Self::new(Class::Universal, true, 16);
+	pub const SET:              Self = Self::new(Class::Universal, true, 17);
+	pub const NUMERIC_STRING:   Self = Self::universal(18);
+	pub const PRINTABLE_STRING: Self = Self::universal(19);
+	pub const TELETEX_STRING:   Self = Self::universal(20);
+	pub const VIDEOTEX_STRING:  Self = Self::universal(21);
+	pub const IA5_STRING:       Self = Self::universal(22);
+	pub const UTC_TIME:         Self = Self::universal(23);
+	pub const GENERALIZED_TIME: Self = Self::universal(24);
+	pub const GRAPHIC_STRING:   Self = Self::universal(25);
+	pub const VISIBLE_STRING:   Self = Self::universal(26);
+	pub const GENERAL_STRING:   Self = Self::universal(27);
+	pub const UNIVERSAL_STRING: Self = Self::universal(28);
+	pub const BMP_STRING:       Self = Self::universal(30);
+	
+	pub const fn new(class: Class, constructed: bool, number: u32) -> Self {
+		Self { class, constructed, number }
+	}
+	
+	pub const fn universal(number: u32) -> Self {
+		Self::new(Class::Universal, false, number)
+	}
+	
+	pub const fn application(number: u32) -> Self {
+		Self::new(Class::Application, false, number)
+	}
+	
+	pub const fn context(number: u32) -> Self {
+		Self::new(Class::Context, false, number)
+	}
+	
+	pub const fn private(number: u32) -> Self {
+		Self::new(Class::Private, false, number)
+	}
+	
+	/// Returns true if both tags have the same class and number, i.e. ignores the constructed bit.
+	pub fn same_as(self, other: Self) -> bool {
+		self.class == other.class && self.number == other.number
+	}
+	
+	/// Reads the identifier octets from the start of `data` and advances it.
+	pub fn parse(data: &mut &[u8]) -> Result<Self, DeError> {
+		let (&first, mut rest) = data.split_first()
+			.ok_or_else(|| DeError::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+		
+		let class = match first >> 6 {
+			0 => Class::Universal,
+			1 => Class::Application,
+			2 => Class::Context,
+			_ => Class::Private
+		};
+		
+		let number = if first & 0x1F != 0x1F {
+			(first & 0x1F) as u32
+		} else {
+			if rest.first() == Some(&0x80) {
+				return Err(DeError::ParseError("tag number is not minimally encoded".to_string()));
+			}
+			
+			let mut number = 0u32;
+			loop {
+				let (&b, r) = rest.split_first()
+					.ok_or_else(|| DeError::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+				rest = r;
+				
+				if number > u32::MAX >> 7 {
+					return Err(DeError::ParseError("tag number is too large".to_string()));
+				}
+				
+				number = number << 7 | (b & 0x7F) as u32;
+				if b & 0x80 == 0 {
+					break number;
+				}
+			}
+		};
+		
+		*data = rest;
+		Ok(Self { class, constructed: first & 0x20 != 0, number })
+	}
+	
+	pub fn write(&self, out: &mut Vec<u8>) {
+		let id = (self.class as u8) << 6 | (self.constructed as u8) << 5;
+		if self.number < 0x1F {
+			out.push(id | self.number as u8);
+		} else {
+			out.push(id | 0x1F);
+			put_base128(out, self.number as u64);
+		}
+	}
+}
+
+/// Orders tags canonically, i.e. by class and then by number.
+impl Ord for Tag {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		(self.class, self.number, self.constructed).cmp(&(other.class, other.number, other.constructed))
+	}
+}
+
+impl PartialOrd for Tag {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl fmt::Display for Tag {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.class {
+			Class::Universal   => write!(f, "[UNIVERSAL {}]", self.number),
+			Class::Application => write!(f, "[APPLICATION {}]", self.number),
+			Class::Context     => write!(f, "[{}]", self.number),
+			Class::Private     => write!(f, "[PRIVATE {}]", self.number)
+		}
+	}
+}
+
+/// Reads the length octets from the start of `data` and advances it, `None` is an indefinite length.
+pub fn read_len(data: &mut &[u8]) -> Result<Option<usize>, DeError> {
+	let (&first, mut rest) = data.split_first()
+		.ok_or_else(|| DeError::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+	
+	let len = match first {
+		0x80 => None,
+		0xFF => return Err(DeError::ParseError("reserved length octet".to_string())),
+		v if v & 0x80 == 0 => Some(v as usize),
+		v => {
+			let n = (v & 0x7F) as usize;
+			if n > 8 {
+				return Err(DeError::ParseError("length is too long".to_string()));
+			} else if rest.len() < n {
+				return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+			}
+			
+			let len = rest[..n].iter().fold(0u64, |len, &b| len << 8 | b as u64);
+			rest = &rest[n..];
+			Some(usize::try_from(len).map_err(|_| DeError::ParseError("length is too large".to_string()))?)
+		}
+	};
+	
+	*data = rest;
+	Ok(len)
+}
+
+pub fn write_len(out: &mut Vec<u8>, len: usize) {
+	if len < 0x80 {
+		out.push(len as u8);
+	} else {
+		let bytes = (len as u64).to_be_bytes();
+		let skip = bytes.iter().take_while(|&&b| b == 0).count();
+		out.push(0x80 | (8 - skip) as u8);
+		out.extend_from_slice(&bytes[skip..]);
+	}
+}
+
+fn put_header(out: &mut Vec<u8>, tag: Tag, len: usize) {
+	tag.write(out);
+	write_len(out, len);
+}
+
+fn put_base128(out: &mut Vec<u8>, v: u64) {
+	let groups = (64 - v.leading_zeros()).max(1).div_ceil(7);
+	for i in (0..groups).rev() {
+		out.push((v >> (7 * i)) as u8 & 0x7F | if i > 0 { 0x80 } else { 0 });
+	}
+}
+
+/// Reads a complete element, elements with an indefinite length are read up to their end-of-contents.
+pub fn read_element<R: io::Read>(mut reader: R) -> io::Result<Vec<u8>> {
+	let mut out = Vec::new();
+	read_element_into(&mut reader, &mut out, 0)?;
+	Ok(out)
+}
+
+/// Appends the next element to `out`, returns true if it was an end-of-contents marker.
+fn read_element_into(reader: &mut impl io::Read, out: &mut Vec<u8>, depth: usize) -> io::Result<bool> {
+	fn byte(reader: &mut impl io::Read, out: &mut Vec<u8>) -> io::Result<u8> {
+		let mut buf = [0u8];
+		reader.read_exact(&mut buf)?;
+		out.push(buf[0]);
+		Ok(buf[0])
+	}
+	
+	if depth > MAX_DEPTH {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "elements are nested too deeply"));
+	}
+	
+	let start = out.len();
+	let first = byte(reader, out)?;
+	if first & 0x1F == 0x1F {
+		for i in 0.. {
+			if i == 5 {
+				return Err(io::Error::new(io::ErrorKind::InvalidData, "tag number is too large"));
+			} else if byte(reader, out)? & 0x80 == 0 {
+				break;
+			}
+		}
+	}
+	
+	let len = match byte(reader, out)? {
+		0x80 if first & 0x20 == 0 => return Err(io::Error::new(
+			io::ErrorKind::InvalidData, "primitive element with an indefinite length")),
+		0x80 => None,
+		n if n & 0x80 == 0 => Some(n as u64),
+		n if n != 0xFF && n & 0x7F <= 8 => {
+			let mut len = 0;
+			for _ in 0..n & 0x7F {
+				len = len << 8 | byte(reader, out)? as u64;
+			}
+			Some(len)
+		}
+		_ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid length"))
+	};
+	
+	match len {
+		Some(len) => if (reader.by_ref().take(len).read_to_end(out)? as u64) < len {
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		},
+		None => while !read_element_into(reader, out, depth + 1)? {}
+	}
+	
+	Ok(out[start..] == [0, 0])
+}
+
+/// An element with its content borrowed from the encoded data.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Element<'a> {
+	pub tag:     Tag,
+	/// The content octets, without the end-of-contents octets if the length was indefinite.
+	pub content: &'a [u8]
+}
+
+impl<'a> Element<'a> {
+	/// Parses the element at the start of `data`, returns the element and the remaining data.
+	pub fn parse(data: &'a [u8]) -> Result<(Self, &'a [u8]), DeError> {
+		Self::parse_depth(data, 0)
+	}
+	
+	fn parse_depth(data: &'a [u8], depth: usize) -> Result<(Self, &'a [u8]), DeError> {
+		let mut rest = data;
+		let tag = Tag::parse(&mut rest)?;
+		match read_len(&mut rest)? {
+			Some(len) if len > rest.len() => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+			Some(len) => {
+				let (content, rest) = rest.split_at(len);
+				Ok((Self { tag, content }, rest))
+			}
+			None if !tag.constructed => Err(DeError::ParseError(format!(
+				"primitive element {} with an indefinite length", tag))),
+			None if depth > MAX_DEPTH => Err(DeError::ParseError("elements are nested too deeply".to_string())),
+			None => {
+				let mut inner = rest;
+				while !inner.starts_with(&[0, 0]) {
+					if inner.is_empty() {
+						return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+					}
+					inner = Self::parse_depth(inner, depth + 1)?.1;
+				}
+				
+				let content = &rest[..rest.len() - inner.len()];
+				Ok((Self { tag, content }, &inner[2..]))
+			}
+		}
+	}
+	
+	/// Returns an iterator over the elements in the content of a constructed element.
+	pub fn children(&self) -> Elements<'a> {
+		Elements(self.content)
+	}
+	
+	/// Returns the content of a string type, the segments of constructed strings are concatenated.
+	pub fn bytes(&self) -> Result<Cow<'a, [u8]>, DeError> {
+		if !self.tag.constructed {
+			return Ok(Cow::Borrowed(self.content));
+		}
+		
+		let mut out = Vec::new();
+		self.segments(&mut out, 0)?;
+		Ok(Cow::Owned(out))
+	}
+	
+	fn segments(&self, out: &mut Vec<u8>, depth: usize) -> Result<(), DeError> {
+		if depth > MAX_DEPTH {
+			return Err(DeError::ParseError("elements are nested too deeply".to_string()));
+		}
+		
+		for child in self.children() {
+			let child = child?;
+			if self.tag.class == Class::Universal && !child.tag.same_as(self.tag) {
+				return Err(DeError::ParseError(format!("segment {} in constructed string {}", child.tag, self.tag)));
+			} else if child.tag.constructed {
+				child.segments(out, depth + 1)?;
+			} else {
+				out.extend_from_slice(child.content);
+			}
+		}
+		Ok(())
+	}
+	
+	/// Returns the content of a BIT STRING, the segments of constructed strings are concatenated.
+	fn bit_string(&self) -> Result<Cow<'a, [u8]>, DeError> {
+		if !self.tag.constructed {
+			return Ok(Cow::Borrowed(self.content));
+		}
+		
+		let mut out = vec![0];
+		for child in self.children() {
+			let child = child?;
+			match child.content {
+				[unused, data @ ..] if out[0] == 0 && !child.tag.constructed && child.tag.same_as(Tag::BIT_STRING) => {
+					out[0] = *unused;
+					out.extend_from_slice(data);
+				}
+				_ => return Err(DeError::ParseError("invalid segment in constructed bit string".to_string()))
+			}
+		}
+		Ok(Cow::Owned(out))
+	}
+	
+	pub fn write(&self, out: &mut Vec<u8>) {
+		put_header(out, self.tag, self.content.len());
+		out.extend_from_slice(self.content);
+	}
+	
+	/// Returns the element encoded with a definite length.
+	pub fn to_vec(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(self.content.len() + 6);
+		self.write(&mut out);
+		out
+	}
+}
+
+/// An iterator over consecutive elements.
+pub struct Elements<'a>(&'a [u8]);
+
+impl<'a> Elements<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self(data)
+	}
+}
+
+impl<'a> Iterator for Elements<'a> {
+	type Item = Result<Element<'a>, DeError>;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.0.is_empty() {
+			return None;
+		}
+		
+		match Element::parse(self.0) {
+			Ok((element, rest)) => {
+				self.0 = rest;
+				Some(Ok(element))
+			}
+			Err(e) => {
+				self.0 = &[];
+				Some(Err(e))
+			}
+		}
+	}
+}
+
+/// An element with owned content, decodes any element and encodes it as is.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Any {
+	pub tag:     Tag,
+	pub content: Vec<u8>
+}
+
+impl Any {
+	pub fn new(tag: Tag, content: Vec<u8>) -> Self {
+		Self { tag, content }
+	}
+	
+	pub fn element(&self) -> Element<'_> {
+		Element { tag: self.tag, content: &self.content }
+	}
+	
+	pub fn write(&self, out: &mut Vec<u8>) {
+		self.element().write(out)
+	}
+	
+	pub fn to_vec(&self) -> Vec<u8> {
+		self.element().to_vec()
+	}
+	
+	pub fn serialize_from<T: Serialize>(value: &T) -> Result<Self, SerError> {
+		value.serialize(ValueSerializer::default())?
+			.ok_or_else(|| SerError::Custom("cannot encode `None` as an element".to_string()))
+	}
+	
+	pub fn deserialize_to<'de, T: Deserialize<'de>>(&'de self) -> Result<T, DeError> {
+		T::deserialize(Deserializer::new(self.element()))
+	}
+	
+	/// Applies the tag of a field, variant or struct name.
+	fn tagged(self, spec: &Spec) -> Self {
+		match spec.tag {
+			None => self,
+			Some((tag, true)) => Self { tag: Tag { constructed: self.tag.constructed, ..tag }, content: self.content },
+			Some((tag, false)) => Self { tag: Tag { constructed: true, ..tag }, content: self.to_vec() }
+		}
+	}
+}
+
+impl<'a> From<Element<'a>> for Any {
+	fn from(element: Element<'a>) -> Self {
+		Self::new(element.tag, element.content.to_vec())
+	}
+}
+
+impl Serialize for Any {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(ANY_NAME, &Bytes(&self.to_vec()))
+	}
+}
+
+impl<'de> Deserialize<'de> for Any {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let data = deserializer.deserialize_newtype_struct(ANY_NAME, NewtypeVisitor::<OctetString>(PhantomData))?.0;
+		match Element::parse(&data) {
+			Ok((element, [])) => Ok(element.into()),
+			Ok(_)  => Err(de::Error::custom("trailing data after element")),
+			Err(e) => Err(de::Error::custom(e))
+		}
+	}
+}
+
+/// Returns the minimal two's complement encoding of an integer.
+fn minimal_int(mut bytes: &[u8]) -> &[u8] {
+	while let [a, b, ..] = bytes {
+		if (*a == 0 && b & 0x80 == 0) || (*a == 0xFF && b & 0x80 != 0) {
+			bytes = &bytes[1..];
+		} else {
+			break;
+		}
+	}
+	bytes
+}
+
+fn int_value(content: &[u8]) -> Result<i128, DeError> {
+	match content {
+		[] => Err(DeError::ParseError("empty integer".to_string())),
+		_ if content.len() > 16 => Err(DeError::ParseError("integer is too large".to_string())),
+		_ => {
+			let mut buf = [if content[0] & 0x80 != 0 { 0xFF } else { 0 }; 16];
+			buf[16 - content.len()..].copy_from_slice(content);
+			Ok(i128::from_be_bytes(buf))
+		}
+	}
+}
+
+/// An INTEGER of arbitrary size, i.e. its minimal big endian two's complement encoding.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Integer(pub Vec<u8>);
+
+impl Integer {
+	/// Creates an integer from big endian unsigned bytes, e.g. a key modulus.
+	pub fn from_unsigned(bytes: &[u8]) -> Self {
+		let bytes = &bytes[bytes.iter().take_while(|&&b| b == 0).count()..];
+		let mut v = Vec::with_capacity(bytes.len() + 1);
+		if bytes.first().is_none_or(|b| b & 0x80 != 0) {
+			v.push(0);
+		}
+		v.extend_from_slice(bytes);
+		Self(v)
+	}
+	
+	/// Returns the big endian unsigned bytes without leading zeros, or `None` if it is negative.
+	pub fn as_unsigned(&self) -> Option<&[u8]> {
+		(!self.is_negative()).then(|| &self.0[self.0.iter().take_while(|&&b| b == 0).count()..])
+	}
+	
+	pub fn is_negative(&self) -> bool {
+		self.0.first().is_some_and(|b| b & 0x80 != 0)
+	}
+	
+	pub fn to_i128(&self) -> Option<i128> {
+		int_value(minimal_int(&self.0)).ok()
+	}
+}
+
+impl From<i64> for Integer {
+	fn from(v: i64) -> Self {
+		Self(minimal_int(&v.to_be_bytes()).to_vec())
+	}
+}
+
+impl From<u64> for Integer {
+	fn from(v: u64) -> Self {
+		Self::from_unsigned(&v.to_be_bytes())
+	}
+}
+
+impl From<i128> for Integer {
+	fn from(v: i128) -> Self {
+		Self(minimal_int(&v.to_be_bytes()).to_vec())
+	}
+}
+
+impl Serialize for Integer {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(INTEGER_NAME, &Bytes(&self.0))
+	}
+}
+
+impl<'de> Deserialize<'de> for Integer {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(INTEGER_NAME, NewtypeVisitor::<OctetString>(PhantomData))
+			.map(|v| Self(v.0))
+	}
+}
+
+/// A BIT STRING, bits are numbered from the most significant bit of the first byte.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BitString {
+	pub data:   Vec<u8>,
+	/// The number of unused bits in the last byte.
+	pub unused: u8
+}
+
+impl BitString {
+	pub fn new(data: Vec<u8>) -> Self {
+		Self { data, unused: 0 }
+	}
+	
+	pub fn from_content(content: &[u8]) -> Result<Self, DeError> {
+		match content {
+			[0] => Ok(Self::default()),
+			[unused, data @ ..] if *unused < 8 && !data.is_empty() => Ok(Self { data: data.to_vec(), unused: *unused }),
+			_ => Err(DeError::ParseError("invalid bit string".to_string()))
+		}
+	}
+	
+	/// Returns the content octets, unused bits are cleared.
+	pub fn to_content(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(self.data.len() + 1);
+		out.push(if self.data.is_empty() { 0 } else { self.unused & 7 });
+		out.extend_from_slice(&self.data);
+		if let Some(last) = out.last_mut().filter(|_| !self.data.is_empty()) {
+			*last &= 0xFF << (self.unused & 7);
+		}
+		out
+	}
+	
+	/// Returns the number of bits.
+	pub fn len(&self) -> usize {
+		(self.data.len() * 8).saturating_sub(self.unused as usize)
+	}
+	
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	
+	pub fn get(&self, i: usize) -> bool {
+		i < self.len() && self.data[i / 8] & (0x80 >> (i % 8)) != 0
+	}
+	
+	/// Sets bit `i`, the string is extended if necessary.
+	pub fn set(&mut self, i: usize, v: bool) {
+		if i >= self.len() {
+			self.data.resize(i / 8 + 1, 0);
+			self.unused = (7 - i % 8) as u8;
+		}
+		
+		if v {
+			self.data[i / 8] |= 0x80 >> (i % 8);
+		} else {
+			self.data[i / 8] &= !(0x80 >> (i % 8));
+		}
+	}
+	
+	/// Removes trailing zero bits, as DER requires for named bit lists.
+	pub fn trim(&mut self) {
+		let mut len = self.len();
+		while len > 0 && !self.get(len - 1) {
+			len -= 1;
+		}
+		
+		self.data.truncate(len.div_ceil(8));
+		self.unused = ((8 - len % 8) % 8) as u8;
+	}
+}
+
+impl Serialize for BitString {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(BIT_STRING_NAME, &Bytes(&self.to_content()))
+	}
+}
+
+impl<'de> Deserialize<'de> for BitString {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let content = deserializer.deserialize_newtype_struct(BIT_STRING_NAME, NewtypeVisitor::<OctetString>(PhantomData))?;
+		Self::from_content(&content.0).map_err(de::Error::custom)
+	}
+}
+
+/// An OBJECT IDENTIFIER, written and parsed in dotted notation.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ObjectIdentifier(pub Vec<u64>);
+
+impl ObjectIdentifier {
+	pub fn from_content(content: &[u8]) -> Result<Self, DeError> {
+		let mut arcs = Vec::new();
+		let mut v = 0u64;
+		let mut start = true;
+		for &b in content {
+			if start && b == 0x80 {
+				return Err(DeError::ParseError("object identifier arc is not minimally encoded".to_string()));
+			} else if v > u64::MAX >> 7 {
+				return Err(DeError::ParseError("object identifier arc is too large".to_string()));
+			}
+			
+			v = v << 7 | (b & 0x7F) as u64;
+			start = b & 0x80 == 0;
+			if !start {
+				continue;
+			}
+			
+			if arcs.is_empty() {
+				let first = (v / 40).min(2);
+				arcs.push(first);
+				arcs.push(v - first * 40);
+			} else {
+				arcs.push(v);
+			}
+			v = 0;
+		}
+		
+		if !start || arcs.is_empty() {
+			return Err(DeError::ParseError("truncated object identifier".to_string()));
+		}
+		Ok(Self(arcs))
+	}
+	
+	pub fn to_content(&self) -> Result<Vec<u8>, SerError> {
+		let first = match self.0[..] {
+			[a @ 0..=1, b, ..] if b < 40 => a * 40 + b,
+			[2, b, ..] => b.checked_add(80)
+				.ok_or_else(|| SerError::Custom("object identifier arc is too large".to_string()))?,
+			_ => return Err(SerError::Custom(format!("invalid object identifier {}", self)))
+		};
+		
+		let mut out = Vec::new();
+		put_base128(&mut out, first);
+		self.0[2..].iter().for_each(|&v| put_base128(&mut out, v));
+		Ok(out)
+	}
+}
+
+impl fmt::Display for ObjectIdentifier {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, arc) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str(".")?;
+			}
+			write!(f, "{}", arc)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for ObjectIdentifier {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let arcs = s.split('.')
+			.map(|arc| arc.parse::<u64>())
+			.collect::<Result<Vec<_>, _>>()
+			.ok()
+			.filter(|arcs| matches!(arcs[..], [0..=1, 0..=39, ..] | [2, _, ..]));
+		
+		arcs.map(Self).ok_or_else(|| DeError::InvalidValue(format!("`{}`", s), "an object identifier".to_string()))
+	}
+}
+
+impl Serialize for ObjectIdentifier {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(OID_NAME, &self.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for ObjectIdentifier {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(OID_NAME, NewtypeVisitor::<String>(PhantomData))?
+			.parse()
+			.map_err(de::Error::custom)
+	}
+}
+
+/// A date and time in UTC.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateTime {
+	pub year:       u16,
+	pub month:      u8,
+	pub day:        u8,
+	pub hour:       u8,
+	pub minute:     u8,
+	pub second:     u8,
+	pub nanosecond: u32
+}
+
+impl DateTime {
+	/// Returns `None` if the year is outside of 0 to 9999.
+	pub fn from_unix_timestamp(secs: i64) -> Option<Self> {
+		let days = secs.div_euclid(86400);
+		let secs = secs.rem_euclid(86400);
+		let (year, month, day) = civil_from_days(days);
+		Some(Self {
+			year:       u16::try_from(year).ok().filter(|&y| y <= 9999)?,
+			month:      month as _,
+			day:        day as _,
+			hour:       (secs / 3600) as _,
+			minute:     (secs / 60 % 60) as _,
+			second:     (secs % 60) as _,
+			nanosecond: 0
+		})
+	}
+	
+	pub fn unix_timestamp(&self) -> i64 {
+		days_from_civil(self.year as _, self.month as _, self.day as _) * 86400
+			+ self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+	}
+	
+	fn is_valid(&self) -> bool {
+		let days = match self.month {
+			2 if self.year.is_multiple_of(4) && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400)) => 29,
+			2 => 28,
+			4 | 6 | 9 | 11 => 30,
+			_ => 31
+		};
+		
+		(1..=12).contains(&self.month) && (1..=days).contains(&self.day) && self.hour < 24
+			&& self.minute < 60 && self.second < 60 && self.nanosecond < 1_000_000_000
+	}
+	
+	/// Parses the text of a UTCTime or GeneralizedTime, offsets are converted to UTC and local times are taken as UTC.
+	fn parse(s: &str, utc: bool) -> Option<Self> {
+		fn digits(rest: &mut &[u8], n: usize) -> Option<u32> {
+			let v = rest.get(..n).filter(|d| d.iter().all(u8::is_ascii_digit))?
+				.iter()
+				.fold(0, |v, &d| v * 10 + (d - b'0') as u32);
+			*rest = &rest[n..];
+			Some(v)
+		}
+		
+		let rest = &mut s.as_bytes();
+		let year = match utc {
+			true => digits(rest, 2).map(|y| if y < 50 { 2000 + y } else { 1900 + y })?,
+			false => digits(rest, 4)?
+		};
+		
+		let month = digits(rest, 2)?;
+		let day = digits(rest, 2)?;
+		let hour = digits(rest, 2)?;
+		let minute = if utc { digits(rest, 2)? } else { digits(rest, 2).unwrap_or(0) };
+		let second = digits(rest, 2).unwrap_or(0);
+		let mut nanosecond = 0;
+		
+		if let (false, [b'.' | b',', fraction @ ..]) = (utc, *rest) {
+			let len = fraction.iter().take_while(|d| d.is_ascii_digit()).count();
+			if len == 0 {
+				return None;
+			}
+			
+			nanosecond = fraction[..len.min(9)].iter()
+				.fold(0, |v, &d| v * 10 + (d - b'0') as u32) * 10u32.pow(9 - len.min(9) as u32);
+			*rest = &fraction[len..];
+		}
+		
+		let offset = match *rest {
+			b"Z" => 0,
+			[] if !utc => 0,
+			[sign @ (b'+' | b'-'), tz @ ..] => {
+				let mut tz = tz;
+				let hours = digits(&mut tz, 2)? as i64;
+				let minutes = (if utc { digits(&mut tz, 2)? } else { digits(&mut tz, 2).unwrap_or(0) }) as i64;
+				if !tz.is_empty() || hours > 23 || minutes > 59 {
+					return None;
+				}
+				(hours * 60 + minutes) * 60 * if *sign == b'-' { -1 } else { 1 }
+			}
+			_ => return None
+		};
+		
+		let time = Self {
+			year:   year as _,
+			month:  month as _,
+			day:    day as _,
+			hour:   hour as _,
+			minute: minute as _,
+			second: second as _,
+			nanosecond
+		};
+		
+		if !time.is_valid() {
+			return None;
+		} else if offset == 0 {
+			return Some(time);
+		}
+		
+		Self::from_unix_timestamp(time.unix_timestamp() - offset)
+			.map(|v| Self { nanosecond, ..v })
+	}
+}
+
+/// Formats the time in RFC 3339 format.
+impl fmt::Display for DateTime {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", self.year, self.month, self.day, self.hour, self.minute, self.second)?;
+		if self.nanosecond != 0 {
+			write!(f, ".{}", format!("{:09}", self.nanosecond).trim_end_matches('0'))?;
+		}
+		f.write_str("Z")
+	}
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let yoe = year - era * 400;
+	let doy = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+	let days = days + 719468;
+	let era = days.div_euclid(146097);
+	let doe = days - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+	let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+	(yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+/// A UTCTime, years are in the range 1950 to 2049.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct UtcTime(pub DateTime);
+
+impl fmt::Display for UtcTime {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let DateTime { year, month, day, hour, minute, second, .. } = self.0;
+		write!(f, "{:02}{:02}{:02}{:02}{:02}{:02}Z", year % 100, month, day, hour, minute, second)
+	}
+}
+
+impl FromStr for UtcTime {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		DateTime::parse(s, true)
+			.map(Self)
+			.ok_or_else(|| DeError::InvalidValue(format!("`{}`", s), "a UTCTime".to_string()))
+	}
+}
+
+impl Serialize for UtcTime {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if !(1950..2050).contains(&self.0.year) || self.0.nanosecond != 0 || !self.0.is_valid() {
+			return Err(ser::Error::custom(format!("cannot encode {} as a UTCTime", self.0)));
+		}
+		serializer.serialize_newtype_struct(UTC_TIME_NAME, &self.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for UtcTime {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(UTC_TIME_NAME, NewtypeVisitor::<String>(PhantomData))?
+			.parse()
+			.map_err(de::Error::custom)
+	}
+}
+
+/// A GeneralizedTime.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GeneralizedTime(pub DateTime);
+
+impl fmt::Display for GeneralizedTime {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let DateTime { year, month, day, hour, minute, second, nanosecond } = self.0;
+		write!(f, "{:04}{:02}{:02}{:02}{:02}{:02}", year, month, day, hour, minute, second)?;
+		if nanosecond != 0 {
+			write!(f, ".{}", format!("{:09}", nanosecond).trim_end_matches('0'))?;
+		}
+		f.write_str("Z")
+	}
+}
+
+impl FromStr for GeneralizedTime {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		DateTime::parse(s, false)
+			.map(Self)
+			.ok_or_else(|| DeError::InvalidValue(format!("`{}`", s), "a GeneralizedTime".to_string()))
+	}
+}
+
+impl Serialize for GeneralizedTime {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if !self.0.is_valid() {
+			return Err(ser::Error::custom(format!("cannot encode {} as a GeneralizedTime", self.0)));
+		}
+		serializer.serialize_newtype_struct(GENERALIZED_TIME_NAME, &self.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for GeneralizedTime {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(GENERALIZED_TIME_NAME, NewtypeVisitor::<String>(PhantomData))?
+			.parse()
+			.map_err(de::Error::custom)
+	}
+}
+
+/// A SET OF, elements are sorted by their encoding when encoding.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SetOf<T>(pub Vec<T>);
+
+impl<T: Serialize> Serialize for SetOf<T> {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(SET_OF_NAME, &self.0)
+	}
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SetOf<T> {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(SET_OF_NAME, NewtypeVisitor(PhantomData))
+			.map(Self)
+	}
+}
+
+/// An OCTET STRING.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OctetString(pub Vec<u8>);
+
+impl Serialize for OctetString {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for OctetString {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct BytesVisitor;
+		
+		impl<'de> Visitor<'de> for BytesVisitor {
+			type Value = OctetString;
+			
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("bytes")
+			}
+			
+			fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<OctetString, E> {
+				Ok(OctetString(v.to_vec()))
+			}
+			
+			fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<OctetString, E> {
+				Ok(OctetString(v))
+			}
+			
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<OctetString, E> {
+				Ok(OctetString(v.as_bytes().to_vec()))
+			}
+			
+			fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<OctetString, A::Error> {
+				let mut bytes = Vec::new();
+				while let Some(b) = seq.next_element()? {
+					bytes.push(b);
+				}
+				Ok(OctetString(bytes))
+			}
+		}
+		
+		deserializer.deserialize_byte_buf(BytesVisitor)
+	}
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(self.0)
+	}
+}
+
+struct NewtypeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NewtypeVisitor<T> {
+	type Value = T;
+	
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a newtype struct")
+	}
+	
+	fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+		T::deserialize(deserializer)
+	}
+}
+
+fn encode_real(v: f64) -> Vec<u8> {
+	if v == 0.0 {
+		return if v.is_sign_negative() { vec![0x43] } else { Vec::new() };
+	} else if v.is_nan() {
+		return vec![0x42];
+	} else if v.is_infinite() {
+		return vec![if v > 0.0 { 0x40 } else { 0x41 }];
+	}
+	
+	let bits = v.to_bits();
+	let biased = (bits >> 52 & 0x7FF) as i64;
+	let fraction = bits & ((1 << 52) - 1);
+	let (mut mantissa, mut exponent) = match biased {
+		0 => (fraction, -1074),
+		_ => (fraction | 1 << 52, biased - 1075)
+	};
+	
+	exponent += mantissa.trailing_zeros() as i64;
+	mantissa >>= mantissa.trailing_zeros();
+	
+	let exponent = exponent.to_be_bytes();
+	let exponent = minimal_int(&exponent);
+	let mantissa = mantissa.to_be_bytes();
+	let mantissa = &mantissa[mantissa.iter().take_while(|&&b| b == 0).count()..];
+	
+	let mut out = vec![0x80 | (v.is_sign_negative() as u8) << 6 | (exponent.len() - 1) as u8];
+	out.extend_from_slice(exponent);
+	out.extend_from_slice(mantissa);
+	out
+}
+
+fn decode_real(content: &[u8]) -> Result<f64, DeError> {
+	let err = || DeError::ParseError("invalid real".to_string());
+	let Some((&first, rest)) = content.split_first() else {
+		return Ok(0.0);
+	};
+	
+	if first & 0x80 == 0 {
+		return match first {
+			0x40 => Ok(f64::INFINITY),
+			0x41 => Ok(f64::NEG_INFINITY),
+			0x42 => Ok(f64::NAN),
+			0x43 => Ok(-0.0),
+			1..=3 => std::str::from_utf8(rest)?.trim().replace(',', ".").parse().map_err(|_| err()),
+			_ => Err(err())
+		};
+	}
+	
+	let shift = match first >> 4 & 3 {
+		0 => 1,
+		1 => 3,
+		2 => 4,
+		_ => return Err(err())
+	};
+	
+	let (len, rest) = match first & 3 {
+		3 => rest.split_first().map(|(&len, rest)| (len as usize, rest)).ok_or_else(err)?,
+		n => (n as usize + 1, rest)
+	};
+	
+	if len == 0 || len > 8 || rest.len() < len {
+		return Err(err());
+	}
+	
+	let exponent = int_value(&rest[..len])? as i64;
+	let mantissa = &rest[len..];
+	let mantissa = &mantissa[mantissa.iter().take_while(|&&b| b == 0).count()..];
+	if mantissa.len() > 8 {
+		return Err(DeError::ParseError("real mantissa is too long".to_string()));
+	}
+	
+	let mut v = mantissa.iter().fold(0u64, |v, &b| v << 8 | b as u64) as f64;
+	let mut exponent = exponent.clamp(-1 << 20, 1 << 20) * shift + (first >> 2 & 3) as i64;
+	while exponent.abs() > 1000 {
+		v *= 2f64.powi(1000 * exponent.signum() as i32);
+		exponent -= 1000 * exponent.signum();
+	}
+	
+	v *= 2f64.powi(exponent as i32);
+	Ok(if first & 0x40 != 0 { -v } else { v })
+}
+
+fn encode_str(v: &str, tag: Tag) -> Result<Vec<u8>, SerError> {
+	let err = || SerError::Custom(format!("cannot encode `{}` as {}", v, tag));
+	let ascii = |f: fn(&u8) -> bool| v.bytes().all(|b| f(&b)).then(|| v.as_bytes().to_vec()).ok_or_else(err);
+	
+	match Tag::universal(tag.number) {
+		_ if tag.class != Class::Universal => Err(err()),
+		Tag::UTF8_STRING | Tag::OCTET_STRING | Tag::GRAPHIC_STRING | Tag::GENERAL_STRING => Ok(v.as_bytes().to_vec()),
+		Tag::PRINTABLE_STRING => ascii(|b| b.is_ascii_alphanumeric() || b" '()+,-./:=?".contains(b)),
+		Tag::NUMERIC_STRING => ascii(|b| b.is_ascii_digit() || *b == b' '),
+		Tag::IA5_STRING | Tag::UTC_TIME | Tag::GENERALIZED_TIME => ascii(u8::is_ascii),
+		Tag::VISIBLE_STRING => ascii(|b| (0x20..0x7F).contains(b)),
+		Tag::TELETEX_STRING => v.chars().map(|c| u8::try_from(c).map_err(|_| err())).collect(),
+		Tag::BMP_STRING => v.encode_utf16().all(|c| !(0xD800..0xE000).contains(&c))
+			.then(|| v.encode_utf16().flat_map(u16::to_be_bytes).collect())
+			.ok_or_else(err),
+		Tag::UNIVERSAL_STRING => Ok(v.chars().flat_map(|c| (c as u32).to_be_bytes()).collect()),
+		Tag::OID => v.parse::<ObjectIdentifier>().map_err(|_| err())?.to_content(),
+		_ => Err(err())
+	}
+}
+
+fn decode_str(bytes: Cow<[u8]>, tag: Tag) -> Result<Cow<str>, DeError> {
+	let err = || DeError::InvalidValue(format!("{:02X?}", bytes), format!("a string of type {}", tag));
+	match Tag::universal(tag.number) {
+		_ if tag.class != Class::Universal => Err(err()),
+		Tag::TELETEX_STRING => Ok(Cow::Owned(bytes.iter().map(|&b| b as char).collect())),
+		Tag::BMP_STRING if bytes.len().is_multiple_of(2) => char::decode_utf16(bytes.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])))
+			.collect::<Result<String, _>>()
+			.map(Cow::Owned)
+			.map_err(|_| err()),
+		Tag::UNIVERSAL_STRING if bytes.len().is_multiple_of(4) => bytes.chunks(4)
+			.map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+			.collect::<Option<String>>()
+			.map(Cow::Owned)
+			.ok_or_else(err),
+		Tag::OID => Ok(Cow::Owned(ObjectIdentifier::from_content(&bytes)?.to_string())),
+		_ if STRING_TAGS.iter().any(|t| t.same_as(tag)) => match bytes {
+			Cow::Borrowed(bytes) => Ok(Cow::Borrowed(std::str::from_utf8(bytes)?)),
+			Cow::Owned(bytes)    => Ok(Cow::Owned(String::from_utf8(bytes)?))
+		},
+		_ => Err(err())
+	}
+}
+
+/// The tag and type given by the name of a field, variant or struct.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Spec {
+	/// The tag and whether it is IMPLICIT.
+	tag: Option<(Tag, bool)>,
+	ty:  Option<Tag>
+}
+
+impl Spec {
+	fn parse(name: &str) -> Result<Self, String> {
+		fn skip_word(s: &str) -> &str {
+			s.split_once(char::is_whitespace).map_or("", |(_, rest)| rest.trim_start())
+		}
+		
+		let mut rest = name.trim();
+		if !rest.starts_with('[') && Self::type_tag(rest).is_none() {
+			rest = skip_word(rest);
+		}
+		
+		let mut spec = Self::default();
+		if let Some(tag) = rest.strip_prefix('[') {
+			let (tag, r) = tag.split_once(']').ok_or_else(|| format!("missing `]` in `{}`", name))?;
+			let (class, number) = match tag.split_whitespace().collect::<Vec<_>>()[..] {
+				["UNIVERSAL", n]   => (Class::Universal, n),
+				["APPLICATION", n] => (Class::Application, n),
+				["PRIVATE", n]     => (Class::Private, n),
+				[n]                => (Class::Context, n),
+				_ => return Err(format!("invalid tag `[{}]` in `{}`", tag, name))
+			};
+			
+			let number = number.parse().map_err(|_| format!("invalid tag number in `{}`", name))?;
+			rest = r.trim_start();
+			
+			let keyword = rest.split_whitespace().next();
+			if matches!(keyword, Some("IMPLICIT" | "EXPLICIT")) {
+				rest = skip_word(rest);
+			}
+			spec.tag = Some((Tag::new(class, false, number), keyword == Some("IMPLICIT")));
+		}
+		
+		spec.ty = Self::type_tag(rest);
+		Ok(spec)
+	}
+	
+	fn type_tag(s: &str) -> Option<Tag> {
+		let mut words = s.split_whitespace();
+		Some(match (words.next()?, words.next()) {
+			("BOOLEAN", _)                   => Tag::BOOLEAN,
+			("INTEGER", _)                   => Tag::INTEGER,
+			("BIT", Some("STRING"))          => Tag::BIT_STRING,
+			("OCTET", Some("STRING"))        => Tag::OCTET_STRING,
+			("NULL", _)                      => Tag::NULL,
+			("OBJECT", Some("IDENTIFIER"))   => Tag::OID,
+			("REAL", _)                      => Tag::REAL,
+			("ENUMERATED", _)                => Tag::ENUMERATED,
+			("UTF8String", _)                => Tag::UTF8_STRING,
+			("SEQUENCE", _)                  => Tag::SEQUENCE,
+			("SET", _)                       => Tag::SET,
+			("NumericString", _)             => Tag::NUMERIC_STRING,
+			("PrintableString", _)           => Tag::PRINTABLE_STRING,
+			("TeletexString" | "T61String", _) => Tag::TELETEX_STRING,
+			("IA5String", _)                 => Tag::IA5_STRING,
+			("UTCTime", _)                   => Tag::UTC_TIME,
+			("GeneralizedTime", _)           => Tag::GENERALIZED_TIME,
+			("VisibleString", _)             => Tag::VISIBLE_STRING,
+			("UniversalString", _)           => Tag::UNIVERSAL_STRING,
+			("BMPString", _)                 => Tag::BMP_STRING,
+			_ => return None
+		})
+	}
+	
+	fn is_empty(&self) -> bool {
+		self.tag.is_none() && self.ty.is_none()
+	}
+	
+	/// Returns true if an element with the given tag can be the value of this field or variant.
+	fn matches(&self, tag: Tag) -> bool {
+		match (self.tag, self.ty) {
+			(Some((t, _)), _) => t.same_as(tag),
+			(None, Some(ty))  => ty.same_as(tag),
+			(None, None)      => true
+		}
+	}
+	
+	fn hint(&self, default: Hint) -> Hint {
+		self.ty.map_or(default, Hint::Type)
+	}
+}
+
+/// How a value is encoded.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+enum Hint {
+	#[default]
+	None,
+	/// The type given by a name.
+	Type(Tag),
+	/// The value is the content of an element with the given tag.
+	Content(Tag),
+	/// The value is a complete element.
+	Raw
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct ValueSerializer(Hint);
+
+impl ValueSerializer {
+	/// Returns the tag of the value, the first tag is the default one.
+	fn tag(self, what: &str, tags: &[Tag]) -> Result<Tag, SerError> {
+		match self.0 {
+			Hint::None => Ok(tags[0]),
+			Hint::Type(t) | Hint::Content(t) => tags.iter().copied().find(|v| v.same_as(t))
+				.ok_or_else(|| SerError::Custom(format!("cannot encode {} as {}", what, t))),
+			Hint::Raw => Err(SerError::Custom(format!("cannot encode {} as an element", what)))
+		}
+	}
+	
+	fn element(self, what: &str, tags: &[Tag], content: Vec<u8>) -> Result<Option<Any>, SerError> {
+		Ok(Some(Any::new(self.tag(what, tags)?, content)))
+	}
+	
+	fn container(self, spec: Spec, set_order: Order) -> Result<SeqSerializer, SerError> {
+		let tag = Self(spec.hint(self.0)).tag("a sequence", &[Tag::SEQUENCE, Tag::SET])?;
+		Ok(SeqSerializer {
+			order: if tag.same_as(Tag::SET) { set_order } else { Order::Keep },
+			tag,
+			spec,
+			items: Vec::new(),
+			key:   None
+		})
+	}
+}
+
+impl ser::Serializer for ValueSerializer {
+	type Ok                     = Option<Any>;
+	type Error                  = SerError;
+	type SerializeSeq           = SeqSerializer;
+	type SerializeTuple         = SeqSerializer;
+	type SerializeTupleStruct   = SeqSerializer;
+	type SerializeTupleVariant  = SeqSerializer;
+	type SerializeMap           = SeqSerializer;
+	type SerializeStruct        = SeqSerializer;
+	type SerializeStructVariant = SeqSerializer;
+	
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		self.element("a boolean", &[Tag::BOOLEAN], vec![if v { 0xFF } else { 0 }])
+	}
+	
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+		self.element("an integer", &[Tag::INTEGER, Tag::ENUMERATED], minimal_int(&v.to_be_bytes()).to_vec())
+	}
+	
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		self.serialize_i128(v as _)
+	}
+	
+	fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+		self.element("an integer", &[Tag::INTEGER, Tag::ENUMERATED], Integer::from_unsigned(&v.to_be_bytes()).0)
+	}
+	
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		self.serialize_f64(v as _)
+	}
+	
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		self.element("a float", &[Tag::REAL], encode_real(v))
+	}
+	
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		self.serialize_str(v.encode_utf8(&mut [0; 4]))
+	}
+	
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		let tag = match self.0 {
+			Hint::None => Tag::UTF8_STRING,
+			Hint::Type(tag) | Hint::Content(tag) => tag,
+			Hint::Raw => return Err(SerError::Custom("cannot encode a string as an element".to_string()))
+		};
+		Ok(Some(Any::new(tag, encode_str(v, tag)?)))
+	}
+	
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		match self.0 {
+			Hint::Raw => match Element::parse(v) {
+				Ok((element, [])) => Ok(Some(element.into())),
+				_ => Err(SerError::Custom("invalid raw element".to_string()))
+			},
+			Hint::Content(Tag::INTEGER) if v.is_empty() => Err(SerError::Custom("empty integer".to_string())),
+			Hint::Content(Tag::INTEGER) => Ok(Some(Any::new(Tag::INTEGER, minimal_int(v).to_vec()))),
+			Hint::Content(Tag::BIT_STRING) => BitString::from_content(v)
+				.map(|v| Some(Any::new(Tag::BIT_STRING, v.to_content())))
+				.map_err(|e| SerError::Custom(e.to_string())),
+			Hint::Content(tag) => Ok(Some(Any::new(tag, v.to_vec()))),
+			_ => match self.tag("bytes", &[Tag::OCTET_STRING, Tag::BIT_STRING])? {
+				Tag::BIT_STRING => Ok(Some(Any::new(Tag::BIT_STRING, [&[0], v].concat()))),
+				tag => Ok(Some(Any::new(tag, v.to_vec())))
+			}
+		}
+	}
+	
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+	
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		self.element("a unit", &[Tag::NULL], Vec::new())
+	}
+	
+	fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+		let spec = Spec::parse(name).map_err(SerError::Custom)?;
+		Ok(Self(spec.hint(self.0)).serialize_unit()?.map(|v| v.tagged(&spec)))
+	}
+	
+	fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		let spec = Spec::parse(variant).map_err(SerError::Custom)?;
+		if !spec.is_empty() {
+			return Ok(Self(spec.hint(Hint::None)).serialize_unit()?.map(|v| v.tagged(&spec)));
+		}
+		
+		let value = variant.parse::<i64>().unwrap_or(variant_index as _);
+		self.element("an enumeration", &[Tag::ENUMERATED, Tag::INTEGER], minimal_int(&value.to_be_bytes()).to_vec())
+	}
+	
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		let hint = match name {
+			INTEGER_NAME          => Hint::Content(Tag::INTEGER),
+			BIT_STRING_NAME       => Hint::Content(Tag::BIT_STRING),
+			OID_NAME              => Hint::Content(Tag::OID),
+			UTC_TIME_NAME         => Hint::Content(Tag::UTC_TIME),
+			GENERALIZED_TIME_NAME => Hint::Content(Tag::GENERALIZED_TIME),
+			SET_OF_NAME           => Hint::Type(Tag::SET),
+			ANY_NAME              => Hint::Raw,
+			_ => {
+				let spec = Spec::parse(name).map_err(SerError::Custom)?;
+				return Ok(value.serialize(Self(spec.hint(self.0)))?.map(|v| v.tagged(&spec)));
+			}
+		};
+		value.serialize(Self(hint))
+	}
+	
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		let spec = Spec::parse(variant).map_err(SerError::Custom)?;
+		Ok(value.serialize(Self(spec.hint(Hint::None)))?.map(|v| v.tagged(&spec)))
+	}
+	
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		self.container(Spec::default(), Order::Encodings)
+	}
+	
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.container(Spec::default(), Order::Tags)
+	}
+	
+	fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.container(Spec::parse(name).map_err(SerError::Custom)?, Order::Tags)
+	}
+	
+	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Self(Hint::None).container(Spec::parse(variant).map_err(SerError::Custom)?, Order::Tags)
+	}
+	
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		self.container(Spec::default(), Order::Encodings)
+	}
+	
+	fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		self.container(Spec::parse(name).map_err(SerError::Custom)?, Order::Tags)
+	}
+	
+	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Self(Hint::None).container(Spec::parse(variant).map_err(SerError::Custom)?, Order::Tags)
+	}
+	
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+/// The order of the elements of a SET, SET OF sorts by encoding and SET by tag.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Order {
+	Keep,
+	Tags,
+	Encodings
+}
+
+struct SeqSerializer {
+	tag:   Tag,
+	order: Order,
+	spec:  Spec,
+	items: Vec<Any>,
+	key:   Option<Any>
+}
+
+impl SeqSerializer {
+	fn push<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), SerError> {
+		let spec = Spec::parse(name).map_err(SerError::Custom)?;
+		if let Some(v) = value.serialize(ValueSerializer(spec.hint(Hint::None)))? {
+			self.items.push(v.tagged(&spec));
+		}
+		Ok(())
+	}
+	
+	fn finish(self) -> Result<Option<Any>, SerError> {
+		let mut items = self.items;
+		if self.order == Order::Tags {
+			items.sort_by_key(|v| v.tag);
+		}
+		
+		let mut items = items.iter().map(Any::to_vec).collect::<Vec<_>>();
+		if self.order == Order::Encodings {
+			items.sort();
+		}
+		Ok(Some(Any::new(self.tag, items.concat()).tagged(&self.spec)))
+	}
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.push("", value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.push("", value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.push("", value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.push("", value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeMap for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = key.serialize(ValueSerializer::default())?;
+		Ok(())
+	}
+	
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let (Some(key), Some(value)) = (self.key.take(), value.serialize(ValueSerializer::default())?) else {
+			return Err(SerError::Custom("map keys and values cannot be `None`".to_string()));
+		};
+		self.items.push(Any::new(Tag::SEQUENCE, [key.to_vec(), value.to_vec()].concat()));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeStruct for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		self.push(key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeStructVariant for SeqSerializer {
+	type Ok    = Option<Any>;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		self.push(key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+/// Deserializes a single element.
+#[derive(Copy, Clone, Debug)]
+pub struct Deserializer<'de> {
+	element:  Element<'de>,
+	hint:     Hint,
+	/// The tag was replaced by an IMPLICIT tag, the element is decoded as the requested type.
+	implicit: bool
+}
+
+impl<'de> Deserializer<'de> {
+	pub fn new(element: Element<'de>) -> Self {
+		Self { element, hint: Hint::None, implicit: false }
+	}
+	
+	/// Applies the tag and type of a field, variant or struct name.
+	fn tagged(mut self, spec: &Spec) -> Result<Self, DeError> {
+		if let Some((tag, implicit)) = spec.tag {
+			if !self.implicit && !self.element.tag.same_as(tag) {
+				return Err(DeError::InvalidType(format!("element {}", self.element.tag), format!("element {}", tag)));
+			}
+			
+			if implicit {
+				self.implicit = true;
+			} else {
+				let (element, rest) = Element::parse(self.element.content)?;
+				if !rest.is_empty() {
+					return Err(DeError::ParseError(format!("trailing data in explicitly tagged element {}", tag)));
+				}
+				
+				self.element = element;
+				self.implicit = false;
+			}
+		}
+		
+		self.hint = spec.hint(self.hint);
+		Ok(self)
+	}
+	
+	fn expect(&self, what: &str, tags: &[Tag]) -> Result<(), DeError> {
+		let tags = match self.hint {
+			Hint::Content(ref tag) => std::slice::from_ref(tag),
+			_ => tags
+		};
+		
+		match self.implicit || tags.iter().any(|t| t.same_as(self.element.tag)) {
+			true  => Ok(()),
+			false => Err(DeError::InvalidType(format!("element {}", self.element.tag), what.to_string()))
+		}
+	}
+	
+	/// Returns the type of the element, i.e. the type given by its name if it is implicitly tagged.
+	fn ty(&self) -> Option<Tag> {
+		match (self.implicit, self.hint) {
+			(true, Hint::Type(tag) | Hint::Content(tag)) => Some(tag),
+			(true, _) => None,
+			(false, _) => (self.element.tag.class == Class::Universal).then_some(self.element.tag)
+		}.map(|t| Tag::universal(t.number))
+	}
+	
+	fn string(&self) -> Result<Cow<'de, str>, DeError> {
+		self.expect("a string", STRING_TAGS)?;
+		decode_str(self.element.bytes()?, self.ty().unwrap_or(Tag::UTF8_STRING))
+	}
+	
+	fn bytes(&self) -> Result<Cow<'de, [u8]>, DeError> {
+		match self.hint {
+			Hint::Raw => return Ok(Cow::Owned(self.element.to_vec())),
+			Hint::Content(Tag::BIT_STRING) => {
+				self.expect("a bit string", &[])?;
+				return self.element.bit_string();
+			}
+			Hint::Content(_) => {
+				self.expect("", &[])?;
+				return self.element.bytes();
+			}
+			_ => self.expect("bytes", &[Tag::OCTET_STRING, Tag::BIT_STRING])?
+		}
+		
+		match self.ty() {
+			Some(Tag::BIT_STRING) => match self.element.bit_string()? {
+				Cow::Borrowed(v) => Ok(Cow::Borrowed(v.get(1..).unwrap_or_default())),
+				Cow::Owned(v)    => Ok(Cow::Owned(v[1..].to_vec()))
+			},
+			_ => self.element.bytes()
+		}
+	}
+	
+	fn integer(&self) -> Result<i128, DeError> {
+		self.expect("an integer", &[Tag::INTEGER, Tag::ENUMERATED])?;
+		int_value(self.element.content)
+	}
+	
+	fn visit_integer<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+		if let [0, v @ ..] = self.element.content {
+			if v.len() == 16 {
+				self.expect("an integer", &[Tag::INTEGER, Tag::ENUMERATED])?;
+				return visitor.visit_u128(u128::from_be_bytes(v.try_into().unwrap()));
+			}
+		}
+		
+		let v = self.integer()?;
+		if let Ok(v) = i64::try_from(v) {
+			visitor.visit_i64(v)
+		} else if let Ok(v) = u64::try_from(v) {
+			visitor.visit_u64(v)
+		} else {
+			visitor.visit_i128(v)
+		}
+	}
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.ty() {
+			Some(Tag::BOOLEAN) => self.deserialize_bool(visitor),
+			Some(Tag::INTEGER | Tag::ENUMERATED) => self.visit_integer(visitor),
+			Some(Tag::REAL) => self.deserialize_f64(visitor),
+			Some(Tag::NULL) => self.deserialize_unit(visitor),
+			Some(Tag::OCTET_STRING | Tag::BIT_STRING) => self.deserialize_bytes(visitor),
+			Some(tag) if STRING_TAGS.iter().any(|t| t.same_as(tag)) => self.deserialize_str(visitor),
+			_ if self.element.tag.constructed => visitor.visit_seq(SeqAccess(self.element.children())),
+			_ => visitor.visit_borrowed_bytes(self.element.content)
+		}
+	}
+	
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.expect("a boolean", &[Tag::BOOLEAN])?;
+		match self.element.content {
+			[v] => visitor.visit_bool(*v != 0),
+			_ => Err(DeError::ParseError("invalid boolean".to_string()))
+		}
+	}
+	
+	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.visit_integer(visitor)
+	}
+	
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_f64(visitor)
+	}
+	
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.expect("a real", &[Tag::REAL])?;
+		visitor.visit_f64(decode_real(self.element.content)?)
+	}
+	
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_str(visitor)
+	}
+	
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.string()? {
+			Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+			Cow::Owned(v)    => visitor.visit_string(v)
+		}
+	}
+	
+	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_str(visitor)
+	}
+	
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.bytes()? {
+			Cow::Borrowed(v) => visitor.visit_borrowed_bytes(v),
+			Cow::Owned(v)    => visitor.visit_byte_buf(v)
+		}
+	}
+	
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_bytes(visitor)
+	}
+	
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+	
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.expect("null", &[Tag::NULL])?;
+		match self.element.content {
+			[] => visitor.visit_unit(),
+			_  => Err(DeError::ParseError("invalid null".to_string()))
+		}
+	}
+	
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		self.tagged(&Spec::parse(name).map_err(DeError::Custom)?)?.deserialize_unit(visitor)
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		let hint = match name {
+			INTEGER_NAME          => Hint::Content(Tag::INTEGER),
+			BIT_STRING_NAME       => Hint::Content(Tag::BIT_STRING),
+			OID_NAME              => Hint::Content(Tag::OID),
+			UTC_TIME_NAME         => Hint::Content(Tag::UTC_TIME),
+			GENERALIZED_TIME_NAME => Hint::Content(Tag::GENERALIZED_TIME),
+			SET_OF_NAME           => Hint::Type(Tag::SET),
+			ANY_NAME              => Hint::Raw,
+			_ => return visitor.visit_newtype_struct(self.tagged(&Spec::parse(name).map_err(DeError::Custom)?)?)
+		};
+		visitor.visit_newtype_struct(Self { hint, ..self })
+	}
+	
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.expect("a sequence", &[Tag::SEQUENCE, Tag::SET])?;
+		visitor.visit_seq(SeqAccess(self.element.children()))
+	}
+	
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+	
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.tagged(&Spec::parse(name).map_err(DeError::Custom)?)?.deserialize_seq(visitor)
+	}
+	
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.expect("a sequence", &[Tag::SEQUENCE, Tag::SET])?;
+		visitor.visit_map(MapAccess { entries: self.element.children(), value: None })
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		let de = self.tagged(&Spec::parse(name).map_err(DeError::Custom)?)?;
+		de.expect("a sequence", &[Tag::SEQUENCE, Tag::SET])?;
+		
+		let set = de.ty().is_some_and(|t| t.same_as(Tag::SET));
+		let specs = fields.iter()
+			.map(|f| Spec::parse(f))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(DeError::Custom)?;
+		
+		visitor.visit_map(StructAccess {
+			elements: de.element.children().collect::<Result<_, _>>()?,
+			next:     0,
+			fields,
+			used:     vec![false; specs.len()],
+			specs,
+			field:    0,
+			set,
+			value:    None
+		})
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		let specs = variants.iter()
+			.map(|v| Spec::parse(v))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(DeError::Custom)?;
+		
+		let tag = self.element.tag;
+		let choice = specs.iter().any(|s| !s.is_empty());
+		let enumerated = !self.implicit && tag.same_as(Tag::ENUMERATED) && !specs.iter().any(|s| !s.is_empty() && s.matches(tag));
+		
+		if !choice || enumerated {
+			let value = self.integer()?;
+			let variant = variants.iter()
+				.position(|v| v.parse::<i128>() == Ok(value))
+				.or_else(|| usize::try_from(value).ok().filter(|&i| variants.get(i).is_some_and(|v| v.parse::<i128>().is_err())))
+				.ok_or_else(|| DeError::InvalidValue(value.to_string(), "a variant of the enumeration".to_string()))?;
+			return visitor.visit_enum(IntoDeserializer::<DeError>::into_deserializer(variants[variant]));
+		} else if self.implicit {
+			return Err(DeError::ParseError("a CHOICE cannot be implicitly tagged".to_string()));
+		}
+		
+		let variant = specs.iter()
+			.position(|s| !s.is_empty() && s.matches(tag))
+			.or_else(|| specs.iter().position(Spec::is_empty))
+			.ok_or_else(|| DeError::InvalidType(format!("element {}", tag), "a variant of the choice".to_string()))?;
+		
+		visitor.visit_enum(ChoiceAccess { variant: variants[variant], de: self.tagged(&specs[variant])? })
+	}
+	
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_str(visitor)
+	}
+	
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+	type Error = DeError;
+	
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		de::Deserialize::deserialize(self)
+	}
+	
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		seed.deserialize(self)
+	}
+	
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_seq(self, visitor)
+	}
+	
+	fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_struct(self, "", fields, visitor)
+	}
+}
+
+struct SeqAccess<'de>(Elements<'de>);
+
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de> {
+	type Error = DeError;
+	
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		match self.0.next() {
+			Some(element) => seed.deserialize(Deserializer::new(element?)).map(Some),
+			None => Ok(None)
+		}
+	}
+}
+
+/// Yields the entries of a SEQUENCE OF SEQUENCE { key, value }.
+struct MapAccess<'de> {
+	entries: Elements<'de>,
+	value:   Option<Element<'de>>
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		let Some(entry) = self.entries.next().transpose()? else {
+			return Ok(None);
+		};
+		
+		let mut items = entry.children();
+		match (entry.tag.same_as(Tag::SEQUENCE), items.next().transpose()?, items.next().transpose()?, items.next()) {
+			(true, Some(key), Some(value), None) => {
+				self.value = Some(value);
+				seed.deserialize(Deserializer::new(key)).map(Some)
+			}
+			_ => Err(DeError::ParseError("map entries must be a SEQUENCE { key, value }".to_string()))
+		}
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		let value = self.value.take().ok_or_else(|| DeError::Custom("value requested before key".to_string()))?;
+		seed.deserialize(Deserializer::new(value))
+	}
+}
+
+/// Assigns the elements of a SEQUENCE or SET to fields, fields without an element are missing.
+struct StructAccess<'de> {
+	elements: Vec<Element<'de>>,
+	next:     usize,
+	fields:   &'static [&'static str],
+	specs:    Vec<Spec>,
+	used:     Vec<bool>,
+	/// The first field that can be assigned in a SEQUENCE.
+	field:    usize,
+	set:      bool,
+	value:    Option<Deserializer<'de>>
+}
+
+impl<'de> de::MapAccess<'de> for StructAccess<'de> {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		while let Some(&element) = self.elements.get(self.next) {
+			self.next += 1;
+			
+			let start = if self.set { 0 } else { self.field };
+			let Some(i) = (start..self.fields.len()).find(|&i| !self.used[i] && self.specs[i].matches(element.tag)) else {
+				continue;
+			};
+			
+			self.used[i] = true;
+			self.field = i + 1;
+			self.value = Some(Deserializer::new(element).tagged(&self.specs[i])?);
+			return seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(self.fields[i])).map(Some);
+		}
+		Ok(None)
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		let value = self.value.take().ok_or_else(|| DeError::Custom("value requested before key".to_string()))?;
+		seed.deserialize(value)
+	}
+}
+
+struct ChoiceAccess<'de> {
+	variant: &'static str,
+	de:      Deserializer<'de>
+}
+
+impl<'de> de::EnumAccess<'de> for ChoiceAccess<'de> {
+	type Error   = DeError;
+	type Variant = Deserializer<'de>;
+	
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let variant = seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(self.variant))?;
+		Ok((variant, self.de))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::collections::BTreeMap};
+	
+	fn hex(s: &str) -> Vec<u8> {
+		let s = s.split_whitespace().collect::<String>();
+		(0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+	}
+	
+	fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + fmt::Debug>(value: T, expected: &[u8]) {
+		let data = serialize_vec(&value).unwrap();
+		assert_eq!(data, expected, "{:?}", value);
+		assert_eq!(deserialize_slice::<T>(&data).unwrap(), value);
+	}
+	
+	#[test]
+	fn tags() {
+		for (tag, data) in [
+			(Tag::INTEGER, &[0x02][..]),
+			(Tag::SEQUENCE, &[0x30]),
+			(Tag::context(0), &[0x80]),
+			(Tag::application(30), &[0x5E]),
+			(Tag::new(Class::Private, true, 31), &[0xFF, 0x1F]),
+			(Tag::new(Class::Application, true, 200), &[0x7F, 0x81, 0x48])
+		] {
+			let mut out = Vec::new();
+			tag.write(&mut out);
+			assert_eq!(out, data);
+			assert_eq!(Tag::parse(&mut &out[..]).unwrap(), tag);
+		}
+		
+		assert!(Tag::parse(&mut &[0x1F, 0x80, 0x01][..]).is_err());
+		assert!(Tag::parse(&mut &[0x1F, 0x81][..]).is_err());
+		assert!(Tag::context(1) < Tag::private(0) && Tag::universal(31) < Tag::application(0));
+		
+		let mut out = Vec::new();
+		write_len(&mut out, 0x1234);
+		assert_eq!(out, [0x82, 0x12, 0x34]);
+		assert_eq!(read_len(&mut &out[..]).unwrap(), Some(0x1234));
+		assert_eq!(read_len(&mut &[0x80][..]).unwrap(), None);
+		
+		let mut out = Vec::new();
+		out.write_ber_int(BER_INTEGER, -129).unwrap();
+		out.write_ber_int(BER_INTEGER, 1 << 40).unwrap();
+		assert_eq!(out, [0x02, 0x02, 0xFF, 0x7F, 0x02, 0x06, 0x01, 0, 0, 0, 0, 0]);
+		
+		let mut reader = &out[..];
+		assert_eq!(reader.read_ber_int(BER_INTEGER).unwrap(), -129);
+		assert_eq!(reader.read_ber_int(BER_INTEGER).unwrap(), 1 << 40);
+	}
+	
+	#[test]
+	fn primitives() {
+		roundtrip(true, &[0x01, 0x01, 0xFF]);
+		roundtrip(0u8, &[0x02, 0x01, 0x00]);
+		roundtrip(127i32, &[0x02, 0x01, 0x7F]);
+		roundtrip(128u16, &[0x02, 0x02, 0x00, 0x80]);
+		roundtrip(-128i8, &[0x02, 0x01, 0x80]);
+		roundtrip(-129i64, &[0x02, 0x02, 0xFF, 0x7F]);
+		roundtrip(u64::MAX, &[0x02, 0x09, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+		roundtrip(u128::MAX, &[[0x02, 0x11, 0].as_slice(), &[0xFF; 16]].concat());
+		roundtrip(i128::MIN, &[[0x02, 0x10, 0x80].as_slice(), &[0; 15]].concat());
+		roundtrip((), &[0x05, 0x00]);
+		roundtrip("hi".to_string(), &[0x0C, 0x02, b'h', b'i']);
+		roundtrip(OctetString(vec![1, 2]), &[0x04, 0x02, 1, 2]);
+		roundtrip(vec![1u8, 2], &[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]);
+		roundtrip(Integer::from_unsigned(&[0, 0, 0x80]), &[0x02, 0x02, 0x00, 0x80]);
+		roundtrip(Integer::from(-1i64), &[0x02, 0x01, 0xFF]);
+		
+		assert!(deserialize_slice::<u8>(&[0x02, 0x02, 0x01, 0x00]).is_err());
+		assert!(deserialize_slice::<bool>(&[0x02, 0x01, 0x01]).is_err());
+		assert!(deserialize_slice::<i32>(&[0x02, 0x01, 0x01, 0x00]).is_err());
+		assert_eq!(Integer::from_unsigned(&[0x01, 0xFF]).as_unsigned(), Some(&[0x01, 0xFF][..]));
+		assert_eq!(Integer::from(-5i64).to_i128(), Some(-5));
+		assert_eq!(Integer(vec![0xFF, 0xFF, 0xFB]).to_i128(), Some(-5));
+	}
+	
+	#[test]
+	fn reals() {
+		roundtrip(0.0f64, &[0x09, 0x00]);
+		roundtrip(1.0f64, &[0x09, 0x03, 0x80, 0x00, 0x01]);
+		roundtrip(0.5f64, &[0x09, 0x03, 0x80, 0xFF, 0x01]);
+		roundtrip(-3.0f32, &[0x09, 0x03, 0xC0, 0x00, 0x03]);
+		roundtrip(f64::NEG_INFINITY, &[0x09, 0x01, 0x41]);
+		
+		for v in [1e300, -1e-300, f64::MIN_POSITIVE, 5e-324, f64::MAX, std::f64::consts::PI] {
+			assert_eq!(deserialize_slice::<f64>(&serialize_vec(&v).unwrap()).unwrap(), v);
+		}
+		
+		assert!(deserialize_slice::<f64>(&[0x09, 0x01, 0x42]).unwrap().is_nan());
+		assert!(deserialize_slice::<f64>(&[0x09, 0x01, 0x43]).unwrap().is_sign_negative());
+		assert_eq!(deserialize_slice::<f64>(&[0x09, 0x04, 0x03, b'1', b'E', b'3']).unwrap(), 1000.0);
+		assert_eq!(deserialize_slice::<f64>(&[0x09, 0x03, 0x90, 0xFE, 0x0A]).unwrap(), 0.15625);
+	}
+	
+	#[test]
+	fn strings() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Strings {
+			#[serde(rename = "a PrintableString")]
+			a: String,
+			#[serde(rename = "b IA5String")]
+			b: String,
+			#[serde(rename = "c BMPString")]
+			c: String,
+			#[serde(rename = "d UniversalString")]
+			d: String,
+			#[serde(rename = "e OBJECT IDENTIFIER")]
+			e: String,
+			#[serde(rename = "f BIT STRING")]
+			f: OctetString
+		}
+		
+		roundtrip(Strings {
+			a: "A b".to_string(),
+			b: "x@y".to_string(),
+			c: "é".to_string(),
+			d: "😀".to_string(),
+			e: "2.5.4.3".to_string(),
+			f: OctetString(vec![0xAA])
+		}, &hex("301D 1303 412062 1603 784079 1E02 00E9 1C04 0001F600 0603 550403 0302 00AA"));
+		
+		assert_eq!(deserialize_slice::<String>(&hex("14 02 E9 41")).unwrap(), "éA");
+		assert_eq!(deserialize_slice::<String>(&hex("24 80 04 01 61 04 01 62 00 00")).unwrap(), "ab");
+		assert!(serialize_vec(&Strings { a: "a@b".to_string(), ..deserialize_slice(&hex(
+			"301D 1303 412062 1603 784079 1E02 00E9 1C04 0001F600 0603 550403 0302 00AA")).unwrap() }).is_err());
+	}
+	
+	#[test]
+	fn oids_and_bit_strings() {
+		let oid = "1.2.840.113549.1.1.11".parse::<ObjectIdentifier>().unwrap();
+		assert_eq!(oid.to_content().unwrap(), hex("2A 86 48 86 F7 0D 01 01 0B"));
+		assert_eq!(oid.to_string(), "1.2.840.113549.1.1.11");
+		roundtrip(oid, &hex("06 09 2A 86 48 86 F7 0D 01 01 0B"));
+		roundtrip(ObjectIdentifier(vec![2, 999, 3]), &hex("06 03 88 37 03"));
+		
+		assert!("1.40".parse::<ObjectIdentifier>().is_err());
+		assert!("3.1".parse::<ObjectIdentifier>().is_err());
+		assert!("1".parse::<ObjectIdentifier>().is_err());
+		assert!(ObjectIdentifier::from_content(&[0x2A, 0x86]).is_err());
+		assert!(ObjectIdentifier::from_content(&[0x2A, 0x80, 0x01]).is_err());
+		
+		let mut bits = BitString::default();
+		bits.set(0, true);
+		bits.set(5, true);
+		assert_eq!((bits.len(), bits.get(0), bits.get(1), bits.get(5)), (6, true, false, true));
+		roundtrip(bits.clone(), &[0x03, 0x02, 0x02, 0x84]);
+		
+		bits.set(12, false);
+		bits.trim();
+		assert_eq!(bits, BitString { data: vec![0x84], unused: 2 });
+		assert_eq!(BitString { data: vec![0xFF], unused: 4 }.to_content(), [4, 0xF0]);
+		assert_eq!(deserialize_slice::<BitString>(&hex("23 80 03 02 00 0A 03 02 04 F0 00 00")).unwrap(),
+			BitString { data: vec![0x0A, 0xF0], unused: 4 });
+		assert!(deserialize_slice::<BitString>(&[0x03, 0x02, 0x08, 0x00]).is_err());
+	}
+	
+	#[test]
+	fn times() {
+		let time = |year, month, day, hour, minute, second| DateTime { year, month, day, hour, minute, second, nanosecond: 0 };
+		
+		assert_eq!("991231235959Z".parse::<UtcTime>().unwrap().0, time(1999, 12, 31, 23, 59, 59));
+		assert_eq!("491231235959Z".parse::<UtcTime>().unwrap().0, time(2049, 12, 31, 23, 59, 59));
+		assert_eq!("0001010030+0100".parse::<UtcTime>().unwrap().0, time(1999, 12, 31, 23, 30, 0));
+		assert!("20240229".parse::<GeneralizedTime>().is_err());
+		assert_eq!("2024022912".parse::<GeneralizedTime>().unwrap().0, time(2024, 2, 29, 12, 0, 0));
+		assert_eq!("20240229120000.5-0230".parse::<GeneralizedTime>().unwrap().0,
+			DateTime { nanosecond: 500_000_000, ..time(2024, 2, 29, 14, 30, 0) });
+		
+		for s in ["230229000000Z", "991231240000Z", "9912312359Z0", "9912312359", "20241301000000Z", "20240101000000.Z"] {
+			assert!(s.parse::<UtcTime>().is_err() && s.parse::<GeneralizedTime>().is_err(), "{}", s);
+		}
+		
+		let t = time(2024, 2, 29, 12, 34, 56);
+		assert_eq!(t.unix_timestamp(), 1709210096);
+		assert_eq!(DateTime::from_unix_timestamp(1709210096), Some(t));
+		assert_eq!(DateTime::from_unix_timestamp(-1), Some(time(1969, 12, 31, 23, 59, 59)));
+		assert_eq!(DateTime { nanosecond: 120_000_000, ..t }.to_string(), "2024-02-29T12:34:56.12Z");
+		
+		roundtrip(UtcTime(t), b"\x17\x0D240229123456Z");
+		roundtrip(GeneralizedTime(DateTime { nanosecond: 5_000_000, ..t }), b"\x18\x1320240229123456.005Z");
+		assert!(serialize_vec(&UtcTime(time(2050, 1, 1, 0, 0, 0))).is_err());
+		assert!(deserialize_slice::<UtcTime>(b"\x18\x0F20240229123456Z").is_err());
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Tagged {
+		id:       u32,
+		#[serde(rename = "name [0]")]
+		name:     Option<String>,
+		#[serde(rename = "flags [1] IMPLICIT")]
+		flags:    Option<BitString>,
+		#[serde(rename = "critical BOOLEAN", default, skip_serializing_if = "std::ops::Not::not")]
+		critical: bool,
+		#[serde(rename = "[APPLICATION 40] IMPLICIT SET")]
+		values:   Vec<i32>,
+		kind:     Kind,
+		shape:    Shape
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Kind {
+		A,
+		#[serde(rename = "5")]
+		B
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Shape {
+		#[serde(rename = "circle [0] IMPLICIT")]
+		Circle(f64),
+		#[serde(rename = "box [1]")]
+		Box { w: u8, h: u8 },
+		#[serde(rename = "none [2] IMPLICIT")]
+		None,
+		Other(String)
+	}
+	
+	#[test]
+	fn tagging() {
+		let mut value = Tagged {
+			id:       1,
+			name:     Some("a".to_string()),
+			flags:    Some(BitString { data: vec![0x80], unused: 7 }),
+			critical: true,
+			values:   vec![3, -1, 256],
+			kind:     Kind::B,
+			shape:    Shape::Box { w: 2, h: 3 }
+		};
+		
+		assert_eq!(serialize_vec(&value).unwrap(), hex("30 29 020101 A003 0C0161 8102 0780 0101FF 7F28 0A 020103 0201FF 02020100 0A0105 \
+			A108 3006 020102 020103"));
+		let data = serialize_vec(&value).unwrap();
+		assert_eq!(deserialize_slice::<Tagged>(&data).unwrap(), value);
+		
+		value.name = None;
+		value.flags = None;
+		value.critical = false;
+		value.kind = Kind::A;
+		value.shape = Shape::Other("x".to_string());
+		let data = serialize_vec(&value).unwrap();
+		assert_eq!(data, hex("30 16 020101 7F28 0A 020103 0201FF 02020100 0A0100 0C0178"));
+		assert_eq!(deserialize_slice::<Tagged>(&data).unwrap(), value);
+		
+		value.shape = Shape::Circle(0.5);
+		assert_eq!(deserialize_slice::<Tagged>(&serialize_vec(&value).unwrap()).unwrap(), value);
+		value.shape = Shape::None;
+		assert!(serialize_vec(&value).unwrap().ends_with(&[0x82, 0x00]));
+		assert_eq!(deserialize_slice::<Tagged>(&serialize_vec(&value).unwrap()).unwrap(), value);
+		
+		let mut map = BTreeMap::new();
+		map.insert("b".to_string(), 2);
+		map.insert("a".to_string(), 1);
+		roundtrip(map, &hex("30 10 3006 0C0161 020101 3006 0C0162 020102"));
+		
+		assert!(deserialize_slice::<Tagged>(&hex("30 03 020101")).is_err());
+		assert!(deserialize_slice::<Kind>(&hex("0A 01 07")).is_err());
+	}
+	
+	#[test]
+	fn sets() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		#[serde(rename = "Record SET")]
+		struct Record {
+			#[serde(rename = "name [1] IMPLICIT")]
+			name: String,
+			#[serde(rename = "id [0] IMPLICIT")]
+			id:   u8,
+			ok:   bool
+		}
+		
+		roundtrip(SetOf(vec![vec![1u8], vec![2], vec![1, 1]]), &hex("31 12 3003 020101 3003 020102 3006 020101 020101"));
+		assert_eq!(serialize_vec(&SetOf(vec![vec![2u8], vec![1, 1], vec![1]])).unwrap(), serialize_vec(&SetOf(vec![vec![1u8], vec![2], vec![1, 1]])).unwrap());
+		roundtrip(Record { name: "n".to_string(), id: 7, ok: true }, &hex("31 09 0101FF 800107 81016E"));
+		assert_eq!(deserialize_slice::<Record>(&hex("31 09 81016E 0101FF 800107")).unwrap(),
+			Record { name: "n".to_string(), id: 7, ok: true });
+	}
+	
+	#[test]
+	fn indefinite_lengths() {
+		#[derive(Debug, PartialEq, Deserialize)]
+		struct Message {
+			id:   u8,
+			data: OctetString,
+			#[serde(rename = "[0]")]
+			tail: Vec<bool>
+		}
+		
+		let data = hex("30 80 020105 24 80 0402 0102 0401 03 0000 A0 80 30 80 0101FF 0101 00 0000 0000 0000");
+		let expected = Message { id: 5, data: OctetString(vec![1, 2, 3]), tail: vec![true, false] };
+		assert_eq!(deserialize_slice::<Message>(&data).unwrap(), expected);
+		
+		let reader = [&data[..], &[0x05, 0x00]].concat();
+		let mut reader = &reader[..];
+		assert_eq!(read_element(&mut reader).unwrap(), data);
+		assert_eq!(read_element(&mut reader).unwrap(), [0x05, 0x00]);
+		assert!(read_element(&mut reader).is_err());
+		assert_eq!(deserialize::<Message, _>(&data[..]).unwrap(), expected);
+		
+		let (element, rest) = Element::parse(&data).unwrap();
+		assert_eq!((element.tag, element.content.len(), rest.len()), (Tag::SEQUENCE, data.len() - 4, 0));
+		assert_eq!(element.children().count(), 3);
+		assert_eq!(Any::from(element).deserialize_to::<Message>().unwrap(), expected);
+		
+		assert!(Element::parse(&hex("30 80 020105")).is_err());
+		assert!(Element::parse(&hex("04 80 00 00")).is_err());
+		assert!(Element::parse(&hex("30 05 020105")).is_err());
+		assert!(read_element(&hex("30 80 020105")[..]).is_err());
+		assert!(deserialize_slice::<u8>(&hex("020105 00")).is_err());
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct LdapMessage {
+		id:       i32,
+		op:       ProtocolOp,
+		#[serde(rename = "controls [0] IMPLICIT")]
+		controls: Option<Vec<Control>>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Control {
+		#[serde(rename = "type OCTET STRING")]
+		ty:       String,
+		#[serde(rename = "criticality BOOLEAN", default)]
+		critical: bool,
+		value:    Option<OctetString>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum ProtocolOp {
+		#[serde(rename = "bindRequest [APPLICATION 0] IMPLICIT")]
+		BindRequest {
+			version:        u8,
+			#[serde(rename = "name OCTET STRING")]
+			name:           String,
+			authentication: Authentication
+		},
+		#[serde(rename = "bindResponse [APPLICATION 1] IMPLICIT")]
+		BindResponse {
+			code:       ResultCode,
+			#[serde(rename = "matchedDN OCTET STRING")]
+			matched_dn: String,
+			#[serde(rename = "diagnosticMessage OCTET STRING")]
+			message:    String
+		},
+		#[serde(rename = "unbindRequest [APPLICATION 2] IMPLICIT")]
+		UnbindRequest
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Authentication {
+		#[serde(rename = "simple [0] IMPLICIT")]
+		Simple(OctetString),
+		#[serde(rename = "sasl [3] IMPLICIT")]
+		Sasl {
+			#[serde(rename = "mechanism OCTET STRING")]
+			mechanism:   String,
+			credentials: Option<OctetString>
+		}
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum ResultCode {
+		#[serde(rename = "0")]
+		Success,
+		#[serde(rename = "49")]
+		InvalidCredentials
+	}
+	
+	#[test]
+	fn ldap() {
+		roundtrip(LdapMessage {
+			id:       1,
+			op:       ProtocolOp::BindRequest {
+				version:        3,
+				name:           String::new(),
+				authentication: Authentication::Simple(OctetString::default())
+			},
+			controls: None
+		}, &hex("30 0C 020101 60 07 020103 0400 8000"));
+		
+		roundtrip(LdapMessage {
+			id:       2,
+			op:       ProtocolOp::BindResponse {
+				code:       ResultCode::InvalidCredentials,
+				matched_dn: String::new(),
+				message:    "no".to_string()
+			},
+			controls: Some(vec![Control { ty: "1.2".to_string(), critical: true, value: None }])
+		}, &hex("30 1A 020102 61 09 0A0131 0400 0402 6E6F A0 0A 30 08 0403 312E32 0101FF"));
+		
+		roundtrip(LdapMessage { id: 3, op: ProtocolOp::UnbindRequest, controls: None }, &hex("30 05 020103 4200"));
+		
+		roundtrip(LdapMessage {
+			id:       4,
+			op:       ProtocolOp::BindRequest {
+				version:        3,
+				name:           "cn=a".to_string(),
+				authentication: Authentication::Sasl { mechanism: "EXTERNAL".to_string(), credentials: None }
+			},
+			controls: None
+		}, &hex("30 1A 020104 60 15 020103 0404 636E3D61 A3 0A 0408 45585445524E414C"));
+		
+		assert!(deserialize_slice::<LdapMessage>(&hex("30 05 020103 4300")).is_err());
+	}
+	
+	const CERTIFICATE: &str = "
+		308201dc30820182a00302010202091234567890abcdef01300a06082a8648ce3d0403023035310b3009060355040613
+		0241543110300e060355040a0c074578616d706c653114301206035504030c0b6578616d706c652e6f7267301e170d32
+		36313031393030343131355a170d3237313031393030343131355a3035310b30090603550406130241543110300e0603
+		55040a0c074578616d706c653114301206035504030c0b6578616d706c652e6f72673059301306072a8648ce3d020106
+		082a8648ce3d03010703420004db39b1cb5ea4d4d0606acdb20599cb7843b8934975025d0b59439afa96377ff5715a4a
+		5ddc8fdc297b7026d84ceeba6e2700c5fe612ccb58d2a623c2692a5ff8a37b3079301d0603551d0e041604145c6429b7
+		47bce3039c2752d68d090e1e862d38aa301f0603551d230418301680145c6429b747bce3039c2752d68d090e1e862d38
+		aa300f0603551d130101ff040530030101ff300e0603551d0f0101ff04040302028430160603551d11040f300d820b65
+		78616d706c652e6f7267300a06082a8648ce3d0403020348003045022100c808ffcd21671eff043661751e32e9e6d2dd
+		a5f0ebfe9260a10ab87cb06db0dd02207e4fbf0b1474ef22b71c99b777d31a9edb987cfde700e76dc475c533c25b22bf";
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Certificate {
+		tbs:       TbsCertificate,
+		algorithm: AlgorithmIdentifier,
+		signature: BitString
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct TbsCertificate {
+		#[serde(rename = "version [0]")]
+		version:    Option<u8>,
+		serial:     Integer,
+		signature:  AlgorithmIdentifier,
+		issuer:     Vec<SetOf<Attribute>>,
+		validity:   (Time, Time),
+		subject:    Vec<SetOf<Attribute>>,
+		key:        (AlgorithmIdentifier, BitString),
+		#[serde(rename = "extensions [3]")]
+		extensions: Option<Vec<Extension>>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct AlgorithmIdentifier {
+		algorithm:  ObjectIdentifier,
+		parameters: Option<Any>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Attribute {
+		ty:    ObjectIdentifier,
+		value: Any
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Time {
+		#[serde(rename = "UTCTime")]
+		Utc(UtcTime),
+		#[serde(rename = "GeneralizedTime")]
+		Generalized(GeneralizedTime)
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Extension {
+		id:       ObjectIdentifier,
+		#[serde(rename = "critical BOOLEAN", default, skip_serializing_if = "std::ops::Not::not")]
+		critical: bool,
+		value:    OctetString
+	}
+	
+	#[test]
+	fn certificate() {
+		let data = hex(CERTIFICATE);
+		let cert = deserialize_slice::<Certificate>(&data).unwrap();
+		let tbs = &cert.tbs;
+		
+		assert_eq!(tbs.version, Some(2));
+		assert_eq!(tbs.serial.as_unsigned(), Some(&hex("1234567890abcdef01")[..]));
+		assert_eq!(cert.algorithm.algorithm.to_string(), "1.2.840.10045.4.3.2");
+		assert_eq!(cert.algorithm.parameters, None);
+		assert_eq!(tbs.issuer, tbs.subject);
+		assert_eq!(tbs.subject.len(), 3);
+		assert_eq!(tbs.subject[2].0[0].ty.to_string(), "2.5.4.3");
+		assert_eq!(tbs.subject[2].0[0].value.deserialize_to::<String>().unwrap(), "example.org");
+		assert_eq!(tbs.subject[0].0[0].value.tag, Tag::PRINTABLE_STRING);
+		assert_eq!(tbs.validity.0, Time::Utc(UtcTime(DateTime {
+			year: 2026, month: 10, day: 19, hour: 0, minute: 41, second: 15, nanosecond: 0 })));
+		assert_eq!(tbs.key.0.parameters.as_ref().unwrap().deserialize_to::<ObjectIdentifier>().unwrap().to_string(),
+			"1.2.840.10045.3.1.7");
+		assert_eq!(tbs.key.1.len(), 520);
+		
+		let extensions = tbs.extensions.as_ref().unwrap();
+		assert_eq!(extensions.len(), 5);
+		assert_eq!(extensions.iter().map(|e| e.critical).collect::<Vec<_>>(), [false, false, true, true, false]);
+		
+		let usage = deserialize_slice::<BitString>(&extensions[3].value.0).unwrap();
+		assert_eq!((usage.get(0), usage.get(1), usage.get(5)), (true, false, true));
+		
+		assert_eq!(serialize_vec(&cert).unwrap(), data);
+	}
+}
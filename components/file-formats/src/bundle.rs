@@ -0,0 +1,719 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Binary asset bundles that are accessed in place.
+//!
+//! A bundle starts with a fixed header, followed by the section contents and the table of
+//! contents with the section names and metadata at the end of the file. All integers are
+//! little-endian and every section starts at a multiple of the bundle's alignment (64 bytes
+//! by default), so a bundle can be memory-mapped and section contents can be handed to the
+//! GPU or reinterpreted as typed slices without copying. [`Bundle`] borrows all data from the
+//! underlying buffer, which may be a [`Mapping`] of the file, [`BundleWriter`] lays out and
+//! writes a bundle.
+//!
+//! Readers accept any minor version of the supported major version, unknown section kinds are
+//! kept as [`SectionKind::Other`].
+
+use std::{io::{self, Error, ErrorKind}, borrow::Cow};
+
+pub use mmap::Mapping;
+
+pub const MAGIC: [u8; 8] = *b"\x89GEB\r\n\x1A\n";
+pub const VERSION_MAJOR: u16 = 1;
+pub const VERSION_MINOR: u16 = 0;
+pub const DEFAULT_ALIGNMENT: usize = 64;
+
+const HEADER_SIZE: usize = 40;
+const ENTRY_SIZE: usize = 48;
+
+fn invalid_data(msg: impl ToString) -> Error {
+	Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+fn align(v: usize, alignment: usize) -> usize {
+	v.next_multiple_of(alignment)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SectionKind {
+	Mesh,
+	Texture,
+	SdfVolume,
+	/// Serialized entities and components.
+	Ecs,
+	Other(u32)
+}
+
+impl SectionKind {
+	pub fn from_u32(v: u32) -> Self {
+		match v {
+			1 => Self::Mesh,
+			2 => Self::Texture,
+			3 => Self::SdfVolume,
+			4 => Self::Ecs,
+			v => Self::Other(v)
+		}
+	}
+	
+	pub fn to_u32(self) -> u32 {
+		match self {
+			Self::Mesh      => 1,
+			Self::Texture   => 2,
+			Self::SdfVolume => 3,
+			Self::Ecs       => 4,
+			Self::Other(v)  => v
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Semantic {
+	Position,
+	Normal,
+	Tangent,
+	TexCoord,
+	Color,
+	Joints,
+	Weights,
+	Other(u8)
+}
+
+impl Semantic {
+	pub fn from_u8(v: u8) -> Self {
+		match v {
+			0 => Self::Position,
+			1 => Self::Normal,
+			2 => Self::Tangent,
+			3 => Self::TexCoord,
+			4 => Self::Color,
+			5 => Self::Joints,
+			6 => Self::Weights,
+			v => Self::Other(v)
+		}
+	}
+	
+	pub fn to_u8(self) -> u8 {
+		match self {
+			Self::Position => 0,
+			Self::Normal   => 1,
+			Self::Tangent  => 2,
+			Self::TexCoord => 3,
+			Self::Color    => 4,
+			Self::Joints   => 5,
+			Self::Weights  => 6,
+			Self::Other(v) => v
+		}
+	}
+}
+
+/// An attribute of interleaved vertices.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VertexAttribute {
+	pub semantic: Semantic,
+	/// A `VkFormat` value.
+	pub format:   u32,
+	/// Offset of the attribute within a vertex.
+	pub offset:   u32
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MeshInfo {
+	/// A `VkPrimitiveTopology` value.
+	pub topology:      u32,
+	pub vertex_count:  u64,
+	pub vertex_stride: u32,
+	pub attributes:    Vec<VertexAttribute>,
+	pub index_count:   u64,
+	/// Size of an index in bytes, 2 or 4, or 0 if the mesh is not indexed.
+	pub index_size:    u8
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TextureInfo {
+	/// A `VkFormat` value.
+	pub format: u32,
+	pub width:  u32,
+	pub height: u32,
+	pub depth:  u32,
+	pub layers: u32,
+	/// 6 for cube maps, 1 otherwise.
+	pub faces:  u32
+}
+
+/// A signed distance field sampled on a regular grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdfVolumeInfo {
+	/// A `VkFormat` value of the voxels.
+	pub format:     u32,
+	pub dimensions: [u32; 3],
+	/// Bounds of the volume in object space.
+	pub min:        [f32; 3],
+	pub max:        [f32; 3]
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EcsInfo {
+	/// Version of the serialization format, defined by the writer of the data.
+	pub version:      u32,
+	pub entity_count: u64
+}
+
+#[derive(Clone, Debug)]
+pub struct Mesh<'a> {
+	pub info:     MeshInfo,
+	pub vertices: &'a [u8],
+	pub indices:  &'a [u8]
+}
+
+#[derive(Clone, Debug)]
+pub struct Texture<'a> {
+	pub info:   TextureInfo,
+	/// The mip levels, largest first, each containing all layers and faces.
+	pub levels: Vec<&'a [u8]>
+}
+
+#[derive(Clone, Debug)]
+pub struct SdfVolume<'a> {
+	pub info:   SdfVolumeInfo,
+	pub voxels: &'a [u8]
+}
+
+#[derive(Clone, Debug)]
+pub struct EcsData<'a> {
+	pub info: EcsInfo,
+	pub data: &'a [u8]
+}
+
+/// Reads little-endian values from a slice.
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+	fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+		if len > self.0.len() {
+			return Err(ErrorKind::UnexpectedEof.into());
+		}
+		
+		let (v, rest) = self.0.split_at(len);
+		self.0 = rest;
+		Ok(v)
+	}
+	
+	fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+		Ok(self.take(N)?.try_into().unwrap())
+	}
+	
+	fn u8(&mut self) -> io::Result<u8> {
+		Ok(self.take(1)?[0])
+	}
+	
+	fn u16(&mut self) -> io::Result<u16> {
+		self.array().map(u16::from_le_bytes)
+	}
+	
+	fn u32(&mut self) -> io::Result<u32> {
+		self.array().map(u32::from_le_bytes)
+	}
+	
+	fn u64(&mut self) -> io::Result<u64> {
+		self.array().map(u64::from_le_bytes)
+	}
+	
+	fn usize(&mut self) -> io::Result<usize> {
+		usize::try_from(self.u64()?).map_err(|_| invalid_data("offset out of range"))
+	}
+	
+	fn f32(&mut self) -> io::Result<f32> {
+		self.array().map(f32::from_le_bytes)
+	}
+	
+	fn vec3<T>(&mut self, mut f: impl FnMut(&mut Self) -> io::Result<T>) -> io::Result<[T; 3]> {
+		Ok([f(self)?, f(self)?, f(self)?])
+	}
+}
+
+/// Returns `len` bytes at `offset`, or an error if they are out of bounds.
+fn slice(data: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+	offset.checked_add(len)
+		.and_then(|end| data.get(offset..end))
+		.ok_or_else(|| invalid_data("section out of bounds"))
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Section<'a> {
+	pub kind:     SectionKind,
+	pub name:     &'a str,
+	/// Describes the contents, the format depends on the kind.
+	pub metadata: &'a [u8],
+	pub data:     &'a [u8]
+}
+
+impl<'a> Section<'a> {
+	fn expect(&self, kind: SectionKind) -> io::Result<Bytes<'a>> {
+		match self.kind == kind {
+			true => Ok(Bytes(self.metadata)),
+			false => Err(Error::new(ErrorKind::InvalidInput, format!("section `{}` is a {:?} section", self.name, self.kind)))
+		}
+	}
+	
+	pub fn mesh(&self) -> io::Result<Mesh<'a>> {
+		let mut meta = self.expect(SectionKind::Mesh)?;
+		let mut info = MeshInfo {
+			topology:      meta.u32()?,
+			vertex_count:  meta.u64()?,
+			vertex_stride: meta.u32()?,
+			attributes:    Vec::new(),
+			index_count:   meta.u64()?,
+			index_size:    meta.u8()?
+		};
+		let index_offset = meta.usize()?;
+		
+		for _ in 0..meta.u32()? {
+			info.attributes.push(VertexAttribute {
+				semantic: Semantic::from_u8(meta.u8()?),
+				format:   meta.u32()?,
+				offset:   meta.u32()?
+			});
+		}
+		
+		let size = |count: u64, size: u64| count.checked_mul(size)
+			.and_then(|v| usize::try_from(v).ok())
+			.ok_or_else(|| invalid_data("mesh size out of range"));
+		let vertices = slice(self.data, 0, size(info.vertex_count, info.vertex_stride as _)?)?;
+		let indices = slice(self.data, index_offset, size(info.index_count, info.index_size as _)?)?;
+		Ok(Mesh { info, vertices, indices })
+	}
+	
+	pub fn texture(&self) -> io::Result<Texture<'a>> {
+		let mut meta = self.expect(SectionKind::Texture)?;
+		let info = TextureInfo {
+			format: meta.u32()?,
+			width:  meta.u32()?,
+			height: meta.u32()?,
+			depth:  meta.u32()?,
+			layers: meta.u32()?,
+			faces:  meta.u32()?
+		};
+		
+		let levels = (0..meta.u32()?)
+			.map(|_| slice(self.data, meta.usize()?, meta.usize()?))
+			.collect::<io::Result<_>>()?;
+		Ok(Texture { info, levels })
+	}
+	
+	pub fn sdf_volume(&self) -> io::Result<SdfVolume<'a>> {
+		let mut meta = self.expect(SectionKind::SdfVolume)?;
+		let info = SdfVolumeInfo {
+			format:     meta.u32()?,
+			dimensions: meta.vec3(Bytes::u32)?,
+			min:        meta.vec3(Bytes::f32)?,
+			max:        meta.vec3(Bytes::f32)?
+		};
+		Ok(SdfVolume { info, voxels: self.data })
+	}
+	
+	pub fn ecs(&self) -> io::Result<EcsData<'a>> {
+		let mut meta = self.expect(SectionKind::Ecs)?;
+		let info = EcsInfo { version: meta.u32()?, entity_count: meta.u64()? };
+		Ok(EcsData { info, data: self.data })
+	}
+}
+
+/// A parsed bundle that borrows all data from the underlying buffer.
+#[derive(Clone, Debug)]
+pub struct Bundle<'a> {
+	pub version:   (u16, u16),
+	pub alignment: usize,
+	sections:      Vec<Section<'a>>
+}
+
+impl<'a> Bundle<'a> {
+	/// Validates the header and the table of contents. Section contents are only validated
+	/// when they are accessed.
+	pub fn parse(data: &'a [u8]) -> io::Result<Self> {
+		let mut header = Bytes(data);
+		if header.array::<8>()? != MAGIC {
+			return Err(invalid_data("not an asset bundle"));
+		}
+		
+		let version = (header.u16()?, header.u16()?);
+		if version.0 != VERSION_MAJOR {
+			return Err(invalid_data(format!("unsupported bundle version {}.{}", version.0, version.1)));
+		}
+		
+		let alignment = header.u32()? as usize;
+		let count = header.u32()? as usize;
+		let _flags = header.u32()?;
+		let toc_offset = header.usize()?;
+		let file_size = header.usize()?;
+		
+		if !alignment.is_power_of_two() || alignment < 8 {
+			return Err(invalid_data(format!("invalid alignment {}", alignment)));
+		} else if file_size != data.len() {
+			return Err(invalid_data(format!("bundle size is {} bytes, expected {}", data.len(), file_size)));
+		}
+		
+		let mut toc = Bytes(slice(data, toc_offset, count.checked_mul(ENTRY_SIZE)
+			.ok_or_else(|| invalid_data("invalid section count"))?)?);
+		let sections = (0..count).map(|_| {
+			let kind = SectionKind::from_u32(toc.u32()?);
+			let name_len = toc.u32()? as usize;
+			let name = std::str::from_utf8(slice(data, toc.usize()?, name_len)?)
+				.map_err(|_| invalid_data("section name is not valid UTF-8"))?;
+			let metadata = slice(data, toc.usize()?, toc.usize()?)?;
+			let offset = toc.usize()?;
+			
+			if !offset.is_multiple_of(alignment) {
+				return Err(invalid_data(format!("section `{}` is not aligned", name)));
+			}
+			
+			Ok(Section { kind, name, metadata, data: slice(data, offset, toc.usize()?)? })
+		}).collect::<io::Result<_>>()?;
+		
+		Ok(Self { version, alignment, sections })
+	}
+	
+	pub fn sections(&self) -> &[Section<'a>] {
+		&self.sections
+	}
+	
+	pub fn section(&self, name: &str) -> Option<&Section<'a>> {
+		self.sections.iter().find(|s| s.name == name)
+	}
+	
+	pub fn sections_of(&self, kind: SectionKind) -> impl Iterator<Item = &Section<'a>> {
+		self.sections.iter().filter(move |s| s.kind == kind)
+	}
+}
+
+struct PendingSection<'a> {
+	kind:     SectionKind,
+	name:     String,
+	metadata: Vec<u8>,
+	/// Offsets within the section and contents.
+	parts:    Vec<(usize, Cow<'a, [u8]>)>,
+	len:      usize
+}
+
+/// Lays out and writes a bundle. Section contents are borrowed until the bundle is written.
+pub struct BundleWriter<'a> {
+	alignment: usize,
+	sections:  Vec<PendingSection<'a>>
+}
+
+impl Default for BundleWriter<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a> BundleWriter<'a> {
+	pub fn new() -> Self {
+		Self::with_alignment(DEFAULT_ALIGNMENT)
+	}
+	
+	/// Creates a writer with the given alignment of sections and of parts within sections,
+	/// which must be a power of two and at least 8.
+	pub fn with_alignment(alignment: usize) -> Self {
+		assert!(alignment.is_power_of_two() && alignment >= 8, "invalid alignment");
+		Self { alignment, sections: Vec::new() }
+	}
+	
+	/// Adds a section of any kind, with opaque metadata and contents.
+	pub fn section(&mut self, kind: SectionKind, name: &str, metadata: Vec<u8>, data: impl Into<Cow<'a, [u8]>>) -> &mut Self {
+		self.parts(kind, name, metadata, vec![data.into()])
+	}
+	
+	fn parts(&mut self, kind: SectionKind, name: &str, metadata: Vec<u8>, data: Vec<Cow<'a, [u8]>>) -> &mut Self {
+		let mut len = 0;
+		let parts = data.into_iter().map(|v| {
+			let offset = if v.is_empty() { len } else { align(len, self.alignment) };
+			len = offset + v.len();
+			(offset, v)
+		}).collect();
+		
+		self.sections.push(PendingSection { kind, name: name.to_string(), metadata, parts, len });
+		self
+	}
+	
+	/// Adds a mesh with interleaved vertices and optional indices.
+	pub fn mesh(&mut self, name: &str, info: &MeshInfo, vertices: &'a [u8], indices: &'a [u8]) -> io::Result<&mut Self> {
+		if vertices.len() as u64 != info.vertex_count * info.vertex_stride as u64 {
+			return Err(Error::new(ErrorKind::InvalidInput, "vertex data does not match the vertex count and stride"));
+		} else if indices.len() as u64 != info.index_count * info.index_size as u64 {
+			return Err(Error::new(ErrorKind::InvalidInput, "index data does not match the index count and size"));
+		}
+		
+		let index_offset = if indices.is_empty() { 0 } else { align(vertices.len(), self.alignment) };
+		let mut meta = Vec::new();
+		meta.extend_from_slice(&info.topology.to_le_bytes());
+		meta.extend_from_slice(&info.vertex_count.to_le_bytes());
+		meta.extend_from_slice(&info.vertex_stride.to_le_bytes());
+		meta.extend_from_slice(&info.index_count.to_le_bytes());
+		meta.push(info.index_size);
+		meta.extend_from_slice(&(index_offset as u64).to_le_bytes());
+		meta.extend_from_slice(&(info.attributes.len() as u32).to_le_bytes());
+		
+		for attribute in &info.attributes {
+			meta.push(attribute.semantic.to_u8());
+			meta.extend_from_slice(&attribute.format.to_le_bytes());
+			meta.extend_from_slice(&attribute.offset.to_le_bytes());
+		}
+		
+		Ok(self.parts(SectionKind::Mesh, name, meta, vec![vertices.into(), indices.into()]))
+	}
+	
+	/// Adds a texture, with one slice per mip level.
+	pub fn texture(&mut self, name: &str, info: &TextureInfo, levels: &[&'a [u8]]) -> &mut Self {
+		let mut meta = Vec::new();
+		for v in [info.format, info.width, info.height, info.depth, info.layers, info.faces, levels.len() as u32] {
+			meta.extend_from_slice(&v.to_le_bytes());
+		}
+		
+		let mut offset = 0;
+		for level in levels {
+			offset = align(offset, self.alignment);
+			meta.extend_from_slice(&(offset as u64).to_le_bytes());
+			meta.extend_from_slice(&(level.len() as u64).to_le_bytes());
+			offset += level.len();
+		}
+		
+		self.parts(SectionKind::Texture, name, meta, levels.iter().map(|v| Cow::Borrowed(*v)).collect())
+	}
+	
+	pub fn sdf_volume(&mut self, name: &str, info: &SdfVolumeInfo, voxels: &'a [u8]) -> &mut Self {
+		let mut meta = Vec::new();
+		for v in [info.format].iter().chain(&info.dimensions) {
+			meta.extend_from_slice(&v.to_le_bytes());
+		}
+		
+		for v in info.min.iter().chain(&info.max) {
+			meta.extend_from_slice(&v.to_le_bytes());
+		}
+		
+		self.section(SectionKind::SdfVolume, name, meta, voxels)
+	}
+	
+	pub fn ecs(&mut self, name: &str, info: &EcsInfo, data: impl Into<Cow<'a, [u8]>>) -> &mut Self {
+		let mut meta = info.version.to_le_bytes().to_vec();
+		meta.extend_from_slice(&info.entity_count.to_le_bytes());
+		self.section(SectionKind::Ecs, name, meta, data)
+	}
+	
+	pub fn write(&self, mut writer: impl io::Write) -> io::Result<()> {
+		let mut offsets = Vec::with_capacity(self.sections.len());
+		let mut pos = HEADER_SIZE;
+		for section in &self.sections {
+			pos = align(pos, self.alignment);
+			offsets.push(pos);
+			pos += section.len;
+		}
+		
+		let toc_offset = align(pos, 8);
+		let mut trailer = Vec::new();
+		let mut blob = toc_offset + self.sections.len() * ENTRY_SIZE;
+		for (section, offset) in self.sections.iter().zip(&offsets) {
+			let meta_offset = align(blob + section.name.len(), 8);
+			for v in [
+				section.kind.to_u32() as u64 | ((section.name.len() as u64) << 32),
+				blob as u64,
+				meta_offset as u64,
+				section.metadata.len() as u64,
+				*offset as u64,
+				section.len as u64
+			] {
+				trailer.extend_from_slice(&v.to_le_bytes());
+			}
+			blob = align(meta_offset + section.metadata.len(), 8);
+		}
+		
+		for section in &self.sections {
+			trailer.extend_from_slice(section.name.as_bytes());
+			trailer.resize(align(toc_offset + trailer.len(), 8) - toc_offset, 0);
+			trailer.extend_from_slice(&section.metadata);
+			trailer.resize(align(toc_offset + trailer.len(), 8) - toc_offset, 0);
+		}
+		
+		let mut header = Vec::with_capacity(HEADER_SIZE);
+		header.extend_from_slice(&MAGIC);
+		header.extend_from_slice(&VERSION_MAJOR.to_le_bytes());
+		header.extend_from_slice(&VERSION_MINOR.to_le_bytes());
+		header.extend_from_slice(&(self.alignment as u32).to_le_bytes());
+		header.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
+		header.extend_from_slice(&0u32.to_le_bytes());
+		header.extend_from_slice(&(toc_offset as u64).to_le_bytes());
+		header.extend_from_slice(&((toc_offset + trailer.len()) as u64).to_le_bytes());
+		writer.write_all(&header)?;
+		
+		let mut pos = HEADER_SIZE;
+		let zeros = vec![0u8; self.alignment];
+		for (section, offset) in self.sections.iter().zip(offsets) {
+			for (part_offset, data) in &section.parts {
+				writer.write_all(&zeros[..offset + part_offset - pos])?;
+				writer.write_all(data)?;
+				pos = offset + part_offset + data.len();
+			}
+		}
+		
+		writer.write_all(&zeros[..toc_offset - pos])?;
+		writer.write_all(&trailer)
+	}
+	
+	pub fn to_vec(&self) -> Vec<u8> {
+		let mut buf = Vec::new();
+		self.write(&mut buf).expect("writing to a vec failed");
+		buf
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn mesh_info() -> MeshInfo {
+		MeshInfo {
+			topology:      3,
+			vertex_count:  3,
+			vertex_stride: 20,
+			attributes:    vec![
+				VertexAttribute { semantic: Semantic::Position, format: 106, offset: 0 },
+				VertexAttribute { semantic: Semantic::TexCoord, format: 103, offset: 12 }
+			],
+			index_count:   3,
+			index_size:    2
+		}
+	}
+	
+	fn bundle(vertices: &[u8], indices: &[u8], levels: &[&[u8]], voxels: &[u8]) -> Vec<u8> {
+		let mut writer = BundleWriter::new();
+		writer.mesh("meshes/triangle", &mesh_info(), vertices, indices).unwrap();
+		writer
+			.texture("textures/albedo", &TextureInfo { format: 37, width: 4, height: 4, depth: 1, layers: 1, faces: 1 }, levels)
+			.sdf_volume("volumes/sphere", &SdfVolumeInfo { format: 100, dimensions: [2, 2, 2], min: [-1.0; 3], max: [1.0; 3] }, voxels)
+			.ecs("world", &EcsInfo { version: 3, entity_count: 2 }, vec![1, 2, 3])
+			.section(SectionKind::Other(0x1000), "extra", vec![9], &b"custom"[..]);
+		writer.to_vec()
+	}
+	
+	#[test]
+	fn roundtrip() {
+		let vertices = (0..60).collect::<Vec<u8>>();
+		let indices = [0, 0, 1, 0, 2, 0];
+		let levels = [&[0xAA; 64][..], &[0xBB; 16], &[0xCC; 4]];
+		let voxels = [0x7F; 32];
+		let data = bundle(&vertices, &indices, &levels, &voxels);
+		let bundle = Bundle::parse(&data).unwrap();
+		
+		assert_eq!((bundle.version, bundle.alignment), ((VERSION_MAJOR, VERSION_MINOR), DEFAULT_ALIGNMENT));
+		assert_eq!(bundle.sections().iter().map(|s| (s.kind, s.name)).collect::<Vec<_>>(), [
+			(SectionKind::Mesh, "meshes/triangle"),
+			(SectionKind::Texture, "textures/albedo"),
+			(SectionKind::SdfVolume, "volumes/sphere"),
+			(SectionKind::Ecs, "world"),
+			(SectionKind::Other(0x1000), "extra")
+		]);
+		
+		// all contents point into the buffer and are aligned within it
+		let range = data.as_ptr_range();
+		for section in bundle.sections() {
+			assert!(range.contains(&section.data.as_ptr()));
+			assert_eq!((section.data.as_ptr() as usize - data.as_ptr() as usize) % DEFAULT_ALIGNMENT, 0);
+		}
+		
+		let mesh = bundle.section("meshes/triangle").unwrap().mesh().unwrap();
+		assert_eq!((mesh.info, mesh.vertices, mesh.indices), (mesh_info(), &vertices[..], &indices[..]));
+		assert_eq!((mesh.indices.as_ptr() as usize - data.as_ptr() as usize) % DEFAULT_ALIGNMENT, 0);
+		
+		let texture = bundle.sections_of(SectionKind::Texture).next().unwrap().texture().unwrap();
+		assert_eq!((texture.info.width, texture.levels), (4, levels.to_vec()));
+		
+		let volume = bundle.section("volumes/sphere").unwrap().sdf_volume().unwrap();
+		assert_eq!((volume.info.dimensions, volume.info.min, volume.voxels), ([2; 3], [-1.0; 3], &voxels[..]));
+		
+		let ecs = bundle.section("world").unwrap().ecs().unwrap();
+		assert_eq!((ecs.info, ecs.data), (EcsInfo { version: 3, entity_count: 2 }, &[1, 2, 3][..]));
+		
+		let extra = bundle.section("extra").unwrap();
+		assert_eq!((extra.metadata, extra.data), (&[9][..], &b"custom"[..]));
+		assert!(extra.mesh().is_err());
+	}
+	
+	#[test]
+	fn unindexed_and_small_alignment() {
+		let info = MeshInfo { vertex_count: 2, vertex_stride: 4, index_count: 0, index_size: 0, ..MeshInfo::default() };
+		let mut writer = BundleWriter::with_alignment(16);
+		writer.mesh("a", &info, &[1; 8], &[]).unwrap();
+		writer.mesh("b", &info, &[2; 8], &[]).unwrap();
+		assert!(writer.mesh("c", &info, &[3; 4], &[]).is_err());
+		
+		let data = writer.to_vec();
+		let bundle = Bundle::parse(&data).unwrap();
+		assert_eq!(bundle.alignment, 16);
+		assert_eq!(bundle.sections().iter().map(|s| s.data.len()).collect::<Vec<_>>(), [8, 8]);
+		assert_eq!(bundle.section("b").unwrap().mesh().unwrap().vertices, [2; 8]);
+		assert!(bundle.section("b").unwrap().mesh().unwrap().indices.is_empty());
+		
+		let empty = BundleWriter::new().to_vec();
+		assert_eq!(empty.len(), HEADER_SIZE);
+		assert!(Bundle::parse(&empty).unwrap().sections().is_empty());
+	}
+	
+	#[test]
+	fn invalid() {
+		let data = bundle(&[0; 60], &[0; 6], &[&[0; 4]], &[0; 8]);
+		assert!(Bundle::parse(&data[..data.len() - 1]).is_err());
+		assert!(Bundle::parse(&data[..20]).is_err());
+		
+		let mut corrupt = data.clone();
+		corrupt[0] = 0;
+		assert!(Bundle::parse(&corrupt).is_err());
+		
+		let mut newer = data.clone();
+		newer[8] = 2;
+		assert!(Bundle::parse(&newer).is_err());
+		
+		let mut minor = data.clone();
+		minor[10] = 7;
+		assert_eq!(Bundle::parse(&minor).unwrap().version, (1, 7));
+		
+		// move the first section's contents out of bounds
+		let toc = u64::from_le_bytes(data[24..32].try_into().unwrap()) as usize;
+		let mut moved = data.clone();
+		moved[toc + 32..toc + 40].copy_from_slice(&(data.len() as u64).to_le_bytes());
+		assert!(Bundle::parse(&moved).is_err());
+	}
+	
+	#[test]
+	fn mapping() {
+		let data = bundle(&[5; 60], &[0; 6], &[&[0; 4]], &[0; 8]);
+		let path = std::env::temp_dir().join(format!("bundle-test-{}.bin", std::process::id()));
+		std::fs::write(&path, &data).unwrap();
+		
+		let mapping = Mapping::open(&path).unwrap();
+		assert_eq!(&mapping[..], &data[..]);
+		assert_eq!(mapping.as_ptr() as usize % DEFAULT_ALIGNMENT, 0);
+		
+		let data: &'static [u8] = mapping.leak();
+		let bundle = Bundle::parse(data).unwrap();
+		assert_eq!(bundle.section("meshes/triangle").unwrap().mesh().unwrap().vertices, [5; 60]);
+		std::fs::remove_file(path).unwrap();
+	}
+}
@@ -47,6 +47,119 @@ impl Type {
 	pub fn deserialize_to<'de, T: Deserialize<'de>>(self) -> Result<T, Error> {
 		T::deserialize(self)
 	}
+	
+	/// Returns the value at the given path. Enum variants are transparent, the path continues
+	/// in the variant's value.
+	pub fn get(&self, path: impl AsRef<[Segment]>) -> Option<&Self> {
+		path.as_ref().iter().try_fold(self, Self::child)
+	}
+	
+	pub fn get_mut(&mut self, path: impl AsRef<[Segment]>) -> Option<&mut Self> {
+		path.as_ref().iter().try_fold(self, Self::child_mut)
+	}
+	
+	/// Sets the value at the given path and returns the previous value. Missing map entries
+	/// are created on the way, a unit value becomes a map or sequence as needed and an index
+	/// equal to the length of a sequence appends to it.
+	pub fn set(&mut self, path: impl AsRef<[Segment]>, value: Self) -> Result<Option<Self>, Error> {
+		let Some((last, parents)) = path.as_ref().split_last() else {
+			return Ok(Some(std::mem::replace(self, value)));
+		};
+		
+		parents.iter().try_fold(self, Self::entry)?.insert(last, value)
+	}
+	
+	/// Removes the value at the given path, elements after a removed sequence element move up.
+	pub fn remove(&mut self, path: impl AsRef<[Segment]>) -> Option<Self> {
+		let (last, parents) = path.as_ref().split_last()?;
+		self.get_mut(parents)?.take(last)
+	}
+	
+	/// Computes the changes that turn `self` into `other`.
+	pub fn diff(&self, other: &Self) -> Patch {
+		let mut patch = Patch::default();
+		diff(&mut Path::default(), self, other, &mut patch.0);
+		patch
+	}
+	
+	/// Merges the changes that were made to `base` in `ours` and `theirs`. Changes to the same
+	/// value that don't agree are reported as conflicts and resolved in favor of `ours`.
+	pub fn merge(base: &Self, ours: &Self, theirs: &Self) -> Merge {
+		let mut conflicts = Vec::new();
+		let value = merge(&mut Path::default(), Some(base), Some(ours), Some(theirs), &mut conflicts)
+			.unwrap_or_default();
+		Merge { value, conflicts }
+	}
+	
+	fn kind(&self) -> &'static str {
+		match self {
+			Self::Unit      => "unit",
+			Self::Bool(_)   => "bool",
+			Self::Char(_)   => "char",
+			Self::UInt(_)   => "unsigned integer",
+			Self::SInt(_)   => "signed integer",
+			Self::F32(_)    => "f32",
+			Self::F64(_)    => "f64",
+			Self::String(_) => "string",
+			Self::Seq(_)    => "sequence",
+			Self::Map(_)    => "map",
+			Self::Enum(..)  => "enum",
+			Self::Bytes(_)  => "bytes"
+		}
+	}
+	
+	fn child(&self, segment: &Segment) -> Option<&Self> {
+		match (self, segment) {
+			(Self::Map(v), Segment::Key(k))   => v.get(k),
+			(Self::Seq(v), Segment::Index(i)) => v.get(*i),
+			(Self::Enum(_, v), _)             => v.child(segment),
+			_ => None
+		}
+	}
+	
+	fn child_mut(&mut self, segment: &Segment) -> Option<&mut Self> {
+		match (self, segment) {
+			(Self::Map(v), Segment::Key(k))   => v.get_mut(k),
+			(Self::Seq(v), Segment::Index(i)) => v.get_mut(*i),
+			(Self::Enum(_, v), _)             => v.child_mut(segment),
+			_ => None
+		}
+	}
+	
+	fn entry(&mut self, segment: &Segment) -> Result<&mut Self, Error> {
+		if *self == Self::Unit {
+			*self = segment.container();
+		}
+		
+		match (self, segment) {
+			(Self::Map(v), Segment::Key(k)) => Ok(v.entry(k.clone()).or_default()),
+			(Self::Seq(v), Segment::Index(i)) => {
+				if *i == v.len() {
+					v.push(Self::Unit);
+				}
+				
+				let len = v.len();
+				v.get_mut(*i).ok_or_else(|| Error(format!("index {} is out of bounds for a sequence of length {}", i, len)))
+			}
+			(Self::Enum(_, v), _) => v.entry(segment),
+			(v, _) => Err(Error(format!("cannot access `{}` in a {}", segment, v.kind())))
+		}
+	}
+	
+	fn insert(&mut self, segment: &Segment, value: Self) -> Result<Option<Self>, Error> {
+		let exists = self.child(segment).is_some();
+		let prev = std::mem::replace(self.entry(segment)?, value);
+		Ok(exists.then_some(prev))
+	}
+	
+	fn take(&mut self, segment: &Segment) -> Option<Self> {
+		match (self, segment) {
+			(Self::Map(v), Segment::Key(k))   => v.remove(k),
+			(Self::Seq(v), Segment::Index(i)) => (*i < v.len()).then(|| v.remove(*i)),
+			(Self::Enum(_, v), _)             => v.take(segment),
+			_ => None
+		}
+	}
 }
 
 impl Default for Type {
@@ -55,6 +168,316 @@ impl Default for Type {
 	}
 }
 
+/// A path into a [`Type`], written like `meshes[2].primitives[0].material`. Keys that are not
+/// made up of alphanumeric characters, `_` and `-` are quoted, e.g. `extras["a.b"]`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Path(pub Vec<Segment>);
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Segment {
+	Key(String),
+	Index(usize)
+}
+
+impl Path {
+	pub fn push(&mut self, segment: impl Into<Segment>) {
+		self.0.push(segment.into());
+	}
+	
+	pub fn join(&self, segment: impl Into<Segment>) -> Self {
+		let mut path = self.clone();
+		path.push(segment);
+		path
+	}
+}
+
+impl AsRef<[Segment]> for Path {
+	fn as_ref(&self) -> &[Segment] {
+		&self.0
+	}
+}
+
+impl std::str::FromStr for Path {
+	type Err = Error;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let err = |msg: &str| Error(format!("invalid path `{}`: {}", s, msg));
+		let mut path = Vec::new();
+		let mut chars = s.chars().peekable();
+		
+		while let Some(c) = chars.next() {
+			match c {
+				'[' if chars.peek() == Some(&'"') => {
+					chars.next();
+					let mut key = String::new();
+					loop {
+						match chars.next() {
+							Some('"') => break,
+							Some('\\') => key.push(chars.next().ok_or_else(|| err("unterminated key"))?),
+							Some(c) => key.push(c),
+							None => return Err(err("unterminated key"))
+						}
+					}
+					
+					if chars.next() != Some(']') {
+						return Err(err("expected `]`"));
+					}
+					
+					path.push(Segment::Key(key));
+				}
+				'[' => {
+					let mut index = String::new();
+					loop {
+						match chars.next() {
+							Some(']') => break,
+							Some(c) => index.push(c),
+							None => return Err(err("expected `]`"))
+						}
+					}
+					
+					path.push(Segment::Index(index.parse().map_err(|_| err("invalid index"))?));
+				}
+				'.' if path.is_empty() => return Err(err("unexpected `.`")),
+				c => {
+					let mut key = String::new();
+					match c {
+						'.' => (),
+						_ if path.is_empty() => key.push(c),
+						_ => return Err(err("expected `.` or `[`"))
+					}
+					
+					while let Some(&c) = chars.peek().filter(|c| **c != '.' && **c != '[') {
+						key.push(c);
+						chars.next();
+					}
+					
+					if !Segment::is_plain_key(&key) {
+						return Err(err("invalid key"));
+					}
+					
+					path.push(Segment::Key(key));
+				}
+			}
+		}
+		
+		Ok(Self(path))
+	}
+}
+
+impl fmt::Display for Path {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, segment) in self.0.iter().enumerate() {
+			if i > 0 && matches!(segment, Segment::Key(k) if Segment::is_plain_key(k)) {
+				f.write_str(".")?;
+			}
+			
+			fmt::Display::fmt(segment, f)?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl Serialize for Path {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for Path {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(<D::Error as de::Error>::custom)
+	}
+}
+
+impl Segment {
+	fn is_plain_key(key: &str) -> bool {
+		!key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+	}
+	
+	fn container(&self) -> Type {
+		match self {
+			Self::Key(_)   => Type::Map(HashMap::new()),
+			Self::Index(_) => Type::Seq(Vec::new())
+		}
+	}
+}
+
+impl From<&str> for Segment {
+	fn from(v: &str) -> Self {
+		Self::Key(v.to_string())
+	}
+}
+
+impl From<String> for Segment {
+	fn from(v: String) -> Self {
+		Self::Key(v)
+	}
+}
+
+impl From<usize> for Segment {
+	fn from(v: usize) -> Self {
+		Self::Index(v)
+	}
+}
+
+impl fmt::Display for Segment {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Key(k) if Self::is_plain_key(k) => f.write_str(k),
+			Self::Key(k) => {
+				f.write_str("[\"")?;
+				for c in k.chars() {
+					if c == '"' || c == '\\' {
+						f.write_str("\\")?;
+					}
+					
+					fmt::Write::write_char(f, c)?;
+				}
+				f.write_str("\"]")
+			}
+			Self::Index(i) => write!(f, "[{}]", i)
+		}
+	}
+}
+
+/// A list of changes, as produced by [`Type::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Patch(pub Vec<Operation>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+	Set(Path, Type),
+	Remove(Path)
+}
+
+impl Patch {
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	
+	/// Applies the operations in order, stops at the first operation that fails.
+	pub fn apply(&self, target: &mut Type) -> Result<(), Error> {
+		for op in &self.0 {
+			match op {
+				Operation::Set(path, value) => {
+					target.set(path, value.clone())?;
+				}
+				Operation::Remove(path) => {
+					target.remove(path).ok_or_else(|| Error(format!("no value at `{}`", path)))?;
+				}
+			}
+		}
+		
+		Ok(())
+	}
+}
+
+/// The result of [`Type::merge`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge {
+	pub value:     Type,
+	pub conflicts: Vec<Conflict>
+}
+
+/// Conflicting changes to the same value, `None` means that the value does not exist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+	pub path:   Path,
+	pub base:   Option<Type>,
+	pub ours:   Option<Type>,
+	pub theirs: Option<Type>
+}
+
+fn sorted_keys<'a>(maps: &[&'a HashMap<String, Type>]) -> Vec<&'a String> {
+	let mut keys = maps.iter().flat_map(|m| m.keys()).collect::<Vec<_>>();
+	keys.sort_unstable();
+	keys.dedup();
+	keys
+}
+
+fn diff(path: &mut Path, old: &Type, new: &Type, ops: &mut Vec<Operation>) {
+	match (old, new) {
+		_ if old == new => (),
+		(Type::Map(old), Type::Map(new)) => for key in sorted_keys(&[old, new]) {
+			path.push(key.as_str());
+			match (old.get(key), new.get(key)) {
+				(Some(old), Some(new)) => diff(path, old, new, ops),
+				(None, Some(new)) => ops.push(Operation::Set(path.clone(), new.clone())),
+				(Some(_), None) => ops.push(Operation::Remove(path.clone())),
+				(None, None) => unreachable!()
+			}
+			path.0.pop();
+		}
+		(Type::Seq(old), Type::Seq(new)) => {
+			for (i, (old, new)) in old.iter().zip(new).enumerate() {
+				path.push(i);
+				diff(path, old, new, ops);
+				path.0.pop();
+			}
+			
+			ops.extend(new.iter().enumerate().skip(old.len())
+				.map(|(i, v)| Operation::Set(path.join(i), v.clone())));
+			ops.extend((new.len()..old.len()).rev()
+				.map(|i| Operation::Remove(path.join(i))));
+		}
+		_ => ops.push(Operation::Set(path.clone(), new.clone()))
+	}
+}
+
+fn merge(
+	path:      &mut Path,
+	base:      Option<&Type>,
+	ours:      Option<&Type>,
+	theirs:    Option<&Type>,
+	conflicts: &mut Vec<Conflict>
+) -> Option<Type> {
+	if ours == theirs || base == theirs {
+		return ours.cloned();
+	} else if base == ours {
+		return theirs.cloned();
+	}
+	
+	match (base, ours, theirs) {
+		(Some(Type::Map(base)), Some(Type::Map(ours)), Some(Type::Map(theirs))) => {
+			let mut map = HashMap::new();
+			for key in sorted_keys(&[base, ours, theirs]) {
+				path.push(key.as_str());
+				if let Some(v) = merge(path, base.get(key), ours.get(key), theirs.get(key), conflicts) {
+					map.insert(key.clone(), v);
+				}
+				path.0.pop();
+			}
+			
+			Some(Type::Map(map))
+		}
+		(Some(Type::Seq(base)), Some(Type::Seq(ours)), Some(Type::Seq(theirs)))
+			if base.len() == ours.len() && base.len() == theirs.len() => {
+			let mut seq = Vec::with_capacity(base.len());
+			for (i, ((base, ours), theirs)) in base.iter().zip(ours).zip(theirs).enumerate() {
+				path.push(i);
+				seq.push(merge(path, Some(base), Some(ours), Some(theirs), conflicts)
+					.expect("merged value of existing elements"));
+				path.0.pop();
+			}
+			
+			Some(Type::Seq(seq))
+		}
+		(Some(Type::Enum(i, base)), Some(Type::Enum(j, ours)), Some(Type::Enum(k, theirs)))
+			if i == j && i == k => merge(path, Some(base), Some(ours), Some(theirs), conflicts)
+				.map(|v| Type::Enum(*i, Box::new(v))),
+		_ => {
+			conflicts.push(Conflict {
+				path:   path.clone(),
+				base:   base.cloned(),
+				ours:   ours.cloned(),
+				theirs: theirs.cloned()
+			});
+			ours.cloned()
+		}
+	}
+}
+
 pub struct SerType;
 
 impl Serializer for SerType {
@@ -327,10 +750,14 @@ impl<'de> Deserializer<'de> for Type {
 	
 	forward_to_deserialize_any! {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct
+        bytes byte_buf unit unit_struct seq tuple tuple_struct
         map struct enum identifier ignored_any
     }
 	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
 	fn deserialize_option<V>(self, visitor: V) -> Result<<V as Visitor<'de>>::Value, Self::Error> where
 		V: Visitor<'de> {
 		match self {
@@ -435,6 +862,149 @@ impl serde::de::Error for Error {
 	}
 }
 
+#[cfg(feature = "json")]
+impl From<crate::json::Value> for Type {
+	fn from(v: crate::json::Value) -> Self {
+		use crate::json::{Value, Number};
+		match v {
+			Value::Null                     => Self::Unit,
+			Value::Bool(v)                  => Self::Bool(v),
+			Value::Number(Number::PosInt(v)) => Self::UInt(v as _),
+			Value::Number(Number::NegInt(v)) => Self::SInt(v as _),
+			Value::Number(Number::Float(v))  => Self::F64(v),
+			Value::String(v)                => Self::String(v),
+			Value::Array(v)                 => Self::Seq(v.into_iter().map(Self::from).collect()),
+			Value::Object(v)                => Self::Map(v.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+		}
+	}
+}
+
+/// Fails for enums and bytes, which JSON has no representation for.
+#[cfg(feature = "json")]
+impl TryFrom<Type> for crate::json::Value {
+	type Error = Error;
+	
+	fn try_from(v: Type) -> Result<Self, Self::Error> {
+		use crate::json::Number;
+		Ok(match v {
+			Type::Unit      => Self::Null,
+			Type::Bool(v)   => Self::Bool(v),
+			Type::Char(v)   => Self::String(v.to_string()),
+			Type::UInt(v)   => Self::Number(Number::PosInt(v as _)),
+			Type::SInt(v)   => Self::Number(Number::from(v as i64)),
+			Type::F32(v)    => Self::Number(Number::Float(v as _)),
+			Type::F64(v)    => Self::Number(Number::Float(v)),
+			Type::String(v) => Self::String(v),
+			Type::Seq(v)    => Self::Array(v.into_iter().map(Self::try_from).collect::<Result<_, _>>()?),
+			Type::Map(v)    => Self::Object(v.into_iter()
+				.map(|(k, v)| Ok((k, Self::try_from(v)?)))
+				.collect::<Result<_, Error>>()?),
+			v => return Err(Error(format!("a {} cannot be represented in JSON", v.kind())))
+		})
+	}
+}
+
+/// Datetimes become maps with the datetime in its TOML notation as the only entry, with the key
+/// `$__toml_datetime`, which are converted back to datetimes.
+#[cfg(feature = "toml")]
+impl From<crate::toml::Value> for Type {
+	fn from(v: crate::toml::Value) -> Self {
+		use crate::toml::Value;
+		match v {
+			Value::String(v)   => Self::String(v),
+			Value::Integer(v)  => Self::SInt(v as _),
+			Value::Float(v)    => Self::F64(v),
+			Value::Bool(v)     => Self::Bool(v),
+			Value::DateTime(v) => Self::Map(HashMap::from([(crate::toml::DATETIME_NAME.to_string(), Self::String(v.to_string()))])),
+			Value::Array(v)    => Self::Seq(v.into_iter().map(Self::from).collect()),
+			Value::Table(v)    => Self::Map(v.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+		}
+	}
+}
+
+/// Fails for units, enums, bytes and integers above `i64::MAX`, which TOML has no
+/// representation for.
+#[cfg(feature = "toml")]
+impl TryFrom<Type> for crate::toml::Value {
+	type Error = Error;
+	
+	fn try_from(v: Type) -> Result<Self, Self::Error> {
+		Ok(match v {
+			Type::Bool(v)   => Self::Bool(v),
+			Type::Char(v)   => Self::String(v.to_string()),
+			Type::UInt(v)   => Self::Integer(i64::try_from(v)
+				.map_err(|_| Error(format!("integer {} is out of range for TOML", v)))?),
+			Type::SInt(v)   => Self::Integer(v as _),
+			Type::F32(v)    => Self::Float(v as _),
+			Type::F64(v)    => Self::Float(v),
+			Type::String(v) => Self::String(v),
+			Type::Seq(v)    => Self::Array(v.into_iter().map(Self::try_from).collect::<Result<_, _>>()?),
+			Type::Map(v)    => match v.get(crate::toml::DATETIME_NAME) {
+				Some(Type::String(datetime)) if v.len() == 1 => Self::DateTime(datetime.parse()
+					.map_err(|_| Error(format!("invalid TOML datetime `{}`", datetime)))?),
+				_ => Self::Table(v.into_iter()
+					.map(|(k, v)| Ok((k, Self::try_from(v)?)))
+					.collect::<Result<_, Error>>()?)
+			},
+			v => return Err(Error(format!("a {} cannot be represented in TOML", v.kind())))
+		})
+	}
+}
+
+/// Master elements become sequences of enums, where the variant index is the element id, so
+/// that the order and repetition of children is kept. Dates become signed integers.
+#[cfg(feature = "ebml")]
+impl From<crate::ebml::Value> for Type {
+	fn from(v: crate::ebml::Value) -> Self {
+		use crate::ebml::Value;
+		match v {
+			Value::Signed(v)   => Self::SInt(v as _),
+			Value::Unsigned(v) => Self::UInt(v as _),
+			Value::Float(v)    => Self::F64(v),
+			Value::String(v)   => Self::String(v),
+			Value::Date(v)     => Self::SInt(v as _),
+			Value::Binary(v)   => Self::Bytes(v),
+			Value::Master(v)   => Self::Seq(v.into_iter()
+				.map(|(id, v)| Self::Enum(id, Box::new(Self::from(v))))
+				.collect())
+		}
+	}
+}
+
+/// Sequences of enums and maps with numeric keys become master elements, bools become
+/// unsigned integers. Fails for units, top level enums and other sequences.
+#[cfg(feature = "ebml")]
+impl TryFrom<Type> for crate::ebml::Value {
+	type Error = Error;
+	
+	fn try_from(v: Type) -> Result<Self, Self::Error> {
+		Ok(match v {
+			Type::Bool(v)   => Self::Unsigned(v as _),
+			Type::Char(v)   => Self::String(v.to_string()),
+			Type::UInt(v)   => Self::Unsigned(v as _),
+			Type::SInt(v)   => Self::Signed(v as _),
+			Type::F32(v)    => Self::Float(v as _),
+			Type::F64(v)    => Self::Float(v),
+			Type::String(v) => Self::String(v),
+			Type::Bytes(v)  => Self::Binary(v),
+			Type::Seq(v)    => Self::Master(v.into_iter()
+				.map(|v| match v {
+					Type::Enum(id, v) => Ok((id, Self::try_from(*v)?)),
+					v => Err(Error(format!("expected an enum as child element, got a {}", v.kind())))
+				})
+				.collect::<Result<_, _>>()?),
+			Type::Map(v)    => {
+				let mut children = v.into_iter()
+					.map(|(k, v)| Ok((k.parse().map_err(|_| Error(format!("invalid element id `{}`", k)))?, Self::try_from(v)?)))
+					.collect::<Result<Vec<_>, Error>>()?;
+				children.sort_by_key(|(id, _)| *id);
+				Self::Master(children)
+			}
+			v => return Err(Error(format!("a {} cannot be represented in EBML", v.kind())))
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -484,4 +1054,181 @@ mod tests {
 	fn deserialize_self() {
 		assert_eq!(Type::deserialize(dyn_data()), Ok(dyn_data()));
 	}
+	
+	fn path(s: &str) -> Path {
+		s.parse().unwrap()
+	}
+	
+	fn map<const N: usize>(entries: [(&str, Type); N]) -> Type {
+		Type::Map(entries.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+	}
+	
+	fn document() -> Type {
+		map([
+			("name", Type::String("scene".to_string())),
+			("meshes", Type::Seq(vec![
+				map([("primitives", Type::Seq(vec![map([("material", Type::UInt(0))])]))]),
+				map([("primitives", Type::Seq(vec![]))])
+			])),
+			("mode", Type::Enum(1, Box::new(map([("speed", Type::F32(2.0))]))))
+		])
+	}
+	
+	#[test]
+	fn paths() {
+		assert_eq!(path("meshes[2].primitives[0].material").0, [
+			Segment::from("meshes"), 2.into(), "primitives".into(), 0.into(), "material".into()]);
+		assert_eq!(path(r#"[1]["a.b\\\""].c"#).0, [Segment::from(1), r#"a.b\""#.into(), "c".into()]);
+		assert_eq!(path(""), Path::default());
+		
+		for s in ["a.b[3]", "[0].x", r#"x[""].y"#, r#"a["q\\"]"#] {
+			assert_eq!(path(s).to_string(), s);
+		}
+		
+		for s in [".a", "a.", "a..b", "a[0]b", "a[x]", r#"a["b]"#, "a[0", "a b"] {
+			assert!(s.parse::<Path>().is_err(), "{}", s);
+		}
+	}
+	
+	#[test]
+	fn get_set_remove() {
+		let mut doc = document();
+		assert_eq!(doc.get(path("meshes[0].primitives[0].material")), Some(&Type::UInt(0)));
+		assert_eq!(doc.get(path("mode.speed")), Some(&Type::F32(2.0)));
+		assert_eq!(doc.get(path("meshes[2]")), None);
+		assert_eq!(doc.get(path("name.x")), None);
+		
+		assert_eq!(doc.set(path("meshes[0].primitives[0].material"), Type::UInt(3)), Ok(Some(Type::UInt(0))));
+		assert_eq!(doc.set(path("meshes[1].primitives[0].material"), Type::UInt(4)), Ok(None));
+		assert_eq!(doc.set(path(r#"meshes[2].extras["a.b"]"#), Type::Bool(true)), Ok(None));
+		assert_eq!(doc.get(path("meshes[1].primitives[0].material")), Some(&Type::UInt(4)));
+		assert_eq!(doc.get(path("meshes[2]")), Some(&map([("extras", map([("a.b", Type::Bool(true))]))])));
+		assert!(doc.set(path("meshes[5]"), Type::Unit).is_err());
+		assert!(doc.set(path("name[0]"), Type::Unit).is_err());
+		
+		assert_eq!(doc.remove(path("meshes[0]")), Some(map([("primitives", Type::Seq(vec![map([("material", Type::UInt(3))])]))])));
+		assert_eq!(doc.get(path("meshes[0].primitives[0].material")), Some(&Type::UInt(4)));
+		assert_eq!(doc.remove(path("mode.speed")), Some(Type::F32(2.0)));
+		assert_eq!(doc.remove(path("mode.speed")), None);
+		assert_eq!(doc.clone().set(Path::default(), Type::Unit), Ok(Some(doc)));
+	}
+	
+	#[test]
+	fn diff_apply() {
+		let old = document();
+		let mut new = document();
+		new.set(path("name"), Type::String("level".to_string())).unwrap();
+		new.set(path("meshes[0].primitives[1]"), Type::Unit).unwrap();
+		new.remove(path("meshes[1]"));
+		new.remove(path("mode"));
+		new.set(path("extras.tag"), Type::Char('x')).unwrap();
+		
+		let patch = old.diff(&new);
+		assert_eq!(patch, Patch(vec![
+			Operation::Set(path("extras"), map([("tag", Type::Char('x'))])),
+			Operation::Set(path("meshes[0].primitives[1]"), Type::Unit),
+			Operation::Remove(path("meshes[1]")),
+			Operation::Remove(path("mode")),
+			Operation::Set(path("name"), Type::String("level".to_string()))
+		]));
+		
+		let mut doc = old.clone();
+		patch.apply(&mut doc).unwrap();
+		assert_eq!(doc, new);
+		assert!(new.diff(&new).is_empty());
+		
+		let mut doc = new.clone();
+		new.diff(&old).apply(&mut doc).unwrap();
+		assert_eq!(doc, old);
+		
+		let patch = Type::serialize_from(&patch).unwrap().deserialize_to::<Patch>().unwrap();
+		assert!(patch.apply(&mut doc).is_ok() && patch.apply(&mut doc).is_err());
+	}
+	
+	#[test]
+	fn merge() {
+		let base = document();
+		let mut ours = document();
+		let mut theirs = document();
+		ours.set(path("name"), Type::String("ours".to_string())).unwrap();
+		ours.set(path("mode.speed"), Type::F32(3.0)).unwrap();
+		ours.set(path("meshes[0].primitives[0].material"), Type::UInt(1)).unwrap();
+		theirs.set(path("name"), Type::String("theirs".to_string())).unwrap();
+		theirs.set(path("mode.paused"), Type::Bool(true)).unwrap();
+		theirs.remove(path("meshes[0].primitives[0].material"));
+		theirs.set(path("meshes[1].primitives[0]"), Type::Unit).unwrap();
+		
+		let merged = Type::merge(&base, &ours, &theirs);
+		let mut expected = ours.clone();
+		expected.set(path("mode.paused"), Type::Bool(true)).unwrap();
+		expected.set(path("meshes[1].primitives[0]"), Type::Unit).unwrap();
+		assert_eq!(merged.value, expected);
+		assert_eq!(merged.conflicts, [
+			Conflict {
+				path:   path("meshes[0].primitives[0].material"),
+				base:   Some(Type::UInt(0)),
+				ours:   Some(Type::UInt(1)),
+				theirs: None
+			},
+			Conflict {
+				path:   path("name"),
+				base:   Some(Type::String("scene".to_string())),
+				ours:   Some(Type::String("ours".to_string())),
+				theirs: Some(Type::String("theirs".to_string()))
+			}
+		]);
+		
+		let merged = Type::merge(&base, &base, &theirs);
+		assert_eq!((merged.value, merged.conflicts), (theirs, vec![]));
+	}
+	
+	#[cfg(feature = "toml")]
+	#[test]
+	fn toml_datetimes() {
+		use crate::toml;
+		
+		let src = "offset = 1979-05-27T07:32:00.5-07:00\nlocal = 1979-05-27T07:32:00\ndate = 1979-05-27\ntime = 00:32:00";
+		let value = toml::Document::parse(src).unwrap().to_value().unwrap();
+		let doc = Type::from(value.clone());
+		assert_eq!(doc.get(path("date")), Some(&map([("$__toml_datetime", Type::String("1979-05-27".to_string()))])));
+		assert_eq!(toml::Value::try_from(doc), Ok(value));
+		
+		// other maps with the key stay tables
+		let doc = map([("$__toml_datetime", Type::String("1979-05-27".to_string())), ("a", Type::Bool(true))]);
+		assert!(matches!(toml::Value::try_from(doc), Ok(toml::Value::Table(_))));
+		assert!(toml::Value::try_from(map([("$__toml_datetime", Type::String("27.05.1979".to_string()))])).is_err());
+	}
+	
+	#[cfg(all(feature = "json", feature = "toml", feature = "ebml"))]
+	#[test]
+	fn conversions() {
+		use crate::{json, toml, ebml};
+		
+		let src = br#"{"name": "a", "values": [1, -2, 3.5, null, true], "nested": {"x": {}}}"#;
+		let value = json::deserialize_slice::<json::Value>(src).unwrap();
+		let doc = Type::from(value.clone());
+		assert_eq!(doc.get(path("values[1]")), Some(&Type::SInt(-2)));
+		assert_eq!(json::Value::try_from(doc), Ok(value));
+		assert!(json::Value::try_from(Type::Bytes(vec![1])).is_err());
+		
+		let value = toml::deserialize_str::<toml::Value>("a = 1\nb = [1.5, \"x\"]\n[c]\nd = true").unwrap();
+		let doc = Type::from(value.clone());
+		assert_eq!(doc.get(path("c.d")), Some(&Type::Bool(true)));
+		assert_eq!(toml::Value::try_from(doc), Ok(value));
+		assert!(toml::Value::try_from(Type::UInt(u64::MAX as _)).is_err());
+		assert!(toml::Value::try_from(Type::Seq(vec![Type::Unit])).is_err());
+		
+		let value = ebml::Value::Master(vec![
+			(0x4286, ebml::Value::Unsigned(1)),
+			(0xEC, ebml::Value::Binary(vec![0; 2])),
+			(0xEC, ebml::Value::Binary(vec![1])),
+			(0x18538067, ebml::Value::Master(vec![(0x7BA9, ebml::Value::String("title".to_string()))]))
+		]);
+		let doc = Type::from(value.clone());
+		assert_eq!(doc.get(path("[3][0]")), Some(&Type::Enum(0x7BA9, Box::new(Type::String("title".to_string())))));
+		assert_eq!(ebml::Value::try_from(doc), Ok(value));
+		assert_eq!(ebml::Value::try_from(map([("2", Type::Bool(true)), ("1", Type::F32(0.5))])),
+			Ok(ebml::Value::Master(vec![(1, ebml::Value::Float(0.5)), (2, ebml::Value::Unsigned(1))])));
+		assert!(ebml::Value::try_from(Type::Seq(vec![Type::UInt(1)])).is_err());
+	}
 }
\ No newline at end of file
@@ -20,7 +20,14 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO ELF
+//! ELF reader.
+//!
+//! [`ElfReader`] reads the file header, the program headers and the section headers with their
+//! names up front, for 32 and 64 bit files of either byte order. Section contents, symbol
+//! tables, dynamic entries and notes are read on demand. Addresses and sizes are stored as
+//! `usize`, regardless of the class of the file.
+
+use std::io::{self, Read, Seek, SeekFrom, Error, ErrorKind};
 
 pub const MAGIC: [u8; 4] = [0x7F, 0x45, 0x4C, 0x46];
 pub const VERSION_1: u8 = 1;
@@ -173,21 +180,36 @@ pub struct ProgramHeader {
 	pub align:     usize
 }
 
+impl ProgramHeader {
+	pub const FLAG_EXEC:  u32 = 0x1;
+	pub const FLAG_WRITE: u32 = 0x2;
+	pub const FLAG_READ:  u32 = 0x4;
+	
+	/// The flags, which are stored in `flags64` or `flags32`, depending on the class of the file.
+	pub fn flags(&self) -> u32 {
+		self.flags64 | self.flags32
+	}
+}
+
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ProgramType {
-	Null    = 0x00000000,
-	Load    = 0x00000001,
-	Dynamic = 0x00000002,
-	Interp  = 0x00000003,
-	Note    = 0x00000004,
-	ShLib   = 0x00000005,
-	Phdr    = 0x00000006,
-	Tls     = 0x00000007,
-	LoOs    = 0x60000000,
-	HiOs    = 0x6FFFFFFF,
-	LoProc  = 0x70000000,
-	HiProc  = 0x7FFFFFFF,
+	Null        = 0x00000000,
+	Load        = 0x00000001,
+	Dynamic     = 0x00000002,
+	Interp      = 0x00000003,
+	Note        = 0x00000004,
+	ShLib       = 0x00000005,
+	Phdr        = 0x00000006,
+	Tls         = 0x00000007,
+	LoOs        = 0x60000000,
+	GnuEhFrame  = 0x6474E550,
+	GnuStack    = 0x6474E551,
+	GnuRelro    = 0x6474E552,
+	GnuProperty = 0x6474E553,
+	HiOs        = 0x6FFFFFFF,
+	LoProc      = 0x70000000,
+	HiProc      = 0x7FFFFFFF,
 	__NonExhaustive__
 }
 
@@ -244,5 +266,766 @@ pub enum SectionType {
 	SymTableShndx = 0x0000_0012,
 	Num           = 0x0000_0013,
 	LoOs          = 0x6000_0000,
+	GnuAttributes = 0x6FFF_FFF5,
+	GnuHash       = 0x6FFF_FFF6,
+	GnuVerDef     = 0x6FFF_FFFD,
+	GnuVerNeed    = 0x6FFF_FFFE,
+	GnuVerSym     = 0x6FFF_FFFF,
 	__NonExhaustive__
-}
\ No newline at end of file
+}
+
+/// Special section indices of symbols.
+pub const SECTION_UNDEF:  u16 = 0x0000;
+pub const SECTION_ABS:    u16 = 0xFFF1;
+pub const SECTION_COMMON: u16 = 0xFFF2;
+/// The actual index is stored in a `SymTableShndx` section. In the file header, the index of
+/// the section name table is stored in the `link` field of the first section header instead.
+pub const SECTION_XINDEX: u16 = 0xFFFF;
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+macro_rules! from_raw {
+	($name:ident: $ty:ty, $fallback:expr, $( $variant:ident ),* ) => {
+		impl $name {
+			fn from_raw(v: $ty) -> Option<Self> {
+				$( if v == Self::$variant as $ty { return Some(Self::$variant); } )*
+				#[allow(clippy::redundant_closure_call)]
+				($fallback)(v)
+			}
+		}
+	};
+}
+
+from_raw!(Class: u8, |_| None, _32Bit, _64Bit);
+from_raw!(Data: u8, |_| None, Little, Big);
+from_raw!(OsAbi: u8, |_| Some(Self::__NonExhaustive__),
+	SystemV, HpUx, NetBsd, Linux, GnuHurd, Solaris, Aix, Irix, FreeBsd, Tru64, NovellModesto, OpenBsd, OpenMvs,
+	NonStopKernel, Aros, FenixOs, CloudAbi, OpenVos);
+from_raw!(Type: u16, |v| match v {
+	0xFE00..=0xFEFF => Some(Self::LoOs),
+	0xFF00..=0xFFFF => Some(Self::LoProc),
+	_ => None
+}, None, Rel, Exec, Dyn, Core, LoOs, HiOs, LoProc, HiProc);
+from_raw!(Machine: u16, |_| Some(Self::__NonExhaustive__),
+	Unspecified, AttWe32100, Sparc, x86, M68k, M88k, IntelMcu, Intel80860, Mips, IbmSystem370, MipsRs3000LE,
+	HpPaRisc, Intel80960, PPC32, PPC64, S390, IbmSpuSpc, NecV800, FR20, TrwRh32, MotorolaRCE, AArch32,
+	DigitalAlpha, SuperH, Sparc9, SiemensTriCore, ArgonautRiscCore, H8_300, H8_300H, H8S, H8_500, IA64,
+	StandfordMipsX, MotorolaColdFire, M68HC12, MMA, SiemensPcp, SonyNCpu, Ndr1, MotorolaStarCore, ToyotaMe16,
+	ST100, ALC, AMD64, TMS320C6000, AArch64, RiscV, BPF, WDC65C816);
+from_raw!(ProgramType: u32, |v| Some(match v {
+	0x6000_0000..=0x6FFF_FFFF => Self::LoOs,
+	0x7000_0000..=0x7FFF_FFFF => Self::LoProc,
+	_ => Self::__NonExhaustive__
+}), Null, Load, Dynamic, Interp, Note, ShLib, Phdr, Tls, LoOs, GnuEhFrame, GnuStack, GnuRelro, GnuProperty,
+	HiOs, LoProc, HiProc);
+from_raw!(SectionType: u32, |v| Some(match v {
+	0x6000_0000..=0x6FFF_FFFF => Self::LoOs,
+	_ => Self::__NonExhaustive__
+}), Null, ProgBits, SymTable, StrTable, Rela, Hash, Dynamic, Note, NoBits, Rel, ShLib, DynSym, InitArray,
+	FiniArray, PreInitArray, Group, SymTableShndx, Num, LoOs, GnuAttributes, GnuHash, GnuVerDef, GnuVerNeed,
+	GnuVerSym);
+
+#[derive(Clone, Debug)]
+pub struct Section {
+	pub name:   String,
+	pub header: SectionHeader
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymbolBinding {
+	Local  = 0,
+	Global = 1,
+	Weak   = 2,
+	__NonExhaustive__
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymbolType {
+	NoType   = 0,
+	Object   = 1,
+	Func     = 2,
+	Section  = 3,
+	File     = 4,
+	Common   = 5,
+	Tls      = 6,
+	GnuIFunc = 10,
+	__NonExhaustive__
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymbolVisibility {
+	Default   = 0,
+	Internal  = 1,
+	Hidden    = 2,
+	Protected = 3
+}
+
+from_raw!(SymbolBinding: u8, |_| Some(Self::__NonExhaustive__), Local, Global, Weak);
+from_raw!(SymbolType: u8, |_| Some(Self::__NonExhaustive__), NoType, Object, Func, Section, File, Common, Tls, GnuIFunc);
+from_raw!(SymbolVisibility: u8, |_| None, Default, Internal, Hidden, Protected);
+
+#[derive(Clone, Debug)]
+pub struct Symbol {
+	pub name:       String,
+	pub value:      usize,
+	pub size:       usize,
+	pub binding:    SymbolBinding,
+	pub r#type:     SymbolType,
+	pub visibility: SymbolVisibility,
+	/// The index of the section the symbol is defined in, or one of the `SECTION_*` constants.
+	pub section:    u16
+}
+
+impl Symbol {
+	/// Whether the symbol is defined in this file and visible to other files.
+	pub fn is_exported(&self) -> bool {
+		self.section != SECTION_UNDEF
+			&& matches!(self.binding, SymbolBinding::Global | SymbolBinding::Weak)
+			&& matches!(self.visibility, SymbolVisibility::Default | SymbolVisibility::Protected)
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DynamicEntry {
+	pub tag:   usize,
+	pub value: usize
+}
+
+impl DynamicEntry {
+	pub const TAG_NULL:     usize = 0;
+	pub const TAG_NEEDED:   usize = 1;
+	pub const TAG_HASH:     usize = 4;
+	pub const TAG_STRTAB:   usize = 5;
+	pub const TAG_SYMTAB:   usize = 6;
+	pub const TAG_STRSZ:    usize = 10;
+	pub const TAG_SYMENT:   usize = 11;
+	pub const TAG_INIT:     usize = 12;
+	pub const TAG_FINI:     usize = 13;
+	pub const TAG_SONAME:   usize = 14;
+	pub const TAG_RPATH:    usize = 15;
+	pub const TAG_TEXTREL:  usize = 22;
+	pub const TAG_FLAGS:    usize = 30;
+	pub const TAG_RUNPATH:  usize = 29;
+	pub const TAG_GNU_HASH: usize = 0x6FFF_FEF5;
+	pub const TAG_FLAGS_1:  usize = 0x6FFF_FFFB;
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Note {
+	pub name:   String,
+	pub r#type: u32,
+	pub desc:   Vec<u8>
+}
+
+impl Note {
+	pub const TYPE_GNU_ABI_TAG:         u32 = 1;
+	pub const TYPE_GNU_BUILD_ID:        u32 = 3;
+	pub const TYPE_GNU_PROPERTY_TYPE_0: u32 = 5;
+}
+
+/// Reads integers of the byte order and class of a file.
+#[derive(Copy, Clone, Debug)]
+struct Layout {
+	big:  bool,
+	wide: bool
+}
+
+impl Layout {
+	fn addr_size(self) -> usize {
+		if self.wide { 8 } else { 4 }
+	}
+	
+	fn bytes<const N: usize>(self, buf: &[u8], offset: &mut usize) -> io::Result<[u8; N]> {
+		let bytes = buf.get(*offset..*offset + N)
+			.ok_or_else(|| invalid_data("unexpected end of structure"))?;
+		*offset += N;
+		Ok(bytes.try_into().unwrap())
+	}
+	
+	fn u8(self, buf: &[u8], offset: &mut usize) -> io::Result<u8> {
+		Ok(self.bytes::<1>(buf, offset)?[0])
+	}
+	
+	fn u16(self, buf: &[u8], offset: &mut usize) -> io::Result<u16> {
+		let b = self.bytes(buf, offset)?;
+		Ok(if self.big { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+	}
+	
+	fn u32(self, buf: &[u8], offset: &mut usize) -> io::Result<u32> {
+		let b = self.bytes(buf, offset)?;
+		Ok(if self.big { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+	}
+	
+	fn u64(self, buf: &[u8], offset: &mut usize) -> io::Result<u64> {
+		let b = self.bytes(buf, offset)?;
+		Ok(if self.big { u64::from_be_bytes(b) } else { u64::from_le_bytes(b) })
+	}
+	
+	/// Reads an address, offset or size, which is 4 or 8 bytes long, depending on the class.
+	fn addr(self, buf: &[u8], offset: &mut usize) -> io::Result<usize> {
+		match self.wide {
+			true  => self.u64(buf, offset).map(|v| v as usize),
+			false => self.u32(buf, offset).map(|v| v as usize)
+		}
+	}
+}
+
+/// Returns the null terminated string at the offset.
+fn string(table: &[u8], offset: usize) -> io::Result<String> {
+	if table.is_empty() && offset == 0 {
+		return Ok(String::new());
+	}
+	
+	let s = table.get(offset..).ok_or_else(|| invalid_data("invalid string table offset"))?;
+	let len = s.iter().position(|b| *b == 0).ok_or_else(|| invalid_data("unterminated string"))?;
+	String::from_utf8(s[..len].to_vec()).map_err(|_| invalid_data("invalid UTF-8 string"))
+}
+
+pub struct ElfReader<R> {
+	reader:              R,
+	layout:              Layout,
+	pub header:          FileHeader,
+	pub program_headers: Vec<ProgramHeader>,
+	pub sections:        Vec<Section>
+}
+
+impl<R: Read + Seek> ElfReader<R> {
+	pub fn new(mut reader: R) -> io::Result<Self> {
+		let mut ident = [0u8; 16];
+		reader.read_exact(&mut ident)?;
+		
+		if ident[..4] != MAGIC {
+			return Err(invalid_data("not an ELF file"));
+		} else if ident[6] != VERSION_1 {
+			return Err(invalid_data("unsupported ELF version"));
+		}
+		
+		let class = Class::from_raw(ident[4]).ok_or_else(|| invalid_data("invalid class"))?;
+		let data = Data::from_raw(ident[5]).ok_or_else(|| invalid_data("invalid data encoding"))?;
+		let layout = Layout { big: data == Data::Big, wide: class == Class::_64Bit };
+		
+		let mut buf = vec![0u8; if layout.wide { 48 } else { 36 }];
+		reader.read_exact(&mut buf)?;
+		let l = layout;
+		let o = &mut 0;
+		
+		let mut header = FileHeader {
+			magic:       MAGIC,
+			class,
+			data,
+			version:     ident[6],
+			os_abi:      OsAbi::from_raw(ident[7]).unwrap(),
+			abi_version: ident[8],
+			_pad0:       [0; 7],
+			r#type:      Type::from_raw(l.u16(&buf, o)?).ok_or_else(|| invalid_data("invalid file type"))?,
+			machine:     Machine::from_raw(l.u16(&buf, o)?).unwrap(),
+			version2:    l.u32(&buf, o)?,
+			entry:       l.addr(&buf, o)?,
+			phoff:       l.addr(&buf, o)?,
+			shoff:       l.addr(&buf, o)?,
+			flags:       l.u32(&buf, o)?,
+			eh_size:     l.u16(&buf, o)?,
+			phent_size:  l.u16(&buf, o)?,
+			ph_num:      l.u16(&buf, o)?,
+			shent_size:  l.u16(&buf, o)?,
+			sh_num:      l.u16(&buf, o)?,
+			shstr_ndx:   l.u16(&buf, o)?
+		};
+		
+		let mut self_ = Self { reader, layout, header, program_headers: Vec::new(), sections: Vec::new() };
+		
+		// section headers
+		let mut section_headers = Vec::new();
+		if header.shoff != 0 {
+			let ent_size = header.shent_size as usize;
+			if ent_size < 10 * 4 + 6 * (l.addr_size() - 4) {
+				return Err(invalid_data("invalid section header size"));
+			}
+			
+			// with more than 0xFF00 sections, the first section header stores the number of sections,
+			// the index of the section name table and the number of program headers
+			let first = self_.read_at(header.shoff as u64, ent_size)?;
+			let first = self_.section_header(&first)?;
+			let count = match header.sh_num {
+				0 => first.1.size,
+				n => n as usize
+			};
+			
+			if header.shstr_ndx == SECTION_XINDEX {
+				header.shstr_ndx = 0;
+			}
+			
+			let shstr_ndx = match header.shstr_ndx {
+				0 if first.1.link != 0 => first.1.link as usize,
+				n => n as usize
+			};
+			
+			if header.ph_num == 0xFFFF {
+				header.ph_num = first.1.info as u16;
+			}
+			
+			let buf = self_.read_at(header.shoff as u64, count.checked_mul(ent_size)
+				.ok_or_else(|| invalid_data("invalid section header count"))?)?;
+			
+			for entry in buf.chunks_exact(ent_size) {
+				section_headers.push(self_.section_header(entry)?);
+			}
+			
+			let names = match section_headers.get(shstr_ndx) {
+				Some((_, names)) if shstr_ndx != 0 => self_.read_data(names)?,
+				_ => Vec::new()
+			};
+			
+			self_.sections = section_headers.into_iter()
+				.map(|(name, header)| Ok(Section {
+					name: if names.is_empty() { String::new() } else { string(&names, name as usize)? },
+					header
+				}))
+				.collect::<io::Result<_>>()?;
+		}
+		
+		// program headers
+		if header.phoff != 0 && header.ph_num != 0 {
+			let ent_size = header.phent_size as usize;
+			if ent_size < if l.wide { 56 } else { 32 } {
+				return Err(invalid_data("invalid program header size"));
+			}
+			
+			let buf = self_.read_at(header.phoff as u64, header.ph_num as usize * ent_size)?;
+			for entry in buf.chunks_exact(ent_size) {
+				let o = &mut 0;
+				let r#type = ProgramType::from_raw(l.u32(entry, o)?).unwrap();
+				let flags64 = if l.wide { l.u32(entry, o)? } else { 0 };
+				self_.program_headers.push(ProgramHeader {
+					r#type,
+					flags64,
+					offset:    l.addr(entry, o)?,
+					vaddr:     l.addr(entry, o)?,
+					paddr:     l.addr(entry, o)?,
+					file_size: l.addr(entry, o)?,
+					mem_size:  l.addr(entry, o)?,
+					flags32:   if l.wide { 0 } else { l.u32(entry, o)? },
+					align:     l.addr(entry, o)?
+				});
+			}
+		}
+		
+		self_.header = header;
+		Ok(self_)
+	}
+	
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+	
+	fn read_at(&mut self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+		self.reader.seek(SeekFrom::Start(offset))?;
+		let mut buf = Vec::new();
+		
+		// the buffer is not allocated up front, as the length may be corrupt
+		if (&mut self.reader).take(len as u64).read_to_end(&mut buf)? != len {
+			return Err(ErrorKind::UnexpectedEof.into());
+		}
+		
+		Ok(buf)
+	}
+	
+	/// Parses a section header, returns the offset of the name and the header.
+	fn section_header(&self, buf: &[u8]) -> io::Result<(u32, SectionHeader)> {
+		let l = self.layout;
+		let o = &mut 0;
+		Ok((l.u32(buf, o)?, SectionHeader {
+			r#type:     SectionType::from_raw(l.u32(buf, o)?).unwrap(),
+			flags:      l.addr(buf, o)?,
+			addr:       l.addr(buf, o)?,
+			offset:     l.addr(buf, o)?,
+			size:       l.addr(buf, o)?,
+			link:       l.u32(buf, o)?,
+			info:       l.u32(buf, o)?,
+			addr_align: l.addr(buf, o)?,
+			ent_size:   l.addr(buf, o)?
+		}))
+	}
+	
+	fn read_data(&mut self, header: &SectionHeader) -> io::Result<Vec<u8>> {
+		match header.r#type {
+			SectionType::NoBits => Ok(vec![0; header.size]),
+			_ => self.read_at(header.offset as u64, header.size)
+		}
+	}
+	
+	pub fn section(&self, name: &str) -> Option<&Section> {
+		self.sections.iter().find(|section| section.name == name)
+	}
+	
+	/// Reads the content of the section with the given index.
+	pub fn read_section(&mut self, index: usize) -> io::Result<Vec<u8>> {
+		let header = self.sections.get(index)
+			.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "invalid section index"))?
+			.header;
+		self.read_data(&header)
+	}
+	
+	/// Reads the content of the first section with the given name, e.g. a custom section that
+	/// was added with `#[link_section]`.
+	pub fn read_section_by_name(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+		match self.sections.iter().position(|section| section.name == name) {
+			Some(index) => self.read_section(index).map(Some),
+			None => Ok(None)
+		}
+	}
+	
+	/// Reads the symbols of the first section of the given type, i.e. `SymTable` or `DynSym`.
+	/// The first symbol, which is always undefined, is included.
+	pub fn symbols(&mut self, r#type: SectionType) -> io::Result<Vec<Symbol>> {
+		let Some(index) = self.sections.iter().position(|section| section.header.r#type == r#type) else {
+			return Ok(Vec::new());
+		};
+		
+		let header = self.sections[index].header;
+		let data = self.read_data(&header)?;
+		let names = self.read_section(header.link as usize)?;
+		let l = self.layout;
+		let ent_size = match header.ent_size {
+			0 => if l.wide { 24 } else { 16 },
+			n => n
+		};
+		
+		data.chunks_exact(ent_size)
+			.map(|entry| {
+				let o = &mut 0;
+				let name = l.u32(entry, o)?;
+				let (value, size, info, other, section) = match l.wide {
+					true  => {
+						let (info, other, section) = (l.u8(entry, o)?, l.u8(entry, o)?, l.u16(entry, o)?);
+						(l.addr(entry, o)?, l.addr(entry, o)?, info, other, section)
+					}
+					false => (l.addr(entry, o)?, l.addr(entry, o)?, l.u8(entry, o)?, l.u8(entry, o)?, l.u16(entry, o)?)
+				};
+				
+				Ok(Symbol {
+					name:       string(&names, name as usize)?,
+					value,
+					size,
+					binding:    SymbolBinding::from_raw(info >> 4).unwrap(),
+					r#type:     SymbolType::from_raw(info & 0xF).unwrap(),
+					visibility: SymbolVisibility::from_raw(other & 0x3).unwrap(),
+					section
+				})
+			})
+			.collect()
+	}
+	
+	/// Reads the entries of the dynamic section, up to the terminating `TAG_NULL` entry.
+	pub fn dynamic(&mut self) -> io::Result<Vec<DynamicEntry>> {
+		let data = match self.sections.iter().position(|section| section.header.r#type == SectionType::Dynamic) {
+			Some(index) => self.read_section(index)?,
+			None => match self.program_headers.iter().find(|header| header.r#type == ProgramType::Dynamic) {
+				Some(header) => {
+					let (offset, len) = (header.offset, header.file_size);
+					self.read_at(offset as u64, len)?
+				}
+				None => return Ok(Vec::new())
+			}
+		};
+		
+		let l = self.layout;
+		let mut entries = Vec::new();
+		
+		for entry in data.chunks_exact(2 * l.addr_size()) {
+			let o = &mut 0;
+			let entry = DynamicEntry { tag: l.addr(entry, o)?, value: l.addr(entry, o)? };
+			if entry.tag == DynamicEntry::TAG_NULL {
+				break;
+			}
+			
+			entries.push(entry);
+		}
+		
+		Ok(entries)
+	}
+	
+	/// The names of the libraries the file depends on, from the `TAG_NEEDED` entries.
+	pub fn needed(&mut self) -> io::Result<Vec<String>> {
+		let Some(index) = self.sections.iter().position(|section| section.header.r#type == SectionType::Dynamic) else {
+			return Ok(Vec::new());
+		};
+		
+		let names = self.read_section(self.sections[index].header.link as usize)?;
+		self.dynamic()?.iter()
+			.filter(|entry| entry.tag == DynamicEntry::TAG_NEEDED)
+			.map(|entry| string(&names, entry.value))
+			.collect()
+	}
+	
+	/// Reads the notes of all note sections, or of all note segments if there are no section
+	/// headers.
+	pub fn notes(&mut self) -> io::Result<Vec<Note>> {
+		let ranges = match self.sections.is_empty() {
+			false => self.sections.iter()
+				.filter(|section| section.header.r#type == SectionType::Note)
+				.map(|section| (section.header.offset, section.header.size, section.header.addr_align))
+				.collect::<Vec<_>>(),
+			true => self.program_headers.iter()
+				.filter(|header| header.r#type == ProgramType::Note)
+				.map(|header| (header.offset, header.file_size, header.align))
+				.collect()
+		};
+		
+		let l = self.layout;
+		let mut notes = Vec::new();
+		
+		for (offset, len, align) in ranges {
+			let data = self.read_at(offset as u64, len)?;
+			let align = if align == 8 { 8 } else { 4 };
+			let o = &mut 0;
+			
+			while *o < data.len() {
+				let name_len = l.u32(&data, o)? as usize;
+				let desc_len = l.u32(&data, o)? as usize;
+				let r#type = l.u32(&data, o)?;
+				let name = data.get(*o..*o + name_len).ok_or_else(|| invalid_data("invalid note"))?;
+				*o = (*o + name_len).next_multiple_of(align);
+				let desc = data.get(*o..*o + desc_len).ok_or_else(|| invalid_data("invalid note"))?;
+				*o = (*o + desc_len).next_multiple_of(align);
+				
+				notes.push(Note {
+					name: String::from_utf8_lossy(name.strip_suffix(&[0]).unwrap_or(name)).into_owned(),
+					r#type,
+					desc: desc.to_vec()
+				});
+			}
+		}
+		
+		Ok(notes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	struct Writer {
+		buf:  Vec<u8>,
+		big:  bool,
+		wide: bool
+	}
+	
+	impl Writer {
+		fn u8(&mut self, v: u8) {
+			self.buf.push(v);
+		}
+		
+		fn u16(&mut self, v: u16) {
+			self.buf.extend(if self.big { v.to_be_bytes() } else { v.to_le_bytes() });
+		}
+		
+		fn u32(&mut self, v: u32) {
+			self.buf.extend(if self.big { v.to_be_bytes() } else { v.to_le_bytes() });
+		}
+		
+		fn addr(&mut self, v: usize) {
+			match self.wide {
+				true  => self.buf.extend(if self.big { (v as u64).to_be_bytes() } else { (v as u64).to_le_bytes() }),
+				false => self.u32(v as u32)
+			}
+		}
+	}
+	
+	fn strtab(strings: &[&str]) -> (Vec<u8>, Vec<u32>) {
+		let mut table = vec![0u8];
+		let offsets = strings.iter().map(|s| {
+			let offset = table.len() as u32;
+			table.extend(s.bytes().chain([0]));
+			offset
+		}).collect();
+		(table, offsets)
+	}
+	
+	/// A shared object with a dynamic symbol table, a dynamic section, a build ID note and a
+	/// custom section.
+	fn file(big: bool, wide: bool) -> Vec<u8> {
+		let section_names = [".shstrtab", ".dynstr", ".dynsym", ".dynamic", ".note.gnu.build-id", ".assets"];
+		let (shstrtab, name_offsets) = strtab(&section_names);
+		let (dynstr, sym_names) = strtab(&["plugin_name", "plugin_setup", "plugin_destroy", "hidden", "malloc",
+			"libc.so.6", "libplugin.so"]);
+		
+		let mut w = Writer { buf: Vec::new(), big, wide };
+		
+		// symbols: name, info, other, section
+		w.buf.clear();
+		for (name, info, other, section) in [(0, 0, 0, 0), (sym_names[0], 0x11, 0, 6), (sym_names[1], 0x12, 0, 6),
+			(sym_names[2], 0x22, 0, 6), (sym_names[3], 0x12, 2, 6), (sym_names[4], 0x12, 0, 0)] {
+			w.u32(name);
+			if wide {
+				w.u8(info);
+				w.u8(other);
+				w.u16(section);
+				w.addr(0x1000);
+				w.addr(16);
+			} else {
+				w.addr(0x1000);
+				w.addr(16);
+				w.u8(info);
+				w.u8(other);
+				w.u16(section);
+			}
+		}
+		let dynsym = std::mem::take(&mut w.buf);
+		
+		for (tag, value) in [(DynamicEntry::TAG_NEEDED, sym_names[5]), (DynamicEntry::TAG_SONAME, sym_names[6]), (0, 0)] {
+			w.addr(tag);
+			w.addr(value as usize);
+		}
+		let dynamic = std::mem::take(&mut w.buf);
+		
+		w.u32(4);
+		w.u32(20);
+		w.u32(Note::TYPE_GNU_BUILD_ID);
+		w.buf.extend(b"GNU\0");
+		w.buf.extend([0xAB; 20]);
+		let note = std::mem::take(&mut w.buf);
+		
+		let contents = [shstrtab, dynstr, dynsym, dynamic, note, b"{\"assets\":[]}".to_vec()];
+		let header_size = if wide { 64 } else { 52 };
+		let ph_size = if wide { 56 } else { 32 };
+		let sh_size = if wide { 64 } else { 40 };
+		let mut offset = header_size + 2 * ph_size;
+		let offsets = contents.iter().map(|data| {
+			let o = offset;
+			offset += data.len();
+			o
+		}).collect::<Vec<_>>();
+		let shoff = offset;
+		
+		// file header
+		w.buf.extend(MAGIC);
+		w.buf.extend([if wide { 2 } else { 1 }, if big { 2 } else { 1 }, 1, 3, 0, 0, 0, 0, 0, 0, 0, 0]);
+		w.u16(Type::Dyn as u16);
+		w.u16(Machine::AArch64 as u16);
+		w.u32(1);
+		w.addr(0);
+		w.addr(header_size);
+		w.addr(shoff);
+		w.u32(0);
+		w.u16(header_size as u16);
+		w.u16(ph_size as u16);
+		w.u16(2);
+		w.u16(sh_size as u16);
+		w.u16(contents.len() as u16 + 1);
+		w.u16(1);
+		
+		// program headers
+		for (r#type, flags, offset, size) in [(1, 5, 0, shoff), (2, 6, offsets[3], contents[3].len())] {
+			w.u32(r#type);
+			if wide {
+				w.u32(flags);
+			}
+			w.addr(offset);
+			w.addr(offset);
+			w.addr(offset);
+			w.addr(size);
+			w.addr(size);
+			if !wide {
+				w.u32(flags);
+			}
+			w.addr(8);
+		}
+		
+		for data in &contents {
+			w.buf.extend(data);
+		}
+		
+		// section headers
+		w.buf.extend(vec![0; sh_size]);
+		let types = [SectionType::StrTable, SectionType::StrTable, SectionType::DynSym, SectionType::Dynamic,
+			SectionType::Note, SectionType::ProgBits];
+		for i in 0..contents.len() {
+			w.u32(name_offsets[i]);
+			w.u32(types[i] as u32);
+			w.addr(0);
+			w.addr(offsets[i]);
+			w.addr(offsets[i]);
+			w.addr(contents[i].len());
+			w.u32(if i == 2 || i == 3 { 2 } else { 0 });
+			w.u32(if i == 2 { 1 } else { 0 });
+			w.addr(4);
+			w.addr(0);
+		}
+		
+		w.buf
+	}
+	
+	#[test]
+	fn headers() {
+		for (big, wide) in [(false, true), (true, true), (false, false), (true, false)] {
+			let reader = ElfReader::new(io::Cursor::new(file(big, wide))).unwrap();
+			assert_eq!(reader.header.class, if wide { Class::_64Bit } else { Class::_32Bit });
+			assert_eq!(reader.header.data, if big { Data::Big } else { Data::Little });
+			assert_eq!((reader.header.os_abi, reader.header.r#type, reader.header.machine), (OsAbi::Linux, Type::Dyn, Machine::AArch64));
+			
+			assert_eq!(reader.program_headers.iter().map(|header| (header.r#type, header.flags())).collect::<Vec<_>>(), [
+				(ProgramType::Load, ProgramHeader::FLAG_READ | ProgramHeader::FLAG_EXEC),
+				(ProgramType::Dynamic, ProgramHeader::FLAG_READ | ProgramHeader::FLAG_WRITE)
+			]);
+			
+			assert_eq!(reader.sections.iter().map(|section| section.name.as_str()).collect::<Vec<_>>(),
+				["", ".shstrtab", ".dynstr", ".dynsym", ".dynamic", ".note.gnu.build-id", ".assets"]);
+			assert_eq!(reader.section(".dynsym").unwrap().header.r#type, SectionType::DynSym);
+		}
+	}
+	
+	#[test]
+	fn symbols() {
+		for (big, wide) in [(false, true), (true, false)] {
+			let mut reader = ElfReader::new(io::Cursor::new(file(big, wide))).unwrap();
+			let symbols = reader.symbols(SectionType::DynSym).unwrap();
+			
+			assert_eq!(symbols.iter().map(|symbol| symbol.name.as_str()).collect::<Vec<_>>(),
+				["", "plugin_name", "plugin_setup", "plugin_destroy", "hidden", "malloc"]);
+			assert_eq!(symbols.iter().filter(|symbol| symbol.is_exported()).count(), 3);
+			assert_eq!((symbols[1].r#type, symbols[1].binding), (SymbolType::Object, SymbolBinding::Global));
+			assert_eq!((symbols[3].r#type, symbols[3].binding), (SymbolType::Func, SymbolBinding::Weak));
+			assert_eq!((symbols[4].visibility, symbols[5].section), (SymbolVisibility::Hidden, SECTION_UNDEF));
+			assert_eq!((symbols[2].value, symbols[2].size), (0x1000, 16));
+			assert!(reader.symbols(SectionType::SymTable).unwrap().is_empty());
+		}
+	}
+	
+	#[test]
+	fn dynamic_and_notes() {
+		for (big, wide) in [(false, true), (true, false)] {
+			let mut reader = ElfReader::new(io::Cursor::new(file(big, wide))).unwrap();
+			assert_eq!(reader.dynamic().unwrap().len(), 2);
+			assert_eq!(reader.needed().unwrap(), ["libc.so.6"]);
+			assert_eq!(reader.notes().unwrap(), [Note { name: "GNU".to_string(), r#type: Note::TYPE_GNU_BUILD_ID, desc: vec![0xAB; 20] }]);
+			assert_eq!(reader.read_section_by_name(".assets").unwrap().unwrap(), b"{\"assets\":[]}");
+			assert!(reader.read_section_by_name(".missing").unwrap().is_none());
+		}
+	}
+	
+	#[test]
+	fn invalid() {
+		let mut data = file(false, true);
+		assert!(ElfReader::new(io::Cursor::new(&data[..40])).is_err());
+		data[4] = 3;
+		assert!(ElfReader::new(io::Cursor::new(&data)).is_err());
+		data[0] = 0;
+		assert!(ElfReader::new(io::Cursor::new(&data)).is_err());
+	}
+	
+	#[test]
+	#[cfg(target_os = "linux")]
+	fn current_exe() {
+		let file = std::fs::File::open(std::env::current_exe().unwrap()).unwrap();
+		let mut reader = ElfReader::new(io::BufReader::new(file)).unwrap();
+		assert_eq!(reader.header.class == Class::_64Bit, cfg!(target_pointer_width = "64"));
+		assert!(reader.section(".text").is_some());
+		assert!(!reader.symbols(SectionType::DynSym).unwrap().is_empty());
+	}
+}
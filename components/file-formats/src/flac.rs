@@ -20,4 +20,1383 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO FLAC
\ No newline at end of file
+//! FLAC decoder.
+//!
+//! [`FlacReader`] parses the metadata blocks up front and decodes frames on demand. Decoded
+//! samples are available as [`Block`]s, as interleaved `i32`s or through [`io::Read`] as
+//! interleaved little-endian integers that are scaled up to 8, 16 or 32 bits. Seeking is sample
+//! accurate, the seek table is used if present, otherwise the stream is bisected.
+
+use std::io::{self, Read, Seek, SeekFrom, BufRead, Error, ErrorKind};
+
+pub const MAGIC: [u8; 4] = *b"fLaC";
+
+pub const BLOCK_STREAMINFO:     u8 = 0;
+pub const BLOCK_PADDING:        u8 = 1;
+pub const BLOCK_APPLICATION:    u8 = 2;
+pub const BLOCK_SEEKTABLE:      u8 = 3;
+pub const BLOCK_VORBIS_COMMENT: u8 = 4;
+pub const BLOCK_CUESHEET:       u8 = 5;
+pub const BLOCK_PICTURE:        u8 = 6;
+
+/// The sample number of seek points that are placeholders.
+pub const PLACEHOLDER: u64 = u64::MAX;
+
+/// How many bytes are scanned for a frame header when bisecting, if the maximum frame size is unknown.
+const SCAN_LEN: u64 = 1 << 16;
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StreamInfo {
+	pub min_block_size:  u16,
+	pub max_block_size:  u16,
+	/// The minimum frame size in bytes, 0 if unknown.
+	pub min_frame_size:  u32,
+	/// The maximum frame size in bytes, 0 if unknown.
+	pub max_frame_size:  u32,
+	pub sample_rate:     u32,
+	pub channels:        u8,
+	pub bits_per_sample: u8,
+	/// The number of samples per channel, 0 if unknown.
+	pub total_samples:   u64,
+	/// The MD5 signature of the unencoded samples, all zeros if unknown.
+	pub md5:             [u8; 16]
+}
+
+impl StreamInfo {
+	pub fn parse(data: &[u8]) -> io::Result<Self> {
+		if data.len() < 34 {
+			return Err(invalid_data("STREAMINFO block is too short"));
+		}
+		
+		let mut bits = BitReader::new(data);
+		let info = Self {
+			min_block_size:  bits.read(16)? as _,
+			max_block_size:  bits.read(16)? as _,
+			min_frame_size:  bits.read(24)? as _,
+			max_frame_size:  bits.read(24)? as _,
+			sample_rate:     bits.read(20)? as _,
+			channels:        bits.read(3)? as u8 + 1,
+			bits_per_sample: bits.read(5)? as u8 + 1,
+			total_samples:   bits.read(36)?,
+			md5:             data[18..34].try_into().unwrap()
+		};
+		
+		if info.min_block_size < 16 || info.max_block_size < info.min_block_size {
+			Err(invalid_data("invalid block size"))
+		} else if info.sample_rate == 0 {
+			Err(invalid_data("invalid sample rate"))
+		} else if info.bits_per_sample < 4 {
+			Err(invalid_data("invalid bits per sample"))
+		} else {
+			Ok(info)
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeekPoint {
+	/// The first sample of the target frame, [`PLACEHOLDER`] for placeholder points.
+	pub sample:  u64,
+	/// The offset of the target frame from the first frame in bytes.
+	pub offset:  u64,
+	pub samples: u16
+}
+
+impl SeekPoint {
+	pub fn parse_table(data: &[u8]) -> io::Result<Vec<Self>> {
+		if !data.len().is_multiple_of(18) {
+			return Err(invalid_data("invalid SEEKTABLE length"));
+		}
+		
+		Ok(data.chunks_exact(18)
+			.map(|point| Self {
+				sample:  u64::from_be_bytes(point[..8].try_into().unwrap()),
+				offset:  u64::from_be_bytes(point[8..16].try_into().unwrap()),
+				samples: u16::from_be_bytes([point[16], point[17]])
+			})
+			.collect())
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VorbisComment {
+	pub vendor:   String,
+	/// The comments as field name and value, names are case-insensitive and may appear more than once.
+	pub comments: Vec<(String, String)>
+}
+
+impl VorbisComment {
+	pub fn parse(mut data: &[u8]) -> io::Result<Self> {
+		fn u32_le(data: &mut &[u8]) -> io::Result<u32> {
+			let mut buf = [0u8; 4];
+			data.read_exact(&mut buf)?;
+			Ok(u32::from_le_bytes(buf))
+		}
+		
+		fn string(data: &mut &[u8]) -> io::Result<String> {
+			let len = u32_le(data)? as usize;
+			if len > data.len() {
+				return Err(invalid_data("VORBIS_COMMENT string is too long"));
+			}
+			
+			let (s, rest) = data.split_at(len);
+			*data = rest;
+			Ok(String::from_utf8_lossy(s).into_owned())
+		}
+		
+		let vendor = string(&mut data)?;
+		let mut comments = Vec::new();
+		for _ in 0..u32_le(&mut data)? {
+			let comment = string(&mut data)?;
+			comments.push(match comment.split_once('=') {
+				Some((name, value)) => (name.to_string(), value.to_string()),
+				None => (comment, String::new())
+			});
+		}
+		
+		Ok(Self { vendor, comments })
+	}
+	
+	/// Returns the values of all comments with the given field name.
+	pub fn get<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+		self.comments.iter()
+			.filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+			.map(|(_, v)| v.as_str())
+	}
+}
+
+/// The decoded samples of one frame.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Block {
+	/// The number of the first sample per channel in this block.
+	pub first_sample:    u64,
+	pub sample_rate:     u32,
+	pub channels:        usize,
+	pub bits_per_sample: u32,
+	/// The interleaved samples.
+	pub samples:         Vec<i32>
+}
+
+impl Block {
+	/// Returns the number of samples per channel.
+	pub fn len(&self) -> usize {
+		self.samples.len() / self.channels.max(1)
+	}
+	
+	pub fn is_empty(&self) -> bool {
+		self.samples.is_empty()
+	}
+}
+
+pub struct FlacReader<R> {
+	reader:         io::BufReader<R>,
+	pub info:       StreamInfo,
+	pub seek_table: Vec<SeekPoint>,
+	pub comment:    Option<VorbisComment>,
+	/// All other metadata blocks except padding, e.g. pictures or cue sheets, as block type and data.
+	pub metadata:   Vec<(u8, Vec<u8>)>,
+	first_frame:    u64,
+	/// The first sample of the next frame.
+	next_sample:    u64,
+	block:          Block,
+	/// The index of the next interleaved sample in `block`.
+	index:          usize,
+	/// The next byte of the sample at `index`, for partial reads.
+	byte:           usize,
+	subframes:      Vec<Vec<i64>>
+}
+
+impl<R: Read + Seek> FlacReader<R> {
+	pub fn new(reader: R) -> io::Result<Self> {
+		let mut reader = io::BufReader::new(reader);
+		let mut magic = [0u8; 4];
+		reader.read_exact(&mut magic)?;
+		
+		// skip an ID3v2 tag, which some taggers put in front of the stream
+		if magic[..3] == *b"ID3" {
+			let mut header = [0u8; 6];
+			reader.read_exact(&mut header)?;
+			let size = header[2..].iter().fold(0i64, |size, b| size << 7 | (b & 0x7F) as i64);
+			reader.seek_relative(size + if header[1] & 0x10 != 0 { 10 } else { 0 })?;
+			reader.read_exact(&mut magic)?;
+		}
+		
+		if magic != MAGIC {
+			return Err(invalid_data("not a FLAC stream"));
+		}
+		
+		let mut info = None;
+		let mut seek_table = Vec::new();
+		let mut comment = None;
+		let mut metadata = Vec::new();
+		loop {
+			let mut header = [0u8; 4];
+			reader.read_exact(&mut header)?;
+			let ty = header[0] & 0x7F;
+			let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as u64;
+			
+			if info.is_none() && ty != BLOCK_STREAMINFO {
+				return Err(invalid_data("the first metadata block must be STREAMINFO"));
+			} else if ty == BLOCK_PADDING {
+				reader.seek_relative(len as i64)?;
+			} else {
+				let mut data = Vec::new();
+				(&mut reader).take(len).read_to_end(&mut data)?;
+				if data.len() as u64 != len {
+					return Err(ErrorKind::UnexpectedEof.into());
+				}
+				
+				match ty {
+					BLOCK_STREAMINFO if info.is_none() => info = Some(StreamInfo::parse(&data)?),
+					BLOCK_STREAMINFO     => return Err(invalid_data("more than one STREAMINFO block")),
+					BLOCK_SEEKTABLE      => seek_table = SeekPoint::parse_table(&data)?,
+					BLOCK_VORBIS_COMMENT => comment = Some(VorbisComment::parse(&data)?),
+					127                  => return Err(invalid_data("invalid metadata block type")),
+					_                    => metadata.push((ty, data))
+				}
+			}
+			
+			if header[0] & 0x80 != 0 {
+				break;
+			}
+		}
+		
+		let info = info.unwrap();
+		Ok(Self {
+			first_frame: reader.stream_position()?,
+			reader,
+			info,
+			seek_table,
+			comment,
+			metadata,
+			next_sample: 0,
+			block:       Block::default(),
+			index:       0,
+			byte:        0,
+			subframes:   vec![Vec::new(); info.channels as usize]
+		})
+	}
+	
+	/// Returns the number of the next sample per channel.
+	pub fn position(&self) -> u64 {
+		match self.block.is_empty() {
+			true  => self.block.first_sample,
+			false => self.block.first_sample + (self.index / self.block.channels) as u64
+		}
+	}
+	
+	/// Returns the size of the samples as returned by [`io::Read`], in bytes.
+	pub fn sample_width(&self) -> usize {
+		match self.info.bits_per_sample {
+			0..=8  => 1,
+			9..=16 => 2,
+			_      => 4
+		}
+	}
+	
+	/// Decodes the next frame, the samples of the current block that weren't read are skipped.
+	pub fn read_block(&mut self) -> io::Result<Option<Block>> {
+		self.index = 0;
+		self.byte = 0;
+		self.block.samples.clear();
+		self.block.first_sample = self.next_sample;
+		
+		// anything after the last sample, e.g. an ID3v1 tag, is ignored
+		let total = self.info.total_samples;
+		if self.reader.fill_buf()?.is_empty() || (total != 0 && self.next_sample >= total) {
+			return Ok(None);
+		}
+		
+		let mut bits = BitReader::new(&mut self.reader);
+		let header = FrameHeader::read(&mut bits, &self.info)?;
+		if header.channels.count() != self.info.channels as usize {
+			return Err(invalid_data("the number of channels does not match STREAMINFO"));
+		}
+		
+		for (i, subframe) in self.subframes.iter_mut().enumerate() {
+			let side = match header.channels {
+				Channels::LeftSide | Channels::MidSide => i == 1,
+				Channels::SideRight                    => i == 0,
+				Channels::Independent(_)               => false
+			};
+			read_subframe(&mut bits, header.bits_per_sample + side as u32, header.block_size, subframe)?;
+		}
+		
+		bits.align();
+		let crc = crc16(&bits.bytes);
+		if bits.read(16)? as u16 != crc {
+			return Err(invalid_data("frame CRC mismatch"));
+		}
+		
+		if let [a, b] = self.subframes.as_mut_slice() {
+			for (a, b) in a.iter_mut().zip(b.iter_mut()) {
+				(*a, *b) = match header.channels {
+					Channels::LeftSide       => (*a, *a - *b),
+					Channels::SideRight      => (*a + *b, *b),
+					Channels::MidSide        => {
+						let mid = *a << 1 | (*b & 1);
+						((mid + *b) >> 1, (mid - *b) >> 1)
+					}
+					Channels::Independent(_) => break
+				};
+			}
+		}
+		
+		let mut samples = Vec::with_capacity(header.block_size * self.subframes.len());
+		for i in 0..header.block_size {
+			samples.extend(self.subframes.iter().map(|s| s[i] as i32));
+		}
+		
+		self.next_sample = header.first_sample + header.block_size as u64;
+		Ok(Some(Block {
+			first_sample:    header.first_sample,
+			sample_rate:     header.sample_rate,
+			channels:        self.subframes.len(),
+			bits_per_sample: header.bits_per_sample,
+			samples
+		}))
+	}
+	
+	/// Fills the current block if all of its samples have been read, returns false at the end of the stream.
+	fn fill_block(&mut self) -> io::Result<bool> {
+		if self.index < self.block.samples.len() {
+			return Ok(true);
+		}
+		
+		match self.read_block()? {
+			Some(block) => {
+				self.block = block;
+				Ok(true)
+			}
+			None => Ok(false)
+		}
+	}
+	
+	/// Reads interleaved samples, returns the number of samples read, which is 0 at the end of the stream.
+	pub fn read_samples(&mut self, buf: &mut [i32]) -> io::Result<usize> {
+		if self.byte != 0 {
+			self.index += 1;
+			self.byte = 0;
+		}
+		
+		let mut len = 0;
+		while len < buf.len() && self.fill_block()? {
+			let n = (buf.len() - len).min(self.block.samples.len() - self.index);
+			buf[len..len + n].copy_from_slice(&self.block.samples[self.index..self.index + n]);
+			self.index += n;
+			len += n;
+		}
+		Ok(len)
+	}
+	
+	/// Seeks to a sample, such that the next sample read is the first sample of the frame at that position.
+	pub fn seek_sample(&mut self, sample: u64) -> io::Result<()> {
+		let total = self.info.total_samples;
+		if total != 0 && sample > total {
+			return Err(Error::new(ErrorKind::InvalidInput, "seek beyond the end of the stream"));
+		}
+		
+		let (offset, first_sample) = if sample >= self.block.first_sample && sample < self.next_sample && !self.block.is_empty() {
+			// within the current block
+			self.index = (sample - self.block.first_sample) as usize * self.block.channels;
+			self.byte = 0;
+			return Ok(());
+		} else if let Some(point) = self.seek_table.iter().rev().find(|p| p.sample != PLACEHOLDER && p.sample <= sample) {
+			(self.first_frame + point.offset, point.sample)
+		} else {
+			self.bisect(sample)?
+		};
+		
+		self.reader.seek(SeekFrom::Start(offset))?;
+		self.next_sample = first_sample;
+		while let Some(block) = self.read_block()? {
+			let end = block.first_sample + block.len() as u64;
+			if sample < block.first_sample {
+				return Err(invalid_data("seek point does not match the frame"));
+			} else if sample < end {
+				self.index = (sample - block.first_sample) as usize * block.channels;
+				self.block = block;
+				return Ok(());
+			}
+		}
+		
+		self.block.first_sample = sample;
+		Ok(())
+	}
+	
+	/// Returns the offset and first sample of a frame at or before the sample, found by bisecting the stream.
+	fn bisect(&mut self, sample: u64) -> io::Result<(u64, u64)> {
+		let scan_len = match self.info.max_frame_size {
+			0   => SCAN_LEN,
+			len => len as u64 + 16
+		};
+		
+		let end = self.reader.seek(SeekFrom::End(0))?;
+		let (mut lo, mut lo_sample, mut hi) = (self.first_frame, 0, end);
+		while hi - lo > scan_len {
+			let mid = lo + (hi - lo) / 2;
+			match self.find_frame(mid, scan_len)? {
+				Some((offset, first_sample)) if first_sample <= sample && offset < hi => {
+					lo = offset;
+					lo_sample = first_sample;
+				}
+				_ => hi = mid
+			}
+		}
+		Ok((lo, lo_sample))
+	}
+	
+	/// Scans for a valid frame header.
+	fn find_frame(&mut self, offset: u64, len: u64) -> io::Result<Option<(u64, u64)>> {
+		self.reader.seek(SeekFrom::Start(offset))?;
+		let mut data = Vec::new();
+		(&mut self.reader).take(len).read_to_end(&mut data)?;
+		
+		for i in 0..data.len().saturating_sub(1) {
+			if data[i] != 0xFF || data[i + 1] >> 1 != 0x7C {
+				continue;
+			}
+			
+			match FrameHeader::read(&mut BitReader::new(&data[i..]), &self.info) {
+				Ok(header) if self.info.total_samples == 0 || header.first_sample < self.info.total_samples =>
+					return Ok(Some((offset + i as u64, header.first_sample))),
+				_ => ()
+			}
+		}
+		Ok(None)
+	}
+	
+	/// Decodes the whole stream and compares the MD5 signature of the samples with the one in
+	/// STREAMINFO, the position is restored afterwards. Returns `None` if there is no signature.
+	pub fn verify(&mut self) -> io::Result<Option<bool>> {
+		if self.info.md5 == [0; 16] {
+			return Ok(None);
+		}
+		
+		let (position, channel, byte) = (self.position(), self.index % self.info.channels as usize, self.byte);
+		self.reader.seek(SeekFrom::Start(self.first_frame))?;
+		self.next_sample = 0;
+		
+		let width = (self.info.bits_per_sample as usize).div_ceil(8);
+		let mut md5 = Md5::new();
+		let mut buf = Vec::new();
+		while let Some(block) = self.read_block()? {
+			buf.clear();
+			block.samples.iter().for_each(|s| buf.extend_from_slice(&s.to_le_bytes()[..width]));
+			md5.update(&buf);
+		}
+		
+		self.block = Block::default();
+		self.seek_sample(position)?;
+		if !self.block.is_empty() {
+			self.index += channel;
+			self.byte = byte;
+		}
+		Ok(Some(md5.finish() == self.info.md5))
+	}
+}
+
+impl<R: Read + Seek> Read for FlacReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let width = self.sample_width();
+		let shift = width as u32 * 8 - self.info.bits_per_sample as u32;
+		let mut len = 0;
+		
+		while len < buf.len() && self.fill_block()? {
+			for &sample in &self.block.samples[self.index..] {
+				let bytes = (sample << shift).to_le_bytes();
+				let n = (width - self.byte).min(buf.len() - len);
+				buf[len..len + n].copy_from_slice(&bytes[self.byte..self.byte + n]);
+				len += n;
+				self.byte += n;
+				
+				if self.byte < width {
+					break;
+				}
+				
+				self.byte = 0;
+				self.index += 1;
+				if len == buf.len() {
+					break;
+				}
+			}
+		}
+		Ok(len)
+	}
+}
+
+impl<R: Read + Seek> Seek for FlacReader<R> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let frame = (self.sample_width() * self.info.channels as usize) as i128;
+		let current = self.position() as i128 * frame + (self.index % self.info.channels as usize * self.sample_width() + self.byte) as i128;
+		let target = match pos {
+			SeekFrom::Start(n)   => n as i128,
+			SeekFrom::Current(n) => current + n as i128,
+			SeekFrom::End(_) if self.info.total_samples == 0 =>
+				return Err(Error::new(ErrorKind::Unsupported, "the length of the stream is unknown")),
+			SeekFrom::End(n)     => self.info.total_samples as i128 * frame + n as i128
+		};
+		
+		if target < 0 {
+			return Err(Error::new(ErrorKind::InvalidInput, "seek before the start of the stream"));
+		}
+		
+		self.seek_sample((target / frame) as u64)?;
+		if !self.block.is_empty() {
+			let rest = (target % frame) as usize;
+			self.index += rest / self.sample_width();
+			self.byte = rest % self.sample_width();
+		}
+		Ok(target as u64)
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Channels {
+	Independent(u8),
+	LeftSide,
+	SideRight,
+	MidSide
+}
+
+impl Channels {
+	fn count(self) -> usize {
+		match self {
+			Self::Independent(n) => n as usize,
+			_ => 2
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct FrameHeader {
+	first_sample:    u64,
+	block_size:      usize,
+	sample_rate:     u32,
+	channels:        Channels,
+	bits_per_sample: u32
+}
+
+impl FrameHeader {
+	fn read<R: Read>(bits: &mut BitReader<R>, info: &StreamInfo) -> io::Result<Self> {
+		bits.bytes.clear();
+		if bits.read(15)? != 0x7FFC {
+			return Err(invalid_data("lost frame sync"));
+		}
+		
+		let variable = bits.read(1)? == 1;
+		let block_size = bits.read(4)?;
+		let sample_rate = bits.read(4)?;
+		let channels = match bits.read(4)? {
+			n @ 0..=7 => Channels::Independent(n as u8 + 1),
+			8         => Channels::LeftSide,
+			9         => Channels::SideRight,
+			10        => Channels::MidSide,
+			_         => return Err(invalid_data("reserved channel assignment"))
+		};
+		
+		let bits_per_sample = match bits.read(3)? {
+			0 => info.bits_per_sample as u32,
+			1 => 8,
+			2 => 12,
+			4 => 16,
+			5 => 20,
+			6 => 24,
+			7 => 32,
+			_ => return Err(invalid_data("reserved sample size"))
+		};
+		
+		if bits.read(1)? != 0 {
+			return Err(invalid_data("reserved bit is set in frame header"));
+		}
+		
+		// the frame or sample number is coded like UTF-8, with up to 36 bits
+		let first = bits.read(8)? as u8;
+		let (mut number, len) = match first.leading_ones() {
+			0       => (first as u64, 0),
+			n @ 2..=7 => ((first & (0x7F >> n)) as u64, n - 1),
+			_       => return Err(invalid_data("invalid coded number in frame header"))
+		};
+		
+		for _ in 0..len {
+			let b = bits.read(8)?;
+			if b & 0xC0 != 0x80 {
+				return Err(invalid_data("invalid coded number in frame header"));
+			}
+			number = number << 6 | (b & 0x3F);
+		}
+		
+		let block_size = match block_size {
+			0         => return Err(invalid_data("reserved block size")),
+			1         => 192,
+			n @ 2..=5 => 576 << (n - 2),
+			6         => bits.read(8)? as usize + 1,
+			7         => bits.read(16)? as usize + 1,
+			n         => 256 << (n - 8)
+		};
+		
+		let sample_rate = match sample_rate {
+			0  => info.sample_rate,
+			1  => 88200,
+			2  => 176400,
+			3  => 192000,
+			4  => 8000,
+			5  => 16000,
+			6  => 22050,
+			7  => 24000,
+			8  => 32000,
+			9  => 44100,
+			10 => 48000,
+			11 => 96000,
+			12 => bits.read(8)? as u32 * 1000,
+			13 => bits.read(16)? as u32,
+			14 => bits.read(16)? as u32 * 10,
+			_  => return Err(invalid_data("invalid sample rate"))
+		};
+		
+		let crc = crc8(&bits.bytes);
+		if bits.read(8)? as u8 != crc {
+			return Err(invalid_data("frame header CRC mismatch"));
+		}
+		
+		Ok(Self {
+			first_sample: match variable {
+				true  => number,
+				false => number * info.min_block_size as u64
+			},
+			block_size,
+			sample_rate,
+			channels,
+			bits_per_sample
+		})
+	}
+}
+
+fn read_subframe<R: Read>(bits: &mut BitReader<R>, bits_per_sample: u32, block_size: usize, out: &mut Vec<i64>) -> io::Result<()> {
+	out.clear();
+	if bits.read(1)? != 0 {
+		return Err(invalid_data("invalid subframe padding"));
+	}
+	
+	let ty = bits.read(6)?;
+	let wasted = match bits.read(1)? {
+		1 => bits.unary()? + 1,
+		_ => 0
+	};
+	
+	if wasted >= bits_per_sample {
+		return Err(invalid_data("invalid number of wasted bits"));
+	}
+	
+	let bps = bits_per_sample - wasted;
+	match ty {
+		0 => out.resize(block_size, bits.signed(bps)?),
+		1 => for _ in 0..block_size {
+			out.push(bits.signed(bps)?);
+		},
+		8..=12 => {
+			let order = ty as usize - 8;
+			read_warmup(bits, bps, order, block_size, out)?;
+			read_residual(bits, order, block_size, out)?;
+			for i in order..block_size {
+				out[i] += match order {
+					0 => 0,
+					1 => out[i - 1],
+					2 => 2 * out[i - 1] - out[i - 2],
+					3 => 3 * out[i - 1] - 3 * out[i - 2] + out[i - 3],
+					_ => 4 * out[i - 1] - 6 * out[i - 2] + 4 * out[i - 3] - out[i - 4]
+				};
+			}
+		}
+		32..=63 => {
+			let order = ty as usize - 31;
+			read_warmup(bits, bps, order, block_size, out)?;
+			let precision = bits.read(4)? as u32 + 1;
+			if precision == 16 {
+				return Err(invalid_data("invalid LPC coefficient precision"));
+			}
+			
+			let shift = bits.signed(5)?;
+			if shift < 0 {
+				return Err(invalid_data("negative LPC shift"));
+			}
+			
+			let coefs = (0..order).map(|_| bits.signed(precision)).collect::<io::Result<Vec<_>>>()?;
+			read_residual(bits, order, block_size, out)?;
+			for i in order..block_size {
+				let prediction = coefs.iter().enumerate()
+					.map(|(j, c)| c * out[i - 1 - j])
+					.sum::<i64>();
+				out[i] += prediction >> shift;
+			}
+		}
+		_ => return Err(invalid_data("reserved subframe type"))
+	}
+	
+	if wasted > 0 {
+		out.iter_mut().for_each(|s| *s <<= wasted);
+	}
+	Ok(())
+}
+
+fn read_warmup<R: Read>(bits: &mut BitReader<R>, bps: u32, order: usize, block_size: usize, out: &mut Vec<i64>) -> io::Result<()> {
+	if order > block_size {
+		return Err(invalid_data("predictor order is larger than the block size"));
+	}
+	
+	for _ in 0..order {
+		out.push(bits.signed(bps)?);
+	}
+	Ok(())
+}
+
+fn read_residual<R: Read>(bits: &mut BitReader<R>, order: usize, block_size: usize, out: &mut Vec<i64>) -> io::Result<()> {
+	let (param_bits, escape) = match bits.read(2)? {
+		0 => (4, 15),
+		1 => (5, 31),
+		_ => return Err(invalid_data("reserved residual coding method"))
+	};
+	
+	let partition_order = bits.read(4)? as u32;
+	let partition_len = block_size >> partition_order;
+	if partition_len << partition_order != block_size || partition_len < order {
+		return Err(invalid_data("invalid partition order"));
+	}
+	
+	for partition in 0..1 << partition_order {
+		let len = if partition == 0 { partition_len - order } else { partition_len };
+		let param = bits.read(param_bits)? as u32;
+		if param == escape {
+			let bps = bits.read(5)? as u32;
+			for _ in 0..len {
+				out.push(bits.signed(bps)?);
+			}
+		} else {
+			for _ in 0..len {
+				let v = (bits.unary()? as u64) << param | bits.read(param)?;
+				out.push((v >> 1) as i64 ^ -((v & 1) as i64));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Reads bits MSB first and records the bytes read for the CRCs.
+struct BitReader<R> {
+	reader: R,
+	acc:    u64,
+	bits:   u32,
+	bytes:  Vec<u8>
+}
+
+impl<R: Read> BitReader<R> {
+	fn new(reader: R) -> Self {
+		Self { reader, acc: 0, bits: 0, bytes: Vec::new() }
+	}
+	
+	fn byte(&mut self) -> io::Result<u64> {
+		let mut buf = [0u8];
+		self.reader.read_exact(&mut buf)?;
+		self.bytes.push(buf[0]);
+		Ok(buf[0] as u64)
+	}
+	
+	/// Reads up to 56 bits.
+	fn read(&mut self, n: u32) -> io::Result<u64> {
+		if n == 0 {
+			return Ok(0);
+		}
+		
+		while self.bits < n {
+			self.acc = self.acc << 8 | self.byte()?;
+			self.bits += 8;
+		}
+		
+		self.bits -= n;
+		Ok(self.acc >> self.bits & ((1 << n) - 1))
+	}
+	
+	fn signed(&mut self, n: u32) -> io::Result<i64> {
+		match n {
+			0 => Ok(0),
+			n => Ok(((self.read(n)? << (64 - n)) as i64) >> (64 - n))
+		}
+	}
+	
+	/// Counts the zeros before the next one.
+	fn unary(&mut self) -> io::Result<u32> {
+		let mut n = 0;
+		loop {
+			if self.bits == 0 {
+				self.acc = self.byte()?;
+				self.bits = 8;
+			}
+			
+			let rest = self.acc & ((1 << self.bits) - 1);
+			if rest == 0 {
+				n += self.bits;
+				self.bits = 0;
+			} else {
+				let high = 63 - rest.leading_zeros();
+				n += self.bits - 1 - high;
+				self.bits = high;
+				return Ok(n);
+			}
+		}
+	}
+	
+	fn align(&mut self) {
+		self.bits -= self.bits % 8;
+	}
+}
+
+/// The CRC-8 of frame headers, with the polynomial `0x07`.
+pub fn crc8(data: &[u8]) -> u8 {
+	const TABLE: [u8; 256] = {
+		let mut table = [0u8; 256];
+		let mut i = 0;
+		while i < 256 {
+			let mut c = i as u8;
+			let mut k = 0;
+			while k < 8 {
+				c = if c & 0x80 != 0 { c << 1 ^ 0x07 } else { c << 1 };
+				k += 1;
+			}
+			table[i] = c;
+			i += 1;
+		}
+		table
+	};
+	
+	data.iter().fold(0, |c, byte| TABLE[(c ^ byte) as usize])
+}
+
+/// The CRC-16 of frames, with the polynomial `0x8005`.
+pub fn crc16(data: &[u8]) -> u16 {
+	const TABLE: [u16; 256] = {
+		let mut table = [0u16; 256];
+		let mut i = 0;
+		while i < 256 {
+			let mut c = (i as u16) << 8;
+			let mut k = 0;
+			while k < 8 {
+				c = if c & 0x8000 != 0 { c << 1 ^ 0x8005 } else { c << 1 };
+				k += 1;
+			}
+			table[i] = c;
+			i += 1;
+		}
+		table
+	};
+	
+	data.iter().fold(0, |c, byte| TABLE[((c >> 8) as u8 ^ byte) as usize] ^ (c << 8))
+}
+
+/// MD5 (RFC 1321), only used to verify decoded samples.
+pub struct Md5 {
+	state: [u32; 4],
+	buf:   Vec<u8>,
+	len:   u64
+}
+
+impl Md5 {
+	const K: [u32; 64] = [
+		0xD76AA478, 0xE8C7B756, 0x242070DB, 0xC1BDCEEE, 0xF57C0FAF, 0x4787C62A, 0xA8304613, 0xFD469501,
+		0x698098D8, 0x8B44F7AF, 0xFFFF5BB1, 0x895CD7BE, 0x6B901122, 0xFD987193, 0xA679438E, 0x49B40821,
+		0xF61E2562, 0xC040B340, 0x265E5A51, 0xE9B6C7AA, 0xD62F105D, 0x02441453, 0xD8A1E681, 0xE7D3FBC8,
+		0x21E1CDE6, 0xC33707D6, 0xF4D50D87, 0x455A14ED, 0xA9E3E905, 0xFCEFA3F8, 0x676F02D9, 0x8D2A4C8A,
+		0xFFFA3942, 0x8771F681, 0x6D9D6122, 0xFDE5380C, 0xA4BEEA44, 0x4BDECFA9, 0xF6BB4B60, 0xBEBFBC70,
+		0x289B7EC6, 0xEAA127FA, 0xD4EF3085, 0x04881D05, 0xD9D4D039, 0xE6DB99E5, 0x1FA27CF8, 0xC4AC5665,
+		0xF4292244, 0x432AFF97, 0xAB9423A7, 0xFC93A039, 0x655B59C3, 0x8F0CCC92, 0xFFEFF47D, 0x85845DD1,
+		0x6FA87E4F, 0xFE2CE6E0, 0xA3014314, 0x4E0811A1, 0xF7537E82, 0xBD3AF235, 0x2AD7D2BB, 0xEB86D391
+	];
+	
+	const S: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+	
+	pub fn new() -> Self {
+		Self { state: [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476], buf: Vec::with_capacity(64), len: 0 }
+	}
+	
+	pub fn update(&mut self, mut data: &[u8]) {
+		self.len += data.len() as u64;
+		if !self.buf.is_empty() {
+			let n = (64 - self.buf.len()).min(data.len());
+			self.buf.extend_from_slice(&data[..n]);
+			data = &data[n..];
+			if self.buf.len() < 64 {
+				return;
+			}
+			
+			let block = std::mem::take(&mut self.buf);
+			self.block(&block);
+			self.buf = block;
+			self.buf.clear();
+		}
+		
+		let mut chunks = data.chunks_exact(64);
+		for chunk in &mut chunks {
+			self.block(chunk);
+		}
+		self.buf.extend_from_slice(chunks.remainder());
+	}
+	
+	pub fn finish(mut self) -> [u8; 16] {
+		let bits = self.len.wrapping_mul(8);
+		let padding = (119 - (self.len % 64) as usize) % 64 + 1;
+		let mut tail = vec![0u8; padding];
+		tail[0] = 0x80;
+		self.update(&tail);
+		self.update(&bits.to_le_bytes());
+		
+		let mut out = [0u8; 16];
+		for (out, v) in out.chunks_exact_mut(4).zip(self.state) {
+			out.copy_from_slice(&v.to_le_bytes());
+		}
+		out
+	}
+	
+	fn block(&mut self, block: &[u8]) {
+		let m = (0..16)
+			.map(|i| u32::from_le_bytes(block[i * 4..i * 4 + 4].try_into().unwrap()))
+			.collect::<Vec<_>>();
+		let [mut a, mut b, mut c, mut d] = self.state;
+		
+		for i in 0..64 {
+			let (f, g) = match i / 16 {
+				0 => ((b & c) | (!b & d), i),
+				1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+				2 => (b ^ c ^ d, (3 * i + 5) % 16),
+				_ => (c ^ (b | !d), (7 * i) % 16)
+			};
+			
+			let f = f.wrapping_add(a).wrapping_add(Self::K[i]).wrapping_add(m[g]);
+			a = d;
+			d = c;
+			c = b;
+			b = b.wrapping_add(f.rotate_left(Self::S[i / 16 * 4 + i % 4]));
+		}
+		
+		for (s, v) in self.state.iter_mut().zip([a, b, c, d]) {
+			*s = s.wrapping_add(v);
+		}
+	}
+}
+
+impl Default for Md5 {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[derive(Default)]
+	struct BitWriter {
+		bytes: Vec<u8>,
+		len:   usize
+	}
+	
+	impl BitWriter {
+		fn write(&mut self, n: u32, v: u64) {
+			for i in (0..n).rev() {
+				if self.len.is_multiple_of(8) {
+					self.bytes.push(0);
+				}
+				
+				self.bytes[self.len / 8] |= ((v >> i & 1) as u8) << (7 - self.len % 8);
+				self.len += 1;
+			}
+		}
+		
+		fn signed(&mut self, n: u32, v: i64) {
+			self.write(n, v as u64 & (u64::MAX >> (64 - n)));
+		}
+		
+		fn rice(&mut self, param: u32, v: i64) {
+			let v = (v << 1 ^ v >> 63) as u64;
+			for _ in 0..v >> param {
+				self.write(1, 0);
+			}
+			self.write(1, 1);
+			self.write(param, v & ((1 << param) - 1));
+		}
+		
+		fn align(&mut self) {
+			self.len = self.len.div_ceil(8) * 8;
+		}
+	}
+	
+	/// Encodes the subframe kind `kind % 8`: verbatim, fixed of order 0 to 4, or LPC of order 2 or 4.
+	/// Constant channels are always encoded as constant subframes and wasted bits are detected.
+	fn subframe(w: &mut BitWriter, bps: u32, samples: &[i64], kind: usize, escape: bool) {
+		let wasted = samples.iter().fold(0i64, |acc, s| acc | s).trailing_zeros().min(bps - 1);
+		let samples = samples.iter().map(|s| s >> wasted).collect::<Vec<_>>();
+		let bps = bps - wasted;
+		let constant = samples.iter().all(|s| *s == samples[0]);
+		// LPC coefficients, precision and shift
+		type Lpc = Option<(&'static [i64], u32, i64)>;
+		let (ty, order, lpc): (u64, usize, Lpc) = match kind % 8 {
+			_ if constant => (0, 0, None),
+			0 => (1, 0, None),
+			n @ 1..=5 => (8 + n as u64 - 1, n - 1, None),
+			6 => (33, 2, Some((&[2, -1], 3, 0))),
+			_ => (35, 4, Some((&[5, -2, 1, -1], 4, 2)))
+		};
+		
+		w.write(1, 0);
+		w.write(6, ty);
+		match wasted {
+			0 => w.write(1, 0),
+			n => {
+				w.write(1, 1);
+				w.write(n, 1);
+			}
+		}
+		
+		match ty {
+			0 => return w.signed(bps, samples[0]),
+			1 => return samples.iter().for_each(|s| w.signed(bps, *s)),
+			_ => samples[..order].iter().for_each(|s| w.signed(bps, *s))
+		}
+		
+		let residual = (order..samples.len()).map(|i| samples[i] - match lpc {
+			Some((coefs, _, shift)) => coefs.iter().enumerate().map(|(j, c)| c * samples[i - 1 - j]).sum::<i64>() >> shift,
+			None => match order {
+				0 => 0,
+				1 => samples[i - 1],
+				2 => 2 * samples[i - 1] - samples[i - 2],
+				3 => 3 * samples[i - 1] - 3 * samples[i - 2] + samples[i - 3],
+				_ => 4 * samples[i - 1] - 6 * samples[i - 2] + 4 * samples[i - 3] - samples[i - 4]
+			}
+		}).collect::<Vec<_>>();
+		
+		if let Some((coefs, precision, shift)) = lpc {
+			w.write(4, precision as u64 - 1);
+			w.signed(5, shift);
+			coefs.iter().for_each(|c| w.signed(precision, *c));
+		}
+		
+		let partition_order = (0..=2u32).rev()
+			.find(|p| samples.len() % (1 << p) == 0 && samples.len() >> p >= order)
+			.unwrap();
+		let partitions = {
+			let len = samples.len() >> partition_order;
+			let mut partitions = vec![&residual[..len - order]];
+			partitions.extend(residual[len - order..].chunks(len));
+			partitions
+		};
+		
+		let params = partitions.iter().map(|p| {
+			let mean = p.iter().map(|v| (v << 1 ^ v >> 63) as u64).sum::<u64>() / p.len().max(1) as u64;
+			64 - mean.leading_zeros().min(63) - 1
+		}).collect::<Vec<_>>();
+		let (param_bits, max) = if params.iter().any(|p| *p >= 15) { (5, 30) } else { (4, 14) };
+		
+		w.write(2, (param_bits == 5) as u64);
+		w.write(4, partition_order as u64);
+		for (i, (partition, param)) in partitions.iter().zip(params).enumerate() {
+			if escape && i == partitions.len() - 1 {
+				w.write(param_bits, (1 << param_bits) - 1);
+				w.write(5, bps as u64 + 2);
+				partition.iter().for_each(|v| w.signed(bps + 2, *v));
+			} else {
+				let param = param.min(max);
+				w.write(param_bits, param as u64);
+				partition.iter().for_each(|v| w.rice(param, *v));
+			}
+		}
+	}
+	
+	/// Encodes a frame, stereo frames cycle through all channel assignments.
+	fn frame(number: u64, index: usize, info: &StreamInfo, samples: &[i32]) -> Vec<u8> {
+		let channels = info.channels as usize;
+		let len = samples.len() / channels;
+		let bps = info.bits_per_sample as u32;
+		let mut w = BitWriter::default();
+		
+		w.write(15, 0x7FFC);
+		w.write(1, (info.min_block_size != info.max_block_size) as u64);
+		let block_size = match len {
+			4096      => 12,
+			..=256    => 6,
+			_         => 7
+		};
+		w.write(4, block_size);
+		w.write(4, if info.sample_rate == 44100 { 9 } else { 0 });
+		
+		let channel = |c: usize| samples.iter().skip(c).step_by(channels).map(|s| *s as i64).collect::<Vec<_>>();
+		let assignment = if channels == 2 { index % 4 } else { 0 };
+		let subframes = match assignment {
+			1 => vec![(channel(0), 0), (channel(0).iter().zip(channel(1)).map(|(l, r)| l - r).collect(), 1)],
+			2 => vec![(channel(0).iter().zip(channel(1)).map(|(l, r)| l - r).collect(), 1), (channel(1), 0)],
+			3 => vec![
+				(channel(0).iter().zip(channel(1)).map(|(l, r)| (l + r) >> 1).collect(), 0),
+				(channel(0).iter().zip(channel(1)).map(|(l, r)| l - r).collect(), 1)
+			],
+			_ => (0..channels).map(|c| (channel(c), 0)).collect()
+		};
+		
+		w.write(4, match assignment {
+			0 => channels as u64 - 1,
+			n => 7 + n as u64
+		});
+		w.write(3, 0);
+		w.write(1, 0);
+		
+		match number {
+			0..=0x7F => w.write(8, number),
+			_ => {
+				let n = (1..=6u32).find(|n| number < 1 << (5 * n + 6)).unwrap();
+				w.write(8, (0xFF00u64 >> (n + 1) & 0xFF) | number >> (6 * n));
+				(0..n).rev().for_each(|k| w.write(8, 0x80 | (number >> (6 * k) & 0x3F)));
+			}
+		}
+		
+		match block_size {
+			6 => w.write(8, len as u64 - 1),
+			7 => w.write(16, len as u64 - 1),
+			_ => ()
+		}
+		w.write(8, crc8(&w.bytes) as u64);
+		
+		for (c, (samples, side)) in subframes.iter().enumerate() {
+			subframe(&mut w, bps + side, samples, index + c * 3, index % 3 == 1);
+		}
+		
+		w.align();
+		w.write(16, crc16(&w.bytes) as u64);
+		w.bytes
+	}
+	
+	/// Encodes a stream with the given block sizes, all blocks but the last one must have the same size
+	/// for fixed block size streams.
+	fn encode(info: &mut StreamInfo, block_sizes: &[usize], seek_table: bool, samples: &[i32]) -> Vec<u8> {
+		let channels = info.channels as usize;
+		let width = (info.bits_per_sample as usize).div_ceil(8);
+		let mut md5 = Md5::new();
+		samples.iter().for_each(|s| md5.update(&s.to_le_bytes()[..width]));
+		info.md5 = md5.finish();
+		info.total_samples = (samples.len() / channels) as u64;
+		
+		let mut frames = Vec::new();
+		let mut points = Vec::new();
+		let mut sample = 0;
+		for (i, &len) in block_sizes.iter().enumerate() {
+			if i % 2 == 0 {
+				points.push(SeekPoint { sample: sample as u64, offset: frames.len() as u64, samples: len as u16 });
+			}
+			
+			let number = if info.min_block_size == info.max_block_size { i as u64 } else { sample as u64 };
+			let frame = frame(number, i, info, &samples[sample * channels..(sample + len) * channels]);
+			info.max_frame_size = info.max_frame_size.max(frame.len() as u32);
+			frames.extend(frame);
+			sample += len;
+		}
+		assert_eq!(sample * channels, samples.len());
+		points.push(SeekPoint { sample: PLACEHOLDER, offset: 0, samples: 0 });
+		
+		let mut w = BitWriter::default();
+		w.write(16, info.min_block_size as u64);
+		w.write(16, info.max_block_size as u64);
+		w.write(24, info.min_frame_size as u64);
+		w.write(24, info.max_frame_size as u64);
+		w.write(20, info.sample_rate as u64);
+		w.write(3, info.channels as u64 - 1);
+		w.write(5, info.bits_per_sample as u64 - 1);
+		w.write(36, info.total_samples);
+		w.bytes.extend_from_slice(&info.md5);
+		
+		let mut comment = Vec::new();
+		for s in ["test", "2", "TITLE=Foo", "artist=Bar"] {
+			if s == "2" {
+				comment.extend_from_slice(&2u32.to_le_bytes());
+				continue;
+			}
+			comment.extend_from_slice(&(s.len() as u32).to_le_bytes());
+			comment.extend_from_slice(s.as_bytes());
+		}
+		
+		let mut blocks = vec![(BLOCK_STREAMINFO, w.bytes), (BLOCK_PADDING, vec![0; 10]), (BLOCK_VORBIS_COMMENT, comment)];
+		if seek_table {
+			blocks.push((BLOCK_SEEKTABLE, points.iter().flat_map(|p| {
+				let mut point = p.sample.to_be_bytes().to_vec();
+				point.extend_from_slice(&p.offset.to_be_bytes());
+				point.extend_from_slice(&p.samples.to_be_bytes());
+				point
+			}).collect()));
+		}
+		blocks.push((BLOCK_APPLICATION, b"abcd".to_vec()));
+		
+		let mut out = MAGIC.to_vec();
+		let count = blocks.len();
+		for (i, (ty, data)) in blocks.into_iter().enumerate() {
+			out.push(ty | if i + 1 == count { 0x80 } else { 0 });
+			out.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+			out.extend(data);
+		}
+		out.extend(frames);
+		out
+	}
+	
+	/// Generates a noisy sine wave for each channel.
+	fn signal(len: usize, channels: usize, bps: u32) -> Vec<i32> {
+		let amplitude = (1i64 << (bps - 2)) as f64;
+		let mut seed = 12345u32;
+		(0..len * channels).map(|i| {
+			seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+			let noise = (seed >> 16) as i64 % (1 << (bps / 2)) - (1 << (bps / 2 - 1));
+			let (t, c) = (i / channels, i % channels);
+			match c {
+				2 => 3,
+				_ => (amplitude * (t as f64 * 0.01 * (c + 1) as f64).sin()) as i32 + noise as i32
+			}
+		}).collect()
+	}
+	
+	fn stream_info(block_size: (u16, u16), sample_rate: u32, channels: u8, bits_per_sample: u8) -> StreamInfo {
+		StreamInfo {
+			min_block_size: block_size.0,
+			max_block_size: block_size.1,
+			sample_rate,
+			channels,
+			bits_per_sample,
+			..StreamInfo::default()
+		}
+	}
+	
+	fn decode_all<R: Read + Seek>(reader: &mut FlacReader<R>) -> io::Result<Vec<i32>> {
+		let mut samples = Vec::new();
+		while let Some(block) = reader.read_block()? {
+			samples.extend(block.samples);
+		}
+		Ok(samples)
+	}
+	
+	#[test]
+	fn checksums() {
+		assert_eq!(crc8(b"123456789"), 0xF4);
+		assert_eq!(crc16(b"123456789"), 0xFEE8);
+		
+		let hex = |data: &[u8]| {
+			let mut md5 = Md5::new();
+			data.chunks(7).for_each(|chunk| md5.update(chunk));
+			md5.finish().iter().map(|b| format!("{:02x}", b)).collect::<String>()
+		};
+		assert_eq!(hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+		assert_eq!(hex(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+		assert_eq!(hex(b"The quick brown fox jumps over the lazy dog"), "9e107d9d372bb6826bd81d3542a419d6");
+		assert_eq!(hex(&[b'a'; 1000]), "cabe45dcc9ae5b66ba86600cca6b8ba8");
+	}
+	
+	#[test]
+	fn decode() {
+		// 16 bit stereo, fixed block size, all channel assignments
+		let samples = signal(10000, 2, 16);
+		let mut info = stream_info((1024, 1024), 44100, 2, 16);
+		let data = encode(&mut info, &[1024, 1024, 1024, 1024, 1024, 1024, 1024, 1024, 1024, 784], true, &samples);
+		let mut reader = FlacReader::new(io::Cursor::new(data)).unwrap();
+		assert_eq!(reader.info, info);
+		assert_eq!(reader.seek_table.len(), 6);
+		assert_eq!(reader.comment.as_ref().unwrap().vendor, "test");
+		assert_eq!(reader.comment.as_ref().unwrap().get("title").collect::<Vec<_>>(), ["Foo"]);
+		assert_eq!(reader.metadata, [(BLOCK_APPLICATION, b"abcd".to_vec())]);
+		assert_eq!(reader.sample_width(), 2);
+		assert_eq!(decode_all(&mut reader).unwrap(), samples);
+		assert_eq!(reader.verify().unwrap(), Some(true));
+		
+		// 24 bit mono, variable block size
+		let samples = signal(3000, 1, 24);
+		let mut info = stream_info((100, 1000), 96000, 1, 24);
+		let data = encode(&mut info, &[1000, 100, 900, 555, 445], false, &samples);
+		let mut reader = FlacReader::new(io::Cursor::new(data)).unwrap();
+		let mut buf = vec![0; 4000];
+		assert_eq!(reader.read_samples(&mut buf).unwrap(), 3000);
+		assert_eq!(&buf[..3000], samples.as_slice());
+		assert_eq!(reader.read_samples(&mut buf).unwrap(), 0);
+		assert_eq!(reader.verify().unwrap(), Some(true));
+		
+		// 8 bit, 3 channels with a constant channel and wasted bits
+		let samples = signal(600, 3, 8).into_iter().enumerate()
+			.map(|(i, s)| if i % 3 == 1 { s & !3 } else { s })
+			.collect::<Vec<_>>();
+		let mut info = stream_info((256, 256), 8000, 3, 8);
+		let data = encode(&mut info, &[256, 256, 88], false, &samples);
+		let mut reader = FlacReader::new(io::Cursor::new(data)).unwrap();
+		assert_eq!(decode_all(&mut reader).unwrap(), samples);
+		
+		// io::Read returns the samples as 8 bit
+		reader.seek(SeekFrom::Start(0)).unwrap();
+		let mut bytes = Vec::new();
+		reader.read_to_end(&mut bytes).unwrap();
+		assert_eq!(bytes, samples.iter().map(|s| *s as u8).collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn errors() {
+		let samples = signal(2048, 2, 16);
+		let mut info = stream_info((1024, 1024), 48000, 2, 16);
+		let data = encode(&mut info, &[1024, 1024], false, &samples);
+		
+		let mut corrupt = data.clone();
+		let len = corrupt.len();
+		corrupt[len - 100] ^= 0x10;
+		let mut reader = FlacReader::new(io::Cursor::new(corrupt)).unwrap();
+		assert!(reader.read_block().unwrap().is_some());
+		assert_eq!(reader.read_block().unwrap_err().kind(), ErrorKind::InvalidData);
+		
+		let mut corrupt = data.clone();
+		corrupt[8] = 0;
+		assert!(FlacReader::new(io::Cursor::new(corrupt)).is_err());
+		assert!(FlacReader::new(io::Cursor::new(&data[1..])).is_err());
+		
+		// the MD5 signature does not match
+		let mut corrupt = data;
+		corrupt[30] ^= 1;
+		assert_eq!(FlacReader::new(io::Cursor::new(corrupt)).unwrap().verify().unwrap(), Some(false));
+	}
+	
+	#[test]
+	fn seek() {
+		let samples = signal(20000, 2, 16);
+		for (block_sizes, seek_table) in [(vec![1000; 20], true), (vec![1000; 20], false)] {
+			let mut info = stream_info((1000, 1000), 44100, 2, 16);
+			let data = encode(&mut info, &block_sizes, seek_table, &samples);
+			let mut reader = FlacReader::new(io::Cursor::new(data)).unwrap();
+			
+			for sample in [0, 1, 999, 1000, 1001, 12345, 5000, 19999, 42] {
+				reader.seek_sample(sample).unwrap();
+				assert_eq!(reader.position(), sample);
+				let mut buf = [0; 6];
+				let n = reader.read_samples(&mut buf).unwrap();
+				let start = sample as usize * 2;
+				assert_eq!(&buf[..n], &samples[start..(start + 6).min(samples.len())]);
+			}
+			
+			reader.seek_sample(20000).unwrap();
+			assert_eq!(reader.read_samples(&mut [0; 4]).unwrap(), 0);
+			assert!(reader.seek_sample(20001).is_err());
+			
+			// byte offsets within samples
+			let bytes = samples.iter().flat_map(|s| (*s as i16).to_le_bytes()).collect::<Vec<_>>();
+			for offset in [0, 3, 4001, 7777, 79999] {
+				assert_eq!(reader.seek(SeekFrom::Start(offset)).unwrap(), offset);
+				let mut buf = [0; 7];
+				let n = reader.read(&mut buf).unwrap();
+				assert_eq!(&buf[..n], &bytes[offset as usize..(offset as usize + n)]);
+				assert!(n > 0);
+			}
+			
+			reader.seek(SeekFrom::Start(100)).unwrap();
+			assert_eq!(reader.seek(SeekFrom::Current(-1)).unwrap(), 99);
+			assert_eq!(reader.seek(SeekFrom::End(-2)).unwrap(), 79998);
+			assert_eq!(reader.verify().unwrap(), Some(true));
+			assert_eq!(reader.stream_position().unwrap(), 79998);
+		}
+	}
+}
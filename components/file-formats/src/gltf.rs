@@ -21,19 +21,27 @@
 // SOFTWARE.
 
 use {
-	crate::utils::DeError,
-	std::{io, collections::HashMap},
+	crate::utils::{DeError, SerError},
+	std::{io, path::Path, collections::HashMap, convert::TryFrom},
 	serde::*
 };
 
 pub const BINARY_GLTF_MAGIC: [u8; 4] = *b"glTF";
+pub const BINARY_GLTF_VERSION: u32   = 2;
 pub const CHUNK_TYPE_JSON:   [u8; 4] = *b"JSON";
 pub const CHUNK_TYPE_BIN:    [u8; 4] = *b"BIN\0";
 
+pub const TARGET_ARRAY_BUFFER:         OpenGlBufferTarget = 34962;
+pub const TARGET_ELEMENT_ARRAY_BUFFER: OpenGlBufferTarget = 34963;
+
 pub type OpenGlBufferTarget  = usize;
 pub type OpenGlComponentType = usize;
 pub type OpenGlConstant      = usize;
 
+fn invalid_data(msg: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
 pub fn read(mut reader: impl io::Read) -> Result<Document, DeError> {
 	let mut buf = [0u8; 4];
 	reader.read_exact(&mut buf).map_err(DeError::Io)?;
@@ -42,37 +50,35 @@ pub fn read(mut reader: impl io::Read) -> Result<Document, DeError> {
 		[b'{', ..] | [0xEF, 0xBB, 0xBF, ..] => {
 			let mut buf = buf.to_vec();
 			reader.read_to_end(&mut buf).map_err(DeError::Io)?;
-			crate::json::deserialize(&mut buf.as_slice())
+			crate::json::deserialize(buf.as_slice())
 		}
 		BINARY_GLTF_MAGIC => {
 			let mut buf = [0u8; 8];
 			reader.read_exact(&mut buf).map_err(DeError::Io)?;
 			
 			let version    = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
-			let mut length = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]) - 12;
+			let mut length = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]])
+				.checked_sub(12)
+				.ok_or_else(|| invalid_data("invalid file length"))?;
 			let mut chunks = Vec::new();
 			let mut doc    = Option::<Document>::None;
 			
 			while length > 0 {
 				reader.read_exact(&mut buf).map_err(DeError::Io)?;
 				let chunk_length = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
-				length -= chunk_length;
+				length = length.checked_sub(chunk_length + 8)
+					.ok_or_else(|| invalid_data("chunk exceeds the file length"))?;
+				
+				let mut data = vec![0u8; chunk_length as usize];
+				reader.read_exact(&mut data).map_err(DeError::Io)?;
 				
 				match [buf[4], buf[5], buf[6], buf[7]] {
-					CHUNK_TYPE_JSON => {
-						let mut buf = vec![0u8; chunk_length as usize];
-						reader.read_exact(&mut buf).map_err(DeError::Io)?;
-						
-						if doc.is_none() {
-							doc = Some(crate::json::deserialize(&mut buf.as_slice())?);
-						}
-					},
-					CHUNK_TYPE_BIN => {
-						let mut buf = vec![0u8; chunk_length as usize];
-						reader.read_exact(&mut buf).map_err(DeError::Io)?;
-						chunks.push(buf);
-					}
-					_ => return Err(DeError::Io(io::Error::new(io::ErrorKind::InvalidData, "invalid chunk type")))
+					CHUNK_TYPE_JSON if doc.is_none() => doc = Some(crate::json::deserialize(data.as_slice())?),
+					CHUNK_TYPE_JSON => return Err(DeError::Io(invalid_data("multiple JSON chunks present"))),
+					CHUNK_TYPE_BIN if doc.is_some() => chunks.push(data),
+					CHUNK_TYPE_BIN => return Err(DeError::Io(invalid_data("the first chunk must be a JSON chunk"))),
+					// unknown chunks must be ignored
+					_ => ()
 				}
 			}
 			
@@ -82,19 +88,43 @@ pub fn read(mut reader: impl io::Read) -> Result<Document, DeError> {
 					doc.binary_buffers = chunks;
 					Ok(doc)
 				}
-				None => Err(DeError::Io(io::Error::new(io::ErrorKind::InvalidData, "no JSON chunk present")))
+				None => Err(DeError::Io(invalid_data("no JSON chunk present")))
 			}
 		}
-		_ => Err(DeError::Io(io::Error::new(io::ErrorKind::InvalidData, "invalid file")))
+		_ => Err(DeError::Io(invalid_data("invalid file")))
 	}
 }
 
-pub fn write(writer: impl io::Write, doc: &Document) -> Result<(), crate::utils::SerError> {
-	if doc.binary_buffers.is_empty() {
-		crate::json::serialize(writer, doc)
-	} else {
-		unimplemented!()
+/// Writes the document as glTF JSON, or as binary glTF if there are binary buffers. The
+/// binary chunk is referenced by the first buffer, which must not have an URI.
+pub fn write(mut writer: impl io::Write, doc: &Document) -> Result<(), SerError> {
+	let bin = match doc.binary_buffers.as_slice() {
+		[] => return crate::json::serialize(writer, doc),
+		[bin] => bin,
+		_ => return Err(SerError::Custom("binary glTF files can only contain a single binary buffer".to_string()))
+	};
+	
+	if doc.buffers.first().is_none_or(|buffer| buffer.uri.is_some()) {
+		return Err(SerError::Custom("the first buffer must reference the binary chunk".to_string()));
 	}
+	
+	let mut json = Vec::new();
+	crate::json::serialize(&mut json, doc)?;
+	json.resize((json.len() + 3) & !3, b' ');
+	let bin_len = (bin.len() + 3) & !3;
+	let length = 12 + 8 + json.len() + 8 + bin_len;
+	
+	writer.write_all(&BINARY_GLTF_MAGIC)?;
+	writer.write_all(&BINARY_GLTF_VERSION.to_le_bytes())?;
+	writer.write_all(&(length as u32).to_le_bytes())?;
+	writer.write_all(&(json.len() as u32).to_le_bytes())?;
+	writer.write_all(&CHUNK_TYPE_JSON)?;
+	writer.write_all(&json)?;
+	writer.write_all(&(bin_len as u32).to_le_bytes())?;
+	writer.write_all(&CHUNK_TYPE_BIN)?;
+	writer.write_all(bin)?;
+	writer.write_all(&[0u8; 3][..bin_len - bin.len()])?;
+	Ok(())
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -104,70 +134,300 @@ pub struct Document {
 	pub version:             Option<u32>,
 	#[serde(skip)]
 	pub binary_buffers:      Vec<Vec<u8>>,
-	pub scene:               usize,
 	#[serde(default)]
+	pub asset:               Asset,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub scene:               Option<usize>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub scenes:              Vec<Scene>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub nodes:               Vec<Node>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub meshes:              Vec<Mesh>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub materials:           Vec<Material>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub buffers:             Vec<Buffer>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub buffer_views:        Vec<BufferView>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub accessors:           Vec<Accessor>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub cameras:             Vec<Camera>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub images:              Vec<Image>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub textures:            Vec<Texture>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub samplers:            Vec<Sampler>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub skins:               Vec<Skin>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub animations:          Vec<Animation>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub extensions_used:     Vec<String>,
-	#[serde(default)]
-	pub extensions_required: Vec<String>
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub extensions_required: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extensions:          Option<DocumentExtensions>
+}
+
+impl Document {
+	/// Loads the data of all buffers. Buffers without an URI reference the binary chunk,
+	/// data URIs are decoded and all other URIs are passed to `resolve`.
+	pub fn load_buffers(&self, mut resolve: impl FnMut(&str) -> io::Result<Vec<u8>>) -> Result<Vec<Vec<u8>>, DeError> {
+		let mut binary_buffers = self.binary_buffers.iter();
+		self.buffers.iter().map(|buffer| {
+			let data = match &buffer.uri {
+				None => binary_buffers.next()
+					.cloned()
+					.ok_or_else(|| invalid_data("buffer references a missing binary chunk"))?,
+				Some(uri) if uri.starts_with("data:") => decode_data_uri(uri)?,
+				Some(uri) => resolve(uri)?
+			};
+			
+			match data.len() >= buffer.byte_length {
+				true  => Ok(data),
+				false => Err(DeError::Io(invalid_data("buffer is smaller than its byte length")))
+			}
+		}).collect()
+	}
+	
+	/// Loads the data of all buffers, external files are resolved relative to `base`.
+	pub fn load_buffers_from(&self, base: impl AsRef<Path>) -> Result<Vec<Vec<u8>>, DeError> {
+		self.load_buffers(|uri| std::fs::read(base.as_ref().join(percent_decode(uri)?)))
+	}
+	
+	/// Returns a reader for the accessor with the given index.
+	pub fn accessor<'a>(&'a self, index: usize, buffers: &'a [Vec<u8>]) -> Result<AccessorReader<'a>, DeError> {
+		let accessor = self.accessors.get(index).ok_or_else(|| invalid_data("accessor index out of bounds"))?;
+		AccessorReader::new(self, accessor, buffers)
+	}
+	
+	/// Appends data to the binary buffer and returns the index of the new buffer view. The
+	/// binary buffer and the buffer referencing it are created if necessary.
+	pub fn push_buffer_view(&mut self, data: &[u8], byte_stride: Option<usize>, target: Option<OpenGlBufferTarget>) -> usize {
+		if self.binary_buffers.is_empty() {
+			self.binary_buffers.push(Vec::new());
+			self.buffers.insert(0, Buffer::default());
+			self.buffer_views.iter_mut().for_each(|view| view.buffer += 1);
+		}
+		
+		let bin = &mut self.binary_buffers[0];
+		bin.resize((bin.len() + 3) & !3, 0);
+		let byte_offset = bin.len();
+		bin.extend_from_slice(data);
+		self.buffers[0].byte_length = bin.len();
+		
+		self.buffer_views.push(BufferView {
+			buffer:      0,
+			byte_offset,
+			byte_length: data.len(),
+			byte_stride,
+			target,
+			name:        None
+		});
+		self.buffer_views.len() - 1
+	}
+	
+	/// Appends tightly packed elements to the binary buffer and returns the index of the new
+	/// accessor, `min` and `max` are computed from the data.
+	pub fn push_accessor(
+		&mut self,
+		data:           &[u8],
+		r#type:         AccessorType,
+		component_type: ComponentType,
+		normalized:     bool,
+		target:         Option<OpenGlBufferTarget>
+	) -> Result<usize, SerError> {
+		let element_size = r#type.element_size(component_type);
+		if !data.len().is_multiple_of(element_size) {
+			return Err(SerError::Custom("data length is not a multiple of the element size".to_string()));
+		}
+		
+		let buffer_view = self.push_buffer_view(data, None, target);
+		let mut accessor = Accessor {
+			buffer_view:    Some(buffer_view),
+			r#type,
+			component_type: component_type.to_gl(),
+			normalized,
+			count:          data.len() / element_size,
+			..Accessor::default()
+		};
+		
+		let values = AccessorReader::new(self, &accessor, &self.binary_buffers)
+			.expect("accessor must be valid")
+			.read_f32();
+		let components = r#type.components();
+		if !values.is_empty() {
+			accessor.min = (0..components).map(|i| values.iter().skip(i).step_by(components).fold(f64::INFINITY, |a, v| a.min(*v as _))).collect();
+			accessor.max = (0..components).map(|i| values.iter().skip(i).step_by(components).fold(f64::NEG_INFINITY, |a, v| a.max(*v as _))).collect();
+		}
+		
+		self.accessors.push(accessor);
+		Ok(self.accessors.len() - 1)
+	}
+}
+
+fn decode_data_uri(uri: &str) -> io::Result<Vec<u8>> {
+	let (header, data) = uri.split_once(',').ok_or_else(|| invalid_data("invalid data URI"))?;
+	match header.ends_with(";base64") {
+		true  => base64_decode(data).ok_or_else(|| invalid_data("invalid base64 data")),
+		false => Err(invalid_data("only base64 data URIs are supported"))
+	}
+}
+
+fn percent_decode(uri: &str) -> io::Result<String> {
+	let mut bytes = Vec::with_capacity(uri.len());
+	let mut iter = uri.bytes();
+	
+	while let Some(ch) = iter.next() {
+		match ch {
+			b'%' => {
+				let hex = [iter.next().unwrap_or(0), iter.next().unwrap_or(0)];
+				let v = std::str::from_utf8(&hex).ok()
+					.and_then(|s| u8::from_str_radix(s, 16).ok())
+					.ok_or_else(|| invalid_data("invalid percent encoding"))?;
+				bytes.push(v);
+			}
+			ch => bytes.push(ch)
+		}
+	}
+	
+	String::from_utf8(bytes).map_err(|_| invalid_data("URI is not valid UTF-8"))
+}
+
+fn base64_decode(data: &str) -> Option<Vec<u8>> {
+	fn value(ch: u8) -> Option<u32> {
+		Some(match ch {
+			b'A'..=b'Z' => ch - b'A',
+			b'a'..=b'z' => ch - b'a' + 26,
+			b'0'..=b'9' => ch - b'0' + 52,
+			b'+' | b'-' => 62,
+			b'/' | b'_' => 63,
+			_ => return None
+		} as u32)
+	}
+	
+	let data = data.trim_end_matches('=').as_bytes();
+	let mut out = Vec::with_capacity(data.len() * 3 / 4);
+	
+	for chunk in data.chunks(4) {
+		let v = chunk.iter().enumerate()
+			.try_fold(0u32, |acc, (i, ch)| Some(acc | value(*ch)? << (18 - 6 * i)))?;
+		let bytes = v.to_be_bytes();
+		match chunk.len() {
+			4 => out.extend_from_slice(&bytes[1..4]),
+			3 => out.extend_from_slice(&bytes[1..3]),
+			2 => out.push(bytes[1]),
+			_ => return None
+		}
+	}
+	
+	Some(out)
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+	const CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+	
+	for chunk in data.chunks(3) {
+		let v = chunk.iter().enumerate().fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+		for i in 0..4 {
+			match i <= chunk.len() {
+				true  => out.push(CHARS[(v >> (18 - 6 * i) & 0x3F) as usize] as char),
+				false => out.push('=')
+			}
+		}
+	}
+	
+	out
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Asset {
+	pub version:     String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub min_version: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub generator:   Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub copyright:   Option<String>
+}
+
+impl Default for Asset {
+	fn default() -> Self {
+		Self { version: "2.0".to_string(), min_version: None, generator: None, copyright: None }
+	}
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Scene {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:  Option<String>,
+	#[serde(default)]
 	pub nodes: Vec<usize>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Node {
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub name:        Option<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub children:    Vec<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub matrix:      Option<[f64; 16]>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub translation: Option<[f64; 3]>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub rotation:    Option<[f64; 4]>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub scale:       Option<[f64; 3]>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub mesh:        Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub skin:        Option<usize>,
-	pub camera:      Option<usize>
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub camera:      Option<usize>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub weights:     Vec<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extensions:  Option<NodeExtensions>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Mesh {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:       Option<String>,
 	pub primitives: Vec<Primitive>,
-	#[serde(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub weights:    Vec<f64>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Primitive {
+	#[serde(default)]
 	pub mode:       MeshPrimitiveRenderingMode,
-	pub indices:    usize,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub indices:    Option<usize>,
 	pub attributes: HashMap<String, usize>,
-	pub material:   usize,
-	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub material:   Option<usize>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub targets:    Vec<HashMap<String, usize>>
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
 pub enum MeshPrimitiveRenderingMode {
 	Points,
 	Lines,
-	Triangles
+	LineLoop,
+	LineStrip,
+	Triangles,
+	TriangleStrip,
+	TriangleFan
 }
 
 impl Default for MeshPrimitiveRenderingMode {
@@ -176,82 +436,482 @@ impl Default for MeshPrimitiveRenderingMode {
 	}
 }
 
+impl TryFrom<u32> for MeshPrimitiveRenderingMode {
+	type Error = String;
+	
+	fn try_from(v: u32) -> Result<Self, Self::Error> {
+		Ok(match v {
+			0 => Self::Points,
+			1 => Self::Lines,
+			2 => Self::LineLoop,
+			3 => Self::LineStrip,
+			4 => Self::Triangles,
+			5 => Self::TriangleStrip,
+			6 => Self::TriangleFan,
+			v => return Err(format!("invalid primitive mode {}", v))
+		})
+	}
+}
+
+impl From<MeshPrimitiveRenderingMode> for u32 {
+	fn from(v: MeshPrimitiveRenderingMode) -> Self {
+		v as _
+	}
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Buffer {
 	pub byte_length: usize,
-	pub uri:         String
+	/// `None` if the buffer references the binary chunk of a binary glTF file.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub uri:         Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:        Option<String>
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BufferView {
 	pub buffer:      usize,
+	#[serde(default)]
 	pub byte_offset: usize,
 	pub byte_length: usize,
-	pub byte_stride: usize,
-	pub target:      OpenGlBufferTarget
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub byte_stride: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub target:      Option<OpenGlBufferTarget>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:        Option<String>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Accessor {
-	pub buffer_view:    usize,
+	/// `None` if the accessor is initialized with zeros.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub buffer_view:    Option<usize>,
+	#[serde(default)]
 	pub byte_offset:    usize,
-	pub r#type:         String,
+	pub r#type:         AccessorType,
 	pub component_type: OpenGlComponentType,
+	#[serde(default)]
+	pub normalized:     bool,
 	pub count:          usize,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub sparse:         Option<AccessorSparse>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub min:            Vec<f64>,
-	pub max:            Vec<f32>
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub max:            Vec<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:           Option<String>
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AccessorType {
+	Scalar,
+	Vec2,
+	Vec3,
+	Vec4,
+	Mat2,
+	Mat3,
+	Mat4
+}
+
+impl Default for AccessorType {
+	fn default() -> Self {
+		Self::Scalar
+	}
+}
+
+impl AccessorType {
+	pub fn components(self) -> usize {
+		match self {
+			Self::Scalar => 1,
+			Self::Vec2   => 2,
+			Self::Vec3   => 3,
+			Self::Vec4   => 4,
+			Self::Mat2   => 4,
+			Self::Mat3   => 9,
+			Self::Mat4   => 16
+		}
+	}
+	
+	/// Returns the number of rows of a column, all types except matrices have a single column.
+	pub fn rows(self) -> usize {
+		match self {
+			Self::Mat2 => 2,
+			Self::Mat3 => 3,
+			Self::Mat4 => 4,
+			_ => self.components()
+		}
+	}
+	
+	/// Returns the size of an element in bytes. Matrix columns are aligned to four bytes.
+	pub fn element_size(self, component_type: ComponentType) -> usize {
+		self.column_size(component_type) * self.components() / self.rows()
+	}
+	
+	fn column_size(self, component_type: ComponentType) -> usize {
+		let size = self.rows() * component_type.size();
+		match self {
+			Self::Mat2 | Self::Mat3 | Self::Mat4 => (size + 3) & !3,
+			_ => size
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ComponentType {
+	Byte,
+	UnsignedByte,
+	Short,
+	UnsignedShort,
+	UnsignedInt,
+	Float
+}
+
+impl ComponentType {
+	pub fn from_gl(v: OpenGlComponentType) -> Option<Self> {
+		Some(match v {
+			5120 => Self::Byte,
+			5121 => Self::UnsignedByte,
+			5122 => Self::Short,
+			5123 => Self::UnsignedShort,
+			5125 => Self::UnsignedInt,
+			5126 => Self::Float,
+			_ => return None
+		})
+	}
+	
+	pub fn to_gl(self) -> OpenGlComponentType {
+		match self {
+			Self::Byte          => 5120,
+			Self::UnsignedByte  => 5121,
+			Self::Short         => 5122,
+			Self::UnsignedShort => 5123,
+			Self::UnsignedInt   => 5125,
+			Self::Float         => 5126
+		}
+	}
+	
+	pub fn size(self) -> usize {
+		match self {
+			Self::Byte | Self::UnsignedByte   => 1,
+			Self::Short | Self::UnsignedShort => 2,
+			Self::UnsignedInt | Self::Float   => 4
+		}
+	}
+	
+	fn read_f32(self, bytes: &[u8], normalized: bool) -> f32 {
+		match (self, normalized) {
+			(Self::Byte, false)          => bytes[0] as i8 as f32,
+			(Self::Byte, true)           => (bytes[0] as i8 as f32 / 127.0).max(-1.0),
+			(Self::UnsignedByte, false)  => bytes[0] as f32,
+			(Self::UnsignedByte, true)   => bytes[0] as f32 / 255.0,
+			(Self::Short, false)         => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+			(Self::Short, true)          => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767.0).max(-1.0),
+			(Self::UnsignedShort, false) => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+			(Self::UnsignedShort, true)  => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+			(Self::UnsignedInt, _)       => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+			(Self::Float, _)             => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+		}
+	}
+	
+	fn read_u32(self, bytes: &[u8]) -> Option<u32> {
+		Some(match self {
+			Self::UnsignedByte  => bytes[0] as u32,
+			Self::UnsignedShort => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+			Self::UnsignedInt   => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+			_ => return None
+		})
+	}
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AccessorSparse {
 	pub count:   usize,
 	pub values:  AccessorSparseValues,
 	pub indices: AccessorSparseIndices
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccessorSparseValues {
-	pub buffer_view: usize
+	pub buffer_view: usize,
+	#[serde(default)]
+	pub byte_offset: usize
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccessorSparseIndices {
 	pub buffer_view:    usize,
+	#[serde(default)]
+	pub byte_offset:    usize,
 	pub component_type: OpenGlComponentType
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+/// Decodes the elements of an accessor, taking byte strides, matrix column alignment and
+/// sparse substitution into account.
+#[derive(Clone, Debug)]
+pub struct AccessorReader<'a> {
+	accessor:       &'a Accessor,
+	component_type: ComponentType,
+	/// The data of the buffer view, starting at the offset of the accessor.
+	data:           Option<&'a [u8]>,
+	stride:         usize,
+	/// Sorted indices of the substituted elements and their tightly packed values.
+	sparse:         Option<(Vec<u32>, &'a [u8])>
+}
+
+impl<'a> AccessorReader<'a> {
+	pub fn new(doc: &'a Document, accessor: &'a Accessor, buffers: &'a [Vec<u8>]) -> Result<Self, DeError> {
+		let component_type = ComponentType::from_gl(accessor.component_type)
+			.ok_or_else(|| invalid_data("invalid component type"))?;
+		let element_size = accessor.r#type.element_size(component_type);
+		
+		let view_data = |index: usize, offset: usize, len: usize| -> Result<(&'a [u8], Option<usize>), DeError> {
+			let view = doc.buffer_views.get(index).ok_or_else(|| invalid_data("buffer view index out of bounds"))?;
+			let data = buffers.get(view.buffer)
+				.and_then(|buffer| buffer.get(view.byte_offset..view.byte_offset + view.byte_length))
+				.ok_or_else(|| invalid_data("buffer view out of bounds"))?;
+			match data.get(offset..).filter(|data| data.len() >= len) {
+				Some(data) => Ok((data, view.byte_stride)),
+				None => Err(DeError::Io(invalid_data("accessor out of bounds")))
+			}
+		};
+		
+		let (data, stride) = match accessor.buffer_view {
+			Some(view) => {
+				let (_, stride) = view_data(view, 0, 0)?;
+				let stride = stride.unwrap_or(element_size);
+				if stride < element_size {
+					return Err(DeError::Io(invalid_data("byte stride is smaller than the element size")));
+				}
+				
+				let len = match accessor.count {
+					0 => 0,
+					n => stride * (n - 1) + element_size
+				};
+				(Some(view_data(view, accessor.byte_offset, len)?.0), stride)
+			}
+			None => (None, element_size)
+		};
+		
+		let sparse = match &accessor.sparse {
+			Some(sparse) => {
+				let index_type = ComponentType::from_gl(sparse.indices.component_type)
+					.filter(|ty| ty.read_u32(&[0; 4]).is_some())
+					.ok_or_else(|| invalid_data("invalid sparse index component type"))?;
+				let (indices, _) = view_data(sparse.indices.buffer_view, sparse.indices.byte_offset, sparse.count * index_type.size())?;
+				let (values, _) = view_data(sparse.values.buffer_view, sparse.values.byte_offset, sparse.count * element_size)?;
+				let indices = indices.chunks_exact(index_type.size())
+					.take(sparse.count)
+					.map(|bytes| index_type.read_u32(bytes).unwrap())
+					.collect::<Vec<_>>();
+				
+				if indices.windows(2).any(|w| w[0] >= w[1]) || indices.last().is_some_and(|i| *i as usize >= accessor.count) {
+					return Err(DeError::Io(invalid_data("sparse indices must be increasing and within the accessor")));
+				}
+				
+				Some((indices, values))
+			}
+			None => None
+		};
+		
+		Ok(Self { accessor, component_type, data, stride, sparse })
+	}
+	
+	pub fn len(&self) -> usize {
+		self.accessor.count
+	}
+	
+	pub fn is_empty(&self) -> bool {
+		self.accessor.count == 0
+	}
+	
+	pub fn accessor_type(&self) -> AccessorType {
+		self.accessor.r#type
+	}
+	
+	pub fn component_type(&self) -> ComponentType {
+		self.component_type
+	}
+	
+	pub fn normalized(&self) -> bool {
+		self.accessor.normalized
+	}
+	
+	pub fn element_size(&self) -> usize {
+		self.accessor.r#type.element_size(self.component_type)
+	}
+	
+	/// Returns the raw bytes of an element, or `None` if the element is zero initialized.
+	pub fn element(&self, index: usize) -> Option<&'a [u8]> {
+		let size = self.element_size();
+		if let Some((indices, values)) = &self.sparse {
+			if let Ok(i) = indices.binary_search(&(index as u32)) {
+				return Some(&values[i * size..(i + 1) * size]);
+			}
+		}
+		
+		self.data.map(|data| &data[index * self.stride..index * self.stride + size])
+	}
+	
+	/// Copies the raw bytes of all elements, tightly packed.
+	pub fn read_bytes(&self) -> Vec<u8> {
+		let size = self.element_size();
+		let mut out = vec![0u8; size * self.len()];
+		for (i, chunk) in out.chunks_exact_mut(size).enumerate() {
+			if let Some(element) = self.element(i) {
+				chunk.copy_from_slice(element);
+			}
+		}
+		out
+	}
+	
+	/// Reads all components as floats, normalized integers are mapped to `[0, 1]` or `[-1, 1]`.
+	pub fn read_f32(&self) -> Vec<f32> {
+		let mut out = Vec::with_capacity(self.len() * self.accessor.r#type.components());
+		self.for_each_component(|bytes| out.push(bytes.map_or(0.0, |bytes| self.component_type.read_f32(bytes, self.accessor.normalized))));
+		out
+	}
+	
+	/// Reads all components as unsigned integers, as used by indices and joints.
+	pub fn read_u32(&self) -> Result<Vec<u32>, DeError> {
+		if self.component_type.read_u32(&[0; 4]).is_none() {
+			return Err(DeError::Io(invalid_data("accessor does not contain unsigned integers")));
+		}
+		
+		let mut out = Vec::with_capacity(self.len() * self.accessor.r#type.components());
+		self.for_each_component(|bytes| out.push(bytes.map_or(0, |bytes| self.component_type.read_u32(bytes).unwrap())));
+		Ok(out)
+	}
+	
+	fn for_each_component(&self, mut f: impl FnMut(Option<&[u8]>)) {
+		let ty = self.accessor.r#type;
+		let size = self.component_type.size();
+		let column_size = ty.column_size(self.component_type);
+		
+		for i in 0..self.len() {
+			let element = self.element(i);
+			for c in 0..ty.components() {
+				let offset = c / ty.rows() * column_size + c % ty.rows() * size;
+				f(element.map(|element| &element[offset..offset + size]));
+			}
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Material {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:                   Option<String>,
+	#[serde(default)]
 	pub pbr_metallic_roughness: MaterialPbrMetallicRoughness,
-	pub normal_texture:         MaterialTexture,
-	pub occlusion_texture:      MaterialTexture,
-	pub emissive_texture:       MaterialTexture,
-	pub emissive_factors:       [f64; 3]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub normal_texture:         Option<MaterialTexture>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub occlusion_texture:      Option<MaterialTexture>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub emissive_texture:       Option<MaterialTexture>,
+	#[serde(default)]
+	pub emissive_factor:        [f64; 3],
+	#[serde(default)]
+	pub alpha_mode:             AlphaMode,
+	#[serde(default = "default_alpha_cutoff")]
+	pub alpha_cutoff:           f64,
+	#[serde(default)]
+	pub double_sided:           bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extensions:             Option<MaterialExtensions>
 }
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+impl Default for Material {
+	fn default() -> Self {
+		Self {
+			name:                   None,
+			pbr_metallic_roughness: MaterialPbrMetallicRoughness::default(),
+			normal_texture:         None,
+			occlusion_texture:      None,
+			emissive_texture:       None,
+			emissive_factor:        [0.0; 3],
+			alpha_mode:             AlphaMode::Opaque,
+			alpha_cutoff:           default_alpha_cutoff(),
+			double_sided:           false,
+			extensions:             None
+		}
+	}
+}
+
+fn default_alpha_cutoff() -> f64 {
+	0.5
+}
+
+fn default_one() -> f64 {
+	1.0
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AlphaMode {
+	Opaque,
+	Mask,
+	Blend
+}
+
+impl Default for AlphaMode {
+	fn default() -> Self {
+		Self::Opaque
+	}
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MaterialPbrMetallicRoughness {
-	pub base_color_texture:         MaterialTexture,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub base_color_texture:         Option<MaterialTexture>,
+	#[serde(default = "MaterialPbrMetallicRoughness::default_base_color_factor")]
 	pub base_color_factor:          [f64; 4],
-	pub metallic_roughness_texture: MaterialTexture,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub metallic_roughness_texture: Option<MaterialTexture>,
+	#[serde(default = "default_one")]
 	pub metallic_factor:            f64,
+	#[serde(default = "default_one")]
 	pub roughness_factor:           f64
 }
 
+impl MaterialPbrMetallicRoughness {
+	fn default_base_color_factor() -> [f64; 4] {
+		[1.0; 4]
+	}
+}
+
+impl Default for MaterialPbrMetallicRoughness {
+	fn default() -> Self {
+		Self {
+			base_color_texture:         None,
+			base_color_factor:          Self::default_base_color_factor(),
+			metallic_roughness_texture: None,
+			metallic_factor:            1.0,
+			roughness_factor:           1.0
+		}
+	}
+}
+
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MaterialTexture {
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub scale:     Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub strength:  Option<f64>,
 	pub index:     usize,
+	#[serde(default)]
 	pub tex_coord: usize
 }
 
@@ -259,7 +919,9 @@ pub struct MaterialTexture {
 #[serde(rename_all = "camelCase")]
 pub struct Camera {
 	pub r#type:       CameraType,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub perspective:  Option<CameraPerspective>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub orthographic: Option<CameraOrthographic>
 }
 
@@ -281,8 +943,10 @@ impl Default for CameraType {
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CameraPerspective {
-	pub aspect_ratio: f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub aspect_ratio: Option<f64>,
 	pub yfov:         f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub zfar:         Option<f64>,
 	pub znear:        f64
 }
@@ -297,35 +961,52 @@ pub struct CameraOrthographic {
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Texture {
-	pub source:  usize,
-	pub sampler: usize
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source:  Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sampler: Option<usize>
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(untagged, rename_all = "camelCase")]
+#[serde(untagged)]
 pub enum Image {
-	Uri { uri: String },
-	Buffer { buffer_view: usize, mime_type: String }
+	Uri {
+		uri: String
+	},
+	#[serde(rename_all = "camelCase")]
+	Buffer {
+		buffer_view: usize,
+		mime_type:   String
+	}
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Sampler {
-	pub mag_filter: OpenGlConstant,
-	pub min_filter: OpenGlConstant,
-	pub wrap_s:     OpenGlConstant,
-	pub wrap_t:     OpenGlConstant
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mag_filter: Option<OpenGlConstant>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub min_filter: Option<OpenGlConstant>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub wrap_s:     Option<OpenGlConstant>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub wrap_t:     Option<OpenGlConstant>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Skin {
-	pub inverse_bind_matrices: usize,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inverse_bind_matrices: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub skeleton:              Option<usize>,
 	pub joints:                Vec<usize>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Animation {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:     Option<String>,
 	pub channels: Vec<AnimationChannel>,
 	pub samplers: Vec<AnimationSampler>
 }
@@ -338,7 +1019,8 @@ pub struct AnimationChannel {
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct AnimationChannelTarget {
-	pub node: usize,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub node: Option<usize>,
 	pub path: AnimationChannelTargetPath
 }
 
@@ -354,6 +1036,7 @@ pub enum AnimationChannelTargetPath {
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AnimationSampler {
 	pub input:         usize,
+	#[serde(default)]
 	pub interpolation: AnimationInterpolation,
 	pub output:        usize
 }
@@ -373,3 +1056,307 @@ impl Default for AnimationInterpolation {
 	}
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DocumentExtensions {
+	#[serde(rename = "KHR_lights_punctual", skip_serializing_if = "Option::is_none")]
+	pub khr_lights_punctual: Option<KhrLightsPunctual>
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NodeExtensions {
+	#[serde(rename = "KHR_lights_punctual", skip_serializing_if = "Option::is_none")]
+	pub khr_lights_punctual: Option<KhrLightsPunctualNode>
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct KhrLightsPunctual {
+	pub lights: Vec<Light>
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct KhrLightsPunctualNode {
+	pub light: usize
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Light {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name:      Option<String>,
+	#[serde(default = "default_ones")]
+	pub color:     [f64; 3],
+	#[serde(default = "default_one")]
+	pub intensity: f64,
+	pub r#type:    LightType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub range:     Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub spot:      Option<LightSpot>
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LightType {
+	Directional,
+	Point,
+	Spot
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LightSpot {
+	#[serde(default)]
+	pub inner_cone_angle: f64,
+	#[serde(default = "LightSpot::default_outer_cone_angle")]
+	pub outer_cone_angle: f64
+}
+
+impl LightSpot {
+	fn default_outer_cone_angle() -> f64 {
+		std::f64::consts::FRAC_PI_4
+	}
+}
+
+impl Default for LightSpot {
+	fn default() -> Self {
+		Self { inner_cone_angle: 0.0, outer_cone_angle: Self::default_outer_cone_angle() }
+	}
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MaterialExtensions {
+	#[serde(rename = "KHR_materials_emissive_strength", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_emissive_strength: Option<KhrMaterialsEmissiveStrength>,
+	#[serde(rename = "KHR_materials_ior", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_ior:               Option<KhrMaterialsIor>,
+	#[serde(rename = "KHR_materials_transmission", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_transmission:      Option<KhrMaterialsTransmission>,
+	#[serde(rename = "KHR_materials_volume", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_volume:            Option<KhrMaterialsVolume>,
+	#[serde(rename = "KHR_materials_clearcoat", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_clearcoat:         Option<KhrMaterialsClearcoat>,
+	#[serde(rename = "KHR_materials_sheen", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_sheen:             Option<KhrMaterialsSheen>,
+	#[serde(rename = "KHR_materials_specular", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_specular:          Option<KhrMaterialsSpecular>,
+	#[serde(rename = "KHR_materials_unlit", skip_serializing_if = "Option::is_none")]
+	pub khr_materials_unlit:             Option<KhrMaterialsUnlit>
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsEmissiveStrength {
+	#[serde(default = "default_one")]
+	pub emissive_strength: f64
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct KhrMaterialsIor {
+	#[serde(default = "KhrMaterialsIor::default_ior")]
+	pub ior: f64
+}
+
+impl KhrMaterialsIor {
+	fn default_ior() -> f64 {
+		1.5
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsTransmission {
+	#[serde(default)]
+	pub transmission_factor:  f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub transmission_texture: Option<MaterialTexture>
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsVolume {
+	#[serde(default)]
+	pub thickness_factor:     f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub thickness_texture:    Option<MaterialTexture>,
+	/// `None` means infinite distance, i.e. no attenuation.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub attenuation_distance: Option<f64>,
+	#[serde(default = "default_ones")]
+	pub attenuation_color:    [f64; 3]
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsClearcoat {
+	#[serde(default)]
+	pub clearcoat_factor:            f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub clearcoat_texture:           Option<MaterialTexture>,
+	#[serde(default)]
+	pub clearcoat_roughness_factor:  f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub clearcoat_roughness_texture: Option<MaterialTexture>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub clearcoat_normal_texture:    Option<MaterialTexture>
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsSheen {
+	#[serde(default)]
+	pub sheen_color_factor:      [f64; 3],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sheen_color_texture:     Option<MaterialTexture>,
+	#[serde(default)]
+	pub sheen_roughness_factor:  f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sheen_roughness_texture: Option<MaterialTexture>
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KhrMaterialsSpecular {
+	#[serde(default = "default_one")]
+	pub specular_factor:        f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub specular_texture:       Option<MaterialTexture>,
+	#[serde(default = "default_ones")]
+	pub specular_color_factor:  [f64; 3],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub specular_color_texture: Option<MaterialTexture>
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct KhrMaterialsUnlit {}
+
+fn default_ones() -> [f64; 3] {
+	[1.0; 3]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn f32_bytes(v: &[f32]) -> Vec<u8> {
+		v.iter().flat_map(|v| v.to_le_bytes()).collect()
+	}
+	
+	#[test]
+	fn base64() {
+		for data in [&b""[..], b"a", b"ab", b"abc", b"abcd", &[0, 255, 128, 7, 9]] {
+			assert_eq!(base64_decode(&base64_encode(data)).unwrap(), data);
+		}
+		assert_eq!(base64_encode(b"glTF"), "Z2xURg==");
+		assert!(base64_decode("a").is_none());
+	}
+	
+	#[test]
+	fn glb_round_trip() {
+		let mut doc = Document::default();
+		let positions = doc.push_accessor(&f32_bytes(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.5]), AccessorType::Vec3,
+			ComponentType::Float, false, Some(TARGET_ARRAY_BUFFER)).unwrap();
+		let indices = doc.push_accessor(&[0, 1, 2], AccessorType::Scalar, ComponentType::UnsignedByte, false,
+			Some(TARGET_ELEMENT_ARRAY_BUFFER)).unwrap();
+		doc.meshes.push(Mesh {
+			primitives: vec![Primitive {
+				indices:    Some(indices),
+				attributes: HashMap::from([("POSITION".to_string(), positions)]),
+				..Primitive::default()
+			}],
+			..Mesh::default()
+		});
+		
+		let mut buf = Vec::new();
+		write(&mut buf, &doc).unwrap();
+		assert_eq!(&buf[..4], b"glTF");
+		assert_eq!(buf.len() % 4, 0);
+		assert_eq!(u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]) as usize, buf.len());
+		
+		let doc = read(buf.as_slice()).unwrap();
+		let buffers = doc.load_buffers(|_| unreachable!()).unwrap();
+		assert_eq!(doc.version, Some(2));
+		assert_eq!(doc.accessors[positions].max, [1.0, 1.0, 0.5]);
+		assert_eq!(doc.accessor(positions, &buffers).unwrap().read_f32(), [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.5]);
+		assert_eq!(doc.accessor(indices, &buffers).unwrap().read_u32().unwrap(), [0, 1, 2]);
+		assert_eq!(doc.meshes[0].primitives[0].mode, MeshPrimitiveRenderingMode::Triangles);
+	}
+	
+	#[test]
+	fn accessors() {
+		// two interleaved elements: normalized u8 vec2 + i16 scalar, stride 8
+		let data = [255, 0, 0, 0, 0x00, 0x80, 0, 0, 0, 255, 0, 0, 0xFF, 0x7F, 0, 0];
+		// sparse: replaces element 1 of the second accessor
+		let sparse_indices = [1u8, 0, 0, 0];
+		let sparse_values = (-1i16).to_le_bytes();
+		let src = format!(r#"{{
+			"asset": {{ "version": "2.0" }},
+			"buffers": [ {{ "byteLength": 16, "uri": "data:application/octet-stream;base64,{}" }}, {{ "byteLength": 6, "uri": "sparse%20data.bin" }} ],
+			"bufferViews": [
+				{{ "buffer": 0, "byteLength": 16, "byteStride": 8 }},
+				{{ "buffer": 1, "byteLength": 4 }},
+				{{ "buffer": 1, "byteOffset": 4, "byteLength": 2 }}
+			],
+			"accessors": [
+				{{ "bufferView": 0, "type": "VEC2", "componentType": 5121, "normalized": true, "count": 2 }},
+				{{ "bufferView": 0, "byteOffset": 4, "type": "SCALAR", "componentType": 5122, "count": 2,
+					"sparse": {{ "count": 1, "indices": {{ "bufferView": 1, "componentType": 5121 }}, "values": {{ "bufferView": 2 }} }} }},
+				{{ "type": "VEC3", "componentType": 5126, "count": 1 }}
+			],
+			"meshes": [ {{ "primitives": [ {{ "attributes": {{ "TEXCOORD_0": 0 }}, "mode": 0 }} ] }} ]
+		}}"#, base64_encode(&data));
+		
+		let doc = read(src.as_bytes()).unwrap();
+		let buffers = doc.load_buffers(|uri| {
+			assert_eq!(percent_decode(uri).unwrap(), "sparse data.bin");
+			Ok([&sparse_indices[..], &sparse_values].concat())
+		}).unwrap();
+		
+		assert_eq!(doc.accessor(0, &buffers).unwrap().read_f32(), [1.0, 0.0, 0.0, 1.0]);
+		assert_eq!(doc.accessor(1, &buffers).unwrap().read_f32(), [-32768.0, -1.0]);
+		assert_eq!(doc.accessor(1, &buffers).unwrap().read_bytes(), [0x00, 0x80, 0xFF, 0xFF]);
+		assert_eq!(doc.accessor(2, &buffers).unwrap().read_f32(), [0.0; 3]);
+		assert!(doc.accessor(0, &buffers).unwrap().read_u32().is_ok());
+		assert!(doc.accessor(1, &buffers).unwrap().read_u32().is_err());
+		assert_eq!(doc.meshes[0].primitives[0].mode, MeshPrimitiveRenderingMode::Points);
+	}
+	
+	#[test]
+	fn matrix_alignment() {
+		assert_eq!(AccessorType::Mat2.element_size(ComponentType::UnsignedByte), 8);
+		assert_eq!(AccessorType::Mat3.element_size(ComponentType::Short), 24);
+		assert_eq!(AccessorType::Mat4.element_size(ComponentType::Float), 64);
+		
+		let mut doc = Document::default();
+		let index = doc.push_accessor(&[1, 2, 0, 0, 3, 4, 0, 0], AccessorType::Mat2, ComponentType::UnsignedByte, false, None).unwrap();
+		let buffers = doc.binary_buffers.clone();
+		assert_eq!(doc.accessor(index, &buffers).unwrap().read_u32().unwrap(), [1, 2, 3, 4]);
+	}
+	
+	#[test]
+	fn extensions() {
+		let src = br#"{
+			"asset": { "version": "2.0" },
+			"extensionsUsed": [ "KHR_lights_punctual", "KHR_materials_ior", "KHR_materials_unlit" ],
+			"extensions": { "KHR_lights_punctual": { "lights": [ { "type": "spot", "spot": { "innerConeAngle": 0.25 } } ] } },
+			"nodes": [ { "extensions": { "KHR_lights_punctual": { "light": 0 } } } ],
+			"materials": [ { "extensions": { "KHR_materials_ior": {}, "KHR_materials_unlit": {}, "EXT_unknown": { "a": 1 } } } ]
+		}"#;
+		
+		let doc = read(&src[..]).unwrap();
+		let light = &doc.extensions.as_ref().unwrap().khr_lights_punctual.as_ref().unwrap().lights[0];
+		assert_eq!(light.r#type, LightType::Spot);
+		assert_eq!(light.color, [1.0; 3]);
+		assert_eq!(light.spot.unwrap().inner_cone_angle, 0.25);
+		assert_eq!(light.spot.unwrap().outer_cone_angle, std::f64::consts::FRAC_PI_4);
+		assert_eq!(doc.nodes[0].extensions.as_ref().unwrap().khr_lights_punctual.unwrap().light, 0);
+		let ext = doc.materials[0].extensions.as_ref().unwrap();
+		assert_eq!(ext.khr_materials_ior.unwrap().ior, 1.5);
+		assert!(ext.khr_materials_unlit.is_some());
+		assert!(ext.khr_materials_sheen.is_none());
+		
+		let mut buf = Vec::new();
+		write(&mut buf, &doc).unwrap();
+		let doc = read(buf.as_slice()).unwrap();
+		assert!(doc.materials[0].extensions.as_ref().unwrap().khr_materials_unlit.is_some());
+		assert_eq!(doc.extensions.unwrap().khr_lights_punctual.unwrap().lights.len(), 1);
+	}
+}
@@ -0,0 +1,534 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Baseline and extended sequential JPEG decoding with Huffman coding, see ITU T.81.
+//! Progressive and arithmetic coded images are not supported.
+
+use std::io::{self, Error, ErrorKind};
+
+/// Maps the zig-zag index of a coefficient to its index in natural order.
+const ZIGZAG: [usize; 64] = [
+	0,  1,  8,  16, 9,  2,  3,  10, 17, 24, 32, 25, 18, 11, 4,  5,
+	12, 19, 26, 33, 40, 48, 41, 34, 27, 20, 13, 6,  7,  14, 21, 28,
+	35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51,
+	58, 59, 52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63
+];
+
+/// A decoded image with 8 bit samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+	pub width:    u32,
+	pub height:   u32,
+	/// 1 gray or 3 RGB
+	pub channels: usize,
+	pub data:     Vec<u8>
+}
+
+#[derive(Clone, Debug, Default)]
+struct Component {
+	id:       u8,
+	h:        usize,
+	v:        usize,
+	quant:    usize,
+	dc_table: usize,
+	ac_table: usize,
+	dc_pred:  i32,
+	/// the width of `samples`, a multiple of 8
+	stride:   usize,
+	samples:  Vec<u8>
+}
+
+#[derive(Clone, Debug, Default)]
+struct Huffman {
+	max_code: [i32; 17],
+	min_code: [i32; 17],
+	val_ptr:  [i32; 17],
+	values:   Vec<u8>
+}
+
+impl Huffman {
+	fn new(counts: &[u8], values: &[u8]) -> Self {
+		let mut table = Self { values: values.to_vec(), ..Self::default() };
+		let mut code = 0;
+		let mut k = 0;
+		
+		for l in 1..=16 {
+			let count = counts[l - 1] as i32;
+			table.val_ptr[l] = k;
+			table.min_code[l] = code;
+			code += count;
+			k += count;
+			table.max_code[l] = if count > 0 { code - 1 } else { -1 };
+			code <<= 1;
+		}
+		
+		table
+	}
+	
+	fn decode(&self, reader: &mut BitReader) -> io::Result<u8> {
+		let mut code = 0;
+		for l in 1..=16 {
+			code = code << 1 | reader.bit() as i32;
+			if code <= self.max_code[l] {
+				return self.values.get((self.val_ptr[l] + code - self.min_code[l]) as usize)
+					.copied()
+					.ok_or_else(|| invalid_data("invalid JPEG Huffman code"));
+			}
+		}
+		
+		Err(invalid_data("invalid JPEG Huffman code"))
+	}
+}
+
+/// Reads entropy coded data, removes stuffed zero bytes and stops at markers.
+struct BitReader<'a> {
+	data:  &'a [u8],
+	pos:   usize,
+	buf:   u32,
+	count: u32
+}
+
+impl BitReader<'_> {
+	fn bit(&mut self) -> u32 {
+		if self.count == 0 {
+			self.buf = match (self.data.get(self.pos), self.data.get(self.pos + 1)) {
+				(Some(0xFF), Some(0x00)) => {
+					self.pos += 2;
+					0xFF
+				}
+				// a marker, pad with zeros until the caller handles it
+				(Some(0xFF), _) | (None, _) => 0,
+				(Some(byte), _) => {
+					self.pos += 1;
+					*byte as u32
+				}
+			};
+			
+			self.count = 8;
+		}
+		
+		self.count -= 1;
+		(self.buf >> self.count) & 1
+	}
+	
+	fn bits(&mut self, n: u32) -> u32 {
+		(0..n).fold(0, |acc, _| acc << 1 | self.bit())
+	}
+	
+	/// Returns the value of a coefficient with `n` additional bits. `n` comes from a Huffman
+	/// table and is not bounded by it, the largest magnitude category is 16 (lossless DC).
+	fn receive_extend(&mut self, n: u32) -> io::Result<i32> {
+		match n {
+			0 => Ok(0),
+			1..=16 => {
+				let v = self.bits(n) as i32;
+				Ok(if v < 1 << (n - 1) { v - (1 << n) + 1 } else { v })
+			}
+			_ => Err(invalid_data("invalid JPEG magnitude category"))
+		}
+	}
+	
+	/// Discards the remaining bits and skips a restart marker.
+	fn restart(&mut self) -> io::Result<()> {
+		self.count = 0;
+		match self.data.get(self.pos..self.pos + 2) {
+			Some([0xFF, 0xD0..=0xD7]) => {
+				self.pos += 2;
+				Ok(())
+			}
+			_ => Err(invalid_data("missing JPEG restart marker"))
+		}
+	}
+}
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn u16_be(data: &[u8], pos: usize) -> io::Result<usize> {
+	data.get(pos..pos + 2)
+		.map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+		.ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))
+}
+
+/// Decodes a JPEG file.
+pub fn decode(data: &[u8]) -> io::Result<Image> {
+	if data.get(..2) != Some(&[0xFF, 0xD8]) {
+		return Err(invalid_data("invalid JPEG signature"));
+	}
+	
+	let mut pos = 2;
+	let mut quant = [[0u16; 64]; 4];
+	let mut dc_tables = [Huffman::default(), Huffman::default(), Huffman::default(), Huffman::default()];
+	let mut ac_tables = dc_tables.clone();
+	let mut components = Vec::<Component>::new();
+	let mut size = (0, 0);
+	let mut restart_interval = 0;
+	let mut adobe_transform = None;
+	
+	loop {
+		// skip fill bytes
+		while data.get(pos) == Some(&0xFF) && data.get(pos + 1) == Some(&0xFF) {
+			pos += 1;
+		}
+		
+		let marker = match data.get(pos..pos + 2) {
+			Some([0xFF, marker]) => *marker,
+			Some(_) => return Err(invalid_data("expected JPEG marker")),
+			None => return Err(Error::from(ErrorKind::UnexpectedEof))
+		};
+		
+		if marker == 0xD9 {
+			break;
+		}
+		
+		let len = u16_be(data, pos + 2)?;
+		let segment = data.get(pos + 4..pos + 2 + len).filter(|_| len >= 2)
+			.ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+		pos += 2 + len;
+		
+		match marker {
+			0xDB => {
+				let mut s = segment;
+				while let Some(&pq_tq) = s.first() {
+					let wide = pq_tq >> 4 == 1;
+					let table = quant.get_mut(pq_tq as usize & 0xF).ok_or_else(|| invalid_data("invalid JPEG quantization table"))?;
+					let len = if wide { 128 } else { 64 };
+					let values = s.get(1..1 + len).ok_or_else(|| invalid_data("invalid JPEG quantization table"))?;
+					
+					for (i, value) in table.iter_mut().enumerate() {
+						*value = if wide { u16::from_be_bytes([values[2 * i], values[2 * i + 1]]) } else { values[i] as u16 };
+					}
+					
+					s = &s[1 + len..];
+				}
+			}
+			0xC4 => {
+				let mut s = segment;
+				while let Some(&tc_th) = s.first() {
+					let counts = s.get(1..17).ok_or_else(|| invalid_data("invalid JPEG Huffman table"))?;
+					let total = counts.iter().map(|c| *c as usize).sum::<usize>();
+					let values = s.get(17..17 + total).ok_or_else(|| invalid_data("invalid JPEG Huffman table"))?;
+					let table = match tc_th >> 4 {
+						0 => dc_tables.get_mut(tc_th as usize & 0xF),
+						_ => ac_tables.get_mut(tc_th as usize & 0xF)
+					}.ok_or_else(|| invalid_data("invalid JPEG Huffman table"))?;
+					
+					*table = Huffman::new(counts, values);
+					s = &s[17 + total..];
+				}
+			}
+			0xC0 | 0xC1 => {
+				if segment.len() < 6 || segment[0] != 8 {
+					return Err(Error::new(ErrorKind::Unsupported, "only 8 bit JPEG images are supported"));
+				}
+				
+				size = (u16_be(segment, 3)?, u16_be(segment, 1)?);
+				let count = segment[5] as usize;
+				if size.0 == 0 || size.1 == 0 || !matches!(count, 1 | 3) || segment.len() < 6 + count * 3 {
+					return Err(invalid_data("invalid JPEG frame header"));
+				}
+				
+				components = segment[6..6 + count * 3].chunks_exact(3).map(|c| Component {
+					id:    c[0],
+					h:     (c[1] >> 4) as usize,
+					v:     (c[1] & 0xF) as usize,
+					quant: (c[2] & 3) as usize,
+					..Component::default()
+				}).collect();
+				
+				if components.iter().any(|c| !(1..=4).contains(&c.h) || !(1..=4).contains(&c.v)) {
+					return Err(invalid_data("invalid JPEG sampling factors"));
+				}
+				
+				let h_max = components.iter().map(|c| c.h).max().unwrap();
+				let v_max = components.iter().map(|c| c.v).max().unwrap();
+				let mcus = (size.0.div_ceil(8 * h_max), size.1.div_ceil(8 * v_max));
+				
+				for c in &mut components {
+					c.stride = mcus.0 * c.h * 8;
+					c.samples = vec![0; c.stride * mcus.1 * c.v * 8];
+				}
+			}
+			0xC2 | 0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF =>
+				return Err(Error::new(ErrorKind::Unsupported, "only sequential Huffman coded JPEG images are supported")),
+			0xDD => restart_interval = u16_be(segment, 0)?,
+			0xEE if segment.starts_with(b"Adobe") && segment.len() >= 12 => adobe_transform = Some(segment[11]),
+			0xDA => {
+				if components.is_empty() {
+					return Err(invalid_data("JPEG scan before frame header"));
+				}
+				
+				let count = segment[0] as usize;
+				let mut scan = Vec::with_capacity(count);
+				for c in segment.get(1..1 + count * 2).ok_or_else(|| invalid_data("invalid JPEG scan header"))?.chunks_exact(2) {
+					let i = components.iter().position(|comp| comp.id == c[0])
+						.ok_or_else(|| invalid_data("invalid JPEG scan component"))?;
+					components[i].dc_table = (c[1] >> 4) as usize & 3;
+					components[i].ac_table = (c[1] & 0xF) as usize & 3;
+					scan.push(i);
+				}
+				
+				let mut reader = BitReader { data, pos, buf: 0, count: 0 };
+				decode_scan(&mut reader, &mut components, &scan, size, restart_interval, &quant, &dc_tables, &ac_tables)?;
+				pos = reader.pos;
+				
+				// skip to the next marker
+				while pos + 1 < data.len() && (data[pos] != 0xFF || matches!(data[pos + 1], 0x00 | 0xD0..=0xD7)) {
+					pos += 1;
+				}
+			}
+			_ => ()
+		}
+	}
+	
+	if components.is_empty() {
+		return Err(invalid_data("missing JPEG frame header"));
+	}
+	
+	let rgb = adobe_transform == Some(0) || components.iter().map(|c| c.id).eq(*b"RGB");
+	Ok(Image {
+		width:    size.0 as u32,
+		height:   size.1 as u32,
+		channels: components.len(),
+		data:     convert(&components, size, rgb)
+	})
+}
+
+#[allow(clippy::too_many_arguments)]
+fn decode_scan(
+	reader:           &mut BitReader,
+	components:       &mut [Component],
+	scan:             &[usize],
+	size:             (usize, usize),
+	restart_interval: usize,
+	quant:            &[[u16; 64]; 4],
+	dc_tables:        &[Huffman; 4],
+	ac_tables:        &[Huffman; 4]
+) -> io::Result<()> {
+	let h_max = components.iter().map(|c| c.h).max().unwrap();
+	let v_max = components.iter().map(|c| c.v).max().unwrap();
+	
+	// a scan with a single component is not interleaved and covers only the blocks inside the image
+	let (mcus_x, mcus_y) = match scan {
+		&[i] => (
+			(size.0 * components[i].h).div_ceil(h_max).div_ceil(8),
+			(size.1 * components[i].v).div_ceil(v_max).div_ceil(8)
+		),
+		_ => (size.0.div_ceil(8 * h_max), size.1.div_ceil(8 * v_max))
+	};
+	
+	components.iter_mut().for_each(|c| c.dc_pred = 0);
+	let mut block = [0f32; 64];
+	let mut cos = [0f32; 64];
+	for (i, v) in cos.iter_mut().enumerate() {
+		let (x, u) = (i / 8, i % 8);
+		*v = ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0).cos();
+		if u == 0 {
+			*v *= std::f32::consts::FRAC_1_SQRT_2;
+		}
+	}
+	
+	for mcu in 0..mcus_x * mcus_y {
+		if restart_interval != 0 && mcu != 0 && mcu % restart_interval == 0 {
+			reader.restart()?;
+			components.iter_mut().for_each(|c| c.dc_pred = 0);
+		}
+		
+		let (mx, my) = (mcu % mcus_x, mcu / mcus_x);
+		for &i in scan {
+			let c = &mut components[i];
+			let (h, v) = if scan.len() == 1 { (1, 1) } else { (c.h, c.v) };
+			
+			for by in 0..v {
+				for bx in 0..h {
+					let q = &quant[c.quant];
+					block.fill(0.0);
+					
+					let t = dc_tables[c.dc_table].decode(reader)? as u32;
+					c.dc_pred += reader.receive_extend(t)?;
+					block[0] = c.dc_pred as f32 * q[0] as f32;
+					
+					let mut k = 1;
+					while k < 64 {
+						let rs = ac_tables[c.ac_table].decode(reader)?;
+						let (r, s) = ((rs >> 4) as usize, (rs & 0xF) as u32);
+						if s == 0 {
+							if r != 15 {
+								break;
+							}
+							k += 16;
+							continue;
+						}
+						
+						k += r;
+						if k > 63 {
+							return Err(invalid_data("invalid JPEG coefficient index"));
+						}
+						block[ZIGZAG[k]] = reader.receive_extend(s)? as f32 * q[k] as f32;
+						k += 1;
+					}
+					
+					let x = (mx * h + bx) * 8;
+					let y = (my * v + by) * 8;
+					idct(&block, &cos, &mut c.samples[y * c.stride + x..], c.stride);
+				}
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+/// Inverse DCT of a dequantized block, writes the level shifted samples. `table` contains
+/// `C(u) * cos((2x + 1) * u * pi / 16)` at `x * 8 + u`.
+fn idct(block: &[f32; 64], table: &[f32; 64], out: &mut [u8], stride: usize) {
+	let mut tmp = [0f32; 64];
+	for y in 0..8 {
+		for u in 0..8 {
+			tmp[y * 8 + u] = (0..8).map(|v| table[y * 8 + v] * block[v * 8 + u]).sum::<f32>() / 2.0;
+		}
+	}
+	
+	for y in 0..8 {
+		for x in 0..8 {
+			let value = (0..8).map(|u| table[x * 8 + u] * tmp[y * 8 + u]).sum::<f32>() / 2.0;
+			out[y * stride + x] = (value + 128.0).round().clamp(0.0, 255.0) as u8;
+		}
+	}
+}
+
+/// Upsamples the components and converts them to RGB if required.
+fn convert(components: &[Component], size: (usize, usize), rgb: bool) -> Vec<u8> {
+	let h_max = components.iter().map(|c| c.h).max().unwrap();
+	let v_max = components.iter().map(|c| c.v).max().unwrap();
+	let mut data = vec![0u8; size.0 * size.1 * components.len()];
+	
+	for (ci, c) in components.iter().enumerate() {
+		let width = (size.0 * c.h).div_ceil(h_max);
+		let height = (size.1 * c.v).div_ceil(v_max);
+		let sample = |x: usize, y: usize| c.samples[y.min(height - 1) * c.stride + x.min(width - 1)] as f32;
+		
+		for y in 0..size.1 {
+			// the centers of the subsampled pixels are used for bilinear filtering
+			let sy = ((y as f32 + 0.5) * c.v as f32 / v_max as f32 - 0.5).max(0.0);
+			let (y0, fy) = (sy as usize, sy.fract());
+			
+			for x in 0..size.0 {
+				let sx = ((x as f32 + 0.5) * c.h as f32 / h_max as f32 - 0.5).max(0.0);
+				let (x0, fx) = (sx as usize, sx.fract());
+				let top = sample(x0, y0) * (1.0 - fx) + sample(x0 + 1, y0) * fx;
+				let bottom = sample(x0, y0 + 1) * (1.0 - fx) + sample(x0 + 1, y0 + 1) * fx;
+				data[(y * size.0 + x) * components.len() + ci] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+			}
+		}
+	}
+	
+	if components.len() == 3 && !rgb {
+		for pixel in data.chunks_exact_mut(3) {
+			let (y, cb, cr) = (pixel[0] as f32, pixel[1] as f32 - 128.0, pixel[2] as f32 - 128.0);
+			pixel[0] = (y + 1.402 * cr).round().clamp(0.0, 255.0) as u8;
+			pixel[1] = (y - 0.344_136 * cb - 0.714_136 * cr).round().clamp(0.0, 255.0) as u8;
+			pixel[2] = (y + 1.772 * cb).round().clamp(0.0, 255.0) as u8;
+		}
+	}
+	
+	data
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn decode_subsampled() {
+		// a 16x16 image with 4:2:0 chroma subsampling
+		let data = [
+			0xff, 0xd8, 0xff, 0xdb, 0x00, 0x43, 0x00, 0x03, 0x02, 0x02, 0x02, 0x02, 0x02, 0x03, 0x02, 0x02,
+			0x02, 0x03, 0x03, 0x03, 0x03, 0x04, 0x06, 0x04, 0x04, 0x04, 0x04, 0x04, 0x08, 0x06, 0x06, 0x05,
+			0x06, 0x09, 0x08, 0x0a, 0x0a, 0x09, 0x08, 0x09, 0x09, 0x0a, 0x0c, 0x0f, 0x0c, 0x0a, 0x0b, 0x0e,
+			0x0b, 0x09, 0x09, 0x0d, 0x11, 0x0d, 0x0e, 0x0f, 0x10, 0x10, 0x11, 0x10, 0x0a, 0x0c, 0x12, 0x13,
+			0x12, 0x10, 0x13, 0x0f, 0x10, 0x10, 0x10, 0xff, 0xdb, 0x00, 0x43, 0x01, 0x03, 0x03, 0x03, 0x04,
+			0x03, 0x04, 0x08, 0x04, 0x04, 0x08, 0x10, 0x0b, 0x09, 0x0b, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+			0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+			0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+			0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0xff, 0xc0, 0x00, 0x11,
+			0x08, 0x00, 0x10, 0x00, 0x10, 0x03, 0x01, 0x22, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01, 0xff,
+			0xc4, 0x00, 0x16, 0x00, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x07, 0x04, 0x05, 0xff, 0xc4, 0x00, 0x24, 0x10, 0x00, 0x01, 0x04, 0x01,
+			0x04, 0x02, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04,
+			0x06, 0x05, 0x07, 0x08, 0x12, 0x13, 0x11, 0x22, 0x00, 0x14, 0x09, 0x31, 0x32, 0xff, 0xc4, 0x00,
+			0x15, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x06, 0xff, 0xc4, 0x00, 0x23, 0x11, 0x00, 0x01, 0x02, 0x05, 0x03, 0x05, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x11, 0x03, 0x04, 0x05, 0x06,
+			0x21, 0x00, 0x12, 0x31, 0x15, 0x16, 0x61, 0x81, 0xe1, 0xff, 0xda, 0x00, 0x0c, 0x03, 0x01, 0x00,
+			0x02, 0x11, 0x03, 0x11, 0x00, 0x3f, 0x00, 0x14, 0xa6, 0xd2, 0x6a, 0x1b, 0x73, 0xc1, 0xe6, 0x13,
+			0x12, 0xd4, 0x95, 0x1c, 0xf3, 0x11, 0x63, 0xe4, 0x25, 0x65, 0xbe, 0xba, 0x5a, 0xec, 0x69, 0x45,
+			0x40, 0xb1, 0xe5, 0x20, 0xb2, 0x54, 0xa5, 0x1f, 0xd2, 0xca, 0xb8, 0xfa, 0xf2, 0x20, 0xab, 0x96,
+			0x3d, 0x97, 0x6c, 0x93, 0x35, 0xe6, 0x9b, 0x77, 0xd7, 0xe6, 0x6d, 0xa7, 0x17, 0x81, 0xa5, 0x57,
+			0x1c, 0x7f, 0x1c, 0xea, 0x71, 0xe2, 0x4b, 0x39, 0xd7, 0xe3, 0x22, 0x53, 0xf2, 0x1a, 0x69, 0xde,
+			0xd4, 0x71, 0x4a, 0x38, 0xb4, 0x82, 0xe8, 0x4b, 0x89, 0x2a, 0x71, 0x69, 0x1e, 0xcd, 0x2d, 0x21,
+			0x3b, 0xf1, 0xef, 0xb9, 0x1a, 0x74, 0xac, 0xee, 0xa1, 0x5a, 0x75, 0x8e, 0xd5, 0x48, 0xac, 0x65,
+			0x5b, 0x85, 0x8b, 0x81, 0x85, 0x7b, 0x21, 0x29, 0x98, 0x67, 0xa9, 0x6b, 0x94, 0xb9, 0x49, 0x65,
+			0x4f, 0xb9, 0xc8, 0x85, 0x29, 0x11, 0x4b, 0x81, 0x2a, 0xf0, 0x7a, 0xd9, 0xf2, 0x3c, 0x80, 0x7e,
+			0x55, 0xbe, 0x0d, 0xf6, 0x62, 0xa1, 0x40, 0xcc, 0xe8, 0xe6, 0x9a, 0x3d, 0x5c, 0xb7, 0x43, 0xb3,
+			0xd7, 0x7a, 0x65, 0x58, 0xb1, 0xd9, 0x51, 0x21, 0x88, 0xbf, 0x64, 0xb8, 0xd3, 0xf1, 0xc3, 0x68,
+			0x04, 0x29, 0xc0, 0xd0, 0xfe, 0xbb, 0x3c, 0x02, 0xe0, 0x3c, 0x54, 0x07, 0xb4, 0xbd, 0xd9, 0x7b,
+			0x54, 0xe6, 0x27, 0xfb, 0x6e, 0xdf, 0x94, 0x60, 0x14, 0x82, 0x62, 0x13, 0x8d, 0xb8, 0x52, 0x98,
+			0x28, 0x37, 0x05, 0x89, 0x72, 0x79, 0x60, 0xe4, 0x32, 0x89, 0x6f, 0xc3, 0x82, 0x8e, 0xa7, 0x52,
+			0x8c, 0xea, 0x20, 0x8d, 0xbe, 0x78, 0x19, 0x1f, 0x07, 0xad, 0x7f, 0xff, 0xd9
+		];
+		
+		let image = decode(&data).unwrap();
+		assert_eq!((image.width, image.height, image.channels), (16, 16, 3));
+		
+		let pixel = |x: usize, y: usize| &image.data[(y * 16 + x) * 3..(y * 16 + x) * 3 + 3];
+		for (x, y, expected) in [(3, 5, [74, 125, 178]), (10, 11, [237, 188, 51]), (0, 15, [0, 1, 0])] {
+			assert!(pixel(x, y).iter().zip(expected).all(|(a, b)| (*a as i32 - b).abs() <= 2), "{:?}", pixel(x, y));
+		}
+		
+		assert!(decode(&data[..300]).is_err());
+	}
+	
+	#[test]
+	fn decode_invalid_category() {
+		// an 8x8 grayscale image whose DC table maps the code `0` to the category 32
+		let mut data = vec![0xff, 0xd8, 0xff, 0xdb, 0x00, 0x43, 0x00];
+		data.extend_from_slice(&[1; 64]);
+		data.extend_from_slice(&[0xff, 0xc0, 0x00, 0x0b, 0x08, 0x00, 0x08, 0x00, 0x08, 0x01, 0x01, 0x11, 0x00]);
+		for (class, value) in [(0x00, 32), (0x10, 0x00)] {
+			data.extend_from_slice(&[0xff, 0xc4, 0x00, 0x14, class, 1]);
+			data.extend_from_slice(&[0; 15]);
+			data.push(value);
+		}
+		data.extend_from_slice(&[0xff, 0xda, 0x00, 0x08, 0x01, 0x01, 0x00, 0x00, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xd9]);
+		
+		let e = decode(&data).unwrap_err();
+		assert_eq!(e.kind(), ErrorKind::InvalidData);
+		
+		// the same image with the category 8 decodes
+		let i = data.iter().position(|v| *v == 32).unwrap();
+		data[i] = 8;
+		let image = decode(&data).unwrap();
+		assert_eq!((image.width, image.height, image.channels), (8, 8, 1));
+	}
+}
@@ -22,30 +22,39 @@
 
 use {
 	crate::utils::*,
-	std::{io, fmt, marker::PhantomData},
-	serde::{*, de::{self, *}, ser::*, forward_to_deserialize_any}
+	std::{io, fmt},
+	serde::{*, de::{self, *}, ser::*}
 };
 
 pub fn serialize<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
 	value.serialize(&mut Serializer::new(writer))
 }
 
+/// Serializes the value with line breaks and tab indentation.
+pub fn serialize_pretty<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
+	value.serialize(&mut Serializer::pretty(writer))
+}
+
 pub fn deserialize<'de, T: Deserialize<'de>, R: io::BufRead + fmt::Debug>(reader: R) -> Result<T, DeError> {
-	T::deserialize(&mut Deserializer::new(reader))
+	let mut deserializer = Deserializer::new(reader);
+	T::deserialize(&mut deserializer)
+		.and_then(|v| deserializer.end().map(|_| v))
+		.map_err(|e| deserializer.locate(e))
 }
 
 pub fn deserialize_slice<'de, T: Deserialize<'de>>(slice: &[u8]) -> Result<T, DeError> {
 	deserialize(io::BufReader::new(slice))
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Value {
 	String(String),
-	Number(f64),
+	Number(Number),
 	Object(std::collections::HashMap<String, Self>),
 	Array(Vec<Self>),
 	Bool(bool),
+	#[default]
 	Null
 }
 
@@ -59,7 +68,21 @@ impl Value {
 	
 	pub fn as_number(&self) -> Option<f64> {
 		match self {
-			Self::Number(v) => Some(*v),
+			Self::Number(v) => Some(v.as_f64()),
+			_ => None
+		}
+	}
+	
+	pub fn as_u64(&self) -> Option<u64> {
+		match self {
+			Self::Number(v) => v.as_u64(),
+			_ => None
+		}
+	}
+	
+	pub fn as_i64(&self) -> Option<i64> {
+		match self {
+			Self::Number(v) => v.as_i64(),
 			_ => None
 		}
 	}
@@ -90,12 +113,6 @@ impl Value {
 	}
 }
 
-impl Default for Value {
-	fn default() -> Self {
-		Self::Null
-	}
-}
-
 impl From<String> for Value {
 	fn from(v: String) -> Self {
 		Self::String(v)
@@ -104,6 +121,24 @@ impl From<String> for Value {
 
 impl From<f64> for Value {
 	fn from(v: f64) -> Self {
+		Self::Number(Number::Float(v))
+	}
+}
+
+impl From<u64> for Value {
+	fn from(v: u64) -> Self {
+		Self::Number(Number::PosInt(v))
+	}
+}
+
+impl From<i64> for Value {
+	fn from(v: i64) -> Self {
+		Self::Number(v.into())
+	}
+}
+
+impl From<Number> for Value {
+	fn from(v: Number) -> Self {
 		Self::Number(v)
 	}
 }
@@ -132,18 +167,214 @@ impl From<()> for Value {
 	}
 }
 
+/// A JSON number. Integers are kept exact if they fit into an `u64` or `i64`, all other
+/// numbers are parsed as correctly rounded `f64`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Number {
+	PosInt(u64),
+	/// Always negative.
+	NegInt(i64),
+	Float(f64)
+}
+
+impl Number {
+	pub fn as_u64(&self) -> Option<u64> {
+		match *self {
+			Self::PosInt(v) => Some(v),
+			_ => None
+		}
+	}
+	
+	pub fn as_i64(&self) -> Option<i64> {
+		match *self {
+			Self::PosInt(v) => i64::try_from(v).ok(),
+			Self::NegInt(v) => Some(v),
+			_ => None
+		}
+	}
+	
+	pub fn as_f64(&self) -> f64 {
+		match *self {
+			Self::PosInt(v) => v as _,
+			Self::NegInt(v) => v as _,
+			Self::Float(v)  => v
+		}
+	}
+	
+	fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+		match self {
+			Self::PosInt(v) => visitor.visit_u64(v),
+			Self::NegInt(v) => visitor.visit_i64(v),
+			Self::Float(v)  => visitor.visit_f64(v)
+		}
+	}
+}
+
+impl From<i64> for Number {
+	fn from(v: i64) -> Self {
+		match v {
+			0.. => Self::PosInt(v as _),
+			_   => Self::NegInt(v)
+		}
+	}
+}
+
+impl fmt::Display for Number {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::PosInt(v) => fmt::Display::fmt(v, f),
+			Self::NegInt(v) => fmt::Display::fmt(v, f),
+			Self::Float(v)  => fmt::Display::fmt(v, f)
+		}
+	}
+}
+
+impl Serialize for Number {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match *self {
+			Self::PosInt(v) => serializer.serialize_u64(v),
+			Self::NegInt(v) => serializer.serialize_i64(v),
+			Self::Float(v)  => serializer.serialize_f64(v)
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Number {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct NumberVisitor;
+		
+		impl<'de> Visitor<'de> for NumberVisitor {
+			type Value = Number;
+			
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a number")
+			}
+			
+			fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+				Ok(Number::PosInt(v))
+			}
+			
+			fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+				Ok(v.into())
+			}
+			
+			fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+				Ok(Number::Float(v))
+			}
+		}
+		
+		deserializer.deserialize_any(NumberVisitor)
+	}
+}
+
 pub struct Serializer<T: io::Write> {
 	writer: T,
-	first:  bool
+	first:  bool,
+	indent: Option<String>,
+	depth:  usize
 }
 
 impl<T: io::Write> Serializer<T> {
 	pub fn new(writer: T) -> Self {
-		Self { writer, first: false }
+		Self { writer, first: false, indent: None, depth: 0 }
+	}
+	
+	/// Creates a serializer that puts every element on its own line, indented by tabs.
+	pub fn pretty(writer: T) -> Self {
+		Self::with_indent(writer, "\t")
+	}
+	
+	pub fn with_indent(writer: T, indent: impl Into<String>) -> Self {
+		Self { writer, first: false, indent: Some(indent.into()), depth: 0 }
+	}
+	
+	pub fn into_inner(self) -> T {
+		self.writer
+	}
+	
+	fn newline(&mut self) -> io::Result<()> {
+		if let Some(indent) = &self.indent {
+			self.writer.write_all(b"\n")?;
+			for _ in 0..self.depth {
+				self.writer.write_all(indent.as_bytes())?;
+			}
+		}
+		Ok(())
+	}
+	
+	fn begin(&mut self, ch: u8) -> io::Result<()> {
+		self.writer.write_all(&[ch])?;
+		self.depth += 1;
+		self.first = true;
+		Ok(())
+	}
+	
+	fn element(&mut self) -> io::Result<()> {
+		if !self.first {
+			self.writer.write_all(b",")?;
+		}
+		self.first = false;
+		self.newline()
+	}
+	
+	fn end(&mut self, ch: u8) -> io::Result<()> {
+		self.depth -= 1;
+		if !self.first {
+			self.newline()?;
+		}
+		self.first = false;
+		self.writer.write_all(&[ch])
+	}
+	
+	fn colon(&mut self) -> io::Result<()> {
+		self.writer.write_all(match self.indent {
+			Some(_) => b": ",
+			None    => b":"
+		})
+	}
+	
+	/// Begins an object with a single key, as used for enum variants.
+	fn begin_variant(&mut self, variant: &str) -> Result<(), SerError> {
+		self.begin(b'{')?;
+		self.element()?;
+		write_string(&mut self.writer, variant)?;
+		self.colon()?;
+		Ok(())
 	}
 }
 
-impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
+fn write_string(mut writer: impl io::Write, v: &str) -> io::Result<()> {
+	writer.write_all(b"\"")?;
+	let mut start = 0;
+	
+	for (i, ch) in v.bytes().enumerate() {
+		let escape: &[u8] = match ch {
+			b'"'  => b"\\\"",
+			b'\\' => b"\\\\",
+			b'\n' => b"\\n",
+			b'\r' => b"\\r",
+			b'\t' => b"\\t",
+			0x08  => b"\\b",
+			0x0C  => b"\\f",
+			0x00..=0x1F => {
+				writer.write_all(&v.as_bytes()[start..i])?;
+				write!(writer, "\\u{:04x}", ch)?;
+				start = i + 1;
+				continue;
+			}
+			_ => continue
+		};
+		
+		writer.write_all(&v.as_bytes()[start..i])?;
+		writer.write_all(escape)?;
+		start = i + 1;
+	}
+	
+	writer.write_all(&v.as_bytes()[start..])?;
+	writer.write_all(b"\"")
+}
+
+impl<T: io::Write> serde::Serializer for &mut Serializer<T> {
 	type Ok                     = ();
 	type Error                  = SerError;
 	type SerializeSeq           = Self;
@@ -194,19 +425,23 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 	
 	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		self.serialize_f64(v as _)
 	}
 	
 	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		// JSON has no representation for NaN and infinity
+		match v.is_finite() {
+			true  => write!(&mut self.writer, "{}", v).map_err(Into::into),
+			false => self.serialize_unit()
+		}
 	}
 	
 	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "\"{}\"", v).map_err(Into::into)
+		self.serialize_str(v.encode_utf8(&mut [0u8; 4]))
 	}
 	
 	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "\"{}\"", v.replace("\"", "\\\"")).map_err(Into::into)
+		write_string(&mut self.writer, v).map_err(Into::into)
 	}
 	
 	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
@@ -240,17 +475,14 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 	
 	fn serialize_newtype_variant<S: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &S) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"{\"")?;
-		self.writer.write_all(variant.as_bytes())?;
-		self.writer.write_all(b"\":")?;
+		self.begin_variant(variant)?;
 		value.serialize(&mut*self)?;
-		self.writer.write_all(b"}")?;
+		self.end(b'}')?;
 		Ok(())
 	}
 	
 	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-		self.first = true;
-		self.writer.write_all(b"[")?;
+		self.begin(b'[')?;
 		Ok(self)
 	}
 	
@@ -263,15 +495,13 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 	
 	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
-		self.writer.write_all(b"{\"")?;
-		self.writer.write_all(variant.as_bytes())?;
-		self.writer.write_all(b"\":[")?;
+		self.begin_variant(variant)?;
+		self.begin(b'[')?;
 		Ok(self)
 	}
 	
 	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		self.first = true;
-		self.writer.write_all(b"{")?;
+		self.begin(b'{')?;
 		Ok(self)
 	}
 	
@@ -280,9 +510,8 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 	
 	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
-		self.writer.write_all(b"{\"")?;
-		self.writer.write_all(variant.as_bytes())?;
-		self.writer.write_all(b"\":{")?;
+		self.begin_variant(variant)?;
+		self.begin(b'{')?;
 		Ok(self)
 	}
 	
@@ -291,27 +520,22 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeSeq for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeSeq for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
 	fn serialize_element<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
-		if !self.first {
-			self.writer.write_all(b",")?;
-		} else {
-			self.first = false;
-		}
-		
+		self.element()?;
 		value.serialize(&mut**self)
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"]")?;
+		Serializer::end(self, b']')?;
 		Ok(())
 	}
 }
 
-impl<'a, T: io::Write> SerializeTuple for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeTuple for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
@@ -324,7 +548,7 @@ impl<'a, T: io::Write> SerializeTuple for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeTupleStruct for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeTupleStruct for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
@@ -337,7 +561,7 @@ impl<'a, T: io::Write> SerializeTupleStruct for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeTupleVariant for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeTupleVariant for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
@@ -346,37 +570,33 @@ impl<'a, T: io::Write> SerializeTupleVariant for &'a mut Serializer<T> {
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"]}")?;
+		Serializer::end(self, b']')?;
+		Serializer::end(self, b'}')?;
 		Ok(())
 	}
 }
 
-impl<'a, T: io::Write> SerializeMap for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeMap for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
 	fn serialize_key<S: ?Sized + Serialize>(&mut self, key: &S) -> Result<(), Self::Error> {
-		if !self.first {
-			self.writer.write_all(b",")?;
-		} else {
-			self.first = false;
-		}
-		
-		key.serialize(&mut**self)
+		self.element()?;
+		key.serialize(MapKeySerializer(&mut**self))
 	}
 	
 	fn serialize_value<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
-		self.writer.write_all(b":")?;
+		self.colon()?;
 		value.serialize(&mut**self)
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"}")?;
+		Serializer::end(self, b'}')?;
 		Ok(())
 	}
 }
 
-impl<'a, T: io::Write> SerializeStruct for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeStruct for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
@@ -389,7 +609,7 @@ impl<'a, T: io::Write> SerializeStruct for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeStructVariant for &'a mut Serializer<T> {
+impl<T: io::Write> SerializeStructVariant for &mut Serializer<T> {
 	type Ok    = ();
 	type Error = SerError;
 	
@@ -398,11 +618,119 @@ impl<'a, T: io::Write> SerializeStructVariant for &'a mut Serializer<T> {
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"}}")?;
+		Serializer::end(self, b'}')?;
+		Serializer::end(self, b'}')?;
 		Ok(())
 	}
 }
 
+/// Object keys must be strings, other primitive keys are quoted.
+struct MapKeySerializer<'a, T: io::Write>(&'a mut Serializer<T>);
+
+macro_rules! serialize_quoted {
+	( $( $fn:ident, $ty:ty; )* ) => {
+		$(
+			fn $fn(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+				write!(&mut self.0.writer, "\"{}\"", v).map_err(Into::into)
+			}
+		)*
+	};
+}
+
+impl<'a, T: io::Write> serde::Serializer for MapKeySerializer<'a, T> {
+	type Ok                     = ();
+	type Error                  = SerError;
+	type SerializeSeq           = Impossible<(), SerError>;
+	type SerializeTuple         = Impossible<(), SerError>;
+	type SerializeTupleStruct   = Impossible<(), SerError>;
+	type SerializeTupleVariant  = Impossible<(), SerError>;
+	type SerializeMap           = Impossible<(), SerError>;
+	type SerializeStruct        = Impossible<(), SerError>;
+	type SerializeStructVariant = Impossible<(), SerError>;
+	
+	serialize_quoted!(
+		serialize_bool, bool;
+		serialize_i8, i8;
+		serialize_i16, i16;
+		serialize_i32, i32;
+		serialize_i64, i64;
+		serialize_u8, u8;
+		serialize_u16, u16;
+		serialize_u32, u32;
+		serialize_u64, u64;
+		serialize_f32, f32;
+		serialize_f64, f64;
+	);
+	
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_char(v)
+	}
+	
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_str(v)
+	}
+	
+	fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_some<S: ?Sized + Serialize>(self, value: &S) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_str(variant)
+	}
+	
+	fn serialize_newtype_struct<S: ?Sized + Serialize>(self, _name: &'static str, value: &S) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_newtype_variant<S: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &S) -> Result<Self::Ok, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+	
+	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Err(SerError::UnsupportedType)
+	}
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Expected {
 	MapComma,
@@ -412,7 +740,10 @@ pub enum Expected {
 	SeqEnd,
 	Key,
 	Value,
-	EnumVariant
+	EnumVariant,
+	Digit,
+	Escape,
+	EndOfFile
 }
 
 impl std::fmt::Display for Expected {
@@ -421,165 +752,454 @@ impl std::fmt::Display for Expected {
 	}
 }
 
+/// A token of a JSON document, as returned by the `Reader`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+	StartObject,
+	EndObject,
+	StartArray,
+	EndArray,
+	Key(String),
+	String(String),
+	Number(Number),
+	Bool(bool),
+	Null
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum State {
+	/// Expecting a value, at the start of the document or after a key.
+	Value,
+	/// Expecting a value or the end of the array.
+	ArrayFirst,
+	/// Expecting a comma or the end of the array.
+	ArrayNext,
+	/// Expecting a key or the end of the object.
+	ObjectFirst,
+	/// Expecting a comma or the end of the object.
+	ObjectNext,
+	/// The top level value has been read.
+	Done
+}
+
+/// A pull parser that reads a document event by event, without building a tree of values.
+/// The syntax is fully validated, malformed documents result in an error at the offending
+/// position.
 #[derive(Debug)]
-pub struct Deserializer<T: io::BufRead> {
-	reader: T,
-	first:  bool
+pub struct Reader<T: io::BufRead> {
+	reader:  T,
+	/// The containers that are currently open, `true` for objects.
+	stack:   Vec<bool>,
+	state:   State,
+	peeked:  Option<Event>,
+	line:    usize,
+	column:  usize,
+	/// Position of the start of the last event.
+	token:   (usize, usize)
 }
 
-impl<T: io::BufRead> Deserializer<T> {
+impl<T: io::BufRead> Reader<T> {
 	pub fn new(mut reader: T) -> Self {
 		// consume magic bytes for unicode text files
 		if reader.fill_buf().unwrap_or(&[]).starts_with(&UNICODE_FILE_MAGIC_BYTES) {
 			reader.consume(3);
 		}
 		
-		Self { reader, first: true }
+		Self { reader, stack: Vec::new(), state: State::Value, peeked: None, line: 1, column: 1, token: (1, 1) }
+	}
+	
+	/// Returns the line and column of the start of the last event.
+	pub fn position(&self) -> (usize, usize) {
+		self.token
 	}
 	
-	fn peek(&mut self) -> io::Result<u8> {
-		match self.reader.fill_buf()? {
-			[ch, ..] => Ok(*ch),
-			_ => Err(io::Error::new(io::ErrorKind::UnexpectedEof, ""))
+	/// Returns the number of objects and arrays that are currently open.
+	pub fn depth(&self) -> usize {
+		self.stack.len() - self.peeked.as_ref().map_or(0, |e| matches!(e, Event::StartObject | Event::StartArray) as usize)
+	}
+	
+	pub fn into_inner(self) -> T {
+		self.reader
+	}
+	
+	/// Returns the next event, or `None` after the end of the document.
+	pub fn next_event(&mut self) -> Result<Option<Event>, DeError> {
+		match self.peeked.take() {
+			Some(event) => Ok(Some(event)),
+			None => self.read_event().map_err(|e| e.at(self.token.0, self.token.1))
+		}
+	}
+	
+	pub fn peek_event(&mut self) -> Result<Option<&Event>, DeError> {
+		if self.peeked.is_none() {
+			self.peeked = self.read_event().map_err(|e| e.at(self.token.0, self.token.1))?;
 		}
+		Ok(self.peeked.as_ref())
 	}
 	
-	fn next(&mut self) -> io::Result<u8> {
+	/// Skips the next value, including all nested values.
+	pub fn skip_value(&mut self) -> Result<(), DeError> {
+		let mut depth = 0usize;
 		loop {
-			match self.reader.fill_buf()? {
-				[ch, ..] if ch.is_ascii_whitespace() => self.reader.consume(1),
-				[ch, ..] => {
-					let ch = *ch;
-					self.reader.consume(1);
-					return Ok(ch)
-				}
-				[] => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, ""))
+			match self.next_event()? {
+				Some(Event::StartObject | Event::StartArray) => depth += 1,
+				Some(Event::EndObject | Event::EndArray) => depth -= 1,
+				Some(Event::Key(_)) => continue,
+				Some(_) => (),
+				None => return Err(DeError::invalid_token("EOF", Expected::Value).at(self.line, self.column))
+			}
+			
+			if depth == 0 {
+				return Ok(());
 			}
 		}
 	}
-}
-
-macro_rules! visit_number {
-	( $( $de_fn:ident, $visit_fn:ident; )* ) => {
-		$( visit_number!($de_fn, $visit_fn); )*
-	};
-    ($de_fn:ident, $visit_fn:ident) => {
-		fn $de_fn<V>(self, visitor: V) -> Result<V::Value, Self::Error> where
-			V: Visitor<'de> {
-			struct NumberVisitor<'de, V: Visitor<'de>>(V, PhantomData<&'de ()>);
-			
-			impl<'de, V: Visitor<'de>> Visitor<'de> for NumberVisitor<'de, V> {
-				type Value = V::Value;
-				
-				fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-					self.0.expecting(formatter)
-				}
-				
-				fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
-					self.0.$visit_fn(v as _)
+	
+	fn peek(&mut self) -> Result<Option<u8>, DeError> {
+		Ok(self.reader.fill_buf()?.first().copied())
+	}
+	
+	fn bump(&mut self) -> Result<Option<u8>, DeError> {
+		let ch = self.peek()?;
+		if let Some(ch) = ch {
+			self.reader.consume(1);
+			match ch {
+				b'\n' => {
+					self.line += 1;
+					self.column = 1;
 				}
+				// utf-8 continuation bytes don't start a new column
+				0x80..=0xBF => (),
+				_ => self.column += 1
 			}
-			
-			self.deserialize_any(NumberVisitor(visitor, PhantomData))
 		}
-    };
-}
-
-impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<T> {
-	type Error = DeError;
+		Ok(ch)
+	}
 	
-	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		return match self.next()? {
-			b'"' => {
-				let mut buf = Vec::new();
-				self.reader.read_until(b'"', &mut buf)?;
-				match buf.as_slice() {
-					[.., b'"'] => {
-						buf.truncate(buf.len() - 1);
-						visitor.visit_string(String::from_utf8(buf).map_err(|e| e.utf8_error())?)
-					}
-					_ => Err(DeError::invalid_token("EOF", Expected::Value))
+	fn skip_ws(&mut self) -> Result<Option<u8>, DeError> {
+		loop {
+			match self.peek()? {
+				Some(b' ' | b'\t' | b'\n' | b'\r') => { self.bump()?; }
+				ch => {
+					self.token = (self.line, self.column);
+					return Ok(ch);
 				}
 			}
-			b'{' => {
-				self.first = true;
-				visitor.visit_map(self)
-			}
-			b'[' => {
-				self.first = true;
-				visitor.visit_seq(self)
+		}
+	}
+	
+	fn unexpected(&self, ch: Option<u8>, exp: Expected) -> DeError {
+		match ch {
+			Some(ch) if ch.is_ascii_graphic() => DeError::invalid_token(ch as char, exp),
+			Some(ch) => DeError::invalid_token(format_args!("{:#04x}", ch), exp),
+			None => DeError::invalid_token("EOF", exp)
+		}
+	}
+	
+	fn expect(&mut self, ch: u8, exp: Expected) -> Result<(), DeError> {
+		match self.bump()? {
+			Some(v) if v == ch => Ok(()),
+			v => Err(self.unexpected(v, exp))
+		}
+	}
+	
+	/// Called after a value was read, to continue with the enclosing container.
+	fn end_value(&mut self) {
+		self.state = match self.stack.last() {
+			None        => State::Done,
+			Some(true)  => State::ObjectNext,
+			Some(false) => State::ArrayNext
+		};
+	}
+	
+	fn end_container(&mut self, event: Event) -> Result<Option<Event>, DeError> {
+		self.bump()?;
+		self.stack.pop();
+		self.end_value();
+		Ok(Some(event))
+	}
+	
+	fn read_event(&mut self) -> Result<Option<Event>, DeError> {
+		let ch = self.skip_ws()?;
+		match (self.state, ch) {
+			(State::Done, None) => Ok(None),
+			(State::Done, ch) => Err(self.unexpected(ch, Expected::EndOfFile)),
+			(State::Value, _) => self.read_value(),
+			(State::ArrayFirst, Some(b']')) | (State::ArrayNext, Some(b']')) => self.end_container(Event::EndArray),
+			(State::ArrayFirst, _) => self.read_value(),
+			(State::ArrayNext, Some(b',')) => {
+				self.bump()?;
+				self.skip_ws()?;
+				self.read_value()
 			}
-			b't'  => {
-				let mut buf = [0u8; 3];
-				let _ = self.reader.read(&mut buf)?;
-				match &buf {
-					b"rue" => visitor.visit_bool(true),
-					_ => Err(DeError::invalid_token("\u{FFFD}", Expected::Value))
-				}
+			(State::ArrayNext, ch) => Err(self.unexpected(ch, Expected::SeqComma)),
+			(State::ObjectFirst, Some(b'}')) | (State::ObjectNext, Some(b'}')) => self.end_container(Event::EndObject),
+			(State::ObjectFirst, _) => self.read_key(),
+			(State::ObjectNext, Some(b',')) => {
+				self.bump()?;
+				self.skip_ws()?;
+				self.read_key()
 			}
-			b'f'  => {
-				let mut buf = [0u8; 4];
-				let _ = self.reader.read(&mut buf)?;
-				match &buf {
-					b"alse" => visitor.visit_bool(true),
-					_ => Err(DeError::invalid_token("\u{FFFD}", Expected::Value))
+			(State::ObjectNext, ch) => Err(self.unexpected(ch, Expected::MapComma))
+		}
+	}
+	
+	fn read_key(&mut self) -> Result<Option<Event>, DeError> {
+		match self.peek()? {
+			Some(b'"') => {
+				let key = self.read_string()?;
+				let (line, column) = self.token;
+				let ch = self.skip_ws()?;
+				if ch != Some(b':') {
+					return Err(self.unexpected(ch, Expected::MapColon));
 				}
+				self.bump()?;
+				self.token = (line, column);
+				self.state = State::Value;
+				Ok(Some(Event::Key(key)))
 			}
-			b'n' => {
-				let mut buf = [0u8; 3];
-				let _ = self.reader.read(&mut buf)?;
-				match &buf {
-					b"ull" => visitor.visit_unit(),
-					_ => Err(DeError::invalid_token("\u{FFFD}", Expected::Value))
-				}
+			ch => Err(self.unexpected(ch, Expected::Key))
+		}
+	}
+	
+	fn read_value(&mut self) -> Result<Option<Event>, DeError> {
+		let event = match self.peek()? {
+			Some(b'{') => {
+				self.bump()?;
+				self.stack.push(true);
+				self.state = State::ObjectFirst;
+				return Ok(Some(Event::StartObject));
 			}
-			b'0'..=b'9' | b'-' => {
-				unimplemented!("number parsing")
+			Some(b'[') => {
+				self.bump()?;
+				self.stack.push(false);
+				self.state = State::ArrayFirst;
+				return Ok(Some(Event::StartArray));
 			}
-			ch => Err(DeError::invalid_token(ch, Expected::Value))
+			Some(b'"') => Event::String(self.read_string()?),
+			Some(b't') => self.read_literal(b"true", Event::Bool(true))?,
+			Some(b'f') => self.read_literal(b"false", Event::Bool(false))?,
+			Some(b'n') => self.read_literal(b"null", Event::Null)?,
+			Some(b'-' | b'0'..=b'9') => Event::Number(self.read_number()?),
+			ch => return Err(self.unexpected(ch, Expected::Value))
+		};
+		
+		self.end_value();
+		Ok(Some(event))
+	}
+	
+	fn read_literal(&mut self, literal: &[u8], event: Event) -> Result<Event, DeError> {
+		for ch in literal {
+			self.expect(*ch, Expected::Value)?;
 		}
+		Ok(event)
 	}
 	
-	visit_number!(
-		deserialize_i8, visit_i8;
-		deserialize_i16, visit_i16;
-		deserialize_i32, visit_i32;
-		deserialize_i64, visit_i64;
-		deserialize_u8, visit_u8;
-		deserialize_u16, visit_u16;
-		deserialize_u32, visit_u32;
-		deserialize_u64, visit_u64;
-		deserialize_f32, visit_f32;
-	);
+	fn read_digits(&mut self, buf: &mut String) -> Result<(), DeError> {
+		let start = buf.len();
+		while let Some(ch @ b'0'..=b'9') = self.peek()? {
+			buf.push(ch as char);
+			self.bump()?;
+		}
+		
+		match buf.len() == start {
+			true  => {
+				let ch = self.peek()?;
+				Err(self.unexpected(ch, Expected::Digit).at(self.line, self.column))
+			}
+			false => Ok(())
+		}
+	}
 	
-	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		struct CharVisitor<'de, V: Visitor<'de>>(V, PhantomData<&'de ()>);
+	fn read_number(&mut self) -> Result<Number, DeError> {
+		let mut buf = String::new();
+		if self.peek()? == Some(b'-') {
+			buf.push('-');
+			self.bump()?;
+		}
 		
-		impl<'de, V: Visitor<'de>> Visitor<'de> for CharVisitor<'de, V> {
-			type Value = V::Value;
-			
-			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-				self.0.expecting(formatter)
+		match self.peek()? {
+			Some(b'0') => {
+				buf.push('0');
+				self.bump()?;
 			}
-			
-			fn visit_string<E>(self, v: String) -> Result<Self::Value, E> where
-				E: de::Error, {
-				match v.chars().count() {
-					1 => self.0.visit_char(v.chars().next().unwrap()),
-					_ => Err(E::invalid_type(Unexpected::Str(v.as_str()), &self))
-				}
+			_ => self.read_digits(&mut buf)?
+		}
+		
+		let mut float = false;
+		if self.peek()? == Some(b'.') {
+			float = true;
+			buf.push('.');
+			self.bump()?;
+			self.read_digits(&mut buf)?;
+		}
+		
+		if let Some(b'e' | b'E') = self.peek()? {
+			float = true;
+			buf.push('e');
+			self.bump()?;
+			if let Some(ch @ (b'+' | b'-')) = self.peek()? {
+				buf.push(ch as char);
+				self.bump()?;
 			}
-			
-			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where
-				E: de::Error, {
-				match v.chars().count() {
-					1 => self.0.visit_char(v.chars().next().unwrap()),
-					_ => Err(E::invalid_type(Unexpected::Str(v), &self))
+			self.read_digits(&mut buf)?;
+		}
+		
+		// integers that don't fit into 64 bits fall back to floats
+		if !float {
+			if let Ok(v) = buf.parse::<u64>() {
+				return Ok(Number::PosInt(v));
+			} else if let Ok(v) = buf.parse::<i64>() {
+				// `-0` has no integer representation, the sign is kept by a float
+				return Ok(match v {
+					0 => Number::Float(-0.0),
+					v => v.into()
+				});
+			}
+		}
+		
+		// `str::parse` is correctly rounded, numbers out of range become infinite
+		match buf.parse::<f64>() {
+			Ok(v) if v.is_finite() => Ok(Number::Float(v)),
+			Ok(_) => Err(DeError::ParseError(format!("number `{}` out of range", buf))),
+			Err(e) => Err(DeError::ParseError(e.to_string()))
+		}
+	}
+	
+	fn read_hex4(&mut self) -> Result<u16, DeError> {
+		let mut v = 0;
+		for _ in 0..4 {
+			let ch = self.bump()?;
+			v = v * 16 + match ch.map(|ch| (ch as char).to_digit(16)) {
+				Some(Some(digit)) => digit as u16,
+				_ => return Err(self.unexpected(ch, Expected::Escape))
+			};
+		}
+		Ok(v)
+	}
+	
+	fn read_string(&mut self) -> Result<String, DeError> {
+		self.bump()?;
+		let mut buf = Vec::new();
+		
+		loop {
+			let (line, column) = (self.line, self.column);
+			match self.bump()? {
+				Some(b'"') => break,
+				Some(b'\\') => match self.bump()? {
+					Some(b'"')  => buf.push(b'"'),
+					Some(b'\\') => buf.push(b'\\'),
+					Some(b'/')  => buf.push(b'/'),
+					Some(b'b')  => buf.push(0x08),
+					Some(b'f')  => buf.push(0x0C),
+					Some(b'n')  => buf.push(b'\n'),
+					Some(b'r')  => buf.push(b'\r'),
+					Some(b't')  => buf.push(b'\t'),
+					Some(b'u')  => {
+						let mut code = self.read_hex4()? as u32;
+						// characters outside the BMP are encoded as surrogate pairs
+						if (0xD800..0xDC00).contains(&code) {
+							self.expect(b'\\', Expected::Escape)?;
+							self.expect(b'u', Expected::Escape)?;
+							let low = self.read_hex4()? as u32;
+							if !(0xDC00..0xE000).contains(&low) {
+								return Err(DeError::ParseError("invalid surrogate pair".to_string()).at(line, column));
+							}
+							code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+						}
+						
+						match char::from_u32(code) {
+							Some(ch) => buf.extend_from_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes()),
+							None => return Err(DeError::ParseError("invalid unicode escape".to_string()).at(line, column))
+						}
+					}
+					ch => return Err(self.unexpected(ch, Expected::Escape).at(line, column))
 				}
+				Some(ch @ 0x00..=0x1F) => return Err(DeError::ParseError(format!("unescaped control character {:#04x} in string", ch)).at(line, column)),
+				Some(ch) => buf.push(ch),
+				None => return Err(self.unexpected(None, Expected::Value).at(line, column))
 			}
 		}
 		
-		self.deserialize_any(CharVisitor(visitor, PhantomData))
+		String::from_utf8(buf).map_err(Into::into)
+	}
+}
+
+impl<T: io::BufRead> Iterator for Reader<T> {
+	type Item = Result<Event, DeError>;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_event().transpose()
+	}
+}
+
+#[derive(Debug)]
+pub struct Deserializer<T: io::BufRead> {
+	reader: Reader<T>
+}
+
+impl<T: io::BufRead> Deserializer<T> {
+	pub fn new(reader: T) -> Self {
+		Self { reader: Reader::new(reader) }
+	}
+	
+	/// Checks that there is no trailing data after the top level value.
+	pub fn end(&mut self) -> Result<(), DeError> {
+		match self.reader.next_event()? {
+			None => Ok(()),
+			Some(_) => Err(DeError::invalid_token("value", Expected::EndOfFile))
+		}
+	}
+	
+	/// Attaches the position of the current value to errors that were returned by a visitor.
+	fn locate(&self, error: DeError) -> DeError {
+		let (line, column) = self.reader.position();
+		error.at(line, column)
+	}
+	
+	fn next(&mut self) -> Result<Event, DeError> {
+		match self.reader.next_event()? {
+			Some(event) => Ok(event),
+			None => Err(DeError::invalid_token("EOF", Expected::Value))
+		}
+	}
+	
+	fn end_seq(&mut self) -> Result<(), DeError> {
+		match self.next()? {
+			Event::EndArray => Ok(()),
+			_ => Err(DeError::invalid_token("value", Expected::SeqEnd))
+		}
+	}
+	
+	fn end_map(&mut self) -> Result<(), DeError> {
+		match self.next()? {
+			Event::EndObject => Ok(()),
+			_ => Err(DeError::invalid_token("key", Expected::MapEnd))
+		}
+	}
+}
+
+impl<'de, T: io::BufRead> serde::Deserializer<'de> for &mut Deserializer<T> {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.next()? {
+			Event::StartObject => {
+				let v = visitor.visit_map(&mut*self).map_err(|e| self.locate(e))?;
+				self.end_map()?;
+				Ok(v)
+			}
+			Event::StartArray => {
+				let v = visitor.visit_seq(&mut*self).map_err(|e| self.locate(e))?;
+				self.end_seq()?;
+				Ok(v)
+			}
+			Event::String(v) => visitor.visit_string(v),
+			Event::Number(v) => v.visit(visitor),
+			Event::Bool(v)   => visitor.visit_bool(v),
+			Event::Null      => visitor.visit_unit(),
+			Event::EndObject | Event::EndArray | Event::Key(_) => Err(DeError::invalid_token("end of container", Expected::Value))
+		}.map_err(|e| self.locate(e))
 	}
 	
 	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
@@ -587,22 +1207,17 @@ impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<
 	}
 	
 	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self.peek()? {
-			b'[' => visitor.visit_byte_buf(Vec::<u8>::deserialize(self)?),
+		match self.reader.peek_event()? {
+			Some(Event::StartArray) => visitor.visit_byte_buf(Vec::<u8>::deserialize(self)?),
 			_ => self.deserialize_any(visitor)
 		}
 	}
 	
 	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self.peek()? {
-			b'n' => {
-				self.reader.consume(1);
-				let mut buf = [0u8; 3];
-				let _ = self.reader.read(&mut buf)?;
-				match &buf {
-					b"ull" => visitor.visit_none(),
-					_ => Err(DeError::invalid_token("", Expected::Value))
-				}
+		match self.reader.peek_event()? {
+			Some(Event::Null) => {
+				self.next()?;
+				visitor.visit_none()
 			}
 			_ => visitor.visit_some(self)
 		}
@@ -613,94 +1228,72 @@ impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<
 	}
 	
 	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
-		match self.peek()? {
-			b'{' => {
-				self.reader.consume(1);
-				visitor.visit_enum(self)
+		match self.next()? {
+			Event::StartObject => {
+				let v = visitor.visit_enum(&mut*self)?;
+				self.end_map()?;
+				Ok(v)
 			},
-			b'"' => visitor.visit_enum(String::deserialize(self)?.into_deserializer()),
-			ch => Err(DeError::invalid_token(ch, Expected::EnumVariant))
-		}
+			Event::String(v) => visitor.visit_enum(v.into_deserializer()),
+			_ => Err(DeError::invalid_token("value", Expected::EnumVariant))
+		}.map_err(|e| self.locate(e))
 	}
 	
-	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if self.next()? != b'"' {
-			return Err(DeError::invalid_token("", Expected::Key))
-		}
-		
-		let mut buf = Vec::new();
-		self.reader.read_until(b'"', &mut buf)?;
-		let mut s = String::from_utf8(buf)?;
-		
-		if !s.ends_with('"') {
-			Err(DeError::invalid_token("EOF", Expected::Key))
-		} else {
-			s.truncate(s.len() - 1);
-			visitor.visit_string(s)
-		}
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.reader.skip_value()?;
+		visitor.visit_unit()
 	}
 	
 	fn is_human_readable(&self) -> bool {
 		true
 	}
 	
-	forward_to_deserialize_any!(f64 bool str string unit unit_struct seq tuple tuple_struct map struct ignored_any);
+	forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		unit unit_struct seq tuple tuple_struct map struct identifier);
 }
 
-impl<'a, 'de, T: io::BufRead> MapAccess<'de> for &'a mut Deserializer<T> {
+impl<'de, T: io::BufRead> MapAccess<'de> for &mut Deserializer<T> {
 	type Error = DeError;
 	
 	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
-		let ch = self.next()?;
-		
-		if ch == b'}' {
-			return Ok(None)
-		} else if !self.first && ch != b',' {
-			return Err(DeError::invalid_token(ch, Expected::MapComma))
+		match self.reader.peek_event()? {
+			Some(Event::Key(_)) => match self.next()? {
+				Event::Key(key) => seed.deserialize(MapKeyDeserializer(key)).map(Some),
+				_ => unreachable!()
+			},
+			_ => Ok(None)
 		}
-		
-		self.first = false;
-		seed.deserialize(&mut**self).map(Some)
 	}
 	
 	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
-		match self.next()? {
-			b':' => seed.deserialize(&mut**self),
-			ch => Err(DeError::invalid_token(ch, Expected::MapColon))
-		}
+		seed.deserialize(&mut**self)
 	}
 }
 
-impl<'a, 'de, T: io::BufRead> SeqAccess<'de> for &'a mut Deserializer<T> {
+impl<'de, T: io::BufRead> SeqAccess<'de> for &mut Deserializer<T> {
 	type Error = DeError;
 	
 	fn next_element_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<Option<V::Value>, Self::Error> {
-		let ch = self.next()?;
-		
-		if ch == b'}' {
-			return Ok(None)
-		} else if !self.first && ch != b',' {
-			return Err(DeError::invalid_token(ch, Expected::SeqComma))
+		match self.reader.peek_event()? {
+			Some(Event::EndArray) => Ok(None),
+			_ => seed.deserialize(&mut**self).map(Some)
 		}
-		
-		self.first = false;
-		seed.deserialize(&mut**self).map(Some)
 	}
 }
 
-impl<'a, 'de, T: io::BufRead> EnumAccess<'de> for &'a mut Deserializer<T> {
+impl<'de, T: io::BufRead> EnumAccess<'de> for &mut Deserializer<T> {
 	type Error   = DeError;
 	type Variant = Self;
 	
 	fn variant_seed<V: DeserializeSeed<'de>>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
 		match MapAccess::next_key_seed(&mut self, seed)? {
 			Some(key) => Ok((key, self)),
-			None => Err(DeError::Custom("expected an enum variant".to_string()))
+			None => Err(DeError::invalid_token("end of object", Expected::EnumVariant))
 		}
 	}
 }
 
-impl<'a, 'de, T: io::BufRead> VariantAccess<'de> for &'a mut Deserializer<T> {
+impl<'de, T: io::BufRead> VariantAccess<'de> for &mut Deserializer<T> {
 	type Error = DeError;
 	
 	fn unit_variant(self) -> Result<(), Self::Error> {
@@ -720,6 +1313,59 @@ impl<'a, 'de, T: io::BufRead> VariantAccess<'de> for &'a mut Deserializer<T> {
 	}
 }
 
+/// Object keys are always strings, keys of other types are parsed from the string.
+struct MapKeyDeserializer(String);
+
+macro_rules! deserialize_parsed {
+	( $( $de_fn:ident, $visit_fn:ident; )* ) => {
+		$(
+			fn $de_fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				match self.0.parse() {
+					Ok(v) => visitor.$visit_fn(v),
+					Err(_) => Err(DeError::invalid_token(self.0, Expected::Key))
+				}
+			}
+		)*
+	};
+}
+
+impl<'de> serde::Deserializer<'de> for MapKeyDeserializer {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_string(self.0)
+	}
+	
+	deserialize_parsed!(
+		deserialize_bool, visit_bool;
+		deserialize_i8, visit_i8;
+		deserialize_i16, visit_i16;
+		deserialize_i32, visit_i32;
+		deserialize_i64, visit_i64;
+		deserialize_u8, visit_u8;
+		deserialize_u16, visit_u16;
+		deserialize_u32, visit_u32;
+		deserialize_u64, visit_u64;
+		deserialize_f32, visit_f32;
+		deserialize_f64, visit_f64;
+	);
+	
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_enum(self.0.into_deserializer())
+	}
+	
+	forward_to_deserialize_any!(i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+		tuple_struct map struct identifier ignored_any);
+}
+
 #[cfg(test)]
 mod tests {
 	use serde::*;
@@ -759,4 +1405,106 @@ mod tests {
 		
 		assert_eq!(v, Struct { a: true, b: "test".to_string(), c: Struct2 { a: "test".to_string() } });
 	}
-}
\ No newline at end of file
+	
+	#[test]
+	fn numbers() {
+		assert_eq!(deserialize_slice::<u64>(b"18446744073709551615").unwrap(), u64::MAX);
+		assert_eq!(deserialize_slice::<i64>(b"-9223372036854775808").unwrap(), i64::MIN);
+		assert_eq!(deserialize_slice::<u8>(b"255").unwrap(), 255);
+		assert_eq!(deserialize_slice::<f64>(b"0.1").unwrap(), 0.1);
+		assert_eq!(deserialize_slice::<f64>(b"-1.5E-3").unwrap(), -1.5e-3);
+		assert_eq!(deserialize_slice::<f64>(b"2.2250738585072011e-308").unwrap(), 2.225_073_858_507_201e-308);
+		assert_eq!(deserialize_slice::<f32>(b"1e2").unwrap(), 100.0);
+		assert_eq!(deserialize_slice::<f64>(b"18446744073709551616").unwrap(), 18446744073709551616.0);
+		assert_eq!(deserialize_slice::<Vec<Number>>(b"[0, -0, 1, -1, 1.0]").unwrap(),
+			[Number::PosInt(0), Number::Float(-0.0), Number::PosInt(1), Number::NegInt(-1), Number::Float(1.0)]);
+		assert!(matches!(deserialize_slice::<Number>(b"-0").unwrap(), Number::Float(v) if v.is_sign_negative()));
+		assert!(matches!(deserialize_slice::<Number>(b"-0.0").unwrap(), Number::Float(v) if v.is_sign_negative()));
+		assert_eq!(deserialize_slice::<f64>(b"1e308").unwrap(), 1e308);
+		assert_eq!(deserialize_slice::<f64>(b"1e-400").unwrap(), 0.0);
+		assert!(deserialize_slice::<u8>(b"256").is_err());
+		
+		for src in ["01", "-", "1.", ".5", "1e", "+1", "0x10", "1.5.2", "1e400", "-1e400"] {
+			assert!(deserialize_slice::<f64>(src.as_bytes()).is_err(), "{}", src);
+		}
+		
+		assert!(deserialize_slice::<f64>(&[b'9'; 400]).is_err());
+		
+		for (src, pos) in [("1.", (1, 3)), ("1.x", (1, 3)), ("[1, -a]", (1, 6)), ("\n  1e+", (2, 6)), ("1E400", (1, 1))] {
+			let e = deserialize_slice::<Value>(src.as_bytes()).unwrap_err();
+			assert_eq!(e.position(), Some(pos), "{}", src);
+		}
+	}
+	
+	#[test]
+	fn strings() {
+		let s = "quote \" backslash \\ newline \n tab \t control \u{1} unicode \u{e9} \u{1f600}";
+		let mut buf = Vec::new();
+		serialize(&mut buf, &s).unwrap();
+		assert_eq!(deserialize_slice::<String>(&buf).unwrap(), s);
+		assert_eq!(deserialize_slice::<String>(br#""\u00e9\ud83d\ude00\/""#).unwrap(), "\u{e9}\u{1f600}/");
+		assert!(deserialize_slice::<String>(b"\"\\ud83d\"").is_err());
+		assert!(deserialize_slice::<String>(b"\"a\nb\"").is_err());
+	}
+	
+	#[test]
+	fn errors() {
+		let e = deserialize_slice::<Struct>(b"{\n\t\"a\": true,\n\t\"b\": \"test\"\n\t\"c\": {}\n}").unwrap_err();
+		assert_eq!(e.position(), Some((4, 2)));
+		let e = deserialize_slice::<Struct>(b"{\n\t\"a\": 1,\n\t\"b\": \"\", \"c\": { \"a\": \"\" }\n}").unwrap_err();
+		assert_eq!(e.position(), Some((2, 7)));
+		let e = deserialize_slice::<Vec<u32>>(b"[1, 2] 3").unwrap_err();
+		assert_eq!(e.position(), Some((1, 8)));
+		assert!(deserialize_slice::<Vec<u32>>(b"[1, 2,]").is_err());
+		assert!(deserialize_slice::<bool>(b"fals").is_err());
+	}
+	
+	#[test]
+	fn pretty() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		enum Enum {
+			Unit,
+			Tuple(u32, u32),
+			Struct { a: Vec<u32> }
+		}
+		
+		let v = (vec![Enum::Unit, Enum::Tuple(1, 2), Enum::Struct { a: vec![] }], std::collections::BTreeMap::from([(1u32, None::<f32>)]));
+		let mut buf = Vec::new();
+		serialize_pretty(&mut buf, &v).unwrap();
+		assert_eq!(std::str::from_utf8(&buf).unwrap(), "[\n\t[\n\t\t\"Unit\",\n\t\t{\n\t\t\t\"Tuple\": [\n\t\t\t\t1,\n\t\t\t\t2\n\t\t\t]\n\t\t},\n\t\t\
+			{\n\t\t\t\"Struct\": {\n\t\t\t\t\"a\": []\n\t\t\t}\n\t\t}\n\t],\n\t{\n\t\t\"1\": null\n\t}\n]");
+		assert_eq!(deserialize_slice::<(Vec<Enum>, std::collections::BTreeMap<u32, Option<f32>>)>(&buf).unwrap(), v);
+		
+		let mut compact = Vec::new();
+		serialize(&mut compact, &v).unwrap();
+		assert_eq!(std::str::from_utf8(&compact).unwrap(), r#"[["Unit",{"Tuple":[1,2]},{"Struct":{"a":[]}}],{"1":null}]"#);
+	}
+	
+	#[test]
+	fn reader() {
+		let mut reader = Reader::new(r#"{ "a": [1, {"b": null}], "c": "d" }"#.as_bytes());
+		assert_eq!(reader.next_event().unwrap(), Some(Event::StartObject));
+		assert_eq!(reader.next_event().unwrap(), Some(Event::Key("a".to_string())));
+		reader.skip_value().unwrap();
+		assert_eq!(reader.next_event().unwrap(), Some(Event::Key("c".to_string())));
+		assert_eq!(reader.position(), (1, 26));
+		assert_eq!(reader.depth(), 1);
+		assert_eq!(reader.collect::<Result<Vec<_>, _>>().unwrap(), [Event::String("d".to_string()), Event::EndObject]);
+		
+		let events = Reader::new("[true, false, -2]".as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(events, [Event::StartArray, Event::Bool(true), Event::Bool(false), Event::Number(Number::NegInt(-2)), Event::EndArray]);
+		
+		let e = Reader::new("[1 2]".as_bytes()).collect::<Result<Vec<_>, _>>().unwrap_err();
+		assert_eq!(e.position(), Some((1, 4)));
+	}
+	
+	#[test]
+	fn value() {
+		let v = deserialize_slice::<Value>(br#"{"a": [1, -2, 3.5, "x", true, null]}"#).unwrap();
+		let a = v.as_object().unwrap()["a"].as_array().unwrap();
+		assert_eq!(a[0].as_u64(), Some(1));
+		assert_eq!(a[1].as_i64(), Some(-2));
+		assert_eq!(a[2].as_number(), Some(3.5));
+		assert!(a[5].is_null());
+	}
+}
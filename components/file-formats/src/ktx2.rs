@@ -25,13 +25,19 @@ use {
 };
 
 /// The identifier of a KTX2 file.
-pub const IDENTIFIER: [u8; 12] = *b"\xABKTX 22\xBB\r\n\x1A\n";
+pub const IDENTIFIER: [u8; 12] = *b"\xABKTX 20\xBB\r\n\x1A\n";
 
 #[cfg(feature = "vk")]
 pub type Format = vk::VkFormat;
 #[cfg(not(feature = "vk"))]
 pub type Format = u32;
 
+/// The `VkFormat` value of a format.
+#[allow(clippy::unnecessary_cast)]
+fn format_value(format: Format) -> u32 {
+	format as u32
+}
+
 pub struct ReadHeader;
 pub struct ReadIndex;
 pub struct ReadDataFormatDescriptor;
@@ -42,26 +48,41 @@ pub struct ReadMipLevel;
 /// A KTX2 header.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Header {
-	pub format:                       Format,
-	pub type_size:                    u32,
-	pub pixel_width:                  u32,
-	pub pixel_height:                 u32,
-	pub pixel_depth:                  u32,
-	pub number_of_array_elements:     u32,
-	pub number_of_faces:              u32,
-	pub number_of_mip_levels:         u32,
-	pub supercompression_scheme:      SupercompressionScheme,
-	pub bytes_of_images:              u64,
-	pub bytes_of_uncompressed_images: u64
+	pub format:                   Format,
+	pub type_size:                u32,
+	pub pixel_width:              u32,
+	pub pixel_height:             u32,
+	pub pixel_depth:              u32,
+	pub number_of_array_elements: u32,
+	pub number_of_faces:          u32,
+	pub number_of_mip_levels:     u32,
+	pub supercompression_scheme:  SupercompressionScheme
+}
+
+impl Header {
+	/// The number of levels stored in the file, a level count of zero still stores the base level.
+	pub fn levels(&self) -> usize {
+		self.number_of_mip_levels.max(1) as usize
+	}
+	
+	/// The extent of a mip level in pixels.
+	pub fn level_extent(&self, level: usize) -> [u32; 3] {
+		[
+			(self.pixel_width >> level).max(1),
+			(self.pixel_height >> level).max(1),
+			(self.pixel_depth >> level).max(1)
+		]
+	}
 }
 
+/// The offsets are relative to the start of the file.
 pub struct Index {
 	pub data_format_descriptor_offset:         u32,
 	pub bytes_of_data_format_descriptor:       u32,
 	pub key_value_data_offset:                 u32,
 	pub bytes_of_key_value_data:               u32,
-	pub supercompression_global_data_offset:   u32,
-	pub bytes_of_supercompression_global_data: u32,
+	pub supercompression_global_data_offset:   u64,
+	pub bytes_of_supercompression_global_data: u64,
 	pub levels:                                Box<[Level]>
 }
 
@@ -79,19 +100,302 @@ impl Default for Index {
 	}
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Level {
 	pub offset:                       u64,
 	pub bytes_of_images:              u64,
 	pub bytes_of_uncompressed_images: u64
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq)]
 pub enum SupercompressionScheme {
-	None      = 0x0,
-	CrunchCrn = 0x1,
-	Zlib      = 0x2,
-	Zstd      = 0x3
+	#[default]
+	None    = 0x0,
+	BasisLz = 0x1,
+	Zstd    = 0x2,
+	Zlib    = 0x3
+}
+
+/// The KHR_DF_VENDORID_KHRONOS vendor id.
+pub const VENDOR_ID_KHRONOS: u32 = 0;
+/// The KHR_DF_KHR_DESCRIPTORTYPE_BASICFORMAT descriptor type.
+pub const DESCRIPTOR_TYPE_BASIC: u32 = 0;
+/// The KHR_DF_VERSIONNUMBER_1_3 version number.
+pub const VERSION_NUMBER: u16 = 2;
+
+pub const FLAG_ALPHA_PREMULTIPLIED: u8 = 0x1;
+
+pub const CHANNEL_RED:     u8 = 0;
+pub const CHANNEL_GREEN:   u8 = 1;
+pub const CHANNEL_BLUE:    u8 = 2;
+pub const CHANNEL_STENCIL: u8 = 13;
+pub const CHANNEL_DEPTH:   u8 = 14;
+pub const CHANNEL_ALPHA:   u8 = 15;
+
+pub const QUALIFIER_LINEAR:   u8 = 0x1;
+pub const QUALIFIER_EXPONENT: u8 = 0x2;
+pub const QUALIFIER_SIGNED:   u8 = 0x4;
+pub const QUALIFIER_FLOAT:    u8 = 0x8;
+
+/// A Khronos Data Format Descriptor.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DataFormatDescriptor {
+	pub blocks: Vec<DescriptorBlock>
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DescriptorBlock {
+	Basic(BasicDescriptor),
+	/// A block that is not interpreted, `data` excludes the block header.
+	Other {
+		vendor_id:       u32,
+		descriptor_type: u32,
+		version:         u16,
+		data:            Vec<u8>
+	}
+}
+
+/// The basic descriptor block, sizes are stored as their actual value, not minus one like in the file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BasicDescriptor {
+	pub color_model:           ColorModel,
+	pub color_primaries:       ColorPrimaries,
+	pub transfer_function:     TransferFunction,
+	pub flags:                 u8,
+	pub texel_block_dimension: [u16; 4],
+	pub bytes_plane:           [u8; 8],
+	pub samples:               Vec<Sample>
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Sample {
+	pub bit_offset: u16,
+	pub bit_length: u16,
+	pub channel:    u8,
+	/// A combination of the `QUALIFIER_*` bits.
+	pub qualifiers: u8,
+	pub position:   [u8; 4],
+	pub lower:      u32,
+	pub upper:      u32
+}
+
+macro_rules! dfd_enum {
+	($name:ident, $msg:literal, $( $variant:ident = $value:literal ),* ) => {
+		#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+		pub enum $name {
+			$( $variant = $value ),*
+		}
+		
+		impl Default for $name {
+			fn default() -> Self {
+				Self::Unspecified
+			}
+		}
+		
+		impl TryFrom<u8> for $name {
+			type Error = Error;
+			
+			fn try_from(v: u8) -> Result<Self> {
+				match v {
+					$( $value => Ok(Self::$variant), )*
+					_ => Err(Error::new(ErrorKind::InvalidData, $msg))
+				}
+			}
+		}
+	};
+}
+
+dfd_enum!(ColorModel, "invalid color model",
+	Unspecified = 0, Rgbsda = 1, Yuvsda = 2, Yiqsda = 3, Labsda = 4, Cmyka = 5, Xyzw = 6, HsvaAng = 7,
+	HslaAng = 8, HsvaHex = 9, HslaHex = 10, Ycgcoa = 11, Yccbccrc = 12, Ictcp = 13, CieXyz = 14, CieXyy = 15,
+	Bc1a = 128, Bc2 = 129, Bc3 = 130, Bc4 = 131, Bc5 = 132, Bc6h = 133, Bc7 = 134, Etc1 = 160, Etc2 = 161,
+	Astc = 162, Etc1s = 163, Pvrtc = 164, Pvrtc2 = 165, Uastc = 166);
+
+dfd_enum!(ColorPrimaries, "invalid color primaries",
+	Unspecified = 0, Bt709 = 1, Bt601Ebu = 2, Bt601Smpte = 3, Bt2020 = 4, CieXyz = 5, Aces = 6, AcesCc = 7,
+	Ntsc1953 = 8, Pal525 = 9, DisplayP3 = 10, AdobeRgb = 11);
+
+dfd_enum!(TransferFunction, "invalid transfer function",
+	Unspecified = 0, Linear = 1, Srgb = 2, Itu = 3, Ntsc = 4, Slog = 5, Slog2 = 6, Bt1886 = 7, HlgOetf = 8,
+	HlgEotf = 9, PqEotf = 10, PqOetf = 11, DciP3 = 12, PalOetf = 13, Pal625Eotf = 14, St240 = 15, AcesCc = 16,
+	AcesCct = 17, AdobeRgb = 18);
+
+fn u16_at(buf: &[u8], i: usize) -> u16 {
+	u16::from_le_bytes([buf[i], buf[i + 1]])
+}
+
+fn u32_at(buf: &[u8], i: usize) -> u32 {
+	u32::from_le_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]])
+}
+
+fn u64_at(buf: &[u8], i: usize) -> u64 {
+	u32_at(buf, i) as u64 | (u32_at(buf, i + 4) as u64) << 32
+}
+
+impl DataFormatDescriptor {
+	/// Parses a descriptor, including the leading total size.
+	pub fn parse(buf: &[u8]) -> Result<Self> {
+		let invalid = || Error::new(ErrorKind::InvalidData, "invalid data format descriptor");
+		
+		if buf.len() < 4 || u32_at(buf, 0) as usize != buf.len() {
+			return Err(invalid());
+		}
+		
+		let mut blocks = Vec::new();
+		let mut off = 4;
+		
+		while off < buf.len() {
+			if buf.len() - off < 8 {
+				return Err(invalid());
+			}
+			
+			let vendor_id = u32_at(buf, off) & 0x1FFFF;
+			let descriptor_type = u32_at(buf, off) >> 17;
+			let version = u16_at(buf, off + 4);
+			let size = u16_at(buf, off + 6) as usize;
+			let block = buf.get(off..off + size).filter(|_| size >= 8).ok_or_else(invalid)?;
+			
+			blocks.push(if vendor_id == VENDOR_ID_KHRONOS && descriptor_type == DESCRIPTOR_TYPE_BASIC {
+				if size < 24 || !(size - 24).is_multiple_of(16) {
+					return Err(invalid());
+				}
+				
+				DescriptorBlock::Basic(BasicDescriptor {
+					color_model:           ColorModel::try_from(block[8])?,
+					color_primaries:       ColorPrimaries::try_from(block[9])?,
+					transfer_function:     TransferFunction::try_from(block[10])?,
+					flags:                 block[11],
+					texel_block_dimension: [0, 1, 2, 3].map(|i| block[12 + i] as u16 + 1),
+					bytes_plane:           [0, 1, 2, 3, 4, 5, 6, 7].map(|i| block[16 + i]),
+					samples:               block[24..].chunks_exact(16).map(|sample| Sample {
+						bit_offset: u16_at(sample, 0),
+						bit_length: sample[2] as u16 + 1,
+						channel:    sample[3] & 0xF,
+						qualifiers: sample[3] >> 4,
+						position:   [sample[4], sample[5], sample[6], sample[7]],
+						lower:      u32_at(sample, 8),
+						upper:      u32_at(sample, 12)
+					}).collect()
+				})
+			} else {
+				DescriptorBlock::Other { vendor_id, descriptor_type, version, data: block[8..].to_vec() }
+			});
+			
+			off += size;
+		}
+		
+		Ok(Self { blocks })
+	}
+	
+	/// Serializes the descriptor, including the leading total size.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut buf = vec![0u8; 4];
+		
+		for block in &self.blocks {
+			let (vendor_id, descriptor_type, version, data) = match block {
+				DescriptorBlock::Basic(basic) => {
+					let mut data = vec![
+						basic.color_model as u8,
+						basic.color_primaries as u8,
+						basic.transfer_function as u8,
+						basic.flags
+					];
+					data.extend(basic.texel_block_dimension.iter().map(|v| v.saturating_sub(1) as u8));
+					data.extend_from_slice(&basic.bytes_plane);
+					
+					for sample in &basic.samples {
+						data.extend_from_slice(&sample.bit_offset.to_le_bytes());
+						data.push(sample.bit_length.saturating_sub(1) as u8);
+						data.push(sample.channel & 0xF | sample.qualifiers << 4);
+						data.extend_from_slice(&sample.position);
+						data.extend_from_slice(&sample.lower.to_le_bytes());
+						data.extend_from_slice(&sample.upper.to_le_bytes());
+					}
+					
+					(VENDOR_ID_KHRONOS, DESCRIPTOR_TYPE_BASIC, VERSION_NUMBER, std::borrow::Cow::Owned(data))
+				}
+				DescriptorBlock::Other { vendor_id, descriptor_type, version, data } =>
+					(*vendor_id, *descriptor_type, *version, std::borrow::Cow::Borrowed(data))
+			};
+			
+			buf.extend_from_slice(&(vendor_id & 0x1FFFF | descriptor_type << 17).to_le_bytes());
+			buf.extend_from_slice(&version.to_le_bytes());
+			buf.extend_from_slice(&(data.len() as u16 + 8).to_le_bytes());
+			buf.extend_from_slice(&data);
+		}
+		
+		let len = buf.len() as u32;
+		buf[..4].copy_from_slice(&len.to_le_bytes());
+		buf
+	}
+	
+	pub fn basic(&self) -> Option<&BasicDescriptor> {
+		self.blocks.iter().find_map(|block| match block {
+			DescriptorBlock::Basic(basic) => Some(basic),
+			_ => None
+		})
+	}
+	
+	/// Builds the descriptor of an uncompressed 8, 16 or 32 bit per channel Vulkan format,
+	/// returns `None` for other formats.
+	pub fn for_format(format: u32) -> Option<Self> {
+		#[derive(Copy, Clone, Eq, PartialEq)]
+		enum Numeric { Unorm, Snorm, Uscaled, Sscaled, Uint, Sint, Srgb, Sfloat }
+		use Numeric::*;
+		
+		const RGBA: [u8; 4] = [CHANNEL_RED, CHANNEL_GREEN, CHANNEL_BLUE, CHANNEL_ALPHA];
+		const BGRA: [u8; 4] = [CHANNEL_BLUE, CHANNEL_GREEN, CHANNEL_RED, CHANNEL_ALPHA];
+		const EIGHT: [Numeric; 7] = [Unorm, Snorm, Uscaled, Sscaled, Uint, Sint, Srgb];
+		const SIXTEEN: [Numeric; 7] = [Unorm, Snorm, Uscaled, Sscaled, Uint, Sint, Sfloat];
+		const THIRTY_TWO: [Numeric; 3] = [Uint, Sint, Sfloat];
+		
+		let (bits, channels, numeric) = match format {
+			9..=15   => (8, &RGBA[..1], EIGHT[format as usize - 9]),
+			16..=22  => (8, &RGBA[..2], EIGHT[format as usize - 16]),
+			23..=29  => (8, &RGBA[..3], EIGHT[format as usize - 23]),
+			30..=36  => (8, &BGRA[..3], EIGHT[format as usize - 30]),
+			37..=43  => (8, &RGBA[..], EIGHT[format as usize - 37]),
+			44..=50  => (8, &BGRA[..], EIGHT[format as usize - 44]),
+			70..=97  => (16, &RGBA[..(format as usize - 70) / 7 + 1], SIXTEEN[(format as usize - 70) % 7]),
+			98..=109 => (32, &RGBA[..(format as usize - 98) / 3 + 1], THIRTY_TWO[(format as usize - 98) % 3]),
+			_        => return None
+		};
+		
+		let max = |bits: u32| ((1u64 << bits) - 1) as u32;
+		let samples = channels.iter().enumerate().map(|(i, channel)| {
+			let signed = matches!(numeric, Snorm | Sscaled | Sint | Sfloat);
+			let (lower, upper) = match numeric {
+				Unorm | Srgb      => (0, max(bits)),
+				Snorm             => ((-(max(bits - 1) as i32)) as u32, max(bits - 1)),
+				Uscaled | Uint    => (0, 1),
+				Sscaled | Sint    => (-1i32 as u32, 1),
+				Sfloat if bits == 16 => (0xBC00, 0x3C00),
+				Sfloat            => ((-1f32).to_bits(), 1f32.to_bits())
+			};
+			
+			Sample {
+				bit_offset: (i * bits as usize) as u16,
+				bit_length: bits as u16,
+				channel:    *channel,
+				qualifiers: if signed { QUALIFIER_SIGNED } else { 0 }
+					| if numeric == Sfloat { QUALIFIER_FLOAT } else { 0 }
+					| if numeric == Srgb && *channel == CHANNEL_ALPHA { QUALIFIER_LINEAR } else { 0 },
+				position:   [0; 4],
+				lower,
+				upper
+			}
+		}).collect::<Vec<_>>();
+		
+		Some(Self { blocks: vec![DescriptorBlock::Basic(BasicDescriptor {
+			color_model:           ColorModel::Rgbsda,
+			color_primaries:       ColorPrimaries::Bt709,
+			transfer_function:     if numeric == Srgb { TransferFunction::Srgb } else { TransferFunction::Linear },
+			flags:                 0,
+			texel_block_dimension: [1; 4],
+			bytes_plane:           [(samples.len() * bits as usize / 8) as u8, 0, 0, 0, 0, 0, 0, 0],
+			samples
+		})] })
+	}
 }
 
 pub struct KeyValueIter<'a> {
@@ -103,31 +407,36 @@ impl<'a> Iterator for KeyValueIter<'a> {
 	type Item = (&'a str, &'a [u8]);
 	
 	fn next(&mut self) -> Option<Self::Item> {
-		if self.idx >= self.buf.len() {
-			return None;
-		}
-		
-		#[allow(clippy::cast_ptr_alignment)]
-		let range = u32::from_le_bytes([
-			self.buf[self.idx], self.buf[self.idx + 1], self.buf[self.idx + 2], self.buf[self.idx + 3]
-		]) as usize;
-		self.idx += 4;
-		let off = self.idx;
-		let ext = self.idx + range;
-		
-		while self.buf[self.idx] != 0 && self.idx < ext { self.idx += 1; }
-		
-		let str = std::str::from_utf8(&self.buf[off..self.idx]).unwrap();
-		let buf = &self.buf[self.idx + 1..ext];
-		self.idx = ext;
-		Some((str, buf))
+		let len = u32_at(self.buf.get(self.idx..self.idx + 4)?, 0) as usize;
+		let entry = self.buf.get(self.idx + 4..self.idx + 4 + len)?;
+		let nul = entry.iter().position(|b| *b == 0)?;
+		self.idx += (len + 7) & !3;
+		Some((std::str::from_utf8(&entry[..nul]).ok()?, &entry[nul + 1..]))
+	}
+}
+
+fn decompress(scheme: SupercompressionScheme, buf: Vec<u8>) -> Result<Vec<u8>> {
+	match scheme {
+		SupercompressionScheme::None    => Ok(buf),
+		SupercompressionScheme::Zstd    => crate::zstd::decompress(&buf),
+		SupercompressionScheme::Zlib    => crate::zlib::decompress(&buf),
+		SupercompressionScheme::BasisLz => Err(Error::new(ErrorKind::Unsupported, "BasisLZ is not supported"))
+	}
+}
+
+fn compress(scheme: SupercompressionScheme, buf: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>> {
+	match scheme {
+		SupercompressionScheme::None    => Ok(buf.into()),
+		SupercompressionScheme::Zstd    => Ok(crate::zstd::compress(buf).into()),
+		SupercompressionScheme::Zlib    => Ok(crate::zlib::compress(buf).into()),
+		SupercompressionScheme::BasisLz => Err(Error::new(ErrorKind::Unsupported, "BasisLZ is not supported"))
 	}
 }
 
 // TODO use state pattern
 pub trait Read: io::Read + io::Seek + Sized {
 	fn ktx2_read_header(&mut self) -> Result<Header> {
-		let mut buf = [0u8; 64];
+		let mut buf = [0u8; 48];
 		self.read_exact(&mut buf)?;
 		
 		if buf[..12] != IDENTIFIER {
@@ -136,92 +445,103 @@ pub trait Read: io::Read + io::Seek + Sized {
 		
 		let buf = &buf[12..];
 		Ok(Header {
-			format:                       {
+			format:                   {
 				#[cfg(feature = "vk")]
-				let v = Format::try_from(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+				let v = Format::try_from(u32_at(buf, 0))
 					.map_err(|_| Error::new(ErrorKind::InvalidData, "invalid format"))?;
 				
 				#[cfg(not(feature = "vk"))]
-				let v = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+				let v = u32_at(buf, 0);
 				
 				v
 			},
-			type_size:                    u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]),
-			pixel_width:                  u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]),
-			pixel_height:                 u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]),
-			pixel_depth:                  u32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]),
-			number_of_array_elements:     u32::from_le_bytes([buf[20], buf[21], buf[22], buf[23]]),
-			number_of_faces:              u32::from_le_bytes([buf[24], buf[25], buf[26], buf[27]]),
-			number_of_mip_levels:         u32::from_le_bytes([buf[28], buf[29], buf[30], buf[31]]),
-			supercompression_scheme:      match u32::from_le_bytes([buf[32], buf[33], buf[34], buf[35]]) {
+			type_size:                u32_at(buf, 4),
+			pixel_width:              u32_at(buf, 8),
+			pixel_height:             u32_at(buf, 12),
+			pixel_depth:              u32_at(buf, 16),
+			number_of_array_elements: u32_at(buf, 20),
+			number_of_faces:          u32_at(buf, 24),
+			number_of_mip_levels:     u32_at(buf, 28),
+			supercompression_scheme:  match u32_at(buf, 32) {
 				0 => SupercompressionScheme::None,
-				1 => SupercompressionScheme::CrunchCrn,
-				2 => SupercompressionScheme::Zlib,
-				3 => SupercompressionScheme::Zstd,
+				1 => SupercompressionScheme::BasisLz,
+				2 => SupercompressionScheme::Zstd,
+				3 => SupercompressionScheme::Zlib,
 				_ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid supercompression scheme"))
-			},
-			bytes_of_images:              u64::from_le_bytes([
-				buf[36], buf[37], buf[38], buf[39], buf[40], buf[41], buf[42], buf[43]]),
-			bytes_of_uncompressed_images: u64::from_le_bytes([
-				buf[44], buf[45], buf[46], buf[47], buf[48], buf[49], buf[50], buf[51]])
+			}
 		})
 	}
 	
+	/// Reads the index, which immediately follows the header.
 	fn ktx2_read_index(&mut self, header: &Header) -> Result<Index> {
-		let mut buf = [0u8; 24];
+		let mut buf = [0u8; 32];
 		self.read_exact(&mut buf)?;
 		Ok(Index {
-			data_format_descriptor_offset:         u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]),
-			bytes_of_data_format_descriptor:       u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]),
-			key_value_data_offset:                 u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]),
-			bytes_of_key_value_data:               u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]),
-			supercompression_global_data_offset:   u32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]),
-			bytes_of_supercompression_global_data: u32::from_le_bytes([buf[20], buf[21], buf[22], buf[23]]),
-			levels: (0..header.number_of_mip_levels).map(|_| {
-					self.read_exact(&mut buf)?;
+			data_format_descriptor_offset:         u32_at(&buf, 0),
+			bytes_of_data_format_descriptor:       u32_at(&buf, 4),
+			key_value_data_offset:                 u32_at(&buf, 8),
+			bytes_of_key_value_data:               u32_at(&buf, 12),
+			supercompression_global_data_offset:   u64_at(&buf, 16),
+			bytes_of_supercompression_global_data: u64_at(&buf, 24),
+			levels: (0..header.levels()).map(|_| {
+					self.read_exact(&mut buf[..24])?;
 					Ok(Level {
-						offset:                       u64::from_le_bytes([
-							buf[0], buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7]]),
-						bytes_of_images:              u64::from_le_bytes([
-							buf[8], buf[9], buf[10], buf[11], buf[12], buf[13], buf[14], buf[15]]),
-						bytes_of_uncompressed_images: u64::from_le_bytes([
-							buf[16], buf[17], buf[18], buf[19], buf[20], buf[21], buf[22], buf[23]])
+						offset:                       u64_at(&buf, 0),
+						bytes_of_images:              u64_at(&buf, 8),
+						bytes_of_uncompressed_images: u64_at(&buf, 16)
 					})
 			}).collect::<io::Result<Vec<Level>>>()?
 				.into_boxed_slice()
 		})
 	}
 	
-	fn ktx2_read_data_format_descriptor(&mut self, _header: &Header) -> Result<()> {
-		unimplemented!() // TODO
+	fn ktx2_read_data_format_descriptor(&mut self, index: &Index) -> Result<DataFormatDescriptor> {
+		let mut buf = vec![0u8; index.bytes_of_data_format_descriptor as usize];
+		self.seek(SeekFrom::Start(index.data_format_descriptor_offset as u64))?;
+		self.read_exact(&mut buf)?;
+		DataFormatDescriptor::parse(&buf)
 	}
 	
 	fn ktx2_read_key_value_data<'a>(&mut self, index: &Index, buf: &'a mut [u8]) -> Result<KeyValueIter<'a>> {
-		self.read_exact(&mut buf[..index.bytes_of_key_value_data as usize])?;
-		Ok(KeyValueIter {
-			buf: &mut buf[..index.bytes_of_key_value_data as usize],
-			idx: 0
-		})
+		let buf = &mut buf[..index.bytes_of_key_value_data as usize];
+		self.seek(SeekFrom::Start(index.key_value_data_offset as u64))?;
+		self.read_exact(buf)?;
+		Ok(KeyValueIter { buf, idx: 0 })
 	}
 	
 	fn ktx2_read_supercomression_global_data(&mut self, index: &Index, buf: &mut [u8]) -> Result<()> {
-		self.seek(SeekFrom::Current(index.supercompression_global_data_offset as i64))?;
+		self.seek(SeekFrom::Start(index.supercompression_global_data_offset))?;
 		self.read_exact(&mut buf[..index.bytes_of_supercompression_global_data as usize])
 	}
 	
+	/// Reads the raw, possibly supercompressed, data of a mip level.
 	fn ktx2_read_mip_level(&mut self, index: &Index, level: usize, buf: &mut [u8]) -> Result<()> {
-		self.seek(SeekFrom::Current(index.levels[level].offset as i64))?;
+		self.seek(SeekFrom::Start(index.levels[level].offset))?;
 		self.read_exact(&mut buf[..index.levels[level].bytes_of_images as usize])
 	}
+	
+	/// Reads a mip level and undoes the supercompression.
+	fn ktx2_read_mip_level_data(&mut self, header: &Header, index: &Index, level: usize) -> Result<Vec<u8>> {
+		let mut buf = vec![0u8; index.levels[level].bytes_of_images as usize];
+		self.ktx2_read_mip_level(index, level, &mut buf)?;
+		let buf = decompress(header.supercompression_scheme, buf)?;
+		
+		match buf.len() as u64 == index.levels[level].bytes_of_uncompressed_images {
+			true  => Ok(buf),
+			false => Err(Error::new(ErrorKind::InvalidData, "mip level size mismatch"))
+		}
+	}
 }
 
 impl<T: io::Read + io::Seek> Read for T {}
 
+/// Writes the parts of a file. The offsets in the index are set to the current stream position,
+/// so the header and index have to be written again after all other parts.
 pub trait Write: io::Write + io::Seek {
 	fn ktx2_write_header(&mut self, header: &Header) -> Result<()> {
-		let mut buf = [0u8; 64];
+		let mut buf = [0u8; 48];
 		buf[..12].copy_from_slice(&IDENTIFIER);
-		buf[12..16].copy_from_slice(&(header.format as u32).to_le_bytes());
+		buf[12..16].copy_from_slice(&format_value(header.format).to_le_bytes());
 		buf[16..20].copy_from_slice(&header.type_size.to_le_bytes());
 		buf[20..24].copy_from_slice(&header.pixel_width.to_le_bytes());
 		buf[24..28].copy_from_slice(&header.pixel_height.to_le_bytes());
@@ -230,56 +550,277 @@ pub trait Write: io::Write + io::Seek {
 		buf[36..40].copy_from_slice(&header.number_of_faces.to_le_bytes());
 		buf[40..44].copy_from_slice(&header.number_of_mip_levels.to_le_bytes());
 		buf[44..48].copy_from_slice(&(header.supercompression_scheme as u32).to_le_bytes());
-		buf[48..56].copy_from_slice(&header.bytes_of_images.to_le_bytes());
-		buf[56..64].copy_from_slice(&header.bytes_of_uncompressed_images.to_le_bytes());
 		self.write_all(&buf)
 	}
 	
 	fn ktx2_write_index(&mut self, index: &Index) -> Result<()> {
-		let mut buf = [0u8; 24];
+		let mut buf = [0u8; 32];
 		buf[0..4].copy_from_slice(&index.data_format_descriptor_offset.to_le_bytes());
 		buf[4..8].copy_from_slice(&index.bytes_of_data_format_descriptor.to_le_bytes());
 		buf[8..12].copy_from_slice(&index.key_value_data_offset.to_le_bytes());
 		buf[12..16].copy_from_slice(&index.bytes_of_key_value_data.to_le_bytes());
-		buf[16..20].copy_from_slice(&index.supercompression_global_data_offset.to_le_bytes());
-		buf[20..24].copy_from_slice(&index.bytes_of_supercompression_global_data.to_le_bytes());
+		buf[16..24].copy_from_slice(&index.supercompression_global_data_offset.to_le_bytes());
+		buf[24..32].copy_from_slice(&index.bytes_of_supercompression_global_data.to_le_bytes());
 		self.write_all(&buf)?;
 		
 		for level in &*index.levels {
 			buf[0..8].copy_from_slice(&level.offset.to_le_bytes());
 			buf[8..16].copy_from_slice(&level.bytes_of_images.to_le_bytes());
 			buf[16..24].copy_from_slice(&level.bytes_of_uncompressed_images.to_le_bytes());
-			self.write_all(&buf)?;
+			self.write_all(&buf[..24])?;
 		}
 		
 		Ok(())
 	}
 	
-	fn ktx2_write_data_format_descriptor(&mut self, index: &mut Index, buf: &[u8]) -> Result<()> {
-		//index.data_format_descriptor_offset = self.stream_position().unwrap() as u32;
+	/// Pads the stream with zeros until the position is a multiple of `align`.
+	fn ktx2_write_padding(&mut self, align: u64) -> Result<u64> {
+		let pos = self.stream_position()?;
+		let padded = pos.div_ceil(align) * align;
+		self.write_all(&vec![0u8; (padded - pos) as usize])?;
+		Ok(padded)
+	}
+	
+	fn ktx2_write_data_format_descriptor(&mut self, index: &mut Index, dfd: &DataFormatDescriptor) -> Result<()> {
+		let buf = dfd.to_bytes();
+		index.data_format_descriptor_offset = self.ktx2_write_padding(4)? as u32;
 		index.bytes_of_data_format_descriptor = buf.len() as u32;
-		self.write_all(buf)
+		self.write_all(&buf)
 	}
 	
-	fn ktx2_write_key_value_data<'a, I: IntoIterator<Item = (&'a str, &'a [u8])>>(&mut self, _index: &Index, iter: I) -> Result<()> {
-		let _iter = iter.into_iter();
-		unimplemented!()
+	/// Writes the entries sorted by key, as required by the specification.
+	fn ktx2_write_key_value_data<'a, I: IntoIterator<Item = (&'a str, &'a [u8])>>(&mut self, index: &mut Index, iter: I) -> Result<()> {
+		let mut entries = iter.into_iter().collect::<Vec<_>>();
+		entries.sort_by_key(|(key, _)| *key);
+		
+		let mut buf = Vec::new();
+		for (key, value) in entries {
+			buf.extend_from_slice(&((key.len() + 1 + value.len()) as u32).to_le_bytes());
+			buf.extend_from_slice(key.as_bytes());
+			buf.push(0);
+			buf.extend_from_slice(value);
+			buf.resize(buf.len().next_multiple_of(4), 0);
+		}
+		
+		index.key_value_data_offset = match buf.is_empty() {
+			true  => 0,
+			false => self.ktx2_write_padding(4)? as u32
+		};
+		index.bytes_of_key_value_data = buf.len() as u32;
+		self.write_all(&buf)
 	}
 	
 	fn ktx2_write_supercompression_global_data(&mut self, index: &mut Index, buf: &[u8]) -> Result<()> {
-		//index.supercompression_global_data_offset = self.stream_position().unwrap() as u32;
-		index.bytes_of_supercompression_global_data = buf.len() as u32;
+		index.supercompression_global_data_offset = match buf.is_empty() {
+			true  => 0,
+			false => self.ktx2_write_padding(8)?
+		};
+		index.bytes_of_supercompression_global_data = buf.len() as u64;
 		self.write_all(buf)
 	}
 	
-	fn ktx2_write_mip_levels<'a, I: IntoIterator<Item = &'a [u8]>>(&'a mut self, _index: &Index, _iter: I) -> Result<()> {
-		unimplemented!()
+	/// Writes the uncompressed levels, starting with the base level, and applies the supercompression
+	/// of the header. The levels are stored smallest first and aligned to `lcm(texel_block_size, 4)`
+	/// if not supercompressed.
+	fn ktx2_write_mip_levels<'a, I: IntoIterator<Item = &'a [u8]>>(
+		&mut self,
+		header:           &Header,
+		index:            &mut Index,
+		texel_block_size: u64,
+		iter:             I
+	) -> Result<()> {
+		let levels = iter.into_iter().collect::<Vec<_>>();
+		let align = match header.supercompression_scheme {
+			SupercompressionScheme::None => {
+				let size = texel_block_size.max(1);
+				let (mut a, mut b) = (size, 4);
+				while b != 0 { (a, b) = (b, a % b); }
+				size * 4 / a
+			}
+			_ => 1
+		};
+		
+		let mut entries = vec![Level::default(); levels.len()];
+		for (level, data) in levels.iter().enumerate().rev() {
+			let buf = compress(header.supercompression_scheme, data)?;
+			entries[level] = Level {
+				offset:                       self.ktx2_write_padding(align)?,
+				bytes_of_images:              buf.len() as u64,
+				bytes_of_uncompressed_images: data.len() as u64
+			};
+			self.write_all(&buf)?;
+		}
+		
+		index.levels = entries.into_boxed_slice();
+		Ok(())
 	}
 }
 
 impl<T: io::Write + io::Seek> Write for T {}
 
+/// A complete texture with uncompressed levels.
+#[derive(Clone, Debug)]
+pub struct Ktx2Texture {
+	pub header:         Header,
+	pub dfd:            DataFormatDescriptor,
+	pub key_value_data: Vec<(String, Vec<u8>)>,
+	/// The levels, starting with the base level. Each level contains all layers, faces and
+	/// depth slices, in that order.
+	pub levels:         Vec<Vec<u8>>
+}
+
+impl Ktx2Texture {
+	/// Creates a 2D texture of an uncompressed format, see `DataFormatDescriptor::for_format`.
+	pub fn new(format: Format, width: u32, height: u32, levels: Vec<Vec<u8>>) -> Result<Self> {
+		let dfd = DataFormatDescriptor::for_format(format_value(format))
+			.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "unsupported format"))?;
+		let type_size = dfd.basic().map_or(1, |basic| basic.samples[0].bit_length as u32 / 8);
+		
+		Ok(Self {
+			header: Header {
+				format,
+				type_size,
+				pixel_width:              width,
+				pixel_height:             height,
+				pixel_depth:              0,
+				number_of_array_elements: 0,
+				number_of_faces:          1,
+				number_of_mip_levels:     levels.len() as u32,
+				supercompression_scheme:  SupercompressionScheme::None
+			},
+			dfd,
+			key_value_data: Vec::new(),
+			levels
+		})
+	}
+	
+	/// Reads a file and decompresses all levels.
+	pub fn read(mut reader: impl io::Read + io::Seek) -> Result<Self> {
+		let header = reader.ktx2_read_header()?;
+		let index = reader.ktx2_read_index(&header)?;
+		let dfd = reader.ktx2_read_data_format_descriptor(&index)?;
+		let mut buf = vec![0u8; index.bytes_of_key_value_data as usize];
+		let key_value_data = reader.ktx2_read_key_value_data(&index, &mut buf)?
+			.map(|(key, value)| (key.to_string(), value.to_vec()))
+			.collect();
+		let levels = (0..header.levels())
+			.map(|level| reader.ktx2_read_mip_level_data(&header, &index, level))
+			.collect::<Result<_>>()?;
+		
+		Ok(Self { header, dfd, key_value_data, levels })
+	}
+	
+	/// Writes the texture using the supercompression scheme of the header.
+	pub fn write(&self, mut writer: impl io::Write) -> Result<()> {
+		if self.levels.len() != self.header.levels() {
+			return Err(Error::new(ErrorKind::InvalidInput, "the level count does not match the header"));
+		}
+		
+		let mut cursor = io::Cursor::new(Vec::new());
+		let mut index = Index {
+			levels: vec![Level::default(); self.levels.len()].into_boxed_slice(),
+			..Index::default()
+		};
+		
+		// reserve space for the header and index
+		cursor.ktx2_write_header(&self.header)?;
+		cursor.ktx2_write_index(&index)?;
+		cursor.ktx2_write_data_format_descriptor(&mut index, &self.dfd)?;
+		cursor.ktx2_write_key_value_data(&mut index, self.key_value_data.iter()
+			.map(|(key, value)| (key.as_str(), value.as_slice())))?;
+		cursor.ktx2_write_supercompression_global_data(&mut index, &[])?;
+		cursor.ktx2_write_mip_levels(
+			&self.header,
+			&mut index,
+			self.dfd.basic().map_or(1, |basic| basic.bytes_plane[0] as u64),
+			self.levels.iter().map(Vec::as_slice)
+		)?;
+		
+		cursor.set_position(0);
+		cursor.ktx2_write_header(&self.header)?;
+		cursor.ktx2_write_index(&index)?;
+		writer.write_all(cursor.get_ref())
+	}
+	
+	/// Returns the value of a key, e.g. `KTXorientation`.
+	pub fn value(&self, key: &str) -> Option<&[u8]> {
+		self.key_value_data.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_slice())
+	}
+}
+
 #[cfg(test)]
 mod tests {
-
-}
\ No newline at end of file
+	use super::*;
+	
+	#[cfg(feature = "vk")]
+	const R8G8B8A8_SRGB: Format = vk::VK_FORMAT_R8G8B8A8_SRGB;
+	#[cfg(not(feature = "vk"))]
+	const R8G8B8A8_SRGB: Format = 43;
+	
+	fn texture() -> Ktx2Texture {
+		let mut texture = Ktx2Texture::new(R8G8B8A8_SRGB, 4, 2, vec![
+			(0..32).collect(),
+			(0..8).map(|i| i * 3).collect(),
+			vec![7; 4]
+		]).unwrap();
+		texture.key_value_data.push(("KTXwriter".to_string(), b"game-engine\0".to_vec()));
+		texture.key_value_data.push(("KTXorientation".to_string(), b"rd\0".to_vec()));
+		texture
+	}
+	
+	#[test]
+	fn round_trip() {
+		for scheme in [SupercompressionScheme::None, SupercompressionScheme::Zstd, SupercompressionScheme::Zlib] {
+			let mut texture = texture();
+			texture.header.supercompression_scheme = scheme;
+			
+			let mut buf = Vec::new();
+			texture.write(&mut buf).unwrap();
+			assert_eq!(buf[..12], IDENTIFIER);
+			
+			let read = Ktx2Texture::read(io::Cursor::new(&buf)).unwrap();
+			assert_eq!(read.header, texture.header);
+			assert_eq!(read.dfd, texture.dfd);
+			assert_eq!(read.levels, texture.levels);
+			assert_eq!(read.value("KTXorientation"), Some(&b"rd\0"[..]));
+			// keys are sorted when written
+			assert_eq!(read.key_value_data[0].0, "KTXorientation");
+			
+			let mut cursor = io::Cursor::new(&buf);
+			let header = cursor.ktx2_read_header().unwrap();
+			let index = cursor.ktx2_read_index(&header).unwrap();
+			assert_eq!(index.bytes_of_key_value_data % 4, 0);
+			assert!(index.levels[2].offset < index.levels[1].offset);
+			assert!(index.levels[1].offset < index.levels[0].offset);
+			
+			if scheme == SupercompressionScheme::None {
+				assert!(index.levels.iter().all(|level| level.offset % 4 == 0));
+			}
+		}
+	}
+	
+	#[test]
+	fn data_format_descriptor() {
+		let dfd = DataFormatDescriptor::for_format(43).unwrap();
+		let bytes = dfd.to_bytes();
+		assert_eq!(bytes.len(), 4 + 24 + 4 * 16);
+		assert_eq!(u32_at(&bytes, 4 + 4), 0x0002 | (24 + 4 * 16) << 16);
+		assert_eq!(DataFormatDescriptor::parse(&bytes).unwrap(), dfd);
+		
+		let basic = dfd.basic().unwrap();
+		assert_eq!(basic.transfer_function, TransferFunction::Srgb);
+		assert_eq!(basic.bytes_plane[0], 4);
+		assert_eq!(basic.samples[3].channel, CHANNEL_ALPHA);
+		assert_eq!(basic.samples[3].qualifiers, QUALIFIER_LINEAR);
+		assert_eq!(basic.samples[2].bit_offset, 16);
+		
+		let basic = DataFormatDescriptor::for_format(109).unwrap();
+		let basic = basic.basic().unwrap();
+		assert_eq!(basic.bytes_plane[0], 16);
+		assert_eq!(basic.samples[0].qualifiers, QUALIFIER_SIGNED | QUALIFIER_FLOAT);
+		assert_eq!(basic.samples[0].upper, 1f32.to_bits());
+		
+		assert!(DataFormatDescriptor::for_format(131).is_none());
+		assert!(DataFormatDescriptor::parse(&bytes[..bytes.len() - 1]).is_err());
+	}
+}
@@ -28,8 +28,12 @@
 pub mod ktx2;
 #[cfg(feature = "flac")]
 pub mod flac;
+#[cfg(feature = "ogg")]
+pub mod ogg;
 #[cfg(feature = "opus")]
 pub mod opus;
+#[cfg(feature = "otf")]
+pub mod otf;
 #[cfg(feature = "mkv")]
 pub mod mkv;
 #[cfg(feature = "ebml")]
@@ -48,15 +52,25 @@ pub mod svg;
 pub mod protobuf;
 #[cfg(feature = "ber")]
 pub mod ber;
+#[cfg(feature = "bundle")]
+pub mod bundle;
 #[cfg(feature = "yaml")]
 pub mod yaml;
 #[cfg(feature = "elf")]
 pub mod elf;
 #[cfg(feature = "dyn_repr")]
 pub mod dyn_repr;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "zlib")]
+pub mod zlib;
+#[cfg(feature = "zstd")]
+pub mod zstd;
+#[cfg(feature = "png")]
+pub mod png;
+#[cfg(feature = "jpeg")]
+pub mod jpeg;
 pub mod utils;
 
 #[cfg(feature = "serde")]
-pub use serde;
-#[cfg(feature = "ttf")]
-pub use ttf_parser as ttf;
\ No newline at end of file
+pub use serde;
\ No newline at end of file
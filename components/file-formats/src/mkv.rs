@@ -20,4 +20,905 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO MKV (generate from XML file)
+//! Matroska and WebM demuxer.
+//!
+//! The element IDs in [`id`] and the structs of the master elements are generated from the
+//! EBML and Matroska schemas in `codegen/mkvgen/res` by `mkvgen`. IDs include the length marker,
+//! as in the schemas. [`MkvReader`] reads the header elements (info, tracks, cues, chapters and
+//! tags) up front, following the seek head for elements that are stored after the clusters, and
+//! then streams the clusters frame by frame, undoing lacing and header stripping. Seeking uses
+//! the cues if present, otherwise the clusters are scanned.
+
+use {
+	crate::ebml::{ElementId, write_len},
+	std::{io::{self, Read, Seek as _, SeekFrom, Error, ErrorKind}, collections::VecDeque}
+};
+
+mod elements;
+
+pub use elements::*;
+
+/// The size of elements with an unknown size, i.e. a size with all bits set.
+pub const UNKNOWN_SIZE: u64 = u64::MAX;
+
+pub const FLAG_KEYFRAME:    u8 = 0x80;
+pub const FLAG_INVISIBLE:   u8 = 0x08;
+pub const FLAG_DISCARDABLE: u8 = 0x01;
+
+const FLAG_LACING:          u8 = 0x06;
+
+/// The highest version of the Matroska specification the reader understands.
+pub const DOC_TYPE_READ_VERSION: u64 = 4;
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn missing(name: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, format!("missing element `{}`", name))
+}
+
+/// A master element that is read as a whole.
+pub trait Master: Sized {
+	const ID: ElementId;
+	
+	/// Reads the content of the element, `len` is the size from the element header.
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self>;
+}
+
+pub fn read_uint(reader: &mut dyn io::Read, len: u64) -> io::Result<u64> {
+	if len > 8 {
+		return Err(invalid_data("integer too long"));
+	}
+	
+	let mut buf = [0u8; 8];
+	reader.read_exact(&mut buf[8 - len as usize..])?;
+	Ok(u64::from_be_bytes(buf))
+}
+
+pub fn read_int(reader: &mut dyn io::Read, len: u64) -> io::Result<i64> {
+	let v = read_uint(reader, len)?;
+	Ok(match len {
+		0 => 0,
+		_ => ((v << (64 - 8 * len)) as i64) >> (64 - 8 * len)
+	})
+}
+
+pub fn read_float(reader: &mut dyn io::Read, len: u64) -> io::Result<f64> {
+	match len {
+		0 => Ok(0.0),
+		4 => Ok(f32::from_bits(read_uint(reader, len)? as u32) as f64),
+		8 => Ok(f64::from_bits(read_uint(reader, len)?)),
+		_ => Err(invalid_data("invalid float size"))
+	}
+}
+
+/// Reads a string, trailing null bytes are removed.
+pub fn read_string(reader: &mut dyn io::Read, len: u64) -> io::Result<String> {
+	let mut buf = read_binary(reader, len)?;
+	buf.truncate(buf.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1));
+	String::from_utf8(buf).map_err(|_| invalid_data("invalid UTF-8 string"))
+}
+
+pub fn read_binary(reader: &mut dyn io::Read, len: u64) -> io::Result<Vec<u8>> {
+	// the buffer is not allocated up front, as the size may be corrupt
+	let mut buf = Vec::new();
+	if reader.take(len).read_to_end(&mut buf)? as u64 != len {
+		return Err(ErrorKind::UnexpectedEof.into());
+	}
+	
+	Ok(buf)
+}
+
+pub fn skip(reader: &mut dyn io::Read, len: u64) -> io::Result<()> {
+	if io::copy(&mut reader.take(len), &mut io::sink())? != len {
+		return Err(ErrorKind::UnexpectedEof.into());
+	}
+	
+	Ok(())
+}
+
+/// Reads a variable size integer, returns the value without the length marker and the length.
+fn read_vint(reader: &mut impl io::Read, max_len: u32) -> io::Result<(u64, u32)> {
+	let mut b = [0u8];
+	reader.read_exact(&mut b)?;
+	let len = b[0].leading_zeros() + 1;
+	
+	if len > max_len {
+		return Err(invalid_data("invalid variable size integer"));
+	}
+	
+	let mut value = b[0] as u64 & 0xFF >> len;
+	for _ in 1..len {
+		reader.read_exact(&mut b)?;
+		value = value << 8 | b[0] as u64;
+	}
+	
+	Ok((value, len))
+}
+
+/// Reads an element header, returns the ID, the size of the element or [`UNKNOWN_SIZE`] and the
+/// size of the header.
+pub fn read_header(reader: &mut impl io::Read) -> io::Result<(ElementId, u64, u64)> {
+	let (id, id_len) = read_vint(reader, 4)?;
+	let (len, len_len) = read_vint(reader, 8)?;
+	let id = id as ElementId | 1 << (7 * id_len);
+	let len = match len == (1 << (7 * len_len)) - 1 {
+		true  => UNKNOWN_SIZE,
+		false => len
+	};
+	
+	Ok((id, len, (id_len + len_len) as u64))
+}
+
+/// Writes a variable size integer with the given length in bytes.
+fn write_vint(writer: &mut impl io::Write, value: u64, len: u32) -> io::Result<()> {
+	let value = value | 1 << (7 * len);
+	writer.write_all(&value.to_be_bytes()[8 - len as usize..])
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Lacing {
+	#[default]
+	None,
+	/// Frame sizes are stored like in Xiph codecs, as a sequence of 255s and the remainder.
+	Xiph,
+	/// All frames have the same size.
+	Fixed,
+	/// The first frame size is stored as a variable size integer, the others as signed
+	/// differences to the previous size.
+	Ebml
+}
+
+/// The content of a `SimpleBlock` or a `Block` element.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Block {
+	pub track:     u64,
+	/// The timestamp relative to the cluster timestamp, in track ticks.
+	pub timestamp: i16,
+	/// The flags without the lacing bits, see `FLAG_*`. Only [`FLAG_INVISIBLE`] is used in a
+	/// `Block` element, keyframes are signalled by the absence of `ReferenceBlock`s instead.
+	pub flags:     u8,
+	pub lacing:    Lacing,
+	pub frames:    Vec<Vec<u8>>
+}
+
+impl Block {
+	pub fn parse(data: &[u8]) -> io::Result<Self> {
+		let mut reader = data;
+		let (track, _) = read_vint(&mut reader, 8)?;
+		let mut header = [0u8; 3];
+		reader.read_exact(&mut header)?;
+		let timestamp = i16::from_be_bytes([header[0], header[1]]);
+		let flags = header[2];
+		let lacing = match flags & FLAG_LACING {
+			0x00 => Lacing::None,
+			0x02 => Lacing::Xiph,
+			0x04 => Lacing::Fixed,
+			_    => Lacing::Ebml
+		};
+		
+		if lacing == Lacing::None {
+			return Ok(Self { track, timestamp, flags: flags & !FLAG_LACING, lacing, frames: vec![reader.to_vec()] });
+		}
+		
+		let mut count = [0u8];
+		reader.read_exact(&mut count)?;
+		let count = count[0] as usize + 1;
+		let mut sizes = Vec::with_capacity(count);
+		
+		match lacing {
+			Lacing::Xiph => for _ in 1..count {
+				let mut size = 0;
+				loop {
+					let mut b = [0u8];
+					reader.read_exact(&mut b)?;
+					size += b[0] as usize;
+					if b[0] != 0xFF {
+						break;
+					}
+				}
+				sizes.push(size);
+			},
+			Lacing::Ebml => for i in 1..count {
+				let (value, len) = read_vint(&mut reader, 8)?;
+				let size = match i {
+					1 => value as i64,
+					_ => *sizes.last().unwrap() as i64 + value as i64 - ((1 << (7 * len - 1)) - 1)
+				};
+				
+				if size < 0 {
+					return Err(invalid_data("invalid lacing"));
+				}
+				
+				sizes.push(size as usize);
+			},
+			_ => {
+				if !reader.len().is_multiple_of(count) {
+					return Err(invalid_data("invalid lacing"));
+				}
+				
+				sizes.resize(count - 1, reader.len() / count);
+			}
+		}
+		
+		let total = sizes.iter().sum::<usize>();
+		if total > reader.len() {
+			return Err(invalid_data("invalid lacing"));
+		}
+		
+		sizes.push(reader.len() - total);
+		let frames = sizes.iter()
+			.map(|&size| {
+				let (frame, rest) = reader.split_at(size);
+				reader = rest;
+				frame.to_vec()
+			})
+			.collect();
+		
+		Ok(Self { track, timestamp, flags: flags & !FLAG_LACING, lacing, frames })
+	}
+	
+	pub fn write(&self, mut writer: impl io::Write) -> io::Result<()> {
+		let lacing = match self.lacing {
+			_ if self.frames.len() == 1 => Lacing::None,
+			Lacing::None if self.frames.is_empty() => Lacing::None,
+			Lacing::None => return Err(Error::new(ErrorKind::InvalidInput, "multiple frames without lacing")),
+			_ if self.frames.len() > 256 => return Err(Error::new(ErrorKind::InvalidInput, "too many frames")),
+			lacing => lacing
+		};
+		
+		let lacing_flags = match lacing {
+			Lacing::None  => 0x00,
+			Lacing::Xiph  => 0x02,
+			Lacing::Fixed => 0x04,
+			Lacing::Ebml  => 0x06
+		};
+		
+		write_len(&mut writer, self.track)?;
+		writer.write_all(&self.timestamp.to_be_bytes())?;
+		writer.write_all(&[self.flags & !FLAG_LACING | lacing_flags])?;
+		
+		if lacing != Lacing::None {
+			writer.write_all(&[(self.frames.len() - 1) as u8])?;
+		}
+		
+		let sizes = &self.frames[..self.frames.len().saturating_sub(1)];
+		match lacing {
+			Lacing::None => (),
+			Lacing::Xiph => for frame in sizes {
+				writer.write_all(&vec![0xFF; frame.len() / 255])?;
+				writer.write_all(&[(frame.len() % 255) as u8])?;
+			},
+			Lacing::Fixed => if self.frames.iter().any(|frame| frame.len() != self.frames[0].len()) {
+				return Err(Error::new(ErrorKind::InvalidInput, "frames of different sizes with fixed lacing"));
+			},
+			Lacing::Ebml => for (i, frame) in sizes.iter().enumerate() {
+				if i == 0 {
+					write_len(&mut writer, frame.len() as u64)?;
+					continue;
+				}
+				
+				let diff = frame.len() as i64 - sizes[i - 1].len() as i64;
+				let len = (1..=8)
+					.find(|len| diff.unsigned_abs() < 1 << (7 * len - 1))
+					.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "frame too large"))?;
+				write_vint(&mut writer, (diff + (1 << (7 * len - 1)) - 1) as u64, len)?;
+			}
+		}
+		
+		for frame in &self.frames {
+			writer.write_all(frame)?;
+		}
+		
+		Ok(())
+	}
+}
+
+/// A frame of a track, with lacing and header stripping undone.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frame {
+	pub track:           u64,
+	/// The presentation timestamp in nanoseconds.
+	pub timestamp:       i64,
+	/// The duration in nanoseconds, from the `BlockGroup` or the default duration of the track.
+	pub duration:        Option<u64>,
+	pub keyframe:        bool,
+	pub invisible:       bool,
+	pub discardable:     bool,
+	/// The duration of the padding at the end of the frame in nanoseconds, e.g. Opus encoder
+	/// padding at the end of the stream. Only set on the last frame of a block.
+	pub discard_padding: i64,
+	pub data:            Vec<u8>
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Cluster {
+	timestamp: u64,
+	/// The end of the cluster, `None` if the size is unknown.
+	end:       Option<u64>
+}
+
+/// Whether the element is a child of `Segment`, these end clusters with an unknown size.
+fn is_top_level(id: ElementId) -> bool {
+	matches!(id, id::SEEK_HEAD | id::INFO | id::TRACKS | id::CUES | id::ATTACHMENTS
+		| id::CHAPTERS | id::TAGS | id::CLUSTER)
+}
+
+pub struct MkvReader<R> {
+	reader:          io::BufReader<R>,
+	pub header:      Ebml,
+	pub info:        Info,
+	pub tracks:      Vec<TrackEntry>,
+	pub cues:        Option<Cues>,
+	pub chapters:    Option<Chapters>,
+	pub tags:        Vec<Tags>,
+	/// The position of the segment data, all positions in the segment are relative to this.
+	segment:         u64,
+	/// The end of the segment, `None` if the size is unknown.
+	segment_end:     Option<u64>,
+	first_cluster:   Option<u64>,
+	pos:             u64,
+	cluster:         Option<Cluster>,
+	frames:          VecDeque<Frame>
+}
+
+impl<R: Read + io::Seek> MkvReader<R> {
+	pub fn new(reader: R) -> io::Result<Self> {
+		let mut reader = io::BufReader::new(reader);
+		let (id, len, mut pos) = read_header(&mut reader)?;
+		
+		if id != id::EBML {
+			return Err(invalid_data("not an EBML document"));
+		}
+		
+		let header = Ebml::read(&mut reader, len)?;
+		pos += len;
+		
+		if header.doc_type != "matroska" && header.doc_type != "webm" {
+			return Err(invalid_data("unsupported document type"));
+		} else if header.doc_type_read_version > DOC_TYPE_READ_VERSION {
+			return Err(invalid_data("unsupported document type version"));
+		}
+		
+		let segment_end = loop {
+			let (id, len, header_len) = read_header(&mut reader)?;
+			pos += header_len;
+			
+			match id {
+				id::SEGMENT => break (len != UNKNOWN_SIZE).then(|| pos + len),
+				_ if len == UNKNOWN_SIZE => return Err(invalid_data("element with unknown size")),
+				_ => {
+					reader.seek_relative(len as i64)?;
+					pos += len;
+				}
+			}
+		};
+		
+		let mut self_ = Self {
+			reader,
+			header,
+			info:          Info::default(),
+			tracks:        Vec::new(),
+			cues:          None,
+			chapters:      None,
+			tags:          Vec::new(),
+			segment:       pos,
+			segment_end,
+			first_cluster: None,
+			pos,
+			cluster:       None,
+			frames:        VecDeque::new()
+		};
+		
+		let mut info = None;
+		let mut seeks = Vec::new();
+		
+		// read the elements in front of the first cluster
+		while let Some((start, id, len)) = self_.next_element()? {
+			if id == id::CLUSTER {
+				self_.first_cluster = Some(start);
+				break;
+			} else if len == UNKNOWN_SIZE {
+				return Err(invalid_data("element with unknown size"));
+			}
+			
+			match id {
+				id::SEEK_HEAD => seeks.extend(SeekHead::read(&mut self_.reader, len)?.seek),
+				id::INFO      => info = Some(Info::read(&mut self_.reader, len)?),
+				id::TRACKS    => self_.tracks = Tracks::read(&mut self_.reader, len)?.track_entry,
+				id::CUES      => self_.cues = Some(Cues::read(&mut self_.reader, len)?),
+				id::CHAPTERS  => self_.chapters = Some(Chapters::read(&mut self_.reader, len)?),
+				id::TAGS      => self_.tags.push(Tags::read(&mut self_.reader, len)?),
+				_             => self_.reader.seek_relative(len as i64)?
+			}
+			
+			self_.pos += len;
+		}
+		
+		// elements after the clusters, usually the cues, are found through the seek head
+		for seek in seeks {
+			let Ok(seek_id) = read_uint(&mut seek.seek_id.as_slice(), seek.seek_id.len() as u64) else { continue };
+			let loaded = match seek_id as ElementId {
+				id::INFO     => info.is_some(),
+				id::TRACKS   => !self_.tracks.is_empty(),
+				id::CUES     => self_.cues.is_some(),
+				id::CHAPTERS => self_.chapters.is_some(),
+				id::TAGS     => !self_.tags.is_empty(),
+				_            => true
+			};
+			
+			if loaded {
+				continue;
+			}
+			
+			self_.seek_to(self_.segment + seek.seek_position)?;
+			let (id, len, _) = read_header(&mut self_.reader)?;
+			
+			if id != seek_id as ElementId || len == UNKNOWN_SIZE {
+				return Err(invalid_data("invalid seek head entry"));
+			}
+			
+			match id {
+				id::INFO     => info = Some(Info::read(&mut self_.reader, len)?),
+				id::TRACKS   => self_.tracks = Tracks::read(&mut self_.reader, len)?.track_entry,
+				id::CUES     => self_.cues = Some(Cues::read(&mut self_.reader, len)?),
+				id::CHAPTERS => self_.chapters = Some(Chapters::read(&mut self_.reader, len)?),
+				_            => self_.tags.push(Tags::read(&mut self_.reader, len)?)
+			}
+		}
+		
+		self_.info = info.ok_or_else(|| missing("Info"))?;
+		
+		if let Some(first_cluster) = self_.first_cluster {
+			self_.seek_to(first_cluster)?;
+		}
+		
+		Ok(self_)
+	}
+	
+	pub fn track(&self, number: u64) -> Option<&TrackEntry> {
+		self.tracks.iter().find(|track| track.track_number == number)
+	}
+	
+	/// The duration of the segment in nanoseconds.
+	pub fn duration(&self) -> Option<u64> {
+		self.info.duration.map(|duration| (duration * self.info.timestamp_scale as f64) as u64)
+	}
+	
+	/// Reads the next frame of any track.
+	pub fn read_frame(&mut self) -> io::Result<Option<Frame>> {
+		loop {
+			if let Some(frame) = self.frames.pop_front() {
+				return Ok(Some(frame));
+			} else if !self.read_block()? {
+				return Ok(None);
+			}
+		}
+	}
+	
+	/// Seeks to the last cluster that starts at or before the timestamp in nanoseconds and
+	/// contains a keyframe of the track, if the cues list the track. Returns the timestamp of
+	/// the cluster, frames before the requested timestamp have to be skipped by the caller.
+	pub fn seek(&mut self, timestamp: i64, track: Option<u64>) -> io::Result<i64> {
+		let Some(first_cluster) = self.first_cluster else {
+			return Ok(0);
+		};
+		
+		let scale = self.info.timestamp_scale as i64;
+		let cue = self.cues.as_ref().and_then(|cues| cues.cue_point.iter()
+			.filter(|point| point.cue_time as i64 * scale <= timestamp)
+			.filter_map(|point| point.cue_track_positions.iter()
+				.find(|pos| track.is_none_or(|track| pos.cue_track == track))
+				.map(|pos| (point.cue_time, pos.cue_cluster_position)))
+			.max_by_key(|(time, _)| *time));
+		
+		if let Some((time, pos)) = cue {
+			self.seek_to(self.segment + pos)?;
+			return Ok(time as i64 * scale);
+		} else if self.cues.is_some() {
+			self.seek_to(first_cluster)?;
+			return Ok(0);
+		}
+		
+		// no cues, find the cluster by its timestamp
+		self.seek_to(first_cluster)?;
+		let mut best = None;
+		let mut cluster = first_cluster;
+		
+		while let Some((start, id, len)) = self.next_element()? {
+			match id {
+				id::CLUSTER => {
+					cluster = start;
+					continue;
+				}
+				id::TIMESTAMP if self.cluster.is_some() => {
+					let time = read_uint(&mut self.reader, len)?;
+					self.pos += len;
+					
+					if time as i64 * scale > timestamp && best.is_some() {
+						break;
+					}
+					
+					// skip the rest of the cluster, if possible
+					best = Some((cluster, time));
+					if let Some(end) = self.cluster.and_then(|cluster| cluster.end) {
+						self.seek_to(end)?;
+					}
+				}
+				_ if len == UNKNOWN_SIZE => return Err(invalid_data("element with unknown size")),
+				_ => self.skip(len)?
+			}
+		}
+		
+		let (pos, time) = best.unwrap_or((first_cluster, 0));
+		self.seek_to(pos)?;
+		Ok(time as i64 * scale)
+	}
+	
+	fn seek_to(&mut self, pos: u64) -> io::Result<()> {
+		self.reader.seek(SeekFrom::Start(pos))?;
+		self.pos = pos;
+		self.cluster = None;
+		self.frames.clear();
+		Ok(())
+	}
+	
+	fn skip(&mut self, len: u64) -> io::Result<()> {
+		self.reader.seek_relative(len as i64)?;
+		self.pos += len;
+		Ok(())
+	}
+	
+	/// Reads the next element header in the segment, returns the start, ID and size. The content
+	/// has to be read or skipped by the caller, except for clusters.
+	fn next_element(&mut self) -> io::Result<Option<(u64, ElementId, u64)>> {
+		if self.segment_end.is_some_and(|end| self.pos >= end) {
+			return Ok(None);
+		} else if self.cluster.is_some_and(|cluster| cluster.end.is_some_and(|end| self.pos >= end)) {
+			self.cluster = None;
+		}
+		
+		let start = self.pos;
+		let (id, len, header_len) = match read_header(&mut self.reader) {
+			Ok(header) => header,
+			// the end of a segment with an unknown size or a truncated file
+			Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+			Err(e) => return Err(e)
+		};
+		
+		self.pos += header_len;
+		
+		if is_top_level(id) {
+			self.cluster = None;
+		}
+		
+		if id == id::CLUSTER {
+			self.cluster = Some(Cluster { timestamp: 0, end: (len != UNKNOWN_SIZE).then(|| self.pos + len) });
+		}
+		
+		Ok(Some((start, id, len)))
+	}
+	
+	/// Reads the next block and queues its frames, returns `false` at the end of the segment.
+	fn read_block(&mut self) -> io::Result<bool> {
+		while let Some((_, id, len)) = self.next_element()? {
+			let Some(cluster) = self.cluster else {
+				match len {
+					UNKNOWN_SIZE => return Err(invalid_data("element with unknown size")),
+					len          => self.skip(len)?
+				}
+				continue;
+			};
+			
+			match id {
+				id::CLUSTER => (),
+				id::TIMESTAMP => {
+					self.cluster = Some(Cluster { timestamp: read_uint(&mut self.reader, len)?, ..cluster });
+					self.pos += len;
+				}
+				id::SIMPLE_BLOCK => {
+					let block = Block::parse(&read_binary(&mut self.reader, len)?)?;
+					self.pos += len;
+					let keyframe = block.flags & FLAG_KEYFRAME != 0;
+					self.queue_frames(cluster, block, keyframe, None, 0);
+					return Ok(true);
+				}
+				id::BLOCK_GROUP => {
+					let group = BlockGroup::read(&mut self.reader, len)?;
+					self.pos += len;
+					let block = Block::parse(&group.block)?;
+					let keyframe = group.reference_block.is_empty();
+					self.queue_frames(cluster, block, keyframe, group.block_duration, group.discard_padding.unwrap_or(0));
+					return Ok(true);
+				}
+				_ if len == UNKNOWN_SIZE => return Err(invalid_data("element with unknown size")),
+				_ => self.skip(len)?
+			}
+		}
+		
+		Ok(false)
+	}
+	
+	fn queue_frames(&mut self, cluster: Cluster, block: Block, keyframe: bool, block_duration: Option<u64>, discard_padding: i64) {
+		let scale = self.info.timestamp_scale;
+		let track = self.track(block.track);
+		let count = block.frames.len() as u64;
+		let duration = block_duration.map(|duration| duration * scale / count)
+			.or_else(|| track.and_then(|track| track.default_duration));
+		
+		// header stripping, the only content encoding that is undone
+		let prefix = track
+			.and_then(|track| track.content_encodings.as_ref())
+			.and_then(|encodings| encodings.content_encoding.iter().find(|encoding| encoding.content_encoding_scope & 1 != 0
+				&& encoding.content_encoding_type == 0))
+			.and_then(|encoding| encoding.content_compression.as_ref())
+			.filter(|compression| compression.content_comp_algo == content_comp_algo::HEADER_STRIPPING)
+			.and_then(|compression| compression.content_comp_settings.clone())
+			.unwrap_or_default();
+		
+		let timestamp = (cluster.timestamp as i64 + block.timestamp as i64) * scale as i64;
+		let last = block.frames.len() - 1;
+		
+		for (i, data) in block.frames.into_iter().enumerate() {
+			self.frames.push_back(Frame {
+				track:           block.track,
+				timestamp:       timestamp + duration.unwrap_or(0) as i64 * i as i64,
+				duration,
+				keyframe,
+				invisible:       block.flags & FLAG_INVISIBLE != 0,
+				discardable:     block.flags & FLAG_DISCARDABLE != 0,
+				discard_padding: if i == last { discard_padding } else { 0 },
+				data:            match prefix.is_empty() {
+					true  => data,
+					false => [prefix.as_slice(), &data].concat()
+				}
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn el(id: ElementId, data: &[u8]) -> Vec<u8> {
+		let mut buf = id.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect::<Vec<_>>();
+		write_len(&mut buf, data.len() as u64).unwrap();
+		buf.extend_from_slice(data);
+		buf
+	}
+	
+	/// An element with an unknown size.
+	fn el_unknown(id: ElementId, data: &[u8]) -> Vec<u8> {
+		let mut buf = id.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect::<Vec<_>>();
+		buf.push(0xFF);
+		buf.extend_from_slice(data);
+		buf
+	}
+	
+	fn uint(id: ElementId, v: u64) -> Vec<u8> {
+		el(id, &v.to_be_bytes())
+	}
+	
+	fn block(id: ElementId, track: u64, timestamp: i16, flags: u8, lacing: Lacing, frames: &[&[u8]]) -> Vec<u8> {
+		let mut data = Vec::new();
+		Block { track, timestamp, flags, lacing, frames: frames.iter().map(|frame| frame.to_vec()).collect() }
+			.write(&mut data).unwrap();
+		el(id, &data)
+	}
+	
+	/// A file with a video, an Opus and a subtitle track and three clusters one second apart.
+	/// The cues are stored after the clusters and found through the seek head.
+	fn file(streamed: bool) -> Vec<u8> {
+		let header = el(id::EBML, &[
+			el(id::DOC_TYPE, b"webm"),
+			uint(id::DOC_TYPE_VERSION, 4),
+			uint(id::DOC_TYPE_READ_VERSION, 2)
+		].concat());
+		
+		let info = el(id::INFO, &[
+			uint(id::TIMESTAMP_SCALE, 1_000_000),
+			el(id::DURATION, &3000f64.to_be_bytes()),
+			el(id::MUXING_APP, b"test"),
+			el(id::WRITING_APP, b"test")
+		].concat());
+		
+		let tracks = el(id::TRACKS, &[
+			el(id::TRACK_ENTRY, &[
+				uint(id::TRACK_NUMBER, 1),
+				uint(id::TRACK_UID, 11),
+				uint(id::TRACK_TYPE, track_type::VIDEO),
+				el(id::CODEC_ID, b"V_VP9"),
+				el(id::VIDEO, &[uint(id::PIXEL_WIDTH, 640), uint(id::PIXEL_HEIGHT, 360)].concat())
+			].concat()),
+			el(id::TRACK_ENTRY, &[
+				uint(id::TRACK_NUMBER, 2),
+				uint(id::TRACK_UID, 12),
+				uint(id::TRACK_TYPE, track_type::AUDIO),
+				el(id::CODEC_ID, b"A_OPUS"),
+				el(id::CODEC_PRIVATE, b"OpusHead\x01\x02\x38\x01\x80\xBB\0\0\0\0\0"),
+				uint(id::CODEC_DELAY, 6_500_000),
+				uint(id::SEEK_PRE_ROLL, 80_000_000),
+				uint(id::DEFAULT_DURATION, 20_000_000),
+				el(id::AUDIO, &[el(id::SAMPLING_FREQUENCY, &48000f32.to_be_bytes()), uint(id::CHANNELS, 2)].concat())
+			].concat()),
+			el(id::TRACK_ENTRY, &[
+				uint(id::TRACK_NUMBER, 3),
+				uint(id::TRACK_UID, 13),
+				uint(id::TRACK_TYPE, track_type::SUBTITLE),
+				el(id::CODEC_ID, b"S_TEXT/UTF8"),
+				el(id::LANGUAGE, b"ger\0"),
+				el(id::CONTENT_ENCODINGS, &el(id::CONTENT_ENCODING, &el(id::CONTENT_COMPRESSION, &[
+					uint(id::CONTENT_COMP_ALGO, content_comp_algo::HEADER_STRIPPING),
+					el(id::CONTENT_COMP_SETTINGS, b"Hello, ")
+				].concat())))
+			].concat())
+		].concat());
+		
+		let clusters = (0..3u8).map(|i| {
+			let mut data = [
+				uint(id::TIMESTAMP, i as u64 * 1000),
+				block(id::SIMPLE_BLOCK, 1, 0, FLAG_KEYFRAME, Lacing::None, &[&[i; 100]]),
+				block(id::SIMPLE_BLOCK, 2, 0, FLAG_KEYFRAME, Lacing::Xiph, &[&[1; 300], &[2; 10], &[3; 20]]),
+				block(id::SIMPLE_BLOCK, 1, 40, 0, Lacing::None, &[&[i; 50]])
+			].concat();
+			
+			if i == 2 {
+				data.extend(el(id::BLOCK_GROUP, &[
+					block(id::BLOCK, 3, 500, 0, Lacing::None, &[b"world"]),
+					uint(id::BLOCK_DURATION, 250)
+				].concat()));
+				data.extend(el(id::BLOCK_GROUP, &[
+					block(id::BLOCK, 2, 60, 0, Lacing::Ebml, &[&[4; 5], &[5; 3]]),
+					el(id::REFERENCE_BLOCK, &[0xEC]),
+					uint(id::DISCARD_PADDING, 1000)
+				].concat()));
+			}
+			
+			match streamed {
+				true  => el_unknown(id::CLUSTER, &data),
+				false => el(id::CLUSTER, &data)
+			}
+		}).collect::<Vec<_>>();
+		
+		if streamed {
+			return [header, el_unknown(id::SEGMENT, &[info, tracks, clusters.concat()].concat())].concat();
+		}
+		
+		let seek_head_len = el(id::SEEK_HEAD, &el(id::SEEK, &[el(id::SEEK_ID, &[0; 4]), uint(id::SEEK_POSITION, 0)].concat())).len();
+		let mut pos = (seek_head_len + info.len() + tracks.len()) as u64;
+		let mut cue_points = Vec::new();
+		
+		for (i, cluster) in clusters.iter().enumerate() {
+			cue_points.extend(el(id::CUE_POINT, &[
+				uint(id::CUE_TIME, i as u64 * 1000),
+				el(id::CUE_TRACK_POSITIONS, &[uint(id::CUE_TRACK, 1), uint(id::CUE_CLUSTER_POSITION, pos)].concat())
+			].concat()));
+			pos += cluster.len() as u64;
+		}
+		
+		let seek_head = el(id::SEEK_HEAD, &el(id::SEEK, &[
+			el(id::SEEK_ID, &id::CUES.to_be_bytes()),
+			uint(id::SEEK_POSITION, pos)
+		].concat()));
+		
+		let segment = [seek_head, info, tracks, clusters.concat(), el(id::CUES, &cue_points)].concat();
+		[header, el(id::SEGMENT, &segment)].concat()
+	}
+	
+	#[test]
+	fn lacing() {
+		let frames = vec![vec![1; 300], vec![2; 5], vec![3; 1000], vec![4; 0], vec![5; 7]];
+		for lacing in [Lacing::None, Lacing::Xiph, Lacing::Fixed, Lacing::Ebml] {
+			let frames = match lacing {
+				Lacing::None  => vec![frames[0].clone()],
+				Lacing::Fixed => vec![vec![6; 10]; 4],
+				_             => frames.clone()
+			};
+			
+			let block = Block { track: 200, timestamp: -3, flags: FLAG_KEYFRAME, lacing, frames };
+			let mut data = Vec::new();
+			block.write(&mut data).unwrap();
+			assert_eq!(Block::parse(&data).unwrap(), block);
+		}
+		
+		// EBML lacing with a negative size difference
+		let block = Block::parse(&[0x81, 0, 1, 0x86, 2, 0x83, 0xBD, 1, 1, 1, 2, 3, 3]).unwrap();
+		assert_eq!(block.frames, vec![vec![1; 3], vec![2], vec![3; 2]]);
+		
+		assert!(Block::parse(&[0x81, 0, 0, 0x04, 2, 1, 2, 3, 4]).is_err());
+		assert!(Block::parse(&[0x81, 0, 0, 0x02, 1, 0xFF, 1]).is_err());
+	}
+	
+	#[test]
+	fn header() {
+		let reader = MkvReader::new(io::Cursor::new(file(false))).unwrap();
+		assert_eq!(reader.header.doc_type, "webm");
+		assert_eq!(reader.info.muxing_app, "test");
+		assert_eq!(reader.duration(), Some(3_000_000_000));
+		assert_eq!(reader.tracks.len(), 3);
+		
+		let video = reader.track(1).unwrap();
+		assert_eq!(video.codec_id, "V_VP9");
+		assert_eq!(video.flag_enabled, 1);
+		assert_eq!(video.language, "eng");
+		assert_eq!(video.video.as_ref().map(|video| (video.pixel_width, video.pixel_height)), Some((640, 360)));
+		
+		let audio = reader.track(2).unwrap();
+		assert_eq!(audio.track_type, track_type::AUDIO);
+		assert_eq!(&audio.codec_private.as_ref().unwrap()[..8], b"OpusHead");
+		assert_eq!(audio.seek_pre_roll, 80_000_000);
+		assert_eq!(audio.audio.as_ref().map(|audio| (audio.sampling_frequency, audio.channels)), Some((48000.0, 2)));
+		assert_eq!(reader.track(3).unwrap().language, "ger");
+		
+		let cues = reader.cues.as_ref().unwrap();
+		assert_eq!(cues.cue_point.iter().map(|point| point.cue_time).collect::<Vec<_>>(), [0, 1000, 2000]);
+		
+		let mut data = file(false);
+		data[4..8].copy_from_slice(b"\x84mkv");
+		assert!(MkvReader::new(io::Cursor::new(data)).is_err());
+	}
+	
+	#[test]
+	fn frames() {
+		for streamed in [false, true] {
+			let mut reader = MkvReader::new(io::Cursor::new(file(streamed))).unwrap();
+			let mut frames = Vec::new();
+			while let Some(frame) = reader.read_frame().unwrap() {
+				frames.push(frame);
+			}
+			
+			assert_eq!(frames.len(), 3 * 5 + 3);
+			assert_eq!(frames.iter().map(|frame| (frame.track, frame.timestamp / 1_000_000, frame.keyframe)).collect::<Vec<_>>(), [
+				(1, 0, true), (2, 0, true), (2, 20, true), (2, 40, true), (1, 40, false),
+				(1, 1000, true), (2, 1000, true), (2, 1020, true), (2, 1040, true), (1, 1040, false),
+				(1, 2000, true), (2, 2000, true), (2, 2020, true), (2, 2040, true), (1, 2040, false),
+				(3, 2500, true), (2, 2060, false), (2, 2080, false)
+			]);
+			
+			assert_eq!(frames[1].data, [1; 300]);
+			assert_eq!(frames[2].data, [2; 10]);
+			assert_eq!(frames[2].duration, Some(20_000_000));
+			assert_eq!(frames[5].data, [1; 100]);
+			assert_eq!(frames[15].data, b"Hello, world");
+			assert_eq!(frames[15].duration, Some(250_000_000));
+			assert_eq!(frames[17].data, [5; 3]);
+			assert_eq!((frames[16].discard_padding, frames[17].discard_padding), (0, 1000));
+		}
+	}
+	
+	#[test]
+	fn seek() {
+		for streamed in [false, true] {
+			let mut reader = MkvReader::new(io::Cursor::new(file(streamed))).unwrap();
+			assert!(reader.cues.is_some() != streamed);
+			
+			for (target, cluster) in [(1500, 1000), (2000, 2000), (999, 0), (5000, 2000), (-10, 0)] {
+				assert_eq!(reader.seek(target * 1_000_000, Some(1)).unwrap(), cluster * 1_000_000);
+				let frame = reader.read_frame().unwrap().unwrap();
+				assert_eq!((frame.track, frame.timestamp), (1, cluster * 1_000_000));
+			}
+			
+			reader.seek(2_000_000_000, None).unwrap();
+			assert_eq!(std::iter::from_fn(|| reader.read_frame().unwrap()).count(), 8);
+		}
+	}
+	
+	#[test]
+	fn elements() {
+		let data = [uint(id::TRACK_NUMBER, 1), el(id::VOID, &[0; 3]), el(id::CODEC_ID, b"A_OPUS")].concat();
+		let track = TrackEntry::read(&mut data.as_slice(), data.len() as u64);
+		assert_eq!(track.unwrap_err().to_string(), "missing element `TrackUID`");
+		
+		let data = [data, uint(id::TRACK_UID, 5), uint(id::TRACK_TYPE, track_type::AUDIO), uint(id::FLAG_DEFAULT, 0)].concat();
+		let track = TrackEntry::read(&mut data.as_slice(), data.len() as u64).unwrap();
+		assert_eq!((track.track_uid, track.flag_default, track.flag_lacing), (5, 0, 1));
+		
+		assert_eq!(element_name(id::SIMPLE_BLOCK), Some("SimpleBlock"));
+		assert_eq!(element_name(0x4000), None);
+		assert_eq!(read_header(&mut [0x1F, 0x43, 0xB6, 0x75, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF].as_slice()).unwrap(),
+			(id::CLUSTER, UNKNOWN_SIZE, 12));
+		assert_eq!(read_int(&mut [0xFF, 0xFE].as_slice(), 2).unwrap(), -2);
+	}
+}
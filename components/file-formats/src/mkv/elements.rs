@@ -0,0 +1,3097 @@
+// Generated by mkvgen from the `ebml`, `matroska` EBML schemas, do not edit.
+
+use super::*;
+
+pub mod id {
+	use crate::ebml::ElementId;
+	
+	/// Set the EBML characteristics of the data to follow. Each EBML Document has to start with this.
+	pub const EBML: ElementId = 0x1A45DFA3;
+	/// The version of EBML specifications used to create the EBML Document.
+	pub const EBML_VERSION: ElementId = 0x4286;
+	/// The minimum EBML version an EBML Reader has to support to read this EBML Document.
+	pub const EBML_READ_VERSION: ElementId = 0x42F7;
+	/// The maximum length of the IDs you'll find in this EBML Document.
+	pub const EBML_MAX_ID_LENGTH: ElementId = 0x42F2;
+	/// The maximum length of the sizes you'll find in this EBML Document.
+	pub const EBML_MAX_SIZE_LENGTH: ElementId = 0x42F3;
+	/// A string that describes and identifies the content of the EBML Body that follows this EBML Header.
+	pub const DOC_TYPE: ElementId = 0x4282;
+	/// The version of DocType interpreter used to create the EBML Document.
+	pub const DOC_TYPE_VERSION: ElementId = 0x4287;
+	/// The minimum DocType version an EBML Reader has to support to read this EBML Document.
+	pub const DOC_TYPE_READ_VERSION: ElementId = 0x4285;
+	/// A DocTypeExtension adds extra Elements to the main DocType+DocTypeVersion tuple it's attached to.
+	pub const DOC_TYPE_EXTENSION: ElementId = 0x4281;
+	/// The name of the DocTypeExtension to differentiate it from other DocTypeExtensions of the same DocType+DocTypeVersion tuple.
+	pub const DOC_TYPE_EXTENSION_NAME: ElementId = 0x4283;
+	/// The version of the DocTypeExtension.
+	pub const DOC_TYPE_EXTENSION_VERSION: ElementId = 0x4284;
+	/// The CRC is computed on all the data of the Master Element it's in. The CRC Element should be the first in it's parent Master for easier reading.
+	pub const CRC_32: ElementId = 0xBF;
+	/// Used to void data or to avoid unexpected behaviors when using damaged data. The content is discarded.
+	pub const VOID: ElementId = 0xEC;
+	/// The Root Element that contains all other Top-Level Elements; see the data layout.
+	pub const SEGMENT: ElementId = 0x18538067;
+	/// Contains seeking information of Top-Level Elements.
+	pub const SEEK_HEAD: ElementId = 0x114D9B74;
+	/// Contains a single seek entry to an EBML Element.
+	pub const SEEK: ElementId = 0x4DBB;
+	/// The binary EBML ID of a Top-Level Element.
+	pub const SEEK_ID: ElementId = 0x53AB;
+	/// The Segment Position of a Top-Level Element.
+	pub const SEEK_POSITION: ElementId = 0x53AC;
+	/// Contains general information about the Segment.
+	pub const INFO: ElementId = 0x1549A966;
+	/// A randomly generated unique ID to identify the Segment amongst many others (128 bits).
+	pub const SEGMENT_UUID: ElementId = 0x73A4;
+	/// A filename corresponding to this Segment.
+	pub const SEGMENT_FILENAME: ElementId = 0x7384;
+	/// An ID to identify the previous Segment of a Linked Segment.
+	pub const PREV_UUID: ElementId = 0x3CB923;
+	/// A filename corresponding to the file of the previous Linked Segment.
+	pub const PREV_FILENAME: ElementId = 0x3C83AB;
+	/// An ID to identify the next Segment of a Linked Segment.
+	pub const NEXT_UUID: ElementId = 0x3EB923;
+	/// A filename corresponding to the file of the next Linked Segment.
+	pub const NEXT_FILENAME: ElementId = 0x3E83BB;
+	/// A unique ID that all Segments of a Linked Segment share (128 bits).
+	pub const SEGMENT_FAMILY: ElementId = 0x4444;
+	/// The mapping between this Segment and a segment value in the given Chapter Codec.
+	pub const CHAPTER_TRANSLATE: ElementId = 0x6924;
+	/// The binary value used to represent this Segment in the chapter codec data.
+	pub const CHAPTER_TRANSLATE_ID: ElementId = 0x69A5;
+	/// This ChapterTranslate applies to this chapter codec of the given chapter edition(s).
+	pub const CHAPTER_TRANSLATE_CODEC: ElementId = 0x69BF;
+	/// Specify a chapter edition UID on which this ChapterTranslate applies.
+	pub const CHAPTER_TRANSLATE_EDITION_UID: ElementId = 0x69FC;
+	/// Base unit for Segment Ticks and Track Ticks, in nanoseconds. A TimestampScale value of 1000000 means scaled timestamps in the Segment are expressed in milliseconds.
+	pub const TIMESTAMP_SCALE: ElementId = 0x2AD7B1;
+	/// Duration of the Segment, expressed in Segment Ticks which is based on TimestampScale.
+	pub const DURATION: ElementId = 0x4489;
+	/// The date and time that the Segment was created by the muxing application or library.
+	pub const DATE_UTC: ElementId = 0x4461;
+	/// General name of the Segment.
+	pub const TITLE: ElementId = 0x7BA9;
+	/// Muxing application or library.
+	pub const MUXING_APP: ElementId = 0x4D80;
+	/// Writing application.
+	pub const WRITING_APP: ElementId = 0x5741;
+	/// The Top-Level Element containing the (monolithic) Block structure.
+	pub const CLUSTER: ElementId = 0x1F43B675;
+	/// Absolute timestamp of the cluster, expressed in Segment Ticks which is based on TimestampScale.
+	pub const TIMESTAMP: ElementId = 0xE7;
+	/// The Segment Position of the Cluster in the Segment (0 in live streams).
+	pub const POSITION: ElementId = 0xA7;
+	/// Size of the previous Cluster, in octets. Can be useful for backward playing.
+	pub const PREV_SIZE: ElementId = 0xAB;
+	/// Similar to Block but without all the extra information, mostly used to reduce overhead when no extra feature is needed.
+	pub const SIMPLE_BLOCK: ElementId = 0xA3;
+	/// Basic container of information containing a single Block and information specific to that Block.
+	pub const BLOCK_GROUP: ElementId = 0xA0;
+	/// Block containing the actual data to be rendered and a timestamp relative to the Cluster Timestamp.
+	pub const BLOCK: ElementId = 0xA1;
+	/// Contain additional binary data to complete the main one.
+	pub const BLOCK_ADDITIONS: ElementId = 0x75A1;
+	/// Contain the BlockAdditional and some parameters.
+	pub const BLOCK_MORE: ElementId = 0xA6;
+	/// Interpreted by the codec as it wishes (using the BlockAddID).
+	pub const BLOCK_ADDITIONAL: ElementId = 0xA5;
+	/// An ID to identify how to interpret the BlockAdditional data.
+	pub const BLOCK_ADD_ID: ElementId = 0xEE;
+	/// The duration of the Block, expressed in Track Ticks.
+	pub const BLOCK_DURATION: ElementId = 0x9B;
+	/// This frame is referenced and has the specified cache priority.
+	pub const REFERENCE_PRIORITY: ElementId = 0xFA;
+	/// A timestamp value, relative to the timestamp of the Block in this BlockGroup, expressed in Track Ticks. This is used to reference other frames necessary to decode this frame.
+	pub const REFERENCE_BLOCK: ElementId = 0xFB;
+	/// The new codec state to use. Data interpretation is private to the codec.
+	pub const CODEC_STATE: ElementId = 0xA4;
+	/// Duration of the silent data added to the Block, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const DISCARD_PADDING: ElementId = 0x75A2;
+	/// A Top-Level Element of information with many tracks described.
+	pub const TRACKS: ElementId = 0x1654AE6B;
+	/// Describes a track with all Elements.
+	pub const TRACK_ENTRY: ElementId = 0xAE;
+	/// The track number as used in the Block Header.
+	pub const TRACK_NUMBER: ElementId = 0xD7;
+	/// A unique ID to identify the Track.
+	pub const TRACK_UID: ElementId = 0x73C5;
+	/// The TrackType defines the type of each frame found in the Track.
+	pub const TRACK_TYPE: ElementId = 0x83;
+	/// Set to 1 if the track is usable.
+	pub const FLAG_ENABLED: ElementId = 0xB9;
+	/// Set if that track (audio, video or subs) is eligible for automatic selection by the player.
+	pub const FLAG_DEFAULT: ElementId = 0x88;
+	/// Applies only to subtitles. Set if that track is eligible for automatic selection by the player if it matches the user's language preference.
+	pub const FLAG_FORCED: ElementId = 0x55AA;
+	/// Set to 1 if and only if that track is suitable for users with hearing impairments.
+	pub const FLAG_HEARING_IMPAIRED: ElementId = 0x55AB;
+	/// Set to 1 if and only if that track is suitable for users with visual impairments.
+	pub const FLAG_VISUAL_IMPAIRED: ElementId = 0x55AC;
+	/// Set to 1 if and only if that track contains textual descriptions of video content.
+	pub const FLAG_TEXT_DESCRIPTIONS: ElementId = 0x55AD;
+	/// Set to 1 if and only if that track is in the content's original language.
+	pub const FLAG_ORIGINAL: ElementId = 0x55AE;
+	/// Set to 1 if and only if that track contains commentary.
+	pub const FLAG_COMMENTARY: ElementId = 0x55AF;
+	/// Set to 1 if the track **MAY** contain blocks using lacing.
+	pub const FLAG_LACING: ElementId = 0x9C;
+	/// Number of nanoseconds per frame, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const DEFAULT_DURATION: ElementId = 0x23E383;
+	/// The period between two successive fields at the output of the decoding process, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const DEFAULT_DECODED_FIELD_DURATION: ElementId = 0x234E7A;
+	/// The maximum value of BlockAddID.
+	pub const MAX_BLOCK_ADDITION_ID: ElementId = 0x55EE;
+	/// Contains elements that extend the track format, by adding content either to each frame, with BlockAddID, or to the track as a whole with BlockAddIDExtraData.
+	pub const BLOCK_ADDITION_MAPPING: ElementId = 0x41E4;
+	/// If the track format extension needs content beside frames, the value refers to the BlockAddID value being described.
+	pub const BLOCK_ADD_ID_VALUE: ElementId = 0x41F0;
+	/// A human-friendly name describing the type of BlockAdditional data.
+	pub const BLOCK_ADD_ID_NAME: ElementId = 0x41A4;
+	/// Stores the registered identifier of the Block Additional Mapping to define how the BlockAdditional data should be handled.
+	pub const BLOCK_ADD_ID_TYPE: ElementId = 0x41E7;
+	/// Extra binary data that the BlockAddIDType can use to interpret the BlockAdditional data.
+	pub const BLOCK_ADD_ID_EXTRA_DATA: ElementId = 0x41ED;
+	/// A human-readable track name.
+	pub const NAME: ElementId = 0x536E;
+	/// The language of the track, in the Matroska languages form.
+	pub const LANGUAGE: ElementId = 0x22B59C;
+	/// The language of the track, in the BCP47 form. If this Element is used, then any Language Elements used in the same TrackEntry **MUST** be ignored.
+	pub const LANGUAGE_BCP47: ElementId = 0x22B59D;
+	/// An ID corresponding to the codec, see the codec page for more info.
+	pub const CODEC_ID: ElementId = 0x86;
+	/// Private data only known to the codec.
+	pub const CODEC_PRIVATE: ElementId = 0x63A2;
+	/// A human-readable string specifying the codec.
+	pub const CODEC_NAME: ElementId = 0x258688;
+	/// CodecDelay is the codec-built-in delay, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const CODEC_DELAY: ElementId = 0x56AA;
+	/// After a discontinuity, SeekPreRoll is the duration of the data the decoder **MUST** decode before the decoded data is valid, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const SEEK_PRE_ROLL: ElementId = 0x56BB;
+	/// The mapping between this TrackEntry and a track value in the given Chapter Codec.
+	pub const TRACK_TRANSLATE: ElementId = 0x6624;
+	/// The binary value used to represent this TrackEntry in the chapter codec data.
+	pub const TRACK_TRANSLATE_TRACK_ID: ElementId = 0x66A5;
+	/// This TrackTranslate applies to this chapter codec of the given chapter edition(s).
+	pub const TRACK_TRANSLATE_CODEC: ElementId = 0x66BF;
+	/// Specify a chapter edition UID on which this TrackTranslate applies.
+	pub const TRACK_TRANSLATE_EDITION_UID: ElementId = 0x66FC;
+	/// Video settings.
+	pub const VIDEO: ElementId = 0xE0;
+	/// Specify whether the video frames in this track are interlaced.
+	pub const FLAG_INTERLACED: ElementId = 0x9A;
+	/// Specify the field ordering of video frames in this track.
+	pub const FIELD_ORDER: ElementId = 0x9D;
+	/// Stereo-3D video mode.
+	pub const STEREO_MODE: ElementId = 0x53B8;
+	/// Indicate whether the BlockAdditional Element with BlockAddID of "1" contains Alpha data.
+	pub const ALPHA_MODE: ElementId = 0x53C0;
+	/// Width of the encoded video frames in pixels.
+	pub const PIXEL_WIDTH: ElementId = 0xB0;
+	/// Height of the encoded video frames in pixels.
+	pub const PIXEL_HEIGHT: ElementId = 0xBA;
+	/// The number of video pixels to remove at the bottom of the image.
+	pub const PIXEL_CROP_BOTTOM: ElementId = 0x54AA;
+	/// The number of video pixels to remove at the top of the image.
+	pub const PIXEL_CROP_TOP: ElementId = 0x54BB;
+	/// The number of video pixels to remove on the left of the image.
+	pub const PIXEL_CROP_LEFT: ElementId = 0x54CC;
+	/// The number of video pixels to remove on the right of the image.
+	pub const PIXEL_CROP_RIGHT: ElementId = 0x54DD;
+	/// Width of the video frames to display. Applies to the video frame after cropping (PixelCrop* Elements).
+	pub const DISPLAY_WIDTH: ElementId = 0x54B0;
+	/// Height of the video frames to display. Applies to the video frame after cropping (PixelCrop* Elements).
+	pub const DISPLAY_HEIGHT: ElementId = 0x54BA;
+	/// How DisplayWidth & DisplayHeight are interpreted.
+	pub const DISPLAY_UNIT: ElementId = 0x54B2;
+	/// Specify the uncompressed pixel format used for the Track's data as a FourCC.
+	pub const UNCOMPRESSED_FOUR_CC: ElementId = 0x2EB524;
+	/// Settings describing the colour format.
+	pub const COLOUR: ElementId = 0x55B0;
+	/// The Matrix Coefficients of the video used to derive luma and chroma values from red, green, and blue color primaries.
+	pub const MATRIX_COEFFICIENTS: ElementId = 0x55B1;
+	/// Number of decoded bits per channel. A value of 0 indicates that the BitsPerChannel is unspecified.
+	pub const BITS_PER_CHANNEL: ElementId = 0x55B2;
+	/// The amount of pixels to remove in the Cr and Cb channels for every pixel not removed horizontally.
+	pub const CHROMA_SUBSAMPLING_HORZ: ElementId = 0x55B3;
+	/// The amount of pixels to remove in the Cr and Cb channels for every pixel not removed vertically.
+	pub const CHROMA_SUBSAMPLING_VERT: ElementId = 0x55B4;
+	/// The amount of pixels to remove in the Cb channel for every pixel not removed horizontally. This is additive with ChromaSubsamplingHorz.
+	pub const CB_SUBSAMPLING_HORZ: ElementId = 0x55B5;
+	/// The amount of pixels to remove in the Cb channel for every pixel not removed vertically. This is additive with ChromaSubsamplingVert.
+	pub const CB_SUBSAMPLING_VERT: ElementId = 0x55B6;
+	/// How chroma is subsampled horizontally.
+	pub const CHROMA_SITING_HORZ: ElementId = 0x55B7;
+	/// How chroma is subsampled vertically.
+	pub const CHROMA_SITING_VERT: ElementId = 0x55B8;
+	/// Clipping of the color ranges.
+	pub const RANGE: ElementId = 0x55B9;
+	/// The transfer characteristics of the video.
+	pub const TRANSFER_CHARACTERISTICS: ElementId = 0x55BA;
+	/// The colour primaries of the video.
+	pub const PRIMARIES: ElementId = 0x55BB;
+	/// Maximum brightness of a single pixel (Maximum Content Light Level) in candelas per square meter (cd/m^2).
+	pub const MAX_CLL: ElementId = 0x55BC;
+	/// Maximum brightness of a single full frame (Maximum Frame-Average Light Level) in candelas per square meter (cd/m^2).
+	pub const MAX_FALL: ElementId = 0x55BD;
+	/// SMPTE 2086 mastering data.
+	pub const MASTERING_METADATA: ElementId = 0x55D0;
+	/// Red X chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_R_CHROMATICITY_X: ElementId = 0x55D1;
+	/// Red Y chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_R_CHROMATICITY_Y: ElementId = 0x55D2;
+	/// Green X chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_G_CHROMATICITY_X: ElementId = 0x55D3;
+	/// Green Y chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_G_CHROMATICITY_Y: ElementId = 0x55D4;
+	/// Blue X chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_B_CHROMATICITY_X: ElementId = 0x55D5;
+	/// Blue Y chromaticity coordinate, as defined by CIE 1931.
+	pub const PRIMARY_B_CHROMATICITY_Y: ElementId = 0x55D6;
+	/// White X chromaticity coordinate, as defined by CIE 1931.
+	pub const WHITE_POINT_CHROMATICITY_X: ElementId = 0x55D7;
+	/// White Y chromaticity coordinate, as defined by CIE 1931.
+	pub const WHITE_POINT_CHROMATICITY_Y: ElementId = 0x55D8;
+	/// Maximum luminance. Represented in candelas per square meter (cd/m^2).
+	pub const LUMINANCE_MAX: ElementId = 0x55D9;
+	/// Minimum luminance. Represented in candelas per square meter (cd/m^2).
+	pub const LUMINANCE_MIN: ElementId = 0x55DA;
+	/// Describes the video projection details. Used to render spherical, VR videos or flipping videos horizontally/vertically.
+	pub const PROJECTION: ElementId = 0x7670;
+	/// Describes the projection used for this video track.
+	pub const PROJECTION_TYPE: ElementId = 0x7671;
+	/// Private data that only applies to a specific projection.
+	pub const PROJECTION_PRIVATE: ElementId = 0x7672;
+	/// Specifies a yaw rotation to the projection, in degrees.
+	pub const PROJECTION_POSE_YAW: ElementId = 0x7673;
+	/// Specifies a pitch rotation to the projection, in degrees.
+	pub const PROJECTION_POSE_PITCH: ElementId = 0x7674;
+	/// Specifies a roll rotation to the projection, in degrees.
+	pub const PROJECTION_POSE_ROLL: ElementId = 0x7675;
+	/// Audio settings.
+	pub const AUDIO: ElementId = 0xE1;
+	/// Sampling frequency in Hz.
+	pub const SAMPLING_FREQUENCY: ElementId = 0xB5;
+	/// Real output sampling frequency in Hz (used for SBR techniques).
+	pub const OUTPUT_SAMPLING_FREQUENCY: ElementId = 0x78B5;
+	/// Numbers of channels in the track.
+	pub const CHANNELS: ElementId = 0x9F;
+	/// Bits per sample, mostly used for PCM.
+	pub const BIT_DEPTH: ElementId = 0x6264;
+	/// Audio emphasis applied on audio samples. The player **MUST** apply the inverse emphasis to get the proper audio samples.
+	pub const EMPHASIS: ElementId = 0x52F1;
+	/// Operation that needs to be applied on tracks to create this virtual track.
+	pub const TRACK_OPERATION: ElementId = 0xE2;
+	/// Contains the list of all video plane tracks that need to be combined to create this 3D track.
+	pub const TRACK_COMBINE_PLANES: ElementId = 0xE3;
+	/// Contains a video plane track that need to be combined to create this 3D track.
+	pub const TRACK_PLANE: ElementId = 0xE4;
+	/// The trackUID number of the track representing the plane.
+	pub const TRACK_PLANE_UID: ElementId = 0xE5;
+	/// The kind of plane this track corresponds to.
+	pub const TRACK_PLANE_TYPE: ElementId = 0xE6;
+	/// Contains the list of all tracks whose Blocks need to be combined to create this virtual track.
+	pub const TRACK_JOIN_BLOCKS: ElementId = 0xE9;
+	/// The trackUID number of a track whose blocks are used to create this virtual track.
+	pub const TRACK_JOIN_UID: ElementId = 0xED;
+	/// Settings for several content encoding mechanisms like compression or encryption.
+	pub const CONTENT_ENCODINGS: ElementId = 0x6D80;
+	/// Settings for one content encoding like compression or encryption.
+	pub const CONTENT_ENCODING: ElementId = 0x6240;
+	/// Tell in which order to apply each ContentEncoding of the ContentEncodings. The decoder/demuxer **MUST** start with the ContentEncoding with the highest ContentEncodingOrder and work its way down to the ContentEncoding with the lowest ContentEncodingOrder.
+	pub const CONTENT_ENCODING_ORDER: ElementId = 0x5031;
+	/// A bit field that describes which Elements have been modified in this way.
+	pub const CONTENT_ENCODING_SCOPE: ElementId = 0x5032;
+	/// A value describing what kind of transformation is applied.
+	pub const CONTENT_ENCODING_TYPE: ElementId = 0x5033;
+	/// Settings describing the compression used.
+	pub const CONTENT_COMPRESSION: ElementId = 0x5034;
+	/// The compression algorithm used.
+	pub const CONTENT_COMP_ALGO: ElementId = 0x4254;
+	/// Settings that might be needed by the decompressor. For Header Stripping (ContentCompAlgo=3), the bytes that were removed from the beginning of each frame of the track.
+	pub const CONTENT_COMP_SETTINGS: ElementId = 0x4255;
+	/// Settings describing the encryption used.
+	pub const CONTENT_ENCRYPTION: ElementId = 0x5035;
+	/// The encryption algorithm used.
+	pub const CONTENT_ENC_ALGO: ElementId = 0x47E1;
+	/// For public key algorithms this is the ID of the public key the the data was encrypted with.
+	pub const CONTENT_ENC_KEY_ID: ElementId = 0x47E2;
+	/// Settings describing the encryption algorithm used.
+	pub const CONTENT_ENC_AES_SETTINGS: ElementId = 0x47E7;
+	/// The AES cipher mode used in the encryption.
+	pub const AES_SETTINGS_CIPHER_MODE: ElementId = 0x47E8;
+	/// A Top-Level Element to speed seeking access. All entries are local to the Segment.
+	pub const CUES: ElementId = 0x1C53BB6B;
+	/// Contains all information relative to a seek point in the Segment.
+	pub const CUE_POINT: ElementId = 0xBB;
+	/// Absolute timestamp of the seek point, expressed in Segment Ticks which is based on TimestampScale.
+	pub const CUE_TIME: ElementId = 0xB3;
+	/// Contain positions for different tracks corresponding to the timestamp.
+	pub const CUE_TRACK_POSITIONS: ElementId = 0xB7;
+	/// The track for which a position is given.
+	pub const CUE_TRACK: ElementId = 0xF7;
+	/// The Segment Position of the Cluster containing the associated Block.
+	pub const CUE_CLUSTER_POSITION: ElementId = 0xF1;
+	/// The relative position inside the Cluster of the referenced SimpleBlock or BlockGroup with 0 being the first possible position for an Element inside that Cluster.
+	pub const CUE_RELATIVE_POSITION: ElementId = 0xF0;
+	/// The duration of the block, expressed in Segment Ticks which is based on TimestampScale.
+	pub const CUE_DURATION: ElementId = 0xB2;
+	/// Number of the Block in the specified Cluster.
+	pub const CUE_BLOCK_NUMBER: ElementId = 0x5378;
+	/// The Segment Position of the Codec State corresponding to this Cue Element. 0 means that the data is taken from the initial Track Entry.
+	pub const CUE_CODEC_STATE: ElementId = 0xEA;
+	/// The Clusters containing the referenced Blocks.
+	pub const CUE_REFERENCE: ElementId = 0xDB;
+	/// Timestamp of the referenced Block, expressed in Segment Ticks which is based on TimestampScale.
+	pub const CUE_REF_TIME: ElementId = 0x96;
+	/// Contain attached files.
+	pub const ATTACHMENTS: ElementId = 0x1941A469;
+	/// An attached file.
+	pub const ATTACHED_FILE: ElementId = 0x61A7;
+	/// A human-friendly name for the attached file.
+	pub const FILE_DESCRIPTION: ElementId = 0x467E;
+	/// Filename of the attached file.
+	pub const FILE_NAME: ElementId = 0x466E;
+	/// Media type of the file following the format described in RFC 6838.
+	pub const FILE_MEDIA_TYPE: ElementId = 0x4660;
+	/// The data of the file.
+	pub const FILE_DATA: ElementId = 0x465C;
+	/// Unique ID representing the file, as random as possible.
+	pub const FILE_UID: ElementId = 0x46AE;
+	/// A system to define basic menus and partition data.
+	pub const CHAPTERS: ElementId = 0x1043A770;
+	/// Contains all information about a Segment edition.
+	pub const EDITION_ENTRY: ElementId = 0x45B9;
+	/// A unique ID to identify the edition. It's useful for tagging an edition.
+	pub const EDITION_UID: ElementId = 0x45BC;
+	/// Set to 1 if an edition is hidden. Hidden editions **SHOULD NOT** be available to the user interface (but still to Control Tracks).
+	pub const EDITION_FLAG_HIDDEN: ElementId = 0x45BD;
+	/// Set to 1 if the edition **SHOULD** be used as the default one.
+	pub const EDITION_FLAG_DEFAULT: ElementId = 0x45DB;
+	/// Set to 1 if the chapters can be defined multiple times and the order to play them is enforced.
+	pub const EDITION_FLAG_ORDERED: ElementId = 0x45DD;
+	/// Contains a possible string to use for the edition display for the given languages.
+	pub const EDITION_DISPLAY: ElementId = 0x4520;
+	/// Contains the string to use as the edition name.
+	pub const EDITION_STRING: ElementId = 0x4521;
+	/// One language corresponding to the EditionString, in the BCP47 form.
+	pub const EDITION_LANGUAGE_IETF: ElementId = 0x45E4;
+	/// Contains the atom information to use as the chapter atom (apply to all tracks).
+	pub const CHAPTER_ATOM: ElementId = 0xB6;
+	/// A unique ID to identify the Chapter.
+	pub const CHAPTER_UID: ElementId = 0x73C4;
+	/// A unique string ID to identify the Chapter. Use for WebVTT cue identifier storage.
+	pub const CHAPTER_STRING_UID: ElementId = 0x5654;
+	/// Timestamp of the start of Chapter, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const CHAPTER_TIME_START: ElementId = 0x91;
+	/// Timestamp of the end of Chapter timestamp excluded, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub const CHAPTER_TIME_END: ElementId = 0x92;
+	/// Set to 1 if a chapter is hidden. Hidden chapters **SHOULD NOT** be available to the user interface (but still to Control Tracks).
+	pub const CHAPTER_FLAG_HIDDEN: ElementId = 0x98;
+	/// Set to 1 if the chapter is enabled. It can be enabled/disabled by a Control Track.
+	pub const CHAPTER_FLAG_ENABLED: ElementId = 0x4598;
+	/// The SegmentUUID of another Segment to play during this chapter.
+	pub const CHAPTER_SEGMENT_UUID: ElementId = 0x6E67;
+	/// Indicate what type of content the ChapterAtom contains and might be skipped.
+	pub const CHAPTER_SKIP_TYPE: ElementId = 0x4588;
+	/// The EditionUID to play from the Segment linked in ChapterSegmentUUID.
+	pub const CHAPTER_SEGMENT_EDITION_UID: ElementId = 0x6EBC;
+	/// Specify the physical equivalent of this ChapterAtom like "DVD" (60) or "SIDE" (50).
+	pub const CHAPTER_PHYSICAL_EQUIV: ElementId = 0x63C3;
+	/// List of tracks on which the chapter applies. If this Element is not present, all tracks apply.
+	pub const CHAPTER_TRACK: ElementId = 0x8F;
+	/// UID of the Track to apply this chapter to.
+	pub const CHAPTER_TRACK_UID: ElementId = 0x89;
+	/// Contains all possible strings to use for the chapter display.
+	pub const CHAPTER_DISPLAY: ElementId = 0x80;
+	/// Contains the string to use as the chapter atom.
+	pub const CHAP_STRING: ElementId = 0x85;
+	/// A language corresponding to the string, in the Matroska languages form.
+	pub const CHAP_LANGUAGE: ElementId = 0x437C;
+	/// A language corresponding to the ChapString, in the BCP47 form.
+	pub const CHAP_LANGUAGE_BCP47: ElementId = 0x437D;
+	/// A country corresponding to the string, in the Matroska countries form.
+	pub const CHAP_COUNTRY: ElementId = 0x437E;
+	/// Contains all the commands associated to the Atom.
+	pub const CHAP_PROCESS: ElementId = 0x6944;
+	/// Contains the type of the codec used for the processing.
+	pub const CHAP_PROCESS_CODEC_ID: ElementId = 0x6955;
+	/// Some optional data attached to the ChapProcessCodecID information.
+	pub const CHAP_PROCESS_PRIVATE: ElementId = 0x450D;
+	/// Contains all the commands associated to the Atom.
+	pub const CHAP_PROCESS_COMMAND: ElementId = 0x6911;
+	/// Defines when the process command **SHOULD** be handled.
+	pub const CHAP_PROCESS_TIME: ElementId = 0x6922;
+	/// Contains the command information.
+	pub const CHAP_PROCESS_DATA: ElementId = 0x6933;
+	/// Element containing metadata describing Tracks, Editions, Chapters, Attachments, or the Segment as a whole.
+	pub const TAGS: ElementId = 0x1254C367;
+	/// A single metadata descriptor.
+	pub const TAG: ElementId = 0x7373;
+	/// Specifies which other elements the metadata represented by the Tag applies to. If empty or omitted, then the Tag describes everything in the Segment.
+	pub const TARGETS: ElementId = 0x63C0;
+	/// A number to indicate the logical level of the target.
+	pub const TARGET_TYPE_VALUE: ElementId = 0x68CA;
+	/// An informational string that can be used to display the logical level of the target like "ALBUM", "TRACK", "MOVIE", "CHAPTER", etc.
+	pub const TARGET_TYPE: ElementId = 0x63CA;
+	/// A UID that identifies the Track(s) that the tags belong to.
+	pub const TAG_TRACK_UID: ElementId = 0x63C5;
+	/// A UID that identifies the EditionEntry(s) that the tags belong to.
+	pub const TAG_EDITION_UID: ElementId = 0x63C9;
+	/// A UID that identifies the Chapter(s) that the tags belong to.
+	pub const TAG_CHAPTER_UID: ElementId = 0x63C4;
+	/// A UID that identifies the Attachment(s) that the tags belong to.
+	pub const TAG_ATTACHMENT_UID: ElementId = 0x63C6;
+	/// Contains general information about the target.
+	pub const SIMPLE_TAG: ElementId = 0x67C8;
+	/// The name of the Tag that is going to be stored.
+	pub const TAG_NAME: ElementId = 0x45A3;
+	/// Specifies the language of the tag specified, in the Matroska languages form.
+	pub const TAG_LANGUAGE: ElementId = 0x447A;
+	/// The language used in the TagString, in the BCP47 form.
+	pub const TAG_LANGUAGE_BCP47: ElementId = 0x447B;
+	/// A boolean value to indicate if this is the default/original language to use for the given tag.
+	pub const TAG_DEFAULT: ElementId = 0x4484;
+	/// The value of the Tag.
+	pub const TAG_STRING: ElementId = 0x4487;
+	/// The values of the Tag, if it is binary. Note that this cannot be used in the same SimpleTag as TagString.
+	pub const TAG_BINARY: ElementId = 0x4485;
+}
+
+/// Returns the name of the element with the given ID, as used in the schema.
+pub fn element_name(id: ElementId) -> Option<&'static str> {
+	Some(match id {
+		id::EBML => "EBML",
+		id::EBML_VERSION => "EBMLVersion",
+		id::EBML_READ_VERSION => "EBMLReadVersion",
+		id::EBML_MAX_ID_LENGTH => "EBMLMaxIDLength",
+		id::EBML_MAX_SIZE_LENGTH => "EBMLMaxSizeLength",
+		id::DOC_TYPE => "DocType",
+		id::DOC_TYPE_VERSION => "DocTypeVersion",
+		id::DOC_TYPE_READ_VERSION => "DocTypeReadVersion",
+		id::DOC_TYPE_EXTENSION => "DocTypeExtension",
+		id::DOC_TYPE_EXTENSION_NAME => "DocTypeExtensionName",
+		id::DOC_TYPE_EXTENSION_VERSION => "DocTypeExtensionVersion",
+		id::CRC_32 => ")CRC-32",
+		id::VOID => ")Void",
+		id::SEGMENT => "Segment",
+		id::SEEK_HEAD => "SeekHead",
+		id::SEEK => "Seek",
+		id::SEEK_ID => "SeekID",
+		id::SEEK_POSITION => "SeekPosition",
+		id::INFO => "Info",
+		id::SEGMENT_UUID => "SegmentUUID",
+		id::SEGMENT_FILENAME => "SegmentFilename",
+		id::PREV_UUID => "PrevUUID",
+		id::PREV_FILENAME => "PrevFilename",
+		id::NEXT_UUID => "NextUUID",
+		id::NEXT_FILENAME => "NextFilename",
+		id::SEGMENT_FAMILY => "SegmentFamily",
+		id::CHAPTER_TRANSLATE => "ChapterTranslate",
+		id::CHAPTER_TRANSLATE_ID => "ChapterTranslateID",
+		id::CHAPTER_TRANSLATE_CODEC => "ChapterTranslateCodec",
+		id::CHAPTER_TRANSLATE_EDITION_UID => "ChapterTranslateEditionUID",
+		id::TIMESTAMP_SCALE => "TimestampScale",
+		id::DURATION => "Duration",
+		id::DATE_UTC => "DateUTC",
+		id::TITLE => "Title",
+		id::MUXING_APP => "MuxingApp",
+		id::WRITING_APP => "WritingApp",
+		id::CLUSTER => "Cluster",
+		id::TIMESTAMP => "Timestamp",
+		id::POSITION => "Position",
+		id::PREV_SIZE => "PrevSize",
+		id::SIMPLE_BLOCK => "SimpleBlock",
+		id::BLOCK_GROUP => "BlockGroup",
+		id::BLOCK => "Block",
+		id::BLOCK_ADDITIONS => "BlockAdditions",
+		id::BLOCK_MORE => "BlockMore",
+		id::BLOCK_ADDITIONAL => "BlockAdditional",
+		id::BLOCK_ADD_ID => "BlockAddID",
+		id::BLOCK_DURATION => "BlockDuration",
+		id::REFERENCE_PRIORITY => "ReferencePriority",
+		id::REFERENCE_BLOCK => "ReferenceBlock",
+		id::CODEC_STATE => "CodecState",
+		id::DISCARD_PADDING => "DiscardPadding",
+		id::TRACKS => "Tracks",
+		id::TRACK_ENTRY => "TrackEntry",
+		id::TRACK_NUMBER => "TrackNumber",
+		id::TRACK_UID => "TrackUID",
+		id::TRACK_TYPE => "TrackType",
+		id::FLAG_ENABLED => "FlagEnabled",
+		id::FLAG_DEFAULT => "FlagDefault",
+		id::FLAG_FORCED => "FlagForced",
+		id::FLAG_HEARING_IMPAIRED => "FlagHearingImpaired",
+		id::FLAG_VISUAL_IMPAIRED => "FlagVisualImpaired",
+		id::FLAG_TEXT_DESCRIPTIONS => "FlagTextDescriptions",
+		id::FLAG_ORIGINAL => "FlagOriginal",
+		id::FLAG_COMMENTARY => "FlagCommentary",
+		id::FLAG_LACING => "FlagLacing",
+		id::DEFAULT_DURATION => "DefaultDuration",
+		id::DEFAULT_DECODED_FIELD_DURATION => "DefaultDecodedFieldDuration",
+		id::MAX_BLOCK_ADDITION_ID => "MaxBlockAdditionID",
+		id::BLOCK_ADDITION_MAPPING => "BlockAdditionMapping",
+		id::BLOCK_ADD_ID_VALUE => "BlockAddIDValue",
+		id::BLOCK_ADD_ID_NAME => "BlockAddIDName",
+		id::BLOCK_ADD_ID_TYPE => "BlockAddIDType",
+		id::BLOCK_ADD_ID_EXTRA_DATA => "BlockAddIDExtraData",
+		id::NAME => "Name",
+		id::LANGUAGE => "Language",
+		id::LANGUAGE_BCP47 => "LanguageBCP47",
+		id::CODEC_ID => "CodecID",
+		id::CODEC_PRIVATE => "CodecPrivate",
+		id::CODEC_NAME => "CodecName",
+		id::CODEC_DELAY => "CodecDelay",
+		id::SEEK_PRE_ROLL => "SeekPreRoll",
+		id::TRACK_TRANSLATE => "TrackTranslate",
+		id::TRACK_TRANSLATE_TRACK_ID => "TrackTranslateTrackID",
+		id::TRACK_TRANSLATE_CODEC => "TrackTranslateCodec",
+		id::TRACK_TRANSLATE_EDITION_UID => "TrackTranslateEditionUID",
+		id::VIDEO => "Video",
+		id::FLAG_INTERLACED => "FlagInterlaced",
+		id::FIELD_ORDER => "FieldOrder",
+		id::STEREO_MODE => "StereoMode",
+		id::ALPHA_MODE => "AlphaMode",
+		id::PIXEL_WIDTH => "PixelWidth",
+		id::PIXEL_HEIGHT => "PixelHeight",
+		id::PIXEL_CROP_BOTTOM => "PixelCropBottom",
+		id::PIXEL_CROP_TOP => "PixelCropTop",
+		id::PIXEL_CROP_LEFT => "PixelCropLeft",
+		id::PIXEL_CROP_RIGHT => "PixelCropRight",
+		id::DISPLAY_WIDTH => "DisplayWidth",
+		id::DISPLAY_HEIGHT => "DisplayHeight",
+		id::DISPLAY_UNIT => "DisplayUnit",
+		id::UNCOMPRESSED_FOUR_CC => "UncompressedFourCC",
+		id::COLOUR => "Colour",
+		id::MATRIX_COEFFICIENTS => "MatrixCoefficients",
+		id::BITS_PER_CHANNEL => "BitsPerChannel",
+		id::CHROMA_SUBSAMPLING_HORZ => "ChromaSubsamplingHorz",
+		id::CHROMA_SUBSAMPLING_VERT => "ChromaSubsamplingVert",
+		id::CB_SUBSAMPLING_HORZ => "CbSubsamplingHorz",
+		id::CB_SUBSAMPLING_VERT => "CbSubsamplingVert",
+		id::CHROMA_SITING_HORZ => "ChromaSitingHorz",
+		id::CHROMA_SITING_VERT => "ChromaSitingVert",
+		id::RANGE => "Range",
+		id::TRANSFER_CHARACTERISTICS => "TransferCharacteristics",
+		id::PRIMARIES => "Primaries",
+		id::MAX_CLL => "MaxCLL",
+		id::MAX_FALL => "MaxFALL",
+		id::MASTERING_METADATA => "MasteringMetadata",
+		id::PRIMARY_R_CHROMATICITY_X => "PrimaryRChromaticityX",
+		id::PRIMARY_R_CHROMATICITY_Y => "PrimaryRChromaticityY",
+		id::PRIMARY_G_CHROMATICITY_X => "PrimaryGChromaticityX",
+		id::PRIMARY_G_CHROMATICITY_Y => "PrimaryGChromaticityY",
+		id::PRIMARY_B_CHROMATICITY_X => "PrimaryBChromaticityX",
+		id::PRIMARY_B_CHROMATICITY_Y => "PrimaryBChromaticityY",
+		id::WHITE_POINT_CHROMATICITY_X => "WhitePointChromaticityX",
+		id::WHITE_POINT_CHROMATICITY_Y => "WhitePointChromaticityY",
+		id::LUMINANCE_MAX => "LuminanceMax",
+		id::LUMINANCE_MIN => "LuminanceMin",
+		id::PROJECTION => "Projection",
+		id::PROJECTION_TYPE => "ProjectionType",
+		id::PROJECTION_PRIVATE => "ProjectionPrivate",
+		id::PROJECTION_POSE_YAW => "ProjectionPoseYaw",
+		id::PROJECTION_POSE_PITCH => "ProjectionPosePitch",
+		id::PROJECTION_POSE_ROLL => "ProjectionPoseRoll",
+		id::AUDIO => "Audio",
+		id::SAMPLING_FREQUENCY => "SamplingFrequency",
+		id::OUTPUT_SAMPLING_FREQUENCY => "OutputSamplingFrequency",
+		id::CHANNELS => "Channels",
+		id::BIT_DEPTH => "BitDepth",
+		id::EMPHASIS => "Emphasis",
+		id::TRACK_OPERATION => "TrackOperation",
+		id::TRACK_COMBINE_PLANES => "TrackCombinePlanes",
+		id::TRACK_PLANE => "TrackPlane",
+		id::TRACK_PLANE_UID => "TrackPlaneUID",
+		id::TRACK_PLANE_TYPE => "TrackPlaneType",
+		id::TRACK_JOIN_BLOCKS => "TrackJoinBlocks",
+		id::TRACK_JOIN_UID => "TrackJoinUID",
+		id::CONTENT_ENCODINGS => "ContentEncodings",
+		id::CONTENT_ENCODING => "ContentEncoding",
+		id::CONTENT_ENCODING_ORDER => "ContentEncodingOrder",
+		id::CONTENT_ENCODING_SCOPE => "ContentEncodingScope",
+		id::CONTENT_ENCODING_TYPE => "ContentEncodingType",
+		id::CONTENT_COMPRESSION => "ContentCompression",
+		id::CONTENT_COMP_ALGO => "ContentCompAlgo",
+		id::CONTENT_COMP_SETTINGS => "ContentCompSettings",
+		id::CONTENT_ENCRYPTION => "ContentEncryption",
+		id::CONTENT_ENC_ALGO => "ContentEncAlgo",
+		id::CONTENT_ENC_KEY_ID => "ContentEncKeyID",
+		id::CONTENT_ENC_AES_SETTINGS => "ContentEncAESSettings",
+		id::AES_SETTINGS_CIPHER_MODE => "AESSettingsCipherMode",
+		id::CUES => "Cues",
+		id::CUE_POINT => "CuePoint",
+		id::CUE_TIME => "CueTime",
+		id::CUE_TRACK_POSITIONS => "CueTrackPositions",
+		id::CUE_TRACK => "CueTrack",
+		id::CUE_CLUSTER_POSITION => "CueClusterPosition",
+		id::CUE_RELATIVE_POSITION => "CueRelativePosition",
+		id::CUE_DURATION => "CueDuration",
+		id::CUE_BLOCK_NUMBER => "CueBlockNumber",
+		id::CUE_CODEC_STATE => "CueCodecState",
+		id::CUE_REFERENCE => "CueReference",
+		id::CUE_REF_TIME => "CueRefTime",
+		id::ATTACHMENTS => "Attachments",
+		id::ATTACHED_FILE => "AttachedFile",
+		id::FILE_DESCRIPTION => "FileDescription",
+		id::FILE_NAME => "FileName",
+		id::FILE_MEDIA_TYPE => "FileMediaType",
+		id::FILE_DATA => "FileData",
+		id::FILE_UID => "FileUID",
+		id::CHAPTERS => "Chapters",
+		id::EDITION_ENTRY => "EditionEntry",
+		id::EDITION_UID => "EditionUID",
+		id::EDITION_FLAG_HIDDEN => "EditionFlagHidden",
+		id::EDITION_FLAG_DEFAULT => "EditionFlagDefault",
+		id::EDITION_FLAG_ORDERED => "EditionFlagOrdered",
+		id::EDITION_DISPLAY => "EditionDisplay",
+		id::EDITION_STRING => "EditionString",
+		id::EDITION_LANGUAGE_IETF => "EditionLanguageIETF",
+		id::CHAPTER_ATOM => "ChapterAtom",
+		id::CHAPTER_UID => "ChapterUID",
+		id::CHAPTER_STRING_UID => "ChapterStringUID",
+		id::CHAPTER_TIME_START => "ChapterTimeStart",
+		id::CHAPTER_TIME_END => "ChapterTimeEnd",
+		id::CHAPTER_FLAG_HIDDEN => "ChapterFlagHidden",
+		id::CHAPTER_FLAG_ENABLED => "ChapterFlagEnabled",
+		id::CHAPTER_SEGMENT_UUID => "ChapterSegmentUUID",
+		id::CHAPTER_SKIP_TYPE => "ChapterSkipType",
+		id::CHAPTER_SEGMENT_EDITION_UID => "ChapterSegmentEditionUID",
+		id::CHAPTER_PHYSICAL_EQUIV => "ChapterPhysicalEquiv",
+		id::CHAPTER_TRACK => "ChapterTrack",
+		id::CHAPTER_TRACK_UID => "ChapterTrackUID",
+		id::CHAPTER_DISPLAY => "ChapterDisplay",
+		id::CHAP_STRING => "ChapString",
+		id::CHAP_LANGUAGE => "ChapLanguage",
+		id::CHAP_LANGUAGE_BCP47 => "ChapLanguageBCP47",
+		id::CHAP_COUNTRY => "ChapCountry",
+		id::CHAP_PROCESS => "ChapProcess",
+		id::CHAP_PROCESS_CODEC_ID => "ChapProcessCodecID",
+		id::CHAP_PROCESS_PRIVATE => "ChapProcessPrivate",
+		id::CHAP_PROCESS_COMMAND => "ChapProcessCommand",
+		id::CHAP_PROCESS_TIME => "ChapProcessTime",
+		id::CHAP_PROCESS_DATA => "ChapProcessData",
+		id::TAGS => "Tags",
+		id::TAG => "Tag",
+		id::TARGETS => "Targets",
+		id::TARGET_TYPE_VALUE => "TargetTypeValue",
+		id::TARGET_TYPE => "TargetType",
+		id::TAG_TRACK_UID => "TagTrackUID",
+		id::TAG_EDITION_UID => "TagEditionUID",
+		id::TAG_CHAPTER_UID => "TagChapterUID",
+		id::TAG_ATTACHMENT_UID => "TagAttachmentUID",
+		id::SIMPLE_TAG => "SimpleTag",
+		id::TAG_NAME => "TagName",
+		id::TAG_LANGUAGE => "TagLanguage",
+		id::TAG_LANGUAGE_BCP47 => "TagLanguageBCP47",
+		id::TAG_DEFAULT => "TagDefault",
+		id::TAG_STRING => "TagString",
+		id::TAG_BINARY => "TagBinary",
+		_ => return None
+	})
+}
+
+/// The values of [`ChapterTranslateCodec`](id::CHAPTER_TRANSLATE_CODEC).
+pub mod chapter_translate_codec {
+	pub const MATROSKA_SCRIPT: u64 = 0;
+	pub const DVD_MENU: u64 = 1;
+}
+
+/// The values of [`TrackType`](id::TRACK_TYPE).
+pub mod track_type {
+	pub const VIDEO: u64 = 1;
+	pub const AUDIO: u64 = 2;
+	pub const COMPLEX: u64 = 3;
+	pub const LOGO: u64 = 16;
+	pub const SUBTITLE: u64 = 17;
+	pub const BUTTONS: u64 = 18;
+	pub const CONTROL: u64 = 32;
+	pub const METADATA: u64 = 33;
+}
+
+/// The values of [`TrackTranslateCodec`](id::TRACK_TRANSLATE_CODEC).
+pub mod track_translate_codec {
+	pub const MATROSKA_SCRIPT: u64 = 0;
+	pub const DVD_MENU: u64 = 1;
+}
+
+/// The values of [`FlagInterlaced`](id::FLAG_INTERLACED).
+pub mod flag_interlaced {
+	pub const UNDETERMINED: u64 = 0;
+	pub const INTERLACED: u64 = 1;
+	pub const PROGRESSIVE: u64 = 2;
+}
+
+/// The values of [`FieldOrder`](id::FIELD_ORDER).
+pub mod field_order {
+	pub const PROGRESSIVE: u64 = 0;
+	pub const TFF: u64 = 1;
+	pub const UNDETERMINED: u64 = 2;
+	pub const BFF: u64 = 6;
+	pub const BFF_SWAPPED: u64 = 9;
+	pub const TFF_SWAPPED: u64 = 14;
+}
+
+/// The values of [`StereoMode`](id::STEREO_MODE).
+pub mod stereo_mode {
+	pub const MONO: u64 = 0;
+	pub const SIDE_BY_SIDE_LEFT_EYE_FIRST: u64 = 1;
+	pub const TOP_BOTTOM_RIGHT_EYE_IS_FIRST: u64 = 2;
+	pub const TOP_BOTTOM_LEFT_EYE_IS_FIRST: u64 = 3;
+	pub const CHECKBOARD_RIGHT_EYE_IS_FIRST: u64 = 4;
+	pub const CHECKBOARD_LEFT_EYE_IS_FIRST: u64 = 5;
+	pub const ROW_INTERLEAVED_RIGHT_EYE_IS_FIRST: u64 = 6;
+	pub const ROW_INTERLEAVED_LEFT_EYE_IS_FIRST: u64 = 7;
+	pub const COLUMN_INTERLEAVED_RIGHT_EYE_IS_FIRST: u64 = 8;
+	pub const COLUMN_INTERLEAVED_LEFT_EYE_IS_FIRST: u64 = 9;
+	pub const ANAGLYPH_CYAN_RED: u64 = 10;
+	pub const SIDE_BY_SIDE_RIGHT_EYE_FIRST: u64 = 11;
+	pub const ANAGLYPH_GREEN_MAGENTA: u64 = 12;
+	pub const BOTH_EYES_LACED_IN_ONE_BLOCK_LEFT_EYE_IS_FIRST: u64 = 13;
+	pub const BOTH_EYES_LACED_IN_ONE_BLOCK_RIGHT_EYE_IS_FIRST: u64 = 14;
+}
+
+/// The values of [`AlphaMode`](id::ALPHA_MODE).
+pub mod alpha_mode {
+	pub const NONE: u64 = 0;
+	pub const PRESENT: u64 = 1;
+}
+
+/// The values of [`DisplayUnit`](id::DISPLAY_UNIT).
+pub mod display_unit {
+	pub const PIXELS: u64 = 0;
+	pub const CENTIMETERS: u64 = 1;
+	pub const INCHES: u64 = 2;
+	pub const DISPLAY_ASPECT_RATIO: u64 = 3;
+	pub const UNKNOWN: u64 = 4;
+}
+
+/// The values of [`ChromaSitingHorz`](id::CHROMA_SITING_HORZ).
+pub mod chroma_siting_horz {
+	pub const UNSPECIFIED: u64 = 0;
+	pub const LEFT_COLLOCATED: u64 = 1;
+	pub const HALF: u64 = 2;
+}
+
+/// The values of [`ChromaSitingVert`](id::CHROMA_SITING_VERT).
+pub mod chroma_siting_vert {
+	pub const UNSPECIFIED: u64 = 0;
+	pub const TOP_COLLOCATED: u64 = 1;
+	pub const HALF: u64 = 2;
+}
+
+/// The values of [`Range`](id::RANGE).
+pub mod range {
+	pub const UNSPECIFIED: u64 = 0;
+	pub const BROADCAST_RANGE: u64 = 1;
+	pub const FULL_RANGE_NO_CLIPPING: u64 = 2;
+	pub const DEFINED_BY_MATRIX_COEFFICIENTS_TRANSFER_CHARACTERISTICS: u64 = 3;
+}
+
+/// The values of [`ProjectionType`](id::PROJECTION_TYPE).
+pub mod projection_type {
+	pub const RECTANGULAR: u64 = 0;
+	pub const EQUIRECTANGULAR: u64 = 1;
+	pub const CUBEMAP: u64 = 2;
+	pub const MESH: u64 = 3;
+}
+
+/// The values of [`TrackPlaneType`](id::TRACK_PLANE_TYPE).
+pub mod track_plane_type {
+	pub const LEFT_EYE: u64 = 0;
+	pub const RIGHT_EYE: u64 = 1;
+	pub const BACKGROUND: u64 = 2;
+}
+
+/// The values of [`ContentEncodingScope`](id::CONTENT_ENCODING_SCOPE).
+pub mod content_encoding_scope {
+	pub const BLOCK: u64 = 1;
+	pub const PRIVATE: u64 = 2;
+	pub const NEXT: u64 = 4;
+}
+
+/// The values of [`ContentEncodingType`](id::CONTENT_ENCODING_TYPE).
+pub mod content_encoding_type {
+	pub const COMPRESSION: u64 = 0;
+	pub const ENCRYPTION: u64 = 1;
+}
+
+/// The values of [`ContentCompAlgo`](id::CONTENT_COMP_ALGO).
+pub mod content_comp_algo {
+	pub const ZLIB: u64 = 0;
+	pub const BZLIB: u64 = 1;
+	pub const LZO1X: u64 = 2;
+	pub const HEADER_STRIPPING: u64 = 3;
+}
+
+/// The values of [`ContentEncAlgo`](id::CONTENT_ENC_ALGO).
+pub mod content_enc_algo {
+	pub const NOT_ENCRYPTED: u64 = 0;
+	pub const DES: u64 = 1;
+	pub const _3_DES: u64 = 2;
+	pub const TWOFISH: u64 = 3;
+	pub const BLOWFISH: u64 = 4;
+	pub const AES: u64 = 5;
+}
+
+/// The values of [`AESSettingsCipherMode`](id::AES_SETTINGS_CIPHER_MODE).
+pub mod aes_settings_cipher_mode {
+	pub const AES_CTR: u64 = 1;
+	pub const AES_CBC: u64 = 2;
+}
+
+/// The values of [`ChapterSkipType`](id::CHAPTER_SKIP_TYPE).
+pub mod chapter_skip_type {
+	pub const NO_SKIPPING: u64 = 0;
+	pub const OPENING_CREDITS: u64 = 1;
+	pub const END_CREDITS: u64 = 2;
+	pub const RECAP: u64 = 3;
+	pub const NEXT_PREVIEW: u64 = 4;
+	pub const PREVIEW: u64 = 5;
+	pub const ADVERTISEMENT: u64 = 6;
+	pub const INTERMISSION: u64 = 7;
+}
+
+/// The values of [`ChapProcessCodecID`](id::CHAP_PROCESS_CODEC_ID).
+pub mod chap_process_codec_id {
+	pub const MATROSKA_SCRIPT: u64 = 0;
+	pub const DVD_MENU: u64 = 1;
+}
+
+/// The values of [`ChapProcessTime`](id::CHAP_PROCESS_TIME).
+pub mod chap_process_time {
+	pub const DURING_THE_WHOLE_CHAPTER: u64 = 0;
+	pub const BEFORE_STARTING_PLAYBACK: u64 = 1;
+	pub const AFTER_PLAYBACK_OF_THE_CHAPTER: u64 = 2;
+}
+
+/// The values of [`TargetTypeValue`](id::TARGET_TYPE_VALUE).
+pub mod target_type_value {
+	pub const COLLECTION: u64 = 70;
+	pub const EDITION_ISSUE_VOLUME_OPUS_SEASON_SEQUEL: u64 = 60;
+	pub const ALBUM_OPERA_CONCERT_MOVIE_EPISODE: u64 = 50;
+	pub const PART_SESSION: u64 = 40;
+	pub const TRACK_SONG_CHAPTER: u64 = 30;
+	pub const SUBTRACK_MOVEMENT_SCENE: u64 = 20;
+	pub const SHOT: u64 = 10;
+}
+
+/// Set the EBML characteristics of the data to follow. Each EBML Document has to start with this.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ebml {
+	/// The version of EBML specifications used to create the EBML Document.
+	pub ebml_version: u64,
+	/// The minimum EBML version an EBML Reader has to support to read this EBML Document.
+	pub ebml_read_version: u64,
+	/// The maximum length of the IDs you'll find in this EBML Document.
+	pub ebml_max_id_length: u64,
+	/// The maximum length of the sizes you'll find in this EBML Document.
+	pub ebml_max_size_length: u64,
+	/// A string that describes and identifies the content of the EBML Body that follows this EBML Header.
+	pub doc_type: String,
+	/// The version of DocType interpreter used to create the EBML Document.
+	pub doc_type_version: u64,
+	/// The minimum DocType version an EBML Reader has to support to read this EBML Document.
+	pub doc_type_read_version: u64,
+	/// A DocTypeExtension adds extra Elements to the main DocType+DocTypeVersion tuple it's attached to.
+	pub doc_type_extension: Vec<DocTypeExtension>,
+}
+
+impl Master for Ebml {
+	const ID: ElementId = id::EBML;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut ebml_version = None;
+		let mut ebml_read_version = None;
+		let mut ebml_max_id_length = None;
+		let mut ebml_max_size_length = None;
+		let mut doc_type = None;
+		let mut doc_type_version = None;
+		let mut doc_type_read_version = None;
+		let mut doc_type_extension = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::EBML_VERSION => ebml_version = Some(read_uint(&mut reader, len)?),
+				id::EBML_READ_VERSION => ebml_read_version = Some(read_uint(&mut reader, len)?),
+				id::EBML_MAX_ID_LENGTH => ebml_max_id_length = Some(read_uint(&mut reader, len)?),
+				id::EBML_MAX_SIZE_LENGTH => ebml_max_size_length = Some(read_uint(&mut reader, len)?),
+				id::DOC_TYPE => doc_type = Some(read_string(&mut reader, len)?),
+				id::DOC_TYPE_VERSION => doc_type_version = Some(read_uint(&mut reader, len)?),
+				id::DOC_TYPE_READ_VERSION => doc_type_read_version = Some(read_uint(&mut reader, len)?),
+				id::DOC_TYPE_EXTENSION => doc_type_extension.push(DocTypeExtension::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let ebml_version = ebml_version.unwrap_or(1);
+		let ebml_read_version = ebml_read_version.unwrap_or(1);
+		let ebml_max_id_length = ebml_max_id_length.unwrap_or(4);
+		let ebml_max_size_length = ebml_max_size_length.unwrap_or(8);
+		let doc_type = doc_type.ok_or_else(|| missing("DocType"))?;
+		let doc_type_version = doc_type_version.unwrap_or(1);
+		let doc_type_read_version = doc_type_read_version.unwrap_or(1);
+		
+		Ok(Self {
+			ebml_version,
+			ebml_read_version,
+			ebml_max_id_length,
+			ebml_max_size_length,
+			doc_type,
+			doc_type_version,
+			doc_type_read_version,
+			doc_type_extension
+		})
+	}
+}
+
+/// A DocTypeExtension adds extra Elements to the main DocType+DocTypeVersion tuple it's attached to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocTypeExtension {
+	/// The name of the DocTypeExtension to differentiate it from other DocTypeExtensions of the same DocType+DocTypeVersion tuple.
+	pub doc_type_extension_name: String,
+	/// The version of the DocTypeExtension.
+	pub doc_type_extension_version: u64,
+}
+
+impl Master for DocTypeExtension {
+	const ID: ElementId = id::DOC_TYPE_EXTENSION;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut doc_type_extension_name = None;
+		let mut doc_type_extension_version = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::DOC_TYPE_EXTENSION_NAME => doc_type_extension_name = Some(read_string(&mut reader, len)?),
+				id::DOC_TYPE_EXTENSION_VERSION => doc_type_extension_version = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let doc_type_extension_name = doc_type_extension_name.ok_or_else(|| missing("DocTypeExtensionName"))?;
+		let doc_type_extension_version = doc_type_extension_version.ok_or_else(|| missing("DocTypeExtensionVersion"))?;
+		
+		Ok(Self {
+			doc_type_extension_name,
+			doc_type_extension_version
+		})
+	}
+}
+
+/// Contains seeking information of Top-Level Elements.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SeekHead {
+	/// Contains a single seek entry to an EBML Element.
+	pub seek: Vec<Seek>,
+}
+
+impl Master for SeekHead {
+	const ID: ElementId = id::SEEK_HEAD;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut seek = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::SEEK => seek.push(Seek::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			seek
+		})
+	}
+}
+
+/// Contains a single seek entry to an EBML Element.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Seek {
+	/// The binary EBML ID of a Top-Level Element.
+	pub seek_id: Vec<u8>,
+	/// The Segment Position of a Top-Level Element.
+	pub seek_position: u64,
+}
+
+impl Master for Seek {
+	const ID: ElementId = id::SEEK;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut seek_id = None;
+		let mut seek_position = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::SEEK_ID => seek_id = Some(read_binary(&mut reader, len)?),
+				id::SEEK_POSITION => seek_position = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let seek_id = seek_id.ok_or_else(|| missing("SeekID"))?;
+		let seek_position = seek_position.ok_or_else(|| missing("SeekPosition"))?;
+		
+		Ok(Self {
+			seek_id,
+			seek_position
+		})
+	}
+}
+
+/// Contains general information about the Segment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Info {
+	/// A randomly generated unique ID to identify the Segment amongst many others (128 bits).
+	pub segment_uuid: Option<Vec<u8>>,
+	/// A filename corresponding to this Segment.
+	pub segment_filename: Option<String>,
+	/// An ID to identify the previous Segment of a Linked Segment.
+	pub prev_uuid: Option<Vec<u8>>,
+	/// A filename corresponding to the file of the previous Linked Segment.
+	pub prev_filename: Option<String>,
+	/// An ID to identify the next Segment of a Linked Segment.
+	pub next_uuid: Option<Vec<u8>>,
+	/// A filename corresponding to the file of the next Linked Segment.
+	pub next_filename: Option<String>,
+	/// A unique ID that all Segments of a Linked Segment share (128 bits).
+	pub segment_family: Vec<Vec<u8>>,
+	/// The mapping between this Segment and a segment value in the given Chapter Codec.
+	pub chapter_translate: Vec<ChapterTranslate>,
+	/// Base unit for Segment Ticks and Track Ticks, in nanoseconds. A TimestampScale value of 1000000 means scaled timestamps in the Segment are expressed in milliseconds.
+	pub timestamp_scale: u64,
+	/// Duration of the Segment, expressed in Segment Ticks which is based on TimestampScale.
+	pub duration: Option<f64>,
+	/// The date and time that the Segment was created by the muxing application or library.
+	pub date_utc: Option<i64>,
+	/// General name of the Segment.
+	pub title: Option<String>,
+	/// Muxing application or library.
+	pub muxing_app: String,
+	/// Writing application.
+	pub writing_app: String,
+}
+
+impl Master for Info {
+	const ID: ElementId = id::INFO;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut segment_uuid = None;
+		let mut segment_filename = None;
+		let mut prev_uuid = None;
+		let mut prev_filename = None;
+		let mut next_uuid = None;
+		let mut next_filename = None;
+		let mut segment_family = Vec::new();
+		let mut chapter_translate = Vec::new();
+		let mut timestamp_scale = None;
+		let mut duration = None;
+		let mut date_utc = None;
+		let mut title = None;
+		let mut muxing_app = None;
+		let mut writing_app = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::SEGMENT_UUID => segment_uuid = Some(read_binary(&mut reader, len)?),
+				id::SEGMENT_FILENAME => segment_filename = Some(read_string(&mut reader, len)?),
+				id::PREV_UUID => prev_uuid = Some(read_binary(&mut reader, len)?),
+				id::PREV_FILENAME => prev_filename = Some(read_string(&mut reader, len)?),
+				id::NEXT_UUID => next_uuid = Some(read_binary(&mut reader, len)?),
+				id::NEXT_FILENAME => next_filename = Some(read_string(&mut reader, len)?),
+				id::SEGMENT_FAMILY => segment_family.push(read_binary(&mut reader, len)?),
+				id::CHAPTER_TRANSLATE => chapter_translate.push(ChapterTranslate::read(&mut reader, len)?),
+				id::TIMESTAMP_SCALE => timestamp_scale = Some(read_uint(&mut reader, len)?),
+				id::DURATION => duration = Some(read_float(&mut reader, len)?),
+				id::DATE_UTC => date_utc = Some(read_int(&mut reader, len)?),
+				id::TITLE => title = Some(read_string(&mut reader, len)?),
+				id::MUXING_APP => muxing_app = Some(read_string(&mut reader, len)?),
+				id::WRITING_APP => writing_app = Some(read_string(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let timestamp_scale = timestamp_scale.unwrap_or(1000000);
+		let muxing_app = muxing_app.ok_or_else(|| missing("MuxingApp"))?;
+		let writing_app = writing_app.ok_or_else(|| missing("WritingApp"))?;
+		
+		Ok(Self {
+			segment_uuid,
+			segment_filename,
+			prev_uuid,
+			prev_filename,
+			next_uuid,
+			next_filename,
+			segment_family,
+			chapter_translate,
+			timestamp_scale,
+			duration,
+			date_utc,
+			title,
+			muxing_app,
+			writing_app
+		})
+	}
+}
+
+/// The mapping between this Segment and a segment value in the given Chapter Codec.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapterTranslate {
+	/// The binary value used to represent this Segment in the chapter codec data.
+	pub chapter_translate_id: Vec<u8>,
+	/// This ChapterTranslate applies to this chapter codec of the given chapter edition(s).
+	pub chapter_translate_codec: u64,
+	/// Specify a chapter edition UID on which this ChapterTranslate applies.
+	pub chapter_translate_edition_uid: Vec<u64>,
+}
+
+impl Master for ChapterTranslate {
+	const ID: ElementId = id::CHAPTER_TRANSLATE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chapter_translate_id = None;
+		let mut chapter_translate_codec = None;
+		let mut chapter_translate_edition_uid = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAPTER_TRANSLATE_ID => chapter_translate_id = Some(read_binary(&mut reader, len)?),
+				id::CHAPTER_TRANSLATE_CODEC => chapter_translate_codec = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_TRANSLATE_EDITION_UID => chapter_translate_edition_uid.push(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let chapter_translate_id = chapter_translate_id.ok_or_else(|| missing("ChapterTranslateID"))?;
+		let chapter_translate_codec = chapter_translate_codec.ok_or_else(|| missing("ChapterTranslateCodec"))?;
+		
+		Ok(Self {
+			chapter_translate_id,
+			chapter_translate_codec,
+			chapter_translate_edition_uid
+		})
+	}
+}
+
+/// Basic container of information containing a single Block and information specific to that Block.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockGroup {
+	/// Block containing the actual data to be rendered and a timestamp relative to the Cluster Timestamp.
+	pub block: Vec<u8>,
+	/// Contain additional binary data to complete the main one.
+	pub block_additions: Option<BlockAdditions>,
+	/// The duration of the Block, expressed in Track Ticks.
+	pub block_duration: Option<u64>,
+	/// This frame is referenced and has the specified cache priority.
+	pub reference_priority: u64,
+	/// A timestamp value, relative to the timestamp of the Block in this BlockGroup, expressed in Track Ticks. This is used to reference other frames necessary to decode this frame.
+	pub reference_block: Vec<i64>,
+	/// The new codec state to use. Data interpretation is private to the codec.
+	pub codec_state: Option<Vec<u8>>,
+	/// Duration of the silent data added to the Block, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub discard_padding: Option<i64>,
+}
+
+impl Master for BlockGroup {
+	const ID: ElementId = id::BLOCK_GROUP;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut block = None;
+		let mut block_additions = None;
+		let mut block_duration = None;
+		let mut reference_priority = None;
+		let mut reference_block = Vec::new();
+		let mut codec_state = None;
+		let mut discard_padding = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::BLOCK => block = Some(read_binary(&mut reader, len)?),
+				id::BLOCK_ADDITIONS => block_additions = Some(BlockAdditions::read(&mut reader, len)?),
+				id::BLOCK_DURATION => block_duration = Some(read_uint(&mut reader, len)?),
+				id::REFERENCE_PRIORITY => reference_priority = Some(read_uint(&mut reader, len)?),
+				id::REFERENCE_BLOCK => reference_block.push(read_int(&mut reader, len)?),
+				id::CODEC_STATE => codec_state = Some(read_binary(&mut reader, len)?),
+				id::DISCARD_PADDING => discard_padding = Some(read_int(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let block = block.ok_or_else(|| missing("Block"))?;
+		let reference_priority = reference_priority.unwrap_or(0);
+		
+		Ok(Self {
+			block,
+			block_additions,
+			block_duration,
+			reference_priority,
+			reference_block,
+			codec_state,
+			discard_padding
+		})
+	}
+}
+
+/// Contain additional binary data to complete the main one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockAdditions {
+	/// Contain the BlockAdditional and some parameters.
+	pub block_more: Vec<BlockMore>,
+}
+
+impl Master for BlockAdditions {
+	const ID: ElementId = id::BLOCK_ADDITIONS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut block_more = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::BLOCK_MORE => block_more.push(BlockMore::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			block_more
+		})
+	}
+}
+
+/// Contain the BlockAdditional and some parameters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockMore {
+	/// Interpreted by the codec as it wishes (using the BlockAddID).
+	pub block_additional: Vec<u8>,
+	/// An ID to identify how to interpret the BlockAdditional data.
+	pub block_add_id: u64,
+}
+
+impl Master for BlockMore {
+	const ID: ElementId = id::BLOCK_MORE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut block_additional = None;
+		let mut block_add_id = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::BLOCK_ADDITIONAL => block_additional = Some(read_binary(&mut reader, len)?),
+				id::BLOCK_ADD_ID => block_add_id = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let block_additional = block_additional.ok_or_else(|| missing("BlockAdditional"))?;
+		let block_add_id = block_add_id.unwrap_or(1);
+		
+		Ok(Self {
+			block_additional,
+			block_add_id
+		})
+	}
+}
+
+/// A Top-Level Element of information with many tracks described.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tracks {
+	/// Describes a track with all Elements.
+	pub track_entry: Vec<TrackEntry>,
+}
+
+impl Master for Tracks {
+	const ID: ElementId = id::TRACKS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_entry = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_ENTRY => track_entry.push(TrackEntry::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			track_entry
+		})
+	}
+}
+
+/// Describes a track with all Elements.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackEntry {
+	/// The track number as used in the Block Header.
+	pub track_number: u64,
+	/// A unique ID to identify the Track.
+	pub track_uid: u64,
+	/// The TrackType defines the type of each frame found in the Track.
+	pub track_type: u64,
+	/// Set to 1 if the track is usable.
+	pub flag_enabled: u64,
+	/// Set if that track (audio, video or subs) is eligible for automatic selection by the player.
+	pub flag_default: u64,
+	/// Applies only to subtitles. Set if that track is eligible for automatic selection by the player if it matches the user's language preference.
+	pub flag_forced: u64,
+	/// Set to 1 if and only if that track is suitable for users with hearing impairments.
+	pub flag_hearing_impaired: Option<u64>,
+	/// Set to 1 if and only if that track is suitable for users with visual impairments.
+	pub flag_visual_impaired: Option<u64>,
+	/// Set to 1 if and only if that track contains textual descriptions of video content.
+	pub flag_text_descriptions: Option<u64>,
+	/// Set to 1 if and only if that track is in the content's original language.
+	pub flag_original: Option<u64>,
+	/// Set to 1 if and only if that track contains commentary.
+	pub flag_commentary: Option<u64>,
+	/// Set to 1 if the track **MAY** contain blocks using lacing.
+	pub flag_lacing: u64,
+	/// Number of nanoseconds per frame, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub default_duration: Option<u64>,
+	/// The period between two successive fields at the output of the decoding process, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub default_decoded_field_duration: Option<u64>,
+	/// The maximum value of BlockAddID.
+	pub max_block_addition_id: u64,
+	/// Contains elements that extend the track format, by adding content either to each frame, with BlockAddID, or to the track as a whole with BlockAddIDExtraData.
+	pub block_addition_mapping: Vec<BlockAdditionMapping>,
+	/// A human-readable track name.
+	pub name: Option<String>,
+	/// The language of the track, in the Matroska languages form.
+	pub language: String,
+	/// The language of the track, in the BCP47 form. If this Element is used, then any Language Elements used in the same TrackEntry **MUST** be ignored.
+	pub language_bcp47: Option<String>,
+	/// An ID corresponding to the codec, see the codec page for more info.
+	pub codec_id: String,
+	/// Private data only known to the codec.
+	pub codec_private: Option<Vec<u8>>,
+	/// A human-readable string specifying the codec.
+	pub codec_name: Option<String>,
+	/// CodecDelay is the codec-built-in delay, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub codec_delay: u64,
+	/// After a discontinuity, SeekPreRoll is the duration of the data the decoder **MUST** decode before the decoded data is valid, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub seek_pre_roll: u64,
+	/// The mapping between this TrackEntry and a track value in the given Chapter Codec.
+	pub track_translate: Vec<TrackTranslate>,
+	/// Video settings.
+	pub video: Option<Video>,
+	/// Audio settings.
+	pub audio: Option<Audio>,
+	/// Operation that needs to be applied on tracks to create this virtual track.
+	pub track_operation: Option<TrackOperation>,
+	/// Settings for several content encoding mechanisms like compression or encryption.
+	pub content_encodings: Option<ContentEncodings>,
+}
+
+impl Master for TrackEntry {
+	const ID: ElementId = id::TRACK_ENTRY;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_number = None;
+		let mut track_uid = None;
+		let mut track_type = None;
+		let mut flag_enabled = None;
+		let mut flag_default = None;
+		let mut flag_forced = None;
+		let mut flag_hearing_impaired = None;
+		let mut flag_visual_impaired = None;
+		let mut flag_text_descriptions = None;
+		let mut flag_original = None;
+		let mut flag_commentary = None;
+		let mut flag_lacing = None;
+		let mut default_duration = None;
+		let mut default_decoded_field_duration = None;
+		let mut max_block_addition_id = None;
+		let mut block_addition_mapping = Vec::new();
+		let mut name = None;
+		let mut language = None;
+		let mut language_bcp47 = None;
+		let mut codec_id = None;
+		let mut codec_private = None;
+		let mut codec_name = None;
+		let mut codec_delay = None;
+		let mut seek_pre_roll = None;
+		let mut track_translate = Vec::new();
+		let mut video = None;
+		let mut audio = None;
+		let mut track_operation = None;
+		let mut content_encodings = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_NUMBER => track_number = Some(read_uint(&mut reader, len)?),
+				id::TRACK_UID => track_uid = Some(read_uint(&mut reader, len)?),
+				id::TRACK_TYPE => track_type = Some(read_uint(&mut reader, len)?),
+				id::FLAG_ENABLED => flag_enabled = Some(read_uint(&mut reader, len)?),
+				id::FLAG_DEFAULT => flag_default = Some(read_uint(&mut reader, len)?),
+				id::FLAG_FORCED => flag_forced = Some(read_uint(&mut reader, len)?),
+				id::FLAG_HEARING_IMPAIRED => flag_hearing_impaired = Some(read_uint(&mut reader, len)?),
+				id::FLAG_VISUAL_IMPAIRED => flag_visual_impaired = Some(read_uint(&mut reader, len)?),
+				id::FLAG_TEXT_DESCRIPTIONS => flag_text_descriptions = Some(read_uint(&mut reader, len)?),
+				id::FLAG_ORIGINAL => flag_original = Some(read_uint(&mut reader, len)?),
+				id::FLAG_COMMENTARY => flag_commentary = Some(read_uint(&mut reader, len)?),
+				id::FLAG_LACING => flag_lacing = Some(read_uint(&mut reader, len)?),
+				id::DEFAULT_DURATION => default_duration = Some(read_uint(&mut reader, len)?),
+				id::DEFAULT_DECODED_FIELD_DURATION => default_decoded_field_duration = Some(read_uint(&mut reader, len)?),
+				id::MAX_BLOCK_ADDITION_ID => max_block_addition_id = Some(read_uint(&mut reader, len)?),
+				id::BLOCK_ADDITION_MAPPING => block_addition_mapping.push(BlockAdditionMapping::read(&mut reader, len)?),
+				id::NAME => name = Some(read_string(&mut reader, len)?),
+				id::LANGUAGE => language = Some(read_string(&mut reader, len)?),
+				id::LANGUAGE_BCP47 => language_bcp47 = Some(read_string(&mut reader, len)?),
+				id::CODEC_ID => codec_id = Some(read_string(&mut reader, len)?),
+				id::CODEC_PRIVATE => codec_private = Some(read_binary(&mut reader, len)?),
+				id::CODEC_NAME => codec_name = Some(read_string(&mut reader, len)?),
+				id::CODEC_DELAY => codec_delay = Some(read_uint(&mut reader, len)?),
+				id::SEEK_PRE_ROLL => seek_pre_roll = Some(read_uint(&mut reader, len)?),
+				id::TRACK_TRANSLATE => track_translate.push(TrackTranslate::read(&mut reader, len)?),
+				id::VIDEO => video = Some(Video::read(&mut reader, len)?),
+				id::AUDIO => audio = Some(Audio::read(&mut reader, len)?),
+				id::TRACK_OPERATION => track_operation = Some(TrackOperation::read(&mut reader, len)?),
+				id::CONTENT_ENCODINGS => content_encodings = Some(ContentEncodings::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let track_number = track_number.ok_or_else(|| missing("TrackNumber"))?;
+		let track_uid = track_uid.ok_or_else(|| missing("TrackUID"))?;
+		let track_type = track_type.ok_or_else(|| missing("TrackType"))?;
+		let flag_enabled = flag_enabled.unwrap_or(1);
+		let flag_default = flag_default.unwrap_or(1);
+		let flag_forced = flag_forced.unwrap_or(0);
+		let flag_lacing = flag_lacing.unwrap_or(1);
+		let max_block_addition_id = max_block_addition_id.unwrap_or(0);
+		let language = language.unwrap_or_else(|| "eng".to_string());
+		let codec_id = codec_id.ok_or_else(|| missing("CodecID"))?;
+		let codec_delay = codec_delay.unwrap_or(0);
+		let seek_pre_roll = seek_pre_roll.unwrap_or(0);
+		
+		Ok(Self {
+			track_number,
+			track_uid,
+			track_type,
+			flag_enabled,
+			flag_default,
+			flag_forced,
+			flag_hearing_impaired,
+			flag_visual_impaired,
+			flag_text_descriptions,
+			flag_original,
+			flag_commentary,
+			flag_lacing,
+			default_duration,
+			default_decoded_field_duration,
+			max_block_addition_id,
+			block_addition_mapping,
+			name,
+			language,
+			language_bcp47,
+			codec_id,
+			codec_private,
+			codec_name,
+			codec_delay,
+			seek_pre_roll,
+			track_translate,
+			video,
+			audio,
+			track_operation,
+			content_encodings
+		})
+	}
+}
+
+/// Contains elements that extend the track format, by adding content either to each frame, with BlockAddID, or to the track as a whole with BlockAddIDExtraData.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockAdditionMapping {
+	/// If the track format extension needs content beside frames, the value refers to the BlockAddID value being described.
+	pub block_add_id_value: Option<u64>,
+	/// A human-friendly name describing the type of BlockAdditional data.
+	pub block_add_id_name: Option<String>,
+	/// Stores the registered identifier of the Block Additional Mapping to define how the BlockAdditional data should be handled.
+	pub block_add_id_type: u64,
+	/// Extra binary data that the BlockAddIDType can use to interpret the BlockAdditional data.
+	pub block_add_id_extra_data: Option<Vec<u8>>,
+}
+
+impl Master for BlockAdditionMapping {
+	const ID: ElementId = id::BLOCK_ADDITION_MAPPING;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut block_add_id_value = None;
+		let mut block_add_id_name = None;
+		let mut block_add_id_type = None;
+		let mut block_add_id_extra_data = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::BLOCK_ADD_ID_VALUE => block_add_id_value = Some(read_uint(&mut reader, len)?),
+				id::BLOCK_ADD_ID_NAME => block_add_id_name = Some(read_string(&mut reader, len)?),
+				id::BLOCK_ADD_ID_TYPE => block_add_id_type = Some(read_uint(&mut reader, len)?),
+				id::BLOCK_ADD_ID_EXTRA_DATA => block_add_id_extra_data = Some(read_binary(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let block_add_id_type = block_add_id_type.unwrap_or(0);
+		
+		Ok(Self {
+			block_add_id_value,
+			block_add_id_name,
+			block_add_id_type,
+			block_add_id_extra_data
+		})
+	}
+}
+
+/// The mapping between this TrackEntry and a track value in the given Chapter Codec.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackTranslate {
+	/// The binary value used to represent this TrackEntry in the chapter codec data.
+	pub track_translate_track_id: Vec<u8>,
+	/// This TrackTranslate applies to this chapter codec of the given chapter edition(s).
+	pub track_translate_codec: u64,
+	/// Specify a chapter edition UID on which this TrackTranslate applies.
+	pub track_translate_edition_uid: Vec<u64>,
+}
+
+impl Master for TrackTranslate {
+	const ID: ElementId = id::TRACK_TRANSLATE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_translate_track_id = None;
+		let mut track_translate_codec = None;
+		let mut track_translate_edition_uid = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_TRANSLATE_TRACK_ID => track_translate_track_id = Some(read_binary(&mut reader, len)?),
+				id::TRACK_TRANSLATE_CODEC => track_translate_codec = Some(read_uint(&mut reader, len)?),
+				id::TRACK_TRANSLATE_EDITION_UID => track_translate_edition_uid.push(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let track_translate_track_id = track_translate_track_id.ok_or_else(|| missing("TrackTranslateTrackID"))?;
+		let track_translate_codec = track_translate_codec.ok_or_else(|| missing("TrackTranslateCodec"))?;
+		
+		Ok(Self {
+			track_translate_track_id,
+			track_translate_codec,
+			track_translate_edition_uid
+		})
+	}
+}
+
+/// Video settings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Video {
+	/// Specify whether the video frames in this track are interlaced.
+	pub flag_interlaced: u64,
+	/// Specify the field ordering of video frames in this track.
+	pub field_order: u64,
+	/// Stereo-3D video mode.
+	pub stereo_mode: u64,
+	/// Indicate whether the BlockAdditional Element with BlockAddID of "1" contains Alpha data.
+	pub alpha_mode: u64,
+	/// Width of the encoded video frames in pixels.
+	pub pixel_width: u64,
+	/// Height of the encoded video frames in pixels.
+	pub pixel_height: u64,
+	/// The number of video pixels to remove at the bottom of the image.
+	pub pixel_crop_bottom: u64,
+	/// The number of video pixels to remove at the top of the image.
+	pub pixel_crop_top: u64,
+	/// The number of video pixels to remove on the left of the image.
+	pub pixel_crop_left: u64,
+	/// The number of video pixels to remove on the right of the image.
+	pub pixel_crop_right: u64,
+	/// Width of the video frames to display. Applies to the video frame after cropping (PixelCrop* Elements).
+	pub display_width: Option<u64>,
+	/// Height of the video frames to display. Applies to the video frame after cropping (PixelCrop* Elements).
+	pub display_height: Option<u64>,
+	/// How DisplayWidth & DisplayHeight are interpreted.
+	pub display_unit: u64,
+	/// Specify the uncompressed pixel format used for the Track's data as a FourCC.
+	pub uncompressed_four_cc: Option<Vec<u8>>,
+	/// Settings describing the colour format.
+	pub colour: Option<Colour>,
+	/// Describes the video projection details. Used to render spherical, VR videos or flipping videos horizontally/vertically.
+	pub projection: Option<Projection>,
+}
+
+impl Master for Video {
+	const ID: ElementId = id::VIDEO;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut flag_interlaced = None;
+		let mut field_order = None;
+		let mut stereo_mode = None;
+		let mut alpha_mode = None;
+		let mut pixel_width = None;
+		let mut pixel_height = None;
+		let mut pixel_crop_bottom = None;
+		let mut pixel_crop_top = None;
+		let mut pixel_crop_left = None;
+		let mut pixel_crop_right = None;
+		let mut display_width = None;
+		let mut display_height = None;
+		let mut display_unit = None;
+		let mut uncompressed_four_cc = None;
+		let mut colour = None;
+		let mut projection = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::FLAG_INTERLACED => flag_interlaced = Some(read_uint(&mut reader, len)?),
+				id::FIELD_ORDER => field_order = Some(read_uint(&mut reader, len)?),
+				id::STEREO_MODE => stereo_mode = Some(read_uint(&mut reader, len)?),
+				id::ALPHA_MODE => alpha_mode = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_WIDTH => pixel_width = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_HEIGHT => pixel_height = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_CROP_BOTTOM => pixel_crop_bottom = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_CROP_TOP => pixel_crop_top = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_CROP_LEFT => pixel_crop_left = Some(read_uint(&mut reader, len)?),
+				id::PIXEL_CROP_RIGHT => pixel_crop_right = Some(read_uint(&mut reader, len)?),
+				id::DISPLAY_WIDTH => display_width = Some(read_uint(&mut reader, len)?),
+				id::DISPLAY_HEIGHT => display_height = Some(read_uint(&mut reader, len)?),
+				id::DISPLAY_UNIT => display_unit = Some(read_uint(&mut reader, len)?),
+				id::UNCOMPRESSED_FOUR_CC => uncompressed_four_cc = Some(read_binary(&mut reader, len)?),
+				id::COLOUR => colour = Some(Colour::read(&mut reader, len)?),
+				id::PROJECTION => projection = Some(Projection::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let flag_interlaced = flag_interlaced.unwrap_or(0);
+		let field_order = field_order.unwrap_or(2);
+		let stereo_mode = stereo_mode.unwrap_or(0);
+		let alpha_mode = alpha_mode.unwrap_or(0);
+		let pixel_width = pixel_width.ok_or_else(|| missing("PixelWidth"))?;
+		let pixel_height = pixel_height.ok_or_else(|| missing("PixelHeight"))?;
+		let pixel_crop_bottom = pixel_crop_bottom.unwrap_or(0);
+		let pixel_crop_top = pixel_crop_top.unwrap_or(0);
+		let pixel_crop_left = pixel_crop_left.unwrap_or(0);
+		let pixel_crop_right = pixel_crop_right.unwrap_or(0);
+		let display_unit = display_unit.unwrap_or(0);
+		
+		Ok(Self {
+			flag_interlaced,
+			field_order,
+			stereo_mode,
+			alpha_mode,
+			pixel_width,
+			pixel_height,
+			pixel_crop_bottom,
+			pixel_crop_top,
+			pixel_crop_left,
+			pixel_crop_right,
+			display_width,
+			display_height,
+			display_unit,
+			uncompressed_four_cc,
+			colour,
+			projection
+		})
+	}
+}
+
+/// Settings describing the colour format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Colour {
+	/// The Matrix Coefficients of the video used to derive luma and chroma values from red, green, and blue color primaries.
+	pub matrix_coefficients: u64,
+	/// Number of decoded bits per channel. A value of 0 indicates that the BitsPerChannel is unspecified.
+	pub bits_per_channel: u64,
+	/// The amount of pixels to remove in the Cr and Cb channels for every pixel not removed horizontally.
+	pub chroma_subsampling_horz: Option<u64>,
+	/// The amount of pixels to remove in the Cr and Cb channels for every pixel not removed vertically.
+	pub chroma_subsampling_vert: Option<u64>,
+	/// The amount of pixels to remove in the Cb channel for every pixel not removed horizontally. This is additive with ChromaSubsamplingHorz.
+	pub cb_subsampling_horz: Option<u64>,
+	/// The amount of pixels to remove in the Cb channel for every pixel not removed vertically. This is additive with ChromaSubsamplingVert.
+	pub cb_subsampling_vert: Option<u64>,
+	/// How chroma is subsampled horizontally.
+	pub chroma_siting_horz: u64,
+	/// How chroma is subsampled vertically.
+	pub chroma_siting_vert: u64,
+	/// Clipping of the color ranges.
+	pub range: u64,
+	/// The transfer characteristics of the video.
+	pub transfer_characteristics: u64,
+	/// The colour primaries of the video.
+	pub primaries: u64,
+	/// Maximum brightness of a single pixel (Maximum Content Light Level) in candelas per square meter (cd/m^2).
+	pub max_cll: Option<u64>,
+	/// Maximum brightness of a single full frame (Maximum Frame-Average Light Level) in candelas per square meter (cd/m^2).
+	pub max_fall: Option<u64>,
+	/// SMPTE 2086 mastering data.
+	pub mastering_metadata: Option<MasteringMetadata>,
+}
+
+impl Master for Colour {
+	const ID: ElementId = id::COLOUR;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut matrix_coefficients = None;
+		let mut bits_per_channel = None;
+		let mut chroma_subsampling_horz = None;
+		let mut chroma_subsampling_vert = None;
+		let mut cb_subsampling_horz = None;
+		let mut cb_subsampling_vert = None;
+		let mut chroma_siting_horz = None;
+		let mut chroma_siting_vert = None;
+		let mut range = None;
+		let mut transfer_characteristics = None;
+		let mut primaries = None;
+		let mut max_cll = None;
+		let mut max_fall = None;
+		let mut mastering_metadata = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::MATRIX_COEFFICIENTS => matrix_coefficients = Some(read_uint(&mut reader, len)?),
+				id::BITS_PER_CHANNEL => bits_per_channel = Some(read_uint(&mut reader, len)?),
+				id::CHROMA_SUBSAMPLING_HORZ => chroma_subsampling_horz = Some(read_uint(&mut reader, len)?),
+				id::CHROMA_SUBSAMPLING_VERT => chroma_subsampling_vert = Some(read_uint(&mut reader, len)?),
+				id::CB_SUBSAMPLING_HORZ => cb_subsampling_horz = Some(read_uint(&mut reader, len)?),
+				id::CB_SUBSAMPLING_VERT => cb_subsampling_vert = Some(read_uint(&mut reader, len)?),
+				id::CHROMA_SITING_HORZ => chroma_siting_horz = Some(read_uint(&mut reader, len)?),
+				id::CHROMA_SITING_VERT => chroma_siting_vert = Some(read_uint(&mut reader, len)?),
+				id::RANGE => range = Some(read_uint(&mut reader, len)?),
+				id::TRANSFER_CHARACTERISTICS => transfer_characteristics = Some(read_uint(&mut reader, len)?),
+				id::PRIMARIES => primaries = Some(read_uint(&mut reader, len)?),
+				id::MAX_CLL => max_cll = Some(read_uint(&mut reader, len)?),
+				id::MAX_FALL => max_fall = Some(read_uint(&mut reader, len)?),
+				id::MASTERING_METADATA => mastering_metadata = Some(MasteringMetadata::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let matrix_coefficients = matrix_coefficients.unwrap_or(2);
+		let bits_per_channel = bits_per_channel.unwrap_or(0);
+		let chroma_siting_horz = chroma_siting_horz.unwrap_or(0);
+		let chroma_siting_vert = chroma_siting_vert.unwrap_or(0);
+		let range = range.unwrap_or(0);
+		let transfer_characteristics = transfer_characteristics.unwrap_or(2);
+		let primaries = primaries.unwrap_or(2);
+		
+		Ok(Self {
+			matrix_coefficients,
+			bits_per_channel,
+			chroma_subsampling_horz,
+			chroma_subsampling_vert,
+			cb_subsampling_horz,
+			cb_subsampling_vert,
+			chroma_siting_horz,
+			chroma_siting_vert,
+			range,
+			transfer_characteristics,
+			primaries,
+			max_cll,
+			max_fall,
+			mastering_metadata
+		})
+	}
+}
+
+/// SMPTE 2086 mastering data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MasteringMetadata {
+	/// Red X chromaticity coordinate, as defined by CIE 1931.
+	pub primary_r_chromaticity_x: Option<f64>,
+	/// Red Y chromaticity coordinate, as defined by CIE 1931.
+	pub primary_r_chromaticity_y: Option<f64>,
+	/// Green X chromaticity coordinate, as defined by CIE 1931.
+	pub primary_g_chromaticity_x: Option<f64>,
+	/// Green Y chromaticity coordinate, as defined by CIE 1931.
+	pub primary_g_chromaticity_y: Option<f64>,
+	/// Blue X chromaticity coordinate, as defined by CIE 1931.
+	pub primary_b_chromaticity_x: Option<f64>,
+	/// Blue Y chromaticity coordinate, as defined by CIE 1931.
+	pub primary_b_chromaticity_y: Option<f64>,
+	/// White X chromaticity coordinate, as defined by CIE 1931.
+	pub white_point_chromaticity_x: Option<f64>,
+	/// White Y chromaticity coordinate, as defined by CIE 1931.
+	pub white_point_chromaticity_y: Option<f64>,
+	/// Maximum luminance. Represented in candelas per square meter (cd/m^2).
+	pub luminance_max: Option<f64>,
+	/// Minimum luminance. Represented in candelas per square meter (cd/m^2).
+	pub luminance_min: Option<f64>,
+}
+
+impl Master for MasteringMetadata {
+	const ID: ElementId = id::MASTERING_METADATA;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut primary_r_chromaticity_x = None;
+		let mut primary_r_chromaticity_y = None;
+		let mut primary_g_chromaticity_x = None;
+		let mut primary_g_chromaticity_y = None;
+		let mut primary_b_chromaticity_x = None;
+		let mut primary_b_chromaticity_y = None;
+		let mut white_point_chromaticity_x = None;
+		let mut white_point_chromaticity_y = None;
+		let mut luminance_max = None;
+		let mut luminance_min = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::PRIMARY_R_CHROMATICITY_X => primary_r_chromaticity_x = Some(read_float(&mut reader, len)?),
+				id::PRIMARY_R_CHROMATICITY_Y => primary_r_chromaticity_y = Some(read_float(&mut reader, len)?),
+				id::PRIMARY_G_CHROMATICITY_X => primary_g_chromaticity_x = Some(read_float(&mut reader, len)?),
+				id::PRIMARY_G_CHROMATICITY_Y => primary_g_chromaticity_y = Some(read_float(&mut reader, len)?),
+				id::PRIMARY_B_CHROMATICITY_X => primary_b_chromaticity_x = Some(read_float(&mut reader, len)?),
+				id::PRIMARY_B_CHROMATICITY_Y => primary_b_chromaticity_y = Some(read_float(&mut reader, len)?),
+				id::WHITE_POINT_CHROMATICITY_X => white_point_chromaticity_x = Some(read_float(&mut reader, len)?),
+				id::WHITE_POINT_CHROMATICITY_Y => white_point_chromaticity_y = Some(read_float(&mut reader, len)?),
+				id::LUMINANCE_MAX => luminance_max = Some(read_float(&mut reader, len)?),
+				id::LUMINANCE_MIN => luminance_min = Some(read_float(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			primary_r_chromaticity_x,
+			primary_r_chromaticity_y,
+			primary_g_chromaticity_x,
+			primary_g_chromaticity_y,
+			primary_b_chromaticity_x,
+			primary_b_chromaticity_y,
+			white_point_chromaticity_x,
+			white_point_chromaticity_y,
+			luminance_max,
+			luminance_min
+		})
+	}
+}
+
+/// Describes the video projection details. Used to render spherical, VR videos or flipping videos horizontally/vertically.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+	/// Describes the projection used for this video track.
+	pub projection_type: u64,
+	/// Private data that only applies to a specific projection.
+	pub projection_private: Option<Vec<u8>>,
+	/// Specifies a yaw rotation to the projection, in degrees.
+	pub projection_pose_yaw: f64,
+	/// Specifies a pitch rotation to the projection, in degrees.
+	pub projection_pose_pitch: f64,
+	/// Specifies a roll rotation to the projection, in degrees.
+	pub projection_pose_roll: f64,
+}
+
+impl Master for Projection {
+	const ID: ElementId = id::PROJECTION;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut projection_type = None;
+		let mut projection_private = None;
+		let mut projection_pose_yaw = None;
+		let mut projection_pose_pitch = None;
+		let mut projection_pose_roll = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::PROJECTION_TYPE => projection_type = Some(read_uint(&mut reader, len)?),
+				id::PROJECTION_PRIVATE => projection_private = Some(read_binary(&mut reader, len)?),
+				id::PROJECTION_POSE_YAW => projection_pose_yaw = Some(read_float(&mut reader, len)?),
+				id::PROJECTION_POSE_PITCH => projection_pose_pitch = Some(read_float(&mut reader, len)?),
+				id::PROJECTION_POSE_ROLL => projection_pose_roll = Some(read_float(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let projection_type = projection_type.unwrap_or(0);
+		let projection_pose_yaw = projection_pose_yaw.unwrap_or(0.0);
+		let projection_pose_pitch = projection_pose_pitch.unwrap_or(0.0);
+		let projection_pose_roll = projection_pose_roll.unwrap_or(0.0);
+		
+		Ok(Self {
+			projection_type,
+			projection_private,
+			projection_pose_yaw,
+			projection_pose_pitch,
+			projection_pose_roll
+		})
+	}
+}
+
+/// Audio settings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Audio {
+	/// Sampling frequency in Hz.
+	pub sampling_frequency: f64,
+	/// Real output sampling frequency in Hz (used for SBR techniques).
+	pub output_sampling_frequency: Option<f64>,
+	/// Numbers of channels in the track.
+	pub channels: u64,
+	/// Bits per sample, mostly used for PCM.
+	pub bit_depth: Option<u64>,
+	/// Audio emphasis applied on audio samples. The player **MUST** apply the inverse emphasis to get the proper audio samples.
+	pub emphasis: u64,
+}
+
+impl Master for Audio {
+	const ID: ElementId = id::AUDIO;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut sampling_frequency = None;
+		let mut output_sampling_frequency = None;
+		let mut channels = None;
+		let mut bit_depth = None;
+		let mut emphasis = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::SAMPLING_FREQUENCY => sampling_frequency = Some(read_float(&mut reader, len)?),
+				id::OUTPUT_SAMPLING_FREQUENCY => output_sampling_frequency = Some(read_float(&mut reader, len)?),
+				id::CHANNELS => channels = Some(read_uint(&mut reader, len)?),
+				id::BIT_DEPTH => bit_depth = Some(read_uint(&mut reader, len)?),
+				id::EMPHASIS => emphasis = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let sampling_frequency = sampling_frequency.unwrap_or(8000.0);
+		let channels = channels.unwrap_or(1);
+		let emphasis = emphasis.unwrap_or(0);
+		
+		Ok(Self {
+			sampling_frequency,
+			output_sampling_frequency,
+			channels,
+			bit_depth,
+			emphasis
+		})
+	}
+}
+
+/// Operation that needs to be applied on tracks to create this virtual track.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackOperation {
+	/// Contains the list of all video plane tracks that need to be combined to create this 3D track.
+	pub track_combine_planes: Option<TrackCombinePlanes>,
+	/// Contains the list of all tracks whose Blocks need to be combined to create this virtual track.
+	pub track_join_blocks: Option<TrackJoinBlocks>,
+}
+
+impl Master for TrackOperation {
+	const ID: ElementId = id::TRACK_OPERATION;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_combine_planes = None;
+		let mut track_join_blocks = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_COMBINE_PLANES => track_combine_planes = Some(TrackCombinePlanes::read(&mut reader, len)?),
+				id::TRACK_JOIN_BLOCKS => track_join_blocks = Some(TrackJoinBlocks::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			track_combine_planes,
+			track_join_blocks
+		})
+	}
+}
+
+/// Contains the list of all video plane tracks that need to be combined to create this 3D track.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackCombinePlanes {
+	/// Contains a video plane track that need to be combined to create this 3D track.
+	pub track_plane: Vec<TrackPlane>,
+}
+
+impl Master for TrackCombinePlanes {
+	const ID: ElementId = id::TRACK_COMBINE_PLANES;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_plane = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_PLANE => track_plane.push(TrackPlane::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			track_plane
+		})
+	}
+}
+
+/// Contains a video plane track that need to be combined to create this 3D track.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackPlane {
+	/// The trackUID number of the track representing the plane.
+	pub track_plane_uid: u64,
+	/// The kind of plane this track corresponds to.
+	pub track_plane_type: u64,
+}
+
+impl Master for TrackPlane {
+	const ID: ElementId = id::TRACK_PLANE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_plane_uid = None;
+		let mut track_plane_type = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_PLANE_UID => track_plane_uid = Some(read_uint(&mut reader, len)?),
+				id::TRACK_PLANE_TYPE => track_plane_type = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let track_plane_uid = track_plane_uid.ok_or_else(|| missing("TrackPlaneUID"))?;
+		let track_plane_type = track_plane_type.ok_or_else(|| missing("TrackPlaneType"))?;
+		
+		Ok(Self {
+			track_plane_uid,
+			track_plane_type
+		})
+	}
+}
+
+/// Contains the list of all tracks whose Blocks need to be combined to create this virtual track.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackJoinBlocks {
+	/// The trackUID number of a track whose blocks are used to create this virtual track.
+	pub track_join_uid: Vec<u64>,
+}
+
+impl Master for TrackJoinBlocks {
+	const ID: ElementId = id::TRACK_JOIN_BLOCKS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut track_join_uid = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TRACK_JOIN_UID => track_join_uid.push(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			track_join_uid
+		})
+	}
+}
+
+/// Settings for several content encoding mechanisms like compression or encryption.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentEncodings {
+	/// Settings for one content encoding like compression or encryption.
+	pub content_encoding: Vec<ContentEncoding>,
+}
+
+impl Master for ContentEncodings {
+	const ID: ElementId = id::CONTENT_ENCODINGS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut content_encoding = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CONTENT_ENCODING => content_encoding.push(ContentEncoding::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			content_encoding
+		})
+	}
+}
+
+/// Settings for one content encoding like compression or encryption.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentEncoding {
+	/// Tell in which order to apply each ContentEncoding of the ContentEncodings. The decoder/demuxer **MUST** start with the ContentEncoding with the highest ContentEncodingOrder and work its way down to the ContentEncoding with the lowest ContentEncodingOrder.
+	pub content_encoding_order: u64,
+	/// A bit field that describes which Elements have been modified in this way.
+	pub content_encoding_scope: u64,
+	/// A value describing what kind of transformation is applied.
+	pub content_encoding_type: u64,
+	/// Settings describing the compression used.
+	pub content_compression: Option<ContentCompression>,
+	/// Settings describing the encryption used.
+	pub content_encryption: Option<ContentEncryption>,
+}
+
+impl Master for ContentEncoding {
+	const ID: ElementId = id::CONTENT_ENCODING;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut content_encoding_order = None;
+		let mut content_encoding_scope = None;
+		let mut content_encoding_type = None;
+		let mut content_compression = None;
+		let mut content_encryption = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CONTENT_ENCODING_ORDER => content_encoding_order = Some(read_uint(&mut reader, len)?),
+				id::CONTENT_ENCODING_SCOPE => content_encoding_scope = Some(read_uint(&mut reader, len)?),
+				id::CONTENT_ENCODING_TYPE => content_encoding_type = Some(read_uint(&mut reader, len)?),
+				id::CONTENT_COMPRESSION => content_compression = Some(ContentCompression::read(&mut reader, len)?),
+				id::CONTENT_ENCRYPTION => content_encryption = Some(ContentEncryption::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let content_encoding_order = content_encoding_order.unwrap_or(0);
+		let content_encoding_scope = content_encoding_scope.unwrap_or(1);
+		let content_encoding_type = content_encoding_type.unwrap_or(0);
+		
+		Ok(Self {
+			content_encoding_order,
+			content_encoding_scope,
+			content_encoding_type,
+			content_compression,
+			content_encryption
+		})
+	}
+}
+
+/// Settings describing the compression used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentCompression {
+	/// The compression algorithm used.
+	pub content_comp_algo: u64,
+	/// Settings that might be needed by the decompressor. For Header Stripping (ContentCompAlgo=3), the bytes that were removed from the beginning of each frame of the track.
+	pub content_comp_settings: Option<Vec<u8>>,
+}
+
+impl Master for ContentCompression {
+	const ID: ElementId = id::CONTENT_COMPRESSION;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut content_comp_algo = None;
+		let mut content_comp_settings = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CONTENT_COMP_ALGO => content_comp_algo = Some(read_uint(&mut reader, len)?),
+				id::CONTENT_COMP_SETTINGS => content_comp_settings = Some(read_binary(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let content_comp_algo = content_comp_algo.unwrap_or(0);
+		
+		Ok(Self {
+			content_comp_algo,
+			content_comp_settings
+		})
+	}
+}
+
+/// Settings describing the encryption used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentEncryption {
+	/// The encryption algorithm used.
+	pub content_enc_algo: u64,
+	/// For public key algorithms this is the ID of the public key the the data was encrypted with.
+	pub content_enc_key_id: Option<Vec<u8>>,
+	/// Settings describing the encryption algorithm used.
+	pub content_enc_aes_settings: Option<ContentEncAesSettings>,
+}
+
+impl Master for ContentEncryption {
+	const ID: ElementId = id::CONTENT_ENCRYPTION;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut content_enc_algo = None;
+		let mut content_enc_key_id = None;
+		let mut content_enc_aes_settings = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CONTENT_ENC_ALGO => content_enc_algo = Some(read_uint(&mut reader, len)?),
+				id::CONTENT_ENC_KEY_ID => content_enc_key_id = Some(read_binary(&mut reader, len)?),
+				id::CONTENT_ENC_AES_SETTINGS => content_enc_aes_settings = Some(ContentEncAesSettings::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let content_enc_algo = content_enc_algo.unwrap_or(0);
+		
+		Ok(Self {
+			content_enc_algo,
+			content_enc_key_id,
+			content_enc_aes_settings
+		})
+	}
+}
+
+/// Settings describing the encryption algorithm used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentEncAesSettings {
+	/// The AES cipher mode used in the encryption.
+	pub aes_settings_cipher_mode: u64,
+}
+
+impl Master for ContentEncAesSettings {
+	const ID: ElementId = id::CONTENT_ENC_AES_SETTINGS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut aes_settings_cipher_mode = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::AES_SETTINGS_CIPHER_MODE => aes_settings_cipher_mode = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let aes_settings_cipher_mode = aes_settings_cipher_mode.ok_or_else(|| missing("AESSettingsCipherMode"))?;
+		
+		Ok(Self {
+			aes_settings_cipher_mode
+		})
+	}
+}
+
+/// A Top-Level Element to speed seeking access. All entries are local to the Segment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cues {
+	/// Contains all information relative to a seek point in the Segment.
+	pub cue_point: Vec<CuePoint>,
+}
+
+impl Master for Cues {
+	const ID: ElementId = id::CUES;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut cue_point = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CUE_POINT => cue_point.push(CuePoint::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			cue_point
+		})
+	}
+}
+
+/// Contains all information relative to a seek point in the Segment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CuePoint {
+	/// Absolute timestamp of the seek point, expressed in Segment Ticks which is based on TimestampScale.
+	pub cue_time: u64,
+	/// Contain positions for different tracks corresponding to the timestamp.
+	pub cue_track_positions: Vec<CueTrackPositions>,
+}
+
+impl Master for CuePoint {
+	const ID: ElementId = id::CUE_POINT;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut cue_time = None;
+		let mut cue_track_positions = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CUE_TIME => cue_time = Some(read_uint(&mut reader, len)?),
+				id::CUE_TRACK_POSITIONS => cue_track_positions.push(CueTrackPositions::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let cue_time = cue_time.ok_or_else(|| missing("CueTime"))?;
+		
+		Ok(Self {
+			cue_time,
+			cue_track_positions
+		})
+	}
+}
+
+/// Contain positions for different tracks corresponding to the timestamp.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CueTrackPositions {
+	/// The track for which a position is given.
+	pub cue_track: u64,
+	/// The Segment Position of the Cluster containing the associated Block.
+	pub cue_cluster_position: u64,
+	/// The relative position inside the Cluster of the referenced SimpleBlock or BlockGroup with 0 being the first possible position for an Element inside that Cluster.
+	pub cue_relative_position: Option<u64>,
+	/// The duration of the block, expressed in Segment Ticks which is based on TimestampScale.
+	pub cue_duration: Option<u64>,
+	/// Number of the Block in the specified Cluster.
+	pub cue_block_number: Option<u64>,
+	/// The Segment Position of the Codec State corresponding to this Cue Element. 0 means that the data is taken from the initial Track Entry.
+	pub cue_codec_state: u64,
+	/// The Clusters containing the referenced Blocks.
+	pub cue_reference: Vec<CueReference>,
+}
+
+impl Master for CueTrackPositions {
+	const ID: ElementId = id::CUE_TRACK_POSITIONS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut cue_track = None;
+		let mut cue_cluster_position = None;
+		let mut cue_relative_position = None;
+		let mut cue_duration = None;
+		let mut cue_block_number = None;
+		let mut cue_codec_state = None;
+		let mut cue_reference = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CUE_TRACK => cue_track = Some(read_uint(&mut reader, len)?),
+				id::CUE_CLUSTER_POSITION => cue_cluster_position = Some(read_uint(&mut reader, len)?),
+				id::CUE_RELATIVE_POSITION => cue_relative_position = Some(read_uint(&mut reader, len)?),
+				id::CUE_DURATION => cue_duration = Some(read_uint(&mut reader, len)?),
+				id::CUE_BLOCK_NUMBER => cue_block_number = Some(read_uint(&mut reader, len)?),
+				id::CUE_CODEC_STATE => cue_codec_state = Some(read_uint(&mut reader, len)?),
+				id::CUE_REFERENCE => cue_reference.push(CueReference::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let cue_track = cue_track.ok_or_else(|| missing("CueTrack"))?;
+		let cue_cluster_position = cue_cluster_position.ok_or_else(|| missing("CueClusterPosition"))?;
+		let cue_codec_state = cue_codec_state.unwrap_or(0);
+		
+		Ok(Self {
+			cue_track,
+			cue_cluster_position,
+			cue_relative_position,
+			cue_duration,
+			cue_block_number,
+			cue_codec_state,
+			cue_reference
+		})
+	}
+}
+
+/// The Clusters containing the referenced Blocks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CueReference {
+	/// Timestamp of the referenced Block, expressed in Segment Ticks which is based on TimestampScale.
+	pub cue_ref_time: u64,
+}
+
+impl Master for CueReference {
+	const ID: ElementId = id::CUE_REFERENCE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut cue_ref_time = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CUE_REF_TIME => cue_ref_time = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let cue_ref_time = cue_ref_time.ok_or_else(|| missing("CueRefTime"))?;
+		
+		Ok(Self {
+			cue_ref_time
+		})
+	}
+}
+
+/// Contain attached files.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attachments {
+	/// An attached file.
+	pub attached_file: Vec<AttachedFile>,
+}
+
+impl Master for Attachments {
+	const ID: ElementId = id::ATTACHMENTS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut attached_file = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::ATTACHED_FILE => attached_file.push(AttachedFile::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			attached_file
+		})
+	}
+}
+
+/// An attached file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttachedFile {
+	/// A human-friendly name for the attached file.
+	pub file_description: Option<String>,
+	/// Filename of the attached file.
+	pub file_name: String,
+	/// Media type of the file following the format described in RFC 6838.
+	pub file_media_type: String,
+	/// The data of the file.
+	pub file_data: Vec<u8>,
+	/// Unique ID representing the file, as random as possible.
+	pub file_uid: u64,
+}
+
+impl Master for AttachedFile {
+	const ID: ElementId = id::ATTACHED_FILE;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut file_description = None;
+		let mut file_name = None;
+		let mut file_media_type = None;
+		let mut file_data = None;
+		let mut file_uid = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::FILE_DESCRIPTION => file_description = Some(read_string(&mut reader, len)?),
+				id::FILE_NAME => file_name = Some(read_string(&mut reader, len)?),
+				id::FILE_MEDIA_TYPE => file_media_type = Some(read_string(&mut reader, len)?),
+				id::FILE_DATA => file_data = Some(read_binary(&mut reader, len)?),
+				id::FILE_UID => file_uid = Some(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let file_name = file_name.ok_or_else(|| missing("FileName"))?;
+		let file_media_type = file_media_type.ok_or_else(|| missing("FileMediaType"))?;
+		let file_data = file_data.ok_or_else(|| missing("FileData"))?;
+		let file_uid = file_uid.ok_or_else(|| missing("FileUID"))?;
+		
+		Ok(Self {
+			file_description,
+			file_name,
+			file_media_type,
+			file_data,
+			file_uid
+		})
+	}
+}
+
+/// A system to define basic menus and partition data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chapters {
+	/// Contains all information about a Segment edition.
+	pub edition_entry: Vec<EditionEntry>,
+}
+
+impl Master for Chapters {
+	const ID: ElementId = id::CHAPTERS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut edition_entry = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::EDITION_ENTRY => edition_entry.push(EditionEntry::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			edition_entry
+		})
+	}
+}
+
+/// Contains all information about a Segment edition.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EditionEntry {
+	/// A unique ID to identify the edition. It's useful for tagging an edition.
+	pub edition_uid: Option<u64>,
+	/// Set to 1 if an edition is hidden. Hidden editions **SHOULD NOT** be available to the user interface (but still to Control Tracks).
+	pub edition_flag_hidden: u64,
+	/// Set to 1 if the edition **SHOULD** be used as the default one.
+	pub edition_flag_default: u64,
+	/// Set to 1 if the chapters can be defined multiple times and the order to play them is enforced.
+	pub edition_flag_ordered: u64,
+	/// Contains a possible string to use for the edition display for the given languages.
+	pub edition_display: Vec<EditionDisplay>,
+	/// Contains the atom information to use as the chapter atom (apply to all tracks).
+	pub chapter_atom: Vec<ChapterAtom>,
+}
+
+impl Master for EditionEntry {
+	const ID: ElementId = id::EDITION_ENTRY;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut edition_uid = None;
+		let mut edition_flag_hidden = None;
+		let mut edition_flag_default = None;
+		let mut edition_flag_ordered = None;
+		let mut edition_display = Vec::new();
+		let mut chapter_atom = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::EDITION_UID => edition_uid = Some(read_uint(&mut reader, len)?),
+				id::EDITION_FLAG_HIDDEN => edition_flag_hidden = Some(read_uint(&mut reader, len)?),
+				id::EDITION_FLAG_DEFAULT => edition_flag_default = Some(read_uint(&mut reader, len)?),
+				id::EDITION_FLAG_ORDERED => edition_flag_ordered = Some(read_uint(&mut reader, len)?),
+				id::EDITION_DISPLAY => edition_display.push(EditionDisplay::read(&mut reader, len)?),
+				id::CHAPTER_ATOM => chapter_atom.push(ChapterAtom::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let edition_flag_hidden = edition_flag_hidden.unwrap_or(0);
+		let edition_flag_default = edition_flag_default.unwrap_or(0);
+		let edition_flag_ordered = edition_flag_ordered.unwrap_or(0);
+		
+		Ok(Self {
+			edition_uid,
+			edition_flag_hidden,
+			edition_flag_default,
+			edition_flag_ordered,
+			edition_display,
+			chapter_atom
+		})
+	}
+}
+
+/// Contains a possible string to use for the edition display for the given languages.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EditionDisplay {
+	/// Contains the string to use as the edition name.
+	pub edition_string: String,
+	/// One language corresponding to the EditionString, in the BCP47 form.
+	pub edition_language_ietf: Vec<String>,
+}
+
+impl Master for EditionDisplay {
+	const ID: ElementId = id::EDITION_DISPLAY;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut edition_string = None;
+		let mut edition_language_ietf = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::EDITION_STRING => edition_string = Some(read_string(&mut reader, len)?),
+				id::EDITION_LANGUAGE_IETF => edition_language_ietf.push(read_string(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let edition_string = edition_string.ok_or_else(|| missing("EditionString"))?;
+		
+		Ok(Self {
+			edition_string,
+			edition_language_ietf
+		})
+	}
+}
+
+/// Contains the atom information to use as the chapter atom (apply to all tracks).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapterAtom {
+	/// Contains the atom information to use as the chapter atom (apply to all tracks).
+	pub chapter_atom: Vec<ChapterAtom>,
+	/// A unique ID to identify the Chapter.
+	pub chapter_uid: u64,
+	/// A unique string ID to identify the Chapter. Use for WebVTT cue identifier storage.
+	pub chapter_string_uid: Option<String>,
+	/// Timestamp of the start of Chapter, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub chapter_time_start: u64,
+	/// Timestamp of the end of Chapter timestamp excluded, expressed in Matroska Ticks -- i.e., in nanoseconds.
+	pub chapter_time_end: Option<u64>,
+	/// Set to 1 if a chapter is hidden. Hidden chapters **SHOULD NOT** be available to the user interface (but still to Control Tracks).
+	pub chapter_flag_hidden: u64,
+	/// Set to 1 if the chapter is enabled. It can be enabled/disabled by a Control Track.
+	pub chapter_flag_enabled: u64,
+	/// The SegmentUUID of another Segment to play during this chapter.
+	pub chapter_segment_uuid: Option<Vec<u8>>,
+	/// Indicate what type of content the ChapterAtom contains and might be skipped.
+	pub chapter_skip_type: Option<u64>,
+	/// The EditionUID to play from the Segment linked in ChapterSegmentUUID.
+	pub chapter_segment_edition_uid: Option<u64>,
+	/// Specify the physical equivalent of this ChapterAtom like "DVD" (60) or "SIDE" (50).
+	pub chapter_physical_equiv: Option<u64>,
+	/// List of tracks on which the chapter applies. If this Element is not present, all tracks apply.
+	pub chapter_track: Option<ChapterTrack>,
+	/// Contains all possible strings to use for the chapter display.
+	pub chapter_display: Vec<ChapterDisplay>,
+	/// Contains all the commands associated to the Atom.
+	pub chap_process: Vec<ChapProcess>,
+}
+
+impl Master for ChapterAtom {
+	const ID: ElementId = id::CHAPTER_ATOM;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chapter_atom = Vec::new();
+		let mut chapter_uid = None;
+		let mut chapter_string_uid = None;
+		let mut chapter_time_start = None;
+		let mut chapter_time_end = None;
+		let mut chapter_flag_hidden = None;
+		let mut chapter_flag_enabled = None;
+		let mut chapter_segment_uuid = None;
+		let mut chapter_skip_type = None;
+		let mut chapter_segment_edition_uid = None;
+		let mut chapter_physical_equiv = None;
+		let mut chapter_track = None;
+		let mut chapter_display = Vec::new();
+		let mut chap_process = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAPTER_ATOM => chapter_atom.push(ChapterAtom::read(&mut reader, len)?),
+				id::CHAPTER_UID => chapter_uid = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_STRING_UID => chapter_string_uid = Some(read_string(&mut reader, len)?),
+				id::CHAPTER_TIME_START => chapter_time_start = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_TIME_END => chapter_time_end = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_FLAG_HIDDEN => chapter_flag_hidden = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_FLAG_ENABLED => chapter_flag_enabled = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_SEGMENT_UUID => chapter_segment_uuid = Some(read_binary(&mut reader, len)?),
+				id::CHAPTER_SKIP_TYPE => chapter_skip_type = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_SEGMENT_EDITION_UID => chapter_segment_edition_uid = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_PHYSICAL_EQUIV => chapter_physical_equiv = Some(read_uint(&mut reader, len)?),
+				id::CHAPTER_TRACK => chapter_track = Some(ChapterTrack::read(&mut reader, len)?),
+				id::CHAPTER_DISPLAY => chapter_display.push(ChapterDisplay::read(&mut reader, len)?),
+				id::CHAP_PROCESS => chap_process.push(ChapProcess::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let chapter_uid = chapter_uid.ok_or_else(|| missing("ChapterUID"))?;
+		let chapter_time_start = chapter_time_start.ok_or_else(|| missing("ChapterTimeStart"))?;
+		let chapter_flag_hidden = chapter_flag_hidden.unwrap_or(0);
+		let chapter_flag_enabled = chapter_flag_enabled.unwrap_or(1);
+		
+		Ok(Self {
+			chapter_atom,
+			chapter_uid,
+			chapter_string_uid,
+			chapter_time_start,
+			chapter_time_end,
+			chapter_flag_hidden,
+			chapter_flag_enabled,
+			chapter_segment_uuid,
+			chapter_skip_type,
+			chapter_segment_edition_uid,
+			chapter_physical_equiv,
+			chapter_track,
+			chapter_display,
+			chap_process
+		})
+	}
+}
+
+/// List of tracks on which the chapter applies. If this Element is not present, all tracks apply.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapterTrack {
+	/// UID of the Track to apply this chapter to.
+	pub chapter_track_uid: Vec<u64>,
+}
+
+impl Master for ChapterTrack {
+	const ID: ElementId = id::CHAPTER_TRACK;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chapter_track_uid = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAPTER_TRACK_UID => chapter_track_uid.push(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			chapter_track_uid
+		})
+	}
+}
+
+/// Contains all possible strings to use for the chapter display.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapterDisplay {
+	/// Contains the string to use as the chapter atom.
+	pub chap_string: String,
+	/// A language corresponding to the string, in the Matroska languages form.
+	pub chap_language: Vec<String>,
+	/// A language corresponding to the ChapString, in the BCP47 form.
+	pub chap_language_bcp47: Vec<String>,
+	/// A country corresponding to the string, in the Matroska countries form.
+	pub chap_country: Vec<String>,
+}
+
+impl Master for ChapterDisplay {
+	const ID: ElementId = id::CHAPTER_DISPLAY;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chap_string = None;
+		let mut chap_language = Vec::new();
+		let mut chap_language_bcp47 = Vec::new();
+		let mut chap_country = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAP_STRING => chap_string = Some(read_string(&mut reader, len)?),
+				id::CHAP_LANGUAGE => chap_language.push(read_string(&mut reader, len)?),
+				id::CHAP_LANGUAGE_BCP47 => chap_language_bcp47.push(read_string(&mut reader, len)?),
+				id::CHAP_COUNTRY => chap_country.push(read_string(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let chap_string = chap_string.ok_or_else(|| missing("ChapString"))?;
+		if chap_language.is_empty() {
+			chap_language.push("eng".to_string());
+		}
+		
+		Ok(Self {
+			chap_string,
+			chap_language,
+			chap_language_bcp47,
+			chap_country
+		})
+	}
+}
+
+/// Contains all the commands associated to the Atom.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapProcess {
+	/// Contains the type of the codec used for the processing.
+	pub chap_process_codec_id: u64,
+	/// Some optional data attached to the ChapProcessCodecID information.
+	pub chap_process_private: Option<Vec<u8>>,
+	/// Contains all the commands associated to the Atom.
+	pub chap_process_command: Vec<ChapProcessCommand>,
+}
+
+impl Master for ChapProcess {
+	const ID: ElementId = id::CHAP_PROCESS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chap_process_codec_id = None;
+		let mut chap_process_private = None;
+		let mut chap_process_command = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAP_PROCESS_CODEC_ID => chap_process_codec_id = Some(read_uint(&mut reader, len)?),
+				id::CHAP_PROCESS_PRIVATE => chap_process_private = Some(read_binary(&mut reader, len)?),
+				id::CHAP_PROCESS_COMMAND => chap_process_command.push(ChapProcessCommand::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let chap_process_codec_id = chap_process_codec_id.unwrap_or(0);
+		
+		Ok(Self {
+			chap_process_codec_id,
+			chap_process_private,
+			chap_process_command
+		})
+	}
+}
+
+/// Contains all the commands associated to the Atom.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChapProcessCommand {
+	/// Defines when the process command **SHOULD** be handled.
+	pub chap_process_time: u64,
+	/// Contains the command information.
+	pub chap_process_data: Vec<u8>,
+}
+
+impl Master for ChapProcessCommand {
+	const ID: ElementId = id::CHAP_PROCESS_COMMAND;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut chap_process_time = None;
+		let mut chap_process_data = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::CHAP_PROCESS_TIME => chap_process_time = Some(read_uint(&mut reader, len)?),
+				id::CHAP_PROCESS_DATA => chap_process_data = Some(read_binary(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let chap_process_time = chap_process_time.ok_or_else(|| missing("ChapProcessTime"))?;
+		let chap_process_data = chap_process_data.ok_or_else(|| missing("ChapProcessData"))?;
+		
+		Ok(Self {
+			chap_process_time,
+			chap_process_data
+		})
+	}
+}
+
+/// Element containing metadata describing Tracks, Editions, Chapters, Attachments, or the Segment as a whole.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tags {
+	/// A single metadata descriptor.
+	pub tag: Vec<Tag>,
+}
+
+impl Master for Tags {
+	const ID: ElementId = id::TAGS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut tag = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TAG => tag.push(Tag::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		Ok(Self {
+			tag
+		})
+	}
+}
+
+/// A single metadata descriptor.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tag {
+	/// Specifies which other elements the metadata represented by the Tag applies to. If empty or omitted, then the Tag describes everything in the Segment.
+	pub targets: Targets,
+	/// Contains general information about the target.
+	pub simple_tag: Vec<SimpleTag>,
+}
+
+impl Master for Tag {
+	const ID: ElementId = id::TAG;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut targets = None;
+		let mut simple_tag = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TARGETS => targets = Some(Targets::read(&mut reader, len)?),
+				id::SIMPLE_TAG => simple_tag.push(SimpleTag::read(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let targets = targets.ok_or_else(|| missing("Targets"))?;
+		
+		Ok(Self {
+			targets,
+			simple_tag
+		})
+	}
+}
+
+/// Specifies which other elements the metadata represented by the Tag applies to. If empty or omitted, then the Tag describes everything in the Segment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Targets {
+	/// A number to indicate the logical level of the target.
+	pub target_type_value: u64,
+	/// An informational string that can be used to display the logical level of the target like "ALBUM", "TRACK", "MOVIE", "CHAPTER", etc.
+	pub target_type: Option<String>,
+	/// A UID that identifies the Track(s) that the tags belong to.
+	pub tag_track_uid: Vec<u64>,
+	/// A UID that identifies the EditionEntry(s) that the tags belong to.
+	pub tag_edition_uid: Vec<u64>,
+	/// A UID that identifies the Chapter(s) that the tags belong to.
+	pub tag_chapter_uid: Vec<u64>,
+	/// A UID that identifies the Attachment(s) that the tags belong to.
+	pub tag_attachment_uid: Vec<u64>,
+}
+
+impl Master for Targets {
+	const ID: ElementId = id::TARGETS;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut target_type_value = None;
+		let mut target_type = None;
+		let mut tag_track_uid = Vec::new();
+		let mut tag_edition_uid = Vec::new();
+		let mut tag_chapter_uid = Vec::new();
+		let mut tag_attachment_uid = Vec::new();
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::TARGET_TYPE_VALUE => target_type_value = Some(read_uint(&mut reader, len)?),
+				id::TARGET_TYPE => target_type = Some(read_string(&mut reader, len)?),
+				id::TAG_TRACK_UID => tag_track_uid.push(read_uint(&mut reader, len)?),
+				id::TAG_EDITION_UID => tag_edition_uid.push(read_uint(&mut reader, len)?),
+				id::TAG_CHAPTER_UID => tag_chapter_uid.push(read_uint(&mut reader, len)?),
+				id::TAG_ATTACHMENT_UID => tag_attachment_uid.push(read_uint(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let target_type_value = target_type_value.unwrap_or(50);
+		
+		Ok(Self {
+			target_type_value,
+			target_type,
+			tag_track_uid,
+			tag_edition_uid,
+			tag_chapter_uid,
+			tag_attachment_uid
+		})
+	}
+}
+
+/// Contains general information about the target.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimpleTag {
+	/// Contains general information about the target.
+	pub simple_tag: Vec<SimpleTag>,
+	/// The name of the Tag that is going to be stored.
+	pub tag_name: String,
+	/// Specifies the language of the tag specified, in the Matroska languages form.
+	pub tag_language: String,
+	/// The language used in the TagString, in the BCP47 form.
+	pub tag_language_bcp47: Option<String>,
+	/// A boolean value to indicate if this is the default/original language to use for the given tag.
+	pub tag_default: u64,
+	/// The value of the Tag.
+	pub tag_string: Option<String>,
+	/// The values of the Tag, if it is binary. Note that this cannot be used in the same SimpleTag as TagString.
+	pub tag_binary: Option<Vec<u8>>,
+}
+
+impl Master for SimpleTag {
+	const ID: ElementId = id::SIMPLE_TAG;
+	
+	fn read(reader: &mut dyn io::Read, len: u64) -> io::Result<Self> {
+		let mut reader = reader.take(len);
+		let mut simple_tag = Vec::new();
+		let mut tag_name = None;
+		let mut tag_language = None;
+		let mut tag_language_bcp47 = None;
+		let mut tag_default = None;
+		let mut tag_string = None;
+		let mut tag_binary = None;
+		
+		while reader.limit() > 0 {
+			let (id, len, _) = read_header(&mut reader)?;
+			match id {
+				id::SIMPLE_TAG => simple_tag.push(SimpleTag::read(&mut reader, len)?),
+				id::TAG_NAME => tag_name = Some(read_string(&mut reader, len)?),
+				id::TAG_LANGUAGE => tag_language = Some(read_string(&mut reader, len)?),
+				id::TAG_LANGUAGE_BCP47 => tag_language_bcp47 = Some(read_string(&mut reader, len)?),
+				id::TAG_DEFAULT => tag_default = Some(read_uint(&mut reader, len)?),
+				id::TAG_STRING => tag_string = Some(read_string(&mut reader, len)?),
+				id::TAG_BINARY => tag_binary = Some(read_binary(&mut reader, len)?),
+				_ => skip(&mut reader, len)?
+			}
+		}
+		
+		let tag_name = tag_name.ok_or_else(|| missing("TagName"))?;
+		let tag_language = tag_language.unwrap_or_else(|| "und".to_string());
+		let tag_default = tag_default.unwrap_or(1);
+		
+		Ok(Self {
+			simple_tag,
+			tag_name,
+			tag_language,
+			tag_language_bcp47,
+			tag_default,
+			tag_string,
+			tag_binary
+		})
+	}
+}
@@ -0,0 +1,509 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Ogg container (RFC 3533).
+//!
+//! [`OggReader`] reads the pages of one logical stream and reassembles its packets. Pages with a
+//! wrong checksum are skipped by searching for the next capture pattern, packets that were split
+//! across a lost page are dropped. Seeking by granule position bisects the stream.
+
+use std::{collections::VecDeque, io::{self, Read, Write, Seek, SeekFrom, Error, ErrorKind}};
+
+pub const MAGIC: [u8; 4] = *b"OggS";
+
+/// The page continues a packet from the previous page.
+pub const FLAG_CONTINUED: u8 = 0x01;
+/// The page is the first page of a logical stream.
+pub const FLAG_BOS:       u8 = 0x02;
+/// The page is the last page of a logical stream.
+pub const FLAG_EOS:       u8 = 0x04;
+
+/// The granule position of pages on which no packet ends.
+pub const NO_GRANULE: u64 = u64::MAX;
+
+/// The size of the largest possible page, including the header.
+pub const MAX_PAGE_SIZE: usize = 27 + 255 + 255 * 255;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Page {
+	pub flags:    u8,
+	/// The codec specific position after the last packet that ends on this page, [`NO_GRANULE`] if there is none.
+	pub granule:  u64,
+	pub serial:   u32,
+	pub sequence: u32,
+	/// The lacing values, a value below 255 ends a packet.
+	pub segments: Vec<u8>,
+	pub data:     Vec<u8>
+}
+
+impl Page {
+	pub fn is_continued(&self) -> bool {
+		self.flags & FLAG_CONTINUED != 0
+	}
+	
+	pub fn is_bos(&self) -> bool {
+		self.flags & FLAG_BOS != 0
+	}
+	
+	pub fn is_eos(&self) -> bool {
+		self.flags & FLAG_EOS != 0
+	}
+	
+	/// Writes the page, the checksum is computed.
+	pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+		if self.segments.len() > 255 || self.segments.iter().map(|l| *l as usize).sum::<usize>() != self.data.len() {
+			return Err(Error::new(ErrorKind::InvalidInput, "the lacing values do not match the data"));
+		}
+		
+		let mut buf = Vec::with_capacity(27 + self.segments.len() + self.data.len());
+		buf.extend_from_slice(&MAGIC);
+		buf.push(0);
+		buf.push(self.flags);
+		buf.extend_from_slice(&self.granule.to_le_bytes());
+		buf.extend_from_slice(&self.serial.to_le_bytes());
+		buf.extend_from_slice(&self.sequence.to_le_bytes());
+		buf.extend_from_slice(&[0; 4]);
+		buf.push(self.segments.len() as u8);
+		buf.extend_from_slice(&self.segments);
+		buf.extend_from_slice(&self.data);
+		let crc = crc32(&buf);
+		buf[22..26].copy_from_slice(&crc.to_le_bytes());
+		writer.write_all(&buf)
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Packet {
+	pub data:    Vec<u8>,
+	/// The granule position of the page, if this is the last packet that ends on it, otherwise [`NO_GRANULE`].
+	pub granule: u64,
+	/// The packet is on the first page of the stream.
+	pub bos:     bool,
+	/// The packet is the last packet of the stream.
+	pub eos:     bool
+}
+
+pub struct OggReader<R> {
+	reader:     io::BufReader<R>,
+	/// The serial number of the logical stream that is read, pages of other streams are skipped.
+	/// If not set, this is the serial number of the first page.
+	pub serial: Option<u32>,
+	/// The offset of the first page.
+	start:      u64,
+	/// The offset of the next byte in the reader.
+	offset:     u64,
+	/// The sequence number of the next page.
+	sequence:   Option<u32>,
+	packets:    VecDeque<Packet>,
+	/// The data of a packet that is continued on the next page.
+	partial:    Vec<u8>,
+	continued:  bool,
+	/// Drop all packets that end on the next page, set after seeking.
+	skip:       bool
+}
+
+impl<R: Read + Seek> OggReader<R> {
+	pub fn new(mut reader: R) -> io::Result<Self> {
+		let start = reader.stream_position()?;
+		Ok(Self {
+			reader:    io::BufReader::new(reader),
+			serial:    None,
+			start,
+			offset:    start,
+			sequence:  None,
+			packets:   VecDeque::new(),
+			partial:   Vec::new(),
+			continued: false,
+			skip:      false
+		})
+	}
+	
+	/// Returns the offset of the next page that is read, packets that were already read from
+	/// previous pages may still be pending.
+	pub fn position(&self) -> u64 {
+		self.offset
+	}
+	
+	/// Reads the next page of any logical stream with a valid checksum, `None` at the end of the stream.
+	pub fn read_page(&mut self) -> io::Result<Option<Page>> {
+		Ok(self.next_page()?.map(|(_, page)| page))
+	}
+	
+	/// Returns the offset and content of the next valid page.
+	fn next_page(&mut self) -> io::Result<Option<(u64, Page)>> {
+		loop {
+			// search for the capture pattern
+			let mut window = [0u8; 4];
+			let mut skipped = 0u64;
+			while window != MAGIC || skipped < 4 {
+				let mut byte = [0u8];
+				if self.reader.read(&mut byte)? == 0 {
+					return Ok(None);
+				}
+				
+				window = [window[1], window[2], window[3], byte[0]];
+				skipped += 1;
+			}
+			
+			let offset = self.offset + skipped - 4;
+			self.offset += skipped;
+			
+			let mut header = [0u8; 27];
+			header[..4].copy_from_slice(&MAGIC);
+			if !self.read_full(&mut header[4..])? {
+				return Ok(None);
+			}
+			
+			let mut segments = vec![0u8; header[26] as usize];
+			if !self.read_full(&mut segments)? {
+				return Ok(None);
+			}
+			
+			let mut data = vec![0u8; segments.iter().map(|l| *l as usize).sum()];
+			if !self.read_full(&mut data)? {
+				return Ok(None);
+			}
+			
+			let crc = u32::from_le_bytes(header[22..26].try_into().unwrap());
+			header[22..26].fill(0);
+			let valid = header[4] == 0 && crc == [&header[..], &segments, &data].iter()
+				.fold(0, |crc, data| crc32_update(crc, data));
+			
+			if !valid {
+				// resynchronize after the start of this page, a capture pattern in the data may have been mistaken for a page
+				self.reader.seek(SeekFrom::Start(offset + 1))?;
+				self.offset = offset + 1;
+				continue;
+			}
+			
+			return Ok(Some((offset, Page {
+				flags:    header[5],
+				granule:  u64::from_le_bytes(header[6..14].try_into().unwrap()),
+				serial:   u32::from_le_bytes(header[14..18].try_into().unwrap()),
+				sequence: u32::from_le_bytes(header[18..22].try_into().unwrap()),
+				segments,
+				data
+			})));
+		}
+	}
+	
+	/// Fills the buffer, returns false if the end of the stream was reached before.
+	fn read_full(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+		let mut len = 0;
+		while len < buf.len() {
+			match self.reader.read(&mut buf[len..])? {
+				0 => break,
+				n => len += n
+			}
+		}
+		self.offset += len as u64;
+		Ok(len == buf.len())
+	}
+	
+	/// Reads the next packet of the logical stream, `None` at the end of the stream.
+	pub fn read_packet(&mut self) -> io::Result<Option<Packet>> {
+		loop {
+			if let Some(packet) = self.packets.pop_front() {
+				return Ok(Some(packet));
+			}
+			
+			match self.next_page()? {
+				Some((_, page)) => self.push_page(page),
+				None => return Ok(None)
+			}
+		}
+	}
+	
+	fn push_page(&mut self, page: Page) {
+		if *self.serial.get_or_insert(page.serial) != page.serial {
+			return;
+		}
+		
+		// a page was lost or the packet was not continued, the partial packet is incomplete
+		if self.sequence.is_some_and(|s| s != page.sequence) || !page.is_continued() {
+			self.partial.clear();
+			self.continued = false;
+		}
+		
+		self.sequence = Some(page.sequence.wrapping_add(1));
+		let skip = std::mem::take(&mut self.skip);
+		let last = page.segments.iter().rposition(|l| *l < 255);
+		let mut drop = page.is_continued() && !self.continued;
+		let mut pos = 0;
+		
+		for (i, &len) in page.segments.iter().enumerate() {
+			let data = &page.data[pos..pos + len as usize];
+			pos += len as usize;
+			if !drop {
+				self.partial.extend_from_slice(data);
+			}
+			
+			if len < 255 {
+				if !drop && !skip {
+					self.packets.push_back(Packet {
+						data:    std::mem::take(&mut self.partial),
+						granule: if Some(i) == last { page.granule } else { NO_GRANULE },
+						bos:     page.is_bos(),
+						eos:     page.is_eos() && Some(i) == last
+					});
+				}
+				
+				self.partial.clear();
+				drop = false;
+			}
+		}
+		
+		if let Some(&len) = page.segments.last() {
+			self.continued = !drop && len == 255;
+		}
+	}
+	
+	/// Seeks to the page at the offset, pending packets are discarded.
+	pub fn seek_page(&mut self, offset: u64) -> io::Result<()> {
+		self.reader.seek(SeekFrom::Start(offset))?;
+		self.offset = offset;
+		self.sequence = None;
+		self.packets.clear();
+		self.partial.clear();
+		self.continued = false;
+		self.skip = false;
+		Ok(())
+	}
+	
+	/// Seeks to the last page of the logical stream with a granule position at or before `granule`,
+	/// such that the next packet read is the first packet that ends after that position. Returns
+	/// the granule position of the page, if there is none the reader is positioned at the start.
+	pub fn seek_granule(&mut self, granule: u64) -> io::Result<Option<u64>> {
+		const SCAN_LEN: u64 = 2 * MAX_PAGE_SIZE as u64;
+		
+		let end = self.reader.seek(SeekFrom::End(0))?;
+		let (mut lo, mut hi) = (self.start, end);
+		let mut found = None;
+		
+		while hi - lo > SCAN_LEN {
+			let mid = lo + (hi - lo) / 2;
+			self.seek_page(mid)?;
+			match self.next_granule_page(hi)? {
+				Some((offset, g)) if g <= granule => {
+					lo = offset;
+					found = Some((offset, g));
+				}
+				_ => hi = mid
+			}
+		}
+		
+		self.seek_page(lo)?;
+		while let Some((offset, g)) = self.next_granule_page(u64::MAX)? {
+			if g > granule {
+				break;
+			}
+			found = Some((offset, g));
+		}
+		
+		match found {
+			Some((offset, g)) => {
+				self.seek_page(offset)?;
+				self.skip = true;
+				Ok(Some(g))
+			}
+			None => {
+				self.seek_page(self.start)?;
+				Ok(None)
+			}
+		}
+	}
+	
+	/// Returns the offset and granule position of the next page of the logical stream that starts
+	/// before `limit` and has a granule position.
+	fn next_granule_page(&mut self, limit: u64) -> io::Result<Option<(u64, u64)>> {
+		while let Some((offset, page)) = self.next_page()? {
+			if offset >= limit {
+				break;
+			} else if self.serial.is_none_or(|s| s == page.serial) && page.granule != NO_GRANULE {
+				return Ok(Some((offset, page.granule)));
+			}
+		}
+		Ok(None)
+	}
+	
+	/// Returns the granule position of the last page of the logical stream, the position is
+	/// restored afterwards.
+	pub fn last_granule(&mut self) -> io::Result<Option<u64>> {
+		let (position, offset) = (self.reader.stream_position()?, self.offset);
+		let end = self.reader.seek(SeekFrom::End(0))?;
+		let mut len = MAX_PAGE_SIZE as u64;
+		let mut last = None;
+		
+		loop {
+			let start = end.saturating_sub(len).max(self.start);
+			self.reader.seek(SeekFrom::Start(start))?;
+			self.offset = start;
+			while let Some((_, g)) = self.next_granule_page(u64::MAX)? {
+				last = Some(g);
+			}
+			
+			if last.is_some() || start == self.start {
+				break;
+			}
+			len *= 2;
+		}
+		
+		self.reader.seek(SeekFrom::Start(position))?;
+		self.offset = offset;
+		Ok(last)
+	}
+}
+
+/// The CRC-32 of pages, with the polynomial `0x04C11DB7`, no reflection and an initial value of 0.
+pub fn crc32(data: &[u8]) -> u32 {
+	crc32_update(0, data)
+}
+
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+	const TABLE: [u32; 256] = {
+		let mut table = [0u32; 256];
+		let mut i = 0;
+		while i < 256 {
+			let mut c = (i as u32) << 24;
+			let mut k = 0;
+			while k < 8 {
+				c = if c & 0x8000_0000 != 0 { c << 1 ^ 0x04C1_1DB7 } else { c << 1 };
+				k += 1;
+			}
+			table[i] = c;
+			i += 1;
+		}
+		table
+	};
+	
+	data.iter().fold(crc, |c, byte| TABLE[((c >> 24) as u8 ^ byte) as usize] ^ (c << 8))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	/// Splits the packets into pages of at most `max` bytes, the granule position of each packet
+	/// is its index times 10.
+	fn encode(packets: &[Vec<u8>], max: usize) -> Vec<u8> {
+		let mut out = Vec::new();
+		let mut page = Page { flags: FLAG_BOS, granule: NO_GRANULE, serial: 7, ..Page::default() };
+		for (i, packet) in packets.iter().enumerate() {
+			let mut rest = &packet[..];
+			loop {
+				let len = rest.len().min(255);
+				page.segments.push(len as u8);
+				page.data.extend_from_slice(&rest[..len]);
+				rest = &rest[len..];
+				
+				let end = len < 255;
+				if end {
+					page.granule = i as u64 * 10;
+				}
+				
+				if page.data.len() >= max || page.segments.len() == 255 || (end && i + 1 == packets.len()) {
+					if end && i + 1 == packets.len() {
+						page.flags |= FLAG_EOS;
+					}
+					
+					page.write(&mut out).unwrap();
+					page = Page {
+						flags:    if end { 0 } else { FLAG_CONTINUED },
+						granule:  NO_GRANULE,
+						serial:   7,
+						sequence: page.sequence + 1,
+						..Page::default()
+					};
+				}
+				
+				if end {
+					break;
+				}
+			}
+		}
+		out
+	}
+	
+	fn packets() -> Vec<Vec<u8>> {
+		(0..200).map(|i| (0..i * 37 % 1000).map(|j| (i * 7 + j) as u8).collect()).collect()
+	}
+	
+	fn read_all(reader: &mut OggReader<io::Cursor<Vec<u8>>>) -> Vec<Packet> {
+		std::iter::from_fn(|| reader.read_packet().unwrap()).collect()
+	}
+	
+	#[test]
+	fn checksum() {
+		assert_eq!(crc32(b"123456789"), 0x89A1_897F);
+	}
+	
+	#[test]
+	fn packets_across_pages() {
+		let packets = packets();
+		let data = encode(&packets, 700);
+		let mut reader = OggReader::new(io::Cursor::new(data)).unwrap();
+		let read = read_all(&mut reader);
+		assert_eq!(read.iter().map(|p| &p.data).collect::<Vec<_>>(), packets.iter().collect::<Vec<_>>());
+		assert!(read[0].bos && !read.last().unwrap().bos);
+		assert!(read.last().unwrap().eos);
+		assert_eq!(read.last().unwrap().granule, 1990);
+		assert_eq!(reader.serial, Some(7));
+		assert_eq!(reader.last_granule().unwrap(), Some(1990));
+	}
+	
+	#[test]
+	fn corrupt_page() {
+		let packets = packets();
+		let mut data = encode(&packets, 700);
+		// corrupt the data of the third page
+		let mut offset = 0;
+		for _ in 0..2 {
+			offset += 27 + data[offset + 26] as usize + data[offset + 27..offset + 27 + data[offset + 26] as usize]
+				.iter().map(|l| *l as usize).sum::<usize>();
+		}
+		data[offset + 40] ^= 0x55;
+		
+		let read = read_all(&mut OggReader::new(io::Cursor::new(data)).unwrap());
+		assert!(read.len() < packets.len());
+		assert!(read.iter().all(|p| packets.contains(&p.data)));
+		assert_eq!(read.last().unwrap().data, *packets.last().unwrap());
+	}
+	
+	#[test]
+	fn seek() {
+		let packets = packets();
+		let data = encode(&packets, 300);
+		let mut reader = OggReader::new(io::Cursor::new(data)).unwrap();
+		for target in [0, 5, 555, 1234, 1990, 5000] {
+			let granule = reader.seek_granule(target).unwrap();
+			let first = reader.read_packet().unwrap();
+			match granule {
+				Some(g) => {
+					assert!(g <= target);
+					// the next packet is the one after the packet that ends at `g`
+					assert_eq!(first.as_ref().map(|p| &p.data), packets.get(g as usize / 10 + 1));
+				}
+				None => assert_eq!(first.unwrap().data, packets[0])
+			}
+		}
+	}
+}
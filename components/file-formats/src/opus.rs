@@ -20,4 +20,1465 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO OPUS
\ No newline at end of file
+//! Opus decoder (RFC 6716) and Ogg Opus streams (RFC 7845).
+//!
+//! [`Decoder`] decodes SILK, CELT and hybrid packets of a mono or stereo stream to 48 kHz float
+//! samples, including packet loss concealment and forward error correction, and
+//! [`MultistreamDecoder`] combines several streams for up to 255 channels. [`OggOpusReader`]
+//! reads an Ogg Opus file, applies the pre-skip, end trimming and output gain and provides the
+//! samples as floats or through [`io::Read`] as interleaved little-endian `i16`s. Seeking is sample
+//! accurate, the decoder is run for 80 ms before the target to converge.
+
+mod range;
+mod silk;
+mod silk_tables;
+mod celt;
+mod celt_tables;
+
+use {
+	self::{range::RangeDecoder, silk::{SilkDecoder, DecControl, Lost}, celt::CeltDecoder},
+	crate::ogg::{self, OggReader, NO_GRANULE},
+	std::{collections::VecDeque, io::{self, Read, Seek, SeekFrom, Error, ErrorKind}}
+};
+
+pub const HEAD_MAGIC: [u8; 8] = *b"OpusHead";
+pub const TAGS_MAGIC: [u8; 8] = *b"OpusTags";
+
+/// The sample rate of decoded samples, Ogg Opus granule positions are always in 48 kHz samples.
+pub const SAMPLE_RATE: u32 = 48000;
+
+/// The maximum number of samples per channel in a packet, 120 ms.
+pub const MAX_PACKET_SAMPLES: usize = 5760;
+
+/// The maximum size of a frame in bytes.
+pub const MAX_FRAME_LEN: usize = 1275;
+
+/// The number of samples decoded before a seek target, to let the decoder converge.
+pub const PRE_ROLL: u64 = 3840;
+
+const F2_5: usize = 120;
+const F5:   usize = 240;
+const F10:  usize = 480;
+const F20:  usize = 960;
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+	Silk,
+	Hybrid,
+	Celt
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Default)]
+pub enum Bandwidth {
+	/// 4 kHz
+	Narrow,
+	/// 6 kHz
+	Medium,
+	/// 8 kHz
+	Wide,
+	/// 12 kHz
+	SuperWide,
+	/// 20 kHz
+	#[default]
+ Full
+}
+
+
+/// The table of contents byte at the start of each packet.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Toc {
+	pub mode:       Mode,
+	pub bandwidth:  Bandwidth,
+	/// The number of samples per channel of each frame.
+	pub frame_size: usize,
+	pub stereo:     bool,
+	/// The frame count code, 0 for one frame, 1 and 2 for two frames of equal or different size and
+	/// 3 for an arbitrary number of frames.
+	pub code:       u8
+}
+
+impl Toc {
+	pub fn parse(toc: u8) -> Self {
+		let config = toc >> 3;
+		let (mode, bandwidth, frame_size) = match config {
+			0..=11  => (Mode::Silk, [Bandwidth::Narrow, Bandwidth::Medium, Bandwidth::Wide][config as usize / 4], [480, 960, 1920, 2880][config as usize % 4]),
+			12..=15 => (Mode::Hybrid, [Bandwidth::SuperWide, Bandwidth::Full][config as usize / 2 - 6], 480 << (config & 1)),
+			_       => (Mode::Celt, [Bandwidth::Narrow, Bandwidth::Wide, Bandwidth::SuperWide, Bandwidth::Full][config as usize / 4 - 4], 120 << (config & 3))
+		};
+		
+		Self { mode, bandwidth, frame_size, stereo: toc & 0x4 != 0, code: toc & 0x3 }
+	}
+}
+
+/// A packet split into frames.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Packet<'a> {
+	pub toc:    Toc,
+	pub frames: Vec<&'a [u8]>,
+	/// The size of the packet including padding, which differs from the length of the data for
+	/// self-delimited packets.
+	pub len:    usize
+}
+
+impl<'a> Packet<'a> {
+	/// Parses a packet, self-delimited packets as used by multistream packets for all but the last
+	/// stream have the size of the last frame encoded explicitly.
+	pub fn parse(data: &'a [u8], self_delimited: bool) -> io::Result<Self> {
+		fn parse_size(data: &[u8]) -> Option<(usize, usize)> {
+			match *data {
+				[] => None,
+				[b, ..] if b < 252 => Some((b as usize, 1)),
+				[_] => None,
+				[b0, b1, ..] => Some((4 * b1 as usize + b0 as usize, 2))
+			}
+		}
+		
+		let err = || invalid_data("invalid Opus packet");
+		let packet = data;
+		let (&toc, mut data) = data.split_first().ok_or_else(err)?;
+		let toc = Toc::parse(toc);
+		let mut sizes = [0usize; 48];
+		let mut last_size = data.len();
+		let mut cbr = false;
+		
+		let count = match toc.code {
+			0 => 1,
+			1 => {
+				cbr = true;
+				if !self_delimited {
+					if data.len() % 2 != 0 {
+						return Err(err());
+					}
+					last_size = data.len() / 2;
+					sizes[0] = last_size;
+				}
+				2
+			}
+			2 => {
+				let (size, bytes) = parse_size(data).ok_or_else(err)?;
+				data = &data[bytes..];
+				if size > data.len() {
+					return Err(err());
+				}
+				sizes[0] = size;
+				last_size = data.len() - size;
+				2
+			}
+			_ => {
+				let (&ch, rest) = data.split_first().ok_or_else(err)?;
+				data = rest;
+				let count = (ch & 0x3F) as usize;
+				if count == 0 || toc.frame_size * count > MAX_PACKET_SAMPLES {
+					return Err(err());
+				}
+				
+				if ch & 0x40 != 0 {
+					loop {
+						let (&p, rest) = data.split_first().ok_or_else(err)?;
+						data = rest;
+						let len = if p == 255 { 254 } else { p as usize };
+						if len > data.len() {
+							return Err(err());
+						}
+						data = &data[..data.len() - len];
+						if p != 255 {
+							break;
+						}
+					}
+				}
+				
+				cbr = ch & 0x80 == 0;
+				if !cbr {
+					let mut last = data.len() as isize;
+					for size in &mut sizes[..count - 1] {
+						let (s, bytes) = parse_size(data).ok_or_else(err)?;
+						data = &data[bytes..];
+						if s > data.len() {
+							return Err(err());
+						}
+						*size = s;
+						last -= (bytes + s) as isize;
+					}
+					
+					if last < 0 {
+						return Err(err());
+					}
+					last_size = last as usize;
+				} else if !self_delimited {
+					last_size = data.len() / count;
+					if last_size * count != data.len() {
+						return Err(err());
+					}
+					sizes[..count - 1].fill(last_size);
+				}
+				count
+			}
+		};
+		
+		if self_delimited {
+			let (size, bytes) = parse_size(data).ok_or_else(err)?;
+			data = &data[bytes..];
+			if size > data.len() {
+				return Err(err());
+			}
+			
+			if cbr {
+				if size * count > data.len() {
+					return Err(err());
+				}
+				sizes[..count - 1].fill(size);
+			} else if bytes + size > last_size {
+				return Err(err());
+			}
+			sizes[count - 1] = size;
+		} else {
+			if last_size > MAX_FRAME_LEN {
+				return Err(err());
+			}
+			sizes[count - 1] = last_size;
+		}
+		
+		let mut frames = Vec::with_capacity(count);
+		for &size in &sizes[..count] {
+			let (frame, rest) = data.split_at(size);
+			frames.push(frame);
+			data = rest;
+		}
+		
+		Ok(Self { toc, frames, len: packet.len() - data.len() })
+	}
+	
+	/// Returns the number of samples per channel.
+	pub fn samples(&self) -> usize {
+		self.frames.len() * self.toc.frame_size
+	}
+}
+
+/// Decodes the packets of a mono or stereo stream.
+pub struct Decoder {
+	channels:             usize,
+	silk:                 SilkDecoder,
+	celt:                 CeltDecoder,
+	silk_ctrl:            DecControl,
+	silk_pcm:             Vec<i16>,
+	/// The parameters of the current packet.
+	mode:                 Option<Mode>,
+	bandwidth:            Bandwidth,
+	frame_size:           usize,
+	stream_channels:      usize,
+	/// The mode of the previous frame, `None` if nothing was decoded yet.
+	prev_mode:            Option<Mode>,
+	prev_redundancy:      bool,
+	last_packet_duration: usize,
+	range_final:          u32
+}
+
+impl Decoder {
+	/// Creates a decoder with one or two output channels.
+	pub fn new(channels: usize) -> Self {
+		assert!(channels == 1 || channels == 2, "invalid number of channels");
+		Self {
+			channels,
+			silk:                 SilkDecoder::new(),
+			celt:                 CeltDecoder::new(channels),
+			silk_ctrl:            DecControl {
+				channels_api:         channels,
+				channels_internal:    channels,
+				internal_sample_rate: 16000,
+				payload_size_ms:      20
+			},
+			silk_pcm:             Vec::new(),
+			mode:                 None,
+			bandwidth:            Bandwidth::default(),
+			frame_size:           F20,
+			stream_channels:      channels,
+			prev_mode:            None,
+			prev_redundancy:      false,
+			last_packet_duration: 0,
+			range_final:          0
+		}
+	}
+	
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+	
+	/// Resets the decoder state, e.g. after seeking.
+	pub fn reset(&mut self) {
+		self.silk.reset();
+		self.celt.reset();
+		self.mode = None;
+		self.bandwidth = Bandwidth::default();
+		self.frame_size = F20;
+		self.stream_channels = self.channels;
+		self.prev_mode = None;
+		self.prev_redundancy = false;
+		self.last_packet_duration = 0;
+		self.range_final = 0;
+	}
+	
+	/// Returns the final state of the range decoder of the last packet, which the encoder reports
+	/// to verify a decoded stream.
+	pub fn final_range(&self) -> u32 {
+		self.range_final
+	}
+	
+	/// Returns the number of samples per channel of the last packet.
+	pub fn last_packet_duration(&self) -> usize {
+		self.last_packet_duration
+	}
+	
+	/// Decodes a packet and writes the interleaved samples to `pcm`, which must be large enough for
+	/// the packet. If `data` is `None`, the packet was lost and `pcm.len() / channels` samples are
+	/// concealed, a multiple of 2.5 ms. If `fec` is set, the lost packet before `data` is recovered
+	/// from its forward error correction data, or concealed if there is none. Returns the number of
+	/// samples per channel.
+	pub fn decode(&mut self, data: Option<&[u8]>, pcm: &mut [f32], fec: bool) -> io::Result<usize> {
+		let frame_size = pcm.len() / self.channels;
+		self.decode_native(data, pcm, frame_size, fec, false).map(|(n, _)| n)
+	}
+	
+	/// Returns the number of samples per channel and the size of the packet.
+	fn decode_native(
+		&mut self,
+		data:           Option<&[u8]>,
+		pcm:            &mut [f32],
+		frame_size:     usize,
+		fec:            bool,
+		self_delimited: bool
+	) -> io::Result<(usize, usize)> {
+		let ch = self.channels;
+		let data = match data {
+			Some(data) if !data.is_empty() => data,
+			_ => {
+				if !frame_size.is_multiple_of(F2_5) {
+					return Err(Error::new(ErrorKind::InvalidInput, "the number of samples to conceal must be a multiple of 2.5 ms"));
+				}
+				
+				let mut n = 0;
+				while n < frame_size {
+					n += self.decode_frame(None, &mut pcm[n * ch..], frame_size - n, false)?;
+				}
+				self.last_packet_duration = n;
+				return Ok((n, 0));
+			}
+		};
+		
+		let packet = Packet::parse(data, self_delimited)?;
+		let toc = packet.toc;
+		if fec {
+			if !frame_size.is_multiple_of(F2_5) {
+				return Err(Error::new(ErrorKind::InvalidInput, "the number of samples to recover must be a multiple of 2.5 ms"));
+			}
+			
+			// only SILK carries FEC data
+			if frame_size < toc.frame_size || toc.mode == Mode::Celt || self.mode == Some(Mode::Celt) {
+				return self.decode_native(None, pcm, frame_size, false, false).map(|(n, _)| (n, packet.len));
+			}
+			
+			let duration = self.last_packet_duration;
+			if frame_size > toc.frame_size {
+				if let Err(e) = self.decode_native(None, pcm, frame_size - toc.frame_size, false, false) {
+					self.last_packet_duration = duration;
+					return Err(e);
+				}
+			}
+			
+			self.set_toc(toc);
+			self.decode_frame(Some(packet.frames[0]), &mut pcm[ch * (frame_size - toc.frame_size)..], toc.frame_size, true)?;
+			self.last_packet_duration = frame_size;
+			return Ok((frame_size, packet.len));
+		}
+		
+		if packet.samples() > frame_size || packet.samples() * ch > pcm.len() {
+			return Err(Error::new(ErrorKind::InvalidInput, "the buffer is too small for the packet"));
+		}
+		
+		self.set_toc(toc);
+		let mut n = 0;
+		for frame in &packet.frames {
+			n += self.decode_frame(Some(frame), &mut pcm[n * ch..], frame_size - n, false)?;
+		}
+		self.last_packet_duration = n;
+		Ok((n, packet.len))
+	}
+	
+	fn set_toc(&mut self, toc: Toc) {
+		self.mode = Some(toc.mode);
+		self.bandwidth = toc.bandwidth;
+		self.frame_size = toc.frame_size;
+		self.stream_channels = if toc.stereo { 2 } else { 1 };
+	}
+	
+	/// Decodes one frame, or conceals up to `frame_size` samples if `data` is `None`. Returns the
+	/// number of samples per channel.
+	fn decode_frame(&mut self, data: Option<&[u8]>, pcm: &mut [f32], frame_size: usize, fec: bool) -> io::Result<usize> {
+		let ch = self.channels;
+		let mut frame_size = frame_size.min(MAX_PACKET_SAMPLES);
+		// frames of one byte or less are lost, e.g. discontinuous transmission
+		let data = data.filter(|data| data.len() > 1);
+		if data.is_none() {
+			frame_size = frame_size.min(self.frame_size);
+		}
+		
+		let (mode, audiosize) = match data {
+			Some(_) => (self.mode.unwrap(), self.frame_size),
+			None => {
+				let Some(mode) = self.prev_mode else {
+					pcm[..frame_size * ch].fill(0.0);
+					return Ok(frame_size);
+				};
+				
+				// conceal in chunks that are valid frame sizes
+				if frame_size > F20 {
+					let mut n = 0;
+					while n < frame_size {
+						n += self.decode_frame(None, &mut pcm[n * ch..], (frame_size - n).min(F20), false)?;
+					}
+					return Ok(frame_size);
+				}
+				
+				let mut audiosize = frame_size;
+				if audiosize < F20 {
+					if audiosize > F10 {
+						audiosize = F10;
+					} else if mode != Mode::Silk && audiosize > F5 && audiosize < F10 {
+						audiosize = F5;
+					}
+				}
+				(mode, audiosize)
+			}
+		};
+		
+		if audiosize > frame_size {
+			return Err(Error::new(ErrorKind::InvalidInput, "the buffer is too small for the frame"));
+		}
+		
+		let mut dec = RangeDecoder::new(data.unwrap_or(&[]));
+		let mut len = data.map_or(0, |data| data.len());
+		
+		// fade from the concealed previous mode into the new one
+		let mut transition = data.is_some() && self.prev_mode.is_some_and(|prev| (mode == Mode::Celt && prev != Mode::Celt && !self.prev_redundancy)
+			|| (mode != Mode::Celt && prev == Mode::Celt));
+		let mut pcm_transition = [0f32; F5 * 2];
+		if transition && mode == Mode::Celt {
+			self.decode_frame(None, &mut pcm_transition, F5.min(audiosize), false)?;
+		}
+		
+		let frame_size = audiosize;
+		let mut silk_pcm = std::mem::take(&mut self.silk_pcm);
+		if mode != Mode::Celt {
+			silk_pcm.clear();
+			silk_pcm.resize(frame_size.max(F10) * ch, 0);
+			if self.prev_mode == Some(Mode::Celt) {
+				self.silk.reset();
+			}
+			
+			self.silk_ctrl.payload_size_ms = (audiosize as u32 * 1000 / SAMPLE_RATE).max(10);
+			if data.is_some() {
+				self.silk_ctrl.channels_internal = self.stream_channels;
+				self.silk_ctrl.internal_sample_rate = match (mode, self.bandwidth) {
+					(Mode::Silk, Bandwidth::Narrow) => 8000,
+					(Mode::Silk, Bandwidth::Medium) => 12000,
+					_                               => 16000
+				};
+			}
+			
+			let lost = match (data, fec) {
+				(None, _)        => Lost::Yes,
+				(Some(_), true)  => Lost::Lbrr,
+				(Some(_), false) => Lost::No
+			};
+			
+			let mut n = 0;
+			while n < frame_size {
+				n += match self.silk.decode(&self.silk_ctrl, lost, n == 0, &mut dec, &mut silk_pcm[n * ch..]) {
+					Some(len) => len,
+					None if lost != Lost::No => {
+						silk_pcm[n * ch..frame_size * ch].fill(0);
+						frame_size - n
+					}
+					None => {
+						self.silk_pcm = silk_pcm;
+						return Err(invalid_data("invalid SILK frame"));
+					}
+				};
+			}
+		}
+		
+		let mut redundancy = false;
+		let mut redundancy_bytes = 0;
+		let mut celt_to_silk = false;
+		if !fec && mode != Mode::Celt && data.is_some()
+			&& dec.tell() + 17 + if mode == Mode::Hybrid { 20 } else { 0 } <= 8 * len as i32 {
+			redundancy = mode != Mode::Hybrid || dec.bit_logp(12);
+			if redundancy {
+				celt_to_silk = dec.bit_logp(1);
+				redundancy_bytes = match mode {
+					Mode::Hybrid => dec.uint(256) as usize + 2,
+					_            => len - ((dec.tell() + 7) >> 3) as usize
+				};
+				
+				if redundancy_bytes > len || (len - redundancy_bytes) * 8 < dec.tell() as usize {
+					len = 0;
+					redundancy_bytes = 0;
+					redundancy = false;
+				} else {
+					len -= redundancy_bytes;
+				}
+				dec.shrink(dec.storage() - redundancy_bytes);
+			}
+		}
+		
+		let start_band = if mode != Mode::Celt { 17 } else { 0 };
+		if redundancy {
+			transition = false;
+		}
+		
+		if transition && mode != Mode::Celt {
+			self.decode_frame(None, &mut pcm_transition, F5.min(audiosize), false)?;
+		}
+		
+		if data.is_some() {
+			self.celt.end = match self.bandwidth {
+				Bandwidth::Narrow                  => 13,
+				Bandwidth::Medium | Bandwidth::Wide => 17,
+				Bandwidth::SuperWide               => 19,
+				Bandwidth::Full                    => 21
+			};
+			self.celt.stream_channels = self.stream_channels;
+		}
+		
+		let redundant_data = data.map_or(&[][..], |data| &data[len..len + redundancy_bytes]);
+		let mut redundant_audio = [0f32; F5 * 2];
+		let mut redundant_rng = 0;
+		if redundancy && celt_to_silk {
+			self.celt.start = 0;
+			self.celt.decode(Some(&mut RangeDecoder::new(redundant_data)), &mut redundant_audio, F5);
+			redundant_rng = self.celt.final_range();
+		}
+		
+		self.celt.start = start_band;
+		if mode != Mode::Silk {
+			if Some(mode) != self.prev_mode && self.prev_mode.is_some() && !self.prev_redundancy {
+				self.celt.reset();
+			}
+			
+			let dec = match fec || data.is_none() || len <= 1 {
+				true  => None,
+				false => Some(&mut dec)
+			};
+			self.celt.decode(dec, pcm, F20.min(frame_size));
+		} else {
+			pcm[..frame_size * ch].fill(0.0);
+			// let the CELT part of a previous hybrid frame decay
+			if self.prev_mode == Some(Mode::Hybrid) && !(redundancy && celt_to_silk && self.prev_redundancy) {
+				self.celt.start = 0;
+				self.celt.decode(Some(&mut RangeDecoder::new(&[0xFF, 0xFF])), pcm, F2_5);
+			}
+		}
+		
+		if mode != Mode::Celt {
+			for (out, &sample) in pcm.iter_mut().zip(&silk_pcm[..frame_size * ch]) {
+				*out += sample as f32 * (1.0 / 32768.0);
+			}
+		}
+		self.silk_pcm = silk_pcm;
+		
+		let window = *self.celt.window();
+		if redundancy && !celt_to_silk {
+			self.celt.reset();
+			self.celt.start = 0;
+			self.celt.decode(Some(&mut RangeDecoder::new(redundant_data)), &mut redundant_audio, F5);
+			redundant_rng = self.celt.final_range();
+			
+			let tail = ch * (frame_size - F2_5)..ch * frame_size;
+			let mut faded = [0f32; F2_5 * 2];
+			smooth_fade(&pcm[tail.clone()], &redundant_audio[ch * F2_5..], &mut faded, ch, &window);
+			pcm[tail].copy_from_slice(&faded[..ch * F2_5]);
+		}
+		
+		if redundancy && celt_to_silk {
+			pcm[..ch * F2_5].copy_from_slice(&redundant_audio[..ch * F2_5]);
+			let mut faded = [0f32; F2_5 * 2];
+			smooth_fade(&redundant_audio[ch * F2_5..], &pcm[ch * F2_5..], &mut faded, ch, &window);
+			pcm[ch * F2_5..ch * F5].copy_from_slice(&faded[..ch * F2_5]);
+		}
+		
+		if transition {
+			let mut faded = [0f32; F2_5 * 2];
+			if audiosize >= F5 {
+				pcm[..ch * F2_5].copy_from_slice(&pcm_transition[..ch * F2_5]);
+				smooth_fade(&pcm_transition[ch * F2_5..], &pcm[ch * F2_5..], &mut faded, ch, &window);
+				pcm[ch * F2_5..ch * F5].copy_from_slice(&faded[..ch * F2_5]);
+			} else {
+				smooth_fade(&pcm_transition, &pcm[..], &mut faded, ch, &window);
+				pcm[..ch * F2_5].copy_from_slice(&faded[..ch * F2_5]);
+			}
+		}
+		
+		self.range_final = if len <= 1 { 0 } else { dec.range() ^ redundant_rng };
+		self.prev_mode = Some(mode);
+		self.prev_redundancy = redundancy && !celt_to_silk;
+		Ok(audiosize)
+	}
+}
+
+/// Crossfades 2.5 ms from `a` to `b`, using the squared CELT window.
+fn smooth_fade(a: &[f32], b: &[f32], out: &mut [f32], channels: usize, window: &[f32]) {
+	for (i, w) in window[..F2_5].iter().enumerate() {
+		let w = w * w;
+		for c in 0..channels {
+			let j = i * channels + c;
+			out[j] = w * b[j] + (1.0 - w) * a[j];
+		}
+	}
+}
+
+/// Decodes multistream packets, which contain several mono or stereo streams that are mapped to
+/// the output channels.
+pub struct MultistreamDecoder {
+	channels: usize,
+	coupled:  usize,
+	/// The stream channel of each output channel, streams are numbered with the coupled streams
+	/// first, where each contributes two channels. 255 is a silent channel.
+	mapping:  Vec<u8>,
+	decoders: Vec<Decoder>,
+	buf:      Vec<f32>
+}
+
+impl MultistreamDecoder {
+	pub fn new(streams: usize, coupled: usize, mapping: &[u8]) -> io::Result<Self> {
+		if streams == 0 || coupled > streams || streams + coupled > 255 || mapping.is_empty()
+			|| mapping.iter().any(|&m| m != 255 && m as usize >= streams + coupled) {
+			return Err(Error::new(ErrorKind::InvalidInput, "invalid channel mapping"));
+		}
+		
+		Ok(Self {
+			channels: mapping.len(),
+			coupled,
+			mapping:  mapping.to_vec(),
+			decoders: (0..streams).map(|s| Decoder::new(if s < coupled { 2 } else { 1 })).collect(),
+			buf:      Vec::new()
+		})
+	}
+	
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+	
+	pub fn reset(&mut self) {
+		self.decoders.iter_mut().for_each(Decoder::reset);
+	}
+	
+	/// Decodes a packet, see [`Decoder::decode`].
+	pub fn decode(&mut self, data: Option<&[u8]>, pcm: &mut [f32], fec: bool) -> io::Result<usize> {
+		let frame_size = pcm.len() / self.channels;
+		let streams = self.decoders.len();
+		let mut data = data.filter(|data| !data.is_empty());
+		let mut samples = None;
+		self.buf.resize(frame_size * 2, 0.0);
+		pcm.fill(0.0);
+		
+		for (s, decoder) in self.decoders.iter_mut().enumerate() {
+			let last = s + 1 == streams;
+			if data.is_some_and(|data| data.is_empty()) {
+				return Err(invalid_data("invalid Opus multistream packet"));
+			}
+			
+			let dch = decoder.channels;
+			let (n, len) = decoder.decode_native(data, &mut self.buf[..frame_size * dch], frame_size, fec, !last)?;
+			if samples.is_some_and(|samples| samples != n) {
+				return Err(invalid_data("the streams of the packet have different durations"));
+			}
+			samples = Some(n);
+			data = data.map(|data| &data[len.min(data.len())..]);
+			
+			for (c, &m) in self.mapping.iter().enumerate() {
+				let m = m as usize;
+				let source = match m {
+					255                        => None,
+					m if m < 2 * self.coupled  => (m / 2 == s).then_some(m % 2),
+					m                          => (m - self.coupled == s).then_some(0)
+				};
+				
+				if let Some(source) = source {
+					for i in 0..n {
+						pcm[i * self.channels + c] = self.buf[i * dch + source];
+					}
+				}
+			}
+		}
+		Ok(samples.unwrap_or(0))
+	}
+}
+
+/// Applies a soft clipping to samples that exceed ±1 without introducing discontinuities, which
+/// is preferable to hard clipping when converting to integers. `mem` holds one value per channel
+/// to continue the clipping across calls and is initially zero.
+pub fn soft_clip(pcm: &mut [f32], channels: usize, mem: &mut [f32]) {
+	let n = pcm.len() / channels;
+	if n == 0 {
+		return;
+	}
+	
+	pcm.iter_mut().for_each(|x| *x = x.clamp(-2.0, 2.0));
+	for (c, mem) in mem[..channels].iter_mut().enumerate() {
+		let x = |i: usize| i * channels + c;
+		let mut a = *mem;
+		
+		// continue the curve of the previous call until the next zero crossing
+		for i in 0..n {
+			let v = pcm[x(i)];
+			if v * a >= 0.0 {
+				break;
+			}
+			pcm[x(i)] = v + a * v * v;
+		}
+		
+		let mut curr = 0;
+		let x0 = pcm[x(0)];
+		loop {
+			let Some(i) = (curr..n).find(|&i| pcm[x(i)].abs() > 1.0) else {
+				a = 0.0;
+				break;
+			};
+			
+			// the segment around the peak between zero crossings
+			let v = pcm[x(i)];
+			let (mut start, mut end) = (i, i);
+			let mut max = v.abs();
+			let mut peak = i;
+			while start > 0 && v * pcm[x(start - 1)] >= 0.0 {
+				start -= 1;
+			}
+			
+			while end < n && v * pcm[x(end)] >= 0.0 {
+				if pcm[x(end)].abs() > max {
+					max = pcm[x(end)].abs();
+					peak = end;
+				}
+				end += 1;
+			}
+			
+			let special = start == 0 && v * pcm[x(0)] >= 0.0;
+			a = (max - 1.0) / (max * max);
+			a += a * 2.4e-7;
+			if v > 0.0 {
+				a = -a;
+			}
+			
+			for i in start..end {
+				let v = pcm[x(i)];
+				pcm[x(i)] = v + a * v * v;
+			}
+			
+			// avoid a discontinuity at the start, if the segment started in the previous call
+			if special && peak >= 2 {
+				let mut offset = x0 - pcm[x(0)];
+				let delta = offset / peak as f32;
+				for i in curr..peak {
+					offset -= delta;
+					pcm[x(i)] = (pcm[x(i)] + offset).clamp(-1.0, 1.0);
+				}
+			}
+			
+			curr = end;
+			if curr == n {
+				break;
+			}
+		}
+		*mem = a;
+	}
+}
+
+/// The identification header of an Ogg Opus stream.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpusHead {
+	pub version:           u8,
+	pub channels:          u8,
+	/// The number of samples to discard at the start of the stream.
+	pub pre_skip:          u16,
+	/// The sample rate of the input, only informational as the stream is always decoded at 48 kHz.
+	pub input_sample_rate: u32,
+	/// The gain to apply to the output in dB, in Q7.8.
+	pub output_gain:       i16,
+	pub mapping_family:    u8,
+	pub streams:           u8,
+	pub coupled_streams:   u8,
+	/// The stream channel of each output channel, see [`MultistreamDecoder`].
+	pub mapping:           Vec<u8>
+}
+
+impl OpusHead {
+	pub fn parse(data: &[u8]) -> io::Result<Self> {
+		if data.len() < 19 || data[..8] != HEAD_MAGIC {
+			return Err(invalid_data("not an OpusHead packet"));
+		} else if data[8] >> 4 != 0 {
+			return Err(Error::new(ErrorKind::Unsupported, "unsupported Ogg Opus version"));
+		}
+		
+		let channels = data[9];
+		let mapping_family = data[18];
+		let (streams, coupled_streams, mapping) = match mapping_family {
+			0 if (1..=2).contains(&channels) => (1, channels - 1, (0..channels).collect()),
+			0 => return Err(invalid_data("mapping family 0 only allows one or two channels")),
+			_ => {
+				if channels == 0 || data.len() < 21 + channels as usize {
+					return Err(invalid_data("OpusHead packet is too short"));
+				}
+				
+				let (streams, coupled) = (data[19], data[20]);
+				let mapping = data[21..21 + channels as usize].to_vec();
+				if streams == 0 || coupled > streams || streams as usize + coupled as usize > 255
+					|| mapping.iter().any(|&m| m != 255 && m as usize >= streams as usize + coupled as usize) {
+					return Err(invalid_data("invalid channel mapping"));
+				}
+				(streams, coupled, mapping)
+			}
+		};
+		
+		Ok(Self {
+			version:           data[8],
+			channels,
+			pre_skip:          u16::from_le_bytes([data[10], data[11]]),
+			input_sample_rate: u32::from_le_bytes(data[12..16].try_into().unwrap()),
+			output_gain:       i16::from_le_bytes([data[16], data[17]]),
+			mapping_family,
+			streams,
+			coupled_streams,
+			mapping
+		})
+	}
+}
+
+/// The comment header of an Ogg Opus stream, in the same format as Vorbis comments.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpusTags {
+	pub vendor:   String,
+	/// The comments as field name and value, names are case-insensitive and may appear more than once.
+	pub comments: Vec<(String, String)>
+}
+
+impl OpusTags {
+	pub fn parse(data: &[u8]) -> io::Result<Self> {
+		fn string(data: &mut &[u8]) -> io::Result<String> {
+			let len = u32_le(data)? as usize;
+			if len > data.len() {
+				return Err(invalid_data("OpusTags string is too long"));
+			}
+			
+			let (s, rest) = data.split_at(len);
+			*data = rest;
+			Ok(String::from_utf8_lossy(s).into_owned())
+		}
+		
+		fn u32_le(data: &mut &[u8]) -> io::Result<u32> {
+			let mut buf = [0u8; 4];
+			data.read_exact(&mut buf)?;
+			Ok(u32::from_le_bytes(buf))
+		}
+		
+		let mut data = data.strip_prefix(&TAGS_MAGIC).ok_or_else(|| invalid_data("not an OpusTags packet"))?;
+		let vendor = string(&mut data)?;
+		let mut comments = Vec::new();
+		for _ in 0..u32_le(&mut data)? {
+			let comment = string(&mut data)?;
+			comments.push(match comment.split_once('=') {
+				Some((name, value)) => (name.to_string(), value.to_string()),
+				None => (comment, String::new())
+			});
+		}
+		
+		Ok(Self { vendor, comments })
+	}
+	
+	/// Returns the values of all comments with the given field name.
+	pub fn get<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+		self.comments.iter()
+			.filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+			.map(|(_, v)| v.as_str())
+	}
+}
+
+/// Reads an Ogg Opus stream. Sample 0 is the first sample after the pre-skip.
+pub struct OggOpusReader<R> {
+	ogg:               OggReader<R>,
+	pub head:          OpusHead,
+	pub tags:          OpusTags,
+	/// The number of samples per channel, 0 if unknown.
+	pub total_samples: u64,
+	decoder:           MultistreamDecoder,
+	/// The linear output gain.
+	gain:              f32,
+	/// The offset of the first audio page.
+	data_offset:       u64,
+	/// The granule position of the first decoded sample, which is not 0 for streams that were cut.
+	start_granule:     u64,
+	/// Packets that were read ahead to find the start granule position.
+	pending:           VecDeque<ogg::Packet>,
+	/// The granule position of the first sample of the next packet.
+	granule:           u64,
+	/// Samples before this granule position are discarded.
+	skip_until:        u64,
+	buf:               Vec<f32>,
+	/// The decoded samples of the last packet.
+	pcm:               Vec<f32>,
+	/// The granule position of the first sample in `pcm`.
+	pcm_granule:       u64,
+	/// The index of the next interleaved sample in `pcm`.
+	index:             usize,
+	/// The next byte of the sample at `index`, for partial reads.
+	byte:              usize,
+	clip_mem:          Vec<f32>
+}
+
+impl<R: Read + Seek> OggOpusReader<R> {
+	pub fn new(reader: R) -> io::Result<Self> {
+		let mut ogg = OggReader::new(reader)?;
+		let start = ogg.position();
+		
+		// find the Opus stream among the first pages of a multiplexed file
+		loop {
+			match ogg.read_page()? {
+				Some(page) if page.is_bos() && page.data.starts_with(&HEAD_MAGIC) => {
+					ogg.serial = Some(page.serial);
+					break;
+				}
+				Some(page) if page.is_bos() => (),
+				_ => return Err(invalid_data("not an Ogg Opus stream"))
+			}
+		}
+		
+		ogg.seek_page(start)?;
+		let head = OpusHead::parse(&ogg.read_packet()?.ok_or(ErrorKind::UnexpectedEof)?.data)?;
+		let tags = OpusTags::parse(&ogg.read_packet()?.ok_or(ErrorKind::UnexpectedEof)?.data)?;
+		let data_offset = ogg.position();
+		let last_granule = ogg.last_granule()?;
+		let decoder = MultistreamDecoder::new(head.streams as usize, head.coupled_streams as usize, &head.mapping)?;
+		
+		// the granule position of the first page minus the duration of its packets
+		let mut pending = VecDeque::new();
+		let mut duration = 0u64;
+		let start_granule = loop {
+			let Some(packet) = ogg.read_packet()? else {
+				break 0;
+			};
+			
+			duration += Packet::parse(&packet.data, head.streams > 1).map_or(0, |p| p.samples()) as u64;
+			let (granule, eos) = (packet.granule, packet.eos);
+			pending.push_back(packet);
+			match granule {
+				NO_GRANULE => (),
+				// the last packet may be trimmed, so the start can not be computed
+				_ if eos => break 0,
+				granule => break granule.saturating_sub(duration)
+			}
+		};
+		
+		let skip_until = start_granule + head.pre_skip as u64;
+		let channels = head.channels as usize;
+		Ok(Self {
+			ogg,
+			total_samples: last_granule.map_or(0, |g| g.saturating_sub(skip_until)),
+			decoder,
+			gain:          10f32.powf(head.output_gain as f32 / (20.0 * 256.0)),
+			data_offset,
+			start_granule,
+			pending,
+			granule:       start_granule,
+			skip_until,
+			buf:           vec![0.0; MAX_PACKET_SAMPLES * channels],
+			pcm:           Vec::new(),
+			pcm_granule:   skip_until,
+			index:         0,
+			byte:          0,
+			clip_mem:      vec![0.0; channels],
+			head,
+			tags
+		})
+	}
+	
+	pub fn channels(&self) -> usize {
+		self.head.channels as usize
+	}
+	
+	/// Returns the number of the next sample per channel.
+	pub fn position(&self) -> u64 {
+		(self.pcm_granule + (self.index / self.channels()) as u64).saturating_sub(self.start_granule + self.head.pre_skip as u64)
+	}
+	
+	/// Decodes packets until there are samples left, returns false at the end of the stream.
+	fn fill(&mut self) -> io::Result<bool> {
+		let channels = self.channels();
+		while self.index >= self.pcm.len() {
+			let packet = match self.pending.pop_front() {
+				Some(packet) => packet,
+				None => match self.ogg.read_packet()? {
+					Some(packet) => packet,
+					None => return Ok(false)
+				}
+			};
+			
+			let n = self.decoder.decode(Some(&packet.data), &mut self.buf, false)?;
+			let mut first = self.granule;
+			let mut end = n;
+			self.granule += n as u64;
+			if packet.granule != NO_GRANULE {
+				if packet.eos {
+					// the last packet is trimmed to the granule position of the page
+					end = n.saturating_sub(self.granule.saturating_sub(packet.granule) as usize);
+				} else {
+					// resynchronize, e.g. after a lost page
+					first = packet.granule.saturating_sub(n as u64);
+				}
+				self.granule = packet.granule;
+			}
+			
+			let pcm = &mut self.buf[..n * channels];
+			if self.gain != 1.0 {
+				pcm.iter_mut().for_each(|s| *s *= self.gain);
+			}
+			soft_clip(pcm, channels, &mut self.clip_mem);
+			
+			let begin = (self.skip_until.saturating_sub(first) as usize).min(end);
+			self.pcm.clear();
+			self.pcm.extend_from_slice(&self.buf[begin * channels..end * channels]);
+			self.pcm_granule = first + begin as u64;
+			self.index = 0;
+		}
+		Ok(true)
+	}
+	
+	/// Reads interleaved samples, returns the number of samples read.
+	pub fn read_samples(&mut self, buf: &mut [f32]) -> io::Result<usize> {
+		if self.byte != 0 {
+			self.index += 1;
+			self.byte = 0;
+		}
+		
+		let mut len = 0;
+		while len < buf.len() && self.fill()? {
+			let n = (buf.len() - len).min(self.pcm.len() - self.index);
+			buf[len..len + n].copy_from_slice(&self.pcm[self.index..self.index + n]);
+			self.index += n;
+			len += n;
+		}
+		Ok(len)
+	}
+	
+	/// Seeks to a sample, the stream is decoded from up to 80 ms before it.
+	pub fn seek_sample(&mut self, sample: u64) -> io::Result<()> {
+		let total = self.total_samples;
+		if total != 0 && sample > total {
+			return Err(Error::new(ErrorKind::InvalidInput, "seek beyond the end of the stream"));
+		}
+		
+		let channels = self.channels();
+		let target = sample + self.start_granule + self.head.pre_skip as u64;
+		if target >= self.pcm_granule && target < self.pcm_granule + (self.pcm.len() / channels) as u64 {
+			// within the current packet
+			self.index = (target - self.pcm_granule) as usize * channels;
+			self.byte = 0;
+			return Ok(());
+		}
+		
+		self.pending.clear();
+		match self.ogg.seek_granule(target.saturating_sub(PRE_ROLL))? {
+			Some(granule) if granule >= self.start_granule => self.granule = granule,
+			_ => {
+				self.ogg.seek_page(self.data_offset)?;
+				self.granule = self.start_granule;
+			}
+		}
+		
+		self.decoder.reset();
+		self.clip_mem.fill(0.0);
+		self.skip_until = target;
+		self.pcm.clear();
+		self.pcm_granule = target;
+		self.index = 0;
+		self.byte = 0;
+		self.fill()?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> Read for OggOpusReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let mut len = 0;
+		while len < buf.len() && self.fill()? {
+			for &sample in &self.pcm[self.index..] {
+				let bytes = ((sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16).to_le_bytes();
+				let n = (2 - self.byte).min(buf.len() - len);
+				buf[len..len + n].copy_from_slice(&bytes[self.byte..self.byte + n]);
+				len += n;
+				self.byte += n;
+				
+				if self.byte < 2 {
+					break;
+				}
+				
+				self.byte = 0;
+				self.index += 1;
+				if len == buf.len() {
+					break;
+				}
+			}
+		}
+		Ok(len)
+	}
+}
+
+impl<R: Read + Seek> Seek for OggOpusReader<R> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let frame = 2 * self.channels() as i128;
+		let current = self.position() as i128 * frame + (self.index % self.channels() * 2 + self.byte) as i128;
+		let target = match pos {
+			SeekFrom::Start(n)   => n as i128,
+			SeekFrom::Current(n) => current + n as i128,
+			SeekFrom::End(_) if self.total_samples == 0 =>
+				return Err(Error::new(ErrorKind::Unsupported, "the length of the stream is unknown")),
+			SeekFrom::End(n)     => self.total_samples as i128 * frame + n as i128
+		};
+		
+		if target < 0 {
+			return Err(Error::new(ErrorKind::InvalidInput, "seek before the start of the stream"));
+		}
+		
+		self.seek_sample((target / frame) as u64)?;
+		if !self.pcm.is_empty() {
+			let rest = (target % frame) as usize;
+			self.index += rest / 2;
+			self.byte = rest % 2;
+		}
+		Ok(target as u64)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	/// 20 ms SILK packets of a 440 Hz sine, with the final range of each.
+	const SILK: [(&[u8], u32); 5] = [
+		(&[
+			0x4B, 0x01, 0x84, 0x28, 0x0A, 0x55, 0xC9, 0xFF, 0xE9, 0x5A, 0x62, 0x7C, 0x27, 0x70, 0xBB, 0x11,
+			0x0B, 0xC4, 0x50, 0xAA, 0x94, 0xD7, 0xD5, 0xF4, 0xD9, 0x9B, 0x7E, 0xA8, 0xF2, 0x7D, 0xB7, 0x20,
+			0xB8, 0xE5, 0xEF, 0xC5, 0x8D, 0xC7, 0x7D, 0xE8
+		], 0x058D1618),
+		(&[
+			0x4B, 0x41, 0x04, 0xB5, 0x96, 0x5C, 0x39, 0x5E, 0x7F, 0xE2, 0x3B, 0x77, 0x35, 0xB2, 0x17, 0xE5,
+			0xC2, 0x5F, 0x31, 0x5B, 0xD5, 0xD4, 0x08, 0x7B, 0x54, 0xB4, 0xCF, 0xAF, 0x03, 0xA8, 0x2F, 0x2A,
+			0x0F, 0x66, 0x9C, 0x75, 0x00, 0x00, 0x00, 0x00
+		], 0x4E9FA800),
+		(&[
+			0x4B, 0x01, 0xAB, 0xD2, 0x7C, 0x81, 0xA8, 0xD1, 0x41, 0x75, 0x39, 0xAC, 0x6F, 0x0F, 0xB2, 0xDD,
+			0x7A, 0x16, 0xC0, 0xDE, 0x20, 0x2F, 0xD5, 0xD0, 0x8E, 0x8A, 0x7B, 0x68, 0xFD, 0x17, 0xCC, 0xCB,
+			0x94, 0x71, 0x8B, 0xDD, 0x56, 0xE5, 0x6B, 0x92
+		], 0x0121D990),
+		(&[
+			0x48, 0xA7, 0xDC, 0xAC, 0xD0, 0xC8, 0x3F, 0xCE, 0x53, 0xF2, 0xB7, 0xED, 0x74, 0x35, 0xE2, 0x72,
+			0x33, 0xF1, 0xB1, 0x4E, 0xE3, 0x74, 0x76, 0xD7, 0x17, 0x91, 0xB4, 0xD7, 0x2C, 0x8D, 0xB5, 0xED,
+			0xC5, 0x8B, 0x76, 0x16, 0xDE, 0xEA, 0x4D, 0xC8
+		], 0x0542B0B0),
+		(&[
+			0x4B, 0x41, 0x05, 0xA7, 0xDC, 0xAC, 0xD0, 0xC8, 0x3F, 0xCE, 0x5D, 0x5D, 0xCA, 0x6D, 0x2A, 0x05,
+			0x23, 0xE1, 0xCF, 0x56, 0x49, 0x91, 0xBA, 0xA6, 0x71, 0xA7, 0x3C, 0xB6, 0x24, 0x74, 0x78, 0x93,
+			0xE5, 0xC8, 0xEE, 0x00, 0x00, 0x00, 0x00, 0x00
+		], 0x02645BC0)
+	];
+	
+	/// 20 ms HYBRID packets of a 440 Hz sine, with the final range of each.
+	const HYBRID: [(&[u8], u32); 5] = [
+		(&[
+			0x78, 0x83, 0x40, 0x5C, 0xEB, 0x08, 0xEA, 0x9E, 0x01, 0xC7, 0xFE, 0xC2, 0x95, 0xE2, 0x42, 0x51,
+			0x5F, 0x1D, 0xB8, 0x7C, 0x0A, 0x87, 0xC1, 0xF1, 0xC2, 0xFB, 0x9B, 0xB1, 0x9D, 0xF1, 0x4F, 0x2D,
+			0x11, 0xA4, 0xED, 0xC1, 0x8E, 0xCD, 0x4F, 0x0A, 0x43, 0x83, 0xF5, 0x4D, 0xC3, 0x5A, 0x15, 0x47,
+			0xAE, 0x69, 0xA4, 0x87, 0xAF, 0xD3, 0x55, 0x94, 0x23, 0x39, 0xDB, 0x5C
+		], 0x1E5E9D00),
+		(&[
+			0x78, 0xB4, 0xAF, 0x00, 0x73, 0xA8, 0x7F, 0xA8, 0x35, 0xAE, 0x65, 0x6E, 0x94, 0xB0, 0x29, 0x36,
+			0xA6, 0x18, 0x20, 0x56, 0x31, 0x92, 0x03, 0x18, 0xF2, 0x4E, 0x84, 0x98, 0x58, 0x6B, 0xC6, 0x8F,
+			0x41, 0x16, 0xDD, 0xFA, 0xDC, 0x61, 0xE9, 0xDC, 0x4A, 0xDF, 0x12, 0xA0, 0xE2, 0xFA, 0xE8, 0xF7,
+			0x41, 0xBA, 0x87, 0x61, 0xEB, 0x64, 0x51, 0x44, 0x1E, 0x37, 0xD4, 0xAC
+		], 0x2C2D3000),
+		(&[
+			0x78, 0xA2, 0x8B, 0x4C, 0x10, 0x59, 0xD2, 0x66, 0xD3, 0x2F, 0xBC, 0x58, 0x8D, 0x9B, 0x9F, 0x67,
+			0x48, 0xB8, 0x9E, 0xA0, 0x92, 0xCA, 0xE1, 0xE9, 0xEE, 0xA0, 0x91, 0x12, 0x1B, 0xF9, 0xDA, 0x4C,
+			0x45, 0xA9, 0x2B, 0x78, 0xDC, 0x25, 0x6A, 0x6C, 0x4E, 0xA8, 0x55, 0x0C, 0x14, 0xAA, 0x4D, 0xFD,
+			0x72, 0x3F, 0x6D, 0x12, 0xF8, 0xDC, 0xB4, 0xB5, 0xCD, 0x86, 0x82, 0xC5
+		], 0x11755B00),
+		(&[
+			0x78, 0xA3, 0xD3, 0xAC, 0x10, 0x59, 0xD2, 0x60, 0xFB, 0x9E, 0x4E, 0x33, 0x83, 0x60, 0xE3, 0x42,
+			0xEB, 0xFB, 0x04, 0xE5, 0x51, 0x1F, 0x8C, 0xD3, 0x64, 0x27, 0x11, 0x15, 0x20, 0x88, 0x7B, 0x55,
+			0x97, 0x34, 0x4B, 0x88, 0xC3, 0xAF, 0x8C, 0xC8, 0x5D, 0x84, 0x2B, 0xC2, 0x79, 0x6B, 0x51, 0x05,
+			0x6D, 0xDB, 0xBC, 0x73, 0x6F, 0x5A, 0x38, 0xE8, 0xA9, 0x5F, 0x79, 0xA6
+		], 0x04D8EA00),
+		(&[
+			0x78, 0xA3, 0xD3, 0xAC, 0x10, 0x59, 0xD2, 0x61, 0x02, 0xBB, 0x60, 0x8C, 0x81, 0x1F, 0xF7, 0x4E,
+			0x12, 0x3B, 0x6C, 0x1B, 0xBB, 0x82, 0xAD, 0xDD, 0xD5, 0xBD, 0x39, 0x60, 0xEF, 0xF4, 0xB4, 0xAE,
+			0xA6, 0x52, 0x43, 0xA2, 0x70, 0x09, 0x48, 0x6D, 0x3C, 0x93, 0x05, 0x3A, 0x3D, 0x17, 0x54, 0x54,
+			0xEB, 0x54, 0x90, 0x52, 0xCA, 0x3A, 0xED, 0x86, 0xCF, 0x15, 0x92, 0x0A
+		], 0x02E19000)
+	];
+	
+	/// 20 ms CELT packets of a 440 Hz sine with the right channel at half the amplitude, with the final range of each.
+	const CELT: [(&[u8], u32); 5] = [
+		(&[
+			0xFC, 0xB5, 0x0B, 0xE6, 0x11, 0xDF, 0x56, 0x24, 0xBB, 0x8D, 0xD4, 0xD3, 0x6D, 0x75, 0xDE, 0x7D,
+			0x7D, 0x03, 0xC5, 0xF8, 0x4C, 0xD7, 0x8C, 0x80, 0x4A, 0x95, 0xB0, 0xAD, 0xE0, 0xF8, 0x6F, 0xDA,
+			0x39, 0x07, 0xD8, 0xF1, 0x85, 0xBF, 0x13, 0xE7, 0x01, 0x0F, 0xD8, 0x26, 0x31, 0xE3, 0xC1, 0xEA,
+			0xEE, 0x65, 0x77, 0x00, 0xB6, 0x00, 0x38, 0x64, 0x5A, 0x2C, 0x26, 0x4C, 0xEF, 0x6E, 0x04, 0x61,
+			0x09, 0xB2, 0x80, 0xA1, 0x3E, 0x27, 0xC9, 0x0C, 0x63, 0xB8, 0x8D, 0x6E, 0xA1, 0x43, 0x59, 0x6D
+		], 0x02C77900),
+		(&[
+			0xFC, 0xB0, 0x4B, 0x6D, 0x15, 0x5C, 0x54, 0x90, 0xEA, 0x74, 0x0D, 0xDA, 0x22, 0x35, 0x42, 0x2F,
+			0xBD, 0xC7, 0x22, 0x89, 0xD2, 0x7B, 0xBA, 0xDB, 0xAC, 0x93, 0x5F, 0x44, 0xA2, 0x38, 0x20, 0x2B,
+			0xC5, 0x71, 0x61, 0xBD, 0xF5, 0xFA, 0xB4, 0x62, 0x35, 0x69, 0xD6, 0x00, 0xB9, 0x1E, 0x2C, 0x3A,
+			0xE5, 0x41, 0xE6, 0xBD, 0x25, 0xB0, 0xED, 0xF5, 0x06, 0xB9, 0x28, 0x9D, 0xD2, 0xD7, 0x2B, 0xA6,
+			0xE5, 0x5C, 0xC5, 0x5E, 0xFA, 0xB9, 0x70, 0xF6, 0x98, 0xE4, 0x2B, 0xC8, 0x87, 0x67, 0xD3, 0xAE
+		], 0x00DCC58E),
+		(&[
+			0xFC, 0xAC, 0xAC, 0x49, 0x8B, 0x49, 0x7A, 0xD0, 0x84, 0x8C, 0xA1, 0xD6, 0x3F, 0xD1, 0xD2, 0x59,
+			0xDD, 0x3D, 0x29, 0x36, 0x7F, 0x29, 0x60, 0xDE, 0x0D, 0xA8, 0xA1, 0xA0, 0xE0, 0xB9, 0xA8, 0xAA,
+			0xF5, 0xCC, 0x65, 0xFC, 0x9D, 0x10, 0x87, 0x98, 0x73, 0x4F, 0xB2, 0xEB, 0xFE, 0x35, 0x05, 0x19,
+			0x16, 0x2B, 0x27, 0xE2, 0x6D, 0x73, 0x88, 0x15, 0x2E, 0x64, 0x70, 0x93, 0x96, 0xFB, 0xDD, 0x97,
+			0x57, 0xC6, 0x15, 0x83, 0x80, 0xFE, 0xC8, 0xCD, 0x06, 0xDC, 0x94, 0xA7, 0x47, 0xE4, 0x19, 0xAE
+		], 0x16971A00),
+		(&[
+			0xFC, 0xAB, 0x1E, 0x25, 0x1B, 0x0E, 0x56, 0x31, 0xE0, 0xF1, 0xCA, 0x48, 0x7E, 0x14, 0x3F, 0x36,
+			0x7F, 0xD1, 0xD5, 0x04, 0x32, 0x75, 0x61, 0x4A, 0xBB, 0x3D, 0xB3, 0xC6, 0x96, 0x12, 0xA2, 0x40,
+			0xEE, 0xFE, 0xD6, 0x98, 0x07, 0x83, 0x38, 0x45, 0x13, 0x1C, 0x54, 0xCF, 0x5D, 0x6A, 0x67, 0x66,
+			0x0F, 0x81, 0x04, 0x14, 0x25, 0x99, 0x6C, 0xE7, 0x28, 0x1D, 0xC0, 0x1C, 0xB5, 0x4F, 0x92, 0x5C,
+			0x16, 0x4C, 0x59, 0x38, 0xEC, 0x81, 0x77, 0xCF, 0x75, 0x4B, 0x9C, 0x92, 0xFA, 0x22, 0xDB, 0xAE
+		], 0x497A6600),
+		(&[
+			0xFC, 0xB6, 0x48, 0x78, 0x46, 0xEC, 0x66, 0xAA, 0xA3, 0x0C, 0x76, 0xAE, 0x50, 0x88, 0xEB, 0xC3,
+			0x91, 0xC0, 0x38, 0xD8, 0xBD, 0x84, 0x8F, 0xC8, 0xDC, 0xBB, 0xB6, 0x3D, 0x4F, 0x13, 0xF0, 0x2D,
+			0xE5, 0xAD, 0x8E, 0x15, 0x4B, 0x92, 0x37, 0xD2, 0xFC, 0x70, 0xBA, 0x8A, 0x40, 0xE2, 0x59, 0x90,
+			0xAB, 0x7E, 0x63, 0x10, 0x57, 0xE1, 0x0F, 0x3F, 0xDE, 0x7B, 0x8C, 0x02, 0x49, 0xB9, 0x66, 0xB8,
+			0xED, 0xFC, 0x3A, 0xCC, 0x44, 0x6B, 0x3E, 0xEE, 0x1E, 0xF7, 0x27, 0x88, 0xA8, 0x79, 0x1B, 0xAE
+		], 0x4FB5EF00)
+	];
+	
+	/// Returns the amplitude of the 440 Hz component of a channel and the RMS of the residual.
+	fn sine_fit(pcm: &[f32], channels: usize, channel: usize) -> (f32, f32) {
+		let x = pcm.iter().skip(channel).step_by(channels).map(|x| *x as f64).collect::<Vec<_>>();
+		let w = 2.0 * std::f64::consts::PI * 440.0 / 48000.0;
+		let (mut ss, mut sc, mut cc, mut xs, mut xc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+		for (i, x) in x.iter().enumerate() {
+			let (s, c) = (w * i as f64).sin_cos();
+			ss += s * s;
+			sc += s * c;
+			cc += c * c;
+			xs += x * s;
+			xc += x * c;
+		}
+		
+		let det = ss * cc - sc * sc;
+		let (a, b) = ((xs * cc - xc * sc) / det, (xc * ss - xs * sc) / det);
+		let residual = x.iter().enumerate()
+			.map(|(i, x)| x - a * (w * i as f64).sin() - b * (w * i as f64).cos())
+			.map(|r| r * r)
+			.sum::<f64>() / x.len() as f64;
+		(a.hypot(b) as f32, residual.sqrt() as f32)
+	}
+	
+	#[test]
+	fn toc() {
+		assert_eq!(Toc::parse(0xF8), Toc { mode: Mode::Celt, bandwidth: Bandwidth::Full, frame_size: 960, stereo: false, code: 0 });
+		assert_eq!(Toc::parse(0x08), Toc { mode: Mode::Silk, bandwidth: Bandwidth::Narrow, frame_size: 960, stereo: false, code: 0 });
+		assert_eq!(Toc::parse(0x6D), Toc { mode: Mode::Hybrid, bandwidth: Bandwidth::SuperWide, frame_size: 960, stereo: true, code: 1 });
+		assert_eq!(Toc::parse(0xBB).frame_size, 960);
+		assert_eq!(Toc::parse(0xBB).bandwidth, Bandwidth::Wide);
+		assert_eq!(Toc::parse(0x18).frame_size, 2880);
+	}
+	
+	#[test]
+	fn packet() {
+		let frames = |data: &[u8], self_delimited| Packet::parse(data, self_delimited).map(|p| (p.frames.concat(), p.frames.len(), p.len));
+		assert_eq!(frames(&[0xF8, 1, 2, 3], false).unwrap(), (vec![1, 2, 3], 1, 4));
+		assert_eq!(frames(&[0xF9, 1, 2, 3, 4], false).unwrap(), (vec![1, 2, 3, 4], 2, 5));
+		assert!(frames(&[0xF9, 1, 2, 3], false).is_err());
+		assert_eq!(Packet::parse(&[0xFA, 1, 9, 7, 8], false).unwrap().frames, [&[9][..], &[7, 8]]);
+		// three CBR frames with two bytes of padding
+		assert_eq!(Packet::parse(&[0xFB, 0x43, 2, 4, 5, 6, 0, 0], false).unwrap().frames, [&[4][..], &[5], &[6]]);
+		assert_eq!(Packet::parse(&[0xFB, 0x82, 1, 4, 5, 6], false).unwrap().frames, [&[4][..], &[5, 6]]);
+		// self-delimited packets are followed by the next stream
+		assert_eq!(frames(&[0xF8, 2, 5, 6, 99, 99], true).unwrap(), (vec![5, 6], 1, 4));
+		assert_eq!(frames(&[0xFB, 0x43, 2, 1, 4, 5, 6, 0, 0, 99], true).unwrap(), (vec![4, 5, 6], 3, 9));
+		// more than 120 ms
+		assert!(frames(&[0xFB, 0x07], false).is_err());
+		assert!(frames(&[], false).is_err());
+		assert!(frames(&[0xF8, 7, 1], true).is_err());
+	}
+	
+	#[test]
+	fn decode() {
+		for (packets, channels, amplitudes) in [(&SILK, 1, [0.5, 0.0]), (&HYBRID, 1, [0.5, 0.0]), (&CELT, 2, [0.5, 0.25])] {
+			let mut decoder = Decoder::new(channels);
+			let mut pcm = vec![0f32; MAX_PACKET_SAMPLES * channels];
+			let mut out = Vec::new();
+			for (packet, range) in packets {
+				let n = decoder.decode(Some(packet), &mut pcm, false).unwrap();
+				assert_eq!(n, 960);
+				assert_eq!(decoder.final_range(), *range);
+				out.extend_from_slice(&pcm[..n * channels]);
+			}
+			
+			for (c, amplitude) in amplitudes[..channels].iter().enumerate() {
+				let (a, residual) = sine_fit(&out[2 * 960 * channels..], channels, c);
+				assert!((a - amplitude).abs() < 0.05 * amplitude, "{a} {amplitude}");
+				assert!(residual < 0.05 * amplitude, "{residual} {amplitude}");
+			}
+		}
+	}
+	
+	#[test]
+	fn channels() {
+		// a mono stream decoded to stereo has identical channels
+		let mut decoder = Decoder::new(2);
+		let mut pcm = vec![0f32; 960 * 2];
+		for (packet, range) in &HYBRID {
+			decoder.decode(Some(packet), &mut pcm, false).unwrap();
+			assert_eq!(decoder.final_range(), *range);
+			assert!(pcm.chunks(2).all(|s| s[0] == s[1]));
+		}
+		
+		// a stereo stream decoded to mono is downmixed
+		let mut decoder = Decoder::new(1);
+		let mut out = Vec::new();
+		for (packet, range) in &CELT {
+			let n = decoder.decode(Some(packet), &mut pcm, false).unwrap();
+			assert_eq!(decoder.final_range(), *range);
+			out.extend_from_slice(&pcm[..n]);
+		}
+		let (a, _) = sine_fit(&out[2 * 960..], 1, 0);
+		assert!((a - 0.375).abs() < 0.03, "{a}");
+	}
+	
+	#[test]
+	fn lost() {
+		let mut decoder = Decoder::new(1);
+		let mut pcm = vec![0f32; 960];
+		for (i, (packet, range)) in SILK.iter().enumerate() {
+			match i {
+				2 => {
+					assert_eq!(decoder.decode(None, &mut pcm, false).unwrap(), 960);
+					assert!(pcm.iter().all(|s| s.abs() < 1.0) && pcm.iter().any(|s| s.abs() > 0.1));
+				}
+				_ => {
+					decoder.decode(Some(packet), &mut pcm, false).unwrap();
+					assert_eq!(decoder.final_range(), *range);
+				}
+			}
+		}
+		assert!(decoder.decode(None, &mut pcm[..100], false).is_err());
+	}
+	
+	#[test]
+	fn multistream() {
+		// two mono streams, the first one is self-delimited
+		let mut data = vec![HYBRID[0].0[0], HYBRID[0].0.len() as u8 - 1];
+		data.extend_from_slice(&HYBRID[0].0[1..]);
+		data.extend_from_slice(SILK[0].0);
+		
+		let mut decoder = MultistreamDecoder::new(2, 0, &[1, 255, 0]).unwrap();
+		let mut pcm = vec![0f32; 960 * 3];
+		assert_eq!(decoder.decode(Some(&data), &mut pcm, false).unwrap(), 960);
+		
+		let mut expected = [vec![0f32; 960], vec![0f32; 960]];
+		Decoder::new(1).decode(Some(HYBRID[0].0), &mut expected[0], false).unwrap();
+		Decoder::new(1).decode(Some(SILK[0].0), &mut expected[1], false).unwrap();
+		for (i, s) in pcm.chunks(3).enumerate() {
+			assert_eq!(s, [expected[1][i], 0.0, expected[0][i]]);
+		}
+		
+		assert!(MultistreamDecoder::new(1, 0, &[1]).is_err());
+		assert!(decoder.decode(Some(&data[..20]), &mut pcm, false).is_err());
+	}
+	
+	#[test]
+	fn clip() {
+		let mut pcm = (0..480).map(|i| 1.5 * (i as f32 * 0.05).sin()).collect::<Vec<_>>();
+		let mut mem = [0.0];
+		soft_clip(&mut pcm, 1, &mut mem);
+		assert!(pcm.iter().all(|s| s.abs() <= 1.0));
+		assert!(pcm.windows(2).all(|w| (w[1] - w[0]).abs() < 0.1));
+		
+		let mut quiet = vec![0.5, -0.5, 0.25];
+		soft_clip(&mut quiet, 1, &mut [0.0]);
+		assert_eq!(quiet, [0.5, -0.5, 0.25]);
+	}
+	
+	/// Writes an Ogg Opus stream with the CELT packets, one per page, the last page is trimmed by 100 samples.
+	fn ogg_opus() -> Vec<u8> {
+		let mut head = HEAD_MAGIC.to_vec();
+		head.extend_from_slice(&[1, 2, 0x38, 0x01, 0x80, 0xBB, 0, 0, 0, 0, 0]);
+		let mut tags = TAGS_MAGIC.to_vec();
+		tags.extend_from_slice(&[4, 0, 0, 0, b't', b'e', b's', b't', 1, 0, 0, 0, 11, 0, 0, 0]);
+		tags.extend_from_slice(b"TITLE=Sine!");
+		
+		let mut out = Vec::new();
+		let mut write = |data: &[u8], flags, granule, sequence| {
+			let mut segments = vec![255; data.len() / 255];
+			segments.push((data.len() % 255) as u8);
+			ogg::Page { flags, granule, serial: 1, sequence, segments, data: data.to_vec() }.write(&mut out).unwrap();
+		};
+		
+		write(&head, ogg::FLAG_BOS, 0, 0);
+		write(&tags, 0, 0, 1);
+		for (i, (packet, _)) in CELT.iter().enumerate() {
+			match i {
+				4 => write(packet, ogg::FLAG_EOS, 4 * 960 + 860, 6),
+				_ => write(packet, 0, (i as u64 + 1) * 960, i as u32 + 2)
+			}
+		}
+		out
+	}
+	
+	#[test]
+	fn ogg_reader() {
+		let mut decoder = Decoder::new(2);
+		let mut expected = Vec::new();
+		let mut pcm = vec![0f32; 960 * 2];
+		for (packet, _) in &CELT {
+			decoder.decode(Some(packet), &mut pcm, false).unwrap();
+			expected.extend_from_slice(&pcm);
+		}
+		expected.drain(..312 * 2);
+		expected.truncate((4 * 960 + 860 - 312) * 2);
+		
+		let mut reader = OggOpusReader::new(io::Cursor::new(ogg_opus())).unwrap();
+		assert_eq!(reader.head.pre_skip, 312);
+		assert_eq!(reader.head.input_sample_rate, 48000);
+		assert_eq!(reader.tags.vendor, "test");
+		assert_eq!(reader.tags.get("title").collect::<Vec<_>>(), ["Sine!"]);
+		assert_eq!(reader.total_samples, 4 * 960 + 860 - 312);
+		
+		let mut samples = vec![0f32; 20000];
+		let n = reader.read_samples(&mut samples).unwrap();
+		assert_eq!(&samples[..n], &expected[..]);
+		assert_eq!(reader.position(), reader.total_samples);
+		
+		reader.seek_sample(2000).unwrap();
+		assert_eq!(reader.position(), 2000);
+		let n = reader.read_samples(&mut samples).unwrap();
+		assert_eq!(&samples[..n], &expected[4000..]);
+		assert!(reader.seek_sample(10000).is_err());
+		
+		let mut bytes = Vec::new();
+		reader.seek(SeekFrom::Start(6)).unwrap();
+		reader.read_to_end(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), expected.len() * 2 - 6);
+		assert_eq!(i16::from_le_bytes([bytes[0], bytes[1]]), (expected[3] * 32768.0).round() as i16);
+		
+		assert!(OggOpusReader::new(io::Cursor::new(vec![0u8; 100])).is_err());
+	}
+}
@@ -0,0 +1,2182 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The CELT decoder (RFC 6716, section 4.3).
+//!
+//! This is a port of the floating point reference decoder for the 48 kHz mode used by Opus. Lost
+//! frames are concealed with noise shaped by the previous band energies, the pitch based
+//! concealment of the reference decoder is not implemented.
+
+use {
+	super::{range::{RangeDecoder, BITRES, ilog}, celt_tables::*},
+	std::{f64::consts::{PI, LN_2}, ops::{Add, Sub, Mul}}
+};
+
+const NB_EBANDS:              usize = 21;
+const NB_ALLOC_VECTORS:       usize = 11;
+pub const OVERLAP:             usize = 120;
+const SHORT_MDCT_SIZE:        usize = 120;
+const MAX_LM:                 usize = 3;
+const DECODE_BUFFER_SIZE:     usize = 2048;
+const COMBFILTER_MINPERIOD:   usize = 15;
+const PREEMPH:                f32   = 0.850_006_1;
+const MAX_FINE_BITS:          i32   = 8;
+const FINE_OFFSET:            i32   = 21;
+const QTHETA_OFFSET:          i32   = 4;
+const QTHETA_OFFSET_TWOPHASE: i32   = 16;
+const ALLOC_STEPS:            i32   = 6;
+const LOG_MAX_PSEUDO:         usize = 6;
+const MAX_PULSES:             usize = 128;
+const MAX_BAND_SIZE:          usize = 176;
+const EPSILON:                f32   = 1e-15;
+const VERY_SMALL:             f32   = 1e-30;
+const SIG_SCALE:              f32   = 32768.0;
+
+const SPREAD_NONE:       usize = 0;
+const SPREAD_NORMAL:     usize = 2;
+const SPREAD_AGGRESSIVE: usize = 3;
+
+const BIT_INTERLEAVE:   [u8; 16] = [0, 1, 1, 1, 2, 3, 3, 3, 2, 3, 3, 3, 2, 3, 3, 3];
+const BIT_DEINTERLEAVE: [u8; 16] = [
+	0x00, 0x03, 0x0C, 0x0F, 0x30, 0x33, 0x3C, 0x3F, 0xC0, 0xC3, 0xCC, 0xCF, 0xF0, 0xF3, 0xFC, 0xFF
+];
+const ORDERY: [usize; 30] = [
+	1, 0,
+	3, 0, 2, 1,
+	7, 0, 4, 3, 6, 1, 5, 2,
+	15, 0, 8, 7, 12, 3, 11, 4, 14, 1, 9, 6, 13, 2, 10, 5
+];
+
+#[inline]
+fn exp2(x: f32) -> f32 {
+	(LN_2 * x as f64).exp() as f32
+}
+
+#[inline]
+fn cos_norm(x: f32) -> f32 {
+	(0.5 * PI * x as f64).cos() as f32
+}
+
+#[inline]
+fn lcg_rand(seed: u32) -> u32 {
+	seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223)
+}
+
+/// Returns the width of the band in MDCT bins at LM 0.
+#[inline]
+fn band_width(i: usize) -> usize {
+	(EBANDS[i + 1] - EBANDS[i]) as usize
+}
+
+#[inline]
+fn frac_mul16(a: i32, b: i32) -> i32 {
+	(16384 + (a as i16 as i32) * (b as i16 as i32)) >> 15
+}
+
+fn bitexact_cos(x: i32) -> i32 {
+	let tmp = (4096 + x * x) >> 13;
+	let x2 = (32767 - tmp) + frac_mul16(tmp, -7651 + frac_mul16(tmp, 8277 + frac_mul16(-626, tmp)));
+	1 + x2
+}
+
+fn bitexact_log2tan(isin: i32, icos: i32) -> i32 {
+	let lc = ilog(icos as u32) as i32;
+	let ls = ilog(isin as u32) as i32;
+	let icos = icos << (15 - lc);
+	let isin = isin << (15 - ls);
+	(ls - lc) * (1 << 11)
+		+ frac_mul16(isin, frac_mul16(isin, -2597) + 7932)
+		- frac_mul16(icos, frac_mul16(icos, -2597) + 7932)
+}
+
+fn isqrt32(mut v: u32) -> u32 {
+	let mut g = 0;
+	let mut bshift = (ilog(v) as i32 - 1) >> 1;
+	let mut b = 1u32 << bshift;
+	loop {
+		let t = ((g << 1) + b) << bshift;
+		if t <= v {
+			g += b;
+			v -= t;
+		}
+		b >>= 1;
+		bshift -= 1;
+		if bshift < 0 {
+			return g;
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Complex {
+	r: f32,
+	i: f32
+}
+
+impl Add for Complex {
+	type Output = Self;
+	
+	fn add(self, rhs: Self) -> Self {
+		Self { r: self.r + rhs.r, i: self.i + rhs.i }
+	}
+}
+
+impl Sub for Complex {
+	type Output = Self;
+	
+	fn sub(self, rhs: Self) -> Self {
+		Self { r: self.r - rhs.r, i: self.i - rhs.i }
+	}
+}
+
+impl Mul for Complex {
+	type Output = Self;
+	
+	fn mul(self, rhs: Self) -> Self {
+		Self { r: self.r * rhs.r - self.i * rhs.i, i: self.r * rhs.i + self.i * rhs.r }
+	}
+}
+
+impl Mul<f32> for Complex {
+	type Output = Self;
+	
+	fn mul(self, rhs: f32) -> Self {
+		Self { r: self.r * rhs, i: self.i * rhs }
+	}
+}
+
+/// A mixed radix FFT, the twiddles are shared with the largest FFT.
+struct Fft {
+	/// The radix and remaining length of each stage.
+	factors: &'static [(usize, usize)],
+	bitrev:  Vec<usize>,
+	shift:   usize
+}
+
+impl Fft {
+	fn new(factors: &'static [(usize, usize)], shift: usize) -> Self {
+		fn compute_bitrev(f: &mut [usize], fout: usize, fi: usize, fstride: usize, factors: &[(usize, usize)]) {
+			let (p, m) = factors[0];
+			for j in 0..p {
+				if m == 1 {
+					f[fi + j * fstride] = fout + j;
+				} else {
+					compute_bitrev(f, fout + j * m, fi + j * fstride, fstride * p, &factors[1..]);
+				}
+			}
+		}
+		
+		let n = factors[0].0 * factors[0].1;
+		let mut bitrev = vec![0; n];
+		compute_bitrev(&mut bitrev, 0, 0, 1, factors);
+		Self { factors, bitrev, shift }
+	}
+	
+	fn process(&self, tw: &[Complex], fout: &mut [Complex]) {
+		let mut fstride = [1; 6];
+		for (l, &(p, _)) in self.factors.iter().enumerate() {
+			fstride[l + 1] = fstride[l] * p;
+		}
+		
+		let mut m = self.factors[self.factors.len() - 1].1;
+		for i in (0..self.factors.len()).rev() {
+			let m2 = if i != 0 { self.factors[i - 1].1 } else { 1 };
+			let stride = fstride[i] << self.shift;
+			match self.factors[i].0 {
+				2 => bfly2(fout, fstride[i]),
+				3 => bfly3(fout, tw, stride, m, fstride[i], m2),
+				4 => bfly4(fout, tw, stride, m, fstride[i], m2),
+				_ => bfly5(fout, tw, stride, m, fstride[i], m2)
+			}
+			m = m2;
+		}
+	}
+}
+
+fn bfly2(fout: &mut [Complex], n: usize) {
+	const TW: f32 = 0.707_106_77;
+	for f in fout.chunks_exact_mut(8).take(n) {
+		let t = f[4];
+		f[4] = f[0] - t;
+		f[0] = f[0] + t;
+		let t = Complex { r: (f[5].r + f[5].i) * TW, i: (f[5].i - f[5].r) * TW };
+		f[5] = f[1] - t;
+		f[1] = f[1] + t;
+		let t = Complex { r: f[6].i, i: -f[6].r };
+		f[6] = f[2] - t;
+		f[2] = f[2] + t;
+		let t = Complex { r: (f[7].i - f[7].r) * TW, i: -(f[7].i + f[7].r) * TW };
+		f[7] = f[3] - t;
+		f[3] = f[3] + t;
+	}
+}
+
+fn bfly3(fout: &mut [Complex], tw: &[Complex], fstride: usize, m: usize, n: usize, mm: usize) {
+	let epi3 = tw[fstride * m];
+	for i in 0..n {
+		for k in 0..m {
+			let j = i * mm + k;
+			let s1 = fout[j + m] * tw[k * fstride];
+			let s2 = fout[j + 2 * m] * tw[2 * k * fstride];
+			let s3 = s1 + s2;
+			let s0 = (s1 - s2) * epi3.i;
+			let t = fout[j] - s3 * 0.5;
+			fout[j] = fout[j] + s3;
+			fout[j + 2 * m] = Complex { r: t.r + s0.i, i: t.i - s0.r };
+			fout[j + m] = Complex { r: t.r - s0.i, i: t.i + s0.r };
+		}
+	}
+}
+
+fn bfly4(fout: &mut [Complex], tw: &[Complex], fstride: usize, m: usize, n: usize, mm: usize) {
+	if m == 1 {
+		for f in fout.chunks_exact_mut(4).take(n) {
+			let s0 = f[0] - f[2];
+			f[0] = f[0] + f[2];
+			let s1 = f[1] + f[3];
+			f[2] = f[0] - s1;
+			f[0] = f[0] + s1;
+			let s1 = f[1] - f[3];
+			f[1] = Complex { r: s0.r + s1.i, i: s0.i - s1.r };
+			f[3] = Complex { r: s0.r - s1.i, i: s0.i + s1.r };
+		}
+		return;
+	}
+	
+	for i in 0..n {
+		for j in 0..m {
+			let k = i * mm + j;
+			let s0 = fout[k + m] * tw[j * fstride];
+			let s1 = fout[k + 2 * m] * tw[2 * j * fstride];
+			let s2 = fout[k + 3 * m] * tw[3 * j * fstride];
+			let s5 = fout[k] - s1;
+			let f0 = fout[k] + s1;
+			let s3 = s0 + s2;
+			let s4 = s0 - s2;
+			fout[k + 2 * m] = f0 - s3;
+			fout[k] = f0 + s3;
+			fout[k + m] = Complex { r: s5.r + s4.i, i: s5.i - s4.r };
+			fout[k + 3 * m] = Complex { r: s5.r - s4.i, i: s5.i + s4.r };
+		}
+	}
+}
+
+fn bfly5(fout: &mut [Complex], tw: &[Complex], fstride: usize, m: usize, n: usize, mm: usize) {
+	let ya = tw[fstride * m];
+	let yb = tw[fstride * 2 * m];
+	for i in 0..n {
+		for u in 0..m {
+			let j = i * mm + u;
+			let s0 = fout[j];
+			let s1 = fout[j + m] * tw[u * fstride];
+			let s2 = fout[j + 2 * m] * tw[2 * u * fstride];
+			let s3 = fout[j + 3 * m] * tw[3 * u * fstride];
+			let s4 = fout[j + 4 * m] * tw[4 * u * fstride];
+			let s7 = s1 + s4;
+			let s10 = s1 - s4;
+			let s8 = s2 + s3;
+			let s9 = s2 - s3;
+			
+			fout[j] = Complex { r: s0.r + (s7.r + s8.r), i: s0.i + (s7.i + s8.i) };
+			
+			let s5 = Complex { r: s0.r + (s7.r * ya.r + s8.r * yb.r), i: s0.i + (s7.i * ya.r + s8.i * yb.r) };
+			let s6 = Complex { r: s10.i * ya.i + s9.i * yb.i, i: -(s10.r * ya.i + s9.r * yb.i) };
+			fout[j + m] = s5 - s6;
+			fout[j + 4 * m] = s5 + s6;
+			
+			let s11 = Complex { r: s0.r + (s7.r * yb.r + s8.r * ya.r), i: s0.i + (s7.i * yb.r + s8.i * ya.r) };
+			let s12 = Complex { r: s9.i * ya.i - s10.i * yb.i, i: s10.r * yb.i - s9.r * ya.i };
+			fout[j + 2 * m] = s11 + s12;
+			fout[j + 3 * m] = s11 - s12;
+		}
+	}
+}
+
+/// The inverse MDCT for the frame sizes 1920 (long blocks of 20 ms frames) down to 240.
+struct Mdct {
+	trig:     Vec<f32>,
+	twiddles: Vec<Complex>,
+	ffts:     [Fft; 4]
+}
+
+impl Mdct {
+	const TRIG_OFFSETS: [usize; 4] = [0, 960, 1440, 1680];
+	
+	fn new() -> Self {
+		let mut trig = Vec::with_capacity(1800);
+		for shift in 0..4 {
+			let n = 1920 >> shift;
+			trig.extend((0..n / 2).map(|i| (2.0 * PI * (i as f64 + 0.125) / n as f64).cos() as f32));
+		}
+		
+		let twiddles = (0..480)
+			.map(|i| {
+				let phase = -2.0 * PI * i as f64 / 480.0;
+				Complex { r: phase.cos() as f32, i: phase.sin() as f32 }
+			})
+			.collect();
+		
+		Self {
+			trig,
+			twiddles,
+			ffts: [
+				Fft::new(&[(5, 96), (3, 32), (4, 8), (2, 4), (4, 1)], 0),
+				Fft::new(&[(5, 48), (3, 16), (4, 4), (4, 1)], 1),
+				Fft::new(&[(5, 24), (3, 8), (2, 4), (4, 1)], 2),
+				Fft::new(&[(5, 12), (3, 4), (4, 1)], 3)
+			]
+		}
+	}
+	
+	/// Computes the inverse MDCT of `input` (with the given stride) and overlap-adds it to `out`.
+	/// The first `OVERLAP / 2` samples of `out` must hold the overlap of the previous block.
+	fn backward(&self, input: &[f32], stride: usize, out: &mut [f32], window: &[f32], shift: usize) {
+		let n = 1920 >> shift;
+		let n2 = n / 2;
+		let n4 = n / 4;
+		let trig = &self.trig[Self::TRIG_OFFSETS[shift]..];
+		let fft = &self.ffts[shift];
+		
+		let mut buf = [Complex::default(); 480];
+		let buf = &mut buf[..n4];
+		for i in 0..n4 {
+			let x1 = input[2 * stride * i];
+			let x2 = input[stride * (n2 - 1 - 2 * i)];
+			let yr = x2 * trig[i] + x1 * trig[n4 + i];
+			let yi = x1 * trig[i] - x2 * trig[n4 + i];
+			buf[fft.bitrev[i]] = Complex { r: yi, i: yr };
+		}
+		
+		fft.process(&self.twiddles, buf);
+		
+		let ov2 = OVERLAP / 2;
+		for (i, c) in buf.iter().enumerate() {
+			out[ov2 + 2 * i] = c.r;
+			out[ov2 + 2 * i + 1] = c.i;
+		}
+		
+		// post-rotation, in place from both ends
+		let mut p0 = ov2;
+		let mut p1 = ov2 + n2 - 2;
+		for i in 0..(n4 + 1) >> 1 {
+			let (re, im) = (out[p0 + 1], out[p0]);
+			let (t0, t1) = (trig[i], trig[n4 + i]);
+			let yr0 = re * t0 + im * t1;
+			let yi0 = re * t1 - im * t0;
+			let (re, im) = (out[p1 + 1], out[p1]);
+			out[p0] = yr0;
+			out[p1 + 1] = yi0;
+			let (t0, t1) = (trig[n4 - i - 1], trig[n2 - i - 1]);
+			out[p1] = re * t0 + im * t1;
+			out[p0 + 1] = re * t1 - im * t0;
+			p0 += 2;
+			p1 = p1.wrapping_sub(2);
+		}
+		
+		// mirror for TDAC
+		for i in 0..OVERLAP / 2 {
+			let x1 = out[OVERLAP - 1 - i];
+			let x2 = out[i];
+			out[i] = window[OVERLAP - 1 - i] * x2 - window[i] * x1;
+			out[OVERLAP - 1 - i] = window[i] * x2 + window[OVERLAP - 1 - i] * x1;
+		}
+	}
+}
+
+/// Applies the pitch post-filter in place to `n` samples of `x`, starting at `off`. The filter
+/// parameters (period, gain, tapset) are crossfaded from `old` to `new` over the overlap.
+fn comb_filter(
+	x:      &mut [f32],
+	off:    usize,
+	n:      usize,
+	old:    (usize, f32, usize),
+	new:    (usize, f32, usize),
+	window: &[f32]
+) {
+	const GAINS: [[f32; 3]; 3] = [
+		[0.306_640_63, 0.217_041_02, 0.129_638_67],
+		[0.463_867_2, 0.268_066_4,  0.0],
+		[0.799_804_7,  0.100_097_66, 0.0]
+	];
+	
+	let ((t0, g0, tapset0), (t1, g1, tapset1)) = (old, new);
+	if g0 == 0.0 && g1 == 0.0 {
+		return;
+	}
+	
+	let t0 = t0.max(COMBFILTER_MINPERIOD);
+	let t1 = t1.max(COMBFILTER_MINPERIOD);
+	let g00 = g0 * GAINS[tapset0][0];
+	let g01 = g0 * GAINS[tapset0][1];
+	let g02 = g0 * GAINS[tapset0][2];
+	let g10 = g1 * GAINS[tapset1][0];
+	let g11 = g1 * GAINS[tapset1][1];
+	let g12 = g1 * GAINS[tapset1][2];
+	let overlap = if g0 == g1 && t0 == t1 && tapset0 == tapset1 { 0 } else { OVERLAP };
+	
+	let mut x1 = x[off + 1 - t1];
+	let mut x2 = x[off - t1];
+	let mut x3 = x[off - t1 - 1];
+	let mut x4 = x[off - t1 - 2];
+	for (i, w) in window[..overlap].iter().enumerate() {
+		let p = off + i;
+		let x0 = x[p + 2 - t1];
+		let f = w * w;
+		x[p] = x[p]
+			+ (1.0 - f) * g00 * x[p - t0]
+			+ (1.0 - f) * g01 * (x[p + 1 - t0] + x[p - t0 - 1])
+			+ (1.0 - f) * g02 * (x[p + 2 - t0] + x[p - t0 - 2])
+			+ f * g10 * x2
+			+ f * g11 * (x1 + x3)
+			+ f * g12 * (x0 + x4);
+		x4 = x3;
+		x3 = x2;
+		x2 = x1;
+		x1 = x0;
+	}
+	
+	if g1 == 0.0 {
+		return;
+	}
+	
+	let start = off + overlap;
+	let mut x4 = x[start - t1 - 2];
+	let mut x3 = x[start - t1 - 1];
+	let mut x2 = x[start - t1];
+	let mut x1 = x[start + 1 - t1];
+	for p in start..off + n {
+		let x0 = x[p + 2 - t1];
+		x[p] = x[p] + g10 * x2 + g11 * (x1 + x3) + g12 * (x0 + x4);
+		x4 = x3;
+		x3 = x2;
+		x2 = x1;
+		x1 = x0;
+	}
+}
+
+fn renormalise_vector(x: &mut [f32], gain: f32) {
+	let e = EPSILON + x.iter().map(|v| v * v).sum::<f32>();
+	let g = gain / e.sqrt();
+	x.iter_mut().for_each(|v| *v *= g);
+}
+
+fn haar1(x: &mut [f32], n0: usize, stride: usize) {
+	const S: f32 = std::f32::consts::FRAC_1_SQRT_2;
+	for i in 0..stride {
+		for j in 0..n0 >> 1 {
+			let tmp1 = S * x[stride * 2 * j + i];
+			let tmp2 = S * x[stride * (2 * j + 1) + i];
+			x[stride * 2 * j + i] = tmp1 + tmp2;
+			x[stride * (2 * j + 1) + i] = tmp1 - tmp2;
+		}
+	}
+}
+
+fn deinterleave_hadamard(x: &mut [f32], n0: usize, stride: usize, hadamard: bool) {
+	let mut tmp = [0f32; MAX_BAND_SIZE];
+	let n = n0 * stride;
+	for i in 0..stride {
+		let row = if hadamard { ORDERY[stride - 2 + i] } else { i };
+		for j in 0..n0 {
+			tmp[row * n0 + j] = x[j * stride + i];
+		}
+	}
+	x[..n].copy_from_slice(&tmp[..n]);
+}
+
+fn interleave_hadamard(x: &mut [f32], n0: usize, stride: usize, hadamard: bool) {
+	let mut tmp = [0f32; MAX_BAND_SIZE];
+	let n = n0 * stride;
+	for i in 0..stride {
+		let row = if hadamard { ORDERY[stride - 2 + i] } else { i };
+		for j in 0..n0 {
+			tmp[j * stride + i] = x[row * n0 + j];
+		}
+	}
+	x[..n].copy_from_slice(&tmp[..n]);
+}
+
+fn stereo_merge(x: &mut [f32], y: &mut [f32], mid: f32) {
+	let (mut xp, mut side) = (0f32, 0f32);
+	for (a, b) in y.iter().zip(x.iter()) {
+		xp += a * b;
+		side += a * a;
+	}
+	
+	xp *= mid;
+	let el = mid * mid + side - 2.0 * xp;
+	let er = mid * mid + side + 2.0 * xp;
+	if er < 6e-4 || el < 6e-4 {
+		y.copy_from_slice(x);
+		return;
+	}
+	
+	let lgain = 1.0 / el.sqrt();
+	let rgain = 1.0 / er.sqrt();
+	for (a, b) in x.iter_mut().zip(y.iter_mut()) {
+		let l = mid * *a;
+		let r = *b;
+		*a = lgain * (l - r);
+		*b = rgain * (l + r);
+	}
+}
+
+fn exp_rotation1(x: &mut [f32], stride: usize, c: f32, s: f32) {
+	let len = x.len();
+	for i in 0..len.saturating_sub(stride) {
+		let (x1, x2) = (x[i], x[i + stride]);
+		x[i + stride] = c * x2 + s * x1;
+		x[i] = c * x1 - s * x2;
+	}
+	
+	for i in (0..len.saturating_sub(2 * stride)).rev() {
+		let (x1, x2) = (x[i], x[i + stride]);
+		x[i + stride] = c * x2 + s * x1;
+		x[i] = c * x1 - s * x2;
+	}
+}
+
+/// Undoes the spreading rotation applied by the encoder.
+fn exp_rotation(x: &mut [f32], stride: usize, k: usize, spread: usize) {
+	const SPREAD_FACTOR: [usize; 3] = [15, 10, 5];
+	
+	let len = x.len();
+	if 2 * k >= len || spread == SPREAD_NONE {
+		return;
+	}
+	
+	let gain = len as f32 / (len + SPREAD_FACTOR[spread - 1] * k) as f32;
+	let theta = 0.5 * gain * gain;
+	let c = cos_norm(theta);
+	let s = cos_norm(1.0 - theta);
+	
+	let mut stride2 = 0;
+	if len >= 8 * stride {
+		stride2 = 1;
+		while (stride2 * stride2 + stride2) * stride + (stride >> 2) < len {
+			stride2 += 1;
+		}
+	}
+	
+	for x in x.chunks_exact_mut(len / stride) {
+		if stride2 != 0 {
+			exp_rotation1(x, stride2, s, c);
+		}
+		exp_rotation1(x, 1, c, s);
+	}
+}
+
+fn unext(u: &mut [u32], mut u0: u32) {
+	for j in 1..u.len() {
+		let u1 = u[j].wrapping_add(u[j - 1]).wrapping_add(u0);
+		u[j - 1] = u0;
+		u0 = u1;
+	}
+	let last = u.len() - 1;
+	u[last] = u0;
+}
+
+fn uprev(u: &mut [u32], mut u0: u32) {
+	for j in 1..u.len() {
+		let u1 = u[j].wrapping_sub(u[j - 1]).wrapping_sub(u0);
+		u[j - 1] = u0;
+		u0 = u1;
+	}
+	let last = u.len() - 1;
+	u[last] = u0;
+}
+
+/// Decodes a vector of `k` pulses (section 4.3.4.2) into `y` and returns its squared norm.
+fn decode_pulses(y: &mut [i32], k: usize, dec: &mut RangeDecoder) -> f32 {
+	let n = y.len();
+	let mut u = [0u32; MAX_PULSES + 2];
+	let u = &mut u[..k + 2];
+	
+	// compute the row n of the U(n, k) table
+	u[1] = 1;
+	for (j, v) in u.iter_mut().enumerate().skip(2) {
+		*v = 2 * j as u32 - 1;
+	}
+	for _ in 2..n {
+		unext(&mut u[1..], 1);
+	}
+	
+	let mut i = dec.uint(u[k].wrapping_add(u[k + 1]));
+	let mut k = k;
+	let mut yy = 0;
+	for v in y.iter_mut() {
+		let p = u[k + 1];
+		let s = -((i >= p) as i32);
+		i = i.wrapping_sub(p & s as u32);
+		let k0 = k;
+		let mut p = u[k];
+		while p > i {
+			k -= 1;
+			p = u[k];
+		}
+		i = i.wrapping_sub(p);
+		let val = ((k0 - k) as i32 + s) ^ s;
+		*v = val;
+		yy += val * val;
+		uprev(&mut u[..k + 2], 0);
+	}
+	yy as f32
+}
+
+fn get_pulses(i: i32) -> usize {
+	(if i < 8 { i } else { (8 + (i & 7)) << ((i >> 3) - 1) }) as usize
+}
+
+fn cache_bits(lm: i32, band: usize) -> &'static [u8] {
+	&CACHE_BITS[CACHE_INDEX[(lm + 1) as usize * NB_EBANDS + band] as usize..]
+}
+
+fn bits2pulses(band: usize, lm: i32, bits: i32) -> i32 {
+	let cache = cache_bits(lm, band);
+	let bits = bits - 1;
+	let (mut lo, mut hi) = (0, cache[0] as usize);
+	for _ in 0..LOG_MAX_PSEUDO {
+		let mid = (lo + hi + 1) >> 1;
+		if cache[mid] as i32 >= bits {
+			hi = mid;
+		} else {
+			lo = mid;
+		}
+	}
+	
+	let lo_bits = if lo == 0 { -1 } else { cache[lo] as i32 };
+	if bits - lo_bits <= cache[hi] as i32 - bits { lo as i32 } else { hi as i32 }
+}
+
+fn pulses2bits(band: usize, lm: i32, pulses: i32) -> i32 {
+	if pulses == 0 { 0 } else { cache_bits(lm, band)[pulses as usize] as i32 + 1 }
+}
+
+fn compute_qn(n: usize, b: i32, offset: i32, pulse_cap: i32, stereo: bool) -> i32 {
+	const EXP2_TABLE8: [i32; 8] = [16384, 17866, 19483, 21247, 23170, 25267, 27554, 30048];
+	
+	let mut n2 = 2 * n as i32 - 1;
+	if stereo && n == 2 {
+		n2 -= 1;
+	}
+	
+	let qb = ((b + n2 * offset) / n2).min(b - pulse_cap - (4 << BITRES)).min(8 << BITRES);
+	if qb < (1 << BITRES >> 1) {
+		1
+	} else {
+		let qn = EXP2_TABLE8[(qb & 7) as usize] >> (14 - (qb >> BITRES));
+		(qn + 1) >> 1 << 1
+	}
+}
+
+fn copy_lowband<'a>(buf: &'a mut [f32], norm: &[f32], offset: Option<usize>, n: usize) -> Option<&'a mut [f32]> {
+	offset.map(move |offset| {
+		buf[..n].copy_from_slice(&norm[offset..offset + n]);
+		&mut buf[..n]
+	})
+}
+
+/// The result of the bit allocation (section 4.3.3).
+struct Allocation {
+	coded_bands:   usize,
+	intensity:     usize,
+	dual_stereo:   bool,
+	balance:       i32,
+	pulses:        [i32; NB_EBANDS],
+	fine_quant:    [i32; NB_EBANDS],
+	fine_priority: [i32; NB_EBANDS]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compute_allocation(
+	start:     usize,
+	end:       usize,
+	offsets:   &[i32; NB_EBANDS],
+	cap:       &[i32; NB_EBANDS],
+	alloc_trim: i32,
+	total:     i32,
+	c:         usize,
+	lm:        usize,
+	dec:       &mut RangeDecoder
+) -> Allocation {
+	let ci = c as i32;
+	let mut total = total.max(0);
+	let mut skip_start = start;
+	
+	// reserve a bit to signal the end of manually skipped bands
+	let skip_rsv = if total >= 1 << BITRES { 1 << BITRES } else { 0 };
+	total -= skip_rsv;
+	
+	// reserve bits for the intensity and dual stereo parameters
+	let (mut intensity_rsv, mut dual_stereo_rsv) = (0, 0);
+	if c == 2 {
+		intensity_rsv = LOG2_FRAC_TABLE[end - start] as i32;
+		if intensity_rsv > total {
+			intensity_rsv = 0;
+		} else {
+			total -= intensity_rsv;
+			dual_stereo_rsv = if total >= 1 << BITRES { 1 << BITRES } else { 0 };
+			total -= dual_stereo_rsv;
+		}
+	}
+	
+	let mut thresh = [0; NB_EBANDS];
+	let mut trim_offset = [0; NB_EBANDS];
+	for j in start..end {
+		let w = band_width(j) as i32;
+		// below this threshold, we're sure not to allocate any PVQ bits
+		thresh[j] = (ci << BITRES).max(((3 * w) << lm << BITRES) >> 4);
+		// tilt of the allocation curve
+		trim_offset[j] = (ci * w * (alloc_trim - 5 - lm as i32) * (end - j - 1) as i32 * (1 << (lm as u32 + BITRES))) >> 6;
+		// giving less resolution to single-coefficient bands because they get more benefit from
+		// having one coarse value per coefficient
+		if w << lm == 1 {
+			trim_offset[j] -= ci << BITRES;
+		}
+	}
+	
+	let alloc_bits = |vector: usize, j: usize| {
+		let bits = (ci * band_width(j) as i32 * (BAND_ALLOCATION[vector * NB_EBANDS + j] as i32)) << lm >> 2;
+		if bits > 0 { (bits + trim_offset[j]).max(0) } else { bits }
+	};
+	
+	let (mut lo, mut hi) = (1, NB_ALLOC_VECTORS - 1);
+	loop {
+		let mid = (lo + hi) >> 1;
+		let mut done = false;
+		let mut psum = 0;
+		for j in (start..end).rev() {
+			let bits = alloc_bits(mid, j) + offsets[j];
+			if bits >= thresh[j] || done {
+				done = true;
+				// don't allocate more than we can actually use
+				psum += bits.min(cap[j]);
+			} else if bits >= ci << BITRES {
+				psum += ci << BITRES;
+			}
+		}
+		
+		if psum > total {
+			hi = mid - 1;
+		} else {
+			lo = mid + 1;
+		}
+		
+		if lo > hi {
+			break;
+		}
+	}
+	
+	let hi = lo;
+	let lo = lo - 1;
+	let mut bits1 = [0; NB_EBANDS];
+	let mut bits2 = [0; NB_EBANDS];
+	for j in start..end {
+		let mut bits1j = alloc_bits(lo, j);
+		let mut bits2j = if hi >= NB_ALLOC_VECTORS { cap[j] } else { alloc_bits(hi, j) };
+		if lo > 0 {
+			bits1j += offsets[j];
+		}
+		bits2j += offsets[j];
+		if offsets[j] > 0 {
+			skip_start = j;
+		}
+		bits1[j] = bits1j;
+		bits2[j] = (bits2j - bits1j).max(0);
+	}
+	
+	// interpolate between the two allocation vectors
+	let alloc_floor = ci << BITRES;
+	let stereo = (c > 1) as i32;
+	let log_m = (lm as i32) << BITRES;
+	let (mut lo, mut hi) = (0, 1 << ALLOC_STEPS);
+	for _ in 0..ALLOC_STEPS {
+		let mid = (lo + hi) >> 1;
+		let mut psum = 0;
+		let mut done = false;
+		for j in (start..end).rev() {
+			let tmp = bits1[j] + ((mid * bits2[j]) >> ALLOC_STEPS);
+			if tmp >= thresh[j] || done {
+				done = true;
+				psum += tmp.min(cap[j]);
+			} else if tmp >= alloc_floor {
+				psum += alloc_floor;
+			}
+		}
+		
+		if psum > total {
+			hi = mid;
+		} else {
+			lo = mid;
+		}
+	}
+	
+	let mut psum = 0;
+	let mut done = false;
+	let mut bits = [0; NB_EBANDS];
+	for j in (start..end).rev() {
+		let mut tmp = bits1[j] + ((lo * bits2[j]) >> ALLOC_STEPS);
+		if tmp < thresh[j] && !done {
+			tmp = if tmp >= alloc_floor { alloc_floor } else { 0 };
+		} else {
+			done = true;
+		}
+		tmp = tmp.min(cap[j]);
+		bits[j] = tmp;
+		psum += tmp;
+	}
+	
+	// decide which bands to skip, working backwards from the end
+	let mut coded_bands = end;
+	loop {
+		let j = coded_bands - 1;
+		if j <= skip_start {
+			// give the bit we reserved to end skipping back
+			total += skip_rsv;
+			break;
+		}
+		
+		// figure out how many left-over bits we would be adding to this band
+		let mut left = total - psum;
+		let span = (EBANDS[coded_bands] - EBANDS[start]) as i32;
+		let percoeff = left / span;
+		left -= span * percoeff;
+		let rem = (left - (EBANDS[j] - EBANDS[start]) as i32).max(0);
+		let mut band_bits = bits[j] + percoeff * band_width(j) as i32 + rem;
+		
+		// only code a skip decision if we're above the threshold for this band
+		if band_bits >= thresh[j].max(alloc_floor + (1 << BITRES)) {
+			if dec.bit_logp(1) {
+				break;
+			}
+			
+			// we used a bit to skip this band
+			psum += 1 << BITRES;
+			band_bits -= 1 << BITRES;
+		}
+		
+		// reclaim the bits originally allocated to this band
+		psum -= bits[j] + intensity_rsv;
+		if intensity_rsv > 0 {
+			intensity_rsv = LOG2_FRAC_TABLE[j - start] as i32;
+		}
+		psum += intensity_rsv;
+		if band_bits >= alloc_floor {
+			// if we have enough for a fine energy bit per channel, use it
+			psum += alloc_floor;
+			bits[j] = alloc_floor;
+		} else {
+			// otherwise this band gets nothing at all
+			bits[j] = 0;
+		}
+		coded_bands -= 1;
+	}
+	
+	let intensity = if intensity_rsv > 0 {
+		start + dec.uint((coded_bands + 1 - start) as u32) as usize
+	} else {
+		0
+	};
+	
+	if intensity <= start {
+		total += dual_stereo_rsv;
+		dual_stereo_rsv = 0;
+	}
+	
+	let dual_stereo = dual_stereo_rsv > 0 && dec.bit_logp(1);
+	
+	// allocate the remaining bits
+	let mut left = total - psum;
+	let span = (EBANDS[coded_bands] - EBANDS[start]) as i32;
+	let percoeff = left / span;
+	left -= span * percoeff;
+	for (j, bits) in bits.iter_mut().enumerate().take(coded_bands).skip(start) {
+		*bits += percoeff * band_width(j) as i32;
+	}
+	for (j, bits) in bits.iter_mut().enumerate().take(coded_bands).skip(start) {
+		let tmp = left.min(band_width(j) as i32);
+		*bits += tmp;
+		left -= tmp;
+	}
+	
+	let mut fine_quant = [0; NB_EBANDS];
+	let mut fine_priority = [0; NB_EBANDS];
+	let mut balance = 0;
+	for j in start..coded_bands {
+		let n = (band_width(j) << lm) as i32;
+		let bit = bits[j] + balance;
+		let mut excess;
+		
+		if n > 1 {
+			excess = (bit - cap[j]).max(0);
+			bits[j] = bit - excess;
+			
+			// compensate for the extra degree of freedom in stereo
+			let den = ci * n + (c == 2 && n > 2 && !dual_stereo && j < intensity) as i32;
+			let nclogn = den * (LOG_N[j] as i32 + log_m);
+			
+			// offset for the number of fine bits by log2(N)/2 + FINE_OFFSET compared to their
+			// "fair share" of total/N
+			let mut offset = (nclogn >> 1) - den * FINE_OFFSET;
+			
+			// N=2 is the only point that doesn't match the curve
+			if n == 2 {
+				offset += den << BITRES >> 2;
+			}
+			
+			// changing the offset for allocating the second and third fine energy bit
+			if bits[j] + offset < (den * 2) << BITRES {
+				offset += nclogn >> 2;
+			} else if bits[j] + offset < (den * 3) << BITRES {
+				offset += nclogn >> 3;
+			}
+			
+			// divide with rounding
+			let mut ebits = (bits[j] + offset + (den << (BITRES - 1))).max(0);
+			ebits = (ebits / den) >> BITRES;
+			
+			// make sure not to bust
+			if ci * ebits > bits[j] >> BITRES {
+				ebits = bits[j] >> stereo >> BITRES;
+			}
+			
+			// more than that is useless because that's about as far as PVQ can go
+			ebits = ebits.min(MAX_FINE_BITS);
+			
+			// if we rounded down or capped this band, make it a candidate for the final fine
+			// energy pass
+			fine_priority[j] = (ebits * (den << BITRES) >= bits[j] + offset) as i32;
+			
+			// remove the allocated fine bits; the rest are assigned to PVQ
+			bits[j] -= (ci * ebits) << BITRES;
+			fine_quant[j] = ebits;
+		} else {
+			// for N=1, all bits go to fine energy except for a single sign bit
+			excess = (bit - (ci << BITRES)).max(0);
+			bits[j] = bit - excess;
+			fine_quant[j] = 0;
+			fine_priority[j] = 1;
+		}
+		
+		// fine energy can't take advantage of the re-balancing in quant_all_bands, so we
+		// re-balance ourselves
+		if excess > 0 {
+			let extra_fine = (excess >> (stereo + BITRES as i32)).min(MAX_FINE_BITS - fine_quant[j]);
+			fine_quant[j] += extra_fine;
+			let extra_bits = (extra_fine * ci) << BITRES;
+			fine_priority[j] = (extra_bits >= excess - balance) as i32;
+			excess -= extra_bits;
+		}
+		balance = excess;
+	}
+	
+	// the skipped bands use all their bits for fine energy
+	for j in coded_bands..end {
+		fine_quant[j] = bits[j] >> stereo >> BITRES;
+		bits[j] = 0;
+		fine_priority[j] = (fine_quant[j] < 1) as i32;
+	}
+	
+	Allocation { coded_bands, intensity, dual_stereo, balance, pulses: bits, fine_quant, fine_priority }
+}
+
+/// The results of decoding the split angle of a band.
+struct Split {
+	inv:    bool,
+	imid:   i32,
+	iside:  i32,
+	delta:  i32,
+	itheta: i32,
+	qalloc: i32
+}
+
+/// The state shared by the recursive decoding of the bands of a frame.
+struct Bands<'a, 'b> {
+	dec:               &'a mut RangeDecoder<'b>,
+	/// The index of the current band.
+	i:                 usize,
+	lm:                usize,
+	/// The number of short blocks of the frame.
+	blocks:            usize,
+	intensity:         usize,
+	spread:            usize,
+	tf_change:         i32,
+	remaining_bits:    i32,
+	seed:              u32,
+	disable_inv:       bool,
+	avoid_split_noise: bool
+}
+
+impl Bands<'_, '_> {
+	fn quant_band_n1(&mut self, x: &mut [f32], y: Option<&mut [f32]>, lowband_out: Option<&mut [f32]>) -> u32 {
+		let mut decode_sign = |x: &mut [f32]| {
+			let mut sign = 0;
+			if self.remaining_bits >= 1 << BITRES {
+				sign = self.dec.bits(1);
+				self.remaining_bits -= 1 << BITRES;
+			}
+			x[0] = if sign != 0 { -1.0 } else { 1.0 };
+		};
+		
+		decode_sign(x);
+		if let Some(y) = y {
+			decode_sign(y);
+		}
+		
+		if let Some(out) = lowband_out {
+			out[0] = x[0];
+		}
+		1
+	}
+	
+	#[allow(clippy::too_many_arguments)]
+	fn compute_theta(
+		&mut self,
+		n:      usize,
+		b:      &mut i32,
+		blocks: usize,
+		b0:     usize,
+		lm:     i32,
+		stereo: bool,
+		fill:   &mut u32
+	) -> Split {
+		let i = self.i;
+		let pulse_cap = LOG_N[i] as i32 + lm * (1 << BITRES);
+		let offset = (pulse_cap >> 1) - if stereo && n == 2 { QTHETA_OFFSET_TWOPHASE } else { QTHETA_OFFSET };
+		let mut qn = compute_qn(n, *b, offset, pulse_cap, stereo);
+		if stereo && i >= self.intensity {
+			qn = 1;
+		}
+		
+		let tell = self.dec.tell_frac() as i32;
+		let mut itheta = 0;
+		let mut inv = false;
+		if qn != 1 {
+			if stereo && n > 2 {
+				// step PDF
+				let p0 = 3;
+				let x0 = qn / 2;
+				let ft = p0 * (x0 + 1) + x0;
+				let fs = self.dec.decode(ft as u32) as i32;
+				let x = if fs < (x0 + 1) * p0 { fs / p0 } else { x0 + 1 + (fs - (x0 + 1) * p0) };
+				let (fl, fh) = if x <= x0 {
+					(p0 * x, p0 * (x + 1))
+				} else {
+					((x - 1 - x0) + (x0 + 1) * p0, (x - x0) + (x0 + 1) * p0)
+				};
+				self.dec.update(fl as u32, fh as u32, ft as u32);
+				itheta = x;
+			} else if b0 > 1 || stereo {
+				// uniform PDF
+				itheta = self.dec.uint(qn as u32 + 1) as i32;
+			} else {
+				// triangular PDF
+				let ft = ((qn >> 1) + 1) * ((qn >> 1) + 1);
+				let fm = self.dec.decode(ft as u32) as i32;
+				let (fs, fl);
+				if fm < (((qn >> 1) * ((qn >> 1) + 1)) >> 1) {
+					itheta = (isqrt32(8 * fm as u32 + 1) as i32 - 1) >> 1;
+					fs = itheta + 1;
+					fl = (itheta * (itheta + 1)) >> 1;
+				} else {
+					itheta = (2 * (qn + 1) - isqrt32(8 * (ft - fm - 1) as u32 + 1) as i32) >> 1;
+					fs = qn + 1 - itheta;
+					fl = ft - (((qn + 1 - itheta) * (qn + 2 - itheta)) >> 1);
+				}
+				self.dec.update(fl as u32, (fl + fs) as u32, ft as u32);
+			}
+			itheta = itheta * 16384 / qn;
+		} else if stereo {
+			if *b > 2 << BITRES && self.remaining_bits > 2 << BITRES {
+				inv = self.dec.bit_logp(2);
+			}
+			if self.disable_inv {
+				inv = false;
+			}
+		}
+		
+		let qalloc = self.dec.tell_frac() as i32 - tell;
+		*b -= qalloc;
+		
+		let (imid, iside, delta) = match itheta {
+			0 => {
+				*fill &= (1 << blocks) - 1;
+				(32767, 0, -16384)
+			}
+			16384 => {
+				*fill &= ((1 << blocks) - 1) << blocks;
+				(0, 32767, 16384)
+			}
+			_ => {
+				let imid = bitexact_cos(itheta);
+				let iside = bitexact_cos(16384 - itheta);
+				(imid, iside, frac_mul16((n as i32 - 1) << 7, bitexact_log2tan(iside, imid)))
+			}
+		};
+		
+		Split { inv, imid, iside, delta, itheta, qalloc }
+	}
+	
+	/// Decodes a band using the pulse vector quantizer, recursively splitting it in halves if
+	/// there are too many bits for a single codebook.
+	#[allow(clippy::too_many_arguments)]
+	fn quant_partition(
+		&mut self,
+		x:       &mut [f32],
+		mut b:   i32,
+		blocks:  usize,
+		lowband: Option<&[f32]>,
+		lm:      i32,
+		gain:    f32,
+		fill:    u32
+	) -> u32 {
+		let n = x.len();
+		let i = self.i;
+		let mut fill = fill;
+		
+		if lm != -1 && n > 2 && b > { let cache = cache_bits(lm, i); cache[cache[0] as usize] as i32 + 12 } {
+			let b0 = blocks;
+			let n = n >> 1;
+			let (x, y) = x.split_at_mut(n);
+			let lm = lm - 1;
+			if blocks == 1 {
+				fill = (fill & 1) | (fill << 1);
+			}
+			let blocks = (blocks + 1) >> 1;
+			
+			let split = self.compute_theta(n, &mut b, blocks, b0, lm, false, &mut fill);
+			let mid = split.imid as f32 / 32768.0;
+			let side = split.iside as f32 / 32768.0;
+			let mut delta = split.delta;
+			
+			// give more bits to low-energy MDCTs than they would otherwise deserve
+			if b0 > 1 && split.itheta & 0x3fff != 0 {
+				if split.itheta > 8192 {
+					// rough approximation for pre-echo masking
+					delta -= delta >> (4 - lm);
+				} else {
+					// corresponds to a forward-masking slope of 1.5 dB per 10 ms
+					delta = (delta + ((n as i32) << BITRES >> (5 - lm))).min(0);
+				}
+			}
+			
+			let mut mbits = ((b - delta) / 2).min(b).max(0);
+			let mut sbits = b - mbits;
+			self.remaining_bits -= split.qalloc;
+			
+			let next_lowband2 = lowband.map(|l| &l[n..]);
+			let mut rebalance = self.remaining_bits;
+			if mbits >= sbits {
+				let mut cm = self.quant_partition(x, mbits, blocks, lowband, lm, gain * mid, fill);
+				rebalance = mbits - (rebalance - self.remaining_bits);
+				if rebalance > 3 << BITRES && split.itheta != 0 {
+					sbits += rebalance - (3 << BITRES);
+				}
+				cm |= self.quant_partition(y, sbits, blocks, next_lowband2, lm, gain * side, fill >> blocks) << (b0 >> 1);
+				cm
+			} else {
+				let mut cm = self.quant_partition(y, sbits, blocks, next_lowband2, lm, gain * side, fill >> blocks) << (b0 >> 1);
+				rebalance = sbits - (rebalance - self.remaining_bits);
+				if rebalance > 3 << BITRES && split.itheta != 16384 {
+					mbits += rebalance - (3 << BITRES);
+				}
+				cm |= self.quant_partition(x, mbits, blocks, lowband, lm, gain * mid, fill);
+				cm
+			}
+		} else {
+			// basic no-split case
+			let mut q = bits2pulses(i, lm, b);
+			let mut curr_bits = pulses2bits(i, lm, q);
+			self.remaining_bits -= curr_bits;
+			
+			// ensures we can never bust the budget
+			while self.remaining_bits < 0 && q > 0 {
+				self.remaining_bits += curr_bits;
+				q -= 1;
+				curr_bits = pulses2bits(i, lm, q);
+				self.remaining_bits -= curr_bits;
+			}
+			
+			if q != 0 {
+				let k = get_pulses(q);
+				let mut iy = [0; MAX_BAND_SIZE];
+				let iy = &mut iy[..n];
+				let ryy = decode_pulses(iy, k, self.dec);
+				let g = gain / ryy.sqrt();
+				for (v, &p) in x.iter_mut().zip(iy.iter()) {
+					*v = g * p as f32;
+				}
+				exp_rotation(x, blocks, k, self.spread);
+				
+				// extract the collapse mask
+				if blocks <= 1 {
+					return 1;
+				}
+				let n0 = n / blocks;
+				iy.chunks_exact(n0)
+					.enumerate()
+					.fold(0, |mask, (i, p)| mask | ((p.iter().any(|&v| v != 0) as u32) << i))
+			} else {
+				// if there's no pulse, fill the band anyway
+				let cm_mask = (1u32 << blocks) - 1;
+				fill &= cm_mask;
+				if fill == 0 {
+					x.fill(0.0);
+					return 0;
+				}
+				
+				let cm = match lowband {
+					None => {
+						// noise
+						for v in x.iter_mut() {
+							self.seed = lcg_rand(self.seed);
+							*v = (self.seed as i32 >> 20) as f32;
+						}
+						cm_mask
+					}
+					Some(lowband) => {
+						// folded spectrum
+						for (v, l) in x.iter_mut().zip(lowband) {
+							self.seed = lcg_rand(self.seed);
+							// about 48 dB below the "normal" folding level
+							let tmp = if self.seed & 0x8000 != 0 { 1.0 / 256.0 } else { -1.0 / 256.0 };
+							*v = l + tmp;
+						}
+						fill
+					}
+				};
+				renormalise_vector(x, gain);
+				cm
+			}
+		}
+	}
+	
+	/// Decodes a mono band, or one channel of a dual stereo band.
+	fn quant_band(
+		&mut self,
+		x:           &mut [f32],
+		b:           i32,
+		lowband:     Option<&mut [f32]>,
+		lowband_out: Option<&mut [f32]>,
+		gain:        f32,
+		fill:        u32
+	) -> u32 {
+		let n0 = x.len();
+		let long_blocks = self.blocks == 1;
+		let mut blocks = self.blocks;
+		let mut n_b = n0 / blocks;
+		let mut fill = fill;
+		let mut lowband = lowband;
+		
+		if n0 == 1 {
+			return self.quant_band_n1(x, None, lowband_out);
+		}
+		
+		let mut tf_change = self.tf_change;
+		let recombine = tf_change.max(0) as usize;
+		
+		// band recombining to increase frequency resolution
+		for k in 0..recombine {
+			if let Some(lowband) = lowband.as_deref_mut() {
+				haar1(lowband, n0 >> k, 1 << k);
+			}
+			fill = BIT_INTERLEAVE[(fill & 0xF) as usize] as u32 | (BIT_INTERLEAVE[(fill >> 4) as usize] as u32) << 2;
+		}
+		blocks >>= recombine;
+		n_b <<= recombine;
+		
+		// increasing the time resolution
+		let mut time_divide = 0;
+		while n_b & 1 == 0 && tf_change < 0 {
+			if let Some(lowband) = lowband.as_deref_mut() {
+				haar1(lowband, n_b, blocks);
+			}
+			fill |= fill << blocks;
+			blocks <<= 1;
+			n_b >>= 1;
+			time_divide += 1;
+			tf_change += 1;
+		}
+		
+		let b0 = blocks;
+		let n_b0 = n_b;
+		
+		// reorganize the samples in time order instead of frequency order
+		if b0 > 1 {
+			if let Some(lowband) = lowband.as_deref_mut() {
+				deinterleave_hadamard(lowband, n_b >> recombine, b0 << recombine, long_blocks);
+			}
+		}
+		
+		let mut cm = self.quant_partition(x, b, blocks, lowband.as_deref(), self.lm as i32, gain, fill);
+		
+		// undo the sample reorganization going from time order to frequency order
+		if b0 > 1 {
+			interleave_hadamard(x, n_b >> recombine, b0 << recombine, long_blocks);
+		}
+		
+		// undo time-freq changes that we did earlier
+		let mut n_b = n_b0;
+		let mut blocks = b0;
+		for _ in 0..time_divide {
+			blocks >>= 1;
+			n_b <<= 1;
+			cm |= cm >> blocks;
+			haar1(x, n_b, blocks);
+		}
+		
+		for k in 0..recombine {
+			cm = BIT_DEINTERLEAVE[cm as usize] as u32;
+			haar1(x, n0 >> k, 1 << k);
+		}
+		blocks <<= recombine;
+		
+		// scale the output for later folding
+		if let Some(out) = lowband_out {
+			let n = (n0 as f32).sqrt();
+			for (o, v) in out.iter_mut().zip(x.iter()) {
+				*o = n * v;
+			}
+		}
+		
+		cm & ((1 << blocks) - 1)
+	}
+	
+	/// Decodes a stereo band coded as mid and side.
+	fn quant_band_stereo(
+		&mut self,
+		x:           &mut [f32],
+		y:           &mut [f32],
+		b:           i32,
+		lowband:     Option<&mut [f32]>,
+		lowband_out: Option<&mut [f32]>,
+		fill:        u32
+	) -> u32 {
+		let n = x.len();
+		if n == 1 {
+			return self.quant_band_n1(x, Some(y), lowband_out);
+		}
+		
+		let orig_fill = fill;
+		let mut fill = fill;
+		let mut b = b;
+		let split = self.compute_theta(n, &mut b, self.blocks, self.blocks, self.lm as i32, true, &mut fill);
+		let mid = split.imid as f32 / 32768.0;
+		let side = split.iside as f32 / 32768.0;
+		let mut cm;
+		
+		if n == 2 {
+			// special case for N=2 that only works for stereo and takes advantage of the fact
+			// that mid and side are orthogonal
+			let sbits = if split.itheta != 0 && split.itheta != 16384 { 1 << BITRES } else { 0 };
+			let mbits = b - sbits;
+			self.remaining_bits -= split.qalloc + sbits;
+			let sign = if sbits != 0 { self.dec.bits(1) } else { 0 };
+			let sign = 1.0 - 2.0 * sign as f32;
+			
+			let (x2, y2) = if split.itheta > 8192 { (&mut *y, &mut *x) } else { (&mut *x, &mut *y) };
+			cm = self.quant_band(x2, mbits, lowband, lowband_out, 1.0, orig_fill);
+			y2[0] = -sign * x2[1];
+			y2[1] = sign * x2[0];
+			
+			for j in 0..2 {
+				let l = mid * x[j];
+				let r = side * y[j];
+				x[j] = l - r;
+				y[j] = l + r;
+			}
+		} else {
+			let mut mbits = ((b - split.delta) / 2).min(b).max(0);
+			let mut sbits = b - mbits;
+			self.remaining_bits -= split.qalloc;
+			
+			let mut rebalance = self.remaining_bits;
+			if mbits >= sbits {
+				cm = self.quant_band(x, mbits, lowband, lowband_out, 1.0, fill);
+				rebalance = mbits - (rebalance - self.remaining_bits);
+				if rebalance > 3 << BITRES && split.itheta != 0 {
+					sbits += rebalance - (3 << BITRES);
+				}
+				cm |= self.quant_band(y, sbits, None, None, side, fill >> self.blocks);
+			} else {
+				cm = self.quant_band(y, sbits, None, None, side, fill >> self.blocks);
+				rebalance = sbits - (rebalance - self.remaining_bits);
+				if rebalance > 3 << BITRES && split.itheta != 16384 {
+					mbits += rebalance - (3 << BITRES);
+				}
+				cm |= self.quant_band(x, mbits, lowband, lowband_out, 1.0, fill);
+			}
+			stereo_merge(x, y, mid);
+		}
+		
+		if split.inv {
+			y.iter_mut().for_each(|v| *v = -*v);
+		}
+		cm
+	}
+	
+	/// Decodes the normalized spectrum of all bands (section 4.3.4).
+	#[allow(clippy::too_many_arguments)]
+	fn quant_all_bands(
+		&mut self,
+		start:          usize,
+		end:            usize,
+		x:              &mut [f32],
+		collapse_masks: &mut [u8],
+		alloc:          &Allocation,
+		tf_res:         &[i32; NB_EBANDS],
+		total_bits:     i32
+	) {
+		let m = 1 << self.lm;
+		let c = if x.len() > m * SHORT_MDCT_SIZE { 2 } else { 1 };
+		let (x, mut y) = match c {
+			2 => {
+				let (x, y) = x.split_at_mut(m * SHORT_MDCT_SIZE);
+				(x, Some(y))
+			}
+			_ => (x, None)
+		};
+		
+		let norm_offset = m * EBANDS[start] as usize;
+		let norm_len = m * EBANDS[NB_EBANDS - 1] as usize - norm_offset;
+		let mut norm = vec![0f32; c * norm_len];
+		let mut lowband_offset = 0;
+		let mut update_lowband = true;
+		let mut dual_stereo = alloc.dual_stereo;
+		let mut balance = alloc.balance;
+		self.avoid_split_noise = self.blocks > 1;
+		
+		for i in start..end {
+			self.i = i;
+			let last = i == end - 1;
+			let lo = m * EBANDS[i] as usize;
+			let hi = m * EBANDS[i + 1] as usize;
+			let n = hi - lo;
+			let tell = self.dec.tell_frac() as i32;
+			
+			// compute how many bits we want to allocate to this band
+			if i != start {
+				balance -= tell;
+			}
+			let remaining_bits = total_bits - tell - 1;
+			self.remaining_bits = remaining_bits;
+			let b = if i < alloc.coded_bands {
+				let curr_balance = balance / (alloc.coded_bands - i).min(3) as i32;
+				(remaining_bits + 1).min(alloc.pulses[i] + curr_balance).clamp(0, 16383)
+			} else {
+				0
+			};
+			
+			if (lo >= n + m * EBANDS[start] as usize || i == start + 1) && (update_lowband || lowband_offset == 0) {
+				lowband_offset = i;
+			}
+			
+			if i == start + 1 {
+				// duplicate enough of the first band folding data to be able to fold the second band
+				let n1 = m * band_width(start);
+				let n2 = m * band_width(start + 1);
+				if n2 > n1 {
+					norm.copy_within(2 * n1 - n2..n1, n1);
+					if dual_stereo {
+						norm.copy_within(norm_len + 2 * n1 - n2..norm_len + n1, norm_len + n1);
+					}
+				}
+			}
+			
+			self.tf_change = tf_res[i];
+			
+			// get a conservative estimate of the collapse mask of the bands we're going to be
+			// folding from
+			let mut effective_lowband = None;
+			let (mut x_cm, mut y_cm);
+			if lowband_offset != 0 && (self.spread != SPREAD_AGGRESSIVE || self.blocks > 1 || self.tf_change < 0) {
+				let eff = (m * EBANDS[lowband_offset] as usize).saturating_sub(norm_offset + n);
+				let mut fold_start = lowband_offset - 1;
+				while m * EBANDS[fold_start] as usize > eff + norm_offset {
+					fold_start -= 1;
+				}
+				let mut fold_end = lowband_offset;
+				while fold_end < i && (m * EBANDS[fold_end] as usize) < eff + norm_offset + n {
+					fold_end += 1;
+				}
+				
+				x_cm = 0;
+				y_cm = 0;
+				for f in fold_start..fold_end {
+					x_cm |= collapse_masks[f * c] as u32;
+					y_cm |= collapse_masks[f * c + c - 1] as u32;
+				}
+				effective_lowband = Some(eff);
+			} else {
+				// we can't use the collapse masks of the folded bands, assume the worst
+				x_cm = (1 << self.blocks) - 1;
+				y_cm = x_cm;
+			}
+			
+			if dual_stereo && i == self.intensity {
+				// switch off dual stereo to do intensity
+				dual_stereo = false;
+				let (norm, norm2) = norm.split_at_mut(norm_len);
+				for (a, b) in norm[..lo - norm_offset].iter_mut().zip(norm2.iter()) {
+					*a = 0.5 * (*a + b);
+				}
+			}
+			
+			// the folding source is copied, as it is modified in place and may overlap the output
+			let out = lo - norm_offset;
+			let mut lowband_buf = [0f32; MAX_BAND_SIZE];
+			let x = &mut x[lo..hi];
+			
+			if dual_stereo {
+				let y = &mut y.as_deref_mut().unwrap()[lo..hi];
+				let (norm, norm2) = norm.split_at_mut(norm_len);
+				let lowband = copy_lowband(&mut lowband_buf, norm, effective_lowband, n);
+				x_cm = self.quant_band(x, b / 2, lowband, (!last).then(|| &mut norm[out..out + n]), 1.0, x_cm);
+				let lowband = copy_lowband(&mut lowband_buf, norm2, effective_lowband, n);
+				y_cm = self.quant_band(y, b / 2, lowband, (!last).then(|| &mut norm2[out..out + n]), 1.0, y_cm);
+			} else {
+				let lowband = copy_lowband(&mut lowband_buf, &norm, effective_lowband, n);
+				let lowband_out = (!last).then(|| &mut norm[out..out + n]);
+				x_cm = match y.as_deref_mut() {
+					Some(y) => self.quant_band_stereo(x, &mut y[lo..hi], b, lowband, lowband_out, x_cm | y_cm),
+					None    => self.quant_band(x, b, lowband, lowband_out, 1.0, x_cm | y_cm)
+				};
+				y_cm = x_cm;
+			}
+			
+			collapse_masks[i * c] = x_cm as u8;
+			collapse_masks[i * c + c - 1] = y_cm as u8;
+			balance += alloc.pulses[i] + tell;
+			
+			// update the folding position only as long as we have 1 bit/sample depth
+			update_lowband = b > (n << BITRES) as i32;
+			self.avoid_split_noise = false;
+		}
+	}
+}
+
+fn unquant_coarse_energy(
+	old_e: &mut [f32; 2 * NB_EBANDS],
+	start: usize,
+	end:   usize,
+	intra: bool,
+	dec:   &mut RangeDecoder,
+	c:     usize,
+	lm:    usize
+) {
+	let prob = &E_PROB_MODEL[lm][intra as usize];
+	let (coef, beta) = if intra { (0.0, BETA_INTRA) } else { (PRED_COEF[lm], BETA_COEF[lm]) };
+	let budget = dec.storage() as i32 * 8;
+	let mut prev = [0f32; 2];
+	
+	for i in start..end {
+		for (ch, prev) in prev.iter_mut().enumerate().take(c) {
+			let tell = dec.tell();
+			let qi = if budget - tell >= 15 {
+				let pi = 2 * i.min(20);
+				dec.laplace((prob[pi] as u32) << 7, (prob[pi + 1] as u32) << 6)
+			} else if budget - tell >= 2 {
+				let qi = dec.icdf(&SMALL_ENERGY_ICDF, 2) as i32;
+				(qi >> 1) ^ -(qi & 1)
+			} else if budget - tell >= 1 {
+				-(dec.bit_logp(1) as i32)
+			} else {
+				-1
+			};
+			
+			let q = qi as f32;
+			let e = &mut old_e[i + ch * NB_EBANDS];
+			*e = e.max(-9.0);
+			*e = coef * *e + *prev + q;
+			*prev = *prev + q - beta * q;
+		}
+	}
+}
+
+fn unquant_fine_energy(
+	old_e:      &mut [f32; 2 * NB_EBANDS],
+	start:      usize,
+	end:        usize,
+	fine_quant: &[i32; NB_EBANDS],
+	dec:        &mut RangeDecoder,
+	c:          usize
+) {
+	for i in start..end {
+		if fine_quant[i] <= 0 {
+			continue;
+		}
+		
+		for ch in 0..c {
+			let q2 = dec.bits(fine_quant[i] as u32);
+			let offset = (q2 as f32 + 0.5) * (1 << (14 - fine_quant[i])) as f32 * (1.0 / 16384.0) - 0.5;
+			old_e[i + ch * NB_EBANDS] += offset;
+		}
+	}
+}
+
+fn unquant_energy_finalise(
+	old_e:     &mut [f32; 2 * NB_EBANDS],
+	start:     usize,
+	end:       usize,
+	alloc:     &Allocation,
+	bits_left: i32,
+	dec:       &mut RangeDecoder,
+	c:         usize
+) {
+	let mut bits_left = bits_left;
+	for prio in 0..2 {
+		for i in start..end {
+			if bits_left < c as i32 {
+				break;
+			}
+			
+			if alloc.fine_quant[i] >= MAX_FINE_BITS || alloc.fine_priority[i] != prio {
+				continue;
+			}
+			
+			for ch in 0..c {
+				let q2 = dec.bits(1);
+				let offset = (q2 as f32 - 0.5) * (1 << (14 - alloc.fine_quant[i] - 1)) as f32 * (1.0 / 16384.0);
+				old_e[i + ch * NB_EBANDS] += offset;
+				bits_left -= 1;
+			}
+		}
+	}
+}
+
+/// Decodes the time-frequency resolution changes of each band.
+fn tf_decode(start: usize, end: usize, transient: bool, lm: usize, dec: &mut RangeDecoder) -> [i32; NB_EBANDS] {
+	let mut tf_res = [0; NB_EBANDS];
+	let mut budget = dec.storage() as i32 * 8;
+	let mut tell = dec.tell();
+	let mut logp = if transient { 2 } else { 4 };
+	let tf_select_rsv = lm > 0 && tell + (logp as i32) < budget;
+	budget -= tf_select_rsv as i32;
+	
+	let (mut tf_changed, mut curr) = (0, 0);
+	for res in &mut tf_res[start..end] {
+		if tell + logp as i32 <= budget {
+			curr ^= dec.bit_logp(logp) as i32;
+			tell = dec.tell();
+			tf_changed |= curr;
+		}
+		*res = curr;
+		logp = if transient { 4 } else { 5 };
+	}
+	
+	let t = 4 * transient as usize;
+	let table = &TF_SELECT_TABLE[lm];
+	let tf_changed = tf_changed as usize;
+	let tf_select = if tf_select_rsv && table[t + tf_changed] != table[t + 2 + tf_changed] {
+		dec.bit_logp(1) as usize
+	} else {
+		0
+	};
+	
+	for res in &mut tf_res[start..end] {
+		*res = table[t + 2 * tf_select + *res as usize] as i32;
+	}
+	tf_res
+}
+
+/// Multiplies the normalized spectrum with the band energies.
+fn denormalise_bands(x: &[f32], freq: &mut [f32], band_log_e: &[f32], start: usize, end: usize, m: usize, silence: bool) {
+	let n = m * SHORT_MDCT_SIZE;
+	let (start, end) = if silence { (0, 0) } else { (start, end) };
+	let bound = m * EBANDS[end] as usize;
+	
+	freq[..m * EBANDS[start] as usize].fill(0.0);
+	for i in start..end {
+		let band = m * EBANDS[i] as usize..m * EBANDS[i + 1] as usize;
+		let g = exp2((band_log_e[i] + E_MEANS[i]).min(32.0));
+		for (f, v) in freq[band.clone()].iter_mut().zip(&x[band]) {
+			*f = v * g;
+		}
+	}
+	freq[bound..n].fill(0.0);
+}
+
+/// A CELT decoder for the 48 kHz mode with 2.5 to 20 ms frames.
+pub struct CeltDecoder {
+	channels:                 usize,
+	/// The number of channels in the stream, may differ from the number of output channels.
+	pub stream_channels:      usize,
+	/// The first band to decode, 17 for hybrid frames.
+	pub start:                usize,
+	/// The end band, which depends on the bandwidth.
+	pub end:                  usize,
+	disable_inv:              bool,
+	rng:                      u32,
+	loss_count:               u32,
+	postfilter_period:        usize,
+	postfilter_period_old:    usize,
+	postfilter_gain:          f32,
+	postfilter_gain_old:      f32,
+	postfilter_tapset:        usize,
+	postfilter_tapset_old:    usize,
+	preemph_mem:              [f32; 2],
+	decode_mem:               [Vec<f32>; 2],
+	old_band_e:               [f32; 2 * NB_EBANDS],
+	old_log_e:                [f32; 2 * NB_EBANDS],
+	old_log_e2:               [f32; 2 * NB_EBANDS],
+	background_log_e:         [f32; 2 * NB_EBANDS],
+	window:                   [f32; OVERLAP],
+	mdct:                     Mdct
+}
+
+impl CeltDecoder {
+	pub fn new(channels: usize) -> Self {
+		let mut window = [0f32; OVERLAP];
+		for (i, w) in window.iter_mut().enumerate() {
+			let s = (0.5 * PI * (i as f64 + 0.5) / OVERLAP as f64).sin();
+			*w = (0.5 * PI * s * s).sin() as f32;
+		}
+		
+		let mut dec = Self {
+			channels,
+			stream_channels:       channels,
+			start:                 0,
+			end:                   NB_EBANDS,
+			disable_inv:           channels == 1,
+			rng:                   0,
+			loss_count:            0,
+			postfilter_period:     0,
+			postfilter_period_old: 0,
+			postfilter_gain:       0.0,
+			postfilter_gain_old:   0.0,
+			postfilter_tapset:     0,
+			postfilter_tapset_old: 0,
+			preemph_mem:           [0.0; 2],
+			decode_mem:            [
+				vec![0.0; DECODE_BUFFER_SIZE + OVERLAP],
+				vec![0.0; DECODE_BUFFER_SIZE + OVERLAP]
+			],
+			old_band_e:            [0.0; 2 * NB_EBANDS],
+			old_log_e:             [0.0; 2 * NB_EBANDS],
+			old_log_e2:            [0.0; 2 * NB_EBANDS],
+			background_log_e:      [0.0; 2 * NB_EBANDS],
+			window,
+			mdct:                  Mdct::new()
+		};
+		dec.reset();
+		dec
+	}
+	
+	/// Resets the decoder state, the band range and channels are kept.
+	pub fn reset(&mut self) {
+		self.rng = 0;
+		self.loss_count = 0;
+		self.postfilter_period = 0;
+		self.postfilter_period_old = 0;
+		self.postfilter_gain = 0.0;
+		self.postfilter_gain_old = 0.0;
+		self.postfilter_tapset = 0;
+		self.postfilter_tapset_old = 0;
+		self.preemph_mem = [0.0; 2];
+		self.decode_mem.iter_mut().for_each(|mem| mem.fill(0.0));
+		self.old_band_e = [0.0; 2 * NB_EBANDS];
+		self.old_log_e = [-28.0; 2 * NB_EBANDS];
+		self.old_log_e2 = [-28.0; 2 * NB_EBANDS];
+		self.background_log_e = [0.0; 2 * NB_EBANDS];
+	}
+	
+	/// Returns the final state of the range decoder of the last frame.
+	pub fn final_range(&self) -> u32 {
+		self.rng
+	}
+	
+	/// Returns the window of the MDCT overlap, which is also used to fade between frames of different modes.
+	pub fn window(&self) -> &[f32; OVERLAP] {
+		&self.window
+	}
+	
+	/// Decodes a frame of `frame_size` samples per channel (120, 240, 480 or 960) and writes the
+	/// interleaved samples to `pcm`. If `dec` is `None` or contains less than two bytes, the frame
+	/// is concealed. Returns the number of samples per channel, or `None` if the frame size is
+	/// invalid.
+	pub fn decode(&mut self, dec: Option<&mut RangeDecoder>, pcm: &mut [f32], frame_size: usize) -> Option<usize> {
+		let lm = (0..=MAX_LM).find(|&lm| SHORT_MDCT_SIZE << lm == frame_size)?;
+		let m = 1 << lm;
+		let n = frame_size;
+		let cc = self.channels;
+		let c = self.stream_channels;
+		let (start, end) = (self.start, self.end);
+		let eff_end = end.min(NB_EBANDS);
+		
+		let dec = match dec {
+			Some(dec) if dec.storage() > 1 => dec,
+			_ => {
+				self.decode_lost(n, lm);
+				self.deemphasis(pcm, n);
+				return Some(n);
+			}
+		};
+		
+		let len = dec.storage() as i32;
+		if c == 1 {
+			for i in 0..NB_EBANDS {
+				self.old_band_e[i] = self.old_band_e[i].max(self.old_band_e[NB_EBANDS + i]);
+			}
+		}
+		
+		let mut total_bits = len * 8;
+		let mut tell = dec.tell();
+		let silence = if tell >= total_bits {
+			true
+		} else if tell == 1 {
+			dec.bit_logp(15)
+		} else {
+			false
+		};
+		
+		if silence {
+			// pretend we've read all the remaining bits
+			dec.skip_remaining();
+			tell = len * 8;
+		}
+		
+		let mut postfilter_gain = 0.0;
+		let mut postfilter_pitch = 0;
+		let mut postfilter_tapset = 0;
+		if start == 0 && tell + 16 <= total_bits {
+			if dec.bit_logp(1) {
+				let octave = dec.uint(6);
+				postfilter_pitch = ((16 << octave) + dec.bits(4 + octave) - 1) as usize;
+				let qg = dec.bits(3);
+				if dec.tell() + 2 <= total_bits {
+					postfilter_tapset = dec.icdf(&TAPSET_ICDF, 2);
+				}
+				postfilter_gain = 0.09375 * (qg + 1) as f32;
+			}
+			tell = dec.tell();
+		}
+		
+		let transient = if lm > 0 && tell + 3 <= total_bits {
+			let transient = dec.bit_logp(3);
+			tell = dec.tell();
+			transient
+		} else {
+			false
+		};
+		
+		// decode the global flags (first symbols in the stream)
+		let intra = tell + 3 <= total_bits && dec.bit_logp(3);
+		
+		// get band energies
+		unquant_coarse_energy(&mut self.old_band_e, start, end, intra, dec, c, lm);
+		
+		let tf_res = tf_decode(start, end, transient, lm, dec);
+		
+		let spread = if dec.tell() + 4 <= total_bits { dec.icdf(&SPREAD_ICDF, 5) } else { SPREAD_NORMAL };
+		
+		let mut cap = [0; NB_EBANDS];
+		for (i, cap) in cap.iter_mut().enumerate() {
+			let n = (band_width(i) << lm) as i32;
+			*cap = ((CACHE_CAPS[NB_EBANDS * (2 * lm + c - 1) + i] as i32 + 64) * c as i32 * n) >> 2;
+		}
+		
+		let mut offsets = [0; NB_EBANDS];
+		let mut dynalloc_logp = 6;
+		total_bits <<= BITRES;
+		let mut tell = dec.tell_frac() as i32;
+		for i in start..end {
+			let width = ((c * band_width(i)) << lm) as i32;
+			// quanta is 6 bits, but no more than 1 bit/sample and no less than 1/8 bit/sample
+			let quanta = (width << BITRES).min((6 << BITRES).max(width));
+			let mut dynalloc_loop_logp = dynalloc_logp;
+			let mut boost = 0;
+			while tell + (dynalloc_loop_logp << BITRES) < total_bits && boost < cap[i] {
+				let flag = dec.bit_logp(dynalloc_loop_logp as u32);
+				tell = dec.tell_frac() as i32;
+				if !flag {
+					break;
+				}
+				boost += quanta;
+				total_bits -= quanta;
+				dynalloc_loop_logp = 1;
+			}
+			offsets[i] = boost;
+			
+			// making dynalloc more likely
+			if boost > 0 {
+				dynalloc_logp = (dynalloc_logp - 1).max(2);
+			}
+		}
+		
+		let alloc_trim = if tell + (6 << BITRES) <= total_bits { dec.icdf(&TRIM_ICDF, 7) as i32 } else { 5 };
+		
+		let mut bits = ((len * 8) << BITRES) - dec.tell_frac() as i32 - 1;
+		let anti_collapse_rsv = if transient && lm >= 2 && bits >= (lm as i32 + 2) << BITRES { 1 << BITRES } else { 0 };
+		bits -= anti_collapse_rsv;
+		
+		let alloc = compute_allocation(start, end, &offsets, &cap, alloc_trim, bits, c, lm, dec);
+		unquant_fine_energy(&mut self.old_band_e, start, end, &alloc.fine_quant, dec, c);
+		
+		for mem in &mut self.decode_mem[..cc] {
+			mem.copy_within(n..DECODE_BUFFER_SIZE + OVERLAP / 2, 0);
+		}
+		
+		// decode fixed codebook
+		let mut collapse_masks = [0u8; 2 * NB_EBANDS];
+		let mut x = vec![0f32; c * n];
+		let mut bands = Bands {
+			dec:               &mut *dec,
+			i:                 start,
+			lm,
+			blocks:            if transient { m } else { 1 },
+			intensity:         alloc.intensity,
+			spread,
+			tf_change:         0,
+			remaining_bits:    0,
+			seed:              self.rng,
+			disable_inv:       self.disable_inv,
+			avoid_split_noise: false
+		};
+		bands.quant_all_bands(
+			start,
+			end,
+			&mut x,
+			&mut collapse_masks,
+			&alloc,
+			&tf_res,
+			len * (8 << BITRES) - anti_collapse_rsv
+		);
+		self.rng = bands.seed;
+		
+		let anti_collapse_on = anti_collapse_rsv > 0 && dec.bits(1) != 0;
+		
+		let bits_left = len * 8 - dec.tell();
+		unquant_energy_finalise(&mut self.old_band_e, start, end, &alloc, bits_left, dec, c);
+		
+		if anti_collapse_on {
+			self.anti_collapse(&mut x, &collapse_masks, lm, start, end, &alloc.pulses);
+		}
+		
+		if silence {
+			self.old_band_e = [-28.0; 2 * NB_EBANDS];
+		}
+		
+		self.synthesis(&x, start, eff_end, transient, lm, silence);
+		
+		for ch in 0..cc {
+			self.postfilter_period = self.postfilter_period.max(COMBFILTER_MINPERIOD);
+			self.postfilter_period_old = self.postfilter_period_old.max(COMBFILTER_MINPERIOD);
+			let old = (self.postfilter_period_old, self.postfilter_gain_old, self.postfilter_tapset_old);
+			let current = (self.postfilter_period, self.postfilter_gain, self.postfilter_tapset);
+			let off = DECODE_BUFFER_SIZE - n;
+			comb_filter(&mut self.decode_mem[ch], off, SHORT_MDCT_SIZE, old, current, &self.window);
+			if lm != 0 {
+				let new = (postfilter_pitch, postfilter_gain, postfilter_tapset);
+				comb_filter(&mut self.decode_mem[ch], off + SHORT_MDCT_SIZE, n - SHORT_MDCT_SIZE, current, new, &self.window);
+			}
+		}
+		
+		self.postfilter_period_old = self.postfilter_period;
+		self.postfilter_gain_old = self.postfilter_gain;
+		self.postfilter_tapset_old = self.postfilter_tapset;
+		self.postfilter_period = postfilter_pitch;
+		self.postfilter_gain = postfilter_gain;
+		self.postfilter_tapset = postfilter_tapset;
+		if lm != 0 {
+			self.postfilter_period_old = self.postfilter_period;
+			self.postfilter_gain_old = self.postfilter_gain;
+			self.postfilter_tapset_old = self.postfilter_tapset;
+		}
+		
+		if c == 1 {
+			self.old_band_e.copy_within(..NB_EBANDS, NB_EBANDS);
+		}
+		
+		// update the energy history used for anti-collapse and concealment
+		if !transient {
+			self.old_log_e2 = self.old_log_e;
+			self.old_log_e = self.old_band_e;
+			let max_background_increase = if self.loss_count < 10 { m as f32 * 0.001 } else { 1.0 };
+			for (bg, e) in self.background_log_e.iter_mut().zip(&self.old_band_e) {
+				*bg = (*bg + max_background_increase).min(*e);
+			}
+		} else {
+			for (log_e, e) in self.old_log_e.iter_mut().zip(&self.old_band_e) {
+				*log_e = log_e.min(*e);
+			}
+		}
+		
+		for ch in 0..2 {
+			for i in (0..start).chain(end..NB_EBANDS) {
+				self.old_band_e[ch * NB_EBANDS + i] = 0.0;
+				self.old_log_e[ch * NB_EBANDS + i] = -28.0;
+				self.old_log_e2[ch * NB_EBANDS + i] = -28.0;
+			}
+		}
+		
+		self.rng = dec.range();
+		self.deemphasis(pcm, n);
+		self.loss_count = 0;
+		Some(n)
+	}
+	
+	/// Conceals a lost frame with noise at the energy of the previous frames.
+	fn decode_lost(&mut self, n: usize, lm: usize) {
+		let c = self.channels;
+		let (start, end) = (self.start, self.end);
+		let eff_end = start.max(end.min(NB_EBANDS));
+		
+		// energy decay
+		let decay = if self.loss_count == 0 { 1.5 } else { 0.5 };
+		for ch in 0..c {
+			for i in start..end {
+				let k = ch * NB_EBANDS + i;
+				self.old_band_e[k] = self.background_log_e[k].max(self.old_band_e[k] - decay);
+			}
+		}
+		
+		let mut seed = self.rng;
+		let mut x = vec![0f32; c * n];
+		for ch in 0..c {
+			for i in start..eff_end {
+				let offset = n * ch + ((EBANDS[i] as usize) << lm);
+				let band = &mut x[offset..offset + (band_width(i) << lm)];
+				for v in band.iter_mut() {
+					seed = lcg_rand(seed);
+					*v = (seed as i32 >> 20) as f32;
+				}
+				renormalise_vector(band, 1.0);
+			}
+		}
+		self.rng = seed;
+		
+		for mem in &mut self.decode_mem[..c] {
+			mem.copy_within(n..DECODE_BUFFER_SIZE + OVERLAP / 2, 0);
+		}
+		
+		self.synthesis(&x, start, eff_end, false, lm, false);
+		self.loss_count += 1;
+	}
+	
+	/// Injects noise into the blocks of transient frames that did not receive any pulses, to
+	/// avoid audible holes.
+	fn anti_collapse(&self, x: &mut [f32], collapse_masks: &[u8], lm: usize, start: usize, end: usize, pulses: &[i32]) {
+		let size = SHORT_MDCT_SIZE << lm;
+		let c = x.len() / size;
+		let mut seed = self.rng;
+		
+		for i in start..end {
+			let n0 = band_width(i);
+			
+			// depth in 1/8 bits
+			let depth = ((1 + pulses[i]) as u32 / n0 as u32) >> lm;
+			let thresh = 0.5 * exp2(-0.125 * depth as f32);
+			let sqrt_1 = 1.0 / ((n0 << lm) as f32).sqrt();
+			
+			for ch in 0..c {
+				let mut prev1 = self.old_log_e[ch * NB_EBANDS + i];
+				let mut prev2 = self.old_log_e2[ch * NB_EBANDS + i];
+				if c == 1 {
+					prev1 = prev1.max(self.old_log_e[NB_EBANDS + i]);
+					prev2 = prev2.max(self.old_log_e2[NB_EBANDS + i]);
+				}
+				
+				let ediff = (self.old_band_e[ch * NB_EBANDS + i] - prev1.min(prev2)).max(0.0);
+				let mut r = 2.0 * exp2(-ediff);
+				if lm == 3 {
+					r *= std::f32::consts::SQRT_2;
+				}
+				r = thresh.min(r) * sqrt_1;
+				
+				let offset = ch * size + ((EBANDS[i] as usize) << lm);
+				let band = &mut x[offset..offset + (n0 << lm)];
+				let mut renormalize = false;
+				for k in 0..1 << lm {
+					// detect collapse
+					if collapse_masks[i * c + ch] & (1 << k) == 0 {
+						// fill with noise
+						for j in 0..n0 {
+							seed = lcg_rand(seed);
+							band[(j << lm) + k] = if seed & 0x8000 != 0 { r } else { -r };
+						}
+						renormalize = true;
+					}
+				}
+				
+				// we just added some energy, so we need to renormalise
+				if renormalize {
+					renormalise_vector(band, 1.0);
+				}
+			}
+		}
+	}
+	
+	/// Denormalises the spectrum and runs the inverse MDCT into the decode buffers.
+	fn synthesis(&mut self, x: &[f32], start: usize, end: usize, transient: bool, lm: usize, silence: bool) {
+		let m = 1 << lm;
+		let n = SHORT_MDCT_SIZE << lm;
+		let c = x.len() / n;
+		let cc = self.channels;
+		let (blocks, nb, shift) = if transient {
+			(m, SHORT_MDCT_SIZE, MAX_LM)
+		} else {
+			(1, SHORT_MDCT_SIZE << lm, MAX_LM - lm)
+		};
+		
+		let out = DECODE_BUFFER_SIZE - n;
+		let mut freq = vec![0f32; n];
+		let imdct = |freq: &[f32], mem: &mut [f32]| for b in 0..blocks {
+			self.mdct.backward(&freq[b..], blocks, &mut mem[out + nb * b..], &self.window, shift);
+		};
+		
+		if cc == 2 && c == 1 {
+			// copying a mono stream to two channels
+			denormalise_bands(x, &mut freq, &self.old_band_e, start, end, m, silence);
+			for mem in &mut self.decode_mem {
+				imdct(&freq, mem);
+			}
+		} else if cc == 1 && c == 2 {
+			// downmixing a stereo stream to mono
+			let mut freq2 = vec![0f32; n];
+			denormalise_bands(x, &mut freq, &self.old_band_e, start, end, m, silence);
+			denormalise_bands(&x[n..], &mut freq2, &self.old_band_e[NB_EBANDS..], start, end, m, silence);
+			for (a, b) in freq.iter_mut().zip(&freq2) {
+				*a = 0.5 * *a + 0.5 * b;
+			}
+			imdct(&freq, &mut self.decode_mem[0]);
+		} else {
+			for ch in 0..cc {
+				denormalise_bands(&x[ch * n..], &mut freq, &self.old_band_e[ch * NB_EBANDS..], start, end, m, silence);
+				imdct(&freq, &mut self.decode_mem[ch]);
+			}
+		}
+	}
+	
+	fn deemphasis(&mut self, pcm: &mut [f32], n: usize) {
+		let cc = self.channels;
+		for ch in 0..cc {
+			let mut m = self.preemph_mem[ch];
+			let mem = &self.decode_mem[ch][DECODE_BUFFER_SIZE - n..DECODE_BUFFER_SIZE];
+			for (j, x) in mem.iter().enumerate() {
+				let tmp = x + VERY_SMALL + m;
+				m = PREEMPH * tmp;
+				pcm[j * cc + ch] = tmp * (1.0 / SIG_SCALE);
+			}
+			self.preemph_mem[ch] = m;
+		}
+	}
+}
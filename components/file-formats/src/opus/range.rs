@@ -0,0 +1,278 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The range decoder shared by SILK and CELT (RFC 6716, section 4.1).
+
+const SYM_BITS:   u32 = 8;
+const CODE_BITS:  u32 = 32;
+const SYM_MAX:    u32 = (1 << SYM_BITS) - 1;
+const CODE_TOP:   u32 = 1 << (CODE_BITS - 1);
+const CODE_BOT:   u32 = CODE_TOP >> SYM_BITS;
+const CODE_EXTRA: u32 = (CODE_BITS - 2) % SYM_BITS + 1;
+const UINT_BITS:  u32 = 8;
+
+/// The resolution of fractional bit counts, i.e. 1/8th bits.
+pub const BITRES: u32 = 3;
+
+/// Returns the number of bits needed to represent the value, 0 for 0.
+#[inline]
+pub fn ilog(v: u32) -> u32 {
+	32 - v.leading_zeros()
+}
+
+#[derive(Clone, Debug)]
+pub struct RangeDecoder<'a> {
+	buf:         &'a [u8],
+	/// The number of bytes at the end of the buffer that were read as raw bits.
+	end_offs:    usize,
+	end_window:  u32,
+	nend_bits:   u32,
+	/// The total number of whole bits read, not including the partial bits in the range.
+	nbits_total: i32,
+	offs:        usize,
+	rng:         u32,
+	val:         u32,
+	ext:         u32,
+	rem:         u32,
+	/// Set if an invalid symbol was decoded, the stream is corrupt.
+	pub error:   bool
+}
+
+impl<'a> RangeDecoder<'a> {
+	pub fn new(buf: &'a [u8]) -> Self {
+		let mut dec = Self {
+			buf,
+			end_offs:    0,
+			end_window:  0,
+			nend_bits:   0,
+			nbits_total: (CODE_BITS + 1 - (CODE_BITS - CODE_EXTRA) / SYM_BITS * SYM_BITS) as i32,
+			offs:        0,
+			rng:         1 << CODE_EXTRA,
+			val:         0,
+			ext:         0,
+			rem:         0,
+			error:       false
+		};
+		dec.rem = dec.read_byte();
+		dec.val = dec.rng - 1 - (dec.rem >> (SYM_BITS - CODE_EXTRA));
+		dec.normalize();
+		dec
+	}
+	
+	/// Returns the size of the buffer in bytes.
+	pub fn storage(&self) -> usize {
+		self.buf.len()
+	}
+	
+	/// Shrinks the buffer, this is used by CELT to exclude redundancy data at the end of hybrid frames.
+	pub fn shrink(&mut self, len: usize) {
+		self.buf = &self.buf[..len];
+	}
+	
+	/// Marks all remaining bits as read, as done by CELT for silent frames.
+	pub fn skip_remaining(&mut self) {
+		self.nbits_total += (self.buf.len() * 8) as i32 - self.tell();
+	}
+	
+	/// Returns the final range, which the encoder reports to verify a decoded stream.
+	pub fn range(&self) -> u32 {
+		self.rng
+	}
+	
+	fn read_byte(&mut self) -> u32 {
+		match self.buf.get(self.offs) {
+			Some(b) => {
+				self.offs += 1;
+				*b as u32
+			}
+			None => 0
+		}
+	}
+	
+	fn read_byte_from_end(&mut self) -> u32 {
+		if self.end_offs < self.buf.len() {
+			self.end_offs += 1;
+			self.buf[self.buf.len() - self.end_offs] as u32
+		} else {
+			0
+		}
+	}
+	
+	fn normalize(&mut self) {
+		while self.rng <= CODE_BOT {
+			self.nbits_total += SYM_BITS as i32;
+			self.rng <<= SYM_BITS;
+			let sym = self.rem;
+			self.rem = self.read_byte();
+			let sym = (sym << SYM_BITS | self.rem) >> (SYM_BITS - CODE_EXTRA);
+			self.val = ((self.val << SYM_BITS) + (SYM_MAX & !sym)) & (CODE_TOP - 1);
+		}
+	}
+	
+	/// Returns the cumulative frequency of the next symbol, which must be followed by a call to [`Self::update`].
+	pub fn decode(&mut self, ft: u32) -> u32 {
+		self.ext = self.rng / ft;
+		let s = self.val / self.ext;
+		ft - (s + 1).min(ft)
+	}
+	
+	pub fn decode_bin(&mut self, bits: u32) -> u32 {
+		self.ext = self.rng >> bits;
+		let s = self.val / self.ext;
+		(1 << bits) - (s + 1).min(1 << bits)
+	}
+	
+	pub fn update(&mut self, fl: u32, fh: u32, ft: u32) {
+		let s = self.ext.wrapping_mul(ft - fh);
+		self.val = self.val.wrapping_sub(s);
+		self.rng = match fl {
+			0 => self.rng.wrapping_sub(s),
+			_ => self.ext.wrapping_mul(fh - fl)
+		};
+		self.normalize();
+	}
+	
+	/// Decodes a bit, which is one with a probability of `1 / (1 << logp)`.
+	pub fn bit_logp(&mut self, logp: u32) -> bool {
+		let s = self.rng >> logp;
+		let bit = self.val < s;
+		if bit {
+			self.rng = s;
+		} else {
+			self.val -= s;
+			self.rng -= s;
+		}
+		self.normalize();
+		bit
+	}
+	
+	/// Decodes a symbol with an inverse cumulative distribution function, with a total of `1 << ftb`.
+	pub fn icdf(&mut self, icdf: &[u8], ftb: u32) -> usize {
+		let r = self.rng >> ftb;
+		let (mut s, mut t) = (self.rng, self.rng);
+		let mut ret = 0;
+		for (i, &v) in icdf.iter().enumerate() {
+			t = s;
+			s = r.wrapping_mul(v as u32);
+			ret = i;
+			if self.val >= s {
+				break;
+			}
+		}
+		self.val -= s;
+		self.rng = t - s;
+		self.normalize();
+		ret
+	}
+	
+	/// Decodes a uniformly distributed integer in `0..ft`.
+	pub fn uint(&mut self, ft: u32) -> u32 {
+		let ft = ft - 1;
+		let ftb = ilog(ft);
+		if ftb > UINT_BITS {
+			let ftb = ftb - UINT_BITS;
+			let top = (ft >> ftb) + 1;
+			let s = self.decode(top);
+			self.update(s, s + 1, top);
+			let t = s << ftb | self.bits(ftb);
+			if t <= ft {
+				return t;
+			}
+			self.error = true;
+			ft
+		} else {
+			let s = self.decode(ft + 1);
+			self.update(s, s + 1, ft + 1);
+			s
+		}
+	}
+	
+	/// Reads raw bits from the end of the buffer.
+	pub fn bits(&mut self, bits: u32) -> u32 {
+		let mut window = self.end_window;
+		let mut available = self.nend_bits;
+		if available < bits {
+			while available <= 32 - SYM_BITS {
+				window |= self.read_byte_from_end() << available;
+				available += SYM_BITS;
+			}
+		}
+		
+		let ret = window & ((1u64 << bits) - 1) as u32;
+		self.end_window = if bits == 32 { 0 } else { window >> bits };
+		self.nend_bits = available - bits;
+		self.nbits_total += bits as i32;
+		ret
+	}
+	
+	/// Decodes a value with a Laplace distribution, as used for CELT band energies.
+	pub fn laplace(&mut self, mut fs: u32, decay: u32) -> i32 {
+		const MINP: u32 = 1;
+		const NMIN: u32 = 16;
+		
+		let mut val = 0i32;
+		let fm = self.decode_bin(15);
+		let mut fl = 0;
+		if fm >= fs {
+			val += 1;
+			fl = fs;
+			fs = (((32768 - MINP * 2 * NMIN - fs) * (16384 - decay)) >> 15) + MINP;
+			while fs > MINP && fm >= fl + 2 * fs {
+				fs *= 2;
+				fl += fs;
+				fs = (((fs - 2 * MINP) * decay) >> 15) + MINP;
+				val += 1;
+			}
+			
+			if fs <= MINP {
+				let di = (fm - fl) >> 1;
+				val += di as i32;
+				fl += 2 * di * MINP;
+			}
+			
+			if fm < fl + fs {
+				val = -val;
+			} else {
+				fl += fs;
+			}
+		}
+		self.update(fl, (fl + fs).min(32768), 32768);
+		val
+	}
+	
+	/// Returns the number of bits read so far, rounded up.
+	pub fn tell(&self) -> i32 {
+		self.nbits_total - ilog(self.rng) as i32
+	}
+	
+	/// Returns the number of bits read so far in 1/8th bits, rounded up.
+	pub fn tell_frac(&self) -> u32 {
+		const CORRECTION: [u32; 8] = [35733, 38967, 42495, 46340, 50535, 55109, 60097, 65535];
+		
+		let nbits = (self.nbits_total as u32) << BITRES;
+		let l = ilog(self.rng);
+		let r = self.rng >> (l - 16);
+		let mut b = (r >> 12) - 8;
+		b += (r > CORRECTION[b as usize]) as u32;
+		nbits - ((l << 3) + b)
+	}
+}
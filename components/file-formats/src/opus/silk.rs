@@ -0,0 +1,1929 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The SILK decoder (RFC 6716, section 4.2).
+//!
+//! This is a port of the fixed-point reference decoder, including packet loss concealment and
+//! comfort noise generation. The output is always resampled to 48 kHz.
+
+use super::{range::RangeDecoder, silk_tables::*};
+
+const MAX_LPC_ORDER:       usize = 16;
+const MIN_LPC_ORDER:       usize = 10;
+const LTP_ORDER:           usize = 5;
+const MAX_NB_SUBFR:        usize = 4;
+const MAX_FRAMES:          usize = 3;
+const SUB_FRAME_LENGTH_MS: usize = 5;
+const LTP_MEM_LENGTH_MS:   usize = 20;
+const MAX_FS_KHZ:          usize = 16;
+const MAX_SUB_FRAME_LEN:   usize = SUB_FRAME_LENGTH_MS * MAX_FS_KHZ;
+const MAX_FRAME_LENGTH:    usize = MAX_SUB_FRAME_LEN * MAX_NB_SUBFR;
+const MAX_LTP_MEM_LENGTH:  usize = LTP_MEM_LENGTH_MS * MAX_FS_KHZ;
+const SHELL_FRAME_LENGTH:  usize = 16;
+const MAX_PULSES:          usize = 16;
+const N_RATE_LEVELS:       usize = 10;
+const API_FS_KHZ:          usize = 48;
+
+const TYPE_NO_VOICE_ACTIVITY: i32 = 0;
+const TYPE_VOICED:            i32 = 2;
+
+const CODE_INDEPENDENTLY:               i32 = 0;
+const CODE_INDEPENDENTLY_NO_LTP_SCALING: i32 = 1;
+const CODE_CONDITIONALLY:               i32 = 2;
+
+const N_LEVELS_QGAIN:         i32 = 64;
+const MIN_DELTA_GAIN_QUANT:   i32 = -4;
+const MAX_DELTA_GAIN_QUANT:   i32 = 36;
+const GAIN_OFFSET:            i32 = (2 * 128) / 6 + 16 * 128;
+const GAIN_INV_SCALE_Q16:     i32 = (65536 * ((86 * 128) / 6)) / 63;
+const NLSF_QUANT_MAX_AMP:     i32 = 4;
+const NLSF_QUANT_LEVEL_ADJ:   i32 = 102;
+const QUANT_LEVEL_ADJUST_Q10: i32 = 80;
+const STEREO_INTERP_LEN_MS:   usize = 8;
+const BWE_AFTER_LOSS_Q16:     i32 = 63570;
+const MAX_STABILIZE_ITERS:    i32 = 16;
+
+const CNG_BUF_MASK_MAX:            i32 = 255;
+const CNG_GAIN_SMTH_Q16:           i32 = 4634;
+const CNG_GAIN_SMTH_THRESHOLD_Q16: i32 = 46396;
+const CNG_NLSF_SMTH_Q16:           i32 = 16348;
+
+const PLC_BWE_COEF_Q16:             i32 = 64881;
+const V_PITCH_GAIN_START_MIN_Q14:   i32 = 11469;
+const V_PITCH_GAIN_START_MAX_Q14:   i32 = 15565;
+const MAX_PITCH_LAG_MS:             i32 = 18;
+const RAND_BUF_SIZE:                i32 = 128;
+const LOG2_INV_LPC_GAIN_HIGH_THRES: u32 = 3;
+const LOG2_INV_LPC_GAIN_LOW_THRES:  u32 = 8;
+const PITCH_DRIFT_FAC_Q16:          i32 = 655;
+const HARM_ATT_Q15:                 [i32; 2] = [32440, 31130];
+const PLC_RAND_ATTENUATE_V_Q15:     [i32; 2] = [31130, 26214];
+const PLC_RAND_ATTENUATE_UV_Q15:    [i32; 2] = [32440, 29491];
+
+/// How a frame is to be decoded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Lost {
+	/// The frame is decoded normally.
+	No,
+	/// The packet was lost, the frame is concealed.
+	Yes,
+	/// The packet was lost, but the frame is decoded from the low bit-rate redundancy (FEC) data
+	/// of the next packet.
+	Lbrr
+}
+
+/// Per-call parameters, set by the Opus layer from the TOC byte.
+#[derive(Copy, Clone, Debug)]
+pub struct DecControl {
+	pub channels_api:         usize,
+	pub channels_internal:    usize,
+	pub internal_sample_rate: u32,
+	pub payload_size_ms:      u32
+}
+
+#[derive(Copy, Clone, Debug)]
+struct NlsfCodebook {
+	n_vectors:          usize,
+	order:              usize,
+	quant_step_size_q16: i32,
+	cb1_nlsf_q8:        &'static [u8],
+	cb1_wght_q9:        &'static [i16],
+	cb1_icdf:           &'static [u8],
+	pred_q8:            &'static [u8],
+	ec_sel:             &'static [u8],
+	ec_icdf:            &'static [u8],
+	delta_min_q15:      &'static [i16]
+}
+
+const NLSF_CB_NB_MB: NlsfCodebook = NlsfCodebook {
+	n_vectors:           32,
+	order:               10,
+	quant_step_size_q16: 11797,
+	cb1_nlsf_q8:         &NLSF_CB1_NB_MB_Q8,
+	cb1_wght_q9:         &NLSF_CB1_WGHT_Q9,
+	cb1_icdf:            &NLSF_CB1_ICDF_NB_MB,
+	pred_q8:             &NLSF_PRED_NB_MB_Q8,
+	ec_sel:              &NLSF_CB2_SELECT_NB_MB,
+	ec_icdf:             &NLSF_CB2_ICDF_NB_MB,
+	delta_min_q15:       &NLSF_DELTA_MIN_NB_MB_Q15
+};
+
+const NLSF_CB_WB: NlsfCodebook = NlsfCodebook {
+	n_vectors:           32,
+	order:               16,
+	quant_step_size_q16: 9830,
+	cb1_nlsf_q8:         &NLSF_CB1_WB_Q8,
+	cb1_wght_q9:         &NLSF_CB1_WB_WGHT_Q9,
+	cb1_icdf:            &NLSF_CB1_ICDF_WB,
+	pred_q8:             &NLSF_PRED_WB_Q8,
+	ec_sel:              &NLSF_CB2_SELECT_WB,
+	ec_icdf:             &NLSF_CB2_ICDF_WB,
+	delta_min_q15:       &NLSF_DELTA_MIN_WB_Q15
+};
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Indices {
+	gains:          [i32; MAX_NB_SUBFR],
+	ltp:            [i32; MAX_NB_SUBFR],
+	nlsf:           [i32; MAX_LPC_ORDER + 1],
+	lag:            i32,
+	contour:        i32,
+	signal_type:    i32,
+	quant_offset:   i32,
+	nlsf_interp_q2: i32,
+	per:            i32,
+	ltp_scale:      i32,
+	seed:           i32
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Control {
+	pitch_l:       [i32; MAX_NB_SUBFR],
+	gains_q16:     [i32; MAX_NB_SUBFR],
+	pred_coef_q12: [[i16; MAX_LPC_ORDER]; 2],
+	ltp_coef_q14:  [i16; LTP_ORDER * MAX_NB_SUBFR],
+	ltp_scale_q14: i32
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Plc {
+	pitch_l_q8:        i32,
+	ltp_coef_q14:      [i16; LTP_ORDER],
+	prev_lpc_q12:      [i16; MAX_LPC_ORDER],
+	last_frame_lost:   bool,
+	rand_seed:         i32,
+	rand_scale_q14:    i16,
+	conc_energy:       i32,
+	conc_energy_shift: i32,
+	prev_ltp_scale_q14: i32,
+	prev_gain_q16:     [i32; 2],
+	fs_khz:            usize,
+	nb_subfr:          usize,
+	subfr_length:      usize
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Cng {
+	exc_buf_q14:  [i32; MAX_FRAME_LENGTH],
+	smth_nlsf_q15: [i16; MAX_LPC_ORDER],
+	synth_state:  [i32; MAX_LPC_ORDER],
+	smth_gain_q16: i32,
+	rand_seed:    i32,
+	fs_khz:       usize
+}
+
+impl Default for Cng {
+	fn default() -> Self {
+		Self {
+			exc_buf_q14:   [0; MAX_FRAME_LENGTH],
+			smth_nlsf_q15: [0; MAX_LPC_ORDER],
+			synth_state:   [0; MAX_LPC_ORDER],
+			smth_gain_q16: 0,
+			rand_seed:     0,
+			fs_khz:        0
+		}
+	}
+}
+
+/// Upsamples from the internal sample rate to 48 kHz, with a 2x all-pass upsampler followed by
+/// a polyphase FIR interpolator.
+#[derive(Copy, Clone, Debug, Default)]
+struct Resampler {
+	iir:           [i32; 6],
+	fir:           [i16; 8],
+	delay_buf:     [i16; MAX_FS_KHZ],
+	input_delay:   usize,
+	fs_in_khz:     usize,
+	batch_size:    usize,
+	inv_ratio_q16: i32
+}
+
+impl Resampler {
+	fn new(fs_in_khz: usize) -> Self {
+		let fs_in = fs_in_khz as i32 * 1000;
+		let fs_out = API_FS_KHZ as i32 * 1000;
+		let mut inv_ratio_q16 = ((fs_in << 15) / fs_out) << 2;
+		while smulww(inv_ratio_q16, fs_out) < fs_in << 1 {
+			inv_ratio_q16 += 1;
+		}
+		
+		Self {
+			input_delay: match fs_in_khz { 8 => 0, 12 => 4, _ => 7 },
+			fs_in_khz,
+			batch_size: fs_in_khz * 10,
+			inv_ratio_q16,
+			..Self::default()
+		}
+	}
+	
+	fn resample(&mut self, out: &mut [i16], input: &[i16]) {
+		let n = self.fs_in_khz - self.input_delay;
+		self.delay_buf[self.input_delay..self.fs_in_khz].copy_from_slice(&input[..n]);
+		let delay_buf = self.delay_buf;
+		self.iir_fir(&mut out[..API_FS_KHZ], &delay_buf[..self.fs_in_khz]);
+		self.iir_fir(&mut out[API_FS_KHZ..], &input[n..input.len() - self.input_delay]);
+		self.delay_buf[..self.input_delay].copy_from_slice(&input[input.len() - self.input_delay..]);
+	}
+	
+	fn iir_fir(&mut self, out: &mut [i16], mut input: &[i16]) {
+		let mut buf = [0i16; 2 * MAX_FS_KHZ * 10 + 8];
+		buf[..8].copy_from_slice(&self.fir);
+		let mut out_idx = 0;
+		let mut n;
+		loop {
+			n = input.len().min(self.batch_size);
+			up2_hq(&mut self.iir, &mut buf[8..8 + 2 * n], &input[..n]);
+			let max_index_q16 = (n as i32) << 17;
+			let mut index_q16 = 0;
+			while index_q16 < max_index_q16 {
+				let table_index = smulwb(index_q16 & 0xffff, 12) as usize;
+				let b = &buf[(index_q16 >> 16) as usize..];
+				let (c0, c1) = (&RESAMPLER_FRAC_FIR_12[table_index], &RESAMPLER_FRAC_FIR_12[11 - table_index]);
+				let mut res_q15 = smulbb(b[0] as i32, c0[0] as i32);
+				res_q15 = smlabb(res_q15, b[1] as i32, c0[1] as i32);
+				res_q15 = smlabb(res_q15, b[2] as i32, c0[2] as i32);
+				res_q15 = smlabb(res_q15, b[3] as i32, c0[3] as i32);
+				res_q15 = smlabb(res_q15, b[4] as i32, c1[3] as i32);
+				res_q15 = smlabb(res_q15, b[5] as i32, c1[2] as i32);
+				res_q15 = smlabb(res_q15, b[6] as i32, c1[1] as i32);
+				res_q15 = smlabb(res_q15, b[7] as i32, c1[0] as i32);
+				out[out_idx] = sat16(rshift_round(res_q15, 15));
+				out_idx += 1;
+				index_q16 += self.inv_ratio_q16;
+			}
+			
+			input = &input[n..];
+			if input.is_empty() {
+				break;
+			}
+			buf.copy_within(2 * n..2 * n + 8, 0);
+		}
+		self.fir.copy_from_slice(&buf[2 * n..2 * n + 8]);
+	}
+}
+
+fn up2_hq(s: &mut [i32; 6], out: &mut [i16], input: &[i16]) {
+	for (k, &x) in input.iter().enumerate() {
+		let in32 = (x as i32) << 10;
+		for (phase, coefs) in [RESAMPLER_UP2_HQ_0, RESAMPLER_UP2_HQ_1].iter().enumerate() {
+			let s = &mut s[phase * 3..phase * 3 + 3];
+			let y = in32 - s[0];
+			let x = smulwb(y, coefs[0] as i32);
+			let out1 = s[0] + x;
+			s[0] = in32 + x;
+			
+			let y = out1 - s[1];
+			let x = smulwb(y, coefs[1] as i32);
+			let out2 = s[1] + x;
+			s[1] = out1 + x;
+			
+			let y = out2 - s[2];
+			let x = smlawb(y, y, coefs[2] as i32);
+			let out1 = s[2] + x;
+			s[2] = out2 + x;
+			out[2 * k + phase] = sat16(rshift_round(out1, 10));
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Stereo {
+	pred_prev_q13: [i32; 2],
+	s_mid:         [i16; 2],
+	s_side:        [i16; 2]
+}
+
+#[derive(Clone, Debug)]
+struct Channel {
+	prev_gain_q16:           i32,
+	exc_q14:                 [i32; MAX_FRAME_LENGTH],
+	slpc_q14_buf:            [i32; MAX_LPC_ORDER],
+	out_buf:                 [i16; MAX_LTP_MEM_LENGTH + 2 * MAX_SUB_FRAME_LEN],
+	lag_prev:                i32,
+	last_gain_index:         i32,
+	fs_khz:                  usize,
+	nb_subfr:                usize,
+	frame_length:            usize,
+	subfr_length:            usize,
+	ltp_mem_length:          usize,
+	lpc_order:               usize,
+	prev_nlsf_q15:           [i16; MAX_LPC_ORDER],
+	first_frame_after_reset: bool,
+	pitch_lag_low_bits_icdf: &'static [u8],
+	pitch_contour_icdf:      &'static [u8],
+	frames_decoded:          usize,
+	frames_per_packet:       usize,
+	ec_prev_signal_type:     i32,
+	ec_prev_lag_index:       i32,
+	vad_flags:               [bool; MAX_FRAMES],
+	lbrr_flag:               bool,
+	lbrr_flags:              [bool; MAX_FRAMES],
+	resampler:               Resampler,
+	nlsf_cb:                 &'static NlsfCodebook,
+	indices:                 Indices,
+	cng:                     Cng,
+	loss_cnt:                i32,
+	prev_signal_type:        i32,
+	plc:                     Plc
+}
+
+impl Default for Channel {
+	fn default() -> Self {
+		let mut ch = Self {
+			prev_gain_q16:           65536,
+			exc_q14:                 [0; MAX_FRAME_LENGTH],
+			slpc_q14_buf:            [0; MAX_LPC_ORDER],
+			out_buf:                 [0; MAX_LTP_MEM_LENGTH + 2 * MAX_SUB_FRAME_LEN],
+			lag_prev:                0,
+			last_gain_index:         0,
+			fs_khz:                  0,
+			nb_subfr:                0,
+			frame_length:            0,
+			subfr_length:            0,
+			ltp_mem_length:          0,
+			lpc_order:               0,
+			prev_nlsf_q15:           [0; MAX_LPC_ORDER],
+			first_frame_after_reset: true,
+			pitch_lag_low_bits_icdf: &UNIFORM8_ICDF,
+			pitch_contour_icdf:      &PITCH_CONTOUR_ICDF,
+			frames_decoded:          0,
+			frames_per_packet:       0,
+			ec_prev_signal_type:     0,
+			ec_prev_lag_index:       0,
+			vad_flags:               [false; MAX_FRAMES],
+			lbrr_flag:               false,
+			lbrr_flags:              [false; MAX_FRAMES],
+			resampler:               Resampler::default(),
+			nlsf_cb:                 &NLSF_CB_NB_MB,
+			indices:                 Indices::default(),
+			cng:                     Cng::default(),
+			loss_cnt:                0,
+			prev_signal_type:        0,
+			plc:                     Plc::default()
+		};
+		ch.cng_reset();
+		ch.plc_reset();
+		ch
+	}
+}
+
+impl Channel {
+	fn set_fs(&mut self, fs_khz: usize) {
+		self.subfr_length = SUB_FRAME_LENGTH_MS * fs_khz;
+		let frame_length = self.nb_subfr * self.subfr_length;
+		if self.fs_khz != fs_khz {
+			self.resampler = Resampler::new(fs_khz);
+		}
+		
+		if self.fs_khz != fs_khz || frame_length != self.frame_length {
+			self.pitch_contour_icdf = match (fs_khz, self.nb_subfr) {
+				(8, MAX_NB_SUBFR) => &PITCH_CONTOUR_NB_ICDF,
+				(8, _)            => &PITCH_CONTOUR_10_MS_NB_ICDF,
+				(_, MAX_NB_SUBFR) => &PITCH_CONTOUR_ICDF,
+				_                 => &PITCH_CONTOUR_10_MS_ICDF
+			};
+			
+			if self.fs_khz != fs_khz {
+				self.ltp_mem_length = LTP_MEM_LENGTH_MS * fs_khz;
+				(self.lpc_order, self.nlsf_cb) = match fs_khz {
+					16 => (MAX_LPC_ORDER, &NLSF_CB_WB),
+					_  => (MIN_LPC_ORDER, &NLSF_CB_NB_MB)
+				};
+				self.pitch_lag_low_bits_icdf = match fs_khz {
+					16 => &UNIFORM8_ICDF,
+					12 => &UNIFORM6_ICDF,
+					_  => &UNIFORM4_ICDF
+				};
+				self.first_frame_after_reset = true;
+				self.lag_prev = 100;
+				self.last_gain_index = 10;
+				self.prev_signal_type = TYPE_NO_VOICE_ACTIVITY;
+				self.out_buf.fill(0);
+				self.slpc_q14_buf.fill(0);
+			}
+			
+			self.fs_khz = fs_khz;
+			self.frame_length = frame_length;
+		}
+	}
+	
+	fn decode_frame(&mut self, dec: &mut RangeDecoder, out: &mut [i16], lost: Lost, cond_coding: i32) -> usize {
+		let len = self.frame_length;
+		let mut ctrl = Control::default();
+		
+		if lost == Lost::No || (lost == Lost::Lbrr && self.lbrr_flags[self.frames_decoded]) {
+			let mut pulses = [0i16; MAX_FRAME_LENGTH];
+			self.decode_indices(dec, self.frames_decoded, lost == Lost::Lbrr, cond_coding);
+			decode_pulses(dec, &mut pulses, self.indices.signal_type, self.indices.quant_offset, len);
+			self.decode_parameters(&mut ctrl, cond_coding);
+			self.decode_core(&mut ctrl, &mut out[..len], &pulses);
+			self.plc(&mut ctrl, &mut out[..len], false);
+			self.loss_cnt = 0;
+			self.prev_signal_type = self.indices.signal_type;
+			self.first_frame_after_reset = false;
+		} else {
+			self.plc(&mut ctrl, &mut out[..len], true);
+		}
+		
+		let mv_len = self.ltp_mem_length - len;
+		self.out_buf.copy_within(len..len + mv_len, 0);
+		self.out_buf[mv_len..mv_len + len].copy_from_slice(&out[..len]);
+		self.cng(&ctrl, &mut out[..len]);
+		self.plc_glue_frames(&mut out[..len]);
+		self.lag_prev = ctrl.pitch_l[self.nb_subfr - 1];
+		len
+	}
+	
+	fn decode_indices(&mut self, dec: &mut RangeDecoder, frame_index: usize, decode_lbrr: bool, cond_coding: i32) {
+		let ix = if decode_lbrr || self.vad_flags[frame_index] {
+			dec.icdf(&TYPE_OFFSET_VAD_ICDF, 8) as i32 + 2
+		} else {
+			dec.icdf(&TYPE_OFFSET_NO_VAD_ICDF, 8) as i32
+		};
+		let ind = &mut self.indices;
+		ind.signal_type = ix >> 1;
+		ind.quant_offset = ix & 1;
+		
+		// gains
+		if cond_coding == CODE_CONDITIONALLY {
+			ind.gains[0] = dec.icdf(&DELTA_GAIN_ICDF, 8) as i32;
+		} else {
+			ind.gains[0] = (dec.icdf(&GAIN_ICDF[ind.signal_type as usize], 8) as i32) << 3;
+			ind.gains[0] += dec.icdf(&UNIFORM8_ICDF, 8) as i32;
+		}
+		
+		for i in 1..self.nb_subfr {
+			ind.gains[i] = dec.icdf(&DELTA_GAIN_ICDF, 8) as i32;
+		}
+		
+		// NLSFs
+		let cb = self.nlsf_cb;
+		ind.nlsf[0] = dec.icdf(&cb.cb1_icdf[(ind.signal_type as usize >> 1) * cb.n_vectors..], 8) as i32;
+		let (ec_ix, _) = nlsf_unpack(cb, ind.nlsf[0] as usize);
+		for (i, &ec) in ec_ix[..cb.order].iter().enumerate() {
+			let mut ix = dec.icdf(&cb.ec_icdf[ec as usize..], 8) as i32;
+			if ix == 0 {
+				ix -= dec.icdf(&NLSF_EXT_ICDF, 8) as i32;
+			} else if ix == 2 * NLSF_QUANT_MAX_AMP {
+				ix += dec.icdf(&NLSF_EXT_ICDF, 8) as i32;
+			}
+			ind.nlsf[i + 1] = ix - NLSF_QUANT_MAX_AMP;
+		}
+		
+		ind.nlsf_interp_q2 = match self.nb_subfr {
+			MAX_NB_SUBFR => dec.icdf(&NLSF_INTERPOLATION_FACTOR_ICDF, 8) as i32,
+			_            => 4
+		};
+		
+		if ind.signal_type == TYPE_VOICED {
+			// pitch lags
+			let mut absolute = true;
+			if cond_coding == CODE_CONDITIONALLY && self.ec_prev_signal_type == TYPE_VOICED {
+				let delta = dec.icdf(&PITCH_DELTA_ICDF, 8) as i32;
+				if delta > 0 {
+					ind.lag = self.ec_prev_lag_index + delta - 9;
+					absolute = false;
+				}
+			}
+			
+			if absolute {
+				ind.lag = dec.icdf(&PITCH_LAG_ICDF, 8) as i32 * (self.fs_khz as i32 >> 1);
+				ind.lag += dec.icdf(self.pitch_lag_low_bits_icdf, 8) as i32;
+			}
+			self.ec_prev_lag_index = ind.lag;
+			ind.contour = dec.icdf(self.pitch_contour_icdf, 8) as i32;
+			
+			// LTP gains
+			ind.per = dec.icdf(&LTP_PER_INDEX_ICDF, 8) as i32;
+			let icdf: &[u8] = match ind.per {
+				0 => &LTP_GAIN_ICDF_0,
+				1 => &LTP_GAIN_ICDF_1,
+				_ => &LTP_GAIN_ICDF_2
+			};
+			for k in 0..self.nb_subfr {
+				ind.ltp[k] = dec.icdf(icdf, 8) as i32;
+			}
+			
+			ind.ltp_scale = match cond_coding {
+				CODE_INDEPENDENTLY => dec.icdf(&LTPSCALE_ICDF, 8) as i32,
+				_                  => 0
+			};
+		}
+		
+		self.ec_prev_signal_type = ind.signal_type;
+		ind.seed = dec.icdf(&UNIFORM4_ICDF, 8) as i32;
+	}
+	
+	fn decode_parameters(&mut self, ctrl: &mut Control, cond_coding: i32) {
+		gains_dequant(&mut ctrl.gains_q16, &self.indices.gains, &mut self.last_gain_index,
+			cond_coding == CODE_CONDITIONALLY, self.nb_subfr);
+		
+		let order = self.lpc_order;
+		let mut nlsf_q15 = [0i16; MAX_LPC_ORDER];
+		nlsf_decode(&mut nlsf_q15[..order], &self.indices.nlsf, self.nlsf_cb);
+		nlsf2a(&mut ctrl.pred_coef_q12[1][..order], &nlsf_q15[..order]);
+		
+		if self.first_frame_after_reset {
+			self.indices.nlsf_interp_q2 = 4;
+		}
+		
+		if self.indices.nlsf_interp_q2 < 4 {
+			let mut nlsf0_q15 = [0i16; MAX_LPC_ORDER];
+			for i in 0..order {
+				nlsf0_q15[i] = (self.prev_nlsf_q15[i] as i32 + ((self.indices.nlsf_interp_q2
+					* (nlsf_q15[i] as i32 - self.prev_nlsf_q15[i] as i32)) >> 2)) as i16;
+			}
+			nlsf2a(&mut ctrl.pred_coef_q12[0][..order], &nlsf0_q15[..order]);
+		} else {
+			ctrl.pred_coef_q12[0] = ctrl.pred_coef_q12[1];
+		}
+		
+		self.prev_nlsf_q15[..order].copy_from_slice(&nlsf_q15[..order]);
+		
+		if self.loss_cnt != 0 {
+			bwexpander(&mut ctrl.pred_coef_q12[0][..order], BWE_AFTER_LOSS_Q16);
+			bwexpander(&mut ctrl.pred_coef_q12[1][..order], BWE_AFTER_LOSS_Q16);
+		}
+		
+		if self.indices.signal_type == TYPE_VOICED {
+			decode_pitch(self.indices.lag, self.indices.contour as usize, &mut ctrl.pitch_l, self.fs_khz, self.nb_subfr);
+			let cbk: &[[i8; 5]] = match self.indices.per {
+				0 => &LTP_GAIN_VQ_0,
+				1 => &LTP_GAIN_VQ_1,
+				_ => &LTP_GAIN_VQ_2
+			};
+			for k in 0..self.nb_subfr {
+				let v = &cbk[self.indices.ltp[k] as usize];
+				for (coef, &v) in ctrl.ltp_coef_q14[k * LTP_ORDER..(k + 1) * LTP_ORDER].iter_mut().zip(v) {
+					*coef = (v as i16) << 7;
+				}
+			}
+			ctrl.ltp_scale_q14 = LTPSCALES_TABLE_Q14[self.indices.ltp_scale as usize] as i32;
+		} else {
+			ctrl.pitch_l = [0; MAX_NB_SUBFR];
+			ctrl.ltp_coef_q14 = [0; LTP_ORDER * MAX_NB_SUBFR];
+			self.indices.per = 0;
+			ctrl.ltp_scale_q14 = 0;
+		}
+	}
+	
+	fn decode_core(&mut self, ctrl: &mut Control, xq: &mut [i16], pulses: &[i16]) {
+		let mut s_ltp = [0i16; MAX_LTP_MEM_LENGTH];
+		let mut s_ltp_q15 = [0i32; MAX_LTP_MEM_LENGTH + MAX_FRAME_LENGTH];
+		let mut res_q14 = [0i32; MAX_SUB_FRAME_LEN];
+		let mut s_lpc_q14 = [0i32; MAX_SUB_FRAME_LEN + MAX_LPC_ORDER];
+		
+		let offset_q10 = QUANTIZATION_OFFSETS_Q10[self.indices.signal_type as usize >> 1][self.indices.quant_offset as usize] as i32;
+		let nlsf_interpolation = self.indices.nlsf_interp_q2 < 4;
+		
+		// decode excitation
+		let mut rand_seed = self.indices.seed;
+		for (i, &pulse) in pulses[..self.frame_length].iter().enumerate() {
+			rand_seed = silk_rand(rand_seed);
+			let mut exc = (pulse as i32) << 14;
+			if exc > 0 {
+				exc -= QUANT_LEVEL_ADJUST_Q10 << 4;
+			} else if exc < 0 {
+				exc += QUANT_LEVEL_ADJUST_Q10 << 4;
+			}
+			exc += offset_q10 << 4;
+			if rand_seed < 0 {
+				exc = -exc;
+			}
+			self.exc_q14[i] = exc;
+			rand_seed = rand_seed.wrapping_add(pulse as i32);
+		}
+		
+		s_lpc_q14[..MAX_LPC_ORDER].copy_from_slice(&self.slpc_q14_buf);
+		let mut s_ltp_buf_idx = self.ltp_mem_length;
+		let sub = self.subfr_length;
+		for k in 0..self.nb_subfr {
+			let a_q12 = ctrl.pred_coef_q12[k >> 1];
+			let mut signal_type = self.indices.signal_type;
+			let gain_q10 = ctrl.gains_q16[k] >> 6;
+			let mut inv_gain_q31 = inverse32_varq(ctrl.gains_q16[k], 47);
+			
+			// scale the LPC state when the gain changes
+			let gain_adj_q16 = if ctrl.gains_q16[k] != self.prev_gain_q16 {
+				let adj = div32_varq(self.prev_gain_q16, ctrl.gains_q16[k], 16);
+				for v in &mut s_lpc_q14[..MAX_LPC_ORDER] {
+					*v = smulww(adj, *v);
+				}
+				adj
+			} else {
+				1 << 16
+			};
+			self.prev_gain_q16 = ctrl.gains_q16[k];
+			
+			// avoid abrupt transitions from voiced PLC to unvoiced normal decoding
+			if self.loss_cnt != 0 && self.prev_signal_type == TYPE_VOICED
+				&& self.indices.signal_type != TYPE_VOICED && k < MAX_NB_SUBFR / 2 {
+				ctrl.ltp_coef_q14[k * LTP_ORDER..(k + 1) * LTP_ORDER].fill(0);
+				ctrl.ltp_coef_q14[k * LTP_ORDER + LTP_ORDER / 2] = 1 << 12;
+				signal_type = TYPE_VOICED;
+				ctrl.pitch_l[k] = self.lag_prev;
+			}
+			
+			let b_q14 = &ctrl.ltp_coef_q14[k * LTP_ORDER..(k + 1) * LTP_ORDER];
+			if signal_type == TYPE_VOICED {
+				let lag = ctrl.pitch_l[k] as usize;
+				
+				// re-whitening
+				if k == 0 || (k == 2 && nlsf_interpolation) {
+					let start_idx = self.ltp_mem_length - lag - self.lpc_order - LTP_ORDER / 2;
+					if k == 2 {
+						self.out_buf[self.ltp_mem_length..self.ltp_mem_length + 2 * sub].copy_from_slice(&xq[..2 * sub]);
+					}
+					
+					lpc_analysis_filter(
+						&mut s_ltp[start_idx..self.ltp_mem_length],
+						&self.out_buf[start_idx + k * sub..],
+						&a_q12[..self.lpc_order]
+					);
+					
+					if k == 0 {
+						inv_gain_q31 = smulwb(inv_gain_q31, ctrl.ltp_scale_q14) << 2;
+					}
+					
+					for i in 0..lag + LTP_ORDER / 2 {
+						s_ltp_q15[s_ltp_buf_idx - i - 1] = smulwb(inv_gain_q31, s_ltp[self.ltp_mem_length - i - 1] as i32);
+					}
+				} else if gain_adj_q16 != 1 << 16 {
+					for i in 0..lag + LTP_ORDER / 2 {
+						let v = &mut s_ltp_q15[s_ltp_buf_idx - i - 1];
+						*v = smulww(gain_adj_q16, *v);
+					}
+				}
+				
+				// long-term prediction
+				for (i, res) in res_q14[..sub].iter_mut().enumerate() {
+					let p = s_ltp_buf_idx - lag + LTP_ORDER / 2;
+					let mut ltp_pred_q13 = 2;
+					for (j, &b) in b_q14.iter().enumerate() {
+						ltp_pred_q13 = smlawb(ltp_pred_q13, s_ltp_q15[p - j], b as i32);
+					}
+					
+					*res = self.exc_q14[k * sub + i].wrapping_add(ltp_pred_q13 << 1);
+					s_ltp_q15[s_ltp_buf_idx] = *res << 1;
+					s_ltp_buf_idx += 1;
+				}
+			} else {
+				res_q14[..sub].copy_from_slice(&self.exc_q14[k * sub..(k + 1) * sub]);
+			}
+			
+			// short-term prediction
+			for i in 0..sub {
+				let lpc_pred_q10 = lpc_predict(&s_lpc_q14[..MAX_LPC_ORDER + i], &a_q12[..self.lpc_order]);
+				let v = res_q14[i].saturating_add(lshift_sat32(lpc_pred_q10, 4));
+				s_lpc_q14[MAX_LPC_ORDER + i] = v;
+				xq[k * sub + i] = sat16(rshift_round(smulww(v, gain_q10), 8));
+			}
+			
+			s_lpc_q14.copy_within(sub..sub + MAX_LPC_ORDER, 0);
+		}
+		
+		self.slpc_q14_buf.copy_from_slice(&s_lpc_q14[..MAX_LPC_ORDER]);
+	}
+	
+	fn plc_reset(&mut self) {
+		self.plc.pitch_l_q8 = (self.frame_length as i32) << 7;
+		self.plc.prev_gain_q16 = [1 << 16; 2];
+		self.plc.subfr_length = 20;
+		self.plc.nb_subfr = 2;
+	}
+	
+	fn plc(&mut self, ctrl: &mut Control, frame: &mut [i16], lost: bool) {
+		if self.fs_khz != self.plc.fs_khz {
+			self.plc_reset();
+			self.plc.fs_khz = self.fs_khz;
+		}
+		
+		if lost {
+			self.plc_conceal(ctrl, frame);
+			self.loss_cnt += 1;
+		} else {
+			self.plc_update(ctrl);
+		}
+	}
+	
+	fn plc_update(&mut self, ctrl: &Control) {
+		let plc = &mut self.plc;
+		self.prev_signal_type = self.indices.signal_type;
+		let mut ltp_gain_q14 = 0;
+		if self.indices.signal_type == TYPE_VOICED {
+			// find the subframe with the highest LTP gain, in the last pitch period
+			let mut j = 0;
+			while (j * self.subfr_length) < ctrl.pitch_l[self.nb_subfr - 1] as usize && j != self.nb_subfr {
+				let s = (self.nb_subfr - 1 - j) * LTP_ORDER;
+				let temp = ctrl.ltp_coef_q14[s..s + LTP_ORDER].iter().map(|&v| v as i32).sum::<i32>();
+				if temp > ltp_gain_q14 {
+					ltp_gain_q14 = temp;
+					plc.ltp_coef_q14.copy_from_slice(&ctrl.ltp_coef_q14[s..s + LTP_ORDER]);
+					plc.pitch_l_q8 = ctrl.pitch_l[self.nb_subfr - 1 - j] << 8;
+				}
+				j += 1;
+			}
+			
+			plc.ltp_coef_q14 = [0; LTP_ORDER];
+			plc.ltp_coef_q14[LTP_ORDER / 2] = ltp_gain_q14 as i16;
+			
+			// limit the LTP coefficients
+			if ltp_gain_q14 < V_PITCH_GAIN_START_MIN_Q14 {
+				let scale_q10 = (V_PITCH_GAIN_START_MIN_Q14 << 10) / ltp_gain_q14.max(1);
+				for v in &mut plc.ltp_coef_q14 {
+					*v = (smulbb(*v as i32, scale_q10) >> 10) as i16;
+				}
+			} else if ltp_gain_q14 > V_PITCH_GAIN_START_MAX_Q14 {
+				let scale_q14 = (V_PITCH_GAIN_START_MAX_Q14 << 14) / ltp_gain_q14.max(1);
+				for v in &mut plc.ltp_coef_q14 {
+					*v = (smulbb(*v as i32, scale_q14) >> 14) as i16;
+				}
+			}
+		} else {
+			plc.pitch_l_q8 = (self.fs_khz as i32 * 18) << 8;
+			plc.ltp_coef_q14 = [0; LTP_ORDER];
+		}
+		
+		plc.prev_lpc_q12[..self.lpc_order].copy_from_slice(&ctrl.pred_coef_q12[1][..self.lpc_order]);
+		plc.prev_ltp_scale_q14 = ctrl.ltp_scale_q14;
+		plc.prev_gain_q16.copy_from_slice(&ctrl.gains_q16[self.nb_subfr - 2..self.nb_subfr]);
+		plc.subfr_length = self.subfr_length;
+		plc.nb_subfr = self.nb_subfr;
+	}
+	
+	fn plc_conceal(&mut self, ctrl: &mut Control, frame: &mut [i16]) {
+		let mut s_ltp = [0i16; MAX_LTP_MEM_LENGTH];
+		let mut s_ltp_q14 = [0i32; MAX_LTP_MEM_LENGTH + MAX_FRAME_LENGTH];
+		let prev_gain_q10 = [self.plc.prev_gain_q16[0] >> 6, self.plc.prev_gain_q16[1] >> 6];
+		
+		if self.first_frame_after_reset {
+			self.plc.prev_lpc_q12 = [0; MAX_LPC_ORDER];
+		}
+		
+		// find the random noise component, from the subframe with the lower energy
+		let (energy1, shift1, energy2, shift2) = {
+			let mut exc_buf = [0i16; 2 * MAX_SUB_FRAME_LEN];
+			let sub = self.subfr_length;
+			for k in 0..2 {
+				for i in 0..sub {
+					exc_buf[k * sub + i] = sat16(smulww(self.exc_q14[i + (k + self.nb_subfr - 2) * sub], prev_gain_q10[k]) >> 8);
+				}
+			}
+			let (e1, s1) = sum_sqr_shift(&exc_buf[..sub]);
+			let (e2, s2) = sum_sqr_shift(&exc_buf[sub..2 * sub]);
+			(e1, s1, e2, s2)
+		};
+		
+		let rand_offset = if (energy1 >> shift2) < (energy2 >> shift1) {
+			((self.plc.nb_subfr as i32 - 1) * self.plc.subfr_length as i32 - RAND_BUF_SIZE).max(0)
+		} else {
+			(self.plc.nb_subfr as i32 * self.plc.subfr_length as i32 - RAND_BUF_SIZE).max(0)
+		} as usize;
+		
+		let att = (self.loss_cnt as usize).min(1);
+		let harm_gain_q15 = HARM_ATT_Q15[att];
+		let mut rand_gain_q15 = match self.prev_signal_type {
+			TYPE_VOICED => PLC_RAND_ATTENUATE_V_Q15[att],
+			_           => PLC_RAND_ATTENUATE_UV_Q15[att]
+		};
+		
+		let order = self.lpc_order;
+		bwexpander(&mut self.plc.prev_lpc_q12[..order], PLC_BWE_COEF_Q16);
+		let a_q12 = self.plc.prev_lpc_q12;
+		
+		let mut rand_scale_q14 = self.plc.rand_scale_q14;
+		if self.loss_cnt == 0 {
+			rand_scale_q14 = 1 << 14;
+			if self.prev_signal_type == TYPE_VOICED {
+				for &b in &self.plc.ltp_coef_q14 {
+					rand_scale_q14 = rand_scale_q14.wrapping_sub(b);
+				}
+				rand_scale_q14 = rand_scale_q14.max(3277);
+				rand_scale_q14 = (smulbb(rand_scale_q14 as i32, self.plc.prev_ltp_scale_q14) >> 14) as i16;
+			} else {
+				let inv_gain_q30 = lpc_inverse_pred_gain(&self.plc.prev_lpc_q12[..order]);
+				let down_scale_q30 = inv_gain_q30.clamp(
+					(1 << 30) >> LOG2_INV_LPC_GAIN_LOW_THRES,
+					(1 << 30) >> LOG2_INV_LPC_GAIN_HIGH_THRES
+				) << LOG2_INV_LPC_GAIN_HIGH_THRES;
+				rand_gain_q15 = smulwb(down_scale_q30, rand_gain_q15) >> 14;
+			}
+		}
+		
+		let mut rand_seed = self.plc.rand_seed;
+		let mut lag = rshift_round(self.plc.pitch_l_q8, 8) as usize;
+		let mut s_ltp_buf_idx = self.ltp_mem_length;
+		
+		// rewhiten the LTP state
+		let idx = self.ltp_mem_length - lag - order - LTP_ORDER / 2;
+		lpc_analysis_filter(&mut s_ltp[idx..self.ltp_mem_length], &self.out_buf[idx..], &a_q12[..order]);
+		let inv_gain_q30 = inverse32_varq(self.plc.prev_gain_q16[1], 46).min(i32::MAX >> 1);
+		for i in idx + order..self.ltp_mem_length {
+			s_ltp_q14[i] = smulwb(inv_gain_q30, s_ltp[i] as i32);
+		}
+		
+		// LTP synthesis filtering
+		let b_q14 = &mut self.plc.ltp_coef_q14;
+		for _ in 0..self.nb_subfr {
+			for _ in 0..self.subfr_length {
+				let p = s_ltp_buf_idx - lag + LTP_ORDER / 2;
+				let mut ltp_pred_q12 = 2;
+				for (j, &b) in b_q14.iter().enumerate() {
+					ltp_pred_q12 = smlawb(ltp_pred_q12, s_ltp_q14[p - j], b as i32);
+				}
+				
+				rand_seed = silk_rand(rand_seed);
+				let idx = ((rand_seed >> 25) & (RAND_BUF_SIZE - 1)) as usize;
+				s_ltp_q14[s_ltp_buf_idx] = smlawb(ltp_pred_q12, self.exc_q14[rand_offset + idx], rand_scale_q14 as i32) << 2;
+				s_ltp_buf_idx += 1;
+			}
+			
+			// gradually reduce the LTP gain and the excitation gain
+			for b in b_q14.iter_mut() {
+				*b = (smulbb(harm_gain_q15, *b as i32) >> 15) as i16;
+			}
+			rand_scale_q14 = (smulbb(rand_scale_q14 as i32, rand_gain_q15) >> 15) as i16;
+			
+			// slowly increase the pitch lag
+			self.plc.pitch_l_q8 = smlawb(self.plc.pitch_l_q8, self.plc.pitch_l_q8, PITCH_DRIFT_FAC_Q16);
+			self.plc.pitch_l_q8 = self.plc.pitch_l_q8.min((MAX_PITCH_LAG_MS * self.fs_khz as i32) << 8);
+			lag = rshift_round(self.plc.pitch_l_q8, 8) as usize;
+		}
+		
+		// LPC synthesis filtering
+		let s_lpc_q14 = &mut s_ltp_q14[self.ltp_mem_length - MAX_LPC_ORDER..];
+		s_lpc_q14[..MAX_LPC_ORDER].copy_from_slice(&self.slpc_q14_buf);
+		for i in 0..self.frame_length {
+			let lpc_pred_q10 = lpc_predict(&s_lpc_q14[..MAX_LPC_ORDER + i], &a_q12[..order]);
+			let v = s_lpc_q14[MAX_LPC_ORDER + i].saturating_add(lshift_sat32(lpc_pred_q10, 4));
+			s_lpc_q14[MAX_LPC_ORDER + i] = v;
+			frame[i] = sat16(rshift_round(smulww(v, prev_gain_q10[1]), 8));
+		}
+		
+		self.slpc_q14_buf.copy_from_slice(&s_lpc_q14[self.frame_length..self.frame_length + MAX_LPC_ORDER]);
+		self.plc.rand_seed = rand_seed;
+		self.plc.rand_scale_q14 = rand_scale_q14;
+		ctrl.pitch_l = [lag as i32; MAX_NB_SUBFR];
+	}
+	
+	fn plc_glue_frames(&mut self, frame: &mut [i16]) {
+		let plc = &mut self.plc;
+		if self.loss_cnt != 0 {
+			(plc.conc_energy, plc.conc_energy_shift) = sum_sqr_shift(frame);
+			plc.last_frame_lost = true;
+			return;
+		}
+		
+		if plc.last_frame_lost {
+			let (mut energy, energy_shift) = sum_sqr_shift(frame);
+			
+			// normalize the energies
+			if energy_shift > plc.conc_energy_shift {
+				plc.conc_energy >>= energy_shift - plc.conc_energy_shift;
+			} else if energy_shift < plc.conc_energy_shift {
+				energy >>= plc.conc_energy_shift - energy_shift;
+			}
+			
+			// fade in the energy if the concealed frame was quieter
+			if energy > plc.conc_energy {
+				let lz = clz32(plc.conc_energy) - 1;
+				plc.conc_energy <<= lz;
+				energy >>= (24 - lz).max(0);
+				let frac_q24 = plc.conc_energy / energy.max(1);
+				let mut gain_q16 = sqrt_approx(frac_q24) << 4;
+				let slope_q16 = (((1 << 16) - gain_q16) / frame.len() as i32) << 2;
+				for v in frame.iter_mut() {
+					*v = smulwb(gain_q16, *v as i32) as i16;
+					gain_q16 += slope_q16;
+					if gain_q16 > 1 << 16 {
+						break;
+					}
+				}
+			}
+		}
+		plc.last_frame_lost = false;
+	}
+	
+	fn cng_reset(&mut self) {
+		let step = i16::MAX as i32 / (self.lpc_order as i32 + 1);
+		let mut acc = 0;
+		for v in &mut self.cng.smth_nlsf_q15[..self.lpc_order] {
+			acc += step;
+			*v = acc as i16;
+		}
+		self.cng.smth_gain_q16 = 0;
+		self.cng.rand_seed = 3176576;
+	}
+	
+	fn cng(&mut self, ctrl: &Control, frame: &mut [i16]) {
+		if self.fs_khz != self.cng.fs_khz {
+			self.cng_reset();
+			self.cng.fs_khz = self.fs_khz;
+		}
+		
+		let order = self.lpc_order;
+		let cng = &mut self.cng;
+		if self.loss_cnt == 0 && self.prev_signal_type == TYPE_NO_VOICE_ACTIVITY {
+			// smooth the NLSFs and update the excitation buffer with the loudest subframe
+			for i in 0..order {
+				cng.smth_nlsf_q15[i] += smulwb(self.prev_nlsf_q15[i] as i32 - cng.smth_nlsf_q15[i] as i32, CNG_NLSF_SMTH_Q16) as i16;
+			}
+			
+			let mut max_gain_q16 = 0;
+			let mut subfr = 0;
+			for i in 0..self.nb_subfr {
+				if ctrl.gains_q16[i] > max_gain_q16 {
+					max_gain_q16 = ctrl.gains_q16[i];
+					subfr = i;
+				}
+			}
+			
+			let sub = self.subfr_length;
+			cng.exc_buf_q14.copy_within(..(self.nb_subfr - 1) * sub, sub);
+			cng.exc_buf_q14[..sub].copy_from_slice(&self.exc_q14[subfr * sub..(subfr + 1) * sub]);
+			
+			for i in 0..self.nb_subfr {
+				cng.smth_gain_q16 += smulwb(ctrl.gains_q16[i] - cng.smth_gain_q16, CNG_GAIN_SMTH_Q16);
+				if smulww(cng.smth_gain_q16, CNG_GAIN_SMTH_THRESHOLD_Q16) > ctrl.gains_q16[i] {
+					cng.smth_gain_q16 = ctrl.gains_q16[i];
+				}
+			}
+		}
+		
+		if self.loss_cnt == 0 {
+			cng.synth_state[..order].fill(0);
+			return;
+		}
+		
+		// add comfort noise while concealing lost frames
+		let mut cng_sig_q14 = [0i32; MAX_FRAME_LENGTH + MAX_LPC_ORDER];
+		let mut gain_q16 = smulww(self.plc.rand_scale_q14 as i32, self.plc.prev_gain_q16[1]);
+		if gain_q16 >= 1 << 21 || cng.smth_gain_q16 > 1 << 23 {
+			gain_q16 = smultt(gain_q16, gain_q16);
+			gain_q16 = smultt(cng.smth_gain_q16, cng.smth_gain_q16).wrapping_sub(gain_q16 << 5);
+			gain_q16 = sqrt_approx(gain_q16) << 16;
+		} else {
+			gain_q16 = smulww(gain_q16, gain_q16);
+			gain_q16 = smulww(cng.smth_gain_q16, cng.smth_gain_q16).wrapping_sub(gain_q16 << 5);
+			gain_q16 = sqrt_approx(gain_q16) << 8;
+		}
+		let gain_q10 = gain_q16 >> 6;
+		
+		// generate the excitation
+		let len = frame.len();
+		let mut exc_mask = CNG_BUF_MASK_MAX;
+		while exc_mask > len as i32 {
+			exc_mask >>= 1;
+		}
+		
+		let mut seed = cng.rand_seed;
+		for v in &mut cng_sig_q14[MAX_LPC_ORDER..MAX_LPC_ORDER + len] {
+			seed = silk_rand(seed);
+			*v = cng.exc_buf_q14[((seed >> 24) & exc_mask) as usize];
+		}
+		cng.rand_seed = seed;
+		
+		let mut a_q12 = [0i16; MAX_LPC_ORDER];
+		nlsf2a(&mut a_q12[..order], &cng.smth_nlsf_q15[..order]);
+		
+		cng_sig_q14[..MAX_LPC_ORDER].copy_from_slice(&cng.synth_state);
+		for i in 0..len {
+			let lpc_pred_q10 = lpc_predict(&cng_sig_q14[..MAX_LPC_ORDER + i], &a_q12[..order]);
+			let v = cng_sig_q14[MAX_LPC_ORDER + i].saturating_add(lshift_sat32(lpc_pred_q10, 4));
+			cng_sig_q14[MAX_LPC_ORDER + i] = v;
+			frame[i] = sat16(frame[i] as i32 + sat16(rshift_round(smulww(v, gain_q10), 8)) as i32);
+		}
+		cng.synth_state.copy_from_slice(&cng_sig_q14[len..len + MAX_LPC_ORDER]);
+	}
+}
+
+/// Decodes SILK frames of a mono or stereo stream.
+#[derive(Clone, Debug)]
+#[derive(Default)]
+pub struct SilkDecoder {
+	channels:                [Channel; 2],
+	stereo:                  Stereo,
+	channels_api:            usize,
+	channels_internal:       usize,
+	prev_decode_only_middle: bool
+}
+
+
+impl SilkDecoder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	/// Resets the decoder, except for the channel counts of the previous call.
+	pub fn reset(&mut self) {
+		self.channels = [Channel::default(), Channel::default()];
+		self.stereo = Stereo::default();
+		self.prev_decode_only_middle = false;
+	}
+	
+	/// Decodes one SILK frame of 10 or 20 ms and writes the interleaved 48 kHz samples to `out`.
+	/// Returns the number of samples per channel, or `None` if the parameters are invalid.
+	pub fn decode(
+		&mut self,
+		ctrl:       &DecControl,
+		lost:       Lost,
+		new_packet: bool,
+		dec:        &mut RangeDecoder,
+		out:        &mut [i16]
+	) -> Option<usize> {
+		let n_internal = ctrl.channels_internal;
+		
+		if new_packet {
+			for ch in &mut self.channels[..n_internal] {
+				ch.frames_decoded = 0;
+			}
+		}
+		
+		// a new channel was added, reset its state
+		if n_internal > self.channels_internal {
+			self.channels[1] = Channel::default();
+		}
+		
+		let stereo_to_mono = n_internal == 1 && self.channels_internal == 2
+			&& ctrl.internal_sample_rate as usize == 1000 * self.channels[0].fs_khz;
+		
+		if self.channels[0].frames_decoded == 0 {
+			for ch in &mut self.channels[..n_internal] {
+				(ch.frames_per_packet, ch.nb_subfr) = match ctrl.payload_size_ms {
+					0 | 10 => (1, 2),
+					20     => (1, 4),
+					40     => (2, 4),
+					60     => (3, 4),
+					_      => return None
+				};
+				let fs_khz = (ctrl.internal_sample_rate >> 10) as usize + 1;
+				if fs_khz != 8 && fs_khz != 12 && fs_khz != 16 {
+					return None;
+				}
+				ch.set_fs(fs_khz);
+			}
+		}
+		
+		if ctrl.channels_api == 2 && n_internal == 2 && (self.channels_api == 1 || self.channels_internal == 1) {
+			self.stereo.pred_prev_q13 = [0; 2];
+			self.stereo.s_side = [0; 2];
+			self.channels[1].resampler = self.channels[0].resampler;
+		}
+		self.channels_api = ctrl.channels_api;
+		self.channels_internal = n_internal;
+		
+		let mut ms_pred_q13 = [0; 2];
+		let mut decode_only_middle = false;
+		
+		// the VAD and LBRR flags are at the start of the packet
+		if lost != Lost::Yes && self.channels[0].frames_decoded == 0 {
+			for ch in &mut self.channels[..n_internal] {
+				for i in 0..ch.frames_per_packet {
+					ch.vad_flags[i] = dec.bit_logp(1);
+				}
+				ch.lbrr_flag = dec.bit_logp(1);
+			}
+			
+			for ch in &mut self.channels[..n_internal] {
+				ch.lbrr_flags = [false; MAX_FRAMES];
+				if ch.lbrr_flag {
+					if ch.frames_per_packet == 1 {
+						ch.lbrr_flags[0] = true;
+					} else {
+						let icdf: &[u8] = match ch.frames_per_packet {
+							2 => &LBRR_FLAGS_2_ICDF,
+							_ => &LBRR_FLAGS_3_ICDF
+						};
+						let symbol = dec.icdf(icdf, 8) + 1;
+						for i in 0..ch.frames_per_packet {
+							ch.lbrr_flags[i] = (symbol >> i) & 1 != 0;
+						}
+					}
+				}
+			}
+			
+			// skip the LBRR data when decoding normally
+			if lost == Lost::No {
+				for i in 0..self.channels[0].frames_per_packet {
+					for n in 0..n_internal {
+						if !self.channels[n].lbrr_flags[i] {
+							continue;
+						}
+						
+						let mut pulses = [0i16; MAX_FRAME_LENGTH];
+						if n_internal == 2 && n == 0 {
+							stereo_decode_pred(dec, &mut ms_pred_q13);
+							if !self.channels[1].lbrr_flags[i] {
+								decode_only_middle = dec.icdf(&STEREO_ONLY_CODE_MID_ICDF, 8) != 0;
+							}
+						}
+						
+						let ch = &mut self.channels[n];
+						let cond_coding = match i > 0 && ch.lbrr_flags[i - 1] {
+							true  => CODE_CONDITIONALLY,
+							false => CODE_INDEPENDENTLY
+						};
+						ch.decode_indices(dec, i, true, cond_coding);
+						decode_pulses(dec, &mut pulses, ch.indices.signal_type, ch.indices.quant_offset, ch.frame_length);
+					}
+				}
+			}
+		}
+		
+		// stereo prediction weights
+		if n_internal == 2 {
+			let frame = self.channels[0].frames_decoded;
+			if lost == Lost::No || (lost == Lost::Lbrr && self.channels[0].lbrr_flags[frame]) {
+				stereo_decode_pred(dec, &mut ms_pred_q13);
+				if (lost == Lost::No && !self.channels[1].vad_flags[frame])
+					|| (lost == Lost::Lbrr && !self.channels[1].lbrr_flags[frame]) {
+					decode_only_middle = dec.icdf(&STEREO_ONLY_CODE_MID_ICDF, 8) != 0;
+				} else {
+					decode_only_middle = false;
+				}
+			} else {
+				ms_pred_q13 = self.stereo.pred_prev_q13;
+			}
+		}
+		
+		// reset the side channel when it was not coded in the previous frame
+		if n_internal == 2 && !decode_only_middle && self.prev_decode_only_middle {
+			let ch = &mut self.channels[1];
+			ch.out_buf.fill(0);
+			ch.slpc_q14_buf.fill(0);
+			ch.lag_prev = 100;
+			ch.last_gain_index = 10;
+			ch.prev_signal_type = TYPE_NO_VOICE_ACTIVITY;
+			ch.first_frame_after_reset = true;
+		}
+		
+		let has_side = match lost {
+			Lost::No => !decode_only_middle,
+			_        => !self.prev_decode_only_middle || (n_internal == 2 && lost == Lost::Lbrr
+				&& self.channels[1].lbrr_flags[self.channels[1].frames_decoded])
+		};
+		
+		let mut samples = [[0i16; MAX_FRAME_LENGTH + 2]; 2];
+		let mut n_samples = 0;
+		for (n, samples) in samples[..n_internal].iter_mut().enumerate() {
+			if n == 0 || has_side {
+				let frame_index = self.channels[0].frames_decoded as isize - n as isize;
+				let cond_coding = if frame_index <= 0 {
+					CODE_INDEPENDENTLY
+				} else if lost == Lost::Lbrr {
+					match self.channels[n].lbrr_flags[frame_index as usize - 1] {
+						true  => CODE_CONDITIONALLY,
+						false => CODE_INDEPENDENTLY
+					}
+				} else if n > 0 && self.prev_decode_only_middle {
+					CODE_INDEPENDENTLY_NO_LTP_SCALING
+				} else {
+					CODE_CONDITIONALLY
+				};
+				n_samples = self.channels[n].decode_frame(dec, &mut samples[2..], lost, cond_coding);
+			} else {
+				samples[2..2 + n_samples].fill(0);
+			}
+			self.channels[n].frames_decoded += 1;
+		}
+		
+		if ctrl.channels_api == 2 && n_internal == 2 {
+			let [mid, side] = &mut samples;
+			stereo_ms_to_lr(&mut self.stereo, mid, side, &ms_pred_q13, self.channels[0].fs_khz, n_samples);
+		} else {
+			// buffer the mid channel, to keep the delay the same as in stereo
+			samples[0][..2].copy_from_slice(&self.stereo.s_mid);
+			self.stereo.s_mid.copy_from_slice(&samples[0][n_samples..n_samples + 2]);
+		}
+		
+		let n_out = n_samples * API_FS_KHZ / self.channels[0].fs_khz;
+		let mut resampled = [0i16; MAX_FRAME_LENGTH * API_FS_KHZ / 8];
+		for n in 0..ctrl.channels_api.min(n_internal) {
+			self.channels[n].resampler.resample(&mut resampled[..n_out], &samples[n][1..1 + n_samples]);
+			for i in 0..n_out {
+				out[n + ctrl.channels_api * i] = resampled[i];
+			}
+		}
+		
+		// create the second channel from the mono signal
+		if ctrl.channels_api == 2 && n_internal == 1 {
+			if stereo_to_mono {
+				self.channels[1].resampler.resample(&mut resampled[..n_out], &samples[0][1..1 + n_samples]);
+				for i in 0..n_out {
+					out[1 + 2 * i] = resampled[i];
+				}
+			} else {
+				for i in 0..n_out {
+					out[1 + 2 * i] = out[2 * i];
+				}
+			}
+		}
+		
+		if lost == Lost::Yes {
+			for ch in &mut self.channels[..self.channels_internal] {
+				ch.last_gain_index = 10;
+			}
+		} else {
+			self.prev_decode_only_middle = decode_only_middle;
+		}
+		
+		Some(n_out)
+	}
+}
+
+fn decode_pulses(dec: &mut RangeDecoder, pulses: &mut [i16], signal_type: i32, quant_offset: i32, frame_length: usize) {
+	let mut sum_pulses = [0i32; MAX_FRAME_LENGTH / SHELL_FRAME_LENGTH];
+	let mut n_lshifts = [0i32; MAX_FRAME_LENGTH / SHELL_FRAME_LENGTH];
+	
+	let rate_level = dec.icdf(&RATE_LEVELS_ICDF[signal_type as usize >> 1], 8);
+	let iter = frame_length.div_ceil(SHELL_FRAME_LENGTH);
+	
+	// sum-weighted pulses per shell block
+	for i in 0..iter {
+		sum_pulses[i] = dec.icdf(&PULSES_PER_BLOCK_ICDF[rate_level], 8) as i32;
+		while sum_pulses[i] == MAX_PULSES as i32 + 1 {
+			n_lshifts[i] += 1;
+			let offset = (n_lshifts[i] == 10) as usize;
+			sum_pulses[i] = dec.icdf(&PULSES_PER_BLOCK_ICDF[N_RATE_LEVELS - 1][offset..], 8) as i32;
+		}
+	}
+	
+	// pulse locations
+	for i in 0..iter {
+		let block = &mut pulses[i * SHELL_FRAME_LENGTH..(i + 1) * SHELL_FRAME_LENGTH];
+		if sum_pulses[i] > 0 {
+			shell_decoder(block, dec, sum_pulses[i]);
+		} else {
+			block.fill(0);
+		}
+	}
+	
+	// least significant bits
+	for i in 0..iter {
+		if n_lshifts[i] > 0 {
+			for p in &mut pulses[i * SHELL_FRAME_LENGTH..(i + 1) * SHELL_FRAME_LENGTH] {
+				let mut abs_q = *p as i32;
+				for _ in 0..n_lshifts[i] {
+					abs_q = (abs_q << 1) + dec.icdf(&LSB_ICDF, 8) as i32;
+				}
+				*p = abs_q as i16;
+			}
+			sum_pulses[i] |= n_lshifts[i] << 5;
+		}
+	}
+	
+	// signs
+	let sign_icdf = &SIGN_ICDF[7 * (quant_offset + (signal_type << 1)) as usize..];
+	for i in 0..(frame_length + SHELL_FRAME_LENGTH / 2) / SHELL_FRAME_LENGTH {
+		let p = sum_pulses[i];
+		if p > 0 {
+			let icdf = [sign_icdf[(p & 0x1f).min(6) as usize], 0];
+			for q in &mut pulses[i * SHELL_FRAME_LENGTH..(i + 1) * SHELL_FRAME_LENGTH] {
+				if *q > 0 {
+					*q *= (dec.icdf(&icdf, 8) as i16) * 2 - 1;
+				}
+			}
+		}
+	}
+}
+
+fn shell_decoder(pulses0: &mut [i16], dec: &mut RangeDecoder, pulses4: i32) {
+	fn split(dec: &mut RangeDecoder, p: i32, table: &[u8]) -> (i32, i32) {
+		if p > 0 {
+			let c = dec.icdf(&table[SHELL_CODE_TABLE_OFFSETS[p as usize] as usize..], 8) as i32;
+			(c, p - c)
+		} else {
+			(0, 0)
+		}
+	}
+	
+	let mut pulses1 = [0i32; 8];
+	let mut pulses2 = [0i32; 4];
+	let mut pulses3 = [0i32; 2];
+	(pulses3[0], pulses3[1]) = split(dec, pulses4, &SHELL_CODE_TABLE3);
+	for j in 0..2 {
+		(pulses2[2 * j], pulses2[2 * j + 1]) = split(dec, pulses3[j], &SHELL_CODE_TABLE2);
+		for k in 2 * j..2 * j + 2 {
+			(pulses1[2 * k], pulses1[2 * k + 1]) = split(dec, pulses2[k], &SHELL_CODE_TABLE1);
+			for l in 2 * k..2 * k + 2 {
+				let (a, b) = split(dec, pulses1[l], &SHELL_CODE_TABLE0);
+				pulses0[2 * l] = a as i16;
+				pulses0[2 * l + 1] = b as i16;
+			}
+		}
+	}
+}
+
+fn gains_dequant(gain_q16: &mut [i32; MAX_NB_SUBFR], ind: &[i32; MAX_NB_SUBFR], prev_ind: &mut i32, conditional: bool, nb_subfr: usize) {
+	for k in 0..nb_subfr {
+		if k == 0 && !conditional {
+			// the gain index may not drop more than 16 steps
+			*prev_ind = ind[k].max(*prev_ind - 16);
+		} else {
+			let ind_tmp = ind[k] + MIN_DELTA_GAIN_QUANT;
+			let threshold = 2 * MAX_DELTA_GAIN_QUANT - N_LEVELS_QGAIN + *prev_ind;
+			if ind_tmp > threshold {
+				*prev_ind += (ind_tmp << 1) - threshold;
+			} else {
+				*prev_ind += ind_tmp;
+			}
+		}
+		
+		*prev_ind = (*prev_ind).clamp(0, N_LEVELS_QGAIN - 1);
+		gain_q16[k] = log2lin((smulwb(GAIN_INV_SCALE_Q16, *prev_ind) + GAIN_OFFSET).min(3967));
+	}
+}
+
+fn nlsf_unpack(cb: &NlsfCodebook, cb1_index: usize) -> ([i16; MAX_LPC_ORDER], [u8; MAX_LPC_ORDER]) {
+	let mut ec_ix = [0i16; MAX_LPC_ORDER];
+	let mut pred_q8 = [0u8; MAX_LPC_ORDER];
+	let sel = &cb.ec_sel[cb1_index * cb.order / 2..];
+	for i in (0..cb.order).step_by(2) {
+		let entry = sel[i / 2] as usize;
+		ec_ix[i] = (((entry >> 1) & 7) * (2 * NLSF_QUANT_MAX_AMP as usize + 1)) as i16;
+		pred_q8[i] = cb.pred_q8[i + (entry & 1) * (cb.order - 1)];
+		ec_ix[i + 1] = (((entry >> 5) & 7) * (2 * NLSF_QUANT_MAX_AMP as usize + 1)) as i16;
+		pred_q8[i + 1] = cb.pred_q8[i + ((entry >> 4) & 1) * (cb.order - 1) + 1];
+	}
+	(ec_ix, pred_q8)
+}
+
+fn nlsf_decode(nlsf_q15: &mut [i16], indices: &[i32], cb: &NlsfCodebook) {
+	let (_, pred_q8) = nlsf_unpack(cb, indices[0] as usize);
+	
+	// predictive residual dequantizer
+	let order = cb.order;
+	let mut res_q10 = [0i16; MAX_LPC_ORDER];
+	let mut out_q10 = 0i32;
+	for i in (0..order).rev() {
+		let pred_q10 = smulbb(out_q10, pred_q8[i] as i32) >> 8;
+		out_q10 = indices[i + 1] << 10;
+		if out_q10 > 0 {
+			out_q10 -= NLSF_QUANT_LEVEL_ADJ;
+		} else if out_q10 < 0 {
+			out_q10 += NLSF_QUANT_LEVEL_ADJ;
+		}
+		out_q10 = smlawb(pred_q10, out_q10, cb.quant_step_size_q16);
+		res_q10[i] = out_q10 as i16;
+	}
+	
+	let base = indices[0] as usize * order;
+	for i in 0..order {
+		let tmp = ((res_q10[i] as i32) << 14) / cb.cb1_wght_q9[base + i] as i32 + ((cb.cb1_nlsf_q8[base + i] as i32) << 7);
+		nlsf_q15[i] = tmp.clamp(0, 32767) as i16;
+	}
+	
+	nlsf_stabilize(nlsf_q15, cb.delta_min_q15);
+}
+
+fn nlsf_stabilize(nlsf: &mut [i16], delta_min: &[i16]) {
+	let l = nlsf.len();
+	for _ in 0..20 {
+		// find the smallest distance
+		let mut min_diff = nlsf[0] as i32 - delta_min[0] as i32;
+		let mut ii = 0;
+		for i in 1..l {
+			let diff = nlsf[i] as i32 - (nlsf[i - 1] as i32 + delta_min[i] as i32);
+			if diff < min_diff {
+				min_diff = diff;
+				ii = i;
+			}
+		}
+		
+		let diff = (1 << 15) - (nlsf[l - 1] as i32 + delta_min[l] as i32);
+		if diff < min_diff {
+			min_diff = diff;
+			ii = l;
+		}
+		
+		if min_diff >= 0 {
+			return;
+		}
+		
+		if ii == 0 {
+			nlsf[0] = delta_min[0];
+		} else if ii == l {
+			nlsf[l - 1] = ((1 << 15) - delta_min[l] as i32) as i16;
+		} else {
+			// move the two coefficients apart around their center
+			let mut min_center = delta_min[..ii].iter().map(|&v| v as i32).sum::<i32>();
+			min_center += delta_min[ii] as i32 >> 1;
+			let mut max_center = 1 << 15;
+			max_center -= delta_min[ii + 1..=l].iter().map(|&v| v as i32).sum::<i32>();
+			max_center -= delta_min[ii] as i32 >> 1;
+			let center = limit(rshift_round(nlsf[ii - 1] as i32 + nlsf[ii] as i32, 1), min_center, max_center);
+			nlsf[ii - 1] = (center - (delta_min[ii] as i32 >> 1)) as i16;
+			nlsf[ii] = nlsf[ii - 1] + delta_min[ii];
+		}
+	}
+	
+	// fall back to sorting and clamping
+	nlsf.sort_unstable();
+	nlsf[0] = nlsf[0].max(delta_min[0]);
+	for i in 1..l {
+		nlsf[i] = nlsf[i].max(nlsf[i - 1].saturating_add(delta_min[i]));
+	}
+	nlsf[l - 1] = (nlsf[l - 1] as i32).min((1 << 15) - delta_min[l] as i32) as i16;
+	for i in (0..l - 1).rev() {
+		nlsf[i] = nlsf[i].min(nlsf[i + 1] - delta_min[i + 1]);
+	}
+}
+
+/// Converts normalized line spectral frequencies to LPC coefficients.
+fn nlsf2a(a_q12: &mut [i16], nlsf: &[i16]) {
+	const QA: u32 = 16;
+	const ORDERING16: [usize; 16] = [0, 15, 8, 7, 4, 11, 12, 3, 2, 13, 10, 5, 6, 9, 14, 1];
+	const ORDERING10: [usize; 10] = [0, 9, 6, 3, 4, 5, 8, 1, 2, 7];
+	
+	fn find_poly(out: &mut [i32], c_lsf: &[i32], dd: usize) {
+		out[0] = 1 << QA;
+		out[1] = -c_lsf[0];
+		for k in 1..dd {
+			let ftmp = c_lsf[2 * k] as i64;
+			out[k + 1] = (out[k - 1] << 1) - rshift_round64(ftmp * out[k] as i64, QA) as i32;
+			for n in (2..=k).rev() {
+				out[n] += out[n - 2] - rshift_round64(ftmp * out[n - 1] as i64, QA) as i32;
+			}
+			out[1] -= ftmp as i32;
+		}
+	}
+	
+	let d = nlsf.len();
+	let ordering: &[usize] = match d {
+		16 => &ORDERING16,
+		_  => &ORDERING10
+	};
+	
+	let mut cos_lsf_qa = [0i32; MAX_LPC_ORDER];
+	for k in 0..d {
+		let f_int = (nlsf[k] >> 8) as usize;
+		let f_frac = nlsf[k] as i32 - ((f_int as i32) << 8);
+		let cos_val = LSFCOSTAB_FIX_Q12[f_int] as i32;
+		let delta = LSFCOSTAB_FIX_Q12[f_int + 1] as i32 - cos_val;
+		cos_lsf_qa[ordering[k]] = rshift_round((cos_val << 8) + delta * f_frac, 20 - QA);
+	}
+	
+	let dd = d / 2;
+	let mut p = [0i32; MAX_LPC_ORDER / 2 + 1];
+	let mut q = [0i32; MAX_LPC_ORDER / 2 + 1];
+	find_poly(&mut p, &cos_lsf_qa[..], dd);
+	find_poly(&mut q, &cos_lsf_qa[1..], dd);
+	
+	let mut a32_qa1 = [0i32; MAX_LPC_ORDER];
+	for k in 0..dd {
+		let p_tmp = p[k + 1] + p[k];
+		let q_tmp = q[k + 1] - q[k];
+		a32_qa1[k] = -q_tmp - p_tmp;
+		a32_qa1[d - k - 1] = q_tmp - p_tmp;
+	}
+	
+	lpc_fit(a_q12, &mut a32_qa1[..d], 12, QA + 1);
+	
+	// make sure the filter is stable
+	let mut i = 0;
+	while lpc_inverse_pred_gain(a_q12) == 0 && i < MAX_STABILIZE_ITERS {
+		bwexpander_32(&mut a32_qa1[..d], 65536 - (2 << i));
+		for k in 0..d {
+			a_q12[k] = rshift_round(a32_qa1[k], QA + 1 - 12) as i16;
+		}
+		i += 1;
+	}
+}
+
+fn lpc_fit(a_qout: &mut [i16], a_qin: &mut [i32], qout: u32, qin: u32) {
+	let d = a_qin.len();
+	let mut i = 0;
+	while i < 10 {
+		let (idx, maxabs) = a_qin.iter().enumerate()
+			.fold((0, 0), |(idx, max), (k, &v)| match v.wrapping_abs() > max {
+				true  => (k, v.wrapping_abs()),
+				false => (idx, max)
+			});
+		let maxabs = rshift_round(maxabs, qin - qout);
+		if maxabs > i16::MAX as i32 {
+			// reduce the magnitude with bandwidth expansion
+			let maxabs = maxabs.min(163838);
+			let chirp_q16 = 65470 - ((maxabs - i16::MAX as i32) << 14) / ((maxabs * (idx as i32 + 1)) >> 2);
+			bwexpander_32(a_qin, chirp_q16);
+		} else {
+			break;
+		}
+		i += 1;
+	}
+	
+	if i == 10 {
+		for k in 0..d {
+			a_qout[k] = sat16(rshift_round(a_qin[k], qin - qout));
+			a_qin[k] = (a_qout[k] as i32) << (qin - qout);
+		}
+	} else {
+		for k in 0..d {
+			a_qout[k] = rshift_round(a_qin[k], qin - qout) as i16;
+		}
+	}
+}
+
+fn bwexpander(ar: &mut [i16], mut chirp_q16: i32) {
+	let chirp_minus_one_q16 = chirp_q16 - 65536;
+	let d = ar.len();
+	for v in &mut ar[..d - 1] {
+		*v = rshift_round(chirp_q16 * *v as i32, 16) as i16;
+		chirp_q16 += rshift_round(chirp_q16 * chirp_minus_one_q16, 16);
+	}
+	ar[d - 1] = rshift_round(chirp_q16 * ar[d - 1] as i32, 16) as i16;
+}
+
+fn bwexpander_32(ar: &mut [i32], mut chirp_q16: i32) {
+	let chirp_minus_one_q16 = chirp_q16 - 65536;
+	let d = ar.len();
+	for v in &mut ar[..d - 1] {
+		*v = smulww(chirp_q16, *v);
+		chirp_q16 += rshift_round(chirp_q16 * chirp_minus_one_q16, 16);
+	}
+	ar[d - 1] = smulww(chirp_q16, ar[d - 1]);
+}
+
+/// Returns the inverse prediction gain in Q30, or 0 if the filter is unstable.
+fn lpc_inverse_pred_gain(a_q12: &[i16]) -> i32 {
+	const QA: u32 = 24;
+	const A_LIMIT: i32 = 16773022;
+	const MIN_INV_GAIN_Q30: i32 = 107374;
+	
+	let order = a_q12.len();
+	let mut a_qa = [0i32; MAX_LPC_ORDER];
+	let mut dc_resp = 0;
+	for k in 0..order {
+		dc_resp += a_q12[k] as i32;
+		a_qa[k] = (a_q12[k] as i32) << (QA - 12);
+	}
+	
+	if dc_resp >= 4096 {
+		return 0;
+	}
+	
+	let mut inv_gain_q30 = 1 << 30;
+	for k in (1..order).rev() {
+		if a_qa[k] > A_LIMIT || a_qa[k] < -A_LIMIT {
+			return 0;
+		}
+		
+		let rc_q31 = -(a_qa[k] << (31 - QA));
+		let rc_mult1_q30 = (1 << 30) - smmul(rc_q31, rc_q31);
+		inv_gain_q30 = smmul(inv_gain_q30, rc_mult1_q30) << 2;
+		if inv_gain_q30 < MIN_INV_GAIN_Q30 {
+			return 0;
+		}
+		
+		let mult2q = 32 - clz32(rc_mult1_q30.abs()) as u32;
+		let rc_mult2 = inverse32_varq(rc_mult1_q30, mult2q as i32 + 30);
+		for n in 0..(k + 1) >> 1 {
+			let tmp1 = a_qa[n];
+			let tmp2 = a_qa[k - n - 1];
+			let v = rshift_round64(tmp1.saturating_sub(rshift_round64(tmp2 as i64 * rc_q31 as i64, 31) as i32) as i64 * rc_mult2 as i64, mult2q);
+			if v > i32::MAX as i64 || v < i32::MIN as i64 {
+				return 0;
+			}
+			a_qa[n] = v as i32;
+			
+			let v = rshift_round64(tmp2.saturating_sub(rshift_round64(tmp1 as i64 * rc_q31 as i64, 31) as i32) as i64 * rc_mult2 as i64, mult2q);
+			if v > i32::MAX as i64 || v < i32::MIN as i64 {
+				return 0;
+			}
+			a_qa[k - n - 1] = v as i32;
+		}
+	}
+	
+	if a_qa[0] > A_LIMIT || a_qa[0] < -A_LIMIT {
+		return 0;
+	}
+	
+	let rc_q31 = -(a_qa[0] << (31 - QA));
+	let rc_mult1_q30 = (1 << 30) - smmul(rc_q31, rc_q31);
+	inv_gain_q30 = smmul(inv_gain_q30, rc_mult1_q30) << 2;
+	if inv_gain_q30 < MIN_INV_GAIN_Q30 {
+		return 0;
+	}
+	inv_gain_q30
+}
+
+/// Filters `input` with the whitening filter `1 - sum(b[j] * z^-(j + 1))`, the first `b.len()`
+/// output samples are set to zero.
+fn lpc_analysis_filter(out: &mut [i16], input: &[i16], b: &[i16]) {
+	let d = b.len();
+	for ix in d..out.len() {
+		let mut acc = 0i32;
+		for j in 0..d {
+			acc = acc.wrapping_add(smulbb(input[ix - 1 - j] as i32, b[j] as i32));
+		}
+		let out32_q12 = ((input[ix] as i32) << 12).wrapping_sub(acc);
+		out[ix] = sat16(rshift_round(out32_q12, 12));
+	}
+	out[..d].fill(0);
+}
+
+/// Returns the short-term prediction of the next sample from the preceding samples in `s`.
+#[inline]
+fn lpc_predict(s: &[i32], a_q12: &[i16]) -> i32 {
+	let n = s.len();
+	let mut pred = a_q12.len() as i32 >> 1;
+	for (j, &a) in a_q12.iter().enumerate() {
+		pred = smlawb(pred, s[n - 1 - j], a as i32);
+	}
+	pred
+}
+
+fn decode_pitch(lag_index: i32, contour_index: usize, pitch_lags: &mut [i32; MAX_NB_SUBFR], fs_khz: usize, nb_subfr: usize) {
+	let min_lag = 2 * fs_khz as i32;
+	let max_lag = MAX_PITCH_LAG_MS * fs_khz as i32;
+	let lag = min_lag + lag_index;
+	for k in 0..nb_subfr {
+		let cb = match (fs_khz, nb_subfr) {
+			(8, MAX_NB_SUBFR) => CB_LAGS_STAGE2[k][contour_index],
+			(8, _)            => CB_LAGS_STAGE2_10_MS[k][contour_index],
+			(_, MAX_NB_SUBFR) => CB_LAGS_STAGE3[k][contour_index],
+			_                 => CB_LAGS_STAGE3_10_MS[k][contour_index]
+		};
+		pitch_lags[k] = limit(lag + cb as i32, min_lag, max_lag);
+	}
+}
+
+fn stereo_decode_pred(dec: &mut RangeDecoder, pred_q13: &mut [i32; 2]) {
+	let n = dec.icdf(&STEREO_PRED_JOINT_ICDF, 8);
+	let mut ix = [[0usize; 3]; 2];
+	ix[0][2] = n / 5;
+	ix[1][2] = n - 5 * ix[0][2];
+	for v in &mut ix {
+		v[0] = dec.icdf(&UNIFORM3_ICDF, 8);
+		v[1] = dec.icdf(&UNIFORM5_ICDF, 8);
+	}
+	
+	for n in 0..2 {
+		ix[n][0] += 3 * ix[n][2];
+		let low_q13 = STEREO_PRED_QUANT_Q13[ix[n][0]] as i32;
+		let step_q13 = smulwb(STEREO_PRED_QUANT_Q13[ix[n][0] + 1] as i32 - low_q13, 6554);
+		pred_q13[n] = low_q13 + step_q13 * (2 * ix[n][1] as i32 + 1);
+	}
+	pred_q13[0] -= pred_q13[1];
+}
+
+fn stereo_ms_to_lr(state: &mut Stereo, x1: &mut [i16], x2: &mut [i16], pred_q13: &[i32; 2], fs_khz: usize, frame_length: usize) {
+	// buffering
+	x1[..2].copy_from_slice(&state.s_mid);
+	x2[..2].copy_from_slice(&state.s_side);
+	state.s_mid.copy_from_slice(&x1[frame_length..frame_length + 2]);
+	state.s_side.copy_from_slice(&x2[frame_length..frame_length + 2]);
+	
+	// interpolate the predictors and add the prediction to the side channel
+	let mut pred0_q13 = state.pred_prev_q13[0];
+	let mut pred1_q13 = state.pred_prev_q13[1];
+	let interp_len = STEREO_INTERP_LEN_MS * fs_khz;
+	let denom_q16 = (1 << 16) / interp_len as i32;
+	let delta0_q13 = rshift_round(smulbb(pred_q13[0] - state.pred_prev_q13[0], denom_q16), 16);
+	let delta1_q13 = rshift_round(smulbb(pred_q13[1] - state.pred_prev_q13[1], denom_q16), 16);
+	for n in 0..frame_length {
+		if n < interp_len {
+			pred0_q13 += delta0_q13;
+			pred1_q13 += delta1_q13;
+		} else {
+			pred0_q13 = pred_q13[0];
+			pred1_q13 = pred_q13[1];
+		}
+		
+		let sum = (x1[n] as i32 + x1[n + 2] as i32 + ((x1[n + 1] as i32) << 1)) << 9;
+		let sum = smlawb((x2[n + 1] as i32) << 8, sum, pred0_q13);
+		let sum = smlawb(sum, (x1[n + 1] as i32) << 11, pred1_q13);
+		x2[n + 1] = sat16(rshift_round(sum, 8));
+	}
+	state.pred_prev_q13 = *pred_q13;
+	
+	// convert to left/right
+	for n in 0..frame_length {
+		let sum = x1[n + 1] as i32 + x2[n + 1] as i32;
+		let diff = x1[n + 1] as i32 - x2[n + 1] as i32;
+		x1[n + 1] = sat16(sum);
+		x2[n + 1] = sat16(diff);
+	}
+}
+
+#[inline]
+fn smulwb(a: i32, b: i32) -> i32 {
+	((a as i64 * b as i16 as i64) >> 16) as i32
+}
+
+#[inline]
+fn smlawb(a: i32, b: i32, c: i32) -> i32 {
+	(a as i64 + ((b as i64 * c as i16 as i64) >> 16)) as i32
+}
+
+#[inline]
+fn smulww(a: i32, b: i32) -> i32 {
+	((a as i64 * b as i64) >> 16) as i32
+}
+
+#[inline]
+fn smlaww(a: i32, b: i32, c: i32) -> i32 {
+	(a as i64 + ((b as i64 * c as i64) >> 16)) as i32
+}
+
+#[inline]
+fn smulbb(a: i32, b: i32) -> i32 {
+	a as i16 as i32 * b as i16 as i32
+}
+
+#[inline]
+fn smlabb(a: i32, b: i32, c: i32) -> i32 {
+	a.wrapping_add(smulbb(b, c))
+}
+
+#[inline]
+fn smultt(a: i32, b: i32) -> i32 {
+	(a >> 16) * (b >> 16)
+}
+
+#[inline]
+fn smmul(a: i32, b: i32) -> i32 {
+	((a as i64 * b as i64) >> 32) as i32
+}
+
+#[inline]
+fn rshift_round(a: i32, s: u32) -> i32 {
+	match s {
+		1 => (a >> 1) + (a & 1),
+		_ => ((a >> (s - 1)) + 1) >> 1
+	}
+}
+
+#[inline]
+fn rshift_round64(a: i64, s: u32) -> i64 {
+	match s {
+		1 => (a >> 1) + (a & 1),
+		_ => ((a >> (s - 1)) + 1) >> 1
+	}
+}
+
+#[inline]
+fn sat16(a: i32) -> i16 {
+	a.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+#[inline]
+fn limit(a: i32, l1: i32, l2: i32) -> i32 {
+	match l1 > l2 {
+		true  => a.clamp(l2, l1),
+		false => a.clamp(l1, l2)
+	}
+}
+
+#[inline]
+fn lshift_sat32(a: i32, s: u32) -> i32 {
+	limit(a, i32::MIN >> s, i32::MAX >> s) << s
+}
+
+#[inline]
+fn clz32(a: i32) -> i32 {
+	a.leading_zeros() as i32
+}
+
+#[inline]
+fn silk_rand(seed: i32) -> i32 {
+	907633515i32.wrapping_add(seed.wrapping_mul(196314165))
+}
+
+/// Returns the number of leading zeros and the 7 bits after the leading one.
+fn clz_frac(a: i32) -> (i32, i32) {
+	let lz = clz32(a);
+	(lz, ((a as u32).rotate_right((24 - lz).rem_euclid(32) as u32) & 0x7f) as i32)
+}
+
+fn sqrt_approx(x: i32) -> i32 {
+	if x <= 0 {
+		return 0;
+	}
+	
+	let (lz, frac_q7) = clz_frac(x);
+	let y = match lz & 1 {
+		0 => 46214,
+		_ => 32768
+	} >> (lz >> 1);
+	smlawb(y, y, smulbb(213, frac_q7))
+}
+
+/// Approximates `(a << q_res) / b`.
+fn div32_varq(a: i32, b: i32, q_res: i32) -> i32 {
+	let a_headrm = clz32(a.wrapping_abs()) - 1;
+	let mut a_nrm = a << a_headrm;
+	let b_headrm = clz32(b.wrapping_abs()) - 1;
+	let b_nrm = b << b_headrm;
+	let b_inv = (i32::MAX >> 2) / (b_nrm >> 16);
+	let mut result = smulwb(a_nrm, b_inv);
+	a_nrm = a_nrm.wrapping_sub(smmul(b_nrm, result) << 3);
+	result = smlawb(result, a_nrm, b_inv);
+	
+	let lshift = 29 + a_headrm - b_headrm - q_res;
+	match lshift {
+		..=-1  => lshift_sat32(result, -lshift as u32),
+		0..=31 => result >> lshift,
+		_      => 0
+	}
+}
+
+/// Approximates `(1 << q_res) / b`.
+fn inverse32_varq(b: i32, q_res: i32) -> i32 {
+	let b_headrm = clz32(b.wrapping_abs()) - 1;
+	let b_nrm = b << b_headrm;
+	let b_inv = (i32::MAX >> 2) / (b_nrm >> 16);
+	let mut result = b_inv << 16;
+	let err_q32 = ((1 << 29) - smulwb(b_nrm, b_inv)) << 3;
+	result = smlaww(result, err_q32, b_inv);
+	
+	let lshift = 61 - b_headrm - q_res;
+	match lshift {
+		..=0   => lshift_sat32(result, -lshift as u32),
+		1..=31 => result >> lshift,
+		_      => 0
+	}
+}
+
+/// Approximates `2^(in_log_q7 / 128)`.
+fn log2lin(in_log_q7: i32) -> i32 {
+	if in_log_q7 < 0 {
+		return 0;
+	} else if in_log_q7 >= 3967 {
+		return i32::MAX;
+	}
+	
+	let out = 1 << (in_log_q7 >> 7);
+	let frac_q7 = in_log_q7 & 0x7f;
+	let poly = smlawb(frac_q7, smulbb(frac_q7, 128 - frac_q7), -174);
+	match in_log_q7 < 2048 {
+		true  => out + ((out * poly) >> 7),
+		false => out + (out >> 7) * poly
+	}
+}
+
+/// Returns the energy of the signal and the shift that was applied to keep it in 32 bits.
+fn sum_sqr_shift(x: &[i16]) -> (i32, i32) {
+	fn sum(x: &[i16], shift: i32, init: i32) -> i32 {
+		let mut nrg = init as u32;
+		let mut chunks = x.chunks_exact(2);
+		for c in &mut chunks {
+			let tmp = (smulbb(c[0] as i32, c[0] as i32) as u32).wrapping_add(smulbb(c[1] as i32, c[1] as i32) as u32);
+			nrg = nrg.wrapping_add(tmp >> shift);
+		}
+		if let [v] = chunks.remainder() {
+			nrg = nrg.wrapping_add(smulbb(*v as i32, *v as i32) as u32 >> shift);
+		}
+		nrg as i32
+	}
+	
+	let len = x.len() as i32;
+	let shift = 31 - clz32(len);
+	let nrg = sum(x, shift, len);
+	let shift = (shift + 3 - clz32(nrg)).max(0);
+	(sum(x, shift, 0), shift)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn fixed_point() {
+		assert_eq!(log2lin(0), 1);
+		assert_eq!(log2lin(7 << 7), 128);
+		assert_eq!(log2lin(3967), i32::MAX);
+		assert_eq!(sqrt_approx(1 << 16), 256);
+		assert!((div32_varq(1000, 10, 0) - 100).abs() <= 1);
+		assert!((inverse32_varq(1 << 16, 32) - (1 << 16)).abs() <= 1);
+		assert_eq!(sum_sqr_shift(&[3, 4]), (25, 0));
+	}
+	
+	#[test]
+	fn nlsf_to_lpc() {
+		// evenly spaced NLSFs correspond to a flat spectrum
+		let nlsf = [2979, 5958, 8937, 11916, 14895, 17873, 20852, 23831, 26810, 29789];
+		let mut a_q12 = [0i16; 10];
+		nlsf2a(&mut a_q12, &nlsf);
+		assert!(a_q12.iter().all(|&a| a.abs() < 64), "{:?}", a_q12);
+		assert_ne!(lpc_inverse_pred_gain(&a_q12), 0);
+	}
+}
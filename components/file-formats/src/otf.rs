@@ -0,0 +1,2889 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! TrueType and OpenType font reader.
+//!
+//! [`Font`] reads a single font from a file or from a font collection (`ttcf`). The table
+//! directory and the fixed size tables are read up front, everything else is read on demand
+//! from the borrowed or owned data, so a `Font` is cheap to keep around.
+//!
+//! Supported are:
+//! - character maps in the formats 0, 4, 6 and 12,
+//! - TrueType (`glyf`) outlines including composite glyphs and CFF (`CFF `) outlines,
+//! - horizontal and vertical metrics and the legacy `kern` table,
+//! - `GSUB` single, multiple, alternate, ligature, context, chained context and reverse chained
+//!   substitution,
+//! - `GPOS` single, pair, cursive, mark to base, mark to ligature, mark to mark, context and
+//!   chained context positioning,
+//! - variable fonts with `fvar` axes, `avar` mappings and `gvar` deltas.
+//!
+//! `CFF2` outlines and device tables are not supported.
+//!
+//! The layout functions work on glyph ids, a shaper is expected to map the text to glyphs with
+//! [`Font::glyph_index`] and to choose the script, language and features.
+
+use std::{fmt, io::{Error, ErrorKind}};
+
+/// An index into the glyphs of a font.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GlyphId(pub u16);
+
+/// An OpenType tag, like a table, script or feature name.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Tag(pub [u8; 4]);
+
+impl Tag {
+	pub const fn new(tag: &[u8; 4]) -> Self {
+		Self(*tag)
+	}
+	
+	fn from_u32(v: u32) -> Self {
+		Self(v.to_be_bytes())
+	}
+}
+
+impl fmt::Debug for Tag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Tag({})", self)
+	}
+}
+
+impl fmt::Display for Tag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.iter().try_for_each(|&b| write!(f, "{}", b as char))
+	}
+}
+
+const TTCF: u32 = u32::from_be_bytes(*b"ttcf");
+const OTTO: u32 = u32::from_be_bytes(*b"OTTO");
+const TRUE: u32 = u32::from_be_bytes(*b"true");
+
+const AVAR: Tag = Tag::new(b"avar");
+const CFF:  Tag = Tag::new(b"CFF ");
+const CMAP: Tag = Tag::new(b"cmap");
+const FVAR: Tag = Tag::new(b"fvar");
+const GDEF: Tag = Tag::new(b"GDEF");
+const GLYF: Tag = Tag::new(b"glyf");
+const GPOS: Tag = Tag::new(b"GPOS");
+const GSUB: Tag = Tag::new(b"GSUB");
+const GVAR: Tag = Tag::new(b"gvar");
+const HEAD: Tag = Tag::new(b"head");
+const HHEA: Tag = Tag::new(b"hhea");
+const HMTX: Tag = Tag::new(b"hmtx");
+const KERN: Tag = Tag::new(b"kern");
+const LOCA: Tag = Tag::new(b"loca");
+const MAXP: Tag = Tag::new(b"maxp");
+const VHEA: Tag = Tag::new(b"vhea");
+const VMTX: Tag = Tag::new(b"vmtx");
+
+/// The default script, used if a font has no lookups for the requested script.
+pub const SCRIPT_DEFAULT: Tag = Tag::new(b"DFLT");
+/// The feature of the legacy `kern` table and of pair adjustments.
+pub const FEATURE_KERN: Tag = Tag::new(b"kern");
+
+/// Returns the number of fonts in a font file, which is one unless it is a collection.
+pub fn font_count(data: &[u8]) -> Result<usize, Error> {
+	match data.u32_at(0).ok_or_else(|| invalid_data("unexpected end of data"))? {
+		TTCF => data.u32_at(8).map(|v| v as usize).ok_or_else(|| invalid_data("unexpected end of data")),
+		_    => Ok(1)
+	}
+}
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Receives the contours of a glyph outline, in font units with the y axis pointing up.
+pub trait OutlineBuilder {
+	fn move_to(&mut self, x: f32, y: f32);
+	
+	fn line_to(&mut self, x: f32, y: f32);
+	
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32);
+	
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32);
+	
+	fn close(&mut self);
+}
+
+/// A bounding box in font units.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rect {
+	pub x_min: i16,
+	pub y_min: i16,
+	pub x_max: i16,
+	pub y_max: i16
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct HorizontalMetrics {
+	pub advance:           u16,
+	pub left_side_bearing: i16
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct VerticalMetrics {
+	pub advance:          u16,
+	pub top_side_bearing: i16
+}
+
+/// A variation axis of a variable font, the values are in user coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Axis {
+	pub tag:     Tag,
+	pub min:     f32,
+	pub default: f32,
+	pub max:     f32,
+	pub name_id: u16,
+	pub hidden:  bool
+}
+
+/// A named instance of a variable font, with one user coordinate per axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+	pub subfamily_name_id:  u16,
+	pub postscript_name_id: Option<u16>,
+	pub coords:             Vec<f32>
+}
+
+/// A glyph in a buffer passed to [`Font::substitute`] and [`Font::position`].
+///
+/// The cluster is carried through substitutions, a ligature gets the smallest cluster of its
+/// components. A lookup is only applied to glyphs whose mask intersects the mask of one of the
+/// features that reference the lookup.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GlyphInfo {
+	pub id:      GlyphId,
+	pub cluster: usize,
+	pub mask:    u32
+}
+
+impl GlyphInfo {
+	pub fn new(id: GlyphId, cluster: usize) -> Self {
+		Self { id, cluster, mask: u32::MAX }
+	}
+}
+
+/// The advance and the offset of a positioned glyph, in font units.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlyphPosition {
+	pub x_advance: i32,
+	pub y_advance: i32,
+	pub x_offset:  i32,
+	pub y_offset:  i32
+}
+
+/// The direction of a run of glyphs. The glyphs are always passed in logical order, the
+/// direction decides how attachments are resolved.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Direction {
+	#[default]
+	LeftToRight,
+	RightToLeft
+}
+
+/// A feature to apply, restricted to the glyphs whose mask intersects the given mask.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Feature {
+	pub tag:  Tag,
+	pub mask: u32
+}
+
+impl Feature {
+	/// A feature that applies to all glyphs.
+	pub const fn new(tag: &[u8; 4]) -> Self {
+		Self { tag: Tag::new(tag), mask: u32::MAX }
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+struct TableRecord {
+	tag:    Tag,
+	offset: usize,
+	len:    usize
+}
+
+pub struct Font<D> {
+	data:          D,
+	tables:        Vec<TableRecord>,
+	units_per_em:  u16,
+	bbox:          Rect,
+	index_to_loc:  i16,
+	glyph_count:   u16,
+	ascender:      i16,
+	descender:     i16,
+	line_gap:      i16,
+	h_metrics:     u16,
+	v_metrics:     u16,
+	cmap:          Option<(usize, u16)>,
+	cff:           Option<Cff>,
+	axes:          Vec<Axis>,
+	coords:        Vec<f32>
+}
+
+impl<D: AsRef<[u8]>> Font<D> {
+	/// Parses the font with the given index, which must be zero unless the data is a collection.
+	pub fn parse(data: D, index: usize) -> Result<Self, Error> {
+		let bytes = data.as_ref();
+		let eof = || invalid_data("unexpected end of data");
+		let offset = match bytes.u32_at(0).ok_or_else(eof)? {
+			TTCF => {
+				if index >= bytes.u32_at(8).ok_or_else(eof)? as usize {
+					return Err(Error::new(ErrorKind::InvalidInput, "font index out of range"));
+				}
+				bytes.u32_at(12 + index * 4).ok_or_else(eof)? as usize
+			}
+			_ if index == 0 => 0,
+			_ => return Err(Error::new(ErrorKind::InvalidInput, "font index out of range"))
+		};
+		
+		match bytes.u32_at(offset).ok_or_else(eof)? {
+			0x0001_0000 | OTTO | TRUE => (),
+			_ => return Err(invalid_data("invalid sfnt version"))
+		}
+		
+		let mut tables = (0..bytes.u16_at(offset + 4).ok_or_else(eof)? as usize)
+			.map(|i| {
+				let o = offset + 12 + i * 16;
+				let record = TableRecord {
+					tag:    Tag::from_u32(bytes.u32_at(o).ok_or_else(eof)?),
+					offset: bytes.u32_at(o + 8).ok_or_else(eof)? as usize,
+					len:    bytes.u32_at(o + 12).ok_or_else(eof)? as usize
+				};
+				
+				match bytes.slice(record.offset, record.len) {
+					Some(_) => Ok(record),
+					None    => Err(invalid_data("table out of bounds"))
+				}
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		tables.sort_by_key(|t| t.tag);
+		
+		let table = |tag: Tag| tables.binary_search_by_key(&tag, |t| t.tag).ok()
+			.map(|i| &bytes[tables[i].offset..tables[i].offset + tables[i].len]);
+		let head = table(HEAD).ok_or_else(|| invalid_data("missing head table"))?;
+		let hhea = table(HHEA).ok_or_else(|| invalid_data("missing hhea table"))?;
+		let maxp = table(MAXP).ok_or_else(|| invalid_data("missing maxp table"))?;
+		let vhea = table(VHEA);
+		
+		let cmap = table(CMAP).and_then(Self::parse_cmap);
+		let cff = match table(CFF) {
+			Some(cff) => Some(Cff::parse(cff).ok_or_else(|| invalid_data("invalid CFF table"))?),
+			None      => None
+		};
+		let axes = table(FVAR).map_or(Ok(Vec::new()), |fvar| parse_axes(fvar).ok_or_else(|| invalid_data("invalid fvar table")))?;
+		
+		Ok(Self {
+			units_per_em: head.u16_at(18).ok_or_else(eof)?,
+			bbox:         Rect {
+				x_min: head.i16_at(36).ok_or_else(eof)?,
+				y_min: head.i16_at(38).ok_or_else(eof)?,
+				x_max: head.i16_at(40).ok_or_else(eof)?,
+				y_max: head.i16_at(42).ok_or_else(eof)?
+			},
+			index_to_loc: head.i16_at(50).ok_or_else(eof)?,
+			glyph_count:  maxp.u16_at(4).ok_or_else(eof)?,
+			ascender:     hhea.i16_at(4).ok_or_else(eof)?,
+			descender:    hhea.i16_at(6).ok_or_else(eof)?,
+			line_gap:     hhea.i16_at(8).ok_or_else(eof)?,
+			h_metrics:    hhea.u16_at(34).ok_or_else(eof)?,
+			v_metrics:    vhea.and_then(|vhea| vhea.u16_at(34)).unwrap_or(0),
+			coords:       vec![0.0; axes.len()],
+			tables,
+			cmap,
+			cff,
+			axes,
+			data
+		})
+	}
+	
+	/// Picks the best unicode subtable, preferring full repertoire subtables.
+	fn parse_cmap(cmap: &[u8]) -> Option<(usize, u16)> {
+		let mut best = None;
+		for i in 0..cmap.u16_at(2)? as usize {
+			let platform = cmap.u16_at(4 + i * 8)?;
+			let encoding = cmap.u16_at(6 + i * 8)?;
+			let offset = cmap.u32_at(8 + i * 8)? as usize;
+			let format = cmap.u16_at(offset)?;
+			let rank = match (platform, encoding, format) {
+				(0, 4 | 6, 12) | (3, 10, 12) => 3,
+				(0, _, 4 | 6 | 12) | (3, 1, 4 | 6 | 12) => 2,
+				(0, _, 0) | (3, 0 | 1, 0) => 1,
+				_ => continue
+			};
+			
+			if best.is_none_or(|(r, _, _)| rank > r) {
+				best = Some((rank, offset, format));
+			}
+		}
+		best.map(|(_, offset, format)| (offset, format))
+	}
+	
+	fn data(&self) -> &[u8] {
+		self.data.as_ref()
+	}
+	
+	/// Returns the raw data of a table.
+	pub fn table(&self, tag: Tag) -> Option<&[u8]> {
+		let record = &self.tables[self.tables.binary_search_by_key(&tag, |t| t.tag).ok()?];
+		Some(&self.data()[record.offset..record.offset + record.len])
+	}
+	
+	/// Returns the tags of all tables in the font.
+	pub fn tables(&self) -> impl Iterator<Item = Tag> + '_ {
+		self.tables.iter().map(|t| t.tag)
+	}
+	
+	pub fn units_per_em(&self) -> u16 {
+		self.units_per_em
+	}
+	
+	/// Returns the bounding box of all glyphs, as stored in the `head` table.
+	pub fn global_bbox(&self) -> Rect {
+		self.bbox
+	}
+	
+	pub fn ascender(&self) -> i16 {
+		self.ascender
+	}
+	
+	pub fn descender(&self) -> i16 {
+		self.descender
+	}
+	
+	pub fn line_gap(&self) -> i16 {
+		self.line_gap
+	}
+	
+	pub fn glyph_count(&self) -> u16 {
+		self.glyph_count
+	}
+	
+	/// Maps a character to a glyph, returns `None` if the font has no glyph for it.
+	pub fn glyph_index(&self, ch: char) -> Option<GlyphId> {
+		let (offset, format) = self.cmap?;
+		let cmap = self.table(CMAP)?.get(offset..)?;
+		let c = ch as u32;
+		let id = match format {
+			0 => cmap.u8_at(6 + c as usize).filter(|_| c < 256)? as u16,
+			4 => {
+				let c = u16::try_from(c).ok()?;
+				let seg_x2 = cmap.u16_at(6)? as usize;
+				let i = search(seg_x2 / 2, |i| Some(match cmap.u16_at(14 + i * 2)? {
+					end if end < c => std::cmp::Ordering::Less,
+					_ if i == 0 || cmap.u16_at(12 + i * 2)? < c => std::cmp::Ordering::Equal,
+					_ => std::cmp::Ordering::Greater
+				}))?;
+				let start = cmap.u16_at(16 + seg_x2 + i * 2)?;
+				let delta = cmap.u16_at(16 + seg_x2 * 2 + i * 2)?;
+				let range_pos = 16 + seg_x2 * 3 + i * 2;
+				match (c >= start, cmap.u16_at(range_pos)?) {
+					(false, _) => return None,
+					(true, 0)  => c.wrapping_add(delta),
+					(true, range) => match cmap.u16_at(range_pos + range as usize + (c - start) as usize * 2)? {
+						0  => 0,
+						id => id.wrapping_add(delta)
+					}
+				}
+			}
+			6 => {
+				let first = cmap.u16_at(6)? as u32;
+				let count = cmap.u16_at(8)? as u32;
+				match c.checked_sub(first) {
+					Some(i) if i < count => cmap.u16_at(10 + i as usize * 2)?,
+					_ => return None
+				}
+			}
+			12 => {
+				let i = search(cmap.u32_at(12)? as usize, |i| Some(match (cmap.u32_at(16 + i * 12)?, cmap.u32_at(20 + i * 12)?) {
+					(_, end) if end < c => std::cmp::Ordering::Less,
+					(start, _) if start > c => std::cmp::Ordering::Greater,
+					_ => std::cmp::Ordering::Equal
+				}))?;
+				u16::try_from(cmap.u32_at(24 + i * 12)? + c - cmap.u32_at(16 + i * 12)?).ok()?
+			}
+			_ => return None
+		};
+		
+		(id != 0 && id < self.glyph_count).then_some(GlyphId(id))
+	}
+	
+	pub fn glyph_hor_metrics(&self, glyph: GlyphId) -> Option<HorizontalMetrics> {
+		let (advance, side_bearing) = self.metrics(HMTX, self.h_metrics, glyph)?;
+		let advance = match self.is_variable() {
+			true => match self.phantom_deltas(glyph) {
+				Some(d) => (advance as f32 + d[1].0 - d[0].0).round().max(0.0) as u16,
+				None    => advance
+			},
+			false => advance
+		};
+		Some(HorizontalMetrics { advance, left_side_bearing: side_bearing })
+	}
+	
+	pub fn glyph_ver_metrics(&self, glyph: GlyphId) -> Option<VerticalMetrics> {
+		let (advance, side_bearing) = self.metrics(VMTX, self.v_metrics, glyph)?;
+		let advance = match self.is_variable() {
+			true => match self.phantom_deltas(glyph) {
+				Some(d) => (advance as f32 + d[2].1 - d[3].1).round().max(0.0) as u16,
+				None    => advance
+			},
+			false => advance
+		};
+		Some(VerticalMetrics { advance, top_side_bearing: side_bearing })
+	}
+	
+	fn metrics(&self, tag: Tag, long: u16, glyph: GlyphId) -> Option<(u16, i16)> {
+		let table = self.table(tag)?;
+		let (long, i) = (long as usize, glyph.0 as usize);
+		if glyph.0 >= self.glyph_count || long == 0 {
+			return None;
+		}
+		
+		match i < long {
+			true  => Some((table.u16_at(i * 4)?, table.i16_at(i * 4 + 2)?)),
+			false => Some((table.u16_at((long - 1) * 4)?, table.i16_at(long * 4 + (i - long) * 2).unwrap_or(0)))
+		}
+	}
+	
+	/// Emits the outline of a glyph and returns the bounding box of all its points, or `None` if
+	/// the glyph has no outline.
+	pub fn outline_glyph(&self, glyph: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<Rect> {
+		if glyph.0 >= self.glyph_count {
+			return None;
+		}
+		
+		let mut bounds = Bounds {
+			inner: builder,
+			min:   (f32::MAX, f32::MAX),
+			max:   (f32::MIN, f32::MIN)
+		};
+		
+		match &self.cff {
+			Some(cff) if self.table(GLYF).is_none() => cff.outline(self.table(CFF)?, glyph, &mut bounds)?,
+			_ => {
+				let (mut points, mut ends) = (Vec::new(), Vec::new());
+				self.glyf_points(glyph, 0, &mut points, &mut ends)?;
+				emit_contours(&points, &ends, &mut bounds);
+			}
+		}
+		
+		(bounds.min.0 <= bounds.max.0).then(|| Rect {
+			x_min: bounds.min.0.floor() as i16,
+			y_min: bounds.min.1.floor() as i16,
+			x_max: bounds.max.0.ceil() as i16,
+			y_max: bounds.max.1.ceil() as i16
+		})
+	}
+	
+	fn glyf_data(&self, glyph: GlyphId) -> Option<&[u8]> {
+		let loca = self.table(LOCA)?;
+		let i = glyph.0 as usize;
+		if glyph.0 >= self.glyph_count {
+			return None;
+		}
+		
+		let (start, end) = match self.index_to_loc {
+			0 => (loca.u16_at(i * 2)? as usize * 2, loca.u16_at(i * 2 + 2)? as usize * 2),
+			_ => (loca.u32_at(i * 4)? as usize, loca.u32_at(i * 4 + 4)? as usize)
+		};
+		self.table(GLYF)?.get(start..end)
+	}
+	
+	/// Appends the points of a glyph, with variations applied, and the indices of the last
+	/// point of each contour.
+	fn glyf_points(&self, glyph: GlyphId, depth: u8, points: &mut Vec<Point>, ends: &mut Vec<usize>) -> Option<()> {
+		const ARG_1_AND_2_ARE_WORDS:    u16 = 0x0001;
+		const ARGS_ARE_XY_VALUES:       u16 = 0x0002;
+		const WE_HAVE_A_SCALE:          u16 = 0x0008;
+		const MORE_COMPONENTS:          u16 = 0x0020;
+		const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+		const WE_HAVE_A_TWO_BY_TWO:     u16 = 0x0080;
+		const SCALED_COMPONENT_OFFSET:  u16 = 0x0800;
+		
+		let data = self.glyf_data(glyph)?;
+		if data.is_empty() {
+			return Some(());
+		}
+		
+		let base = points.len();
+		let contours = data.i16_at(0)?;
+		if contours >= 0 {
+			let n = contours as usize;
+			let local_ends = (0..n).map(|i| data.u16_at(10 + i * 2).map(|v| v as usize)).collect::<Option<Vec<_>>>()?;
+			let count = local_ends.last().map_or(0, |&end| end + 1);
+			let mut o = 12 + n * 2 + data.u16_at(10 + n * 2)? as usize;
+			let mut flags = Vec::with_capacity(count);
+			while flags.len() < count {
+				let flag = data.u8_at(o)?;
+				o += 1;
+				flags.push(flag);
+				if flag & 0x08 != 0 {
+					flags.extend(std::iter::repeat_n(flag, data.u8_at(o)? as usize));
+					o += 1;
+				}
+			}
+			flags.truncate(count);
+			
+			let mut local = Vec::with_capacity(count + 4);
+			let mut v = 0i32;
+			for &flag in &flags {
+				match (flag & 0x02 != 0, flag & 0x10 != 0) {
+					(true, positive) => {
+						let d = data.u8_at(o)? as i32;
+						v += if positive { d } else { -d };
+						o += 1;
+					}
+					(false, false) => {
+						v += data.i16_at(o)? as i32;
+						o += 2;
+					}
+					(false, true) => ()
+				}
+				local.push(Point { x: v as f32, y: 0.0, on: flag & 0x01 != 0 });
+			}
+			
+			v = 0;
+			for (point, &flag) in local.iter_mut().zip(&flags) {
+				match (flag & 0x04 != 0, flag & 0x20 != 0) {
+					(true, positive) => {
+						let d = data.u8_at(o)? as i32;
+						v += if positive { d } else { -d };
+						o += 1;
+					}
+					(false, false) => {
+						v += data.i16_at(o)? as i32;
+						o += 2;
+					}
+					(false, true) => ()
+				}
+				point.y = v as f32;
+			}
+			
+			if self.is_variable() {
+				local.extend([Point::default(); 4]);
+				if let Some(deltas) = self.glyph_deltas(glyph, &local, &local_ends) {
+					local.iter_mut().zip(deltas).for_each(|(p, (dx, dy))| {
+						p.x += dx;
+						p.y += dy;
+					});
+				}
+				local.truncate(count);
+			}
+			
+			points.extend(local);
+			ends.extend(local_ends.iter().map(|end| end + base));
+			return Some(());
+		}
+		
+		if depth >= 8 {
+			return None;
+		}
+		
+		let mut components = Vec::new();
+		let mut o = 10;
+		loop {
+			let flags = data.u16_at(o)?;
+			let glyph = GlyphId(data.u16_at(o + 2)?);
+			o += 4;
+			let (arg1, arg2) = match (flags & ARG_1_AND_2_ARE_WORDS != 0, flags & ARGS_ARE_XY_VALUES != 0) {
+				(true, true)   => (data.i16_at(o)? as i32, data.i16_at(o + 2)? as i32),
+				(true, false)  => (data.u16_at(o)? as i32, data.u16_at(o + 2)? as i32),
+				(false, true)  => (data.u8_at(o)? as i8 as i32, data.u8_at(o + 1)? as i8 as i32),
+				(false, false) => (data.u8_at(o)? as i32, data.u8_at(o + 1)? as i32)
+			};
+			o += if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+			let transform = if flags & WE_HAVE_A_SCALE != 0 {
+				let s = data.f2dot14_at(o)?;
+				o += 2;
+				[s, 0.0, 0.0, s]
+			} else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+				o += 4;
+				[data.f2dot14_at(o - 4)?, 0.0, 0.0, data.f2dot14_at(o - 2)?]
+			} else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+				o += 8;
+				[data.f2dot14_at(o - 8)?, data.f2dot14_at(o - 6)?, data.f2dot14_at(o - 4)?, data.f2dot14_at(o - 2)?]
+			} else {
+				[1.0, 0.0, 0.0, 1.0]
+			};
+			
+			components.push((flags, glyph, (arg1 as f32, arg2 as f32), transform));
+			if flags & MORE_COMPONENTS == 0 {
+				break;
+			}
+		}
+		
+		if self.is_variable() {
+			let offsets = components.iter()
+				.map(|&(_, _, (x, y), _)| Point { x, y, on: true })
+				.chain([Point::default(); 4])
+				.collect::<Vec<_>>();
+			if let Some(deltas) = self.glyph_deltas(glyph, &offsets, &[]) {
+				for (component, (dx, dy)) in components.iter_mut().zip(deltas) {
+					if component.0 & ARGS_ARE_XY_VALUES != 0 {
+						component.2 .0 += dx;
+						component.2 .1 += dy;
+					}
+				}
+			}
+		}
+		
+		for (flags, glyph, (arg1, arg2), [a, b, c, d]) in components {
+			let (mut local, mut local_ends) = (Vec::new(), Vec::new());
+			self.glyf_points(glyph, depth + 1, &mut local, &mut local_ends)?;
+			for p in &mut local {
+				(p.x, p.y) = (a * p.x + c * p.y, b * p.x + d * p.y);
+			}
+			
+			let (dx, dy) = match flags & ARGS_ARE_XY_VALUES != 0 {
+				true if flags & SCALED_COMPONENT_OFFSET != 0 => (a * arg1 + c * arg2, b * arg1 + d * arg2),
+				true  => (arg1, arg2),
+				false => {
+					let p1 = points.get(base + arg1 as usize)?;
+					let p2 = local.get(arg2 as usize)?;
+					(p1.x - p2.x, p1.y - p2.y)
+				}
+			};
+			
+			let offset = points.len();
+			points.extend(local.into_iter().map(|p| Point { x: p.x + dx, y: p.y + dy, ..p }));
+			ends.extend(local_ends.into_iter().map(|end| end + offset));
+		}
+		Some(())
+	}
+	
+	/// Returns the number of points of a glyph, excluding the phantom points. For composite
+	/// glyphs, this is the number of components.
+	fn glyf_point_count(&self, glyph: GlyphId) -> Option<usize> {
+		let data = self.glyf_data(glyph)?;
+		if data.is_empty() {
+			return Some(0);
+		}
+		
+		match data.i16_at(0)? {
+			0 => Some(0),
+			n if n > 0 => Some(data.u16_at(10 + (n as usize - 1) * 2)? as usize + 1),
+			_ => {
+				let (mut count, mut o) = (0, 10);
+				loop {
+					let flags = data.u16_at(o)?;
+					count += 1;
+					o += 4 + if flags & 0x0001 != 0 { 4 } else { 2 } + match flags {
+						f if f & 0x0008 != 0 => 2,
+						f if f & 0x0040 != 0 => 4,
+						f if f & 0x0080 != 0 => 8,
+						_ => 0
+					};
+					if flags & 0x0020 == 0 {
+						return Some(count);
+					}
+				}
+			}
+		}
+	}
+	
+	/// Returns the `gvar` deltas of the four phantom points of a glyph.
+	fn phantom_deltas(&self, glyph: GlyphId) -> Option<[(f32, f32); 4]> {
+		let count = self.glyf_point_count(glyph)?;
+		let deltas = self.glyph_deltas(glyph, &vec![Point::default(); count + 4], &[])?;
+		Some([deltas[count], deltas[count + 1], deltas[count + 2], deltas[count + 3]])
+	}
+	
+	// VARIATIONS ----------------------------------------------------------------------------------
+	
+	/// Returns the variation axes, empty if this is not a variable font.
+	pub fn axes(&self) -> &[Axis] {
+		&self.axes
+	}
+	
+	/// Returns the named instances of a variable font.
+	pub fn instances(&self) -> Vec<Instance> {
+		self.table(FVAR).and_then(parse_instances).unwrap_or_default()
+	}
+	
+	/// Returns `true` if any variation coordinate differs from the default.
+	pub fn is_variable(&self) -> bool {
+		self.coords.iter().any(|&v| v != 0.0)
+	}
+	
+	/// Returns the normalized coordinates in the range `-1.0..=1.0`, one per axis.
+	pub fn normalized_coords(&self) -> &[f32] {
+		&self.coords
+	}
+	
+	/// Sets the value of a variation axis in user coordinates, the value is clamped to the range
+	/// of the axis. Returns `false` if the font has no such axis.
+	pub fn set_variation(&mut self, axis: Tag, value: f32) -> bool {
+		let Some(i) = self.axes.iter().position(|a| a.tag == axis) else {
+			return false;
+		};
+		
+		let Axis { min, default, max, .. } = self.axes[i];
+		let value = value.clamp(min, max);
+		let mut v = if value < default {
+			(value - default) / (default - min)
+		} else if value > default {
+			(value - default) / (max - default)
+		} else {
+			0.0
+		};
+		
+		if let Some(avar) = self.table(AVAR) {
+			v = map_avar(avar, i, v).unwrap_or(v);
+		}
+		
+		self.coords[i] = (v.clamp(-1.0, 1.0) * 16384.0).round() / 16384.0;
+		true
+	}
+	
+	/// Resets all axes to their default values.
+	pub fn reset_variations(&mut self) {
+		self.coords.iter_mut().for_each(|v| *v = 0.0);
+	}
+	
+	/// Computes the `gvar` deltas for the points of a glyph, which must include the four phantom
+	/// points. Points without explicit deltas are interpolated within their contour.
+	fn glyph_deltas(&self, glyph: GlyphId, points: &[Point], ends: &[usize]) -> Option<Vec<(f32, f32)>> {
+		let gvar = self.table(GVAR)?;
+		let axis_count = gvar.u16_at(4)? as usize;
+		if axis_count != self.coords.len() {
+			return None;
+		}
+		
+		let shared_count = gvar.u16_at(6)? as usize;
+		let shared = gvar.u32_at(8)? as usize;
+		let g = glyph.0 as usize;
+		let array = gvar.u32_at(16)? as usize;
+		let (start, end) = match gvar.u16_at(14)? & 1 != 0 {
+			true  => (gvar.u32_at(20 + g * 4)? as usize, gvar.u32_at(24 + g * 4)? as usize),
+			false => (gvar.u16_at(20 + g * 2)? as usize * 2, gvar.u16_at(22 + g * 2)? as usize * 2)
+		};
+		let data = gvar.get(array + start..array + end)?;
+		if data.is_empty() {
+			return None;
+		}
+		
+		let count = data.u16_at(0)?;
+		let mut serialized = data.u16_at(2)? as usize;
+		let shared_points = match count & 0x8000 != 0 {
+			true => {
+				let (points, len) = unpack_points(data.get(serialized..)?)?;
+				serialized += len;
+				points
+			}
+			false => None
+		};
+		
+		let read_tuple = |table: &[u8], o: usize| (0..axis_count)
+			.map(|i| table.f2dot14_at(o + i * 2))
+			.collect::<Option<Vec<_>>>();
+		let mut deltas = vec![(0f32, 0f32); points.len()];
+		let mut o = 4;
+		for _ in 0..count & 0x0FFF {
+			let size = data.u16_at(o)? as usize;
+			let index = data.u16_at(o + 2)?;
+			o += 4;
+			let peak = match index & 0x8000 != 0 {
+				true => {
+					o += axis_count * 2;
+					read_tuple(data, o - axis_count * 2)?
+				}
+				false if ((index & 0x0FFF) as usize) < shared_count => read_tuple(gvar, shared + (index & 0x0FFF) as usize * axis_count * 2)?,
+				false => return None
+			};
+			let region = match index & 0x4000 != 0 {
+				true => {
+					o += axis_count * 4;
+					Some((read_tuple(data, o - axis_count * 4)?, read_tuple(data, o - axis_count * 2)?))
+				}
+				false => None
+			};
+			
+			let tuple = data.get(serialized..serialized + size)?;
+			serialized += size;
+			let scalar = tuple_scalar(&self.coords, &peak, region.as_ref());
+			if scalar == 0.0 {
+				continue;
+			}
+			
+			let (tuple_points, mut len) = match index & 0x2000 != 0 {
+				true  => unpack_points(tuple)?,
+				false => (shared_points.clone(), 0)
+			};
+			let n = tuple_points.as_ref().map_or(points.len(), Vec::len);
+			let (xs, x_len) = unpack_deltas(tuple.get(len..)?, n)?;
+			len += x_len;
+			let (ys, _) = unpack_deltas(tuple.get(len..)?, n)?;
+			
+			match tuple_points {
+				None => for (d, (x, y)) in deltas.iter_mut().zip(xs.into_iter().zip(ys)) {
+					d.0 += x * scalar;
+					d.1 += y * scalar;
+				}
+				Some(indices) => {
+					let mut touched = vec![None; points.len()];
+					for (&i, (x, y)) in indices.iter().zip(xs.into_iter().zip(ys)) {
+						if let Some(t) = touched.get_mut(i) {
+							*t = Some((x, y));
+						}
+					}
+					
+					for (d, t) in deltas.iter_mut().zip(interpolate(points, ends, &touched)) {
+						d.0 += t.0 * scalar;
+						d.1 += t.1 * scalar;
+					}
+				}
+			}
+		}
+		Some(deltas)
+	}
+	
+	// LAYOUT --------------------------------------------------------------------------------------
+	
+	/// Returns the horizontal kerning between two glyphs, from the legacy `kern` table or from
+	/// the pair adjustments of the `kern` feature.
+	pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+		if let Some(kern) = self.table(KERN) {
+			return legacy_kerning(kern, left, right);
+		}
+		
+		self.table(GPOS)?;
+		let glyphs = [GlyphInfo::new(left, 0), GlyphInfo::new(right, 1)];
+		let pos = self.position(&glyphs, Direction::LeftToRight, SCRIPT_DEFAULT, None, &[Feature::new(b"kern")]);
+		let advance = self.glyph_hor_metrics(left).map_or(0, |m| m.advance as i32);
+		match pos[0].x_advance - advance + pos[1].x_offset {
+			0 => None,
+			v => Some(v as i16)
+		}
+	}
+	
+	/// Returns the scripts of the `GSUB` and `GPOS` tables, sorted and without duplicates.
+	pub fn scripts(&self) -> Vec<Tag> {
+		let mut scripts = [GSUB, GPOS].into_iter()
+			.filter_map(|tag| self.table(tag)?.sub16(4))
+			.flat_map(|list| (0..list.u16_at(0).unwrap_or(0) as usize)
+				.filter_map(move |i| list.u32_at(2 + i * 6).map(Tag::from_u32)))
+			.collect::<Vec<_>>();
+		scripts.sort();
+		scripts.dedup();
+		scripts
+	}
+	
+	fn glyph_class(&self, glyph: GlyphId) -> u16 {
+		self.table(GDEF).and_then(|gdef| gdef.sub16(4)).map_or(0, |cd| class(cd, glyph))
+	}
+	
+	fn is_mark(&self, glyph: GlyphId) -> bool {
+		self.glyph_class(glyph) == 3
+	}
+	
+	/// Returns `true` if the lookup flags say that the glyph should be skipped.
+	fn ignored(&self, lookup: &Lookup, glyph: GlyphId) -> bool {
+		const IGNORE_BASE_GLYPHS:      u16 = 0x0002;
+		const IGNORE_LIGATURES:        u16 = 0x0004;
+		const IGNORE_MARKS:            u16 = 0x0008;
+		const USE_MARK_FILTERING_SET:  u16 = 0x0010;
+		
+		if lookup.flag & 0xFF1E == 0 {
+			return false;
+		}
+		
+		match self.glyph_class(glyph) {
+			1 => lookup.flag & IGNORE_BASE_GLYPHS != 0,
+			2 => lookup.flag & IGNORE_LIGATURES != 0,
+			3 if lookup.flag & IGNORE_MARKS != 0 => true,
+			3 if lookup.flag & USE_MARK_FILTERING_SET != 0 => self.table(GDEF)
+				.filter(|gdef| gdef.u16_at(2).is_some_and(|minor| minor >= 2))
+				.and_then(|gdef| {
+					let sets = gdef.sub16(12)?;
+					coverage(sets.get(sets.u32_at(4 + lookup.filter as usize * 4)? as usize..)?, glyph)
+				})
+				.is_none(),
+			3 if lookup.flag & 0xFF00 != 0 => self.table(GDEF)
+				.and_then(|gdef| gdef.sub16(10))
+				.map_or(0, |cd| class(cd, glyph)) != lookup.flag >> 8,
+			_ => false
+		}
+	}
+	
+	fn next(&self, lookup: &Lookup, glyphs: &[GlyphInfo], i: usize) -> Option<usize> {
+		(i + 1..glyphs.len()).find(|&j| !self.ignored(lookup, glyphs[j].id))
+	}
+	
+	fn prev(&self, lookup: &Lookup, glyphs: &[GlyphInfo], i: usize) -> Option<usize> {
+		(0..i).rev().find(|&j| !self.ignored(lookup, glyphs[j].id))
+	}
+	
+	/// Applies the `GSUB` lookups of the given features, in lookup order.
+	pub fn substitute(&self, glyphs: &mut Vec<GlyphInfo>, script: Tag, language: Option<Tag>, features: &[Feature]) {
+		let Some(gsub) = self.table(GSUB) else {
+			return;
+		};
+		
+		for (index, mask) in lookups(gsub, script, language, features) {
+			let Some(lookup) = Lookup::parse(gsub, index, 7) else {
+				continue;
+			};
+			
+			if lookup.kind == 8 {
+				for i in (0..glyphs.len()).rev() {
+					if glyphs[i].mask & mask != 0 && !self.ignored(&lookup, glyphs[i].id) {
+						self.apply_substitution(gsub, &lookup, glyphs, i, mask, 0);
+					}
+				}
+				continue;
+			}
+			
+			let mut i = 0;
+			while i < glyphs.len() {
+				if glyphs[i].mask & mask != 0 && !self.ignored(&lookup, glyphs[i].id) {
+					if let Some(next) = self.apply_substitution(gsub, &lookup, glyphs, i, mask, 0) {
+						i = next;
+						continue;
+					}
+				}
+				i += 1;
+			}
+		}
+	}
+	
+	/// Applies a substitution lookup at the given position and returns the position after the
+	/// substituted glyphs, or `None` if no subtable matched.
+	fn apply_substitution(
+		&self,
+		gsub:   &[u8],
+		lookup: &Lookup,
+		glyphs: &mut Vec<GlyphInfo>,
+		i:      usize,
+		mask:   u32,
+		depth:  u8
+	) -> Option<usize> {
+		let glyph = glyphs[i].id;
+		for &sub in &lookup.subtables {
+			let applied = match lookup.kind {
+				1 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					glyphs[i].id = match sub.u16_at(0)? {
+						1 => GlyphId(glyph.0.wrapping_add(sub.u16_at(4)?)),
+						2 => GlyphId(sub.u16_at(6 + c * 2)?),
+						_ => return None
+					};
+					Some(i + 1)
+				}),
+				2 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let sequence = sub.sub16(6 + c * 2)?;
+					let ids = (0..sequence.u16_at(0)? as usize)
+						.map(|k| sequence.u16_at(2 + k * 2).map(|id| GlyphInfo { id: GlyphId(id), ..glyphs[i] }))
+						.collect::<Option<Vec<_>>>()?;
+					let len = ids.len();
+					glyphs.splice(i..i + 1, ids);
+					Some(i + len)
+				}),
+				3 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let alternates = sub.sub16(6 + c * 2)?;
+					alternates.u16_at(0).filter(|&n| n > 0)?;
+					glyphs[i].id = GlyphId(alternates.u16_at(2)?);
+					Some(i + 1)
+				}),
+				4 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let set = sub.sub16(6 + c * 2)?;
+					(0..set.u16_at(0)? as usize).find_map(|k| {
+						let ligature = set.sub16(2 + k * 2)?;
+						let mut matched = vec![i];
+						for n in 1..ligature.u16_at(2)? as usize {
+							let j = self.next(lookup, glyphs, *matched.last()?)?;
+							if glyphs[j].id.0 != ligature.u16_at(2 + n * 2)? || glyphs[j].mask & mask == 0 {
+								return None;
+							}
+							matched.push(j);
+						}
+						
+						glyphs[i].id = GlyphId(ligature.u16_at(0)?);
+						glyphs[i].cluster = matched.iter().map(|&j| glyphs[j].cluster).min()?;
+						matched[1..].iter().rev().for_each(|&j| { glyphs.remove(j); });
+						Some(i + 1)
+					})
+				}),
+				5 | 6 => match self.match_context(sub, lookup.kind == 6, lookup, glyphs, i) {
+					Some((mut matched, records, count)) if depth < 8 => {
+						for k in 0..count {
+							let (seq, index) = (records.u16_at(k * 4)? as usize, records.u16_at(k * 4 + 2)?);
+							let (Some(&pos), Some(nested)) = (matched.get(seq), Lookup::parse(gsub, index, 7)) else {
+								continue;
+							};
+							
+							let len = glyphs.len();
+							if pos < len {
+								self.apply_substitution(gsub, &nested, glyphs, pos, mask, depth + 1);
+							}
+							let delta = glyphs.len() as isize - len as isize;
+							matched.iter_mut().filter(|j| **j > pos).for_each(|j| *j = (*j as isize + delta).max(pos as isize) as usize);
+						}
+						Some((matched.last()? + 1).clamp(i + 1, glyphs.len().max(i + 1)))
+					}
+					_ => None
+				},
+				8 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let back_count = sub.u16_at(4)? as usize;
+					let ahead_pos = 6 + back_count * 2;
+					let ahead_count = sub.u16_at(ahead_pos)? as usize;
+					let subst_pos = ahead_pos + 2 + ahead_count * 2;
+					let matches = |j: usize, o: usize| sub.sub16(o).and_then(|cov| coverage(cov, glyphs[j].id)).is_some();
+					
+					let mut j = i;
+					for k in 0..back_count {
+						j = self.prev(lookup, glyphs, j)?;
+						matches(j, 6 + k * 2).then_some(())?;
+					}
+					j = i;
+					for k in 0..ahead_count {
+						j = self.next(lookup, glyphs, j)?;
+						matches(j, ahead_pos + 2 + k * 2).then_some(())?;
+					}
+					
+					glyphs[i].id = GlyphId(sub.u16_at(subst_pos + 2 + c * 2)?);
+					Some(i + 1)
+				}),
+				_ => None
+			};
+			
+			if applied.is_some() {
+				return applied;
+			}
+		}
+		None
+	}
+	
+	/// Positions the glyphs with their advances from the `hmtx` table and the `GPOS` lookups of
+	/// the given features. Fonts without a `GPOS` table are kerned with the legacy `kern` table
+	/// if the `kern` feature is requested. Marks have no advance.
+	///
+	/// The positions are in the order of the glyphs, a right-to-left run has to be reversed
+	/// by the caller to get the visual order.
+	pub fn position(
+		&self,
+		glyphs:    &[GlyphInfo],
+		direction: Direction,
+		script:    Tag,
+		language:  Option<Tag>,
+		features:  &[Feature]
+	) -> Vec<GlyphPosition> {
+		let mut positions = glyphs.iter()
+			.map(|g| GlyphPosition {
+				x_advance: self.glyph_hor_metrics(g.id).map_or(0, |m| m.advance as i32),
+				..GlyphPosition::default()
+			})
+			.collect::<Vec<_>>();
+		
+		let Some(gpos) = self.table(GPOS) else {
+			if let (Some(kern), Some(feature)) = (self.table(KERN), features.iter().find(|f| f.tag == FEATURE_KERN)) {
+				for i in 1..glyphs.len() {
+					if glyphs[i - 1].mask & feature.mask != 0 {
+						positions[i - 1].x_advance += legacy_kerning(kern, glyphs[i - 1].id, glyphs[i].id).unwrap_or(0) as i32;
+					}
+				}
+			}
+			return positions;
+		};
+		
+		let mut attachments = vec![None; glyphs.len()];
+		for (index, mask) in lookups(gpos, script, language, features) {
+			let Some(lookup) = Lookup::parse(gpos, index, 9) else {
+				continue;
+			};
+			
+			let mut i = 0;
+			while i < glyphs.len() {
+				if glyphs[i].mask & mask != 0 && !self.ignored(&lookup, glyphs[i].id) {
+					if let Some(next) = self.apply_positioning(gpos, &lookup, glyphs, &mut positions, &mut attachments, direction, i, mask, 0) {
+						i = next.max(i + 1);
+						continue;
+					}
+				}
+				i += 1;
+			}
+		}
+		
+		for (g, p) in glyphs.iter().zip(&mut positions) {
+			if self.is_mark(g.id) {
+				p.x_advance = 0;
+				p.y_advance = 0;
+			}
+		}
+		
+		// attached glyphs are offset relative to their parent, resolve them after the advances
+		// of the marks were zeroed
+		for i in 0..positions.len() {
+			resolve_attachment(&mut positions, &mut attachments, direction, i);
+		}
+		positions
+	}
+	
+	/// Applies a positioning lookup at the given position and returns the position of the next
+	/// glyph to process, or `None` if no subtable matched.
+	#[allow(clippy::too_many_arguments)]
+	fn apply_positioning(
+		&self,
+		gpos:        &[u8],
+		lookup:      &Lookup,
+		glyphs:      &[GlyphInfo],
+		positions:   &mut [GlyphPosition],
+		attachments: &mut [Option<Attachment>],
+		direction:   Direction,
+		i:           usize,
+		mask:        u32,
+		depth:       u8
+	) -> Option<usize> {
+		let glyph = glyphs[i].id;
+		for &sub in &lookup.subtables {
+			let applied = match lookup.kind {
+				1 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let format = sub.u16_at(4)?;
+					let o = match sub.u16_at(0)? {
+						1 => 6,
+						2 => 8 + c * value_size(format),
+						_ => return None
+					};
+					apply_value(sub, o, format, &mut positions[i])?;
+					Some(i + 1)
+				}),
+				2 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let j = self.next(lookup, glyphs, i).filter(|&j| glyphs[j].mask & mask != 0)?;
+					let (format1, format2) = (sub.u16_at(4)?, sub.u16_at(6)?);
+					let (size1, size2) = (value_size(format1), value_size(format2));
+					let (record, o) = match sub.u16_at(0)? {
+						1 => {
+							let set = sub.sub16(10 + c * 2)?;
+							let size = 2 + size1 + size2;
+							let k = search(set.u16_at(0)? as usize, |k| Some(set.u16_at(2 + k * size)?.cmp(&glyphs[j].id.0)))?;
+							(set, 4 + k * size)
+						}
+						2 => {
+							let class1 = class(sub.sub16(8)?, glyph) as usize;
+							let class2 = class(sub.sub16(10)?, glyphs[j].id) as usize;
+							let (count1, count2) = (sub.u16_at(12)? as usize, sub.u16_at(14)? as usize);
+							if class1 >= count1 || class2 >= count2 {
+								return None;
+							}
+							(sub, 16 + (class1 * count2 + class2) * (size1 + size2))
+						}
+						_ => return None
+					};
+					
+					apply_value(record, o, format1, &mut positions[i])?;
+					apply_value(record, o + size1, format2, &mut positions[j])?;
+					Some(if format2 != 0 { j + 1 } else { j })
+				}),
+				3 => coverage(sub.sub16(2)?, glyph).and_then(|c| {
+					let entry = anchor(sub.sub16(6 + c * 4)?)?;
+					let prev = self.prev(lookup, glyphs, i).filter(|&j| glyphs[j].mask & mask != 0)?;
+					let exit = anchor(sub.sub16(8 + coverage(sub.sub16(2)?, glyphs[prev].id)? * 4)?)?;
+					attach_cursive(positions, attachments, direction, lookup.flag & RIGHT_TO_LEFT != 0, prev, i, exit, entry);
+					Some(i + 1)
+				}),
+				4 | 5 => coverage(sub.sub16(2)?, glyph).and_then(|m| {
+					let base = (0..i).rev().find(|&j| !self.is_mark(glyphs[j].id))?;
+					let b = coverage(sub.sub16(4)?, glyphs[base].id)?;
+					let classes = sub.u16_at(6)? as usize;
+					let (mark_class, mark_anchor) = mark_record(sub.sub16(8)?, m)?;
+					let array = sub.sub16(10)?;
+					let (table, o) = match lookup.kind {
+						4 => (array, 2 + (b * classes + mark_class) * 2),
+						_ => {
+							let attach = array.sub16(2 + b * 2)?;
+							let component = (attach.u16_at(0)? as usize).checked_sub(1)?;
+							(attach, 2 + (component * classes + mark_class) * 2)
+						}
+					};
+					attach_mark(positions, attachments, i, base, anchor(table.sub16(o)?)?, mark_anchor);
+					Some(i + 1)
+				}),
+				6 => coverage(sub.sub16(2)?, glyph).and_then(|m| {
+					let prev = self.prev(lookup, glyphs, i).filter(|&j| self.is_mark(glyphs[j].id))?;
+					let b = coverage(sub.sub16(4)?, glyphs[prev].id)?;
+					let classes = sub.u16_at(6)? as usize;
+					let (mark_class, mark_anchor) = mark_record(sub.sub16(8)?, m)?;
+					let base_anchor = anchor(sub.sub16(10)?.sub16(2 + (b * classes + mark_class) * 2)?)?;
+					attach_mark(positions, attachments, i, prev, base_anchor, mark_anchor);
+					Some(i + 1)
+				}),
+				7 | 8 => match self.match_context(sub, lookup.kind == 8, lookup, glyphs, i) {
+					Some((matched, records, count)) if depth < 8 => {
+						for k in 0..count {
+							let (seq, index) = (records.u16_at(k * 4)? as usize, records.u16_at(k * 4 + 2)?);
+							if let (Some(&pos), Some(nested)) = (matched.get(seq), Lookup::parse(gpos, index, 9)) {
+								self.apply_positioning(gpos, &nested, glyphs, positions, attachments, direction, pos, mask, depth + 1);
+							}
+						}
+						Some(matched.last()? + 1)
+					}
+					_ => None
+				},
+				_ => None
+			};
+			
+			if applied.is_some() {
+				return applied;
+			}
+		}
+		None
+	}
+	
+	/// Matches a context or chained context subtable at the given position. Returns the
+	/// positions of the input glyphs and the sequence lookup records.
+	fn match_context<'a>(
+		&self,
+		sub:     &'a [u8],
+		chained: bool,
+		lookup:  &Lookup,
+		glyphs:  &[GlyphInfo],
+		i:       usize
+	) -> Option<(Vec<usize>, &'a [u8], usize)> {
+		let glyph = glyphs[i].id;
+		match (sub.u16_at(0)?, chained) {
+			(1 | 2, _) => {
+				let c = coverage(sub.sub16(2)?, glyph)?;
+				let (matchers, sets) = match (sub.u16_at(0)?, chained) {
+					(1, false) => ([Matcher::Glyph; 3], 6),
+					(1, true)  => ([Matcher::Glyph; 3], 6),
+					(_, false) => ([Matcher::Class(sub.sub16(4)?); 3], 8),
+					(_, true)  => ([
+						Matcher::Class(sub.sub16(4).unwrap_or(&[])),
+						Matcher::Class(sub.sub16(6)?),
+						Matcher::Class(sub.sub16(8).unwrap_or(&[]))
+					], 12)
+				};
+				let set = match matchers[1] {
+					Matcher::Class(cd) => sub.sub16(sets + class(cd, glyph) as usize * 2),
+					_ => sub.sub16(sets + c * 2)
+				}?;
+				
+				(0..set.u16_at(0)? as usize).find_map(|k| {
+					let rule = set.sub16(2 + k * 2)?;
+					let (back, input, ahead, records) = match chained {
+						false => {
+							let input = rule.u16_at(0)? as usize;
+							(Seq::default(), Seq::new(rule, 4, input.checked_sub(1)?), Seq::default(), 4 + (input - 1) * 2)
+						}
+						true => {
+							let back = Seq::new(rule, 2, rule.u16_at(0)? as usize);
+							let input_pos = back.end();
+							let input = Seq::new(rule, input_pos + 2, (rule.u16_at(input_pos)? as usize).checked_sub(1)?);
+							let ahead_pos = input.end();
+							let ahead = Seq::new(rule, ahead_pos + 2, rule.u16_at(ahead_pos)? as usize);
+							(back, input, ahead, ahead.end())
+						}
+					};
+					
+					let (count, records) = match chained {
+						false => (rule.u16_at(2)? as usize, rule.get(records..)?),
+						true  => (rule.u16_at(records)? as usize, rule.get(records + 2..)?)
+					};
+					self.match_rule(lookup, glyphs, i, &matchers, [back, input, ahead])
+						.map(|matched| (matched, records, count))
+				})
+			}
+			(3, false) => {
+				let input = Seq::new(sub, 6, sub.u16_at(2)? as usize);
+				coverage(sub.sub16(6)?, glyph)?;
+				let count = sub.u16_at(4)? as usize;
+				let matched = self.match_rule(lookup, glyphs, i, &[Matcher::Coverage(sub); 3], [
+					Seq::default(), Seq { offset: input.offset + 2, len: input.len.checked_sub(1)?, ..input }, Seq::default()])?;
+				Some((matched, sub.get(input.end()..)?, count))
+			}
+			(3, true) => {
+				let back = Seq::new(sub, 4, sub.u16_at(2)? as usize);
+				let input = Seq::new(sub, back.end() + 2, sub.u16_at(back.end())? as usize);
+				let ahead = Seq::new(sub, input.end() + 2, sub.u16_at(input.end())? as usize);
+				coverage(sub.sub16(input.offset)?, glyph)?;
+				let count = sub.u16_at(ahead.end())? as usize;
+				let matched = self.match_rule(lookup, glyphs, i, &[Matcher::Coverage(sub); 3], [
+					back, Seq { offset: input.offset + 2, len: input.len.checked_sub(1)?, ..input }, ahead])?;
+				Some((matched, sub.get(ahead.end() + 2..)?, count))
+			}
+			_ => None
+		}
+	}
+	
+	/// Matches the backtrack, the input after the first glyph and the lookahead sequence of a
+	/// rule and returns the positions of the input glyphs.
+	fn match_rule(&self, lookup: &Lookup, glyphs: &[GlyphInfo], i: usize, matchers: &[Matcher; 3], [back, input, ahead]: [Seq; 3]) -> Option<Vec<usize>> {
+		let mut matched = vec![i];
+		let mut j = i;
+		for k in 0..input.len {
+			j = self.next(lookup, glyphs, j)?;
+			matchers[1].matches(glyphs[j].id, input.get(k)?).then_some(())?;
+			matched.push(j);
+		}
+		
+		j = i;
+		for k in 0..back.len {
+			j = self.prev(lookup, glyphs, j)?;
+			matchers[0].matches(glyphs[j].id, back.get(k)?).then_some(())?;
+		}
+		
+		j = *matched.last()?;
+		for k in 0..ahead.len {
+			j = self.next(lookup, glyphs, j)?;
+			matchers[2].matches(glyphs[j].id, ahead.get(k)?).then_some(())?;
+		}
+		Some(matched)
+	}
+}
+
+impl<D> fmt::Debug for Font<D> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Font")
+			.field("tables", &self.tables.iter().map(|t| t.tag).collect::<Vec<_>>())
+			.field("units_per_em", &self.units_per_em)
+			.field("glyph_count", &self.glyph_count)
+			.field("axes", &self.axes)
+			.finish_non_exhaustive()
+	}
+}
+
+// BINARY HELPERS ----------------------------------------------------------------------------------
+
+trait Bytes {
+	fn u8_at(&self, o: usize) -> Option<u8>;
+	
+	fn u16_at(&self, o: usize) -> Option<u16>;
+	
+	fn u32_at(&self, o: usize) -> Option<u32>;
+	
+	fn slice(&self, o: usize, len: usize) -> Option<&[u8]>;
+	
+	/// Returns the subtable at the 16 bit offset stored at the given position, `None` if the
+	/// offset is null.
+	fn sub16(&self, o: usize) -> Option<&[u8]>;
+	
+	fn i16_at(&self, o: usize) -> Option<i16> {
+		self.u16_at(o).map(|v| v as i16)
+	}
+	
+	fn f2dot14_at(&self, o: usize) -> Option<f32> {
+		self.i16_at(o).map(|v| v as f32 / 16384.0)
+	}
+	
+	fn fixed_at(&self, o: usize) -> Option<f32> {
+		self.u32_at(o).map(|v| v as i32 as f32 / 65536.0)
+	}
+}
+
+impl Bytes for [u8] {
+	fn u8_at(&self, o: usize) -> Option<u8> {
+		self.get(o).copied()
+	}
+	
+	fn u16_at(&self, o: usize) -> Option<u16> {
+		Some(u16::from_be_bytes(self.get(o..o.checked_add(2)?)?.try_into().ok()?))
+	}
+	
+	fn u32_at(&self, o: usize) -> Option<u32> {
+		Some(u32::from_be_bytes(self.get(o..o.checked_add(4)?)?.try_into().ok()?))
+	}
+	
+	fn slice(&self, o: usize, len: usize) -> Option<&[u8]> {
+		self.get(o..o.checked_add(len)?)
+	}
+	
+	fn sub16(&self, o: usize) -> Option<&[u8]> {
+		match self.u16_at(o)? {
+			0 => None,
+			offset => self.get(offset as usize..)
+		}
+	}
+}
+
+/// Binary search over `len` elements, `f` compares an element to the target.
+fn search(len: usize, f: impl Fn(usize) -> Option<std::cmp::Ordering>) -> Option<usize> {
+	let (mut lo, mut hi) = (0, len);
+	while lo < hi {
+		let mid = (lo + hi) / 2;
+		match f(mid)? {
+			std::cmp::Ordering::Less    => lo = mid + 1,
+			std::cmp::Ordering::Greater => hi = mid,
+			std::cmp::Ordering::Equal   => return Some(mid)
+		}
+	}
+	None
+}
+
+// OUTLINES ----------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Point {
+	x:  f32,
+	y:  f32,
+	on: bool
+}
+
+/// Forwards an outline and tracks the bounds of all points.
+struct Bounds<'a> {
+	inner: &'a mut dyn OutlineBuilder,
+	min:   (f32, f32),
+	max:   (f32, f32)
+}
+
+impl Bounds<'_> {
+	fn extend(&mut self, x: f32, y: f32) {
+		self.min = (self.min.0.min(x), self.min.1.min(y));
+		self.max = (self.max.0.max(x), self.max.1.max(y));
+	}
+}
+
+impl OutlineBuilder for Bounds<'_> {
+	fn move_to(&mut self, x: f32, y: f32) {
+		self.extend(x, y);
+		self.inner.move_to(x, y);
+	}
+	
+	fn line_to(&mut self, x: f32, y: f32) {
+		self.extend(x, y);
+		self.inner.line_to(x, y);
+	}
+	
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+		self.extend(x1, y1);
+		self.extend(x, y);
+		self.inner.quad_to(x1, y1, x, y);
+	}
+	
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+		self.extend(x1, y1);
+		self.extend(x2, y2);
+		self.extend(x, y);
+		self.inner.curve_to(x1, y1, x2, y2, x, y);
+	}
+	
+	fn close(&mut self) {
+		self.inner.close();
+	}
+}
+
+/// Emits quadratic contours, inserting the implied on-curve points between two off-curve points.
+/// Each contour is explicitly closed with a segment back to its first point.
+fn emit_contours(points: &[Point], ends: &[usize], builder: &mut dyn OutlineBuilder) {
+	let mid = |a: Point, b: Point| Point { x: (a.x + b.x) / 2.0, y: (a.y + b.y) / 2.0, on: true };
+	let mut start = 0;
+	for &end in ends {
+		let Some(contour) = points.get(start..=end) else {
+			break;
+		};
+		start = end + 1;
+		
+		let n = contour.len();
+		let (first, skip) = match contour.iter().position(|p| p.on) {
+			Some(k) => (contour[k], k + 1),
+			None    => (mid(contour[n - 1], contour[0]), 0)
+		};
+		
+		builder.move_to(first.x, first.y);
+		let mut control: Option<Point> = None;
+		let mut last = first;
+		for &p in contour.iter().cycle().skip(skip).take(if skip == 0 { n } else { n - 1 }) {
+			match (p.on, control) {
+				(true, Some(c)) => builder.quad_to(c.x, c.y, p.x, p.y),
+				(true, None)    => builder.line_to(p.x, p.y),
+				(false, Some(c)) => {
+					let m = mid(c, p);
+					builder.quad_to(c.x, c.y, m.x, m.y);
+				}
+				(false, None) => ()
+			}
+			
+			control = (!p.on).then_some(p);
+			last = p;
+		}
+		
+		match control {
+			Some(c) => builder.quad_to(c.x, c.y, first.x, first.y),
+			None if (last.x, last.y) != (first.x, first.y) => builder.line_to(first.x, first.y),
+			None => ()
+		}
+		builder.close();
+	}
+}
+
+// VARIATIONS --------------------------------------------------------------------------------------
+
+fn parse_axes(fvar: &[u8]) -> Option<Vec<Axis>> {
+	let offset = fvar.u16_at(4)? as usize;
+	let size = fvar.u16_at(10)? as usize;
+	(0..fvar.u16_at(8)? as usize)
+		.map(|i| {
+			let o = offset + i * size;
+			Some(Axis {
+				tag:     Tag::from_u32(fvar.u32_at(o)?),
+				min:     fvar.fixed_at(o + 4)?,
+				default: fvar.fixed_at(o + 8)?,
+				max:     fvar.fixed_at(o + 12)?,
+				hidden:  fvar.u16_at(o + 16)? & 1 != 0,
+				name_id: fvar.u16_at(o + 18)?
+			})
+		})
+		.collect()
+}
+
+fn parse_instances(fvar: &[u8]) -> Option<Vec<Instance>> {
+	let axes = fvar.u16_at(8)? as usize;
+	let size = fvar.u16_at(14)? as usize;
+	let offset = fvar.u16_at(4)? as usize + axes * fvar.u16_at(10)? as usize;
+	(0..fvar.u16_at(12)? as usize)
+		.map(|i| {
+			let o = offset + i * size;
+			Some(Instance {
+				subfamily_name_id:  fvar.u16_at(o)?,
+				postscript_name_id: (size >= axes * 4 + 6).then(|| fvar.u16_at(o + 4 + axes * 4)).flatten(),
+				coords:             (0..axes).map(|k| fvar.fixed_at(o + 4 + k * 4)).collect::<Option<_>>()?
+			})
+		})
+		.collect()
+}
+
+/// Maps a normalized coordinate through the segment map of an axis.
+fn map_avar(avar: &[u8], axis: usize, v: f32) -> Option<f32> {
+	let mut o = 8;
+	for _ in 0..axis {
+		o += 2 + avar.u16_at(o)? as usize * 4;
+	}
+	
+	let count = avar.u16_at(o)? as usize;
+	let map = |k: usize| Some((avar.f2dot14_at(o + 2 + k * 4)?, avar.f2dot14_at(o + 4 + k * 4)?));
+	let mut prev = map(0)?;
+	if v <= prev.0 {
+		return Some(prev.1);
+	}
+	
+	for k in 1..count {
+		let next = map(k)?;
+		if v <= next.0 {
+			return Some(prev.1 + (v - prev.0) * (next.1 - prev.1) / (next.0 - prev.0));
+		}
+		prev = next;
+	}
+	Some(prev.1)
+}
+
+/// Computes the scalar of a tuple variation for the current coordinates.
+fn tuple_scalar(coords: &[f32], peak: &[f32], region: Option<&(Vec<f32>, Vec<f32>)>) -> f32 {
+	let mut scalar = 1.0;
+	for (i, (&c, &p)) in coords.iter().zip(peak).enumerate() {
+		if p == 0.0 || c == p {
+			continue;
+		} else if c == 0.0 {
+			return 0.0;
+		}
+		
+		match region {
+			Some((start, end)) => {
+				let (s, e) = (start[i], end[i]);
+				if s > p || p > e || (s < 0.0 && e > 0.0) {
+					continue;
+				} else if c < s || c > e {
+					return 0.0;
+				} else if c < p {
+					scalar *= (c - s) / (p - s);
+				} else {
+					scalar *= (e - c) / (e - p);
+				}
+			}
+			None if c < p.min(0.0) || c > p.max(0.0) => return 0.0,
+			None => scalar *= c / p
+		}
+	}
+	scalar
+}
+
+/// Unpacks point numbers, `None` means all points.
+fn unpack_points(data: &[u8]) -> Option<(Option<Vec<usize>>, usize)> {
+	let (count, mut o) = match data.u8_at(0)? {
+		0 => return Some((None, 1)),
+		b if b & 0x80 != 0 => (((b as usize & 0x7F) << 8) | data.u8_at(1)? as usize, 2),
+		b => (b as usize, 1)
+	};
+	
+	let mut points = Vec::with_capacity(count);
+	let mut point = 0;
+	while points.len() < count {
+		let control = data.u8_at(o)?;
+		o += 1;
+		for _ in 0..(control & 0x7F) as usize + 1 {
+			point += match control & 0x80 != 0 {
+				true  => data.u16_at(o)? as usize,
+				false => data.u8_at(o)? as usize
+			};
+			o += if control & 0x80 != 0 { 2 } else { 1 };
+			points.push(point);
+		}
+	}
+	points.truncate(count);
+	Some((Some(points), o))
+}
+
+fn unpack_deltas(data: &[u8], count: usize) -> Option<(Vec<f32>, usize)> {
+	let mut deltas = Vec::with_capacity(count);
+	let mut o = 0;
+	while deltas.len() < count {
+		let control = data.u8_at(o)?;
+		let run = (control & 0x3F) as usize + 1;
+		o += 1;
+		match control & 0xC0 {
+			0x80 => deltas.extend(std::iter::repeat_n(0.0, run)),
+			0x40 => for _ in 0..run {
+				deltas.push(data.i16_at(o)? as f32);
+				o += 2;
+			}
+			0x00 => for _ in 0..run {
+				deltas.push(data.u8_at(o)? as i8 as f32);
+				o += 1;
+			}
+			_ => for _ in 0..run {
+				deltas.push(data.u32_at(o)? as i32 as f32);
+				o += 4;
+			}
+		}
+	}
+	deltas.truncate(count);
+	Some((deltas, o))
+}
+
+/// Infers the deltas of untouched points from the nearest touched points of the same contour.
+/// Points outside of contours, like the phantom points, keep a zero delta.
+fn interpolate(points: &[Point], ends: &[usize], touched: &[Option<(f32, f32)>]) -> Vec<(f32, f32)> {
+	fn infer(v: f32, v1: f32, v2: f32, d1: f32, d2: f32) -> f32 {
+		if v1 == v2 {
+			return if d1 == d2 { d1 } else { 0.0 };
+		}
+		
+		let (lo, hi, d_lo, d_hi) = if v1 < v2 { (v1, v2, d1, d2) } else { (v2, v1, d2, d1) };
+		if v <= lo {
+			d_lo
+		} else if v >= hi {
+			d_hi
+		} else {
+			d_lo + (v - lo) * (d_hi - d_lo) / (hi - lo)
+		}
+	}
+	
+	let mut deltas = touched.iter().map(|t| t.unwrap_or((0.0, 0.0))).collect::<Vec<_>>();
+	let mut start = 0;
+	for &end in ends {
+		if end >= touched.len() || end < start {
+			break;
+		}
+		
+		let refs = (start..=end).filter(|&i| touched[i].is_some()).collect::<Vec<_>>();
+		for (k, &p) in refs.iter().enumerate() {
+			let n = refs[(k + 1) % refs.len()];
+			let (dp, dn) = (deltas[p], deltas[n]);
+			let mut i = if p == end { start } else { p + 1 };
+			while i != n {
+				deltas[i] = (
+					infer(points[i].x, points[p].x, points[n].x, dp.0, dn.0),
+					infer(points[i].y, points[p].y, points[n].y, dp.1, dn.1)
+				);
+				i = if i == end { start } else { i + 1 };
+			}
+		}
+		start = end + 1;
+	}
+	deltas
+}
+
+// LAYOUT ------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+struct Lookup<'a> {
+	kind:      u16,
+	flag:      u16,
+	filter:    u16,
+	subtables: Vec<&'a [u8]>
+}
+
+impl<'a> Lookup<'a> {
+	/// Parses a lookup of a `GSUB` or `GPOS` table and resolves extension subtables.
+	fn parse(table: &'a [u8], index: u16, extension: u16) -> Option<Self> {
+		let list = table.sub16(8)?;
+		let lookup = list.sub16(2 + index as usize * 2).filter(|_| index < list.u16_at(0).unwrap_or(0))?;
+		let mut kind = lookup.u16_at(0)?;
+		let flag = lookup.u16_at(2)?;
+		let count = lookup.u16_at(4)? as usize;
+		let subtables = (0..count)
+			.map(|k| {
+				let sub = lookup.sub16(6 + k * 2)?;
+				match kind == extension {
+					true  => sub.get(sub.u32_at(4)? as usize..),
+					false => Some(sub)
+				}
+			})
+			.collect::<Option<Vec<_>>>()?;
+		
+		if kind == extension {
+			kind = lookup.sub16(6)?.u16_at(2)?;
+		}
+		
+		Some(Self {
+			kind,
+			flag,
+			filter: if flag & 0x0010 != 0 { lookup.u16_at(6 + count * 2)? } else { 0 },
+			subtables
+		})
+	}
+}
+
+/// Collects the lookups of the requested features with the combined mask of the features,
+/// sorted by lookup index.
+fn lookups(table: &[u8], script: Tag, language: Option<Tag>, features: &[Feature]) -> Vec<(u16, u32)> {
+	fn inner(table: &[u8], script: Tag, language: Option<Tag>, features: &[Feature]) -> Option<Vec<(u16, u32)>> {
+		let scripts = table.sub16(4)?;
+		let find = |list: &[u8], o: usize, size: usize, tag: Tag| (0..list.u16_at(o)? as usize)
+			.find(|&i| list.u32_at(o + 2 + i * size).map(Tag::from_u32) == Some(tag))
+			.map(|i| o + 2 + i * size);
+		let record = [script, SCRIPT_DEFAULT, Tag::new(b"latn")].into_iter()
+			.find_map(|tag| find(scripts, 0, 6, tag))?;
+		let script = scripts.sub16(record + 4)?;
+		let lang = match language.and_then(|tag| find(script, 2, 6, tag)) {
+			Some(record) => script.sub16(record + 4)?,
+			None         => script.sub16(0)?
+		};
+		
+		let list = table.sub16(6)?;
+		let mut lookups = Vec::new();
+		let mut add = |index: usize, mask: u32| -> Option<()> {
+			let feature = list.sub16(2 + index * 6 + 4)?;
+			for k in 0..feature.u16_at(2)? as usize {
+				lookups.push((feature.u16_at(4 + k * 2)?, mask));
+			}
+			Some(())
+		};
+		
+		if let Some(required) = lang.u16_at(2).filter(|&v| v != 0xFFFF) {
+			add(required as usize, u32::MAX);
+		}
+		
+		for k in 0..lang.u16_at(4)? as usize {
+			let index = lang.u16_at(6 + k * 2)? as usize;
+			let tag = Tag::from_u32(list.u32_at(2 + index * 6)?);
+			let mask = features.iter().filter(|f| f.tag == tag).fold(0, |mask, f| mask | f.mask);
+			if mask != 0 {
+				add(index, mask);
+			}
+		}
+		
+		lookups.sort_by_key(|&(index, _)| index);
+		lookups.dedup_by(|(i1, m1), (i0, m0)| {
+			let duplicate = i1 == i0;
+			if duplicate {
+				*m0 |= *m1;
+			}
+			duplicate
+		});
+		Some(lookups)
+	}
+	
+	inner(table, script, language, features).unwrap_or_default()
+}
+
+fn coverage(table: &[u8], glyph: GlyphId) -> Option<usize> {
+	let g = glyph.0;
+	match table.u16_at(0)? {
+		1 => search(table.u16_at(2)? as usize, |i| Some(table.u16_at(4 + i * 2)?.cmp(&g))),
+		2 => {
+			let i = search(table.u16_at(2)? as usize, |i| Some(match (table.u16_at(4 + i * 6)?, table.u16_at(6 + i * 6)?) {
+				(_, end) if end < g => std::cmp::Ordering::Less,
+				(start, _) if start > g => std::cmp::Ordering::Greater,
+				_ => std::cmp::Ordering::Equal
+			}))?;
+			Some(table.u16_at(8 + i * 6)? as usize + (g - table.u16_at(4 + i * 6)?) as usize)
+		}
+		_ => None
+	}
+}
+
+fn class(table: &[u8], glyph: GlyphId) -> u16 {
+	let g = glyph.0;
+	let class = match table.u16_at(0) {
+		Some(1) => table.u16_at(2).and_then(|start| table.u16_at(6 + g.checked_sub(start)? as usize * 2)
+			.filter(|_| g - start < table.u16_at(4).unwrap_or(0))),
+		Some(2) => search(table.u16_at(2).unwrap_or(0) as usize, |i| Some(match (table.u16_at(4 + i * 6)?, table.u16_at(6 + i * 6)?) {
+			(_, end) if end < g => std::cmp::Ordering::Less,
+			(start, _) if start > g => std::cmp::Ordering::Greater,
+			_ => std::cmp::Ordering::Equal
+		})).and_then(|i| table.u16_at(8 + i * 6)),
+		_ => None
+	};
+	class.unwrap_or(0)
+}
+
+/// How the values of a context rule are matched against glyphs.
+#[derive(Copy, Clone, Debug)]
+enum Matcher<'a> {
+	Glyph,
+	Class(&'a [u8]),
+	/// The values are offsets to coverage tables, relative to the given subtable.
+	Coverage(&'a [u8])
+}
+
+impl Matcher<'_> {
+	fn matches(&self, glyph: GlyphId, value: u16) -> bool {
+		match *self {
+			Self::Glyph         => glyph.0 == value,
+			Self::Class(cd)     => class(cd, glyph) == value,
+			Self::Coverage(sub) => sub.get(value as usize..).and_then(|cov| coverage(cov, glyph)).is_some()
+		}
+	}
+}
+
+/// An array of 16 bit values in a subtable.
+#[derive(Copy, Clone, Debug, Default)]
+struct Seq<'a> {
+	data:   &'a [u8],
+	offset: usize,
+	len:    usize
+}
+
+impl<'a> Seq<'a> {
+	fn new(data: &'a [u8], offset: usize, len: usize) -> Self {
+		Self { data, offset, len }
+	}
+	
+	fn get(&self, i: usize) -> Option<u16> {
+		self.data.u16_at(self.offset + i * 2)
+	}
+	
+	fn end(&self) -> usize {
+		self.offset + self.len * 2
+	}
+}
+
+fn value_size(format: u16) -> usize {
+	(format & 0xFF).count_ones() as usize * 2
+}
+
+/// Adds a value record to a glyph position. Device tables are skipped.
+fn apply_value(data: &[u8], mut o: usize, format: u16, position: &mut GlyphPosition) -> Option<()> {
+	let fields = [&mut position.x_offset, &mut position.y_offset, &mut position.x_advance, &mut position.y_advance];
+	for (bit, field) in fields.into_iter().enumerate() {
+		if format & (1 << bit) != 0 {
+			*field += data.i16_at(o)? as i32;
+			o += 2;
+		}
+	}
+	Some(())
+}
+
+fn anchor(table: &[u8]) -> Option<(i32, i32)> {
+	Some((table.i16_at(2)? as i32, table.i16_at(4)? as i32))
+}
+
+/// Returns the class and the anchor of a mark from a mark array.
+fn mark_record(array: &[u8], index: usize) -> Option<(usize, (i32, i32))> {
+	let class = array.u16_at(2 + index * 4)? as usize;
+	Some((class, anchor(array.sub16(4 + index * 4)?)?))
+}
+
+/// The lookup flag of cursive attachments that attach each glyph to the following glyph
+/// instead of the preceding one.
+const RIGHT_TO_LEFT: u16 = 0x0001;
+
+/// A glyph whose offset is relative to another glyph.
+#[derive(Copy, Clone, Debug)]
+struct Attachment {
+	parent:  usize,
+	cursive: bool
+}
+
+/// Moves a mark so that its anchor coincides with the anchor of the base.
+fn attach_mark(
+	positions:   &mut [GlyphPosition],
+	attachments: &mut [Option<Attachment>],
+	mark:        usize,
+	base:        usize,
+	base_anchor: (i32, i32),
+	mark_anchor: (i32, i32)
+) {
+	positions[mark].x_offset = base_anchor.0 - mark_anchor.0;
+	positions[mark].y_offset = base_anchor.1 - mark_anchor.1;
+	attachments[mark] = Some(Attachment { parent: base, cursive: false });
+}
+
+/// Connects the exit anchor of a glyph to the entry anchor of the next glyph. The advances are
+/// adjusted so that the anchors meet horizontally, vertically the child is offset relative to
+/// its parent, which is the later glyph unless the lookup has the right-to-left flag.
+#[allow(clippy::too_many_arguments)]
+fn attach_cursive(
+	positions:     &mut [GlyphPosition],
+	attachments:   &mut [Option<Attachment>],
+	direction:     Direction,
+	right_to_left: bool,
+	prev:          usize,
+	cur:           usize,
+	exit:          (i32, i32),
+	entry:         (i32, i32)
+) {
+	match direction {
+		Direction::LeftToRight => {
+			positions[prev].x_advance = exit.0 + positions[prev].x_offset;
+			let d = entry.0 + positions[cur].x_offset;
+			positions[cur].x_advance -= d;
+			positions[cur].x_offset -= d;
+		}
+		Direction::RightToLeft => {
+			let d = exit.0 + positions[prev].x_offset;
+			positions[prev].x_advance -= d;
+			positions[prev].x_offset -= d;
+			positions[cur].x_advance = entry.0 + positions[cur].x_offset;
+		}
+	}
+	
+	let (child, parent, y_offset) = match right_to_left {
+		true  => (prev, cur, entry.1 - exit.1),
+		false => (cur, prev, exit.1 - entry.1)
+	};
+	
+	// a child that is already attached becomes the parent of its old chain
+	reverse_cursive_chain(positions, attachments, child, parent);
+	positions[child].y_offset = y_offset;
+	attachments[child] = Some(Attachment { parent, cursive: true });
+	if attachments[parent].is_some_and(|a| a.parent == child) {
+		attachments[parent] = None;
+	}
+}
+
+fn reverse_cursive_chain(positions: &mut [GlyphPosition], attachments: &mut [Option<Attachment>], i: usize, new_parent: usize) {
+	let Some(Attachment { parent, cursive: true }) = attachments[i] else {
+		return;
+	};
+	
+	attachments[i] = None;
+	if parent == new_parent {
+		return;
+	}
+	
+	reverse_cursive_chain(positions, attachments, parent, new_parent);
+	positions[parent].y_offset = -positions[i].y_offset;
+	attachments[parent] = Some(Attachment { parent: i, cursive: true });
+}
+
+/// Makes the offset of an attached glyph relative to the origin of the glyph, after the offset
+/// of its parent was resolved.
+fn resolve_attachment(positions: &mut [GlyphPosition], attachments: &mut [Option<Attachment>], direction: Direction, i: usize) {
+	let Some(Attachment { parent, cursive }) = attachments[i].take() else {
+		return;
+	};
+	
+	resolve_attachment(positions, attachments, direction, parent);
+	positions[i].y_offset += positions[parent].y_offset;
+	if cursive {
+		return;
+	}
+	
+	positions[i].x_offset += positions[parent].x_offset;
+	positions[i].x_offset += match direction {
+		Direction::LeftToRight => -positions[parent..i].iter().map(|p| p.x_advance).sum::<i32>(),
+		Direction::RightToLeft => positions[parent + 1..=i].iter().map(|p| p.x_advance).sum::<i32>()
+	};
+}
+
+/// Sums the kerning of a pair over the horizontal subtables of a `kern` table, in the OpenType
+/// or the Apple layout. The subtable formats 0, 2 and 3 are supported.
+fn legacy_kerning(kern: &[u8], left: GlyphId, right: GlyphId) -> Option<i16> {
+	let (apple, count, mut o) = match kern.u16_at(0)? {
+		0 => (false, kern.u16_at(2)? as u32, 4),
+		1 => (true, kern.u32_at(4)?, 8),
+		_ => return None
+	};
+	
+	let mut value = None;
+	for _ in 0..count {
+		let (len, format, horizontal, header) = match apple {
+			false => (kern.u16_at(o + 2)? as usize, kern.u8_at(o + 4)?, kern.u8_at(o + 5)? & 0x07 == 0x01, 6),
+			true  => (kern.u32_at(o)? as usize, kern.u8_at(o + 5)?, kern.u8_at(o + 4)? & 0xE0 == 0, 8)
+		};
+		
+		if horizontal {
+			if let Some(v) = kern.get(o..).and_then(|sub| kern_subtable(sub, header, format, left, right)) {
+				value = Some(value.unwrap_or(0i16).wrapping_add(v));
+			}
+		}
+		o += len.max(header);
+	}
+	value
+}
+
+fn kern_subtable(sub: &[u8], h: usize, format: u8, left: GlyphId, right: GlyphId) -> Option<i16> {
+	match format {
+		0 => {
+			let key = ((left.0 as u32) << 16) | right.0 as u32;
+			let i = search(sub.u16_at(h)? as usize, |i| Some(sub.u32_at(h + 8 + i * 6)?.cmp(&key)))?;
+			sub.i16_at(h + 12 + i * 6)
+		}
+		2 => {
+			let class = |table: usize, glyph: GlyphId| {
+				let first = sub.u16_at(table)?;
+				let index = glyph.0.checked_sub(first).filter(|&i| i < sub.u16_at(table + 2).unwrap_or(0))?;
+				sub.u16_at(table + 4 + index as usize * 2)
+			};
+			let left = class(sub.u16_at(h + 2)? as usize, left).unwrap_or(0) as usize;
+			let right = class(sub.u16_at(h + 4)? as usize, right).unwrap_or(0) as usize;
+			if left < sub.u16_at(h + 6)? as usize {
+				return None;
+			}
+			sub.i16_at(left + right)
+		}
+		3 => {
+			let glyphs = sub.u16_at(h)? as usize;
+			let (values, left_classes, right_classes) = (sub.u8_at(h + 2)? as usize, sub.u8_at(h + 3)? as usize, sub.u8_at(h + 4)? as usize);
+			let (l, r) = (left.0 as usize, right.0 as usize);
+			if l >= glyphs || r >= glyphs {
+				return None;
+			}
+			
+			let classes = h + 6 + values * 2;
+			let (lc, rc) = (sub.u8_at(classes + l)? as usize, sub.u8_at(classes + glyphs + r)? as usize);
+			if lc >= left_classes || rc >= right_classes {
+				return None;
+			}
+			
+			let index = sub.u8_at(classes + glyphs * 2 + lc * right_classes + rc)? as usize;
+			(index < values).then(|| sub.i16_at(h + 6 + index * 2)).flatten()
+		}
+		_ => None
+	}
+}
+
+// CFF ---------------------------------------------------------------------------------------------
+
+/// A CFF INDEX, the positions are relative to the start of the table.
+#[derive(Copy, Clone, Debug, Default)]
+struct Index {
+	count:    usize,
+	off_size: usize,
+	offsets:  usize
+}
+
+impl Index {
+	/// Parses an INDEX and returns it with the position after its data.
+	fn parse(cff: &[u8], pos: usize) -> Option<(Self, usize)> {
+		let count = cff.u16_at(pos)? as usize;
+		if count == 0 {
+			return Some((Self::default(), pos + 2));
+		}
+		
+		let index = Self { count, off_size: cff.u8_at(pos + 2)? as usize, offsets: pos + 3 };
+		if !(1..=4).contains(&index.off_size) {
+			return None;
+		}
+		
+		let end = index.data_start() + index.offset(cff, count)?;
+		Some((index, end))
+	}
+	
+	fn data_start(&self) -> usize {
+		self.offsets + (self.count + 1) * self.off_size - 1
+	}
+	
+	fn offset(&self, cff: &[u8], i: usize) -> Option<usize> {
+		let o = self.offsets + i * self.off_size;
+		Some(cff.get(o..o + self.off_size)?.iter().fold(0, |v, &b| (v << 8) | b as usize))
+	}
+	
+	fn get<'a>(&self, cff: &'a [u8], i: usize) -> Option<&'a [u8]> {
+		if i >= self.count {
+			return None;
+		}
+		
+		let start = self.offset(cff, i)?;
+		let end = self.offset(cff, i + 1)?;
+		cff.get(self.data_start() + start..self.data_start() + end)
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Cff {
+	char_strings: Index,
+	global_subrs: Index,
+	local_subrs:  Option<Index>,
+	/// The position of the FDSelect and the local subroutines of each font DICT of a CID font.
+	cid:          Option<(usize, Vec<Option<Index>>)>
+}
+
+impl Cff {
+	const CHAR_STRINGS: u16 = 17;
+	const PRIVATE:      u16 = 18;
+	const SUBRS:        u16 = 19;
+	const FD_ARRAY:     u16 = 0x0C24;
+	const FD_SELECT:    u16 = 0x0C25;
+	
+	fn parse(cff: &[u8]) -> Option<Self> {
+		let (_names, pos) = Index::parse(cff, cff.u8_at(2)? as usize)?;
+		let (top, pos) = Index::parse(cff, pos)?;
+		let (_strings, pos) = Index::parse(cff, pos)?;
+		let (global_subrs, _) = Index::parse(cff, pos)?;
+		let top = dict(top.get(cff, 0)?);
+		let operand = |dict: &[(u16, Vec<f64>)], op: u16, i: usize| dict.iter()
+			.find(|(o, _)| *o == op)
+			.and_then(|(_, operands)| operands.get(i).map(|&v| v as usize));
+		let local_subrs = |dict: &[(u16, Vec<f64>)]| -> Option<Index> {
+			let (size, offset) = (operand(dict, Self::PRIVATE, 0)?, operand(dict, Self::PRIVATE, 1)?);
+			let private = dict_at(cff, offset, size)?;
+			Some(Index::parse(cff, offset + operand(&private, Self::SUBRS, 0)?)?.0)
+		};
+		
+		let cid = match (operand(&top, Self::FD_ARRAY, 0), operand(&top, Self::FD_SELECT, 0)) {
+			(Some(fd_array), Some(fd_select)) => {
+				let (fds, _) = Index::parse(cff, fd_array)?;
+				let subrs = (0..fds.count).map(|i| local_subrs(&dict(fds.get(cff, i)?))).collect();
+				Some((fd_select, subrs))
+			}
+			_ => None
+		};
+		
+		Some(Self {
+			char_strings: Index::parse(cff, operand(&top, Self::CHAR_STRINGS, 0)?)?.0,
+			local_subrs:  local_subrs(&top),
+			global_subrs,
+			cid
+		})
+	}
+	
+	/// Returns the font DICT of a glyph of a CID font.
+	fn fd_index(cff: &[u8], fd_select: usize, glyph: u16) -> Option<usize> {
+		match cff.u8_at(fd_select)? {
+			0 => cff.u8_at(fd_select + 1 + glyph as usize).map(|v| v as usize),
+			3 => {
+				let count = cff.u16_at(fd_select + 1)? as usize;
+				(0..count).find_map(|i| {
+					let o = fd_select + 3 + i * 3;
+					let (first, next) = (cff.u16_at(o)?, cff.u16_at(o + 3)?);
+					(first..next).contains(&glyph).then(|| cff.u8_at(o + 2).map(|v| v as usize)).flatten()
+				})
+			}
+			_ => None
+		}
+	}
+	
+	fn outline(&self, cff: &[u8], glyph: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<()> {
+		let char_string = self.char_strings.get(cff, glyph.0 as usize)?;
+		let local = match &self.cid {
+			Some((fd_select, subrs)) => *subrs.get(Self::fd_index(cff, *fd_select, glyph.0)?)?,
+			None => self.local_subrs
+		};
+		
+		let mut interpreter = CharString {
+			cff,
+			global:  self.global_subrs,
+			local,
+			builder,
+			stack:   Vec::with_capacity(48),
+			x:       0.0,
+			y:       0.0,
+			start:   None,
+			hints:   0,
+			width:   false
+		};
+		interpreter.run(char_string, 0)?;
+		interpreter.close();
+		Some(())
+	}
+}
+
+fn dict_at(cff: &[u8], offset: usize, size: usize) -> Option<Vec<(u16, Vec<f64>)>> {
+	Some(dict(cff.slice(offset, size)?))
+}
+
+/// Parses a DICT into operators with their operands.
+fn dict(data: &[u8]) -> Vec<(u16, Vec<f64>)> {
+	let mut entries = Vec::new();
+	let mut operands = Vec::new();
+	let mut i = 0;
+	while let Some(&b) = data.get(i) {
+		i += 1;
+		match b {
+			0..=21 => {
+				let op = match b {
+					12 => {
+						i += 1;
+						0x0C00 | data.get(i - 1).copied().unwrap_or(0) as u16
+					}
+					_ => b as u16
+				};
+				entries.push((op, std::mem::take(&mut operands)));
+			}
+			28 => {
+				operands.push(data.i16_at(i).unwrap_or(0) as f64);
+				i += 2;
+			}
+			29 => {
+				operands.push(data.u32_at(i).unwrap_or(0) as i32 as f64);
+				i += 4;
+			}
+			30 => {
+				let mut s = String::new();
+				'real: while let Some(&b) = data.get(i) {
+					i += 1;
+					for nibble in [b >> 4, b & 0xF] {
+						match nibble {
+							0..=9 => s.push((b'0' + nibble) as char),
+							0xA   => s.push('.'),
+							0xB   => s.push('E'),
+							0xC   => s.push_str("E-"),
+							0xE   => s.push('-'),
+							0xF   => break 'real,
+							_     => ()
+						}
+					}
+				}
+				operands.push(s.parse().unwrap_or(0.0));
+			}
+			32..=246 => operands.push(b as f64 - 139.0),
+			247..=250 => {
+				operands.push(((b as f64 - 247.0) * 256.0) + data.u8_at(i).unwrap_or(0) as f64 + 108.0);
+				i += 1;
+			}
+			251..=254 => {
+				operands.push(-((b as f64 - 251.0) * 256.0) - data.u8_at(i).unwrap_or(0) as f64 - 108.0);
+				i += 1;
+			}
+			_ => ()
+		}
+	}
+	entries
+}
+
+/// A Type 2 charstring interpreter.
+struct CharString<'a, 'b> {
+	cff:     &'a [u8],
+	global:  Index,
+	local:   Option<Index>,
+	builder: &'b mut dyn OutlineBuilder,
+	stack:   Vec<f32>,
+	x:       f32,
+	y:       f32,
+	/// The first point of the current contour.
+	start:   Option<(f32, f32)>,
+	hints:   usize,
+	width:   bool
+}
+
+impl CharString<'_, '_> {
+	fn bias(count: usize) -> usize {
+		match count {
+			0..1240      => 107,
+			1240..33900  => 1131,
+			_            => 32768
+		}
+	}
+	
+	/// Removes the optional width that precedes the arguments of the first stack clearing
+	/// operator, if the number of arguments exceeds `expected`.
+	fn strip_width(&mut self, has_width: bool) {
+		if !self.width {
+			self.width = true;
+			if has_width && !self.stack.is_empty() {
+				self.stack.remove(0);
+			}
+		}
+	}
+	
+	fn stems(&mut self) {
+		self.strip_width(self.stack.len() % 2 == 1);
+		self.hints += self.stack.len() / 2;
+		self.stack.clear();
+	}
+	
+	fn close(&mut self) {
+		if let Some(start) = self.start.take() {
+			if start != (self.x, self.y) {
+				self.builder.line_to(start.0, start.1);
+			}
+			self.builder.close();
+		}
+	}
+	
+	fn move_to(&mut self, dx: f32, dy: f32) {
+		self.close();
+		self.x += dx;
+		self.y += dy;
+		self.start = Some((self.x, self.y));
+		self.builder.move_to(self.x, self.y);
+	}
+	
+	fn line_to(&mut self, dx: f32, dy: f32) {
+		self.x += dx;
+		self.y += dy;
+		self.builder.line_to(self.x, self.y);
+	}
+	
+	fn curve_to(&mut self, d: [f32; 6]) {
+		let (x1, y1) = (self.x + d[0], self.y + d[1]);
+		let (x2, y2) = (x1 + d[2], y1 + d[3]);
+		(self.x, self.y) = (x2 + d[4], y2 + d[5]);
+		self.builder.curve_to(x1, y1, x2, y2, self.x, self.y);
+	}
+	
+	/// Runs a charstring, returns `Some(true)` once `endchar` was reached.
+	fn run(&mut self, code: &[u8], depth: u8) -> Option<bool> {
+		if depth > 10 {
+			return None;
+		}
+		
+		let mut i = 0;
+		while let Some(&b) = code.get(i) {
+			i += 1;
+			match b {
+				1 | 3 | 18 | 23 => self.stems(),
+				19 | 20 => {
+					self.stems();
+					i += self.hints.div_ceil(8);
+				}
+				21 => {
+					self.strip_width(self.stack.len() > 2);
+					let (dx, dy) = (*self.stack.first()?, *self.stack.get(1)?);
+					self.move_to(dx, dy);
+					self.stack.clear();
+				}
+				22 | 4 => {
+					self.strip_width(self.stack.len() > 1);
+					let d = *self.stack.first()?;
+					if b == 22 { self.move_to(d, 0.0) } else { self.move_to(0.0, d) }
+					self.stack.clear();
+				}
+				5 => {
+					for pair in std::mem::take(&mut self.stack).chunks_exact(2) {
+						self.line_to(pair[0], pair[1]);
+					}
+				}
+				6 | 7 => {
+					let mut horizontal = b == 6;
+					for d in std::mem::take(&mut self.stack) {
+						if horizontal { self.line_to(d, 0.0) } else { self.line_to(0.0, d) }
+						horizontal = !horizontal;
+					}
+				}
+				8 => {
+					for c in std::mem::take(&mut self.stack).chunks_exact(6) {
+						self.curve_to([c[0], c[1], c[2], c[3], c[4], c[5]]);
+					}
+				}
+				24 => {
+					let stack = std::mem::take(&mut self.stack);
+					let split = stack.len().checked_sub(2)?;
+					for c in stack[..split].chunks_exact(6) {
+						self.curve_to([c[0], c[1], c[2], c[3], c[4], c[5]]);
+					}
+					self.line_to(stack[split], stack[split + 1]);
+				}
+				25 => {
+					let stack = std::mem::take(&mut self.stack);
+					let split = stack.len().checked_sub(6)?;
+					for pair in stack[..split].chunks_exact(2) {
+						self.line_to(pair[0], pair[1]);
+					}
+					let c = &stack[split..];
+					self.curve_to([c[0], c[1], c[2], c[3], c[4], c[5]]);
+				}
+				26 | 27 => {
+					let stack = std::mem::take(&mut self.stack);
+					let (mut first, rest) = match stack.len() % 2 {
+						1 => (stack[0], &stack[1..]),
+						_ => (0.0, &stack[..])
+					};
+					for c in rest.chunks_exact(4) {
+						match b {
+							26 => self.curve_to([first, c[0], c[1], c[2], 0.0, c[3]]),
+							_  => self.curve_to([c[0], first, c[1], c[2], c[3], 0.0])
+						}
+						first = 0.0;
+					}
+				}
+				30 | 31 => {
+					let stack = std::mem::take(&mut self.stack);
+					let mut horizontal = b == 31;
+					let chunks = stack.len() / 4;
+					for (k, c) in stack.chunks_exact(4).enumerate() {
+						let last = if k + 1 == chunks && stack.len() % 4 == 1 { stack[stack.len() - 1] } else { 0.0 };
+						match horizontal {
+							true  => self.curve_to([c[0], 0.0, c[1], c[2], last, c[3]]),
+							false => self.curve_to([0.0, c[0], c[1], c[2], c[3], last])
+						}
+						horizontal = !horizontal;
+					}
+				}
+				10 | 29 => {
+					let index = match b {
+						10 => self.local?,
+						_  => self.global
+					};
+					let subr = (self.stack.pop()? as isize + Self::bias(index.count) as isize) as usize;
+					if self.run(index.get(self.cff, subr)?, depth + 1)? {
+						return Some(true);
+					}
+				}
+				11 => return Some(false),
+				14 => {
+					self.strip_width(self.stack.len() == 1 || self.stack.len() == 5);
+					self.stack.clear();
+					return Some(true);
+				}
+				12 => {
+					let op = *code.get(i)?;
+					i += 1;
+					let s = std::mem::take(&mut self.stack);
+					match (op, s.len()) {
+						(35, 13) => {
+							self.curve_to([s[0], s[1], s[2], s[3], s[4], s[5]]);
+							self.curve_to([s[6], s[7], s[8], s[9], s[10], s[11]]);
+						}
+						(34, 7) => {
+							self.curve_to([s[0], 0.0, s[1], s[2], s[3], 0.0]);
+							self.curve_to([s[4], 0.0, s[5], -s[2], s[6], 0.0]);
+						}
+						(36, 9) => {
+							self.curve_to([s[0], s[1], s[2], s[3], s[4], 0.0]);
+							self.curve_to([s[5], 0.0, s[6], s[7], s[8], -(s[1] + s[3] + s[7])]);
+						}
+						(37, 11) => {
+							let dx = s[0] + s[2] + s[4] + s[6] + s[8];
+							let dy = s[1] + s[3] + s[5] + s[7] + s[9];
+							let (dx6, dy6) = match dx.abs() > dy.abs() {
+								true  => (s[10], -dy),
+								false => (-dx, s[10])
+							};
+							self.curve_to([s[0], s[1], s[2], s[3], s[4], s[5]]);
+							self.curve_to([s[6], s[7], s[8], s[9], dx6, dy6]);
+						}
+						_ => ()
+					}
+				}
+				28 => {
+					self.stack.push(code.i16_at(i)? as f32);
+					i += 2;
+				}
+				32..=246 => self.stack.push(b as f32 - 139.0),
+				247..=250 => {
+					self.stack.push((b as f32 - 247.0) * 256.0 + *code.get(i)? as f32 + 108.0);
+					i += 1;
+				}
+				251..=254 => {
+					self.stack.push(-(b as f32 - 251.0) * 256.0 - *code.get(i)? as f32 - 108.0);
+					i += 1;
+				}
+				255 => {
+					self.stack.push(code.u32_at(i)? as i32 as f32 / 65536.0);
+					i += 4;
+				}
+				_ => self.stack.clear()
+			}
+			
+			if self.stack.len() > 48 {
+				return None;
+			}
+		}
+		Some(false)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	const NOTDEF: u16 = 0;
+	const A:      u16 = 1;
+	const F:      u16 = 2;
+	const I:      u16 = 3;
+	const FI:     u16 = 4;
+	const ACUTE:  u16 = 5;
+	const B:      u16 = 6;
+	const I_ALT:  u16 = 7;
+	
+	fn words(values: &[u16]) -> Vec<u8> {
+		values.iter().flat_map(|v| v.to_be_bytes()).collect()
+	}
+	
+	fn tag(tag: &[u8; 4]) -> [u16; 2] {
+		[u16::from_be_bytes([tag[0], tag[1]]), u16::from_be_bytes([tag[2], tag[3]])]
+	}
+	
+	#[derive(Default)]
+	struct Recorder(Vec<String>);
+	
+	impl OutlineBuilder for Recorder {
+		fn move_to(&mut self, x: f32, y: f32) {
+			self.0.push(format!("M {x} {y}"));
+		}
+		
+		fn line_to(&mut self, x: f32, y: f32) {
+			self.0.push(format!("L {x} {y}"));
+		}
+		
+		fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+			self.0.push(format!("Q {x1} {y1} {x} {y}"));
+		}
+		
+		fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+			self.0.push(format!("C {x1} {y1} {x2} {y2} {x} {y}"));
+		}
+		
+		fn close(&mut self) {
+			self.0.push("Z".to_string());
+		}
+	}
+	
+	fn sfnt(version: u32, tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+		let mut buf = version.to_be_bytes().to_vec();
+		buf.extend(words(&[tables.len() as u16, 0, 0, 0]));
+		let mut offset = 12 + tables.len() * 16;
+		for (tag, data) in tables {
+			buf.extend(*tag);
+			buf.extend([0; 4]);
+			buf.extend((offset as u32).to_be_bytes());
+			buf.extend((data.len() as u32).to_be_bytes());
+			offset += data.len().next_multiple_of(4);
+		}
+		
+		for (_, data) in tables {
+			buf.extend(data);
+			buf.resize(buf.len().next_multiple_of(4), 0);
+		}
+		buf
+	}
+	
+	/// The tables shared by all test fonts, with eight glyphs and the given advances.
+	fn base_tables(advances: [u16; 8]) -> Vec<(&'static [u8; 4], Vec<u8>)> {
+		let mut head = vec![0; 54];
+		head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+		head[36..44].copy_from_slice(&words(&[0, (-200i16) as u16, 150, 800]));
+		head[50..52].copy_from_slice(&1u16.to_be_bytes());
+		
+		let mut hhea = vec![0; 36];
+		hhea[4..10].copy_from_slice(&words(&[800, (-200i16) as u16, 90]));
+		hhea[34..36].copy_from_slice(&8u16.to_be_bytes());
+		
+		let maxp = [0x0000_5000u32.to_be_bytes().to_vec(), words(&[8])].concat();
+		let hmtx = advances.iter().flat_map(|&a| words(&[a, 0])).collect();
+		vec![(b"head", head), (b"hhea", hhea), (b"maxp", maxp), (b"hmtx", hmtx)]
+	}
+	
+	/// A triangle with an off-curve apex as `a` and a composite of it as `b`.
+	fn glyf_tables() -> Vec<(&'static [u8; 4], Vec<u8>)> {
+		let triangle = [
+			words(&[1, 0, 0, 100, 100, 2, 0]),
+			vec![0x01, 0x00, 0x01],
+			words(&[0, 50, 50, 0, 100, (-100i16) as u16])
+		].concat();
+		let composite = words(&[(-1i16) as u16, 50, 10, 150, 110, 0x0003, A, 50, 10]);
+		
+		let mut glyf = Vec::new();
+		let mut loca = Vec::new();
+		for glyph in 0..8 {
+			loca.extend((glyf.len() as u32).to_be_bytes());
+			match glyph {
+				A => glyf.extend(&triangle),
+				B => glyf.extend(&composite),
+				_ => ()
+			}
+			glyf.resize(glyf.len().next_multiple_of(4), 0);
+		}
+		loca.extend((glyf.len() as u32).to_be_bytes());
+		vec![(b"loca", loca), (b"glyf", glyf)]
+	}
+	
+	fn cmap4(map: &[(u16, u16)]) -> Vec<u8> {
+		let seg_x2 = (map.len() as u16 + 1) * 2;
+		let mut sub = words(&[4, 0, 0, seg_x2, 0, 0, 0]);
+		sub.extend(map.iter().flat_map(|&(c, _)| c.to_be_bytes()).chain(words(&[0xFFFF, 0])));
+		sub.extend(map.iter().flat_map(|&(c, _)| c.to_be_bytes()).chain(words(&[0xFFFF])));
+		sub.extend(map.iter().flat_map(|&(c, g)| g.wrapping_sub(c).to_be_bytes()).chain(words(&[1])));
+		sub.extend(vec![0; map.len() * 2 + 2]);
+		let len = sub.len() as u16;
+		sub[2..4].copy_from_slice(&len.to_be_bytes());
+		[words(&[0, 1, 3, 1, 0, 12]), sub].concat()
+	}
+	
+	fn cmap12(map: &[(u32, u16)]) -> Vec<u8> {
+		let mut sub = [words(&[12, 0]), ((16 + map.len() * 12) as u32).to_be_bytes().to_vec(),
+			0u32.to_be_bytes().to_vec(), (map.len() as u32).to_be_bytes().to_vec()].concat();
+		sub.extend(map.iter().flat_map(|&(c, g)| [c, c, g as u32]).flat_map(u32::to_be_bytes));
+		[words(&[0, 1, 3, 10, 0, 12]), sub].concat()
+	}
+	
+	/// A `GSUB` or `GPOS` table with a single default script and a subtable per lookup.
+	fn layout(features: &[(&[u8; 4], &[u16])], lookups: &[(u16, Vec<u8>)]) -> Vec<u8> {
+		let scripts = [words(&[1]), words(&tag(b"DFLT")), words(&[8, 4, 0, 0, 0xFFFF, features.len() as u16]),
+			words(&(0..features.len() as u16).collect::<Vec<_>>())].concat();
+		
+		let mut feature_list = words(&[features.len() as u16]);
+		let mut feature_tables = Vec::new();
+		for (t, indices) in features {
+			feature_list.extend(words(&tag(t)));
+			feature_list.extend(words(&[(2 + features.len() * 6 + feature_tables.len()) as u16]));
+			feature_tables.extend(words(&[0, indices.len() as u16]));
+			feature_tables.extend(words(indices));
+		}
+		feature_list.extend(feature_tables);
+		
+		let mut lookup_list = words(&[lookups.len() as u16]);
+		let mut lookup_tables = Vec::new();
+		for (kind, subtable) in lookups {
+			lookup_list.extend(words(&[(2 + lookups.len() * 2 + lookup_tables.len()) as u16]));
+			lookup_tables.extend(words(&[*kind, 0, 1, 8]));
+			lookup_tables.extend(subtable);
+		}
+		lookup_list.extend(lookup_tables);
+		
+		let header = words(&[1, 0, 10, (10 + scripts.len()) as u16, (10 + scripts.len() + feature_list.len()) as u16]);
+		[header, scripts, feature_list, lookup_list].concat()
+	}
+	
+	fn font() -> Vec<u8> {
+		let mut tables = base_tables([500, 120, 300, 250, 550, 0, 200, 260]);
+		tables.extend(glyf_tables());
+		tables.push((b"cmap", cmap4(&[('a' as u16, A), ('b' as u16, B), ('f' as u16, F), ('i' as u16, I), (0x0301, ACUTE)])));
+		
+		// liga: f i -> fi, calt: i -> i.alt after a
+		tables.push((b"GSUB", layout(&[(b"calt", &[1]), (b"liga", &[0])], &[
+			(4, words(&[1, 8, 1, 14, 1, 1, F, 1, 4, FI, 2, I])),
+			(6, words(&[3, 1, 18, 1, 24, 0, 1, 0, 2, 1, 1, A, 1, 1, I])),
+			(1, words(&[2, 8, 1, I_ALT, 1, 1, I]))
+		])));
+		
+		// kern: a b -20, mark: acute on a
+		tables.push((b"GPOS", layout(&[(b"kern", &[0]), (b"mark", &[1])], &[
+			(2, words(&[1, 12, 0x0004, 0, 1, 18, 1, 1, A, 1, B, (-20i16) as u16])),
+			(4, words(&[1, 12, 18, 1, 24, 36, 1, 1, ACUTE, 1, 1, A, 1, 0, 6, 1, 50, 0, 1, 4, 1, 40, 100]))
+		])));
+		tables.push((b"GDEF", words(&[1, 0, 12, 0, 0, 0, 1, ACUTE, 1, 3])));
+		tables.sort_by_key(|(t, _)| *t);
+		sfnt(0x0001_0000, &tables)
+	}
+	
+	fn info(ids: &[u16]) -> Vec<GlyphInfo> {
+		ids.iter().enumerate().map(|(i, &id)| GlyphInfo::new(GlyphId(id), i)).collect()
+	}
+	
+	fn ids(glyphs: &[GlyphInfo]) -> Vec<u16> {
+		glyphs.iter().map(|g| g.id.0).collect()
+	}
+	
+	#[test]
+	fn metrics_and_cmap() {
+		let font = Font::parse(font(), 0).unwrap();
+		assert_eq!(font.units_per_em(), 1000);
+		assert_eq!(font.glyph_count(), 8);
+		assert_eq!((font.ascender(), font.descender(), font.line_gap()), (800, -200, 90));
+		assert_eq!(font.global_bbox(), Rect { x_min: 0, y_min: -200, x_max: 150, y_max: 800 });
+		assert_eq!(font.glyph_hor_metrics(GlyphId(FI)), Some(HorizontalMetrics { advance: 550, left_side_bearing: 0 }));
+		assert_eq!(font.glyph_ver_metrics(GlyphId(A)), None);
+		
+		assert_eq!(font.glyph_index('a'), Some(GlyphId(A)));
+		assert_eq!(font.glyph_index('i'), Some(GlyphId(I)));
+		assert_eq!(font.glyph_index('\u{301}'), Some(GlyphId(ACUTE)));
+		assert_eq!(font.glyph_index('c'), None);
+		assert_eq!(font.glyph_index('\u{1F600}'), None);
+		
+		let mut tables = base_tables([0; 8]);
+		tables.push((b"cmap", cmap12(&[('a' as u32, A), (0x1F600, B)])));
+		let font = Font::parse(sfnt(0x0001_0000, &tables), 0).unwrap();
+		assert_eq!(font.glyph_index('a'), Some(GlyphId(A)));
+		assert_eq!(font.glyph_index('\u{1F600}'), Some(GlyphId(B)));
+		assert_eq!(font.glyph_index('b'), None);
+	}
+	
+	#[test]
+	fn glyf_outlines() {
+		let font = Font::parse(font(), 0).unwrap();
+		let mut out = Recorder::default();
+		assert_eq!(font.outline_glyph(GlyphId(A), &mut out), Some(Rect { x_min: 0, y_min: 0, x_max: 100, y_max: 100 }));
+		assert_eq!(out.0, ["M 0 0", "Q 50 100 100 0", "L 0 0", "Z"]);
+		
+		let mut out = Recorder::default();
+		assert_eq!(font.outline_glyph(GlyphId(B), &mut out), Some(Rect { x_min: 50, y_min: 10, x_max: 150, y_max: 110 }));
+		assert_eq!(out.0, ["M 50 10", "Q 100 110 150 10", "L 50 10", "Z"]);
+		
+		assert_eq!(font.outline_glyph(GlyphId(NOTDEF), &mut Recorder::default()), None);
+		assert_eq!(font.outline_glyph(GlyphId(8), &mut Recorder::default()), None);
+	}
+	
+	#[test]
+	fn collection() {
+		// both fonts share the same table directory, moved behind the collection header
+		let single = font();
+		let mut data = b"ttcf".to_vec();
+		data.extend(words(&[1, 0, 0, 2, 0, 20, 0, 20]));
+		data.extend(&single);
+		for i in 0..single.u16_at(4).unwrap() as usize {
+			let o = 20 + 12 + i * 16 + 8;
+			let offset = data.u32_at(o).unwrap() + 20;
+			data[o..o + 4].copy_from_slice(&offset.to_be_bytes());
+		}
+		
+		assert_eq!(font_count(&data).unwrap(), 2);
+		assert_eq!(font_count(&single).unwrap(), 1);
+		
+		let font = Font::parse(&data[..], 1).unwrap();
+		assert_eq!(font.glyph_index('b'), Some(GlyphId(B)));
+		assert_eq!(font.glyph_hor_metrics(GlyphId(B)).unwrap().advance, 200);
+		assert_eq!(Font::parse(&data[..], 2).unwrap_err().kind(), ErrorKind::InvalidInput);
+		assert_eq!(Font::parse(&single[..], 1).unwrap_err().kind(), ErrorKind::InvalidInput);
+	}
+	
+	#[test]
+	fn substitution() {
+		let font = Font::parse(font(), 0).unwrap();
+		let features = [Feature::new(b"liga"), Feature::new(b"calt")];
+		let mut glyphs = info(&[F, I, A, I, I]);
+		font.substitute(&mut glyphs, SCRIPT_DEFAULT, None, &features);
+		assert_eq!(ids(&glyphs), [FI, A, I_ALT, I]);
+		assert_eq!(glyphs.iter().map(|g| g.cluster).collect::<Vec<_>>(), [0, 2, 3, 4]);
+		
+		// disabled by the mask
+		let mut glyphs = info(&[F, I]);
+		glyphs[1].mask = 0;
+		font.substitute(&mut glyphs, SCRIPT_DEFAULT, None, &[Feature { tag: Tag::new(b"liga"), mask: 1 }]);
+		assert_eq!(ids(&glyphs), [F, I]);
+		
+		let mut glyphs = info(&[F, I]);
+		font.substitute(&mut glyphs, SCRIPT_DEFAULT, None, &[Feature::new(b"calt")]);
+		assert_eq!(ids(&glyphs), [F, I]);
+	}
+	
+	#[test]
+	fn positioning() {
+		let font = Font::parse(font(), 0).unwrap();
+		let features = [Feature::new(b"kern"), Feature::new(b"mark")];
+		let pos = font.position(&info(&[A, B, A, ACUTE, ACUTE]), Direction::LeftToRight, SCRIPT_DEFAULT, None, &features);
+		assert_eq!(pos, [
+			GlyphPosition { x_advance: 100, ..GlyphPosition::default() },
+			GlyphPosition { x_advance: 200, ..GlyphPosition::default() },
+			GlyphPosition { x_advance: 120, ..GlyphPosition::default() },
+			GlyphPosition { x_offset: -130, y_offset: 100, ..GlyphPosition::default() },
+			GlyphPosition { x_offset: -130, y_offset: 100, ..GlyphPosition::default() }
+		]);
+		
+		assert_eq!(font.kerning(GlyphId(A), GlyphId(B)), Some(-20));
+		assert_eq!(font.kerning(GlyphId(B), GlyphId(A)), None);
+	}
+	
+	#[test]
+	fn cursive() {
+		let mut tables = base_tables([500, 120, 300, 250, 550, 0, 200, 260]);
+		// a exits at 100,10, b enters at 20,0 and exits at 280,30
+		tables.push((b"GPOS", layout(&[(b"curs", &[0])], &[
+			(3, words(&[1, 14, 2, 0, 22, 28, 34, 1, 2, A, B, 1, 100, 10, 1, 20, 0, 1, 280, 30]))
+		])));
+		tables.sort_by_key(|(t, _)| *t);
+		let font = Font::parse(sfnt(0x0001_0000, &tables), 0).unwrap();
+		assert_eq!(font.scripts(), [SCRIPT_DEFAULT]);
+		
+		let pos = |x_advance, x_offset, y_offset| GlyphPosition { x_advance, x_offset, y_offset, y_advance: 0 };
+		let features = [Feature::new(b"curs")];
+		assert_eq!(font.position(&info(&[A, B, B]), Direction::LeftToRight, SCRIPT_DEFAULT, None, &features),
+			[pos(100, 0, 0), pos(260, -20, 10), pos(180, -20, 40)]);
+		assert_eq!(font.position(&info(&[A, B, B]), Direction::RightToLeft, SCRIPT_DEFAULT, None, &features),
+			[pos(20, -100, 0), pos(-260, -280, 10), pos(20, 0, 40)]);
+	}
+	
+	#[test]
+	fn legacy_kern() {
+		let mut tables = base_tables([500, 120, 300, 250, 550, 0, 200, 260]);
+		tables.push((b"kern", words(&[0, 1, 0, 26, 0x0001, 2, 12, 1, 0, A, B, (-30i16) as u16, B, A, 15])));
+		let font = Font::parse(sfnt(0x0001_0000, &tables), 0).unwrap();
+		assert_eq!(font.kerning(GlyphId(A), GlyphId(B)), Some(-30));
+		assert_eq!(font.kerning(GlyphId(B), GlyphId(A)), Some(15));
+		assert_eq!(font.kerning(GlyphId(A), GlyphId(A)), None);
+		
+		let pos = font.position(&info(&[A, B, A]), Direction::LeftToRight, SCRIPT_DEFAULT, None, &[Feature::new(b"kern")]);
+		assert_eq!(pos.iter().map(|p| p.x_advance).collect::<Vec<_>>(), [90, 215, 120]);
+		let pos = font.position(&info(&[A, B, A]), Direction::LeftToRight, SCRIPT_DEFAULT, None, &[]);
+		assert_eq!(pos.iter().map(|p| p.x_advance).collect::<Vec<_>>(), [120, 200, 120]);
+	}
+	
+	#[test]
+	fn variations() {
+		let mut tables = base_tables([500, 120, 300, 250, 550, 0, 200, 260]);
+		tables.extend(glyf_tables());
+		
+		let fixed = |v: i32| ((v << 16) as u32).to_be_bytes();
+		let mut fvar = words(&[1, 0, 16, 2, 1, 20, 1, 10]);
+		fvar.extend(tag(b"wght").iter().flat_map(|v| v.to_be_bytes()));
+		fvar.extend([fixed(100), fixed(400), fixed(900)].concat());
+		fvar.extend(words(&[0, 256, 257, 0]));
+		fvar.extend(fixed(700));
+		tables.push((b"fvar", fvar));
+		
+		// a at the maximum: all points +10 in x, advance +20
+		let data = [words(&[0x8001, 10, 9, 0x8000, 0x4000]), vec![0, 6, 10, 10, 10, 0, 20, 0, 0, 0x86]].concat();
+		let mut gvar = [words(&[1, 0, 1, 0, 0, 0, 8, 1]), 56u32.to_be_bytes().to_vec()].concat();
+		for glyph in 0..=8u32 {
+			gvar.extend(if glyph > A as u32 { data.len() as u32 } else { 0 }.to_be_bytes());
+		}
+		gvar.extend(data);
+		tables.push((b"gvar", gvar));
+		tables.sort_by_key(|(t, _)| *t);
+		
+		let mut font = Font::parse(sfnt(0x0001_0000, &tables), 0).unwrap();
+		assert_eq!(font.axes(), [Axis { tag: Tag::new(b"wght"), min: 100.0, default: 400.0, max: 900.0, name_id: 256, hidden: false }]);
+		assert_eq!(font.instances(), [Instance { subfamily_name_id: 257, postscript_name_id: None, coords: vec![700.0] }]);
+		assert!(!font.is_variable());
+		assert!(!font.set_variation(Tag::new(b"wdth"), 100.0));
+		
+		assert!(font.set_variation(Tag::new(b"wght"), 650.0));
+		assert_eq!(font.normalized_coords(), [0.5]);
+		assert_eq!(font.glyph_hor_metrics(GlyphId(A)).unwrap().advance, 130);
+		let mut out = Recorder::default();
+		assert_eq!(font.outline_glyph(GlyphId(A), &mut out), Some(Rect { x_min: 5, y_min: 0, x_max: 105, y_max: 100 }));
+		assert_eq!(out.0, ["M 5 0", "Q 55 100 105 0", "L 5 0", "Z"]);
+		
+		assert!(font.set_variation(Tag::new(b"wght"), 2000.0));
+		assert_eq!(font.normalized_coords(), [1.0]);
+		assert_eq!(font.glyph_hor_metrics(GlyphId(A)).unwrap().advance, 140);
+		let mut out = Recorder::default();
+		font.outline_glyph(GlyphId(B), &mut out);
+		assert_eq!(out.0, ["M 60 10", "Q 110 110 160 10", "L 60 10", "Z"]);
+		
+		font.reset_variations();
+		assert_eq!(font.glyph_hor_metrics(GlyphId(A)).unwrap().advance, 120);
+	}
+	
+	#[test]
+	fn cff_outlines() {
+		let char_strings = [vec![14], vec![149, 159, 21, 239, 139, 5, 139, 239, 5, 139, 139, 89, 139, 89, 89, 8, 14]];
+		let mut cff = vec![1, 0, 4, 1, 0, 1, 1, 1, 2, b'A'];
+		let top_dict_at = cff.len();
+		cff.extend([0, 1, 1, 1, 7, 29, 0, 0, 0, 0, 17]);
+		cff.extend([0, 0, 0, 0]);
+		let offset = cff.len() as u32;
+		cff[top_dict_at + 6..top_dict_at + 10].copy_from_slice(&offset.to_be_bytes());
+		cff.extend([0, 2, 1, 1, 2, 2 + char_strings[1].len() as u8]);
+		cff.extend(char_strings.concat());
+		
+		let mut tables = base_tables([500, 120, 300, 250, 550, 0, 200, 260]);
+		tables.push((b"CFF ", cff));
+		tables.sort_by_key(|(t, _)| *t);
+		let font = Font::parse(sfnt(OTTO, &tables), 0).unwrap();
+		
+		let mut out = Recorder::default();
+		assert_eq!(font.outline_glyph(GlyphId(A), &mut out), Some(Rect { x_min: 10, y_min: 20, x_max: 110, y_max: 120 }));
+		assert_eq!(out.0, ["M 10 20", "L 110 20", "L 110 120", "C 110 120 60 120 10 70", "L 10 20", "Z"]);
+		assert_eq!(font.outline_glyph(GlyphId(NOTDEF), &mut Recorder::default()), None);
+	}
+}
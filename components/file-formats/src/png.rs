@@ -0,0 +1,449 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! PNG decoding, see <https://www.w3.org/TR/png/>.
+
+use {
+	crate::zlib,
+	std::io::{self, Error, ErrorKind}
+};
+
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// The offsets and strides of the seven Adam7 passes.
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+	(0, 0, 8, 8), (4, 0, 8, 8), (0, 4, 4, 8), (2, 0, 4, 4), (0, 2, 2, 4), (1, 0, 2, 2), (0, 1, 1, 2)
+];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ColorType {
+	Gray      = 0,
+	Rgb       = 2,
+	Indexed   = 3,
+	GrayAlpha = 4,
+	Rgba      = 6
+}
+
+impl ColorType {
+	fn channels(self) -> usize {
+		match self {
+			Self::Gray | Self::Indexed => 1,
+			Self::GrayAlpha            => 2,
+			Self::Rgb                  => 3,
+			Self::Rgba                 => 4
+		}
+	}
+}
+
+/// A decoded image. Indexed colors and transparency chunks are expanded and bit depths below 8 are
+/// scaled to 8 bits, so the samples are either 8 or 16 bits wide.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+	pub width:     u32,
+	pub height:    u32,
+	/// 1 gray, 2 gray and alpha, 3 RGB and 4 RGBA
+	pub channels:  usize,
+	/// 8 or 16
+	pub bit_depth: u8,
+	/// The gamma of the `gAMA` chunk, an `sRGB` chunk implies a gamma of 1/2.2.
+	pub gamma:     Option<f32>,
+	/// The rows of samples without padding, 16 bit samples are little endian.
+	pub data:      Vec<u8>
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ImageHeader {
+	width:      usize,
+	height:     usize,
+	bit_depth:  u8,
+	color_type: ColorType,
+	interlaced: bool
+}
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn u32_be(buf: &[u8]) -> u32 {
+	u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
+
+/// The CRC-32 used by PNG and zip, with the reflected polynomial `0xEDB88320`.
+pub fn crc32(data: &[u8]) -> u32 {
+	const TABLE: [u32; 256] = {
+		let mut table = [0u32; 256];
+		let mut i = 0;
+		while i < 256 {
+			let mut c = i as u32;
+			let mut k = 0;
+			while k < 8 {
+				c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+				k += 1;
+			}
+			table[i] = c;
+			i += 1;
+		}
+		table
+	};
+	
+	!data.iter().fold(!0u32, |c, byte| TABLE[((c ^ *byte as u32) & 0xFF) as usize] ^ (c >> 8))
+}
+
+/// Decodes a PNG file.
+pub fn decode(data: &[u8]) -> io::Result<Image> {
+	if data.len() < 8 || data[..8] != SIGNATURE {
+		return Err(invalid_data("invalid PNG signature"));
+	}
+	
+	let mut pos = 8;
+	let mut header = None;
+	let mut palette = Vec::new();
+	let mut transparency = None;
+	let mut gamma = None;
+	let mut idat = Vec::new();
+	
+	loop {
+		let len = data.get(pos..pos + 4).map(u32_be).ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))? as usize;
+		let chunk = data.get(pos + 4..pos + 12 + len).ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+		let (ty, body) = (&chunk[..4], &chunk[4..4 + len]);
+		pos += 12 + len;
+		
+		if crc32(&chunk[..4 + len]) != u32_be(&chunk[4 + len..]) {
+			return Err(invalid_data("PNG chunk checksum mismatch"));
+		}
+		
+		if header.is_none() && ty != b"IHDR" {
+			return Err(invalid_data("the first PNG chunk must be IHDR"));
+		}
+		
+		match ty {
+			b"IHDR" => {
+				if len != 13 || body[10] != 0 || body[11] != 0 || body[12] > 1 {
+					return Err(invalid_data("invalid PNG image header"));
+				}
+				
+				let color_type = match body[9] {
+					0 => ColorType::Gray,
+					2 => ColorType::Rgb,
+					3 => ColorType::Indexed,
+					4 => ColorType::GrayAlpha,
+					6 => ColorType::Rgba,
+					_ => return Err(invalid_data("invalid PNG color type"))
+				};
+				
+				let bit_depth = body[8];
+				let valid = match color_type {
+					ColorType::Gray    => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+					ColorType::Indexed => matches!(bit_depth, 1 | 2 | 4 | 8),
+					_                  => matches!(bit_depth, 8 | 16)
+				};
+				
+				let (width, height) = (u32_be(body) as usize, u32_be(&body[4..]) as usize);
+				if !valid || width == 0 || height == 0 {
+					return Err(invalid_data("invalid PNG image header"));
+				}
+				
+				header = Some(ImageHeader { width, height, bit_depth, color_type, interlaced: body[12] == 1 });
+			}
+			b"PLTE" if len.is_multiple_of(3) && len <= 768 => palette = body.chunks_exact(3)
+				.map(|c| [c[0], c[1], c[2], 0xFF])
+				.collect::<Vec<_>>(),
+			b"PLTE" => return Err(invalid_data("invalid PNG palette")),
+			b"tRNS" => transparency = Some(body.to_vec()),
+			b"gAMA" if len == 4 => gamma = Some(u32_be(body) as f32 / 100_000.0),
+			b"sRGB" => gamma = Some(1.0 / 2.2),
+			b"IDAT" => idat.extend_from_slice(body),
+			b"IEND" => break,
+			_ if ty[0].is_ascii_uppercase() => return Err(invalid_data("unknown critical PNG chunk")),
+			_ => ()
+		}
+	}
+	
+	let header = header.ok_or_else(|| invalid_data("missing PNG image header"))?;
+	if header.color_type == ColorType::Indexed {
+		if palette.is_empty() {
+			return Err(invalid_data("missing PNG palette"));
+		}
+		
+		for (entry, alpha) in palette.iter_mut().zip(transparency.take().unwrap_or_default()) {
+			entry[3] = alpha;
+		}
+	}
+	
+	// a transparent color key adds an alpha channel
+	let key = match (header.color_type, &transparency) {
+		(ColorType::Gray, Some(t)) if t.len() >= 2 => Some([u16::from_be_bytes([t[0], t[1]]), 0, 0]),
+		(ColorType::Rgb, Some(t)) if t.len() >= 6 => Some([
+			u16::from_be_bytes([t[0], t[1]]),
+			u16::from_be_bytes([t[2], t[3]]),
+			u16::from_be_bytes([t[4], t[5]])
+		]),
+		_ => None
+	};
+	
+	let channels = match header.color_type {
+		ColorType::Indexed if palette.iter().any(|entry| entry[3] != 0xFF) => 4,
+		ColorType::Indexed => 3,
+		ty => ty.channels() + key.is_some() as usize
+	};
+	
+	let sample_size = if header.bit_depth == 16 { 2 } else { 1 };
+	let mut image = Image {
+		width:     header.width as u32,
+		height:    header.height as u32,
+		channels,
+		bit_depth: 8 * sample_size as u8,
+		gamma,
+		data:      vec![0; header.width * header.height * channels * sample_size]
+	};
+	
+	let raw = zlib::decompress(&idat)?;
+	let passes: &[_] = if header.interlaced { &ADAM7 } else { &[(0, 0, 1, 1)] };
+	let mut offset = 0;
+	
+	for &(x0, y0, dx, dy) in passes {
+		let width = (header.width + dx - 1 - x0) / dx;
+		let height = (header.height + dy - 1 - y0) / dy;
+		if width == 0 || height == 0 {
+			continue;
+		}
+		
+		let bits = header.color_type.channels() * header.bit_depth as usize;
+		let stride = (width * bits).div_ceil(8);
+		let size = (stride + 1) * height;
+		let rows = raw.get(offset..offset + size).ok_or_else(|| invalid_data("not enough PNG image data"))?;
+		let rows = unfilter(rows, stride, bits.div_ceil(8))?;
+		offset += size;
+		
+		for (y, row) in rows.chunks_exact(stride).enumerate() {
+			for x in 0..width {
+				let out = ((y0 + y * dy) * header.width + x0 + x * dx) * channels * sample_size;
+				expand_pixel(&header, row, x, &palette, key, &mut image.data[out..out + channels * sample_size])?;
+			}
+		}
+	}
+	
+	Ok(image)
+}
+
+/// Reverses the filters and returns the rows without filter type bytes.
+fn unfilter(rows: &[u8], stride: usize, bpp: usize) -> io::Result<Vec<u8>> {
+	let mut out = vec![0u8; stride * (rows.len() / (stride + 1))];
+	
+	for (y, row) in rows.chunks_exact(stride + 1).enumerate() {
+		let (prev, cur) = out.split_at_mut(y * stride);
+		let prev = prev.get(prev.len().saturating_sub(stride)..).filter(|_| y > 0);
+		let cur = &mut cur[..stride];
+		let up = |i: usize| prev.map_or(0, |prev| prev[i]);
+		
+		for i in 0..stride {
+			let a = if i >= bpp { cur[i - bpp] } else { 0 };
+			let b = up(i);
+			let c = if i >= bpp { up(i - bpp) } else { 0 };
+			
+			cur[i] = row[i + 1].wrapping_add(match row[0] {
+				0 => 0,
+				1 => a,
+				2 => b,
+				3 => ((a as u16 + b as u16) / 2) as u8,
+				4 => {
+					let p = a as i16 + b as i16 - c as i16;
+					let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+					if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+				}
+				_ => return Err(invalid_data("invalid PNG filter type"))
+			});
+		}
+	}
+	
+	Ok(out)
+}
+
+/// Writes the samples of a pixel of an unfiltered row to `out`.
+fn expand_pixel(
+	header:  &ImageHeader,
+	row:     &[u8],
+	x:       usize,
+	palette: &[[u8; 4]],
+	key:     Option<[u16; 3]>,
+	out:     &mut [u8]
+) -> io::Result<()> {
+	let depth = header.bit_depth as usize;
+	let channels = header.color_type.channels();
+	let sample = |i: usize| -> u16 {
+		let bit = (x * channels + i) * depth;
+		match depth {
+			16 => u16::from_be_bytes([row[bit / 8], row[bit / 8 + 1]]),
+			8  => row[bit / 8] as u16,
+			_  => (row[bit / 8] >> (8 - depth - bit % 8)) as u16 & ((1 << depth) - 1)
+		}
+	};
+	
+	match header.color_type {
+		ColorType::Indexed => {
+			let entry = palette.get(sample(0) as usize).ok_or_else(|| invalid_data("PNG palette index out of range"))?;
+			let len = out.len();
+			out.copy_from_slice(&entry[..len]);
+		}
+		_ => {
+			let samples = [sample(0), sample(1 % channels), sample(2 % channels), sample(3 % channels)];
+			let transparent = key.is_some_and(|key| (0..channels).all(|i| samples[i] == key[i]));
+			
+			for (i, out) in out.chunks_exact_mut(if depth == 16 { 2 } else { 1 }).enumerate() {
+				let value = match i < channels {
+					true if depth < 8 => samples[i] * 255 / ((1 << depth) - 1),
+					true              => samples[i],
+					false if transparent => 0,
+					false             => 0xFFFF
+				};
+				
+				if depth == 16 {
+					out.copy_from_slice(&value.to_le_bytes());
+				} else {
+					out[0] = value as u8;
+				}
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn chunk(out: &mut Vec<u8>, ty: &[u8; 4], body: &[u8]) {
+		out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+		let start = out.len();
+		out.extend_from_slice(ty);
+		out.extend_from_slice(body);
+		let crc = crc32(&out[start..]);
+		out.extend_from_slice(&crc.to_be_bytes());
+	}
+	
+	/// Encodes an image, the filter type of each row cycles through all filters.
+	fn encode(width: u32, height: u32, depth: u8, color_type: u8, bpp: usize, rows: &[u8], extra: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+		let stride = rows.len() / height as usize;
+		let mut raw = Vec::new();
+		
+		for y in 0..height as usize {
+			let ty = (y % 5) as u8;
+			raw.push(ty);
+			for i in 0..stride {
+				let a = if i >= bpp { rows[y * stride + i - bpp] } else { 0 };
+				let b = if y > 0 { rows[(y - 1) * stride + i] } else { 0 };
+				let c = if y > 0 && i >= bpp { rows[(y - 1) * stride + i - bpp] } else { 0 };
+				let predictor = match ty {
+					0 => 0,
+					1 => a,
+					2 => b,
+					3 => ((a as u16 + b as u16) / 2) as u8,
+					_ => {
+						let p = a as i16 + b as i16 - c as i16;
+						let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+						if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+					}
+				};
+				raw.push(rows[y * stride + i].wrapping_sub(predictor));
+			}
+		}
+		
+		let mut ihdr = width.to_be_bytes().to_vec();
+		ihdr.extend_from_slice(&height.to_be_bytes());
+		ihdr.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+		
+		let mut out = SIGNATURE.to_vec();
+		chunk(&mut out, b"IHDR", &ihdr);
+		for (ty, body) in extra {
+			chunk(&mut out, ty, body);
+		}
+		chunk(&mut out, b"IDAT", &zlib::compress(&raw));
+		chunk(&mut out, b"IEND", &[]);
+		out
+	}
+	
+	#[test]
+	fn filters() {
+		let rows = (0..7 * 6 * 3).map(|i| (i * 37 % 251) as u8).collect::<Vec<_>>();
+		let image = decode(&encode(7, 6, 8, 2, 3, &rows, &[(b"gAMA", &45455u32.to_be_bytes())])).unwrap();
+		assert_eq!((image.width, image.height, image.channels, image.bit_depth), (7, 6, 3, 8));
+		assert_eq!(image.gamma, Some(0.45455));
+		assert_eq!(image.data, rows);
+	}
+	
+	#[test]
+	fn palette() {
+		// 2 bit indices 0, 1, 2 and 2, 1, 0, the second entry is half transparent
+		let data = encode(3, 2, 2, 3, 1, &[0b0001_1000, 0b1001_0000], &[
+			(b"PLTE", &[255, 0, 0, 0, 255, 0, 0, 0, 255]),
+			(b"tRNS", &[255, 128])
+		]);
+		let image = decode(&data).unwrap();
+		assert_eq!(image.channels, 4);
+		assert_eq!(image.data, [
+			255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 255,
+			0, 0, 255, 255, 0, 255, 0, 128, 255, 0, 0, 255
+		]);
+		
+		let mut corrupt = data.clone();
+		corrupt[40] ^= 1;
+		assert!(decode(&corrupt).is_err());
+	}
+	
+	#[test]
+	fn sixteen_bit() {
+		let rows = [0x12, 0x34, 0xFF, 0xFF, 0x00, 0x01, 0x80, 0x00];
+		let image = decode(&encode(2, 2, 16, 0, 2, &rows, &[(b"tRNS", &[0xFF, 0xFF])])).unwrap();
+		assert_eq!((image.channels, image.bit_depth), (2, 16));
+		assert_eq!(image.data, [
+			0x34, 0x12, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00,
+			0x01, 0x00, 0xFF, 0xFF, 0x00, 0x80, 0xFF, 0xFF
+		]);
+		
+		// 1 bit gray is scaled to 8 bits
+		let image = decode(&encode(3, 1, 1, 0, 1, &[0b1010_0000], &[])).unwrap();
+		assert_eq!(image.data, [255, 0, 255]);
+	}
+	
+	#[test]
+	fn interlaced() {
+		let (width, height) = (5, 6);
+		let pixels = (0..width * height).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+		let mut raw = Vec::new();
+		
+		for (x0, y0, dx, dy) in ADAM7 {
+			for y in (y0..height).step_by(dy) {
+				if x0 < width {
+					raw.push(0);
+					raw.extend((x0..width).step_by(dx).map(|x| pixels[y * width + x]));
+				}
+			}
+		}
+		
+		let mut out = SIGNATURE.to_vec();
+		chunk(&mut out, b"IHDR", &[0, 0, 0, width as u8, 0, 0, 0, height as u8, 8, 0, 0, 0, 1]);
+		chunk(&mut out, b"IDAT", &zlib::compress(&raw));
+		chunk(&mut out, b"IEND", &[]);
+		assert_eq!(decode(&out).unwrap().data, pixels);
+	}
+}
@@ -20,4 +20,2766 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO protobuf
\ No newline at end of file
+//! Protocol Buffers wire format and `.proto` schemas.
+//!
+//! Without a schema, messages map to structs whose field names are field numbers, i.e.
+//! `#[serde(rename = "1")]`. Integers are varints, wrap them in [`SInt`] for zigzag or in [`Fixed`]
+//! for fixed size encoding, floats are always fixed. Sequences of scalars are packed, so `Vec<u8>`
+//! is a packed `repeated uint32`, use [`Bytes`] for `bytes` fields. Maps are repeated entry
+//! messages. Unit variants are enum values, all other variants are encoded as a message with a
+//! single field, numbered by the variant name or its index plus one, i.e. a message that only
+//! contains a `oneof`. Fields that are
+//! missing when decoding get their default value, unknown fields are skipped.
+//!
+//! With a [`Schema`] parsed from `.proto` files, messages are decoded into and encoded from
+//! [`Type`], which in turn converts from and to anything implementing serde's traits.
+
+use {
+	crate::{utils::*, dyn_repr::Type},
+	std::{io, fmt, str::FromStr, collections::HashMap},
+	serde::{
+		Serialize, Deserialize,
+		de::{self, Visitor, DeserializeSeed, IntoDeserializer},
+		ser::{self, SerializeSeq, SerializeStruct}
+	}
+};
+
+const SINT_NAME:  &str = "$__protobuf_sint";
+const FIXED_NAME: &str = "$__protobuf_fixed";
+const MAX_DEPTH:  usize = 100;
+
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+pub fn serialize<W: io::Write, T: Serialize>(mut writer: W, value: &T) -> Result<(), SerError> {
+	writer.write_all(&serialize_vec(value)?)?;
+	Ok(())
+}
+
+pub fn serialize_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, SerError> {
+	match value.serialize(ValueSerializer)? {
+		Encoded::Message(data) => Ok(data),
+		_ => Err(SerError::Custom("the top-level value must be a message".to_string()))
+	}
+}
+
+pub fn deserialize<'de, T: Deserialize<'de>, R: io::Read>(mut reader: R) -> Result<T, DeError> {
+	let mut data = Vec::new();
+	reader.read_to_end(&mut data)?;
+	deserialize_slice(&data)
+}
+
+pub fn deserialize_slice<'de, T: Deserialize<'de>>(data: &[u8]) -> Result<T, DeError> {
+	T::deserialize(Deserializer::new(data))
+}
+
+/// Serializes a value as the given message of the schema, fields are matched by name.
+pub fn serialize_with<W: io::Write, T: Serialize>(mut writer: W, schema: &Schema, message: &str, value: &T) -> Result<(), SerError> {
+	let value = Type::serialize_from(value)
+		.map_err(|e| SerError::Custom(e.to_string()))?;
+	writer.write_all(&schema.encode(message, &value)?)?;
+	Ok(())
+}
+
+/// Deserializes the given message of the schema, fields are matched by name.
+pub fn deserialize_with<'de, T: Deserialize<'de>, R: io::Read>(mut reader: R, schema: &Schema, message: &str) -> Result<T, DeError> {
+	let mut data = Vec::new();
+	reader.read_to_end(&mut data)?;
+	schema.decode(message, &data)?
+		.deserialize_to()
+		.map_err(|e| DeError::Custom(e.to_string()))
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum WireType {
+	Varint = 0,
+	I64    = 1,
+	Len    = 2,
+	SGroup = 3,
+	EGroup = 4,
+	I32    = 5
+}
+
+impl WireType {
+	pub fn from_u8(v: u8) -> Option<Self> {
+		Some(match v {
+			0 => Self::Varint,
+			1 => Self::I64,
+			2 => Self::Len,
+			3 => Self::SGroup,
+			4 => Self::EGroup,
+			5 => Self::I32,
+			_ => return None
+		})
+	}
+}
+
+/// A field value as it appears on the wire, groups are skipped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WireValue<'a> {
+	Varint(u64),
+	I64(u64),
+	Len(&'a [u8]),
+	I32(u32)
+}
+
+impl WireValue<'_> {
+	pub fn wire_type(&self) -> WireType {
+		match self {
+			Self::Varint(_) => WireType::Varint,
+			Self::I64(_)    => WireType::I64,
+			Self::Len(_)    => WireType::Len,
+			Self::I32(_)    => WireType::I32
+		}
+	}
+}
+
+pub fn read_varint<R: io::Read>(mut reader: R) -> io::Result<u64> {
+	let mut value = 0;
+	for i in 0..10 {
+		let mut buf = [0u8];
+		reader.read_exact(&mut buf)?;
+		value |= ((buf[0] & 0x7F) as u64) << (i * 7);
+		if buf[0] & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err(io::Error::new(io::ErrorKind::InvalidData, "varint is longer than 10 bytes"))
+}
+
+pub fn write_varint<W: io::Write>(mut writer: W, value: u64) -> io::Result<()> {
+	let mut buf = Vec::with_capacity(10);
+	put_varint(&mut buf, value);
+	writer.write_all(&buf)
+}
+
+pub fn read_tag<R: io::Read>(reader: R) -> io::Result<(u32, WireType)> {
+	let tag = read_varint(reader)?;
+	let number = tag >> 3;
+	if number == 0 || number > MAX_FIELD_NUMBER as u64 {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid field number {}", number)));
+	}
+	
+	WireType::from_u8((tag & 7) as u8)
+		.map(|wire| (number as u32, wire))
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("invalid wire type {}", tag & 7)))
+}
+
+pub fn write_tag<W: io::Write>(writer: W, number: u32, wire: WireType) -> io::Result<()> {
+	write_varint(writer, (number as u64) << 3 | wire as u64)
+}
+
+pub fn zigzag_encode(v: i64) -> u64 {
+	((v << 1) ^ (v >> 63)) as u64
+}
+
+pub fn zigzag_decode(v: u64) -> i64 {
+	((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		out.push(value as u8 | 0x80);
+		value >>= 7;
+	}
+	out.push(value as u8);
+}
+
+fn put_tag(out: &mut Vec<u8>, number: u32, wire: WireType) {
+	put_varint(out, (number as u64) << 3 | wire as u64);
+}
+
+fn put_len(out: &mut Vec<u8>, number: u32, data: &[u8]) {
+	put_tag(out, number, WireType::Len);
+	put_varint(out, data.len() as u64);
+	out.extend_from_slice(data);
+}
+
+/// Returns an iterator over the fields of an encoded message.
+pub fn fields(data: &[u8]) -> Fields<'_> {
+	Fields(data)
+}
+
+pub struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+	fn read_bytes(&mut self, len: u64) -> Result<&'a [u8], DeError> {
+		if len > self.0.len() as u64 {
+			return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+		}
+		
+		let (data, rest) = self.0.split_at(len as usize);
+		self.0 = rest;
+		Ok(data)
+	}
+	
+	fn value(&mut self, number: u32, wire: WireType, depth: usize) -> Result<Option<WireValue<'a>>, DeError> {
+		Ok(Some(match wire {
+			WireType::Varint => WireValue::Varint(read_varint(&mut self.0)?),
+			WireType::I64    => WireValue::I64(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap())),
+			WireType::I32    => WireValue::I32(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap())),
+			WireType::Len    => {
+				let len = read_varint(&mut self.0)?;
+				WireValue::Len(self.read_bytes(len)?)
+			}
+			WireType::SGroup => {
+				self.skip_group(number, depth + 1)?;
+				return Ok(None);
+			}
+			WireType::EGroup => return Err(DeError::ParseError(format!("unexpected end of group {}", number)))
+		}))
+	}
+	
+	fn skip_group(&mut self, number: u32, depth: usize) -> Result<(), DeError> {
+		if depth > MAX_DEPTH {
+			return Err(DeError::ParseError("groups are nested too deeply".to_string()));
+		}
+		
+		loop {
+			match read_tag(&mut self.0)? {
+				(n, WireType::EGroup) if n == number => return Ok(()),
+				(n, WireType::EGroup) => return Err(DeError::ParseError(format!("unexpected end of group {}", n))),
+				(n, wire) => { self.value(n, wire, depth)?; }
+			}
+		}
+	}
+}
+
+impl<'a> Iterator for Fields<'a> {
+	type Item = Result<(u32, WireValue<'a>), DeError>;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.0.is_empty() {
+			let field = read_tag(&mut self.0)
+				.map_err(DeError::from)
+				.and_then(|(number, wire)| Ok(self.value(number, wire, 0)?.map(|v| (number, v))));
+			
+			match field {
+				Ok(Some(field)) => return Some(Ok(field)),
+				Ok(None)        => continue,
+				Err(e)          => {
+					self.0 = &[];
+					return Some(Err(e));
+				}
+			}
+		}
+		None
+	}
+}
+
+/// The encoding of one element of a packed repeated field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Packed {
+	Varint,
+	Fixed32,
+	Fixed64
+}
+
+impl Packed {
+	fn new(encoding: Encoding, bits: u32) -> Self {
+		match encoding {
+			Encoding::Fixed if bits <= 32 => Self::Fixed32,
+			Encoding::Fixed               => Self::Fixed64,
+			_                             => Self::Varint
+		}
+	}
+	
+	fn read<'a>(self, data: &mut &'a [u8]) -> Result<WireValue<'a>, DeError> {
+		let mut fields = Fields(data);
+		let value = match self {
+			Self::Varint  => WireValue::Varint(read_varint(&mut fields.0)?),
+			Self::Fixed32 => WireValue::I32(u32::from_le_bytes(fields.read_bytes(4)?.try_into().unwrap())),
+			Self::Fixed64 => WireValue::I64(u64::from_le_bytes(fields.read_bytes(8)?.try_into().unwrap()))
+		};
+		*data = fields.0;
+		Ok(value)
+	}
+}
+
+/// Encodes an integer with zigzag encoding, i.e. as `sint32` or `sint64`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SInt<T>(pub T);
+
+impl<T: Serialize> Serialize for SInt<T> {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(SINT_NAME, &self.0)
+	}
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SInt<T> {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(SINT_NAME, NewtypeVisitor(std::marker::PhantomData))
+			.map(Self)
+	}
+}
+
+/// Encodes an integer with a fixed size, i.e. as `fixed32`, `sfixed64`, etc.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Fixed<T>(pub T);
+
+impl<T: Serialize> Serialize for Fixed<T> {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(FIXED_NAME, &self.0)
+	}
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Fixed<T> {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(FIXED_NAME, NewtypeVisitor(std::marker::PhantomData))
+			.map(Self)
+	}
+}
+
+struct NewtypeVisitor<T>(std::marker::PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NewtypeVisitor<T> {
+	type Value = T;
+	
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("an integer")
+	}
+	
+	fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+		T::deserialize(deserializer)
+	}
+}
+
+/// A `bytes` field.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Serialize for Bytes {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct BytesVisitor;
+		
+		impl<'de> Visitor<'de> for BytesVisitor {
+			type Value = Bytes;
+			
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("bytes")
+			}
+			
+			fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+				Ok(Bytes(v.to_vec()))
+			}
+			
+			fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+				Ok(Bytes(v))
+			}
+			
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<Bytes, E> {
+				Ok(Bytes(v.as_bytes().to_vec()))
+			}
+			
+			fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+				let mut bytes = Vec::new();
+				while let Some(b) = seq.next_element()? {
+					bytes.push(b);
+				}
+				Ok(Bytes(bytes))
+			}
+		}
+		
+		deserializer.deserialize_byte_buf(BytesVisitor)
+	}
+}
+
+fn field_number(name: &str) -> Option<u32> {
+	name.parse().ok().filter(|n| (1..=MAX_FIELD_NUMBER).contains(n))
+}
+
+/// A value encoded without its tag.
+#[derive(Clone, Debug, PartialEq)]
+enum Encoded {
+	None,
+	Some(Box<Self>),
+	Varint { value: u64, bits: u32 },
+	I32(u32),
+	I64(u64),
+	Len(Vec<u8>),
+	Message(Vec<u8>),
+	Repeated(Vec<Self>)
+}
+
+impl Encoded {
+	fn is_scalar(&self) -> bool {
+		matches!(self, Self::Varint { .. } | Self::I32(_) | Self::I64(_))
+	}
+	
+	fn map_scalars(self, f: &impl Fn(Self) -> Self) -> Self {
+		match self {
+			Self::Some(value)     => Self::Some(Box::new(value.map_scalars(f))),
+			Self::Repeated(items) => Self::Repeated(items.into_iter().map(|v| v.map_scalars(f)).collect()),
+			v if v.is_scalar()    => f(v),
+			v                     => v
+		}
+	}
+	
+	fn put_scalar(&self, out: &mut Vec<u8>) {
+		match self {
+			Self::Varint { value, .. } => put_varint(out, *value),
+			Self::I32(v)               => out.extend_from_slice(&v.to_le_bytes()),
+			Self::I64(v)               => out.extend_from_slice(&v.to_le_bytes()),
+			_                          => unreachable!()
+		}
+	}
+	
+	/// Writes this value as field `number`, default values are skipped unless `explicit` is set.
+	fn write(self, out: &mut Vec<u8>, number: u32, explicit: bool) -> Result<(), SerError> {
+		match self {
+			Self::None => (),
+			Self::Some(value) => value.write(out, number, true)?,
+			Self::Varint { value, .. } => if explicit || value != 0 {
+				put_tag(out, number, WireType::Varint);
+				put_varint(out, value);
+			},
+			Self::I32(v) => if explicit || v != 0 {
+				put_tag(out, number, WireType::I32);
+				out.extend_from_slice(&v.to_le_bytes());
+			},
+			Self::I64(v) => if explicit || v != 0 {
+				put_tag(out, number, WireType::I64);
+				out.extend_from_slice(&v.to_le_bytes());
+			},
+			Self::Len(data) => if explicit || !data.is_empty() {
+				put_len(out, number, &data);
+			},
+			Self::Message(data) => put_len(out, number, &data),
+			Self::Repeated(items) if items.iter().all(Self::is_scalar) => if !items.is_empty() {
+				let mut data = Vec::new();
+				items.iter().for_each(|v| v.put_scalar(&mut data));
+				put_len(out, number, &data);
+			},
+			Self::Repeated(items) => for item in items {
+				if matches!(item, Self::None | Self::Repeated(_)) {
+					return Err(SerError::Custom(format!(
+						"field {}: repeated fields cannot contain nested sequences or `None`", number)));
+				}
+				item.write(out, number, true)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+	type Ok                     = Encoded;
+	type Error                  = SerError;
+	type SerializeSeq           = SeqSerializer;
+	type SerializeTuple         = SeqSerializer;
+	type SerializeTupleStruct   = SeqSerializer;
+	type SerializeTupleVariant  = VariantSerializer<SeqSerializer>;
+	type SerializeMap           = MapSerializer;
+	type SerializeStruct        = MessageSerializer;
+	type SerializeStructVariant = VariantSerializer<MessageSerializer>;
+	
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as u64, bits: 8 })
+	}
+	
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as i64 as u64, bits: 8 })
+	}
+	
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as i64 as u64, bits: 16 })
+	}
+	
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as i64 as u64, bits: 32 })
+	}
+	
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as u64, bits: 64 })
+	}
+	
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as u64, bits: 8 })
+	}
+	
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as u64, bits: 16 })
+	}
+	
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v as u64, bits: 32 })
+	}
+	
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Varint { value: v, bits: 64 })
+	}
+	
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::I32(v.to_bits()))
+	}
+	
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::I64(v.to_bits()))
+	}
+	
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Len(v.to_string().into_bytes()))
+	}
+	
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Len(v.as_bytes().to_vec()))
+	}
+	
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Len(v.to_vec()))
+	}
+	
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::None)
+	}
+	
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Some(Box::new(value.serialize(self)?)))
+	}
+	
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::None)
+	}
+	
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::None)
+	}
+	
+	fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		let value = variant.parse::<i32>().map_or(variant_index as i64, |v| v as i64);
+		Ok(Encoded::Varint { value: value as u64, bits: 32 })
+	}
+	
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		let value = value.serialize(self)?;
+		Ok(match name {
+			SINT_NAME => value.map_scalars(&|v| match v {
+				Encoded::Varint { value, bits } => Encoded::Varint { value: zigzag_encode(value as i64), bits },
+				v => v
+			}),
+			FIXED_NAME => value.map_scalars(&|v| match v {
+				Encoded::Varint { value, bits } if bits <= 32 => Encoded::I32(value as u32),
+				Encoded::Varint { value, .. } => Encoded::I64(value),
+				v => v
+			}),
+			_ => value
+		})
+	}
+	
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		let mut data = Vec::new();
+		value.serialize(self)?.write(&mut data, variant_number(variant, variant_index), true)?;
+		Ok(Encoded::Message(data))
+	}
+	
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+	}
+	
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.serialize_seq(Some(len))
+	}
+	
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.serialize_seq(Some(len))
+	}
+	
+	fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(VariantSerializer { number: variant_number(variant, variant_index), inner: self.serialize_seq(Some(len))? })
+	}
+	
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(MapSerializer { entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+	}
+	
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(MessageSerializer(Vec::new()))
+	}
+	
+	fn serialize_struct_variant(self, _name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(VariantSerializer { number: variant_number(variant, variant_index), inner: MessageSerializer(Vec::new()) })
+	}
+}
+
+fn variant_number(variant: &str, index: u32) -> u32 {
+	field_number(variant).unwrap_or(index + 1)
+}
+
+struct SeqSerializer(Vec<Encoded>);
+
+impl ser::SerializeSeq for SeqSerializer {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.0.push(value.serialize(ValueSerializer)?);
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Repeated(self.0))
+	}
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+struct MapSerializer {
+	entries: Vec<Encoded>,
+	key:     Option<Encoded>
+}
+
+impl ser::SerializeMap for MapSerializer {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = Some(key.serialize(ValueSerializer)?);
+		Ok(())
+	}
+	
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let mut entry = Vec::new();
+		self.key.take()
+			.ok_or_else(|| SerError::Custom("no key serialized".to_string()))?
+			.write(&mut entry, 1, false)?;
+		value.serialize(ValueSerializer)?.write(&mut entry, 2, false)?;
+		self.entries.push(Encoded::Message(entry));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Repeated(self.entries))
+	}
+}
+
+struct MessageSerializer(Vec<u8>);
+
+impl ser::SerializeStruct for MessageSerializer {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		let number = field_number(key)
+			.ok_or_else(|| SerError::Custom(format!("`{}` is not a valid field number", key)))?;
+		value.serialize(ValueSerializer)?.write(&mut self.0, number, false)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Encoded::Message(self.0))
+	}
+}
+
+struct VariantSerializer<S> {
+	number: u32,
+	inner:  S
+}
+
+impl<S> VariantSerializer<S> {
+	fn message(number: u32, value: Encoded) -> Result<Encoded, SerError> {
+		let mut data = Vec::new();
+		value.write(&mut data, number, true)?;
+		Ok(Encoded::Message(data))
+	}
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Self::message(self.number, SerializeSeq::end(self.inner)?)
+	}
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<MessageSerializer> {
+	type Ok    = Encoded;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		SerializeStruct::serialize_field(&mut self.inner, key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Self::message(self.number, SerializeStruct::end(self.inner)?)
+	}
+}
+
+/// Deserializes a message, fields are grouped by their number.
+pub struct Deserializer<'a> {
+	data: &'a [u8]
+}
+
+impl<'a> Deserializer<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data }
+	}
+	
+	fn collect(&self) -> Result<Vec<(u32, Vec<WireValue<'a>>)>, DeError> {
+		let mut indices = HashMap::new();
+		let mut fields = Vec::<(u32, Vec<_>)>::new();
+		for field in self::fields(self.data) {
+			let (number, value) = field?;
+			let i = *indices.entry(number).or_insert_with(|| {
+				fields.push((number, Vec::new()));
+				fields.len() - 1
+			});
+			fields[i].1.push(value);
+		}
+		Ok(fields)
+	}
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_map(visitor)
+	}
+	
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(FieldAccess { fields: self.collect()?.into_iter(), value: None })
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(StructAccess {
+			fields: fields.iter(),
+			values: self.collect()?.into_iter().collect(),
+			value:  None
+		})
+	}
+	
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf option seq tuple tuple_struct enum identifier
+	}
+}
+
+/// Yields the declared fields of a struct, fields that are missing get their default value.
+struct StructAccess<'a> {
+	fields: std::slice::Iter<'static, &'static str>,
+	values: HashMap<u32, Vec<WireValue<'a>>>,
+	value:  Option<FieldDeserializer<'a>>
+}
+
+impl<'de, 'a> de::MapAccess<'de> for StructAccess<'a> {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		let name = match self.fields.next() {
+			Some(name) => *name,
+			None => return Ok(None)
+		};
+		
+		let number = field_number(name)
+			.ok_or_else(|| DeError::Custom(format!("`{}` is not a valid field number", name)))?;
+		self.value = Some(FieldDeserializer::new(self.values.remove(&number).unwrap_or_default()));
+		seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(name)).map(Some)
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		seed.deserialize(self.value.take().expect("invalid state"))
+	}
+}
+
+/// Yields all fields of a message keyed by their number.
+struct FieldAccess<'a> {
+	fields: std::vec::IntoIter<(u32, Vec<WireValue<'a>>)>,
+	value:  Option<FieldDeserializer<'a>>
+}
+
+impl<'de, 'a> de::MapAccess<'de> for FieldAccess<'a> {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		match self.fields.next() {
+			Some((number, values)) => {
+				self.value = Some(FieldDeserializer::new(values));
+				seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(number)).map(Some)
+			}
+			None => Ok(None)
+		}
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		seed.deserialize(self.value.take().expect("invalid state"))
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Encoding {
+	Default,
+	ZigZag,
+	Fixed
+}
+
+fn wire_error(value: &WireValue, expected: &str) -> DeError {
+	DeError::InvalidType(format!("{:?}", value.wire_type()), expected.to_string())
+}
+
+/// Deserializes all occurrences of one field, the last one wins unless a sequence is requested or
+/// the field is a message, in which case they are merged.
+struct FieldDeserializer<'a> {
+	values:   Vec<WireValue<'a>>,
+	encoding: Encoding
+}
+
+impl<'a> FieldDeserializer<'a> {
+	fn new(values: Vec<WireValue<'a>>) -> Self {
+		Self { values, encoding: Encoding::Default }
+	}
+	
+	fn scalar(&self, packed: Packed) -> Result<u64, DeError> {
+		Ok(match self.values.last() {
+			None                          => 0,
+			Some(WireValue::Varint(v))    => *v,
+			Some(WireValue::I64(v))       => *v,
+			Some(WireValue::I32(v))       => *v as u64,
+			Some(WireValue::Len(data))    => {
+				let mut data = *data;
+				let mut last = WireValue::Varint(0);
+				while !data.is_empty() {
+					last = packed.read(&mut data)?;
+				}
+				return FieldDeserializer::new(vec![last]).scalar(packed);
+			}
+		})
+	}
+	
+	fn signed(&self, bits: u32) -> Result<i64, DeError> {
+		let value = self.scalar(Packed::new(self.encoding, bits))?;
+		Ok(match (self.encoding, self.values.last()) {
+			(Encoding::ZigZag, _)                    => zigzag_decode(value),
+			(_, Some(WireValue::I32(v)))             => *v as i32 as i64,
+			_                                        => value as i64
+		})
+	}
+	
+	fn unsigned(&self, bits: u32) -> Result<u64, DeError> {
+		self.scalar(Packed::new(self.encoding, bits))
+	}
+	
+	fn bytes(&self) -> Result<&'a [u8], DeError> {
+		match self.values.last() {
+			None                       => Ok(&[]),
+			Some(WireValue::Len(data)) => Ok(data),
+			Some(v)                    => Err(wire_error(v, "a length-delimited value"))
+		}
+	}
+	
+	fn str(&self) -> Result<&'a str, DeError> {
+		Ok(std::str::from_utf8(self.bytes()?)?)
+	}
+	
+	fn message<T>(&self, f: impl FnOnce(Deserializer) -> Result<T, DeError>) -> Result<T, DeError> {
+		let mut parts = Vec::with_capacity(self.values.len());
+		for value in &self.values {
+			match value {
+				WireValue::Len(data) => parts.push(*data),
+				v => return Err(wire_error(v, "a message"))
+			}
+		}
+		
+		match parts.as_slice() {
+			[]     => f(Deserializer::new(&[])),
+			[data] => f(Deserializer::new(data)),
+			parts  => f(Deserializer::new(&parts.concat()))
+		}
+	}
+}
+
+enum VariantKey {
+	Name(&'static str),
+	Index(u32)
+}
+
+impl VariantKey {
+	fn new(variants: &'static [&'static str], number: i64, index: i64) -> Self {
+		variants.iter()
+			.find(|v| v.parse::<i64>() == Ok(number))
+			.map_or(Self::Index(index as u32), |v| Self::Name(v))
+	}
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer<'_> {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.values.as_slice() {
+			[]                       => visitor.visit_unit(),
+			[WireValue::Varint(v)]   => visitor.visit_u64(*v),
+			[WireValue::I64(v)]      => visitor.visit_u64(*v),
+			[WireValue::I32(v)]      => visitor.visit_u32(*v),
+			[WireValue::Len(data)]   => visitor.visit_bytes(data),
+			_                        => self.deserialize_seq(visitor)
+		}
+	}
+	
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_bool(self.unsigned(8)? != 0)
+	}
+	
+	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_i8(self.signed(8)? as i8)
+	}
+	
+	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_i16(self.signed(16)? as i16)
+	}
+	
+	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_i32(self.signed(32)? as i32)
+	}
+	
+	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_i64(self.signed(64)?)
+	}
+	
+	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_u8(self.unsigned(8)? as u8)
+	}
+	
+	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_u16(self.unsigned(16)? as u16)
+	}
+	
+	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_u32(self.unsigned(32)? as u32)
+	}
+	
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_u64(self.unsigned(64)?)
+	}
+	
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_f32(f32::from_bits(self.scalar(Packed::Fixed32)? as u32))
+	}
+	
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_f64(f64::from_bits(self.scalar(Packed::Fixed64)?))
+	}
+	
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let s = self.str()?;
+		let mut chars = s.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) => visitor.visit_char(c),
+			_ => Err(DeError::InvalidValue(format!("{:?}", s), "a single character".to_string()))
+		}
+	}
+	
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_str(self.str()?)
+	}
+	
+	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_str(visitor)
+	}
+	
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_bytes(self.bytes()?)
+	}
+	
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_bytes(visitor)
+	}
+	
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if self.values.is_empty() {
+			visitor.visit_none()
+		} else {
+			visitor.visit_some(self)
+		}
+	}
+	
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(mut self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		self.encoding = match name {
+			SINT_NAME  => Encoding::ZigZag,
+			FIXED_NAME => Encoding::Fixed,
+			_          => self.encoding
+		};
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_seq(Repeated { values: self.values.into_iter(), packed: &[], encoding: self.encoding })
+	}
+	
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+	
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+	
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(EntryAccess { entries: self.values.into_iter(), value: None })
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		self.message(|d| de::Deserializer::deserialize_struct(d, name, fields, visitor))
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		match self.values.last() {
+			Some(WireValue::Len(data)) => {
+				let mut last = None;
+				let mut values = Vec::new();
+				for field in fields(data) {
+					let (number, value) = field?;
+					if last != Some(number) {
+						last = Some(number);
+						values.clear();
+					}
+					values.push(value);
+				}
+				
+				let number = last.ok_or_else(|| DeError::Custom("empty message for an enum variant".to_string()))?;
+				visitor.visit_enum(EnumAccess {
+					variant: VariantKey::new(variants, number as i64, number as i64 - 1),
+					value:   FieldDeserializer::new(values)
+				})
+			}
+			_ => {
+				let value = self.signed(32)? as i32 as i64;
+				visitor.visit_enum(EnumAccess {
+					variant: VariantKey::new(variants, value, value),
+					value:   FieldDeserializer::new(Vec::new())
+				})
+			}
+		}
+	}
+	
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_str(visitor)
+	}
+	
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	serde::forward_to_deserialize_any! { i128 u128 }
+}
+
+impl<'de> de::VariantAccess<'de> for FieldDeserializer<'_> {
+	type Error = DeError;
+	
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+	
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		seed.deserialize(self)
+	}
+	
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_seq(self, visitor)
+	}
+	
+	fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_struct(self, "", fields, visitor)
+	}
+}
+
+struct EnumAccess<'a> {
+	variant: VariantKey,
+	value:   FieldDeserializer<'a>
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+	type Error   = DeError;
+	type Variant = FieldDeserializer<'a>;
+	
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let variant = match self.variant {
+			VariantKey::Name(name) => seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(name))?,
+			VariantKey::Index(i)   => seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(i))?
+		};
+		Ok((variant, self.value))
+	}
+}
+
+/// Yields the entries of a map field, which are messages with the key as field 1 and the value as field 2.
+struct EntryAccess<'a> {
+	entries: std::vec::IntoIter<WireValue<'a>>,
+	value:   Option<FieldDeserializer<'a>>
+}
+
+impl<'de, 'a> de::MapAccess<'de> for EntryAccess<'a> {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		let data = match self.entries.next() {
+			Some(WireValue::Len(data)) => data,
+			Some(v) => return Err(wire_error(&v, "a map entry")),
+			None => return Ok(None)
+		};
+		
+		let (mut key, mut value) = (Vec::new(), Vec::new());
+		for field in fields(data) {
+			match field? {
+				(1, v) => key.push(v),
+				(2, v) => value.push(v),
+				_ => ()
+			}
+		}
+		
+		self.value = Some(FieldDeserializer::new(value));
+		seed.deserialize(FieldDeserializer::new(key)).map(Some)
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		seed.deserialize(self.value.take().expect("invalid state"))
+	}
+}
+
+/// Yields the elements of a repeated field, scalars may be packed or not.
+struct Repeated<'a> {
+	values:   std::vec::IntoIter<WireValue<'a>>,
+	packed:   &'a [u8],
+	encoding: Encoding
+}
+
+impl<'a> Repeated<'a> {
+	fn scalar(&mut self, packed: Packed) -> Result<WireValue<'a>, DeError> {
+		if self.packed.is_empty() {
+			match self.values.next() {
+				Some(WireValue::Len(data)) => self.packed = data,
+				Some(v) => return Ok(v),
+				None => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+			}
+		}
+		packed.read(&mut self.packed)
+	}
+	
+	fn value(&mut self) -> Result<WireValue<'a>, DeError> {
+		if !self.packed.is_empty() {
+			return Err(DeError::Custom("expected a scalar in a packed repeated field".to_string()));
+		}
+		self.values.next().ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof).into())
+	}
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Repeated<'a> {
+	type Error = DeError;
+	
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		if self.packed.is_empty() && self.values.as_slice().is_empty() {
+			return Ok(None);
+		}
+		
+		let encoding = self.encoding;
+		seed.deserialize(ElementDeserializer { seq: self, encoding }).map(Some)
+	}
+}
+
+struct ElementDeserializer<'s, 'a> {
+	seq:      &'s mut Repeated<'a>,
+	encoding: Encoding
+}
+
+impl<'a> ElementDeserializer<'_, 'a> {
+	fn scalar(self, packed: Packed) -> Result<FieldDeserializer<'a>, DeError> {
+		Ok(FieldDeserializer { values: vec![self.seq.scalar(packed)?], encoding: self.encoding })
+	}
+	
+	fn value(self) -> Result<FieldDeserializer<'a>, DeError> {
+		Ok(FieldDeserializer { values: vec![self.seq.value()?], encoding: self.encoding })
+	}
+}
+
+macro_rules! element_scalar {
+	($($method:ident $bits:literal),*) => {$(
+		fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+			let packed = Packed::new(self.encoding, $bits);
+			self.scalar(packed)?.$method(visitor)
+		}
+	)*};
+}
+
+macro_rules! element_value {
+	($($method:ident),*) => {$(
+		fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+			self.value()?.$method(visitor)
+		}
+	)*};
+}
+
+impl<'de> de::Deserializer<'de> for ElementDeserializer<'_, '_> {
+	type Error = DeError;
+	
+	element_scalar!(
+		deserialize_bool 8, deserialize_i8 8, deserialize_i16 16, deserialize_i32 32, deserialize_i64 64,
+		deserialize_u8 8, deserialize_u16 16, deserialize_u32 32, deserialize_u64 64
+	);
+	
+	element_value!(
+		deserialize_any, deserialize_char, deserialize_str, deserialize_string, deserialize_bytes,
+		deserialize_byte_buf, deserialize_option, deserialize_unit, deserialize_seq, deserialize_map,
+		deserialize_identifier, deserialize_ignored_any
+	);
+	
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.scalar(Packed::Fixed32)?.deserialize_f32(visitor)
+	}
+	
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.scalar(Packed::Fixed64)?.deserialize_f64(visitor)
+	}
+	
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		self.value()?.deserialize_unit_struct(name, visitor)
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		let encoding = match name {
+			SINT_NAME  => Encoding::ZigZag,
+			FIXED_NAME => Encoding::Fixed,
+			_          => self.encoding
+		};
+		visitor.visit_newtype_struct(ElementDeserializer { seq: self.seq, encoding })
+	}
+	
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.value()?.deserialize_tuple(len, visitor)
+	}
+	
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, name: &'static str, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.value()?.deserialize_tuple_struct(name, len, visitor)
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		self.value()?.deserialize_struct(name, fields, visitor)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		// a length-delimited element is either a message variant or a packed run of enum values,
+		// it's only treated as a variant if it is a valid message with a single field number
+		let is_variant = self.seq.packed.is_empty() && match self.seq.values.as_slice().first() {
+			Some(WireValue::Len(data)) => {
+				let mut numbers = fields(data).map(|f| f.map(|(n, _)| n));
+				match numbers.next() {
+					Some(Ok(first)) => numbers.all(|n| n.is_ok_and(|n| n == first)),
+					_ => false
+				}
+			}
+			_ => false
+		};
+		
+		match is_variant {
+			true  => self.value()?.deserialize_enum(name, variants, visitor),
+			false => self.scalar(Packed::Varint)?.deserialize_enum(name, variants, visitor)
+		}
+	}
+	
+	serde::forward_to_deserialize_any! { i128 u128 }
+}
+
+/// Message and enum definitions parsed from `.proto` files, keyed by their fully qualified names.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+	pub messages: HashMap<String, MessageDescriptor>,
+	pub enums:    HashMap<String, EnumDescriptor>
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessageDescriptor {
+	/// The fully qualified name, e.g. `editor.Level.Entity`.
+	pub name:   String,
+	pub fields: Vec<FieldDescriptor>
+}
+
+impl MessageDescriptor {
+	pub fn field(&self, number: u32) -> Option<&FieldDescriptor> {
+		self.fields.iter().find(|f| f.number == number)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDescriptor {
+	pub name:   String,
+	pub number: u32,
+	pub label:  Label,
+	pub ty:     FieldType,
+	/// Whether the elements of this repeated field are written packed.
+	pub packed: bool,
+	/// The `oneof` this field is part of.
+	pub oneof:  Option<String>
+}
+
+impl FieldDescriptor {
+	/// Whether the field is written even if it has its default value.
+	pub fn has_presence(&self) -> bool {
+		self.label != Label::Singular || self.oneof.is_some() || matches!(self.ty, FieldType::Message(_))
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Label {
+	/// A proto3 field without label, which has no presence.
+	Singular,
+	Optional,
+	Required,
+	Repeated
+}
+
+impl Default for Label {
+	fn default() -> Self {
+		Self::Singular
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FieldType {
+	Double,
+	Float,
+	Int32,
+	Int64,
+	UInt32,
+	UInt64,
+	SInt32,
+	SInt64,
+	Fixed32,
+	Fixed64,
+	SFixed32,
+	SFixed64,
+	Bool,
+	String,
+	Bytes,
+	/// A message, identified by its fully qualified name.
+	Message(String),
+	/// An enum, identified by its fully qualified name.
+	Enum(String),
+	Map(Box<Self>, Box<Self>)
+}
+
+impl FieldType {
+	fn scalar(name: &str) -> Option<Self> {
+		Some(match name {
+			"double"   => Self::Double,
+			"float"    => Self::Float,
+			"int32"    => Self::Int32,
+			"int64"    => Self::Int64,
+			"uint32"   => Self::UInt32,
+			"uint64"   => Self::UInt64,
+			"sint32"   => Self::SInt32,
+			"sint64"   => Self::SInt64,
+			"fixed32"  => Self::Fixed32,
+			"fixed64"  => Self::Fixed64,
+			"sfixed32" => Self::SFixed32,
+			"sfixed64" => Self::SFixed64,
+			"bool"     => Self::Bool,
+			"string"   => Self::String,
+			"bytes"    => Self::Bytes,
+			_          => return None
+		})
+	}
+	
+	pub fn wire_type(&self) -> WireType {
+		match self {
+			Self::Int32 | Self::Int64 | Self::UInt32 | Self::UInt64 | Self::SInt32
+			| Self::SInt64 | Self::Bool | Self::Enum(_)           => WireType::Varint,
+			Self::Fixed32 | Self::SFixed32 | Self::Float          => WireType::I32,
+			Self::Fixed64 | Self::SFixed64 | Self::Double         => WireType::I64,
+			Self::String | Self::Bytes | Self::Message(_) | Self::Map(..) => WireType::Len
+		}
+	}
+	
+	/// Whether repeated fields of this type can be packed.
+	pub fn is_packable(&self) -> bool {
+		self.wire_type() != WireType::Len
+	}
+	
+	fn packed(&self) -> Packed {
+		match self.wire_type() {
+			WireType::I32 => Packed::Fixed32,
+			WireType::I64 => Packed::Fixed64,
+			_             => Packed::Varint
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnumDescriptor {
+	/// The fully qualified name, e.g. `editor.Level.Kind`.
+	pub name:   String,
+	pub values: Vec<(String, i32)>
+}
+
+impl Schema {
+	pub fn parse(src: &str) -> Result<Self, DeError> {
+		let mut schema = Self::default();
+		schema.add(src)?;
+		Ok(schema)
+	}
+	
+	pub fn read<R: io::Read>(mut reader: R) -> Result<Self, DeError> {
+		let mut src = String::new();
+		reader.read_to_string(&mut src)?;
+		Self::parse(&src)
+	}
+	
+	/// Adds the definitions of a `.proto` file. Imports are not followed, the imported files have
+	/// to be added first.
+	pub fn add(&mut self, src: &str) -> Result<(), DeError> {
+		let mut parser = ProtoParser::new(src)?;
+		parser.file()?;
+		
+		let ProtoParser { messages, enums, pending, .. } = parser;
+		let names = messages.iter().map(|m| &m.name)
+			.chain(enums.iter().map(|e| &e.name))
+			.cloned()
+			.collect::<Vec<_>>();
+		
+		for (i, name) in names.iter().enumerate() {
+			if self.messages.contains_key(name) || self.enums.contains_key(name) || names[..i].contains(name) {
+				return Err(DeError::ParseError(format!("`{}` is already defined", name)));
+			}
+		}
+		
+		self.messages.extend(messages.into_iter().map(|m| (m.name.clone(), m)));
+		self.enums.extend(enums.into_iter().map(|e| (e.name.clone(), e)));
+		
+		let mut resolved = Vec::with_capacity(pending.len());
+		for p in &pending {
+			let field = &self.messages[&p.message].fields[p.field];
+			match self.resolve_type(&p.message, &field.ty) {
+				Some(ty) => resolved.push(ty),
+				None => {
+					let error = DeError::ParseError(format!("unknown type in field `{}`", field.name))
+						.at(p.line, p.column);
+					for name in &names {
+						self.messages.remove(name);
+						self.enums.remove(name);
+					}
+					return Err(error);
+				}
+			}
+		}
+		
+		for (p, ty) in pending.iter().zip(resolved) {
+			let field = &mut self.messages.get_mut(&p.message).unwrap().fields[p.field];
+			if let FieldType::Enum(_) = ty {
+				field.packed = field.label == Label::Repeated && p.packed;
+			}
+			field.ty = ty;
+		}
+		Ok(())
+	}
+	
+	pub fn message(&self, name: &str) -> Option<&MessageDescriptor> {
+		self.messages.get(name.strip_prefix('.').unwrap_or(name))
+	}
+	
+	pub fn enumeration(&self, name: &str) -> Option<&EnumDescriptor> {
+		self.enums.get(name.strip_prefix('.').unwrap_or(name))
+	}
+	
+	/// Resolves a type name relative to `scope` like protoc does, by searching the innermost scope first.
+	fn resolve(&self, scope: &str, name: &str) -> Option<FieldType> {
+		let lookup = |name: &str| if self.messages.contains_key(name) {
+			Some(FieldType::Message(name.to_string()))
+		} else if self.enums.contains_key(name) {
+			Some(FieldType::Enum(name.to_string()))
+		} else {
+			None
+		};
+		
+		if let Some(name) = name.strip_prefix('.') {
+			return lookup(name);
+		}
+		
+		let mut scope = scope;
+		loop {
+			let candidate = match scope {
+				"" => name.to_string(),
+				scope => format!("{}.{}", scope, name)
+			};
+			
+			if let Some(ty) = lookup(&candidate) {
+				return Some(ty);
+			} else if scope.is_empty() {
+				return None;
+			}
+			
+			scope = scope.rfind('.').map_or("", |i| &scope[..i]);
+		}
+	}
+	
+	fn resolve_type(&self, scope: &str, ty: &FieldType) -> Option<FieldType> {
+		match ty {
+			FieldType::Message(name) => self.resolve(scope, name),
+			FieldType::Map(key, value) => Some(FieldType::Map(key.clone(), Box::new(self.resolve_type(scope, value)?))),
+			ty => Some(ty.clone())
+		}
+	}
+	
+	/// Decodes a message into a [`Type::Map`] keyed by field names. Fields without presence that are
+	/// missing get their default value, enum values are [`Type::Enum`] with the number as index.
+	pub fn decode(&self, message: &str, data: &[u8]) -> Result<Type, DeError> {
+		let message = self.message(message)
+			.ok_or_else(|| DeError::Custom(format!("unknown message `{}`", message)))?;
+		self.decode_message(message, data, 0)
+	}
+	
+	fn decode_message(&self, message: &MessageDescriptor, data: &[u8], depth: usize) -> Result<Type, DeError> {
+		if depth > MAX_DEPTH {
+			return Err(DeError::ParseError("messages are nested too deeply".to_string()));
+		}
+		
+		let mut values = HashMap::new();
+		for field in &message.fields {
+			let default = match (&field.ty, field.label) {
+				(FieldType::Map(..), _)                      => Type::Map(HashMap::new()),
+				(_, Label::Repeated)                         => Type::Seq(Vec::new()),
+				_ if field.has_presence()                    => continue,
+				(ty, _)                                      => self.default_value(ty)
+			};
+			values.insert(field.name.clone(), default);
+		}
+		
+		for f in fields(data) {
+			let (number, value) = f?;
+			let field = match message.field(number) {
+				Some(field) => field,
+				None => continue
+			};
+			
+			match (&field.ty, field.label) {
+				(FieldType::Map(key_ty, value_ty), _) => {
+					let data = match value {
+						WireValue::Len(data) => data,
+						v => return Err(wire_error(&v, "a map entry"))
+					};
+					
+					let (mut key, mut entry) = (None, None);
+					for f in fields(data) {
+						match f? {
+							(1, v) => key = Some(self.decode_value(key_ty, v, depth)?),
+							(2, v) => entry = Some(self.decode_value(value_ty, v, depth)?),
+							_ => ()
+						}
+					}
+					
+					let key = match key.unwrap_or_else(|| self.default_value(key_ty)) {
+						Type::String(s) => s,
+						Type::Bool(v)   => v.to_string(),
+						Type::SInt(v)   => v.to_string(),
+						Type::UInt(v)   => v.to_string(),
+						v               => return Err(DeError::InvalidType(format!("{:?}", v), "a map key".to_string()))
+					};
+					
+					let entry = match entry {
+						Some(entry) => entry,
+						None => match &**value_ty {
+							FieldType::Message(name) => self.decode_message(&self.messages[name], &[], depth + 1)?,
+							ty => self.default_value(ty)
+						}
+					};
+					
+					if let Some(Type::Map(map)) = values.get_mut(&field.name) {
+						map.insert(key, entry);
+					}
+				}
+				(ty, Label::Repeated) => {
+					let seq = match values.get_mut(&field.name) {
+						Some(Type::Seq(seq)) => seq,
+						_ => unreachable!()
+					};
+					
+					match value {
+						WireValue::Len(mut data) if ty.is_packable() => while !data.is_empty() {
+							seq.push(self.decode_value(ty, ty.packed().read(&mut data)?, depth)?);
+						},
+						value => seq.push(self.decode_value(ty, value, depth)?)
+					}
+				}
+				(ty, _) => {
+					if let Some(oneof) = &field.oneof {
+						message.fields.iter()
+							.filter(|f| f.oneof.as_ref() == Some(oneof))
+							.for_each(|f| { values.remove(&f.name); });
+					}
+					
+					values.insert(field.name.clone(), self.decode_value(ty, value, depth)?);
+				}
+			}
+		}
+		
+		Ok(Type::Map(values))
+	}
+	
+	fn decode_value(&self, ty: &FieldType, value: WireValue, depth: usize) -> Result<Type, DeError> {
+		let v = match (ty.wire_type(), value) {
+			(WireType::Varint, WireValue::Varint(v)) | (WireType::I64, WireValue::I64(v)) => v,
+			(WireType::I32, WireValue::I32(v)) => v as u64,
+			(WireType::Len, WireValue::Len(data)) => return match ty {
+				FieldType::String        => Ok(Type::String(std::str::from_utf8(data)?.to_string())),
+				FieldType::Message(name) => self.decode_message(&self.messages[name], data, depth + 1),
+				_                        => Ok(Type::Bytes(data.to_vec()))
+			},
+			(_, value) => return Err(DeError::InvalidType(format!("{:?}", value.wire_type()), format!("{:?}", ty)))
+		};
+		
+		Ok(match ty {
+			FieldType::Int32 | FieldType::SFixed32 => Type::SInt(v as i32 as _),
+			FieldType::Int64 | FieldType::SFixed64 => Type::SInt(v as i64 as _),
+			FieldType::UInt32 | FieldType::Fixed32 => Type::UInt(v as u32 as _),
+			FieldType::UInt64 | FieldType::Fixed64 => Type::UInt(v as _),
+			FieldType::SInt32                      => Type::SInt(zigzag_decode(v) as i32 as _),
+			FieldType::SInt64                      => Type::SInt(zigzag_decode(v) as _),
+			FieldType::Bool                        => Type::Bool(v != 0),
+			FieldType::Float                       => Type::F32(f32::from_bits(v as u32)),
+			FieldType::Double                      => Type::F64(f64::from_bits(v)),
+			FieldType::Enum(_)                     => Type::Enum(v as i32 as u32, Box::new(Type::Unit)),
+			_                                      => unreachable!()
+		})
+	}
+	
+	fn default_value(&self, ty: &FieldType) -> Type {
+		match ty {
+			FieldType::Int32 | FieldType::Int64 | FieldType::SInt32 | FieldType::SInt64
+			| FieldType::SFixed32 | FieldType::SFixed64 => Type::SInt(0),
+			FieldType::UInt32 | FieldType::UInt64 | FieldType::Fixed32 | FieldType::Fixed64 => Type::UInt(0),
+			FieldType::Bool      => Type::Bool(false),
+			FieldType::Float     => Type::F32(0.0),
+			FieldType::Double    => Type::F64(0.0),
+			FieldType::String    => Type::String(String::new()),
+			FieldType::Bytes     => Type::Bytes(Vec::new()),
+			FieldType::Enum(name) => {
+				let value = self.enums[name].values.first().map_or(0, |(_, v)| *v);
+				Type::Enum(value as u32, Box::new(Type::Unit))
+			}
+			FieldType::Map(..)    => Type::Map(HashMap::new()),
+			FieldType::Message(_) => Type::Unit
+		}
+	}
+	
+	/// Encodes a [`Type::Map`] keyed by field names as a message. Fields that are missing or
+	/// [`Type::Unit`] are skipped, enum values may be given by name or number.
+	pub fn encode(&self, message: &str, value: &Type) -> Result<Vec<u8>, SerError> {
+		let message = self.message(message)
+			.ok_or_else(|| SerError::Custom(format!("unknown message `{}`", message)))?;
+		let mut out = Vec::new();
+		self.encode_message(message, value, &mut out, 0)?;
+		Ok(out)
+	}
+	
+	fn encode_message(&self, message: &MessageDescriptor, value: &Type, out: &mut Vec<u8>, depth: usize) -> Result<(), SerError> {
+		if depth > MAX_DEPTH {
+			return Err(SerError::Custom("messages are nested too deeply".to_string()));
+		}
+		
+		let values = match value {
+			Type::Map(values) => values,
+			v => return Err(SerError::Custom(format!("expected a map for message `{}`, got {:?}", message.name, v)))
+		};
+		
+		if let Some(name) = values.keys().find(|k| message.fields.iter().all(|f| &f.name != *k)) {
+			return Err(SerError::Custom(format!("message `{}` has no field `{}`", message.name, name)));
+		}
+		
+		for field in &message.fields {
+			let value = match values.get(&field.name) {
+				None | Some(Type::Unit) => continue,
+				Some(value) => value
+			};
+			
+			match (&field.ty, value) {
+				(FieldType::Map(key_ty, value_ty), Type::Map(entries)) => {
+					let mut keys = entries.keys().collect::<Vec<_>>();
+					keys.sort();
+					for key in keys {
+						let mut entry = Vec::new();
+						self.encode_value(field, key_ty, &map_key(key_ty, key)?, depth)?.write(&mut entry, 1, false)?;
+						self.encode_value(field, value_ty, &entries[key], depth)?.write(&mut entry, 2, false)?;
+						put_len(out, field.number, &entry);
+					}
+				}
+				(ty, Type::Seq(items)) if field.label == Label::Repeated => {
+					let items = items.iter()
+						.map(|v| self.encode_value(field, ty, v, depth))
+						.collect::<Result<Vec<_>, _>>()?;
+					
+					match field.packed {
+						true  => Encoded::Repeated(items).write(out, field.number, true)?,
+						false => for item in items {
+							item.write(out, field.number, true)?;
+						}
+					}
+				}
+				(ty, value) if field.label != Label::Repeated =>
+					self.encode_value(field, ty, value, depth)?.write(out, field.number, field.has_presence())?,
+				(_, value) => return Err(SerError::Custom(format!(
+					"field `{}`: expected a sequence or map, got {:?}", field.name, value)))
+			}
+		}
+		Ok(())
+	}
+	
+	fn encode_value(&self, field: &FieldDescriptor, ty: &FieldType, value: &Type, depth: usize) -> Result<Encoded, SerError> {
+		fn int<T: TryFrom<i128>>(value: &Type) -> Option<T> {
+			match value {
+				Type::UInt(v) => T::try_from(*v as i128).ok(),
+				Type::SInt(v) => T::try_from(*v as i128).ok(),
+				_             => None
+			}
+		}
+		
+		fn float(value: &Type) -> Option<f64> {
+			match value {
+				Type::F32(v)  => Some(*v as f64),
+				Type::F64(v)  => Some(*v),
+				Type::UInt(v) => Some(*v as f64),
+				Type::SInt(v) => Some(*v as f64),
+				_             => None
+			}
+		}
+		
+		let varint = |value: u64, bits: u32| Some(Encoded::Varint { value, bits });
+		let encoded = match (ty, value) {
+			(FieldType::Int32, v)        => int::<i32>(v).and_then(|v| varint(v as i64 as u64, 32)),
+			(FieldType::Int64, v)        => int::<i64>(v).and_then(|v| varint(v as u64, 64)),
+			(FieldType::UInt32, v)       => int::<u32>(v).and_then(|v| varint(v as u64, 32)),
+			(FieldType::UInt64, v)       => int::<u64>(v).and_then(|v| varint(v, 64)),
+			(FieldType::SInt32, v)       => int::<i32>(v).and_then(|v| varint(zigzag_encode(v as i64), 32)),
+			(FieldType::SInt64, v)       => int::<i64>(v).and_then(|v| varint(zigzag_encode(v), 64)),
+			(FieldType::Bool, Type::Bool(v)) => varint(*v as u64, 8),
+			(FieldType::Enum(name), v)   => self.enum_value(name, v).and_then(|v| varint(v as i64 as u64, 32)),
+			(FieldType::Fixed32, v)      => int::<u32>(v).map(Encoded::I32),
+			(FieldType::SFixed32, v)     => int::<i32>(v).map(|v| Encoded::I32(v as u32)),
+			(FieldType::Fixed64, v)      => int::<u64>(v).map(Encoded::I64),
+			(FieldType::SFixed64, v)     => int::<i64>(v).map(|v| Encoded::I64(v as u64)),
+			(FieldType::Float, v)        => float(v).map(|v| Encoded::I32((v as f32).to_bits())),
+			(FieldType::Double, v)       => float(v).map(|v| Encoded::I64(v.to_bits())),
+			(FieldType::String, Type::String(v)) => Some(Encoded::Len(v.as_bytes().to_vec())),
+			(FieldType::String, Type::Char(v))   => Some(Encoded::Len(v.to_string().into_bytes())),
+			(FieldType::Bytes, Type::Bytes(v))   => Some(Encoded::Len(v.clone())),
+			(FieldType::Bytes, Type::String(v))  => Some(Encoded::Len(v.as_bytes().to_vec())),
+			(FieldType::Bytes, Type::Seq(v))     => v.iter().map(int::<u8>).collect::<Option<Vec<_>>>().map(Encoded::Len),
+			(FieldType::Message(name), v) => {
+				let mut data = Vec::new();
+				self.encode_message(&self.messages[name], v, &mut data, depth + 1)?;
+				Some(Encoded::Message(data))
+			}
+			_ => None
+		};
+		
+		encoded.ok_or_else(|| SerError::Custom(format!("field `{}`: {:?} is not a valid {:?}", field.name, value, ty)))
+	}
+	
+	fn enum_value(&self, name: &str, value: &Type) -> Option<i32> {
+		match value {
+			Type::Enum(v, _) => Some(*v as i32),
+			Type::UInt(v)    => i32::try_from(*v).ok(),
+			Type::SInt(v)    => i32::try_from(*v).ok(),
+			Type::String(v)  => self.enums[name].values.iter()
+				.find(|(n, _)| n == v)
+				.map(|(_, v)| *v),
+			_                => None
+		}
+	}
+}
+
+fn map_key(ty: &FieldType, key: &str) -> Result<Type, SerError> {
+	let error = || SerError::Custom(format!("`{}` is not a valid {:?} map key", key, ty));
+	Ok(match ty {
+		FieldType::String => Type::String(key.to_string()),
+		FieldType::Bool   => Type::Bool(key.parse().map_err(|_| error())?),
+		FieldType::UInt32 | FieldType::UInt64 | FieldType::Fixed32 | FieldType::Fixed64 =>
+			Type::UInt(key.parse().map_err(|_| error())?),
+		_ => Type::SInt(key.parse().map_err(|_| error())?)
+	})
+}
+
+impl FromStr for Schema {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Ident(String),
+	Number(String),
+	Str(String),
+	Symbol(char),
+	End
+}
+
+impl fmt::Display for Token {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Ident(v) | Self::Number(v) => f.write_str(v),
+			Self::Str(v)                     => write!(f, "{:?}", v),
+			Self::Symbol(v)                  => write!(f, "`{}`", v),
+			Self::End                        => f.write_str("end of file")
+		}
+	}
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Token, usize, usize)>, DeError> {
+	let chars = src.chars().collect::<Vec<_>>();
+	let (mut i, mut line, mut column) = (0, 1, 1);
+	let mut tokens = Vec::new();
+	
+	macro_rules! bump {
+		() => {{
+			if chars[i] == '\n' {
+				line += 1;
+				column = 1;
+			} else {
+				column += 1;
+			}
+			i += 1;
+		}};
+	}
+	
+	loop {
+		match (chars.get(i), chars.get(i + 1)) {
+			(Some(c), _) if c.is_whitespace() => bump!(),
+			(Some('/'), Some('/')) => while i < chars.len() && chars[i] != '\n' {
+				bump!();
+			},
+			(Some('/'), Some('*')) => {
+				let (l, c) = (line, column);
+				bump!();
+				bump!();
+				loop {
+					match (chars.get(i), chars.get(i + 1)) {
+						(Some('*'), Some('/')) => break,
+						(Some(_), _) => bump!(),
+						(None, _) => return Err(DeError::ParseError("unterminated comment".to_string()).at(l, c))
+					}
+				}
+				bump!();
+				bump!();
+			}
+			(None, _) => {
+				tokens.push((Token::End, line, column));
+				return Ok(tokens);
+			}
+			(Some(&c), next) => {
+				let (l, col, start) = (line, column, i);
+				let token = if c.is_alphabetic() || c == '_' {
+					while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+						bump!();
+					}
+					Token::Ident(chars[start..i].iter().collect())
+				} else if c.is_ascii_digit() || (c == '.' && next.is_some_and(char::is_ascii_digit)) {
+					let hex = next.is_some_and(|c| *c == 'x' || *c == 'X');
+					while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.' || chars[i] == '_'
+						|| (!hex && (chars[i] == '+' || chars[i] == '-') && matches!(chars[i - 1], 'e' | 'E'))) {
+						bump!();
+					}
+					Token::Number(chars[start..i].iter().collect())
+				} else if c == '"' || c == '\'' {
+					bump!();
+					let mut s = String::new();
+					loop {
+						match chars.get(i) {
+							None | Some('\n') => return Err(DeError::ParseError("unterminated string".to_string()).at(l, col)),
+							Some(&q) if q == c => break,
+							Some('\\') => {
+								bump!();
+								let e = *chars.get(i)
+									.ok_or_else(|| DeError::ParseError("unterminated string".to_string()).at(l, col))?;
+								bump!();
+								macro_rules! digits {
+									($init:expr, $radix:expr, $max:expr) => {{
+										let mut v = $init;
+										for _ in 0..$max {
+											match chars.get(i).and_then(|c| c.to_digit($radix)) {
+												Some(d) => v = v * $radix + d,
+												None => break
+											}
+											bump!();
+										}
+										char::from_u32(v).unwrap_or('\u{FFFD}')
+									}};
+								}
+								
+								match e {
+									'n'         => s.push('\n'),
+									'r'         => s.push('\r'),
+									't'         => s.push('\t'),
+									'a'         => s.push('\x07'),
+									'b'         => s.push('\x08'),
+									'f'         => s.push('\x0C'),
+									'v'         => s.push('\x0B'),
+									'x' | 'X'   => s.push(digits!(0, 16, 2)),
+									'u'         => s.push(digits!(0, 16, 4)),
+									'U'         => s.push(digits!(0, 16, 8)),
+									'0'..='7'   => s.push(digits!(e as u32 - '0' as u32, 8, 2)),
+									c           => s.push(c)
+								}
+							}
+							Some(&c) => {
+								s.push(c);
+								bump!();
+							}
+						}
+					}
+					bump!();
+					Token::Str(s)
+				} else {
+					bump!();
+					Token::Symbol(c)
+				};
+				tokens.push((token, l, col));
+			}
+		}
+	}
+}
+
+fn parse_int(s: &str) -> Option<i128> {
+	if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+		i128::from_str_radix(hex, 16).ok()
+	} else if s.len() > 1 && s.starts_with('0') {
+		i128::from_str_radix(&s[1..], 8).ok()
+	} else {
+		s.parse().ok()
+	}
+}
+
+/// A field whose type name has to be resolved after all definitions are known.
+struct Pending {
+	message: String,
+	field:   usize,
+	packed:  bool,
+	line:    usize,
+	column:  usize
+}
+
+struct ProtoParser {
+	tokens:   Vec<(Token, usize, usize)>,
+	pos:      usize,
+	proto3:   bool,
+	package:  String,
+	messages: Vec<MessageDescriptor>,
+	enums:    Vec<EnumDescriptor>,
+	pending:  Vec<Pending>
+}
+
+impl ProtoParser {
+	fn new(src: &str) -> Result<Self, DeError> {
+		Ok(Self {
+			tokens:   tokenize(src)?,
+			pos:      0,
+			proto3:   false,
+			package:  String::new(),
+			messages: Vec::new(),
+			enums:    Vec::new(),
+			pending:  Vec::new()
+		})
+	}
+	
+	fn peek(&self) -> &Token {
+		&self.tokens[self.pos].0
+	}
+	
+	fn peek_at(&self, offset: usize) -> &Token {
+		&self.tokens[(self.pos + offset).min(self.tokens.len() - 1)].0
+	}
+	
+	fn position(&self) -> (usize, usize) {
+		let (_, line, column) = &self.tokens[self.pos];
+		(*line, *column)
+	}
+	
+	fn next(&mut self) -> Token {
+		let token = self.tokens[self.pos].0.clone();
+		if token != Token::End {
+			self.pos += 1;
+		}
+		token
+	}
+	
+	fn unexpected(&self, expected: &str) -> DeError {
+		let (line, column) = self.position();
+		DeError::invalid_token(self.peek(), expected).at(line, column)
+	}
+	
+	fn error(&self, msg: impl Into<String>) -> DeError {
+		let (line, column) = self.position();
+		DeError::ParseError(msg.into()).at(line, column)
+	}
+	
+	fn is_ident(&self, s: &str) -> bool {
+		matches!(self.peek(), Token::Ident(v) if v == s)
+	}
+	
+	fn symbol(&mut self, c: char) -> Result<(), DeError> {
+		match self.peek() {
+			Token::Symbol(v) if *v == c => {
+				self.pos += 1;
+				Ok(())
+			}
+			_ => Err(self.unexpected(&format!("`{}`", c)))
+		}
+	}
+	
+	fn try_symbol(&mut self, c: char) -> bool {
+		self.symbol(c).is_ok()
+	}
+	
+	fn ident(&mut self) -> Result<String, DeError> {
+		match self.peek().clone() {
+			Token::Ident(v) => {
+				self.pos += 1;
+				Ok(v)
+			}
+			_ => Err(self.unexpected("an identifier"))
+		}
+	}
+	
+	/// Parses a dotted name, optionally fully qualified with a leading dot.
+	fn full_ident(&mut self) -> Result<String, DeError> {
+		let mut name = match self.try_symbol('.') {
+			true  => ".".to_string(),
+			false => String::new()
+		};
+		
+		name.push_str(&self.ident()?);
+		while self.try_symbol('.') {
+			name.push('.');
+			name.push_str(&self.ident()?);
+		}
+		Ok(name)
+	}
+	
+	fn string(&mut self) -> Result<String, DeError> {
+		let mut s = match self.next() {
+			Token::Str(s) => s,
+			_ => {
+				self.pos -= 1;
+				return Err(self.unexpected("a string"));
+			}
+		};
+		
+		while let Token::Str(next) = self.peek() {
+			s.push_str(next);
+			self.pos += 1;
+		}
+		Ok(s)
+	}
+	
+	fn int(&mut self) -> Result<i128, DeError> {
+		let negative = self.try_symbol('-');
+		match self.peek().clone() {
+			Token::Number(s) => match parse_int(&s) {
+				Some(v) => {
+					self.pos += 1;
+					Ok(if negative { -v } else { v })
+				}
+				None => Err(self.unexpected("an integer"))
+			}
+			_ => Err(self.unexpected("an integer"))
+		}
+	}
+	
+	/// Parses an option value, aggregates in braces are skipped and returned as [`Token::End`].
+	fn constant(&mut self) -> Result<Token, DeError> {
+		match self.peek() {
+			Token::Symbol('{') => {
+				self.skip_braces()?;
+				Ok(Token::End)
+			}
+			Token::Symbol('-') | Token::Symbol('+') => {
+				self.pos += 1;
+				match self.peek() {
+					Token::Number(_) | Token::Ident(_) => Ok(self.next()),
+					_ => Err(self.unexpected("a number"))
+				}
+			}
+			Token::Str(_) => self.string().map(Token::Str),
+			Token::Number(_) | Token::Ident(_) => Ok(self.next()),
+			_ => Err(self.unexpected("a constant"))
+		}
+	}
+	
+	/// Parses `name = constant` pairs in brackets.
+	fn options(&mut self) -> Result<Vec<(String, Token)>, DeError> {
+		let mut options = Vec::new();
+		if !self.try_symbol('[') {
+			return Ok(options);
+		}
+		
+		loop {
+			let mut name = String::new();
+			loop {
+				match self.next() {
+					Token::Symbol('=') => break,
+					Token::Ident(v) => name.push_str(&v),
+					Token::Symbol(c @ ('.' | '(' | ')')) => name.push(c),
+					_ => {
+						self.pos -= 1;
+						return Err(self.unexpected("an option name"));
+					}
+				}
+			}
+			
+			options.push((name, self.constant()?));
+			if !self.try_symbol(',') {
+				self.symbol(']')?;
+				return Ok(options);
+			}
+		}
+	}
+	
+	fn option(&mut self) -> Result<(), DeError> {
+		self.pos += 1;
+		while !self.try_symbol('=') {
+			if matches!(self.next(), Token::End | Token::Symbol(';')) {
+				self.pos -= 1;
+				return Err(self.unexpected("`=`"));
+			}
+		}
+		self.constant()?;
+		self.symbol(';')
+	}
+	
+	fn skip_statement(&mut self) -> Result<(), DeError> {
+		loop {
+			match self.next() {
+				Token::Symbol(';') => return Ok(()),
+				Token::End => return Err(self.unexpected("`;`")),
+				_ => ()
+			}
+		}
+	}
+	
+	fn skip_braces(&mut self) -> Result<(), DeError> {
+		self.symbol('{')?;
+		let mut depth = 1;
+		while depth > 0 {
+			match self.next() {
+				Token::Symbol('{') => depth += 1,
+				Token::Symbol('}') => depth -= 1,
+				Token::End => return Err(self.unexpected("`}`")),
+				_ => ()
+			}
+		}
+		Ok(())
+	}
+	
+	/// Skips a definition that is not needed for encoding, such as a service or an extension.
+	fn skip_block(&mut self) -> Result<(), DeError> {
+		while !matches!(self.peek(), Token::Symbol('{')) {
+			if let Token::End | Token::Symbol(';') = self.next() {
+				self.pos -= 1;
+				return Err(self.unexpected("`{`"));
+			}
+		}
+		self.skip_braces()
+	}
+	
+	fn qualify(&self, scope: &str, name: &str) -> String {
+		match (scope, self.package.as_str()) {
+			("", "")      => name.to_string(),
+			("", package) => format!("{}.{}", package, name),
+			(scope, _)    => format!("{}.{}", scope, name)
+		}
+	}
+	
+	fn file(&mut self) -> Result<(), DeError> {
+		loop {
+			match self.peek().clone() {
+				Token::End => return Ok(()),
+				Token::Symbol(';') => self.pos += 1,
+				Token::Ident(v) => match v.as_str() {
+					"syntax" => {
+						self.pos += 1;
+						self.symbol('=')?;
+						match self.string()?.as_str() {
+							"proto2" => self.proto3 = false,
+							"proto3" => self.proto3 = true,
+							v => return Err(self.error(format!("unsupported syntax `{}`", v)))
+						}
+						self.symbol(';')?;
+					}
+					"package" => {
+						self.pos += 1;
+						self.package = self.full_ident()?;
+						self.symbol(';')?;
+					}
+					"import" => {
+						self.pos += 1;
+						if self.is_ident("weak") || self.is_ident("public") {
+							self.pos += 1;
+						}
+						self.string()?;
+						self.symbol(';')?;
+					}
+					"option"  => self.option()?,
+					"message" => {
+						self.pos += 1;
+						self.message("", 0)?;
+					}
+					"enum" => {
+						self.pos += 1;
+						self.enumeration("")?;
+					}
+					"service" | "extend" => {
+						self.pos += 1;
+						self.skip_block()?;
+					}
+					_ => return Err(self.unexpected("a definition"))
+				},
+				_ => return Err(self.unexpected("a definition"))
+			}
+		}
+	}
+	
+	fn message(&mut self, scope: &str, depth: usize) -> Result<(), DeError> {
+		if depth > MAX_DEPTH {
+			return Err(self.error("messages are nested too deeply"));
+		}
+		
+		let name = self.ident()?;
+		let name = self.qualify(scope, &name);
+		let index = self.messages.len();
+		self.messages.push(MessageDescriptor { name: name.clone(), fields: Vec::new() });
+		self.symbol('{')?;
+		
+		loop {
+			match self.peek().clone() {
+				Token::Symbol('}') => {
+					self.pos += 1;
+					return Ok(());
+				}
+				Token::Symbol(';') => self.pos += 1,
+				Token::Ident(v) => match v.as_str() {
+					"message" => {
+						self.pos += 1;
+						self.message(&name, depth + 1)?;
+					}
+					"enum" => {
+						self.pos += 1;
+						self.enumeration(&name)?;
+					}
+					"oneof" => {
+						self.pos += 1;
+						let oneof = self.ident()?;
+						self.symbol('{')?;
+						loop {
+							match self.peek() {
+								Token::Symbol('}') => break,
+								Token::Symbol(';') => self.pos += 1,
+								_ if self.is_ident("option") => self.option()?,
+								_ => self.field(index, Some(&oneof))?
+							}
+						}
+						self.pos += 1;
+					}
+					"extend" => {
+						self.pos += 1;
+						self.skip_block()?;
+					}
+					"option" => self.option()?,
+					"reserved" | "extensions" => self.skip_statement()?,
+					_ => self.field(index, None)?
+				},
+				_ => return Err(self.unexpected("a field"))
+			}
+		}
+	}
+	
+	fn field_type(&mut self) -> Result<FieldType, DeError> {
+		let name = self.full_ident()?;
+		match FieldType::scalar(&name) {
+			Some(ty) => Ok(ty),
+			None if name == "group" => Err(self.error("groups are not supported")),
+			None => Ok(FieldType::Message(name))
+		}
+	}
+	
+	fn field(&mut self, message: usize, oneof: Option<&str>) -> Result<(), DeError> {
+		let (line, column) = self.position();
+		let mut label = match (oneof, self.proto3) {
+			(None, true) => Label::Singular,
+			_            => Label::Optional
+		};
+		
+		if oneof.is_none() {
+			for (name, l) in [("optional", Label::Optional), ("required", Label::Required), ("repeated", Label::Repeated)] {
+				if self.is_ident(name) {
+					self.pos += 1;
+					label = l;
+					break;
+				}
+			}
+		}
+		
+		let ty = if self.is_ident("map") && self.peek_at(1) == &Token::Symbol('<') {
+			self.pos += 2;
+			let key = self.field_type()?;
+			if !matches!(key, FieldType::String | FieldType::Bool) && !key.is_packable()
+				|| matches!(key, FieldType::Float | FieldType::Double) {
+				return Err(self.error("map keys must be integers, booleans or strings"));
+			}
+			
+			self.symbol(',')?;
+			let value = self.field_type()?;
+			self.symbol('>')?;
+			label = Label::Repeated;
+			FieldType::Map(Box::new(key), Box::new(value))
+		} else {
+			self.field_type()?
+		};
+		
+		let name = self.ident()?;
+		self.symbol('=')?;
+		let number = self.int()?;
+		if !(1..=MAX_FIELD_NUMBER as i128).contains(&number) {
+			self.pos -= 1;
+			return Err(self.error(format!("invalid field number {}", number)));
+		}
+		
+		let packed = self.options()?.into_iter()
+			.rev()
+			.find(|(name, _)| name == "packed")
+			.map(|(_, v)| v == Token::Ident("true".to_string()))
+			.unwrap_or(self.proto3 && label == Label::Repeated);
+		self.symbol(';')?;
+		
+		let MessageDescriptor { name: message, fields } = &mut self.messages[message];
+		if fields.iter().any(|f| f.number == number as u32) {
+			return Err(DeError::ParseError(format!("field number {} is used more than once", number)).at(line, column));
+		}
+		
+		if matches!(&ty, FieldType::Message(_) | FieldType::Map(..)) {
+			self.pending.push(Pending { message: message.clone(), field: fields.len(), packed, line, column });
+		}
+		
+		fields.push(FieldDescriptor {
+			packed: packed && label == Label::Repeated && ty.is_packable(),
+			oneof:  oneof.map(str::to_string),
+			number: number as u32,
+			name,
+			label,
+			ty
+		});
+		Ok(())
+	}
+	
+	fn enumeration(&mut self, scope: &str) -> Result<(), DeError> {
+		let name = self.ident()?;
+		let name = self.qualify(scope, &name);
+		let mut values = Vec::new();
+		self.symbol('{')?;
+		
+		loop {
+			match self.peek() {
+				Token::Symbol('}') => {
+					self.pos += 1;
+					break;
+				}
+				Token::Symbol(';') => self.pos += 1,
+				_ if self.is_ident("option") => self.option()?,
+				_ if self.is_ident("reserved") => self.skip_statement()?,
+				_ => {
+					let name = self.ident()?;
+					self.symbol('=')?;
+					let value = i32::try_from(self.int()?)
+						.map_err(|_| self.error("enum values must be 32-bit integers"))?;
+					self.options()?;
+					self.symbol(';')?;
+					values.push((name, value));
+				}
+			}
+		}
+		
+		self.enums.push(EnumDescriptor { name, values });
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::collections::BTreeMap};
+	
+	#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	struct Test1 {
+		#[serde(rename = "1")]
+		a: i32
+	}
+	
+	#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	struct Entity {
+		#[serde(rename = "1")]
+		name:     String,
+		#[serde(rename = "2")]
+		id:       u64,
+		#[serde(rename = "3")]
+		offset:   SInt<i32>,
+		#[serde(rename = "4")]
+		position: [f32; 3],
+		#[serde(rename = "5")]
+		tags:     Vec<String>,
+		#[serde(rename = "6")]
+		parent:   Option<Box<Entity>>,
+		#[serde(rename = "7")]
+		kind:     Kind,
+		#[serde(rename = "8")]
+		props:    BTreeMap<String, i64>,
+		#[serde(rename = "9")]
+		data:     Bytes,
+		#[serde(rename = "10")]
+		hash:     Fixed<u64>,
+		#[serde(rename = "11")]
+		shape:    Option<Shape>,
+		#[serde(rename = "12")]
+		scale:    f64,
+		#[serde(rename = "13")]
+		deltas:   Vec<SInt<i64>>
+	}
+	
+	#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	enum Kind {
+		#[default]
+		Static,
+		Dynamic,
+		Kinematic
+	}
+	
+	#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+	enum Shape {
+		#[serde(rename = "20")]
+		Sphere(f32),
+		#[serde(rename = "21")]
+		Mesh { #[serde(rename = "1")] path: String }
+	}
+	
+	fn entity() -> Entity {
+		Entity {
+			name:     "crate".to_string(),
+			id:       1 << 40,
+			offset:   SInt(-3),
+			position: [1.0, -2.5, 0.0],
+			tags:     vec!["solid".to_string(), String::new()],
+			parent:   Some(Box::new(Entity { name: "level".to_string(), id: 1, ..Entity::default() })),
+			kind:     Kind::Kinematic,
+			props:    [("hp".to_string(), -100), ("lives".to_string(), 3)].into_iter().collect(),
+			data:     Bytes(vec![0, 1, 2, 255]),
+			hash:     Fixed(0xDEAD_BEEF_0000_0001),
+			shape:    Some(Shape::Mesh { path: "crate.glb".to_string() }),
+			scale:    0.5,
+			deltas:   vec![SInt(-1), SInt(i64::MIN), SInt(i64::MAX)]
+		}
+	}
+	
+	#[test]
+	fn wire() {
+		assert_eq!(read_varint(&[0x96, 0x01][..]).unwrap(), 150);
+		assert_eq!(read_varint(&[0xFF; 11][..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+		
+		let mut buf = Vec::new();
+		write_varint(&mut buf, u64::MAX).unwrap();
+		assert_eq!(buf.len(), 10);
+		assert_eq!(read_varint(buf.as_slice()).unwrap(), u64::MAX);
+		
+		for (v, z) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (i32::MAX as i64, 0xFFFF_FFFE), (i32::MIN as i64, 0xFFFF_FFFF)] {
+			assert_eq!(zigzag_encode(v), z);
+			assert_eq!(zigzag_decode(z), v);
+		}
+		assert_eq!(zigzag_decode(zigzag_encode(i64::MIN)), i64::MIN);
+	}
+	
+	#[test]
+	fn known_encodings() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Test2 {
+			#[serde(rename = "2")]
+			b: String
+		}
+		
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Test3 {
+			#[serde(rename = "3")]
+			c: Test1
+		}
+		
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Test4 {
+			#[serde(rename = "6")]
+			f: Vec<i32>
+		}
+		
+		assert_eq!(serialize_vec(&Test1 { a: 150 }).unwrap(), [0x08, 0x96, 0x01]);
+		assert_eq!(serialize_vec(&Test1 { a: 0 }).unwrap(), []);
+		assert_eq!(serialize_vec(&Test1 { a: -2 }).unwrap(), [0x08, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+		assert_eq!(serialize_vec(&Test2 { b: "testing".to_string() }).unwrap(), b"\x12\x07testing");
+		assert_eq!(serialize_vec(&Test3 { c: Test1 { a: 150 } }).unwrap(), [0x1A, 0x03, 0x08, 0x96, 0x01]);
+		assert_eq!(serialize_vec(&Test4 { f: vec![3, 270, 86942] }).unwrap(), [0x32, 0x06, 0x03, 0x8E, 0x02, 0x9E, 0xA7, 0x05]);
+		
+		assert_eq!(deserialize_slice::<Test1>(&[0x08, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]).unwrap(), Test1 { a: -2 });
+		assert_eq!(deserialize_slice::<Test3>(&[0x1A, 0x03, 0x08, 0x96, 0x01]).unwrap(), Test3 { c: Test1 { a: 150 } });
+		// unpacked elements, packed runs and unknown fields may be mixed
+		assert_eq!(deserialize_slice::<Test4>(&[0x30, 0x03, 0x32, 0x02, 0x8E, 0x02, 0x38, 0x01, 0x30, 0x9E, 0xA7, 0x05]).unwrap(),
+			Test4 { f: vec![3, 270, 86942] });
+		// the last value of a scalar wins, messages are merged
+		assert_eq!(deserialize_slice::<Test1>(&[0x08, 0x01, 0x08, 0x02]).unwrap(), Test1 { a: 2 });
+		assert_eq!(deserialize_slice::<Test3>(&[0x1A, 0x02, 0x08, 0x01, 0x1A, 0x00]).unwrap(), Test3 { c: Test1 { a: 1 } });
+		assert_eq!(deserialize_slice::<Test3>(&[]).unwrap(), Test3 { c: Test1 { a: 0 } });
+	}
+	
+	#[test]
+	fn round_trip() {
+		let data = serialize_vec(&entity()).unwrap();
+		assert_eq!(deserialize::<Entity, _>(data.as_slice()).unwrap(), entity());
+		assert_eq!(deserialize_slice::<Entity>(&serialize_vec(&Entity::default()).unwrap()).unwrap(), Entity::default());
+		
+		let mut e = entity();
+		e.shape = Some(Shape::Sphere(0.0));
+		e.kind = Kind::Static;
+		let data = serialize_vec(&e).unwrap();
+		assert_eq!(deserialize_slice::<Entity>(&data).unwrap(), e);
+		
+		
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Wrapper {
+			#[serde(rename = "1")]
+			kinds:  Vec<Kind>,
+			#[serde(rename = "2")]
+			shapes: Vec<Shape>
+		}
+		
+		let w = Wrapper {
+			kinds:  vec![Kind::Dynamic, Kind::Static, Kind::Kinematic],
+			shapes: vec![Shape::Sphere(1.0), Shape::Mesh { path: String::new() }]
+		};
+		let data = serialize_vec(&w).unwrap();
+		assert_eq!(&data[..5], [0x0A, 0x03, 0x01, 0x00, 0x02]);
+		assert_eq!(deserialize_slice::<Wrapper>(&data).unwrap(), w);
+		
+		assert!(serialize_vec(&1u32).is_err());
+		assert!(serialize_vec(&BTreeMap::<String, u32>::new()).is_err());
+	}
+	
+	const PROTO: &str = r#"
+		// level editor protocol
+		syntax = "proto3";
+		package editor.v1;
+		
+		import "google/protobuf/any.proto";
+		option java_package = "com.example.editor";
+		
+		/* an entity in the level */
+		message Entity {
+			string name = 1;
+			uint64 id = 2 [json_name = "entityId"];
+			sint32 offset = 3;
+			repeated float position = 4;
+			repeated string tags = 5;
+			optional Entity parent = 6;
+			Kind kind = 7;
+			map<string, int64> props = 8;
+			bytes data = 9;
+			fixed64 hash = 10;
+			Shape shape = 11;
+			double scale = 12;
+			repeated sint64 deltas = 13 [packed = true];
+			reserved 14, 15 to 19;
+			reserved "old";
+			
+			message Mesh {
+				string path = 1;
+			}
+			
+			message Shape {
+				oneof shape {
+					float sphere = 20;
+					Mesh mesh = 21;
+				}
+			}
+		}
+		
+		enum Kind {
+			option allow_alias = true;
+			STATIC = 0;
+			DYNAMIC = 1 [deprecated = true];
+			KINEMATIC = 2;
+		}
+		
+		service Editor {
+			rpc Load (Entity) returns (Entity) { option (google.api.http) = { get: "/v1/{name}" }; }
+		}
+		
+		message Level {
+			repeated .editor.v1.Entity entities = 1;
+			map<uint32, Entity.Mesh> meshes = 2;
+			repeated int32 unpacked = 3 [packed = false];
+		}
+	"#;
+	
+	#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	struct NamedEntity {
+		name:     String,
+		id:       u64,
+		offset:   i32,
+		position: [f32; 3],
+		tags:     Vec<String>,
+		parent:   Option<Box<NamedEntity>>,
+		kind:     Kind,
+		props:    BTreeMap<String, i64>,
+		data:     Bytes,
+		hash:     u64,
+		shape:    Option<NamedShape>,
+		scale:    f64,
+		deltas:   Vec<i64>
+	}
+	
+	#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	struct NamedShape {
+		sphere: Option<f32>,
+		mesh:   Option<NamedMesh>
+	}
+	
+	#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+	struct NamedMesh {
+		path: String
+	}
+	
+	#[test]
+	fn schema() {
+		let schema = PROTO.parse::<Schema>().unwrap();
+		let message = schema.message("editor.v1.Entity").unwrap();
+		assert_eq!(message.fields.len(), 13);
+		assert!(message.field(4).unwrap().packed);
+		assert!(!message.field(5).unwrap().packed);
+		assert_eq!(message.field(6).unwrap().ty, FieldType::Message("editor.v1.Entity".to_string()));
+		assert_eq!(message.field(7).unwrap().ty, FieldType::Enum("editor.v1.Kind".to_string()));
+		assert_eq!(schema.message("editor.v1.Entity.Shape").unwrap().field(21).unwrap().oneof.as_deref(), Some("shape"));
+		assert_eq!(message.field(8).unwrap().ty, FieldType::Map(Box::new(FieldType::String), Box::new(FieldType::Int64)));
+		
+		let level = schema.message(".editor.v1.Level").unwrap();
+		assert_eq!(level.field(2).unwrap().ty, FieldType::Map(
+			Box::new(FieldType::UInt32), Box::new(FieldType::Message("editor.v1.Entity.Mesh".to_string()))));
+		assert!(!level.field(3).unwrap().packed);
+		assert_eq!(schema.enumeration("editor.v1.Kind").unwrap().values[2], ("KINEMATIC".to_string(), 2));
+		
+		// the dynamic decoder agrees with the serde encoding that uses field numbers
+		let data = serialize_vec(&entity()).unwrap();
+		let value = schema.decode("editor.v1.Entity", &data).unwrap();
+		let map = match &value {
+			Type::Map(map) => map,
+			v => panic!("{:?}", v)
+		};
+		assert_eq!(map["offset"], Type::SInt(-3));
+		assert_eq!(map["kind"], Type::Enum(2, Box::new(Type::Unit)));
+		assert_eq!(map["data"], Type::Bytes(vec![0, 1, 2, 255]));
+		assert_eq!(map["shape"], Type::Map([("mesh".to_string(), Type::Map(
+			[("path".to_string(), Type::String("crate.glb".to_string()))].into_iter().collect()))].into_iter().collect()));
+		
+		let named = deserialize_with::<NamedEntity, _>(data.as_slice(), &schema, "editor.v1.Entity").unwrap();
+		assert_eq!(named.offset, -3);
+		assert_eq!(named.parent.as_ref().unwrap().name, "level");
+		assert_eq!(named.parent.as_ref().unwrap().parent, None);
+		assert_eq!(named.shape.as_ref().unwrap().mesh, Some(NamedMesh { path: "crate.glb".to_string() }));
+		assert_eq!(named.shape.as_ref().unwrap().sphere, None);
+		assert_eq!(named.deltas, [-1, i64::MIN, i64::MAX]);
+		
+		let mut buf = Vec::new();
+		serialize_with(&mut buf, &schema, "editor.v1.Entity", &named).unwrap();
+		assert_eq!(deserialize_slice::<Entity>(&buf).unwrap(), entity());
+		assert_eq!(schema.encode("editor.v1.Entity", &value).unwrap().len(), data.len());
+		
+		let level = Type::Map([
+			("meshes".to_string(), Type::Map([("7".to_string(), Type::Map(HashMap::new()))].into_iter().collect())),
+			("unpacked".to_string(), Type::Seq(vec![Type::SInt(1), Type::SInt(-1)]))
+		].into_iter().collect());
+		let data = schema.encode("editor.v1.Level", &level).unwrap();
+		assert_eq!(data[..7], [0x12, 0x04, 0x08, 0x07, 0x12, 0x00, 0x18]);
+		let decoded = schema.decode("editor.v1.Level", &data).unwrap();
+		assert_eq!(decoded, Type::Map([
+			("entities".to_string(), Type::Seq(Vec::new())),
+			("meshes".to_string(), Type::Map([("7".to_string(), Type::Map([("path".to_string(), Type::String(String::new()))].into_iter().collect()))].into_iter().collect())),
+			("unpacked".to_string(), Type::Seq(vec![Type::SInt(1), Type::SInt(-1)]))
+		].into_iter().collect()));
+		
+		assert!(schema.encode("editor.v1.Level", &Type::Map([("foo".to_string(), Type::Unit)].into_iter().collect())).is_err());
+		assert!(schema.decode("editor.v1.Missing", &[]).is_err());
+	}
+	
+	#[test]
+	fn schema_errors() {
+		let error = |src: &str| Schema::parse(src).unwrap_err().position();
+		assert_eq!(error("syntax = \"proto3\";\nmessage A {\n  int32 a = 1\n}"), Some((4, 1)));
+		assert_eq!(error("message A {\n  Missing a = 1;\n}"), Some((2, 3)));
+		assert_eq!(error("message A { int32 a = 0; }"), Some((1, 23)));
+		assert_eq!(error("message A { int32 a = 1; int32 b = 1; }"), Some((1, 26)));
+		assert_eq!(error("message A { /* }"), Some((1, 13)));
+		assert_eq!(error("enum E { A = \"x\"; }"), Some((1, 14)));
+		assert!(Schema::parse("message A {} message A {}").is_err());
+		
+		assert_eq!(error("package a; message A { b.B b = 1; }"), Some((1, 24)));
+		
+		let mut schema = Schema::parse("package b; message B { string s = 1; }").unwrap();
+		schema.add("package a; import \"b.proto\"; message A { b.B b = 1; }").unwrap();
+		assert_eq!(schema.message("a.A").unwrap().fields[0].ty, FieldType::Message("b.B".to_string()));
+		assert!(schema.add("package c; message C { Nope n = 1; } message D {}").is_err());
+		assert!(schema.message("c.D").is_none());
+	}
+}
@@ -0,0 +1,723 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Validation of documents against a subset of JSON Schema (draft 2020-12). Schemas can be
+//! built from Rust types or loaded from JSON, documents are validated as `dyn_repr::Type`.
+//! All violations are collected, each with the path of the offending value and, if the
+//! document was validated from source, the line and column where the value starts.
+//!
+//! Supported keywords are `type`, `enum`, `const`, `minimum`, `maximum`, `exclusiveMinimum`,
+//! `exclusiveMaximum`, `multipleOf`, `minLength`, `maxLength`, `prefixItems`, `items`,
+//! `minItems`, `maxItems`, `uniqueItems`, `properties`, `required`, `additionalProperties`,
+//! `minProperties`, `maxProperties`, `allOf`, `anyOf`, `oneOf`, `not`, `$ref` and `$defs`.
+//! References can only point to the root schema or its definitions, other keywords are
+//! ignored. Enum variants are validated as their value.
+
+use {
+	crate::dyn_repr::{Type, Path},
+	std::{fmt, collections::HashMap},
+	serde::{Deserialize, Deserializer}
+};
+
+/// Maximum number of nested references without progress in the document, guards against
+/// reference cycles.
+const MAX_REF_DEPTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Schema {
+	/// `true` accepts every value, `false` none.
+	Bool(bool),
+	Object(Box<SchemaObject>)
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SchemaObject {
+	/// Allowed kinds of values, any kind if empty.
+	#[serde(rename = "type", deserialize_with = "one_or_many")]
+	pub ty:                     Vec<Kind>,
+	#[serde(rename = "enum")]
+	pub enum_values:            Option<Vec<Type>>,
+	#[serde(rename = "const")]
+	pub const_value:            Option<Type>,
+	pub minimum:                Option<f64>,
+	pub maximum:                Option<f64>,
+	pub exclusive_minimum:      Option<f64>,
+	pub exclusive_maximum:      Option<f64>,
+	pub multiple_of:            Option<f64>,
+	pub min_length:             Option<usize>,
+	pub max_length:             Option<usize>,
+	pub prefix_items:           Vec<Schema>,
+	/// Schema of all items after the `prefix_items`.
+	pub items:                  Option<Schema>,
+	pub min_items:              Option<usize>,
+	pub max_items:              Option<usize>,
+	pub unique_items:           bool,
+	pub properties:             HashMap<String, Schema>,
+	pub required:               Vec<String>,
+	/// Schema of all properties that are not listed in `properties`.
+	pub additional_properties:  Option<Schema>,
+	pub min_properties:         Option<usize>,
+	pub max_properties:         Option<usize>,
+	pub all_of:                 Vec<Schema>,
+	pub any_of:                 Vec<Schema>,
+	pub one_of:                 Vec<Schema>,
+	pub not:                    Option<Schema>,
+	/// Either `#` or `#/$defs/<name>`.
+	#[serde(rename = "$ref")]
+	pub reference:              Option<String>,
+	#[serde(rename = "$defs", alias = "definitions")]
+	pub defs:                   HashMap<String, Schema>
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+	Null,
+	Boolean,
+	Integer,
+	Number,
+	String,
+	Array,
+	Object
+}
+
+/// A value that does not conform to the schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+	pub path:     Path,
+	/// Line and column of the value in the source document.
+	pub position: Option<(usize, usize)>,
+	pub message:  String
+}
+
+impl Schema {
+	/// Loads a schema from a JSON document.
+	#[cfg(feature = "json")]
+	pub fn from_json(src: &str) -> Result<Self, crate::utils::DeError> {
+		crate::json::deserialize_slice(src.as_bytes())
+	}
+	
+	/// Validates the value and returns all violations, in document order.
+	pub fn validate(&self, value: &Type) -> Vec<Violation> {
+		let mut validator = Validator { root: self, violations: Vec::new(), depth: 0 };
+		validator.validate(self, value, &mut Path::default());
+		validator.violations
+	}
+	
+	/// Parses and validates a JSON document, violations include their source position.
+	#[cfg(feature = "json")]
+	pub fn validate_json(&self, src: &str) -> Result<Vec<Violation>, crate::utils::DeError> {
+		let value = crate::json::deserialize_slice::<crate::json::Value>(src.as_bytes())?;
+		let positions = json_positions(src)?;
+		Ok(locate(self.validate(&Type::from(value)), &positions))
+	}
+	
+	/// Parses and validates a TOML document, violations include their source position.
+	/// Datetimes are validated as strings.
+	#[cfg(feature = "toml")]
+	pub fn validate_toml(&self, src: &str) -> Result<Vec<Violation>, crate::utils::DeError> {
+		let doc = crate::toml::Document::parse(src)?;
+		let positions = toml_positions(src, &doc);
+		Ok(locate(self.validate(&Type::from(doc.to_value()?)), &positions))
+	}
+	
+	fn is_valid(&self, root: &Schema, value: &Type, path: &mut Path, depth: usize) -> bool {
+		let mut validator = Validator { root, violations: Vec::new(), depth };
+		validator.validate(self, value, path);
+		validator.violations.is_empty()
+	}
+}
+
+impl Default for Schema {
+	fn default() -> Self {
+		Self::Bool(true)
+	}
+}
+
+impl From<SchemaObject> for Schema {
+	fn from(v: SchemaObject) -> Self {
+		Self::Object(Box::new(v))
+	}
+}
+
+impl SchemaObject {
+	/// Creates a schema that only accepts values of the given kind.
+	pub fn of(kind: Kind) -> Self {
+		Self { ty: vec![kind], ..Self::default() }
+	}
+}
+
+impl Kind {
+	fn of(value: &Type) -> Self {
+		match value {
+			Type::Unit                  => Self::Null,
+			Type::Bool(_)               => Self::Boolean,
+			Type::UInt(_) | Type::SInt(_) => Self::Integer,
+			Type::F32(_) | Type::F64(_) => Self::Number,
+			Type::String(_) | Type::Char(_) => Self::String,
+			Type::Seq(_) | Type::Bytes(_) => Self::Array,
+			Type::Map(_)                => Self::Object,
+			Type::Enum(_, v)            => Self::of(v)
+		}
+	}
+	
+	fn matches(self, value: &Type) -> bool {
+		match (self, Self::of(value)) {
+			(Self::Integer, Self::Number) => number(value).is_some_and(|v| v.fract() == 0.0),
+			(Self::Number, Self::Integer) => true,
+			(a, b) => a == b
+		}
+	}
+}
+
+impl fmt::Display for Kind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Null    => "null",
+			Self::Boolean => "boolean",
+			Self::Integer => "integer",
+			Self::Number  => "number",
+			Self::String  => "string",
+			Self::Array   => "array",
+			Self::Object  => "object"
+		})
+	}
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some((line, column)) = self.position {
+			write!(f, "{}:{}: ", line, column)?;
+		}
+		
+		if !self.path.0.is_empty() {
+			write!(f, "`{}`: ", self.path)?;
+		}
+		
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for Violation {}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Kind>, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum OneOrMany {
+		One(Kind),
+		Many(Vec<Kind>)
+	}
+	
+	Ok(match OneOrMany::deserialize(deserializer)? {
+		OneOrMany::One(v) => vec![v],
+		OneOrMany::Many(v) => v
+	})
+}
+
+fn number(value: &Type) -> Option<f64> {
+	match *value {
+		Type::UInt(v) => Some(v as _),
+		Type::SInt(v) => Some(v as _),
+		Type::F32(v)  => Some(v as _),
+		Type::F64(v)  => Some(v),
+		Type::Enum(_, ref v) => number(v),
+		_ => None
+	}
+}
+
+/// Compares two values like JSON Schema does: numbers by their value, chars as strings and
+/// enums by their value.
+fn equals(a: &Type, b: &Type) -> bool {
+	match (a, b) {
+		(Type::Enum(_, a), b) | (b, Type::Enum(_, a)) => equals(a, b),
+		(Type::Char(a), Type::String(b)) | (Type::String(b), Type::Char(a)) => {
+			let mut chars = b.chars();
+			chars.next() == Some(*a) && chars.next().is_none()
+		}
+		(Type::Seq(a), Type::Seq(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equals(a, b)),
+		(Type::Map(a), Type::Map(b)) => a.len() == b.len()
+			&& a.iter().all(|(k, a)| b.get(k).is_some_and(|b| equals(a, b))),
+		(Type::Bytes(a), Type::Seq(b)) | (Type::Seq(b), Type::Bytes(a)) => a.len() == b.len()
+			&& a.iter().zip(b).all(|(a, b)| number(b) == Some(*a as f64)),
+		_ => match (number(a), number(b)) {
+			(Some(a), Some(b)) => a == b,
+			_ => a == b
+		}
+	}
+}
+
+struct Validator<'a> {
+	root:       &'a Schema,
+	violations: Vec<Violation>,
+	/// Number of references that were followed without descending into the document.
+	depth:      usize
+}
+
+impl<'a> Validator<'a> {
+	fn error(&mut self, path: &Path, message: impl ToString) {
+		self.violations.push(Violation { path: path.clone(), position: None, message: message.to_string() });
+	}
+	
+	fn validate(&mut self, schema: &Schema, value: &Type, path: &mut Path) {
+		match schema {
+			Schema::Bool(true) => (),
+			Schema::Bool(false) => self.error(path, "no value is allowed here"),
+			Schema::Object(schema) => self.object(schema, value, path)
+		}
+	}
+	
+	/// Validates a child value, resets the reference depth as the document is descended.
+	fn child(&mut self, schema: &Schema, value: &Type, path: &mut Path) {
+		let depth = std::mem::replace(&mut self.depth, 0);
+		self.validate(schema, value, path);
+		self.depth = depth;
+		path.0.pop();
+	}
+	
+	fn resolve(&self, reference: &str) -> Option<&'a Schema> {
+		if reference == "#" {
+			return Some(self.root);
+		}
+		
+		let name = reference.strip_prefix("#/$defs/").or_else(|| reference.strip_prefix("#/definitions/"))?;
+		match self.root {
+			Schema::Object(root) => root.defs.get(&name.replace("~1", "/").replace("~0", "~")),
+			Schema::Bool(_) => None
+		}
+	}
+	
+	fn object(&mut self, schema: &SchemaObject, value: &Type, path: &mut Path) {
+		let value = match value {
+			Type::Enum(_, v) => v,
+			v => v
+		};
+		
+		if let Some(reference) = &schema.reference {
+			match self.resolve(reference) {
+				_ if self.depth >= MAX_REF_DEPTH => self.error(path, format!("too many nested references at `{}`", reference)),
+				Some(target) => {
+					self.depth += 1;
+					self.validate(target, value, path);
+					self.depth -= 1;
+				}
+				None => self.error(path, format!("unresolved reference `{}`", reference))
+			}
+		}
+		
+		if !schema.ty.is_empty() && !schema.ty.iter().any(|kind| kind.matches(value)) {
+			let expected = schema.ty.iter().map(Kind::to_string).collect::<Vec<_>>().join(" or ");
+			self.error(path, format!("expected {}, found {}", expected, Kind::of(value)));
+			return;
+		}
+		
+		if let Some(values) = &schema.enum_values {
+			if !values.iter().any(|v| equals(v, value)) {
+				self.error(path, "value is not one of the allowed values");
+			}
+		}
+		
+		if let Some(v) = &schema.const_value {
+			if !equals(v, value) {
+				self.error(path, "value does not equal the constant");
+			}
+		}
+		
+		if let Some(v) = number(value) {
+			self.number(schema, v, path);
+		}
+		
+		match value {
+			Type::String(v) => self.string(schema, v.chars().count(), path),
+			Type::Char(_) => self.string(schema, 1, path),
+			Type::Seq(v) => self.array(schema, v, path),
+			Type::Bytes(v) => self.array(schema, &v.iter().map(|v| Type::UInt(*v as _)).collect::<Vec<_>>(), path),
+			Type::Map(v) => self.map(schema, v, path),
+			_ => ()
+		}
+		
+		for s in &schema.all_of {
+			self.validate(s, value, path);
+		}
+		
+		if !schema.any_of.is_empty() && !schema.any_of.iter().any(|s| s.is_valid(self.root, value, path, self.depth)) {
+			self.error(path, "value does not match any schema of `anyOf`");
+		}
+		
+		if !schema.one_of.is_empty() {
+			match schema.one_of.iter().filter(|s| s.is_valid(self.root, value, path, self.depth)).count() {
+				1 => (),
+				0 => self.error(path, "value does not match any schema of `oneOf`"),
+				n => self.error(path, format!("value matches {} schemas of `oneOf`", n))
+			}
+		}
+		
+		if schema.not.as_ref().is_some_and(|s| s.is_valid(self.root, value, path, self.depth)) {
+			self.error(path, "value matches the schema of `not`");
+		}
+	}
+	
+	fn number(&mut self, schema: &SchemaObject, v: f64, path: &Path) {
+		if let Some(min) = schema.minimum.filter(|min| v < *min) {
+			self.error(path, format!("{} is less than the minimum of {}", v, min));
+		}
+		
+		if let Some(max) = schema.maximum.filter(|max| v > *max) {
+			self.error(path, format!("{} is greater than the maximum of {}", v, max));
+		}
+		
+		if let Some(min) = schema.exclusive_minimum.filter(|min| v <= *min) {
+			self.error(path, format!("{} is not greater than {}", v, min));
+		}
+		
+		if let Some(max) = schema.exclusive_maximum.filter(|max| v >= *max) {
+			self.error(path, format!("{} is not less than {}", v, max));
+		}
+		
+		if let Some(m) = schema.multiple_of {
+			let q = v / m;
+			if !q.is_finite() || (q - q.round()).abs() > q.abs().max(1.0) * 1e-12 {
+				self.error(path, format!("{} is not a multiple of {}", v, m));
+			}
+		}
+	}
+	
+	fn string(&mut self, schema: &SchemaObject, len: usize, path: &Path) {
+		if let Some(min) = schema.min_length.filter(|min| len < *min) {
+			self.error(path, format!("string is shorter than {} characters", min));
+		}
+		
+		if let Some(max) = schema.max_length.filter(|max| len > *max) {
+			self.error(path, format!("string is longer than {} characters", max));
+		}
+	}
+	
+	fn array(&mut self, schema: &SchemaObject, items: &[Type], path: &mut Path) {
+		if let Some(min) = schema.min_items.filter(|min| items.len() < *min) {
+			self.error(path, format!("array has fewer than {} items", min));
+		}
+		
+		if let Some(max) = schema.max_items.filter(|max| items.len() > *max) {
+			self.error(path, format!("array has more than {} items", max));
+		}
+		
+		if schema.unique_items {
+			for (i, a) in items.iter().enumerate() {
+				if let Some(j) = items[..i].iter().position(|b| equals(a, b)) {
+					self.error(path, format!("items {} and {} are equal", j, i));
+				}
+			}
+		}
+		
+		for (i, item) in items.iter().enumerate() {
+			let schema = match schema.prefix_items.get(i) {
+				Some(s) => s,
+				None => match &schema.items {
+					Some(s) => s,
+					None => continue
+				}
+			};
+			
+			path.push(i);
+			self.child(schema, item, path);
+		}
+	}
+	
+	fn map(&mut self, schema: &SchemaObject, map: &HashMap<String, Type>, path: &mut Path) {
+		for key in schema.required.iter().filter(|k| !map.contains_key(*k)) {
+			self.error(path, format!("missing required property `{}`", key));
+		}
+		
+		if let Some(min) = schema.min_properties.filter(|min| map.len() < *min) {
+			self.error(path, format!("object has fewer than {} properties", min));
+		}
+		
+		if let Some(max) = schema.max_properties.filter(|max| map.len() > *max) {
+			self.error(path, format!("object has more than {} properties", max));
+		}
+		
+		let mut keys = map.keys().collect::<Vec<_>>();
+		keys.sort_unstable();
+		
+		for key in keys {
+			path.push(key.as_str());
+			match (schema.properties.get(key), &schema.additional_properties) {
+				(Some(s), _) | (None, Some(s)) if *s != Schema::Bool(false) => self.child(s, &map[key], path),
+				(None, None) => {
+					path.0.pop();
+				}
+				_ => {
+					self.error(path, format!("property `{}` is not allowed", key));
+					path.0.pop();
+				}
+			}
+		}
+	}
+}
+
+/// Assigns every violation the position of its value, or of the closest ancestor if the value
+/// has no known position.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn locate(mut violations: Vec<Violation>, positions: &HashMap<Path, (usize, usize)>) -> Vec<Violation> {
+	for violation in &mut violations {
+		let mut path = violation.path.clone();
+		violation.position = loop {
+			if let Some(position) = positions.get(&path) {
+				break Some(*position);
+			} else if path.0.pop().is_none() {
+				break None;
+			}
+		};
+	}
+	
+	violations
+}
+
+#[cfg(feature = "json")]
+fn json_positions(src: &str) -> Result<HashMap<Path, (usize, usize)>, crate::utils::DeError> {
+	use crate::json::{Reader, Event};
+	
+	let mut reader = Reader::new(src.as_bytes());
+	let mut positions = HashMap::new();
+	let mut path = Path::default();
+	// the index of the next element for arrays, `None` for objects
+	let mut stack = Vec::<Option<usize>>::new();
+	
+	while let Some(event) = reader.next_event()? {
+		match event {
+			Event::Key(key) => path.push(key),
+			Event::EndObject | Event::EndArray => {
+				stack.pop();
+				if !stack.is_empty() {
+					path.0.pop();
+				}
+			}
+			event => {
+				if let Some(Some(i)) = stack.last_mut() {
+					path.push(*i);
+					*i += 1;
+				}
+				
+				positions.insert(path.clone(), reader.position());
+				match event {
+					Event::StartObject => stack.push(None),
+					Event::StartArray => stack.push(Some(0)),
+					_ if !stack.is_empty() => {
+						path.0.pop();
+					}
+					_ => ()
+				}
+			}
+		}
+	}
+	
+	Ok(positions)
+}
+
+#[cfg(feature = "toml")]
+fn toml_positions(src: &str, doc: &crate::toml::Document) -> HashMap<Path, (usize, usize)> {
+	use crate::toml::{Item, position};
+	
+	let mut positions = HashMap::new();
+	let mut table = Path::default();
+	// number of elements of every array of tables
+	let mut arrays = HashMap::<Vec<String>, usize>::new();
+	let mut offset = 0;
+	
+	for item in doc.items() {
+		match item {
+			Item::Trivia(raw) => offset += raw.len(),
+			Item::Header { raw, key, array } => {
+				if *array {
+					arrays.retain(|k, _| !(k.len() > key.len() && k.starts_with(key)));
+					*arrays.entry(key.clone()).or_default() += 1;
+				}
+				
+				table = Path::default();
+				for i in 0..key.len() {
+					table.push(key[i].as_str());
+					if let Some(n) = arrays.get(&key[..=i]) {
+						table.push(n - 1);
+					}
+				}
+				
+				positions.insert(table.clone(), position(src, offset + raw.len() - raw.trim_start().len()));
+				offset += raw.len();
+			}
+			Item::KeyValue(kv) => {
+				let mut path = table.clone();
+				for key in &kv.key {
+					path.push(key.as_str());
+				}
+				
+				positions.insert(path, position(src, offset + kv.raw_key.len()));
+				offset += kv.raw_key.len() + kv.raw_value.len() + kv.suffix.len();
+			}
+		}
+	}
+	
+	positions
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[cfg(feature = "json")]
+	const SCHEMA: &str = r##"{
+		"type": "object",
+		"required": ["name", "entities"],
+		"additionalProperties": false,
+		"properties": {
+			"name": {"type": "string", "minLength": 1},
+			"version": {"const": 2},
+			"gravity": {"type": "number", "exclusiveMaximum": 0},
+			"entities": {"type": "array", "items": {"$ref": "#/$defs/entity"}, "uniqueItems": true},
+			"tags": {"type": ["array", "null"], "maxItems": 2, "items": {"enum": ["a", "b"]}}
+		},
+		"$defs": {
+			"entity": {
+				"type": "object",
+				"required": ["id"],
+				"properties": {
+					"id": {"type": "integer", "minimum": 0},
+					"scale": {"prefixItems": [{"type": "number"}], "items": false},
+					"children": {"type": "array", "items": {"$ref": "#/$defs/entity"}},
+					"shape": {"oneOf": [{"const": "box"}, {"type": "string", "maxLength": 3}]}
+				}
+			}
+		}
+	}"##;
+	
+	fn messages(violations: &[Violation]) -> Vec<String> {
+		violations.iter().map(Violation::to_string).collect()
+	}
+	
+	#[cfg(feature = "json")]
+	#[test]
+	fn json() {
+		let schema = Schema::from_json(SCHEMA).unwrap();
+		let valid = r#"{"name": "level", "version": 2, "entities": [{"id": 1, "children": [{"id": 2.0}]}], "tags": null}"#;
+		assert_eq!(schema.validate_json(valid).unwrap(), []);
+		
+		let src = r#"{
+	"name": "",
+	"gravity": 9.81,
+	"entities": [
+		{"id": -1, "scale": [1.0, 2.0]},
+		{"children": [{"id": "x", "shape": "box"}]},
+		{"id": -1, "scale": [1.0, 2.0]}
+	],
+	"tags": ["a", "c", "a"],
+	"extra": {}
+}"#;
+		assert_eq!(messages(&schema.validate_json(src).unwrap()), [
+			"4:14: `entities`: items 0 and 2 are equal",
+			"5:10: `entities[0].id`: -1 is less than the minimum of 0",
+			"5:29: `entities[0].scale[1]`: no value is allowed here",
+			"6:3: `entities[1]`: missing required property `id`",
+			"6:24: `entities[1].children[0].id`: expected integer, found string",
+			"6:38: `entities[1].children[0].shape`: value matches 2 schemas of `oneOf`",
+			"7:10: `entities[2].id`: -1 is less than the minimum of 0",
+			"7:29: `entities[2].scale[1]`: no value is allowed here",
+			"10:11: `extra`: property `extra` is not allowed",
+			"3:13: `gravity`: 9.81 is not less than 0",
+			"2:10: `name`: string is shorter than 1 characters",
+			"9:10: `tags`: array has more than 2 items",
+			"9:16: `tags[1]`: value is not one of the allowed values"
+		]);
+		
+		assert!(schema.validate_json("{").is_err());
+		assert!(Schema::from_json(r#"{"type": "text"}"#).is_err());
+	}
+	
+	#[cfg(all(feature = "json", feature = "toml"))]
+	#[test]
+	fn toml() {
+		let schema = Schema::from_json(r#"{
+			"properties": {
+				"window": {"properties": {"width": {"type": "integer", "multipleOf": 8}}},
+				"plugin": {"items": {"required": ["path"], "properties": {
+					"path": {"type": "string"},
+					"options": {"additionalProperties": {"type": "boolean"}}
+				}}}
+			}
+		}"#).unwrap();
+		
+		let src = "\
+title = \"x\"
+
+[window]
+width = 801
+
+[[plugin]]
+path = \"a.so\"
+
+[[plugin]]
+name = \"b\"
+[plugin.options]
+verbose = 1
+inline = { fast = true, safe = \"no\" }
+";
+		assert_eq!(messages(&schema.validate_toml(src).unwrap()), [
+			"9:1: `plugin[1]`: missing required property `path`",
+			"13:10: `plugin[1].options.inline`: expected boolean, found object",
+			"12:11: `plugin[1].options.verbose`: expected boolean, found integer",
+			"4:9: `window.width`: 801 is not a multiple of 8"
+		]);
+	}
+	
+	#[test]
+	fn rust_types() {
+		let node = Schema::from(SchemaObject {
+			ty:         vec![Kind::Object],
+			properties: [
+				("value".to_string(), SchemaObject { any_of: vec![
+					SchemaObject::of(Kind::Integer).into(),
+					SchemaObject { ty: vec![Kind::String], max_length: Some(2), ..SchemaObject::default() }.into()
+				], ..SchemaObject::default() }.into()),
+				("next".to_string(), SchemaObject { reference: Some("#".to_string()), ..SchemaObject::default() }.into()),
+				("flags".to_string(), SchemaObject {
+					not: Some(SchemaObject::of(Kind::Null).into()),
+					..SchemaObject::default()
+				}.into())
+			].into_iter().collect(),
+			..SchemaObject::default()
+		});
+		
+		let value = |v: Type, next: Option<Type>| Type::Map([("value".to_string(), v)].into_iter()
+			.chain(next.map(|n| ("next".to_string(), n)))
+			.collect());
+		let list = value(Type::UInt(1), Some(value(Type::Char('a'), Some(value(Type::String("abc".to_string()), None)))));
+		
+		assert_eq!(node.validate(&value(Type::SInt(-1), None)), []);
+		assert_eq!(messages(&node.validate(&list)), ["`next.next.value`: value does not match any schema of `anyOf`"]);
+		assert_eq!(messages(&node.validate(&Type::Map([("flags".to_string(), Type::Unit)].into_iter().collect()))),
+			["`flags`: value matches the schema of `not`"]);
+		assert_eq!(messages(&node.validate(&Type::Seq(vec![]))), ["expected object, found array"]);
+		
+		let cycle = Schema::from(SchemaObject { reference: Some("#".to_string()), ..SchemaObject::default() });
+		assert_eq!(messages(&cycle.validate(&Type::Unit)), ["too many nested references at `#`"]);
+		assert!(Schema::Bool(true).validate(&Type::Unit).is_empty());
+	}
+}
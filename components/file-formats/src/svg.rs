@@ -20,16 +20,277 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use serde::*;
+//! SVG documents and the attribute value syntaxes of SVG: lengths, transforms, colors, paints and
+//! path data.
+//! 
+//! All attributes are stored as raw strings, the types in this module can be parsed from them with
+//! `str::parse`, e.g. `rect.width.parse::<Length>()`. Path data is parsed with `PathData`, which
+//! converts every command to absolute `PathCommand`s with arcs approximated by cubic curves.
+//! Elements that are not supported are read as `Element::Unknown` and skipped.
 
+use {
+	crate::{utils::*, xml},
+	std::{io, fmt, ops::Mul, str::FromStr},
+	serde::*
+};
+
+pub const NAMESPACE: &str = "http://www.w3.org/2000/svg";
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// The names of all properties that can be specified as presentation attributes, as used by
+/// `Element::presentation`.
+pub const PRESENTATION_ATTRIBUTES: [&str; 14] = [
+	"color", "display", "visibility", "opacity", "fill", "fill-opacity", "fill-rule", "stroke",
+	"stroke-width", "stroke-opacity", "stroke-linecap", "stroke-linejoin", "stroke-miterlimit",
+	"font-size"
+];
+
+/// Reads an SVG document, names in the SVG and XLink namespaces are resolved to their local names.
+pub fn deserialize<R: io::BufRead + fmt::Debug>(reader: R) -> Result<Svg, DeError> {
+	xml::Deserializer::new(reader)
+		.with_namespace(NAMESPACE, "")
+		.with_namespace(XLINK_NAMESPACE, "")
+		.deserialize_document()
+}
+
+pub fn serialize<W: io::Write>(writer: W, svg: &Svg) -> Result<(), SerError> {
+	svg.serialize(&mut xml::Serializer::new(writer).with_namespace(NAMESPACE, ""))
+}
+
+/// Returns the declarations of a `style` attribute as property-value pairs.
+pub fn style(s: &str) -> impl Iterator<Item = (&str, &str)> {
+	s.split(';').filter_map(|declaration| {
+		let (property, value) = declaration.split_once(':')?;
+		let value = value.trim();
+		let value = value.strip_suffix("!important").map_or(value, str::trim_end);
+		Some((property.trim(), value))
+	})
+}
+
+/// Parses a list of numbers separated by whitespace and/or commas, like in `points` or `viewBox`.
+pub fn numbers(s: &str) -> Result<Vec<f32>, DeError> {
+	let mut scanner = Scanner::new(s);
+	let mut numbers = Vec::new();
+	scanner.skip_whitespace();
+	
+	while !scanner.is_empty() {
+		numbers.push(scanner.number()?);
+		scanner.skip_separator();
+	}
+	
+	Ok(numbers)
+}
+
+macro_rules! elements {
+	($(
+		$(#[$meta:meta])*
+		pub struct $name:ident { $($(#[$field_meta:meta])* pub $field:ident: $ty:ty,)* }
+	)*) => {$(
+		$(#[$meta])*
+		pub struct $name {
+			$($(#[$field_meta])* pub $field: $ty,)*
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub id:                String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub class:             String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub style:             String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub transform:         String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub color:             String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub display:           String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub visibility:        String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub opacity:           String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub fill:              String,
+			#[serde(rename = "fill-opacity", skip_serializing_if = "String::is_empty")]
+			pub fill_opacity:      String,
+			#[serde(rename = "fill-rule", skip_serializing_if = "String::is_empty")]
+			pub fill_rule:         String,
+			#[serde(skip_serializing_if = "String::is_empty")]
+			pub stroke:            String,
+			#[serde(rename = "stroke-width", skip_serializing_if = "String::is_empty")]
+			pub stroke_width:      String,
+			#[serde(rename = "stroke-opacity", skip_serializing_if = "String::is_empty")]
+			pub stroke_opacity:    String,
+			#[serde(rename = "stroke-linecap", skip_serializing_if = "String::is_empty")]
+			pub stroke_linecap:    String,
+			#[serde(rename = "stroke-linejoin", skip_serializing_if = "String::is_empty")]
+			pub stroke_linejoin:   String,
+			#[serde(rename = "stroke-miterlimit", skip_serializing_if = "String::is_empty")]
+			pub stroke_miterlimit: String,
+			#[serde(rename = "font-size", skip_serializing_if = "String::is_empty")]
+			pub font_size:         String,
+		}
+		
+		impl $name {
+			/// Returns the specified value of a property, declarations in the `style` attribute
+			/// take precedence over presentation attributes. Empty values are treated as absent.
+			pub fn presentation(&self, property: &str) -> Option<&str> {
+				if let Some((_, value)) = style(&self.style).filter(|(name, _)| *name == property).last() {
+					return Some(value);
+				}
+				
+				let value = match property {
+					"color"             => &self.color,
+					"display"           => &self.display,
+					"visibility"        => &self.visibility,
+					"opacity"           => &self.opacity,
+					"fill"              => &self.fill,
+					"fill-opacity"      => &self.fill_opacity,
+					"fill-rule"         => &self.fill_rule,
+					"stroke"            => &self.stroke,
+					"stroke-width"      => &self.stroke_width,
+					"stroke-opacity"    => &self.stroke_opacity,
+					"stroke-linecap"    => &self.stroke_linecap,
+					"stroke-linejoin"   => &self.stroke_linejoin,
+					"stroke-miterlimit" => &self.stroke_miterlimit,
+					"font-size"         => &self.font_size,
+					_                   => return None
+				};
+				
+				Some(value.trim()).filter(|value| !value.is_empty())
+			}
+		}
+	)*};
+}
+
+elements! {
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename = "svg", default)]
+	pub struct Svg {
+		pub x:                     String,
+		pub y:                     String,
+		pub width:                 String,
+		pub height:                String,
+		#[serde(rename = "viewBox", skip_serializing_if = "String::is_empty")]
+		pub view_box:              String,
+		#[serde(rename = "preserveAspectRatio", skip_serializing_if = "String::is_empty")]
+		pub preserve_aspect_ratio: String,
+		#[serde(rename = "$body")]
+		pub elements:              Vec<Element>,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(default)]
+	pub struct G {
+		#[serde(rename = "$body")]
+		pub elements: Vec<Element>,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(default)]
+	pub struct Rect {
+		pub x:      String,
+		pub y:      String,
+		pub width:  String,
+		pub height: String,
+		pub rx:     String,
+		pub ry:     String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(default)]
+	pub struct Circle {
+		pub cx:     String,
+		pub cy:     String,
+		pub r:      String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(default)]
+	pub struct Ellipse {
+		pub cx:     String,
+		pub cy:     String,
+		pub rx:     String,
+		pub ry:     String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Line {
+		pub x1:          String,
+		pub y1:          String,
+		pub x2:          String,
+		pub y2:          String,
+		pub path_length: String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Polyline {
+		pub path_length: String,
+		pub points:      String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Polygon {
+		pub path_length: String,
+		pub points:      String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Path {
+		pub d:           String,
+		pub path_length: String,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Text {
+		pub length_adjust: String,
+		pub x:             String,
+		pub y:             String,
+		pub dx:            String,
+		pub dy:            String,
+		pub rotate:        String,
+		pub text_length:   String,
+		#[serde(rename = "$body")]
+		pub body:          Vec<TextContent>,
+	}
+	
+	#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase", default)]
+	pub struct Image {
+		pub x:                     String,
+		pub y:                     String,
+		pub width:                 String,
+		pub height:                String,
+		pub preserve_aspect_ratio: String,
+		pub href:                  String,
+	}
+}
+
+/// The content of a `text` or `tspan` element.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Svg {
-	pub x:        String,
-	pub y:        String,
-	pub width:    String,
-	pub height:   String,
-	#[serde(rename = "$body")]
-	pub elements: Vec<Element>
+#[serde(rename_all = "lowercase")]
+pub enum TextContent {
+	#[serde(rename = "$value")]
+	Text(String),
+	TSpan(Box<Text>),
+	#[serde(other, skip_serializing)]
+	Unknown
+}
+
+impl Text {
+	/// Returns the character data of the element and its descendants.
+	pub fn text(&self) -> String {
+		let mut text = String::new();
+		for content in &self.body {
+			match content {
+				TextContent::Text(s)  => text.push_str(s),
+				TextContent::TSpan(e) => text.push_str(&e.text()),
+				TextContent::Unknown  => ()
+			}
+		}
+		text
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,92 +306,892 @@ pub enum Element {
 	Polyline(Polyline),
 	Rect(Rect),
 	Text(Text),
-	TextPath
+	#[serde(rename = "textPath")]
+	TextPath,
+	/// Any element that is not supported, e.g. `defs`, `title` or `linearGradient`.
+	#[serde(other, skip_serializing)]
+	Unknown
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct G {
-	#[serde(rename = "$body")]
-	pub elements: Vec<Element>
+impl Element {
+	/// Returns the specified value of a property, see `Svg::presentation`.
+	pub fn presentation(&self, property: &str) -> Option<&str> {
+		match self {
+			Self::G(e)        => e.presentation(property),
+			Self::Circle(e)   => e.presentation(property),
+			Self::Ellipse(e)  => e.presentation(property),
+			Self::Image(e)    => e.presentation(property),
+			Self::Line(e)     => e.presentation(property),
+			Self::Path(e)     => e.presentation(property),
+			Self::Polygon(e)  => e.presentation(property),
+			Self::Polyline(e) => e.presentation(property),
+			Self::Rect(e)     => e.presentation(property),
+			Self::Text(e)     => e.presentation(property),
+			Self::TextPath | Self::Unknown => None
+		}
+	}
+	
+	/// Returns the value of the `transform` attribute.
+	pub fn transform(&self) -> &str {
+		match self {
+			Self::G(e)        => &e.transform,
+			Self::Circle(e)   => &e.transform,
+			Self::Ellipse(e)  => &e.transform,
+			Self::Image(e)    => &e.transform,
+			Self::Line(e)     => &e.transform,
+			Self::Path(e)     => &e.transform,
+			Self::Polygon(e)  => &e.transform,
+			Self::Polyline(e) => &e.transform,
+			Self::Rect(e)     => &e.transform,
+			Self::Text(e)     => &e.transform,
+			Self::TextPath | Self::Unknown => ""
+		}
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Rect {
-	pub x:      String,
-	pub y:      String,
-	pub width:  String,
-	pub height: String,
-	pub rx:     String,
-	pub ry:     String
+/// A length or coordinate, user units are represented by `Unit::None`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Length {
+	pub value: f32,
+	pub unit:  Unit
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Circle {
-	pub cx:     String,
-	pub cy:     String,
-	pub r:      String
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Unit {
+	None,
+	Px,
+	In,
+	Cm,
+	Mm,
+	Pt,
+	Pc,
+	Em,
+	Ex,
+	Percent
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Ellipse {
-	pub cx:     String,
-	pub cy:     String,
-	pub rx:     String,
-	pub ry:     String
+impl Default for Unit {
+	fn default() -> Self {
+		Self::None
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Line {
-	pub x1:          String,
-	pub y1:          String,
-	pub x2:          String,
-	pub y2:          String,
-	pub path_length: String
+impl Length {
+	pub const fn new(value: f32, unit: Unit) -> Self {
+		Self { value, unit }
+	}
+	
+	/// Converts the length to user units. `font_size` is used for `em` and `ex` units and
+	/// `reference` is the length percentages refer to, e.g. the width of the viewport.
+	pub fn to_user(self, font_size: f32, reference: f32) -> f32 {
+		self.value * match self.unit {
+			Unit::None | Unit::Px => 1.0,
+			Unit::In              => 96.0,
+			Unit::Cm              => 96.0 / 2.54,
+			Unit::Mm              => 96.0 / 25.4,
+			Unit::Pt              => 96.0 / 72.0,
+			Unit::Pc              => 16.0,
+			Unit::Em              => font_size,
+			Unit::Ex              => font_size * 0.5,
+			Unit::Percent         => reference / 100.0
+		}
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Polyline {
-	pub path_length: String,
-	pub points:      String
+impl FromStr for Length {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut scanner = Scanner::new(s.trim());
+		let value = scanner.number()?;
+		let unit = match scanner.rest() {
+			""   => Unit::None,
+			"px" => Unit::Px,
+			"in" => Unit::In,
+			"cm" => Unit::Cm,
+			"mm" => Unit::Mm,
+			"pt" => Unit::Pt,
+			"pc" => Unit::Pc,
+			"em" => Unit::Em,
+			"ex" => Unit::Ex,
+			"%"  => Unit::Percent,
+			unit => return Err(DeError::invalid_token(unit, "a length unit"))
+		};
+		Ok(Self { value, unit })
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Polygon {
-	pub path_length: String,
-	pub points:      String
+impl fmt::Display for Length {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", self.value, match self.unit {
+			Unit::None    => "",
+			Unit::Px      => "px",
+			Unit::In      => "in",
+			Unit::Cm      => "cm",
+			Unit::Mm      => "mm",
+			Unit::Pt      => "pt",
+			Unit::Pc      => "pc",
+			Unit::Em      => "em",
+			Unit::Ex      => "ex",
+			Unit::Percent => "%"
+		})
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Path {
-	pub d:           String,
-	pub path_length: String
+/// An affine transform `[a, b, c, d, e, f]`, which maps `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform(pub [f32; 6]);
+
+impl Transform {
+	pub const IDENTITY: Self = Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+	
+	pub const fn translate(x: f32, y: f32) -> Self {
+		Self([1.0, 0.0, 0.0, 1.0, x, y])
+	}
+	
+	pub const fn scale(x: f32, y: f32) -> Self {
+		Self([x, 0.0, 0.0, y, 0.0, 0.0])
+	}
+	
+	/// Creates a rotation, the angle is in degrees.
+	pub fn rotate(angle: f32) -> Self {
+		let (sin, cos) = angle.to_radians().sin_cos();
+		Self([cos, sin, -sin, cos, 0.0, 0.0])
+	}
+	
+	/// Creates a skew along the x axis, the angle is in degrees.
+	pub fn skew_x(angle: f32) -> Self {
+		Self([1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0])
+	}
+	
+	/// Creates a skew along the y axis, the angle is in degrees.
+	pub fn skew_y(angle: f32) -> Self {
+		Self([1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0])
+	}
+	
+	pub fn apply(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+		let [a, b, c, d, e, f] = self.0;
+		[a * x + c * y + e, b * x + d * y + f]
+	}
+	
+	/// Returns the factor by which areas are scaled, square rooted. This is used to scale
+	/// lengths that do not have a direction, like stroke widths.
+	pub fn scale_factor(&self) -> f32 {
+		let [a, b, c, d, ..] = self.0;
+		(a * d - b * c).abs().sqrt()
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Text {
-	pub length_adjust: String,
-	pub x:             String,
-	pub y:             String,
-	pub dx:            String,
-	pub dy:            String,
-	pub rotate:        String,
-	pub text_length:   String,
-	#[serde(rename = "$body")]
-	pub body:          String
+impl Default for Transform {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Image {
-	pub x:                     String,
-	pub y:                     String,
-	pub width:                 String,
-	pub height:                String,
-	pub preserve_aspect_ratio: String,
-	pub href:                  String,
-}
\ No newline at end of file
+impl Mul for Transform {
+	type Output = Self;
+	
+	/// Concatenates two transforms, `rhs` is applied first.
+	fn mul(self, rhs: Self) -> Self {
+		let [a0, b0, c0, d0, e0, f0] = self.0;
+		let [a1, b1, c1, d1, e1, f1] = rhs.0;
+		Self([
+			a0 * a1 + c0 * b1,
+			b0 * a1 + d0 * b1,
+			a0 * c1 + c0 * d1,
+			b0 * c1 + d0 * d1,
+			a0 * e1 + c0 * f1 + e0,
+			b0 * e1 + d0 * f1 + f0
+		])
+	}
+}
+
+impl FromStr for Transform {
+	type Err = DeError;
+	
+	/// Parses a transform list, e.g. `translate(10 20) rotate(45)`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut scanner = Scanner::new(s);
+		let mut transform = Self::IDENTITY;
+		scanner.skip_separator();
+		
+		while !scanner.is_empty() {
+			let name = scanner.identifier();
+			scanner.skip_whitespace();
+			scanner.expect(b'(')?;
+			scanner.skip_whitespace();
+			
+			let mut args = Vec::with_capacity(6);
+			while scanner.peek() != Some(b')') {
+				args.push(scanner.number()?);
+				scanner.skip_separator();
+			}
+			scanner.expect(b')')?;
+			
+			transform = transform * match (name, args.as_slice()) {
+				("matrix",    &[a, b, c, d, e, f]) => Self([a, b, c, d, e, f]),
+				("translate", &[x])                => Self::translate(x, 0.0),
+				("translate", &[x, y])             => Self::translate(x, y),
+				("scale",     &[x])                => Self::scale(x, x),
+				("scale",     &[x, y])             => Self::scale(x, y),
+				("rotate",    &[angle])            => Self::rotate(angle),
+				("rotate",    &[angle, x, y])      => Self::translate(x, y) * Self::rotate(angle) * Self::translate(-x, -y),
+				("skewX",     &[angle])            => Self::skew_x(angle),
+				("skewY",     &[angle])            => Self::skew_y(angle),
+				_ => return Err(DeError::invalid_token(
+					format!("{}({} arguments)", name, args.len()), "a transform function"))
+			};
+			
+			scanner.skip_separator();
+		}
+		
+		Ok(transform)
+	}
+}
+
+/// A color with straight alpha, all components are in the range `0..=1`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Color(pub [f32; 4]);
+
+impl Color {
+	pub const BLACK:       Self = Self([0.0, 0.0, 0.0, 1.0]);
+	pub const TRANSPARENT: Self = Self([0.0, 0.0, 0.0, 0.0]);
+	
+	pub fn from_rgb(rgb: u32) -> Self {
+		Self([
+			(rgb >> 16 & 0xFF) as f32 / 255.0,
+			(rgb >> 8 & 0xFF) as f32 / 255.0,
+			(rgb & 0xFF) as f32 / 255.0,
+			1.0
+		])
+	}
+	
+	/// Returns the color with its alpha multiplied by `opacity`.
+	pub fn with_opacity(self, opacity: f32) -> Self {
+		let [r, g, b, a] = self.0;
+		Self([r, g, b, a * opacity.clamp(0.0, 1.0)])
+	}
+}
+
+impl Default for Color {
+	fn default() -> Self {
+		Self::BLACK
+	}
+}
+
+impl FromStr for Color {
+	type Err = DeError;
+	
+	/// Parses a hex color (`#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`), an `rgb()` or `rgba()`
+	/// function or a color keyword.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		let invalid = || DeError::invalid_token(s, "a color");
+		
+		if let Some(hex) = s.strip_prefix('#') {
+			let v = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+			let nibble = |i: u32| (v >> (i * 4) & 0xF) as f32 / 15.0;
+			let byte = |i: u32| (v >> (i * 8) & 0xFF) as f32 / 255.0;
+			return Ok(Self(match hex.len() {
+				3 => [nibble(2), nibble(1), nibble(0), 1.0],
+				4 => [nibble(3), nibble(2), nibble(1), nibble(0)],
+				6 => [byte(2), byte(1), byte(0), 1.0],
+				8 => [byte(3), byte(2), byte(1), byte(0)],
+				_ => return Err(invalid())
+			}));
+		}
+		
+		if let Some(args) = s.strip_prefix("rgba(").or_else(|| s.strip_prefix("rgb(")) {
+			let mut scanner = Scanner::new(args.strip_suffix(')').ok_or_else(invalid)?);
+			let mut color = [1f32; 4];
+			scanner.skip_whitespace();
+			
+			for (i, component) in color.iter_mut().enumerate() {
+				if scanner.is_empty() && i == 3 {
+					break;
+				}
+				
+				let v = scanner.number()?;
+				*component = match (scanner.peek() == Some(b'%'), i) {
+					(true, _) => { scanner.bump(); v / 100.0 },
+					(false, 3) => v,
+					(false, _) => v / 255.0
+				}.clamp(0.0, 1.0);
+				
+				// both the legacy comma and the modern space and slash syntax are accepted
+				scanner.skip_whitespace();
+				if let Some(b',' | b'/') = scanner.peek() {
+					scanner.bump();
+					scanner.skip_whitespace();
+				}
+			}
+			
+			return match scanner.is_empty() {
+				true  => Ok(Self(color)),
+				false => Err(invalid())
+			};
+		}
+		
+		if s.eq_ignore_ascii_case("transparent") {
+			return Ok(Self::TRANSPARENT);
+		}
+		
+		let name = s.to_ascii_lowercase();
+		NAMED_COLORS.binary_search_by_key(&name.as_str(), |(name, _)| name)
+			.map(|i| Self::from_rgb(NAMED_COLORS[i].1))
+			.map_err(|_| invalid())
+	}
+}
+
+/// The value of a `fill` or `stroke` property.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+	None,
+	CurrentColor,
+	Color(Color),
+	/// A reference to a paint server like a gradient, with the paint that is used if the
+	/// reference is invalid or not supported.
+	Url(String, Box<Paint>)
+}
+
+impl Default for Paint {
+	fn default() -> Self {
+		Self::None
+	}
+}
+
+impl FromStr for Paint {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		
+		if let Some(rest) = s.strip_prefix("url(") {
+			let (url, fallback) = rest.split_once(')')
+				.ok_or_else(|| DeError::invalid_token(s, "a paint"))?;
+			let url = url.trim().trim_matches(|c| c == '"' || c == '\'');
+			let fallback = match fallback.trim() {
+				"" => Self::None,
+				fallback => fallback.parse()?
+			};
+			return Ok(Self::Url(url.to_string(), Box::new(fallback)));
+		}
+		
+		match s {
+			"none"         => Ok(Self::None),
+			"currentColor" => Ok(Self::CurrentColor),
+			s              => s.parse().map(Self::Color)
+		}
+	}
+}
+
+/// A path segment with absolute coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathCommand {
+	Move([f32; 2]),
+	Line([f32; 2]),
+	/// A quadratic curve with a control point and an end point.
+	Quad([f32; 2], [f32; 2]),
+	/// A cubic curve with two control points and an end point.
+	Cubic([f32; 2], [f32; 2], [f32; 2]),
+	/// Closes the current subpath, the current point is moved to the start of the subpath.
+	Close
+}
+
+/// An iterator over the commands in path data, like the `d` attribute of a `path`.
+/// 
+/// Relative, horizontal, vertical and smooth commands are converted to their absolute forms
+/// and elliptical arcs are approximated by one cubic curve per quarter ellipse. After an error
+/// no further commands are returned, the commands up to the error should still be rendered.
+#[derive(Clone, Debug)]
+pub struct PathData<'a> {
+	scanner: Scanner<'a>,
+	command: Option<u8>,
+	current: [f32; 2],
+	start:   [f32; 2],
+	/// The last control point of the previous command, if it was a curve of the same order
+	control: Option<(u8, [f32; 2])>,
+	pending: std::collections::VecDeque<PathCommand>,
+	failed:  bool
+}
+
+impl<'a> PathData<'a> {
+	pub fn new(s: &'a str) -> Self {
+		let mut scanner = Scanner::new(s);
+		scanner.skip_whitespace();
+		Self {
+			scanner,
+			command: None,
+			current: [0.0; 2],
+			start:   [0.0; 2],
+			control: None,
+			pending: std::collections::VecDeque::new(),
+			failed:  false
+		}
+	}
+	
+	fn point(&mut self, relative: bool) -> Result<[f32; 2], DeError> {
+		let x = self.scanner.number()?;
+		self.scanner.skip_separator();
+		let y = self.scanner.number()?;
+		self.scanner.skip_separator();
+		Ok(match relative {
+			true  => [self.current[0] + x, self.current[1] + y],
+			false => [x, y]
+		})
+	}
+	
+	fn coordinate(&mut self, relative: bool, axis: usize) -> Result<f32, DeError> {
+		let v = self.scanner.number()?;
+		self.scanner.skip_separator();
+		Ok(if relative { self.current[axis] + v } else { v })
+	}
+	
+	fn reflect(&self, order: u8) -> [f32; 2] {
+		match self.control {
+			Some((o, [x, y])) if o == order => [2.0 * self.current[0] - x, 2.0 * self.current[1] - y],
+			_ => self.current
+		}
+	}
+	
+	fn parse(&mut self) -> Result<Option<PathCommand>, DeError> {
+		let command = match self.scanner.peek() {
+			None => return Ok(None),
+			Some(c) if c.is_ascii_alphabetic() => {
+				self.scanner.bump();
+				self.scanner.skip_whitespace();
+				c
+			}
+			// implicit repetition of the previous command, a move is followed by lines
+			Some(_) => match self.command {
+				Some(b'M') => b'L',
+				Some(b'm') => b'l',
+				Some(c) if !c.eq_ignore_ascii_case(&b'z') => c,
+				_ => return Err(self.scanner.unexpected("a path command"))
+			}
+		};
+		
+		if self.command.is_none() && !command.eq_ignore_ascii_case(&b'm') {
+			return Err(DeError::invalid_token(command as char, "a move command"));
+		}
+		
+		let relative = command.is_ascii_lowercase();
+		let mut control = None;
+		self.command = Some(command);
+		
+		let segment = match command.to_ascii_uppercase() {
+			b'M' => {
+				let p = self.point(relative)?;
+				self.start = p;
+				PathCommand::Move(p)
+			}
+			b'L' => PathCommand::Line(self.point(relative)?),
+			b'H' => PathCommand::Line([self.coordinate(relative, 0)?, self.current[1]]),
+			b'V' => PathCommand::Line([self.current[0], self.coordinate(relative, 1)?]),
+			b'C' => {
+				let (p1, p2, p) = (self.point(relative)?, self.point(relative)?, self.point(relative)?);
+				control = Some((3, p2));
+				PathCommand::Cubic(p1, p2, p)
+			}
+			b'S' => {
+				let p1 = self.reflect(3);
+				let (p2, p) = (self.point(relative)?, self.point(relative)?);
+				control = Some((3, p2));
+				PathCommand::Cubic(p1, p2, p)
+			}
+			b'Q' => {
+				let (p1, p) = (self.point(relative)?, self.point(relative)?);
+				control = Some((2, p1));
+				PathCommand::Quad(p1, p)
+			}
+			b'T' => {
+				let p1 = self.reflect(2);
+				let p = self.point(relative)?;
+				control = Some((2, p1));
+				PathCommand::Quad(p1, p)
+			}
+			b'A' => {
+				let rx = self.scanner.number()?;
+				self.scanner.skip_separator();
+				let ry = self.scanner.number()?;
+				self.scanner.skip_separator();
+				let rotation = self.scanner.number()?;
+				self.scanner.skip_separator();
+				let large_arc = self.scanner.flag()?;
+				self.scanner.skip_separator();
+				let sweep = self.scanner.flag()?;
+				self.scanner.skip_separator();
+				let p = self.point(relative)?;
+				
+				arc(self.current, [rx, ry], rotation, large_arc, sweep, p, &mut self.pending);
+				self.control = None;
+				self.current = p;
+				return Ok(self.pending.pop_front());
+			}
+			b'Z' => {
+				self.scanner.skip_separator();
+				self.current = self.start;
+				self.control = None;
+				return Ok(Some(PathCommand::Close));
+			}
+			_ => return Err(DeError::invalid_token(command as char, "a path command"))
+		};
+		
+		self.control = control;
+		self.current = match segment {
+			PathCommand::Move(p) | PathCommand::Line(p) | PathCommand::Quad(_, p) | PathCommand::Cubic(_, _, p) => p,
+			PathCommand::Close => self.start
+		};
+		Ok(Some(segment))
+	}
+}
+
+impl Iterator for PathData<'_> {
+	type Item = Result<PathCommand, DeError>;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(command) = self.pending.pop_front() {
+			return Some(Ok(command));
+		}
+		
+		if self.failed {
+			return None;
+		}
+		
+		let result = self.parse();
+		self.failed = result.is_err();
+		result.transpose()
+	}
+}
+
+/// Approximates an elliptical arc by cubic curves, as described in the implementation notes of
+/// the SVG specification. Degenerate arcs are replaced by a line.
+fn arc(
+	from:      [f32; 2],
+	radii:     [f32; 2],
+	rotation:  f32,
+	large_arc: bool,
+	sweep:     bool,
+	to:        [f32; 2],
+	out:       &mut std::collections::VecDeque<PathCommand>
+) {
+	use std::f64::consts::{PI, FRAC_PI_2};
+	
+	let (mut rx, mut ry) = (radii[0].abs() as f64, radii[1].abs() as f64);
+	if from == to || rx == 0.0 || ry == 0.0 {
+		out.push_back(PathCommand::Line(to));
+		return;
+	}
+	
+	let (x1, y1, x2, y2) = (from[0] as f64, from[1] as f64, to[0] as f64, to[1] as f64);
+	let (sin, cos) = (rotation as f64).to_radians().sin_cos();
+	let (dx, dy) = ((x1 - x2) / 2.0, (y1 - y2) / 2.0);
+	let (x1p, y1p) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+	
+	// scale up radii that are too small to reach the end point
+	let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+	if lambda > 1.0 {
+		rx *= lambda.sqrt();
+		ry *= lambda.sqrt();
+	}
+	
+	let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+	let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+	let coef = (num / den).max(0.0).sqrt() * if large_arc == sweep { -1.0 } else { 1.0 };
+	let (cxp, cyp) = (coef * rx * y1p / ry, -coef * ry * x1p / rx);
+	let (cx, cy) = (cos * cxp - sin * cyp + (x1 + x2) / 2.0, sin * cxp + cos * cyp + (y1 + y2) / 2.0);
+	
+	let angle = |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+	let theta = angle(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+	let mut delta = angle((x1p - cxp) / rx, (y1p - cyp) / ry, (-x1p - cxp) / rx, (-y1p - cyp) / ry);
+	
+	if !sweep && delta > 0.0 {
+		delta -= 2.0 * PI;
+	} else if sweep && delta < 0.0 {
+		delta += 2.0 * PI;
+	}
+	
+	let segments = (delta.abs() / FRAC_PI_2 - 1e-6).ceil().max(1.0) as usize;
+	let step = delta / segments as f64;
+	let k = 4.0 / 3.0 * (step / 4.0).tan();
+	let map = |x: f64, y: f64| [
+		(cx + rx * x * cos - ry * y * sin) as f32,
+		(cy + rx * x * sin + ry * y * cos) as f32
+	];
+	
+	for i in 0..segments {
+		let (t0, t1) = (theta + step * i as f64, theta + step * (i + 1) as f64);
+		let ((s0, c0), (s1, c1)) = (t0.sin_cos(), t1.sin_cos());
+		let end = if i + 1 == segments { to } else { map(c1, s1) };
+		out.push_back(PathCommand::Cubic(map(c0 - k * s0, s0 + k * c0), map(c1 + k * s1, s1 - k * c1), end));
+	}
+}
+
+/// A cursor over the characters of an attribute value.
+#[derive(Clone, Debug)]
+struct Scanner<'a> {
+	src: &'a str,
+	pos: usize
+}
+
+impl<'a> Scanner<'a> {
+	fn new(src: &'a str) -> Self {
+		Self { src, pos: 0 }
+	}
+	
+	fn peek(&self) -> Option<u8> {
+		self.src.as_bytes().get(self.pos).copied()
+	}
+	
+	fn bump(&mut self) {
+		self.pos += 1;
+	}
+	
+	fn rest(&self) -> &'a str {
+		&self.src[self.pos..]
+	}
+	
+	fn is_empty(&self) -> bool {
+		self.pos >= self.src.len()
+	}
+	
+	fn unexpected(&self, expected: &str) -> DeError {
+		match self.rest().chars().next() {
+			Some(c) => DeError::invalid_token(format!("{:?} at offset {}", c, self.pos), expected),
+			None    => DeError::invalid_token("end of value", expected)
+		}
+	}
+	
+	fn expect(&mut self, c: u8) -> Result<(), DeError> {
+		match self.peek() == Some(c) {
+			true  => { self.bump(); Ok(()) },
+			false => Err(self.unexpected(&format!("{:?}", c as char)))
+		}
+	}
+	
+	fn skip_whitespace(&mut self) {
+		while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+			self.bump();
+		}
+	}
+	
+	/// Skips whitespace with at most one comma.
+	fn skip_separator(&mut self) {
+		self.skip_whitespace();
+		if self.peek() == Some(b',') {
+			self.bump();
+			self.skip_whitespace();
+		}
+	}
+	
+	fn identifier(&mut self) -> &'a str {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+			self.bump();
+		}
+		&self.src[start..self.pos]
+	}
+	
+	fn digits(&mut self) -> usize {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+			self.bump();
+		}
+		self.pos - start
+	}
+	
+	/// Reads a number, which may be immediately followed by the next one, e.g. `1.5.5` or `1-2`.
+	fn number(&mut self) -> Result<f32, DeError> {
+		let start = self.pos;
+		
+		if let Some(b'+' | b'-') = self.peek() {
+			self.bump();
+		}
+		
+		let mut digits = self.digits();
+		if self.peek() == Some(b'.') {
+			self.bump();
+			digits += self.digits();
+		}
+		
+		if digits == 0 {
+			self.pos = start;
+			return Err(self.unexpected("a number"));
+		}
+		
+		// only consume the exponent if it is complete, `1em` is a number followed by a unit
+		if let Some(b'e' | b'E') = self.peek() {
+			let mantissa = self.pos;
+			self.bump();
+			if let Some(b'+' | b'-') = self.peek() {
+				self.bump();
+			}
+			if self.digits() == 0 {
+				self.pos = mantissa;
+			}
+		}
+		
+		self.src[start..self.pos].parse()
+			.map_err(|_| DeError::invalid_token(&self.src[start..self.pos], "a number"))
+	}
+	
+	/// Reads a flag of an arc command, flags do not need to be separated from the next value.
+	fn flag(&mut self) -> Result<bool, DeError> {
+		match self.peek() {
+			Some(b'0') => { self.bump(); Ok(false) },
+			Some(b'1') => { self.bump(); Ok(true) },
+			_ => Err(self.unexpected("a flag"))
+		}
+	}
+}
+
+/// The color keywords of CSS, sorted by name.
+const NAMED_COLORS: [(&str, u32); 148] = [
+	("aliceblue", 0xF0F8FF), ("antiquewhite", 0xFAEBD7), ("aqua", 0x00FFFF), ("aquamarine", 0x7FFFD4),
+	("azure", 0xF0FFFF), ("beige", 0xF5F5DC), ("bisque", 0xFFE4C4), ("black", 0x000000),
+	("blanchedalmond", 0xFFEBCD), ("blue", 0x0000FF), ("blueviolet", 0x8A2BE2), ("brown", 0xA52A2A),
+	("burlywood", 0xDEB887), ("cadetblue", 0x5F9EA0), ("chartreuse", 0x7FFF00), ("chocolate", 0xD2691E),
+	("coral", 0xFF7F50), ("cornflowerblue", 0x6495ED), ("cornsilk", 0xFFF8DC), ("crimson", 0xDC143C),
+	("cyan", 0x00FFFF), ("darkblue", 0x00008B), ("darkcyan", 0x008B8B), ("darkgoldenrod", 0xB8860B),
+	("darkgray", 0xA9A9A9), ("darkgreen", 0x006400), ("darkgrey", 0xA9A9A9), ("darkkhaki", 0xBDB76B),
+	("darkmagenta", 0x8B008B), ("darkolivegreen", 0x556B2F), ("darkorange", 0xFF8C00), ("darkorchid", 0x9932CC),
+	("darkred", 0x8B0000), ("darksalmon", 0xE9967A), ("darkseagreen", 0x8FBC8F), ("darkslateblue", 0x483D8B),
+	("darkslategray", 0x2F4F4F), ("darkslategrey", 0x2F4F4F), ("darkturquoise", 0x00CED1), ("darkviolet", 0x9400D3),
+	("deeppink", 0xFF1493), ("deepskyblue", 0x00BFFF), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+	("dodgerblue", 0x1E90FF), ("firebrick", 0xB22222), ("floralwhite", 0xFFFAF0), ("forestgreen", 0x228B22),
+	("fuchsia", 0xFF00FF), ("gainsboro", 0xDCDCDC), ("ghostwhite", 0xF8F8FF), ("gold", 0xFFD700),
+	("goldenrod", 0xDAA520), ("gray", 0x808080), ("green", 0x008000), ("greenyellow", 0xADFF2F),
+	("grey", 0x808080), ("honeydew", 0xF0FFF0), ("hotpink", 0xFF69B4), ("indianred", 0xCD5C5C),
+	("indigo", 0x4B0082), ("ivory", 0xFFFFF0), ("khaki", 0xF0E68C), ("lavender", 0xE6E6FA),
+	("lavenderblush", 0xFFF0F5), ("lawngreen", 0x7CFC00), ("lemonchiffon", 0xFFFACD), ("lightblue", 0xADD8E6),
+	("lightcoral", 0xF08080), ("lightcyan", 0xE0FFFF), ("lightgoldenrodyellow", 0xFAFAD2), ("lightgray", 0xD3D3D3),
+	("lightgreen", 0x90EE90), ("lightgrey", 0xD3D3D3), ("lightpink", 0xFFB6C1), ("lightsalmon", 0xFFA07A),
+	("lightseagreen", 0x20B2AA), ("lightskyblue", 0x87CEFA), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+	("lightsteelblue", 0xB0C4DE), ("lightyellow", 0xFFFFE0), ("lime", 0x00FF00), ("limegreen", 0x32CD32),
+	("linen", 0xFAF0E6), ("magenta", 0xFF00FF), ("maroon", 0x800000), ("mediumaquamarine", 0x66CDAA),
+	("mediumblue", 0x0000CD), ("mediumorchid", 0xBA55D3), ("mediumpurple", 0x9370DB), ("mediumseagreen", 0x3CB371),
+	("mediumslateblue", 0x7B68EE), ("mediumspringgreen", 0x00FA9A), ("mediumturquoise", 0x48D1CC), ("mediumvioletred", 0xC71585),
+	("midnightblue", 0x191970), ("mintcream", 0xF5FFFA), ("mistyrose", 0xFFE4E1), ("moccasin", 0xFFE4B5),
+	("navajowhite", 0xFFDEAD), ("navy", 0x000080), ("oldlace", 0xFDF5E6), ("olive", 0x808000),
+	("olivedrab", 0x6B8E23), ("orange", 0xFFA500), ("orangered", 0xFF4500), ("orchid", 0xDA70D6),
+	("palegoldenrod", 0xEEE8AA), ("palegreen", 0x98FB98), ("paleturquoise", 0xAFEEEE), ("palevioletred", 0xDB7093),
+	("papayawhip", 0xFFEFD5), ("peachpuff", 0xFFDAB9), ("peru", 0xCD853F), ("pink", 0xFFC0CB),
+	("plum", 0xDDA0DD), ("powderblue", 0xB0E0E6), ("purple", 0x800080), ("rebeccapurple", 0x663399),
+	("red", 0xFF0000), ("rosybrown", 0xBC8F8F), ("royalblue", 0x4169E1), ("saddlebrown", 0x8B4513),
+	("salmon", 0xFA8072), ("sandybrown", 0xF4A460), ("seagreen", 0x2E8B57), ("seashell", 0xFFF5EE),
+	("sienna", 0xA0522D), ("silver", 0xC0C0C0), ("skyblue", 0x87CEEB), ("slateblue", 0x6A5ACD),
+	("slategray", 0x708090), ("slategrey", 0x708090), ("snow", 0xFFFAFA), ("springgreen", 0x00FF7F),
+	("steelblue", 0x4682B4), ("tan", 0xD2B48C), ("teal", 0x008080), ("thistle", 0xD8BFD8),
+	("tomato", 0xFF6347), ("turquoise", 0x40E0D0), ("violet", 0xEE82EE), ("wheat", 0xF5DEB3),
+	("white", 0xFFFFFF), ("whitesmoke", 0xF5F5F5), ("yellow", 0xFFFF00), ("yellowgreen", 0x9ACD32)
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn round_trip() {
+		let src = br#"<?xml version="1.0" encoding="UTF-8"?>
+		<svg:svg xmlns:svg="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100" height="50">
+			<svg:title>Unknown elements are skipped</svg:title>
+			<svg:g fill="red" style="stroke: blue; fill: green !important">
+				<svg:rect x="1" y="2" width="3" height="4"/>
+				<svg:path d="M 0 0 L 10 10"/>
+			</svg:g>
+			<svg:defs><svg:linearGradient id="a"/></svg:defs>
+			<svg:image xlink:href="image.png"/>
+			<svg:text x="5">Hello &amp; goodbye<svg:tspan fill="red">!</svg:tspan></svg:text>
+			<svg:textPath/>
+		</svg:svg>"#;
+		
+		let mut svg = deserialize(&src[..]).unwrap();
+		assert_eq!(svg.width, "100");
+		assert!(matches!(&svg.elements[..], [Element::Unknown, Element::G(_), Element::Unknown, Element::Image(_), Element::Text(_), Element::TextPath]));
+		svg.elements.retain(|e| !matches!(e, Element::Unknown));
+		let Element::G(g) = &svg.elements[0] else { unreachable!() };
+		assert!(matches!(&g.elements[..], [Element::Rect(Rect { height, .. }), Element::Path(_)] if height == "4"));
+		assert_eq!(svg.elements[0].presentation("fill"), Some("green"));
+		assert_eq!(svg.elements[0].presentation("stroke"), Some("blue"));
+		assert_eq!(svg.elements[0].presentation("opacity"), None);
+		let Element::Image(image) = &svg.elements[1] else { unreachable!() };
+		assert_eq!(image.href, "image.png");
+		let Element::Text(text) = &svg.elements[2] else { unreachable!() };
+		assert_eq!(text.text(), "Hello & goodbye!");
+		
+		let mut buf = Vec::new();
+		serialize(&mut buf, &svg).unwrap();
+		assert!(buf.starts_with(br#"<?xml version="1.0" encoding="UTF-8"?><svg xmlns="http://www.w3.org/2000/svg" x="" y="" width="100""#));
+		let svg2 = deserialize(&buf[..]).unwrap();
+		assert_eq!(format!("{:?}", svg), format!("{:?}", svg2));
+	}
+	
+	#[test]
+	fn values() {
+		assert_eq!("12.5".parse::<Length>().unwrap(), Length::new(12.5, Unit::None));
+		assert_eq!(" 1e1mm ".parse::<Length>().unwrap(), Length::new(10.0, Unit::Mm));
+		assert_eq!("2em".parse::<Length>().unwrap().to_user(16.0, 0.0), 32.0);
+		assert_eq!("50%".parse::<Length>().unwrap().to_user(16.0, 300.0), 150.0);
+		assert!("1 px".parse::<Length>().is_err());
+		assert!("px".parse::<Length>().is_err());
+		assert_eq!(numbers("0,0 10-5.5.5").unwrap(), [0.0, 0.0, 10.0, -5.5, 0.5]);
+		
+		let transform = "translate(10, 20) scale(2)".parse::<Transform>().unwrap();
+		assert_eq!(transform.apply([1.0, 1.0]), [12.0, 22.0]);
+		assert_eq!(transform.scale_factor(), 2.0);
+		let transform = "rotate(90 5 5)".parse::<Transform>().unwrap();
+		let [x, y] = transform.apply([10.0, 5.0]);
+		assert!((x - 5.0).abs() < 1e-5 && (y - 10.0).abs() < 1e-5);
+		assert_eq!("matrix(1 2 3 4 5 6)".parse::<Transform>().unwrap(), Transform([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+		assert_eq!("".parse::<Transform>().unwrap(), Transform::IDENTITY);
+		assert!("scale(1 2 3)".parse::<Transform>().is_err());
+		assert!("translate(1".parse::<Transform>().is_err());
+		
+		assert_eq!("#f00".parse::<Color>().unwrap(), Color([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!("#0000FF80".parse::<Color>().unwrap(), Color([0.0, 0.0, 1.0, 128.0 / 255.0]));
+		assert_eq!("rgb(255, 0, 51)".parse::<Color>().unwrap(), Color([1.0, 0.0, 0.2, 1.0]));
+		assert_eq!("rgba(0 100% 0 / 50%)".parse::<Color>().unwrap(), Color([0.0, 1.0, 0.0, 0.5]));
+		assert_eq!("White".parse::<Color>().unwrap(), Color([1.0; 4]));
+		assert!("#12345".parse::<Color>().is_err());
+		assert!("rgb(1, 2)x".parse::<Color>().is_err());
+		assert!("notacolor".parse::<Color>().is_err());
+		assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+		
+		assert_eq!("none".parse::<Paint>().unwrap(), Paint::None);
+		assert_eq!("currentColor".parse::<Paint>().unwrap(), Paint::CurrentColor);
+		assert_eq!("url(#grad) red".parse::<Paint>().unwrap(),
+			Paint::Url("#grad".to_string(), Box::new(Paint::Color(Color([1.0, 0.0, 0.0, 1.0])))));
+		
+		assert_eq!(style("fill:red;; stroke : blue ").collect::<Vec<_>>(), [("fill", "red"), ("stroke", "blue")]);
+	}
+	
+	#[test]
+	fn path_data() {
+		use PathCommand::*;
+		
+		let path = PathData::new("m10 10 20 0 v10 h-20z M0,0 C 1 1 2 2 3 3 s 1 1 2 2 Q5 5 6 6 T 8 8 l.5.5")
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(path, [
+			Move([10.0, 10.0]), Line([30.0, 10.0]), Line([30.0, 20.0]), Line([10.0, 20.0]), Close,
+			Move([0.0, 0.0]), Cubic([1.0, 1.0], [2.0, 2.0], [3.0, 3.0]), Cubic([4.0, 4.0], [4.0, 4.0], [5.0, 5.0]),
+			Quad([5.0, 5.0], [6.0, 6.0]), Quad([7.0, 7.0], [8.0, 8.0]), Line([8.5, 8.5])
+		]);
+		
+		// a half circle is split into two quarters, the flags do not need to be separated
+		let path = PathData::new("M0 0A5 5 0 01 10 0").collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(path.len(), 3);
+		let Cubic(_, _, [x, y]) = path[1] else { unreachable!() };
+		assert!((x - 5.0).abs() < 1e-4 && (y + 5.0).abs() < 1e-4);
+		assert!(matches!(path[2], Cubic(_, _, [x, y]) if x == 10.0 && y == 0.0));
+		
+		// commands up to an error are still returned
+		let mut path = PathData::new("M 0 0 L 1 1 L 2");
+		assert_eq!(path.next().unwrap().unwrap(), Move([0.0, 0.0]));
+		assert_eq!(path.next().unwrap().unwrap(), Line([1.0, 1.0]));
+		assert!(path.next().unwrap().is_err());
+		assert!(path.next().is_none());
+		assert!(PathData::new("L 1 1").next().unwrap().is_err());
+	}
+}
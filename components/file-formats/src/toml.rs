@@ -20,22 +20,32 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-#![allow(unused_variables, dead_code, unused_mut)]
+//! TOML 1.0 support. `Value` is the plain data model that is used for (de)serialization,
+//! `Document` keeps the source text of every item, so that files can be edited without losing
+//! comments or formatting.
 
 use {
 	crate::utils::*,
-	std::{io, fmt, collections::HashMap},
+	std::{io, fmt, collections::{HashMap, HashSet, hash_map::Entry}, str::FromStr},
 	serde::{*, de::*, ser::*, forward_to_deserialize_any}
 };
 
+/// Name of the newtype struct that is used to pass datetimes through serde, and the key of
+/// the maps that represent datetimes in a `dyn_repr::Type`.
+pub(crate) const DATETIME_NAME: &str = "$__toml_datetime";
+
 pub fn serialize<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
-	value.serialize(DummySerializer)?.serialize(writer)
+	Value::serializer(value)?.serialize(writer)
 }
 
 pub fn deserialize<'de, T: Deserialize<'de>, R: io::BufRead + fmt::Debug>(reader: R) -> Result<T, DeError> {
 	T::deserialize(Value::deserialize(reader)?)
 }
 
+pub fn deserialize_str<'de, T: Deserialize<'de>>(src: &str) -> Result<T, DeError> {
+	T::deserialize(Document::parse(src)?.to_value()?)
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Value {
@@ -43,7 +53,7 @@ pub enum Value {
 	Integer(i64),
 	Float(f64),
 	Bool(bool),
-	DateTime(u64, u32),
+	DateTime(Datetime),
 	Array(Vec<Self>),
 	Table(HashMap<String, Self>)
 }
@@ -77,9 +87,9 @@ impl Value {
 		}
 	}
 	
-	pub fn as_datetime(&self) -> Option<(u64, u32)> {
+	pub fn as_datetime(&self) -> Option<&Datetime> {
 		match self {
-			Self::DateTime(secs, nanos) => Some((*secs, *nanos)),
+			Self::DateTime(v) => Some(v),
 			_ => None
 		}
 	}
@@ -98,55 +108,42 @@ impl Value {
 		}
 	}
 	
+	/// Returns the value at the given path of keys.
+	pub fn get(&self, path: &[&str]) -> Option<&Self> {
+		path.iter().try_fold(self, |value, key| value.as_table()?.get(*key))
+	}
+	
 	pub fn serializer<T: Serialize + ?Sized>(v: &T) -> Result<Self, SerError> {
-		v.serialize(DummySerializer)
+		v.serialize(DummySerializer).map(|v| v.unwrap_or_else(|| Self::Table(HashMap::new())))
 	}
 	
 	pub fn deserializer<'de, T: Deserialize<'de>>(self) -> Result<T, DeError> {
 		T::deserialize(self)
 	}
 	
-	pub fn serialize(&self, writer: impl io::Write) -> Result<(), SerError> {
-		/*match self {
-			Self::Table(v) => {
-			
-			}
-			Self::Array(v) if v.iter().all(|v| matches!(v, Self::Table(_))) => {
-				// array of tables
-			}
-			Self::Array(v) => {
-				// normal array
-			}
-		}*/
-		
-		unimplemented!();
+	/// Writes this value as a TOML document, the value must be a table.
+	pub fn serialize(&self, mut writer: impl io::Write) -> Result<(), SerError> {
+		let mut out = String::new();
+		match self {
+			Self::Table(table) => write_table(&mut out, &mut Vec::new(), table),
+			_ => return Err(SerError::Custom("the root of a TOML document must be a table".to_string()))
+		}
+		writer.write_all(out.as_bytes())?;
+		Ok(())
 	}
 	
 	pub fn deserialize(mut reader: impl io::BufRead) -> Result<Self, DeError> {
-		let mut map = std::collections::HashMap::new();
-		
-		loop {
-			match reader.fill_buf()? {
-				[ch,   ..] if ch.is_ascii_whitespace() => reader.consume(1),
-				[b'[', ..] => {
-					reader.consume(1);
-					
-					let array = matches!(reader.fill_buf()?, [b'[', ..]);
-					
-					if array {
-						reader.consume(1);
-					}
-					
-					unimplemented!()
-				}
-				[ch, ..] => {
-				
-				}
-				[] => break //return Err(DeError::invalid_token("EOF", "?"))
-			}
+		let mut src = String::new();
+		reader.read_to_string(&mut src)?;
+		Document::parse(&src)?.to_value()
+	}
+	
+	fn is_table_like(&self) -> bool {
+		match self {
+			Self::Table(_) => true,
+			Self::Array(v) => is_array_of_tables(v),
+			_ => false
 		}
-		
-		Ok(Self::Table(map))
 	}
 }
 
@@ -156,6 +153,12 @@ impl From<String> for Value {
 	}
 }
 
+impl From<&str> for Value {
+	fn from(v: &str) -> Self {
+		Self::String(v.to_string())
+	}
+}
+
 impl From<i64> for Value {
 	fn from(v: i64) -> Self {
 		Self::Integer(v)
@@ -174,10 +177,15 @@ impl From<bool> for Value {
 	}
 }
 
+impl From<Datetime> for Value {
+	fn from(v: Datetime) -> Self {
+		Self::DateTime(v)
+	}
+}
+
 impl From<std::time::SystemTime> for Value {
 	fn from(v: std::time::SystemTime) -> Self {
-		let d = v.duration_since(std::time::UNIX_EPOCH).unwrap();
-		Self::DateTime(d.as_secs(), (d.as_nanos() % 1_000_000) as _)
+		Self::DateTime(v.into())
 	}
 }
 
@@ -205,501 +213,1527 @@ impl std::iter::FromIterator<(String, Self)> for Value {
 	}
 }
 
-struct DummySerializer;
+/// A TOML datetime. Depending on which parts are present, this is an offset datetime
+/// (date, time and offset), a local datetime (date and time), a local date or a local time.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Datetime {
+	pub date:   Option<Date>,
+	pub time:   Option<Time>,
+	pub offset: Option<Offset>
+}
 
-impl Serializer for DummySerializer {
-	type Ok                     = Value;
-	type Error                  = SerError;
-	type SerializeSeq           = ValueWrapper<Vec<Value>>;
-	type SerializeTuple         = ValueWrapper<Vec<Value>>;
-	type SerializeTupleStruct   = ValueWrapper<Vec<Value>>;
-	type SerializeTupleVariant  = ValueWrapper<(&'static str, Vec<Value>)>;
-	type SerializeMap           = ValueWrapper<(HashMap<String, Value>, Option<String>)>;
-	type SerializeStruct        = ValueWrapper<(HashMap<String, Value>, Option<String>)>;
-	type SerializeStructVariant = ValueWrapper<(&'static str, HashMap<String, Value>)>;
-	
-	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Bool(v))
-	}
-	
-	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
-	}
-	
-	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Date {
+	pub year:  u16,
+	pub month: u8,
+	pub day:   u8
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Time {
+	pub hour:       u8,
+	pub minute:     u8,
+	pub second:     u8,
+	pub nanosecond: u32
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Offset {
+	/// UTC, written as `Z`.
+	Z,
+	/// Offset from UTC in minutes.
+	Custom(i16)
+}
+
+impl Datetime {
+	/// Returns the seconds and nanoseconds since the unix epoch, only offset datetimes
+	/// describe an instant in time.
+	pub fn unix_timestamp(&self) -> Option<(i64, u32)> {
+		let (date, time, offset) = (self.date?, self.time?, self.offset?);
+		let offset = match offset {
+			Offset::Z => 0,
+			Offset::Custom(minutes) => minutes as i64 * 60
+		};
+		let secs = days_from_civil(date.year as _, date.month as _, date.day as _) * 86400
+			+ time.hour as i64 * 3600 + time.minute as i64 * 60 + time.second as i64 - offset;
+		Some((secs, time.nanosecond))
+	}
+	
+	/// Creates an offset datetime in UTC from seconds and nanoseconds since the unix epoch.
+	pub fn from_unix_timestamp(secs: i64, nanosecond: u32) -> Self {
+		let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+		let secs = secs.rem_euclid(86400);
+		Self {
+			date:   Some(Date { year: year as _, month: month as _, day: day as _ }),
+			time:   Some(Time {
+				hour:   (secs / 3600) as _,
+				minute: (secs / 60 % 60) as _,
+				second: (secs % 60) as _,
+				nanosecond
+			}),
+			offset: Some(Offset::Z)
+		}
 	}
-	
-	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
+}
+
+impl From<std::time::SystemTime> for Datetime {
+	fn from(v: std::time::SystemTime) -> Self {
+		match v.duration_since(std::time::UNIX_EPOCH) {
+			Ok(d) => Self::from_unix_timestamp(d.as_secs() as _, d.subsec_nanos()),
+			Err(e) => {
+				let d = e.duration();
+				match d.subsec_nanos() {
+					0 => Self::from_unix_timestamp(-(d.as_secs() as i64), 0),
+					n => Self::from_unix_timestamp(-(d.as_secs() as i64) - 1, 1_000_000_000 - n)
+				}
+			}
+		}
 	}
-	
-	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
+}
+
+impl fmt::Display for Datetime {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(date) = &self.date {
+			write!(f, "{:04}-{:02}-{:02}", date.year, date.month, date.day)?;
+		}
+		
+		if let Some(time) = &self.time {
+			if self.date.is_some() {
+				f.write_str("T")?;
+			}
+			
+			write!(f, "{:02}:{:02}:{:02}", time.hour, time.minute, time.second)?;
+			
+			if time.nanosecond != 0 {
+				let frac = format!("{:09}", time.nanosecond);
+				write!(f, ".{}", frac.trim_end_matches('0'))?;
+			}
+		}
+		
+		match self.offset {
+			None => Ok(()),
+			Some(Offset::Z) => f.write_str("Z"),
+			Some(Offset::Custom(minutes)) => write!(f, "{}{:02}:{:02}",
+				if minutes < 0 { '-' } else { '+' }, minutes.abs() / 60, minutes.abs() % 60)
+		}
 	}
-	
-	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Integer(v as _))
+}
+
+impl FromStr for Datetime {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parser = Parser::new(s);
+		let datetime = match parser.number_or_datetime()? {
+			Value::DateTime(v) => v,
+			_ => return Err(DeError::invalid_token(s, "a datetime"))
+		};
+		
+		match parser.peek() {
+			None => Ok(datetime),
+			Some(_) => Err(parser.unexpected("end of datetime"))
+		}
 	}
-	
-	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::String(v.to_string()))
+}
+
+impl Serialize for Datetime {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(DATETIME_NAME, &self.to_string())
 	}
-	
-	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::String(v.to_string()))
+}
+
+impl<'de> Deserialize<'de> for Datetime {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct DatetimeVisitor;
+		
+		impl<'de> Visitor<'de> for DatetimeVisitor {
+			type Value = Datetime;
+			
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a TOML datetime")
+			}
+			
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+				v.parse().map_err(E::custom)
+			}
+			
+			fn visit_newtype_struct<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+				deserializer.deserialize_str(self)
+			}
+		}
+		
+		deserializer.deserialize_newtype_struct(DATETIME_NAME, DatetimeVisitor)
 	}
-	
-	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-		let mut s = self.serialize_seq(Some(v.len()))?;
-		v.iter().try_for_each(|b| SerializeSeq::serialize_element(&mut s, b))?;
-		SerializeSeq::end(s)
+}
+
+fn is_leap_year(year: u32) -> bool {
+	year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31
 	}
-	
-	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-		self.serialize_unit()
+}
+
+/// Days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let yoe = year - era * 400;
+	let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+	let days = days + 719468;
+	let era = days.div_euclid(146097);
+	let doe = days - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	(yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+/// A parsed TOML document that retains the source text of every item. Values can be queried
+/// and modified by their key path, all other items (comments, whitespace, formatting of
+/// unmodified values) are written back as they were read.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+	items: Vec<Item>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+	/// Empty lines, comments and whitespace.
+	Trivia(String),
+	/// A `[table]` or `[[array]]` header, including the trailing comment and newline.
+	Header {
+		raw:   String,
+		key:   Vec<String>,
+		array: bool
+	},
+	KeyValue(KeyValue)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyValue {
+	/// Leading whitespace, key and `=` including surrounding whitespace.
+	pub raw_key:   String,
+	pub key:       Vec<String>,
+	pub raw_value: String,
+	pub value:     Value,
+	/// Trailing whitespace, comment and newline.
+	pub suffix:    String
+}
+
+impl Item {
+	fn raw_len(&self) -> usize {
+		match self {
+			Self::Trivia(raw) | Self::Header { raw, .. } => raw.len(),
+			Self::KeyValue(kv) => kv.raw_key.len() + kv.raw_value.len() + kv.suffix.len()
+		}
 	}
-	
-	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
-		value.serialize(self)
+}
+
+impl Document {
+	pub fn new() -> Self {
+		Self::default()
 	}
 	
-	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Table(HashMap::new()))
+	/// Parses and validates a document.
+	pub fn parse(src: &str) -> Result<Self, DeError> {
+		let doc = Parser::new(src).document()?;
+		doc.to_value()?;
+		Ok(doc)
 	}
 	
-	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-		self.serialize_unit()
+	pub fn read(mut reader: impl io::Read) -> Result<Self, DeError> {
+		let mut src = String::new();
+		reader.read_to_string(&mut src)?;
+		Self::parse(&src)
 	}
 	
-	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::String(variant.to_string()))
+	pub fn write(&self, mut writer: impl io::Write) -> io::Result<()> {
+		writer.write_all(self.to_string().as_bytes())
 	}
 	
-	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
-		value.serialize(self)
+	pub fn items(&self) -> &[Item] {
+		&self.items
 	}
 	
-	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Table(std::iter::once((variant.to_string(), value.serialize(self)?)).collect()))
+	/// Builds the data model of this document.
+	pub fn to_value(&self) -> Result<Value, DeError> {
+		let src = self.to_string();
+		let mut builder = Builder::default();
+		let mut offset = 0;
+		
+		for item in &self.items {
+			let r = match item {
+				Item::Trivia(_) => Ok(()),
+				Item::Header { key, array, .. } => builder.header(key, *array),
+				Item::KeyValue(kv) => builder.key_value(&kv.key, kv.value.clone())
+			};
+			
+			if let Err(msg) = r {
+				let start = offset + src[offset..].len() - src[offset..].trim_start().len();
+				return Err(error_at(&src, start, msg));
+			}
+			
+			offset += item.raw_len();
+		}
+		
+		Ok(Value::Table(builder.root))
 	}
 	
-	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-		Ok(ValueWrapper(Vec::new()))
+	/// Returns the value at the given key path. If a key is defined multiple times, e.g.
+	/// in an array of tables, the last definition is returned.
+	pub fn get(&self, path: &[&str]) -> Option<&Value> {
+		let mut found = None;
+		
+		for (table, kv) in self.key_values() {
+			let full = table.iter().chain(&kv.key).collect::<Vec<_>>();
+			if full.len() <= path.len() && full.iter().zip(path).all(|(a, b)| a == b) {
+				if let Some(v) = kv.value.get(&path[full.len()..]) {
+					found = Some(v);
+				}
+			}
+		}
+		
+		found
 	}
 	
-	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-		Ok(ValueWrapper(Vec::new()))
+	/// Sets the value at the given key path, keeping the formatting of all other items. New
+	/// keys are inserted into the section of their parent table if possible, otherwise a new
+	/// table is appended to the document.
+	pub fn set(&mut self, path: &[&str], value: impl Into<Value>) -> Result<(), DeError> {
+		let value = value.into();
+		let (last, parent) = match path.split_last() {
+			Some(v) => v,
+			None => return Err(DeError::Custom("the key path must not be empty".to_string()))
+		};
+		
+		let prev = self.items.clone();
+		let mut table: &[String] = &[];
+		let mut exact = None;
+		let mut section_end = None;
+		let mut dotted_end = None;
+		let mut root_end = 0;
+		
+		for (i, item) in self.items.iter().enumerate() {
+			match item {
+				Item::Header { key, .. } => table = key,
+				Item::KeyValue(kv) => {
+					let full = table.iter().chain(&kv.key).collect::<Vec<_>>();
+					if full.len() <= path.len() && full.iter().zip(path).all(|(a, b)| a == b) {
+						exact = Some((i, full.len()));
+					} else if table.len() < full.len() - 1 && full[..full.len() - 1].iter().zip(parent).all(|(a, b)| a == b)
+						&& full.len() - 1 <= parent.len() && table.iter().zip(path).all(|(a, b)| a == b) {
+						dotted_end = Some((i, table.len()));
+					}
+					
+					if table.is_empty() {
+						root_end = i + 1;
+					}
+				}
+				Item::Trivia(_) => ()
+			}
+			
+			if table == parent && !matches!(item, Item::Trivia(_)) {
+				section_end = Some((i, table.len()));
+			}
+		}
+		
+		match (exact, dotted_end, section_end) {
+			(Some((i, depth)), _, _) => {
+				let Item::KeyValue(kv) = &mut self.items[i] else { unreachable!() };
+				if !set_nested(&mut kv.value, &path[depth..], value) {
+					self.items = prev;
+					return Err(DeError::Custom(format!("key `{}` is not a table", path[..depth].join("."))));
+				}
+				kv.raw_value = inline_to_string(&kv.value);
+			}
+			(None, Some((i, depth)), _) | (None, None, Some((i, depth))) => {
+				let indent = self.indent_at(i);
+				self.items.insert(i + 1, Item::KeyValue(KeyValue::new(indent, &path[depth..], value)));
+			}
+			(None, None, None) if parent.is_empty() => {
+				self.items.insert(root_end, Item::KeyValue(KeyValue::new(String::new(), path, value)));
+			}
+			(None, None, None) => {
+				if !self.items.is_empty() && !self.to_string().ends_with("\n\n") {
+					self.items.push(Item::Trivia("\n".to_string()));
+				}
+				
+				let mut raw = String::from("[");
+				write_key_path(&mut raw, parent);
+				raw.push_str("]\n");
+				self.items.push(Item::Header {
+					raw,
+					key:   parent.iter().map(|s| s.to_string()).collect(),
+					array: false
+				});
+				self.items.push(Item::KeyValue(KeyValue::new(String::new(), &[last], value)));
+			}
+		}
+		
+		self.ensure_trailing_newline();
+		
+		match self.to_value() {
+			Ok(_) => Ok(()),
+			Err(e) => {
+				self.items = prev;
+				Err(e)
+			}
+		}
 	}
 	
-	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
-		Ok(ValueWrapper(Vec::new()))
+	/// Removes the key/value pair at the given key path, including its trailing comment.
+	pub fn remove(&mut self, path: &[&str]) -> Option<Value> {
+		let mut table: &[String] = &[];
+		let mut found = None;
+		
+		for (i, item) in self.items.iter().enumerate() {
+			match item {
+				Item::Header { key, .. } => table = key,
+				Item::KeyValue(kv) => {
+					let full = table.iter().chain(&kv.key).collect::<Vec<_>>();
+					if full.len() <= path.len() && full.iter().zip(path).all(|(a, b)| a == b) {
+						found = Some((i, full.len()));
+					}
+				}
+				Item::Trivia(_) => ()
+			}
+		}
+		
+		let (i, depth) = found?;
+		match &mut self.items[i] {
+			Item::KeyValue(kv) if depth == path.len() => {
+				let value = kv.value.clone();
+				self.items.remove(i);
+				Some(value)
+			}
+			Item::KeyValue(kv) => {
+				let (last, parent) = path[depth..].split_last()?;
+				let value = match &mut kv.value {
+					Value::Table(table) => parent.iter()
+						.try_fold(table, |table, key| match table.get_mut(*key) {
+							Some(Value::Table(table)) => Some(table),
+							_ => None
+						})?
+						.remove(*last)?,
+					_ => return None
+				};
+				kv.raw_value = inline_to_string(&kv.value);
+				Some(value)
+			}
+			_ => unreachable!()
+		}
 	}
 	
-	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
-		Ok(ValueWrapper((variant, Vec::new())))
+	fn key_values(&self) -> impl Iterator<Item = (&[String], &KeyValue)> {
+		let mut table: &[String] = &[];
+		self.items.iter().filter_map(move |item| match item {
+			Item::Header { key, .. } => {
+				table = key;
+				None
+			}
+			Item::KeyValue(kv) => Some((table, kv)),
+			Item::Trivia(_) => None
+		})
 	}
 	
-	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		Ok(ValueWrapper((HashMap::new(), None)))
+	fn indent_at(&self, i: usize) -> String {
+		match &self.items[i] {
+			Item::KeyValue(kv) => kv.raw_key[..kv.raw_key.len() - kv.raw_key.trim_start().len()].to_string(),
+			_ => String::new()
+		}
 	}
 	
-	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
-		Ok(ValueWrapper((HashMap::new(), None)))
+	fn ensure_trailing_newline(&mut self) {
+		let len = self.items.len();
+		for (i, item) in self.items.iter_mut().enumerate() {
+			match item {
+				Item::KeyValue(kv) if !kv.suffix.ends_with('\n') && i + 1 < len => kv.suffix.push('\n'),
+				_ => ()
+			}
+		}
 	}
-	
-	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
-		Ok(ValueWrapper((variant, HashMap::new())))
+}
+
+impl KeyValue {
+	pub fn new(indent: String, key: &[&str], value: Value) -> Self {
+		let mut raw_key = indent;
+		write_key_path(&mut raw_key, key);
+		raw_key.push_str(" = ");
+		Self {
+			raw_key,
+			key: key.iter().map(|s| s.to_string()).collect(),
+			raw_value: inline_to_string(&value),
+			value,
+			suffix: "\n".to_string()
+		}
 	}
 }
 
-impl SerializeSeq for ValueWrapper<Vec<Value>> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		self.0.push(value.serialize(DummySerializer)?);
+impl fmt::Display for Document {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for item in &self.items {
+			match item {
+				Item::Trivia(raw) | Item::Header { raw, .. } => f.write_str(raw)?,
+				Item::KeyValue(kv) => {
+					f.write_str(&kv.raw_key)?;
+					f.write_str(&kv.raw_value)?;
+					f.write_str(&kv.suffix)?;
+				}
+			}
+		}
 		Ok(())
 	}
-	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Array(self.0))
-	}
 }
 
-impl SerializeTuple for ValueWrapper<Vec<Value>> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		SerializeSeq::serialize_element(self, value)
-	}
+impl FromStr for Document {
+	type Err = DeError;
 	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		SerializeSeq::end(self)
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
 	}
 }
 
-impl SerializeTupleStruct for ValueWrapper<Vec<Value>> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		SerializeSeq::serialize_element(self, value)
+impl From<&Value> for Document {
+	fn from(v: &Value) -> Self {
+		let mut out = String::new();
+		if let Value::Table(table) = v {
+			write_table(&mut out, &mut Vec::new(), table);
+		}
+		Parser::new(&out).document().expect("serialized value must be valid TOML")
 	}
-	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		SerializeSeq::end(self)
+}
+
+fn set_nested(value: &mut Value, path: &[&str], new: Value) -> bool {
+	match path.split_first() {
+		None => {
+			*value = new;
+			true
+		}
+		Some((key, rest)) => match value {
+			Value::Table(table) => set_nested(table.entry(key.to_string())
+				.or_insert_with(|| Value::Table(HashMap::new())), rest, new),
+			_ => false
+		}
 	}
 }
 
-impl SerializeTupleVariant for ValueWrapper<(&'static str, Vec<Value>)> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		self.0.1.push(value.serialize(DummySerializer)?);
-		Ok(())
+pub(crate) fn position(src: &str, pos: usize) -> (usize, usize) {
+	let before = &src[..pos.min(src.len())];
+	let line = before.matches('\n').count() + 1;
+	let column = before[before.rfind('\n').map_or(0, |i| i + 1)..].chars().count() + 1;
+	(line, column)
+}
+
+fn error_at(src: &str, pos: usize, msg: impl fmt::Display) -> DeError {
+	let (line, column) = position(src, pos);
+	DeError::ParseError(msg.to_string()).at(line, column)
+}
+
+fn is_control(ch: char) -> bool {
+	(ch < '\u{20}' && ch != '\t') || ch == '\u{7f}'
+}
+
+fn is_bare_key(ch: u8) -> bool {
+	ch.is_ascii_alphanumeric() || ch == b'_' || ch == b'-'
+}
+
+fn is_array_of_tables(v: &[Value]) -> bool {
+	!v.is_empty() && v.iter().all(|v| matches!(v, Value::Table(_)))
+}
+
+fn write_key(out: &mut String, key: &str) {
+	if !key.is_empty() && key.bytes().all(is_bare_key) {
+		out.push_str(key);
+	} else {
+		write_string(out, key);
 	}
-	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Table(std::iter::once((self.0.0.to_string(), Value::Array(self.0.1))).collect()))
+}
+
+fn write_key_path(out: &mut String, path: &[impl AsRef<str>]) {
+	for (i, key) in path.iter().enumerate() {
+		if i > 0 {
+			out.push('.');
+		}
+		write_key(out, key.as_ref());
 	}
 }
 
-impl SerializeMap for ValueWrapper<(HashMap<String, Value>, Option<String>)> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
-		unimplemented!()
+fn write_string(out: &mut String, s: &str) {
+	out.push('"');
+	for ch in s.chars() {
+		match ch {
+			'"'      => out.push_str("\\\""),
+			'\\'     => out.push_str("\\\\"),
+			'\u{8}'  => out.push_str("\\b"),
+			'\t'     => out.push_str("\\t"),
+			'\n'     => out.push_str("\\n"),
+			'\u{c}'  => out.push_str("\\f"),
+			'\r'     => out.push_str("\\r"),
+			ch if is_control(ch) => out.push_str(&format!("\\u{:04X}", ch as u32)),
+			ch => out.push(ch)
+		}
+	}
+	out.push('"');
+}
+
+fn write_inline(out: &mut String, value: &Value) {
+	match value {
+		Value::String(v) => write_string(out, v),
+		Value::Integer(v) => out.push_str(&v.to_string()),
+		Value::Float(v) if v.is_nan() => out.push_str("nan"),
+		Value::Float(v) if v.is_infinite() => out.push_str(if *v > 0.0 { "inf" } else { "-inf" }),
+		Value::Float(v) => out.push_str(&format!("{:?}", v)),
+		Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+		Value::DateTime(v) => out.push_str(&v.to_string()),
+		Value::Array(v) => {
+			out.push('[');
+			for (i, v) in v.iter().enumerate() {
+				out.push_str(if i == 0 { " " } else { ", " });
+				write_inline(out, v);
+			}
+			out.push_str(if v.is_empty() { "]" } else { " ]" });
+		}
+		Value::Table(v) => {
+			let mut keys = v.keys().collect::<Vec<_>>();
+			keys.sort();
+			out.push('{');
+			for (i, key) in keys.into_iter().enumerate() {
+				out.push_str(if i == 0 { " " } else { ", " });
+				write_key(out, key);
+				out.push_str(" = ");
+				write_inline(out, &v[key]);
+			}
+			out.push_str(if v.is_empty() { "}" } else { " }" });
+		}
+	}
+}
+
+fn inline_to_string(value: &Value) -> String {
+	let mut out = String::new();
+	write_inline(&mut out, value);
+	out
+}
+
+fn write_table(out: &mut String, path: &mut Vec<String>, table: &HashMap<String, Value>) {
+	let mut keys = table.keys().collect::<Vec<_>>();
+	keys.sort();
+	
+	for key in keys.iter().filter(|key| !table[**key].is_table_like()) {
+		write_key(out, key);
+		out.push_str(" = ");
+		write_inline(out, &table[*key]);
+		out.push('\n');
+	}
+	
+	for key in keys.iter().filter(|key| table[**key].is_table_like()) {
+		path.push(key.to_string());
+		match &table[*key] {
+			Value::Table(v) => {
+				if !out.is_empty() {
+					out.push('\n');
+				}
+				out.push('[');
+				write_key_path(out, path);
+				out.push_str("]\n");
+				write_table(out, path, v);
+			}
+			Value::Array(v) => for v in v {
+				if !out.is_empty() {
+					out.push('\n');
+				}
+				out.push_str("[[");
+				write_key_path(out, path);
+				out.push_str("]]\n");
+				write_table(out, path, v.as_table().unwrap());
+			}
+			_ => unreachable!()
+		}
+		path.pop();
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum TableKind {
+	/// Created as a parent of a `[table]` header, may still be defined by a header.
+	Implicit,
+	/// Defined by a `[table]` header.
+	Defined,
+	/// Created by a dotted key, may only be extended by dotted keys.
+	Dotted,
+	/// Created by `[[array]]` headers.
+	ArrayOfTables
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum Segment {
+	Key(String),
+	Index(usize)
+}
+
+/// Assembles the values of a document and enforces the rules on (re)defining tables. Tables
+/// without a kind are inline tables, arrays without a kind are static arrays, neither can
+/// be extended.
+#[derive(Debug, Default)]
+struct Builder {
+	root:    HashMap<String, Value>,
+	kinds:   HashMap<Vec<Segment>, TableKind>,
+	current: Vec<Segment>
+}
+
+impl Builder {
+	fn table<'a>(root: &'a mut HashMap<String, Value>, path: &[Segment]) -> &'a mut HashMap<String, Value> {
+		let mut table = root;
+		let mut iter = path.iter();
+		
+		while let Some(Segment::Key(key)) = iter.next() {
+			table = match (table.get_mut(key), iter.as_slice().first()) {
+				(Some(Value::Array(array)), Some(Segment::Index(i))) => {
+					iter.next();
+					match &mut array[*i] {
+						Value::Table(table) => table,
+						_ => unreachable!()
+					}
+				}
+				(Some(Value::Table(table)), _) => table,
+				_ => unreachable!()
+			};
+		}
+		
+		table
 	}
 	
-	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		self.0.0.insert(self.0.1.take().unwrap(), value.serialize(DummySerializer)?);
+	fn header(&mut self, keys: &[String], array: bool) -> Result<(), String> {
+		let mut path = Vec::new();
+		
+		for (i, key) in keys.iter().enumerate() {
+			let last = i + 1 == keys.len();
+			let table = Self::table(&mut self.root, &path);
+			path.push(Segment::Key(key.clone()));
+			
+			match (table.get_mut(key), self.kinds.get(&path).copied(), last, array) {
+				(None, _, true, true) => {
+					table.insert(key.clone(), Value::Array(vec![Value::Table(HashMap::new())]));
+					self.kinds.insert(path.clone(), TableKind::ArrayOfTables);
+					path.push(Segment::Index(0));
+				}
+				(None, _, _, _) => {
+					table.insert(key.clone(), Value::Table(HashMap::new()));
+					self.kinds.insert(path.clone(), if last { TableKind::Defined } else { TableKind::Implicit });
+				}
+				(Some(Value::Array(v)), Some(TableKind::ArrayOfTables), true, true) => {
+					v.push(Value::Table(HashMap::new()));
+					path.push(Segment::Index(v.len() - 1));
+				}
+				(Some(Value::Array(v)), Some(TableKind::ArrayOfTables), false, _) => {
+					path.push(Segment::Index(v.len() - 1));
+				}
+				(Some(Value::Table(_)), Some(TableKind::Implicit), true, false) => {
+					self.kinds.insert(path.clone(), TableKind::Defined);
+				}
+				(Some(Value::Table(_)), Some(TableKind::Implicit | TableKind::Defined | TableKind::Dotted), false, _) => (),
+				(Some(Value::Table(_)), Some(_), true, _) => return Err(format!("table `{}` is already defined", keys.join("."))),
+				(Some(_), _, _, _) => return Err(format!("key `{}` is already defined and cannot be extended", keys[..=i].join(".")))
+			}
+		}
+		
+		self.current = path;
 		Ok(())
 	}
 	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Table(self.0.0))
+	fn key_value(&mut self, keys: &[String], value: Value) -> Result<(), String> {
+		let mut path = self.current.clone();
+		let (last, parents) = keys.split_last().expect("key must not be empty");
+		
+		for key in parents {
+			let table = Self::table(&mut self.root, &path);
+			path.push(Segment::Key(key.clone()));
+			
+			match (table.get(key), self.kinds.get(&path)) {
+				(None, _) => {
+					table.insert(key.clone(), Value::Table(HashMap::new()));
+					self.kinds.insert(path.clone(), TableKind::Dotted);
+				}
+				(Some(Value::Table(_)), Some(TableKind::Dotted)) => (),
+				_ => return Err(format!("key `{}` is already defined and cannot be extended by a dotted key", key))
+			}
+		}
+		
+		match Self::table(&mut self.root, &path).entry(last.clone()) {
+			Entry::Occupied(_) => Err(format!("key `{}` is already defined", keys.join("."))),
+			Entry::Vacant(e) => {
+				e.insert(value);
+				Ok(())
+			}
+		}
 	}
 }
 
-impl SerializeStruct for ValueWrapper<(HashMap<String, Value>, Option<String>)> {
-	type Ok    = Value;
-	type Error = SerError;
-	
-	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
-		SerializeMap::serialize_entry(self, key, value)
+/// Inserts a value into an inline table, `dotted` tracks which tables were created by
+/// dotted keys and thus may be extended.
+fn insert_dotted(
+	mut table: &mut HashMap<String, Value>,
+	dotted:    &mut HashSet<Vec<String>>,
+	keys:      &[String],
+	value:     Value
+) -> Result<(), String> {
+	let (last, parents) = keys.split_last().expect("key must not be empty");
+	
+	for (i, key) in parents.iter().enumerate() {
+		let path = &keys[..=i];
+		let entry = table.entry(key.clone()).or_insert_with(|| {
+			dotted.insert(path.to_vec());
+			Value::Table(HashMap::new())
+		});
+		
+		table = match entry {
+			Value::Table(table) if dotted.contains(path) => table,
+			_ => return Err(format!("key `{}` is already defined and cannot be extended", path.join(".")))
+		};
 	}
 	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		SerializeMap::end(self)
+	match table.entry(last.clone()) {
+		Entry::Occupied(_) => Err(format!("key `{}` is already defined", keys.join("."))),
+		Entry::Vacant(e) => {
+			e.insert(value);
+			Ok(())
+		}
 	}
 }
 
+struct Parser<'a> {
+	src: &'a str,
+	pos: usize
+}
 
-impl SerializeStructVariant for ValueWrapper<(&'static str, HashMap<String, Value>)> {
-	type Ok    = Value;
-	type Error = SerError;
+impl<'a> Parser<'a> {
+	fn new(src: &'a str) -> Self {
+		// skip magic bytes for unicode text files
+		let pos = if src.as_bytes().starts_with(&UNICODE_FILE_MAGIC_BYTES) { 3 } else { 0 };
+		Self { src, pos }
+	}
 	
-	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
-		self.0.1.insert(key.to_string(), value.serialize(DummySerializer)?);
-		Ok(())
+	fn peek(&self) -> Option<u8> {
+		self.src.as_bytes().get(self.pos).copied()
 	}
 	
-	fn end(self) -> Result<Self::Ok, Self::Error> {
-		Ok(Value::Table(std::iter::once((self.0.0.to_string(), Value::Table(self.0.1))).collect()))
+	fn peek_at(&self, offset: usize) -> Option<u8> {
+		self.src.as_bytes().get(self.pos + offset).copied()
 	}
-}
-
-impl<'de> serde::Deserializer<'de> for Value {
-	type Error = DeError;
 	
-	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self {
-			Self::String(v)             => visitor.visit_string(v),
-			Self::Integer(v)            => visitor.visit_i64(v),
-			Self::Float(v)              => visitor.visit_f64(v),
-			Self::Bool(v)               => visitor.visit_bool(v),
-			Self::DateTime(_secs, _nanos) => unimplemented!(),
-			Self::Array(v)              => visitor.visit_seq(ValueWrapper(v.into_iter())),
-			Self::Table(v)              => visitor.visit_map(ValueWrapper(v.into_iter().peekable()))
+	fn rest(&self) -> &'a str {
+		&self.src[self.pos..]
+	}
+	
+	fn eat(&mut self, ch: u8) -> bool {
+		let eq = self.peek() == Some(ch);
+		if eq {
+			self.pos += 1;
 		}
+		eq
 	}
 	
-	forward_to_deserialize_any!(bool i64 f64 str string bytes byte_buf unit unit_struct
-		newtype_struct seq tuple tuple_struct map struct ignored_any);
+	fn expect(&mut self, ch: u8, exp: &str) -> Result<(), DeError> {
+		match self.eat(ch) {
+			true  => Ok(()),
+			false => Err(self.unexpected(exp))
+		}
+	}
 	
-	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_i8(v as _) } else { self.deserialize_any(visitor) }
+	fn error(&self, msg: impl fmt::Display) -> DeError {
+		error_at(self.src, self.pos, msg)
 	}
 	
-	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_i16(v as _) } else { self.deserialize_any(visitor) }
+	fn unexpected(&self, exp: &str) -> DeError {
+		match self.rest().chars().next() {
+			Some(ch) => self.error(format_args!("unexpected {:?}, expected {}", ch, exp)),
+			None => self.error(format_args!("unexpected end of file, expected {}", exp))
+		}
 	}
 	
-	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_i32(v as _) } else { self.deserialize_any(visitor) }
+	fn ws(&mut self) {
+		while matches!(self.peek(), Some(b' ' | b'\t')) {
+			self.pos += 1;
+		}
 	}
 	
-	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_u8(v as _) } else { self.deserialize_any(visitor) }
+	fn comment(&mut self) -> Result<(), DeError> {
+		if !self.eat(b'#') {
+			return Ok(());
+		}
+		
+		while let Some(ch) = self.rest().chars().next() {
+			match ch {
+				'\n' => break,
+				'\r' if self.peek_at(1) == Some(b'\n') => break,
+				ch if is_control(ch) => return Err(self.error(format_args!("control character {:?} in comment", ch))),
+				_ => self.pos += ch.len_utf8()
+			}
+		}
+		
+		Ok(())
 	}
 	
-	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_u16(v as _) } else { self.deserialize_any(visitor) }
+	fn newline(&mut self) -> bool {
+		if self.rest().starts_with("\r\n") {
+			self.pos += 2;
+			true
+		} else {
+			self.eat(b'\n')
+		}
 	}
 	
-	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_u32(v as _) } else { self.deserialize_any(visitor) }
+	/// Whitespace, comments and newlines, as allowed between array elements.
+	fn ws_comment_newline(&mut self) -> Result<(), DeError> {
+		loop {
+			self.ws();
+			self.comment()?;
+			if !self.newline() {
+				return Ok(());
+			}
+		}
 	}
 	
-	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Integer(v) = self { visitor.visit_u64(v as _) } else { self.deserialize_any(visitor) }
+	/// Whitespace, an optional comment and the end of the line.
+	fn line_end(&mut self) -> Result<(), DeError> {
+		self.ws();
+		self.comment()?;
+		match self.newline() || self.peek().is_none() {
+			true  => Ok(()),
+			false => Err(self.unexpected("a newline"))
+		}
 	}
 	
-	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if let Self::Float(v) = self { visitor.visit_f32(v as _) } else { self.deserialize_any(visitor) }
+	fn document(&mut self) -> Result<Document, DeError> {
+		let mut items = Vec::new();
+		
+		loop {
+			let start = self.pos;
+			self.ws();
+			
+			match self.peek() {
+				None | Some(b'#' | b'\n' | b'\r') => {
+					let eof = self.peek().is_none();
+					self.line_end()?;
+					
+					if start != self.pos {
+						match items.last_mut() {
+							Some(Item::Trivia(raw)) => raw.push_str(&self.src[start..self.pos]),
+							_ => items.push(Item::Trivia(self.src[start..self.pos].to_string()))
+						}
+					}
+					
+					if eof {
+						break;
+					}
+				}
+				Some(b'[') => {
+					self.pos += 1;
+					let array = self.eat(b'[');
+					self.ws();
+					let key = self.key()?;
+					self.ws();
+					self.expect(b']', "']'")?;
+					if array {
+						self.expect(b']', "']]'")?;
+					}
+					self.line_end()?;
+					items.push(Item::Header { raw: self.src[start..self.pos].to_string(), key, array });
+				}
+				Some(_) => {
+					let key = self.key()?;
+					self.ws();
+					self.expect(b'=', "'='")?;
+					self.ws();
+					let value_start = self.pos;
+					let value = self.value()?;
+					let value_end = self.pos;
+					self.line_end()?;
+					items.push(Item::KeyValue(KeyValue {
+						raw_key:   self.src[start..value_start].to_string(),
+						key,
+						raw_value: self.src[value_start..value_end].to_string(),
+						value,
+						suffix:    self.src[value_end..self.pos].to_string()
+					}));
+				}
+			}
+		}
+		
+		// keep the magic bytes in the first item so that the document is written back as read
+		if self.src.as_bytes().starts_with(&UNICODE_FILE_MAGIC_BYTES) {
+			match items.first_mut() {
+				Some(Item::Trivia(raw) | Item::Header { raw, .. }) => raw.insert(0, '\u{feff}'),
+				Some(Item::KeyValue(kv)) => kv.raw_key.insert(0, '\u{feff}'),
+				None => items.push(Item::Trivia('\u{feff}'.to_string()))
+			}
+		}
+		
+		Ok(Document { items })
 	}
 	
-	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self {
-			Self::String(s) if s.len() == 1 => visitor.visit_char(s.chars().next().unwrap()),
-			_ => self.deserialize_any(visitor)
+	fn key(&mut self) -> Result<Vec<String>, DeError> {
+		let mut keys = vec![self.simple_key()?];
+		
+		loop {
+			let save = self.pos;
+			self.ws();
+			
+			if !self.eat(b'.') {
+				self.pos = save;
+				return Ok(keys);
+			}
+			
+			self.ws();
+			keys.push(self.simple_key()?);
 		}
 	}
 	
-	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_some(self)
+	fn simple_key(&mut self) -> Result<String, DeError> {
+		match self.peek() {
+			Some(b'"') => {
+				self.pos += 1;
+				self.basic_string()
+			}
+			Some(b'\'') => {
+				self.pos += 1;
+				self.literal_string()
+			}
+			_ => {
+				let start = self.pos;
+				while matches!(self.peek(), Some(ch) if is_bare_key(ch)) {
+					self.pos += 1;
+				}
+				
+				match start == self.pos {
+					true  => Err(self.unexpected("a key")),
+					false => Ok(self.src[start..self.pos].to_string())
+				}
+			}
+		}
 	}
 	
-	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
-		match self {
-			Self::String(v)                => visitor.visit_enum(v.into_deserializer()),
-			Self::Integer(v) if v >= 0     => visitor.visit_enum(variants[v as usize].into_deserializer()),
-			Self::Table(v) if v.len() == 1 => visitor.visit_enum(Self::Table(v)),
-			_                              => self.deserialize_any(visitor)
+	fn value(&mut self) -> Result<Value, DeError> {
+		match self.peek() {
+			Some(b'"') if self.rest().starts_with("\"\"\"") => {
+				self.pos += 3;
+				self.ml_basic_string().map(Value::String)
+			}
+			Some(b'"') => {
+				self.pos += 1;
+				self.basic_string().map(Value::String)
+			}
+			Some(b'\'') if self.rest().starts_with("'''") => {
+				self.pos += 3;
+				self.ml_literal_string().map(Value::String)
+			}
+			Some(b'\'') => {
+				self.pos += 1;
+				self.literal_string().map(Value::String)
+			}
+			Some(b'[') => {
+				self.pos += 1;
+				self.array()
+			}
+			Some(b'{') => {
+				self.pos += 1;
+				self.inline_table()
+			}
+			Some(b't') if self.rest().starts_with("true") => {
+				self.pos += 4;
+				Ok(Value::Bool(true))
+			}
+			Some(b'f') if self.rest().starts_with("false") => {
+				self.pos += 5;
+				Ok(Value::Bool(false))
+			}
+			Some(b'0'..=b'9' | b'+' | b'-' | b'i' | b'n') => self.number_or_datetime(),
+			_ => Err(self.unexpected("a value"))
 		}
 	}
 	
-	fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
-		unreachable!()
+	fn escape(&mut self) -> Result<char, DeError> {
+		let ch = match self.peek() {
+			Some(b'b')  => '\u{8}',
+			Some(b't')  => '\t',
+			Some(b'n')  => '\n',
+			Some(b'f')  => '\u{c}',
+			Some(b'r')  => '\r',
+			Some(b'"')  => '"',
+			Some(b'\\') => '\\',
+			Some(ch @ (b'u' | b'U')) => {
+				let len = if ch == b'u' { 4 } else { 8 };
+				let ch = self.src.get(self.pos + 1..self.pos + 1 + len)
+					.filter(|s| s.bytes().all(|b| b.is_ascii_hexdigit()))
+					.and_then(|s| char::from_u32(u32::from_str_radix(s, 16).ok()?))
+					.ok_or_else(|| self.error("invalid unicode escape sequence"))?;
+				self.pos += len;
+				ch
+			}
+			_ => return Err(self.error("invalid escape sequence"))
+		};
+		
+		self.pos += 1;
+		Ok(ch)
 	}
-}
-
-impl<'de> EnumAccess<'de> for Value {
-	type Error   = DeError;
-	type Variant = Value;
 	
-	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
-		match self {
-			Self::Table(v) => {
-				let (key, val) = v.into_iter().next().unwrap();
-				let key = seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(key))?;
-				Ok((key, val))
+	fn basic_string(&mut self) -> Result<String, DeError> {
+		let mut s = String::new();
+		
+		loop {
+			match self.rest().chars().next() {
+				None => return Err(self.unexpected("'\"'")),
+				Some('"') => {
+					self.pos += 1;
+					return Ok(s);
+				}
+				Some('\\') => {
+					self.pos += 1;
+					s.push(self.escape()?);
+				}
+				Some(ch) if is_control(ch) => return Err(self.error(format_args!("control character {:?} in string", ch))),
+				Some(ch) => {
+					s.push(ch);
+					self.pos += ch.len_utf8();
+				}
 			}
-			_ => unreachable!()
 		}
 	}
-}
-
-impl<'de> VariantAccess<'de> for Value {
-	type Error = DeError;
 	
-	fn unit_variant(self) -> Result<(), Self::Error> {
-		Err(DeError::Custom("expected a string".to_string()))
+	fn ml_basic_string(&mut self) -> Result<String, DeError> {
+		self.newline();
+		let mut s = String::new();
+		
+		loop {
+			match self.rest().chars().next() {
+				None => return Err(self.unexpected("'\"\"\"'")),
+				Some('"') if self.rest().starts_with("\"\"\"") => {
+					let n = self.rest().bytes().take_while(|ch| *ch == b'"').count();
+					if n > 5 {
+						return Err(self.error("too many quotes at the end of a string"));
+					}
+					s.extend(std::iter::repeat_n('"', n - 3));
+					self.pos += n;
+					return Ok(s);
+				}
+				Some('\\') => {
+					self.pos += 1;
+					let save = self.pos;
+					self.ws();
+					
+					if self.newline() {
+						// line ending backslash, trim all whitespace up to the next content
+						while matches!(self.peek(), Some(b' ' | b'\t' | b'\n')) || self.rest().starts_with("\r\n") {
+							self.ws();
+							self.newline();
+						}
+					} else {
+						self.pos = save;
+						s.push(self.escape()?);
+					}
+				}
+				Some(_) if self.newline() => s.push('\n'),
+				Some(ch) if is_control(ch) => return Err(self.error(format_args!("control character {:?} in string", ch))),
+				Some(ch) => {
+					s.push(ch);
+					self.pos += ch.len_utf8();
+				}
+			}
+		}
 	}
 	
-	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
-		seed.deserialize(self)
+	fn literal_string(&mut self) -> Result<String, DeError> {
+		let start = self.pos;
+		
+		loop {
+			match self.rest().chars().next() {
+				None => return Err(self.unexpected("'''")),
+				Some('\'') => {
+					self.pos += 1;
+					return Ok(self.src[start..self.pos - 1].to_string());
+				}
+				Some(ch) if is_control(ch) => return Err(self.error(format_args!("control character {:?} in string", ch))),
+				Some(ch) => self.pos += ch.len_utf8()
+			}
+		}
 	}
 	
-	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
-		serde::Deserializer::deserialize_seq(self, visitor)
+	fn ml_literal_string(&mut self) -> Result<String, DeError> {
+		self.newline();
+		let mut s = String::new();
+		
+		loop {
+			match self.rest().chars().next() {
+				None => return Err(self.unexpected("\"'''\"")),
+				Some('\'') if self.rest().starts_with("'''") => {
+					let n = self.rest().bytes().take_while(|ch| *ch == b'\'').count();
+					if n > 5 {
+						return Err(self.error("too many quotes at the end of a string"));
+					}
+					s.extend(std::iter::repeat_n('\'', n - 3));
+					self.pos += n;
+					return Ok(s);
+				}
+				Some(_) if self.newline() => s.push('\n'),
+				Some(ch) if is_control(ch) => return Err(self.error(format_args!("control character {:?} in string", ch))),
+				Some(ch) => {
+					s.push(ch);
+					self.pos += ch.len_utf8();
+				}
+			}
+		}
 	}
 	
-	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
-		serde::Deserializer::deserialize_map(self, visitor)
+	fn array(&mut self) -> Result<Value, DeError> {
+		let mut values = Vec::new();
+		
+		loop {
+			self.ws_comment_newline()?;
+			
+			if self.eat(b']') {
+				return Ok(Value::Array(values));
+			}
+			
+			values.push(self.value()?);
+			self.ws_comment_newline()?;
+			
+			if !self.eat(b',') {
+				self.expect(b']', "',' or ']'")?;
+				return Ok(Value::Array(values));
+			}
+		}
 	}
-}
-
-struct ValueWrapper<T>(T);
-
-impl<'de> MapAccess<'de> for ValueWrapper<std::iter::Peekable<<std::collections::HashMap<String, Value> as IntoIterator>::IntoIter>> {
-	type Error = DeError;
 	
-	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
-		self.0.peek()
-			.map(|(v, _)| seed.deserialize(v.as_str().into_deserializer()))
-			.transpose()
+	fn inline_table(&mut self) -> Result<Value, DeError> {
+		let mut table = HashMap::new();
+		let mut dotted = HashSet::new();
+		self.ws();
+		
+		if self.eat(b'}') {
+			return Ok(Value::Table(table));
+		}
+		
+		loop {
+			self.ws();
+			let start = self.pos;
+			let key = self.key()?;
+			self.ws();
+			self.expect(b'=', "'='")?;
+			self.ws();
+			let value = self.value()?;
+			insert_dotted(&mut table, &mut dotted, &key, value)
+				.map_err(|msg| error_at(self.src, start, msg))?;
+			self.ws();
+			
+			if self.eat(b'}') {
+				return Ok(Value::Table(table));
+			}
+			
+			self.expect(b',', "',' or '}'")?;
+		}
 	}
 	
-	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
-		seed.deserialize(self.0.next().unwrap().1)
+	fn number_or_datetime(&mut self) -> Result<Value, DeError> {
+		let bytes = self.rest().as_bytes();
+		let digits = |n: usize| bytes.len() >= n && bytes[..n].iter().all(u8::is_ascii_digit);
+		
+		if digits(4) && bytes.get(4) == Some(&b'-') {
+			self.datetime().map(Value::DateTime)
+		} else if digits(2) && bytes.get(2) == Some(&b':') {
+			Ok(Value::DateTime(Datetime { date: None, time: Some(self.time()?), offset: None }))
+		} else {
+			self.number()
+		}
 	}
-}
-
-impl<'de> SeqAccess<'de> for ValueWrapper<<Vec<Value> as IntoIterator>::IntoIter> {
-	type Error = DeError;
 	
-	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
-		self.0.next()
-			.map(|v| seed.deserialize(v))
-			.transpose()
+	/// Digits with optional underscores between them.
+	fn digits(&mut self, is_digit: impl Fn(u8) -> bool) -> Result<String, DeError> {
+		let mut s = String::new();
+		
+		loop {
+			match self.peek() {
+				Some(ch) if is_digit(ch) => {
+					s.push(ch as char);
+					self.pos += 1;
+				}
+				Some(b'_') if !s.is_empty() && matches!(self.peek_at(1), Some(ch) if is_digit(ch)) => self.pos += 1,
+				_ if s.is_empty() => return Err(self.unexpected("a digit")),
+				Some(b'_') => return Err(self.error("underscores must be surrounded by digits")),
+				_ => return Ok(s)
+			}
+		}
 	}
-}
-
-#[derive(Debug)]
-pub struct Deserializer<T: io::BufRead> {
-	reader: T
-}
-
-impl<T: io::BufRead> Deserializer<T> {
-	pub fn new(mut reader: T) -> Self {
-		// consume magic bytes for unicode text files
-		if reader.fill_buf().unwrap_or(&[]).starts_with(&UNICODE_FILE_MAGIC_BYTES) {
-			reader.consume(3);
+	
+	fn number(&mut self) -> Result<Value, DeError> {
+		let start = self.pos;
+		let negative = match self.peek() {
+			Some(b'+') => { self.pos += 1; Some(false) }
+			Some(b'-') => { self.pos += 1; Some(true) }
+			_ => None
+		};
+		
+		if self.rest().starts_with("inf") {
+			self.pos += 3;
+			return Ok(Value::Float(if negative == Some(true) { f64::NEG_INFINITY } else { f64::INFINITY }));
 		}
 		
-		Self { reader }
+		if self.rest().starts_with("nan") {
+			self.pos += 3;
+			return Ok(Value::Float(if negative == Some(true) { -f64::NAN } else { f64::NAN }));
+		}
+		
+		let radix = match (self.peek(), self.peek_at(1)) {
+			(Some(b'0'), Some(b'x')) => 16,
+			(Some(b'0'), Some(b'o')) => 8,
+			(Some(b'0'), Some(b'b')) => 2,
+			_ => 10
+		};
+		
+		if radix != 10 {
+			if negative.is_some() {
+				return Err(error_at(self.src, start, "non-decimal integers must not have a sign"));
+			}
+			
+			self.pos += 2;
+			let digits = self.digits(|ch| (ch as char).is_digit(radix))?;
+			return i64::from_str_radix(&digits, radix)
+				.map(Value::Integer)
+				.map_err(|_| error_at(self.src, start, "integer out of range"));
+		}
+		
+		let int_start = self.pos;
+		let int = self.digits(|ch| ch.is_ascii_digit())?;
+		
+		if int.len() > 1 && int.starts_with('0') {
+			return Err(error_at(self.src, int_start, "leading zeros are not allowed"));
+		}
+		
+		let mut text = String::from(if negative == Some(true) { "-" } else { "" });
+		let mut float = false;
+		text.push_str(&int);
+		
+		if self.eat(b'.') {
+			float = true;
+			text.push('.');
+			text.push_str(&self.digits(|ch| ch.is_ascii_digit())?);
+		}
+		
+		if matches!(self.peek(), Some(b'e' | b'E')) {
+			self.pos += 1;
+			float = true;
+			text.push('e');
+			
+			match self.peek() {
+				Some(b'+') => self.pos += 1,
+				Some(b'-') => {
+					self.pos += 1;
+					text.push('-');
+				}
+				_ => ()
+			}
+			
+			text.push_str(&self.digits(|ch| ch.is_ascii_digit())?);
+		}
+		
+		match float {
+			true  => text.parse().map(Value::Float).map_err(|e| error_at(self.src, start, e)),
+			false => text.parse().map(Value::Integer).map_err(|_| error_at(self.src, start, "integer out of range"))
+		}
 	}
 	
-	fn peek(&mut self) -> io::Result<u8> {
-		match self.reader.fill_buf()? {
-			[ch, ..] => Ok(*ch),
-			_ => Err(io::Error::new(io::ErrorKind::UnexpectedEof, ""))
+	fn fixed_digits(&mut self, n: usize) -> Result<u32, DeError> {
+		let v = self.src.get(self.pos..self.pos + n)
+			.filter(|s| s.bytes().all(|ch| ch.is_ascii_digit()))
+			.ok_or_else(|| self.unexpected("a digit"))?
+			.parse()
+			.unwrap();
+		self.pos += n;
+		Ok(v)
+	}
+	
+	fn date(&mut self) -> Result<Date, DeError> {
+		let start = self.pos;
+		let year = self.fixed_digits(4)?;
+		self.expect(b'-', "'-'")?;
+		let month = self.fixed_digits(2)?;
+		self.expect(b'-', "'-'")?;
+		let day = self.fixed_digits(2)?;
+		
+		if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+			return Err(error_at(self.src, start, "invalid date"));
 		}
+		
+		Ok(Date { year: year as _, month: month as _, day: day as _ })
 	}
 	
-	fn next(&mut self) -> io::Result<u8> {
-		loop {
-			match self.reader.fill_buf()? {
-				[ch, ..] if ch.is_ascii_whitespace() => self.reader.consume(1),
-				[ch, ..] => {
-					let ch = *ch;
-					self.reader.consume(1);
-					return Ok(ch)
-				}
-				[] => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, ""))
+	fn time(&mut self) -> Result<Time, DeError> {
+		let start = self.pos;
+		let hour = self.fixed_digits(2)?;
+		self.expect(b':', "':'")?;
+		let minute = self.fixed_digits(2)?;
+		self.expect(b':', "':'")?;
+		let second = self.fixed_digits(2)?;
+		let mut nanosecond = 0;
+		
+		if self.eat(b'.') {
+			let frac_start = self.pos;
+			while matches!(self.peek(), Some(ch) if ch.is_ascii_digit()) {
+				self.pos += 1;
+			}
+			
+			if frac_start == self.pos {
+				return Err(self.unexpected("a digit"));
 			}
+			
+			// digits beyond nanosecond precision are truncated
+			nanosecond = self.src[frac_start..self.pos].bytes()
+				.chain(std::iter::repeat(b'0'))
+				.take(9)
+				.fold(0, |acc, ch| acc * 10 + (ch - b'0') as u32);
 		}
+		
+		if hour > 23 || minute > 59 || second > 60 {
+			return Err(error_at(self.src, start, "invalid time"));
+		}
+		
+		Ok(Time { hour: hour as _, minute: minute as _, second: second as _, nanosecond })
 	}
-}
-
-/*pub struct Serializer<T: io::Write> {
-	writer: T,
-	first:  bool
-}
-
-impl<T: io::Write> Serializer<T> {
-	pub fn new(writer: T) -> Self {
-		Self { writer, first: false }
+	
+	fn datetime(&mut self) -> Result<Datetime, DeError> {
+		let date = self.date()?;
+		let has_time = match (self.peek(), self.peek_at(1), self.peek_at(2), self.peek_at(3)) {
+			(Some(b'T' | b't'), ..) => true,
+			(Some(b' '), Some(a), Some(b), Some(b':')) => a.is_ascii_digit() && b.is_ascii_digit(),
+			_ => false
+		};
+		
+		if !has_time {
+			return Ok(Datetime { date: Some(date), time: None, offset: None });
+		}
+		
+		self.pos += 1;
+		let time = self.time()?;
+		let offset = match self.peek() {
+			Some(b'Z' | b'z') => {
+				self.pos += 1;
+				Some(Offset::Z)
+			}
+			Some(sign @ (b'+' | b'-')) => {
+				let start = self.pos;
+				self.pos += 1;
+				let hours = self.fixed_digits(2)?;
+				self.expect(b':', "':'")?;
+				let minutes = self.fixed_digits(2)?;
+				
+				if hours > 23 || minutes > 59 {
+					return Err(error_at(self.src, start, "invalid offset"));
+				}
+				
+				let minutes = (hours * 60 + minutes) as i16;
+				Some(Offset::Custom(if sign == b'-' { -minutes } else { minutes }))
+			}
+			_ => None
+		};
+		
+		Ok(Datetime { date: Some(date), time: Some(time), offset })
 	}
 }
 
-impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
-	type Ok                     = ();
+/// Serializes into a `Value`, `None` is only returned for `None` options, which are skipped
+/// in tables, as TOML has no null value.
+struct DummySerializer;
+
+impl serde::Serializer for DummySerializer {
+	type Ok                     = Option<Value>;
 	type Error                  = SerError;
-	type SerializeSeq           = Self;
-	type SerializeTuple         = Self;
-	type SerializeTupleStruct   = Self;
-	type SerializeTupleVariant  = Self;
-	type SerializeMap           = Self;
-	type SerializeStruct        = Self;
-	type SerializeStructVariant = Self;
+	type SerializeSeq           = ValueWrapper<Vec<Value>>;
+	type SerializeTuple         = ValueWrapper<Vec<Value>>;
+	type SerializeTupleStruct   = ValueWrapper<Vec<Value>>;
+	type SerializeTupleVariant  = ValueWrapper<(&'static str, Vec<Value>)>;
+	type SerializeMap           = ValueWrapper<(HashMap<String, Value>, Option<String>)>;
+	type SerializeStruct        = ValueWrapper<(HashMap<String, Value>, Option<String>)>;
+	type SerializeStructVariant = ValueWrapper<(&'static str, HashMap<String, Value>)>;
 	
 	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-		match v {
-			true => self.writer.write_all(b"true"),
-			false => self.writer.write_all(b"false"),
-		}.map_err(Into::into)
+		Ok(Some(Value::Bool(v)))
 	}
 	
 	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v)))
 	}
 	
 	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Integer(v as _)))
 	}
 	
 	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		i64::try_from(v)
+			.map(|v| Some(Value::Integer(v)))
+			.map_err(|_| SerError::Custom(format!("integer {} is out of range for TOML", v)))
 	}
 	
 	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Float(v as _)))
 	}
 	
 	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "{}", v).map_err(Into::into)
+		Ok(Some(Value::Float(v)))
 	}
 	
 	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "\"{}\"", v).map_err(Into::into)
+		Ok(Some(Value::String(v.to_string())))
 	}
 	
 	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-		write!(&mut self.writer, "\"{}\"", v).map_err(Into::into)
+		Ok(Some(Value::String(v.to_string())))
 	}
 	
 	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-		let mut s = self.serialize_seq(Some(v.len()))?;
-		v.iter().try_for_each(|b| SerializeSeq::serialize_element(&mut s, &b))?;
-		SerializeSeq::end(s)
+		Ok(Some(Value::Array(v.iter().map(|v| Value::Integer(*v as _)).collect())))
 	}
 	
 	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-		self.serialize_unit()
+		Ok(None)
 	}
 	
-	fn serialize_some<S: ?Sized + Serialize>(self, value: &S) -> Result<Self::Ok, Self::Error> {
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
 		value.serialize(self)
 	}
 	
 	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"{}").map_err(Into::into)
+		Ok(Some(Value::Table(HashMap::new())))
 	}
 	
 	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
@@ -707,81 +1741,71 @@ impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
 	}
 	
 	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
-		variant.serialize(self)
+		Ok(Some(Value::String(variant.to_string())))
 	}
 	
-	fn serialize_newtype_struct<S: ?Sized + Serialize>(self, _name: &'static str, value: &S) -> Result<Self::Ok, Self::Error> {
-		value.serialize(self)
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		match (name, value.serialize(self)?) {
+			(DATETIME_NAME, Some(Value::String(v))) => v.parse()
+				.map(|v| Some(Value::DateTime(v)))
+				.map_err(|e| SerError::Custom(e.to_string())),
+			(_, v) => Ok(v)
+		}
 	}
 	
-	fn serialize_newtype_variant<S: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, value: &S) -> Result<Self::Ok, Self::Error> {
-		value.serialize(self)
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		let value = value.serialize(self)?.ok_or(SerError::UnsupportedType)?;
+		Ok(Some(Value::Table(std::iter::once((variant.to_string(), value)).collect())))
 	}
 	
 	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-		self.first = true;
-		self.writer.write_all(b"[ ")?;
-		Ok(self)
+		Ok(ValueWrapper(Vec::new()))
 	}
 	
-	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-		self.serialize_seq(Some(len))
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Ok(ValueWrapper(Vec::new()))
 	}
 	
-	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
-		self.serialize_seq(Some(len))
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Ok(ValueWrapper(Vec::new()))
 	}
 	
-	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
-		self.writer.write_all(b"{ ")?;
-		self.writer.write_all(variant.as_bytes())?;
-		self.writer.write_all(b" = [ ")?;
-		Ok(self)
+	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(ValueWrapper((variant, Vec::new())))
 	}
 	
 	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		self.first = true;
-		self.writer.write_all(b"{ ")?;
-		Ok(self)
-	}
-	
-	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
-		self.serialize_map(Some(len))
+		Ok(ValueWrapper((HashMap::new(), None)))
 	}
 	
-	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
-		self.serialize_map(Some(len))
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(ValueWrapper((HashMap::new(), None)))
 	}
 	
-	fn is_human_readable(&self) -> bool {
-		true
+	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(ValueWrapper((variant, HashMap::new())))
 	}
 }
 
-impl<'a, T: io::Write> SerializeSeq for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeSeq for ValueWrapper<Vec<Value>> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_element<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
-		if !self.first {
-			self.first = false;
-			self.writer.write_all(b", ")?;
-		}
-		
-		value.serialize(&mut**self)
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.0.push(value.serialize(DummySerializer)?.ok_or(SerError::UnsupportedType)?);
+		Ok(())
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b" ]")?;
-		Ok(())
+		Ok(Some(Value::Array(self.0)))
 	}
 }
 
-impl<'a, T: io::Write> SerializeTuple for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeTuple for ValueWrapper<Vec<Value>> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_element<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
 		SerializeSeq::serialize_element(self, value)
 	}
 	
@@ -790,11 +1814,11 @@ impl<'a, T: io::Write> SerializeTuple for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeTupleStruct for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeTupleStruct for ValueWrapper<Vec<Value>> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_field<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
 		SerializeSeq::serialize_element(self, value)
 	}
 	
@@ -803,362 +1827,273 @@ impl<'a, T: io::Write> SerializeTupleStruct for &'a mut Serializer<T> {
 	}
 }
 
-impl<'a, T: io::Write> SerializeTupleVariant for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeTupleVariant for ValueWrapper<(&'static str, Vec<Value>)> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_field<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
-		SerializeSeq::serialize_element(self, value)
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.0.1.push(value.serialize(DummySerializer)?.ok_or(SerError::UnsupportedType)?);
+		Ok(())
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		SerializeSeq::end(self)
+		Ok(Some(Value::Table(std::iter::once((self.0.0.to_string(), Value::Array(self.0.1))).collect())))
 	}
 }
 
-impl<'a, T: io::Write> SerializeMap for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeMap for ValueWrapper<(HashMap<String, Value>, Option<String>)> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_key<S: ?Sized + Serialize>(&mut self, key: &S) -> Result<(), Self::Error> {
-		self.writer.write_all(b"\n")?;
-		key.serialize(&mut**self)
+	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.0.1 = Some(match key.serialize(DummySerializer)? {
+			Some(Value::String(v)) => v,
+			Some(Value::Integer(v)) => v.to_string(),
+			Some(Value::Bool(v)) => v.to_string(),
+			_ => return Err(SerError::Custom("keys must be strings, integers or booleans".to_string()))
+		});
+		Ok(())
 	}
 	
-	fn serialize_value<S: ?Sized + Serialize>(&mut self, value: &S) -> Result<(), Self::Error> {
-		self.writer.write_all(b" = ")?;
-		value.serialize(&mut**self)
+	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let key = self.0.1.take().expect("`serialize_key` must be called before `serialize_value`");
+		if let Some(value) = value.serialize(DummySerializer)? {
+			self.0.0.insert(key, value);
+		}
+		Ok(())
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"\n")?;
-		Ok(())
+		Ok(Some(Value::Table(self.0.0)))
 	}
 }
 
-impl<'a, T: io::Write> SerializeStruct for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeStruct for ValueWrapper<(HashMap<String, Value>, Option<String>)> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_field<S: ?Sized + Serialize>(&mut self, key: &'static str, value: &S) -> Result<(), Self::Error> {
-		self.writer.write_all(b"\n")?;
-		key.serialize(&mut**self)?;
-		self.writer.write_all(b" = ")?;
-		value.serialize(&mut**self)
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		SerializeMap::serialize_entry(self, key, value)
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.writer.write_all(b"\n")?;
-		Ok(())
+		SerializeMap::end(self)
 	}
 }
 
-impl<'a, T: io::Write> SerializeStructVariant for &'a mut Serializer<T> {
-	type Ok    = ();
+impl SerializeStructVariant for ValueWrapper<(&'static str, HashMap<String, Value>)> {
+	type Ok    = Option<Value>;
 	type Error = SerError;
 	
-	fn serialize_field<S: ?Sized + Serialize>(&mut self, key: &'static str, value: &S) -> Result<(), Self::Error> {
-		SerializeStruct::serialize_field(self, key, value)
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		if let Some(value) = value.serialize(DummySerializer)? {
+			self.0.1.insert(key.to_string(), value);
+		}
+		Ok(())
 	}
 	
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		SerializeStruct::end(self)
+		Ok(Some(Value::Table(std::iter::once((self.0.0.to_string(), Value::Table(self.0.1))).collect())))
 	}
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum State {
-	Uninit,
-	Default
-}
-
-#[derive(Debug)]
-pub struct Deserializer<T: io::BufRead> {
-	reader: T,
-	first:  bool,
-	depth:  usize,
-	is_map: bool,
-	state:  State
-}
-
-impl<T: io::BufRead> Deserializer<T> {
-	pub fn from_reader(mut reader: T) -> Self {
-		// consume magic bytes for unicode text files
-		if reader.fill_buf().unwrap_or(&[]).starts_with(&crate::utils::UNICODE_FILE_MAGIC_BYTES) {
-			reader.consume(3);
+impl<'de> serde::Deserializer<'de> for Value {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self {
+			Self::String(v)   => visitor.visit_string(v),
+			Self::Integer(v)  => visitor.visit_i64(v),
+			Self::Float(v)    => visitor.visit_f64(v),
+			Self::Bool(v)     => visitor.visit_bool(v),
+			Self::DateTime(v) => visitor.visit_string(v.to_string()),
+			Self::Array(v)    => visitor.visit_seq(ValueWrapper(v.into_iter())),
+			Self::Table(v)    => visitor.visit_map(ValueWrapper(v.into_iter().peekable()))
+		}
+	}
+	
+	forward_to_deserialize_any!(bool i64 f64 str string bytes byte_buf unit unit_struct
+		seq tuple tuple_struct map struct ignored_any);
+	
+	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_i8(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_i16(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_i32(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_u8(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_u16(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_u32(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Integer(v) = self { visitor.visit_u64(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if let Self::Float(v) = self { visitor.visit_f32(v as _) } else { self.deserialize_any(visitor) }
+	}
+	
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self {
+			Self::String(s) if s.len() == 1 => visitor.visit_char(s.chars().next().unwrap()),
+			_ => self.deserialize_any(visitor)
 		}
-		
-		Self { reader, is_map: true, first: true, state: State::Uninit, depth: 0 }
 	}
 	
-	fn next(&mut self, exp: &str) -> io::Result<u8> {
-		match self.reader.fill_buf()? {
-			[ch, ..] => {
-				let ch = *ch;
-				self.reader.consume(1);
-				Ok(ch)
-			},
-			[] => Err(io::Error::new(io::ErrorKind::UnexpectedEof, exp.to_string()))
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		match self {
+			Self::String(v)                => visitor.visit_enum(v.into_deserializer()),
+			Self::Integer(v) if v >= 0     => visitor.visit_enum(variants[v as usize].into_deserializer()),
+			Self::Table(v) if v.len() == 1 => visitor.visit_enum(Self::Table(v)),
+			_                              => self.deserialize_any(visitor)
 		}
 	}
+	
+	fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+		unreachable!()
+	}
 }
 
-impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<T> {
-	type Error = DeError;
+impl<'de> EnumAccess<'de> for Value {
+	type Error   = DeError;
+	type Variant = Value;
 	
-	#[allow(clippy::unused_io_amount)]
-	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		if self.state == State::Uninit {
-			self.state = State::Default;
-			return visitor.visit_map(self);
-		}
-		
-		match self.next("expected a value")? {
-			mut ch if ch.is_ascii_digit() || [b'+', b'-', b'i', b'n'].contains(&ch) => {
-				let mut sign = true;
-				
-				if ch == b'-' || ch == b'+' {
-					if ch == b'-' { sign = false; }
-					ch = self.reader.fill_buf()?[0];
-					self.reader.consume(1);
-				}
-				
-				match ch {
-					b'i' => {
-						let mut buf = [0u8; 2];
-						self.reader.read(&mut buf)?;
-						return match &buf {
-							b"nf" => visitor.visit_f64(if sign { f64::INFINITY } else { f64::NEG_INFINITY }),
-							_ => Err(DeError::invalid_token("", "`inf`"))
-						}
-					}
-					b'n' => {
-						let mut buf = [0u8; 2];
-						self.reader.read(&mut buf)?;
-						return match &buf {
-							b"an" => visitor.visit_f64(if sign { f64::NAN } else { -f64::NAN }),
-							_ => Err(DeError::invalid_token("", "`nan`"))
-						}
-					}
-					_ch => {
-						/*let radix = if ch == b'0' {
-							match self.next("a number")? {
-								'b' => 2,
-								'o' => 8,
-								'x' => 16,
-								c   => {
-									ch = c;
-									10
-								}
-							}
-						} else {
-							10
-						};
-						
-						let mut val = 0u64;
-						
-						'int: {
-						
-						}
-						
-						unimplemented!()
-						
-						
-						
-						loop {
-							match ch {
-								ch if ch.is_ascii_whitespace() => break,
-								'_' => (),
-								ch @ 'e' | ch @ 'E' | ch @ '.' => {
-									float = true;
-									self.buf.push(ch);
-								}
-								ch => self.buf.push(ch)
-							}
-							
-							match self.next()? {
-								ch if ch.is_ascii_whitespace() => break,
-								'_' => (),
-								ch @ 'e' | ch @ 'E' | ch @ '.' => {
-									float = true;
-									self.buf.push(ch);
-								}
-								ch => self.buf.push(ch)
-							}
-						}
-						
-						match (sign, float) {
-							(false, false) => visitor.visit_u64(unimplemented!()),
-							(true,  false) => visitor.visit_i64(unimplemented!()),
-							(_,     true)  => visitor.visit_f64(unimplemented!()),
-						}*/
-						
-						unimplemented!("number parsing")
-					}
-				}
-			}
-			b't' => {
-				let mut buf = [0u8; 3];
-				self.reader.read(&mut buf)?;
-				match &buf {
-					b"rue" => visitor.visit_bool(true),
-					_ => Err(DeError::invalid_token("", "`true`"))
-				}
-			}
-			b'f' => {
-				let mut buf = [0u8; 4];
-				self.reader.read(&mut buf)?;
-				match &buf {
-					b"alse" => visitor.visit_bool(false),
-					_ => Err(DeError::invalid_token("", "`false`"))
-				}
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		match self {
+			Self::Table(v) => {
+				let (key, val) = v.into_iter().next().unwrap();
+				let key = seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(key))?;
+				Ok((key, val))
 			}
-			b'\'' => visitor.visit_string(String::from_utf8(std::iter::repeat_with(|| self.next("'''"))
-				.take_while(|ch| !matches!(ch, Ok(b'\'')))
-				.collect::<Result<Vec<_>, _>>()?)
-				.unwrap()),
-			b'"' => visitor.visit_string(String::from_utf8(std::iter::repeat_with(|| self.next("'\"'"))
-				.take_while(|ch| !matches!(ch, Ok(b'"')))
-				.collect::<Result<Vec<_>, _>>()?)
-				.unwrap()),
-			b'[' => visitor.visit_seq(self),
-			b'{' => visitor.visit_map(self),
-			ch   => Err(DeError::invalid_token(&format!("'{}'", ch), "a value"))
+			_ => unreachable!()
 		}
 	}
+}
+
+impl<'de> VariantAccess<'de> for Value {
+	type Error = DeError;
 	
-	forward_to_deserialize_any! {
-		bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit
-		unit_struct newtype_struct seq tuple tuple_struct map struct enum ignored_any
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		Err(DeError::Custom("expected a string".to_string()))
 	}
 	
-	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		let mut buf = Vec::new();
-		
-		let end = match self.reader.fill_buf()?.first().copied() {
-			Some(end @ b'\'' | end @ b'"') => Some(end),
-			Some(ch) if ch.is_ascii_alphanumeric() || ch == b'_' || ch == b'-' => {
-				buf.push(ch);
-				self.reader.consume(1);
-				None
-			}
-			Some(ch) => return Err(DeError::invalid_char(ch as _, "a key (A-Za-z0-9_-))")),
-			None => return Err(DeError::unexpected_eof("expected a key"))
-		};
-		
-		loop {
-			match (self.reader.fill_buf()?, end) {
-				([ch, ..], _) if ch.is_ascii_alphanumeric() || *ch == b'_' || *ch == b'-' => buf.push(*ch),
-				([ch, ..], Some(end)) if *ch == end => {
-					self.reader.consume(1);
-					break;
-				}
-				([ch, ..], None) if ch.is_ascii_whitespace() || *ch == b'.' || *ch == b'=' => break,
-				([ch, ..], _) => return Err(DeError::invalid_char(*ch as _, "a key (A-Za-z0-9_-))")),
-				([],       _) => return Err(DeError::unexpected_eof("expected a key"))
-			}
-		}
-		
-		visitor.visit_string(String::from_utf8(buf).unwrap())
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		seed.deserialize(self)
 	}
 	
-	fn is_human_readable(&self) -> bool {
-		true
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_seq(self, visitor)
+	}
+	
+	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_map(self, visitor)
 	}
 }
 
-impl<'a, 'de, T: io::BufRead> MapAccess<'de> for &'a mut Deserializer<T> {
+struct ValueWrapper<T>(T);
+
+impl<'de> MapAccess<'de> for ValueWrapper<std::iter::Peekable<<std::collections::HashMap<String, Value> as IntoIterator>::IntoIter>> {
 	type Error = DeError;
 	
 	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
-		// TODO comments
-		
-		let mut delimiter = self.first;
-		
-		if self.first {
-			self.first = false;
-		}
-		
-		loop {
-			match (self.reader.fill_buf()?, delimiter) {
-				([b'\n', ..], _) | ([b',', ..], false) => delimiter = true,
-				([ch, ..], _) if ch.is_ascii_whitespace() => self.reader.consume(1),
-				([b'}', ..], _) => {
-					self.reader.consume(1);
-					return Ok(None)
-				}
-				([b'[', ..], _) | ([], _) => return Ok(None),
-				([_,  ..], true)  => break,
-				([ch, ..], false) => return Err(DeError::invalid_char(*ch as _, "'\\n', ',', '}', '[' or whitespace"))
-			}
-		}
-		
-		seed.deserialize(&mut**self).map(Some)
+		self.0.peek()
+			.map(|(v, _)| seed.deserialize(v.as_str().into_deserializer()))
+			.transpose()
 	}
 	
 	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
-		let mut delimiter = false;
-		
-		loop {
-			match (self.reader.fill_buf()?, delimiter) {
-				([b'=', ..], false) => delimiter = true,
-				([ch,   ..], _) if ch.is_ascii_whitespace() => self.reader.consume(1),
-				([_,    ..], true)  => break,
-				([ch,   ..], false) => return Err(DeError::invalid_char(*ch as _, "'=' or whitespace")),
-				([],         true)  => return Err(DeError::unexpected_eof("a value")),
-				([],         false) => return Err(DeError::unexpected_eof("'=' or whitespace"))
-			}
-		}
-		
-		seed.deserialize(&mut**self)
+		seed.deserialize(self.0.next().unwrap().1)
 	}
 }
 
-impl<'a, 'de, T: io::BufRead> SeqAccess<'de> for &'a mut Deserializer<T> {
+impl<'de> SeqAccess<'de> for ValueWrapper<<Vec<Value> as IntoIterator>::IntoIter> {
 	type Error = DeError;
 	
-	fn next_element_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<Option<V::Value>, Self::Error> {
-		let mut delimiter = self.first;
-		
-		if self.first {
-			self.first = false;
-		}
-		
-		loop {
-			match (self.reader.fill_buf()?, delimiter) {
-				([b']', ..], _) => {
-					self.reader.consume(1);
-					return Ok(None);
-				}
-				([b',', ..], false) => delimiter = true,
-				([ch,   ..], _) if ch.is_ascii_whitespace() => self.reader.consume(1),
-				([_,    ..], true) => break,
-				([ch,   ..], false) => return Err(DeError::invalid_char(*ch as _, "',', ']' or whitespace")),
-				([],         true)  => return Err(DeError::unexpected_eof("a value")),
-				([],         false) => return Err(DeError::unexpected_eof("',', ']' or whitespace"))
-			}
-		}
-		
-		seed.deserialize(&mut**self).map(Some)
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		self.0.next()
+			.map(|v| seed.deserialize(v))
+			.transpose()
 	}
-}*/
+}
+
+/// Reads a whole TOML document, TOML can not be deserialized in a streaming fashion, as tables
+/// may be defined in any order.
+#[derive(Debug)]
+pub struct Deserializer<T: io::BufRead> {
+	reader: T
+}
+
+impl<T: io::BufRead> Deserializer<T> {
+	pub fn new(reader: T) -> Self {
+		Self { reader }
+	}
+	
+	pub fn into_value(self) -> Result<Value, DeError> {
+		Value::deserialize(self.reader)
+	}
+}
+
+impl<'de, T: io::BufRead> serde::Deserializer<'de> for Deserializer<T> {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.into_value()?.deserialize_any(visitor)
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		self.into_value()?.deserialize_struct(name, fields, visitor)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		self.into_value()?.deserialize_enum(name, variants, visitor)
+	}
+	
+	forward_to_deserialize_any!(bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+		option unit unit_struct newtype_struct seq tuple tuple_struct map identifier ignored_any);
+}
 
 #[cfg(test)]
 mod tests {
-	use serde::Deserialize;
+	use {super::*, serde::{Serialize, Deserialize}};
 	
-	#[derive(Debug, Eq, PartialEq, Deserialize)]
+	#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 	struct Struct {
 		a: bool,
 		b: String,
 		c: Struct2
 	}
 	
-	#[derive(Debug, Eq, PartialEq, Deserialize)]
+	#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 	struct Struct2 {
 		a: String
 	}
 	
+	fn parse(src: &str) -> Value {
+		Document::parse(src).unwrap().to_value().unwrap()
+	}
+	
 	#[test]
 	fn simple() {
 		assert_eq!(super::deserialize::<Struct, _>(&mut r#"
@@ -1169,4 +2104,170 @@ mod tests {
 		a = "test"
 		"#.as_bytes()).unwrap(), Struct { a: true, b: "test".to_string(), c: Struct2 { a: "test".to_string() } });
 	}
-}
\ No newline at end of file
+	
+	#[test]
+	fn numbers() {
+		let v = parse("a = +99\nb = -17\nc = 1_000\nd = 0xDEAD_beef\ne = 0o755\nf = 0b1101\n\
+			g = 2.5\nh = -0.01\ni = 5e+22\nj = 6.626e-34\nk = 224_617.445_991\nl = -inf\nm = nan\n");
+		assert_eq!(v.get(&["a"]), Some(&Value::Integer(99)));
+		assert_eq!(v.get(&["b"]), Some(&Value::Integer(-17)));
+		assert_eq!(v.get(&["c"]), Some(&Value::Integer(1000)));
+		assert_eq!(v.get(&["d"]), Some(&Value::Integer(0xdead_beef)));
+		assert_eq!(v.get(&["e"]), Some(&Value::Integer(0o755)));
+		assert_eq!(v.get(&["f"]), Some(&Value::Integer(0b1101)));
+		assert_eq!(v.get(&["g"]), Some(&Value::Float(2.5)));
+		assert_eq!(v.get(&["h"]), Some(&Value::Float(-0.01)));
+		assert_eq!(v.get(&["i"]), Some(&Value::Float(5e22)));
+		assert_eq!(v.get(&["j"]), Some(&Value::Float(6.626e-34)));
+		assert_eq!(v.get(&["k"]), Some(&Value::Float(224_617.445_991)));
+		assert_eq!(v.get(&["l"]), Some(&Value::Float(f64::NEG_INFINITY)));
+		assert!(v.get(&["m"]).and_then(Value::as_float).unwrap().is_nan());
+		
+		for src in ["a = 01", "a = 1__0", "a = _1", "a = 1_", "a = -0x1", "a = 1.", "a = .1", "a = 9223372036854775808"] {
+			assert!(Document::parse(src).is_err(), "{}", src);
+		}
+	}
+	
+	#[test]
+	fn strings() {
+		let v = parse(concat!(
+			"a = \"tab\\there \\u00E9\\U0001F600\"\n",
+			"b = '''\nraw \\n '' text'''\n",
+			"c = \"\"\"\nThe quick \\\n\n   brown fox.\"\"\"\n",
+			"d = 'C:\\path'\n",
+			"e = \"\"\"two quotes\"\"\"\"\"\n"
+		));
+		assert_eq!(v.get(&["a"]).and_then(Value::as_string), Some("tab\there \u{e9}\u{1f600}"));
+		assert_eq!(v.get(&["b"]).and_then(Value::as_string), Some("raw \\n '' text"));
+		assert_eq!(v.get(&["c"]).and_then(Value::as_string), Some("The quick brown fox."));
+		assert_eq!(v.get(&["d"]).and_then(Value::as_string), Some("C:\\path"));
+		assert_eq!(v.get(&["e"]).and_then(Value::as_string), Some("two quotes\"\""));
+		assert!(Document::parse("a = \"\\x\"").is_err());
+		assert!(Document::parse("a = \"unterminated").is_err());
+	}
+	
+	#[test]
+	fn datetimes() {
+		let v = parse("a = 1979-05-27T00:32:00.999999-07:00\nb = 1979-05-27 07:32:00Z\nc = 1979-05-27T07:32:00\n\
+			d = 1979-05-27\ne = 00:32:00.5\n");
+		let a = v.get(&["a"]).and_then(Value::as_datetime).unwrap();
+		assert_eq!(a.offset, Some(Offset::Custom(-7 * 60)));
+		assert_eq!(a.time.unwrap().nanosecond, 999_999_000);
+		assert_eq!(a.to_string(), "1979-05-27T00:32:00.999999-07:00");
+		assert_eq!(a.unix_timestamp(), Some((296_638_320, 999_999_000)));
+		assert_eq!(v.get(&["b"]).and_then(Value::as_datetime).unwrap().unix_timestamp(), Some((296_638_320, 0)));
+		assert_eq!(v.get(&["c"]).and_then(Value::as_datetime).unwrap().offset, None);
+		assert_eq!(v.get(&["d"]).and_then(Value::as_datetime).unwrap().to_string(), "1979-05-27");
+		assert_eq!(v.get(&["e"]).and_then(Value::as_datetime).unwrap().to_string(), "00:32:00.5");
+		assert_eq!(Datetime::from_unix_timestamp(296_638_320, 0).to_string(), "1979-05-27T07:32:00Z");
+		assert!(Document::parse("a = 2021-02-29").is_err());
+		assert!(Document::parse("a = 2021-01-01T25:00:00").is_err());
+	}
+	
+	#[test]
+	fn tables() {
+		let v = parse(r#"
+			name = { first = "Tom", last.name = "Preston-Werner" }
+			physical.color = "orange"
+			physical.shape = "round"
+			site."google.com" = true
+			
+			[a.b.c]
+			d = 1
+			
+			[a]
+			e = [ 1, "two", [ 3 ], { four = 4 }, ] # mixed
+			
+			[[fruits]]
+			name = "apple"
+			
+			[fruits.physical]
+			color = "red"
+			
+			[[fruits]]
+			name = "banana"
+		"#);
+		assert_eq!(v.get(&["name", "last", "name"]).and_then(Value::as_string), Some("Preston-Werner"));
+		assert_eq!(v.get(&["physical", "shape"]).and_then(Value::as_string), Some("round"));
+		assert_eq!(v.get(&["site", "google.com"]), Some(&Value::Bool(true)));
+		assert_eq!(v.get(&["a", "b", "c", "d"]), Some(&Value::Integer(1)));
+		assert_eq!(v.get(&["a", "e"]).and_then(Value::as_array).map(<[_]>::len), Some(4));
+		let fruits = v.get(&["fruits"]).and_then(Value::as_array).unwrap();
+		assert_eq!(fruits[0].get(&["physical", "color"]).and_then(Value::as_string), Some("red"));
+		assert_eq!(fruits[1].get(&["name"]).and_then(Value::as_string), Some("banana"));
+		
+		for src in [
+			"a = 1\na = 2",
+			"[a]\n[a]",
+			"a.b = 1\n[a]",
+			"[a.b]\n[a]\nb.c = 1",
+			"a = { b = 1 }\n[a]",
+			"a = { b = 1 }\na.c = 1",
+			"a = [1]\n[[a]]",
+			"[[a]]\n[a]",
+			"a = { b = 1, }",
+			"a = { b = 1,\n c = 2 }",
+			"a = 1 b = 2"
+		] {
+			assert!(Document::parse(src).is_err(), "{:?}", src);
+		}
+	}
+	
+	#[test]
+	fn error_position() {
+		assert_eq!(Document::parse("a = 1\nb = \"x\"\n[c]\nd = tru\n").unwrap_err().position(), Some((4, 5)));
+		assert_eq!(Document::parse("a = 1\n\n  a = 2\n").unwrap_err().position(), Some((3, 3)));
+	}
+	
+	#[test]
+	fn document_edit() {
+		let src = "\u{feff}# config\ntitle = \"old\" # the title\n\n[server]\n  port = 80\n  # hosts\n  host.name = \"a\"\n\n[[items]]\nid = 1\n";
+		let mut doc = Document::parse(src).unwrap();
+		assert_eq!(doc.to_string(), src);
+		
+		doc.set(&["title"], "new").unwrap();
+		doc.set(&["server", "port"], 8080).unwrap();
+		doc.set(&["server", "host", "ip"], "127.0.0.1").unwrap();
+		doc.set(&["server", "timeout"], 1.5).unwrap();
+		doc.set(&["client", "retries"], 3).unwrap();
+		doc.set(&["version"], 2).unwrap();
+		assert!(doc.set(&["title", "x"], 1).is_err());
+		assert_eq!(doc.remove(&["items", "id"]), Some(Value::Integer(1)));
+		
+		assert_eq!(doc.to_string(), "\u{feff}# config\ntitle = \"new\" # the title\nversion = 2\n\n[server]\n  port = 8080\n  \
+			# hosts\n  host.name = \"a\"\n  host.ip = \"127.0.0.1\"\n  timeout = 1.5\n\n[[items]]\n\n[client]\nretries = 3\n");
+		assert_eq!(doc.get(&["server", "host", "ip"]).and_then(Value::as_string), Some("127.0.0.1"));
+		assert_eq!(Document::parse(&doc.to_string()).unwrap(), doc);
+	}
+	
+	#[test]
+	fn serialize() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Config {
+			name:    String,
+			ratio:   f32,
+			count:   u64,
+			missing: Option<u8>,
+			created: Datetime,
+			inner:   Struct,
+			list:    Vec<Struct2>
+		}
+		
+		let config = Config {
+			name:    "a \"quoted\" name".to_string(),
+			ratio:   0.5,
+			count:   3,
+			missing: None,
+			created: "2020-01-01T12:00:00Z".parse().unwrap(),
+			inner:   Struct { a: false, b: "b".to_string(), c: Struct2 { a: "c".to_string() } },
+			list:    vec![Struct2 { a: "x".to_string() }, Struct2 { a: "y".to_string() }]
+		};
+		
+		let mut buf = Vec::new();
+		super::serialize(&mut buf, &config).unwrap();
+		let text = String::from_utf8(buf).unwrap();
+		assert_eq!(text, "count = 3\ncreated = 2020-01-01T12:00:00Z\nname = \"a \\\"quoted\\\" name\"\nratio = 0.5\n\n\
+			[inner]\na = false\nb = \"b\"\n\n[inner.c]\na = \"c\"\n\n[[list]]\na = \"x\"\n\n[[list]]\na = \"y\"\n");
+		assert_eq!(super::deserialize_str::<Config>(&text).unwrap(), config);
+	}
+}
@@ -87,13 +87,35 @@ mod serde {
 		Io(io::Error),
 		Utf8(std::str::Utf8Error),
 		InvalidToken(String, String),
-		ParseError(String)
+		ParseError(String),
+		/// Wraps an error with the position in the source, lines and columns start at 1.
+		Located {
+			line:   usize,
+			column: usize,
+			error:  Box<DeError>
+		}
 	}
 	
 	impl DeError {
 		pub fn invalid_token(unexp: impl ToString, exp: impl ToString) -> Self {
 			Self::InvalidToken(unexp.to_string(), exp.to_string())
 		}
+		
+		/// Attaches a position to this error, errors that already have a position are returned as is.
+		pub fn at(self, line: usize, column: usize) -> Self {
+			match self {
+				Self::Located { .. } => self,
+				error => Self::Located { line, column, error: Box::new(error) }
+			}
+		}
+		
+		/// Returns the line and column of this error, if known.
+		pub fn position(&self) -> Option<(usize, usize)> {
+			match self {
+				Self::Located { line, column, .. } => Some((*line, *column)),
+				_ => None
+			}
+		}
 	}
 	
 	impl fmt::Debug for DeError {
@@ -116,7 +138,8 @@ mod serde {
 				Self::Io(err)                    => write!(f, "io error: {}", err),
 				Self::Utf8(err)                  => write!(f, "utf8 error: {}", err),
 				Self::InvalidToken(unexp, exp)   => write!(f, "invalid token: {}, expected {}", unexp, exp),
-				Self::ParseError(err)            => write!(f, "parse error: {}", err)
+				Self::ParseError(err)            => write!(f, "parse error: {}", err),
+				Self::Located { line, column, error } => write!(f, "{} at line {}, column {}", error, line, column)
 			}
 		}
 	}
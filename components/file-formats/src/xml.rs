@@ -20,339 +20,921 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+//! XML reading and writing, with serde support.
+//!
+//! Elements are mapped to maps or structs: attributes and child elements are entries keyed by
+//! their name and character data is keyed by `$value`. A struct with a `$body` field receives
+//! the whole content of the element (children and text, in document order) in that field
+//! instead. Deserializing an element as a sequence yields one single-entry map (or enum
+//! variant) per attribute, child element and text node, which allows reading elements whose
+//! children are interleaved.
+//!
+//! Entities, character references, CDATA sections and namespace prefixes are resolved by the
+//! [`Reader`], comments and the document type declaration are skipped.
+
 use {
-	self::State::*,
-	crate::utils::DeError,
-	std::{str::FromStr, io, mem, fmt, marker::PhantomData},
-	serde::{*, de::{self, *}, forward_to_deserialize_any}
+	crate::utils::*,
+	std::{str::FromStr, io, fmt, borrow::Cow, collections::VecDeque, vec},
+	serde::{*, de::{*, value::StringDeserializer}, ser::*}
 };
 
+pub fn serialize<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
+	value.serialize(&mut Serializer::new(writer))
+}
+
+/// Serializes the value with line breaks and tab indentation, elements with mixed content are
+/// written as is.
+pub fn serialize_pretty<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
+	value.serialize(&mut Serializer::pretty(writer))
+}
+
 pub fn deserialize<'de, T: Deserialize<'de>, R: io::BufRead + fmt::Debug>(reader: R) -> Result<T, DeError> {
-	T::deserialize(&mut Deserializer::from_reader(reader))
+	Deserializer::new(reader).deserialize_document()
+}
+
+pub fn deserialize_slice<'de, T: Deserialize<'de>>(slice: &[u8]) -> Result<T, DeError> {
+	deserialize(io::BufReader::new(slice))
 }
 
-#[derive(Debug, Deserialize)]
+/// The text content of an element.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Body<T> {
 	#[serde(rename = "$value")]
 	pub value: T
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum State {
-	/// The outer tag is yet to be parsed.
-	Uninit,
-	/// Reading a tag's name.
-	TagKey,
-	/// Indicates that the next value is a map.
-	TagValue(bool),
-	/// Searching for attributes in a tag.
-	AttrKey,
-	/// An attribute value is to be parsed
-	AttrValue,
-	/// Reading the body of an xml tag while searching for inner tags.
-	BodyValue,
-	BodyKey
+/// Escapes `&`, `<` and `>`, so that the string can be used as character data.
+pub fn escape(s: &str) -> Cow<'_, str> {
+	escape_with(s, false)
 }
 
-fn invalid_state(state: State, file: &'static str, line: u32) -> DeError {
-	DeError::Custom(format!("invalid state: {:?}, {}:{}", state, file, line))
+/// Decodes the predefined entities and character references in the string.
+pub fn unescape(s: &str) -> Result<String, DeError> {
+	let mut buf = String::with_capacity(s.len());
+	let mut rest = s;
+	while let Some(off) = rest.find('&') {
+		buf.push_str(&rest[..off]);
+		let end = rest[off..].find(';')
+			.ok_or_else(|| DeError::invalid_token(&rest[off..], "an entity reference"))?;
+		let name = &rest[off + 1..off + end];
+		buf.push(decode_reference(name)
+			.ok_or_else(|| DeError::Custom(format!("unknown entity `&{};`", name)))?);
+		rest = &rest[off + end + 1..];
+	}
+	buf.push_str(rest);
+	Ok(buf)
 }
 
-struct IgnoredIdSeed;
-
-impl<'de> de::DeserializeSeed<'de> for IgnoredIdSeed {
-	type Value = IgnoredAny;
+fn escape_with(s: &str, attribute: bool) -> Cow<'_, str> {
+	let special = |ch: char| match ch {
+		'&' | '<' | '>' => true,
+		'"' | '\t' | '\n' | '\r' => attribute,
+		_ => false
+	};
+	
+	if !s.contains(special) {
+		return Cow::Borrowed(s);
+	}
 	
-	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-		deserializer.deserialize_identifier(de::IgnoredAny)
+	let mut buf = String::with_capacity(s.len() + 8);
+	for ch in s.chars() {
+		match ch {
+			'&'  => buf.push_str("&amp;"),
+			'<'  => buf.push_str("&lt;"),
+			'>'  => buf.push_str("&gt;"),
+			'"' if attribute => buf.push_str("&quot;"),
+			// attribute values are normalized when read, so whitespace must be written as references
+			'\t' | '\n' | '\r' if attribute => buf.push_str(&format!("&#{};", ch as u32)),
+			ch => buf.push(ch)
+		}
 	}
+	Cow::Owned(buf)
 }
 
-struct IgnoredAnySeed;
+/// Escapes character data, leading and trailing whitespace is written as character references,
+/// as it would be trimmed otherwise.
+fn escape_text(s: &str) -> String {
+	let start = s.len() - s.trim_start().len();
+	let end = s.trim_end().len().max(start);
+	let mut buf = String::with_capacity(s.len());
+	s[..start].chars().for_each(|ch| buf.push_str(&format!("&#{};", ch as u32)));
+	buf.push_str(&escape(&s[start..end]));
+	s[end..].chars().for_each(|ch| buf.push_str(&format!("&#{};", ch as u32)));
+	buf
+}
 
-impl<'de> de::DeserializeSeed<'de> for IgnoredAnySeed {
-	type Value = IgnoredAny;
-	
-	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-		deserializer.deserialize_ignored_any(de::IgnoredAny)
+/// Decodes a predefined entity or character reference, without the `&` and `;`.
+fn decode_reference(name: &str) -> Option<char> {
+	match name {
+		"lt"   => Some('<'),
+		"gt"   => Some('>'),
+		"amp"  => Some('&'),
+		"apos" => Some('\''),
+		"quot" => Some('"'),
+		_ => match name.strip_prefix('#')? {
+			hex if hex.starts_with('x') => u32::from_str_radix(&hex[1..], 16).ok(),
+			dec => dec.parse::<u32>().ok()
+		}.and_then(char::from_u32)
 	}
 }
 
-struct NoopSeed;
+fn is_name_start(ch: u8) -> bool {
+	ch.is_ascii_alphabetic() || ch == b'_' || ch == b':' || ch >= 0x80
+}
+
+fn is_name_char(ch: u8) -> bool {
+	is_name_start(ch) || ch.is_ascii_digit() || ch == b'-' || ch == b'.'
+}
 
-impl<'de> de::DeserializeSeed<'de> for NoopSeed {
-	type Value = ();
-	
-	fn deserialize<D: de::Deserializer<'de>>(self, _deserializer: D) -> Result<Self::Value, D::Error> {
-		Ok(())
+fn is_name(s: &str) -> bool {
+	s.as_bytes().first().is_some_and(|ch| is_name_start(*ch)) && s.bytes().all(is_name_char)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+	/// An opening tag. Empty elements are reported as a `Start` directly followed by an `End`.
+	Start {
+		name:       String,
+		attributes: Vec<(String, String)>
+	},
+	End(String),
+	/// Character data with references decoded and CDATA sections merged in. Whitespace at the
+	/// start and end is trimmed, text consisting of whitespace only is not reported.
+	Text(String),
+	ProcessingInstruction {
+		target: String,
+		data:   String
 	}
 }
 
+/// A pull parser that reads a document event by event.
+///
+/// Names are reported as written in the document, unless their namespace was registered with
+/// [`Reader::with_namespace`], in which case the prefix is replaced with the registered one.
+/// Namespace declarations are not reported as attributes.
 #[derive(Debug)]
-pub struct Deserializer<T: io::BufRead> {
-	reader: T,
-	state:  State,
-	stack:  Vec<String>,
-	buf:    String
+pub struct Reader<T: io::BufRead> {
+	reader:     T,
+	queue:      VecDeque<(Event, (usize, usize))>,
+	/// The raw and resolved names of the open elements and the number of namespace declarations
+	/// that were in scope before they were opened.
+	stack:      Vec<(String, String, usize)>,
+	/// The namespace declarations in scope, as prefix and URI.
+	namespaces: Vec<(String, String)>,
+	/// The registered namespaces, as URI and prefix.
+	prefixes:   Vec<(String, String)>,
+	/// Entities declared in the internal subset of the document type declaration.
+	entities:   Vec<(String, String)>,
+	/// Whether the root element was read.
+	root:       bool,
+	line:       usize,
+	column:     usize,
+	/// Position of the start of the last event.
+	token:      (usize, usize)
 }
 
-impl<T: io::BufRead> Deserializer<T> {
-	pub fn from_reader(mut reader: T) -> Self {
+/// Replacement texts larger than this are rejected, to prevent exponential entity expansion.
+const MAX_ENTITY_LEN: usize = 1 << 20;
+
+impl<T: io::BufRead> Reader<T> {
+	pub fn new(mut reader: T) -> Self {
 		// consume magic bytes for unicode text files
-		if reader.fill_buf().unwrap_or(&[]).starts_with(&crate::utils::UNICODE_FILE_MAGIC_BYTES) {
+		if reader.fill_buf().unwrap_or(&[]).starts_with(&UNICODE_FILE_MAGIC_BYTES) {
 			reader.consume(3);
 		}
 		
-		Self { reader, state:  Uninit, stack: Vec::new(), buf: String::new() }
+		Self {
+			reader,
+			queue:      VecDeque::new(),
+			stack:      Vec::new(),
+			namespaces: Vec::new(),
+			prefixes:   Vec::new(),
+			entities:   Vec::new(),
+			root:       false,
+			line:       1,
+			column:     1,
+			token:      (1, 1)
+		}
 	}
 	
-	fn next(&mut self) -> Result<char, DeError> {
-		let buf = self.reader.fill_buf()?;
-		let len = if buf[0] & 0x80 == 0 {
-			1
-		} else if buf[0] & 0xE0 == 0xC0 {
-			2
-		} else if buf[0] & 0xF0 == 0xE0 {
-			3
-		} else {
-			4
-		};
+	/// Reports names in the namespace `uri` with the given prefix, regardless of the prefix used
+	/// in the document. Names are reported without a prefix, if `prefix` is empty.
+	pub fn with_namespace(mut self, uri: impl Into<String>, prefix: impl Into<String>) -> Self {
+		self.prefixes.push((uri.into(), prefix.into()));
+		self
+	}
+	
+	/// Returns the line and column of the start of the last event.
+	pub fn position(&self) -> (usize, usize) {
+		self.token
+	}
+	
+	pub fn into_inner(self) -> T {
+		self.reader
+	}
+	
+	/// Returns the next event, or `None` after the end of the document.
+	pub fn next_event(&mut self) -> Result<Option<Event>, DeError> {
+		if self.queue.is_empty() {
+			self.fill()?;
+		}
 		
-		let mut buf = [0u8; 4];
-		self.reader.read_exact(&mut buf[..len])?;
-		Ok(std::str::from_utf8(&buf[..len])?.chars().next().unwrap())
+		Ok(self.queue.pop_front().map(|(event, pos)| {
+			self.token = pos;
+			event
+		}))
 	}
 	
-	fn read_until(&mut self, mut f: impl FnMut(u8) -> bool, peek: bool) -> Result<u8, DeError> {
-		loop {
-			let buf = self.reader.fill_buf()?;
-			
-			if buf.is_empty() {
-				return Err(DeError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "")));
+	pub fn peek_event(&mut self) -> Result<Option<&Event>, DeError> {
+		if self.queue.is_empty() {
+			self.fill()?;
+		}
+		
+		Ok(self.queue.front().map(|(event, _)| event))
+	}
+	
+	fn fill(&mut self) -> Result<(), DeError> {
+		self.read_event().map_err(|e| e.at(self.line, self.column))
+	}
+	
+	fn peek(&mut self) -> Result<Option<u8>, DeError> {
+		Ok(self.reader.fill_buf()?.first().copied())
+	}
+	
+	fn bump(&mut self) -> Result<Option<u8>, DeError> {
+		let ch = self.peek()?;
+		if let Some(ch) = ch {
+			self.reader.consume(1);
+			match ch {
+				b'\n' => {
+					self.line += 1;
+					self.column = 1;
+				}
+				// utf-8 continuation bytes don't start a new column
+				0x80..=0xBF => (),
+				_ => self.column += 1
 			}
-			
-			let (i, &ch) = buf.iter()
-				.enumerate()
-				.find(|(_, c)| f(**c))
-				.unwrap_or((buf.len(), &0));
-			
-			self.buf.push_str(std::str::from_utf8(&buf[..i])?);
-			
-			if i != buf.len() {
-				self.reader.consume(i + if peek { 0 } else { 1 });
-				return Ok(ch);
-			} else {
-				let __tmp__ = buf.len();
-				self.reader.consume(__tmp__);
+		}
+		Ok(ch)
+	}
+	
+	fn next_byte(&mut self) -> Result<u8, DeError> {
+		self.bump()?.ok_or_else(|| DeError::invalid_token("EOF", match self.stack.last() {
+			Some((name, ..)) => format!("closing tag `</{}>`", name),
+			None => "more markup".to_string()
+		}))
+	}
+	
+	fn expect(&mut self, s: &[u8]) -> Result<(), DeError> {
+		for ch in s {
+			match self.next_byte()? {
+				v if v == *ch => (),
+				v => return Err(unexpected(v, format_args!("`{}`", String::from_utf8_lossy(s))))
 			}
 		}
+		Ok(())
 	}
 	
-	fn next_value<U: FromStr>(&mut self) -> Result<U, DeError> {
-		self.state = match self.state {
-			AttrValue => self.read_until(|ch| ch == b'"', false).map(|_| AttrKey)?,
-			BodyValue => self.read_until(|ch| ch == b'<', true).map(|_| BodyKey)?,
-			TagValue(true) => {
-				Body::<String>::deserialize(&mut*self)?;
-				self.state
-			},
-			state => return Err(invalid_state(state, file!(), line!()))
-		};
-		
-		self.dbg_val(&self.buf);
-		let v = convert_xml(self.buf.trim()).parse::<U>()
-			.map_err(|_| DeError::ParseError(format!("failed to parse {}", std::any::type_name::<U>())));
-		self.buf.clear();
-		v
+	/// Skips whitespace and returns whether there was any.
+	fn skip_ws(&mut self) -> Result<bool, DeError> {
+		let mut any = false;
+		while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+			self.bump()?;
+			any = true;
+		}
+		Ok(any)
 	}
 	
-	fn dbg_key(&self, key: &str) {
-		if cfg!(feature = "xml_dbg") {
-			print!("\n{}{:10}", "\t".repeat(self.stack.len()), key);
+	/// Reads until and including the delimiter and returns the bytes before it.
+	fn read_until(&mut self, delimiter: &[u8]) -> Result<Vec<u8>, DeError> {
+		let mut buf = Vec::new();
+		while !buf.ends_with(delimiter) {
+			buf.push(self.next_byte()?);
 		}
+		buf.truncate(buf.len() - delimiter.len());
+		Ok(buf)
 	}
 	
-	fn dbg_val(&self, val: &str) {
-		if cfg!(feature = "xml_dbg") {
-			print!(" = \"{}\"", val);
+	fn read_name(&mut self) -> Result<String, DeError> {
+		let mut buf = Vec::new();
+		match self.peek()? {
+			Some(ch) if is_name_start(ch) => (),
+			Some(ch) => return Err(unexpected(ch, "a name")),
+			None => return Err(DeError::invalid_token("EOF", "a name"))
+		}
+		
+		while let Some(ch) = self.peek()?.filter(|ch| is_name_char(*ch)) {
+			self.bump()?;
+			buf.push(ch);
 		}
+		Ok(String::from_utf8(buf)?)
 	}
-}
-
-impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<T> {
-	type Error = DeError;
 	
-	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self.state {
-			// parse a tag's name
-			TagKey => {
-				let ch = self.read_until(|ch| ch.is_ascii_whitespace() || ch == b'>', false)?;
-				self.state = TagValue(ch == b'>');
-				self.dbg_key(&self.buf);
-				let r = visitor.visit_str(self.buf.as_str());
-				self.stack.push(mem::take(&mut self.buf));
-				r
+	/// Reads a reference after the `&` and returns its replacement text.
+	fn read_reference(&mut self) -> Result<String, DeError> {
+		let mut name = Vec::new();
+		loop {
+			match self.next_byte()? {
+				b';' => break,
+				ch if name.len() < 64 && (is_name_char(ch) || ch == b'#') => name.push(ch),
+				ch => return Err(unexpected(ch, "`;`"))
 			}
-			// parse attribute name
-			AttrKey => {
-				self.read_until(|ch| ch.is_ascii_whitespace() || ch == b'=', false)?;
-				self.state = AttrValue;
-				self.dbg_key(&self.buf);
-				let r = visitor.visit_str(self.buf.as_str());
-				self.buf.clear();
-				r
+		}
+		self.entity(std::str::from_utf8(&name)?)
+	}
+	
+	fn entity(&self, name: &str) -> Result<String, DeError> {
+		if let Some(ch) = decode_reference(name) {
+			return Ok(ch.to_string());
+		}
+		
+		self.entities.iter()
+			.find(|(k, _)| k == name)
+			.map(|(_, v)| v.clone())
+			.ok_or_else(|| DeError::Custom(format!("unknown entity `&{};`", name)))
+	}
+	
+	fn read_quoted(&mut self) -> Result<Vec<u8>, DeError> {
+		match self.next_byte()? {
+			quote @ (b'"' | b'\'') => self.read_until(&[quote]),
+			ch => Err(unexpected(ch, "`\"` or `'`"))
+		}
+	}
+	
+	fn read_attribute_value(&mut self) -> Result<String, DeError> {
+		let quote = match self.next_byte()? {
+			quote @ (b'"' | b'\'') => quote,
+			ch => return Err(unexpected(ch, "`\"` or `'`"))
+		};
+		
+		let mut buf = Vec::new();
+		loop {
+			match self.next_byte()? {
+				ch if ch == quote => break,
+				b'<' => return Err(unexpected(b'<', "an attribute value")),
+				b'&' => buf.extend_from_slice(self.read_reference()?.as_bytes()),
+				b'\t' | b'\n' | b'\r' => buf.push(b' '),
+				ch => buf.push(ch)
 			}
-			// return value identifier
-			BodyKey => {
-				self.state = BodyValue;
-				self.dbg_key("$value");
-				visitor.visit_str("$value")
-			},
-			state => Err(invalid_state(state, file!(), line!()))
 		}
+		Ok(String::from_utf8(buf)?)
+	}
+	
+	/// Resolves the namespace prefix of an element or attribute name.
+	fn resolve(&self, name: &str, element: bool) -> Result<String, DeError> {
+		let (prefix, local) = match name.split_once(':') {
+			Some(("xml", _)) => return Ok(name.to_string()),
+			Some((prefix, local)) => (prefix, local),
+			// unprefixed attributes are not in any namespace
+			None if element => ("", name),
+			None => return Ok(name.to_string())
+		};
+		
+		let uri = match self.namespaces.iter().rev().find(|(p, _)| p == prefix) {
+			Some((_, uri)) => uri,
+			None if prefix.is_empty() => return Ok(name.to_string()),
+			None => return Err(DeError::Custom(format!("undeclared namespace prefix `{}`", prefix)))
+		};
+		
+		Ok(match self.prefixes.iter().find(|(u, _)| u == uri) {
+			Some((_, p)) if p.is_empty() => local.to_string(),
+			Some((_, p)) => format!("{}:{}", p, local),
+			None => name.to_string()
+		})
 	}
 	
-	fn deserialize_map<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-		struct Seed<'de, T: Visitor<'de>>(T, PhantomData<&'de ()>);
+	fn read_event(&mut self) -> Result<(), DeError> {
+		let mut text = Text::default();
+		let mut start = (self.line, self.column);
 		
-		impl<'de, T: Visitor<'de>> de::DeserializeSeed<'de> for Seed<'de, T> {
-			type Value = T::Value;
+		let (event, pos) = loop {
+			match self.peek()? {
+				None if !self.stack.is_empty() => return Err(DeError::invalid_token(
+					"EOF", format_args!("closing tag `</{}>`", self.stack.last().unwrap().0))),
+				None if !self.root => return Err(DeError::Custom("missing root element".to_string())),
+				None => return match text.finish() {
+					Some(_) => Err(DeError::Custom("text outside of the root element".to_string()).at(start.0, start.1)),
+					None => Ok(())
+				},
+				Some(b'<') => (),
+				Some(b'&') => {
+					self.bump()?;
+					let s = self.read_reference()?;
+					text.push(s.as_bytes());
+					continue;
+				}
+				Some(b'\r') => {
+					// normalize line breaks
+					self.bump()?;
+					if self.peek()? != Some(b'\n') {
+						text.push_raw(b'\n');
+					}
+					continue;
+				}
+				Some(ch) => {
+					if text.is_empty() {
+						start = (self.line, self.column);
+					}
+					self.bump()?;
+					text.push_raw(ch);
+					continue;
+				}
+			}
 			
-			fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-				deserializer.deserialize_map(self.0)
+			let pos = (self.line, self.column);
+			self.bump()?;
+			match self.peek()? {
+				Some(b'!') => {
+					self.bump()?;
+					match self.peek()? {
+						Some(b'-') => {
+							self.expect(b"--")?;
+							self.read_until(b"-->")?;
+						}
+						Some(b'[') if !self.stack.is_empty() => {
+							self.expect(b"[CDATA[")?;
+							if text.is_empty() {
+								start = pos;
+							}
+							let data = self.read_until(b"]]>")?;
+							text.push(&data);
+						}
+						Some(b'D') if !self.root => self.read_doctype()?,
+						Some(ch) => return Err(unexpected(ch, "a comment, CDATA section or document type declaration")),
+						None => return Err(DeError::invalid_token("EOF", "a comment, CDATA section or document type declaration"))
+					}
+				}
+				Some(b'?') => {
+					self.bump()?;
+					let target = self.read_name()?;
+					let data = String::from_utf8(self.read_until(b"?>")?)?;
+					// the XML declaration is not a processing instruction
+					if !target.eq_ignore_ascii_case("xml") {
+						break (Event::ProcessingInstruction { target, data: data.trim_start().to_string() }, pos);
+					}
+				}
+				Some(b'/') => {
+					self.bump()?;
+					let raw = self.read_name()?;
+					self.skip_ws()?;
+					self.expect(b">")?;
+					break (Event::End(self.close(&raw)?), pos);
+				}
+				_ => {
+					let inside = !self.stack.is_empty();
+					let (event, empty) = self.read_start_tag(pos)?;
+					self.push_text(&mut text, start, inside)?;
+					
+					self.queue.push_back((event, pos));
+					if let Some(raw) = empty {
+						let name = self.close(&raw)?;
+						self.queue.push_back((Event::End(name), pos));
+					}
+					return Ok(());
+				}
+			}
+		};
+		
+		// end tags were already removed from the stack
+		let inside = !self.stack.is_empty() || matches!(event, Event::End(_));
+		self.push_text(&mut text, start, inside)?;
+		self.queue.push_back((event, pos));
+		Ok(())
+	}
+	
+	fn push_text(&mut self, text: &mut Text, start: (usize, usize), inside: bool) -> Result<(), DeError> {
+		match text.finish() {
+			Some(_) if !inside => Err(DeError::Custom("text outside of the root element".to_string()).at(start.0, start.1)),
+			Some(text) => {
+				self.queue.push_back((Event::Text(String::from_utf8(text)?), start));
+				Ok(())
 			}
+			None => Ok(())
+		}
+	}
+	
+	/// Reads a start tag after the `<` and returns the event and, if the tag was an empty
+	/// element tag, the raw name to close. Errors that concern the whole tag are located at `pos`.
+	fn read_start_tag(&mut self, pos: (usize, usize)) -> Result<(Event, Option<String>), DeError> {
+		if self.root && self.stack.is_empty() {
+			return Err(DeError::Custom("multiple root elements".to_string()).at(pos.0, pos.1));
 		}
 		
-		match self.state {
-			TagValue(new_state) => {
-				self.state = if new_state { BodyKey } else { AttrKey };
-				visitor.visit_map(self)
+		let raw = self.read_name()?;
+		let mut attributes = Vec::new();
+		let empty = loop {
+			let ws = self.skip_ws()?;
+			match self.peek()? {
+				Some(b'>') => {
+					self.bump()?;
+					break false;
+				}
+				Some(b'/') => {
+					self.bump()?;
+					self.expect(b">")?;
+					break true;
+				}
+				Some(_) if ws => {
+					let name = self.read_name()?;
+					self.skip_ws()?;
+					self.expect(b"=")?;
+					self.skip_ws()?;
+					attributes.push((name, self.read_attribute_value()?));
+				}
+				Some(ch) => return Err(unexpected(ch, "whitespace, `>` or `/>`")),
+				None => return Err(DeError::invalid_token("EOF", "`>` or `/>`"))
+			}
+		};
+		
+		let scope = self.namespaces.len();
+		let namespaces = &mut self.namespaces;
+		attributes.retain(|(name, value): &(String, String)| match name.strip_prefix("xmlns") {
+			Some("") => {
+				namespaces.push((String::new(), value.clone()));
+				false
+			}
+			Some(prefix) if prefix.starts_with(':') => {
+				namespaces.push((prefix[1..].to_string(), value.clone()));
+				false
+			}
+			_ => true
+		});
+		
+		let name = self.resolve(&raw, true).map_err(|e| e.at(pos.0, pos.1))?;
+		for i in 0..attributes.len() {
+			attributes[i].0 = self.resolve(&attributes[i].0, false).map_err(|e| e.at(pos.0, pos.1))?;
+			if attributes[..i].iter().any(|(k, _)| *k == attributes[i].0) {
+				return Err(DeError::Custom(format!("duplicate attribute `{}`", attributes[i].0)).at(pos.0, pos.1));
 			}
-			Uninit => {
-				self.state = BodyKey;
-				self.next_entry_seed(IgnoredIdSeed, Seed(visitor, PhantomData))
-					.transpose()
-					.expect("XML document was empty")
-					.map(|(_, v)| v)
+		}
+		
+		self.root = true;
+		self.stack.push((raw.clone(), name.clone(), scope));
+		Ok((Event::Start { name, attributes }, if empty { Some(raw) } else { None }))
+	}
+	
+	/// Closes the innermost element and returns its resolved name.
+	fn close(&mut self, raw: &str) -> Result<String, DeError> {
+		match self.stack.pop() {
+			Some((open, name, scope)) if open == raw => {
+				self.namespaces.truncate(scope);
+				Ok(name)
 			}
-			state => Err(invalid_state(state, file!(), line!()))
+			Some((open, ..)) => Err(DeError::invalid_token(
+				format_args!("`</{}>`", raw), format_args!("`</{}>`", open))),
+			None => Err(DeError::invalid_token(format_args!("`</{}>`", raw), "a start tag"))
 		}
 	}
 	
-	fn deserialize_seq<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-		struct Seed<'de, T: Visitor<'de>>(T, PhantomData<&'de ()>);
-		
-		impl<'de, T: Visitor<'de>> de::DeserializeSeed<'de> for Seed<'de, T> {
-			type Value = T::Value;
-			
-			fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-				deserializer.deserialize_seq(self.0)
+	/// Reads a document type declaration after the `<!` and records the entities declared in
+	/// its internal subset, everything else is skipped.
+	fn read_doctype(&mut self) -> Result<(), DeError> {
+		self.expect(b"DOCTYPE")?;
+		loop {
+			match self.next_byte()? {
+				b'>' => return Ok(()),
+				b'[' => break,
+				quote @ (b'"' | b'\'') => { self.read_until(&[quote])?; }
+				_ => ()
 			}
 		}
 		
-		match self.state {
-			TagValue(new_state) => {
-				self.state = if new_state { BodyKey } else { AttrKey };
-				visitor.visit_seq(self)
+		loop {
+			self.skip_ws()?;
+			match self.next_byte()? {
+				b']' => {
+					self.skip_ws()?;
+					return self.expect(b">");
+				}
+				// parameter entity reference
+				b'%' => { self.read_until(b";")?; }
+				b'<' => match self.next_byte()? {
+					b'?' => { self.read_until(b"?>")?; }
+					b'!' => match self.peek()? {
+						Some(b'-') => {
+							self.expect(b"--")?;
+							self.read_until(b"-->")?;
+						}
+						Some(b'E') => {
+							let keyword = self.read_name()?;
+							if keyword == "ENTITY" {
+								self.read_entity_decl()?;
+							} else {
+								self.skip_decl()?;
+							}
+						}
+						_ => self.skip_decl()?
+					}
+					ch => return Err(unexpected(ch, "`!` or `?`"))
+				}
+				ch => return Err(unexpected(ch, "a markup declaration"))
 			}
-			Uninit => {
-				self.state = BodyKey;
-				self.next_entry_seed(IgnoredIdSeed, Seed(visitor, PhantomData))
-					.transpose()
-					.expect("XML document was empty")
-					.map(|(_, v)| v)
+		}
+	}
+	
+	fn read_entity_decl(&mut self) -> Result<(), DeError> {
+		self.skip_ws()?;
+		if self.peek()? == Some(b'%') {
+			// parameter entities are only used in the DTD itself
+			return self.skip_decl();
+		}
+		
+		let name = self.read_name()?;
+		self.skip_ws()?;
+		if !matches!(self.peek()?, Some(b'"' | b'\'')) {
+			// external entities are not loaded
+			return self.skip_decl();
+		}
+		
+		let raw = String::from_utf8(self.read_quoted()?)?;
+		let mut value = String::new();
+		let mut rest = raw.as_str();
+		while let Some(off) = rest.find('&') {
+			value.push_str(&rest[..off]);
+			let end = rest[off..].find(';')
+				.ok_or_else(|| DeError::invalid_token(&rest[off..], "an entity reference"))?;
+			value.push_str(&self.entity(&rest[off + 1..off + end])?);
+			rest = &rest[off + end + 1..];
+			
+			if value.len() > MAX_ENTITY_LEN {
+				return Err(DeError::Custom(format!("replacement text of entity `{}` is too large", name)));
 			}
-			state => Err(invalid_state(state, file!(), line!()))
 		}
+		value.push_str(rest);
+		
+		// the first declaration is binding
+		if !self.entities.iter().any(|(k, _)| *k == name) {
+			self.entities.push((name, value));
+		}
+		self.skip_decl()
 	}
 	
-	fn deserialize_enum<V: Visitor<'de>>(
-		self,
-		_name:     &'static str,
-		_variants: &'static [&'static str],
-		visitor:   V
-	) -> Result<V::Value, Self::Error> {
-		match self.state {
-			AttrValue | BodyValue => visitor.visit_enum(
-				self.next_value::<String>()?.into_deserializer()),
-			Uninit | TagValue(_) => {
-				self.deserialize_map(IgnoredAny)?;
-				visitor.visit_enum(self)
-			},
-			state => Err(invalid_state(state, file!(), line!()))
+	/// Skips the rest of a markup declaration, including the `>`.
+	fn skip_decl(&mut self) -> Result<(), DeError> {
+		loop {
+			match self.next_byte()? {
+				b'>' => return Ok(()),
+				quote @ (b'"' | b'\'') => { self.read_until(&[quote])?; }
+				_ => ()
+			}
 		}
 	}
+}
+
+impl<T: io::BufRead> Iterator for Reader<T> {
+	type Item = Result<Event, DeError>;
 	
-	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		match self.state {
-			TagKey | AttrKey | BodyKey => self.deserialize_identifier(visitor),
-			Uninit | TagValue(_) => self.deserialize_map(visitor),
-			AttrValue | BodyValue =>  visitor.visit_string(self.next_value()?)
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_event().transpose()
+	}
+}
+
+fn unexpected(ch: u8, exp: impl ToString) -> DeError {
+	if ch.is_ascii_graphic() {
+		DeError::invalid_token(format_args!("`{}`", ch as char), exp)
+	} else {
+		DeError::invalid_token(format_args!("{:#04x}", ch), exp)
+	}
+}
+
+/// Character data that is being read, with whitespace at the start and end trimmed.
+#[derive(Default)]
+struct Text {
+	buf:  Vec<u8>,
+	/// The length of the text up to the last character that is not trimmed.
+	keep: usize
+}
+
+impl Text {
+	fn is_empty(&self) -> bool {
+		self.buf.is_empty()
+	}
+	
+	fn push_raw(&mut self, ch: u8) {
+		if !ch.is_ascii_whitespace() {
+			self.buf.push(ch);
+			self.keep = self.buf.len();
+		} else if !self.buf.is_empty() {
+			self.buf.push(ch);
 		}
 	}
 	
-	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_bool(self.next_value()?)
+	/// Pushes decoded references and CDATA sections, which are never trimmed.
+	fn push(&mut self, s: &[u8]) {
+		self.buf.extend_from_slice(s);
+		self.keep = self.buf.len();
+	}
+	
+	fn finish(&mut self) -> Option<Vec<u8>> {
+		self.buf.truncate(self.keep);
+		self.keep = 0;
+		Some(std::mem::take(&mut self.buf)).filter(|buf| !buf.is_empty())
+	}
+}
+
+#[derive(Debug)]
+pub struct Deserializer<T: io::BufRead> {
+	reader: Reader<T>
+}
+
+impl<T: io::BufRead> Deserializer<T> {
+	pub fn new(reader: T) -> Self {
+		Self { reader: Reader::new(reader) }
+	}
+	
+	/// See [`Reader::with_namespace`].
+	pub fn with_namespace(mut self, uri: impl Into<String>, prefix: impl Into<String>) -> Self {
+		self.reader = self.reader.with_namespace(uri, prefix);
+		self
+	}
+	
+	/// Deserializes the root element and checks that nothing follows it. Errors are located at
+	/// the event where they occurred.
+	pub fn deserialize_document<'de, U: Deserialize<'de>>(mut self) -> Result<U, DeError> {
+		U::deserialize(&mut self)
+			.and_then(|v| self.end().map(|_| v))
+			.map_err(|e| self.locate(e))
+	}
+	
+	/// Checks that there is nothing but processing instructions and comments after the root element.
+	pub fn end(&mut self) -> Result<(), DeError> {
+		match self.reader.next_event()? {
+			None => Ok(()),
+			Some(Event::ProcessingInstruction { .. }) => self.end(),
+			Some(_) => Err(DeError::invalid_token("markup", "end of file"))
+		}
 	}
 	
-	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_i8(self.next_value()?)
+	/// Attaches the position of the current event to errors that were returned by a visitor.
+	fn locate(&self, error: DeError) -> DeError {
+		let (line, column) = self.reader.position();
+		error.at(line, column)
 	}
 	
-	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_i16(self.next_value()?)
+	/// Returns the next event, processing instructions are skipped.
+	fn next(&mut self) -> Result<Event, DeError> {
+		loop {
+			match self.reader.next_event()? {
+				Some(Event::ProcessingInstruction { .. }) => continue,
+				Some(event) => return Ok(event),
+				None => return Err(DeError::invalid_token("EOF", "an element"))
+			}
+		}
 	}
 	
-	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_i32(self.next_value()?)
+	fn peek(&mut self) -> Result<&Event, DeError> {
+		while let Some(Event::ProcessingInstruction { .. }) = self.reader.peek_event()? {
+			self.reader.next_event()?;
+		}
+		self.reader.peek_event()?.ok_or_else(|| DeError::invalid_token("EOF", "an element"))
 	}
 	
-	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_i64(self.next_value()?)
+	/// Reads the start tag of the root element and returns its attributes.
+	fn root(&mut self) -> Result<Vec<(String, String)>, DeError> {
+		match self.next()? {
+			Event::Start { attributes, .. } => Ok(attributes),
+			_ => Err(DeError::invalid_token("markup", "the root element"))
+		}
 	}
 	
-	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_u8(self.next_value()?)
+	/// Reads the rest of the current element, which must only contain text.
+	fn text(&mut self) -> Result<String, DeError> {
+		let mut buf = String::new();
+		loop {
+			match self.next()? {
+				Event::Text(text) => buf.push_str(&text),
+				Event::End(_) => return Ok(buf),
+				Event::Start { name, .. } => return Err(DeError::invalid_token(
+					format_args!("element `{}`", name), "text")),
+				Event::ProcessingInstruction { .. } => unreachable!()
+			}
+		}
 	}
 	
-	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_u16(self.next_value()?)
+	/// Skips the rest of the current element.
+	fn skip(&mut self) -> Result<(), DeError> {
+		let mut depth = 0usize;
+		loop {
+			match self.next()? {
+				Event::Start { .. } => depth += 1,
+				Event::End(_) if depth == 0 => return Ok(()),
+				Event::End(_) => depth -= 1,
+				_ => ()
+			}
+		}
 	}
 	
-	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_u32(self.next_value()?)
+	/// Reads the end tag of the current element.
+	fn end_element(&mut self) -> Result<(), DeError> {
+		match self.next()? {
+			Event::End(_) => Ok(()),
+			Event::Start { name, .. } => Err(DeError::invalid_token(
+				format_args!("element `{}`", name), "end of element")),
+			_ => Err(DeError::invalid_token("text", "end of element"))
+		}
 	}
+}
+
+fn parse<U: FromStr>(s: &str) -> Result<U, DeError> {
+	s.parse::<U>().map_err(|_| DeError::ParseError(format!(
+		"failed to parse {} from `{}`", std::any::type_name::<U>(), s)))
+}
+
+/// Implements deserializer methods by forwarding them to the value returned by `$target`.
+macro_rules! forward {
+	(|$this:ident| $target:expr; $($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+		fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+			let $this = self;
+			$target.$method($($arg,)* visitor)
+		}
+	)*};
+}
+
+/// Implements deserializer methods for primitives by parsing the text returned by `text`.
+macro_rules! parse_text {
+	($($method:ident => $visit:ident,)*) => {$(
+		fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+			visitor.$visit(parse(&self.text()?)?)
+		}
+	)*};
+}
+
+impl<'de, T: io::BufRead> serde::Deserializer<'de> for &mut Deserializer<T> {
+	type Error = DeError;
 	
-	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_u64(self.next_value()?)
+	forward! {
+		|this| {
+			let attributes = this.root()?;
+			ElementDeserializer { de: this, attributes }
+		};
+		deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+		deserialize_i64() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+		deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+		deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+		deserialize_unit_struct(name: &'static str) deserialize_newtype_struct(name: &'static str)
+		deserialize_seq() deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+		deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+		deserialize_enum(name: &'static str, variants: &'static [&'static str])
+		deserialize_identifier() deserialize_ignored_any()
+	}
+}
+
+/// Deserializes the content of an element, after its start tag was read.
+struct ElementDeserializer<'a, T: io::BufRead> {
+	de:         &'a mut Deserializer<T>,
+	attributes: Vec<(String, String)>
+}
+
+impl<'a, T: io::BufRead> ElementDeserializer<'a, T> {
+	fn text(self) -> Result<String, DeError> {
+		self.de.text()
 	}
 	
-	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_f32(self.next_value()?)
+	fn access(self, body: bool) -> ElementAccess<'a, T> {
+		ElementAccess { de: self.de, attributes: self.attributes.into_iter(), body, value: None, done: false }
 	}
+}
+
+impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for ElementDeserializer<'a, T> {
+	type Error = DeError;
 	
-	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_f64(self.next_value()?)
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if self.attributes.is_empty() && matches!(self.de.peek()?, Event::Text(_) | Event::End(_)) {
+			visitor.visit_string(self.text()?)
+		} else {
+			self.deserialize_map(visitor)
+		}
 	}
 	
-	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_char(self.next_value()?)
+	parse_text! {
+		deserialize_bool => visit_bool,
+		deserialize_i8   => visit_i8,
+		deserialize_i16  => visit_i16,
+		deserialize_i32  => visit_i32,
+		deserialize_i64  => visit_i64,
+		deserialize_u8   => visit_u8,
+		deserialize_u16  => visit_u16,
+		deserialize_u32  => visit_u32,
+		deserialize_u64  => visit_u64,
+		deserialize_f32  => visit_f32,
+		deserialize_f64  => visit_f64,
+		deserialize_char => visit_char,
 	}
 	
 	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_string(self.next_value()?)
+		visitor.visit_string(self.text()?)
 	}
 	
 	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_string(self.next_value()?)
+		visitor.visit_string(self.text()?)
 	}
 	
 	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_byte_buf(self.next_value::<String>()?.into_bytes())
+		visitor.visit_byte_buf(self.text()?.into_bytes())
 	}
 	
 	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_byte_buf(self.next_value::<String>()?.into_bytes())
+		visitor.visit_byte_buf(self.text()?.into_bytes())
 	}
 	
 	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
@@ -360,219 +942,384 @@ impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for &'a mut Deserializer<
 	}
 	
 	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		self.next_value::<String>()?;
+		self.de.skip()?;
 		visitor.visit_unit()
 	}
 	
-	fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where
-		V: Visitor<'de> {
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
 		self.deserialize_unit(visitor)
 	}
 	
-	fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where
-		V: Visitor<'de> {
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
 		visitor.visit_newtype_struct(self)
 	}
 	
-	fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where
-		V: Visitor<'de> {
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_seq(self.access(false))
+	}
+	
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
 		self.deserialize_seq(visitor)
 	}
 	
-	fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where
-		V: Visitor<'de> {
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
 		self.deserialize_seq(visitor)
 	}
 	
-	fn deserialize_struct<V>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where
-		V: Visitor<'de> {
-		self.deserialize_map(visitor)
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(self.access(false))
+	}
+	
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name:  &'static str,
+		fields: &'static [&'static str],
+		visitor: V
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(self.access(fields.contains(&"$body")))
+	}
+	
+	/// Text content selects a unit variant, otherwise the name of the only child element
+	/// selects the variant.
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name:     &'static str,
+		_variants: &'static [&'static str],
+		visitor:   V
+	) -> Result<V::Value, Self::Error> {
+		if !matches!(self.de.peek()?, Event::Start { .. }) {
+			return visitor.visit_enum(StringDeserializer::<DeError>::new(self.text()?));
+		}
+		
+		let Event::Start { name, attributes } = self.de.next()? else { unreachable!() };
+		let value = visitor.visit_enum(EntryDeserializer {
+			key:   name,
+			value: Value::Element(ElementDeserializer { de: &mut*self.de, attributes })
+		})?;
+		self.de.end_element()?;
+		Ok(value)
+	}
+	
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_string(self.text()?)
 	}
 	
 	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		self.deserialize_any(visitor)
+		self.deserialize_unit(visitor)
+	}
+}
+
+enum Pending {
+	Text(String),
+	Element(String, Vec<(String, String)>),
+	Body
+}
+
+/// Iterates over the attributes and content of an element.
+struct ElementAccess<'a, T: io::BufRead> {
+	de:         &'a mut Deserializer<T>,
+	attributes: vec::IntoIter<(String, String)>,
+	/// Whether the content is returned as a single `$body` entry.
+	body:       bool,
+	value:      Option<Pending>,
+	done:       bool
+}
+
+impl<'a, T: io::BufRead> ElementAccess<'a, T> {
+	fn next_key(&mut self) -> Result<Option<String>, DeError> {
+		if self.done {
+			return Ok(None);
+		}
+		
+		if let Some((key, value)) = self.attributes.next() {
+			self.value = Some(Pending::Text(value));
+			return Ok(Some(key));
+		}
+		
+		if self.body {
+			self.done = true;
+			self.value = Some(Pending::Body);
+			return Ok(Some("$body".to_string()));
+		}
+		
+		match self.de.next()? {
+			Event::Start { name, attributes } => {
+				self.value = Some(Pending::Element(name.clone(), attributes));
+				Ok(Some(name))
+			}
+			Event::Text(text) => {
+				self.value = Some(Pending::Text(text));
+				Ok(Some("$value".to_string()))
+			}
+			_ => {
+				self.done = true;
+				Ok(None)
+			}
+		}
 	}
 	
-	fn is_human_readable(&self) -> bool {
-		true
+	fn next_value(&mut self) -> Value<'_, T> {
+		match self.value.take().expect("value requested before key") {
+			Pending::Text(text) => Value::Text(text),
+			Pending::Element(_, attributes) => Value::Element(ElementDeserializer { de: &mut*self.de, attributes }),
+			Pending::Body => Value::Element(ElementDeserializer { de: &mut*self.de, attributes: Vec::new() })
+		}
 	}
 }
 
-impl<'a, 'de, R: io::BufRead> de::MapAccess<'de> for &'a mut Deserializer<R> {
+impl<'a, 'de, T: io::BufRead> MapAccess<'de> for ElementAccess<'a, T> {
 	type Error = DeError;
 	
 	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
-		let ch = 'outer: loop {
-			match self.state {
-				AttrKey => loop {
-					match self.next()? {
-						ch if ch.is_whitespace() => (),
-						'>' => {
-							self.state = BodyKey;
-							break;
-						}
-						'/' => return match self.next()? {
-							'>' => {
-								self.state = BodyKey;
-								self.stack.pop().unwrap();
-								Ok(None)
-							}
-							ch => Err(DeError::invalid_token(&format!("'{}'", ch as char), "'>'"))
-						},
-						ch => break 'outer ch
-					}
-				}
-				BodyKey => {
-					loop {
-						match self.next()? {
-							ch if ch.is_whitespace() => (),
-							'<' => break,
-							ch => break 'outer ch
-						}
-					}
-					
-					match self.next()? {
-						// closing tag
-						'/' => loop {
-							match self.next()? {
-								ch if ch.is_whitespace() => (),
-								'>' => return match self.stack.pop() {
-									Some(tag) if tag == self.buf => {
-										self.buf.clear();
-										Ok(None)
-									},
-									tag => Err(DeError::Custom(format!(
-										"invalid closing tag: `{}`, expected `{:?}`",
-										mem::take(&mut self.buf), tag
-									)))
-								},
-								ch => self.buf.push(ch)
-							}
-						}
-						// comment/doctype, skip
-						'!' => loop {
-							while self.next()? != '-' {}
-							if self.next()? == '-' && self.next()? == '>' { break; }
-						}
-						'?' => loop {
-							while self.next()? != '?' {}
-							if self.next()? == '>' { break; }
-						}
-						// opening tag
-						ch => {
-							self.state = TagKey;
-							break 'outer ch;
-						}
-					}
-				}
-				state => return Err(invalid_state(state, file!(), line!()))
-			}
-		};
-		
-		self.buf.push(ch);
-		seed.deserialize(&mut**self).map(Some)
+		match self.next_key()? {
+			Some(key) => seed.deserialize(StringDeserializer::<DeError>::new(key)).map(Some),
+			None => Ok(None)
+		}
 	}
 	
-	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error>{
-		match self.state {
-			TagValue(_) | BodyValue => (),
-			AttrValue => loop {
-				match self.next()? {
-					'"' => break,
-					ch if ch.is_whitespace() || ch == '=' => (),
-					ch => return Err(DeError::invalid_token(&format!("'{}'", ch), "'\"', '=' or whitespace"))
-				}
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		match self.value.take() {
+			Some(Pending::Element(name, attributes)) => seed.deserialize(FieldDeserializer {
+				name,
+				element: ElementDeserializer { de: &mut*self.de, attributes }
+			}),
+			value => {
+				self.value = value;
+				seed.deserialize(self.next_value())
 			}
-			state => return Err(invalid_state(state, file!(), line!()))
 		}
-		
-		seed.deserialize(&mut**self)
 	}
 }
 
-impl<'a, 'de, R: io::BufRead> de::EnumAccess<'de> for &'a mut Deserializer<R> {
-	type Error   = DeError;
-	type Variant = Self;
+impl<'a, 'de, T: io::BufRead> SeqAccess<'de> for ElementAccess<'a, T> {
+	type Error = DeError;
 	
-	fn variant_seed<V: DeserializeSeed<'de>>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
-		match self.next_entry_seed(seed, NoopSeed)? {
-			Some((k, _)) => Ok((k, self)),
-			None => panic!("no key was found")
+	fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error> {
+		match self.next_key()? {
+			Some(key) => seed.deserialize(EntryDeserializer { key, value: self.next_value() }).map(Some),
+			None => Ok(None)
 		}
 	}
 }
 
-impl<'a, 'de, R: io::BufRead> de::VariantAccess<'de> for &'a mut Deserializer<R> {
+/// A child element that is the value of a map entry or struct field. Sequences consist of the
+/// element and all directly following elements with the same name, all other types are
+/// deserialized from the element.
+struct FieldDeserializer<'a, T: io::BufRead> {
+	name:    String,
+	element: ElementDeserializer<'a, T>
+}
+
+impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for FieldDeserializer<'a, T> {
 	type Error = DeError;
 	
-	fn unit_variant(self) -> Result<(), Self::Error> {
-		use de::Deserializer;
-		self.deserialize_ignored_any(IgnoredAny).map(|_| ())
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
 	}
 	
-	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
-		seed.deserialize(self)
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
 	}
 	
-	fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
-		serde::Deserializer::deserialize_tuple(self, len, visitor)
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_seq(SiblingAccess {
+			de:    self.element.de,
+			name:  self.name,
+			first: Some(self.element.attributes)
+		})
 	}
 	
-	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
-		serde::Deserializer::deserialize_map(self, visitor)
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+	
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+	
+	forward! {
+		|this| this.element;
+		deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+		deserialize_i64() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+		deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+		deserialize_bytes() deserialize_byte_buf() deserialize_unit() deserialize_unit_struct(name: &'static str)
+		deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+		deserialize_enum(name: &'static str, variants: &'static [&'static str])
+		deserialize_identifier() deserialize_ignored_any()
 	}
 }
 
-impl<'a, 'de, R: io::BufRead> de::SeqAccess<'de> for &'a mut Deserializer<R> {
+struct SiblingAccess<'a, T: io::BufRead> {
+	de:    &'a mut Deserializer<T>,
+	name:  String,
+	/// The attributes of the first element, whose start tag was already read.
+	first: Option<Vec<(String, String)>>
+}
+
+impl<'a, 'de, T: io::BufRead> SeqAccess<'de> for SiblingAccess<'a, T> {
 	type Error = DeError;
 	
-	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
-		
-		struct Visitor;
-		
-		impl<'de> de::Visitor<'de> for Visitor {
-			type Value = String;
-			
-			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-				write!(formatter, "an identifier")
+	fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error> {
+		let attributes = match self.first.take() {
+			Some(attributes) => attributes,
+			None => match self.de.peek()? {
+				Event::Start { name, .. } if *name == self.name => match self.de.next()? {
+					Event::Start { attributes, .. } => attributes,
+					_ => unreachable!()
+				},
+				_ => return Ok(None)
 			}
-			
-			fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
-				Ok(v.to_string())
-			}
-		}
-		
-		struct Seed;
+		};
 		
-		impl<'de> de::DeserializeSeed<'de> for Seed {
-			type Value = String;
-			
-			fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-				deserializer.deserialize_identifier(Visitor)
+		seed.deserialize(ElementDeserializer { de: &mut*self.de, attributes }).map(Some)
+	}
+}
+
+/// An attribute value or text node.
+struct TextDeserializer(String);
+
+impl TextDeserializer {
+	fn text(self) -> Result<String, DeError> {
+		Ok(self.0)
+	}
+}
+
+impl<'de> serde::Deserializer<'de> for TextDeserializer {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_string(self.0)
+	}
+	
+	parse_text! {
+		deserialize_bool => visit_bool,
+		deserialize_i8   => visit_i8,
+		deserialize_i16  => visit_i16,
+		deserialize_i32  => visit_i32,
+		deserialize_i64  => visit_i64,
+		deserialize_u8   => visit_u8,
+		deserialize_u16  => visit_u16,
+		deserialize_u32  => visit_u32,
+		deserialize_u64  => visit_u64,
+		deserialize_f32  => visit_f32,
+		deserialize_f64  => visit_f64,
+		deserialize_char => visit_char,
+	}
+	
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_byte_buf(self.0.into_bytes())
+	}
+	
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_byte_buf(self.0.into_bytes())
+	}
+	
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+	
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name:     &'static str,
+		_variants: &'static [&'static str],
+		visitor:   V
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_enum(StringDeserializer::<DeError>::new(self.0))
+	}
+	
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+	
+	forward_to_deserialize_any! {
+		str string seq tuple tuple_struct map struct identifier
+	}
+}
+
+enum Value<'a, T: io::BufRead> {
+	Text(String),
+	Element(ElementDeserializer<'a, T>)
+}
+
+macro_rules! dispatch {
+	($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+		fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+			match self {
+				Value::Text(text)   => TextDeserializer(text).$method($($arg,)* visitor),
+				Value::Element(de) => de.$method($($arg,)* visitor)
 			}
 		}
-		
-		match self.next_key_seed(Seed)? {
-			Some(id) => seed.deserialize(&mut SeqDeserializer {
-				inner: Some(&mut**self),
-				id:    Some(id.as_str())
-			}).map(Some),
-			None => Ok(None)
+	)*};
+}
+
+impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for Value<'a, T> {
+	type Error = DeError;
+	
+	dispatch! {
+		deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+		deserialize_i64() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+		deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+		deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+		deserialize_unit_struct(name: &'static str) deserialize_newtype_struct(name: &'static str)
+		deserialize_seq() deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+		deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+		deserialize_enum(name: &'static str, variants: &'static [&'static str])
+		deserialize_identifier() deserialize_ignored_any()
+	}
+}
+
+impl<'a, 'de, T: io::BufRead> VariantAccess<'de> for Value<'a, T> {
+	type Error = DeError;
+	
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		match self {
+			Value::Text(_) => Ok(()),
+			Value::Element(de) => de.de.skip()
 		}
 	}
+	
+	fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, Self::Error> {
+		seed.deserialize(self)
+	}
+	
+	fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_tuple(self, len, visitor)
+	}
+	
+	fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_struct(self, "", fields, visitor)
+	}
 }
 
-struct SeqDeserializer<'a, R: io::BufRead> {
-	inner: Option<&'a mut Deserializer<R>>,
-	id:    Option<&'a str>,
+/// A single attribute, child element or text node of an element that is deserialized as a
+/// sequence. Enums use the key as variant, maps contain the key as only entry and all other
+/// types are deserialized from the value.
+struct EntryDeserializer<'a, T: io::BufRead> {
+	key:   String,
+	value: Value<'a, T>
 }
 
-impl<'a, 'de, R: io::BufRead> de::Deserializer<'de> for &'a mut SeqDeserializer<'a, R> {
+impl<'a, 'de, T: io::BufRead> serde::Deserializer<'de> for EntryDeserializer<'a, T> {
 	type Error = DeError;
 	
 	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		self.id.unwrap();
-		visitor.visit_map(self)
+		visitor.visit_map(EntryAccess { key: Some(self.key), value: Some(self.value) })
+	}
+	
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_any(visitor)
 	}
 	
 	fn deserialize_enum<V: Visitor<'de>>(
@@ -585,77 +1332,920 @@ impl<'a, 'de, R: io::BufRead> de::Deserializer<'de> for &'a mut SeqDeserializer<
 	}
 	
 	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		visitor.visit_str(self.id.take().unwrap())
+		if let Value::Element(de) = self.value {
+			de.de.skip()?;
+		}
+		visitor.visit_string(self.key)
 	}
 	
-	forward_to_deserialize_any!(
-		bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit
-		unit_struct newtype_struct seq tuple tuple_struct map struct ignored_any
-	);
+	forward! {
+		|this| this.value;
+		deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+		deserialize_i64() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+		deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+		deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+		deserialize_unit_struct(name: &'static str) deserialize_newtype_struct(name: &'static str)
+		deserialize_seq() deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+		deserialize_struct(name: &'static str, fields: &'static [&'static str]) deserialize_ignored_any()
+	}
+}
+
+impl<'a, 'de, T: io::BufRead> EnumAccess<'de> for EntryDeserializer<'a, T> {
+	type Error   = DeError;
+	type Variant = Value<'a, T>;
+	
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		Ok((seed.deserialize(StringDeserializer::<DeError>::new(self.key))?, self.value))
+	}
+}
+
+struct EntryAccess<'a, T: io::BufRead> {
+	key:   Option<String>,
+	value: Option<Value<'a, T>>
 }
 
-impl<'a, 'de, R: io::BufRead> de::MapAccess<'de> for SeqDeserializer<'a, R> {
+impl<'a, 'de, T: io::BufRead> MapAccess<'de> for EntryAccess<'a, T> {
 	type Error = DeError;
 	
 	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
-		match self.inner.as_ref() {
-			Some(_) => {
-				// reconstruct `self` with appropriate lifetimes
-				let mut new_self = SeqDeserializer {
-					inner: match &mut self.inner {
-						Some(v) => Some(&mut*v),
-						None => None
-					},
-					id: self.id
-				};
-				
-				seed.deserialize(&mut new_self).map(Some)
-			},
-			None => Ok(None)
-		}
+		self.key.take().map(|key| seed.deserialize(StringDeserializer::<DeError>::new(key))).transpose()
 	}
 	
 	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
-		self.inner.take().unwrap().next_value_seed(seed)
+		seed.deserialize(self.value.take().expect("value requested before key"))
 	}
 }
 
-impl<'a, 'de, R: io::BufRead> de::EnumAccess<'de> for &'a mut SeqDeserializer<'a, R> {
-	type Error   = DeError;
-	type Variant = &'a mut Deserializer<R>;
+/// Writes values as XML documents. Structs and maps become elements, with fields that hold
+/// scalars written as attributes and all other fields as child elements named after the
+/// field. The values of `$value` and `$body` fields are written as content of the element.
+/// Sequences in fields are written as repeated elements, sequences of enum variants are written
+/// as the attributes and children of a single element, mirroring the [`Deserializer`].
+pub struct Serializer<T: io::Write> {
+	writer:     T,
+	root:       Option<String>,
+	/// Namespace declarations of the root element, as prefix and URI.
+	namespaces: Vec<(String, String)>,
+	indent:     Option<String>
+}
+
+impl<T: io::Write> Serializer<T> {
+	pub fn new(writer: T) -> Self {
+		Self { writer, root: None, namespaces: Vec::new(), indent: None }
+	}
 	
-	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
-		let mut __tmp__ = self.inner.take().unwrap();
-		match seed.deserialize(self) {
-			Ok(v) => {
-				__tmp__.next_value_seed(NoopSeed)?;
-				Ok((v, __tmp__))
-			},
-			Err(e) => Err(e)
-		}
-	}
-}
-
-/// converts xml expressions (e.g.: &quot; -> ")
-fn convert_xml(mut s: &str) -> String {
-	let mut buf = String::new();
-	while let Some(off) = s.find('&') {
-		let ext = s.find(';').unwrap();
-		buf.push_str(&s[..off]);
-		buf.push(match &s[off + 1..ext] {
-			"exclamation" => '!',
-			"quot"        => '"',
-			"percent"     => '%',
-			"amp"         => '&',
-			"apos"        => '\'',
-			"add"         => '+',
-			"lt"          => '<',
-			"equal"       => '=',
-			"gt"          => '>',
-			_             => panic!()
+	/// Creates a serializer that puts every element on its own line, indented by tabs.
+	pub fn pretty(writer: T) -> Self {
+		Self::with_indent(writer, "\t")
+	}
+	
+	pub fn with_indent(writer: T, indent: impl Into<String>) -> Self {
+		Self { writer, root: None, namespaces: Vec::new(), indent: Some(indent.into()) }
+	}
+	
+	/// Sets the name of the root element, which is otherwise the name of the serialized struct.
+	pub fn with_root(mut self, name: impl Into<String>) -> Self {
+		self.root = Some(name.into());
+		self
+	}
+	
+	/// Declares a namespace on the root element, an empty prefix declares the default namespace.
+	pub fn with_namespace(mut self, uri: impl Into<String>, prefix: impl Into<String>) -> Self {
+		self.namespaces.push((prefix.into(), uri.into()));
+		self
+	}
+	
+	pub fn into_inner(self) -> T {
+		self.writer
+	}
+	
+	fn write_root(&mut self, node: Node) -> Result<(), SerError> {
+		let name = match (&self.root, &node) {
+			(Some(name), _) => name.clone(),
+			(None, Node::Map(Some(name), _)) => name.to_string(),
+			_ => return Err(SerError::Custom("the root element has no name, use `Serializer::with_root`".to_string()))
+		};
+		
+		let mut root = Element::new(name)?;
+		for (prefix, uri) in &self.namespaces {
+			root.attribute(match prefix.as_str() {
+				"" => "xmlns".to_string(),
+				prefix => format!("xmlns:{}", prefix)
+			}, uri.clone())?;
+		}
+		root.fill(node)?;
+		
+		self.writer.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+		if self.indent.is_some() {
+			self.writer.write_all(b"\n")?;
+		}
+		root.write(&mut self.writer, self.indent.as_deref(), 0)?;
+		if self.indent.is_some() {
+			self.writer.write_all(b"\n")?;
+		}
+		Ok(())
+	}
+}
+
+/// Implements serializer methods by serializing the value into a node and writing it as the root.
+macro_rules! serialize_root {
+	($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+		fn $method(self, $($arg: $ty),*) -> Result<Self::Ok, Self::Error> {
+			let node = NodeSerializer.$method($($arg),*)?;
+			self.write_root(node)
+		}
+	)*};
+}
+
+impl<'a, T: io::Write> serde::Serializer for &'a mut Serializer<T> {
+	type Ok                     = ();
+	type Error                  = SerError;
+	type SerializeSeq           = Compound<'a, T>;
+	type SerializeTuple         = Compound<'a, T>;
+	type SerializeTupleStruct   = Compound<'a, T>;
+	type SerializeTupleVariant  = Compound<'a, T>;
+	type SerializeMap           = Compound<'a, T>;
+	type SerializeStruct        = Compound<'a, T>;
+	type SerializeStructVariant = Compound<'a, T>;
+	
+	serialize_root! {
+		serialize_bool(v: bool) serialize_i8(v: i8) serialize_i16(v: i16) serialize_i32(v: i32)
+		serialize_i64(v: i64) serialize_u8(v: u8) serialize_u16(v: u16) serialize_u32(v: u32)
+		serialize_u64(v: u64) serialize_f32(v: f32) serialize_f64(v: f64) serialize_char(v: char)
+		serialize_str(v: &str) serialize_bytes(v: &[u8]) serialize_none() serialize_unit()
+		serialize_unit_struct(name: &'static str)
+		serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str)
+	}
+	
+	fn serialize_some<U: ?Sized + Serialize>(self, value: &U) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_newtype_struct<U: ?Sized + Serialize>(self, _name: &'static str, value: &U) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_newtype_variant<U: ?Sized + Serialize>(
+		self,
+		name:          &'static str,
+		variant_index: u32,
+		variant:       &'static str,
+		value:         &U
+	) -> Result<Self::Ok, Self::Error> {
+		let node = NodeSerializer.serialize_newtype_variant(name, variant_index, variant, value)?;
+		self.write_root(node)
+	}
+	
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_seq(len)? })
+	}
+	
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_tuple(len)? })
+	}
+	
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_tuple_struct(name, len)? })
+	}
+	
+	fn serialize_tuple_variant(
+		self,
+		name:          &'static str,
+		variant_index: u32,
+		variant:       &'static str,
+		len:           usize
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_tuple_variant(name, variant_index, variant, len)? })
+	}
+	
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_map(len)? })
+	}
+	
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_struct(name, len)? })
+	}
+	
+	fn serialize_struct_variant(
+		self,
+		name:          &'static str,
+		variant_index: u32,
+		variant:       &'static str,
+		len:           usize
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(Compound { ser: self, inner: NodeSerializer.serialize_struct_variant(name, variant_index, variant, len)? })
+	}
+}
+
+pub struct Compound<'a, T: io::Write> {
+	ser:   &'a mut Serializer<T>,
+	inner: NodeCompound
+}
+
+impl<'a, T: io::Write> SerializeSeq for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_element<U: ?Sized + Serialize>(&mut self, value: &U) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeTuple for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_element<U: ?Sized + Serialize>(&mut self, value: &U) -> Result<(), Self::Error> {
+		SerializeTuple::serialize_element(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeTupleStruct for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_field<U: ?Sized + Serialize>(&mut self, value: &U) -> Result<(), Self::Error> {
+		SerializeTupleStruct::serialize_field(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeTupleVariant for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_field<U: ?Sized + Serialize>(&mut self, value: &U) -> Result<(), Self::Error> {
+		SerializeTupleVariant::serialize_field(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeMap for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_key<U: ?Sized + Serialize>(&mut self, key: &U) -> Result<(), Self::Error> {
+		SerializeMap::serialize_key(&mut self.inner, key)
+	}
+	
+	fn serialize_value<U: ?Sized + Serialize>(&mut self, value: &U) -> Result<(), Self::Error> {
+		SerializeMap::serialize_value(&mut self.inner, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeStruct for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_field<U: ?Sized + Serialize>(&mut self, key: &'static str, value: &U) -> Result<(), Self::Error> {
+		SerializeStruct::serialize_field(&mut self.inner, key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+impl<'a, T: io::Write> SerializeStructVariant for Compound<'a, T> {
+	type Ok    = ();
+	type Error = SerError;
+	
+	fn serialize_field<U: ?Sized + Serialize>(&mut self, key: &'static str, value: &U) -> Result<(), Self::Error> {
+		SerializeStructVariant::serialize_field(&mut self.inner, key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.ser.write_root(self.inner.end())
+	}
+}
+
+/// An intermediate representation of a serialized value. Whether a value is written as an
+/// attribute, element or text depends on its type, which is only known after serializing it.
+#[derive(Debug)]
+enum Node {
+	None,
+	Unit,
+	Scalar(String),
+	UnitVariant(&'static str),
+	Variant(&'static str, Box<Node>),
+	Seq(Vec<Node>),
+	Map(Option<&'static str>, Vec<(String, Node)>)
+}
+
+struct Element {
+	name:       String,
+	attributes: Vec<(String, String)>,
+	content:    Vec<Content>
+}
+
+enum Content {
+	Text(String),
+	Element(Element)
+}
+
+impl Element {
+	fn new(name: String) -> Result<Self, SerError> {
+		if !is_name(&name) {
+			return Err(SerError::Custom(format!("`{}` is not a valid element name", name)));
+		}
+		Ok(Self { name, attributes: Vec::new(), content: Vec::new() })
+	}
+	
+	fn attribute(&mut self, name: String, value: String) -> Result<(), SerError> {
+		if !is_name(&name) {
+			return Err(SerError::Custom(format!("`{}` is not a valid attribute name", name)));
+		}
+		self.attributes.push((name, value));
+		Ok(())
+	}
+	
+	fn child(&mut self, name: String, node: Node) -> Result<(), SerError> {
+		let mut element = Self::new(name)?;
+		element.fill(node)?;
+		self.content.push(Content::Element(element));
+		Ok(())
+	}
+	
+	/// Adds a struct field or map entry (`field` is true) or an enum variant to this element.
+	/// Sequences in fields are written as repeated elements, sequences in variants as the
+	/// content of a single element.
+	fn entry(&mut self, key: String, node: Node, field: bool) -> Result<(), SerError> {
+		if key == "$value" || key == "$body" {
+			return self.fill(node);
+		}
+		
+		match node {
+			Node::None => Ok(()),
+			Node::Scalar(value) => self.attribute(key, value),
+			Node::UnitVariant(variant) => self.attribute(key, variant.to_string()),
+			Node::Seq(items) if field => items.into_iter()
+				.try_for_each(|item| self.child(key.clone(), item)),
+			node => self.child(key, node)
+		}
+	}
+	
+	/// Adds a value to the content of this element.
+	fn fill(&mut self, node: Node) -> Result<(), SerError> {
+		match node {
+			Node::None | Node::Unit => Ok(()),
+			Node::Scalar(text) => {
+				match self.content.last_mut() {
+					Some(Content::Text(buf)) => buf.push_str(&text),
+					_ => self.content.push(Content::Text(text))
+				}
+				Ok(())
+			}
+			Node::UnitVariant(variant) => self.child(variant.to_string(), Node::Unit),
+			Node::Variant(variant, node) => self.child(variant.to_string(), *node),
+			Node::Map(_, entries) => entries.into_iter()
+				.try_for_each(|(key, node)| self.entry(key, node, true)),
+			Node::Seq(items) => items.into_iter().try_for_each(|item| match item {
+				// variants in a sequence are attributes or children, like the deserializer returns them
+				Node::Variant(variant, node) => self.entry(variant.to_string(), *node, false),
+				Node::Map(Some(name), entries) => self.child(name.to_string(), Node::Map(None, entries)),
+				item => self.fill(item)
+			})
+		}
+	}
+	
+	fn write<W: io::Write>(&self, writer: &mut W, indent: Option<&str>, depth: usize) -> io::Result<()> {
+		write!(writer, "<{}", self.name)?;
+		for (name, value) in &self.attributes {
+			write!(writer, " {}=\"{}\"", name, escape_with(value, true))?;
+		}
+		
+		if self.content.is_empty() {
+			return writer.write_all(b"/>");
+		}
+		
+		writer.write_all(b">")?;
+		// whitespace would change the text of elements with mixed content
+		let indent = indent.filter(|_| self.content.iter().all(|c| matches!(c, Content::Element(_))));
+		for content in &self.content {
+			match content {
+				Content::Text(text) => writer.write_all(escape_text(text).as_bytes())?,
+				Content::Element(element) => {
+					if let Some(indent) = indent {
+						write!(writer, "\n{}", indent.repeat(depth + 1))?;
+					}
+					element.write(writer, indent, depth + 1)?;
+				}
+			}
+		}
+		
+		if let Some(indent) = indent {
+			write!(writer, "\n{}", indent.repeat(depth))?;
+		}
+		write!(writer, "</{}>", self.name)
+	}
+}
+
+/// Serializes values into [`Node`]s.
+struct NodeSerializer;
+
+macro_rules! serialize_scalar {
+	($($method:ident($ty:ty))*) => {$(
+		fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+			Ok(Node::Scalar(v.to_string()))
+		}
+	)*};
+}
+
+impl serde::Serializer for NodeSerializer {
+	type Ok                     = Node;
+	type Error                  = SerError;
+	type SerializeSeq           = NodeCompound;
+	type SerializeTuple         = NodeCompound;
+	type SerializeTupleStruct   = NodeCompound;
+	type SerializeTupleVariant  = NodeCompound;
+	type SerializeMap           = NodeCompound;
+	type SerializeStruct        = NodeCompound;
+	type SerializeStructVariant = NodeCompound;
+	
+	serialize_scalar! {
+		serialize_bool(bool) serialize_i8(i8) serialize_i16(i16) serialize_i32(i32) serialize_i64(i64)
+		serialize_u8(u8) serialize_u16(u16) serialize_u32(u32) serialize_u64(u64) serialize_f32(f32)
+		serialize_f64(f64) serialize_char(char) serialize_str(&str)
+	}
+	
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		std::str::from_utf8(v)
+			.map(|v| Node::Scalar(v.to_string()))
+			.map_err(|_| SerError::UnsupportedType)
+	}
+	
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Node::None)
+	}
+	
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Node::Unit)
+	}
+	
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Node::Unit)
+	}
+	
+	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Node::UnitVariant(variant))
+	}
+	
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(
+		self,
+		_name:          &'static str,
+		_variant_index: u32,
+		variant:        &'static str,
+		value:          &T
+	) -> Result<Self::Ok, Self::Error> {
+		Ok(Node::Variant(variant, Box::new(value.serialize(self)?)))
+	}
+	
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(NodeCompound::seq(None, len.unwrap_or(0)))
+	}
+	
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Ok(NodeCompound::seq(None, len))
+	}
+	
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Ok(NodeCompound::seq(None, len))
+	}
+	
+	fn serialize_tuple_variant(
+		self,
+		_name:          &'static str,
+		_variant_index: u32,
+		variant:        &'static str,
+		len:            usize
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(NodeCompound::seq(Some(variant), len))
+	}
+	
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(NodeCompound::map(None, None, len.unwrap_or(0)))
+	}
+	
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(NodeCompound::map(Some(name), None, len))
+	}
+	
+	fn serialize_struct_variant(
+		self,
+		name:           &'static str,
+		_variant_index: u32,
+		variant:        &'static str,
+		len:            usize
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(NodeCompound::map(Some(name), Some(variant), len))
+	}
+}
+
+struct NodeCompound {
+	name:    Option<&'static str>,
+	variant: Option<&'static str>,
+	map:     bool,
+	key:     Option<String>,
+	entries: Vec<(String, Node)>,
+	items:   Vec<Node>
+}
+
+impl NodeCompound {
+	fn seq(variant: Option<&'static str>, len: usize) -> Self {
+		Self { name: None, variant, map: false, key: None, entries: Vec::new(), items: Vec::with_capacity(len) }
+	}
+	
+	fn map(name: Option<&'static str>, variant: Option<&'static str>, len: usize) -> Self {
+		Self { name, variant, map: true, key: None, entries: Vec::with_capacity(len), items: Vec::new() }
+	}
+	
+	fn end(self) -> Node {
+		let node = match self.map {
+			true  => Node::Map(self.name, self.entries),
+			false => Node::Seq(self.items)
+		};
+		
+		match self.variant {
+			Some(variant) => Node::Variant(variant, Box::new(node)),
+			None => node
+		}
+	}
+}
+
+impl SerializeSeq for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.items.push(value.serialize(NodeSerializer)?);
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeTuple for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeTupleStruct for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeTupleVariant for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeMap for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = Some(match key.serialize(NodeSerializer)? {
+			Node::Scalar(key) => key,
+			Node::UnitVariant(key) => key.to_string(),
+			_ => return Err(SerError::Custom("map keys must be strings".to_string()))
 		});
-		s = &s[ext + 1..];
+		Ok(())
 	}
-	buf.push_str(s);
-	buf
-}
\ No newline at end of file
+	
+	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let key = self.key.take().expect("value serialized before key");
+		self.entries.push((key, value.serialize(NodeSerializer)?));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeStruct for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		self.entries.push((key.to_string(), value.serialize(NodeSerializer)?));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+impl SerializeStructVariant for NodeCompound {
+	type Ok    = Node;
+	type Error = SerError;
+	
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		SerializeStruct::serialize_field(self, key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(NodeCompound::end(self))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename = "library")]
+	struct Library {
+		name:    String,
+		open:    bool,
+		#[serde(rename = "book", default)]
+		books:   Vec<Book>,
+		address: Option<Body<String>>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Book {
+		id:    u32,
+		kind:  Kind,
+		#[serde(rename = "$value")]
+		title: String
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Kind {
+		Novel,
+		Poetry
+	}
+	
+	fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T, pretty: bool) -> (T, String) {
+		let mut buf = Vec::new();
+		match pretty {
+			true  => serialize_pretty(&mut buf, value).unwrap(),
+			false => serialize(&mut buf, value).unwrap()
+		}
+		(deserialize_slice(&buf).unwrap(), String::from_utf8(buf).unwrap())
+	}
+	
+	#[test]
+	fn full() {
+		let v = Library {
+			name:    "A \"<quoted>\" name\n& more".to_string(),
+			open:    true,
+			books:   vec![
+				Book { id: 1, kind: Kind::Novel, title: "  Fish & Chips  ".to_string() },
+				Book { id: 2, kind: Kind::Poetry, title: "<Odes>".to_string() }
+			],
+			address: Some(Body { value: "Main Street".to_string() })
+		};
+		
+		let (w, xml) = round_trip(&v, false);
+		assert_eq!(v, w);
+		assert_eq!(xml, concat!(
+			r#"<?xml version="1.0" encoding="UTF-8"?><library name="A &quot;&lt;quoted&gt;&quot; name&#10;&amp; more" open="true">"#,
+			r#"<book id="1" kind="novel">&#32;&#32;Fish &amp; Chips&#32;&#32;</book><book id="2" kind="poetry">&lt;Odes&gt;</book>"#,
+			r#"<address>Main Street</address></library>"#
+		));
+		
+		let (w, xml) = round_trip(&v, true);
+		assert_eq!(v, w);
+		assert!(xml.contains("\n\t<book id=\"2\" kind=\"poetry\">&lt;Odes&gt;</book>\n"));
+	}
+	
+	#[test]
+	fn markup() {
+		let v = deserialize_slice::<Library>(br#"<?xml version="1.0"?>
+		<!DOCTYPE library [
+			<!ELEMENT library (book*)>
+			<!ENTITY city "Springfield">
+			<!ENTITY address "Main Street, &city;">
+		]>
+		<!-- a comment -->
+		<?style sheet?>
+		<library name='x &#x41;&#66;' open="false">
+			<book id="7" kind="novel"><![CDATA[a <b> & c]]> &amp; d<!-- skipped --></book>
+			<address>&address;</address>
+		</library>
+		<!-- trailing comment -->
+		"#).unwrap();
+		
+		assert_eq!(v, Library {
+			name:    "x AB".to_string(),
+			open:    false,
+			books:   vec![Book { id: 7, kind: Kind::Novel, title: "a <b> & c & d".to_string() }],
+			address: Some(Body { value: "Main Street, Springfield".to_string() })
+		});
+		
+		assert_eq!(unescape("&lt;&#x263A;&gt;").unwrap(), "<\u{263A}>");
+		assert!(unescape("&unknown;").is_err());
+	}
+	
+	#[test]
+	fn namespaces() {
+		let xml = br#"<a:root xmlns:a="urn:a" xmlns="urn:default" a:id="1" xml:lang="en" d="2">
+			<b:child xmlns:b="urn:a" b:x="3"/>
+			<child/>
+			<c:child xmlns:c="urn:c"/>
+		</a:root>"#;
+		
+		let events = Reader::new(&xml[..])
+			.with_namespace("urn:a", "")
+			.with_namespace("urn:default", "d")
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		
+		let attributes = |v: &[(&str, &str)]| v.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect::<Vec<_>>();
+		
+		assert_eq!(events, [
+			Event::Start { name: "root".to_string(), attributes: attributes(&[("id", "1"), ("xml:lang", "en"), ("d", "2")]) },
+			Event::Start { name: "child".to_string(), attributes: attributes(&[("x", "3")]) },
+			Event::End("child".to_string()),
+			Event::Start { name: "d:child".to_string(), attributes: Vec::new() },
+			Event::End("d:child".to_string()),
+			Event::Start { name: "c:child".to_string(), attributes: Vec::new() },
+			Event::End("c:child".to_string()),
+			Event::End("root".to_string())
+		]);
+		
+		let mut buf = Vec::new();
+		Body { value: 1 }.serialize(&mut Serializer::new(&mut buf)
+			.with_root("root")
+			.with_namespace("urn:default", "")
+			.with_namespace("urn:a", "a")).unwrap();
+		assert_eq!(buf, br#"<?xml version="1.0" encoding="UTF-8"?><root xmlns="urn:default" xmlns:a="urn:a">1</root>"#);
+	}
+	
+	#[test]
+	fn errors() {
+		let err = deserialize_slice::<Library>(b"<library name=\"x\" open=\"true\">\n\t<book id=\"1\">\n\t</boo>\n</library>").unwrap_err();
+		assert_eq!(err.position(), Some((3, 8)));
+		
+		let err = deserialize_slice::<Library>(b"<library name=\"x\" open=\"maybe\"/>").unwrap_err();
+		assert_eq!(err.position(), Some((1, 1)));
+		
+		let err = deserialize_slice::<Library>(b"<library>\n  <x:book/>\n</library>").unwrap_err();
+		assert_eq!(err.position(), Some((2, 3)));
+		
+		assert!(deserialize_slice::<Library>(b"<library/><library/>").is_err());
+		assert!(deserialize_slice::<Library>(b"<library a=\"1\" a=\"2\"/>").is_err());
+	}
+	
+	/// Mirrors how wlgen reads Wayland protocol descriptions.
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Protocol {
+		Name(String),
+		Copyright(Body<String>),
+		Interface(Vec<Interface>)
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Interface {
+		Name(String),
+		Version(u32),
+		Request(Vec<Message>),
+		Event(Vec<Message>)
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Message {
+		Name(String),
+		Description(Description),
+		Arg(Arg)
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Description {
+		summary: String,
+		#[serde(rename = "$value", default)]
+		content: String
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Arg {
+		name:       String,
+		r#type:     String,
+		#[serde(rename = "allow-null", skip_serializing_if = "Option::is_none")]
+		allow_null: Option<bool>
+	}
+	
+	#[test]
+	fn wayland() {
+		let src = br#"<?xml version="1.0" encoding="UTF-8"?>
+		<protocol name="wayland">
+			<copyright>
+				Copyright &#169; 2008-2011 Kristian H&#248;gsberg
+			</copyright>
+			<interface name="wl_display" version="1">
+				<request name="sync">
+					<description summary="asynchronous roundtrip">
+						The sync request asks the server to emit the 'done' event.
+					</description>
+					<arg name="callback" type="new_id"/>
+				</request>
+				<event name="error">
+					<arg name="object_id" type="object" allow-null="true"/>
+				</event>
+			</interface>
+		</protocol>"#;
+		
+		let protocol = Deserializer::new(&src[..]).deserialize_document::<Vec<Protocol>>().unwrap();
+		assert_eq!(protocol[1], Protocol::Copyright(Body { value: "Copyright \u{a9} 2008-2011 Kristian H\u{f8}gsberg".to_string() }));
+		let Protocol::Interface(interface) = &protocol[2] else { panic!() };
+		assert_eq!(interface[3], Interface::Event(vec![
+			Message::Name("error".to_string()),
+			Message::Arg(Arg { name: "object_id".to_string(), r#type: "object".to_string(), allow_null: Some(true) })
+		]));
+		
+		let mut buf = Vec::new();
+		protocol.serialize(&mut Serializer::pretty(&mut buf).with_root("protocol")).unwrap();
+		assert_eq!(protocol, deserialize_slice::<Vec<Protocol>>(&buf).unwrap());
+	}
+	
+	/// Mixed content, like the type definitions in the Khronos registries.
+	#[test]
+	fn khronos() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		#[serde(rename_all = "lowercase")]
+		enum Member {
+			Optional(bool),
+			#[serde(rename = "$value")]
+			Text(String),
+			Type(Body<String>),
+			Name(Body<String>),
+			Comment(Body<String>)
+		}
+		
+		let src = br#"<member optional="true">const <type>void</type>* <name>pNext</name><comment>chain</comment></member>"#;
+		let member = deserialize_slice::<Vec<Member>>(src).unwrap();
+		assert_eq!(member, [
+			Member::Optional(true),
+			Member::Text("const".to_string()),
+			Member::Type(Body { value: "void".to_string() }),
+			Member::Text("*".to_string()),
+			Member::Name(Body { value: "pNext".to_string() }),
+			Member::Comment(Body { value: "chain".to_string() })
+		]);
+		
+		let mut buf = Vec::new();
+		member.serialize(&mut Serializer::new(&mut buf).with_root("member")).unwrap();
+		assert_eq!(member, deserialize_slice::<Vec<Member>>(&buf).unwrap());
+	}
+}
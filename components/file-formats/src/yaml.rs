@@ -20,4 +20,2386 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-// TODO YAML
\ No newline at end of file
+//! YAML 1.2 support with the core schema. Streams are parsed into a tree of nodes first, which
+//! resolves anchors, aliases and merge keys (`<<`) and keeps the position of every node, so that
+//! errors during deserialization can be located. The serializer writes block collections that are
+//! indented by two spaces.
+//!
+//! Enum variants are represented like in JSON, unit variants as strings and all other variants as
+//! mappings with a single entry. When deserializing, a variant can also be selected with a local
+//! tag, e.g. `!Circle { radius: 1.0 }`.
+
+use {
+	crate::utils::*,
+	std::{io, fmt, borrow::Cow, collections::{HashMap, HashSet}, str::FromStr},
+	serde::{*, de::*, ser::*, forward_to_deserialize_any}
+};
+
+const CORE_TAG_PREFIX: &str = "tag:yaml.org,2002:";
+const MAX_DEPTH: usize = 512;
+/// The maximum number of nodes that may be created by aliases in a single document, this protects
+/// against exponential growth of nested aliases.
+const MAX_ALIASED_NODES: usize = 1 << 20;
+const INDENT: usize = 2;
+
+pub fn serialize<W: io::Write, T: Serialize>(writer: W, value: &T) -> Result<(), SerError> {
+	Value::serializer(value)?.serialize(writer)
+}
+
+/// Writes every value as a separate document of a single stream.
+pub fn serialize_all<'a, W: io::Write, T: Serialize + 'a>(mut writer: W, values: impl IntoIterator<Item = &'a T>) -> Result<(), SerError> {
+	for (i, value) in values.into_iter().enumerate() {
+		if i > 0 {
+			writer.write_all(b"---\n")?;
+		}
+		
+		Value::serializer(value)?.serialize(&mut writer)?;
+	}
+	Ok(())
+}
+
+pub fn deserialize<'de, T: Deserialize<'de>, R: io::BufRead + fmt::Debug>(reader: R) -> Result<T, DeError> {
+	T::deserialize(Deserializer::new(reader))
+}
+
+pub fn deserialize_str<'de, T: Deserialize<'de>>(src: &str) -> Result<T, DeError> {
+	Document::parse(src)?.deserializer()
+}
+
+pub fn deserialize_slice<'de, T: Deserialize<'de>>(slice: &[u8]) -> Result<T, DeError> {
+	deserialize_str(std::str::from_utf8(slice)?)
+}
+
+/// Deserializes every document of a stream.
+pub fn deserialize_all<'de, T: Deserialize<'de>, R: io::BufRead + fmt::Debug>(mut reader: R) -> Result<Vec<T>, DeError> {
+	let mut src = String::new();
+	reader.read_to_string(&mut src)?;
+	Document::parse_all(&src)?.into_iter()
+		.map(Document::deserializer)
+		.collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	Null,
+	Bool(bool),
+	Integer(i128),
+	Float(f64),
+	String(String),
+	Sequence(Vec<Self>),
+	/// The entries of a mapping in the order they were read, keys can be arbitrary nodes.
+	Mapping(Vec<(Self, Self)>)
+}
+
+impl Default for Value {
+	fn default() -> Self {
+		Self::Null
+	}
+}
+
+impl Value {
+	pub fn is_null(&self) -> bool {
+		matches!(self, Self::Null)
+	}
+	
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Self::Bool(v) => Some(*v),
+			_ => None
+		}
+	}
+	
+	pub fn as_integer(&self) -> Option<i128> {
+		match self {
+			Self::Integer(v) => Some(*v),
+			_ => None
+		}
+	}
+	
+	/// Returns the value of a float or an integer.
+	pub fn as_float(&self) -> Option<f64> {
+		match self {
+			Self::Float(v) => Some(*v),
+			Self::Integer(v) => Some(*v as _),
+			_ => None
+		}
+	}
+	
+	pub fn as_string(&self) -> Option<&str> {
+		match self {
+			Self::String(v) => Some(v),
+			_ => None
+		}
+	}
+	
+	pub fn as_sequence(&self) -> Option<&[Self]> {
+		match self {
+			Self::Sequence(v) => Some(v),
+			_ => None
+		}
+	}
+	
+	pub fn as_mapping(&self) -> Option<&[(Self, Self)]> {
+		match self {
+			Self::Mapping(v) => Some(v),
+			_ => None
+		}
+	}
+	
+	/// Returns the value at the given path of string keys.
+	pub fn get(&self, path: &[&str]) -> Option<&Self> {
+		path.iter().try_fold(self, |value, key| value.as_mapping()?.iter()
+			.find(|(k, _)| k.as_string() == Some(*key))
+			.map(|(_, v)| v))
+	}
+	
+	pub fn serializer<T: Serialize + ?Sized>(v: &T) -> Result<Self, SerError> {
+		v.serialize(ValueSerializer)
+	}
+	
+	pub fn deserializer<'de, T: Deserialize<'de>>(self) -> Result<T, DeError> {
+		T::deserialize(Node::from(self))
+	}
+	
+	/// Writes this value as a single YAML document.
+	pub fn serialize(&self, mut writer: impl io::Write) -> Result<(), SerError> {
+		writeln!(writer, "{}", self)?;
+		Ok(())
+	}
+	
+	/// Reads a stream that contains a single document.
+	pub fn deserialize(mut reader: impl io::BufRead) -> Result<Self, DeError> {
+		let mut src = String::new();
+		reader.read_to_string(&mut src)?;
+		Document::parse(&src)?.to_value()
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut out = String::new();
+		write_node(&mut out, self, 0, Parent::Root);
+		f.write_str(&out)
+	}
+}
+
+impl FromStr for Value {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Document::parse(s)?.to_value()
+	}
+}
+
+impl From<bool> for Value {
+	fn from(v: bool) -> Self {
+		Self::Bool(v)
+	}
+}
+
+impl From<i64> for Value {
+	fn from(v: i64) -> Self {
+		Self::Integer(v as _)
+	}
+}
+
+impl From<f64> for Value {
+	fn from(v: f64) -> Self {
+		Self::Float(v)
+	}
+}
+
+impl From<String> for Value {
+	fn from(v: String) -> Self {
+		Self::String(v)
+	}
+}
+
+impl From<&str> for Value {
+	fn from(v: &str) -> Self {
+		Self::String(v.to_string())
+	}
+}
+
+impl From<Vec<Self>> for Value {
+	fn from(v: Vec<Self>) -> Self {
+		Self::Sequence(v)
+	}
+}
+
+impl std::iter::FromIterator<Self> for Value {
+	fn from_iter<T: IntoIterator<Item = Self>>(iter: T) -> Self {
+		Self::Sequence(iter.into_iter().collect())
+	}
+}
+
+impl std::iter::FromIterator<(Self, Self)> for Value {
+	fn from_iter<T: IntoIterator<Item = (Self, Self)>>(iter: T) -> Self {
+		Self::Mapping(iter.into_iter().collect())
+	}
+}
+
+impl Serialize for Value {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Null       => serializer.serialize_unit(),
+			Self::Bool(v)    => serializer.serialize_bool(*v),
+			Self::Integer(v) => match (i64::try_from(*v), u64::try_from(*v)) {
+				(Ok(v), _) => serializer.serialize_i64(v),
+				(_, Ok(v)) => serializer.serialize_u64(v),
+				_          => serializer.serialize_i128(*v)
+			},
+			Self::Float(v)    => serializer.serialize_f64(*v),
+			Self::String(v)   => serializer.serialize_str(v),
+			Self::Sequence(v) => v.serialize(serializer),
+			Self::Mapping(v)  => {
+				let mut map = serializer.serialize_map(Some(v.len()))?;
+				for (key, value) in v {
+					map.serialize_entry(key, value)?;
+				}
+				map.end()
+			}
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Value {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct ValueVisitor;
+		
+		impl<'de> Visitor<'de> for ValueVisitor {
+			type Value = Value;
+			
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("any YAML value")
+			}
+			
+			fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+				Ok(Value::Bool(v))
+			}
+			
+			fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+				Ok(Value::Integer(v as _))
+			}
+			
+			fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+				Ok(Value::Integer(v))
+			}
+			
+			fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+				Ok(Value::Integer(v as _))
+			}
+			
+			fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+				Ok(Value::Float(v))
+			}
+			
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+				Ok(Value::String(v.to_string()))
+			}
+			
+			fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+				Ok(Value::String(v))
+			}
+			
+			fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+				Ok(Value::Null)
+			}
+			
+			fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+				Ok(Value::Null)
+			}
+			
+			fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+				<Value as Deserialize>::deserialize(deserializer)
+			}
+			
+			fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+				let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+				while let Some(item) = seq.next_element()? {
+					items.push(item);
+				}
+				Ok(Value::Sequence(items))
+			}
+			
+			fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+				let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+				while let Some(entry) = map.next_entry()? {
+					entries.push(entry);
+				}
+				Ok(Value::Mapping(entries))
+			}
+		}
+		
+		deserializer.deserialize_any(ValueVisitor)
+	}
+}
+
+/// A document of a YAML stream. Anchors, aliases and merge keys are resolved while parsing, the
+/// position of every node is kept, so that errors while deserializing can be located.
+#[derive(Clone, Debug)]
+pub struct Document {
+	root: Node
+}
+
+impl Document {
+	/// Parses a stream that contains a single document, an empty stream yields a null document.
+	pub fn parse(src: &str) -> Result<Self, DeError> {
+		let mut documents = Self::parse_all(src)?;
+		match documents.len() {
+			0 => Ok(Self { root: Node::null((1, 1)) }),
+			1 => Ok(documents.remove(0)),
+			_ => Err(DeError::ParseError("expected a single document".to_string())
+				.at(documents[1].root.line, documents[1].root.column))
+		}
+	}
+	
+	/// Parses all documents of a stream.
+	pub fn parse_all(src: &str) -> Result<Vec<Self>, DeError> {
+		let src = src.strip_prefix('\u{feff}').unwrap_or(src);
+		let src = match src.contains('\r') {
+			true  => Cow::Owned(src.replace("\r\n", "\n").replace('\r', "\n")),
+			false => Cow::Borrowed(src)
+		};
+		
+		Ok(Parser::new(&src).documents()?.into_iter()
+			.map(|root| Self { root })
+			.collect())
+	}
+	
+	pub fn read(mut reader: impl io::Read) -> Result<Self, DeError> {
+		let mut src = String::new();
+		reader.read_to_string(&mut src)?;
+		Self::parse(&src)
+	}
+	
+	/// Builds the data model of this document.
+	pub fn to_value(&self) -> Result<Value, DeError> {
+		<Value as Deserialize>::deserialize(self.root.clone())
+	}
+	
+	pub fn deserializer<'de, T: Deserialize<'de>>(self) -> Result<T, DeError> {
+		T::deserialize(self.root)
+	}
+}
+
+impl FromStr for Document {
+	type Err = DeError;
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+/// Reads a whole YAML stream that contains a single document, aliases may refer to any node that
+/// precedes them, so documents are not deserialized in a streaming fashion.
+#[derive(Debug)]
+pub struct Deserializer<T: io::BufRead> {
+	reader: T
+}
+
+impl<T: io::BufRead> Deserializer<T> {
+	pub fn new(reader: T) -> Self {
+		Self { reader }
+	}
+	
+	pub fn into_document(self) -> Result<Document, DeError> {
+		Document::read(self.reader)
+	}
+}
+
+macro_rules! forward_to_document {
+	($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+		fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+			self.into_document()?.root.$method($($arg,)* visitor)
+		}
+	)*};
+}
+
+impl<'de, T: io::BufRead> serde::Deserializer<'de> for Deserializer<T> {
+	type Error = DeError;
+	
+	forward_to_document! {
+		deserialize_any();
+		deserialize_char();
+		deserialize_str();
+		deserialize_string();
+		deserialize_identifier();
+		deserialize_option();
+		deserialize_newtype_struct(name: &'static str);
+		deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+		deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+		deserialize_bytes();
+		deserialize_byte_buf();
+		deserialize_ignored_any();
+	}
+	
+	forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64
+		unit unit_struct seq tuple tuple_struct map);
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+	value:  NodeValue,
+	/// The resolved tag, either a full tag like `tag:yaml.org,2002:str` or a local tag like `!foo`.
+	tag:    Option<String>,
+	line:   usize,
+	column: usize
+}
+
+#[derive(Clone, Debug)]
+enum NodeValue {
+	/// The content of a scalar and whether it is plain, only plain scalars are resolved to other
+	/// types than strings.
+	Scalar(String, bool),
+	Sequence(Vec<Node>),
+	Mapping(Vec<(Node, Node)>)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Resolved {
+	Null,
+	Bool(bool),
+	Int(i128),
+	Float(f64),
+	Str
+}
+
+impl Node {
+	fn new((line, column): (usize, usize), value: NodeValue) -> Self {
+		Self { value, tag: None, line, column }
+	}
+	
+	fn null(position: (usize, usize)) -> Self {
+		Self::new(position, NodeValue::Scalar(String::new(), true))
+	}
+	
+	fn count(&self) -> usize {
+		1 + match &self.value {
+			NodeValue::Scalar(..)   => 0,
+			NodeValue::Sequence(v)  => v.iter().map(Self::count).sum(),
+			NodeValue::Mapping(v)   => v.iter().map(|(k, v)| k.count() + v.count()).sum()
+		}
+	}
+	
+	fn is_merge_key(&self) -> bool {
+		self.tag.is_none() && matches!(&self.value, NodeValue::Scalar(v, true) if v == "<<")
+	}
+	
+	/// Resolves the type of a scalar from its tag or, if it is plain and untagged, with the core
+	/// schema. Collections always resolve to `Resolved::Str`.
+	fn resolve(&self) -> Result<Resolved, DeError> {
+		let NodeValue::Scalar(text, plain) = &self.value else { return Ok(Resolved::Str) };
+		match self.tag.as_deref().map(|tag| tag.strip_prefix(CORE_TAG_PREFIX).unwrap_or(tag)) {
+			Some("str") | Some("!") => Ok(Resolved::Str),
+			Some("null") => match is_null(text) {
+				true  => Ok(Resolved::Null),
+				false => Err(DeError::invalid_token(text, "null"))
+			},
+			Some("bool") => parse_bool(text).map(Resolved::Bool)
+				.ok_or_else(|| DeError::invalid_token(text, "a boolean")),
+			Some("int") => parse_int(text).map(Resolved::Int)
+				.ok_or_else(|| DeError::invalid_token(text, "an integer")),
+			Some("float") => parse_float(text).or_else(|| parse_int(text).map(|v| v as _)).map(Resolved::Float)
+				.ok_or_else(|| DeError::invalid_token(text, "a float")),
+			_ if !*plain => Ok(Resolved::Str),
+			_ => Ok(resolve_plain(text))
+		}
+	}
+	
+	/// Attaches the position of a node to an error, nodes that were converted from a `Value` have
+	/// no position.
+	fn located<T>(line: usize, column: usize, result: Result<T, DeError>) -> Result<T, DeError> {
+		match line {
+			0 => result,
+			_ => result.map_err(|e| e.at(line, column))
+		}
+	}
+}
+
+impl From<Value> for Node {
+	fn from(value: Value) -> Self {
+		let position = (0, 0);
+		match value {
+			Value::Null        => Self::null(position),
+			Value::Bool(v)     => Self::new(position, NodeValue::Scalar(v.to_string(), true)),
+			Value::Integer(v)  => Self::new(position, NodeValue::Scalar(v.to_string(), true)),
+			Value::Float(v)    => Self::new(position, NodeValue::Scalar(float_to_string(v), true)),
+			Value::String(v)   => Self::new(position, NodeValue::Scalar(v, false)),
+			Value::Sequence(v) => Self::new(position, NodeValue::Sequence(v.into_iter().map(Self::from).collect())),
+			Value::Mapping(v)  => Self::new(position, NodeValue::Mapping(v.into_iter()
+				.map(|(k, v)| (Self::from(k), Self::from(v)))
+				.collect()))
+		}
+	}
+}
+
+impl<'de> serde::Deserializer<'de> for Node {
+	type Error = DeError;
+	
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let (line, column) = (self.line, self.column);
+		let resolved = Self::located(line, column, self.resolve())?;
+		Self::located(line, column, match self.value {
+			NodeValue::Sequence(items) => {
+				let len = items.len();
+				let mut seq = NodeSeq(items.into_iter());
+				let value = visitor.visit_seq(&mut seq)?;
+				match seq.0.len() {
+					0 => Ok(value),
+					remaining => Err(DeError::invalid_length(len, &format!("{} elements", len - remaining).as_str()))
+				}
+			}
+			NodeValue::Mapping(entries) => visitor.visit_map(NodeMap { entries: entries.into_iter(), value: None }),
+			NodeValue::Scalar(text, _) => match resolved {
+				Resolved::Null     => visitor.visit_unit(),
+				Resolved::Bool(v)  => visitor.visit_bool(v),
+				Resolved::Int(v)   => match (u64::try_from(v), i64::try_from(v)) {
+					(Ok(v), _) => visitor.visit_u64(v),
+					(_, Ok(v)) => visitor.visit_i64(v),
+					_          => visitor.visit_i128(v)
+				},
+				Resolved::Float(v) => visitor.visit_f64(v),
+				Resolved::Str      => visitor.visit_string(text)
+			}
+		})
+	}
+	
+	forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64
+		unit unit_struct seq tuple tuple_struct map struct);
+	
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+	
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+	
+	/// Scalars are passed as strings regardless of their resolved type, so that e.g. `1.0` can be
+	/// read into a string.
+	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value {
+			NodeValue::Scalar(text, _) => Self::located(self.line, self.column, visitor.visit_string(text)),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+	
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+	
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_byte_buf(visitor)
+	}
+	
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let (line, column) = (self.line, self.column);
+		match self.value {
+			NodeValue::Sequence(_) => {
+				let bytes = Vec::<u8>::deserialize(self)?;
+				Self::located(line, column, visitor.visit_byte_buf(bytes))
+			}
+			_ => self.deserialize_string(visitor)
+		}
+	}
+	
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.resolve() {
+			Ok(Resolved::Null) => Self::located(self.line, self.column, visitor.visit_none()),
+			_ => visitor.visit_some(self)
+		}
+	}
+	
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+	
+	fn deserialize_enum<V: Visitor<'de>>(mut self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		let (line, column) = (self.line, self.column);
+		if let Some(variant) = self.tag.as_deref().and_then(|tag| tag.strip_prefix('!')).filter(|tag| !tag.is_empty()) {
+			let key = Self::new((line, column), NodeValue::Scalar(variant.to_string(), false));
+			self.tag = None;
+			return Self::located(line, column, visitor.visit_enum(NodeEnum { key, value: self }));
+		}
+		
+		match self.value {
+			NodeValue::Scalar(text, _) => Self::located(line, column,
+				visitor.visit_enum(IntoDeserializer::<DeError>::into_deserializer(text))),
+			NodeValue::Mapping(mut entries) if entries.len() == 1 => {
+				let (key, value) = entries.pop().unwrap();
+				Self::located(line, column, visitor.visit_enum(NodeEnum { key, value }))
+			}
+			_ => self.deserialize_any(visitor)
+		}
+	}
+	
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+}
+
+struct NodeSeq(std::vec::IntoIter<Node>);
+
+impl<'de> SeqAccess<'de> for &mut NodeSeq {
+	type Error = DeError;
+	
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		self.0.next()
+			.map(|v| seed.deserialize(v))
+			.transpose()
+	}
+	
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.0.len())
+	}
+}
+
+struct NodeMap {
+	entries: std::vec::IntoIter<(Node, Node)>,
+	value:   Option<Node>
+}
+
+impl<'de> MapAccess<'de> for NodeMap {
+	type Error = DeError;
+	
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		match self.entries.next() {
+			None => Ok(None),
+			Some((key, value)) => {
+				self.value = Some(value);
+				let (line, column) = (key.line, key.column);
+				Node::located(line, column, seed.deserialize(key).map(Some))
+			}
+		}
+	}
+	
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		seed.deserialize(self.value.take().expect("`next_value_seed` called before `next_key_seed`"))
+	}
+	
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.entries.len())
+	}
+}
+
+struct NodeEnum {
+	key:   Node,
+	value: Node
+}
+
+impl<'de> EnumAccess<'de> for NodeEnum {
+	type Error   = DeError;
+	type Variant = Node;
+	
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		Ok((seed.deserialize(self.key)?, self.value))
+	}
+}
+
+impl<'de> VariantAccess<'de> for Node {
+	type Error = DeError;
+	
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		<()>::deserialize(self)
+	}
+	
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		seed.deserialize(self)
+	}
+	
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_seq(self, visitor)
+	}
+	
+	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		serde::Deserializer::deserialize_map(self, visitor)
+	}
+}
+
+fn is_null(text: &str) -> bool {
+	matches!(text, "" | "~" | "null" | "Null" | "NULL")
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+	match text {
+		"true" | "True" | "TRUE"    => Some(true),
+		"false" | "False" | "FALSE" => Some(false),
+		_ => None
+	}
+}
+
+fn parse_int(text: &str) -> Option<i128> {
+	let digits = |s: &str, radix| !s.is_empty() && s.chars().all(|c| c.is_digit(radix));
+	if let Some(hex) = text.strip_prefix("0x") {
+		return digits(hex, 16).then(|| i128::from_str_radix(hex, 16).ok()).flatten();
+	}
+	
+	if let Some(oct) = text.strip_prefix("0o") {
+		return digits(oct, 8).then(|| i128::from_str_radix(oct, 8).ok()).flatten();
+	}
+	
+	digits(text.strip_prefix(['-', '+']).unwrap_or(text), 10)
+		.then(|| text.parse().ok())
+		.flatten()
+}
+
+fn parse_float(text: &str) -> Option<f64> {
+	let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+	let sign = if text.starts_with('-') { -1.0 } else { 1.0 };
+	match unsigned {
+		".inf" | ".Inf" | ".INF" => return Some(sign * f64::INFINITY),
+		".nan" | ".NaN" | ".NAN" if unsigned.len() == text.len() => return Some(f64::NAN),
+		_ => ()
+	}
+	
+	// [-+]? ( \. [0-9]+ | [0-9]+ ( \. [0-9]* )? ) ( [eE] [-+]? [0-9]+ )?
+	let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+		Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+		None    => (unsigned, None)
+	};
+	let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	let valid = (!int.is_empty() || !frac.is_empty())
+		&& int.bytes().chain(frac.bytes()).all(|c| c.is_ascii_digit())
+		&& exponent.is_none_or(|e| {
+			let e = e.strip_prefix(['-', '+']).unwrap_or(e);
+			!e.is_empty() && e.bytes().all(|c| c.is_ascii_digit())
+		});
+	valid.then(|| text.parse().ok()).flatten()
+}
+
+/// Resolves a plain scalar with the core schema.
+fn resolve_plain(text: &str) -> Resolved {
+	if is_null(text) {
+		Resolved::Null
+	} else if let Some(v) = parse_bool(text) {
+		Resolved::Bool(v)
+	} else if let Some(v) = parse_int(text) {
+		Resolved::Int(v)
+	} else if let Some(v) = parse_float(text) {
+		Resolved::Float(v)
+	} else {
+		Resolved::Str
+	}
+}
+
+fn is_flow_indicator(c: Option<u8>) -> bool {
+	matches!(c, Some(b',' | b'[' | b']' | b'{' | b'}'))
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Context {
+	/// An implicit key of a block mapping, which must not span multiple lines.
+	BlockKey,
+	/// A node in block context, continuation lines of plain scalars must be indented at least by
+	/// the given number of spaces.
+	Block(isize),
+	Flow
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Chomping {
+	Strip,
+	Clip,
+	Keep
+}
+
+#[derive(Clone, Debug, Default)]
+struct Properties {
+	anchor: Option<String>,
+	tag:    Option<String>
+}
+
+impl Properties {
+	fn is_empty(&self) -> bool {
+		self.anchor.is_none() && self.tag.is_none()
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+struct State {
+	pos:        usize,
+	line:       usize,
+	line_start: usize
+}
+
+struct Parser<'a> {
+	src:        &'a str,
+	pos:        usize,
+	line:       usize,
+	line_start: usize,
+	depth:      usize,
+	aliased:    usize,
+	anchors:    HashMap<String, Node>,
+	handles:    HashMap<String, String>
+}
+
+impl<'a> Parser<'a> {
+	fn new(src: &'a str) -> Self {
+		Self {
+			src,
+			pos:        0,
+			line:       1,
+			line_start: 0,
+			depth:      0,
+			aliased:    0,
+			anchors:    HashMap::new(),
+			handles:    HashMap::new()
+		}
+	}
+	
+	fn state(&self) -> State {
+		State { pos: self.pos, line: self.line, line_start: self.line_start }
+	}
+	
+	fn restore(&mut self, state: State) {
+		self.pos = state.pos;
+		self.line = state.line;
+		self.line_start = state.line_start;
+	}
+	
+	fn peek(&self) -> Option<u8> {
+		self.src.as_bytes().get(self.pos).copied()
+	}
+	
+	fn peek_at(&self, offset: usize) -> Option<u8> {
+		self.src.as_bytes().get(self.pos + offset).copied()
+	}
+	
+	/// Returns true if the byte at the given offset is whitespace, a line break or the end of input.
+	fn is_blank_at(&self, offset: usize) -> bool {
+		matches!(self.peek_at(offset), None | Some(b' ' | b'\t' | b'\n'))
+	}
+	
+	fn bump(&mut self) {
+		self.bump_char();
+	}
+	
+	fn bump_char(&mut self) -> Option<char> {
+		let c = self.src[self.pos..].chars().next()?;
+		self.pos += c.len_utf8();
+		if c == '\n' {
+			self.line += 1;
+			self.line_start = self.pos;
+		}
+		Some(c)
+	}
+	
+	fn position(&self) -> (usize, usize) {
+		(self.line, self.src[self.line_start..self.pos].chars().count() + 1)
+	}
+	
+	fn error(&self, msg: impl fmt::Display) -> DeError {
+		let (line, column) = self.position();
+		DeError::ParseError(msg.to_string()).at(line, column)
+	}
+	
+	fn unexpected(&self, expected: &str) -> DeError {
+		match self.src[self.pos..].chars().next() {
+			Some(c) => self.error(format_args!("unexpected {:?}, expected {}", c, expected)),
+			None    => self.error(format_args!("unexpected end of input, expected {}", expected))
+		}
+	}
+	
+	fn enter(&mut self) -> Result<(), DeError> {
+		self.depth += 1;
+		match self.depth > MAX_DEPTH {
+			true  => Err(self.error("exceeded the maximum nesting depth")),
+			false => Ok(())
+		}
+	}
+	
+	fn skip_space(&mut self) {
+		while matches!(self.peek(), Some(b' ' | b'\t')) {
+			self.pos += 1;
+		}
+	}
+	
+	fn skip_comment(&mut self) {
+		if self.peek() == Some(b'#') {
+			while !matches!(self.peek(), None | Some(b'\n')) {
+				self.bump_char();
+			}
+		}
+	}
+	
+	/// Skips whitespace, comments and line breaks.
+	fn skip_to_content(&mut self) {
+		loop {
+			self.skip_space();
+			self.skip_comment();
+			match self.peek() {
+				Some(b'\n') => self.bump(),
+				_ => break
+			}
+		}
+	}
+	
+	/// Returns true if only whitespace precedes the current position on this line.
+	fn first_on_line(&self) -> bool {
+		self.src.as_bytes()[self.line_start..self.pos].iter().all(|c| matches!(c, b' ' | b'\t'))
+	}
+	
+	fn indentation(&self) -> Result<isize, DeError> {
+		let prefix = &self.src[self.line_start..self.pos];
+		match prefix.bytes().all(|c| c == b' ') {
+			true  => Ok(prefix.len() as isize),
+			false => Err(self.error("tabs must not be used for indentation"))
+		}
+	}
+	
+	fn column(&self) -> isize {
+		self.src[self.line_start..self.pos].chars().count() as isize
+	}
+	
+	fn at_marker(&self, marker: &str) -> bool {
+		self.pos == self.line_start && self.src[self.pos..].starts_with(marker) && self.is_blank_at(marker.len())
+	}
+	
+	/// Returns true at the end of input and at document markers. Directives are accepted after
+	/// documents that are not terminated by `...`, as no node can start with `%`.
+	fn at_document_boundary(&self) -> bool {
+		self.peek().is_none() || self.at_marker("---") || self.at_marker("...")
+			|| (self.peek() == Some(b'%') && self.pos == self.line_start)
+	}
+	
+	fn documents(&mut self) -> Result<Vec<Node>, DeError> {
+		let mut documents = Vec::new();
+		loop {
+			self.anchors.clear();
+			self.handles.clear();
+			self.aliased = 0;
+			self.skip_to_content();
+			
+			let mut directives = false;
+			while self.peek() == Some(b'%') && self.pos == self.line_start {
+				self.directive()?;
+				directives = true;
+				self.skip_to_content();
+			}
+			
+			if self.at_marker("---") {
+				self.pos += 3;
+			} else if directives {
+				return Err(self.unexpected("'---' after directives"));
+			} else if self.at_marker("...") {
+				self.pos += 3;
+				continue;
+			} else if self.peek().is_none() {
+				break;
+			}
+			
+			let root = self.block_node(-1, false)?;
+			self.skip_to_content();
+			if self.at_marker("...") {
+				self.pos += 3;
+			} else if !self.at_document_boundary() {
+				return Err(self.unexpected("the end of the document"));
+			}
+			documents.push(root);
+		}
+		Ok(documents)
+	}
+	
+	fn directive(&mut self) -> Result<(), DeError> {
+		self.bump();
+		match self.word("a directive name")? {
+			"YAML" => {
+				self.skip_space();
+				let version = self.word("a version")?;
+				if !version.starts_with("1.") {
+					return Err(self.error(format_args!("unsupported YAML version {}", version)));
+				}
+			}
+			"TAG" => {
+				self.skip_space();
+				let handle = self.word("a tag handle")?.to_string();
+				self.skip_space();
+				let prefix = self.word("a tag prefix")?.to_string();
+				self.handles.insert(handle, prefix);
+			}
+			// reserved directives are ignored
+			_ => while !matches!(self.peek(), None | Some(b'\n')) {
+				self.bump_char();
+			}
+		}
+		
+		self.skip_space();
+		self.skip_comment();
+		match self.peek() {
+			None | Some(b'\n') => Ok(()),
+			_ => Err(self.unexpected("the end of the directive"))
+		}
+	}
+	
+	/// Parses an anchor or alias name.
+	fn name(&mut self, expected: &str) -> Result<&'a str, DeError> {
+		let start = self.pos;
+		while !self.is_blank_at(0) && !is_flow_indicator(self.peek()) {
+			self.bump_char();
+		}
+		match start == self.pos {
+			true  => Err(self.unexpected(expected)),
+			false => Ok(&self.src[start..self.pos])
+		}
+	}
+	
+	/// Parses a directive name or parameter.
+	fn word(&mut self, expected: &str) -> Result<&'a str, DeError> {
+		let start = self.pos;
+		while !self.is_blank_at(0) {
+			self.bump_char();
+		}
+		match start == self.pos {
+			true  => Err(self.unexpected(expected)),
+			false => Ok(&self.src[start..self.pos])
+		}
+	}
+	
+	fn properties(&mut self, context: Context) -> Result<Properties, DeError> {
+		let mut props = Properties::default();
+		loop {
+			match self.peek() {
+				Some(b'&') if props.anchor.is_none() => {
+					self.bump();
+					props.anchor = Some(self.name("an anchor name")?.to_string());
+				}
+				Some(b'!') if props.tag.is_none() => props.tag = Some(self.tag()?),
+				_ => break
+			}
+			
+			match context {
+				Context::Flow => self.skip_to_content(),
+				_ => self.skip_space()
+			}
+		}
+		Ok(props)
+	}
+	
+	fn tag(&mut self) -> Result<String, DeError> {
+		self.bump();
+		if self.peek() == Some(b'<') {
+			self.bump();
+			let start = self.pos;
+			while self.peek() != Some(b'>') {
+				match self.peek() {
+					None | Some(b'\n') => return Err(self.unexpected("'>'")),
+					_ => { self.bump_char(); }
+				}
+			}
+			let tag = self.src[start..self.pos].to_string();
+			self.bump();
+			return Ok(tag);
+		}
+		
+		let start = self.pos;
+		while !self.is_blank_at(0) && !is_flow_indicator(self.peek()) {
+			self.bump_char();
+		}
+		
+		let tag = &self.src[start..self.pos];
+		if tag.is_empty() {
+			return Ok("!".to_string());
+		}
+		
+		let (handle, suffix) = match tag.find('!') {
+			Some(i) => (format!("!{}", &tag[..=i]), &tag[i + 1..]),
+			None    => ("!".to_string(), tag)
+		};
+		
+		let prefix = match (self.handles.get(&handle), handle.as_str()) {
+			(Some(prefix), _) => prefix.as_str(),
+			(None, "!")       => "!",
+			(None, "!!")      => CORE_TAG_PREFIX,
+			_ => return Err(self.error(format_args!("undeclared tag handle {}", handle)))
+		};
+		Ok(format!("{}{}", prefix, suffix))
+	}
+	
+	fn apply(&mut self, mut node: Node, props: Properties) -> Node {
+		if props.tag.is_some() {
+			node.tag = props.tag;
+		}
+		
+		if let Some(anchor) = props.anchor {
+			self.anchors.insert(anchor, node.clone());
+		}
+		node
+	}
+	
+	fn alias(&mut self) -> Result<Node, DeError> {
+		let position = self.position();
+		self.bump();
+		let name = self.name("an alias name")?;
+		let mut node = match self.anchors.get(name) {
+			Some(node) => node.clone(),
+			None => return Err(DeError::ParseError(format!("undefined alias *{}", name)).at(position.0, position.1))
+		};
+		
+		self.aliased += node.count();
+		if self.aliased > MAX_ALIASED_NODES {
+			return Err(DeError::ParseError("too many nodes created by aliases".to_string()).at(position.0, position.1));
+		}
+		
+		node.line = position.0;
+		node.column = position.1;
+		Ok(node)
+	}
+	
+	/// Parses a node in block context. The node must be more indented than its parent, a block
+	/// sequence that is the value of a mapping entry may also have the same indentation.
+	fn block_node(&mut self, indent: isize, seq_at_indent: bool) -> Result<Node, DeError> {
+		self.enter()?;
+		let node = self.block_node_inner(indent, seq_at_indent);
+		self.depth -= 1;
+		node
+	}
+	
+	fn block_node_inner(&mut self, indent: isize, seq_at_indent: bool) -> Result<Node, DeError> {
+		self.skip_to_content();
+		let start = self.state();
+		let position = self.position();
+		let props = self.properties(Context::Block(indent + 1))?;
+		if !props.is_empty() {
+			self.skip_to_content();
+		}
+		
+		if self.at_document_boundary() {
+			return Ok(self.apply(Node::null(position), props));
+		}
+		
+		let seq = self.peek() == Some(b'-') && self.is_blank_at(1);
+		if self.first_on_line() {
+			let ind = self.indentation()?;
+			if !(ind > indent || (ind == indent && seq_at_indent && seq)) {
+				return Ok(self.apply(Node::null(position), props));
+			}
+		}
+		
+		// properties on the same line as an implicit key belong to the key, not the mapping
+		let same_line = !props.is_empty() && self.line == start.line;
+		let column = self.column();
+		let node = match self.peek() {
+			Some(b'-') if seq => self.block_sequence(column)?,
+			Some(b'?') if self.is_blank_at(1) => self.block_mapping(column)?,
+			Some(b'|' | b'>') => self.block_scalar(indent)?,
+			_ if self.is_implicit_key() => match same_line {
+				true => {
+					self.restore(start);
+					return self.block_mapping(self.column());
+				}
+				false => self.block_mapping(column)?
+			},
+			_ if same_line => {
+				self.restore(start);
+				return self.inline_node(Context::Block(indent + 1));
+			}
+			_ => self.inline_node(Context::Block(indent + 1))?
+		};
+		Ok(self.apply(node, props))
+	}
+	
+	/// Checks if an implicit key of a block mapping starts at the current position.
+	fn is_implicit_key(&mut self) -> bool {
+		let state = self.state();
+		let aliased = self.aliased;
+		let key = self.inline_node(Context::BlockKey).is_ok() && self.line == state.line && {
+			self.skip_space();
+			self.peek() == Some(b':') && self.is_blank_at(1)
+		};
+		self.restore(state);
+		self.aliased = aliased;
+		key
+	}
+	
+	fn block_sequence(&mut self, indent: isize) -> Result<Node, DeError> {
+		let position = self.position();
+		let mut items = Vec::new();
+		loop {
+			self.bump();
+			items.push(self.block_node(indent, false)?);
+			self.skip_to_content();
+			if self.at_document_boundary() {
+				break;
+			}
+			
+			if !self.first_on_line() {
+				return Err(self.unexpected("a line break"));
+			}
+			
+			let ind = self.indentation()?;
+			if ind > indent {
+				return Err(self.error("bad indentation of a sequence entry"));
+			}
+			
+			if ind < indent || !(self.peek() == Some(b'-') && self.is_blank_at(1)) {
+				break;
+			}
+		}
+		Ok(Node::new(position, NodeValue::Sequence(items)))
+	}
+	
+	fn block_mapping(&mut self, indent: isize) -> Result<Node, DeError> {
+		let position = self.position();
+		let mut entries = Vec::new();
+		loop {
+			if self.peek() == Some(b'?') && self.is_blank_at(1) {
+				self.bump();
+				let key = self.block_node(indent, false)?;
+				self.skip_to_content();
+				let value = match !self.at_document_boundary() && self.first_on_line()
+					&& self.indentation()? == indent && self.peek() == Some(b':') && self.is_blank_at(1) {
+					true => {
+						self.bump();
+						self.block_node(indent, true)?
+					}
+					false => Node::null(self.position())
+				};
+				entries.push((key, value));
+			} else {
+				let key = self.inline_node(Context::BlockKey)?;
+				self.skip_space();
+				if !(self.peek() == Some(b':') && self.is_blank_at(1)) {
+					return Err(self.unexpected("':'"));
+				}
+				self.bump();
+				entries.push((key, self.block_node(indent, true)?));
+			}
+			
+			self.skip_to_content();
+			if self.at_document_boundary() {
+				break;
+			}
+			
+			if !self.first_on_line() {
+				return Err(self.unexpected("a line break"));
+			}
+			
+			let ind = self.indentation()?;
+			if ind > indent {
+				return Err(self.error("bad indentation of a mapping entry"));
+			}
+			
+			if ind < indent || (self.peek() == Some(b'-') && self.is_blank_at(1)) {
+				break;
+			}
+		}
+		self.mapping(position, entries)
+	}
+	
+	/// Builds a mapping node, checks for duplicate keys and applies merge keys. Explicit entries
+	/// take precedence over merged ones, earlier merged mappings over later ones.
+	fn mapping(&self, position: (usize, usize), entries: Vec<(Node, Node)>) -> Result<Node, DeError> {
+		let mut keys = HashSet::new();
+		let mut merged = Vec::new();
+		let mut result = Vec::with_capacity(entries.len());
+		for (key, value) in entries {
+			if key.is_merge_key() {
+				let (line, column) = (value.line, value.column);
+				match value.value {
+					NodeValue::Mapping(entries) => merged.push(entries),
+					NodeValue::Sequence(items) => for item in items {
+						match item.value {
+							NodeValue::Mapping(entries) => merged.push(entries),
+							_ => return Err(DeError::ParseError("merge values must be mappings".to_string())
+								.at(item.line, item.column))
+						}
+					},
+					_ => return Err(DeError::ParseError("merge values must be mappings".to_string()).at(line, column))
+				}
+				continue;
+			}
+			
+			if let NodeValue::Scalar(text, _) = &key.value {
+				if !keys.insert(text.clone()) {
+					return Err(DeError::ParseError(format!("duplicate key {:?}", text)).at(key.line, key.column));
+				}
+			}
+			result.push((key, value));
+		}
+		
+		for (key, value) in merged.into_iter().flatten() {
+			match &key.value {
+				NodeValue::Scalar(text, _) if !keys.insert(text.clone()) => (),
+				_ => result.push((key, value))
+			}
+		}
+		Ok(Node::new(position, NodeValue::Mapping(result)))
+	}
+	
+	/// Parses an alias, a quoted or plain scalar or a flow collection.
+	fn inline_node(&mut self, context: Context) -> Result<Node, DeError> {
+		self.enter()?;
+		let node = self.inline_node_inner(context);
+		self.depth -= 1;
+		node
+	}
+	
+	fn inline_node_inner(&mut self, context: Context) -> Result<Node, DeError> {
+		let position = self.position();
+		let props = self.properties(context)?;
+		let node = match self.peek() {
+			Some(b'*') if props.is_empty() => return self.alias(),
+			Some(b'"')  => Node::new(position, NodeValue::Scalar(self.double_quoted()?, false)),
+			Some(b'\'') => Node::new(position, NodeValue::Scalar(self.single_quoted()?, false)),
+			Some(b'[')  => self.flow_sequence()?,
+			Some(b'{')  => self.flow_mapping()?,
+			// properties without content, e.g. `key: !!str`
+			_ if !props.is_empty() && self.at_node_end(context) => Node::null(position),
+			_ => Node::new(position, NodeValue::Scalar(self.plain(context)?, true))
+		};
+		Ok(self.apply(node, props))
+	}
+	
+	fn at_node_end(&self, context: Context) -> bool {
+		match self.peek() {
+			None | Some(b'\n' | b'#') => true,
+			Some(b':') => self.is_blank_at(1) || (context == Context::Flow && is_flow_indicator(self.peek_at(1))),
+			c => context == Context::Flow && is_flow_indicator(c)
+		}
+	}
+	
+	fn plain(&mut self, context: Context) -> Result<String, DeError> {
+		let flow = context == Context::Flow;
+		match self.peek() {
+			Some(b'-' | b'?' | b':') if !(self.is_blank_at(1) || flow && is_flow_indicator(self.peek_at(1))) => (),
+			None | Some(b'\n' | b'-' | b'?' | b':' | b',' | b'[' | b']' | b'{' | b'}' | b'#' | b'&' | b'*' | b'!'
+				| b'|' | b'>' | b'\'' | b'"' | b'%' | b'@' | b'`') => return Err(self.unexpected("a value")),
+			_ => ()
+		}
+		
+		let mut out = String::new();
+		loop {
+			let start = self.pos;
+			let mut end = self.pos;
+			while let Some(c) = self.peek() {
+				match c {
+					b'\n' => break,
+					b':' if self.is_blank_at(1) || (flow && is_flow_indicator(self.peek_at(1))) => break,
+					b'#' if self.pos > start && matches!(self.src.as_bytes()[self.pos - 1], b' ' | b'\t') => break,
+					b',' | b'[' | b']' | b'{' | b'}' if flow => break,
+					b' ' | b'\t' => self.pos += 1,
+					_ => {
+						self.bump_char();
+						end = self.pos;
+					}
+				}
+			}
+			out.push_str(&self.src[start..end]);
+			
+			let min_indent = match context {
+				Context::BlockKey      => break,
+				Context::Block(indent) => indent,
+				Context::Flow          => 0
+			};
+			
+			if self.peek() != Some(b'\n') {
+				break;
+			}
+			
+			// line breaks are folded into spaces, empty lines into line breaks
+			let eol = self.state();
+			let mut breaks = 0;
+			while self.peek() == Some(b'\n') {
+				self.bump();
+				self.skip_space();
+				breaks += 1;
+			}
+			
+			let stop = self.at_node_end(context)
+				|| self.src[self.line_start..].starts_with("---") || self.src[self.line_start..].starts_with("...")
+				|| ((self.pos - self.line_start) as isize) < min_indent;
+			if stop {
+				self.restore(eol);
+				break;
+			}
+			
+			match breaks {
+				1 => out.push(' '),
+				n => (1..n).for_each(|_| out.push('\n'))
+			}
+		}
+		Ok(out)
+	}
+	
+	/// Folds line breaks in quoted scalars, the current position is at a line break.
+	fn fold_quoted(&mut self, out: &mut String, escaped: bool) {
+		let mut breaks = 0;
+		while self.peek() == Some(b'\n') {
+			self.bump();
+			self.skip_space();
+			breaks += 1;
+		}
+		
+		match (escaped, breaks) {
+			(false, 1) => out.push(' '),
+			(false, n) => (1..n).for_each(|_| out.push('\n')),
+			(true, n)  => (0..n).for_each(|_| out.push('\n'))
+		}
+	}
+	
+	fn double_quoted(&mut self) -> Result<String, DeError> {
+		let (line, column) = self.position();
+		self.bump();
+		let mut out = String::new();
+		// escaped whitespace must not be trimmed before line breaks
+		let mut keep = 0;
+		loop {
+			match self.peek() {
+				None => return Err(DeError::ParseError("unterminated string".to_string()).at(line, column)),
+				Some(b'"') => {
+					self.bump();
+					return Ok(out);
+				}
+				Some(b'\n') => {
+					let trimmed = out.trim_end_matches([' ', '\t']).len();
+					out.truncate(trimmed.max(keep));
+					self.fold_quoted(&mut out, false);
+				}
+				Some(b'\\') => {
+					self.bump();
+					let c = match self.bump_char() {
+						Some(c) => c,
+						None => return Err(DeError::ParseError("unterminated string".to_string()).at(line, column))
+					};
+					match c {
+						'0'        => out.push('\0'),
+						'a'        => out.push('\x07'),
+						'b'        => out.push('\x08'),
+						't' | '\t' => out.push('\t'),
+						'n'        => out.push('\n'),
+						'v'        => out.push('\x0b'),
+						'f'        => out.push('\x0c'),
+						'r'        => out.push('\r'),
+						'e'        => out.push('\x1b'),
+						' '        => out.push(' '),
+						'"'        => out.push('"'),
+						'/'        => out.push('/'),
+						'\\'       => out.push('\\'),
+						'N'        => out.push('\u{85}'),
+						'_'        => out.push('\u{a0}'),
+						'L'        => out.push('\u{2028}'),
+						'P'        => out.push('\u{2029}'),
+						'x'        => out.push(self.hex_escape(2)?),
+						'u'        => out.push(self.hex_escape(4)?),
+						'U'        => out.push(self.hex_escape(8)?),
+						'\n'       => {
+							self.skip_space();
+							self.fold_quoted(&mut out, true);
+						}
+						c => return Err(self.error(format_args!("invalid escape sequence \\{}", c)))
+					}
+					keep = out.len();
+				}
+				Some(_) => out.push(self.bump_char().unwrap())
+			}
+		}
+	}
+	
+	fn hex_escape(&mut self, len: usize) -> Result<char, DeError> {
+		let digits = self.src.get(self.pos..self.pos + len)
+			.filter(|s| s.bytes().all(|c| c.is_ascii_hexdigit()))
+			.ok_or_else(|| self.unexpected("a hexadecimal digit"))?;
+		let c = char::from_u32(u32::from_str_radix(digits, 16).unwrap())
+			.ok_or_else(|| self.error(format_args!("invalid unicode scalar value {}", digits)))?;
+		self.pos += len;
+		Ok(c)
+	}
+	
+	fn single_quoted(&mut self) -> Result<String, DeError> {
+		let (line, column) = self.position();
+		self.bump();
+		let mut out = String::new();
+		loop {
+			match self.peek() {
+				None => return Err(DeError::ParseError("unterminated string".to_string()).at(line, column)),
+				Some(b'\'') if self.peek_at(1) == Some(b'\'') => {
+					out.push('\'');
+					self.pos += 2;
+				}
+				Some(b'\'') => {
+					self.bump();
+					return Ok(out);
+				}
+				Some(b'\n') => {
+					out.truncate(out.trim_end_matches([' ', '\t']).len());
+					self.fold_quoted(&mut out, false);
+				}
+				Some(_) => out.push(self.bump_char().unwrap())
+			}
+		}
+	}
+	
+	/// Parses a literal (`|`) or folded (`>`) block scalar, `indent` is the indentation of the
+	/// parent node.
+	fn block_scalar(&mut self, indent: isize) -> Result<Node, DeError> {
+		let position = self.position();
+		let literal = self.peek() == Some(b'|');
+		self.bump();
+		
+		let (mut chomping, mut explicit) = (Chomping::Clip, None);
+		for _ in 0..2 {
+			match self.peek() {
+				Some(b'-') if chomping == Chomping::Clip => chomping = Chomping::Strip,
+				Some(b'+') if chomping == Chomping::Clip => chomping = Chomping::Keep,
+				Some(c @ b'1'..=b'9') if explicit.is_none() => explicit = Some((c - b'0') as isize),
+				_ => break
+			}
+			self.bump();
+		}
+		
+		self.skip_space();
+		self.skip_comment();
+		match self.peek() {
+			None => (),
+			Some(b'\n') => self.bump(),
+			_ => return Err(self.unexpected("a line break after the block scalar header"))
+		}
+		
+		let content_indent = match explicit {
+			Some(n) => (indent + n).max(0) as usize,
+			// the indentation of the first non-empty line
+			None => self.src[self.pos..].split('\n')
+				.find(|line| !line.trim().is_empty())
+				.map_or(0, |line| line.len() - line.trim_start_matches(' ').len())
+				.max((indent + 1).max(0) as usize)
+		};
+		
+		let mut lines = Vec::new();
+		while self.peek().is_some() && !self.at_marker("---") && !self.at_marker("...") {
+			let end = self.src[self.pos..].find('\n').map_or(self.src.len(), |i| self.pos + i);
+			let line = &self.src[self.pos..end];
+			let spaces = line.len() - line.trim_start_matches(' ').len();
+			match (spaces >= content_indent, line.trim().is_empty()) {
+				(true, _)      => lines.push(&line[content_indent..]),
+				(false, true)  => lines.push(""),
+				(false, false) => break
+			}
+			
+			self.pos = end;
+			if self.peek() == Some(b'\n') {
+				self.bump();
+			}
+		}
+		
+		let content = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |i| i + 1);
+		let mut text = match literal {
+			true  => lines[..content].join("\n"),
+			false => fold_block(&lines[..content])
+		};
+		
+		if content > 0 && chomping != Chomping::Strip {
+			text.push('\n');
+		}
+		
+		if chomping == Chomping::Keep {
+			(content..lines.len()).for_each(|_| text.push('\n'));
+		}
+		Ok(Node::new(position, NodeValue::Scalar(text, false)))
+	}
+	
+	fn flow_sequence(&mut self) -> Result<Node, DeError> {
+		let position = self.position();
+		self.bump();
+		let mut items = Vec::new();
+		loop {
+			self.skip_to_content();
+			match self.peek() {
+				Some(b']') => {
+					self.bump();
+					break;
+				}
+				None => return Err(self.unexpected("']'")),
+				_ => ()
+			}
+			
+			// a single pair mapping, e.g. `[a: b]`
+			let item_position = self.position();
+			let (key, value) = self.flow_entry()?;
+			items.push(match value {
+				Some(value) => self.mapping(item_position, vec![(key, value)])?,
+				None => key
+			});
+			
+			self.skip_to_content();
+			match self.peek() {
+				Some(b',') => self.bump(),
+				Some(b']') => (),
+				_ => return Err(self.unexpected("',' or ']'"))
+			}
+		}
+		Ok(Node::new(position, NodeValue::Sequence(items)))
+	}
+	
+	fn flow_mapping(&mut self) -> Result<Node, DeError> {
+		let position = self.position();
+		self.bump();
+		let mut entries = Vec::new();
+		loop {
+			self.skip_to_content();
+			match self.peek() {
+				Some(b'}') => {
+					self.bump();
+					break;
+				}
+				None => return Err(self.unexpected("'}'")),
+				_ => ()
+			}
+			
+			let (key, value) = self.flow_entry()?;
+			let value = value.unwrap_or_else(|| Node::null((key.line, key.column)));
+			entries.push((key, value));
+			
+			self.skip_to_content();
+			match self.peek() {
+				Some(b',') => self.bump(),
+				Some(b'}') => (),
+				_ => return Err(self.unexpected("',' or '}'"))
+			}
+		}
+		self.mapping(position, entries)
+	}
+	
+	/// Parses an entry of a flow collection, the value is `None` if there is no `:` indicator.
+	fn flow_entry(&mut self) -> Result<(Node, Option<Node>), DeError> {
+		let explicit = self.peek() == Some(b'?') && self.is_blank_at(1);
+		if explicit {
+			self.bump();
+			self.skip_to_content();
+		}
+		
+		// after JSON-like keys the value indicator does not need to be followed by a space
+		let json_like = matches!(self.peek(), Some(b'"' | b'\'' | b'[' | b'{'));
+		let key = match self.peek() == Some(b':') && (self.is_blank_at(1) || is_flow_indicator(self.peek_at(1))) {
+			true  => Node::null(self.position()),
+			false => self.inline_node(Context::Flow)?
+		};
+		
+		self.skip_to_content();
+		if self.peek() != Some(b':') || !(json_like || self.is_blank_at(1) || is_flow_indicator(self.peek_at(1))) {
+			return Ok((key, explicit.then(|| Node::null(self.position()))));
+		}
+		
+		self.bump();
+		self.skip_to_content();
+		let value = match self.peek() {
+			Some(b',' | b']' | b'}') => Node::null(self.position()),
+			_ => self.inline_node(Context::Flow)?
+		};
+		Ok((key, Some(value)))
+	}
+}
+
+/// Folds the lines of a folded block scalar, line breaks between lines that are not more indented
+/// are folded into spaces.
+fn fold_block(lines: &[&str]) -> String {
+	let mut out = String::new();
+	let mut empty = 0;
+	let mut prev_more_indented = None;
+	for line in lines {
+		if line.is_empty() {
+			empty += 1;
+			continue;
+		}
+		
+		let more_indented = line.starts_with([' ', '\t']);
+		match prev_more_indented {
+			None => (0..empty).for_each(|_| out.push('\n')),
+			Some(false) if !more_indented && empty == 0 => out.push(' '),
+			Some(false) if !more_indented => (0..empty).for_each(|_| out.push('\n')),
+			Some(_) => (0..=empty).for_each(|_| out.push('\n'))
+		}
+		
+		out.push_str(line);
+		prev_more_indented = Some(more_indented);
+		empty = 0;
+	}
+	out
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Parent {
+	Root,
+	Mapping,
+	Sequence
+}
+
+/// Writes a node, the output is positioned after the `:` of a mapping entry, after the `-` of a
+/// sequence entry or at the start of the document.
+fn write_node(out: &mut String, value: &Value, indent: usize, parent: Parent) {
+	match (value, parent) {
+		(Value::Mapping(v), Parent::Root) if !v.is_empty() => write_mapping(out, v, indent, true),
+		(Value::Mapping(v), Parent::Mapping) if !v.is_empty() => write_mapping(out, v, indent + INDENT, false),
+		(Value::Mapping(v), Parent::Sequence) if !v.is_empty() => {
+			out.push(' ');
+			write_mapping(out, v, indent + INDENT, true);
+		}
+		(Value::Sequence(v), Parent::Root) if !v.is_empty() => write_sequence(out, v, indent, true),
+		(Value::Sequence(v), Parent::Mapping) if !v.is_empty() => write_sequence(out, v, indent + INDENT, false),
+		(Value::Sequence(v), Parent::Sequence) if !v.is_empty() => {
+			out.push(' ');
+			write_sequence(out, v, indent + INDENT, true);
+		}
+		(Value::String(v), Parent::Root) => write_string(out, v, None, false),
+		(Value::String(v), _) => {
+			out.push(' ');
+			write_string(out, v, Some(indent + INDENT), false);
+		}
+		(value, Parent::Root) => write_flow(out, value),
+		(value, _) => {
+			out.push(' ');
+			write_flow(out, value);
+		}
+	}
+}
+
+fn write_mapping(out: &mut String, entries: &[(Value, Value)], indent: usize, inline_first: bool) {
+	for (i, (key, value)) in entries.iter().enumerate() {
+		if i > 0 || !inline_first {
+			write_newline(out, indent);
+		}
+		
+		// implicit keys are limited to 1024 characters
+		let mut text = String::new();
+		write_flow(&mut text, key);
+		if text.chars().count() > 1024 {
+			out.push_str("? ");
+			out.push_str(&text);
+			write_newline(out, indent);
+		} else {
+			out.push_str(&text);
+		}
+		
+		out.push(':');
+		write_node(out, value, indent, Parent::Mapping);
+	}
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize, inline_first: bool) {
+	for (i, item) in items.iter().enumerate() {
+		if i > 0 || !inline_first {
+			write_newline(out, indent);
+		}
+		
+		out.push('-');
+		write_node(out, item, indent, Parent::Sequence);
+	}
+}
+
+fn write_newline(out: &mut String, indent: usize) {
+	out.push('\n');
+	(0..indent).for_each(|_| out.push(' '));
+}
+
+/// Writes a node in flow style, this is used for scalars and keys.
+fn write_flow(out: &mut String, value: &Value) {
+	match value {
+		Value::Null       => out.push_str("null"),
+		Value::Bool(v)    => out.push_str(if *v { "true" } else { "false" }),
+		Value::Integer(v) => out.push_str(&v.to_string()),
+		Value::Float(v)   => out.push_str(&float_to_string(*v)),
+		Value::String(v)  => write_string(out, v, None, true),
+		Value::Sequence(v) => {
+			out.push('[');
+			for (i, item) in v.iter().enumerate() {
+				if i > 0 {
+					out.push_str(", ");
+				}
+				write_flow(out, item);
+			}
+			out.push(']');
+		}
+		Value::Mapping(v) => {
+			out.push('{');
+			for (i, (key, value)) in v.iter().enumerate() {
+				if i > 0 {
+					out.push_str(", ");
+				}
+				write_flow(out, key);
+				out.push_str(": ");
+				write_flow(out, value);
+			}
+			out.push('}');
+		}
+	}
+}
+
+fn float_to_string(v: f64) -> String {
+	match v {
+		_ if v.is_nan() => ".nan".to_string(),
+		f64::INFINITY => ".inf".to_string(),
+		f64::NEG_INFINITY => "-.inf".to_string(),
+		// the debug representation always contains a `.` or an exponent
+		_ => format!("{:?}", v)
+	}
+}
+
+fn is_printable(c: char) -> bool {
+	!c.is_control() && !matches!(c, '\u{feff}' | '\u{2028}' | '\u{2029}')
+}
+
+/// Returns true if a string can be written as a plain scalar without changing its type or content.
+fn is_plain(s: &str, flow: bool) -> bool {
+	!s.is_empty()
+		&& resolve_plain(s) == Resolved::Str
+		&& s.trim_matches([' ', '\t']).len() == s.len()
+		&& !s.starts_with(['-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`'])
+		&& !s.starts_with("...") && s != "<<"
+		&& !s.contains(": ") && !s.contains(" #") && !s.ends_with(':')
+		&& s.chars().all(|c| is_printable(c) && c != '\t')
+		&& !(flow && s.contains([',', '[', ']', '{', '}']))
+}
+
+/// Writes a string as a plain scalar if possible, multi-line strings as a literal block scalar if
+/// `block` (the indentation of the content) is some, otherwise as a double quoted scalar.
+fn write_string(out: &mut String, s: &str, block: Option<usize>, flow: bool) {
+	if is_plain(s, flow) {
+		out.push_str(s);
+		return;
+	}
+	
+	match block {
+		Some(indent) if s.contains('\n') && !s.trim_end_matches('\n').is_empty()
+			&& s.chars().all(|c| c == '\n' || is_printable(c)) => {
+			let content = s.trim_end_matches('\n');
+			out.push('|');
+			if s.starts_with([' ', '\n']) {
+				out.push_str(&INDENT.to_string());
+			}
+			
+			match s.len() - content.len() {
+				0 => out.push('-'),
+				1 => (),
+				_ => out.push('+')
+			}
+			
+			for line in content.split('\n') {
+				match line.is_empty() {
+					true  => out.push('\n'),
+					false => {
+						write_newline(out, indent);
+						out.push_str(line);
+					}
+				}
+			}
+			
+			(1..s.len() - content.len()).for_each(|_| out.push('\n'));
+		}
+		_ => {
+			out.push('"');
+			for c in s.chars() {
+				match c {
+					'"'  => out.push_str("\\\""),
+					'\\' => out.push_str("\\\\"),
+					'\n' => out.push_str("\\n"),
+					'\t' => out.push_str("\\t"),
+					'\r' => out.push_str("\\r"),
+					'\0' => out.push_str("\\0"),
+					c if (c as u32) < 0x100 && !is_printable(c) => out.push_str(&format!("\\x{:02x}", c as u32)),
+					c if !is_printable(c) => out.push_str(&format!("\\u{:04x}", c as u32)),
+					c => out.push(c)
+				}
+			}
+			out.push('"');
+		}
+	}
+}
+
+struct ValueSerializer;
+
+impl serde::Serializer for ValueSerializer {
+	type Ok                     = Value;
+	type Error                  = SerError;
+	type SerializeSeq           = SequenceSerializer;
+	type SerializeTuple         = SequenceSerializer;
+	type SerializeTupleStruct   = SequenceSerializer;
+	type SerializeTupleVariant  = SequenceSerializer;
+	type SerializeMap           = MappingSerializer;
+	type SerializeStruct        = MappingSerializer;
+	type SerializeStructVariant = MappingSerializer;
+	
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Bool(v))
+	}
+	
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v))
+	}
+	
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Integer(v as _))
+	}
+	
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Float(v as _))
+	}
+	
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Float(v))
+	}
+	
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::String(v.to_string()))
+	}
+	
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::String(v.to_string()))
+	}
+	
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Sequence(v.iter().map(|v| Value::Integer(*v as _)).collect()))
+	}
+	
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Null)
+	}
+	
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Null)
+	}
+	
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Null)
+	}
+	
+	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::String(variant.to_string()))
+	}
+	
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+	
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Mapping(vec![(Value::String(variant.to_string()), value.serialize(self)?)]))
+	}
+	
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(SequenceSerializer { variant: None, items: Vec::with_capacity(len.unwrap_or(0)) })
+	}
+	
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.serialize_seq(Some(len))
+	}
+	
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.serialize_seq(Some(len))
+	}
+	
+	fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(SequenceSerializer { variant: Some(variant), items: Vec::with_capacity(len) })
+	}
+	
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(MappingSerializer { variant: None, entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+	}
+	
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		self.serialize_map(Some(len))
+	}
+	
+	fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(MappingSerializer { variant: Some(variant), entries: Vec::with_capacity(len), key: None })
+	}
+}
+
+/// Wraps the value of a tuple or struct variant in a mapping with a single entry.
+fn variant(variant: Option<&'static str>, value: Value) -> Value {
+	match variant {
+		Some(variant) => Value::Mapping(vec![(Value::String(variant.to_string()), value)]),
+		None => value
+	}
+}
+
+struct SequenceSerializer {
+	variant: Option<&'static str>,
+	items:   Vec<Value>
+}
+
+impl SerializeSeq for SequenceSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		self.items.push(value.serialize(ValueSerializer)?);
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(variant(self.variant, Value::Sequence(self.items)))
+	}
+}
+
+impl SerializeTuple for SequenceSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+impl SerializeTupleStruct for SequenceSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+impl SerializeTupleVariant for SequenceSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		SerializeSeq::serialize_element(self, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeSeq::end(self)
+	}
+}
+
+struct MappingSerializer {
+	variant: Option<&'static str>,
+	entries: Vec<(Value, Value)>,
+	key:     Option<Value>
+}
+
+impl SerializeMap for MappingSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = Some(key.serialize(ValueSerializer)?);
+		Ok(())
+	}
+	
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let key = self.key.take().expect("`serialize_value` called before `serialize_key`");
+		self.entries.push((key, value.serialize(ValueSerializer)?));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(variant(self.variant, Value::Mapping(self.entries)))
+	}
+}
+
+impl SerializeStruct for MappingSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		self.entries.push((Value::String(key.to_string()), value.serialize(ValueSerializer)?));
+		Ok(())
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeMap::end(self)
+	}
+}
+
+impl SerializeStructVariant for MappingSerializer {
+	type Ok    = Value;
+	type Error = SerError;
+	
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+		SerializeStruct::serialize_field(self, key, value)
+	}
+	
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		SerializeMap::end(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, serde::{Serialize, Deserialize}, std::collections::BTreeMap};
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Config {
+		name:    String,
+		version: u32,
+		debug:   bool,
+		scale:   f32,
+		tags:    Vec<String>,
+		limits:  BTreeMap<String, u64>,
+		shape:   Shape,
+		shapes:  Vec<Shape>,
+		parent:  Option<Box<Config>>
+	}
+	
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Shape {
+		Point,
+		Circle(f32),
+		Rect { w: f32, h: f32 },
+		Line(f32, f32)
+	}
+	
+	fn parse(src: &str) -> Value {
+		src.parse().unwrap()
+	}
+	
+	fn string(v: &str) -> Value {
+		Value::String(v.to_string())
+	}
+	
+	#[test]
+	fn full() {
+		let config = Config {
+			name:    "engine: core".to_string(),
+			version: 3,
+			debug:   true,
+			scale:   0.5,
+			tags:    vec!["a".to_string(), "true".to_string(), "123".to_string(), String::new(), " x ".to_string()],
+			limits:  [("memory".to_string(), 1 << 40), ("threads".to_string(), 8)].into_iter().collect(),
+			shape:   Shape::Rect { w: 1.0, h: 2.0 },
+			shapes:  vec![Shape::Point, Shape::Circle(1.5), Shape::Line(0.0, -1.0)],
+			parent:  Some(Box::new(Config {
+				name:    "multi\nline\n".to_string(),
+				version: 0,
+				debug:   false,
+				scale:   -0.0,
+				tags:    Vec::new(),
+				limits:  BTreeMap::new(),
+				shape:   Shape::Point,
+				shapes:  Vec::new(),
+				parent:  None
+			}))
+		};
+		
+		let mut buf = Vec::new();
+		serialize(&mut buf, &config).unwrap();
+		let src = String::from_utf8(buf).unwrap();
+		assert_eq!(deserialize_str::<Config>(&src).unwrap(), config, "{}", src);
+		assert_eq!(deserialize::<Config, _>(src.as_bytes()).unwrap(), config);
+	}
+	
+	#[test]
+	fn block() {
+		let v = parse("# comment\n\
+			a: 1\n\
+			b:\n  c: two   # trailing comment\n  d:\n  - x\n  -   y\n  -\n    - nested\n\
+			e:\n  - f: 1\n    g: 2\n  - - h\n    - i\n\
+			? complex key\n: complex value\n\
+			\"quoted key\": 'value'\n\
+			empty:\n\
+			last: end\n");
+		assert_eq!(v.get(&["a"]), Some(&Value::Integer(1)));
+		assert_eq!(v.get(&["b", "c"]), Some(&string("two")));
+		assert_eq!(v.get(&["b", "d"]), Some(&Value::Sequence(vec![
+			string("x"), string("y"), Value::Sequence(vec![string("nested")])])));
+		let e = v.get(&["e"]).unwrap().as_sequence().unwrap();
+		assert_eq!(e[0].get(&["g"]), Some(&Value::Integer(2)));
+		assert_eq!(e[1], Value::Sequence(vec![string("h"), string("i")]));
+		assert_eq!(v.get(&["complex key"]), Some(&string("complex value")));
+		assert_eq!(v.get(&["quoted key"]), Some(&string("value")));
+		assert_eq!(v.get(&["empty"]), Some(&Value::Null));
+		assert_eq!(v.get(&["last"]), Some(&string("end")));
+		assert_eq!(parse("- a\n- b: c\n  d: e\n-\n"), Value::Sequence(vec![string("a"),
+			Value::Mapping(vec![(string("b"), string("c")), (string("d"), string("e"))]), Value::Null]));
+	}
+	
+	#[test]
+	fn flow() {
+		let v = parse("a: [1, 'two', \"three\", [4], {five: 5}]\n\
+			b: {x: 1, y, \"z\":3, ? w : 4}\n\
+			c: [\n  multi\n  line,\n  k: v, # comment\n]\n\
+			d: []\ne: {}\n");
+		assert_eq!(v.get(&["a"]).unwrap().as_sequence().unwrap(), &[Value::Integer(1), string("two"),
+			string("three"), Value::Sequence(vec![Value::Integer(4)]),
+			Value::Mapping(vec![(string("five"), Value::Integer(5))])]);
+		assert_eq!(v.get(&["b"]), Some(&Value::Mapping(vec![(string("x"), Value::Integer(1)),
+			(string("y"), Value::Null), (string("z"), Value::Integer(3)), (string("w"), Value::Integer(4))])));
+		assert_eq!(v.get(&["c"]), Some(&Value::Sequence(vec![string("multi line"),
+			Value::Mapping(vec![(string("k"), string("v"))])])));
+		assert_eq!(v.get(&["d"]), Some(&Value::Sequence(Vec::new())));
+		assert_eq!(v.get(&["e"]), Some(&Value::Mapping(Vec::new())));
+		assert_eq!(parse("{a: [b, {c: d}]}"), parse("a:\n  - b\n  - c: d\n"));
+	}
+	
+	#[test]
+	fn scalars() {
+		let v = parse("plain: a\n  b\n\n  c\n\
+			single: 'it''s\n  folded '\n\
+			double: \"tab\\t \\x41\\u00e9\\U0001F600 \\\"q\\\" \\\n  joined\\ \n  next\"\n\
+			literal: |\n  line 1\n    indented\n\n  line 3\n\n\
+			folded: >\n  a\n  b\n\n  c\n    more\n  d\n\
+			strip: |-\n  text\n\n\
+			keep: |+\n  text\n\n\
+			explicit: |2\n    x\n  y\n\
+			nulls: [~, null, Null, NULL, ]\n\
+			bools: [true, True, TRUE, false, FALSE, yes]\n\
+			ints: [0, -17, +3, 0o17, 0xFF, 0x, 170141183460469231731687303715884105727]\n\
+			floats: [1.5, -.5, 1e3, 6.02E+23, .inf, -.Inf, 1.]\n\
+			tagged: [!!str 123, !!float 1, !!int \"42\", ! 12, !!null '']\n");
+		assert_eq!(v.get(&["plain"]), Some(&string("a b\nc")));
+		assert_eq!(v.get(&["single"]), Some(&string("it's folded ")));
+		assert_eq!(v.get(&["double"]), Some(&string("tab\t A\u{e9}\u{1F600} \"q\" joined  next")));
+		assert_eq!(v.get(&["literal"]), Some(&string("line 1\n  indented\n\nline 3\n")));
+		assert_eq!(v.get(&["folded"]), Some(&string("a b\nc\n  more\nd\n")));
+		assert_eq!(v.get(&["strip"]), Some(&string("text")));
+		assert_eq!(v.get(&["keep"]), Some(&string("text\n\n")));
+		assert_eq!(v.get(&["explicit"]), Some(&string("  x\ny\n")));
+		assert_eq!(v.get(&["nulls"]), Some(&Value::Sequence(vec![Value::Null; 4])));
+		assert_eq!(v.get(&["bools"]), Some(&Value::Sequence(vec![Value::Bool(true), Value::Bool(true),
+			Value::Bool(true), Value::Bool(false), Value::Bool(false), string("yes")])));
+		assert_eq!(v.get(&["ints"]), Some(&Value::Sequence(vec![Value::Integer(0), Value::Integer(-17),
+			Value::Integer(3), Value::Integer(0o17), Value::Integer(0xff), string("0x"), Value::Integer(i128::MAX)])));
+		assert_eq!(v.get(&["floats"]), Some(&Value::Sequence(vec![Value::Float(1.5), Value::Float(-0.5),
+			Value::Float(1e3), Value::Float(6.02e23), Value::Float(f64::INFINITY), Value::Float(f64::NEG_INFINITY),
+			Value::Float(1.0)])));
+		assert_eq!(v.get(&["tagged"]), Some(&Value::Sequence(vec![string("123"), Value::Float(1.0),
+			Value::Integer(42), string("12"), Value::Null])));
+		assert!(parse(".nan").as_float().unwrap().is_nan());
+		assert_eq!(deserialize_str::<String>("1.0").unwrap(), "1.0");
+		assert_eq!(deserialize_str::<Option<u8>>("~").unwrap(), None);
+		assert_eq!(deserialize_str::<BTreeMap<u8, char>>("1: a\n2: b").unwrap(), [(1, 'a'), (2, 'b')].into_iter().collect());
+	}
+	
+	#[test]
+	fn anchors() {
+		let v = parse("base: &base\n  a: 1\n  b: 2\n\
+			copy: *base\n\
+			merged:\n  <<: *base\n  b: 3\n\
+			multi:\n  <<: [{c: 1}, {c: 2, d: 2}]\n\
+			list: &list [x, &y y]\n\
+			refs: [*list, *y]\n");
+		let base = Value::Mapping(vec![(string("a"), Value::Integer(1)), (string("b"), Value::Integer(2))]);
+		assert_eq!(v.get(&["copy"]), Some(&base));
+		assert_eq!(v.get(&["merged"]), Some(&Value::Mapping(vec![(string("b"), Value::Integer(3)),
+			(string("a"), Value::Integer(1))])));
+		assert_eq!(v.get(&["multi"]), Some(&Value::Mapping(vec![(string("c"), Value::Integer(1)),
+			(string("d"), Value::Integer(2))])));
+		assert_eq!(v.get(&["refs"]), Some(&Value::Sequence(vec![
+			Value::Sequence(vec![string("x"), string("y")]), string("y")])));
+		assert_eq!(deserialize_str::<Shape>("!Circle 2.0").unwrap(), Shape::Circle(2.0));
+		assert_eq!(deserialize_str::<Shape>("!Rect {w: 1, h: 2}").unwrap(), Shape::Rect { w: 1.0, h: 2.0 });
+		assert_eq!(deserialize_str::<Vec<Shape>>("- Point\n- Line: [1, 2]").unwrap(),
+			vec![Shape::Point, Shape::Line(1.0, 2.0)]);
+		
+		// exponential growth through nested aliases is rejected
+		let mut src = "a0: &a0 [x, x, x, x, x, x, x, x]\n".to_string();
+		for i in 1..12 {
+			src.push_str(&format!("a{}: &a{} [*a{2}, *a{2}, *a{2}, *a{2}, *a{2}, *a{2}, *a{2}, *a{2}]\n", i, i, i - 1));
+		}
+		assert!(Document::parse(&src).is_err());
+	}
+	
+	#[test]
+	fn documents() {
+		let src = "%YAML 1.2\n%TAG !e! tag:example.com,2000:\n---\na: !e!foo 1\n...\n--- 2\n---\n- 3\n---\n";
+		let docs = Document::parse_all(src).unwrap();
+		assert_eq!(docs.len(), 4);
+		match &docs[0].root.value {
+			NodeValue::Mapping(entries) => assert_eq!(entries[0].1.tag.as_deref(), Some("tag:example.com,2000:foo")),
+			value => panic!("expected a mapping, found {:?}", value)
+		}
+		assert_eq!(deserialize_all::<Value, _>(src.as_bytes()).unwrap(), vec![
+			Value::Mapping(vec![(string("a"), Value::Integer(1))]), Value::Integer(2),
+			Value::Sequence(vec![Value::Integer(3)]), Value::Null]);
+		assert!(deserialize_str::<Value>("--- 1\n--- 2\n").is_err());
+		assert_eq!(deserialize_str::<Value>("").unwrap(), Value::Null);
+		assert_eq!(Document::parse_all("# only a comment\n").unwrap().len(), 0);
+		assert_eq!(Document::parse_all("a: 1\n%YAML 1.2\n---\nb: 2\n").unwrap().len(), 2);
+		assert_eq!(deserialize_str::<Value>("\u{feff}a: b\r\nc: d\r\n").unwrap().get(&["c"]), Some(&string("d")));
+		
+		let mut buf = Vec::new();
+		serialize_all(&mut buf, &[Value::Integer(1), parse("a: b")]).unwrap();
+		assert_eq!(String::from_utf8(buf).unwrap(), "1\n---\na: b\n");
+	}
+	
+	#[test]
+	fn errors() {
+		let position = |src: &str| Document::parse(src).unwrap_err().position();
+		assert_eq!(position("a: 1\n b: 2\n"), Some((2, 3)));
+		assert_eq!(position("a: 1\na: 2\n"), Some((2, 1)));
+		assert_eq!(position("a: \"unterminated\n"), Some((1, 4)));
+		assert_eq!(position("a: [1, 2\n"), Some((2, 1)));
+		assert_eq!(position("a: *undefined\n"), Some((1, 4)));
+		assert_eq!(position("- a\nb: c\n"), Some((2, 1)));
+		assert_eq!(position("a:\n\t- b\n"), Some((2, 2)));
+		assert_eq!(position("a: \"\\q\"\n"), Some((1, 7)));
+		
+		let error = deserialize_str::<Config>("name: x\nversion: -1\n").unwrap_err();
+		assert_eq!(error.position(), Some((2, 10)));
+		let error = deserialize_str::<Config>("name: x\nversion: 1\n").unwrap_err();
+		assert_eq!(error.position(), Some((1, 1)));
+		let error = deserialize_str::<Vec<Shape>>("- Point\n- Circle: x\n").unwrap_err();
+		assert_eq!(error.position(), Some((2, 11)));
+		let error = deserialize_str::<u8>("!!int x").unwrap_err();
+		assert_eq!(error.position(), Some((1, 1)));
+	}
+	
+	#[test]
+	fn serializer() {
+		let v = parse("a: 1\nb:\n  - x\n  - y: 1\n    z: [1, 2]\n  - - nested\nc: {}\nd: []\n");
+		assert_eq!(v.to_string(), "a: 1\nb:\n  - x\n  - y: 1\n    z:\n      - 1\n      - 2\n  - - nested\nc: {}\nd: []");
+		
+		let strings = ["", " ", "true", "null", "~", "123", "1e3", ".inf", "- a", "a: b", "a #b", "#", "key:",
+			"\n", "\n\n", "multi\nline", "multi\nline\n", "trailing\n\n\n", "\n leading", " indented\nlines", "tab\there",
+			"quote\"s", "back\\slash", "ctrl\x07", "unicode \u{e9}\u{1F600}", "<<", "[flow]", "---", "...", "%", "@",
+			"a,b", "0x1G", "'single'"];
+		for s in strings {
+			let value = Value::Mapping(vec![(string(s), Value::Sequence(vec![string(s), Value::Integer(1)]))]);
+			let text = value.to_string();
+			assert_eq!(parse(&text), value, "{:?} was written as\n{}", s, text);
+			assert_eq!(parse(&string(s).to_string()), string(s), "{:?}", s);
+		}
+		
+		let floats = [0.0, -0.0, 1.0, 0.1, 1e300, -2.5e-8, f64::INFINITY, f64::NEG_INFINITY];
+		for f in floats {
+			assert_eq!(parse(&Value::Float(f).to_string()), Value::Float(f));
+		}
+		
+		let long = Value::Mapping(vec![(string(&"long key ".repeat(200)), Value::Null)]);
+		assert_eq!(parse(&long.to_string()), long);
+		
+		let complex = Value::Mapping(vec![(Value::Sequence(vec![Value::Integer(1), Value::Null]), Value::Bool(true)),
+			(Value::Mapping(vec![(string("k"), string("a, b"))]), Value::Integer(i128::MIN))]);
+		assert_eq!(parse(&complex.to_string()), complex);
+	}
+}
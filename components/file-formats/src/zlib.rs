@@ -0,0 +1,467 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! DEFLATE (RFC 1951) and the zlib container (RFC 1950).
+//!
+//! The compressor uses LZ77 with hash chains and the fixed Huffman codes, which is
+//! a good tradeoff between speed, size and simplicity for asset data.
+
+use std::io::{self, Error, ErrorKind};
+
+const LENGTH_BASE: [u16; 29] = [
+	3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258
+];
+const LENGTH_EXTRA: [u8; 29] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0
+];
+const DIST_BASE: [u16; 30] = [
+	1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+	6145, 8193, 12289, 16385, 24577
+];
+const DIST_EXTRA: [u8; 30] = [
+	0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13
+];
+/// The order in which code length code lengths are stored in dynamic blocks.
+const CLEN_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const WINDOW_SIZE: usize = 32768;
+const MAX_MATCH:   usize = 258;
+const MIN_MATCH:   usize = 3;
+const MAX_CHAIN:   usize = 64;
+const HASH_BITS:   u32   = 15;
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Decompresses a zlib stream and verifies its checksum.
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+	if data.len() < 6 {
+		return Err(invalid_data("zlib stream is too short"));
+	}
+	
+	let (cmf, flg) = (data[0], data[1]);
+	if cmf & 0xF != 8 || cmf >> 4 > 7 || !(((cmf as u16) << 8) | flg as u16).is_multiple_of(31) {
+		return Err(invalid_data("invalid zlib header"));
+	} else if flg & 0x20 != 0 {
+		return Err(invalid_data("zlib preset dictionaries are not supported"));
+	}
+	
+	let (out, len) = inflate_stream(&data[2..])?;
+	let checksum = data.get(2 + len..2 + len + 4)
+		.ok_or_else(|| invalid_data("zlib checksum is missing"))?;
+	
+	if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&out) {
+		return Err(invalid_data("zlib checksum mismatch"));
+	}
+	
+	Ok(out)
+}
+
+/// Compresses data into a zlib stream.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+	let mut out = vec![0x78, 0x9C];
+	out.extend_from_slice(&deflate(data));
+	out.extend_from_slice(&adler32(data).to_be_bytes());
+	out
+}
+
+/// Decompresses a raw DEFLATE stream.
+pub fn inflate(data: &[u8]) -> io::Result<Vec<u8>> {
+	inflate_stream(data).map(|(out, _)| out)
+}
+
+/// Returns the decompressed data and the number of bytes consumed.
+fn inflate_stream(data: &[u8]) -> io::Result<(Vec<u8>, usize)> {
+	let mut reader = BitReader { data, pos: 0, bit: 0 };
+	let mut out = Vec::with_capacity(data.len() * 4);
+	
+	loop {
+		let last = reader.bits(1)? == 1;
+		match reader.bits(2)? {
+			0 => {
+				reader.align();
+				let header = reader.bytes(4)?;
+				let len = u16::from_le_bytes([header[0], header[1]]);
+				if len != !u16::from_le_bytes([header[2], header[3]]) {
+					return Err(invalid_data("stored block length mismatch"));
+				}
+				out.extend_from_slice(reader.bytes(len as usize)?);
+			}
+			1 => {
+				let (lit, dist) = fixed_tables();
+				inflate_block(&mut reader, &mut out, &lit, &dist)?;
+			}
+			2 => {
+				let (lit, dist) = dynamic_tables(&mut reader)?;
+				inflate_block(&mut reader, &mut out, &lit, &dist)?;
+			}
+			_ => return Err(invalid_data("invalid block type"))
+		}
+		
+		if last {
+			break;
+		}
+	}
+	
+	reader.align();
+	Ok((out, reader.pos))
+}
+
+fn inflate_block(reader: &mut BitReader, out: &mut Vec<u8>, lit: &Huffman, dist: &Huffman) -> io::Result<()> {
+	loop {
+		let symbol = lit.decode(reader)? as usize;
+		match symbol {
+			0..=255 => out.push(symbol as u8),
+			256 => return Ok(()),
+			257..=285 => {
+				let i = symbol - 257;
+				let len = LENGTH_BASE[i] as usize + reader.bits(LENGTH_EXTRA[i] as u32)? as usize;
+				let i = dist.decode(reader)? as usize;
+				if i >= 30 {
+					return Err(invalid_data("invalid distance code"));
+				}
+				
+				let distance = DIST_BASE[i] as usize + reader.bits(DIST_EXTRA[i] as u32)? as usize;
+				if distance > out.len() {
+					return Err(invalid_data("distance is too far back"));
+				}
+				
+				let start = out.len() - distance;
+				if distance >= len {
+					out.extend_from_within(start..start + len);
+				} else {
+					for i in 0..len {
+						out.push(out[start + i]);
+					}
+				}
+			}
+			_ => return Err(invalid_data("invalid literal/length code"))
+		}
+	}
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+	let mut lengths = [0u8; 288];
+	lengths[..144].fill(8);
+	lengths[144..256].fill(9);
+	lengths[256..280].fill(7);
+	lengths[280..].fill(8);
+	(Huffman::new(&lengths).unwrap(), Huffman::new(&[5; 30]).unwrap())
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> io::Result<(Huffman, Huffman)> {
+	let hlit = reader.bits(5)? as usize + 257;
+	let hdist = reader.bits(5)? as usize + 1;
+	let hclen = reader.bits(4)? as usize + 4;
+	
+	let mut clen = [0u8; 19];
+	for i in CLEN_ORDER.iter().take(hclen) {
+		clen[*i] = reader.bits(3)? as u8;
+	}
+	
+	let clen = Huffman::new(&clen)?;
+	let mut lengths = Vec::with_capacity(hlit + hdist);
+	
+	while lengths.len() < hlit + hdist {
+		let (value, repeat) = match clen.decode(reader)? {
+			symbol @ 0..=15 => (symbol as u8, 1),
+			16 => (*lengths.last().ok_or_else(|| invalid_data("repeat without previous length"))?, 3 + reader.bits(2)?),
+			17 => (0, 3 + reader.bits(3)?),
+			_  => (0, 11 + reader.bits(7)?)
+		};
+		
+		lengths.extend(std::iter::repeat_n(value, repeat as usize));
+	}
+	
+	if lengths.len() > hlit + hdist {
+		return Err(invalid_data("code lengths exceed the number of codes"));
+	} else if lengths[256] == 0 {
+		return Err(invalid_data("missing end of block code"));
+	}
+	
+	Ok((Huffman::new(&lengths[..hlit])?, Huffman::new(&lengths[hlit..])?))
+}
+
+struct BitReader<'a> {
+	data: &'a [u8],
+	pos:  usize,
+	bit:  u32
+}
+
+impl<'a> BitReader<'a> {
+	fn bits(&mut self, n: u32) -> io::Result<u32> {
+		let mut value = 0;
+		for i in 0..n {
+			let byte = *self.data.get(self.pos).ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+			value |= ((byte as u32 >> self.bit) & 1) << i;
+			self.bit += 1;
+			if self.bit == 8 {
+				self.bit = 0;
+				self.pos += 1;
+			}
+		}
+		Ok(value)
+	}
+	
+	fn align(&mut self) {
+		if self.bit != 0 {
+			self.bit = 0;
+			self.pos += 1;
+		}
+	}
+	
+	fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+		let bytes = self.data.get(self.pos..self.pos + n).ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+		self.pos += n;
+		Ok(bytes)
+	}
+}
+
+/// A canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+	/// number of codes of each length
+	counts:  [u16; 16],
+	/// symbols ordered by code
+	symbols: Vec<u16>
+}
+
+impl Huffman {
+	fn new(lengths: &[u8]) -> io::Result<Self> {
+		let mut counts = [0u16; 16];
+		lengths.iter().for_each(|len| counts[*len as usize] += 1);
+		counts[0] = 0;
+		
+		let mut left = 1i32;
+		for count in &counts[1..] {
+			left = (left << 1) - *count as i32;
+			if left < 0 {
+				return Err(invalid_data("over-subscribed Huffman code"));
+			}
+		}
+		
+		let mut offsets = [0u16; 16];
+		for len in 1..15 {
+			offsets[len + 1] = offsets[len] + counts[len];
+		}
+		
+		let mut symbols = vec![0; lengths.len()];
+		for (symbol, len) in lengths.iter().enumerate() {
+			if *len != 0 {
+				symbols[offsets[*len as usize] as usize] = symbol as u16;
+				offsets[*len as usize] += 1;
+			}
+		}
+		
+		Ok(Self { counts, symbols })
+	}
+	
+	fn decode(&self, reader: &mut BitReader) -> io::Result<u16> {
+		let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+		for len in 1..16 {
+			code |= reader.bits(1)? as i32;
+			let count = self.counts[len] as i32;
+			if code - first < count {
+				return Ok(self.symbols[(index + code - first) as usize]);
+			}
+			
+			index += count;
+			first = (first + count) << 1;
+			code <<= 1;
+		}
+		Err(invalid_data("invalid Huffman code"))
+	}
+}
+
+/// Compresses data into a raw DEFLATE stream.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+	let mut writer = BitWriter::default();
+	writer.bits(1, 1);
+	writer.bits(1, 2);
+	
+	let mut head = vec![u32::MAX; 1 << HASH_BITS];
+	let mut prev = vec![u32::MAX; WINDOW_SIZE];
+	let hash = |i: usize| (((data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32)
+		.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize;
+	let insert = |i: usize, head: &mut [u32], prev: &mut [u32]| if i + MIN_MATCH <= data.len() {
+		let h = hash(i);
+		prev[i % WINDOW_SIZE] = head[h];
+		head[h] = i as u32;
+	};
+	
+	let mut i = 0;
+	while i < data.len() {
+		let (mut best_len, mut best_dist) = (0, 0);
+		if i + MIN_MATCH <= data.len() {
+			let max = MAX_MATCH.min(data.len() - i);
+			let mut candidate = head[hash(i)];
+			for _ in 0..MAX_CHAIN {
+				if candidate == u32::MAX || i - candidate as usize > WINDOW_SIZE - 1 {
+					break;
+				}
+				
+				let j = candidate as usize;
+				let len = data[j..j + max].iter().zip(&data[i..i + max]).take_while(|(a, b)| a == b).count();
+				if len > best_len {
+					best_len = len;
+					best_dist = i - j;
+					if len == max {
+						break;
+					}
+				}
+				
+				let next = prev[j % WINDOW_SIZE];
+				if next == u32::MAX || next >= candidate {
+					break;
+				}
+				candidate = next;
+			}
+		}
+		
+		if best_len >= MIN_MATCH {
+			writer.length(best_len);
+			writer.distance(best_dist);
+			for k in i..i + best_len {
+				insert(k, &mut head, &mut prev);
+			}
+			i += best_len;
+		} else {
+			writer.literal(data[i] as u16);
+			insert(i, &mut head, &mut prev);
+			i += 1;
+		}
+	}
+	
+	writer.literal(256);
+	writer.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+	out:  Vec<u8>,
+	acc:  u64,
+	bits: u32
+}
+
+impl BitWriter {
+	fn bits(&mut self, value: u32, n: u32) {
+		self.acc |= (value as u64) << self.bits;
+		self.bits += n;
+		while self.bits >= 8 {
+			self.out.push(self.acc as u8);
+			self.acc >>= 8;
+			self.bits -= 8;
+		}
+	}
+	
+	/// Writes a Huffman code, which is stored starting with the most significant bit.
+	fn code(&mut self, code: u32, len: u32) {
+		self.bits(code.reverse_bits() >> (32 - len), len);
+	}
+	
+	fn literal(&mut self, symbol: u16) {
+		match symbol {
+			0..=143   => self.code(0x30 + symbol as u32, 8),
+			144..=255 => self.code(0x190 + symbol as u32 - 144, 9),
+			256..=279 => self.code(symbol as u32 - 256, 7),
+			_         => self.code(0xC0 + symbol as u32 - 280, 8)
+		}
+	}
+	
+	fn length(&mut self, len: usize) {
+		let i = LENGTH_BASE.iter().rposition(|base| *base as usize <= len).unwrap();
+		self.literal(257 + i as u16);
+		self.bits((len - LENGTH_BASE[i] as usize) as u32, LENGTH_EXTRA[i] as u32);
+	}
+	
+	fn distance(&mut self, dist: usize) {
+		let i = DIST_BASE.iter().rposition(|base| *base as usize <= dist).unwrap();
+		self.code(i as u32, 5);
+		self.bits((dist - DIST_BASE[i] as usize) as u32, DIST_EXTRA[i] as u32);
+	}
+	
+	fn finish(mut self) -> Vec<u8> {
+		if self.bits > 0 {
+			self.out.push(self.acc as u8);
+		}
+		self.out
+	}
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+	let (mut a, mut b) = (1u32, 0u32);
+	// 5552 is the largest n such that the sums do not overflow before the modulo
+	for chunk in data.chunks(5552) {
+		for byte in chunk {
+			a += *byte as u32;
+			b += a;
+		}
+		a %= 65521;
+		b %= 65521;
+	}
+	b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn round_trip() {
+		let data = (0..100_000u32).map(|i| (i % 251) as u8 ^ (i / 1000) as u8).collect::<Vec<_>>();
+		let compressed = compress(&data);
+		assert!(compressed.len() < data.len() / 4);
+		assert_eq!(decompress(&compressed).unwrap(), data);
+		assert_eq!(decompress(&compress(&[])).unwrap(), []);
+		assert_eq!(decompress(&compress(b"a")).unwrap(), b"a");
+	}
+	
+	#[test]
+	fn dynamic() {
+		let mut x = 1u32;
+		let expected = (0..300).map(|_| {
+			x = x.wrapping_mul(1103515245).wrapping_add(12345) & 0x7FFF_FFFF;
+			b"aaaaaaaabbbbccde"[(x >> 16) as usize & 15]
+		}).collect::<Vec<_>>();
+		
+		// compressed with zlib level 9, uses a dynamic Huffman block
+		let data = [
+			0x78, 0xda, 0x3d, 0x8f, 0xdb, 0x11, 0x44, 0x31, 0x08, 0x42, 0x6b, 0xe5, 0x61, 0xff, 0x2d, 0x2c,
+			0x68, 0xee, 0xfa, 0xe1, 0x18, 0x81, 0xe3, 0x04, 0x06, 0x49, 0x10, 0xd0, 0x48, 0x00, 0x06, 0x6e,
+			0x4b, 0x65, 0xaf, 0x48, 0xd5, 0x40, 0x9d, 0x52, 0x6b, 0x66, 0xc2, 0x43, 0xce, 0xec, 0x4a, 0xa2,
+			0x6b, 0xb2, 0x28, 0x5f, 0x72, 0xbe, 0x50, 0x00, 0x45, 0x40, 0x18, 0x1e, 0xb3, 0xbe, 0xa8, 0x3d,
+			0xb5, 0x77, 0x4a, 0xe3, 0x79, 0xdb, 0xbc, 0xd1, 0x00, 0xd4, 0x50, 0xd0, 0x90, 0x3d, 0xcb, 0x3f,
+			0xc0, 0x31, 0xf0, 0x78, 0x5f, 0x71, 0xec, 0xec, 0x13, 0xa3, 0x9f, 0x5e, 0x42, 0x7f, 0x00, 0xfe,
+			0x93, 0x5b, 0xfa, 0xc6, 0x0e, 0x6b, 0x8e, 0x27, 0x69, 0x2d, 0xbb, 0x97, 0xc4, 0x67, 0xef, 0x17,
+			0xcf, 0xb8, 0xef, 0x1f, 0x8e, 0x3d, 0x72, 0xc8
+		];
+		assert_eq!(decompress(&data).unwrap(), expected);
+		assert!(decompress(&data[..data.len() - 1]).is_err());
+	}
+	
+	#[test]
+	fn stored() {
+		let data = [0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c'];
+		assert_eq!(inflate(&data).unwrap(), b"abc");
+		assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+	}
+}
@@ -0,0 +1,805 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Zstandard (RFC 8878) frames.
+//!
+//! The decoder supports everything but dictionaries. The encoder only emits raw and RLE
+//! blocks, so it produces valid frames quickly, but does not actually compress data.
+
+use std::io::{self, Error, ErrorKind};
+
+const MAGIC:          u32   = 0xFD2F_B528;
+const SKIPPABLE:      u32   = 0x184D_2A50;
+const MAX_BLOCK_SIZE: usize = 128 * 1024;
+
+const LL_DEFAULT: [i16; 36] = [
+	4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1, -1, -1, -1, -1
+];
+const ML_DEFAULT: [i16; 53] = [
+	1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+	1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1
+];
+const OF_DEFAULT: [i16; 29] = [
+	1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1
+];
+
+const LL_BASE: [u32; 36] = [
+	0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64, 128, 256, 512,
+	1024, 2048, 4096, 8192, 16384, 32768, 65536
+];
+const LL_BITS: [u32; 36] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16
+];
+const ML_BASE: [u32; 53] = [
+	3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+	33, 34, 35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027, 2051, 4099, 8195, 16387, 32771, 65539
+];
+const ML_BITS: [u32; 53] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2,
+	3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16
+];
+
+fn invalid_data(msg: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn eof() -> Error {
+	Error::from(ErrorKind::UnexpectedEof)
+}
+
+fn bytes(data: &[u8], pos: usize, len: usize) -> io::Result<&[u8]> {
+	data.get(pos..pos + len).ok_or_else(eof)
+}
+
+fn read_le(data: &[u8], pos: usize, len: usize) -> io::Result<u64> {
+	Ok(bytes(data, pos, len)?.iter().rev().fold(0, |acc, byte| acc << 8 | *byte as u64))
+}
+
+/// Decompresses all frames, skippable frames are ignored.
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+	let mut out = Vec::new();
+	let mut pos = 0;
+	
+	while pos < data.len() {
+		let magic = read_le(data, pos, 4)? as u32;
+		if magic & 0xFFFF_FFF0 == SKIPPABLE {
+			pos += 8 + read_le(data, pos + 4, 4)? as usize;
+			if pos > data.len() {
+				return Err(eof());
+			}
+		} else if magic == MAGIC {
+			pos = decode_frame(data, pos + 4, &mut out)?;
+		} else {
+			return Err(invalid_data("invalid zstd magic number"));
+		}
+	}
+	
+	Ok(out)
+}
+
+/// Writes a single frame containing the data.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+	let mut out = MAGIC.to_le_bytes().to_vec();
+	let (flag, size) = match data.len() {
+		len if len < 256           => (0u8, vec![len as u8]),
+		len if len < 0x1_0000 + 256 => (1, ((len - 256) as u16).to_le_bytes().to_vec()),
+		len if len <= u32::MAX as usize => (2, (len as u32).to_le_bytes().to_vec()),
+		len                        => (3, (len as u64).to_le_bytes().to_vec())
+	};
+	
+	// single segment, no checksum, no dictionary
+	out.push(flag << 6 | 0x20);
+	out.extend_from_slice(&size);
+	
+	let mut blocks = data.chunks(MAX_BLOCK_SIZE).peekable();
+	if blocks.peek().is_none() {
+		out.extend_from_slice(&[1, 0, 0]);
+	}
+	
+	while let Some(block) = blocks.next() {
+		let last = blocks.peek().is_none() as u32;
+		let rle = block.len() > 1 && block.iter().all(|byte| *byte == block[0]);
+		let header = last | (rle as u32) << 1 | (block.len() as u32) << 3;
+		out.extend_from_slice(&header.to_le_bytes()[..3]);
+		out.extend_from_slice(if rle { &block[..1] } else { block });
+	}
+	
+	out
+}
+
+#[derive(Default)]
+struct FrameState {
+	repeats:  [usize; 3],
+	huffman:  Option<HuffmanTable>,
+	literals: Option<FseTable>,
+	offsets:  Option<FseTable>,
+	matches:  Option<FseTable>
+}
+
+/// Decodes a frame starting after the magic number and returns the position after the frame.
+fn decode_frame(data: &[u8], mut pos: usize, out: &mut Vec<u8>) -> io::Result<usize> {
+	let descriptor = *data.get(pos).ok_or_else(eof)?;
+	pos += 1;
+	
+	if descriptor & 0x08 != 0 {
+		return Err(invalid_data("reserved bit set in zstd frame header"));
+	}
+	
+	let single_segment = descriptor & 0x20 != 0;
+	if !single_segment {
+		// window descriptor, not needed as the whole frame is decoded into memory
+		pos += 1;
+	}
+	
+	let dict_size = [0, 1, 2, 4][descriptor as usize & 3];
+	if read_le(data, pos, dict_size)? != 0 {
+		return Err(invalid_data("zstd dictionaries are not supported"));
+	}
+	pos += dict_size;
+	
+	let fcs_size = match descriptor >> 6 {
+		0 => single_segment as usize,
+		1 => 2,
+		2 => 4,
+		_ => 8
+	};
+	let content_size = read_le(data, pos, fcs_size)? + if fcs_size == 2 { 256 } else { 0 };
+	pos += fcs_size;
+	
+	let start = out.len();
+	let mut state = FrameState { repeats: [1, 4, 8], ..FrameState::default() };
+	
+	loop {
+		let header = read_le(data, pos, 3)? as usize;
+		let size = header >> 3;
+		pos += 3;
+		
+		pos += match (header >> 1) & 3 {
+			0 => {
+				out.extend_from_slice(bytes(data, pos, size)?);
+				size
+			}
+			1 => {
+				out.resize(out.len() + size, *data.get(pos).ok_or_else(eof)?);
+				1
+			}
+			2 if size <= MAX_BLOCK_SIZE => {
+				decode_block(bytes(data, pos, size)?, out, &mut state, start)?;
+				size
+			}
+			2 => return Err(invalid_data("zstd block is too large")),
+			_ => return Err(invalid_data("reserved zstd block type"))
+		};
+		
+		if header & 1 == 1 {
+			break;
+		}
+	}
+	
+	if fcs_size != 0 && (out.len() - start) as u64 != content_size {
+		return Err(invalid_data("zstd frame content size mismatch"));
+	}
+	
+	if descriptor & 0x04 != 0 {
+		let checksum = read_le(data, pos, 4)? as u32;
+		if checksum != xxh64(&out[start..], 0) as u32 {
+			return Err(invalid_data("zstd frame checksum mismatch"));
+		}
+		pos += 4;
+	}
+	
+	Ok(pos)
+}
+
+fn decode_block(block: &[u8], out: &mut Vec<u8>, state: &mut FrameState, frame_start: usize) -> io::Result<()> {
+	let (literals, pos) = decode_literals(block, state)?;
+	decode_sequences(&block[pos..], &literals, out, state, frame_start)
+}
+
+/// Returns the literals and the size of the literals section.
+fn decode_literals(block: &[u8], state: &mut FrameState) -> io::Result<(Vec<u8>, usize)> {
+	let b0 = *block.first().ok_or_else(eof)?;
+	let size_format = (b0 >> 2) & 3;
+	
+	match b0 & 3 {
+		ty @ (0 | 1) => {
+			let (size, header) = match size_format {
+				0 | 2 => ((b0 >> 3) as usize, 1),
+				1     => (read_le(block, 0, 2)? as usize >> 4, 2),
+				_     => (read_le(block, 0, 3)? as usize >> 4, 3)
+			};
+			
+			match ty {
+				0 => Ok((bytes(block, header, size)?.to_vec(), header + size)),
+				_ => Ok((vec![*block.get(header).ok_or_else(eof)?; size], header + 1))
+			}
+		}
+		ty => {
+			let (header, bits, streams) = match size_format {
+				0 => (3, 10, 1),
+				1 => (3, 10, 4),
+				2 => (4, 14, 4),
+				_ => (5, 18, 4)
+			};
+			let v = read_le(block, 0, header)? >> 4;
+			let regenerated = (v & ((1 << bits) - 1)) as usize;
+			let compressed = ((v >> bits) & ((1 << bits) - 1)) as usize;
+			let mut src = bytes(block, header, compressed)?;
+			
+			if ty == 2 {
+				let (table, used) = HuffmanTable::read(src)?;
+				state.huffman = Some(table);
+				src = &src[used..];
+			}
+			
+			let table = state.huffman.as_ref()
+				.ok_or_else(|| invalid_data("treeless literals without previous Huffman table"))?;
+			let mut literals = Vec::with_capacity(regenerated);
+			
+			if streams == 1 {
+				table.decode_stream(src, regenerated, &mut literals)?;
+			} else {
+				let sizes = [read_le(src, 0, 2)? as usize, read_le(src, 2, 2)? as usize, read_le(src, 4, 2)? as usize];
+				let segment = regenerated.div_ceil(4);
+				let mut offset = 6;
+				
+				for size in sizes {
+					table.decode_stream(bytes(src, offset, size)?, segment, &mut literals)?;
+					offset += size;
+				}
+				
+				let remaining = regenerated.checked_sub(literals.len())
+					.ok_or_else(|| invalid_data("literal streams exceed the regenerated size"))?;
+				table.decode_stream(src.get(offset..).ok_or_else(eof)?, remaining, &mut literals)?;
+			}
+			
+			Ok((literals, header + compressed))
+		}
+	}
+}
+
+fn decode_sequences(
+	src:         &[u8],
+	literals:    &[u8],
+	out:         &mut Vec<u8>,
+	state:       &mut FrameState,
+	frame_start: usize
+) -> io::Result<()> {
+	let b0 = *src.first().ok_or_else(eof)? as usize;
+	let (count, mut pos) = match b0 {
+		0         => (0, 1),
+		1..=127   => (b0, 1),
+		128..=254 => (((b0 - 128) << 8) + *src.get(1).ok_or_else(eof)? as usize, 2),
+		_         => (read_le(src, 1, 2)? as usize + 0x7F00, 3)
+	};
+	
+	if count == 0 {
+		out.extend_from_slice(literals);
+		return Ok(());
+	}
+	
+	let modes = *src.get(pos).ok_or_else(eof)?;
+	pos += 1;
+	
+	if modes & 3 != 0 {
+		return Err(invalid_data("reserved bits set in zstd symbol compression modes"));
+	}
+	
+	let FrameState { repeats, literals: ll_table, offsets: of_table, matches: ml_table, .. } = state;
+	let ll_table = FseTable::select(modes >> 6, ll_table, &LL_DEFAULT, 6, 9, 35, src, &mut pos)?;
+	let of_table = FseTable::select((modes >> 4) & 3, of_table, &OF_DEFAULT, 5, 8, 31, src, &mut pos)?;
+	let ml_table = FseTable::select((modes >> 2) & 3, ml_table, &ML_DEFAULT, 6, 9, 52, src, &mut pos)?;
+	
+	let mut reader = BackwardReader::new(src.get(pos..).ok_or_else(eof)?)?;
+	let mut ll_state = reader.read(ll_table.log) as usize;
+	let mut of_state = reader.read(of_table.log) as usize;
+	let mut ml_state = reader.read(ml_table.log) as usize;
+	let mut literal_pos = 0;
+	
+	for i in 0..count {
+		let ll_code = ll_table.entries[ll_state].symbol as usize;
+		let of_code = of_table.entries[of_state].symbol as u32;
+		let ml_code = ml_table.entries[ml_state].symbol as usize;
+		
+		if ll_code > 35 || ml_code > 52 || of_code > 31 {
+			return Err(invalid_data("invalid zstd sequence code"));
+		}
+		
+		let offset_value = (1u64 << of_code) + reader.read(of_code);
+		let match_len = (ML_BASE[ml_code] as u64 + reader.read(ML_BITS[ml_code])) as usize;
+		let literal_len = (LL_BASE[ll_code] as u64 + reader.read(LL_BITS[ll_code])) as usize;
+		
+		if i + 1 < count {
+			ll_state = ll_table.update(ll_state, &mut reader);
+			ml_state = ml_table.update(ml_state, &mut reader);
+			of_state = of_table.update(of_state, &mut reader);
+		}
+		
+		let offset = if offset_value > 3 {
+			let offset = offset_value as usize - 3;
+			*repeats = [offset, repeats[0], repeats[1]];
+			offset
+		} else {
+			match offset_value as usize - 1 + (literal_len == 0) as usize {
+				0 => repeats[0],
+				1 => {
+					*repeats = [repeats[1], repeats[0], repeats[2]];
+					repeats[0]
+				}
+				2 => {
+					*repeats = [repeats[2], repeats[0], repeats[1]];
+					repeats[0]
+				}
+				_ => {
+					let offset = repeats[0].checked_sub(1).filter(|offset| *offset != 0)
+						.ok_or_else(|| invalid_data("invalid zstd repeat offset"))?;
+					*repeats = [offset, repeats[0], repeats[1]];
+					offset
+				}
+			}
+		};
+		
+		out.extend_from_slice(literals.get(literal_pos..literal_pos + literal_len)
+			.ok_or_else(|| invalid_data("zstd sequence exceeds the literals"))?);
+		literal_pos += literal_len;
+		
+		if offset > out.len() - frame_start {
+			return Err(invalid_data("zstd match offset is too far back"));
+		}
+		
+		let start = out.len() - offset;
+		if offset >= match_len {
+			out.extend_from_within(start..start + match_len);
+		} else {
+			for i in 0..match_len {
+				out.push(out[start + i]);
+			}
+		}
+	}
+	
+	if reader.pos != 0 {
+		return Err(invalid_data("zstd sequence bitstream was not fully consumed"));
+	}
+	
+	out.extend_from_slice(&literals[literal_pos..]);
+	Ok(())
+}
+
+/// Reads a bitstream backwards, starting with the most significant bit of the last byte
+/// after the padding marker. Reading past the beginning yields zeros.
+struct BackwardReader<'a> {
+	data: &'a [u8],
+	/// number of bits that have not been read yet, negative after reading past the beginning
+	pos:  isize
+}
+
+impl<'a> BackwardReader<'a> {
+	fn new(data: &'a [u8]) -> io::Result<Self> {
+		match data.last() {
+			Some(last) if *last != 0 => Ok(Self {
+				data,
+				pos: (data.len() as isize - 1) * 8 + 7 - last.leading_zeros() as isize
+			}),
+			_ => Err(invalid_data("invalid zstd bitstream padding"))
+		}
+	}
+	
+	fn peek(&self, n: u32) -> u64 {
+		if n == 0 {
+			return 0;
+		}
+		
+		let start = self.pos - n as isize;
+		let low = start.max(0);
+		if self.pos <= low {
+			return 0;
+		}
+		
+		let byte = (low / 8) as usize;
+		let word = (0..8).filter_map(|i| self.data.get(byte + i).map(|b| (*b as u64) << (i * 8))).fold(0, |a, b| a | b);
+		let bits = (word >> (low % 8)) & ((1u64 << (self.pos - low)) - 1);
+		bits << (low - start)
+	}
+	
+	fn read(&mut self, n: u32) -> u64 {
+		let value = self.peek(n);
+		self.pos -= n as isize;
+		value
+	}
+}
+
+/// Reads a bitstream forwards, starting with the least significant bit of the first byte.
+struct ForwardReader<'a> {
+	data: &'a [u8],
+	pos:  usize
+}
+
+impl ForwardReader<'_> {
+	fn peek(&self, n: u32) -> u32 {
+		let byte = self.pos / 8;
+		let word = (0..5).filter_map(|i| self.data.get(byte + i).map(|b| (*b as u64) << (i * 8))).fold(0, |a, b| a | b);
+		((word >> (self.pos % 8)) & ((1u64 << n) - 1)) as u32
+	}
+	
+	fn read(&mut self, n: u32) -> u32 {
+		let value = self.peek(n);
+		self.pos += n as usize;
+		value
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct FseEntry {
+	symbol:   u8,
+	nb_bits:  u8,
+	baseline: u16
+}
+
+#[derive(Clone, Debug)]
+struct FseTable {
+	log:     u32,
+	entries: Vec<FseEntry>
+}
+
+impl FseTable {
+	/// Reads a table description and returns the table and the number of bytes used.
+	fn read(data: &[u8], max_log: u32, max_symbol: usize) -> io::Result<(Self, usize)> {
+		let mut reader = ForwardReader { data, pos: 0 };
+		let log = reader.read(4) + 5;
+		if log > max_log {
+			return Err(invalid_data("zstd FSE accuracy log is too large"));
+		}
+		
+		let mut remaining = (1i32 << log) + 1;
+		let mut threshold = 1i32 << log;
+		let mut nb_bits = log + 1;
+		let mut probabilities = Vec::new();
+		let mut previous_zero = false;
+		
+		while remaining > 1 && probabilities.len() <= max_symbol {
+			if previous_zero {
+				loop {
+					let repeat = reader.read(2);
+					probabilities.resize(probabilities.len() + repeat as usize, 0);
+					if repeat != 3 {
+						break;
+					}
+				}
+				
+				if probabilities.len() > max_symbol {
+					break;
+				}
+			}
+			
+			let max = (2 * threshold - 1) - remaining;
+			let low = reader.peek(nb_bits - 1) as i32;
+			let count = if low < max {
+				reader.pos += nb_bits as usize - 1;
+				low
+			} else {
+				let value = reader.read(nb_bits) as i32;
+				if value >= threshold { value - max } else { value }
+			} - 1;
+			
+			remaining -= count.abs();
+			probabilities.push(count as i16);
+			previous_zero = count == 0;
+			
+			while remaining < threshold {
+				nb_bits -= 1;
+				threshold >>= 1;
+			}
+		}
+		
+		let used = reader.pos.div_ceil(8);
+		if remaining != 1 || probabilities.len() > max_symbol + 1 || used > data.len() {
+			return Err(invalid_data("invalid zstd FSE table description"));
+		}
+		
+		Ok((Self::new(log, &probabilities)?, used))
+	}
+	
+	fn new(log: u32, probabilities: &[i16]) -> io::Result<Self> {
+		let size = 1usize << log;
+		let mut entries = vec![FseEntry::default(); size];
+		let mut next = vec![0u32; probabilities.len()];
+		let mut high = size;
+		
+		for (symbol, probability) in probabilities.iter().enumerate() {
+			if *probability == -1 {
+				high = high.checked_sub(1).ok_or_else(|| invalid_data("invalid zstd FSE probabilities"))?;
+				entries[high].symbol = symbol as u8;
+				next[symbol] = 1;
+			} else {
+				next[symbol] = *probability as u32;
+			}
+		}
+		
+		let step = (size >> 1) + (size >> 3) + 3;
+		let mut pos = 0;
+		
+		for (symbol, probability) in probabilities.iter().enumerate() {
+			for _ in 0..(*probability).max(0) {
+				entries[pos].symbol = symbol as u8;
+				pos = (pos + step) & (size - 1);
+				while pos >= high {
+					pos = (pos + step) & (size - 1);
+				}
+			}
+		}
+		
+		if pos != 0 {
+			return Err(invalid_data("invalid zstd FSE probabilities"));
+		}
+		
+		for entry in &mut entries {
+			let state = next[entry.symbol as usize];
+			next[entry.symbol as usize] += 1;
+			let nb_bits = log - (31 - state.leading_zeros());
+			entry.nb_bits = nb_bits as u8;
+			entry.baseline = ((state << nb_bits) - size as u32) as u16;
+		}
+		
+		Ok(Self { log, entries })
+	}
+	
+	fn rle(symbol: u8) -> Self {
+		Self { log: 0, entries: vec![FseEntry { symbol, nb_bits: 0, baseline: 0 }] }
+	}
+	
+	/// Returns the table for a symbol compression mode, `slot` holds the table of the previous block.
+	#[allow(clippy::too_many_arguments)]
+	fn select<'a>(
+		mode:        u8,
+		slot:        &'a mut Option<Self>,
+		default:     &[i16],
+		default_log: u32,
+		max_log:     u32,
+		max_symbol:  usize,
+		src:         &[u8],
+		pos:         &mut usize
+	) -> io::Result<&'a Self> {
+		match mode {
+			0 => *slot = Some(Self::new(default_log, default)?),
+			1 => {
+				*slot = Some(Self::rle(*src.get(*pos).ok_or_else(eof)?));
+				*pos += 1;
+			}
+			2 => {
+				let (table, used) = Self::read(src.get(*pos..).ok_or_else(eof)?, max_log, max_symbol)?;
+				*slot = Some(table);
+				*pos += used;
+			}
+			_ => ()
+		}
+		
+		slot.as_ref().ok_or_else(|| invalid_data("zstd repeat mode without previous table"))
+	}
+	
+	fn update(&self, state: usize, reader: &mut BackwardReader) -> usize {
+		let entry = self.entries[state];
+		entry.baseline as usize + reader.read(entry.nb_bits as u32) as usize
+	}
+}
+
+#[derive(Clone, Debug)]
+struct HuffmanTable {
+	max_bits: u32,
+	/// symbol and number of bits, indexed by the next `max_bits` bits
+	entries:  Vec<(u8, u8)>
+}
+
+impl HuffmanTable {
+	/// Reads a tree description and returns the table and the number of bytes used.
+	fn read(data: &[u8]) -> io::Result<(Self, usize)> {
+		let header = *data.first().ok_or_else(eof)? as usize;
+		let (mut weights, used) = if header < 128 {
+			(Self::decode_weights(bytes(data, 1, header)?)?, 1 + header)
+		} else {
+			let count = header - 127;
+			let src = bytes(data, 1, count.div_ceil(2))?;
+			let weights = (0..count)
+				.map(|i| (src[i / 2] >> (4 - i % 2 * 4)) & 0xF)
+				.collect();
+			(weights, 1 + count.div_ceil(2))
+		};
+		
+		if weights.iter().any(|w| *w > 11) {
+			return Err(invalid_data("invalid zstd Huffman weight"));
+		}
+		
+		let sum = weights.iter().filter(|w| **w > 0).map(|w| 1u32 << (w - 1)).sum::<u32>();
+		if sum == 0 {
+			return Err(invalid_data("invalid zstd Huffman weights"));
+		}
+		
+		let max_bits = 32 - sum.leading_zeros();
+		let rest = (1u32 << max_bits) - sum;
+		if max_bits > 11 || !rest.is_power_of_two() || weights.len() > 255 {
+			return Err(invalid_data("invalid zstd Huffman weights"));
+		}
+		weights.push(rest.trailing_zeros() as u8 + 1);
+		
+		let mut counts = [0u32; 13];
+		weights.iter().for_each(|w| counts[*w as usize] += 1);
+		
+		let mut starts = [0u32; 13];
+		let mut next = 0;
+		for w in 1..=max_bits as usize {
+			starts[w] = next;
+			next += counts[w] << (w - 1);
+		}
+		
+		let mut entries = vec![(0, 0); 1 << max_bits];
+		for (symbol, w) in weights.iter().enumerate().filter(|(_, w)| **w > 0) {
+			let len = 1 << (w - 1);
+			let start = starts[*w as usize] as usize;
+			entries[start..start + len].fill((symbol as u8, (max_bits + 1 - *w as u32) as u8));
+			starts[*w as usize] += len as u32;
+		}
+		
+		Ok((Self { max_bits, entries }, used))
+	}
+	
+	/// Decodes FSE compressed weights, which use two interleaved states.
+	fn decode_weights(data: &[u8]) -> io::Result<Vec<u8>> {
+		let (table, used) = FseTable::read(data, 6, 255)?;
+		let mut reader = BackwardReader::new(&data[used..])?;
+		let mut states = [reader.read(table.log) as usize, reader.read(table.log) as usize];
+		let mut weights = Vec::new();
+		
+		for i in (0..2).cycle() {
+			if weights.len() > 255 {
+				return Err(invalid_data("too many zstd Huffman weights"));
+			}
+			
+			weights.push(table.entries[states[i]].symbol);
+			states[i] = table.update(states[i], &mut reader);
+			
+			if reader.pos < 0 {
+				weights.push(table.entries[states[1 - i]].symbol);
+				break;
+			}
+		}
+		
+		Ok(weights)
+	}
+	
+	fn decode_stream(&self, data: &[u8], count: usize, out: &mut Vec<u8>) -> io::Result<()> {
+		let mut reader = BackwardReader::new(data)?;
+		for _ in 0..count {
+			let (symbol, nb_bits) = self.entries[reader.peek(self.max_bits) as usize];
+			out.push(symbol);
+			reader.pos -= nb_bits as isize;
+		}
+		
+		match reader.pos {
+			0 => Ok(()),
+			_ => Err(invalid_data("zstd literal stream was not fully consumed"))
+		}
+	}
+}
+
+/// XXH64, the lower 32 bits are used as frame checksum.
+pub fn xxh64(data: &[u8], seed: u64) -> u64 {
+	const P1: u64 = 0x9E37_79B1_85EB_CA87;
+	const P2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+	const P3: u64 = 0x1656_67B1_9E37_79F9;
+	const P4: u64 = 0x85EB_CA77_C2B2_AE63;
+	const P5: u64 = 0x27D4_EB2F_1656_67C5;
+	
+	fn round(acc: u64, lane: u64) -> u64 {
+		acc.wrapping_add(lane.wrapping_mul(P2)).rotate_left(31).wrapping_mul(P1)
+	}
+	
+	fn merge(acc: u64, v: u64) -> u64 {
+		(acc ^ round(0, v)).wrapping_mul(P1).wrapping_add(P4)
+	}
+	
+	let u64_at = |i: usize| u64::from_le_bytes(data[i..i + 8].try_into().unwrap());
+	let mut i = 0;
+	let mut h = if data.len() >= 32 {
+		let mut v = [seed.wrapping_add(P1).wrapping_add(P2), seed.wrapping_add(P2), seed, seed.wrapping_sub(P1)];
+		while i + 32 <= data.len() {
+			for (j, v) in v.iter_mut().enumerate() {
+				*v = round(*v, u64_at(i + j * 8));
+			}
+			i += 32;
+		}
+		
+		let h = v[0].rotate_left(1)
+			.wrapping_add(v[1].rotate_left(7))
+			.wrapping_add(v[2].rotate_left(12))
+			.wrapping_add(v[3].rotate_left(18));
+		v.iter().fold(h, |h, v| merge(h, *v))
+	} else {
+		seed.wrapping_add(P5)
+	};
+	
+	h = h.wrapping_add(data.len() as u64);
+	
+	while i + 8 <= data.len() {
+		h = (h ^ round(0, u64_at(i))).rotate_left(27).wrapping_mul(P1).wrapping_add(P4);
+		i += 8;
+	}
+	
+	if i + 4 <= data.len() {
+		let v = u32::from_le_bytes(data[i..i + 4].try_into().unwrap()) as u64;
+		h = (h ^ v.wrapping_mul(P1)).rotate_left(23).wrapping_mul(P2).wrapping_add(P3);
+		i += 4;
+	}
+	
+	for byte in &data[i..] {
+		h = (h ^ (*byte as u64).wrapping_mul(P5)).rotate_left(11).wrapping_mul(P1);
+	}
+	
+	h ^= h >> 33;
+	h = h.wrapping_mul(P2);
+	h ^= h >> 29;
+	h = h.wrapping_mul(P3);
+	h ^ (h >> 32)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn round_trip() {
+		for len in [0, 1, 255, 256, 1000, 0x1_0100, MAX_BLOCK_SIZE * 2 + 7] {
+			let data = (0..len).map(|i| if i % 3000 < 1000 { 7 } else { (i % 251) as u8 }).collect::<Vec<_>>();
+			assert_eq!(decompress(&compress(&data)).unwrap(), data);
+		}
+	}
+	
+	#[test]
+	fn compressed() {
+		let mut x = 1u32;
+		let expected = (0..300).map(|_| {
+			x = x.wrapping_mul(1103515245).wrapping_add(12345) & 0x7FFF_FFFF;
+			b"aaaaaaaabbbbccde"[(x >> 16) as usize & 15]
+		}).collect::<Vec<_>>();
+		
+		// compressed with zstd level 19, uses Huffman coded literals and sequences
+		let mut data = [
+			0x28, 0xb5, 0x2f, 0xfd, 0x64, 0x2c, 0x00, 0x05, 0x03, 0x00, 0x16, 0xd2, 0x15, 0x08, 0xd0, 0xa5,
+			0x03, 0x64, 0x27, 0x00, 0x80, 0xaf, 0x14, 0x00, 0x12, 0x00, 0x13, 0x00, 0x85, 0x92, 0xc2, 0x88,
+			0xa8, 0x80, 0x4d, 0xad, 0x30, 0x4e, 0xef, 0x4a, 0xf5, 0x31, 0x8c, 0x27, 0x91, 0x5b, 0x85, 0x01,
+			0x01, 0xd3, 0x4c, 0xd7, 0x74, 0xfc, 0x6c, 0xe0, 0xfa, 0x62, 0x5e, 0x95, 0xe9, 0x8d, 0x3e, 0x20,
+			0x05, 0x0e, 0x6d, 0xa9, 0x33, 0xbe, 0xa0, 0x8c, 0x05, 0x10, 0x2d, 0xbe, 0xee, 0xbb, 0x10, 0x00,
+			0x59, 0x32, 0x67, 0x74, 0x03, 0xf7, 0x79, 0x44, 0xbd, 0x97, 0x85, 0x6e, 0x02, 0x58, 0x2e, 0xe8,
+			0xfc, 0xce, 0xbf, 0x0f, 0x01, 0x00, 0x3c, 0x03, 0x48, 0x01, 0xa0, 0xd0, 0xda, 0x79
+		];
+		assert_eq!(decompress(&data).unwrap(), expected);
+		
+		// corrupt the checksum
+		data[data.len() - 1] ^= 1;
+		assert!(decompress(&data).is_err());
+	}
+	
+	#[test]
+	fn xxh64_vectors() {
+		assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+		assert_eq!(xxh64(b"a", 0), 0xD24E_C4F1_A98C_6E5B);
+		assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+	}
+}
@@ -824,6 +824,14 @@ pub mod quat {
                  (angle * T::val(0.5)).cos())
         }
 
+        /// The Hamilton product, the rotation of `other` followed by the rotation of `self`.
+        pub fn mul_quat(self, other: Self) -> Self {
+            Self(self.3 * other.0 + self.0 * other.3 + self.1 * other.2 - self.2 * other.1,
+                 self.3 * other.1 - self.0 * other.2 + self.1 * other.3 + self.2 * other.0,
+                 self.3 * other.2 + self.0 * other.1 - self.1 * other.0 + self.2 * other.3,
+                 self.3 * other.3 - self.0 * other.0 - self.1 * other.1 - self.2 * other.2)
+        }
+
         pub fn to_axis_angle(self) -> (Vec3<T>, T) {
             let acos = self.3.acos();
             let inv_sqrt = T::val(1.0) / (T::val(1.0) - self.3 * self.3).sqrt();
@@ -901,9 +909,71 @@ pub mod quat {
             let rz = axis.2 * inv_len * sin;
             let rw = (angle * T::val(0.5)).cos();
             Self(self.3 * rx + self.0 * rw + self.1 * rz - self.2 * ry,
-                 self.3 * ry + self.0 * rz + self.1 * rw - self.2 * rx,
-                 self.3 * rz + self.0 * ry + self.1 * rx - self.2 * rw,
-                 self.3 * rw + self.0 * rx + self.1 * ry - self.2 * rz)
+                 self.3 * ry - self.0 * rz + self.1 * rw + self.2 * rx,
+                 self.3 * rz + self.0 * ry - self.1 * rx + self.2 * rw,
+                 self.3 * rw - self.0 * rx - self.1 * ry - self.2 * rz)
+        }
+
+        /// Spherical linear interpolation along the shortest arc, falls back to a normalized
+        /// linear interpolation for nearly parallel quaternions.
+        pub fn slerp(self, other: Self, t: T) -> Self {
+            let zero = T::val(0.0);
+            let one = T::val(1.0);
+            let mut cos = self.dot(other);
+            let other = if cos < zero {
+                cos = zero - cos;
+                Self(zero - other.0, zero - other.1, zero - other.2, zero - other.3)
+            } else {
+                other
+            };
+
+            if cos > T::val(0.9995) {
+                let v = self + (other - self) * t;
+                return v.normalize(one);
+            }
+
+            let angle = cos.acos();
+            let inv_sin = one / angle.sin();
+            let a = ((one - t) * angle).sin() * inv_sin;
+            let b = (t * angle).sin() * inv_sin;
+            self * a + other * b
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn slerp() {
+            let a = Quat32::from_rotation_z(0.0);
+            let b = Quat32::from_rotation_z(core::f32::consts::FRAC_PI_2);
+            let c = a.slerp(b, 0.5);
+            let d = Quat32::from_rotation_z(core::f32::consts::FRAC_PI_4);
+            assert!((c - d).len() < 1e-6, "{:?} != {:?}", c, d);
+            assert!((a.slerp(b, 1.0) - b).len() < 1e-6);
+        }
+
+        #[test]
+        fn rotate_axis() {
+            let q = Quat32::from_axis_angle(Vec3(1.0, 2.0, 3.0), 0.7);
+            let cases = [
+                (Vec3(1.0, 0.0, 0.0), q.rotate_x(1.1)),
+                (Vec3(0.0, 1.0, 0.0), q.rotate_y(1.1)),
+                (Vec3(0.0, 0.0, 1.0), q.rotate_z(1.1)),
+                (Vec3(0.0, 2.0, 0.0), q.rotate_y(1.1))
+            ];
+
+            for (axis, expected) in cases {
+                let r = q.rotate_axis(axis, 1.1);
+                assert!((r - expected).len() < 1e-6, "{:?}: {:?} != {:?}", axis, r, expected);
+                assert!((r.len() - 1.0).abs() < 1e-6);
+            }
+
+            let axis = Vec3(0.0, 1.0, 1.0);
+            let r = q.rotate_axis(axis, 0.4);
+            let expected = q.mul_quat(Quat32::from_axis_angle(axis, 0.4));
+            assert!((r - expected).len() < 1e-6, "{:?} != {:?}", r, expected);
         }
     }
 }
@@ -941,6 +1011,12 @@ pub mod mat {
 				}
 			}
 
+			impl<T> $name<T> {
+				pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> $name<U> {
+					$name( $( self.$x.map(&mut f), )* )
+				}
+			}
+
 	    	impl<T> core::ops::Index<usize> for $name<T> {
 				type Output = $vec<T>;
 
@@ -987,17 +1063,40 @@ pub mod mat {
     #[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
     pub struct Mat2<T>(pub Vec2<T>, pub Vec2<T>);
 
-    impl Default for Mat2<f32> {
-        fn default() -> Self {
-            Self(Vec2(1f32, 0f32),
-                 Vec2(0f32, 1f32))
+    impl<T: Copy> Mat2<T> {
+        pub fn from_rotation(angle: T) -> Self where T: Float {
+            let (sin, cos) = (angle.sin(), angle.cos());
+            Self(Vec2(cos, sin), Vec2(-sin, cos))
+        }
+
+        pub fn from_scale(scale: Vec2<T>) -> Self where T: Float {
+            Self(Vec2(scale.0, T::val(0.0)), Vec2(T::val(0.0), scale.1))
+        }
+
+        pub fn transform(&self, vec: Vec2<T>) -> Vec2<T> where T: Add<Output = T> + Mul<Output = T> {
+            self.0 * vec.0 + self.1 * vec.1
+        }
+
+        pub fn transpose(self) -> Self {
+            Self(Vec2((self.0).0, (self.1).0),
+                 Vec2((self.0).1, (self.1).1))
+        }
+
+        pub fn det(&self) -> T where T: Sub<Output = T> + Mul<Output = T> {
+            (self.0).0 * (self.1).1 - (self.1).0 * (self.0).1
+        }
+
+        /// Returns the inverse or `None` if the matrix is singular, `!` does not check the
+        /// determinant.
+        pub fn inverse(&self) -> Option<Self> where T: Float {
+            invertible(self.det()).then(|| !*self)
         }
     }
 
-    impl Default for Mat2<f64> {
+    impl<T: Float> Default for Mat2<T> {
         fn default() -> Self {
-            Self(Vec2(1f64, 0f64),
-                 Vec2(0f64, 1f64))
+            Self(Vec2(T::val(1.0), T::val(0.0)),
+                 Vec2(T::val(0.0), T::val(1.0)))
         }
     }
 
@@ -1007,24 +1106,102 @@ pub mod mat {
         }
     }
 
+    impl<T: Add<Output=T> + Mul<Output=T> + Copy> Mul for Mat2<T> {
+        type Output = Self;
+
+        fn mul(self, rhs: Self) -> Self::Output {
+            Self(self.transform(rhs.0), self.transform(rhs.1))
+        }
+    }
+
+    impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Neg<Output=T> + Copy> Div for Mat2<T> {
+        type Output = Self;
+
+        fn div(self, rhs: Self) -> Self::Output {
+            self * (!rhs)
+        }
+    }
+
+    impl<T: Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Neg<Output=T> + Copy> Not for Mat2<T> {
+        type Output = Self;
+
+        fn not(self) -> Self {
+            let det = self.det();
+            Self(Vec2((self.1).1 / det, -(self.0).1 / det),
+                 Vec2(-(self.1).0 / det, (self.0).0 / det))
+        }
+    }
+
     mat_impl!(Mat2, Vec2; v0: 0, v1: 1);
 
     #[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
     pub struct Mat3<T>(pub Vec3<T>, pub Vec3<T>, pub Vec3<T>);
 
-    impl Default for Mat3<f32> {
-        fn default() -> Self {
-            Self(Vec3(1f32, 0f32, 0f32),
-                 Vec3(0f32, 1f32, 0f32),
-                 Vec3(0f32, 0f32, 1f32))
+    impl<T: Copy> Mat3<T> {
+        pub fn from_rotation(quat: Quat<T>) -> Self where T: Float {
+            Mat4::from_rotation(quat).into()
+        }
+
+        pub fn from_scale(scale: Vec3<T>) -> Self where T: Float {
+            Self(Vec3(scale.0, T::val(0.0), T::val(0.0)),
+                 Vec3(T::val(0.0), scale.1, T::val(0.0)),
+                 Vec3(T::val(0.0), T::val(0.0), scale.2))
+        }
+
+        pub fn transform(&self, vec: Vec3<T>) -> Vec3<T> where T: Add<Output = T> + Mul<Output = T> {
+            self.0 * vec.0 + self.1 * vec.1 + self.2 * vec.2
+        }
+
+        pub fn transpose(self) -> Self {
+            Self(Vec3((self.0).0, (self.1).0, (self.2).0),
+                 Vec3((self.0).1, (self.1).1, (self.2).1),
+                 Vec3((self.0).2, (self.1).2, (self.2).2))
+        }
+
+        pub fn det(&self) -> T where T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> {
+            (self.0).0 * ((self.1).1 * (self.2).2 - (self.1).2 * (self.2).1)
+                + (self.0).1 * ((self.1).2 * (self.2).0 - (self.1).0 * (self.2).2)
+                + (self.0).2 * ((self.1).0 * (self.2).1 - (self.1).1 * (self.2).0)
+        }
+
+        /// Returns the inverse or `None` if the matrix is singular, `!` does not check the
+        /// determinant.
+        pub fn inverse(&self) -> Option<Self> where T: Float {
+            invertible(self.det()).then(|| !*self)
+        }
+
+        /// Polar decomposition `A = R * S` into an orthogonal matrix `R` and a symmetric positive
+        /// semi-definite matrix `S`. `R` is the closest orthogonal matrix to `A` and a reflection
+        /// if the determinant of `A` is negative. Returns `None` if the matrix is singular.
+        pub fn polar(&self) -> Option<(Self, Self)> where T: Float {
+            const MAX_ITERATIONS: usize = 32;
+
+            // Newton iteration R = (R + R^-T) / 2, scaled by the ratio of the norms for faster
+            // convergence
+            let norm = |m: &Self| (m.0.len2() + m.1.len2() + m.2.len2()).sqrt();
+            let mut r = *self;
+            for _ in 0..MAX_ITERATIONS {
+                let inv = r.inverse()?.transpose();
+                let gamma = (norm(&inv) / norm(&r)).sqrt();
+                let next = (r.map(|v| v * gamma) + inv.map(|v| v / gamma)).map(|v| v * T::val(0.5));
+                let delta = norm(&(next - r));
+                r = next;
+
+                if delta <= T::EPSILON * T::val(16.0) {
+                    break;
+                }
+            }
+
+            let s = r.transpose() * *self;
+            Some((r, (s + s.transpose()).map(|v| v * T::val(0.5))))
         }
     }
 
-    impl Default for Mat3<f64> {
+    impl<T: Float> Default for Mat3<T> {
         fn default() -> Self {
-            Self(Vec3(1f64, 0f64, 0f64),
-                 Vec3(0f64, 1f64, 0f64),
-                 Vec3(0f64, 0f64, 1f64))
+            Self(Vec3(T::val(1.0), T::val(0.0), T::val(0.0)),
+                 Vec3(T::val(0.0), T::val(1.0), T::val(0.0)),
+                 Vec3(T::val(0.0), T::val(0.0), T::val(1.0)))
         }
     }
 
@@ -1034,6 +1211,41 @@ pub mod mat {
         }
     }
 
+    /// The upper left 3x3 matrix.
+    impl<T: Copy> From<Mat4<T>> for Mat3<T> {
+        fn from(m: Mat4<T>) -> Self {
+            Self(m.0.xyz(), m.1.xyz(), m.2.xyz())
+        }
+    }
+
+    impl<T: Add<Output=T> + Mul<Output=T> + Copy> Mul for Mat3<T> {
+        type Output = Self;
+
+        fn mul(self, rhs: Self) -> Self::Output {
+            Self(self.transform(rhs.0), self.transform(rhs.1), self.transform(rhs.2))
+        }
+    }
+
+    impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Copy> Div for Mat3<T> {
+        type Output = Self;
+
+        fn div(self, rhs: Self) -> Self::Output {
+            self * (!rhs)
+        }
+    }
+
+    impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Copy> Not for Mat3<T> {
+        type Output = Self;
+
+        fn not(self) -> Self {
+            // the rows of the inverse are the cross products of the columns
+            let det = self.det();
+            Self(self.1.cross(self.2) / det,
+                 self.2.cross(self.0) / det,
+                 self.0.cross(self.1) / det).transpose()
+        }
+    }
+
     mat_impl!(Mat3, Vec3; v0: 0, v1: 1, v2: 2);
 
     #[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
@@ -1190,6 +1402,78 @@ pub mod mat {
             mat
         }
 
+        /// Perspective projection without a far plane, depths approach 1 at infinity.
+        pub fn from_perspective_infinite(
+            y_fov:         T,
+            aspect:        T,
+            z_near:        T,
+            z_zero_to_one: bool
+        ) -> Self where T: Float {
+            let mut mat = Self::from_perspective_scale(y_fov, aspect);
+            (mat.2).2 = T::val(-1.0);
+            (mat.3).2 = if z_zero_to_one { -z_near } else { T::val(-2.0) * z_near };
+            mat
+        }
+
+        /// Perspective projection with reversed depth, the near plane is at depth 1 and the far
+        /// plane at 0. Spreads the precision of floating point depth buffers evenly over the
+        /// distance, the depth range is always 0 to 1.
+        pub fn from_perspective_reversed_z(
+            y_fov:  T,
+            aspect: T,
+            z_near: T,
+            z_far:  T
+        ) -> Self where T: Float {
+            let mut mat = Self::from_perspective_scale(y_fov, aspect);
+            (mat.2).2 = z_near / (z_far - z_near);
+            (mat.3).2 = z_far * z_near / (z_far - z_near);
+            mat
+        }
+
+        /// Perspective projection with reversed depth and without a far plane, depths approach 0
+        /// at infinity.
+        pub fn from_perspective_infinite_reversed_z(
+            y_fov:  T,
+            aspect: T,
+            z_near: T
+        ) -> Self where T: Float {
+            let mut mat = Self::from_perspective_scale(y_fov, aspect);
+            (mat.2).2 = T::val(0.0);
+            (mat.3).2 = z_near;
+            mat
+        }
+
+        /// The parts of a perspective projection that do not depend on the depth mapping.
+        fn from_perspective_scale(y_fov: T, aspect: T) -> Self where T: Float {
+            let mut mat = Self::default();
+            let h = (y_fov * T::val(0.5)).tan();
+            (mat.0).0 = T::val(1.0) / (h * aspect);
+            (mat.1).1 = T::val(1.0) / h;
+            (mat.2).3 = T::val(-1.0);
+            (mat.3).3 = T::val(0.0);
+            mat
+        }
+
+        /// Right-handed view matrix of a camera at `eye` that looks at `target`. The camera looks
+        /// along the negative z axis like the projections expect, `up` must not be parallel to
+        /// the view direction.
+        pub fn look_at(eye: Vec3<T>, target: Vec3<T>, up: Vec3<T>) -> Self where T: Float {
+            Self::look_to(eye, target - eye, up)
+        }
+
+        /// Right-handed view matrix of a camera at `eye` that looks in the direction `dir`.
+        pub fn look_to(eye: Vec3<T>, dir: Vec3<T>, up: Vec3<T>) -> Self where T: Float {
+            let f = dir.normalize(T::val(1.0));
+            let s = f.cross(up).normalize(T::val(1.0));
+            let u = s.cross(f);
+            Self(
+                Vec4(s.0, u.0, -f.0, T::val(0.0)),
+                Vec4(s.1, u.1, -f.1, T::val(0.0)),
+                Vec4(s.2, u.2, -f.2, T::val(0.0)),
+                Vec4(-s.dot(eye), -u.dot(eye), f.dot(eye), T::val(1.0))
+            )
+        }
+
         pub fn transform(&self, vec: Vec4<T>) -> Vec4<T> where T: Add<Output = T> + Mul<Output = T> {
             self.0 * vec.0 + self.1 * vec.1 + self.2 * vec.2 + self.3 * vec.3
         }
@@ -1198,6 +1482,12 @@ pub mod mat {
             self.0.xyz() * vec.0 + self.1.xyz() * vec.1 + self.2.xyz() * vec.2 + self.3.xyz()
         }
 
+        /// Transforms all positions of `src` to `dst`, which must have the same length.
+        pub fn transform_pos_batch(&self, src: &[Vec3<T>], dst: &mut [Vec3<T>]) where T: Add<Output = T> + Mul<Output = T> {
+            assert_eq!(src.len(), dst.len(), "source and destination have different lengths");
+            src.iter().zip(dst).for_each(|(src, dst)| *dst = self.transform_pos(*src));
+        }
+
         pub fn transform_dir(&self, vec: Vec3<T>) -> Vec3<T> where T: Add<Output = T> + Mul<Output = T> {
             self.0.xyz() * vec.0 + self.1.xyz() * vec.1 + self.2.xyz() * vec.2
         }
@@ -1405,6 +1695,53 @@ pub mod mat {
                 self.3
             )
         }
+
+        /// Returns the inverse or `None` if the matrix is singular, `!` does not check the
+        /// determinant.
+        pub fn inverse(&self) -> Option<Self> where T: Float {
+            invertible(self.det()).then(|| !*self)
+        }
+
+        /// Inverts a matrix with the last row `(0, 0, 0, 1)`, like the transforms built from
+        /// translations, rotations and scales. Cheaper and more precise than [`Mat4::inverse`],
+        /// returns `None` if the matrix is singular.
+        pub fn inverse_affine(&self) -> Option<Self> where T: Float {
+            let inv = Mat3::from(*self).inverse()?;
+            let t = -inv.transform(self.get_translation());
+            let mut mat = Self::from(inv);
+            mat.3 = Vec4::from((t, T::val(1.0)));
+            Some(mat)
+        }
+
+        /// The matrix that transforms normals, the inverse transpose of the upper left 3x3
+        /// matrix. Returns `None` if it is singular, the transformed normals have to be normalized
+        /// if the matrix scales.
+        pub fn normal_matrix(&self) -> Option<Mat3<T>> where T: Float {
+            Some(Mat3::from(*self).inverse()?.transpose())
+        }
+
+        /// Decomposes an affine transform into translation, rotation and scale, the inverse of
+        /// [`Mat4::from_transform`]. The rotation is the orthogonal factor of the polar
+        /// decomposition, the closest rotation if the matrix also shears, the shear is lost. A
+        /// negative determinant results in a negative scale on all axes. Returns `None` if the
+        /// matrix is singular.
+        pub fn decompose(&self) -> Option<(Vec3<T>, Quat<T>, Vec3<T>)> where T: Float {
+            let (mut r, mut s) = Mat3::from(*self).polar()?;
+            if r.det() < T::val(0.0) {
+                r = -r;
+                s = -s;
+            }
+
+            let rotation = Self::from(r).get_rotation().normalize(T::val(1.0));
+            Some((self.get_translation(), rotation, Vec3((s.0).0, (s.1).1, (s.2).2)))
+        }
+
+        pub fn transpose(self) -> Self {
+            Self(Vec4((self.0).0, (self.1).0, (self.2).0, (self.3).0),
+                 Vec4((self.0).1, (self.1).1, (self.2).1, (self.3).1),
+                 Vec4((self.0).2, (self.1).2, (self.2).2, (self.3).2),
+                 Vec4((self.0).3, (self.1).3, (self.2).3, (self.3).3))
+        }
     }
 
     impl<T: Float> Default for Mat4<T> {
@@ -1418,6 +1755,17 @@ pub mod mat {
         }
     }
 
+    impl<T: Float> From<Mat3<T>> for Mat4<T> {
+        fn from(m: Mat3<T>) -> Self {
+            Self(
+                Vec4::from((m.0, T::val(0.0))),
+                Vec4::from((m.1, T::val(0.0))),
+                Vec4::from((m.2, T::val(0.0))),
+                Vec4(T::val(0.0), T::val(0.0), T::val(0.0), T::val(1.0))
+            )
+        }
+    }
+
     impl<T: Copy> From<[T; 16]> for Mat4<T> {
         fn from(v: [T; 16]) -> Self {
             Self(
@@ -1488,128 +1836,905 @@ pub mod mat {
 
     mat_impl!(Mat4, Vec4; v0: 0, v1: 1, v2: 2, v3: 3);
 
-    pub type Mat2f32 = Mat2<f32>;
-    pub type Mat2f64 = Mat2<f32>;
-    pub type Mat3f32 = Mat2<f32>;
-    pub type Mat3f64 = Mat2<f32>;
-    pub type Mat4f32 = Mat2<f32>;
-    pub type Mat4f64 = Mat2<f32>;
-}
+    /// If the determinant is neither zero, infinite nor NaN.
+    fn invertible<T: Float>(det: T) -> bool {
+        det.abs() > T::val(0.0) && det.abs() < T::INF
+    }
 
-pub mod curves2d {
-    use super::*;
+    /// LU decomposition with partial pivoting `P * A = L * U`, `P` is a permutation matrix, `L`
+    /// is lower triangular with a unit diagonal and `U` is upper triangular.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Lu<M> {
+        pub p: M,
+        pub l: M,
+        pub u: M,
+        /// If `P` swaps an odd number of rows.
+        odd:   bool
+    }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Bezier1(pub Vec2<f32>, pub Vec2<f32>);
+    /// QR decomposition `A = Q * R` with Householder reflections, `Q` is orthogonal and `R` is
+    /// upper triangular with a non-negative diagonal.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Qr<M> {
+        pub q: M,
+        pub r: M
+    }
 
-    impl Bezier1 {
-        /// https://en.wikipedia.org/wiki/Line%E2%80%93line_intersection#Given_two_points_on_each_line
-		/// https://stackoverflow.com/questions/563198
-        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
-            Bezier2(self.0, self.0 + (self.1 - self.0) * 0.5, self.1).intersections(l0, l1)
-        }
+    macro_rules! mat_decomp_impl {
+        ( $name:ident, $vec:ident, $n:literal ) => {
+            impl<T: Copy> $name<T> {
+                fn rows(&self) -> [[T; $n]; $n] {
+                    to_rows(self)
+                }
+            }
 
-        pub fn distance(self, p: Vec2<f32>) -> f32 {
-            let Self(p0, p1) = self;
-            let d = p1 - p0;
-            p.distance(p0 + d * (d.dot(p - p0) / d.dot(d)).clamp(0f32, 1f32))
-        }
-    }
+            impl<T: Float> $name<T> {
+                /// Returns `None` if the matrix is singular.
+                pub fn lu(&self) -> Option<Lu<Self>> {
+                    let (a, perm, odd) = lu(self.rows())?;
+                    let (zero, one) = (T::val(0.0), T::val(1.0));
+                    Some(Lu {
+                        p: from_rows(core::array::from_fn(|i| core::array::from_fn(|j| if perm[i] == j { one } else { zero }))),
+                        l: from_rows(core::array::from_fn(|i| core::array::from_fn(|j| match i.cmp(&j) {
+                            core::cmp::Ordering::Greater => a[i][j],
+                            core::cmp::Ordering::Equal   => one,
+                            core::cmp::Ordering::Less    => zero
+                        }))),
+                        u: from_rows(core::array::from_fn(|i| core::array::from_fn(|j| if i <= j { a[i][j] } else { zero }))),
+                        odd
+                    })
+                }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Bezier2(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+                pub fn qr(&self) -> Qr<Self> {
+                    let (q, r) = qr(self.rows());
+                    Qr { q: from_rows(q), r: from_rows(r) }
+                }
+            }
 
-    impl Bezier2 {
-        pub fn b(self, t: f32) -> Vec2<f32> {
-            debug_assert!((0.0..=1.0).contains(&t));
-            let Bezier2(p0, p1, p2) = self;
-            let tinv = 1.0 - t;
-            p0 * (tinv * tinv)
-                + p1 * 2.0 * t * tinv
-                + p2 * (t * t)
-        }
+            impl<T: Float> Lu<$name<T>> {
+                pub fn det(&self) -> T {
+                    let det = (0..$n).fold(T::val(1.0), |det, i| det * self.u[i][i]);
+                    if self.odd { -det } else { det }
+                }
 
-        /// https://github.com/w8r/bezier-intersect/blob/master/src/quadratic.js
-        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
-            let Bezier2(p0, p1, p2) = self;
+                /// Solves `A * x = b`.
+                pub fn solve(&self, b: $vec<T>) -> $vec<T> {
+                    let x = forward(&self.l.rows(), to_array(&self.p.transform(b)));
+                    $vec::from(backward(&self.u.rows(), x))
+                }
 
-            // Convert line to normal form: ax + by + c = 0
-            let n = Vec2(l0.1 - l1.1, l1.0 - l0.0); // Find normal to line: negative inverse of original line's slope
-            let cl = l0.0 * l1.1 - l1.0 * l0.1;      // Determine new c coefficient
+                pub fn inverse(&self) -> $name<T> {
+                    let mut inv = $name::default();
+                    for i in 0..$n {
+                        inv[i] = self.solve(inv[i]);
+                    }
+                    inv
+                }
+            }
 
-            let min = l0.0.min(l1.0);
-            let max = l0.0.max(l1.0);
+            impl<T: Float> Qr<$name<T>> {
+                /// Solves `A * x = b`, returns `None` if `A` is singular.
+                pub fn solve(&self, b: $vec<T>) -> Option<$vec<T>> {
+                    if (0..$n).any(|i| self.r[i][i] == T::val(0.0)) {
+                        return None;
+                    }
 
-            // solve quadratic equation
-            Polynomial([
-                n.dot(p0 + p1 * -2.0 + p2),
-                n.dot(p0 * -2.0 + p1 * 2.0),
-                n.dot(p0) + cl
-            ]).roots_iter()
-                .filter(|t| *t >= 0.0 && *t <= 1.0) // check if point is on bezier
-                .map(|t| self.b(t).0)                       // calculate point on bezier
-                .filter(|b| *b >= min && *b <= max) // check if point is on line segment
-                .count()                                           // count intersections
-        }
+                    let x = to_array(&self.q.transpose().transform(b));
+                    Some($vec::from(backward(&self.r.rows(), x)))
+                }
+            }
+        };
+    }
 
-        /// http://blog.gludion.com/2009/08/distance-to-quadratic-bezier-curve.html
-		/// alternative: https://www.academia.edu/34616746/An_algorithm_for_computing_the_shortest_distance_between_a_point_and_quadratic_Bezier_curve
-        pub fn distance2(self, p: Vec2<f32>) -> f32 {
-            let Bezier2(p0, p1, p2) = self;
+    mat_decomp_impl!(Mat2, Vec2, 2);
+    mat_decomp_impl!(Mat3, Vec3, 3);
+    mat_decomp_impl!(Mat4, Vec4, 4);
 
-            let a = p1 - p0;
-            let b = p2 - p1 - a;
-            let c = p0 - p;
+    // The decompositions work on arrays of rows, the matrices store columns.
 
-            Polynomial([
-                b.dot(b),
-                3.0 * a.dot(b),
-                2.0 * a.dot(a) + c.dot(b),
-                c.dot(a)
-            ]).roots_iter()
-                .filter(|t| *t >= 0.0 && *t <= 1.0)
-                .map(|t| p.distance2(self.b(t)))
-                .fold(p.distance2(p0), f32::min)
-                .min(p.distance2(p2))
-        }
+    fn to_rows<T: Copy, M: Index<usize, Output = V>, V: Index<usize, Output = T>, const N: usize>(m: &M) -> [[T; N]; N] {
+        core::array::from_fn(|i| core::array::from_fn(|j| m[j][i]))
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Bezier3(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+    fn from_rows<T: Copy, M: From<[[T; N]; N]>, const N: usize>(rows: [[T; N]; N]) -> M {
+        M::from(core::array::from_fn(|i| core::array::from_fn(|j| rows[j][i])))
+    }
 
-    impl From<Bezier2> for Bezier3 {
-        /// Based on https://en.wikipedia.org/wiki/B%C3%A9zier_curve#Properties
-        fn from(Bezier2(p0, p1, p2): Bezier2) -> Self {
-            const FRAC_2_3: f32 = 2.0 / 3.0;
-            Self(
-                p0,
-                p0 * (1f32 - FRAC_2_3) + p1 * FRAC_2_3,
-                p2 * (1f32 - FRAC_2_3) + p1 * FRAC_2_3,
-                p2
-            )
-        }
+    fn to_array<T: Copy, V: Index<usize, Output = T>, const N: usize>(v: &V) -> [T; N] {
+        core::array::from_fn(|i| v[i])
     }
 
-    impl Bezier3 {
-        pub fn b(self, t: f32) -> Vec2<f32> {
-            debug_assert!((0.0..=1.0).contains(&t));
-            let Self(p0, p1, p2, p3) = self;
-            let t2 = t * t;
-            let t3 = t * t2;
-            let tinv = 1.0 - t;
-            let tinv2 = tinv * tinv;
-            let tinv3 = tinv * tinv2;
-            p0 * tinv3
-                + p1 * 3.0 * tinv2 * t
-                + p2 * 3.0 * tinv2 * t2
-                + p3 * t3
+    /// Returns `L` and `U` in one matrix without the unit diagonal of `L`, the source row of each
+    /// row and if the number of swaps is odd, or `None` if the matrix is singular.
+    fn lu<T: Float, const N: usize>(mut a: [[T; N]; N]) -> Option<([[T; N]; N], [usize; N], bool)> {
+        let mut perm = core::array::from_fn(|i| i);
+        let mut odd = false;
+        for k in 0..N {
+            let p = (k..N).fold(k, |p, i| if a[i][k].abs() > a[p][k].abs() { i } else { p });
+            if a[p][k].abs().partial_cmp(&T::val(0.0)) != Some(core::cmp::Ordering::Greater) {
+                return None;
+            }
+
+            if p != k {
+                a.swap(p, k);
+                perm.swap(p, k);
+                odd = !odd;
+            }
+
+            let pivot = a[k];
+            for row in a[k + 1..].iter_mut() {
+                let f = row[k] / pivot[k];
+                row[k] = f;
+                for j in k + 1..N {
+                    row[j] -= f * pivot[j];
+                }
+            }
         }
 
-        /// https://github.com/w8r/bezier-intersect/blob/master/src/cubic.js
-        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
-            let Self(p0, p1, p2, p3) = self;
+        Some((a, perm, odd))
+    }
 
-            // Convert line to normal form: ax + by + c = 0
+    fn qr<T: Float, const N: usize>(mut r: [[T; N]; N]) -> ([[T; N]; N], [[T; N]; N]) {
+        let zero = T::val(0.0);
+        let mut q: [[T; N]; N] = core::array::from_fn(|i| core::array::from_fn(|j| if i == j { T::val(1.0) } else { zero }));
+
+        for k in 0..N.saturating_sub(1) {
+            // the reflection that maps the column below the diagonal onto the axis, the sign of
+            // alpha avoids cancellation
+            let norm = r[k..].iter().fold(zero, |acc, row| acc + row[k] * row[k]).sqrt();
+            let alpha = if r[k][k] > zero { -norm } else { norm };
+            let mut v = [zero; N];
+            for (v, row) in v[k..].iter_mut().zip(&r[k..]) {
+                *v = row[k];
+            }
+            v[k] -= alpha;
+
+            let vv = v.iter().fold(zero, |acc, &v| acc + v * v);
+            if vv == zero {
+                continue;
+            }
+
+            let scale = T::val(2.0) / vv;
+            for j in 0..N {
+                let s = (k..N).fold(zero, |acc, i| acc + v[i] * r[i][j]) * scale;
+                for (row, &v) in r[k..].iter_mut().zip(&v[k..]) {
+                    row[j] -= s * v;
+                }
+            }
+
+            // exact by construction, without the rounding errors
+            r[k][k] = alpha;
+            for row in r[k + 1..].iter_mut() {
+                row[k] = zero;
+            }
+
+            for row in q.iter_mut() {
+                let s = (k..N).fold(zero, |acc, j| acc + row[j] * v[j]) * scale;
+                for (e, &v) in row[k..].iter_mut().zip(&v[k..]) {
+                    *e -= s * v;
+                }
+            }
+        }
+
+        for k in 0..N {
+            if r[k][k] < zero {
+                r[k] = r[k].map(|v| -v);
+                for row in q.iter_mut() {
+                    row[k] = -row[k];
+                }
+            }
+        }
+
+        (q, r)
+    }
+
+    /// Forward substitution with a lower triangular matrix with a unit diagonal.
+    fn forward<T: Float, const N: usize>(l: &[[T; N]; N], mut x: [T; N]) -> [T; N] {
+        for i in 0..N {
+            for j in 0..i {
+                x[i] -= l[i][j] * x[j];
+            }
+        }
+        x
+    }
+
+    /// Back substitution with an upper triangular matrix.
+    fn backward<T: Float, const N: usize>(u: &[[T; N]; N], mut x: [T; N]) -> [T; N] {
+        for i in (0..N).rev() {
+            for j in i + 1..N {
+                x[i] -= u[i][j] * x[j];
+            }
+            x[i] /= u[i][i];
+        }
+        x
+    }
+
+    pub type Mat2f32 = Mat2<f32>;
+    pub type Mat2f64 = Mat2<f64>;
+    pub type Mat3f32 = Mat3<f32>;
+    pub type Mat3f64 = Mat3<f64>;
+    pub type Mat4f32 = Mat4<f32>;
+    pub type Mat4f64 = Mat4<f64>;
+
+    #[cfg(test)]
+    mod tests {
+        use {super::*, rand::{Rng, SeedableRng, rngs::StdRng}};
+
+        const ITERATIONS: usize = 10_000;
+
+        fn random<const N: usize>(rng: &mut StdRng) -> [[f32; N]; N] {
+            core::array::from_fn(|_| core::array::from_fn(|_| rng.gen_range(-10f32..10f32)))
+        }
+
+        fn random_transform(rng: &mut StdRng) -> Mat4<f32> {
+            let axis = Vec3(rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32));
+            let scale = |rng: &mut StdRng| rng.gen_range(0.1f32..10f32) * if rng.gen() { 1f32 } else { -1f32 };
+            Mat4::from_transform(
+                Vec3(rng.gen_range(-100f32..100f32), rng.gen_range(-100f32..100f32), rng.gen_range(-100f32..100f32)),
+                Quat32::from_axis_angle(axis, rng.gen_range(-4f32..4f32)),
+                Vec3(scale(rng), scale(rng), scale(rng))
+            )
+        }
+
+        fn norm<const N: usize>(m: &[[f64; N]; N]) -> f64 {
+            m.iter().map(|row| row.iter().map(|v| v.abs()).sum::<f64>()).fold(0f64, f64::max)
+        }
+
+        /// The largest difference relative to the largest element of the reference.
+        fn error<const N: usize>(m: &[[f64; N]; N], reference: &[[f64; N]; N]) -> f64 {
+            let max = |f: &dyn Fn(usize, usize) -> f64| (0..N * N).map(|i| f(i / N, i % N)).fold(0f64, f64::max);
+            max(&|i, j| (m[i][j] - reference[i][j]).abs()) / max(&|i, j| reference[i][j].abs())
+        }
+
+        macro_rules! decomp_test {
+            ( $fn:ident, $name:ident, $vec:ident, $n:literal ) => {
+                #[test]
+                fn $fn() {
+                    let mut rng = StdRng::seed_from_u64($n);
+                    let identity = $name::<f64>::default().rows();
+                    for _ in 0..ITERATIONS {
+                        let m = from_rows::<f32, $name<f32>, $n>(random(&mut rng));
+                        let m64 = m.map(f64::from);
+                        let lu64 = m64.lu().unwrap();
+                        let reference = lu64.inverse();
+                        let cond = norm(&m64.rows()) * norm(&reference.rows());
+                        let tolerance = 64f64 * f32::EPSILON as f64 * cond;
+
+                        // the reference itself
+                        assert!(error(&(m64 * reference).rows(), &identity) < 1e-9 * cond, "{:?}", m);
+
+                        let inv = m.inverse().unwrap();
+                        assert!(error(&inv.map(f64::from).rows(), &reference.rows()) < tolerance, "{:?}", m);
+
+                        let lu = m.lu().unwrap();
+                        assert!(error(&(lu.p * m).map(f64::from).rows(), &(lu.l * lu.u).map(f64::from).rows()) < 16f64 * f32::EPSILON as f64, "{:?}", m);
+                        assert!(error(&(lu.inverse()).map(f64::from).rows(), &reference.rows()) < tolerance, "{:?}", m);
+                        assert!((lu.det() as f64 - lu64.det()).abs() <= tolerance * lu64.det().abs(), "{:?}", m);
+
+                        let qr = m.qr();
+                        assert!(error(&(qr.q.transpose() * qr.q).map(f64::from).rows(), &identity) < 16f64 * f32::EPSILON as f64, "{:?}", m);
+                        assert!(error(&(qr.q * qr.r).map(f64::from).rows(), &m.map(f64::from).rows()) < 16f64 * f32::EPSILON as f64, "{:?}", m);
+                        for i in 0..$n {
+                            assert!(qr.r[i][i] >= 0f32);
+                            for j in 0..i {
+                                assert_eq!(qr.r[j][i], 0f32);
+                            }
+                        }
+
+                        let b = $vec::from(core::array::from_fn::<f32, $n, _>(|_| rng.gen_range(-10f32..10f32)));
+                        let x64 = lu64.solve(b.map(f64::from));
+                        let x_max = to_array::<f64, _, $n>(&x64).iter().fold(0f64, |max, v| max.max(v.abs()));
+                        for x in [lu.solve(b), qr.solve(b).unwrap()] {
+                            let x = to_array::<f32, _, $n>(&x);
+                            let x64 = to_array::<f64, _, $n>(&x64);
+                            for i in 0..$n {
+                                assert!((x[i] as f64 - x64[i]).abs() <= tolerance * x_max, "{:?}", m);
+                            }
+                        }
+                    }
+                }
+            };
+        }
+
+        decomp_test!(mat2, Mat2, Vec2, 2);
+        decomp_test!(mat3, Mat3, Vec3, 3);
+        decomp_test!(mat4, Mat4, Vec4, 4);
+
+        #[test]
+        fn singular() {
+            let m = Mat3::from([1f32, 2f32, 3f32, 2f32, 4f32, 6f32, 0f32, 1f32, 0f32]);
+            assert_eq!(m.inverse(), None);
+            assert_eq!(m.lu(), None);
+            assert_eq!(m.qr().solve(Vec3(1f32, 0f32, 0f32)), None);
+            assert_eq!(Mat2::from([1f32, 2f32, 2f32, 4f32]).inverse(), None);
+            assert_eq!(Mat4::from_scale(Vec3(1f32, 0f32, 1f32)).inverse(), None);
+            assert_eq!(Mat4::from_scale(Vec3(1f32, 0f32, 1f32)).inverse_affine(), None);
+            assert_eq!(Mat3::from_scale(Vec3(f32::NAN, 1f32, 1f32)).inverse(), None);
+        }
+
+        #[test]
+        fn affine() {
+            let mut rng = StdRng::seed_from_u64(5);
+            let identity = Mat4::<f64>::default().rows();
+            for _ in 0..ITERATIONS {
+                let m = random_transform(&mut rng);
+                let m64 = m.map(f64::from);
+                let reference = (m64.inverse().unwrap()).rows();
+                let cond = norm(&m64.rows()) * norm(&reference);
+                let tolerance = 64f64 * f32::EPSILON as f64 * cond;
+
+                let inv = m.inverse_affine().unwrap();
+                assert!(error(&inv.map(f64::from).rows(), &reference) < tolerance, "{:?}", m);
+                assert!(error(&(m * inv).map(f64::from).rows(), &identity) < tolerance, "{:?}", m);
+                assert!(error(&(m.inverse().unwrap()).map(f64::from).rows(), &reference) < tolerance, "{:?}", m);
+
+                // normals stay perpendicular to the transformed tangents
+                let normal_matrix = m.normal_matrix().unwrap();
+                let normal = Vec3(rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32));
+                let tangent = normal.cross(Vec3(rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32)));
+                let (n, t) = (normal_matrix.transform(normal), m.transform_dir(tangent));
+                assert!(n.dot(t).abs() as f64 <= tolerance * (n.len() * t.len()) as f64, "{:?}", m);
+
+                let (t, r, s) = m.decompose().unwrap();
+                assert!((r.len() - 1f32).abs() < 1e-6);
+                assert!(error(&(Mat4::from_transform(t, r, s)).map(f64::from).rows(), &m.map(f64::from).rows()) < 1e-5, "{:?}", m);
+            }
+        }
+
+        #[test]
+        fn polar() {
+            let mut rng = StdRng::seed_from_u64(6);
+            let identity = Mat3::<f64>::default().rows();
+            for _ in 0..ITERATIONS {
+                let a = from_rows::<f32, Mat3<f32>, 3>(random(&mut rng));
+                let a64 = a.map(f64::from);
+                let cond = norm(&a64.rows()) * norm(&(a64.inverse().unwrap()).rows());
+                let tolerance = 64f64 * f32::EPSILON as f64 * cond;
+
+                let (r, s) = a.polar().unwrap();
+                assert!(error(&(r.transpose() * r).map(f64::from).rows(), &identity) < 16f64 * f32::EPSILON as f64, "{:?}", a);
+                assert_eq!(s, s.transpose());
+                assert_eq!(r.det() < 0f32, a.det() < 0f32);
+                assert!(error(&(r * s).map(f64::from).rows(), &a.map(f64::from).rows()) < tolerance, "{:?}", a);
+
+                let x = Vec3(rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32), rng.gen_range(-1f32..1f32));
+                assert!(x.dot(s.transform(x)) as f64 >= -tolerance * norm(&s.map(f64::from).rows()), "{:?}", a);
+
+                // the f64 polar decomposition as reference
+                let (r64, s64) = a64.polar().unwrap();
+                assert!(error(&r.map(f64::from).rows(), &r64.rows()) < tolerance, "{:?}", a);
+                assert!(error(&s.map(f64::from).rows(), &s64.rows()) < tolerance, "{:?}", a);
+            }
+        }
+
+        #[test]
+        fn look_at() {
+            let (eye, target) = (Vec3(1f32, 2f32, 3f32), Vec3(4f32, 6f32, -9f32));
+            let view = Mat4::look_at(eye, target, Vec3(0f32, 1f32, 0f32));
+            assert!(view.transform_pos(eye).len() < 1e-5);
+            assert!((view.transform_pos(target) - Vec3(0f32, 0f32, -13f32)).len() < 1e-5);
+            assert!(view.transform_dir(Vec3(0f32, 1f32, 0f32)).1 > 0f32);
+            assert!(view.transform_dir(Vec3(1f32, 0f32, 0f32)).0 > 0f32);
+            assert!(error(&(view.inverse_affine().unwrap()).map(f64::from).rows(), &(view.inverse().unwrap()).map(f64::from).rows()) < 1e-6);
+        }
+
+        #[test]
+        fn projections() {
+            let depth = |m: Mat4<f64>, z: f64| {
+                let v = m.transform(Vec4(1f64, 1f64, z, 1f64));
+                v.2 / v.3
+            };
+
+            let (fov, aspect, near, far) = (1.2f64, 1.5f64, 0.1f64, 1000f64);
+            let m = Mat4::from_perspective_reversed_z(fov, aspect, near, far);
+            assert!((depth(m, -near) - 1f64).abs() < 1e-12);
+            assert!(depth(m, -far).abs() < 1e-12);
+            assert!(depth(m, -1f64) > depth(m, -2f64));
+
+            let m = Mat4::from_perspective_infinite_reversed_z(fov, aspect, near);
+            assert!((depth(m, -near) - 1f64).abs() < 1e-12);
+            assert!(depth(m, -1e12) < 1e-12 && depth(m, -1e12) > 0f64);
+
+            let m = Mat4::from_perspective_infinite(fov, aspect, near, true);
+            assert!(depth(m, -near).abs() < 1e-12);
+            assert!((depth(m, -1e12) - 1f64).abs() < 1e-12);
+
+            let m = Mat4::from_perspective_infinite(fov, aspect, near, false);
+            assert!((depth(m, -near) + 1f64).abs() < 1e-12);
+            assert!((depth(m, -1e12) - 1f64).abs() < 1e-12);
+
+            // the same x and y as the finite projection
+            let finite = Mat4::from_perspective(fov, aspect, near, far, true);
+            assert_eq!((m.0, m.1), (finite.0, finite.1));
+        }
+    }
+}
+
+/// SIMD implementations of the hot paths of `Vec4<f32>`, `Quat32` and `Mat4<f32>`, as inherent
+/// methods with a `_simd` suffix, the generic methods are always scalar. The operations are done
+/// in the same order as in the scalar code without fused multiply-adds, so the results are
+/// bit-identical. Uses SSE2 on x86_64, AVX for batches if it is enabled at compile time, NEON on
+/// aarch64 and scalar code on all other targets.
+#[cfg(feature = "simd")]
+#[allow(unused_unsafe)]
+pub mod simd {
+    use super::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+    #[cfg(target_arch = "aarch64")]
+    use core::arch::aarch64::*;
+
+    #[cfg(target_arch = "x86_64")]
+    type Repr = __m128;
+    #[cfg(target_arch = "aarch64")]
+    type Repr = float32x4_t;
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    type Repr = [f32; 4];
+
+    /// Four `f32` lanes in a SIMD register.
+    #[allow(non_camel_case_types)]
+    #[derive(Copy, Clone)]
+    pub struct f32x4(Repr);
+
+    impl f32x4 {
+        #[inline(always)]
+        pub fn new(v0: f32, v1: f32, v2: f32, v3: f32) -> Self {
+            Self::from_array([v0, v1, v2, v3])
+        }
+
+        #[inline(always)]
+        pub fn splat(v: f32) -> Self {
+            #[cfg(target_arch = "x86_64")]
+            return Self(unsafe { _mm_set1_ps(v) });
+            #[cfg(target_arch = "aarch64")]
+            return Self(unsafe { vdupq_n_f32(v) });
+            #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+            return Self([v; 4]);
+        }
+
+        #[inline(always)]
+        pub fn from_array(v: [f32; 4]) -> Self {
+            #[cfg(target_arch = "x86_64")]
+            return Self(unsafe { _mm_loadu_ps(v.as_ptr()) });
+            #[cfg(target_arch = "aarch64")]
+            return Self(unsafe { vld1q_f32(v.as_ptr()) });
+            #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+            return Self(v);
+        }
+
+        #[inline(always)]
+        pub fn to_array(self) -> [f32; 4] {
+            #[cfg(target_arch = "x86_64")]
+            {
+                let mut v = [0f32; 4];
+                unsafe { _mm_storeu_ps(v.as_mut_ptr(), self.0) };
+                v
+            }
+            #[cfg(target_arch = "aarch64")]
+            {
+                let mut v = [0f32; 4];
+                unsafe { vst1q_f32(v.as_mut_ptr(), self.0) };
+                v
+            }
+            #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+            self.0
+        }
+
+        /// Interleaves the lower halves, `[a0, b0, a1, b1]`.
+        #[inline(always)]
+        pub fn zip_lo(self, other: Self) -> Self {
+            #[cfg(target_arch = "x86_64")]
+            return Self(unsafe { _mm_unpacklo_ps(self.0, other.0) });
+            #[cfg(target_arch = "aarch64")]
+            return Self(unsafe { vzip1q_f32(self.0, other.0) });
+            #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+            return Self([self.0[0], other.0[0], self.0[1], other.0[1]]);
+        }
+
+        /// Interleaves the upper halves, `[a2, b2, a3, b3]`.
+        #[inline(always)]
+        pub fn zip_hi(self, other: Self) -> Self {
+            #[cfg(target_arch = "x86_64")]
+            return Self(unsafe { _mm_unpackhi_ps(self.0, other.0) });
+            #[cfg(target_arch = "aarch64")]
+            return Self(unsafe { vzip2q_f32(self.0, other.0) });
+            #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+            return Self([self.0[2], other.0[2], self.0[3], other.0[3]]);
+        }
+
+        /// Adds the lanes in order to `0`, like the scalar dot product.
+        #[inline(always)]
+        pub fn sum(self) -> f32 {
+            let [v0, v1, v2, v3] = self.to_array();
+            f32::default() + v0 + v1 + v2 + v3
+        }
+    }
+
+    impl core::fmt::Debug for f32x4 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_tuple("f32x4").field(&self.to_array()).finish()
+        }
+    }
+
+    impl From<Vec4<f32>> for f32x4 {
+        #[inline(always)]
+        fn from(v: Vec4<f32>) -> Self {
+            Self::new(v.0, v.1, v.2, v.3)
+        }
+    }
+
+    impl From<f32x4> for Vec4<f32> {
+        #[inline(always)]
+        fn from(v: f32x4) -> Self {
+            Vec4::from(v.to_array())
+        }
+    }
+
+    macro_rules! f32x4_op {
+        ( $trait:ident, $fn:ident, $sse:ident, $neon:ident, $op:tt ) => {
+            impl $trait for f32x4 {
+                type Output = Self;
+
+                #[inline(always)]
+                fn $fn(self, rhs: Self) -> Self {
+                    #[cfg(target_arch = "x86_64")]
+                    return Self(unsafe { $sse(self.0, rhs.0) });
+                    #[cfg(target_arch = "aarch64")]
+                    return Self(unsafe { $neon(self.0, rhs.0) });
+                    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+                    return Self([
+                        self.0[0] $op rhs.0[0],
+                        self.0[1] $op rhs.0[1],
+                        self.0[2] $op rhs.0[2],
+                        self.0[3] $op rhs.0[3]
+                    ]);
+                }
+            }
+        };
+    }
+
+    f32x4_op!(Add, add, _mm_add_ps, vaddq_f32, +);
+    f32x4_op!(Sub, sub, _mm_sub_ps, vsubq_f32, -);
+    f32x4_op!(Mul, mul, _mm_mul_ps, vmulq_f32, *);
+    f32x4_op!(Div, div, _mm_div_ps, vdivq_f32, /);
+
+    #[inline(always)]
+    fn columns(m: &Mat4<f32>) -> [f32x4; 4] {
+        [m.0.into(), m.1.into(), m.2.into(), m.3.into()]
+    }
+
+    impl Vec4<f32> {
+        /// The same as [`Vec4::dot`].
+        pub fn dot_simd(self, other: Self) -> f32 {
+            (f32x4::from(self) * f32x4::from(other)).sum()
+        }
+
+        /// The same as [`Quat::mul_quat`].
+        pub fn mul_quat_simd(self, other: Self) -> Self {
+            (f32x4::splat(self.3) * f32x4::new(other.0, other.1, other.2, other.3)
+                + f32x4::splat(self.0) * f32x4::new(other.3, -other.2, other.1, -other.0)
+                + f32x4::splat(self.1) * f32x4::new(other.2, other.3, -other.0, -other.1)
+                + f32x4::splat(self.2) * f32x4::new(-other.1, other.0, other.3, -other.2)).into()
+        }
+    }
+
+    impl Mat4<f32> {
+        /// The same as `self * rhs`.
+        pub fn mul_simd(&self, rhs: &Self) -> Self {
+            let [c0, c1, c2, c3] = columns(self);
+            let col = |v: Vec4<f32>| (c0 * f32x4::splat(v.0) + c1 * f32x4::splat(v.1)
+                + c2 * f32x4::splat(v.2) + c3 * f32x4::splat(v.3)).into();
+            Mat4(col(rhs.0), col(rhs.1), col(rhs.2), col(rhs.3))
+        }
+
+        /// The same as [`Mat4::transpose`].
+        pub fn transpose_simd(self) -> Self {
+            let [c0, c1, c2, c3] = columns(&self);
+            let (t0, t1) = (c0.zip_lo(c2), c0.zip_hi(c2));
+            let (t2, t3) = (c1.zip_lo(c3), c1.zip_hi(c3));
+            Mat4(t0.zip_lo(t2).into(), t0.zip_hi(t2).into(), t1.zip_lo(t3).into(), t1.zip_hi(t3).into())
+        }
+
+        /// The same as `!self`, computed from the same 2x2 minors and cofactors.
+        pub fn inverse_simd(&self) -> Self {
+            let (s0, s1, s2, s3) = (self.0, self.1, self.2, self.3);
+            let v = f32x4::new;
+            let [a, b, c, d] = (v(s0.0, s0.0, s0.0, s0.1) * v(s1.1, s1.2, s1.3, s1.2)
+                - v(s0.1, s0.2, s0.3, s0.2) * v(s1.0, s1.0, s1.0, s1.1)).to_array();
+            let [e, f, g, h] = (v(s0.1, s0.2, s2.0, s2.0) * v(s1.3, s1.3, s3.1, s3.2)
+                - v(s0.3, s0.3, s2.1, s2.2) * v(s1.1, s1.2, s3.0, s3.0)).to_array();
+            let [i, j, k, l] = (v(s2.0, s2.1, s2.1, s2.2) * v(s3.3, s3.2, s3.3, s3.3)
+                - v(s2.3, s2.2, s2.3, s2.3) * v(s3.0, s3.1, s3.1, s3.2)).to_array();
+            let det = f32x4::splat(a * l - b * k + c * j + d * i - e * h + f * g);
+
+            // the rows of the cofactors, negated for the odd lanes of the even columns and the even
+            // lanes of the odd columns before the multiplication, like in the scalar code
+            let (even, odd) = (v(1.0, -1.0, 1.0, -1.0), v(-1.0, 1.0, -1.0, 1.0));
+            let r0 = v(s1.0, s0.0, s3.0, s2.0);
+            let r1 = v(s1.1, s0.1, s3.1, s2.1);
+            let r2 = v(s1.2, s0.2, s3.2, s2.2);
+            let r3 = v(s1.3, s0.3, s3.3, s2.3);
+            let col = |x: f32x4, y: f32x4, z: f32x4| ((x - y + z) / det).into();
+
+            Mat4(
+                col(r1 * even * v(l, l, f, f), r2 * even * v(k, k, e, e), r3 * even * v(j, j, d, d)),
+                col(r0 * odd * v(l, l, f, f), r2 * odd * v(i, i, c, c), r3 * odd * v(h, h, b, b)),
+                col(r0 * even * v(k, k, e, e), r1 * even * v(i, i, c, c), r3 * even * v(g, g, a, a)),
+                col(r0 * odd * v(j, j, d, d), r1 * odd * v(h, h, b, b), r2 * odd * v(g, g, a, a))
+            )
+        }
+
+        /// The same as [`Mat4::transform`].
+        pub fn transform_simd(&self, vec: Vec4<f32>) -> Vec4<f32> {
+            let [c0, c1, c2, c3] = columns(self);
+            (c0 * f32x4::splat(vec.0) + c1 * f32x4::splat(vec.1) + c2 * f32x4::splat(vec.2) + c3 * f32x4::splat(vec.3)).into()
+        }
+
+        /// The same as [`Mat4::transform_pos`].
+        pub fn transform_pos_simd(&self, vec: Vec3<f32>) -> Vec3<f32> {
+            let [c0, c1, c2, c3] = columns(self);
+            let [x, y, z, _] = (c0 * f32x4::splat(vec.0) + c1 * f32x4::splat(vec.1) + c2 * f32x4::splat(vec.2) + c3).to_array();
+            Vec3(x, y, z)
+        }
+
+        /// The same as [`Mat4::transform_pos_batch`], two positions at a time if AVX is enabled.
+        pub fn transform_pos_batch_simd(&self, src: &[Vec3<f32>], dst: &mut [Vec3<f32>]) {
+            assert_eq!(src.len(), dst.len(), "source and destination have different lengths");
+
+            #[cfg(all(target_arch = "x86_64", target_feature = "avx"))]
+            let (src, dst) = unsafe {
+                let dup = |c: Vec4<f32>| {
+                    let c = f32x4::from(c).0;
+                    _mm256_set_m128(c, c)
+                };
+                let (c0, c1, c2, c3) = (dup(self.0), dup(self.1), dup(self.2), dup(self.3));
+                let split = |a: f32, b: f32| _mm256_set_m128(_mm_set1_ps(b), _mm_set1_ps(a));
+
+                let len = src.len() / 2 * 2;
+                for (src, dst) in src[..len].chunks_exact(2).zip(dst[..len].chunks_exact_mut(2)) {
+                    let r = _mm256_add_ps(_mm256_add_ps(_mm256_add_ps(
+                        _mm256_mul_ps(c0, split(src[0].0, src[1].0)),
+                        _mm256_mul_ps(c1, split(src[0].1, src[1].1))),
+                        _mm256_mul_ps(c2, split(src[0].2, src[1].2))),
+                        c3);
+                    let mut out = [0f32; 8];
+                    _mm256_storeu_ps(out.as_mut_ptr(), r);
+                    dst[0] = Vec3(out[0], out[1], out[2]);
+                    dst[1] = Vec3(out[4], out[5], out[6]);
+                }
+
+                (&src[len..], &mut dst[len..])
+            };
+
+            let [c0, c1, c2, c3] = columns(self);
+            for (src, dst) in src.iter().zip(dst) {
+                let [x, y, z, _] = (c0 * f32x4::splat(src.0) + c1 * f32x4::splat(src.1) + c2 * f32x4::splat(src.2) + c3).to_array();
+                *dst = Vec3(x, y, z);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use {super::*, rand::{Rng, SeedableRng, rngs::StdRng}};
+
+        const ITERATIONS: usize = 100_000;
+
+        /// Random values of all magnitudes, including zeros, subnormals and large values.
+        fn value(rng: &mut StdRng) -> f32 {
+            match rng.gen_range(0..8) {
+                0 => 0f32,
+                1 => -0f32,
+                2 => f32::from_bits(rng.gen_range(1..0x0080_0000)) * if rng.gen() { 1f32 } else { -1f32 },
+                3 => f32::from_bits(rng.gen_range(0..0x7F80_0000)) * if rng.gen() { 1f32 } else { -1f32 },
+                _ => rng.gen_range(-100f32..100f32)
+            }
+        }
+
+        fn vec4(rng: &mut StdRng) -> Vec4<f32> {
+            Vec4(value(rng), value(rng), value(rng), value(rng))
+        }
+
+        fn mat4(rng: &mut StdRng) -> Mat4<f32> {
+            Mat4(vec4(rng), vec4(rng), vec4(rng), vec4(rng))
+        }
+
+        fn bits<const N: usize>(v: [f32; N]) -> [u32; N] {
+            // all NaNs are equal
+            v.map(|v| if v.is_nan() { u32::MAX } else { v.to_bits() })
+        }
+
+        fn vec3_bits(v: Vec3<f32>) -> [u32; 3] {
+            bits([v.0, v.1, v.2])
+        }
+
+        fn vec4_bits(v: Vec4<f32>) -> [u32; 4] {
+            bits([v.0, v.1, v.2, v.3])
+        }
+
+        fn mat_bits(m: Mat4<f32>) -> [u32; 16] {
+            bits([m.0.0, m.0.1, m.0.2, m.0.3, m.1.0, m.1.1, m.1.2, m.1.3,
+                  m.2.0, m.2.1, m.2.2, m.2.3, m.3.0, m.3.1, m.3.2, m.3.3])
+        }
+
+        #[test]
+        fn vec4_ops() {
+            let mut rng = StdRng::seed_from_u64(1);
+            for _ in 0..ITERATIONS {
+                let (a, b) = (vec4(&mut rng), vec4(&mut rng));
+                let scalar = f32::default() + a.0 * b.0 + a.1 * b.1 + a.2 * b.2 + a.3 * b.3;
+                assert_eq!(bits([a.dot_simd(b)]), bits([scalar]), "{:?} . {:?}", a, b);
+                assert_eq!(bits([a.dot(b)]), bits([scalar]));
+            }
+        }
+
+        #[test]
+        fn quat_ops() {
+            let mut rng = StdRng::seed_from_u64(2);
+            for _ in 0..ITERATIONS {
+                let (a, b) = (vec4(&mut rng), vec4(&mut rng));
+                assert_eq!(vec4_bits(a.mul_quat_simd(b)), vec4_bits(a.mul_quat(b)), "{:?} * {:?}", a, b);
+            }
+        }
+
+        #[test]
+        fn mat4_ops() {
+            let mut rng = StdRng::seed_from_u64(3);
+            for _ in 0..ITERATIONS {
+                let (a, b) = (mat4(&mut rng), mat4(&mut rng));
+                assert_eq!(mat_bits(a.mul_simd(&b)), mat_bits(a * b), "{:?} * {:?}", a, b);
+                assert_eq!(mat_bits(a.transpose_simd()), mat_bits(a.transpose()));
+                assert_eq!(mat_bits(a.inverse_simd()), mat_bits(!a), "!{:?}", a);
+
+                let v = vec4(&mut rng);
+                assert_eq!(vec4_bits(a.transform_simd(v)), vec4_bits(a.transform(v)));
+                let p = v.xyz();
+                assert_eq!(vec3_bits(a.transform_pos_simd(p)), vec3_bits(a.transform_pos(p)));
+            }
+        }
+
+        #[test]
+        fn mat4_inverse_identity() {
+            let m = Mat4::from_transform(Vec3(1f32, -2f32, 3f32), Quat32::from_axis_angle(Vec3(1f32, 1f32, 0f32), 0.7), Vec3(2f32, 0.5f32, 1f32));
+            let i = m.mul_simd(&m.inverse_simd());
+            assert!(mat_bits(i).iter().zip(mat_bits(Mat4::default())).all(|(a, b)| (f32::from_bits(*a) - f32::from_bits(b)).abs() < 1e-5), "{:?}", i);
+        }
+
+        #[test]
+        fn mat4_batch() {
+            let mut rng = StdRng::seed_from_u64(4);
+            for len in 0..64 {
+                let m = mat4(&mut rng);
+                let src = (0..len).map(|_| vec4(&mut rng).xyz()).collect::<Vec<_>>();
+                let mut dst = vec![Vec3::from(0f32); len];
+                let mut dst_generic = dst.clone();
+                m.transform_pos_batch_simd(&src, &mut dst);
+                m.transform_pos_batch(&src, &mut dst_generic);
+
+                for ((src, dst), dst_generic) in src.iter().zip(&dst).zip(&dst_generic) {
+                    let scalar = m.transform_pos(*src);
+                    assert_eq!(vec3_bits(*dst), vec3_bits(scalar), "{:?} * {:?}", m, src);
+                    assert_eq!(vec3_bits(*dst_generic), vec3_bits(scalar));
+                }
+            }
+        }
+    }
+}
+
+pub mod curves2d {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct Bezier1(pub Vec2<f32>, pub Vec2<f32>);
+
+    impl Bezier1 {
+        /// https://en.wikipedia.org/wiki/Line%E2%80%93line_intersection#Given_two_points_on_each_line
+		/// https://stackoverflow.com/questions/563198
+        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
+            Bezier2(self.0, self.0 + (self.1 - self.0) * 0.5, self.1).intersections(l0, l1)
+        }
+
+        pub fn distance(self, p: Vec2<f32>) -> f32 {
+            let Self(p0, p1) = self;
+            let d = p1 - p0;
+            p.distance(p0 + d * (d.dot(p - p0) / d.dot(d)).clamp(0f32, 1f32))
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct Bezier2(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+
+    impl Bezier2 {
+        pub fn b(self, t: f32) -> Vec2<f32> {
+            debug_assert!((0.0..=1.0).contains(&t));
+            let Bezier2(p0, p1, p2) = self;
+            let tinv = 1.0 - t;
+            p0 * (tinv * tinv)
+                + p1 * 2.0 * t * tinv
+                + p2 * (t * t)
+        }
+
+        /// https://github.com/w8r/bezier-intersect/blob/master/src/quadratic.js
+        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
+            let Bezier2(p0, p1, p2) = self;
+
+            // Convert line to normal form: ax + by + c = 0
+            let n = Vec2(l0.1 - l1.1, l1.0 - l0.0); // Find normal to line: negative inverse of original line's slope
+            let cl = l0.0 * l1.1 - l1.0 * l0.1;      // Determine new c coefficient
+
+            let min = l0.0.min(l1.0);
+            let max = l0.0.max(l1.0);
+
+            // solve quadratic equation
+            Polynomial([
+                n.dot(p0 + p1 * -2.0 + p2),
+                n.dot(p0 * -2.0 + p1 * 2.0),
+                n.dot(p0) + cl
+            ]).roots_iter()
+                .filter(|t| *t >= 0.0 && *t <= 1.0) // check if point is on bezier
+                .map(|t| self.b(t).0)                       // calculate point on bezier
+                .filter(|b| *b >= min && *b <= max) // check if point is on line segment
+                .count()                                           // count intersections
+        }
+
+        /// http://blog.gludion.com/2009/08/distance-to-quadratic-bezier-curve.html
+		/// alternative: https://www.academia.edu/34616746/An_algorithm_for_computing_the_shortest_distance_between_a_point_and_quadratic_Bezier_curve
+        pub fn distance2(self, p: Vec2<f32>) -> f32 {
+            let Bezier2(p0, p1, p2) = self;
+
+            let a = p1 - p0;
+            let b = p2 - p1 - a;
+            let c = p0 - p;
+
+            Polynomial([
+                b.dot(b),
+                3.0 * a.dot(b),
+                2.0 * a.dot(a) + c.dot(b),
+                c.dot(a)
+            ]).roots_iter()
+                .filter(|t| *t >= 0.0 && *t <= 1.0)
+                .map(|t| p.distance2(self.b(t)))
+                .fold(p.distance2(p0), f32::min)
+                .min(p.distance2(p2))
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct Bezier3(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+
+    impl From<Bezier2> for Bezier3 {
+        /// Based on https://en.wikipedia.org/wiki/B%C3%A9zier_curve#Properties
+        fn from(Bezier2(p0, p1, p2): Bezier2) -> Self {
+            const FRAC_2_3: f32 = 2.0 / 3.0;
+            Self(
+                p0,
+                p0 * (1f32 - FRAC_2_3) + p1 * FRAC_2_3,
+                p2 * (1f32 - FRAC_2_3) + p1 * FRAC_2_3,
+                p2
+            )
+        }
+    }
+
+    impl Bezier3 {
+        pub fn b(self, t: f32) -> Vec2<f32> {
+            debug_assert!((0.0..=1.0).contains(&t));
+            let Self(p0, p1, p2, p3) = self;
+            let t2 = t * t;
+            let t3 = t * t2;
+            let tinv = 1.0 - t;
+            let tinv2 = tinv * tinv;
+            let tinv3 = tinv * tinv2;
+            p0 * tinv3
+                + p1 * 3.0 * tinv2 * t
+                + p2 * 3.0 * tinv2 * t2
+                + p3 * t3
+        }
+
+        /// https://github.com/w8r/bezier-intersect/blob/master/src/cubic.js
+        pub fn intersections(self, l0: Vec2<f32>, l1: Vec2<f32>) -> usize {
+            let Self(p0, p1, p2, p3) = self;
+
+            // Convert line to normal form: ax + by + c = 0
             let n = Vec2(l1.1 - l0.1, l0.0 - l1.0); // Find normal to line: negative inverse of original line's slope
             let cl = l0.0 * (l0.1 - l1.1) + l0.1 * (l1.0 - l0.0);//p.0 * r.1 - r.0 * p.1; // Determine new c coefficient
 
@@ -1631,203 +2756,1037 @@ pub mod curves2d {
                 .count()                                                      // count intersections
         }
 
-        // alternative: https://hal.inria.fr/file/index/docid/518379/filename/Xiao-DiaoChen2007c.pdf
-        /// brute force, accurate to 8 places
-        pub fn distance2(self, p: Vec2<f32>) -> f32 {
-            const STEPS: usize = 0x2000;
-            let mut distance = self.3.distance2(p);
-            for i in 0..STEPS {
-                distance = distance.min(self.b(1.0 / STEPS as f32 * i as f32).distance2(p));
+        // alternative: https://hal.inria.fr/file/index/docid/518379/filename/Xiao-DiaoChen2007c.pdf
+        /// brute force, accurate to 8 places
+        pub fn distance2(self, p: Vec2<f32>) -> f32 {
+            const STEPS: usize = 0x2000;
+            let mut distance = self.3.distance2(p);
+            for i in 0..STEPS {
+                distance = distance.min(self.b(1.0 / STEPS as f32 * i as f32).distance2(p));
+            }
+            distance
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn b1_intersections() {
+            let b = Bezier1(Vec2(0.0, 0.0), Vec2(1.0, 0.0));
+            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
+            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
+        }
+
+        #[test]
+        fn b1_distance() {
+            let b = Bezier1(Vec2(0.0, 0.0), Vec2(1.0, 0.0));
+            assert_eq!(1.0, b.distance(Vec2(0.5, 1.0)));
+        }
+
+        #[test]
+        fn b2_intersections() {
+            let b = Bezier2(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0));
+            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
+            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
+        }
+
+        #[test]
+        fn b2_distance() {
+            let b = Bezier2(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0));
+            assert_eq!(1.0, b.distance2(Vec2(0.5, 1.0)));
+        }
+
+        #[test]
+        fn b3_intersections() {
+            let b = Bezier3(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0), Vec2(3.0, 0.0));
+            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
+            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
+        }
+
+        #[test]
+        fn b3_distance() {
+            let b = Bezier3(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0), Vec2(3.0, 0.0));
+            assert!((b.distance2(Vec2(0.5, 1.0)) - 1.0).abs() < 1e-8);
+        }
+    }
+}
+
+pub mod shapes2d {
+    use super::*;
+
+    pub type EquTri2d   = RegularPolygon2d<3>;
+    pub type Quad2d     = RegularPolygon2d<4>;
+    pub type Pentagon2d = RegularPolygon2d<5>;
+    pub type Hexagon2d  = RegularPolygon2d<6>;
+    pub type Octagon2d  = RegularPolygon2d<7>;
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct RegularPolygon2d<const N: usize> {
+        pub pos: Vec2<f32>,
+        pub rad: f32
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct Polygon2d<const N: usize> {
+        pub pos: Vec2<f32>,
+        pub v:   [Vec2<f32>; N]
+    }
+}
+
+pub mod shapes3d {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Sphere {
+        pub center: Vec3<f32>,
+        pub radius: f32
+    }
+
+    impl Sphere {
+        pub fn new(center: Vec3<f32>, radius: f32) -> Self {
+            Self { center, radius }
+        }
+
+        /// A sphere around the center of the bounding box of the points, which is not the
+        /// smallest sphere. Returns `None` if there are no points.
+        pub fn from_points(points: &[Vec3<f32>]) -> Option<Self> {
+            let center = Aabb::from_points(points.iter().copied())?.center();
+            let radius = points.iter().fold(0f32, |r, p| r.max(p.distance2(center))).sqrt();
+            Some(Self { center, radius })
+        }
+
+        pub fn contains(&self, p: Vec3<f32>) -> bool {
+            p.distance2(self.center) <= self.radius * self.radius
+        }
+
+        /// The bounding sphere of the transformed sphere.
+        pub fn transform(&self, m: &Mat4<f32>) -> Self {
+            Self { center: m.transform_pos(self.center), radius: self.radius * m.get_scale().maxcw() }
+        }
+
+        /// Returns the contact if the spheres intersect.
+        pub fn contact(&self, other: &Sphere) -> Option<Contact> {
+            let d = other.center - self.center;
+            let dist = d.len();
+            let depth = self.radius + other.radius - dist;
+            if depth < 0f32 {
+                return None;
+            }
+
+            // any direction separates concentric spheres
+            let normal = if dist > 0f32 { d / dist } else { Vec3(0f32, 1f32, 0f32) };
+            Some(Contact { point: self.center + normal * (self.radius - depth * 0.5), normal, depth })
+        }
+    }
+
+    impl Distance<Vec3<f32>> for Sphere {
+        fn distance(&self, p: &Vec3<f32>) -> f32 {
+            p.distance(self.center) - self.radius
+        }
+    }
+
+    impl ClosestPoint<Vec3<f32>> for Sphere {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            let d = *p - self.center;
+            match d.len2() {
+                len2 if len2 > self.radius * self.radius => self.center + d * (self.radius / len2.sqrt()),
+                _ => *p
+            }
+        }
+    }
+
+    impl RayCast<Ray> for Sphere {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let oc = ray.origin - self.center;
+            let a = ray.dir.len2();
+            let b = oc.dot(ray.dir);
+            let c = oc.len2() - self.radius * self.radius;
+            let disc = b * b - a * c;
+            if a == 0f32 || disc < 0f32 {
+                return None;
+            }
+
+            let sqrt = disc.sqrt();
+            let t = match (-b - sqrt) / a {
+                t if t >= 0f32 => t,
+                _ => (-b + sqrt) / a
+            };
+
+            if t < 0f32 {
+                return None;
+            }
+
+            let point = ray.at(t);
+            Some(RayHit { t, point, normal: (point - self.center) / self.radius })
+        }
+    }
+
+    impl Intersections<Sphere> for Sphere {
+        fn intersections(&self, other: &Sphere) -> u32 {
+            let radius = self.radius + other.radius;
+            (self.center.distance2(other.center) <= radius * radius) as u32
+        }
+    }
+
+    impl Intersections<Aabb> for Sphere {
+        fn intersections(&self, other: &Aabb) -> u32 {
+            other.intersections(self)
+        }
+    }
+
+    impl Intersections<Obb> for Sphere {
+        fn intersections(&self, other: &Obb) -> u32 {
+            other.intersections(self)
+        }
+    }
+
+    /// A box centered at the origin with the half extents.
+    #[derive(Copy, Clone, Debug)]
+    pub struct Cuboid(pub Vec3<f32>);
+
+    impl Distance<Vec3<f32>> for Cuboid {
+        fn distance(&self, p: &Vec3<f32>) -> f32 {
+            let d = (*p).abs() - self.0;
+            d.maxs(0f32).len() + d.0.max(d.1).max(d.2).min(0f32)
+        }
+    }
+
+    impl RayCast<Ray> for Cuboid {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            Aabb::from_center(Vec3(0f32, 0f32, 0f32), self.0).ray_cast(ray)
+        }
+    }
+
+    /// A plane with the normal in `xyz` and the negative distance from the origin along the normal
+    /// in `w`, the normal must be normalized for distances.
+    #[derive(Copy, Clone, Debug)]
+    pub struct Plane(pub Vec4<f32>);
+
+    impl Plane {
+        pub fn new(normal: Vec3<f32>, point: Vec3<f32>) -> Self {
+            let normal = normal.normalize(1f32);
+            Self(Vec4::from((normal, -normal.dot(point))))
+        }
+
+        /// The plane through three points, the normal faces the side on which the points are in
+        /// counter-clockwise order.
+        pub fn from_points(a: Vec3<f32>, b: Vec3<f32>, c: Vec3<f32>) -> Self {
+            Self::new((b - a).cross(c - a), a)
+        }
+
+        pub fn normal(&self) -> Vec3<f32> {
+            self.0.xyz()
+        }
+
+        /// Normalizes the normal, planes without a normal are not changed.
+        pub fn normalize(self) -> Self {
+            let len = self.normal().len();
+            if len > 0f32 { Self(self.0 / len) } else { self }
+        }
+    }
+
+    impl Distance<Vec3<f32>> for Plane {
+        fn distance(&self, p: &Vec3<f32>) -> f32 {
+            //let n = self.0.normalize(1.0);
+            (*p).dot(self.0.xyz()) + (self.0).3
+        }
+    }
+
+    impl ClosestPoint<Vec3<f32>> for Plane {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            *p - self.normal() * self.distance(p)
+        }
+    }
+
+    /// The normal of the hit faces the origin of the ray.
+    impl RayCast<Ray> for Plane {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let n = self.normal();
+            let denom = n.dot(ray.dir);
+            let t = -self.distance(&ray.origin) / denom;
+            (denom != 0f32 && t >= 0f32).then(|| RayHit {
+                t,
+                point:  ray.at(t),
+                normal: if denom > 0f32 { -n } else { n }
+            })
+        }
+    }
+
+    /// A cylinder along the y axis centered at the origin, with the radius in `x` and the half
+    /// height in `y`.
+    #[derive(Copy, Clone, Debug)]
+    pub struct Cylinder(pub Vec2<f32>);
+
+    impl Distance<Vec3<f32>> for Cylinder {
+        fn distance(&self, p: &Vec3<f32>) -> f32 {
+            let d = Vec2((*p).xz().len(), p.1).abs() - self.0;
+            d.0.max(d.1).min(0.0) + d.maxs(0.0).len()
+        }
+    }
+
+    impl RayCast<Ray> for Cylinder {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let Vec2(radius, half_height) = self.0;
+            let (o, d) = (ray.origin, ray.dir);
+
+            // the intersection of the infinite cylinder and the slab between the caps
+            let a = d.0 * d.0 + d.2 * d.2;
+            let c = o.0 * o.0 + o.2 * o.2 - radius * radius;
+            let (side_min, side_max) = if a == 0f32 {
+                if c > 0f32 {
+                    return None;
+                }
+                (f32::NEG_INFINITY, f32::INFINITY)
+            } else {
+                let b = o.0 * d.0 + o.2 * d.2;
+                let disc = b * b - a * c;
+                if disc < 0f32 {
+                    return None;
+                }
+                let sqrt = disc.sqrt();
+                ((-b - sqrt) / a, (-b + sqrt) / a)
+            };
+
+            let (cap_min, cap_max) = if d.1 == 0f32 {
+                if o.1.abs() > half_height {
+                    return None;
+                }
+                (f32::NEG_INFINITY, f32::INFINITY)
+            } else {
+                let (t0, t1) = ((-half_height - o.1) / d.1, (half_height - o.1) / d.1);
+                if t0 < t1 { (t0, t1) } else { (t1, t0) }
+            };
+
+            let (t_min, t_max) = (side_min.max(cap_min), side_max.min(cap_max));
+            if t_min > t_max || t_max < 0f32 {
+                return None;
+            }
+
+            let (t, side, cap_sign) = match t_min >= 0f32 {
+                true  => (t_min, side_min >= cap_min, -d.1.signum()),
+                false => (t_max, side_max <= cap_max, d.1.signum())
+            };
+
+            let point = ray.at(t);
+            let normal = match side {
+                true  => Vec3(point.0, 0f32, point.2) / radius,
+                false => Vec3(0f32, cap_sign, 0f32)
+            };
+            Some(RayHit { t, point, normal })
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct Triangle {
+        pub a: Vec3<f32>,
+        pub b: Vec3<f32>,
+        pub c: Vec3<f32>,
+    }
+
+    impl Distance2<Vec3<f32>> for Triangle {
+        fn distance2(&self, p: &Vec3<f32>) -> f32 {
+            let Triangle { a, b, c } = *self;
+            let p = *p;
+            let ba = b - a;
+            let pa = p - a;
+            let cb = c - b;
+            let pb = p - b;
+            let ac = a - c;
+            let pc = p - c;
+            let nor = ba.cross(ac);
+
+            if ba.cross(nor).dot(pa).sign() +
+                cb.cross(nor).dot(pb).sign() +
+                ac.cross(nor).dot(pc).sign() < 2 {
+                (ba * (ba.dot(pa) / ba.dot(ba)).clamp(0f32, 1f32) - pa).len2()
+                    .min((cb * (cb.dot(pb) / cb.dot(cb)).clamp(0f32, 1f32) - pb).len2())
+                    .min((ac * (ac.dot(pc) / ac.dot(ac)).clamp(0f32, 1f32) - pc).len2())
+            } else {
+                nor.dot(pa) * nor.dot(pa) / nor.dot(nor)
+            }
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    pub struct Quad {
+        pub a: Vec3<f32>,
+        pub b: Vec3<f32>,
+        pub c: Vec3<f32>,
+        pub d: Vec3<f32>,
+    }
+
+    /// Closest point on a triangle, from Real-Time Collision Detection by Christer Ericson.
+    impl ClosestPoint<Vec3<f32>> for Triangle {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            let Triangle { a, b, c } = *self;
+            let p = *p;
+            let ab = b - a;
+            let ac = c - a;
+
+            let ap = p - a;
+            let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+            if d1 <= 0f32 && d2 <= 0f32 {
+                return a;
+            }
+
+            let bp = p - b;
+            let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+            if d3 >= 0f32 && d4 <= d3 {
+                return b;
+            }
+
+            let vc = d1 * d4 - d3 * d2;
+            if vc <= 0f32 && d1 >= 0f32 && d3 <= 0f32 {
+                return a + ab * (d1 / (d1 - d3));
+            }
+
+            let cp = p - c;
+            let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+            if d6 >= 0f32 && d5 <= d6 {
+                return c;
+            }
+
+            let vb = d5 * d2 - d1 * d6;
+            if vb <= 0f32 && d2 >= 0f32 && d6 <= 0f32 {
+                return a + ac * (d2 / (d2 - d6));
+            }
+
+            let va = d3 * d6 - d5 * d4;
+            if va <= 0f32 && d4 - d3 >= 0f32 && d5 - d6 >= 0f32 {
+                return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+            }
+
+            let denom = 1f32 / (va + vb + vc);
+            a + ab * (vb * denom) + ac * (vc * denom)
+        }
+    }
+
+    /// Both sides of the triangle are hit, the normal of the hit faces the origin of the ray.
+    impl RayCast<Ray> for Triangle {
+        type Hit = RayHit;
+
+        /// Möller-Trumbore intersection.
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let Triangle { a, b, c } = *self;
+            let ab = b - a;
+            let ac = c - a;
+            let p = ray.dir.cross(ac);
+            let det = ab.dot(p);
+            if det == 0f32 {
+                return None;
+            }
+
+            let inv_det = 1f32 / det;
+            let s = ray.origin - a;
+            let u = s.dot(p) * inv_det;
+            let q = s.cross(ab);
+            let v = ray.dir.dot(q) * inv_det;
+            let t = ac.dot(q) * inv_det;
+            if u < 0f32 || v < 0f32 || u + v > 1f32 || t < 0f32 {
+                return None;
+            }
+
+            let normal = ab.cross(ac).normalize(1f32);
+            Some(RayHit {
+                t,
+                point:  ray.at(t),
+                normal: if normal.dot(ray.dir) > 0f32 { -normal } else { normal }
+            })
+        }
+    }
+
+    impl Distance2<Vec3<f32>> for Quad {
+        fn distance2(&self, p: &Vec3<f32>) -> f32 {
+            let Quad { a, b, c, d } = *self;
+            let p = *p;
+            let ba = b - a;
+            let pa = p - a;
+            let cb = c - b;
+            let pb = p - b;
+            let dc = d - c;
+            let pc = p - c;
+            let ad = a - d;
+            let pd = p - d;
+            let nor = ba.cross(ad);
+
+            if ba.cross(nor).dot(pa).sign() +
+                cb.cross(nor).dot(pb).sign() +
+                dc.cross(nor).dot(pc).sign() +
+                ad.cross(nor).dot(pd).sign() < 3 {
+                (ba * (ba.dot(pa) / ba.dot(ba)).clamp(0f32, 1f32) - pa).len2()
+                    .min((cb * (cb.dot(pb) / cb.dot(cb)).clamp(0f32, 1f32) - pb).len2())
+                    .min((dc * (dc.dot(pc) / dc.dot(dc)).clamp(0f32, 1f32) - pc).len2())
+                    .min((ad * (ad.dot(pd) / ad.dot(ad)).clamp(0f32, 1f32) - pd).len2())
+            } else {
+                nor.dot(pa) * nor.dot(pa) / nor.dot(nor)
+            }
+        }
+    }
+
+    /// The quad must be planar and convex, both sides are hit as with [`Triangle`].
+    impl RayCast<Ray> for Quad {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let Quad { a, b, c, d } = *self;
+            Triangle { a, b, c }.ray_cast(ray).or_else(|| Triangle { a, b: c, c: d }.ray_cast(ray))
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Segment {
+        pub a: Vec3<f32>,
+        pub b: Vec3<f32>
+    }
+
+    impl Segment {
+        /// Returns the closest points on both segments, the first one is on `self`. From
+        /// Real-Time Collision Detection by Christer Ericson.
+        pub fn closest_points(&self, other: &Segment) -> (Vec3<f32>, Vec3<f32>) {
+            let d1 = self.b - self.a;
+            let d2 = other.b - other.a;
+            let r = self.a - other.a;
+            let (a, e, f) = (d1.len2(), d2.len2(), d2.dot(r));
+
+            let (s, t) = if a == 0f32 && e == 0f32 {
+                (0f32, 0f32)
+            } else if a == 0f32 {
+                (0f32, (f / e).clamp(0f32, 1f32))
+            } else {
+                let c = d1.dot(r);
+                if e == 0f32 {
+                    ((-c / a).clamp(0f32, 1f32), 0f32)
+                } else {
+                    let b = d1.dot(d2);
+                    let denom = a * e - b * b;
+                    // any point is closest for parallel segments
+                    let s = if denom != 0f32 { ((b * f - c * e) / denom).clamp(0f32, 1f32) } else { 0f32 };
+                    let t = (b * s + f) / e;
+                    if t < 0f32 {
+                        ((-c / a).clamp(0f32, 1f32), 0f32)
+                    } else if t > 1f32 {
+                        (((b - c) / a).clamp(0f32, 1f32), 1f32)
+                    } else {
+                        (s, t)
+                    }
+                }
+            };
+
+            (self.a + d1 * s, other.a + d2 * t)
+        }
+    }
+
+    impl ClosestPoint<Vec3<f32>> for Segment {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            let ab = self.b - self.a;
+            let len2 = ab.len2();
+            if len2 == 0f32 {
+                return self.a;
+            }
+
+            self.a + ab * ((*p - self.a).dot(ab) / len2).clamp(0f32, 1f32)
+        }
+    }
+
+    impl Distance2<Vec3<f32>> for Segment {
+        fn distance2(&self, p: &Vec3<f32>) -> f32 {
+            self.closest_point(p).distance2(*p)
+        }
+    }
+
+    /// A half-line, the direction does not have to be normalized, distances along the ray are
+    /// measured in multiples of it.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Ray {
+        pub origin: Vec3<f32>,
+        pub dir:    Vec3<f32>
+    }
+
+    impl Ray {
+        pub fn new(origin: Vec3<f32>, dir: Vec3<f32>) -> Self {
+            Self { origin, dir }
+        }
+
+        /// The ray through a point in normalized device coordinates, for picking. `inv_view_proj`
+        /// is the inverse of the view projection matrix, `near_depth` and `far_depth` are the
+        /// depths of the near and far planes, e.g. `1` and `0` for reversed depth. The origin is
+        /// on the near plane.
+        pub fn from_ndc(ndc: Vec2<f32>, inv_view_proj: &Mat4<f32>, near_depth: f32, far_depth: f32) -> Self {
+            let unproject = |depth: f32| {
+                let v = inv_view_proj.transform(Vec4(ndc.0, ndc.1, depth, 1f32));
+                v.xyz() / v.3
+            };
+
+            // the far plane may be at infinity
+            let origin = unproject(near_depth);
+            Self { origin, dir: (unproject((near_depth + far_depth) * 0.5) - origin).normalize(1f32) }
+        }
+
+        pub fn at(&self, t: f32) -> Vec3<f32> {
+            self.origin + self.dir * t
+        }
+    }
+
+    impl ClosestPoint<Vec3<f32>> for Ray {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            let len2 = self.dir.len2();
+            if len2 == 0f32 {
+                return self.origin;
+            }
+
+            self.at(((*p - self.origin).dot(self.dir) / len2).max(0f32))
+        }
+    }
+
+    impl Distance2<Vec3<f32>> for Ray {
+        fn distance2(&self, p: &Vec3<f32>) -> f32 {
+            self.closest_point(p).distance2(*p)
+        }
+    }
+
+    /// Rays that start inside a shape hit it where they leave it, the normal always points out
+    /// of the shape, unless noted otherwise.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct RayHit {
+        /// The distance along the ray in multiples of its direction.
+        pub t:      f32,
+        pub point:  Vec3<f32>,
+        pub normal: Vec3<f32>
+    }
+
+    /// The contact of two overlapping shapes.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Contact {
+        /// A point in the overlapping volume.
+        pub point:  Vec3<f32>,
+        /// The direction from the first to the second shape in which they are separated the
+        /// fastest.
+        pub normal: Vec3<f32>,
+        /// How far the shapes have to be moved along the normal to only touch.
+        pub depth:  f32
+    }
+
+    impl Contact {
+        /// The contact from the view of the other shape.
+        pub fn flip(self) -> Self {
+            Self { normal: -self.normal, ..self }
+        }
+    }
+
+    /// An axis-aligned bounding box.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Aabb {
+        pub min: Vec3<f32>,
+        pub max: Vec3<f32>
+    }
+
+    impl Aabb {
+        pub fn new(min: Vec3<f32>, max: Vec3<f32>) -> Self {
+            Self { min, max }
+        }
+
+        pub fn from_center(center: Vec3<f32>, half_extents: Vec3<f32>) -> Self {
+            Self { min: center - half_extents, max: center + half_extents }
+        }
+
+        /// Returns `None` if there are no points.
+        pub fn from_points(points: impl IntoIterator<Item = Vec3<f32>>) -> Option<Self> {
+            let mut points = points.into_iter();
+            let first = points.next()?;
+            Some(points.fold(Self { min: first, max: first }, |aabb, p| aabb.extend(p)))
+        }
+
+        pub fn center(&self) -> Vec3<f32> {
+            (self.min + self.max) * 0.5
+        }
+
+        pub fn half_extents(&self) -> Vec3<f32> {
+            (self.max - self.min) * 0.5
+        }
+
+        pub fn size(&self) -> Vec3<f32> {
+            self.max - self.min
+        }
+
+        pub fn extend(self, p: Vec3<f32>) -> Self {
+            Self { min: self.min.min(p), max: self.max.max(p) }
+        }
+
+        pub fn union(self, other: Self) -> Self {
+            Self { min: self.min.min(other.min), max: self.max.max(other.max) }
+        }
+
+        pub fn contains(&self, p: Vec3<f32>) -> bool {
+            p.0 >= self.min.0 && p.1 >= self.min.1 && p.2 >= self.min.2
+                && p.0 <= self.max.0 && p.1 <= self.max.1 && p.2 <= self.max.2
+        }
+
+        /// The bounding box of the transformed box.
+        pub fn transform(&self, m: &Mat4<f32>) -> Self {
+            let h = self.half_extents();
+            let half_extents = m.0.xyz().abs() * h.0 + m.1.xyz().abs() * h.1 + m.2.xyz().abs() * h.2;
+            Self::from_center(m.transform_pos(self.center()), half_extents)
+        }
+
+        /// Returns the contact if the boxes intersect, they are separated along the axis with the
+        /// smallest overlap.
+        pub fn contact(&self, other: &Aabb) -> Option<Contact> {
+            let min = self.min.max(other.min);
+            let max = self.max.min(other.max);
+            let size = max - min;
+            if size.0 < 0f32 || size.1 < 0f32 || size.2 < 0f32 {
+                return None;
             }
-            distance
+
+            let axis = if size.0 <= size.1 && size.0 <= size.2 { 0 } else if size.1 <= size.2 { 1 } else { 2 };
+            let mut normal = Vec3(0f32, 0f32, 0f32);
+            normal[axis] = if other.center()[axis] < self.center()[axis] { -1f32 } else { 1f32 };
+            Some(Contact { point: (min + max) * 0.5, normal, depth: size[axis] })
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+        /// Returns the contact if the sphere intersects the box.
+        pub fn sphere_contact(&self, sphere: &Sphere) -> Option<Contact> {
+            let point = self.closest_point(&sphere.center);
+            let d = sphere.center - point;
+            let dist2 = d.len2();
+            if dist2 > sphere.radius * sphere.radius {
+                return None;
+            }
 
-        #[test]
-        fn b1_intersections() {
-            let b = Bezier1(Vec2(0.0, 0.0), Vec2(1.0, 0.0));
-            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
-            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
-        }
+            if dist2 > 0f32 {
+                let dist = dist2.sqrt();
+                return Some(Contact { point, normal: d / dist, depth: sphere.radius - dist });
+            }
 
-        #[test]
-        fn b1_distance() {
-            let b = Bezier1(Vec2(0.0, 0.0), Vec2(1.0, 0.0));
-            assert_eq!(1.0, b.distance(Vec2(0.5, 1.0)));
+            // the center is inside, the sphere is pushed out through the closest face
+            let (to_min, to_max) = (sphere.center - self.min, self.max - sphere.center);
+            let (mut axis, mut dist, mut sign) = (0, f32::INFINITY, 1f32);
+            for i in 0..3 {
+                if to_min[i] < dist {
+                    (axis, dist, sign) = (i, to_min[i], -1f32);
+                }
+                if to_max[i] < dist {
+                    (axis, dist, sign) = (i, to_max[i], 1f32);
+                }
+            }
+
+            let mut normal = Vec3(0f32, 0f32, 0f32);
+            normal[axis] = sign;
+            Some(Contact { point: sphere.center + normal * dist, normal, depth: sphere.radius + dist })
         }
+    }
 
-        #[test]
-        fn b2_intersections() {
-            let b = Bezier2(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0));
-            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
-            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
+    impl From<Obb> for Aabb {
+        fn from(obb: Obb) -> Self {
+            let Obb { center, axes, half_extents: h } = obb;
+            Self::from_center(center, axes.0.abs() * h.0 + axes.1.abs() * h.1 + axes.2.abs() * h.2)
         }
+    }
 
-        #[test]
-        fn b2_distance() {
-            let b = Bezier2(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0));
-            assert_eq!(1.0, b.distance2(Vec2(0.5, 1.0)));
+    impl Distance<Vec3<f32>> for Aabb {
+        fn distance(&self, p: &Vec3<f32>) -> f32 {
+            Cuboid(self.half_extents()).distance(&(*p - self.center()))
         }
+    }
 
-        #[test]
-        fn b3_intersections() {
-            let b = Bezier3(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0), Vec2(3.0, 0.0));
-            assert_eq!(0, b.intersections(Vec2(0.0, 1.0), Vec2(1.0, 1.0)));
-            assert_eq!(1, b.intersections(Vec2(0.0, -0.5), Vec2(1.0, 0.5)));
+    impl ClosestPoint<Vec3<f32>> for Aabb {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            p.clamp(self.min, self.max)
         }
+    }
 
-        #[test]
-        fn b3_distance() {
-            let b = Bezier3(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(2.0, 0.0), Vec2(3.0, 0.0));
-            assert!((b.distance2(Vec2(0.5, 1.0)) - 1.0).abs() < 1e-8);
+    impl RayCast<Ray> for Aabb {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            // the intersection of the slabs between the planes of each axis
+            let (mut t_min, mut t_max) = (f32::NEG_INFINITY, f32::INFINITY);
+            let (mut enter, mut exit) = (0, 0);
+            for i in 0..3 {
+                let (o, d) = (ray.origin[i], ray.dir[i]);
+                if d == 0f32 {
+                    if o < self.min[i] || o > self.max[i] {
+                        return None;
+                    }
+                    continue;
+                }
+
+                let (t0, t1) = ((self.min[i] - o) / d, (self.max[i] - o) / d);
+                let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+                if near > t_min {
+                    (t_min, enter) = (near, i);
+                }
+                if far < t_max {
+                    (t_max, exit) = (far, i);
+                }
+            }
+
+            if t_min > t_max || t_max < 0f32 {
+                return None;
+            }
+
+            let (t, axis, sign) = match t_min >= 0f32 {
+                true  => (t_min, enter, -ray.dir[enter].signum()),
+                false => (t_max, exit, ray.dir[exit].signum())
+            };
+
+            let mut normal = Vec3(0f32, 0f32, 0f32);
+            normal[axis] = sign;
+            Some(RayHit { t, point: ray.at(t), normal })
         }
     }
-}
 
-pub mod shapes2d {
-    use super::*;
+    impl Intersections<Aabb> for Aabb {
+        fn intersections(&self, other: &Aabb) -> u32 {
+            let (min, max) = (self.min.max(other.min), self.max.min(other.max));
+            (min.0 <= max.0 && min.1 <= max.1 && min.2 <= max.2) as u32
+        }
+    }
 
-    pub type EquTri2d   = RegularPolygon2d<3>;
-    pub type Quad2d     = RegularPolygon2d<4>;
-    pub type Pentagon2d = RegularPolygon2d<5>;
-    pub type Hexagon2d  = RegularPolygon2d<6>;
-    pub type Octagon2d  = RegularPolygon2d<7>;
+    impl Intersections<Sphere> for Aabb {
+        fn intersections(&self, sphere: &Sphere) -> u32 {
+            (self.closest_point(&sphere.center).distance2(sphere.center) <= sphere.radius * sphere.radius) as u32
+        }
+    }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct RegularPolygon2d<const N: usize> {
-        pub pos: Vec2<f32>,
-        pub rad: f32
+    impl Intersections<Obb> for Aabb {
+        fn intersections(&self, other: &Obb) -> u32 {
+            other.intersections(self)
+        }
     }
 
-    #[derive(Copy, Clone)]
-    pub struct Polygon2d<const N: usize> {
-        pub pos: Vec2<f32>,
-        pub v:   [Vec2<f32>; N]
+    /// An oriented bounding box, the columns of `axes` are the orthonormal axes of the box.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Obb {
+        pub center:       Vec3<f32>,
+        pub axes:         Mat3<f32>,
+        pub half_extents: Vec3<f32>
     }
-}
 
-pub mod shapes3d {
-    use super::*;
+    impl Obb {
+        /// The transformed box, the matrix must not shear or project.
+        pub fn from_transform(aabb: &Aabb, m: &Mat4<f32>) -> Self {
+            let scale = m.get_scale();
+            Self {
+                center:       m.transform_pos(aabb.center()),
+                axes:         Mat3(m.0.xyz() / scale.0, m.1.xyz() / scale.1, m.2.xyz() / scale.2),
+                half_extents: aabb.half_extents() * scale
+            }
+        }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Sphere {
-        pub radius: f32
+        /// Converts a point to the coordinates of the box, relative to the center.
+        pub fn to_local(&self, p: Vec3<f32>) -> Vec3<f32> {
+            self.axes.transpose().transform(p - self.center)
+        }
+
+        /// Converts a point from the coordinates of the box.
+        pub fn to_world(&self, p: Vec3<f32>) -> Vec3<f32> {
+            self.axes.transform(p) + self.center
+        }
+
+        pub fn contains(&self, p: Vec3<f32>) -> bool {
+            let p = self.to_local(p).abs();
+            p.0 <= self.half_extents.0 && p.1 <= self.half_extents.1 && p.2 <= self.half_extents.2
+        }
+
+        fn local_aabb(&self) -> Aabb {
+            Aabb::from_center(Vec3(0f32, 0f32, 0f32), self.half_extents)
+        }
+
+        /// Returns the contact if the sphere intersects the box.
+        pub fn sphere_contact(&self, sphere: &Sphere) -> Option<Contact> {
+            let local = Sphere::new(self.to_local(sphere.center), sphere.radius);
+            let contact = self.local_aabb().sphere_contact(&local)?;
+            Some(Contact { point: self.to_world(contact.point), normal: self.axes.transform(contact.normal), ..contact })
+        }
     }
 
-    impl Distance<Vec3<f32>> for Sphere {
+    impl From<Aabb> for Obb {
+        fn from(aabb: Aabb) -> Self {
+            Self { center: aabb.center(), axes: Mat3::default(), half_extents: aabb.half_extents() }
+        }
+    }
+
+    impl Distance<Vec3<f32>> for Obb {
         fn distance(&self, p: &Vec3<f32>) -> f32 {
-            (*p).len() - self.radius
+            Cuboid(self.half_extents).distance(&self.to_local(*p))
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Cuboid(pub Vec3<f32>);
+    impl ClosestPoint<Vec3<f32>> for Obb {
+        fn closest_point(&self, p: &Vec3<f32>) -> Vec3<f32> {
+            self.to_world(self.to_local(*p).clamp(-self.half_extents, self.half_extents))
+        }
+    }
 
-    impl Distance<Vec3<f32>> for Cuboid {
-        fn distance(&self, p: &Vec3<f32>) -> f32 {
-            let d = (*p).abs() - self.0;
-            d.maxs(0f32).len() + d.0.max(d.1).max(d.2).min(0f32)
+    impl RayCast<Ray> for Obb {
+        type Hit = RayHit;
+
+        fn ray_cast(&self, ray: &Ray) -> Option<RayHit> {
+            let local = Ray::new(self.to_local(ray.origin), self.axes.transpose().transform(ray.dir));
+            let hit = self.local_aabb().ray_cast(&local)?;
+            Some(RayHit { t: hit.t, point: ray.at(hit.t), normal: self.axes.transform(hit.normal) })
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Plane(pub Vec4<f32>);
+    impl Intersections<Obb> for Obb {
+        /// Separating axis test with the 15 axes of the faces and the cross products of the
+        /// edges, from Real-Time Collision Detection by Christer Ericson.
+        fn intersections(&self, other: &Obb) -> u32 {
+            // avoids false separations on the cross products of parallel edges
+            const EPSILON: f32 = 1e-6;
+
+            let a = [self.axes.0, self.axes.1, self.axes.2];
+            let b = [other.axes.0, other.axes.1, other.axes.2];
+            let (ea, eb) = (self.half_extents, other.half_extents);
+
+            // the axes of the other box and the translation in the space of this box
+            let r: [[f32; 3]; 3] = core::array::from_fn(|i| core::array::from_fn(|j| a[i].dot(b[j])));
+            let abs_r = r.map(|row| row.map(|v| v.abs() + EPSILON));
+            let d = other.center - self.center;
+            let t = [d.dot(a[0]), d.dot(a[1]), d.dot(a[2])];
+
+            for i in 0..3 {
+                let rb = eb.0 * abs_r[i][0] + eb.1 * abs_r[i][1] + eb.2 * abs_r[i][2];
+                if t[i].abs() > ea[i] + rb {
+                    return 0;
+                }
+            }
 
-    impl Distance<Vec3<f32>> for Plane {
-        fn distance(&self, p: &Vec3<f32>) -> f32 {
-            //let n = self.0.normalize(1.0);
-            (*p).dot(self.0.xyz()) + (self.0).3
+            for j in 0..3 {
+                let ra = ea.0 * abs_r[0][j] + ea.1 * abs_r[1][j] + ea.2 * abs_r[2][j];
+                if (t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j]).abs() > ra + eb[j] {
+                    return 0;
+                }
+            }
+
+            for i in 0..3 {
+                let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+                for j in 0..3 {
+                    let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                    let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                    let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                    if (t[i2] * r[i1][j] - t[i1] * r[i2][j]).abs() > ra + rb {
+                        return 0;
+                    }
+                }
+            }
+
+            1
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Cylinder(pub Vec2<f32>);
+    impl Intersections<Aabb> for Obb {
+        fn intersections(&self, other: &Aabb) -> u32 {
+            self.intersections(&Obb::from(*other))
+        }
+    }
 
-    impl Distance<Vec3<f32>> for Cylinder {
-        fn distance(&self, p: &Vec3<f32>) -> f32 {
-            let d = Vec2((*p).xz().len(), p.1).abs() - self.0;
-            d.0.max(d.1).min(0.0) + d.maxs(0.0).len()
+    impl Intersections<Sphere> for Obb {
+        fn intersections(&self, sphere: &Sphere) -> u32 {
+            (self.closest_point(&sphere.center).distance2(sphere.center) <= sphere.radius * sphere.radius) as u32
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Triangle {
-        pub a: Vec3<f32>,
-        pub b: Vec3<f32>,
-        pub c: Vec3<f32>,
+    /// How much of a shape is inside a volume.
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    pub enum Containment {
+        Outside,
+        Intersecting,
+        Inside
     }
 
-    impl Distance2<Vec3<f32>> for Triangle {
-        fn distance2(&self, p: &Vec3<f32>) -> f32 {
-            let Triangle { a, b, c } = *self;
-            let p = *p;
-            let ba = b - a;
-            let pa = p - a;
-            let cb = c - b;
-            let pb = p - b;
-            let ac = a - c;
-            let pc = p - c;
-            let nor = ba.cross(ac);
+    /// A view volume bounded by six planes with normals that point inside. Shapes are tested
+    /// against each plane separately, so shapes close to the corners may be classified as
+    /// intersecting although they are outside, which is fine for culling.
+    #[derive(Copy, Clone, Debug)]
+    pub struct Frustum {
+        /// The left, right, bottom, top, near and far planes, planes that are at infinity have
+        /// no normal and contain everything.
+        pub planes: [Plane; 6]
+    }
 
-            if ba.cross(nor).dot(pa).sign() +
-                cb.cross(nor).dot(pb).sign() +
-                ac.cross(nor).dot(pc).sign() < 2 {
-                (ba * (ba.dot(pa) / ba.dot(ba)).clamp(0f32, 1f32) - pa).len2()
-                    .min((cb * (cb.dot(pb) / cb.dot(cb)).clamp(0f32, 1f32) - pb).len2())
-                    .min((ac * (ac.dot(pc) / ac.dot(ac)).clamp(0f32, 1f32) - pc).len2())
-            } else {
-                nor.dot(pa) * nor.dot(pa) / nor.dot(nor)
+    impl Frustum {
+        /// Extracts the planes of the view volume of a view projection matrix. `z_zero_to_one`
+        /// is the depth range of the projection, as in [`Mat4::from_perspective`], the planes
+        /// of reversed depth projections are swapped.
+        pub fn from_matrix(m: &Mat4<f32>, z_zero_to_one: bool) -> Self {
+            let row = |i: usize| Vec4(m.0[i], m.1[i], m.2[i], m.3[i]);
+            let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+            let near = if z_zero_to_one { z } else { w + z };
+            Self { planes: [w + x, w - x, w + y, w - y, near, w - z].map(|v| Plane(v).normalize()) }
+        }
+
+        pub fn contains(&self, p: Vec3<f32>) -> bool {
+            self.planes.iter().all(|plane| plane.distance(&p) >= 0f32)
+        }
+
+        /// Classifies a shape by the signed distance of its center to the planes and its extent
+        /// along the normal of each plane.
+        fn classify_by(&self, center: Vec3<f32>, radius: impl Fn(Vec3<f32>) -> f32) -> Containment {
+            let mut containment = Containment::Inside;
+            for plane in &self.planes {
+                let (d, r) = (plane.distance(&center), radius(plane.normal()));
+                if d < -r {
+                    return Containment::Outside;
+                } else if d < r {
+                    containment = Containment::Intersecting;
+                }
             }
+            containment
+        }
+
+        pub fn classify_aabb(&self, aabb: &Aabb) -> Containment {
+            let h = aabb.half_extents();
+            self.classify_by(aabb.center(), |n| n.abs().dot(h))
+        }
+
+        pub fn classify_obb(&self, obb: &Obb) -> Containment {
+            let (axes, h) = (obb.axes, obb.half_extents);
+            self.classify_by(obb.center, |n| n.dot(axes.0).abs() * h.0 + n.dot(axes.1).abs() * h.1 + n.dot(axes.2).abs() * h.2)
+        }
+
+        pub fn classify_sphere(&self, sphere: &Sphere) -> Containment {
+            // planes at infinity have no normal
+            self.classify_by(sphere.center, |n| n.len() * sphere.radius)
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
-    pub struct Quad {
-        pub a: Vec3<f32>,
-        pub b: Vec3<f32>,
-        pub c: Vec3<f32>,
-        pub d: Vec3<f32>,
+    impl Intersections<Aabb> for Frustum {
+        fn intersections(&self, other: &Aabb) -> u32 {
+            (self.classify_aabb(other) != Containment::Outside) as u32
+        }
     }
 
-    impl Distance2<Vec3<f32>> for Quad {
-        fn distance2(&self, p: &Vec3<f32>) -> f32 {
-            let Quad { a, b, c, d } = *self;
-            let p = *p;
-            let ba = b - a;
-            let pa = p - a;
-            let cb = c - b;
-            let pb = p - b;
-            let dc = d - c;
-            let pc = p - c;
-            let ad = a - d;
-            let pd = p - d;
-            let nor = ba.cross(ad);
+    impl Intersections<Obb> for Frustum {
+        fn intersections(&self, other: &Obb) -> u32 {
+            (self.classify_obb(other) != Containment::Outside) as u32
+        }
+    }
 
-            if ba.cross(nor).dot(pa).sign() +
-                cb.cross(nor).dot(pb).sign() +
-                dc.cross(nor).dot(pc).sign() +
-                ad.cross(nor).dot(pd).sign() < 3 {
-                (ba * (ba.dot(pa) / ba.dot(ba)).clamp(0f32, 1f32) - pa).len2()
-                    .min((cb * (cb.dot(pb) / cb.dot(cb)).clamp(0f32, 1f32) - pb).len2())
-                    .min((dc * (dc.dot(pc) / dc.dot(dc)).clamp(0f32, 1f32) - pc).len2())
-                    .min((ad * (ad.dot(pd) / ad.dot(ad)).clamp(0f32, 1f32) - pd).len2())
-            } else {
-                nor.dot(pa) * nor.dot(pa) / nor.dot(nor)
-            }
+    impl Intersections<Sphere> for Frustum {
+        fn intersections(&self, other: &Sphere) -> u32 {
+            (self.classify_sphere(other) != Containment::Outside) as u32
         }
     }
 
     #[cfg(test)]
     mod tests {
-        use super::*;
+        use {super::*, rand::{Rng, SeedableRng, rngs::StdRng}};
 
         #[test]
         fn distance_sphere() {
-            assert_eq!(1f32, Sphere { radius: 0.5 }.distance(&Vec3(0.0, 0.0, 1.5)))
+            assert_eq!(1f32, Sphere::new(Vec3(0.0, 0.0, 0.0), 0.5).distance(&Vec3(0.0, 0.0, 1.5)))
         }
 
         #[test]
@@ -1863,6 +3822,277 @@ pub mod shapes3d {
                 d: Vec3(0.0, 1.0, 0.0),
             }.distance(&Vec3(0.0, 0.0, 1.0)))
         }
+
+        fn approx(a: Vec3<f32>, b: Vec3<f32>) -> bool {
+            (a - b).len() < 1e-4
+        }
+
+        fn random_vec3(rng: &mut StdRng, range: f32) -> Vec3<f32> {
+            Vec3(rng.gen_range(-range..range), rng.gen_range(-range..range), rng.gen_range(-range..range))
+        }
+
+        fn random_obb(rng: &mut StdRng) -> Obb {
+            let aabb = Aabb::from_center(Vec3(0f32, 0f32, 0f32), random_vec3(rng, 2f32).abs() + Vec3(0.1f32, 0.1f32, 0.1f32));
+            let rotation = Quat32::from_axis_angle(random_vec3(rng, 1f32), rng.gen_range(-4f32..4f32));
+            Obb::from_transform(&aabb, &Mat4::from_transform(random_vec3(rng, 5f32), rotation, Vec3(1f32, 1f32, 1f32)))
+        }
+
+        #[test]
+        fn ray_cast() {
+            let aabb = Aabb::new(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0));
+            assert_eq!(aabb.ray_cast(&Ray::new(Vec3(-5.0, 0.5, 0.5), Vec3(1.0, 0.0, 0.0))),
+                Some(RayHit { t: 5.0, point: Vec3(0.0, 0.5, 0.5), normal: Vec3(-1.0, 0.0, 0.0) }));
+            assert_eq!(aabb.ray_cast(&Ray::new(Vec3(0.5, 0.5, 0.5), Vec3(0.0, 2.0, 0.0))),
+                Some(RayHit { t: 0.25, point: Vec3(0.5, 1.0, 0.5), normal: Vec3(0.0, 1.0, 0.0) }));
+            assert_eq!(aabb.ray_cast(&Ray::new(Vec3(-5.0, 2.0, 0.5), Vec3(1.0, 0.0, 0.0))), None);
+            assert_eq!(aabb.ray_cast(&Ray::new(Vec3(5.0, 0.5, 0.5), Vec3(1.0, 0.0, 0.0))), None);
+
+            let sphere = Sphere::new(Vec3(0.0, 0.0, -10.0), 2.0);
+            assert_eq!(sphere.ray_cast(&Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, -1.0))),
+                Some(RayHit { t: 8.0, point: Vec3(0.0, 0.0, -8.0), normal: Vec3(0.0, 0.0, 1.0) }));
+            assert_eq!(sphere.ray_cast(&Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 1.0))), None);
+
+            assert_eq!(Cuboid(Vec3(1.0, 1.0, 1.0)).ray_cast(&Ray::new(Vec3(-5.0, 0.5, 0.5), Vec3(1.0, 0.0, 0.0))),
+                Some(RayHit { t: 4.0, point: Vec3(-1.0, 0.5, 0.5), normal: Vec3(-1.0, 0.0, 0.0) }));
+
+            let cylinder = Cylinder(Vec2(1.0, 2.0));
+            assert_eq!(cylinder.ray_cast(&Ray::new(Vec3(-5.0, 1.0, 0.0), Vec3(1.0, 0.0, 0.0))),
+                Some(RayHit { t: 4.0, point: Vec3(-1.0, 1.0, 0.0), normal: Vec3(-1.0, 0.0, 0.0) }));
+            assert_eq!(cylinder.ray_cast(&Ray::new(Vec3(0.5, 5.0, 0.0), Vec3(0.0, -1.0, 0.0))),
+                Some(RayHit { t: 3.0, point: Vec3(0.5, 2.0, 0.0), normal: Vec3(0.0, 1.0, 0.0) }));
+            assert_eq!(cylinder.ray_cast(&Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 2.0))),
+                Some(RayHit { t: 0.5, point: Vec3(0.0, 0.0, 1.0), normal: Vec3(0.0, 0.0, 1.0) }));
+            assert_eq!(cylinder.ray_cast(&Ray::new(Vec3(-5.0, 2.5, 0.0), Vec3(1.0, 0.0, 0.0))), None);
+            assert_eq!(cylinder.ray_cast(&Ray::new(Vec3(2.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0))), None);
+
+            let triangle = Triangle { a: Vec3(0.0, 0.0, 0.0), b: Vec3(1.0, 0.0, 0.0), c: Vec3(0.0, 1.0, 0.0) };
+            assert_eq!(triangle.ray_cast(&Ray::new(Vec3(0.25, 0.25, 1.0), Vec3(0.0, 0.0, -1.0))),
+                Some(RayHit { t: 1.0, point: Vec3(0.25, 0.25, 0.0), normal: Vec3(0.0, 0.0, 1.0) }));
+            assert_eq!(triangle.ray_cast(&Ray::new(Vec3(0.25, 0.25, -1.0), Vec3(0.0, 0.0, 1.0))).map(|hit| hit.normal),
+                Some(Vec3(0.0, 0.0, -1.0)));
+            assert_eq!(triangle.ray_cast(&Ray::new(Vec3(1.0, 1.0, 1.0), Vec3(0.0, 0.0, -1.0))), None);
+
+            let quad = Quad { a: Vec3(0.0, 0.0, 0.0), b: Vec3(2.0, 0.0, 0.0), c: Vec3(2.0, 1.0, 0.0), d: Vec3(0.0, 1.0, 0.0) };
+            assert_eq!(quad.ray_cast(&Ray::new(Vec3(1.5, 0.25, 1.0), Vec3(0.0, 0.0, -1.0))),
+                Some(RayHit { t: 1.0, point: Vec3(1.5, 0.25, 0.0), normal: Vec3(0.0, 0.0, 1.0) }));
+            assert_eq!(quad.ray_cast(&Ray::new(Vec3(0.5, 0.75, 1.0), Vec3(0.0, 0.0, -1.0))),
+                Some(RayHit { t: 1.0, point: Vec3(0.5, 0.75, 0.0), normal: Vec3(0.0, 0.0, 1.0) }));
+            assert_eq!(quad.ray_cast(&Ray::new(Vec3(2.5, 0.5, 1.0), Vec3(0.0, 0.0, -1.0))), None);
+
+            let plane = Plane::new(Vec3(0.0, 1.0, 0.0), Vec3(0.0, 2.0, 0.0));
+            assert_eq!(plane.ray_cast(&Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0))),
+                Some(RayHit { t: 2.0, point: Vec3(0.0, 2.0, 0.0), normal: Vec3(0.0, -1.0, 0.0) }));
+            assert_eq!(plane.ray_cast(&Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0))), None);
+
+            let rotation = Mat4::from_rotation_axis_angle(Vec3(0.0, 0.0, 1.0), core::f32::consts::FRAC_PI_4);
+            let obb = Obb::from_transform(&Aabb::from_center(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0)), &rotation);
+            let hit = obb.ray_cast(&Ray::new(Vec3(-5.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0))).unwrap();
+            assert!((hit.t - (5.0 - 2f32.sqrt())).abs() < 1e-5, "{:?}", hit);
+            assert!(hit.normal.0 < 0.0 && (hit.normal.len() - 1.0).abs() < 1e-5, "{:?}", hit);
+        }
+
+        #[test]
+        fn ray_cast_surface() {
+            let mut rng = StdRng::seed_from_u64(1);
+            for _ in 0..10_000 {
+                let ray = Ray::new(random_vec3(&mut rng, 10.0), random_vec3(&mut rng, 1.0));
+                let aabb = Aabb::from_points([random_vec3(&mut rng, 5.0), random_vec3(&mut rng, 5.0)]).unwrap();
+                let sphere = Sphere::new(random_vec3(&mut rng, 5.0), rng.gen_range(0.1..5.0));
+                let obb = random_obb(&mut rng);
+                let cuboid = Cuboid(random_vec3(&mut rng, 3.0).abs() + Vec3(0.1, 0.1, 0.1));
+                let cylinder = Cylinder(Vec2(rng.gen_range(0.1..3.0), rng.gen_range(0.1..3.0)));
+                let (a, u, v) = (random_vec3(&mut rng, 5.0), random_vec3(&mut rng, 3.0), random_vec3(&mut rng, 3.0));
+                let quad = Quad { a, b: a + u, c: a + u + v, d: a + v };
+
+                let shapes: [(&dyn Distance<Vec3<f32>>, Option<RayHit>); 6] = [
+                    (&aabb, aabb.ray_cast(&ray)),
+                    (&sphere, sphere.ray_cast(&ray)),
+                    (&obb, obb.ray_cast(&ray)),
+                    (&cuboid, cuboid.ray_cast(&ray)),
+                    (&cylinder, cylinder.ray_cast(&ray)),
+                    (&quad, quad.ray_cast(&ray))
+                ];
+                for (shape, hit) in shapes {
+                    let Some(hit) = hit else { continue };
+                    assert!(hit.t >= 0.0);
+                    assert!(shape.distance(&hit.point).abs() < 1e-3, "{:?} {:?}", ray, hit);
+                    assert!((hit.normal.len() - 1.0).abs() < 1e-4, "{:?} {:?}", ray, hit);
+                    // the normal points out
+                    assert!(shape.distance(&(hit.point + hit.normal * 1e-2)) > 0.0, "{:?} {:?}", ray, hit);
+                    // there is nothing in front of the hit
+                    if shape.distance(&ray.origin) > 0.0 {
+                        assert!(shape.distance(&ray.at(hit.t * 0.99)) > -1e-3, "{:?} {:?}", ray, hit);
+                    }
+                }
+
+                // an axis-aligned box as oriented box
+                let (a, b) = (aabb.ray_cast(&ray), Obb::from(aabb).ray_cast(&ray));
+                assert_eq!(a.is_some(), b.is_some());
+                if let (Some(a), Some(b)) = (a, b) {
+                    assert!((a.t - b.t).abs() < 1e-4 && approx(a.normal, b.normal));
+                }
+            }
+        }
+
+        #[test]
+        fn closest_point() {
+            let mut rng = StdRng::seed_from_u64(2);
+            for _ in 0..10_000 {
+                let p = random_vec3(&mut rng, 10.0);
+                let triangle = Triangle { a: random_vec3(&mut rng, 5.0), b: random_vec3(&mut rng, 5.0), c: random_vec3(&mut rng, 5.0) };
+                let d = triangle.closest_point(&p).distance2(p);
+                assert!((d - triangle.distance2(&p)).abs() <= 1e-3 * d.max(1.0), "{:?} {:?}", triangle, p);
+
+                let obb = random_obb(&mut rng);
+                let q = obb.closest_point(&p);
+                assert!(obb.distance(&q) < 1e-4);
+                assert!((q.distance(p) - obb.distance(&p).max(0.0)).abs() < 1e-4);
+
+                let aabb = Aabb::from(obb);
+                assert!(aabb.distance(&aabb.closest_point(&p)) < 1e-4);
+                assert!((aabb.closest_point(&p).distance(p) - aabb.distance(&p).max(0.0)).abs() < 1e-4);
+            }
+
+            assert_eq!(Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(2.0, 0.0, 0.0)).closest_point(&Vec3(3.0, 1.0, 0.0)), Vec3(3.0, 0.0, 0.0));
+            assert_eq!(Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(2.0, 0.0, 0.0)).closest_point(&Vec3(-3.0, 1.0, 0.0)), Vec3(0.0, 0.0, 0.0));
+            assert_eq!(Plane::new(Vec3(0.0, 0.0, 2.0), Vec3(0.0, 0.0, 1.0)).closest_point(&Vec3(1.0, 2.0, 3.0)), Vec3(1.0, 2.0, 1.0));
+        }
+
+        #[test]
+        fn closest_points_segments() {
+            let a = Segment { a: Vec3(0.0, 0.0, 0.0), b: Vec3(2.0, 0.0, 0.0) };
+            assert_eq!(a.closest_points(&Segment { a: Vec3(1.0, 1.0, -1.0), b: Vec3(1.0, 1.0, 1.0) }), (Vec3(1.0, 0.0, 0.0), Vec3(1.0, 1.0, 0.0)));
+            assert_eq!(a.closest_points(&Segment { a: Vec3(3.0, 1.0, 0.0), b: Vec3(5.0, 1.0, 0.0) }), (Vec3(2.0, 0.0, 0.0), Vec3(3.0, 1.0, 0.0)));
+
+            let mut rng = StdRng::seed_from_u64(3);
+            for _ in 0..200 {
+                let a = Segment { a: random_vec3(&mut rng, 5.0), b: random_vec3(&mut rng, 5.0) };
+                let b = Segment { a: random_vec3(&mut rng, 5.0), b: random_vec3(&mut rng, 5.0) };
+                let (p, q) = a.closest_points(&b);
+                assert!(a.distance(&p) < 1e-4 && b.distance(&q) < 1e-4);
+
+                // no sampled pair is closer
+                let at = |s: &Segment, t: f32| s.a + (s.b - s.a) * t;
+                let min = (0..=50).flat_map(|i| (0..=50).map(move |j| (i as f32 / 50.0, j as f32 / 50.0)))
+                    .map(|(s, t)| at(&a, s).distance(at(&b, t)))
+                    .fold(f32::INFINITY, f32::min);
+                assert!(p.distance(q) <= min + 1e-4, "{:?} {:?}", a, b);
+            }
+        }
+
+        #[test]
+        fn intersections() {
+            let a = Aabb::new(Vec3(0.0, 0.0, 0.0), Vec3(2.0, 2.0, 2.0));
+            let b = Aabb::new(Vec3(1.5, 0.5, 0.5), Vec3(3.0, 1.5, 1.5));
+            assert_eq!(a.contact(&b), Some(Contact { point: Vec3(1.75, 1.0, 1.0), normal: Vec3(1.0, 0.0, 0.0), depth: 0.5 }));
+            assert_eq!(b.contact(&a).map(|c| c.normal), Some(Vec3(-1.0, 0.0, 0.0)));
+            assert!(!a.intersects(&Aabb::new(Vec3(2.5, 0.0, 0.0), Vec3(3.0, 1.0, 1.0))));
+
+            let s = Sphere::new(Vec3(3.0, 1.0, 1.0), 1.5);
+            assert_eq!(a.sphere_contact(&s), Some(Contact { point: Vec3(2.0, 1.0, 1.0), normal: Vec3(1.0, 0.0, 0.0), depth: 0.5 }));
+            assert!(a.intersects(&s) && s.intersects(&a));
+            let inside = Sphere::new(Vec3(1.0, 1.75, 1.0), 0.5);
+            assert_eq!(a.sphere_contact(&inside), Some(Contact { point: Vec3(1.0, 2.0, 1.0), normal: Vec3(0.0, 1.0, 0.0), depth: 0.75 }));
+            assert!(!a.intersects(&Sphere::new(Vec3(3.0, 3.0, 3.0), 1.5)));
+
+            let c = Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).contact(&Sphere::new(Vec3(0.0, 0.0, 1.5), 1.0)).unwrap();
+            assert_eq!(c, Contact { point: Vec3(0.0, 0.0, 0.75), normal: Vec3(0.0, 0.0, 1.0), depth: 0.5 });
+
+            let unit = Aabb::from_center(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0));
+            let rotation = Mat4::from_rotation_axis_angle(Vec3(0.0, 0.0, 1.0), core::f32::consts::FRAC_PI_4);
+            let rotated = |x: f32| Obb::from_transform(&unit, &(Mat4::from_translation(Vec3(x, 0.0, 0.0)) * rotation));
+            assert!(rotated(2.3).intersects(&Obb::from(unit)));
+            assert!(!rotated(2.5).intersects(&Obb::from(unit)));
+            assert!(rotated(2.3).intersects(&unit) && unit.intersects(&rotated(2.3)));
+
+            // separated only by the cross product of two edges
+            let edge = |axis: Vec3<f32>| Mat4::from_rotation_axis_angle(axis, core::f32::consts::FRAC_PI_4);
+            let a = Obb::from_transform(&unit, &edge(Vec3(1.0, 0.0, 0.0)));
+            let b = |z: f32| Obb::from_transform(&unit, &(Mat4::from_translation(Vec3(0.0, 0.0, z)) * edge(Vec3(0.0, 1.0, 0.0))));
+            assert!(a.intersects(&b(2.7)));
+            assert!(!a.intersects(&b(2.9)));
+
+            let mut rng = StdRng::seed_from_u64(4);
+            for _ in 0..10_000 {
+                let (a, b) = (random_obb(&mut rng), random_obb(&mut rng));
+                if !Aabb::from(a).intersects(&Aabb::from(b)) {
+                    assert!(!a.intersects(&b), "{:?} {:?}", a, b);
+                }
+                if a.contains(b.center) || b.contains(a.center) {
+                    assert!(a.intersects(&b), "{:?} {:?}", a, b);
+                }
+
+                let sphere = Sphere::new(random_vec3(&mut rng, 5.0), rng.gen_range(0.1..3.0));
+                assert_eq!(a.intersects(&sphere), sphere.intersects(&a));
+                match a.sphere_contact(&sphere) {
+                    Some(c) => {
+                        assert!(a.distance(&sphere.center) <= sphere.radius + 1e-4);
+                        assert!(a.distance(&c.point) < 1e-4 && (c.normal.len() - 1.0).abs() < 1e-4);
+                        // separated after moving the sphere along the normal
+                        let moved = Sphere::new(sphere.center + c.normal * (c.depth + 1e-2), sphere.radius);
+                        assert!(!a.intersects(&moved), "{:?} {:?} {:?}", a, sphere, c);
+                    }
+                    None => assert!(a.distance(&sphere.center) > sphere.radius - 1e-4)
+                }
+            }
+        }
+
+        #[test]
+        fn transform_bounds() {
+            let rotation = Mat4::from_rotation_axis_angle(Vec3(0.0, 0.0, 1.0), core::f32::consts::FRAC_PI_4);
+            let aabb = Aabb::from_center(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0))
+                .transform(&(Mat4::from_translation(Vec3(1.0, 2.0, 3.0)) * rotation));
+            assert!(approx(aabb.center(), Vec3(1.0, 2.0, 3.0)));
+            assert!(approx(aabb.half_extents(), Vec3(2f32.sqrt(), 2f32.sqrt(), 1.0)));
+
+            let sphere = Sphere::from_points(&[Vec3(-1.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0), Vec3(0.0, 0.5, 0.0)]).unwrap();
+            assert_eq!(sphere, Sphere::new(Vec3(0.0, 0.25, 0.0), 1.0625f32.sqrt()));
+            let sphere = sphere.transform(&Mat4::from_scale(Vec3(1.0, 3.0, 2.0)));
+            assert_eq!(sphere, Sphere::new(Vec3(0.0, 0.75, 0.0), 1.0625f32.sqrt() * 3.0));
+            assert_eq!(Aabb::from_points([]), None);
+        }
+
+        #[test]
+        fn frustum() {
+            let view = Mat4::look_at(Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0));
+            let fov = core::f32::consts::FRAC_PI_2;
+            let projections = [
+                (Mat4::from_perspective(fov, 1.0, 0.1, 100.0, true), true, (0.0, 1.0)),
+                (Mat4::from_perspective(fov, 1.0, 0.1, 100.0, false), false, (-1.0, 1.0)),
+                (Mat4::from_perspective_reversed_z(fov, 1.0, 0.1, 100.0), true, (1.0, 0.0)),
+                (Mat4::from_perspective_infinite_reversed_z(fov, 1.0, 0.1), true, (1.0, 0.0)),
+                (Mat4::from_perspective_infinite(fov, 1.0, 0.1, false), false, (-1.0, 1.0))
+            ];
+
+            for (i, (projection, z_zero_to_one, (near, far))) in projections.into_iter().enumerate() {
+                let frustum = Frustum::from_matrix(&(projection * view), z_zero_to_one);
+                let infinite = i >= 3;
+                assert!(frustum.contains(Vec3(0.0, 0.0, 0.0)), "{}", i);
+                assert!(!frustum.contains(Vec3(0.0, 0.0, 6.0)), "{}", i);
+                assert!(!frustum.contains(Vec3(7.0, 0.0, 0.0)), "{}", i);
+                assert_eq!(frustum.contains(Vec3(0.0, 0.0, -200.0)), infinite, "{}", i);
+
+                let sphere = |x: f32, z: f32| Sphere::new(Vec3(x, 0.0, z), 1.0);
+                assert_eq!(frustum.classify_sphere(&sphere(0.0, 0.0)), Containment::Inside, "{}", i);
+                assert_eq!(frustum.classify_sphere(&sphere(0.0, 10.0)), Containment::Outside, "{}", i);
+                assert_eq!(frustum.classify_sphere(&sphere(50.0, 0.0)), Containment::Outside, "{}", i);
+                // the sides are at 45 degrees
+                assert_eq!(frustum.classify_sphere(&sphere(5.0, 0.0)), Containment::Intersecting, "{}", i);
+                assert_eq!(frustum.classify_sphere(&sphere(0.0, -95.0)), if infinite { Containment::Inside } else { Containment::Intersecting }, "{}", i);
+
+                let aabb = |x: f32, z: f32| Aabb::from_center(Vec3(x, 0.0, z), Vec3(1.0, 1.0, 1.0));
+                assert_eq!(frustum.classify_aabb(&aabb(0.0, 0.0)), Containment::Inside, "{}", i);
+                assert_eq!(frustum.classify_aabb(&aabb(0.0, 10.0)), Containment::Outside, "{}", i);
+                assert_eq!(frustum.classify_aabb(&aabb(5.5, 0.0)), Containment::Intersecting, "{}", i);
+                assert!(frustum.intersects(&aabb(0.0, 0.0)) && !frustum.intersects(&aabb(50.0, 0.0)), "{}", i);
+                assert_eq!(frustum.classify_obb(&Obb::from(aabb(5.5, 0.0))), Containment::Intersecting, "{}", i);
+
+                // picking through the center of the screen
+                let ray = Ray::from_ndc(Vec2(0.0, 0.0), &(projection * view).inverse().unwrap(), near, far);
+                assert!(approx(ray.origin, Vec3(0.0, 0.0, 4.9)), "{} {:?}", i, ray);
+                assert!(approx(ray.dir, Vec3(0.0, 0.0, -1.0)), "{} {:?}", i, ray);
+            }
+        }
     }
 }
 
@@ -1959,10 +4189,12 @@ pub mod types {
     + core::ops::Sub<Output = Self> + core::ops::SubAssign
     + core::ops::Mul<Output = Self> + core::ops::MulAssign
     + core::ops::Div<Output = Self>+ core::ops::DivAssign
+    + core::ops::Neg<Output = Self>
     {
         const INF: Self;
         const NEG_INF: Self;
         const PI: Self;
+        const EPSILON: Self;
 
         fn sin(self) -> Self;
 
@@ -1991,6 +4223,7 @@ pub mod types {
         const INF:     Self = core::f32::INFINITY;
         const NEG_INF: Self = core::f32::NEG_INFINITY;
         const PI:      Self = core::f32::consts::PI;
+        const EPSILON: Self = f32::EPSILON;
 
         fn sin(self) -> Self { self.sin() }
 
@@ -2019,6 +4252,7 @@ pub mod types {
         const INF:     Self = core::f64::INFINITY;
         const NEG_INF: Self = core::f64::NEG_INFINITY;
         const PI:      Self = core::f64::consts::PI;
+        const EPSILON: Self = f64::EPSILON;
 
         fn sin(self) -> Self { self.sin() }
 
@@ -2060,12 +4294,26 @@ pub mod ops {
     }
 
     pub trait Intersections<T> {
+        /// Returns the number of separate intersections, which is at most one for two convex
+        /// volumes, touching shapes intersect.
         fn intersections(&self, _: &T) -> u32;
 
         fn intersects(&self, v: &T) -> bool {
             self.intersections(v) > 0
         }
     }
+
+    pub trait ClosestPoint<T> {
+        /// Returns the point of the shape that is closest to the given point.
+        fn closest_point(&self, _: &T) -> T;
+    }
+
+    pub trait RayCast<R> {
+        type Hit;
+
+        /// Returns the first hit in front of the origin of the ray.
+        fn ray_cast(&self, _: &R) -> Option<Self::Hit>;
+    }
 }
 
 pub mod sdf {
@@ -2473,15 +4721,15 @@ pub mod sdf {
         #[test]
         fn calc_normal_sphere() {
             const EPS: f32 = 0.1;
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(1.0, 0.0, 0.0), EPS), Vec3(1.0, 0.0, 0.0));
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(0.0, 1.0, 0.0), EPS), Vec3(0.0, 1.0, 0.0));
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(0.0, 0.0, 1.0), EPS), Vec3(0.0, 0.0, 1.0));
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(-1.0, 0.0, 0.0), EPS), Vec3(-1.0, 0.0, 0.0));
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(0.0, -1.0, 0.0), EPS), Vec3(0.0, -1.0, 0.0));
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(0.0, 0.0, -1.0), EPS), Vec3(0.0, 0.0, -1.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(1.0, 0.0, 0.0), EPS), Vec3(1.0, 0.0, 0.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(0.0, 1.0, 0.0), EPS), Vec3(0.0, 1.0, 0.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(0.0, 0.0, 1.0), EPS), Vec3(0.0, 0.0, 1.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(-1.0, 0.0, 0.0), EPS), Vec3(-1.0, 0.0, 0.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(0.0, -1.0, 0.0), EPS), Vec3(0.0, -1.0, 0.0));
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(0.0, 0.0, -1.0), EPS), Vec3(0.0, 0.0, -1.0));
 
             let ang: f32 = 45f32.to_radians();
-            assert_eq!(Sphere { radius: 1.0 }.calc_normal(Vec3(ang.cos(), ang.sin(), 0.0), EPS),
+            assert_eq!(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0).calc_normal(Vec3(ang.cos(), ang.sin(), 0.0), EPS),
                        Vec3(core::f32::consts::FRAC_1_SQRT_2, core::f32::consts::FRAC_1_SQRT_2, 0.0));
         }
     }
@@ -0,0 +1,61 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Read-only memory mappings of files, kept separate so that the crates that read the mapped
+//! data can forbid unsafe code.
+
+#![warn(clippy::all)]
+
+use std::{io, fmt, ops::Deref, path::Path};
+
+/// A read-only memory mapping of a file.
+pub struct Mapping(memmap::Mmap);
+
+impl Mapping {
+	/// Maps the whole file. The file must not be modified while it is mapped, as changes
+	/// would be visible through, or truncation would invalidate, the mapping.
+	pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+		let file = std::fs::File::open(path)?;
+		// SAFETY: the mapping is read-only and owned by `Self`, slices into it can't outlive it
+		unsafe { memmap::Mmap::map(&file) }.map(Self)
+	}
+	
+	/// Keeps the mapping alive until the end of the program, for readers that need `'static`
+	/// data, like the mesh sources of the scene.
+	pub fn leak(self) -> &'static [u8] {
+		Box::leak(Box::new(self.0))
+	}
+}
+
+impl Deref for Mapping {
+	type Target = [u8];
+	
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl fmt::Debug for Mapping {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Mapping").field("len", &self.0.len()).finish()
+	}
+}
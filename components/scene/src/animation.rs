@@ -25,16 +25,21 @@ use {super::*, vk::VkFormat};
 pub type AnimationSource = dyn Source<(AnimationDescriptor, Box<dyn std::io::Read>)>;
 pub type AnimationSrc    = Box<AnimationSource>;
 
+/// The data of an animation consists of all tracks in order, each track stores `frame_count`
+/// frames of `vertex_count` interleaved vertices.
 #[derive(Clone, Debug)]
 pub struct AnimationDescriptor {
-	pub tracks: Vec<Track>
+	pub frame_rate: f32,
+	pub tracks:     Vec<Track>
 }
 
 #[derive(Clone, Debug)]
 pub struct Track {
+	/// The entity animated by this track, `None` for the entity the animation is attached to.
+	pub target:       Option<Entity>,
 	pub frame_count:  usize,
 	pub vertex_count: usize,
-	pub attributes:   [MeshAttribute; 8]
+	pub attributes:   [TrackAttribute; 8]
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -20,7 +20,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use super::*;
+use {
+	super::*,
+	engine_core::BoxedFuture,
+	file_formats::{flac::FlacReader, opus::{OggOpusReader, SAMPLE_RATE}},
+	vk::VkFormat,
+	std::{io, sync::Arc}
+};
 
 pub type Audio = Box<dyn Source<Box<dyn AudioData>>>;
 
@@ -34,6 +40,163 @@ pub struct AudioDescriptor {
 	pub format:    vk::VkFormat
 }
 
+/// Returns the format of interleaved signed integer samples, there may be up to four channels.
+pub fn audio_format(channels: usize, sample_width: usize) -> Option<VkFormat> {
+	Some(match (channels, sample_width) {
+		(1, 1) => VkFormat::VK_FORMAT_R8_SINT,
+		(2, 1) => VkFormat::VK_FORMAT_R8G8_SINT,
+		(3, 1) => VkFormat::VK_FORMAT_R8G8B8_SINT,
+		(4, 1) => VkFormat::VK_FORMAT_R8G8B8A8_SINT,
+		(1, 2) => VkFormat::VK_FORMAT_R16_SINT,
+		(2, 2) => VkFormat::VK_FORMAT_R16G16_SINT,
+		(3, 2) => VkFormat::VK_FORMAT_R16G16B16_SINT,
+		(4, 2) => VkFormat::VK_FORMAT_R16G16B16A16_SINT,
+		(1, 4) => VkFormat::VK_FORMAT_R32_SINT,
+		(2, 4) => VkFormat::VK_FORMAT_R32G32_SINT,
+		(3, 4) => VkFormat::VK_FORMAT_R32G32B32_SINT,
+		(4, 4) => VkFormat::VK_FORMAT_R32G32B32A32_SINT,
+		_      => return None
+	})
+}
+
+/// Decodes a FLAC stream while reading, samples are scaled up to 8, 16 or 32 bits.
+pub struct FlacAudio<R> {
+	reader:     FlacReader<R>,
+	descriptor: AudioDescriptor
+}
+
+impl<R: io::Read + io::Seek> FlacAudio<R> {
+	pub fn new(reader: R) -> io::Result<Self> {
+		let reader = FlacReader::new(reader)?;
+		let format = audio_format(reader.info.channels as usize, reader.sample_width())
+			.ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "unsupported number of channels"))?;
+		
+		Ok(Self {
+			descriptor: AudioDescriptor { frequency: reader.info.sample_rate as usize, format },
+			reader
+		})
+	}
+	
+	pub fn reader(&mut self) -> &mut FlacReader<R> {
+		&mut self.reader
+	}
+}
+
+impl<R: io::Read + io::Seek> io::Read for FlacAudio<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.reader.read(buf)
+	}
+}
+
+impl<R: io::Read + io::Seek> io::Seek for FlacAudio<R> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.reader.seek(pos)
+	}
+}
+
+impl<R: io::Read + io::Seek> AudioData for FlacAudio<R> {
+	fn get_descriptor(&mut self) -> AudioDescriptor {
+		self.descriptor
+	}
+}
+
+/// An audio clip stored as a FLAC file, which is decoded when read.
+#[derive(Clone, Debug)]
+pub struct FlacAudioSource {
+	pub data: Arc<[u8]>
+}
+
+impl FlacAudioSource {
+	pub fn new(data: impl Into<Arc<[u8]>>) -> io::Result<Self> {
+		let data = data.into();
+		FlacAudio::new(io::Cursor::new(data.clone()))?;
+		Ok(Self { data })
+	}
+	
+	pub fn read(mut reader: impl io::Read) -> io::Result<Self> {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+		Self::new(data)
+	}
+}
+
+impl Source<Box<dyn AudioData>> for FlacAudioSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<Box<dyn AudioData>>> {
+		Box::pin(async move {
+			Ok(Box::new(FlacAudio::new(io::Cursor::new(self.data.clone()))?) as _)
+		})
+	}
+}
+
+/// Decodes an Ogg Opus stream while reading, samples are 16 bit at 48 kHz.
+pub struct OpusAudio<R> {
+	reader:     OggOpusReader<R>,
+	descriptor: AudioDescriptor
+}
+
+impl<R: io::Read + io::Seek> OpusAudio<R> {
+	pub fn new(reader: R) -> io::Result<Self> {
+		let reader = OggOpusReader::new(reader)?;
+		let format = audio_format(reader.channels(), 2)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "unsupported number of channels"))?;
+		
+		Ok(Self {
+			descriptor: AudioDescriptor { frequency: SAMPLE_RATE as usize, format },
+			reader
+		})
+	}
+	
+	pub fn reader(&mut self) -> &mut OggOpusReader<R> {
+		&mut self.reader
+	}
+}
+
+impl<R: io::Read + io::Seek> io::Read for OpusAudio<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.reader.read(buf)
+	}
+}
+
+impl<R: io::Read + io::Seek> io::Seek for OpusAudio<R> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.reader.seek(pos)
+	}
+}
+
+impl<R: io::Read + io::Seek> AudioData for OpusAudio<R> {
+	fn get_descriptor(&mut self) -> AudioDescriptor {
+		self.descriptor
+	}
+}
+
+/// An audio clip stored as an Ogg Opus file, which is decoded when read.
+#[derive(Clone, Debug)]
+pub struct OpusAudioSource {
+	pub data: Arc<[u8]>
+}
+
+impl OpusAudioSource {
+	pub fn new(data: impl Into<Arc<[u8]>>) -> io::Result<Self> {
+		let data = data.into();
+		OpusAudio::new(io::Cursor::new(data.clone()))?;
+		Ok(Self { data })
+	}
+	
+	pub fn read(mut reader: impl io::Read) -> io::Result<Self> {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+		Self::new(data)
+	}
+}
+
+impl Source<Box<dyn AudioData>> for OpusAudioSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<Box<dyn AudioData>>> {
+		Box::pin(async move {
+			Ok(Box::new(OpusAudio::new(io::Cursor::new(self.data.clone()))?) as _)
+		})
+	}
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct AudioSource {
 	pub handle:       Handle<Audio>,
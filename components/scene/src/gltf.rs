@@ -0,0 +1,1014 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Conversion between glTF documents and scene entities.
+
+use {
+	super::*,
+	engine_core::BoxedFuture,
+	file_formats::{gltf::{self, AccessorType, ComponentType, MeshPrimitiveRenderingMode}, utils::{DeError, SerError}},
+	vk::{VkFormat::{self, *}, VkPrimitiveTopology::{self, *}},
+	std::{io, sync::Arc, collections::HashMap}
+};
+
+/// The glTF attribute semantics that are imported, in the order of the resulting attributes.
+const ATTRIBUTES: [&str; 4] = ["POSITION", "NORMAL", "COLOR_0", "TEXCOORD_0"];
+
+/// Upper bound for the number of resampled frames per animation, about 9.7 hours at 30 fps.
+const MAX_FRAME_COUNT: usize = 1 << 20;
+
+/// Returns the vertex format that matches the layout of an accessor.
+pub fn vertex_format(r#type: AccessorType, component_type: ComponentType, normalized: bool) -> Option<VkFormat> {
+	use {AccessorType::*, ComponentType::*};
+	Some(match (r#type, component_type, normalized) {
+		(Scalar, Float, _)             => VK_FORMAT_R32_SFLOAT,
+		(Vec2,   Float, _)             => VK_FORMAT_R32G32_SFLOAT,
+		(Vec3,   Float, _)             => VK_FORMAT_R32G32B32_SFLOAT,
+		(Vec4,   Float, _)             => VK_FORMAT_R32G32B32A32_SFLOAT,
+		(Scalar, UnsignedInt, _)       => VK_FORMAT_R32_UINT,
+		(Vec2,   UnsignedInt, _)       => VK_FORMAT_R32G32_UINT,
+		(Vec3,   UnsignedInt, _)       => VK_FORMAT_R32G32B32_UINT,
+		(Vec4,   UnsignedInt, _)       => VK_FORMAT_R32G32B32A32_UINT,
+		(Scalar, UnsignedShort, true)  => VK_FORMAT_R16_UNORM,
+		(Vec2,   UnsignedShort, true)  => VK_FORMAT_R16G16_UNORM,
+		(Vec3,   UnsignedShort, true)  => VK_FORMAT_R16G16B16_UNORM,
+		(Vec4,   UnsignedShort, true)  => VK_FORMAT_R16G16B16A16_UNORM,
+		(Scalar, UnsignedShort, false) => VK_FORMAT_R16_UINT,
+		(Vec2,   UnsignedShort, false) => VK_FORMAT_R16G16_UINT,
+		(Vec3,   UnsignedShort, false) => VK_FORMAT_R16G16B16_UINT,
+		(Vec4,   UnsignedShort, false) => VK_FORMAT_R16G16B16A16_UINT,
+		(Scalar, Short, true)          => VK_FORMAT_R16_SNORM,
+		(Vec2,   Short, true)          => VK_FORMAT_R16G16_SNORM,
+		(Vec3,   Short, true)          => VK_FORMAT_R16G16B16_SNORM,
+		(Vec4,   Short, true)          => VK_FORMAT_R16G16B16A16_SNORM,
+		(Scalar, Short, false)         => VK_FORMAT_R16_SINT,
+		(Vec2,   Short, false)         => VK_FORMAT_R16G16_SINT,
+		(Vec3,   Short, false)         => VK_FORMAT_R16G16B16_SINT,
+		(Vec4,   Short, false)         => VK_FORMAT_R16G16B16A16_SINT,
+		(Scalar, UnsignedByte, true)   => VK_FORMAT_R8_UNORM,
+		(Vec2,   UnsignedByte, true)   => VK_FORMAT_R8G8_UNORM,
+		(Vec3,   UnsignedByte, true)   => VK_FORMAT_R8G8B8_UNORM,
+		(Vec4,   UnsignedByte, true)   => VK_FORMAT_R8G8B8A8_UNORM,
+		(Scalar, UnsignedByte, false)  => VK_FORMAT_R8_UINT,
+		(Vec2,   UnsignedByte, false)  => VK_FORMAT_R8G8_UINT,
+		(Vec3,   UnsignedByte, false)  => VK_FORMAT_R8G8B8_UINT,
+		(Vec4,   UnsignedByte, false)  => VK_FORMAT_R8G8B8A8_UINT,
+		(Scalar, Byte, true)           => VK_FORMAT_R8_SNORM,
+		(Vec2,   Byte, true)           => VK_FORMAT_R8G8_SNORM,
+		(Vec3,   Byte, true)           => VK_FORMAT_R8G8B8_SNORM,
+		(Vec4,   Byte, true)           => VK_FORMAT_R8G8B8A8_SNORM,
+		(Scalar, Byte, false)          => VK_FORMAT_R8_SINT,
+		(Vec2,   Byte, false)          => VK_FORMAT_R8G8_SINT,
+		(Vec3,   Byte, false)          => VK_FORMAT_R8G8B8_SINT,
+		(Vec4,   Byte, false)          => VK_FORMAT_R8G8B8A8_SINT,
+		_ => return None
+	})
+}
+
+/// The inverse of `vertex_format`.
+pub fn accessor_format(format: VkFormat) -> Option<(AccessorType, ComponentType, bool)> {
+	use {AccessorType::*, ComponentType::*};
+	[Scalar, Vec2, Vec3, Vec4].iter()
+		.flat_map(|ty| [Float, UnsignedInt, UnsignedShort, Short, UnsignedByte, Byte].iter()
+			.flat_map(move |ct| [(*ty, *ct, false), (*ty, *ct, true)]))
+		.find(|(ty, ct, normalized)| vertex_format(*ty, *ct, *normalized) == Some(format))
+}
+
+pub(crate) fn attribute_size(attribute: &MeshAttribute) -> Option<usize> {
+	match attribute {
+		MeshAttribute::Pos(format)
+		| MeshAttribute::Tex(format)
+		| MeshAttribute::Color(format)
+		| MeshAttribute::Normal(format) => accessor_format(*format).map(|(ty, ct, _)| ty.element_size(ct)),
+		MeshAttribute::Empty => None
+	}
+}
+
+fn invalid_data(msg: &str) -> DeError {
+	DeError::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+/// A mesh that was imported from a glTF primitive. Indexed primitives are expanded, so the
+/// data contains every vertex in draw order, with interleaved attributes.
+#[derive(Clone, Debug)]
+pub struct GltfMeshSource {
+	pub descriptor: MeshDescriptor,
+	pub topology:   VkPrimitiveTopology,
+	pub data:       Arc<[u8]>
+}
+
+impl GltfMeshSource {
+	/// Imports a primitive of a mesh, `buffers` are the buffers loaded by `Document::load_buffers`.
+	/// Attributes without a corresponding `MeshAttribute` are ignored.
+	pub fn new(doc: &gltf::Document, buffers: &[Vec<u8>], mesh: usize, primitive: usize) -> Result<Self, DeError> {
+		let primitive = doc.meshes.get(mesh)
+			.and_then(|mesh| mesh.primitives.get(primitive))
+			.ok_or_else(|| invalid_data("mesh primitive index out of bounds"))?;
+		
+		let mut attributes = Vec::new();
+		let mut readers = Vec::new();
+		
+		for name in ATTRIBUTES {
+			let reader = match primitive.attributes.get(name) {
+				Some(index) => doc.accessor(*index, buffers)?,
+				None => continue
+			};
+			
+			let format = vertex_format(reader.accessor_type(), reader.component_type(), reader.normalized())
+				.ok_or_else(|| invalid_data("unsupported vertex attribute format"))?;
+			
+			attributes.push(match name {
+				"POSITION"   => MeshAttribute::Pos(format),
+				"NORMAL"     => MeshAttribute::Normal(format),
+				"COLOR_0"    => MeshAttribute::Color(format),
+				_            => MeshAttribute::Tex(format)
+			});
+			readers.push(reader);
+		}
+		
+		let count = readers.first().map_or(0, |reader| reader.len());
+		if readers.iter().any(|reader| reader.len() != count) {
+			return Err(invalid_data("vertex attributes have different lengths"));
+		}
+		
+		let mut indices = match primitive.indices {
+			Some(index) => doc.accessor(index, buffers)?.read_u32()?,
+			None => (0..count as u32).collect()
+		};
+		
+		if indices.iter().any(|i| *i as usize >= count) {
+			return Err(invalid_data("vertex index out of bounds"));
+		}
+		
+		let topology = match primitive.mode {
+			MeshPrimitiveRenderingMode::Points        => VK_PRIMITIVE_TOPOLOGY_POINT_LIST,
+			MeshPrimitiveRenderingMode::Lines         => VK_PRIMITIVE_TOPOLOGY_LINE_LIST,
+			MeshPrimitiveRenderingMode::LineStrip     => VK_PRIMITIVE_TOPOLOGY_LINE_STRIP,
+			MeshPrimitiveRenderingMode::Triangles     => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST,
+			MeshPrimitiveRenderingMode::TriangleStrip => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_STRIP,
+			MeshPrimitiveRenderingMode::TriangleFan   => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_FAN,
+			MeshPrimitiveRenderingMode::LineLoop      => {
+				// there is no line loop topology, close the loop explicitly
+				if let Some(first) = indices.first().copied() {
+					indices.push(first);
+				}
+				VK_PRIMITIVE_TOPOLOGY_LINE_STRIP
+			}
+		};
+		
+		let stride = readers.iter().map(|reader| reader.element_size()).sum::<usize>();
+		let mut data = Vec::with_capacity(stride * indices.len());
+		
+		for index in &indices {
+			for reader in &readers {
+				match reader.element(*index as usize) {
+					Some(element) => data.extend_from_slice(element),
+					None => data.resize(data.len() + reader.element_size(), 0)
+				}
+			}
+		}
+		
+		Ok(Self {
+			descriptor: MeshDescriptor::new(indices.len(), &attributes),
+			topology,
+			data:       data.into()
+		})
+	}
+	
+	/// Imports all primitives of all meshes, indexed by mesh and primitive.
+	pub fn load_all(doc: &gltf::Document, buffers: &[Vec<u8>]) -> Result<Vec<Vec<Self>>, DeError> {
+		(0..doc.meshes.len())
+			.map(|mesh| (0..doc.meshes[mesh].primitives.len())
+				.map(|primitive| Self::new(doc, buffers, mesh, primitive))
+				.collect())
+			.collect()
+	}
+}
+
+impl Source<(MeshDescriptor, Box<dyn MeshReader>)> for GltfMeshSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<(MeshDescriptor, Box<dyn MeshReader>)>> {
+		Box::pin(async move {
+			Ok((self.descriptor.clone(), Box::new(GltfMeshReader(self.data.clone(), 0)) as _))
+		})
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfMeshReader(Arc<[u8]>, usize);
+
+impl MeshReader for GltfMeshReader {}
+
+impl io::Read for GltfMeshReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = (&self.0[self.1..]).read(buf)?;
+		self.1 += len;
+		Ok(len)
+	}
+}
+
+/// Appends a mesh with a single primitive to a document and returns the index of the new mesh.
+/// `data` must contain interleaved vertices as described by `descriptor`, the vertex data
+/// is stored in the binary buffer of the document.
+pub fn export_mesh(
+	doc:        &mut gltf::Document,
+	descriptor: &MeshDescriptor,
+	topology:   VkPrimitiveTopology,
+	data:       &[u8]
+) -> Result<usize, SerError> {
+	let mode = match topology {
+		VK_PRIMITIVE_TOPOLOGY_POINT_LIST     => MeshPrimitiveRenderingMode::Points,
+		VK_PRIMITIVE_TOPOLOGY_LINE_LIST      => MeshPrimitiveRenderingMode::Lines,
+		VK_PRIMITIVE_TOPOLOGY_LINE_STRIP     => MeshPrimitiveRenderingMode::LineStrip,
+		VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST  => MeshPrimitiveRenderingMode::Triangles,
+		VK_PRIMITIVE_TOPOLOGY_TRIANGLE_STRIP => MeshPrimitiveRenderingMode::TriangleStrip,
+		VK_PRIMITIVE_TOPOLOGY_TRIANGLE_FAN   => MeshPrimitiveRenderingMode::TriangleFan,
+		_ => return Err(SerError::Custom(format!("topology {:?} is not supported by glTF", topology)))
+	};
+	
+	let sizes = descriptor.attributes.iter()
+		.map(|attribute| attribute_size(attribute)
+			.ok_or_else(|| SerError::Custom(format!("attribute {:?} is not supported by glTF", attribute))))
+		.collect::<Result<Vec<_>, _>>()?;
+	let stride = sizes.iter().sum::<usize>();
+	
+	if data.len() < stride * descriptor.vertex_count {
+		return Err(SerError::Custom("not enough vertex data".to_string()));
+	}
+	
+	let mut attributes = HashMap::new();
+	let mut offset = 0;
+	
+	for (attribute, size) in descriptor.attributes.iter().zip(sizes) {
+		let (name, format) = match attribute {
+			MeshAttribute::Pos(format)    => ("POSITION", *format),
+			MeshAttribute::Normal(format) => ("NORMAL", *format),
+			MeshAttribute::Color(format)  => ("COLOR_0", *format),
+			MeshAttribute::Tex(format)    => ("TEXCOORD_0", *format),
+			MeshAttribute::Empty          => unreachable!()
+		};
+		
+		let (r#type, component_type, normalized) = accessor_format(format).unwrap();
+		let bytes = data.chunks_exact(stride)
+			.take(descriptor.vertex_count)
+			.flat_map(|vertex| &vertex[offset..offset + size])
+			.copied()
+			.collect::<Vec<_>>();
+		let accessor = doc.push_accessor(&bytes, r#type, component_type, normalized, Some(gltf::TARGET_ARRAY_BUFFER))?;
+		attributes.insert(name.to_string(), accessor);
+		offset += size;
+	}
+	
+	doc.meshes.push(gltf::Mesh {
+		primitives: vec![gltf::Primitive { mode, attributes, ..gltf::Primitive::default() }],
+		..gltf::Mesh::default()
+	});
+	Ok(doc.meshes.len() - 1)
+}
+
+/// An animation that was imported from glTF. Node animations are resampled at a fixed frame
+/// rate, every animated node has a track with a position, rotation and scale per frame.
+#[derive(Clone, Debug)]
+pub struct GltfAnimationSource {
+	pub descriptor: AnimationDescriptor,
+	pub data:       Arc<[u8]>
+}
+
+impl Source<(AnimationDescriptor, Box<dyn io::Read>)> for GltfAnimationSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<(AnimationDescriptor, Box<dyn io::Read>)>> {
+		Box::pin(async move {
+			Ok((self.descriptor.clone(), Box::new(io::Cursor::new(self.data.clone())) as _))
+		})
+	}
+}
+
+/// The entities created by `GltfImporter::import`, indexed like the corresponding arrays of the document.
+#[derive(Clone, Debug, Default)]
+pub struct GltfScene {
+	pub roots:      Vec<Entity>,
+	/// `None` for nodes that are not part of the imported scene.
+	pub nodes:      Vec<Option<Entity>>,
+	/// The mesh entities of every primitive of every mesh.
+	pub meshes:     Vec<Vec<Handle<Box<Mesh>>>>,
+	pub materials:  Vec<Handle<Material>>,
+	/// Texture entities only carry a `Name`, the texture source must be attached by the caller.
+	pub textures:   Vec<Handle<Box<Texture>>>,
+	pub animations: Vec<Handle<Box<AnimationSource>>>
+}
+
+/// Spawns the nodes of a glTF scene as entities.
+///
+/// Nodes get a `Transform` and optionally a `Name`, `Parent`, `Camera`, `Light` (KHR_lights_punctual),
+/// `Joints` and `JointsInverseBindMatrices`. Nodes with a single mesh primitive get a `Handle<Box<Mesh>>`
+/// and a `Handle<Material>`, for multiple primitives a child entity is created for each primitive.
+/// Since `Transform` only supports uniform scaling, non-uniform scales are replaced by their mean.
+#[derive(Copy, Clone, Debug)]
+pub struct GltfImporter<'a> {
+	doc:        &'a gltf::Document,
+	buffers:    &'a [Vec<u8>],
+	scene:      Option<usize>,
+	frame_rate: f32
+}
+
+impl<'a> GltfImporter<'a> {
+	/// `buffers` are the buffers loaded by `Document::load_buffers`.
+	pub fn new(doc: &'a gltf::Document, buffers: &'a [Vec<u8>]) -> Self {
+		Self { doc, buffers, scene: doc.scene, frame_rate: 30.0 }
+	}
+	
+	/// Imports the given scene instead of the default scene of the document.
+	pub fn scene(mut self, scene: usize) -> Self {
+		self.scene = Some(scene);
+		self
+	}
+	
+	/// Sets the frame rate at which animations are resampled, the default is 30.
+	pub fn frame_rate(mut self, frame_rate: f32) -> Self {
+		self.frame_rate = frame_rate;
+		self
+	}
+	
+	pub fn import(&self, world: &World, children: &mut ChildrenAccelStorage) -> Result<GltfScene, DeError> {
+		let doc = self.doc;
+		let mut scene = GltfScene {
+			nodes: vec![None; doc.nodes.len()],
+			..GltfScene::default()
+		};
+		
+		scene.textures = doc.textures.iter()
+			.enumerate()
+			.map(|(i, texture)| {
+				let name = match texture.source.and_then(|source| doc.images.get(source)) {
+					Some(gltf::Image::Uri { uri }) if !uri.starts_with("data:") => uri.clone(),
+					_ => format!("texture{}", i)
+				};
+				Handle::new(world.add_entity((Name(name),)))
+			})
+			.collect();
+		
+		scene.materials = doc.materials.iter()
+			.map(|material| {
+				let entity = world.add_entity((self.material(material, &scene.textures)?,));
+				if let Some(name) = &material.name {
+					world.add_component(entity, Name(name.clone()));
+				}
+				Ok(Handle::new(entity))
+			})
+			.collect::<Result<_, DeError>>()?;
+		
+		for (i, mesh) in doc.meshes.iter().enumerate() {
+			let mut primitives = Vec::with_capacity(mesh.primitives.len());
+			for primitive in 0..mesh.primitives.len() {
+				let source = GltfMeshSource::new(doc, self.buffers, i, primitive)?;
+				let entity = world.add_entity((Box::new(source) as Box<Mesh>,));
+				if let Some(name) = &mesh.name {
+					world.add_component(entity, Name(name.clone()));
+				}
+				primitives.push(Handle::new(entity));
+			}
+			scene.meshes.push(primitives);
+		}
+		
+		let parents = self.parents()?;
+		let roots = match self.scene {
+			Some(index) => doc.scenes.get(index)
+				.ok_or_else(|| invalid_data("scene index out of bounds"))?
+				.nodes.clone(),
+			None if !doc.scenes.is_empty() => doc.scenes[0].nodes.clone(),
+			None => (0..doc.nodes.len()).filter(|node| parents[*node].is_none()).collect()
+		};
+		
+		let mut stack = roots.iter().rev().map(|node| (*node, None)).collect::<Vec<_>>();
+		while let Some((node, parent)) = stack.pop() {
+			let entity = self.node(world, children, &mut scene, &parents, node, parent)?;
+			if parent.is_none() {
+				scene.roots.push(entity);
+			}
+			stack.extend(doc.nodes[node].children.iter().rev().map(|child| (*child, Some(entity))));
+		}
+		
+		for animation in &doc.animations {
+			let source = self.animation(animation, &scene)?;
+			let entity = world.add_entity((Box::new(source) as Box<AnimationSource>,));
+			if let Some(name) = &animation.name {
+				world.add_component(entity, Name(name.clone()));
+			}
+			scene.animations.push(Handle::new(entity));
+		}
+		
+		Ok(scene)
+	}
+	
+	/// Maps every node to its parent node and checks that the nodes form a forest.
+	fn parents(&self) -> Result<Vec<Option<usize>>, DeError> {
+		let mut parents = vec![None; self.doc.nodes.len()];
+		for (i, node) in self.doc.nodes.iter().enumerate() {
+			for child in &node.children {
+				match parents.get_mut(*child) {
+					Some(parent @ None) if *child != i => *parent = Some(i),
+					Some(_) => return Err(invalid_data("node has multiple parents")),
+					None => return Err(invalid_data("node index out of bounds"))
+				}
+			}
+		}
+		
+		for mut node in 0..parents.len() {
+			for _ in 0..parents.len() {
+				match parents[node] {
+					Some(parent) => node = parent,
+					None => break
+				}
+			}
+			
+			if parents[node].is_some() {
+				return Err(invalid_data("node hierarchy contains a cycle"));
+			}
+		}
+		
+		Ok(parents)
+	}
+	
+	fn node(
+		&self,
+		world:    &World,
+		children: &mut ChildrenAccelStorage,
+		scene:    &mut GltfScene,
+		parents:  &[Option<usize>],
+		index:    usize,
+		parent:   Option<Entity>
+	) -> Result<Entity, DeError> {
+		let doc = self.doc;
+		let node = doc.nodes.get(index).ok_or_else(|| invalid_data("node index out of bounds"))?;
+		if scene.nodes[index].is_some() {
+			return Err(invalid_data("node is part of the scene more than once"));
+		}
+		
+		let entity = world.add_entity((
+			node_transform(node),
+			LocalTransform::default(),
+			GlobalTransform::default()
+		));
+		scene.nodes[index] = Some(entity);
+		
+		if let Some(name) = &node.name {
+			world.add_component(entity, Name(name.clone()));
+		}
+		
+		if let Some(parent) = parent {
+			world.add_component(entity, Parent(parent));
+			children.insert(entity, parent);
+		}
+		
+		if let Some(camera) = node.camera {
+			let camera = doc.cameras.get(camera).ok_or_else(|| invalid_data("camera index out of bounds"))?;
+			if let Some(camera) = import_camera(camera) {
+				world.add_component(entity, camera);
+			}
+		}
+		
+		if let Some(light) = node.extensions.as_ref().and_then(|ext| ext.khr_lights_punctual) {
+			let light = doc.extensions.as_ref()
+				.and_then(|ext| ext.khr_lights_punctual.as_ref())
+				.and_then(|ext| ext.lights.get(light.light))
+				.ok_or_else(|| invalid_data("light index out of bounds"))?;
+			world.add_component(entity, import_light(light));
+		}
+		
+		if let Some(mesh) = node.mesh {
+			let primitives = scene.meshes.get(mesh).ok_or_else(|| invalid_data("mesh index out of bounds"))?;
+			for (i, handle) in primitives.iter().enumerate() {
+				let target = match primitives.len() {
+					1 => entity,
+					_ => {
+						let child = world.add_entity((
+							Transform::new().rotation(Vec4(0.0, 0.0, 0.0, 1.0)).scaling(1.0),
+							LocalTransform::default(),
+							GlobalTransform::default(),
+							Parent(entity)
+						));
+						children.insert(child, entity);
+						child
+					}
+				};
+				
+				world.add_component(target, *handle);
+				if let Some(material) = doc.meshes[mesh].primitives[i].material {
+					let material = scene.materials.get(material).ok_or_else(|| invalid_data("material index out of bounds"))?;
+					world.add_component(target, *material);
+				}
+			}
+		}
+		
+		if let Some(skin) = node.skin {
+			let skin = doc.skins.get(skin).ok_or_else(|| invalid_data("skin index out of bounds"))?;
+			let (joints, inverse_bind_matrices) = self.skin(skin, parents)?;
+			world.add_component(entity, joints);
+			world.add_component(entity, inverse_bind_matrices);
+		}
+		
+		Ok(entity)
+	}
+	
+	fn skin(&self, skin: &gltf::Skin, parents: &[Option<usize>]) -> Result<(Joints, JointsInverseBindMatrices), DeError> {
+		let doc = self.doc;
+		let joints = skin.joints.iter()
+			.map(|joint| {
+				let node = doc.nodes.get(*joint).ok_or_else(|| invalid_data("joint index out of bounds"))?;
+				let mut ancestor = parents[*joint];
+				let parent = loop {
+					match ancestor {
+						None => break u32::MAX,
+						Some(node) => match skin.joints.iter().position(|joint| *joint == node) {
+							Some(parent) => break parent as u32,
+							None => ancestor = parents[node]
+						}
+					}
+				};
+				Ok((parent, node_transform(node)))
+			})
+			.collect::<Result<_, DeError>>()?;
+		
+		let inverse_bind_matrices = match skin.inverse_bind_matrices {
+			Some(accessor) => {
+				let matrices = doc.accessor(accessor, self.buffers)?.read_f32();
+				if matrices.len() != skin.joints.len() * 16 {
+					return Err(invalid_data("inverse bind matrix count does not match the joint count"));
+				}
+				
+				matrices.chunks_exact(16)
+					.map(|matrix| Mat4::from(<[f32; 16]>::try_from(matrix).unwrap()))
+					.collect()
+			}
+			None => vec![Mat4::default(); skin.joints.len()]
+		};
+		
+		Ok((Joints(joints), JointsInverseBindMatrices(inverse_bind_matrices)))
+	}
+	
+	fn material(&self, material: &gltf::Material, textures: &[Handle<Box<Texture>>]) -> Result<Material, DeError> {
+		let texture = |texture: Option<gltf::MaterialTexture>| -> Result<_, DeError> {
+			texture.map(|texture| Ok(MaterialTexture {
+				texture:   *textures.get(texture.index).ok_or_else(|| invalid_data("texture index out of bounds"))?,
+				tex_coord: texture.tex_coord as u32,
+				scale:     texture.scale.or(texture.strength).unwrap_or(1.0) as f32
+			})).transpose()
+		};
+		let vec3 = |v: [f64; 3]| Vec3(v[0] as f32, v[1] as f32, v[2] as f32);
+		
+		let pbr = &material.pbr_metallic_roughness;
+		let color = pbr.base_color_factor;
+		let mut out = Material {
+			base_color:                 Vec4(color[0] as f32, color[1] as f32, color[2] as f32, color[3] as f32),
+			base_color_texture:         texture(pbr.base_color_texture)?,
+			metalness:                  pbr.metallic_factor as f32,
+			roughness:                  pbr.roughness_factor as f32,
+			metallic_roughness_texture: texture(pbr.metallic_roughness_texture)?,
+			normal_texture:             texture(material.normal_texture)?,
+			occlusion_texture:          texture(material.occlusion_texture)?,
+			emissive:                   vec3(material.emissive_factor),
+			emissive_texture:           texture(material.emissive_texture)?,
+			alpha_mode:                 match material.alpha_mode {
+				gltf::AlphaMode::Opaque => AlphaMode::Opaque,
+				gltf::AlphaMode::Mask   => AlphaMode::Mask(material.alpha_cutoff as f32),
+				gltf::AlphaMode::Blend  => AlphaMode::Blend
+			},
+			double_sided:               material.double_sided,
+			..Material::default()
+		};
+		
+		let ext = match &material.extensions {
+			Some(ext) => ext,
+			None => return Ok(out)
+		};
+		
+		if let Some(ext) = ext.khr_materials_emissive_strength {
+			out.emissive *= ext.emissive_strength as f32;
+		}
+		
+		if let Some(ext) = ext.khr_materials_ior {
+			out.ior = ext.ior as f32;
+		}
+		
+		if let Some(ext) = ext.khr_materials_transmission {
+			out.transmission         = ext.transmission_factor as f32;
+			out.transmission_texture = texture(ext.transmission_texture)?;
+		}
+		
+		if let Some(ext) = ext.khr_materials_volume {
+			out.thickness            = ext.thickness_factor as f32;
+			out.thickness_texture    = texture(ext.thickness_texture)?;
+			out.attenuation_distance = ext.attenuation_distance.map_or(f32::INFINITY, |v| v as f32);
+			out.attenuation_color    = vec3(ext.attenuation_color);
+		}
+		
+		if let Some(ext) = ext.khr_materials_clearcoat {
+			out.clearcoat                   = ext.clearcoat_factor as f32;
+			out.clearcoat_texture           = texture(ext.clearcoat_texture)?;
+			out.clearcoat_roughness         = ext.clearcoat_roughness_factor as f32;
+			out.clearcoat_roughness_texture = texture(ext.clearcoat_roughness_texture)?;
+			out.clearcoat_normal_texture    = texture(ext.clearcoat_normal_texture)?;
+		}
+		
+		if let Some(ext) = ext.khr_materials_sheen {
+			out.sheen_color             = vec3(ext.sheen_color_factor);
+			out.sheen_color_texture     = texture(ext.sheen_color_texture)?;
+			out.sheen_roughness         = ext.sheen_roughness_factor as f32;
+			out.sheen_roughness_texture = texture(ext.sheen_roughness_texture)?;
+		}
+		
+		if let Some(ext) = ext.khr_materials_specular {
+			out.specular               = ext.specular_factor as f32;
+			out.specular_texture       = texture(ext.specular_texture)?;
+			out.specular_color         = vec3(ext.specular_color_factor);
+			out.specular_color_texture = texture(ext.specular_color_texture)?;
+		}
+		
+		out.unlit = ext.khr_materials_unlit.is_some();
+		Ok(out)
+	}
+	
+	/// Resamples all channels that target imported nodes, morph target weights are not supported.
+	fn animation(&self, animation: &gltf::Animation, scene: &GltfScene) -> Result<GltfAnimationSource, DeError> {
+		use gltf::AnimationChannelTargetPath as Path;
+		
+		let mut samplers = HashMap::new();
+		let mut targets = std::collections::BTreeMap::new();
+		let mut duration = 0f32;
+		
+		for channel in &animation.channels {
+			let (node, entity) = match channel.target.node.and_then(|node| Some((node, (*scene.nodes.get(node)?)?))) {
+				Some(target) => target,
+				None => continue
+			};
+			
+			let components = match channel.target.path {
+				Path::Translation | Path::Scale => 3,
+				Path::Rotation => 4,
+				Path::Weights => continue
+			};
+			
+			let sampler = animation.samplers.get(channel.sampler)
+				.ok_or_else(|| invalid_data("animation sampler index out of bounds"))?;
+			if let std::collections::hash_map::Entry::Vacant(entry) = samplers.entry(channel.sampler) {
+				let times = self.doc.accessor(sampler.input, self.buffers)?.read_f32();
+				let values = self.doc.accessor(sampler.output, self.buffers)?.read_f32();
+				let keys = match sampler.interpolation {
+					gltf::AnimationInterpolation::CubicSpline => 3,
+					_ => 1
+				};
+				
+				if values.len() < times.len() * keys * components {
+					return Err(invalid_data("animation sampler output is too short"));
+				}
+				
+				if times.iter().any(|time| !time.is_finite()) {
+					return Err(invalid_data("animation sampler input is not finite"));
+				}
+				
+				duration = duration.max(times.last().copied().unwrap_or(0.0));
+				entry.insert((times, values));
+			}
+			
+			targets.entry((node, entity)).or_insert_with(Vec::new).push((channel.target.path, channel.sampler));
+		}
+		
+		// NaN and infinite times or frame rates fail the range check as well
+		let frames = (duration * self.frame_rate).ceil();
+		if self.frame_rate <= 0.0 || !(0.0..MAX_FRAME_COUNT as f32).contains(&frames) {
+			return Err(invalid_data("animation is too long or has an invalid frame rate"));
+		}
+		
+		let frame_count = frames as usize + 1;
+		let capacity = targets.len().checked_mul(frame_count)
+			.and_then(|v| v.checked_mul(32))
+			.ok_or_else(|| invalid_data("animation is too long"))?;
+		let mut data = Vec::with_capacity(capacity);
+		let mut tracks = Vec::with_capacity(targets.len());
+		
+		for ((node, entity), channels) in targets {
+			let (translation, rotation, scale) = node_trs(&self.doc.nodes[node]);
+			for frame in 0..frame_count {
+				let time = (frame as f32 / self.frame_rate).min(duration);
+				let (mut translation, mut rotation, mut scale) = (translation, rotation, scale);
+				
+				for (path, sampler) in &channels {
+					let (times, values) = &samplers[sampler];
+					let interpolation = animation.samplers[*sampler].interpolation;
+					let v = sample(times, values, if let Path::Rotation = path { 4 } else { 3 }, interpolation, time);
+					
+					match path {
+						Path::Translation => translation = Vec3(v[0], v[1], v[2]),
+						Path::Rotation    => rotation = Vec4(v[0], v[1], v[2], v[3]),
+						_                 => scale = Vec3(v[0], v[1], v[2])
+					}
+				}
+				
+				let scaling = uniform_scale(scale);
+				[translation.0, translation.1, translation.2, rotation.0, rotation.1, rotation.2, rotation.3, scaling]
+					.iter()
+					.for_each(|v| data.extend_from_slice(&v.to_le_bytes()));
+			}
+			
+			let mut attributes = [TrackAttribute::Empty; 8];
+			attributes[0] = TrackAttribute::InstPos(VK_FORMAT_R32G32B32_SFLOAT);
+			attributes[1] = TrackAttribute::InstRot(VK_FORMAT_R32G32B32A32_SFLOAT);
+			attributes[2] = TrackAttribute::InstSca(VK_FORMAT_R32_SFLOAT);
+			tracks.push(Track { target: Some(entity), frame_count, vertex_count: 1, attributes });
+		}
+		
+		Ok(GltfAnimationSource {
+			descriptor: AnimationDescriptor { frame_rate: self.frame_rate, tracks },
+			data:       data.into()
+		})
+	}
+}
+
+/// Evaluates an animation sampler with `n` components per element at `time`.
+fn sample(times: &[f32], values: &[f32], n: usize, interpolation: gltf::AnimationInterpolation, time: f32) -> [f32; 4] {
+	use gltf::AnimationInterpolation::*;
+	
+	let cubic = matches!(interpolation, CubicSpline);
+	// cubic spline elements are stored as (in-tangent, value, out-tangent)
+	let element = |key: usize, offset: usize| {
+		let i = if cubic { (key * 3 + offset) * n } else { key * n };
+		let mut v = [0f32; 4];
+		v[..n].copy_from_slice(&values[i..i + n]);
+		v
+	};
+	
+	let next = times.partition_point(|t| *t <= time);
+	if times.is_empty() {
+		return [0.0, 0.0, 0.0, 1.0];
+	} else if next == 0 {
+		return element(0, 1);
+	} else if next == times.len() {
+		return element(times.len() - 1, 1);
+	}
+	
+	let key = next - 1;
+	let dt = times[next] - times[key];
+	let t = if dt > 0.0 { (time - times[key]) / dt } else { 0.0 };
+	let (a, b) = (element(key, 1), element(next, 1));
+	
+	let mut v = match interpolation {
+		Step => return a,
+		CubicSpline => {
+			let (out_tangent, in_tangent) = (element(key, 2), element(next, 0));
+			let (t2, t3) = (t * t, t * t * t);
+			let mut v = [0f32; 4];
+			for i in 0..n {
+				v[i] = (2.0 * t3 - 3.0 * t2 + 1.0) * a[i]
+					+ (t3 - 2.0 * t2 + t) * dt * out_tangent[i]
+					+ (-2.0 * t3 + 3.0 * t2) * b[i]
+					+ (t3 - t2) * dt * in_tangent[i];
+			}
+			v
+		}
+		_ if n == 4 => {
+			let q = Vec4(a[0], a[1], a[2], a[3]).slerp(Vec4(b[0], b[1], b[2], b[3]), t);
+			return [q.0, q.1, q.2, q.3];
+		}
+		_ => {
+			let mut v = [0f32; 4];
+			for i in 0..n {
+				v[i] = a[i] + (b[i] - a[i]) * t;
+			}
+			v
+		}
+	};
+	
+	if n == 4 {
+		let len = v.iter().map(|v| v * v).sum::<f32>().sqrt();
+		if len > 0.0 {
+			v.iter_mut().for_each(|v| *v /= len);
+		}
+	}
+	
+	v
+}
+
+/// Returns the translation, rotation and scale of a node.
+fn node_trs(node: &gltf::Node) -> (Vec3<f32>, Quat32, Vec3<f32>) {
+	if let Some(matrix) = &node.matrix {
+		return decompose(matrix);
+	}
+	
+	let vec3 = |v: [f64; 3]| Vec3(v[0] as f32, v[1] as f32, v[2] as f32);
+	(
+		node.translation.map_or(Vec3::from(0.0), vec3),
+		node.rotation.map_or(Vec4(0.0, 0.0, 0.0, 1.0), |v| Vec4(v[0] as f32, v[1] as f32, v[2] as f32, v[3] as f32)),
+		node.scale.map_or(Vec3::from(1.0), vec3)
+	)
+}
+
+fn node_transform(node: &gltf::Node) -> Transform {
+	let (translation, rotation, scale) = node_trs(node);
+	Transform { translation, rotation, scaling: uniform_scale(scale) }
+}
+
+fn uniform_scale(scale: Vec3<f32>) -> f32 {
+	(scale.0 + scale.1 + scale.2) / 3.0
+}
+
+/// Decomposes a column-major affine matrix into translation, rotation and scale.
+fn decompose(m: &[f64; 16]) -> (Vec3<f32>, Quat32, Vec3<f32>) {
+	let column = |i: usize| Vec3(m[i * 4] as f32, m[i * 4 + 1] as f32, m[i * 4 + 2] as f32);
+	let (x, y, z) = (column(0), column(1), column(2));
+	let sign = if x.cross(y).dot(z) < 0.0 { -1.0 } else { 1.0 };
+	let scale = Vec3(x.len() * sign, y.len(), z.len());
+	let axis = |v: Vec3<f32>, s: f32| if s != 0.0 { v * (1.0 / s) } else { v };
+	let (x, y, z) = (axis(x, scale.0), axis(y, scale.1), axis(z, scale.2));
+	
+	// x, y and z are the columns of the rotation matrix
+	let trace = x.0 + y.1 + z.2;
+	let rotation = if trace > 0.0 {
+		let s = 0.5 / (trace + 1.0).sqrt();
+		Vec4((y.2 - z.1) * s, (z.0 - x.2) * s, (x.1 - y.0) * s, 0.25 / s)
+	} else if x.0 > y.1 && x.0 > z.2 {
+		let s = 2.0 * (1.0 + x.0 - y.1 - z.2).sqrt();
+		Vec4(0.25 * s, (y.0 + x.1) / s, (z.0 + x.2) / s, (y.2 - z.1) / s)
+	} else if y.1 > z.2 {
+		let s = 2.0 * (1.0 + y.1 - x.0 - z.2).sqrt();
+		Vec4((y.0 + x.1) / s, 0.25 * s, (z.1 + y.2) / s, (z.0 - x.2) / s)
+	} else {
+		let s = 2.0 * (1.0 + z.2 - x.0 - y.1).sqrt();
+		Vec4((z.0 + x.2) / s, (z.1 + y.2) / s, 0.25 * s, (x.1 - y.0) / s)
+	};
+	
+	(Vec3(m[12] as f32, m[13] as f32, m[14] as f32), rotation, scale)
+}
+
+fn import_camera(camera: &gltf::Camera) -> Option<Camera> {
+	match (camera.r#type, camera.perspective, camera.orthographic) {
+		(gltf::CameraType::Perspective, Some(camera), _) => Some(Camera::Pers(PerspectiveCamera {
+			far:    camera.zfar.map_or(f32::INFINITY, |v| v as f32),
+			near:   camera.znear as f32,
+			// an aspect ratio of zero means that the aspect ratio of the viewport is used
+			aspect: camera.aspect_ratio.unwrap_or(0.0) as f32,
+			fov:    camera.yfov as f32
+		})),
+		(gltf::CameraType::Orthographic, _, Some(camera)) => Some(Camera::OrthoSym(OrthographicSymmetricCamera {
+			far:    camera.zfar as f32,
+			near:   camera.znear as f32,
+			planes: Vec2(camera.xmag as f32 * 2.0, camera.ymag as f32 * 2.0)
+		})),
+		_ => None
+	}
+}
+
+fn import_light(light: &gltf::Light) -> Light {
+	let color = Vec3(light.color[0] as f32, light.color[1] as f32, light.color[2] as f32);
+	let intensity = light.intensity as f32;
+	let range = light.range.map_or(f32::INFINITY, |v| v as f32);
+	match light.r#type {
+		gltf::LightType::Directional => Light::Directional(DirectionalLight { color, intensity }),
+		gltf::LightType::Point => Light::Point(PointLight { color, intensity, range }),
+		gltf::LightType::Spot => {
+			let spot = light.spot.unwrap_or_default();
+			Light::Spot(SpotLight {
+				color,
+				intensity,
+				range,
+				inner_cone_angle: spot.inner_cone_angle as f32,
+				outer_cone_angle: spot.outer_cone_angle as f32
+			})
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn bytes(values: &[f32]) -> Vec<u8> {
+		values.iter().flat_map(|v| v.to_le_bytes()).collect()
+	}
+	
+	fn ibm() -> [f32; 16] {
+		[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 1.0]
+	}
+	
+	fn get<T: Component + Clone>(world: &World, entity: Entity) -> Option<T> {
+		world.query::<&T>().get(entity).map(|v| T::clone(*v))
+	}
+	
+	/// A root with a two-primitive mesh, a skinned joint chain below it and an animated joint.
+	fn document() -> (gltf::Document, Vec<Vec<u8>>) {
+		let mut doc = gltf::read(br#"{
+			"asset": {"version": "2.0"},
+			"scene": 0,
+			"scenes": [{"nodes": [0]}],
+			"nodes": [
+				{"name": "root", "children": [1, 2], "translation": [1, 2, 3], "mesh": 0},
+				{"name": "arm", "children": [3], "translation": [0, 1, 0]},
+				{"name": "body", "skin": 0, "scale": [2, 2, 2]},
+				{"name": "hand", "translation": [0, 0.5, 0]}
+			],
+			"meshes": [{"name": "quad", "primitives": [
+				{"attributes": {"POSITION": 0}},
+				{"attributes": {"POSITION": 0}, "material": 0}
+			]}],
+			"materials": [{"name": "red", "pbrMetallicRoughness": {"baseColorFactor": [1, 0, 0, 1]}}],
+			"skins": [{"joints": [1, 3], "inverseBindMatrices": 3}],
+			"animations": [{"name": "wave", "channels": [{"sampler": 0, "target": {"node": 3, "path": "translation"}}],
+				"samplers": [{"input": 1, "output": 2}]}]
+		}"#.as_slice()).unwrap();
+		
+		doc.push_accessor(&bytes(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]), gltf::AccessorType::Vec3, gltf::ComponentType::Float, false, None).unwrap();
+		doc.push_accessor(&bytes(&[0.0, 1.0]), gltf::AccessorType::Scalar, gltf::ComponentType::Float, false, None).unwrap();
+		doc.push_accessor(&bytes(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0]), gltf::AccessorType::Vec3, gltf::ComponentType::Float, false, None).unwrap();
+		doc.push_accessor(&bytes(&[ibm(), ibm()].concat()), gltf::AccessorType::Mat4, gltf::ComponentType::Float, false, None).unwrap();
+		let buffers = doc.load_buffers(|_| unreachable!()).unwrap();
+		(doc, buffers)
+	}
+	
+	#[test]
+	fn import() {
+		let (doc, buffers) = document();
+		let world = World::new();
+		let mut children = ChildrenAccelStorage::default();
+		let importer = GltfImporter::new(&doc, &buffers).frame_rate(4.0);
+		let scene = importer.import(&world, &mut children).unwrap();
+		let nodes = scene.nodes.iter().map(|node| node.unwrap()).collect::<Vec<_>>();
+		
+		assert_eq!(scene.roots, [nodes[0]]);
+		assert_eq!(get::<Name>(&world, nodes[3]).unwrap().0, "hand");
+		assert_eq!(get::<Transform>(&world, nodes[0]).unwrap().translation, Vec3(1.0, 2.0, 3.0));
+		assert_eq!(get::<Transform>(&world, nodes[2]).unwrap().scaling, 2.0);
+		assert_eq!(get::<Parent>(&world, nodes[0]), None);
+		assert_eq!(get::<Parent>(&world, nodes[1]), Some(Parent(nodes[0])));
+		assert_eq!(get::<Parent>(&world, nodes[3]), Some(Parent(nodes[1])));
+		
+		// both primitives are attached to child entities of the node
+		assert_eq!(scene.meshes.len(), 1);
+		assert_eq!(scene.meshes[0].len(), 2);
+		assert_eq!(get::<Handle<Box<Mesh>>>(&world, nodes[0]), None);
+		let mut primitives = Vec::new();
+		children.for_each_child(nodes[0], &mut |_, child| {
+			if let Some(mesh) = get::<Handle<Box<Mesh>>>(&world, child) {
+				primitives.push((mesh, get::<Handle<Material>>(&world, child)));
+			}
+			false
+		});
+		primitives.sort_by_key(|(mesh, _)| mesh.0);
+		assert_eq!(primitives, [(scene.meshes[0][0], None), (scene.meshes[0][1], Some(scene.materials[0]))]);
+		assert_eq!(GltfMeshSource::new(&doc, &buffers, 0, 1).unwrap().descriptor.vertex_count, 3);
+		assert_eq!(get::<Name>(&world, scene.materials[0].0).unwrap().0, "red");
+		
+		// the hand is a child of the arm within the skin
+		let joints = get::<Joints>(&world, nodes[2]).unwrap();
+		assert_eq!(joints.0.iter().map(|(parent, _)| *parent).collect::<Vec<_>>(), [u32::MAX, 0]);
+		assert_eq!(joints.0[1].1.translation, Vec3(0.0, 0.5, 0.0));
+		let matrices = get::<JointsInverseBindMatrices>(&world, nodes[2]).unwrap();
+		assert_eq!(matrices.0.len(), 2);
+		assert_eq!(matrices.0[0], Mat4::from(ibm()));
+		
+		assert_eq!(scene.animations.len(), 1);
+		assert_eq!(get::<Name>(&world, scene.animations[0].0).unwrap().0, "wave");
+		let animation = importer.animation(&doc.animations[0], &scene).unwrap();
+		assert_eq!(animation.descriptor.frame_rate, 4.0);
+		assert_eq!(animation.descriptor.tracks.len(), 1);
+		assert_eq!(animation.descriptor.tracks[0].target, Some(nodes[3]));
+		assert_eq!(animation.descriptor.tracks[0].frame_count, 5);
+		
+		let frames = animation.data.chunks(4).map(|v| f32::from_le_bytes(v.try_into().unwrap())).collect::<Vec<_>>();
+		assert_eq!(frames.len(), 5 * 8);
+		assert_eq!(&frames[8..16], &[0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+		assert_eq!(&frames[32..35], &[2.0, 0.0, 0.0]);
+	}
+	
+	#[test]
+	fn animation_frame_count() {
+		let (mut doc, _) = document();
+		for frame_rate in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+			let buffers = doc.load_buffers(|_| unreachable!()).unwrap();
+			let result = GltfImporter::new(&doc, &buffers).frame_rate(frame_rate).import(&World::new(), &mut ChildrenAccelStorage::default());
+			assert!(result.is_err(), "{}", frame_rate);
+		}
+		
+		for end in [1e9, f32::INFINITY, f32::NAN] {
+			doc.animations[0].samplers[0].input = doc.push_accessor(&bytes(&[0.0, end]), gltf::AccessorType::Scalar, gltf::ComponentType::Float, false, None).unwrap();
+			let buffers = doc.load_buffers(|_| unreachable!()).unwrap();
+			let result = GltfImporter::new(&doc, &buffers).import(&World::new(), &mut ChildrenAccelStorage::default());
+			assert!(result.is_err(), "{}", end);
+		}
+	}
+}
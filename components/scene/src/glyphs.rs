@@ -22,25 +22,55 @@
 
 use {
 	super::*,
-	file_formats::ttf,
+	file_formats::otf,
 	std::io::{self, Read}
 };
 use engine_core::BoxedFuture;
 
+pub use otf::{Axis, Direction, Feature, GlyphId, GlyphInfo, GlyphPosition, Tag, SCRIPT_DEFAULT};
+
 pub type Font = dyn Source<(FontDescriptor, Box<dyn FontReader>)>;
 
+/// Provides the glyphs and the layout data of a font, all functions operate on glyph ids.
 pub trait FontReader: Send + Sync {
-	fn get_glyph(&mut self, ch: char) -> Option<Glyph>;
+	/// Maps a character to a glyph, returns `None` if the font has no glyph for it.
+	fn glyph_index(&mut self, ch: char) -> Option<GlyphId>;
+	
+	fn get_glyph(&mut self, id: GlyphId) -> Option<Glyph>;
+	
+	fn get_kerning(&mut self, id1: GlyphId, id2: GlyphId) -> Option<i16>;
+	
+	/// Applies the substitutions (ligatures, contextual forms, ...) of the given features.
+	fn substitute(&mut self, glyphs: &mut Vec<GlyphInfo>, script: Tag, language: Option<Tag>, features: &[Feature]);
+	
+	/// Computes the advances and offsets of the glyphs, in font units. The glyphs are in
+	/// logical order, for right-to-left text the positions must be reversed to draw them.
+	fn position(
+		&mut self,
+		glyphs:    &[GlyphInfo],
+		direction: Direction,
+		script:    Tag,
+		language:  Option<Tag>,
+		features:  &[Feature]
+	) -> Vec<GlyphPosition>;
 	
-	fn get_kerning(&mut self, ch1: char, ch2: char) -> Option<i16>;
+	/// Returns the scripts the layout tables of the font have features for.
+	fn scripts(&mut self) -> Vec<Tag>;
+	
+	/// Returns the variation axes, empty if this is not a variable font.
+	fn axes(&mut self) -> Vec<Axis>;
+	
+	/// Sets the value of a variation axis, affects all glyphs and metrics returned afterwards.
+	fn set_variation(&mut self, axis: Tag, value: f32) -> bool;
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct FontDescriptor {
-	pub ascender:    i16,
-	pub descender:   i16,
-	pub line_gap:    i16,
-	pub glyphs:      usize,
+	pub units_per_em: u16,
+	pub ascender:     i16,
+	pub descender:    i16,
+	pub line_gap:     i16,
+	pub glyphs:       usize,
 }
 
 #[derive(Debug, Clone)]
@@ -74,35 +104,41 @@ impl<T: AsRef<std::path::Path> + std::fmt::Debug + Send + Sync> Source<(FontDesc
 		Box::pin(async move {
 			let mut data = Vec::new();
 			std::fs::File::open(&self.0)?.read_to_end(&mut data)?;
-			// this is safe as the data the slice refers to is on the heap
-			let d = unsafe { &*(data.as_slice() as *const _) };
-			let font = ttf::Font::from_data(d, 0).unwrap();
+			let font = otf::Font::parse(data, 0)?;
 			
 			Ok((FontDescriptor {
-				ascender:  font.ascender(),
-				descender: font.descender(),
-				line_gap:  font.line_gap(),
-				glyphs:    font.number_of_glyphs() as _
-			}, Box::new(TtfReader(data, font)) as _))
+				units_per_em: font.units_per_em(),
+				ascender:     font.ascender(),
+				descender:    font.descender(),
+				line_gap:     font.line_gap(),
+				glyphs:       font.glyph_count() as _
+			}, Box::new(OtfReader(font)) as _))
 		})
 	}
 }
 
-pub struct TtfReader(Vec<u8>, ttf::Font<'static>);
+pub struct OtfReader(pub otf::Font<Vec<u8>>);
 
-impl FontReader for TtfReader {
-	fn get_glyph(&mut self, ch: char) -> Option<Glyph> {
+impl FontReader for OtfReader {
+	fn glyph_index(&mut self, ch: char) -> Option<GlyphId> {
+		self.0.glyph_index(ch)
+	}
+	
+	fn get_glyph(&mut self, id: GlyphId) -> Option<Glyph> {
+		if id.0 >= self.0.glyph_count() {
+			return None;
+		}
+		
 		let mut path = Path(Vec::new());
-		let id = self.1.glyph_index(ch).ok()?;
-		let hor = self.1.glyph_hor_metrics(id).unwrap_or(ttf::HorizontalMetrics {
+		let hor = self.0.glyph_hor_metrics(id).unwrap_or(otf::HorizontalMetrics {
 			advance:           0,
 			left_side_bearing: 0
 		});
-		let ver = self.1.glyph_ver_metrics(id).unwrap_or(ttf::VerticalMetrics {
+		let ver = self.0.glyph_ver_metrics(id).unwrap_or(otf::VerticalMetrics {
 			advance:          0,
 			top_side_bearing: 0
 		});
-		let rect = self.1.outline_glyph(id, &mut path).ok();
+		let rect = self.0.outline_glyph(id, &mut path);
 		
 		Some(Glyph {
 			metrics: GlyphMetrics {
@@ -115,17 +151,41 @@ impl FontReader for TtfReader {
 		})
 	}
 	
-	fn get_kerning(&mut self, ch1: char, ch2: char) -> Option<i16> {
-		self.1.glyphs_kerning(
-			self.1.glyph_index(ch1).ok()?,
-			self.1.glyph_index(ch2).ok()?
-		).ok()
+	fn get_kerning(&mut self, id1: GlyphId, id2: GlyphId) -> Option<i16> {
+		self.0.kerning(id1, id2)
+	}
+	
+	fn substitute(&mut self, glyphs: &mut Vec<GlyphInfo>, script: Tag, language: Option<Tag>, features: &[Feature]) {
+		self.0.substitute(glyphs, script, language, features)
+	}
+	
+	fn position(
+		&mut self,
+		glyphs:    &[GlyphInfo],
+		direction: Direction,
+		script:    Tag,
+		language:  Option<Tag>,
+		features:  &[Feature]
+	) -> Vec<GlyphPosition> {
+		self.0.position(glyphs, direction, script, language, features)
+	}
+	
+	fn scripts(&mut self) -> Vec<Tag> {
+		self.0.scripts()
+	}
+	
+	fn axes(&mut self) -> Vec<Axis> {
+		self.0.axes().to_vec()
+	}
+	
+	fn set_variation(&mut self, axis: Tag, value: f32) -> bool {
+		self.0.set_variation(axis, value)
 	}
 }
 
 struct Path(Vec<PathElement>);
 
-impl ttf::OutlineBuilder for Path {
+impl otf::OutlineBuilder for Path {
 	fn move_to(&mut self, x: f32, y: f32) {
 		self.0.push(PathElement::Move(Vec2(x, y)));
 	}
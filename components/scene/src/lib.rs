@@ -31,16 +31,21 @@ use {
 	std::{alloc::Allocator, marker::PhantomData}
 };
 
-pub use self::{animation::*, audio::*, glyphs::*, handle::*, mesh::*, texture::*, transform::*, ui::*};
+pub use self::{animation::*, audio::*, glyphs::*, handle::*, light::*, material::*, mesh::*, texture::*, transform::*, ui::*};
 
 pub mod transform;
 pub mod mesh;
 pub mod path;
 pub mod texture;
+pub mod material;
+pub mod light;
 pub mod glyphs;
 pub mod audio;
 pub mod animation;
 pub mod ui;
+pub mod gltf;
+pub mod svg;
+pub mod text;
 
 #[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
 pub struct Hidden;
@@ -108,12 +113,20 @@ impl ChildrenAccelStorage {
 	}
 }
 
+/// The joints of a skeleton, each joint is stored as the index of its parent joint
+/// (`u32::MAX` for root joints) and its transform relative to the parent.
 #[derive(Clone, Debug, Default)]
 pub struct Joints(pub Vec<(u32, Transform)>);
 
+/// Transforms vertices from mesh space into world space for each joint, computed by
+/// `update_joints_global_transform`.
 #[derive(Clone, Debug, Default)]
 pub struct JointsGlobalTransform(pub Vec<Mat4<f32>>);
 
+/// Transforms vertices from mesh space into the local space of each joint.
+#[derive(Clone, Debug, Default)]
+pub struct JointsInverseBindMatrices(pub Vec<Mat4<f32>>);
+
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Color(pub Vec4<f32>);
 
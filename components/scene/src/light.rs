@@ -0,0 +1,86 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Light {
+	Directional(DirectionalLight),
+	Point(PointLight),
+	Spot(SpotLight)
+}
+
+impl Default for Light {
+	fn default() -> Self {
+		Self::Point(PointLight::default())
+	}
+}
+
+/// A light that emits in the direction of the local -Z axis, `intensity` is in lux.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DirectionalLight {
+	pub color:     Vec3<f32>,
+	pub intensity: f32
+}
+
+impl Default for DirectionalLight {
+	fn default() -> Self {
+		Self { color: Vec3::from(1.0), intensity: 1.0 }
+	}
+}
+
+/// A light that emits in all directions, `intensity` is in candela.
+/// A `range` of `f32::INFINITY` means that the light never falls off to zero.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointLight {
+	pub color:     Vec3<f32>,
+	pub intensity: f32,
+	pub range:     f32
+}
+
+impl Default for PointLight {
+	fn default() -> Self {
+		Self { color: Vec3::from(1.0), intensity: 1.0, range: f32::INFINITY }
+	}
+}
+
+/// A light that emits in a cone along the local -Z axis, cone angles are in radians.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpotLight {
+	pub color:            Vec3<f32>,
+	pub intensity:        f32,
+	pub range:            f32,
+	pub inner_cone_angle: f32,
+	pub outer_cone_angle: f32
+}
+
+impl Default for SpotLight {
+	fn default() -> Self {
+		Self {
+			color:            Vec3::from(1.0),
+			intensity:        1.0,
+			range:            f32::INFINITY,
+			inner_cone_angle: 0.0,
+			outer_cone_angle: std::f32::consts::FRAC_PI_4
+		}
+	}
+}
@@ -0,0 +1,126 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::*;
+
+/// Physically based material parameters, using the metallic-roughness model.
+/// Factors are multiplied with the values sampled from the corresponding textures.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Material {
+	pub base_color:                  Vec4<f32>,
+	pub base_color_texture:          Option<MaterialTexture>,
+	pub metalness:                   f32,
+	pub roughness:                   f32,
+	/// Metalness is sampled from the blue, roughness from the green channel.
+	pub metallic_roughness_texture:  Option<MaterialTexture>,
+	pub normal_texture:              Option<MaterialTexture>,
+	pub occlusion_texture:           Option<MaterialTexture>,
+	/// Emissive color, already multiplied with the emissive strength.
+	pub emissive:                    Vec3<f32>,
+	pub emissive_texture:            Option<MaterialTexture>,
+	pub alpha_mode:                  AlphaMode,
+	pub double_sided:                bool,
+	/// The material is not affected by lighting, only the base color is used.
+	pub unlit:                       bool,
+	pub ior:                         f32,
+	pub transmission:                f32,
+	pub transmission_texture:        Option<MaterialTexture>,
+	pub thickness:                   f32,
+	pub thickness_texture:           Option<MaterialTexture>,
+	pub attenuation_distance:        f32,
+	pub attenuation_color:           Vec3<f32>,
+	pub clearcoat:                   f32,
+	pub clearcoat_texture:           Option<MaterialTexture>,
+	pub clearcoat_roughness:         f32,
+	pub clearcoat_roughness_texture: Option<MaterialTexture>,
+	pub clearcoat_normal_texture:    Option<MaterialTexture>,
+	pub sheen_color:                 Vec3<f32>,
+	pub sheen_color_texture:         Option<MaterialTexture>,
+	pub sheen_roughness:             f32,
+	pub sheen_roughness_texture:     Option<MaterialTexture>,
+	pub specular:                    f32,
+	pub specular_texture:            Option<MaterialTexture>,
+	pub specular_color:              Vec3<f32>,
+	pub specular_color_texture:      Option<MaterialTexture>
+}
+
+impl Default for Material {
+	fn default() -> Self {
+		Self {
+			base_color:                  Vec4::from(1.0),
+			base_color_texture:          None,
+			metalness:                   1.0,
+			roughness:                   1.0,
+			metallic_roughness_texture:  None,
+			normal_texture:              None,
+			occlusion_texture:           None,
+			emissive:                    Vec3::from(0.0),
+			emissive_texture:            None,
+			alpha_mode:                  AlphaMode::Opaque,
+			double_sided:                false,
+			unlit:                       false,
+			ior:                         1.5,
+			transmission:                0.0,
+			transmission_texture:        None,
+			thickness:                   0.0,
+			thickness_texture:           None,
+			attenuation_distance:        f32::INFINITY,
+			attenuation_color:           Vec3::from(1.0),
+			clearcoat:                   0.0,
+			clearcoat_texture:           None,
+			clearcoat_roughness:         0.0,
+			clearcoat_roughness_texture: None,
+			clearcoat_normal_texture:    None,
+			sheen_color:                 Vec3::from(0.0),
+			sheen_color_texture:         None,
+			sheen_roughness:             0.0,
+			sheen_roughness_texture:     None,
+			specular:                    1.0,
+			specular_texture:            None,
+			specular_color:              Vec3::from(1.0),
+			specular_color_texture:      None
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialTexture {
+	pub texture:   Handle<Box<Texture>>,
+	/// The index of the texture coordinate set.
+	pub tex_coord: u32,
+	/// Normal scale for normal textures, occlusion strength for occlusion textures, 1 otherwise.
+	pub scale:     f32
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AlphaMode {
+	Opaque,
+	/// Fragments with an alpha value less than the cutoff are discarded.
+	Mask(f32),
+	Blend
+}
+
+impl Default for AlphaMode {
+	fn default() -> Self {
+		Self::Opaque
+	}
+}
@@ -20,7 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use {super::*, vk::VkFormat};
+use {super::*, vk::{VkFormat, VkPrimitiveTopology::{self, *}}, file_formats::bundle::{self, Semantic}, std::io};
 use engine_core::BoxedFuture;
 
 pub type Mesh = dyn Source<(MeshDescriptor, Box<dyn MeshReader>)>;
@@ -103,4 +103,132 @@ impl std::io::Read for StaticMeshSource {
 	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
 		self.1.read(buf)
 	}
-}
\ No newline at end of file
+}
+/// A mesh section of a bundle, see `file_formats::bundle`. The vertices are read in place, so
+/// the bundle usually is a leaked [`Mapping`](bundle::Mapping). Only meshes without indices
+/// are supported, as [`MeshDescriptor`] has none, and the attributes have to be packed in the
+/// order of the descriptor.
+#[derive(Clone, Debug)]
+pub struct BundleMeshSource {
+	pub descriptor: MeshDescriptor,
+	pub topology:   VkPrimitiveTopology,
+	pub data:       &'static [u8]
+}
+
+impl BundleMeshSource {
+	pub fn new(mesh: &bundle::Mesh<'static>) -> io::Result<Self> {
+		let invalid_data = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+		let info = &mesh.info;
+		if info.index_size != 0 {
+			return Err(invalid_data("indexed meshes are not supported"));
+		}
+		
+		let mut attributes = Vec::with_capacity(info.attributes.len());
+		let mut offset = 0;
+		for attribute in &info.attributes {
+			let format = vk_format(attribute.format)
+				.ok_or_else(|| invalid_data("unsupported vertex attribute format"))?;
+			let attribute_ = match attribute.semantic {
+				Semantic::Position => MeshAttribute::Pos(format),
+				Semantic::Normal   => MeshAttribute::Normal(format),
+				Semantic::Color    => MeshAttribute::Color(format),
+				Semantic::TexCoord => MeshAttribute::Tex(format),
+				_ => return Err(invalid_data("unsupported vertex attribute"))
+			};
+			
+			if attribute.offset as usize != offset {
+				return Err(invalid_data("vertex attributes are not packed"));
+			}
+			
+			offset += gltf::attribute_size(&attribute_)
+				.ok_or_else(|| invalid_data("unsupported vertex attribute format"))?;
+			attributes.push(attribute_);
+		}
+		
+		if offset != info.vertex_stride as usize {
+			return Err(invalid_data("vertex attributes are not packed"));
+		}
+		
+		let topology = match info.topology {
+			0 => VK_PRIMITIVE_TOPOLOGY_POINT_LIST,
+			1 => VK_PRIMITIVE_TOPOLOGY_LINE_LIST,
+			2 => VK_PRIMITIVE_TOPOLOGY_LINE_STRIP,
+			3 => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST,
+			4 => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_STRIP,
+			5 => VK_PRIMITIVE_TOPOLOGY_TRIANGLE_FAN,
+			_ => return Err(invalid_data("unsupported primitive topology"))
+		};
+		
+		Ok(Self {
+			descriptor: MeshDescriptor::new(mesh.vertices.len() / offset.max(1), &attributes),
+			topology,
+			data:       mesh.vertices
+		})
+	}
+}
+
+impl Source<(MeshDescriptor, Box<dyn MeshReader>)> for BundleMeshSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<std::io::Result<(MeshDescriptor, Box<dyn MeshReader>)>> {
+		Box::pin(async move {
+			Ok((self.descriptor.clone(), Box::new(self.clone()) as _))
+		})
+	}
+}
+
+impl MeshReader for BundleMeshSource {}
+
+impl std::io::Read for BundleMeshSource {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.data.read(buf)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, vk::VkFormat::*, std::io::Read, file_formats::bundle::{Bundle, BundleWriter, MeshInfo, VertexAttribute}};
+	
+	fn info(index_size: u8, tex_offset: u32) -> MeshInfo {
+		MeshInfo {
+			topology:      3,
+			vertex_count:  3,
+			vertex_stride: 20,
+			attributes:    vec![
+				VertexAttribute { semantic: Semantic::Position, format: VK_FORMAT_R32G32B32_SFLOAT as _, offset: 0 },
+				VertexAttribute { semantic: Semantic::TexCoord, format: VK_FORMAT_R32G32_SFLOAT as _, offset: tex_offset }
+			],
+			index_count:   if index_size == 0 { 0 } else { 3 },
+			index_size
+		}
+	}
+	
+	fn bundle() -> Bundle<'static> {
+		let vertices = (0..60).collect::<Vec<u8>>();
+		let mut writer = BundleWriter::new();
+		writer.mesh("triangle", &info(0, 12), &vertices, &[]).unwrap();
+		writer.mesh("indexed", &info(2, 12), &vertices, &[0, 0, 1, 0, 2, 0]).unwrap();
+		writer.mesh("gap", &info(0, 16), &vertices, &[]).unwrap();
+		Bundle::parse(writer.to_vec().leak()).unwrap()
+	}
+	
+	#[test]
+	fn bundle_mesh() {
+		let bundle = bundle();
+		let mesh = bundle.section("triangle").unwrap().mesh().unwrap();
+		let source = BundleMeshSource::new(&mesh).unwrap();
+		assert_eq!(source.descriptor.vertex_count, 3);
+		assert_eq!(source.descriptor.attributes, [
+			MeshAttribute::Pos(VK_FORMAT_R32G32B32_SFLOAT),
+			MeshAttribute::Tex(VK_FORMAT_R32G32_SFLOAT)
+		]);
+		assert_eq!(source.topology, VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST);
+		// the vertices are not copied
+		assert_eq!(source.data.as_ptr(), mesh.vertices.as_ptr());
+		
+		let mut data = Vec::new();
+		source.clone().read_to_end(&mut data).unwrap();
+		assert_eq!(data, (0..60).collect::<Vec<u8>>());
+		
+		assert!(BundleMeshSource::new(&bundle.section("indexed").unwrap().mesh().unwrap()).is_err());
+		assert!(BundleMeshSource::new(&bundle.section("gap").unwrap().mesh().unwrap()).is_err());
+	}
+}
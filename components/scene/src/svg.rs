@@ -0,0 +1,967 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Conversion of SVG documents into paths and meshes.
+//! 
+//! The shapes of a document are converted into `PathElement`s, the same curve data that glyphs
+//! use, with their transforms applied and their fill and stroke resolved. `SvgImage::mesh`
+//! flattens and triangulates the shapes into a mesh with a position and a color per vertex,
+//! which can be drawn as an UI mesh.
+//! 
+//! Text, images, gradients and CSS style sheets are not supported, references to paint servers
+//! use their fallback paint.
+
+use {
+	super::*,
+	engine_core::BoxedFuture,
+	file_formats::{svg::{self, Color, Length, Paint, PathCommand, PathData, Transform}, utils::DeError},
+	vk::VkFormat::*,
+	std::{io, fmt, sync::Arc, f32::consts::PI}
+};
+
+/// The magic number to approximate a quarter ellipse by a cubic curve.
+const KAPPA: f32 = 0.552_284_8;
+/// The maximum number of line segments a curve is split into.
+const MAX_SEGMENTS: usize = 256;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum FillRule {
+	#[default]
+	NonZero,
+	EvenOdd
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum LineCap {
+	#[default]
+	Butt,
+	Round,
+	Square
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum LineJoin {
+	#[default]
+	Miter,
+	Round,
+	Bevel
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SvgFill {
+	pub color: Vec4<f32>,
+	pub rule:  FillRule
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SvgStroke {
+	pub color:       Vec4<f32>,
+	pub width:       f32,
+	pub cap:         LineCap,
+	pub join:        LineJoin,
+	pub miter_limit: f32
+}
+
+/// A shape with its path in the coordinate system of the viewport.
+/// 
+/// `Close` commands are converted to a line back to the start of the subpath, a subpath is
+/// closed if it ends at its start point.
+#[derive(Clone, Debug, Default)]
+pub struct SvgShape {
+	pub path:   Vec<PathElement>,
+	pub fill:   Option<SvgFill>,
+	pub stroke: Option<SvgStroke>
+}
+
+/// The renderable content of an SVG document.
+#[derive(Clone, Debug, Default)]
+pub struct SvgImage {
+	/// The size of the viewport, in pixels
+	pub size:   Vec2<f32>,
+	/// The shapes in painting order
+	pub shapes: Vec<SvgShape>
+}
+
+impl SvgImage {
+	/// Reads and converts an SVG document.
+	pub fn load<R: io::BufRead + fmt::Debug>(reader: R) -> Result<Self, DeError> {
+		Self::new(&svg::deserialize(reader)?)
+	}
+	
+	/// Converts a document. Invalid values of properties are ignored, like in CSS, invalid
+	/// geometry and transforms are an error. Path data is rendered up to the first error.
+	pub fn new(doc: &svg::Svg) -> Result<Self, DeError> {
+		let view_box = match doc.view_box.trim() {
+			"" => None,
+			view_box => match svg::numbers(view_box)?.as_slice() {
+				&[x, y, w, h] if w > 0.0 && h > 0.0 => Some([x, y, w, h]),
+				_ => return Err(DeError::invalid_token(view_box, "a view box"))
+			}
+		};
+		
+		let default = view_box.map_or(Vec2(100.0, 100.0), |[_, _, w, h]| Vec2(w, h));
+		let font_size = Style::default().font_size;
+		let size = Vec2(
+			length(&doc.width, font_size, default.0)?.unwrap_or(default.0),
+			length(&doc.height, font_size, default.1)?.unwrap_or(default.1)
+		);
+		
+		let transform = match view_box {
+			Some(view_box) => view_box_transform(view_box, size, &doc.preserve_aspect_ratio)?,
+			None => Transform::IDENTITY
+		} * doc.transform.parse::<Transform>()?;
+		
+		let mut converter = Converter {
+			viewport: view_box.map_or(size, |[_, _, w, h]| Vec2(w, h)),
+			shapes:   Vec::new()
+		};
+		
+		let style = match Style::default().cascade(|property| doc.presentation(property), converter.diagonal()) {
+			Some(style) => style,
+			None => return Ok(Self { size, shapes: Vec::new() })
+		};
+		
+		converter.elements(&doc.elements, &style, transform)?;
+		Ok(Self { size, shapes: converter.shapes })
+	}
+	
+	/// Creates a mesh of triangles with fill and stroke of all shapes. `tolerance` is the maximum
+	/// distance of the flattened curves to the actual curves in pixels. Positions are normalized,
+	/// the viewport spans `0..1` on both axes.
+	pub fn mesh(&self, tolerance: f32) -> SvgMeshSource {
+		let scale = Vec2(1.0 / self.size.0, 1.0 / self.size.1);
+		let mut data = Vec::new();
+		let mut count = 0;
+		
+		let mut push = |triangles: Vec<Vec2<f32>>, color: Vec4<f32>| {
+			count += triangles.len();
+			for pos in triangles {
+				let pos = pos * scale;
+				for v in [pos.0, pos.1, color.0, color.1, color.2, color.3] {
+					data.extend_from_slice(&v.to_ne_bytes());
+				}
+			}
+		};
+		
+		for shape in &self.shapes {
+			let polylines = flatten(&shape.path, tolerance);
+			
+			if let Some(fill) = &shape.fill {
+				let polygons = polylines.iter().map(|(points, _)| points.as_slice()).collect::<Vec<_>>();
+				push(self::fill(&polygons, fill.rule), fill.color);
+			}
+			
+			if let Some(stroke) = &shape.stroke {
+				push(self::stroke(&polylines, stroke, tolerance), stroke.color);
+			}
+		}
+		
+		SvgMeshSource {
+			descriptor: MeshDescriptor::new(count, &[
+				MeshAttribute::Pos(VK_FORMAT_R32G32_SFLOAT),
+				MeshAttribute::Color(VK_FORMAT_R32G32B32A32_SFLOAT)
+			]),
+			data:       data.into()
+		}
+	}
+}
+
+/// A triangle list created by `SvgImage::mesh`.
+#[derive(Clone, Debug)]
+pub struct SvgMeshSource {
+	pub descriptor: MeshDescriptor,
+	pub data:       Arc<[u8]>
+}
+
+impl Source<(MeshDescriptor, Box<dyn MeshReader>)> for SvgMeshSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<(MeshDescriptor, Box<dyn MeshReader>)>> {
+		Box::pin(async move {
+			Ok((self.descriptor.clone(), Box::new(SvgMeshReader(self.data.clone(), 0)) as _))
+		})
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct SvgMeshReader(Arc<[u8]>, usize);
+
+impl MeshReader for SvgMeshReader {}
+
+impl io::Read for SvgMeshReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = (&self.0[self.1..]).read(buf)?;
+		self.1 += len;
+		Ok(len)
+	}
+}
+
+/// Converts path commands into path elements and applies a transform.
+pub fn path(commands: &[PathCommand], transform: Transform) -> Vec<PathElement> {
+	let point = |p| {
+		let [x, y] = transform.apply(p);
+		Vec2(x, y)
+	};
+	
+	let mut path = Vec::with_capacity(commands.len());
+	let (mut start, mut current, mut closed) = ([0f32; 2], [0f32; 2], false);
+	
+	for command in commands {
+		// drawing after a close starts a new subpath at the start of the closed one
+		if closed && !matches!(command, PathCommand::Move(_) | PathCommand::Close) {
+			path.push(PathElement::Move(point(start)));
+			closed = false;
+		}
+		
+		match *command {
+			PathCommand::Move(p) => {
+				path.push(PathElement::Move(point(p)));
+				start = p;
+				current = p;
+				closed = false;
+			}
+			PathCommand::Line(p) => {
+				path.push(PathElement::Curve1(point(p)));
+				current = p;
+			}
+			PathCommand::Quad(c, p) => {
+				path.push(PathElement::Curve2(point(c), point(p)));
+				current = p;
+			}
+			PathCommand::Cubic(c0, c1, p) => {
+				path.push(PathElement::Curve3(point(c0), point(c1), point(p)));
+				current = p;
+			}
+			PathCommand::Close => {
+				if current != start {
+					path.push(PathElement::Curve1(point(start)));
+				}
+				current = start;
+				closed = true;
+			}
+		}
+	}
+	
+	path
+}
+
+/// Approximates the curves of a path by line segments, so that the polylines deviate at most
+/// about `tolerance` from the curves. Returns the points of every subpath and whether it is
+/// closed, closed subpaths do not repeat their first point.
+pub fn flatten(path: &[PathElement], tolerance: f32) -> Vec<(Vec<Vec2<f32>>, bool)> {
+	fn finish(points: &mut Vec<Vec2<f32>>, polylines: &mut Vec<(Vec<Vec2<f32>>, bool)>) {
+		let mut points = std::mem::take(points);
+		points.dedup();
+		
+		let closed = points.len() > 2 && points.first() == points.last();
+		if closed {
+			points.pop();
+		}
+		
+		if !points.is_empty() {
+			polylines.push((points, closed));
+		}
+	}
+	
+	let segments = |v: f32| (v.sqrt().ceil() as usize).clamp(1, MAX_SEGMENTS);
+	let tolerance = tolerance.max(1e-4);
+	let mut polylines = Vec::new();
+	let mut points = Vec::new();
+	
+	for element in path {
+		let p0 = points.last().copied().unwrap_or_default();
+		match *element {
+			PathElement::Move(p) => {
+				finish(&mut points, &mut polylines);
+				points.push(p);
+			}
+			PathElement::Curve1(p) => points.push(p),
+			PathElement::Curve2(p1, p2) => {
+				let n = segments((p0 - p1 * 2.0 + p2).len() / (4.0 * tolerance));
+				points.extend((1..=n).map(|i| {
+					let t = i as f32 / n as f32;
+					let u = 1.0 - t;
+					p0 * (u * u) + p1 * (2.0 * u * t) + p2 * (t * t)
+				}));
+			}
+			PathElement::Curve3(p1, p2, p3) => {
+				let dd = (p0 - p1 * 2.0 + p2).len().max((p1 - p2 * 2.0 + p3).len());
+				let n = segments(0.75 * dd / tolerance);
+				points.extend((1..=n).map(|i| {
+					let t = i as f32 / n as f32;
+					let u = 1.0 - t;
+					p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+				}));
+			}
+		}
+	}
+	
+	finish(&mut points, &mut polylines);
+	polylines
+}
+
+/// Triangulates the area enclosed by polygons according to a fill rule, self-intersecting
+/// polygons and holes are supported.
+/// 
+/// The area is split into horizontal bands at every vertex and intersection of edges. Edges do
+/// not cross within a band, so every span between two edges is a trapezoid.
+pub fn fill(polygons: &[&[Vec2<f32>]], rule: FillRule) -> Vec<Vec2<f32>> {
+	// edges from top to bottom with their winding direction, horizontal edges are irrelevant
+	let mut edges = Vec::new();
+	for polygon in polygons {
+		for (i, &a) in polygon.iter().enumerate() {
+			let b = polygon[(i + 1) % polygon.len()];
+			if a.1 < b.1 {
+				edges.push((a, b, 1));
+			} else if a.1 > b.1 {
+				edges.push((b, a, -1));
+			}
+		}
+	}
+	
+	edges.sort_by(|a, b| a.0.1.total_cmp(&b.0.1));
+	let x_at = |(a, b, _): &(Vec2<f32>, Vec2<f32>, i32), y: f32| a.0 + (b.0 - a.0) * (y - a.1) / (b.1 - a.1);
+	let mut ys = edges.iter().flat_map(|(a, b, _)| [a.1, b.1]).collect::<Vec<_>>();
+	
+	for (i, e0) in edges.iter().enumerate() {
+		for e1 in edges[i + 1..].iter().take_while(|e1| e1.0.1 < e0.1.1) {
+			let (top, bottom) = (e1.0.1, e0.1.1.min(e1.1.1));
+			let (d0, d1) = (x_at(e0, top) - x_at(e1, top), x_at(e0, bottom) - x_at(e1, bottom));
+			if d0 * d1 < 0.0 {
+				ys.push(top + (bottom - top) * d0 / (d0 - d1));
+			}
+		}
+	}
+	
+	ys.sort_by(f32::total_cmp);
+	ys.dedup();
+	
+	let mut triangles = Vec::new();
+	let mut active = Vec::new();
+	
+	for band in ys.windows(2) {
+		let (y0, y1) = (band[0], band[1]);
+		let y = (y0 + y1) * 0.5;
+		
+		active.clear();
+		active.extend(edges.iter()
+			.filter(|(a, b, _)| a.1 < y && y < b.1)
+			.map(|edge| (x_at(edge, y0), x_at(edge, y), x_at(edge, y1), edge.2)));
+		active.sort_by(|a, b| a.1.total_cmp(&b.1));
+		
+		let mut winding = 0;
+		for (i, &(l0, _, l1, direction)) in active.iter().enumerate() {
+			winding += direction;
+			let inside = match rule {
+				FillRule::NonZero => winding != 0,
+				FillRule::EvenOdd => winding % 2 != 0
+			};
+			
+			if let (true, Some(&(r0, _, r1, _))) = (inside, active.get(i + 1)) {
+				triangles.extend([
+					Vec2(l0, y0), Vec2(r0, y0), Vec2(r1, y1),
+					Vec2(l0, y0), Vec2(r1, y1), Vec2(l1, y1)
+				]);
+			}
+		}
+	}
+	
+	triangles
+}
+
+/// Triangulates the outline of polylines, as returned by `flatten`. Triangles of adjacent
+/// segments and joins overlap, which is only visible with translucent colors.
+pub fn stroke(polylines: &[(Vec<Vec2<f32>>, bool)], stroke: &SvgStroke, tolerance: f32) -> Vec<Vec2<f32>> {
+	let h = stroke.width * 0.5;
+	let mut triangles = Vec::new();
+	
+	if h <= 0.0 {
+		return triangles;
+	}
+	
+	for (points, closed) in polylines {
+		let n = points.len();
+		
+		// a subpath with a single point is only visible with caps
+		if n == 1 {
+			let p = points[0];
+			match stroke.cap {
+				LineCap::Butt   => (),
+				LineCap::Round  => arc(&mut triangles, p, Vec2(h, 0.0), 2.0 * PI, tolerance),
+				LineCap::Square => quad(&mut triangles, [
+					p + Vec2(-h, -h), p + Vec2(h, -h), p + Vec2(h, h), p + Vec2(-h, h)])
+			}
+			continue;
+		}
+		
+		let direction = |i: usize| {
+			let d = points[(i + 1) % n] - points[i];
+			d / d.len()
+		};
+		
+		for i in 0..if *closed { n } else { n - 1 } {
+			let (a, b) = (points[i], points[(i + 1) % n]);
+			let o = perp(direction(i)) * h;
+			quad(&mut triangles, [a + o, a - o, b - o, b + o]);
+		}
+		
+		for i in if *closed { 0..n } else { 1..n - 1 } {
+			join(&mut triangles, points[i], direction((i + n - 1) % n), direction(i), stroke, tolerance);
+		}
+		
+		if !closed {
+			cap(&mut triangles, points[0], -direction(0), stroke, tolerance);
+			cap(&mut triangles, points[n - 1], direction(n - 2), stroke, tolerance);
+		}
+	}
+	
+	triangles
+}
+
+fn perp(v: Vec2<f32>) -> Vec2<f32> {
+	Vec2(-v.1, v.0)
+}
+
+fn cross(a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+	a.0 * b.1 - a.1 * b.0
+}
+
+fn quad(triangles: &mut Vec<Vec2<f32>>, [a, b, c, d]: [Vec2<f32>; 4]) {
+	triangles.extend([a, b, c, a, c, d]);
+}
+
+/// Adds a circular sector around `center`, which starts at `center + from` and spans `angle`.
+fn arc(triangles: &mut Vec<Vec2<f32>>, center: Vec2<f32>, from: Vec2<f32>, angle: f32, tolerance: f32) {
+	let radius = from.len();
+	let step = 2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos();
+	let n = ((angle.abs() / step).ceil() as usize).clamp(1, MAX_SEGMENTS);
+	let (sin, cos) = (angle / n as f32).sin_cos();
+	let mut v = from;
+	
+	for _ in 0..n {
+		let next = Vec2(v.0 * cos - v.1 * sin, v.0 * sin + v.1 * cos);
+		triangles.extend([center, center + v, center + next]);
+		v = next;
+	}
+}
+
+/// Adds the join between two segments with the directions `d0` and `d1` at `p`.
+fn join(triangles: &mut Vec<Vec2<f32>>, p: Vec2<f32>, d0: Vec2<f32>, d1: Vec2<f32>, stroke: &SvgStroke, tolerance: f32) {
+	let (cross, dot) = (cross(d0, d1), d0.dot(d1));
+	if cross.abs() < 1e-6 && dot > 0.0 {
+		return;
+	}
+	
+	// the join is on the outer side of the turn
+	let h = if cross > 0.0 { -stroke.width * 0.5 } else { stroke.width * 0.5 };
+	let (n0, n1) = (perp(d0) * h, perp(d1) * h);
+	
+	match stroke.join {
+		LineJoin::Round => arc(triangles, p, n0, self::cross(n0, n1).atan2(n0.dot(n1)), tolerance),
+		LineJoin::Miter if ((1.0 + dot) * 0.5).sqrt() * stroke.miter_limit >= 1.0 => {
+			let m = (n0 + n1).normalize(h.abs() / ((1.0 + dot) * 0.5).sqrt());
+			triangles.extend([p, p + n0, p + m, p, p + m, p + n1]);
+		}
+		_ => triangles.extend([p, p + n0, p + n1])
+	}
+}
+
+/// Adds a cap at the end `p` of a subpath, `d` is the direction pointing away from the subpath.
+fn cap(triangles: &mut Vec<Vec2<f32>>, p: Vec2<f32>, d: Vec2<f32>, stroke: &SvgStroke, tolerance: f32) {
+	let h = stroke.width * 0.5;
+	let n = perp(d) * h;
+	
+	match stroke.cap {
+		LineCap::Butt   => (),
+		LineCap::Round  => arc(triangles, p, n, -PI, tolerance),
+		LineCap::Square => quad(triangles, [p + n, p - n, p - n + d * h, p + n + d * h])
+	}
+}
+
+/// Computes the transform from the view box to the viewport, according to `preserveAspectRatio`.
+fn view_box_transform([x, y, w, h]: [f32; 4], size: Vec2<f32>, aspect: &str) -> Result<Transform, DeError> {
+	let mut values = aspect.split_ascii_whitespace();
+	let align = values.next().unwrap_or("xMidYMid");
+	let slice = match values.next() {
+		None | Some("meet") => false,
+		Some("slice") => true,
+		Some(v) => return Err(DeError::invalid_token(v, "`meet` or `slice`"))
+	};
+	
+	let (sx, sy) = (size.0 / w, size.1 / h);
+	if align == "none" {
+		return Ok(Transform::scale(sx, sy) * Transform::translate(-x, -y));
+	}
+	
+	let s = if slice { sx.max(sy) } else { sx.min(sy) };
+	let offset = |align, free| match align {
+		"Min" => Ok(0.0),
+		"Mid" => Ok(free * 0.5),
+		"Max" => Ok(free),
+		_     => Err(DeError::invalid_token(aspect, "an alignment"))
+	};
+	
+	let (tx, ty) = match (align.get(..1), align.get(1..4), align.get(4..5), align.get(5..)) {
+		(Some("x"), Some(ax), Some("Y"), Some(ay)) => (offset(ax, size.0 - w * s)?, offset(ay, size.1 - h * s)?),
+		_ => return Err(DeError::invalid_token(aspect, "an alignment"))
+	};
+	
+	Ok(Transform::translate(tx, ty) * Transform::scale(s, s) * Transform::translate(-x, -y))
+}
+
+/// Parses a length and converts it to user units, empty values are `None`.
+fn length(s: &str, font_size: f32, reference: f32) -> Result<Option<f32>, DeError> {
+	match s.trim() {
+		"" => Ok(None),
+		s  => Ok(Some(s.parse::<Length>()?.to_user(font_size, reference)))
+	}
+}
+
+/// Parses an opacity, either a number or a percentage, which is clamped to `0..=1`.
+fn opacity(s: &str) -> Option<f32> {
+	match s.strip_suffix('%') {
+		Some(s) => s.trim().parse::<f32>().ok().map(|v| v / 100.0),
+		None    => s.parse::<f32>().ok()
+	}.map(|v| v.clamp(0.0, 1.0))
+}
+
+/// The computed values of the properties that affect rendering.
+#[derive(Clone, Debug)]
+struct Style {
+	color:             Color,
+	fill:              Paint,
+	fill_opacity:      f32,
+	fill_rule:         FillRule,
+	stroke:            Paint,
+	stroke_opacity:    f32,
+	stroke_width:      f32,
+	stroke_linecap:    LineCap,
+	stroke_linejoin:   LineJoin,
+	stroke_miterlimit: f32,
+	font_size:         f32,
+	/// The product of the opacities of the element and its ancestors
+	opacity:           f32,
+	visible:           bool
+}
+
+impl Default for Style {
+	fn default() -> Self {
+		Self {
+			color:             Color::BLACK,
+			fill:              Paint::Color(Color::BLACK),
+			fill_opacity:      1.0,
+			fill_rule:         FillRule::NonZero,
+			stroke:            Paint::None,
+			stroke_opacity:    1.0,
+			stroke_width:      1.0,
+			stroke_linecap:    LineCap::Butt,
+			stroke_linejoin:   LineJoin::Miter,
+			stroke_miterlimit: 4.0,
+			font_size:         16.0,
+			opacity:           1.0,
+			visible:           true
+		}
+	}
+}
+
+impl Style {
+	/// Computes the style of a child element, `get` returns the specified values of the element.
+	/// Returns `None` if the element is not displayed.
+	fn cascade<'a>(&self, get: impl Fn(&str) -> Option<&'a str>, diagonal: f32) -> Option<Self> {
+		if get("display") == Some("none") {
+			return None;
+		}
+		
+		let mut style = self.clone();
+		
+		// em lengths of the other properties refer to the computed font size
+		if let Some(Ok(v)) = get("font-size").map(str::parse::<Length>) {
+			style.font_size = v.to_user(self.font_size, self.font_size);
+		}
+		
+		if let Some(Ok(v)) = get("color").map(str::parse) {
+			style.color = v;
+		}
+		
+		if let Some(Ok(v)) = get("fill").map(str::parse) {
+			style.fill = v;
+		}
+		
+		if let Some(Ok(v)) = get("stroke").map(str::parse) {
+			style.stroke = v;
+		}
+		
+		if let Some(Ok(v)) = get("stroke-width").map(str::parse::<Length>) {
+			style.stroke_width = v.to_user(style.font_size, diagonal).max(0.0);
+		}
+		
+		if let Some(Ok(v)) = get("stroke-miterlimit").map(str::parse::<f32>) {
+			style.stroke_miterlimit = v.max(1.0);
+		}
+		
+		style.fill_opacity = get("fill-opacity").and_then(opacity).unwrap_or(self.fill_opacity);
+		style.stroke_opacity = get("stroke-opacity").and_then(opacity).unwrap_or(self.stroke_opacity);
+		style.opacity = self.opacity * get("opacity").and_then(opacity).unwrap_or(1.0);
+		
+		match get("fill-rule") {
+			Some("nonzero") => style.fill_rule = FillRule::NonZero,
+			Some("evenodd") => style.fill_rule = FillRule::EvenOdd,
+			_ => ()
+		}
+		
+		match get("stroke-linecap") {
+			Some("butt")   => style.stroke_linecap = LineCap::Butt,
+			Some("round")  => style.stroke_linecap = LineCap::Round,
+			Some("square") => style.stroke_linecap = LineCap::Square,
+			_ => ()
+		}
+		
+		match get("stroke-linejoin") {
+			Some("miter" | "miter-clip" | "arcs") => style.stroke_linejoin = LineJoin::Miter,
+			Some("round") => style.stroke_linejoin = LineJoin::Round,
+			Some("bevel") => style.stroke_linejoin = LineJoin::Bevel,
+			_ => ()
+		}
+		
+		match get("visibility") {
+			Some("visible") => style.visible = true,
+			Some("hidden" | "collapse") => style.visible = false,
+			_ => ()
+		}
+		
+		Some(style)
+	}
+	
+	/// Resolves a paint to a color, transparent paints are `None`.
+	fn paint(&self, paint: &Paint, opacity: f32) -> Option<Vec4<f32>> {
+		let color = match paint {
+			Paint::None            => return None,
+			Paint::CurrentColor    => self.color,
+			Paint::Color(color)    => *color,
+			Paint::Url(_, fallback) => return self.paint(fallback, opacity)
+		};
+		
+		let Color([r, g, b, a]) = color.with_opacity(opacity * self.opacity);
+		(a > 0.0).then_some(Vec4(r, g, b, a))
+	}
+}
+
+struct Converter {
+	/// The size of the view box or the viewport, which percentages refer to
+	viewport: Vec2<f32>,
+	shapes:   Vec<SvgShape>
+}
+
+impl Converter {
+	/// The reference length of percentages that are neither horizontal nor vertical.
+	fn diagonal(&self) -> f32 {
+		(self.viewport.len2() * 0.5).sqrt()
+	}
+	
+	fn x(&self, s: &str, style: &Style) -> Result<f32, DeError> {
+		Ok(length(s, style.font_size, self.viewport.0)?.unwrap_or(0.0))
+	}
+	
+	fn y(&self, s: &str, style: &Style) -> Result<f32, DeError> {
+		Ok(length(s, style.font_size, self.viewport.1)?.unwrap_or(0.0))
+	}
+	
+	fn r(&self, s: &str, style: &Style) -> Result<f32, DeError> {
+		Ok(length(s, style.font_size, self.diagonal())?.unwrap_or(0.0))
+	}
+	
+	fn elements(&mut self, elements: &[svg::Element], parent: &Style, transform: Transform) -> Result<(), DeError> {
+		use svg::Element::*;
+		
+		for element in elements {
+			let style = match parent.cascade(|property| element.presentation(property), self.diagonal()) {
+				Some(style) => style,
+				None => continue
+			};
+			
+			let transform = transform * element.transform().parse::<Transform>()?;
+			let commands = match element {
+				G(g) => {
+					self.elements(&g.elements, &style, transform)?;
+					continue;
+				}
+				Rect(rect) => self.rect(rect, &style)?,
+				Circle(circle) => {
+					let r = self.r(&circle.r, &style)?;
+					ellipse(self.x(&circle.cx, &style)?, self.y(&circle.cy, &style)?, r, r)
+				}
+				Ellipse(e) => ellipse(
+					self.x(&e.cx, &style)?, self.y(&e.cy, &style)?,
+					self.x(&e.rx, &style)?, self.y(&e.ry, &style)?
+				),
+				Line(line) => vec![
+					PathCommand::Move([self.x(&line.x1, &style)?, self.y(&line.y1, &style)?]),
+					PathCommand::Line([self.x(&line.x2, &style)?, self.y(&line.y2, &style)?])
+				],
+				Polyline(polyline) => points(&polyline.points, false)?,
+				Polygon(polygon) => points(&polygon.points, true)?,
+				Path(path) => PathData::new(&path.d).map_while(Result::ok).collect(),
+				Image(_) | Text(_) | TextPath | Unknown => continue
+			};
+			
+			if style.visible && !commands.is_empty() {
+				self.shape(&commands, &style, transform);
+			}
+		}
+		
+		Ok(())
+	}
+	
+	fn shape(&mut self, commands: &[PathCommand], style: &Style, transform: Transform) {
+		let fill = style.paint(&style.fill, style.fill_opacity)
+			.map(|color| SvgFill { color, rule: style.fill_rule });
+		let stroke = style.paint(&style.stroke, style.stroke_opacity)
+			.filter(|_| style.stroke_width > 0.0)
+			.map(|color| SvgStroke {
+				color,
+				width:       style.stroke_width * transform.scale_factor(),
+				cap:         style.stroke_linecap,
+				join:        style.stroke_linejoin,
+				miter_limit: style.stroke_miterlimit
+			});
+		
+		if fill.is_some() || stroke.is_some() {
+			self.shapes.push(SvgShape { path: path(commands, transform), fill, stroke });
+		}
+	}
+	
+	fn rect(&self, rect: &svg::Rect, style: &Style) -> Result<Vec<PathCommand>, DeError> {
+		use PathCommand::*;
+		
+		let (x, y) = (self.x(&rect.x, style)?, self.y(&rect.y, style)?);
+		let (w, h) = (self.x(&rect.width, style)?, self.y(&rect.height, style)?);
+		if w <= 0.0 || h <= 0.0 {
+			return Ok(Vec::new());
+		}
+		
+		// a missing radius is the same as the other one
+		let (rx, ry) = match (
+			length(&rect.rx, style.font_size, self.viewport.0)?,
+			length(&rect.ry, style.font_size, self.viewport.1)?
+		) {
+			(Some(rx), Some(ry)) => (rx, ry),
+			(Some(r), None) | (None, Some(r)) => (r, r),
+			(None, None) => (0.0, 0.0)
+		};
+		let (rx, ry) = (rx.clamp(0.0, w * 0.5), ry.clamp(0.0, h * 0.5));
+		
+		if rx == 0.0 || ry == 0.0 {
+			return Ok(vec![Move([x, y]), Line([x + w, y]), Line([x + w, y + h]), Line([x, y + h]), Close]);
+		}
+		
+		let (x1, y1) = (x + w, y + h);
+		Ok(vec![
+			Move([x + rx, y]),
+			Line([x1 - rx, y]),
+			corner([x1 - rx, y], [x1, y], [x1, y + ry]),
+			Line([x1, y1 - ry]),
+			corner([x1, y1 - ry], [x1, y1], [x1 - rx, y1]),
+			Line([x + rx, y1]),
+			corner([x + rx, y1], [x, y1], [x, y1 - ry]),
+			Line([x, y + ry]),
+			corner([x, y + ry], [x, y], [x + rx, y]),
+			Close
+		])
+	}
+}
+
+/// Approximates a quarter ellipse from `from` to `to` in the rectangle with the corner `corner`.
+fn corner(from: [f32; 2], corner: [f32; 2], to: [f32; 2]) -> PathCommand {
+	let mix = |a: [f32; 2], b: [f32; 2]| [a[0] + (b[0] - a[0]) * KAPPA, a[1] + (b[1] - a[1]) * KAPPA];
+	PathCommand::Cubic(mix(from, corner), mix(to, corner), to)
+}
+
+fn ellipse(cx: f32, cy: f32, rx: f32, ry: f32) -> Vec<PathCommand> {
+	if rx <= 0.0 || ry <= 0.0 {
+		return Vec::new();
+	}
+	
+	vec![
+		PathCommand::Move([cx + rx, cy]),
+		corner([cx + rx, cy], [cx + rx, cy + ry], [cx, cy + ry]),
+		corner([cx, cy + ry], [cx - rx, cy + ry], [cx - rx, cy]),
+		corner([cx - rx, cy], [cx - rx, cy - ry], [cx, cy - ry]),
+		corner([cx, cy - ry], [cx + rx, cy - ry], [cx + rx, cy]),
+		PathCommand::Close
+	]
+}
+
+/// Converts the `points` of a polyline or polygon, a trailing odd coordinate is ignored.
+fn points(s: &str, close: bool) -> Result<Vec<PathCommand>, DeError> {
+	let mut commands = svg::numbers(s)?
+		.chunks_exact(2)
+		.enumerate()
+		.map(|(i, p)| match i {
+			0 => PathCommand::Move([p[0], p[1]]),
+			_ => PathCommand::Line([p[0], p[1]])
+		})
+		.collect::<Vec<_>>();
+	
+	if close && !commands.is_empty() {
+		commands.push(PathCommand::Close);
+	}
+	
+	Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, core::f32::consts::PI};
+	
+	fn area(triangles: &[Vec2<f32>]) -> f32 {
+		triangles.chunks(3).map(|t| cross(t[1] - t[0], t[2] - t[0]).abs() * 0.5).sum()
+	}
+	
+	fn covers(triangles: &[Vec2<f32>], p: Vec2<f32>) -> bool {
+		triangles.chunks(3).any(|t| {
+			let d = [cross(t[1] - t[0], p - t[0]), cross(t[2] - t[1], p - t[1]), cross(t[0] - t[2], p - t[2])];
+			d.iter().all(|d| *d >= 0.0) || d.iter().all(|d| *d <= 0.0)
+		})
+	}
+	
+	fn distance(p: Vec2<f32>, a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+		let t = ((p - a).dot(b - a) / (b - a).dot(b - a)).clamp(0.0, 1.0);
+		(p - (a + (b - a) * t)).len()
+	}
+	
+	fn square(min: f32, max: f32, reverse: bool) -> Vec<Vec2<f32>> {
+		let mut points = vec![Vec2(min, min), Vec2(max, min), Vec2(max, max), Vec2(min, max)];
+		if reverse {
+			points.reverse();
+		}
+		points
+	}
+	
+	#[test]
+	fn flatten_tolerance() {
+		let (p0, p1, p2, p3) = (Vec2(0.0, 0.0), Vec2(0.0, 40.0), Vec2(60.0, 40.0), Vec2(60.0, 0.0));
+		let curve = |t: f32| {
+			let u = 1.0 - t;
+			p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+		};
+		
+		let mut segments = 0;
+		for tolerance in [2.0, 0.5, 0.1, 0.01] {
+			let polylines = flatten(&[PathElement::Move(p0), PathElement::Curve3(p1, p2, p3)], tolerance);
+			assert_eq!(polylines.len(), 1);
+			
+			let (points, closed) = &polylines[0];
+			assert!(!closed);
+			assert_eq!((points[0], points[points.len() - 1]), (p0, p3));
+			assert!(points.len() - 1 > segments, "{} segments at {}", points.len() - 1, tolerance);
+			segments = points.len() - 1;
+			
+			let error = (0..=1000)
+				.map(|i| curve(i as f32 / 1000.0))
+				.map(|p| points.windows(2).map(|s| distance(p, s[0], s[1])).fold(f32::INFINITY, f32::min))
+				.fold(0.0, f32::max);
+			assert!(error <= tolerance, "error {} at {}", error, tolerance);
+		}
+		
+		// subpaths are split at moves and closed when they return to their start
+		let polylines = flatten(&[
+			PathElement::Move(Vec2(0.0, 0.0)),
+			PathElement::Curve2(Vec2(5.0, 5.0), Vec2(10.0, 0.0)),
+			PathElement::Curve1(Vec2(0.0, 0.0)),
+			PathElement::Move(Vec2(20.0, 0.0)),
+			PathElement::Curve1(Vec2(30.0, 0.0))
+		], 0.1);
+		assert_eq!(polylines.len(), 2);
+		assert!(polylines[0].1 && polylines[0].0.first() != polylines[0].0.last());
+		assert_eq!(polylines[1], (vec![Vec2(20.0, 0.0), Vec2(30.0, 0.0)], false));
+	}
+	
+	#[test]
+	fn fill_rules() {
+		let outer = square(0.0, 10.0, false);
+		for reverse in [false, true] {
+			let inner = square(2.0, 8.0, reverse);
+			let polygons = [outer.as_slice(), inner.as_slice()];
+			
+			let even_odd = fill(&polygons, FillRule::EvenOdd);
+			assert!((area(&even_odd) - 64.0).abs() < 1e-3, "{}", area(&even_odd));
+			assert!(!covers(&even_odd, Vec2(5.0, 5.0)));
+			assert!(covers(&even_odd, Vec2(1.0, 5.0)));
+			
+			// with the same orientation the inner square has a winding number of 2
+			let non_zero = fill(&polygons, FillRule::NonZero);
+			let expected = if reverse { 64.0 } else { 100.0 };
+			assert!((area(&non_zero) - expected).abs() < 1e-3, "{}", area(&non_zero));
+			assert_eq!(covers(&non_zero, Vec2(5.0, 5.0)), !reverse);
+		}
+		
+		// two overlapping squares, the overlap is a hole only for even-odd
+		let (a, b) = (square(0.0, 4.0, false), square(2.0, 6.0, false));
+		assert!((area(&fill(&[&a, &b], FillRule::NonZero)) - 28.0).abs() < 1e-3);
+		assert!((area(&fill(&[&a, &b], FillRule::EvenOdd)) - 24.0).abs() < 1e-3);
+	}
+	
+	#[test]
+	fn stroke_joins() {
+		let corner = [(vec![Vec2(0.0, 0.0), Vec2(10.0, 0.0), Vec2(10.0, 10.0)], false)];
+		let style = |join, miter_limit| SvgStroke { width: 2.0, join, miter_limit, ..SvgStroke::default() };
+		
+		// two segments of 20 plus the join on the outer side of the corner
+		for (stroke_style, expected) in [
+			(style(LineJoin::Bevel, 4.0), 40.5),
+			(style(LineJoin::Miter, 4.0), 41.0),
+			(style(LineJoin::Miter, 1.0), 40.5),
+			(style(LineJoin::Round, 4.0), 40.0 + PI * 0.25)
+		] {
+			let triangles = stroke(&corner, &stroke_style, 0.001);
+			assert!((area(&triangles) - expected).abs() < 1e-2, "{:?}: {}", stroke_style.join, area(&triangles));
+			assert!(covers(&triangles, Vec2(10.5, -0.5)));
+		}
+		
+		let miter = stroke(&corner, &style(LineJoin::Miter, 4.0), 0.1);
+		assert!(covers(&miter, Vec2(10.9, -0.9)));
+		let bevel = stroke(&corner, &style(LineJoin::Bevel, 4.0), 0.1);
+		assert!(!covers(&bevel, Vec2(10.9, -0.9)));
+		
+		// collinear segments don't need a join
+		let line = [(vec![Vec2(0.0, 0.0), Vec2(5.0, 0.0), Vec2(10.0, 0.0)], false)];
+		assert!((area(&stroke(&line, &style(LineJoin::Round, 4.0), 0.001)) - 20.0).abs() < 1e-3);
+	}
+	
+	#[test]
+	fn stroke_caps() {
+		let line = [(vec![Vec2(0.0, 0.0), Vec2(10.0, 0.0)], false)];
+		let dot = [(vec![Vec2(0.0, 0.0)], false)];
+		
+		for (cap, line_area, dot_area) in [(LineCap::Butt, 20.0, 0.0), (LineCap::Square, 24.0, 4.0), (LineCap::Round, 20.0 + PI, PI)] {
+			let style = SvgStroke { width: 2.0, cap, ..SvgStroke::default() };
+			let triangles = stroke(&line, &style, 0.001);
+			assert!((area(&triangles) - line_area).abs() < 1e-2, "{:?}: {}", cap, area(&triangles));
+			assert_eq!(covers(&triangles, Vec2(-0.5, 0.0)), cap != LineCap::Butt);
+			assert!((area(&stroke(&dot, &style, 0.001)) - dot_area).abs() < 1e-2, "{:?}", cap);
+		}
+		
+		// closed subpaths have no caps
+		let style = SvgStroke { width: 2.0, cap: LineCap::Square, join: LineJoin::Bevel, ..SvgStroke::default() };
+		let triangles = stroke(&[(square(0.0, 10.0, false), true)], &style, 0.1);
+		assert!((area(&triangles) - (4.0 * 20.0 + 4.0 * 0.5)).abs() < 1e-3, "{}", area(&triangles));
+		assert!(!covers(&triangles, Vec2(-1.5, 0.0)));
+	}
+}
@@ -0,0 +1,941 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Text layout with bidirectional text, OpenType shaping and line breaking.
+//!
+//! The text is split into paragraphs, the embedding levels are resolved with the Unicode
+//! bidirectional algorithm (UAX #9) and the paragraphs are split into items of a single level
+//! and script, which are shaped separately. Lines are broken at the opportunities of UAX #14,
+//! then the runs of each line are reordered for display. Positions are in the units of
+//! [`LayoutOptions::size`], the y axis points down and the origin is the top left corner of
+//! the layout.
+
+use {
+	super::*,
+	std::{cmp::Ordering, ops::Range},
+	self::{bidi::BidiClass, line_break::{Break, LineBreak}}
+};
+
+pub mod ansi;
+pub mod bidi;
+pub mod line_break;
+pub mod markup;
+mod shaping;
+mod tables;
+
+/// Looks up a character in a table of sorted, inclusive ranges.
+fn lookup<T: Copy>(table: &[(u32, u32, T)], ch: char) -> Option<T> {
+	let ch = ch as u32;
+	table.binary_search_by(|&(start, end, _)| match () {
+		_ if end < ch   => Ordering::Less,
+		_ if start > ch => Ordering::Greater,
+		_               => Ordering::Equal
+	}).ok().map(|i| table[i].2)
+}
+
+/// The scripts that are shaped with script specific rules or OpenType script tags. All other
+/// scripts are `Other` and shaped with the default script.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Script {
+	Common,
+	Inherited,
+	Other,
+	Arabic,
+	Armenian,
+	Bengali,
+	Bopomofo,
+	Cyrillic,
+	Devanagari,
+	Ethiopic,
+	Georgian,
+	Greek,
+	Gujarati,
+	Gurmukhi,
+	Han,
+	Hangul,
+	Hebrew,
+	Hiragana,
+	Kannada,
+	Katakana,
+	Khmer,
+	Lao,
+	Latin,
+	Malayalam,
+	Mongolian,
+	Myanmar,
+	Nko,
+	Oriya,
+	Sinhala,
+	Syriac,
+	Tamil,
+	Telugu,
+	Thaana,
+	Thai,
+	Tibetan
+}
+
+impl Script {
+	pub fn of(ch: char) -> Self {
+		lookup(tables::script::SCRIPTS, ch).unwrap_or(Self::Other)
+	}
+	
+	/// The OpenType script tags, the preferred one first.
+	pub fn tags(self) -> &'static [&'static [u8; 4]] {
+		match self {
+			Self::Common | Self::Inherited | Self::Other => &[],
+			Self::Arabic     => &[b"arab"],
+			Self::Armenian   => &[b"armn"],
+			Self::Bengali    => &[b"bng2", b"beng"],
+			Self::Bopomofo   => &[b"bopo"],
+			Self::Cyrillic   => &[b"cyrl"],
+			Self::Devanagari => &[b"dev2", b"deva"],
+			Self::Ethiopic   => &[b"ethi"],
+			Self::Georgian   => &[b"geor"],
+			Self::Greek      => &[b"grek"],
+			Self::Gujarati   => &[b"gjr2", b"gujr"],
+			Self::Gurmukhi   => &[b"gur2", b"guru"],
+			Self::Han        => &[b"hani"],
+			Self::Hangul     => &[b"hang"],
+			Self::Hebrew     => &[b"hebr"],
+			Self::Hiragana   => &[b"kana"],
+			Self::Kannada    => &[b"knd2", b"knda"],
+			Self::Katakana   => &[b"kana"],
+			Self::Khmer      => &[b"khmr"],
+			Self::Lao        => &[b"lao "],
+			Self::Latin      => &[b"latn"],
+			Self::Malayalam  => &[b"mlm2", b"mlym"],
+			Self::Mongolian  => &[b"mong"],
+			Self::Myanmar    => &[b"mym2", b"mymr"],
+			Self::Nko        => &[b"nko "],
+			Self::Oriya      => &[b"ory2", b"orya"],
+			Self::Sinhala    => &[b"sinh"],
+			Self::Syriac     => &[b"syrc"],
+			Self::Tamil      => &[b"tml2", b"taml"],
+			Self::Telugu     => &[b"tel2", b"telu"],
+			Self::Thaana     => &[b"thaa"],
+			Self::Thai       => &[b"thai"],
+			Self::Tibetan    => &[b"tibt"]
+		}
+	}
+	
+	/// Returns `true` for scripts with joining forms.
+	fn is_joining(self) -> bool {
+		matches!(self, Self::Arabic | Self::Syriac | Self::Nko | Self::Mongolian)
+	}
+	
+	/// The first code point of the block of an Indic script.
+	fn indic_block(self) -> Option<u32> {
+		Some(match self {
+			Self::Devanagari => 0x0900,
+			Self::Bengali    => 0x0980,
+			Self::Gurmukhi   => 0x0A00,
+			Self::Gujarati   => 0x0A80,
+			Self::Oriya      => 0x0B00,
+			Self::Tamil      => 0x0B80,
+			Self::Telugu     => 0x0C00,
+			Self::Kannada    => 0x0C80,
+			Self::Malayalam  => 0x0D00,
+			_ => return None
+		})
+	}
+	
+	/// Returns `true` if a ra followed by a halant at the start of a syllable forms a reph.
+	fn has_implicit_reph(self) -> bool {
+		matches!(self, Self::Devanagari | Self::Bengali | Self::Gujarati | Self::Oriya | Self::Kannada | Self::Telugu)
+	}
+}
+
+/// The options of a [`TextLayout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutOptions {
+	/// The distance between the ascender and the descender of the font.
+	pub size:      f32,
+	/// The maximum width of a line, longer lines are broken.
+	pub max_width: Option<f32>,
+	pub align:     UiHorzAlign,
+	/// The base direction of the paragraphs, detected from the first strong character if `None`.
+	pub direction: Option<Direction>,
+	pub language:  Option<Tag>,
+	/// Features applied in addition to the default features of the scripts.
+	pub features:  Vec<Feature>
+}
+
+impl Default for LayoutOptions {
+	fn default() -> Self {
+		Self {
+			size:      1f32,
+			max_width: None,
+			align:     UiHorzAlign::Left,
+			direction: None,
+			language:  None,
+			features:  Vec::new()
+		}
+	}
+}
+
+impl LayoutOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	pub fn size(mut self, size: f32) -> Self {
+		self.size = size;
+		self
+	}
+	
+	pub fn max_width(mut self, max_width: f32) -> Self {
+		self.max_width = Some(max_width);
+		self
+	}
+	
+	pub fn align(mut self, align: UiHorzAlign) -> Self {
+		self.align = align;
+		self
+	}
+	
+	pub fn direction(mut self, direction: Direction) -> Self {
+		self.direction = Some(direction);
+		self
+	}
+	
+	pub fn language(mut self, language: Tag) -> Self {
+		self.language = Some(language);
+		self
+	}
+	
+	pub fn feature(mut self, feature: Feature) -> Self {
+		self.features.push(feature);
+		self
+	}
+	
+	/// Sets the size and the maximum width from ui values, the size is converted with the
+	/// vertical and the width with the horizontal ratios of the cache.
+	pub fn ui_size(mut self, size: Val, max_width: Option<Val>, cache: &Vec2<UiTransformCache>) -> Self {
+		self.size = cache.1.to_norm(size);
+		self.max_width = max_width.map(|width| cache.0.to_norm(width));
+		self
+	}
+}
+
+/// A glyph of a [`TextLayout`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PositionedGlyph {
+	pub id:      GlyphId,
+	/// The byte offset of the first character of the cluster the glyph belongs to.
+	pub cluster: usize,
+	/// The position of the origin of the glyph on the baseline.
+	pub pos:     Vec2<f32>,
+	pub advance: f32
+}
+
+/// A run of glyphs of a line with the same level and script, the glyphs are in visual order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphRun {
+	/// The byte range of the run in the text.
+	pub range:     Range<usize>,
+	pub direction: Direction,
+	pub script:    Script,
+	/// The bidi embedding level.
+	pub level:     u8,
+	/// The position of the left edge of the run.
+	pub x:         f32,
+	pub width:     f32,
+	pub glyphs:    Vec<PositionedGlyph>,
+	/// The clusters in visual order.
+	clusters:      Vec<Cluster>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Cluster {
+	range: Range<usize>,
+	x:     f32,
+	width: f32
+}
+
+/// A line of a [`TextLayout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+	/// The byte range of the line in the text, including trailing whitespace and the line break.
+	pub range:     Range<usize>,
+	/// The indices of the runs of the line, in visual order.
+	pub runs:      Range<usize>,
+	/// The direction of the paragraph.
+	pub direction: Direction,
+	pub top:       f32,
+	pub baseline:  f32,
+	/// The position of the left edge of the line, without trailing whitespace.
+	pub x:         f32,
+	/// The width of the line, without trailing whitespace.
+	pub width:     f32
+}
+
+/// The position of a caret in a [`TextLayout`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Caret {
+	/// The top of the caret.
+	pub pos:       Vec2<f32>,
+	pub height:    f32,
+	pub line:      usize,
+	/// The direction of the run the caret is in.
+	pub direction: Direction
+}
+
+/// A laid out text, made of lines of glyph runs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+	pub lines:   Vec<Line>,
+	pub runs:    Vec<GlyphRun>,
+	/// The size of the layout, the width is the maximum width if one was given.
+	pub size:    Vec2<f32>,
+	line_height: f32,
+	/// The byte offsets of the characters and the length of the text.
+	boundaries:  Vec<usize>
+}
+
+/// A shaped item, a part of a paragraph with a single level and script.
+struct Item {
+	chars:     Range<usize>,
+	level:     u8,
+	script:    Script,
+	glyphs:    Vec<GlyphInfo>,
+	positions: Vec<GlyphPosition>
+}
+
+fn is_whitespace(ch: char) -> bool {
+	matches!(LineBreak::of(ch), LineBreak::SP | LineBreak::BK | LineBreak::CR | LineBreak::LF | LineBreak::NL | LineBreak::ZW)
+}
+
+fn direction(level: u8) -> Direction {
+	match level % 2 {
+		0 => Direction::LeftToRight,
+		_ => Direction::RightToLeft
+	}
+}
+
+/// Resolves the scripts of the characters, `Common` and `Inherited` characters take the script
+/// of the preceding character, or of the following one at the start of the text.
+fn resolve_scripts(chars: &[char]) -> Vec<Script> {
+	let mut scripts = chars.iter().map(|&ch| Script::of(ch)).collect::<Vec<_>>();
+	let is_real = |script: &Script| !matches!(script, Script::Common | Script::Inherited);
+	let first = scripts.iter().copied().find(is_real).unwrap_or(Script::Common);
+	let mut last = first;
+	for script in &mut scripts {
+		match is_real(script) {
+			true  => last = *script,
+			false => *script = last
+		}
+	}
+	scripts
+}
+
+/// Splits the characters into paragraphs, the separator belongs to the paragraph it ends.
+fn paragraphs(chars: &[char]) -> Vec<Range<usize>> {
+	let mut paragraphs = Vec::new();
+	let mut start = 0;
+	for (i, &ch) in chars.iter().enumerate() {
+		if BidiClass::of(ch) == BidiClass::B && !(ch == '\r' && chars.get(i + 1) == Some(&'\n')) {
+			paragraphs.push(start..i + 1);
+			start = i + 1;
+		}
+	}
+	
+	if start < chars.len() || paragraphs.is_empty() {
+		paragraphs.push(start..chars.len());
+	}
+	paragraphs
+}
+
+impl TextLayout {
+	pub fn new(text: &str, font: &mut dyn FontReader, descriptor: &FontDescriptor, options: &LayoutOptions) -> Self {
+		let height = (descriptor.ascender as f32 - descriptor.descender as f32).max(1f32);
+		let scale = options.size / height;
+		let ascent = descriptor.ascender as f32 * scale;
+		let line_height = (height + descriptor.line_gap as f32) * scale;
+		
+		let mut boundaries = text.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+		boundaries.push(text.len());
+		let mut layout = Self { line_height, boundaries, ..Self::default() };
+		let chars = text.chars().collect::<Vec<_>>();
+		let char_of = |offset: usize| layout.boundaries.partition_point(|&i| i < offset);
+		
+		// itemize and shape
+		let paragraphs = paragraphs(&chars);
+		let scripts = resolve_scripts(&chars);
+		let mut levels = vec![0u8; chars.len()];
+		let mut paragraph_levels = Vec::with_capacity(paragraphs.len());
+		let mut items = Vec::new();
+		for range in &paragraphs {
+			let (level, resolved) = bidi::resolve_levels(&chars[range.clone()], options.direction);
+			levels[range.clone()].copy_from_slice(&resolved);
+			paragraph_levels.push(level);
+			
+			let mut start = range.start;
+			for i in range.start + 1..=range.end {
+				if i < range.end && levels[i] == levels[start] && scripts[i] == scripts[start] {
+					continue;
+				}
+				
+				let (glyphs, positions) = shaping::shape(
+					font,
+					text,
+					layout.boundaries[start]..layout.boundaries[i],
+					scripts[start],
+					direction(levels[start]),
+					options.language,
+					&options.features
+				);
+				items.push(Item { chars: start..i, level: levels[start], script: scripts[start], glyphs, positions });
+				start = i;
+			}
+		}
+		
+		let mut advances = vec![0f32; chars.len()];
+		let mut cluster_starts = vec![false; chars.len()];
+		for item in &items {
+			for (glyph, pos) in item.glyphs.iter().zip(&item.positions) {
+				advances[char_of(glyph.cluster)] += pos.x_advance as f32 * scale;
+				cluster_starts[char_of(glyph.cluster)] = true;
+			}
+		}
+		
+		let visible_end = |start: usize, mut end: usize| {
+			while end > start && is_whitespace(chars[end - 1]) {
+				end -= 1;
+			}
+			end
+		};
+		let width = |start: usize, end: usize| advances[start..visible_end(start, end)].iter().sum::<f32>();
+		
+		// the last cluster boundary after `start` where the line still fits, at least one cluster
+		let fit = |start: usize, end: usize, max: f32| {
+			let mut split = (start + 1..end).find(|&i| cluster_starts[i]).unwrap_or(end);
+			let mut x = 0f32;
+			for i in start + 1..end {
+				x += advances[i - 1];
+				if cluster_starts[i] && i > split && x <= max {
+					split = i;
+				}
+			}
+			split
+		};
+		
+		// break lines, the flag is set if the line is wrapped
+		let mut lines = Vec::new();
+		let mut start = 0;
+		let mut candidate = None;
+		for (offset, kind) in line_break::line_breaks(text) {
+			let end = char_of(offset);
+			if let Some(max) = options.max_width {
+				if width(start, end) > max {
+					if let Some(candidate) = candidate.take() {
+						lines.push((start..candidate, true));
+						start = candidate;
+					}
+					
+					while width(start, end) > max {
+						let split = fit(start, end, max);
+						if split >= end {
+							break;
+						}
+						lines.push((start..split, true));
+						start = split;
+					}
+				}
+			}
+			
+			match kind {
+				Break::Mandatory => {
+					lines.push((start..end, false));
+					start = end;
+					candidate = None;
+				}
+				Break::Allowed => candidate = Some(end)
+			}
+		}
+		
+		if lines.is_empty() || chars.last().is_some_and(|&ch| matches!(
+			LineBreak::of(ch), LineBreak::BK | LineBreak::CR | LineBreak::LF | LineBreak::NL)) {
+			lines.push((chars.len()..chars.len(), false));
+		}
+		
+		// lay out the lines, relative to the start of the line
+		for (i, (range, wrapped)) in lines.into_iter().enumerate() {
+			let paragraph = paragraphs.iter().rposition(|p| p.start <= range.start).unwrap_or(0);
+			let paragraph_level = paragraph_levels[paragraph];
+			let mut line_levels = levels[range.clone()].to_vec();
+			bidi::reset_whitespace(&chars[range.clone()], &mut line_levels, paragraph_level);
+			
+			let visible = visible_end(range.start, range.end);
+			let line_width = width(range.start, range.end);
+			let trailing = advances[visible..range.end].iter().sum::<f32>();
+			let top = i as f32 * line_height;
+			
+			// split the line into runs of the same item and level
+			let mut runs = Vec::<(Range<usize>, usize, u8)>::new();
+			let mut item = items.iter().position(|item| item.chars.contains(&range.start)).unwrap_or(0);
+			for k in range.clone() {
+				while !items[item].chars.contains(&k) {
+					item += 1;
+				}
+				
+				let level = line_levels[k - range.start];
+				match runs.last_mut() {
+					Some((run, run_item, run_level)) if *run_item == item && *run_level == level => run.end = k + 1,
+					_ => runs.push((k..k + 1, item, level))
+				}
+			}
+			
+			let is_space = |glyph: &GlyphInfo| {
+				let k = char_of(glyph.cluster);
+				k < visible && LineBreak::of(chars[k]) == LineBreak::SP
+			};
+			let spaces = items.iter()
+				.flat_map(|item| &item.glyphs)
+				.filter(|glyph| range.contains(&char_of(glyph.cluster)) && is_space(glyph))
+				.count();
+			let extra = match (options.align, options.max_width) {
+				(UiHorzAlign::Justify, Some(max)) if wrapped && spaces > 0 => (max - line_width).max(0f32) / spaces as f32,
+				_ => 0f32
+			};
+			
+			let run_levels = runs.iter().map(|(_, _, level)| *level).collect::<Vec<_>>();
+			let first_run = layout.runs.len();
+			let mut pen = 0f32;
+			for k in bidi::reorder(&run_levels) {
+				let (chars_range, item, level) = runs[k].clone();
+				let item = &items[item];
+				let bytes = layout.boundaries[chars_range.start]..layout.boundaries[chars_range.end];
+				let mut glyphs = item.glyphs.iter()
+					.zip(&item.positions)
+					.filter(|(glyph, _)| bytes.contains(&glyph.cluster))
+					.collect::<Vec<_>>();
+				if level % 2 == 1 {
+					glyphs.reverse();
+				}
+				
+				let mut run = GlyphRun {
+					range:     bytes,
+					direction: direction(level),
+					script:    item.script,
+					level,
+					x:         pen,
+					width:     0f32,
+					glyphs:    Vec::with_capacity(glyphs.len()),
+					clusters:  Vec::new()
+				};
+				
+				for (glyph, pos) in glyphs {
+					let advance = pos.x_advance as f32 * scale + if is_space(glyph) { extra } else { 0f32 };
+					run.glyphs.push(PositionedGlyph {
+						id:      glyph.id,
+						cluster: glyph.cluster,
+						pos:     Vec2(pen + pos.x_offset as f32 * scale, top + ascent - pos.y_offset as f32 * scale),
+						advance
+					});
+					
+					match run.clusters.last_mut() {
+						Some(cluster) if cluster.range.start == glyph.cluster => cluster.width += advance,
+						_ => run.clusters.push(Cluster { range: glyph.cluster..glyph.cluster, x: pen, width: advance })
+					}
+					pen += advance;
+				}
+				
+				// the clusters end at the next cluster in logical order, ignorables at the end
+				// of the run do not belong to a cluster
+				let mut starts = run.clusters.iter().map(|c| c.range.start).collect::<Vec<_>>();
+				starts.sort_unstable();
+				for cluster in &mut run.clusters {
+					let next = starts.partition_point(|&s| s <= cluster.range.start);
+					let mut end = char_of(starts.get(next).copied().unwrap_or(run.range.end));
+					while starts.get(next).is_none()
+						&& end > char_of(cluster.range.start) + 1
+						&& shaping::is_ignorable(chars[end - 1]) {
+						end -= 1;
+					}
+					
+					if Some(&cluster.range.start) == starts.first() {
+						cluster.range.start = run.range.start;
+					}
+					cluster.range.end = layout.boundaries[end];
+				}
+				
+				run.width = pen - run.x;
+				layout.runs.push(run);
+			}
+			
+			let direction = direction(paragraph_level);
+			layout.lines.push(Line {
+				range:    layout.boundaries[range.start]..layout.boundaries[range.end],
+				runs:     first_run..layout.runs.len(),
+				direction,
+				top,
+				baseline: top + ascent,
+				// trailing whitespace is on the left of right-to-left lines
+				x:        if direction == Direction::RightToLeft { trailing } else { 0f32 },
+				width:    line_width + extra * spaces as f32
+			});
+		}
+		
+		// align the lines
+		let available = options.max_width
+			.unwrap_or_else(|| layout.lines.iter().map(|line| line.width).fold(0f32, f32::max));
+		for line in &mut layout.lines {
+			let x = match options.align {
+				UiHorzAlign::Left => 0f32,
+				UiHorzAlign::Center => (available - line.width) * 0.5,
+				UiHorzAlign::Right => available - line.width,
+				UiHorzAlign::Justify => match line.direction {
+					Direction::LeftToRight => 0f32,
+					Direction::RightToLeft => available - line.width
+				}
+			};
+			
+			let shift = x - line.x;
+			line.x = x;
+			for run in &mut layout.runs[line.runs.clone()] {
+				run.x += shift;
+				run.glyphs.iter_mut().for_each(|glyph| glyph.pos.0 += shift);
+				run.clusters.iter_mut().for_each(|cluster| cluster.x += shift);
+			}
+		}
+		
+		layout.size = Vec2(available, layout.lines.len() as f32 * line_height);
+		layout
+	}
+	
+	/// Returns all glyphs of the layout.
+	pub fn glyphs(&self) -> impl Iterator<Item = &PositionedGlyph> {
+		self.runs.iter().flat_map(|run| &run.glyphs)
+	}
+	
+	/// The x position of the caret before the character at `index` within a cluster,
+	/// multi-character clusters like ligatures are split evenly.
+	fn cluster_edge(&self, run: &GlyphRun, cluster: &Cluster, index: usize) -> f32 {
+		let char_of = |offset: usize| self.boundaries.partition_point(|&i| i < offset);
+		let chars = (char_of(cluster.range.end) - char_of(cluster.range.start)).max(1);
+		let fraction = (char_of(index) - char_of(cluster.range.start)) as f32 / chars as f32;
+		match run.direction {
+			Direction::LeftToRight => cluster.x + cluster.width * fraction,
+			Direction::RightToLeft => cluster.x + cluster.width * (1f32 - fraction)
+		}
+	}
+	
+	/// Returns the caret position before the character at the given byte offset.
+	pub fn caret(&self, index: usize) -> Caret {
+		let line = self.lines.iter().rposition(|line| line.range.start <= index).unwrap_or(0);
+		let Some(l) = self.lines.get(line) else {
+			return Caret { pos: Vec2::default(), height: self.line_height, line, direction: Direction::LeftToRight };
+		};
+		
+		let runs = &self.runs[l.runs.clone()];
+		let clusters = || runs.iter().flat_map(|run| run.clusters.iter().map(move |cluster| (run, cluster)));
+		let (x, direction) = clusters()
+			.find(|(_, cluster)| cluster.range.contains(&index))
+			.or_else(|| clusters().find(|(_, cluster)| cluster.range.end == index))
+			.map_or_else(
+				|| (match l.direction {
+					Direction::LeftToRight => l.x,
+					Direction::RightToLeft => l.x + l.width
+				}, l.direction),
+				|(run, cluster)| (self.cluster_edge(run, cluster, index), run.direction)
+			);
+		
+		Caret { pos: Vec2(x, l.top), height: self.line_height, line, direction }
+	}
+	
+	/// Returns the byte offset of the caret position closest to a point.
+	pub fn hit_test(&self, point: Vec2<f32>) -> usize {
+		let Some(line) = self.lines.iter()
+			.position(|line| point.1 < line.top + self.line_height)
+			.or_else(|| self.lines.len().checked_sub(1)) else {
+			return 0;
+		};
+		
+		let l = &self.lines[line];
+		let distance = |cluster: &Cluster| match point.0 {
+			x if x < cluster.x => cluster.x - x,
+			x if x > cluster.x + cluster.width => x - cluster.x - cluster.width,
+			_ => 0f32
+		};
+		
+		let Some((run, cluster)) = self.runs[l.runs.clone()].iter()
+			.flat_map(|run| run.clusters.iter().map(move |cluster| (run, cluster)))
+			.min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b))) else {
+			return l.range.start;
+		};
+		
+		let start = self.boundaries.partition_point(|&i| i < cluster.range.start);
+		let end = self.boundaries.partition_point(|&i| i < cluster.range.end);
+		let mut fraction = match cluster.width {
+			width if width > 0f32 => ((point.0 - cluster.x) / width).clamp(0f32, 1f32),
+			_ => 0f32
+		};
+		if run.direction == Direction::RightToLeft {
+			fraction = 1f32 - fraction;
+		}
+		
+		let index = self.boundaries[start + (fraction * (end - start) as f32).round() as usize];
+		
+		// the end of a line is the start of the next one, stay on this line
+		if index == l.range.end && line + 1 < self.lines.len() && index > l.range.start {
+			self.boundaries[self.boundaries.partition_point(|&i| i < index) - 1]
+		} else {
+			index
+		}
+	}
+	
+	/// Returns the rectangles covering the characters in a byte range, as top left and bottom
+	/// right corners.
+	pub fn selection(&self, range: Range<usize>) -> Vec<(Vec2<f32>, Vec2<f32>)> {
+		let mut rects = Vec::<(Vec2<f32>, Vec2<f32>)>::new();
+		for line in &self.lines {
+			for run in &self.runs[line.runs.clone()] {
+				for cluster in &run.clusters {
+					let start = range.start.max(cluster.range.start);
+					let end = range.end.min(cluster.range.end);
+					if start >= end {
+						continue;
+					}
+					
+					let a = self.cluster_edge(run, cluster, start);
+					let b = self.cluster_edge(run, cluster, end);
+					let (min, max) = (a.min(b), a.max(b));
+					match rects.last_mut() {
+						Some(rect) if rect.0.1 == line.top && (rect.1.0 - min).abs() < 1e-4 => rect.1.0 = max,
+						_ => rects.push((Vec2(min, line.top), Vec2(max, line.top + self.line_height)))
+					}
+				}
+			}
+		}
+		rects
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	/// A font with a glyph for every character of the basic multilingual plane, except for
+	/// control characters. The glyph ids are the code points, the advance is 10 units, except
+	/// for combining diacritical marks, and "fi" is a ligature. The features it is asked to
+	/// apply are recorded with the clusters of the glyphs they apply to.
+	#[derive(Default)]
+	pub(super) struct TestFont {
+		pub(super) applied: Vec<(Tag, Vec<usize>)>
+	}
+	
+	impl FontReader for TestFont {
+		fn glyph_index(&mut self, ch: char) -> Option<GlyphId> {
+			match ch as u32 {
+				_ if ch.is_control() => None,
+				ch @ 0..=0xFFFF => Some(GlyphId(ch as u16)),
+				_ => None
+			}
+		}
+		
+		fn get_glyph(&mut self, _id: GlyphId) -> Option<Glyph> {
+			None
+		}
+		
+		fn get_kerning(&mut self, _id1: GlyphId, _id2: GlyphId) -> Option<i16> {
+			None
+		}
+		
+		fn substitute(&mut self, glyphs: &mut Vec<GlyphInfo>, _script: Tag, _language: Option<Tag>, features: &[Feature]) {
+			for feature in features {
+				let clusters = glyphs.iter().filter(|g| g.mask & feature.mask != 0).map(|g| g.cluster).collect();
+				self.applied.push((feature.tag, clusters));
+				
+				if feature.tag == Tag::new(b"liga") {
+					let mut i = 0;
+					while i + 1 < glyphs.len() {
+						if glyphs[i].id == GlyphId('f' as u16) && glyphs[i + 1].id == GlyphId('i' as u16) {
+							glyphs[i].id = GlyphId(0xFB01);
+							glyphs.remove(i + 1);
+						}
+						i += 1;
+					}
+				}
+			}
+		}
+		
+		fn position(
+			&mut self,
+			glyphs:     &[GlyphInfo],
+			_direction: Direction,
+			_script:    Tag,
+			_language:  Option<Tag>,
+			_features:  &[Feature]
+		) -> Vec<GlyphPosition> {
+			glyphs.iter().map(|glyph| GlyphPosition {
+				x_advance: match glyph.id.0 {
+					0x0300..=0x036F => 0,
+					_ => 10
+				},
+				..GlyphPosition::default()
+			}).collect()
+		}
+		
+		fn scripts(&mut self) -> Vec<Tag> {
+			Vec::new()
+		}
+		
+		fn axes(&mut self) -> Vec<Axis> {
+			Vec::new()
+		}
+		
+		fn set_variation(&mut self, _axis: Tag, _value: f32) -> bool {
+			false
+		}
+	}
+	
+	/// Lays out a text with the test font at a size of 10, so a glyph is 10 wide and a line is
+	/// 10 high, with the baseline at 8.
+	fn layout(text: &str, options: LayoutOptions) -> TextLayout {
+		let descriptor = FontDescriptor { units_per_em: 10, ascender: 8, descender: -2, line_gap: 0, glyphs: 0x10000 };
+		TextLayout::new(text, &mut TestFont::default(), &descriptor, &options.size(10f32))
+	}
+	
+	fn lines(layout: &TextLayout) -> Vec<(Range<usize>, f32, f32)> {
+		layout.lines.iter().map(|line| (line.range.clone(), line.x, line.width)).collect()
+	}
+	
+	#[test]
+	fn wrapping() {
+		let text = layout("aaa bbb ccc", LayoutOptions::new().max_width(75f32));
+		assert_eq!(lines(&text), vec![(0..8, 0f32, 70f32), (8..11, 0f32, 30f32)]);
+		assert_eq!(text.size, Vec2(75f32, 20f32));
+		assert_eq!(text.lines[1].top, 10f32);
+		assert_eq!(text.lines[1].baseline, 18f32);
+		
+		// words longer than a line are broken between clusters
+		let text = layout("abcdefgh", LayoutOptions::new().max_width(35f32));
+		assert_eq!(lines(&text), vec![(0..3, 0f32, 30f32), (3..6, 0f32, 30f32), (6..8, 0f32, 20f32)]);
+		
+		let text = layout("ab\ncd\n", LayoutOptions::new());
+		assert_eq!(lines(&text), vec![(0..3, 0f32, 20f32), (3..6, 0f32, 20f32), (6..6, 0f32, 0f32)]);
+		assert_eq!(text.size, Vec2(20f32, 30f32));
+		
+		let text = layout("", LayoutOptions::new());
+		assert_eq!(lines(&text), vec![(0..0, 0f32, 0f32)]);
+	}
+	
+	#[test]
+	fn alignment() {
+		let x = |align| layout("ab", LayoutOptions::new().max_width(50f32).align(align)).glyphs()
+			.map(|glyph| glyph.pos.0)
+			.collect::<Vec<_>>();
+		assert_eq!(x(UiHorzAlign::Left), vec![0f32, 10f32]);
+		assert_eq!(x(UiHorzAlign::Center), vec![15f32, 25f32]);
+		assert_eq!(x(UiHorzAlign::Right), vec![30f32, 40f32]);
+		assert!(layout("ab", LayoutOptions::new()).glyphs().all(|glyph| glyph.pos.1 == 8f32));
+	}
+	
+	#[test]
+	fn justify() {
+		let text = layout("aa bb cc dd", LayoutOptions::new().max_width(70f32).align(UiHorzAlign::Justify));
+		assert_eq!(lines(&text), vec![(0..6, 0f32, 70f32), (6..11, 0f32, 50f32)]);
+		
+		// the space of the wrapped line is widened, the last line is not justified
+		let x = text.glyphs().map(|glyph| glyph.pos.0).collect::<Vec<_>>();
+		assert_eq!(x, vec![0f32, 10f32, 20f32, 50f32, 60f32, 70f32, 0f32, 10f32, 20f32, 30f32, 40f32]);
+		
+		// right-to-left lines are aligned to the right
+		let text = layout("אב", LayoutOptions::new().max_width(50f32).align(UiHorzAlign::Justify));
+		assert_eq!(lines(&text), vec![(0..4, 30f32, 20f32)]);
+	}
+	
+	#[test]
+	fn bidi_runs() {
+		let text = layout("ab אב", LayoutOptions::new());
+		let runs = text.runs.iter().map(|run| (run.range.clone(), run.level, run.x, run.width)).collect::<Vec<_>>();
+		assert_eq!(runs, vec![(0..3, 0, 0f32, 30f32), (3..7, 1, 30f32, 20f32)]);
+		let ids = text.glyphs().map(|glyph| (glyph.id.0, glyph.pos.0)).collect::<Vec<_>>();
+		assert_eq!(ids, vec![(0x61, 0f32), (0x62, 10f32), (0x20, 20f32), (0x5D1, 30f32), (0x5D0, 40f32)]);
+		
+		// brackets of right-to-left runs are mirrored
+		let text = layout("א(ב)", LayoutOptions::new());
+		let ids = text.glyphs().map(|glyph| glyph.id.0).collect::<Vec<_>>();
+		assert_eq!(ids, vec![0x28, 0x5D1, 0x29, 0x5D0]);
+	}
+	
+	#[test]
+	fn caret() {
+		let text = layout("abc", LayoutOptions::new());
+		assert_eq!(text.caret(1), Caret { pos: Vec2(10f32, 0f32), height: 10f32, line: 0, direction: Direction::LeftToRight });
+		assert_eq!(text.caret(3).pos, Vec2(30f32, 0f32));
+		
+		let text = layout("ab\ncd", LayoutOptions::new());
+		assert_eq!(text.caret(2).pos, Vec2(20f32, 0f32));
+		assert_eq!((text.caret(3).pos, text.caret(3).line), (Vec2(0f32, 10f32), 1));
+		
+		// ligatures are split evenly between their characters
+		let text = layout("fi", LayoutOptions::new());
+		assert_eq!(text.glyphs().count(), 1);
+		assert_eq!(text.caret(1).pos, Vec2(5f32, 0f32));
+		
+		let text = layout("אבג", LayoutOptions::new());
+		assert_eq!(text.caret(0).pos, Vec2(30f32, 0f32));
+		assert_eq!(text.caret(4).pos, Vec2(10f32, 0f32));
+		assert_eq!(text.caret(6).pos, Vec2(0f32, 0f32));
+		assert_eq!(text.caret(6).direction, Direction::RightToLeft);
+	}
+	
+	#[test]
+	fn hit_test() {
+		let text = layout("abc", LayoutOptions::new());
+		assert_eq!(text.hit_test(Vec2(12f32, 5f32)), 1);
+		assert_eq!(text.hit_test(Vec2(18f32, 5f32)), 2);
+		assert_eq!(text.hit_test(Vec2(-5f32, 5f32)), 0);
+		assert_eq!(text.hit_test(Vec2(100f32, 5f32)), 3);
+		
+		// the end of a line stays on the line
+		let text = layout("ab\ncd", LayoutOptions::new());
+		assert_eq!(text.hit_test(Vec2(25f32, 5f32)), 2);
+		assert_eq!(text.hit_test(Vec2(3f32, 15f32)), 3);
+		assert_eq!(text.hit_test(Vec2(3f32, 100f32)), 3);
+		
+		let text = layout("אבג", LayoutOptions::new());
+		assert_eq!(text.hit_test(Vec2(2f32, 5f32)), 6);
+		assert_eq!(text.hit_test(Vec2(28f32, 5f32)), 0);
+	}
+	
+	#[test]
+	fn selection() {
+		let text = layout("abc", LayoutOptions::new());
+		assert_eq!(text.selection(1..3), vec![(Vec2(10f32, 0f32), Vec2(30f32, 10f32))]);
+		assert_eq!(text.selection(1..1), vec![]);
+		
+		let text = layout("ab\ncd", LayoutOptions::new());
+		assert_eq!(text.selection(1..4), vec![
+			(Vec2(10f32, 0f32), Vec2(20f32, 10f32)),
+			(Vec2(0f32, 10f32), Vec2(10f32, 20f32))
+		]);
+		
+		// a logical range is split into visual ranges at direction changes
+		let text = layout("ab אב", LayoutOptions::new());
+		assert_eq!(text.selection(1..5), vec![
+			(Vec2(10f32, 0f32), Vec2(30f32, 10f32)),
+			(Vec2(40f32, 0f32), Vec2(50f32, 10f32))
+		]);
+	}
+}
@@ -0,0 +1,415 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Parser for the text styles of ANSI/VT100 terminals.
+//!
+//! Supported are the SGR (select graphic rendition) sequences, including 256 colors and true
+//! colors, the erase sequences `CSI K` and `CSI J` and hyperlinks (`OSC 8`). All other escape
+//! sequences and control characters except tabs and line breaks are removed from the text.
+
+use super::*;
+
+/// The maximum length of the parameters of a sequence, longer sequences are discarded.
+const MAX_PARAMS_LEN: usize = 1 << 12;
+
+/// The 8 standard and 8 bright colors, as used by xterm.
+pub const COLORS: [Vec4<f64>; 16] = [
+	rgb(0x00, 0x00, 0x00), rgb(0xCD, 0x00, 0x00), rgb(0x00, 0xCD, 0x00), rgb(0xCD, 0xCD, 0x00),
+	rgb(0x00, 0x00, 0xEE), rgb(0xCD, 0x00, 0xCD), rgb(0x00, 0xCD, 0xCD), rgb(0xE5, 0xE5, 0xE5),
+	rgb(0x7F, 0x7F, 0x7F), rgb(0xFF, 0x00, 0x00), rgb(0x00, 0xFF, 0x00), rgb(0xFF, 0xFF, 0x00),
+	rgb(0x5C, 0x5C, 0xFF), rgb(0xFF, 0x00, 0xFF), rgb(0x00, 0xFF, 0xFF), rgb(0xFF, 0xFF, 0xFF)
+];
+
+/// Converts an 8-bit RGB color to an opaque color.
+pub const fn rgb(r: u8, g: u8, b: u8) -> Vec4<f64> {
+	Vec4(r as f64 / 255f64, g as f64 / 255f64, b as f64 / 255f64, 1f64)
+}
+
+/// Returns a color of the 256 color palette: the 16 standard colors, a 6x6x6 color cube and
+/// 24 shades of gray.
+pub fn color_256(index: u8) -> Vec4<f64> {
+	let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+	match index {
+		0..=15   => COLORS[index as usize],
+		16..=231 => {
+			let i = index - 16;
+			rgb(level(i / 36), level(i / 6 % 6), level(i % 6))
+		}
+		_        => {
+			let v = 8 + (index - 232) * 10;
+			rgb(v, v, v)
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+enum State {
+	#[default]
+	Text,
+	Escape,
+	Csi,
+	Osc,
+	/// An escape in an OSC sequence, which is terminated by `ESC \`.
+	OscEscape,
+	/// A sequence that exceeded the maximum length, skipped until its end.
+	Skip
+}
+
+/// A streaming parser, sequences may be split across multiple calls to [`AnsiParser::parse`].
+#[derive(Clone, Debug, Default)]
+pub struct AnsiParser {
+	state:  State,
+	ctrl:   TextCtrl,
+	params: String,
+	text:   String
+}
+
+impl AnsiParser {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	/// Returns the style that is applied to the following text.
+	pub fn ctrl(&self) -> TextCtrl {
+		self.ctrl
+	}
+	
+	/// Parses the input and appends the text to `out`. Incomplete sequences at the end of the
+	/// input are completed by the next call.
+	pub fn parse(&mut self, input: &str, out: &mut FmtText) {
+		for ch in input.chars() {
+			match self.state {
+				State::Text => match ch {
+					'\x1B' => self.begin(State::Escape, out),
+					'\u{9B}' => self.begin(State::Csi, out),
+					'\u{9D}' => self.begin(State::Osc, out),
+					'\t' | '\n' | '\r' => self.text.push(ch),
+					'\0'..='\x1F' | '\x7F'..='\u{9F}' => (),
+					ch => self.text.push(ch)
+				},
+				State::Escape => match ch {
+					'[' => self.state = State::Csi,
+					']' => self.state = State::Osc,
+					// full reset
+					'c' => {
+						self.ctrl = TextCtrl::default();
+						out.push(TextCtrl { clear: Some(ClearMode::Display), ..self.ctrl }, "");
+						self.state = State::Text;
+					}
+					// the parameters and intermediates of other sequences are skipped
+					' '..='/' => (),
+					_ => self.state = State::Text
+				},
+				State::Csi => match ch {
+					' '..='?' if self.params.len() < MAX_PARAMS_LEN => self.params.push(ch),
+					' '..='?' => self.state = State::Skip,
+					'@'..='~' => {
+						self.csi(ch, out);
+						self.state = State::Text;
+					}
+					'\x1B' => self.begin(State::Escape, out),
+					// other control characters are ignored inside sequences
+					'\0'..='\x1F' => (),
+					_ => self.state = State::Text
+				},
+				State::Osc => match ch {
+					'\x07' | '\u{9C}' => {
+						self.osc(out);
+						self.state = State::Text;
+					}
+					'\x1B' => self.state = State::OscEscape,
+					_ if self.params.len() < MAX_PARAMS_LEN => self.params.push(ch),
+					_ => self.state = State::Skip
+				},
+				State::OscEscape => {
+					if ch == '\\' {
+						self.osc(out);
+					}
+					self.state = State::Text;
+				}
+				State::Skip => match ch {
+					'\x1B' => self.begin(State::Escape, out),
+					'@'..='~' | '\x07' | '\u{9C}' => self.state = State::Text,
+					_ => ()
+				}
+			}
+		}
+		
+		self.flush(out);
+	}
+	
+	fn begin(&mut self, state: State, out: &mut FmtText) {
+		self.flush(out);
+		self.params.clear();
+		self.state = state;
+	}
+	
+	fn flush(&mut self, out: &mut FmtText) {
+		out.push(self.ctrl, &self.text);
+		self.text.clear();
+	}
+	
+	fn csi(&mut self, function: char, out: &mut FmtText) {
+		// private sequences and sequences with intermediates are not supported
+		if self.params.bytes().any(|b| !matches!(b, b'0'..=b'9' | b';' | b':')) {
+			return;
+		}
+		
+		let mode = || self.params.parse::<u32>().unwrap_or(0);
+		let clear = match function {
+			'm' => return self.sgr(),
+			'K' => match mode() {
+				0 => ClearMode::LineEnd,
+				1 => ClearMode::LineBeginning,
+				_ => ClearMode::Line
+			},
+			'J' => match mode() {
+				0 => ClearMode::DisplayEnd,
+				1 => ClearMode::DisplayBeginning,
+				_ => ClearMode::Display
+			},
+			_ => return
+		};
+		
+		out.push(TextCtrl { clear: Some(clear), ..self.ctrl }, "");
+	}
+	
+	fn osc(&mut self, out: &mut FmtText) {
+		// OSC 8 ; params ; URI, an empty URI ends the link
+		if let Some((_, uri)) = self.params.strip_prefix("8;").and_then(|v| v.split_once(';')) {
+			self.ctrl.link = (!uri.is_empty()).then(|| out.add_link(uri));
+		}
+	}
+	
+	fn sgr(&mut self) {
+		let params = self.params.split(';')
+			.map(|group| group.split(':').map(|v| v.parse::<u32>().ok()).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		let ctrl = &mut self.ctrl;
+		
+		let mut i = 0;
+		while i < params.len() {
+			let group = &params[i];
+			let code = group[0].unwrap_or(0);
+			i += 1;
+			
+			match code {
+				0        => *ctrl = TextCtrl { link: ctrl.link, ..TextCtrl::default() },
+				1        => ctrl.set(TextCtrl::BOLD, true),
+				2        => ctrl.set(TextCtrl::FAINT, true),
+				3        => ctrl.set(TextCtrl::ITALIC, true),
+				4        => {
+					// 4:0 is no underline, 4:2 double, all other styles are drawn as single
+					ctrl.set(TextCtrl::UNDERLINE | TextCtrl::DOUBLE_UNDERLINE, false);
+					match group.get(1).copied().flatten() {
+						Some(0) => (),
+						Some(2) => ctrl.set(TextCtrl::DOUBLE_UNDERLINE, true),
+						_       => ctrl.set(TextCtrl::UNDERLINE, true)
+					}
+				}
+				5        => ctrl.set(TextCtrl::BLINK, true),
+				6        => ctrl.set(TextCtrl::RAPID_BLINK, true),
+				7        => ctrl.set(TextCtrl::INVERSE, true),
+				8        => ctrl.set(TextCtrl::CONCEAL, true),
+				9        => ctrl.set(TextCtrl::STRIKETHROUGH, true),
+				10..=19  => ctrl.alt_font = (code - 10) as usize,
+				21       => {
+					ctrl.set(TextCtrl::UNDERLINE, false);
+					ctrl.set(TextCtrl::DOUBLE_UNDERLINE, true);
+				}
+				22       => ctrl.set(TextCtrl::BOLD | TextCtrl::FAINT, false),
+				23       => ctrl.set(TextCtrl::ITALIC, false),
+				24       => ctrl.set(TextCtrl::UNDERLINE | TextCtrl::DOUBLE_UNDERLINE, false),
+				25       => ctrl.set(TextCtrl::BLINK | TextCtrl::RAPID_BLINK, false),
+				27       => ctrl.set(TextCtrl::INVERSE, false),
+				28       => ctrl.set(TextCtrl::CONCEAL, false),
+				29       => ctrl.set(TextCtrl::STRIKETHROUGH, false),
+				30..=37  => ctrl.color_fg = Some(COLORS[code as usize - 30]),
+				38       => if let Some(color) = extended_color(group, &params, &mut i) {
+					ctrl.color_fg = Some(color);
+				},
+				39       => ctrl.color_fg = None,
+				40..=47  => ctrl.color_bg = Some(COLORS[code as usize - 40]),
+				48       => if let Some(color) = extended_color(group, &params, &mut i) {
+					ctrl.color_bg = Some(color);
+				},
+				49       => ctrl.color_bg = None,
+				53       => ctrl.set(TextCtrl::OVERLINE, true),
+				55       => ctrl.set(TextCtrl::OVERLINE, false),
+				90..=97  => ctrl.color_fg = Some(COLORS[code as usize - 90 + 8]),
+				100..=107 => ctrl.color_bg = Some(COLORS[code as usize - 100 + 8]),
+				_        => ()
+			}
+		}
+	}
+}
+
+/// Parses the color of a `38` or `48` parameter, either with the arguments as sub parameters
+/// (`38:5:n`, `38:2:[space]:r:g:b`) or as the following parameters (`38;5;n`, `38;2;r;g;b`).
+fn extended_color(group: &[Option<u32>], params: &[Vec<Option<u32>>], i: &mut usize) -> Option<Vec4<f64>> {
+	let byte = |v: Option<u32>| v.unwrap_or(0).min(255) as u8;
+	
+	if group.len() > 1 {
+		return match group[1..] {
+			[Some(5), n, ..]                => Some(color_256(byte(n))),
+			[Some(2), _, r, g, b, ..]       => Some(rgb(byte(r), byte(g), byte(b))),
+			[Some(2), r, g, b]              => Some(rgb(byte(r), byte(g), byte(b))),
+			_                               => None
+		};
+	}
+	
+	let arg = |k: usize| params.get(*i + k).map(|group| group[0]);
+	match arg(0)? {
+		Some(5) => {
+			let n = arg(1)?;
+			*i += 2;
+			Some(color_256(byte(n)))
+		}
+		Some(2) => {
+			let (r, g, b) = (arg(1)?, arg(2)?, arg(3)?);
+			*i += 4;
+			Some(rgb(byte(r), byte(g), byte(b)))
+		}
+		_ => None
+	}
+}
+
+impl FmtText {
+	/// Parses text with ANSI escape sequences, see [`AnsiParser`].
+	pub fn from_ansi(input: &str) -> Self {
+		let mut text = Self::new();
+		AnsiParser::new().parse(input, &mut text);
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn fg(color: Vec4<f64>) -> TextCtrl {
+		TextCtrl { color_fg: Some(color), ..TextCtrl::default() }
+	}
+	
+	fn runs(text: &FmtText) -> Vec<(TextCtrl, &str)> {
+		text.runs().iter().map(|(ctrl, text)| (*ctrl, text.as_str())).collect()
+	}
+	
+	#[test]
+	fn colors() {
+		let text = FmtText::from_ansi("a\x1B[31mb\x1B[97mc\x1B[39md");
+		assert_eq!(runs(&text), vec![
+			(TextCtrl::default(), "a"),
+			(fg(COLORS[1]), "b"),
+			(fg(COLORS[15]), "c"),
+			(TextCtrl::default(), "d")
+		]);
+		
+		let text = FmtText::from_ansi("\x1B[30;47ma\x1B[100mb");
+		let bg = |color| TextCtrl { color_bg: Some(color), ..fg(COLORS[0]) };
+		assert_eq!(runs(&text), vec![(bg(COLORS[7]), "a"), (bg(COLORS[8]), "b")]);
+		
+		assert_eq!(color_256(9), COLORS[9]);
+		assert_eq!(color_256(16), rgb(0, 0, 0));
+		assert_eq!(color_256(196), rgb(255, 0, 0));
+		assert_eq!(color_256(232), rgb(8, 8, 8));
+		assert_eq!(color_256(255), rgb(238, 238, 238));
+	}
+	
+	#[test]
+	fn extended_colors() {
+		let color = |input: &str| FmtText::from_ansi(input).runs()[0].0.color_fg;
+		assert_eq!(color("\x1B[38;5;196mx"), Some(rgb(255, 0, 0)));
+		assert_eq!(color("\x1B[38:5:21mx"), Some(rgb(0, 0, 255)));
+		assert_eq!(color("\x1B[38;2;1;2;3mx"), Some(rgb(1, 2, 3)));
+		assert_eq!(color("\x1B[38:2::1:2:3mx"), Some(rgb(1, 2, 3)));
+		assert_eq!(color("\x1B[38;2;1;2;300mx"), Some(rgb(1, 2, 255)));
+		
+		// the parameters after the color are still applied
+		let ctrl = FmtText::from_ansi("\x1B[48;2;1;2;3;1mx").runs()[0].0;
+		assert_eq!((ctrl.color_bg, ctrl.has(TextCtrl::BOLD)), (Some(rgb(1, 2, 3)), true));
+		// incomplete colors are ignored
+		assert_eq!(color("\x1B[38;2;1mx"), None);
+	}
+	
+	#[test]
+	fn effects() {
+		let ctrl = |input: &str| FmtText::from_ansi(input).runs()[0].0;
+		assert_eq!(ctrl("\x1B[1;3;4;9mx").effects, TextCtrl::BOLD | TextCtrl::ITALIC | TextCtrl::UNDERLINE | TextCtrl::STRIKETHROUGH);
+		assert_eq!(ctrl("\x1B[4:2mx").effects, TextCtrl::DOUBLE_UNDERLINE);
+		assert_eq!(ctrl("\x1B[4;4:0mx").effects, 0);
+		assert_eq!(ctrl("\x1B[1;2;22;5;7;53mx").effects, TextCtrl::BLINK | TextCtrl::INVERSE | TextCtrl::OVERLINE);
+		assert_eq!(ctrl("\x1B[12mx").alt_font, 2);
+		
+		// reset, also with an empty parameter list
+		assert_eq!(ctrl("\x1B[1;31;44m\x1B[0mx"), TextCtrl::default());
+		assert_eq!(ctrl("\x1B[1;31;44m\x1B[mx"), TextCtrl::default());
+		assert_eq!(ctrl("\x1B[1m\x1Bcx").effects, 0);
+	}
+	
+	#[test]
+	fn links() {
+		let text = FmtText::from_ansi("a\x1B]8;;https://example.com\x07b\x1B]8;;\x1B\\c");
+		let link = TextCtrl { link: Some(0), ..TextCtrl::default() };
+		assert_eq!(runs(&text), vec![(TextCtrl::default(), "a"), (link, "b"), (TextCtrl::default(), "c")]);
+		assert_eq!(text.link(0), Some("https://example.com"));
+		assert_eq!(text.link(1), None);
+		
+		// links survive a reset of the style
+		let text = FmtText::from_ansi("\x1B]8;id=1;x\x1B\\\x1B[1ma\x1B[0mb");
+		assert_eq!(text.runs()[1].0.link, Some(0));
+	}
+	
+	#[test]
+	fn clear_and_controls() {
+		let text = FmtText::from_ansi("a\x1B[2Kb\x1B[Jc\x07\x1B[?25l\x1B(Bd\te");
+		let clear = |mode| TextCtrl { clear: Some(mode), ..TextCtrl::default() };
+		assert_eq!(runs(&text), vec![
+			(TextCtrl::default(), "a"),
+			(clear(ClearMode::Line), ""),
+			(TextCtrl::default(), "b"),
+			(clear(ClearMode::DisplayEnd), ""),
+			(TextCtrl::default(), "cd\te")
+		]);
+		assert_eq!(text.to_string(), "abcd\te");
+	}
+	
+	#[test]
+	fn chunks() {
+		let input = "a\x1B[38;5;196mb\x1B]8;;x\x07c\x1B]8;;\x1B\\\x1B[0md";
+		let expected = FmtText::from_ansi(input);
+		
+		// every split of the input, inside of sequences too, gives the same result
+		for split in (0..=input.len()).filter(|&i| input.is_char_boundary(i)) {
+			let (mut parser, mut text) = (AnsiParser::new(), FmtText::new());
+			parser.parse(&input[..split], &mut text);
+			parser.parse(&input[split..], &mut text);
+			assert_eq!(text, expected, "split at {}", split);
+		}
+		
+		let mut parser = AnsiParser::new();
+		parser.parse("\x1B[3", &mut FmtText::new());
+		assert_eq!(parser.ctrl(), TextCtrl::default());
+		parser.parse("1m", &mut FmtText::new());
+		assert_eq!(parser.ctrl(), fg(COLORS[1]));
+	}
+}
@@ -0,0 +1,600 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The Unicode bidirectional algorithm (UAX #9) for a single paragraph.
+
+use {super::{lookup, tables}, crate::Direction, self::BidiClass::*};
+
+/// The maximum explicit embedding depth.
+const MAX_DEPTH: u8 = 125;
+
+/// The maximum nesting depth of bracket pairs.
+const MAX_BRACKETS: usize = 63;
+
+/// The bidirectional character types.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum BidiClass {
+	AL,
+	AN,
+	B,
+	BN,
+	CS,
+	EN,
+	ES,
+	ET,
+	FSI,
+	L,
+	LRE,
+	LRI,
+	LRO,
+	NSM,
+	ON,
+	PDF,
+	PDI,
+	R,
+	RLE,
+	RLI,
+	RLO,
+	S,
+	WS
+}
+
+impl BidiClass {
+	pub fn of(ch: char) -> Self {
+		lookup(tables::bidi::CLASSES, ch).unwrap_or(L)
+	}
+	
+	fn is_isolate_initiator(self) -> bool {
+		matches!(self, LRI | RLI | FSI)
+	}
+	
+	/// Returns `true` for the classes that are removed by rule X9.
+	fn is_removed(self) -> bool {
+		matches!(self, RLE | LRE | RLO | LRO | PDF | BN)
+	}
+	
+	fn is_neutral(self) -> bool {
+		matches!(self, B | S | WS | ON | FSI | LRI | RLI | PDI)
+	}
+	
+	/// The strong direction of a class after the weak types were resolved, numbers count as `R`.
+	fn strong(self) -> Option<Self> {
+		match self {
+			L                => Some(L),
+			R | AL | EN | AN => Some(R),
+			_                => None
+		}
+	}
+}
+
+/// Returns the mirrored character, if the character has one.
+pub fn mirror(ch: char) -> Option<char> {
+	let mirrors = tables::bidi::MIRRORS;
+	mirrors.binary_search_by_key(&ch, |&(ch, _)| ch).ok().map(|i| mirrors[i].1)
+}
+
+/// Returns the opening bracket of a bracket pair and whether the character is the opening one.
+fn bracket(ch: char) -> Option<(char, bool)> {
+	// canonical equivalents of the angle brackets
+	let ch = match ch {
+		'\u{2329}' => '\u{3008}',
+		'\u{232A}' => '\u{3009}',
+		ch         => ch
+	};
+	
+	tables::bidi::BRACKETS.iter().find_map(|&(open, close)| match ch {
+		_ if ch == open  => Some((open, true)),
+		_ if ch == close => Some((open, false)),
+		_                => None
+	})
+}
+
+/// Resolves the embedding levels of the characters of a paragraph. The direction of the
+/// paragraph is the given one or that of the first strong character, it defaults to
+/// left-to-right. Returns the paragraph level and the levels, rule L1 is not yet applied.
+pub fn resolve_levels(chars: &[char], direction: Option<Direction>) -> (u8, Vec<u8>) {
+	let original = chars.iter().map(|&ch| BidiClass::of(ch)).collect::<Vec<_>>();
+	let matches = match_isolates(&original);
+	let paragraph = match direction {
+		Some(Direction::LeftToRight) => 0,
+		Some(Direction::RightToLeft) => 1,
+		None => first_strong(&original, &matches, 0, original.len()).unwrap_or(0)
+	};
+	
+	let mut classes = original.clone();
+	let mut levels = vec![paragraph; chars.len()];
+	explicit_levels(&original, &matches, paragraph, &mut classes, &mut levels);
+	
+	let explicit = levels.clone();
+	for sequence in isolating_run_sequences(&original, &matches, &explicit) {
+		resolve_sequence(chars, &original, &classes, &explicit, &mut levels, &sequence, paragraph);
+	}
+	
+	// the removed characters get the level of the preceding character
+	for i in 0..levels.len() {
+		if original[i].is_removed() {
+			levels[i] = match i {
+				0 => paragraph,
+				_ => levels[i - 1]
+			};
+		}
+	}
+	(paragraph, levels)
+}
+
+/// Applies rule L1 to the levels of a line: separators and the whitespace before them and at
+/// the end of the line are reset to the paragraph level.
+pub fn reset_whitespace(chars: &[char], levels: &mut [u8], paragraph: u8) {
+	let mut trailing = true;
+	for i in (0..chars.len()).rev() {
+		match BidiClass::of(chars[i]) {
+			S | B => {
+				levels[i] = paragraph;
+				trailing = true;
+			}
+			class if class == WS || class.is_isolate_initiator() || class == PDI || class.is_removed() => if trailing {
+				levels[i] = paragraph;
+			}
+			_ => trailing = false
+		}
+	}
+	
+	// removed characters after a separator take the level of the separator
+	let mut separator = false;
+	for (i, &ch) in chars.iter().enumerate() {
+		match BidiClass::of(ch) {
+			S | B => separator = true,
+			class if class.is_removed() && separator => levels[i] = paragraph,
+			_ => separator = false
+		}
+	}
+}
+
+/// Returns the visual order of items with the given levels, in logical order, by rule L2.
+pub fn reorder(levels: &[u8]) -> Vec<usize> {
+	let mut order = (0..levels.len()).collect::<Vec<_>>();
+	let (Some(&max), Some(&min)) = (levels.iter().max(), levels.iter().filter(|&&l| l % 2 == 1).min()) else {
+		return order;
+	};
+	
+	for level in (min..=max).rev() {
+		let mut i = 0;
+		while i < order.len() {
+			let start = i;
+			while i < order.len() && levels[order[i]] >= level {
+				i += 1;
+			}
+			order[start..i].reverse();
+			i += 1;
+		}
+	}
+	order
+}
+
+/// Maps each isolate initiator to its matching PDI and each matched PDI to its initiator.
+fn match_isolates(classes: &[BidiClass]) -> Vec<Option<usize>> {
+	let mut matches = vec![None; classes.len()];
+	let mut stack = Vec::new();
+	for (i, &class) in classes.iter().enumerate() {
+		match class {
+			LRI | RLI | FSI => stack.push(i),
+			PDI => if let Some(j) = stack.pop() {
+				matches[i] = Some(j);
+				matches[j] = Some(i);
+			}
+			B => stack.clear(),
+			_ => ()
+		}
+	}
+	matches
+}
+
+/// Returns the level of the first strong character in the range, skipping isolates.
+fn first_strong(classes: &[BidiClass], matches: &[Option<usize>], mut i: usize, end: usize) -> Option<u8> {
+	while i < end {
+		match classes[i] {
+			L      => return Some(0),
+			R | AL => return Some(1),
+			B      => return None,
+			LRI | RLI | FSI => match matches[i] {
+				Some(pdi) => i = pdi,
+				None      => return None
+			}
+			_ => ()
+		}
+		i += 1;
+	}
+	None
+}
+
+fn next_level(level: u8, rtl: bool) -> u8 {
+	match rtl {
+		true  => (level + 1) | 1,
+		false => (level + 2) & !1
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Status {
+	level:     u8,
+	override_: Option<BidiClass>,
+	isolate:   bool
+}
+
+/// Resolves the explicit levels and directions, rules X1 to X8.
+fn explicit_levels(
+	original:  &[BidiClass],
+	matches:   &[Option<usize>],
+	paragraph: u8,
+	classes:   &mut [BidiClass],
+	levels:    &mut [u8]
+) {
+	let root = Status { level: paragraph, override_: None, isolate: false };
+	let mut stack = vec![root];
+	let (mut overflow_isolates, mut overflow_embeddings, mut valid_isolates) = (0usize, 0usize, 0usize);
+	
+	for i in 0..original.len() {
+		let last = stack.last().copied().unwrap_or(root);
+		match original[i] {
+			class @ (RLE | LRE | RLO | LRO) => {
+				let level = next_level(last.level, matches!(class, RLE | RLO));
+				if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+					let override_ = match class {
+						RLO => Some(R),
+						LRO => Some(L),
+						_   => None
+					};
+					stack.push(Status { level, override_, isolate: false });
+				} else if overflow_isolates == 0 {
+					overflow_embeddings += 1;
+				}
+				levels[i] = last.level;
+			}
+			class @ (RLI | LRI | FSI) => {
+				levels[i] = last.level;
+				if let Some(class) = last.override_ {
+					classes[i] = class;
+				}
+				
+				let rtl = match class {
+					RLI => true,
+					LRI => false,
+					_   => first_strong(original, matches, i + 1, matches[i].unwrap_or(original.len())) == Some(1)
+				};
+				let level = next_level(last.level, rtl);
+				if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+					valid_isolates += 1;
+					stack.push(Status { level, override_: None, isolate: true });
+				} else {
+					overflow_isolates += 1;
+				}
+			}
+			PDI => {
+				if overflow_isolates > 0 {
+					overflow_isolates -= 1;
+				} else if valid_isolates > 0 {
+					overflow_embeddings = 0;
+					while stack.last().is_some_and(|s| !s.isolate) {
+						stack.pop();
+					}
+					stack.pop();
+					valid_isolates -= 1;
+				}
+				
+				let last = stack.last().copied().unwrap_or(root);
+				levels[i] = last.level;
+				if let Some(class) = last.override_ {
+					classes[i] = class;
+				}
+			}
+			PDF => {
+				if overflow_isolates > 0 {
+				} else if overflow_embeddings > 0 {
+					overflow_embeddings -= 1;
+				} else if !last.isolate && stack.len() >= 2 {
+					stack.pop();
+				}
+				levels[i] = stack.last().copied().unwrap_or(root).level;
+			}
+			B  => levels[i] = paragraph,
+			BN => levels[i] = last.level,
+			_  => {
+				levels[i] = last.level;
+				if let Some(class) = last.override_ {
+					classes[i] = class;
+				}
+			}
+		}
+	}
+}
+
+/// Splits the characters that are not removed by rule X9 into isolating run sequences, rule X10.
+fn isolating_run_sequences(original: &[BidiClass], matches: &[Option<usize>], levels: &[u8]) -> Vec<Vec<usize>> {
+	let mut runs: Vec<Vec<usize>> = Vec::new();
+	for i in (0..original.len()).filter(|&i| !original[i].is_removed()) {
+		match runs.last_mut() {
+			Some(run) if levels[run[0]] == levels[i] => run.push(i),
+			_ => runs.push(vec![i])
+		}
+	}
+	
+	let mut appended = vec![false; runs.len()];
+	let mut sequences = Vec::new();
+	for r in 0..runs.len() {
+		if appended[r] {
+			continue;
+		}
+		
+		let mut sequence = runs[r].clone();
+		while let Some(&last) = sequence.last() {
+			let Some(pdi) = matches[last].filter(|_| original[last].is_isolate_initiator()) else {
+				break;
+			};
+			let Some(next) = (r + 1..runs.len()).find(|&k| runs[k][0] == pdi) else {
+				break;
+			};
+			
+			appended[next] = true;
+			sequence.extend_from_slice(&runs[next]);
+		}
+		sequences.push(sequence);
+	}
+	sequences
+}
+
+/// Resolves the weak types, the neutral types and the implicit levels of an isolating run
+/// sequence, rules W1 to I2.
+fn resolve_sequence(
+	chars:     &[char],
+	original:  &[BidiClass],
+	classes:   &[BidiClass],
+	explicit:  &[u8],
+	levels:    &mut [u8],
+	sequence:  &[usize],
+	paragraph: u8
+) {
+	let (first, last) = (sequence[0], sequence[sequence.len() - 1]);
+	let level = explicit[first];
+	let direction = |level: u8| if level % 2 == 0 { L } else { R };
+	let before = (0..first).rev().find(|&i| !original[i].is_removed()).map_or(paragraph, |i| explicit[i]);
+	let after = match original[last].is_isolate_initiator() {
+		true  => paragraph,
+		false => (last + 1..original.len()).find(|&i| !original[i].is_removed()).map_or(paragraph, |i| explicit[i])
+	};
+	let (sos, eos, embedding) = (direction(level.max(before)), direction(level.max(after)), direction(level));
+	let mut t = sequence.iter().map(|&i| classes[i]).collect::<Vec<_>>();
+	let len = t.len();
+	
+	// W1: non-spacing marks take the type of the previous character
+	for k in 0..len {
+		if t[k] == NSM {
+			t[k] = match k.checked_sub(1).map(|j| t[j]) {
+				None                         => sos,
+				Some(LRI | RLI | FSI | PDI)  => ON,
+				Some(class)                  => class
+			};
+		}
+	}
+	
+	// W2, W3: european numbers after arabic letters become arabic numbers
+	let mut strong = sos;
+	for class in &mut t {
+		match *class {
+			L | R | AL => strong = *class,
+			EN if strong == AL => *class = AN,
+			_ => ()
+		}
+	}
+	t.iter_mut().filter(|class| **class == AL).for_each(|class| *class = R);
+	
+	// W4: single separators between numbers
+	for k in 1..len.saturating_sub(1) {
+		match (t[k - 1], t[k], t[k + 1]) {
+			(EN, ES | CS, EN) => t[k] = EN,
+			(AN, CS, AN)      => t[k] = AN,
+			_ => ()
+		}
+	}
+	
+	// W5: terminators adjacent to european numbers
+	let mut k = 0;
+	while k < len {
+		if t[k] != ET {
+			k += 1;
+			continue;
+		}
+		
+		let start = k;
+		while k < len && t[k] == ET {
+			k += 1;
+		}
+		if (start > 0 && t[start - 1] == EN) || (k < len && t[k] == EN) {
+			t[start..k].fill(EN);
+		}
+	}
+	
+	// W6, W7
+	t.iter_mut().filter(|class| matches!(class, ES | ET | CS)).for_each(|class| *class = ON);
+	let mut strong = sos;
+	for class in &mut t {
+		match *class {
+			L | R => strong = *class,
+			EN if strong == L => *class = L,
+			_ => ()
+		}
+	}
+	
+	// N0: bracket pairs take the embedding direction or the direction of their context
+	for (open, close) in bracket_pairs(chars, sequence, &t) {
+		let (mut same, mut opposite) = (false, false);
+		for class in t[open + 1..close].iter().filter_map(|class| class.strong()) {
+			same |= class == embedding;
+			opposite |= class != embedding;
+		}
+		
+		let resolved = match (same, opposite) {
+			(true, _) => embedding,
+			(_, true) => (0..open).rev().find_map(|k| t[k].strong()).unwrap_or(sos),
+			_         => continue
+		};
+		
+		for k in [open, close] {
+			t[k] = resolved;
+			for m in k + 1..len {
+				match original[sequence[m]] {
+					NSM => t[m] = resolved,
+					_   => break
+				}
+			}
+		}
+	}
+	
+	// N1, N2: neutrals between characters of the same direction take that direction
+	let mut k = 0;
+	while k < len {
+		if !t[k].is_neutral() {
+			k += 1;
+			continue;
+		}
+		
+		let start = k;
+		while k < len && t[k].is_neutral() {
+			k += 1;
+		}
+		let prev = start.checked_sub(1).and_then(|j| t[j].strong()).unwrap_or(sos);
+		let next = t.get(k).and_then(|class| class.strong()).unwrap_or(eos);
+		t[start..k].fill(if prev == next { prev } else { embedding });
+	}
+	
+	// I1, I2
+	for (&i, &class) in sequence.iter().zip(&t) {
+		levels[i] += match (levels[i] % 2, class) {
+			(0, R)           => 1,
+			(0, AN | EN)     => 2,
+			(1, L | EN | AN) => 1,
+			_                => 0
+		};
+	}
+}
+
+/// Finds the bracket pairs of an isolating run sequence, sorted by the position of the opening
+/// bracket, rule BD16.
+fn bracket_pairs(chars: &[char], sequence: &[usize], t: &[BidiClass]) -> Vec<(usize, usize)> {
+	let mut stack = Vec::new();
+	let mut pairs = Vec::new();
+	for (k, &i) in sequence.iter().enumerate() {
+		let Some((id, open)) = bracket(chars[i]).filter(|_| t[k] == ON) else {
+			continue;
+		};
+		
+		if open {
+			if stack.len() == MAX_BRACKETS {
+				break;
+			}
+			stack.push((id, k));
+		} else if let Some(p) = stack.iter().rposition(|&(o, _)| o == id) {
+			pairs.push((stack[p].1, k));
+			stack.truncate(p);
+		}
+	}
+	pairs.sort_unstable();
+	pairs
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn levels(text: &str, direction: Option<Direction>) -> (u8, Vec<u8>) {
+		resolve_levels(&text.chars().collect::<Vec<_>>(), direction)
+	}
+	
+	#[test]
+	fn paragraph_direction() {
+		assert_eq!(levels("abc אבג", None), (0, vec![0, 0, 0, 0, 1, 1, 1]));
+		assert_eq!(levels("אבג abc", None), (1, vec![1, 1, 1, 1, 2, 2, 2]));
+		assert_eq!(levels("123 אבג", None), (1, vec![2, 2, 2, 1, 1, 1, 1]));
+		assert_eq!(levels("abc", Some(Direction::RightToLeft)), (1, vec![2, 2, 2]));
+		assert_eq!(levels("", None), (0, vec![]));
+	}
+	
+	#[test]
+	fn weak_types() {
+		// W2: european numbers after arabic letters become arabic numbers
+		assert_eq!(levels("\u{0627} 12", None), (1, vec![1, 1, 2, 2]));
+		// W4, W5: separators and terminators between numbers
+		assert_eq!(levels("אבג 1,5 $2", Some(Direction::LeftToRight)), (0, vec![1, 1, 1, 1, 2, 2, 2, 1, 2, 2]));
+	}
+	
+	#[test]
+	fn neutrals() {
+		// the UAX #9 example "car means CAR.", with hebrew letters for the uppercase ones
+		assert_eq!(levels("car means אבג.", None).1, [vec![0; 10], vec![1; 3], vec![0]].concat());
+		// N0: brackets enclosing strong text opposite to the embedding direction take the
+		// direction of the preceding strong text
+		assert_eq!(levels("אב(גד)", Some(Direction::LeftToRight)).1, vec![1; 6]);
+		assert_eq!(levels("ab(גד)", Some(Direction::LeftToRight)).1, vec![0, 0, 0, 1, 1, 0]);
+	}
+	
+	#[test]
+	fn explicit() {
+		// RLI, PDI
+		assert_eq!(levels("a\u{2067}b\u{2069}c", None), (0, vec![0, 0, 2, 0, 0]));
+		// the first strong character of the paragraph ignores isolated text
+		assert_eq!(levels("\u{2067}a\u{2069}א", None).0, 1);
+		// RLE, PDF
+		assert_eq!(levels("a\u{202B}b\u{202C}c", None), (0, vec![0, 0, 2, 2, 0]));
+		// LRO overrides the classes of the characters
+		assert_eq!(levels("\u{202D}אב\u{202C}", Some(Direction::LeftToRight)).1, vec![0, 2, 2, 2]);
+	}
+	
+	#[test]
+	fn whitespace() {
+		let chars = "a\tb ".chars().collect::<Vec<_>>();
+		let (paragraph, mut levels) = resolve_levels(&chars, Some(Direction::RightToLeft));
+		assert_eq!(levels, vec![2, 2, 2, 1]);
+		reset_whitespace(&chars, &mut levels, paragraph);
+		assert_eq!(levels, vec![2, 1, 2, 1]);
+	}
+	
+	#[test]
+	fn reordering() {
+		assert_eq!(reorder(&[0, 0, 1, 1, 1, 0]), vec![0, 1, 4, 3, 2, 5]);
+		assert_eq!(reorder(&[1, 2, 2, 1]), vec![3, 1, 2, 0]);
+		assert_eq!(reorder(&[0, 0, 0]), vec![0, 1, 2]);
+		assert_eq!(reorder(&[]), Vec::<usize>::new());
+		
+		let chars = "car means אבג.".chars().collect::<Vec<_>>();
+		let (_, levels) = resolve_levels(&chars, None);
+		let visual = reorder(&levels).into_iter().map(|i| chars[i]).collect::<String>();
+		assert_eq!(visual, "car means גבא.");
+	}
+	
+	#[test]
+	fn mirroring() {
+		assert_eq!(mirror('('), Some(')'));
+		assert_eq!(mirror('»'), Some('«'));
+		assert_eq!(mirror('a'), None);
+		assert_eq!(bracket('\u{2329}'), Some(('\u{3008}', true)));
+		assert_eq!(bracket(']'), Some(('[', false)));
+	}
+}
@@ -0,0 +1,220 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Line breaking by the rules of the Unicode line breaking algorithm (UAX #14).
+//!
+//! Complex context dependent characters (`SA`) are broken like alphabetic characters, as
+//! there is no dictionary to find the word boundaries of Thai, Lao, Khmer and Myanmar text.
+
+use {super::{lookup, tables}, self::LineBreak::*};
+
+/// The line breaking classes, after the resolution of rule LB1.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LineBreak {
+	AL, B2, BA, BB, BK, CB, CL, CM, CP, CR, EB, EM, EX, GL, H2, H3, HL, HY, ID, IN, IS, JL, JT, JV,
+	LF, NL, NS, NU, OP, PO, PR, QU, RI, SP, SY, WJ, ZW, ZWJ
+}
+
+impl LineBreak {
+	pub fn of(ch: char) -> Self {
+		match ch as u32 {
+			0xAC00..=0xD7A3 if (ch as u32 - 0xAC00) % 28 == 0 => H2,
+			0xAC00..=0xD7A3 => H3,
+			_ => lookup(tables::line_break::CLASSES, ch).unwrap_or(AL)
+		}
+	}
+}
+
+/// A line break opportunity before a character.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Break {
+	/// The line must be broken, after a newline or at the end of the text.
+	Mandatory,
+	/// The line may be broken.
+	Allowed
+}
+
+/// Returns the byte offsets of the break opportunities in the text, the last one is the
+/// mandatory break at the end of a non-empty text.
+pub fn line_breaks(text: &str) -> Vec<(usize, Break)> {
+	let mut breaks = Vec::new();
+	let mut chars = text.char_indices();
+	let Some((_, first)) = chars.next() else {
+		return breaks;
+	};
+	
+	// the class of the previous character after rules LB9 and LB10, the class before it, the
+	// class before any spaces and the class of the actual previous character
+	let mut prev = match LineBreak::of(first) {
+		CM | ZWJ => AL,
+		class    => class
+	};
+	let (mut prev_prev, mut before_spaces, mut raw) = (prev, prev, LineBreak::of(first));
+	let mut regional_indicators = (prev == RI) as usize;
+	
+	for (i, ch) in chars {
+		let mut class = LineBreak::of(ch);
+		let result = pair(prev, prev_prev, before_spaces, raw, class, ch, regional_indicators);
+		raw = class;
+		
+		// LB9: combining marks take the class of their base
+		if result.is_none() && matches!(class, CM | ZWJ) && !matches!(prev, BK | CR | LF | NL | SP | ZW) {
+			continue;
+		}
+		
+		if let Some(result) = result {
+			breaks.push((i, result));
+		}
+		
+		if matches!(class, CM | ZWJ) {
+			class = AL;
+		}
+		
+		prev_prev = prev;
+		prev = class;
+		if class != SP {
+			before_spaces = class;
+		}
+		regional_indicators = match class {
+			RI => regional_indicators + 1,
+			_  => 0
+		};
+	}
+	
+	breaks.push((text.len(), Break::Mandatory));
+	breaks
+}
+
+/// Returns the break opportunity between two characters, by the rules LB4 to LB31.
+fn pair(
+	a:                   LineBreak,
+	a_prev:              LineBreak,
+	before_spaces:       LineBreak,
+	raw:                 LineBreak,
+	b:                   LineBreak,
+	ch:                  char,
+	regional_indicators: usize
+) -> Option<Break> {
+	use Break::*;
+	
+	let prohibited = match (a, b) {
+		(BK, _)                      => return Some(Mandatory),
+		(CR, LF)                     => true,
+		(CR | LF | NL, _)            => return Some(Mandatory),
+		(_, BK | CR | LF | NL)       => true,
+		(_, SP | ZW)                 => true,
+		_ if before_spaces == ZW     => return Some(Allowed),
+		_ if raw == ZWJ              => true,
+		(_, CM | ZWJ) if a != SP     => true,
+		_ => false
+	};
+	if prohibited {
+		return None;
+	}
+	
+	// LB10: remaining combining marks are alphabetic
+	let b = match b {
+		CM | ZWJ => AL,
+		b        => b
+	};
+	
+	let prohibited = match (a, b) {
+		(WJ, _) | (_, WJ)                                     => true,
+		(GL, _)                                               => true,
+		(_, GL) if !matches!(a, SP | BA | HY)                 => true,
+		(_, CL | CP | EX | IS | SY)                           => true,
+		_ if before_spaces == OP                              => true,
+		(_, OP) if before_spaces == QU                        => true,
+		(_, NS) if matches!(before_spaces, CL | CP)           => true,
+		(_, B2) if before_spaces == B2                        => true,
+		(SP, _)                                               => return Some(Allowed),
+		(QU, _) | (_, QU)                                     => true,
+		(CB, _) | (_, CB)                                     => return Some(Allowed),
+		(_, BA | HY | NS) | (BB, _)                           => true,
+		(HY | BA, _) if a_prev == HL                          => true,
+		(SY, HL)                                              => true,
+		(_, IN)                                               => true,
+		(AL | HL, NU) | (NU, AL | HL)                         => true,
+		(PR, ID | EB | EM) | (ID | EB | EM, PO)               => true,
+		(PR | PO, AL | HL) | (AL | HL, PR | PO)               => true,
+		(CL | CP | NU, PO | PR) | (PO | PR, OP | NU)          => true,
+		(HY | IS | NU | SY, NU)                               => true,
+		(JL, JL | JV | H2 | H3) | (JV | H2, JV | JT)          => true,
+		(JT | H3, JT)                                         => true,
+		(JL | JV | JT | H2 | H3, PO) | (PR, JL | JV | JT | H2 | H3) => true,
+		(AL | HL, AL | HL)                                    => true,
+		(IS, AL | HL)                                         => true,
+		(AL | HL | NU, OP) if !is_wide(ch)                    => true,
+		(CP, AL | HL | NU)                                    => true,
+		(RI, RI)                                              => regional_indicators % 2 == 1,
+		(EB, EM)                                              => true,
+		_                                                     => false
+	};
+	(!prohibited).then_some(Allowed)
+}
+
+/// Returns `true` if the character has the east asian width fullwidth, wide or halfwidth, for
+/// the opening punctuation excluded from rule LB30.
+fn is_wide(ch: char) -> bool {
+	matches!(ch as u32, 0x2329 | 0x3000..=0x303F | 0xFE10..=0xFE19 | 0xFE30..=0xFE6F | 0xFF00..=0xFFEE)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{*, Break::*};
+	
+	#[test]
+	fn spaces_and_newlines() {
+		assert_eq!(line_breaks(""), vec![]);
+		assert_eq!(line_breaks("Hello world"), vec![(6, Allowed), (11, Mandatory)]);
+		assert_eq!(line_breaks("a  b"), vec![(3, Allowed), (4, Mandatory)]);
+		assert_eq!(line_breaks("a\nb"), vec![(2, Mandatory), (3, Mandatory)]);
+		assert_eq!(line_breaks("a\r\nb"), vec![(3, Mandatory), (4, Mandatory)]);
+		assert_eq!(line_breaks("a\n"), vec![(2, Mandatory)]);
+	}
+	
+	#[test]
+	fn glue_and_punctuation() {
+		// LB12: no break around a no-break space
+		assert_eq!(line_breaks("a\u{00A0}b"), vec![(4, Mandatory)]);
+		// LB13: no break before exclamation marks, even after spaces
+		assert_eq!(line_breaks("a !"), vec![(3, Mandatory)]);
+		// LB8: break after a zero width space
+		assert_eq!(line_breaks("a\u{200B}b"), vec![(4, Allowed), (5, Mandatory)]);
+		// LB21: break after, not before hyphens
+		assert_eq!(line_breaks("well-known"), vec![(5, Allowed), (10, Mandatory)]);
+		// LB25: numbers with separators and postfixes are not broken
+		assert_eq!(line_breaks("12.35% off"), vec![(7, Allowed), (10, Mandatory)]);
+	}
+	
+	#[test]
+	fn marks_and_ideographs() {
+		// LB9: combining marks belong to their base
+		assert_eq!(line_breaks("e\u{0301} x"), vec![(4, Allowed), (5, Mandatory)]);
+		// LB31: ideographs can be broken between every character
+		assert_eq!(line_breaks("日本語"), vec![(3, Allowed), (6, Allowed), (9, Mandatory)]);
+		// LB30a: regional indicators are broken in pairs
+		assert_eq!(line_breaks("🇩🇪🇫🇷"), vec![(8, Allowed), (16, Mandatory)]);
+		assert_eq!(LineBreak::of('\u{AC00}'), H2);
+		assert_eq!(LineBreak::of('\u{AC01}'), H3);
+	}
+}
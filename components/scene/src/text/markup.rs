@@ -0,0 +1,312 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A small markup language for formatted text, similar to BBCode:
+//!
+//! ```text
+//! [b]bold[/b], [color=red]red and [i]italic[/i][/color], [url="https://example.com"]link[/url]
+//! ```
+//!
+//! | Tag                 | Effect                                       |
+//! |---------------------|----------------------------------------------|
+//! | `b`, `i`, `faint`   | bold, italic and faint text                  |
+//! | `u`, `uu`, `s`, `o` | underline, double underline, strikethrough, overline |
+//! | `blink`, `inverse`  | blinking text, swapped colors                |
+//! | `color=C`, `bg=C`   | foreground and background color              |
+//! | `font=N`            | alternate font `N`, `0` is the primary font  |
+//! | `url=TARGET`        | a link                                       |
+//!
+//! Colors are `#rgb`, `#rrggbb`, `#rrggbbaa` or the names of the ANSI colors (`red`,
+//! `bright_red`, ...). Values can be quoted with `"`, tags must be closed in reverse order and
+//! `\` escapes the next character.
+
+use {super::{*, ansi::{COLORS, rgb}}, std::fmt};
+
+const COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MarkupErrorKind {
+	/// A `[` without a matching `]`.
+	UnterminatedTag,
+	/// A `\` at the end of the input.
+	TrailingEscape,
+	UnknownTag(String),
+	/// The tag requires a value or has a value but does not accept one.
+	InvalidValue(String),
+	/// A closing tag that does not match the innermost open tag.
+	UnexpectedClose(String),
+	/// A tag that is not closed at the end of the input.
+	Unclosed(String)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarkupError {
+	/// The byte offset of the tag in the input.
+	pub offset: usize,
+	pub kind:   MarkupErrorKind
+}
+
+impl fmt::Display for MarkupError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.kind {
+			MarkupErrorKind::UnterminatedTag      => write!(f, "unterminated tag")?,
+			MarkupErrorKind::TrailingEscape       => write!(f, "escape at the end of the input")?,
+			MarkupErrorKind::UnknownTag(tag)      => write!(f, "unknown tag `{}`", tag)?,
+			MarkupErrorKind::InvalidValue(tag)    => write!(f, "invalid value for tag `{}`", tag)?,
+			MarkupErrorKind::UnexpectedClose(tag) => write!(f, "unexpected closing tag `{}`", tag)?,
+			MarkupErrorKind::Unclosed(tag)        => write!(f, "tag `{}` is not closed", tag)?
+		}
+		write!(f, " at offset {}", self.offset)
+	}
+}
+
+impl std::error::Error for MarkupError {}
+
+/// A parsed tag, `value` is `None` for closing tags and tags without a value.
+struct Tag<'a> {
+	close: bool,
+	name:  &'a str,
+	value: Option<&'a str>,
+	/// The byte offset after the `]`.
+	end:   usize
+}
+
+/// Parses a tag that starts at the `[` at `start`.
+fn parse_tag(input: &str, start: usize) -> Result<Tag<'_>, MarkupError> {
+	let error = |kind| MarkupError { offset: start, kind };
+	let rest = &input[start + 1..];
+	let (close, rest) = match rest.strip_prefix('/') {
+		Some(rest) => (true, rest),
+		None       => (false, rest)
+	};
+	
+	let name_len = rest.find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_').unwrap_or(rest.len());
+	let (name, rest) = rest.split_at(name_len);
+	let (value, rest) = match rest.strip_prefix('=') {
+		Some(rest) if rest.starts_with('"') => {
+			let len = rest[1..].find('"').ok_or_else(|| error(MarkupErrorKind::UnterminatedTag))?;
+			(Some(&rest[1..len + 1]), &rest[len + 2..])
+		}
+		Some(rest) => {
+			let len = rest.find(']').ok_or_else(|| error(MarkupErrorKind::UnterminatedTag))?;
+			(Some(&rest[..len]), &rest[len..])
+		}
+		None => (None, rest)
+	};
+	
+	match rest.starts_with(']') {
+		true  => Ok(Tag { close, name, value, end: input.len() - rest.len() + 1 }),
+		false => Err(error(MarkupErrorKind::UnterminatedTag))
+	}
+}
+
+/// Parses a color name or a hexadecimal color.
+fn parse_color(value: &str) -> Option<Vec4<f64>> {
+	let Some(hex) = value.strip_prefix('#') else {
+		let (bright, name) = match value.strip_prefix("bright_") {
+			Some(name) => (8, name),
+			None       => (0, value)
+		};
+		return COLOR_NAMES.iter().position(|v| *v == name).map(|i| COLORS[i + bright]);
+	};
+	
+	if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+		return None;
+	}
+	
+	let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+	let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+	match hex.len() {
+		3 => Some(rgb(digit(0) * 17, digit(1) * 17, digit(2) * 17)),
+		6 => Some(rgb(byte(0), byte(2), byte(4))),
+		8 => {
+			let Vec4(r, g, b, _) = rgb(byte(0), byte(2), byte(4));
+			Some(Vec4(r, g, b, byte(6) as f64 / 255f64))
+		}
+		_ => None
+	}
+}
+
+/// Applies an opening tag to the style, returns `None` if the value is invalid.
+fn apply(ctrl: &mut TextCtrl, name: &str, value: Option<&str>, out: &mut FmtText) -> Option<()> {
+	let effect = match name {
+		"b"       => TextCtrl::BOLD,
+		"i"       => TextCtrl::ITALIC,
+		"faint"   => TextCtrl::FAINT,
+		"u"       => TextCtrl::UNDERLINE,
+		"uu"      => TextCtrl::DOUBLE_UNDERLINE,
+		"s"       => TextCtrl::STRIKETHROUGH,
+		"o"       => TextCtrl::OVERLINE,
+		"blink"   => TextCtrl::BLINK,
+		"inverse" => TextCtrl::INVERSE,
+		_         => {
+			let value = value?;
+			match name {
+				"color" => ctrl.color_fg = Some(parse_color(value)?),
+				"bg"    => ctrl.color_bg = Some(parse_color(value)?),
+				"font"  => ctrl.alt_font = value.parse().ok()?,
+				_       => ctrl.link = Some(out.add_link(value))
+			}
+			return Some(());
+		}
+	};
+	
+	if value.is_some() {
+		return None;
+	}
+	
+	ctrl.set(effect, true);
+	Some(())
+}
+
+/// Parses markup, see the [module](self) documentation for the syntax.
+pub fn parse(input: &str) -> Result<FmtText, MarkupError> {
+	const TAGS: &[&str] = &["b", "i", "faint", "u", "uu", "s", "o", "blink", "inverse", "color", "bg", "font", "url"];
+	
+	let mut out = FmtText::new();
+	let mut ctrl = TextCtrl::default();
+	// the open tags with their offset and the style before them
+	let mut stack = Vec::<(&str, usize, TextCtrl)>::new();
+	let mut text = String::new();
+	let mut pos = 0;
+	
+	while let Some(ch) = input[pos..].chars().next() {
+		match ch {
+			'\\' => {
+				let ch = input[pos + 1..].chars().next()
+					.ok_or(MarkupError { offset: pos, kind: MarkupErrorKind::TrailingEscape })?;
+				text.push(ch);
+				pos += 1 + ch.len_utf8();
+			}
+			'[' => {
+				let tag = parse_tag(input, pos)?;
+				let error = |kind| MarkupError { offset: pos, kind };
+				if !TAGS.contains(&tag.name) {
+					return Err(error(MarkupErrorKind::UnknownTag(tag.name.to_string())));
+				}
+				
+				out.push(ctrl, &text);
+				text.clear();
+				
+				if tag.close {
+					match stack.pop() {
+						Some((name, _, prev)) if name == tag.name && tag.value.is_none() => ctrl = prev,
+						_ => return Err(error(MarkupErrorKind::UnexpectedClose(tag.name.to_string())))
+					}
+				} else {
+					stack.push((tag.name, pos, ctrl));
+					apply(&mut ctrl, tag.name, tag.value, &mut out)
+						.ok_or_else(|| error(MarkupErrorKind::InvalidValue(tag.name.to_string())))?;
+				}
+				
+				pos = tag.end;
+			}
+			ch => {
+				text.push(ch);
+				pos += ch.len_utf8();
+			}
+		}
+	}
+	
+	if let Some((name, offset, _)) = stack.pop() {
+		return Err(MarkupError { offset, kind: MarkupErrorKind::Unclosed(name.to_string()) });
+	}
+	
+	out.push(ctrl, &text);
+	Ok(out)
+}
+
+impl FmtText {
+	/// Parses markup, see [`markup`](self) for the syntax.
+	pub fn from_markup(input: &str) -> Result<Self, MarkupError> {
+		parse(input)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn runs(input: &str) -> Vec<(usize, Option<Vec4<f64>>, String)> {
+		parse(input).unwrap().runs().iter().map(|(ctrl, text)| (ctrl.effects, ctrl.color_fg, text.clone())).collect()
+	}
+	
+	fn error(input: &str) -> (usize, MarkupErrorKind) {
+		let error = parse(input).unwrap_err();
+		(error.offset, error.kind)
+	}
+	
+	#[test]
+	fn nesting() {
+		let (bold, italic) = (TextCtrl::BOLD, TextCtrl::ITALIC);
+		assert_eq!(runs("a[b]b[i]c[/i]d[/b]e"), vec![
+			(0, None, "a".to_string()),
+			(bold, None, "b".to_string()),
+			(bold | italic, None, "c".to_string()),
+			(bold, None, "d".to_string()),
+			(0, None, "e".to_string())
+		]);
+		assert_eq!(runs("[color=red]a[color=#00f]b[/color]c[/color]"), vec![
+			(0, Some(COLORS[1]), "a".to_string()),
+			(0, Some(rgb(0, 0, 255)), "b".to_string()),
+			(0, Some(COLORS[1]), "c".to_string())
+		]);
+		
+		// the same effect nested twice stays set until the outer tag is closed
+		assert_eq!(runs("[u][u]a[/u]b[/u]"), vec![(TextCtrl::UNDERLINE, None, "ab".to_string())]);
+		assert_eq!(runs("\\[b]\\\\"), vec![(0, None, "[b]\\".to_string())]);
+		assert!(parse("").unwrap().is_empty());
+	}
+	
+	#[test]
+	fn values() {
+		let ctrl = |input: &str| parse(input).unwrap().runs()[0].0;
+		assert_eq!(ctrl("[bg=bright_black]a[/bg]").color_bg, Some(COLORS[8]));
+		assert_eq!(ctrl("[color=#102030]a[/color]").color_fg, Some(rgb(0x10, 0x20, 0x30)));
+		assert_eq!(ctrl("[color=#10203080]a[/color]").color_fg, Some(Vec4(0x10 as f64 / 255f64, 0x20 as f64 / 255f64, 0x30 as f64 / 255f64, 0x80 as f64 / 255f64)));
+		assert_eq!(ctrl("[font=3]a[/font]").alt_font, 3);
+		
+		let text = parse("[url=\"https://example.com/?a=[b]\"]a[/url]b").unwrap();
+		assert_eq!(text.runs()[0].0.link, Some(0));
+		assert_eq!(text.runs()[1].0.link, None);
+		assert_eq!(text.link(0), Some("https://example.com/?a=[b]"));
+		assert_eq!(text.to_string(), "ab");
+	}
+	
+	#[test]
+	fn errors() {
+		assert_eq!(error("ab[b"), (2, MarkupErrorKind::UnterminatedTag));
+		assert_eq!(error("[url=\"x]a"), (0, MarkupErrorKind::UnterminatedTag));
+		assert_eq!(error("a\\"), (1, MarkupErrorKind::TrailingEscape));
+		assert_eq!(error("a[x]"), (1, MarkupErrorKind::UnknownTag("x".to_string())));
+		assert_eq!(error("[color=nope]"), (0, MarkupErrorKind::InvalidValue("color".to_string())));
+		assert_eq!(error("[color=#12345]"), (0, MarkupErrorKind::InvalidValue("color".to_string())));
+		assert_eq!(error("[color]"), (0, MarkupErrorKind::InvalidValue("color".to_string())));
+		assert_eq!(error("[b=1]"), (0, MarkupErrorKind::InvalidValue("b".to_string())));
+		assert_eq!(error("[font=x]"), (0, MarkupErrorKind::InvalidValue("font".to_string())));
+		assert_eq!(error("[b][i]a[/b][/i]"), (7, MarkupErrorKind::UnexpectedClose("b".to_string())));
+		assert_eq!(error("a[/b]"), (1, MarkupErrorKind::UnexpectedClose("b".to_string())));
+		assert_eq!(error("[b]a[i]b[/i]"), (0, MarkupErrorKind::Unclosed("b".to_string())));
+		assert_eq!(error("ä[i]").0, 2);
+		assert_eq!(parse("a[x]").unwrap_err().to_string(), "unknown tag `x` at offset 1");
+	}
+}
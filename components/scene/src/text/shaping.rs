@@ -0,0 +1,512 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! OpenType shaping of a run of text with a single script and direction.
+//!
+//! Arabic, Syriac, N'Ko and Mongolian are shaped with their joining forms. The Indic scripts
+//! of the Brahmi family are split into syllables, pre-base matras are moved before the base
+//! consonant and the reph is moved to the end of the syllable, then the basic and the
+//! presentation features are applied. This covers the common cases, but not all the reordering
+//! rules of the script specific shaping specifications. The text is not normalized, so fonts
+//! only get precomposed characters if the text contains them.
+
+use {
+	super::{bidi, lookup, tables, Script},
+	crate::{Direction, Feature, FontReader, GlyphId, GlyphInfo, GlyphPosition, Tag, SCRIPT_DEFAULT},
+	std::ops::Range
+};
+
+/// The joining types of cursive scripts, transparent characters are derived from the general
+/// category.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Joining {
+	Dual,
+	Right,
+	Left,
+	Causing
+}
+
+/// The general categories of marks and format characters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Category {
+	Mn,
+	Mc,
+	Me,
+	Cf
+}
+
+const GLOBAL: u32 = 0x001;
+const ISOL:   u32 = 0x002;
+const FINA:   u32 = 0x004;
+const MEDI:   u32 = 0x008;
+const INIT:   u32 = 0x010;
+const RPHF:   u32 = 0x020;
+const HALF:   u32 = 0x040;
+const POST:   u32 = 0x080;
+
+const ZWNJ: char = '\u{200C}';
+const ZWJ:  char = '\u{200D}';
+
+/// Features applied to all scripts before the script specific features.
+const FEATURES_START: &[&[u8; 4]] = &[b"ccmp", b"locl"];
+/// Features applied to all scripts after the script specific features.
+const FEATURES_END: &[&[u8; 4]] = &[b"rlig", b"calt", b"liga", b"clig"];
+/// Positioning features applied to all scripts.
+const FEATURES_POSITION: &[&[u8; 4]] = &[b"kern", b"curs", b"dist", b"abvm", b"blwm", b"mark", b"mkmk"];
+
+/// The basic Indic features, in the order they are applied.
+const INDIC_BASIC: &[(&[u8; 4], u32)] = &[
+	(b"nukt", GLOBAL), (b"akhn", GLOBAL), (b"rphf", RPHF), (b"rkrf", GLOBAL), (b"pref", POST),
+	(b"blwf", POST), (b"abvf", POST), (b"half", HALF), (b"pstf", POST), (b"vatu", GLOBAL), (b"cjct", GLOBAL)
+];
+/// The Indic presentation features.
+const INDIC_PRESENTATION: &[&[u8; 4]] = &[b"pres", b"abvs", b"blws", b"psts", b"haln"];
+
+/// Matras that are composed of multiple parts, which are positioned separately.
+const SPLIT_MATRAS: &[(char, &[char])] = &[
+	('\u{09CB}', &['\u{09C7}', '\u{09BE}']), ('\u{09CC}', &['\u{09C7}', '\u{09D7}']),
+	('\u{0B48}', &['\u{0B47}', '\u{0B56}']), ('\u{0B4B}', &['\u{0B47}', '\u{0B3E}']),
+	('\u{0B4C}', &['\u{0B47}', '\u{0B57}']), ('\u{0BCA}', &['\u{0BC6}', '\u{0BBE}']),
+	('\u{0BCB}', &['\u{0BC7}', '\u{0BBE}']), ('\u{0BCC}', &['\u{0BC6}', '\u{0BD7}']),
+	('\u{0C48}', &['\u{0C46}', '\u{0C56}']), ('\u{0CC0}', &['\u{0CBF}', '\u{0CD5}']),
+	('\u{0CC7}', &['\u{0CC6}', '\u{0CD5}']), ('\u{0CC8}', &['\u{0CC6}', '\u{0CD6}']),
+	('\u{0CCA}', &['\u{0CC6}', '\u{0CC2}']), ('\u{0CCB}', &['\u{0CC6}', '\u{0CC2}', '\u{0CD5}']),
+	('\u{0D4A}', &['\u{0D46}', '\u{0D3E}']), ('\u{0D4B}', &['\u{0D47}', '\u{0D3E}']),
+	('\u{0D4C}', &['\u{0D46}', '\u{0D57}'])
+];
+
+/// Matras that are displayed before the base consonant.
+const PRE_BASE_MATRAS: &[char] = &[
+	'\u{093F}', '\u{094E}', '\u{09BF}', '\u{09C7}', '\u{09C8}', '\u{0A3F}', '\u{0ABF}', '\u{0B47}',
+	'\u{0BC6}', '\u{0BC7}', '\u{0BC8}', '\u{0D46}', '\u{0D47}', '\u{0D48}'
+];
+
+fn joining(ch: char) -> Option<Joining> {
+	lookup(tables::shaping::JOINING_TYPES, ch)
+}
+
+fn category(ch: char) -> Option<Category> {
+	lookup(tables::shaping::CATEGORIES, ch)
+}
+
+/// Returns `true` for characters that are invisible unless a font substitutes them.
+pub(super) fn is_ignorable(ch: char) -> bool {
+	ch.is_control() || category(ch) == Some(Category::Cf)
+}
+
+/// Shapes a run of text and returns the glyphs in logical order with their positions in font
+/// units. The clusters are the byte offsets of the characters in the text.
+pub(super) fn shape(
+	font:      &mut dyn FontReader,
+	text:      &str,
+	range:     Range<usize>,
+	script:    Script,
+	direction: Direction,
+	language:  Option<Tag>,
+	features:  &[Feature]
+) -> (Vec<GlyphInfo>, Vec<GlyphPosition>) {
+	let available = font.scripts();
+	let tags = script.tags().iter().map(|&tag| Tag::new(tag)).collect::<Vec<_>>();
+	let tag = tags.iter()
+		.find(|tag| available.contains(tag))
+		.or(tags.first())
+		.copied()
+		.unwrap_or(SCRIPT_DEFAULT);
+	
+	let mut chars = Vec::with_capacity(range.len());
+	for (i, mut ch) in text[range.clone()].char_indices() {
+		if direction == Direction::RightToLeft {
+			ch = bidi::mirror(ch).unwrap_or(ch);
+		}
+		
+		match SPLIT_MATRAS.iter().find(|(matra, _)| *matra == ch) {
+			Some((_, parts)) => chars.extend(parts.iter().map(|&ch| (range.start + i, ch))),
+			None => chars.push((range.start + i, ch))
+		}
+	}
+	
+	let mut glyphs = chars.iter()
+		.map(|&(i, ch)| GlyphInfo { id: font.glyph_index(ch).unwrap_or_default(), cluster: i, mask: GLOBAL })
+		.collect::<Vec<_>>();
+	
+	// the masks are set and Indic syllables are reordered before any substitution
+	let syllables = match script.indic_block() {
+		Some(block) => {
+			let below = (0..glyphs.len())
+				.map(|k| k > 0
+					&& indic_category(script, block, chars[k - 1].1) == Indic::Halant
+					&& has_below_form(font, tag, language, glyphs[k - 1].id, glyphs[k].id))
+				.collect::<Vec<_>>();
+			indic_syllables(script, block, &chars, &below, &mut glyphs)
+		}
+		None => Vec::new()
+	};
+	if script.is_joining() {
+		set_joining_masks(&chars, &mut glyphs);
+	}
+	
+	let mut substitute = |glyphs: &mut Vec<GlyphInfo>, features: &[Feature]| font.substitute(glyphs, tag, language, features);
+	let global = |tags: &[&[u8; 4]]| tags.iter().map(|&tag| Feature { tag: Tag::new(tag), mask: GLOBAL }).collect::<Vec<_>>();
+	
+	substitute(&mut glyphs, &global(FEATURES_START));
+	if script.is_joining() {
+		for (tag, mask) in [(b"isol", ISOL), (b"fina", FINA), (b"medi", MEDI), (b"init", INIT)] {
+			substitute(&mut glyphs, &[Feature { tag: Tag::new(tag), mask }]);
+		}
+	} else if let Some(block) = script.indic_block() {
+		let mut reph = Vec::new();
+		for &(tag, mask) in INDIC_BASIC {
+			substitute(&mut glyphs, &[Feature { tag: Tag::new(tag), mask }]);
+			if mask == RPHF {
+				reph = syllables.iter()
+					.filter(|s| s.reph.is_some_and(|halant| glyphs.iter().all(|g| g.cluster != halant)))
+					.map(|s| s.range.clone())
+					.collect::<Vec<_>>();
+			}
+		}
+		
+		move_reph(block, &chars, &mut glyphs, &reph);
+		substitute(&mut glyphs, &global(INDIC_PRESENTATION));
+		
+		// the glyphs of a syllable are reordered, so they form a single cluster
+		for glyph in &mut glyphs {
+			if let Some(s) = syllables.iter().find(|s| s.range.contains(&glyph.cluster)) {
+				glyph.cluster = s.range.start;
+			}
+		}
+	}
+	
+	let mut end = global(FEATURES_END);
+	end.extend_from_slice(features);
+	substitute(&mut glyphs, &end);
+	
+	let mut position = global(FEATURES_POSITION);
+	position.extend_from_slice(features);
+	let mut positions = font.position(&glyphs, direction, tag, language, &position);
+	
+	// the advances of marks are zeroed, but spacing marks of Indic scripts keep theirs
+	if script.indic_block().is_some() {
+		for (glyph, pos) in glyphs.iter().zip(&mut positions).filter(|(_, pos)| pos.x_advance == 0) {
+			pos.x_advance = font.get_glyph(glyph.id).map_or(0, |g| g.metrics.advance.0 as i32);
+		}
+	}
+	
+	// drop the glyphs of invisible characters the font has no glyph for
+	let char_at = |cluster: usize| chars.iter().find(|(i, _)| *i == cluster).map(|&(_, ch)| ch);
+	let mut k = 0;
+	glyphs.retain(|g| {
+		let keep = g.id != GlyphId(0) || !char_at(g.cluster).is_some_and(is_ignorable);
+		if !keep {
+			positions.remove(k);
+		} else {
+			k += 1;
+		}
+		keep
+	});
+	(glyphs, positions)
+}
+
+/// Sets the masks of the joining forms.
+fn set_joining_masks(chars: &[(usize, char)], glyphs: &mut [GlyphInfo]) {
+	use Joining::*;
+	
+	let mut forms = vec![0; chars.len()];
+	let mut prev: Option<(usize, Joining)> = None;
+	for (i, &(_, ch)) in chars.iter().enumerate() {
+		let kind = joining(ch);
+		if kind.is_none() && ch != ZWNJ && matches!(category(ch), Some(Category::Mn | Category::Me | Category::Cf)) {
+			continue;
+		}
+		
+		if matches!(kind, Some(Dual | Right | Left)) {
+			forms[i] = ISOL;
+		}
+		
+		if let (Some((j, Dual | Left | Causing)), Some(Dual | Right | Causing)) = (prev, kind) {
+			forms[j] = match forms[j] {
+				ISOL => INIT,
+				FINA => MEDI,
+				form => form
+			};
+			if forms[i] != 0 {
+				forms[i] = FINA;
+			}
+		}
+		prev = kind.map(|kind| (i, kind));
+	}
+	
+	for (glyph, form) in glyphs.iter_mut().zip(forms) {
+		glyph.mask |= form;
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Indic {
+	Consonant,
+	Ra,
+	Vowel,
+	Matra,
+	PreBaseMatra,
+	Nukta,
+	Halant,
+	Modifier,
+	Joiner,
+	Other
+}
+
+fn indic_category(script: Script, block: u32, ch: char) -> Indic {
+	use Indic::*;
+	
+	if ch == ZWJ || ch == ZWNJ {
+		return Joiner;
+	}
+	
+	let Some(offset) = (ch as u32).checked_sub(block).filter(|&o| o < 0x80) else {
+		return Other;
+	};
+	
+	match offset {
+		_ if PRE_BASE_MATRAS.contains(&ch) => PreBaseMatra,
+		0x30 => Ra,
+		0x70 if script == Script::Bengali => Ra,
+		0x15..=0x39 | 0x58..=0x5F => Consonant,
+		0x71 if script == Script::Bengali => Consonant,
+		0x04..=0x14 | 0x60..=0x61 => Vowel,
+		0x3C => Nukta,
+		0x4D => Halant,
+		0x3A..=0x3B | 0x3E..=0x4C | 0x4E..=0x4F | 0x55..=0x57 | 0x62..=0x63 => Matra,
+		_ if category(ch).is_some() => Modifier,
+		_ => Other
+	}
+}
+
+/// A syllable of an Indic script.
+#[derive(Clone, Debug)]
+struct Syllable {
+	/// The byte range of the syllable in the text.
+	range: Range<usize>,
+	/// The cluster of the halant of a reph candidate.
+	reph:  Option<usize>
+}
+
+/// Splits the characters into syllables, sets the masks of the basic features and moves
+/// pre-base matras before the consonants.
+fn indic_syllables(
+	script: Script,
+	block:  u32,
+	chars:  &[(usize, char)],
+	below:  &[bool],
+	glyphs: &mut [GlyphInfo]
+) -> Vec<Syllable> {
+	use Indic::*;
+	
+	let categories = chars.iter().map(|&(_, ch)| indic_category(script, block, ch)).collect::<Vec<_>>();
+	let is = |i: usize, expected: &[Indic]| categories.get(i).is_some_and(|c| expected.contains(c));
+	let end_of = |i: usize| chars.get(i).map_or(usize::MAX, |&(offset, _)| offset);
+	
+	let mut syllables = Vec::new();
+	let mut i = 0;
+	while i < chars.len() {
+		let start = i;
+		let mut consonants = Vec::new();
+		match categories[i] {
+			Consonant | Ra => loop {
+				consonants.push(i);
+				i += 1;
+				if is(i, &[Nukta]) {
+					i += 1;
+				}
+				
+				let mut j = i;
+				if is(j, &[Halant]) {
+					j += 1;
+					if is(j, &[Joiner]) {
+						j += 1;
+					}
+					if is(j, &[Consonant, Ra]) {
+						i = j;
+						continue;
+					}
+				}
+				break;
+			}
+			_ => i += 1
+		}
+		
+		while is(i, &[Matra, PreBaseMatra, Nukta, Halant, Modifier, Joiner]) {
+			i += 1;
+		}
+		
+		// a ra with a halant at the start of the syllable becomes a reph
+		let reph = script.has_implicit_reph()
+			&& consonants.len() > 1
+			&& categories[start] == Ra
+			&& is(start + 1, &[Halant])
+			&& !is(start + 2, &[Joiner]);
+		let first = if reph { start + 2 } else { start };
+		
+		// the base is the last consonant without a below-base or post-base form
+		let mut candidates = consonants.iter().copied().filter(|&c| c >= first);
+		let base = candidates.clone().rev().find(|&c| !below[c]).or_else(|| candidates.next()).unwrap_or(first);
+		
+		for (k, glyph) in glyphs.iter_mut().enumerate().take(i).skip(start) {
+			glyph.mask |= match k {
+				_ if reph && k < first => RPHF,
+				_ if k < base          => HALF,
+				_ if k > base          => POST,
+				_                      => 0
+			};
+		}
+		
+		// pre-base matras are moved to the position after a reph
+		let mut target = first;
+		for k in first..i {
+			if categories[k] == PreBaseMatra {
+				glyphs[target..=k].rotate_right(1);
+				target += 1;
+			}
+		}
+		
+		// Telugu and Kannada matras, except some right side ones, precede the below-base forms
+		if matches!(script, Script::Telugu | Script::Kannada) {
+			let after_base = base + 1 + is(base + 1, &[Nukta]) as usize;
+			let mut target = after_base;
+			for k in after_base..i {
+				if categories[k] == Matra && !matches!(chars[k].1, '\u{0C43}' | '\u{0C44}' | '\u{0CC3}'..='\u{0CC4}' | '\u{0CD5}'..='\u{0CD6}') {
+					glyphs[target..=k].rotate_right(1);
+					target += 1;
+				}
+			}
+		}
+		
+		syllables.push(Syllable {
+			range: chars[start].0..end_of(i),
+			reph:  reph.then(|| chars[start + 1].0)
+		});
+	}
+	syllables
+}
+
+/// Returns `true` if a consonant after a halant has a below-base or post-base form.
+fn has_below_form(font: &mut dyn FontReader, script: Tag, language: Option<Tag>, halant: GlyphId, consonant: GlyphId) -> bool {
+	let features = [Feature { tag: Tag::new(b"blwf"), mask: POST }, Feature { tag: Tag::new(b"pstf"), mask: POST }];
+	[[halant, consonant], [consonant, halant]].iter().any(|pair| {
+		let mut glyphs = pair.iter()
+			.enumerate()
+			.map(|(i, &id)| GlyphInfo { id, cluster: i, mask: POST })
+			.collect::<Vec<_>>();
+		font.substitute(&mut glyphs, script, language, &features);
+		glyphs.len() != pair.len() || glyphs.iter().zip(pair).any(|(glyph, &id)| glyph.id != id)
+	})
+}
+
+/// Moves the reph glyphs of the syllables with the given ranges to the end of the syllable,
+/// before the syllable modifiers.
+fn move_reph(block: u32, chars: &[(usize, char)], glyphs: &mut [GlyphInfo], syllables: &[Range<usize>]) {
+	let is_modifier = |cluster: usize| chars.iter()
+		.find(|(i, _)| *i == cluster)
+		.is_some_and(|&(_, ch)| matches!((ch as u32).wrapping_sub(block), 0x01..=0x03));
+	
+	for range in syllables {
+		let Some(start) = glyphs.iter().position(|g| range.contains(&g.cluster)) else {
+			continue;
+		};
+		
+		let mut end = start + glyphs[start..].iter().take_while(|g| range.contains(&g.cluster)).count();
+		while end > start + 1 && is_modifier(glyphs[end - 1].cluster) {
+			end -= 1;
+		}
+		glyphs[start..end].rotate_left(1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, super::super::tests::TestFont};
+	
+	fn run(font: &mut TestFont, text: &str, script: Script, direction: Direction, features: &[Feature]) -> Vec<(u16, usize)> {
+		let (glyphs, positions) = shape(font, text, 0..text.len(), script, direction, None, features);
+		assert_eq!(glyphs.len(), positions.len());
+		glyphs.iter().map(|glyph| (glyph.id.0, glyph.cluster)).collect()
+	}
+	
+	/// The clusters a feature was applied to, from the last time it was applied.
+	fn applied(font: &TestFont, tag: &[u8; 4]) -> Vec<usize> {
+		font.applied.iter().rev().find(|(t, _)| *t == Tag::new(tag)).map(|(_, c)| c.clone()).unwrap_or_default()
+	}
+	
+	#[test]
+	fn features() {
+		let mut font = TestFont::default();
+		let smcp = Feature { tag: Tag::new(b"smcp"), mask: GLOBAL };
+		assert_eq!(run(&mut font, "fix", Script::Latin, Direction::LeftToRight, &[smcp]), vec![(0xFB01, 0), (0x78, 2)]);
+		let tags = font.applied.iter().map(|(tag, _)| tag.0).collect::<Vec<_>>();
+		assert_eq!(tags, vec![*b"ccmp", *b"locl", *b"rlig", *b"calt", *b"liga", *b"clig", *b"smcp"]);
+	}
+	
+	#[test]
+	fn mirroring_and_ignorables() {
+		let mut font = TestFont::default();
+		assert_eq!(run(&mut font, "(a)", Script::Latin, Direction::RightToLeft, &[]), vec![(0x29, 0), (0x61, 1), (0x28, 2)]);
+		// control characters without a glyph are dropped
+		assert_eq!(run(&mut font, "a\u{7}b", Script::Latin, Direction::LeftToRight, &[]), vec![(0x61, 0), (0x62, 2)]);
+	}
+	
+	#[test]
+	fn joining_forms() {
+		let forms = |text: &str| {
+			let mut font = TestFont::default();
+			run(&mut font, text, Script::Arabic, Direction::RightToLeft, &[]);
+			[b"isol", b"init", b"medi", b"fina"].map(|tag| applied(&font, tag))
+		};
+		
+		// beh, yeh and teh are dual joining
+		assert_eq!(forms("بيت"), [vec![], vec![0], vec![2], vec![4]]);
+		assert_eq!(forms("ب ب"), [vec![0, 3], vec![], vec![], vec![]]);
+		// marks are transparent
+		assert_eq!(forms("ب\u{064E}ب"), [vec![], vec![0], vec![], vec![4]]);
+		// alef only joins to the preceding letter
+		assert_eq!(forms("اب"), [vec![0, 2], vec![], vec![], vec![]]);
+		assert_eq!(forms("با"), [vec![], vec![0], vec![], vec![2]]);
+		// a zero width non-joiner breaks the joining
+		assert_eq!(forms("ب\u{200C}ب"), [vec![0, 5], vec![], vec![], vec![]]);
+	}
+	
+	#[test]
+	fn indic() {
+		// the pre-base matra i is moved before the consonant ka, the syllable is one cluster
+		let mut font = TestFont::default();
+		assert_eq!(run(&mut font, "कि", Script::Devanagari, Direction::LeftToRight, &[]), vec![(0x093F, 0), (0x0915, 0)]);
+		
+		// a ra and a halant at the start of a syllable get the reph form, the consonants
+		// before the base get the half forms
+		let mut font = TestFont::default();
+		run(&mut font, "र्क", Script::Devanagari, Direction::LeftToRight, &[]);
+		assert_eq!(applied(&font, b"rphf"), vec![0, 3]);
+		assert_eq!(applied(&font, b"half"), vec![]);
+		
+		let mut font = TestFont::default();
+		run(&mut font, "क्षि", Script::Devanagari, Direction::LeftToRight, &[]);
+		assert_eq!(applied(&font, b"rphf"), vec![]);
+		assert_eq!(applied(&font, b"half"), vec![0, 3]);
+	}
+}
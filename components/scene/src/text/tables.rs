@@ -0,0 +1,997 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Unicode character property tables (Unicode 15.0) used by the bidi, line
+//! breaking, script itemization and shaping code, as sorted inclusive ranges.
+
+pub mod bidi {
+	use crate::text::bidi::BidiClass::{self, *};
+	
+	/// The bidi classes of all code points that are not `L`.
+	pub const CLASSES: &[(u32, u32, BidiClass)] = &[
+		(0x0000, 0x0008, BN), (0x0009, 0x0009, S), (0x000A, 0x000A, B), (0x000B, 0x000B, S),
+		(0x000C, 0x000C, WS), (0x000D, 0x000D, B), (0x000E, 0x001B, BN), (0x001C, 0x001E, B),
+		(0x001F, 0x001F, S), (0x0020, 0x0020, WS), (0x0021, 0x0022, ON), (0x0023, 0x0025, ET),
+		(0x0026, 0x002A, ON), (0x002B, 0x002B, ES), (0x002C, 0x002C, CS), (0x002D, 0x002D, ES),
+		(0x002E, 0x002F, CS), (0x0030, 0x0039, EN), (0x003A, 0x003A, CS), (0x003B, 0x0040, ON),
+		(0x005B, 0x0060, ON), (0x007B, 0x007E, ON), (0x007F, 0x0084, BN), (0x0085, 0x0085, B),
+		(0x0086, 0x009F, BN), (0x00A0, 0x00A0, CS), (0x00A1, 0x00A1, ON), (0x00A2, 0x00A5, ET),
+		(0x00A6, 0x00A9, ON), (0x00AB, 0x00AC, ON), (0x00AD, 0x00AD, BN), (0x00AE, 0x00AF, ON),
+		(0x00B0, 0x00B1, ET), (0x00B2, 0x00B3, EN), (0x00B4, 0x00B4, ON), (0x00B6, 0x00B8, ON),
+		(0x00B9, 0x00B9, EN), (0x00BB, 0x00BF, ON), (0x00D7, 0x00D7, ON), (0x00F7, 0x00F7, ON),
+		(0x02B9, 0x02BA, ON), (0x02C2, 0x02CF, ON), (0x02D2, 0x02DF, ON), (0x02E5, 0x02ED, ON),
+		(0x02EF, 0x02FF, ON), (0x0300, 0x036F, NSM), (0x0374, 0x0375, ON), (0x037E, 0x037E, ON),
+		(0x0384, 0x0385, ON), (0x0387, 0x0387, ON), (0x03F6, 0x03F6, ON), (0x0483, 0x0489, NSM),
+		(0x058A, 0x058A, ON), (0x058D, 0x058E, ON), (0x058F, 0x058F, ET), (0x0590, 0x0590, R),
+		(0x0591, 0x05BD, NSM), (0x05BE, 0x05BE, R), (0x05BF, 0x05BF, NSM), (0x05C0, 0x05C0, R),
+		(0x05C1, 0x05C2, NSM), (0x05C3, 0x05C3, R), (0x05C4, 0x05C5, NSM), (0x05C6, 0x05C6, R),
+		(0x05C7, 0x05C7, NSM), (0x05C8, 0x05FF, R), (0x0600, 0x0605, AN), (0x0606, 0x0607, ON),
+		(0x0608, 0x0608, AL), (0x0609, 0x060A, ET), (0x060B, 0x060B, AL), (0x060C, 0x060C, CS),
+		(0x060D, 0x060D, AL), (0x060E, 0x060F, ON), (0x0610, 0x061A, NSM), (0x061B, 0x064A, AL),
+		(0x064B, 0x065F, NSM), (0x0660, 0x0669, AN), (0x066A, 0x066A, ET), (0x066B, 0x066C, AN),
+		(0x066D, 0x066F, AL), (0x0670, 0x0670, NSM), (0x0671, 0x06D5, AL), (0x06D6, 0x06DC, NSM),
+		(0x06DD, 0x06DD, AN), (0x06DE, 0x06DE, ON), (0x06DF, 0x06E4, NSM), (0x06E5, 0x06E6, AL),
+		(0x06E7, 0x06E8, NSM), (0x06E9, 0x06E9, ON), (0x06EA, 0x06ED, NSM), (0x06EE, 0x06EF, AL),
+		(0x06F0, 0x06F9, EN), (0x06FA, 0x0710, AL), (0x0711, 0x0711, NSM), (0x0712, 0x072F, AL),
+		(0x0730, 0x074A, NSM), (0x074B, 0x07A5, AL), (0x07A6, 0x07B0, NSM), (0x07B1, 0x07BF, AL),
+		(0x07C0, 0x07EA, R), (0x07EB, 0x07F3, NSM), (0x07F4, 0x07F5, R), (0x07F6, 0x07F9, ON),
+		(0x07FA, 0x07FC, R), (0x07FD, 0x07FD, NSM), (0x07FE, 0x0815, R), (0x0816, 0x0819, NSM),
+		(0x081A, 0x081A, R), (0x081B, 0x0823, NSM), (0x0824, 0x0824, R), (0x0825, 0x0827, NSM),
+		(0x0828, 0x0828, R), (0x0829, 0x082D, NSM), (0x082E, 0x0858, R), (0x0859, 0x085B, NSM),
+		(0x085C, 0x085F, R), (0x0860, 0x086A, AL), (0x086B, 0x086F, R), (0x0870, 0x088E, AL),
+		(0x088F, 0x088F, R), (0x0890, 0x0891, AN), (0x0892, 0x0897, R), (0x0898, 0x089F, NSM),
+		(0x08A0, 0x08C9, AL), (0x08CA, 0x08E1, NSM), (0x08E2, 0x08E2, AN), (0x08E3, 0x0902, NSM),
+		(0x093A, 0x093A, NSM), (0x093C, 0x093C, NSM), (0x0941, 0x0948, NSM), (0x094D, 0x094D, NSM),
+		(0x0951, 0x0957, NSM), (0x0962, 0x0963, NSM), (0x0981, 0x0981, NSM), (0x09BC, 0x09BC, NSM),
+		(0x09C1, 0x09C4, NSM), (0x09CD, 0x09CD, NSM), (0x09E2, 0x09E3, NSM), (0x09F2, 0x09F3, ET),
+		(0x09FB, 0x09FB, ET), (0x09FE, 0x09FE, NSM), (0x0A01, 0x0A02, NSM), (0x0A3C, 0x0A3C, NSM),
+		(0x0A41, 0x0A42, NSM), (0x0A47, 0x0A48, NSM), (0x0A4B, 0x0A4D, NSM), (0x0A51, 0x0A51, NSM),
+		(0x0A70, 0x0A71, NSM), (0x0A75, 0x0A75, NSM), (0x0A81, 0x0A82, NSM), (0x0ABC, 0x0ABC, NSM),
+		(0x0AC1, 0x0AC5, NSM), (0x0AC7, 0x0AC8, NSM), (0x0ACD, 0x0ACD, NSM), (0x0AE2, 0x0AE3, NSM),
+		(0x0AF1, 0x0AF1, ET), (0x0AFA, 0x0AFF, NSM), (0x0B01, 0x0B01, NSM), (0x0B3C, 0x0B3C, NSM),
+		(0x0B3F, 0x0B3F, NSM), (0x0B41, 0x0B44, NSM), (0x0B4D, 0x0B4D, NSM), (0x0B55, 0x0B56, NSM),
+		(0x0B62, 0x0B63, NSM), (0x0B82, 0x0B82, NSM), (0x0BC0, 0x0BC0, NSM), (0x0BCD, 0x0BCD, NSM),
+		(0x0BF3, 0x0BF8, ON), (0x0BF9, 0x0BF9, ET), (0x0BFA, 0x0BFA, ON), (0x0C00, 0x0C00, NSM),
+		(0x0C04, 0x0C04, NSM), (0x0C3C, 0x0C3C, NSM), (0x0C3E, 0x0C40, NSM), (0x0C46, 0x0C48, NSM),
+		(0x0C4A, 0x0C4D, NSM), (0x0C55, 0x0C56, NSM), (0x0C62, 0x0C63, NSM), (0x0C78, 0x0C7E, ON),
+		(0x0C81, 0x0C81, NSM), (0x0CBC, 0x0CBC, NSM), (0x0CCC, 0x0CCD, NSM), (0x0CE2, 0x0CE3, NSM),
+		(0x0D00, 0x0D01, NSM), (0x0D3B, 0x0D3C, NSM), (0x0D41, 0x0D44, NSM), (0x0D4D, 0x0D4D, NSM),
+		(0x0D62, 0x0D63, NSM), (0x0D81, 0x0D81, NSM), (0x0DCA, 0x0DCA, NSM), (0x0DD2, 0x0DD4, NSM),
+		(0x0DD6, 0x0DD6, NSM), (0x0E31, 0x0E31, NSM), (0x0E34, 0x0E3A, NSM), (0x0E3F, 0x0E3F, ET),
+		(0x0E47, 0x0E4E, NSM), (0x0EB1, 0x0EB1, NSM), (0x0EB4, 0x0EBC, NSM), (0x0EC8, 0x0ECE, NSM),
+		(0x0F18, 0x0F19, NSM), (0x0F35, 0x0F35, NSM), (0x0F37, 0x0F37, NSM), (0x0F39, 0x0F39, NSM),
+		(0x0F3A, 0x0F3D, ON), (0x0F71, 0x0F7E, NSM), (0x0F80, 0x0F84, NSM), (0x0F86, 0x0F87, NSM),
+		(0x0F8D, 0x0F97, NSM), (0x0F99, 0x0FBC, NSM), (0x0FC6, 0x0FC6, NSM), (0x102D, 0x1030, NSM),
+		(0x1032, 0x1037, NSM), (0x1039, 0x103A, NSM), (0x103D, 0x103E, NSM), (0x1058, 0x1059, NSM),
+		(0x105E, 0x1060, NSM), (0x1071, 0x1074, NSM), (0x1082, 0x1082, NSM), (0x1085, 0x1086, NSM),
+		(0x108D, 0x108D, NSM), (0x109D, 0x109D, NSM), (0x135D, 0x135F, NSM), (0x1390, 0x1399, ON),
+		(0x1400, 0x1400, ON), (0x1680, 0x1680, WS), (0x169B, 0x169C, ON), (0x1712, 0x1714, NSM),
+		(0x1732, 0x1733, NSM), (0x1752, 0x1753, NSM), (0x1772, 0x1773, NSM), (0x17B4, 0x17B5, NSM),
+		(0x17B7, 0x17BD, NSM), (0x17C6, 0x17C6, NSM), (0x17C9, 0x17D3, NSM), (0x17DB, 0x17DB, ET),
+		(0x17DD, 0x17DD, NSM), (0x17F0, 0x17F9, ON), (0x1800, 0x180A, ON), (0x180B, 0x180D, NSM),
+		(0x180E, 0x180E, BN), (0x180F, 0x180F, NSM), (0x1885, 0x1886, NSM), (0x18A9, 0x18A9, NSM),
+		(0x1920, 0x1922, NSM), (0x1927, 0x1928, NSM), (0x1932, 0x1932, NSM), (0x1939, 0x193B, NSM),
+		(0x1940, 0x1940, ON), (0x1944, 0x1945, ON), (0x19DE, 0x19FF, ON), (0x1A17, 0x1A18, NSM),
+		(0x1A1B, 0x1A1B, NSM), (0x1A56, 0x1A56, NSM), (0x1A58, 0x1A5E, NSM), (0x1A60, 0x1A60, NSM),
+		(0x1A62, 0x1A62, NSM), (0x1A65, 0x1A6C, NSM), (0x1A73, 0x1A7C, NSM), (0x1A7F, 0x1A7F, NSM),
+		(0x1AB0, 0x1ACE, NSM), (0x1B00, 0x1B03, NSM), (0x1B34, 0x1B34, NSM), (0x1B36, 0x1B3A, NSM),
+		(0x1B3C, 0x1B3C, NSM), (0x1B42, 0x1B42, NSM), (0x1B6B, 0x1B73, NSM), (0x1B80, 0x1B81, NSM),
+		(0x1BA2, 0x1BA5, NSM), (0x1BA8, 0x1BA9, NSM), (0x1BAB, 0x1BAD, NSM), (0x1BE6, 0x1BE6, NSM),
+		(0x1BE8, 0x1BE9, NSM), (0x1BED, 0x1BED, NSM), (0x1BEF, 0x1BF1, NSM), (0x1C2C, 0x1C33, NSM),
+		(0x1C36, 0x1C37, NSM), (0x1CD0, 0x1CD2, NSM), (0x1CD4, 0x1CE0, NSM), (0x1CE2, 0x1CE8, NSM),
+		(0x1CED, 0x1CED, NSM), (0x1CF4, 0x1CF4, NSM), (0x1CF8, 0x1CF9, NSM), (0x1DC0, 0x1DFF, NSM),
+		(0x1FBD, 0x1FBD, ON), (0x1FBF, 0x1FC1, ON), (0x1FCD, 0x1FCF, ON), (0x1FDD, 0x1FDF, ON),
+		(0x1FED, 0x1FEF, ON), (0x1FFD, 0x1FFE, ON), (0x2000, 0x200A, WS), (0x200B, 0x200D, BN),
+		(0x200F, 0x200F, R), (0x2010, 0x2027, ON), (0x2028, 0x2028, WS), (0x2029, 0x2029, B),
+		(0x202A, 0x202A, LRE), (0x202B, 0x202B, RLE), (0x202C, 0x202C, PDF), (0x202D, 0x202D, LRO),
+		(0x202E, 0x202E, RLO), (0x202F, 0x202F, CS), (0x2030, 0x2034, ET), (0x2035, 0x2043, ON),
+		(0x2044, 0x2044, CS), (0x2045, 0x205E, ON), (0x205F, 0x205F, WS), (0x2060, 0x2064, BN),
+		(0x2066, 0x2066, LRI), (0x2067, 0x2067, RLI), (0x2068, 0x2068, FSI), (0x2069, 0x2069, PDI),
+		(0x206A, 0x206F, BN), (0x2070, 0x2070, EN), (0x2074, 0x2079, EN), (0x207A, 0x207B, ES),
+		(0x207C, 0x207E, ON), (0x2080, 0x2089, EN), (0x208A, 0x208B, ES), (0x208C, 0x208E, ON),
+		(0x20A0, 0x20CF, ET), (0x20D0, 0x20F0, NSM), (0x2100, 0x2101, ON), (0x2103, 0x2106, ON),
+		(0x2108, 0x2109, ON), (0x2114, 0x2114, ON), (0x2116, 0x2118, ON), (0x211E, 0x2123, ON),
+		(0x2125, 0x2125, ON), (0x2127, 0x2127, ON), (0x2129, 0x2129, ON), (0x212E, 0x212E, ET),
+		(0x213A, 0x213B, ON), (0x2140, 0x2144, ON), (0x214A, 0x214D, ON), (0x2150, 0x215F, ON),
+		(0x2189, 0x218B, ON), (0x2190, 0x2211, ON), (0x2212, 0x2212, ES), (0x2213, 0x2213, ET),
+		(0x2214, 0x2335, ON), (0x237B, 0x2394, ON), (0x2396, 0x2426, ON), (0x2440, 0x244A, ON),
+		(0x2460, 0x2487, ON), (0x2488, 0x249B, EN), (0x24EA, 0x26AB, ON), (0x26AD, 0x27FF, ON),
+		(0x2900, 0x2B73, ON), (0x2B76, 0x2B95, ON), (0x2B97, 0x2BFF, ON), (0x2CE5, 0x2CEA, ON),
+		(0x2CEF, 0x2CF1, NSM), (0x2CF9, 0x2CFF, ON), (0x2D7F, 0x2D7F, NSM), (0x2DE0, 0x2DFF, NSM),
+		(0x2E00, 0x2E5D, ON), (0x2E80, 0x2E99, ON), (0x2E9B, 0x2EF3, ON), (0x2F00, 0x2FD5, ON),
+		(0x2FF0, 0x2FFB, ON), (0x3000, 0x3000, WS), (0x3001, 0x3004, ON), (0x3008, 0x3020, ON),
+		(0x302A, 0x302D, NSM), (0x3030, 0x3030, ON), (0x3036, 0x3037, ON), (0x303D, 0x303F, ON),
+		(0x3099, 0x309A, NSM), (0x309B, 0x309C, ON), (0x30A0, 0x30A0, ON), (0x30FB, 0x30FB, ON),
+		(0x31C0, 0x31E3, ON), (0x321D, 0x321E, ON), (0x3250, 0x325F, ON), (0x327C, 0x327E, ON),
+		(0x32B1, 0x32BF, ON), (0x32CC, 0x32CF, ON), (0x3377, 0x337A, ON), (0x33DE, 0x33DF, ON),
+		(0x33FF, 0x33FF, ON), (0x4DC0, 0x4DFF, ON), (0xA490, 0xA4C6, ON), (0xA60D, 0xA60F, ON),
+		(0xA66F, 0xA672, NSM), (0xA673, 0xA673, ON), (0xA674, 0xA67D, NSM), (0xA67E, 0xA67F, ON),
+		(0xA69E, 0xA69F, NSM), (0xA6F0, 0xA6F1, NSM), (0xA700, 0xA721, ON), (0xA788, 0xA788, ON),
+		(0xA802, 0xA802, NSM), (0xA806, 0xA806, NSM), (0xA80B, 0xA80B, NSM), (0xA825, 0xA826, NSM),
+		(0xA828, 0xA82B, ON), (0xA82C, 0xA82C, NSM), (0xA838, 0xA839, ET), (0xA874, 0xA877, ON),
+		(0xA8C4, 0xA8C5, NSM), (0xA8E0, 0xA8F1, NSM), (0xA8FF, 0xA8FF, NSM), (0xA926, 0xA92D, NSM),
+		(0xA947, 0xA951, NSM), (0xA980, 0xA982, NSM), (0xA9B3, 0xA9B3, NSM), (0xA9B6, 0xA9B9, NSM),
+		(0xA9BC, 0xA9BD, NSM), (0xA9E5, 0xA9E5, NSM), (0xAA29, 0xAA2E, NSM), (0xAA31, 0xAA32, NSM),
+		(0xAA35, 0xAA36, NSM), (0xAA43, 0xAA43, NSM), (0xAA4C, 0xAA4C, NSM), (0xAA7C, 0xAA7C, NSM),
+		(0xAAB0, 0xAAB0, NSM), (0xAAB2, 0xAAB4, NSM), (0xAAB7, 0xAAB8, NSM), (0xAABE, 0xAABF, NSM),
+		(0xAAC1, 0xAAC1, NSM), (0xAAEC, 0xAAED, NSM), (0xAAF6, 0xAAF6, NSM), (0xAB6A, 0xAB6B, ON),
+		(0xABE5, 0xABE5, NSM), (0xABE8, 0xABE8, NSM), (0xABED, 0xABED, NSM), (0xFB1D, 0xFB1D, R),
+		(0xFB1E, 0xFB1E, NSM), (0xFB1F, 0xFB28, R), (0xFB29, 0xFB29, ES), (0xFB2A, 0xFB4F, R),
+		(0xFB50, 0xFD3D, AL), (0xFD3E, 0xFD4F, ON), (0xFD50, 0xFDCE, AL), (0xFDCF, 0xFDCF, ON),
+		(0xFDF0, 0xFDFC, AL), (0xFDFD, 0xFDFF, ON), (0xFE00, 0xFE0F, NSM), (0xFE10, 0xFE19, ON),
+		(0xFE20, 0xFE2F, NSM), (0xFE30, 0xFE4F, ON), (0xFE50, 0xFE50, CS), (0xFE51, 0xFE51, ON),
+		(0xFE52, 0xFE52, CS), (0xFE54, 0xFE54, ON), (0xFE55, 0xFE55, CS), (0xFE56, 0xFE5E, ON),
+		(0xFE5F, 0xFE5F, ET), (0xFE60, 0xFE61, ON), (0xFE62, 0xFE63, ES), (0xFE64, 0xFE66, ON),
+		(0xFE68, 0xFE68, ON), (0xFE69, 0xFE6A, ET), (0xFE6B, 0xFE6B, ON), (0xFE70, 0xFEFE, AL),
+		(0xFEFF, 0xFEFF, BN), (0xFF01, 0xFF02, ON), (0xFF03, 0xFF05, ET), (0xFF06, 0xFF0A, ON),
+		(0xFF0B, 0xFF0B, ES), (0xFF0C, 0xFF0C, CS), (0xFF0D, 0xFF0D, ES), (0xFF0E, 0xFF0F, CS),
+		(0xFF10, 0xFF19, EN), (0xFF1A, 0xFF1A, CS), (0xFF1B, 0xFF20, ON), (0xFF3B, 0xFF40, ON),
+		(0xFF5B, 0xFF65, ON), (0xFFE0, 0xFFE1, ET), (0xFFE2, 0xFFE4, ON), (0xFFE5, 0xFFE6, ET),
+		(0xFFE8, 0xFFEE, ON), (0xFFF9, 0xFFFD, ON), (0x10101, 0x10101, ON), (0x10140, 0x1018C, ON),
+		(0x10190, 0x1019C, ON), (0x101A0, 0x101A0, ON), (0x101FD, 0x101FD, NSM), (0x102E0, 0x102E0, NSM),
+		(0x102E1, 0x102FB, EN), (0x10376, 0x1037A, NSM), (0x10800, 0x1091E, R), (0x1091F, 0x1091F, ON),
+		(0x10920, 0x10A00, R), (0x10A01, 0x10A03, NSM), (0x10A04, 0x10A04, R), (0x10A05, 0x10A06, NSM),
+		(0x10A07, 0x10A0B, R), (0x10A0C, 0x10A0F, NSM), (0x10A10, 0x10A37, R), (0x10A38, 0x10A3A, NSM),
+		(0x10A3B, 0x10A3E, R), (0x10A3F, 0x10A3F, NSM), (0x10A40, 0x10AE4, R), (0x10AE5, 0x10AE6, NSM),
+		(0x10AE7, 0x10B38, R), (0x10B39, 0x10B3F, ON), (0x10B40, 0x10CFF, R), (0x10D00, 0x10D23, AL),
+		(0x10D24, 0x10D27, NSM), (0x10D28, 0x10D2F, R), (0x10D30, 0x10D39, AN), (0x10D3A, 0x10E5F, R),
+		(0x10E60, 0x10E7E, AN), (0x10E7F, 0x10EAA, R), (0x10EAB, 0x10EAC, NSM), (0x10EAD, 0x10EFC, R),
+		(0x10EFD, 0x10EFF, NSM), (0x10F00, 0x10F2F, R), (0x10F30, 0x10F45, AL), (0x10F46, 0x10F50, NSM),
+		(0x10F51, 0x10F59, AL), (0x10F5A, 0x10F81, R), (0x10F82, 0x10F85, NSM), (0x10F86, 0x10FFF, R),
+		(0x11001, 0x11001, NSM), (0x11038, 0x11046, NSM), (0x11052, 0x11065, ON), (0x11070, 0x11070, NSM),
+		(0x11073, 0x11074, NSM), (0x1107F, 0x11081, NSM), (0x110B3, 0x110B6, NSM), (0x110B9, 0x110BA, NSM),
+		(0x110C2, 0x110C2, NSM), (0x11100, 0x11102, NSM), (0x11127, 0x1112B, NSM), (0x1112D, 0x11134, NSM),
+		(0x11173, 0x11173, NSM), (0x11180, 0x11181, NSM), (0x111B6, 0x111BE, NSM), (0x111C9, 0x111CC, NSM),
+		(0x111CF, 0x111CF, NSM), (0x1122F, 0x11231, NSM), (0x11234, 0x11234, NSM), (0x11236, 0x11237, NSM),
+		(0x1123E, 0x1123E, NSM), (0x11241, 0x11241, NSM), (0x112DF, 0x112DF, NSM), (0x112E3, 0x112EA, NSM),
+		(0x11300, 0x11301, NSM), (0x1133B, 0x1133C, NSM), (0x11340, 0x11340, NSM), (0x11366, 0x1136C, NSM),
+		(0x11370, 0x11374, NSM), (0x11438, 0x1143F, NSM), (0x11442, 0x11444, NSM), (0x11446, 0x11446, NSM),
+		(0x1145E, 0x1145E, NSM), (0x114B3, 0x114B8, NSM), (0x114BA, 0x114BA, NSM), (0x114BF, 0x114C0, NSM),
+		(0x114C2, 0x114C3, NSM), (0x115B2, 0x115B5, NSM), (0x115BC, 0x115BD, NSM), (0x115BF, 0x115C0, NSM),
+		(0x115DC, 0x115DD, NSM), (0x11633, 0x1163A, NSM), (0x1163D, 0x1163D, NSM), (0x1163F, 0x11640, NSM),
+		(0x11660, 0x1166C, ON), (0x116AB, 0x116AB, NSM), (0x116AD, 0x116AD, NSM), (0x116B0, 0x116B5, NSM),
+		(0x116B7, 0x116B7, NSM), (0x1171D, 0x1171F, NSM), (0x11722, 0x11725, NSM), (0x11727, 0x1172B, NSM),
+		(0x1182F, 0x11837, NSM), (0x11839, 0x1183A, NSM), (0x1193B, 0x1193C, NSM), (0x1193E, 0x1193E, NSM),
+		(0x11943, 0x11943, NSM), (0x119D4, 0x119D7, NSM), (0x119DA, 0x119DB, NSM), (0x119E0, 0x119E0, NSM),
+		(0x11A01, 0x11A06, NSM), (0x11A09, 0x11A0A, NSM), (0x11A33, 0x11A38, NSM), (0x11A3B, 0x11A3E, NSM),
+		(0x11A47, 0x11A47, NSM), (0x11A51, 0x11A56, NSM), (0x11A59, 0x11A5B, NSM), (0x11A8A, 0x11A96, NSM),
+		(0x11A98, 0x11A99, NSM), (0x11C30, 0x11C36, NSM), (0x11C38, 0x11C3D, NSM), (0x11C92, 0x11CA7, NSM),
+		(0x11CAA, 0x11CB0, NSM), (0x11CB2, 0x11CB3, NSM), (0x11CB5, 0x11CB6, NSM), (0x11D31, 0x11D36, NSM),
+		(0x11D3A, 0x11D3A, NSM), (0x11D3C, 0x11D3D, NSM), (0x11D3F, 0x11D45, NSM), (0x11D47, 0x11D47, NSM),
+		(0x11D90, 0x11D91, NSM), (0x11D95, 0x11D95, NSM), (0x11D97, 0x11D97, NSM), (0x11EF3, 0x11EF4, NSM),
+		(0x11F00, 0x11F01, NSM), (0x11F36, 0x11F3A, NSM), (0x11F40, 0x11F40, NSM), (0x11F42, 0x11F42, NSM),
+		(0x11FD5, 0x11FDC, ON), (0x11FDD, 0x11FE0, ET), (0x11FE1, 0x11FF1, ON), (0x13440, 0x13440, NSM),
+		(0x13447, 0x13455, NSM), (0x16AF0, 0x16AF4, NSM), (0x16B30, 0x16B36, NSM), (0x16F4F, 0x16F4F, NSM),
+		(0x16F8F, 0x16F92, NSM), (0x16FE2, 0x16FE2, ON), (0x16FE4, 0x16FE4, NSM), (0x1BC9D, 0x1BC9E, NSM),
+		(0x1BCA0, 0x1BCA3, BN), (0x1CF00, 0x1CF2D, NSM), (0x1CF30, 0x1CF46, NSM), (0x1D167, 0x1D169, NSM),
+		(0x1D173, 0x1D17A, BN), (0x1D17B, 0x1D182, NSM), (0x1D185, 0x1D18B, NSM), (0x1D1AA, 0x1D1AD, NSM),
+		(0x1D1E9, 0x1D1EA, ON), (0x1D200, 0x1D241, ON), (0x1D242, 0x1D244, NSM), (0x1D245, 0x1D245, ON),
+		(0x1D300, 0x1D356, ON), (0x1D6DB, 0x1D6DB, ON), (0x1D715, 0x1D715, ON), (0x1D74F, 0x1D74F, ON),
+		(0x1D789, 0x1D789, ON), (0x1D7C3, 0x1D7C3, ON), (0x1D7CE, 0x1D7FF, EN), (0x1DA00, 0x1DA36, NSM),
+		(0x1DA3B, 0x1DA6C, NSM), (0x1DA75, 0x1DA75, NSM), (0x1DA84, 0x1DA84, NSM), (0x1DA9B, 0x1DA9F, NSM),
+		(0x1DAA1, 0x1DAAF, NSM), (0x1E000, 0x1E006, NSM), (0x1E008, 0x1E018, NSM), (0x1E01B, 0x1E021, NSM),
+		(0x1E023, 0x1E024, NSM), (0x1E026, 0x1E02A, NSM), (0x1E08F, 0x1E08F, NSM), (0x1E130, 0x1E136, NSM),
+		(0x1E2AE, 0x1E2AE, NSM), (0x1E2EC, 0x1E2EF, NSM), (0x1E2FF, 0x1E2FF, ET), (0x1E4EC, 0x1E4EF, NSM),
+		(0x1E800, 0x1E8CF, R), (0x1E8D0, 0x1E8D6, NSM), (0x1E8D7, 0x1E943, R), (0x1E944, 0x1E94A, NSM),
+		(0x1E94B, 0x1EC70, R), (0x1EC71, 0x1ECB4, AL), (0x1ECB5, 0x1ED00, R), (0x1ED01, 0x1ED3D, AL),
+		(0x1ED3E, 0x1EDFF, R), (0x1EE00, 0x1EEEF, AL), (0x1EEF0, 0x1EEF1, ON), (0x1EEF2, 0x1EEFF, AL),
+		(0x1EF00, 0x1EFFF, R), (0x1F000, 0x1F02B, ON), (0x1F030, 0x1F093, ON), (0x1F0A0, 0x1F0AE, ON),
+		(0x1F0B1, 0x1F0BF, ON), (0x1F0C1, 0x1F0CF, ON), (0x1F0D1, 0x1F0F5, ON), (0x1F100, 0x1F10A, EN),
+		(0x1F10B, 0x1F10F, ON), (0x1F12F, 0x1F12F, ON), (0x1F16A, 0x1F16F, ON), (0x1F1AD, 0x1F1AD, ON),
+		(0x1F260, 0x1F265, ON), (0x1F300, 0x1F6D7, ON), (0x1F6DC, 0x1F6EC, ON), (0x1F6F0, 0x1F6FC, ON),
+		(0x1F700, 0x1F776, ON), (0x1F77B, 0x1F7D9, ON), (0x1F7E0, 0x1F7EB, ON), (0x1F7F0, 0x1F7F0, ON),
+		(0x1F800, 0x1F80B, ON), (0x1F810, 0x1F847, ON), (0x1F850, 0x1F859, ON), (0x1F860, 0x1F887, ON),
+		(0x1F890, 0x1F8AD, ON), (0x1F8B0, 0x1F8B1, ON), (0x1F900, 0x1FA53, ON), (0x1FA60, 0x1FA6D, ON),
+		(0x1FA70, 0x1FA7C, ON), (0x1FA80, 0x1FA88, ON), (0x1FA90, 0x1FABD, ON), (0x1FABF, 0x1FAC5, ON),
+		(0x1FACE, 0x1FADB, ON), (0x1FAE0, 0x1FAE8, ON), (0x1FAF0, 0x1FAF8, ON), (0x1FB00, 0x1FB92, ON),
+		(0x1FB94, 0x1FBCA, ON), (0x1FBF0, 0x1FBF9, EN), (0xE0001, 0xE0001, BN), (0xE0020, 0xE007F, BN),
+		(0xE0100, 0xE01EF, NSM),
+	];
+	
+	/// The paired brackets, as opening and closing bracket.
+	pub const BRACKETS: &[(char, char)] = &[
+		('\u{0028}', '\u{0029}'), ('\u{005B}', '\u{005D}'), ('\u{007B}', '\u{007D}'), ('\u{0F3A}', '\u{0F3B}'),
+		('\u{0F3C}', '\u{0F3D}'), ('\u{169B}', '\u{169C}'), ('\u{2045}', '\u{2046}'), ('\u{207D}', '\u{207E}'),
+		('\u{208D}', '\u{208E}'), ('\u{2308}', '\u{2309}'), ('\u{230A}', '\u{230B}'), ('\u{2329}', '\u{232A}'),
+		('\u{2768}', '\u{2769}'), ('\u{276A}', '\u{276B}'), ('\u{276C}', '\u{276D}'), ('\u{276E}', '\u{276F}'),
+		('\u{2770}', '\u{2771}'), ('\u{2772}', '\u{2773}'), ('\u{2774}', '\u{2775}'), ('\u{27C5}', '\u{27C6}'),
+		('\u{27E6}', '\u{27E7}'), ('\u{27E8}', '\u{27E9}'), ('\u{27EA}', '\u{27EB}'), ('\u{27EC}', '\u{27ED}'),
+		('\u{27EE}', '\u{27EF}'), ('\u{2983}', '\u{2984}'), ('\u{2985}', '\u{2986}'), ('\u{2987}', '\u{2988}'),
+		('\u{2989}', '\u{298A}'), ('\u{298B}', '\u{298C}'), ('\u{298D}', '\u{2990}'), ('\u{298F}', '\u{298E}'),
+		('\u{2991}', '\u{2992}'), ('\u{2993}', '\u{2994}'), ('\u{2995}', '\u{2996}'), ('\u{2997}', '\u{2998}'),
+		('\u{29D8}', '\u{29D9}'), ('\u{29DA}', '\u{29DB}'), ('\u{29FC}', '\u{29FD}'), ('\u{2E22}', '\u{2E23}'),
+		('\u{2E24}', '\u{2E25}'), ('\u{2E26}', '\u{2E27}'), ('\u{2E28}', '\u{2E29}'), ('\u{2E55}', '\u{2E56}'),
+		('\u{2E57}', '\u{2E58}'), ('\u{2E59}', '\u{2E5A}'), ('\u{2E5B}', '\u{2E5C}'), ('\u{3008}', '\u{3009}'),
+		('\u{300A}', '\u{300B}'), ('\u{300C}', '\u{300D}'), ('\u{300E}', '\u{300F}'), ('\u{3010}', '\u{3011}'),
+		('\u{3014}', '\u{3015}'), ('\u{3016}', '\u{3017}'), ('\u{3018}', '\u{3019}'), ('\u{301A}', '\u{301B}'),
+		('\u{FE59}', '\u{FE5A}'), ('\u{FE5B}', '\u{FE5C}'), ('\u{FE5D}', '\u{FE5E}'), ('\u{FF08}', '\u{FF09}'),
+		('\u{FF3B}', '\u{FF3D}'), ('\u{FF5B}', '\u{FF5D}'), ('\u{FF5F}', '\u{FF60}'), ('\u{FF62}', '\u{FF63}'),
+	];
+	
+	/// The characters with a mirrored glyph, sorted by the first character.
+	pub const MIRRORS: &[(char, char)] = &[
+		('\u{0028}', '\u{0029}'), ('\u{0029}', '\u{0028}'), ('\u{003C}', '\u{003E}'), ('\u{003E}', '\u{003C}'),
+		('\u{005B}', '\u{005D}'), ('\u{005D}', '\u{005B}'), ('\u{007B}', '\u{007D}'), ('\u{007D}', '\u{007B}'),
+		('\u{00AB}', '\u{00BB}'), ('\u{00BB}', '\u{00AB}'), ('\u{0F3A}', '\u{0F3B}'), ('\u{0F3B}', '\u{0F3A}'),
+		('\u{0F3C}', '\u{0F3D}'), ('\u{0F3D}', '\u{0F3C}'), ('\u{169B}', '\u{169C}'), ('\u{169C}', '\u{169B}'),
+		('\u{2039}', '\u{203A}'), ('\u{203A}', '\u{2039}'), ('\u{2045}', '\u{2046}'), ('\u{2046}', '\u{2045}'),
+		('\u{207D}', '\u{207E}'), ('\u{207E}', '\u{207D}'), ('\u{208D}', '\u{208E}'), ('\u{208E}', '\u{208D}'),
+		('\u{2208}', '\u{220B}'), ('\u{2209}', '\u{220C}'), ('\u{220A}', '\u{220D}'), ('\u{220B}', '\u{2208}'),
+		('\u{220C}', '\u{2209}'), ('\u{220D}', '\u{220A}'), ('\u{2215}', '\u{29F5}'), ('\u{221F}', '\u{2BFE}'),
+		('\u{2220}', '\u{29A3}'), ('\u{2221}', '\u{299B}'), ('\u{2222}', '\u{29A0}'), ('\u{2224}', '\u{2AEE}'),
+		('\u{223C}', '\u{223D}'), ('\u{2243}', '\u{22CD}'), ('\u{2245}', '\u{224C}'), ('\u{2252}', '\u{2253}'),
+		('\u{2254}', '\u{2255}'), ('\u{2264}', '\u{2265}'), ('\u{2266}', '\u{2267}'), ('\u{2267}', '\u{2266}'),
+		('\u{2268}', '\u{2269}'), ('\u{2269}', '\u{2268}'), ('\u{226A}', '\u{226B}'), ('\u{226B}', '\u{226A}'),
+		('\u{226E}', '\u{226F}'), ('\u{226F}', '\u{226E}'), ('\u{2270}', '\u{2271}'), ('\u{2271}', '\u{2270}'),
+		('\u{2272}', '\u{2273}'), ('\u{2273}', '\u{2272}'), ('\u{2274}', '\u{2275}'), ('\u{2275}', '\u{2274}'),
+		('\u{2276}', '\u{2277}'), ('\u{2277}', '\u{2276}'), ('\u{2278}', '\u{2279}'), ('\u{2279}', '\u{2278}'),
+		('\u{227A}', '\u{227B}'), ('\u{227B}', '\u{227A}'), ('\u{227C}', '\u{227D}'), ('\u{227D}', '\u{227C}'),
+		('\u{227E}', '\u{227F}'), ('\u{227F}', '\u{227E}'), ('\u{2280}', '\u{2281}'), ('\u{2281}', '\u{2280}'),
+		('\u{2282}', '\u{2283}'), ('\u{2283}', '\u{2282}'), ('\u{2284}', '\u{2285}'), ('\u{2285}', '\u{2284}'),
+		('\u{2286}', '\u{2287}'), ('\u{2287}', '\u{2286}'), ('\u{2288}', '\u{2289}'), ('\u{2289}', '\u{2288}'),
+		('\u{228A}', '\u{228B}'), ('\u{228B}', '\u{228A}'), ('\u{228F}', '\u{2290}'), ('\u{2290}', '\u{228F}'),
+		('\u{2291}', '\u{2292}'), ('\u{2292}', '\u{2291}'), ('\u{2298}', '\u{29B8}'), ('\u{22A2}', '\u{22A3}'),
+		('\u{22A6}', '\u{2ADE}'), ('\u{22A8}', '\u{2AE4}'), ('\u{22A9}', '\u{2AE3}'), ('\u{22AB}', '\u{2AE5}'),
+		('\u{22B0}', '\u{22B1}'), ('\u{22B1}', '\u{22B0}'), ('\u{22B2}', '\u{22B3}'), ('\u{22B3}', '\u{22B2}'),
+		('\u{22B4}', '\u{22B5}'), ('\u{22B5}', '\u{22B4}'), ('\u{22B6}', '\u{22B7}'), ('\u{22B7}', '\u{22B6}'),
+		('\u{22B8}', '\u{27DC}'), ('\u{22C9}', '\u{22CA}'), ('\u{22CA}', '\u{22C9}'), ('\u{22CB}', '\u{22CC}'),
+		('\u{22CC}', '\u{22CB}'), ('\u{22D0}', '\u{22D1}'), ('\u{22D1}', '\u{22D0}'), ('\u{22D6}', '\u{22D7}'),
+		('\u{22D7}', '\u{22D6}'), ('\u{22D8}', '\u{22D9}'), ('\u{22D9}', '\u{22D8}'), ('\u{22DA}', '\u{22DB}'),
+		('\u{22DB}', '\u{22DA}'), ('\u{22DC}', '\u{22DD}'), ('\u{22DD}', '\u{22DC}'), ('\u{22DE}', '\u{22DF}'),
+		('\u{22DF}', '\u{22DE}'), ('\u{22E0}', '\u{22E1}'), ('\u{22E1}', '\u{22E0}'), ('\u{22E2}', '\u{22E3}'),
+		('\u{22E3}', '\u{22E2}'), ('\u{22E4}', '\u{22E5}'), ('\u{22E5}', '\u{22E4}'), ('\u{22E6}', '\u{22E7}'),
+		('\u{22E7}', '\u{22E6}'), ('\u{22E8}', '\u{22E9}'), ('\u{22E9}', '\u{22E8}'), ('\u{22EA}', '\u{22EB}'),
+		('\u{22EB}', '\u{22EA}'), ('\u{22EC}', '\u{22ED}'), ('\u{22ED}', '\u{22EC}'), ('\u{22F0}', '\u{22F1}'),
+		('\u{22F1}', '\u{22F0}'), ('\u{22F2}', '\u{22FA}'), ('\u{22F3}', '\u{22FB}'), ('\u{22F4}', '\u{22FC}'),
+		('\u{22F6}', '\u{22FD}'), ('\u{22F7}', '\u{22FE}'), ('\u{22FA}', '\u{22F2}'), ('\u{22FB}', '\u{22F3}'),
+		('\u{22FC}', '\u{22F4}'), ('\u{22FD}', '\u{22F6}'), ('\u{22FE}', '\u{22F7}'), ('\u{2308}', '\u{2309}'),
+		('\u{2309}', '\u{2308}'), ('\u{230A}', '\u{230B}'), ('\u{230B}', '\u{230A}'), ('\u{2329}', '\u{232A}'),
+		('\u{232A}', '\u{2329}'), ('\u{2768}', '\u{2769}'), ('\u{2769}', '\u{2768}'), ('\u{276A}', '\u{276B}'),
+		('\u{276B}', '\u{276A}'), ('\u{276C}', '\u{276D}'), ('\u{276D}', '\u{276C}'), ('\u{276E}', '\u{276F}'),
+		('\u{276F}', '\u{276E}'), ('\u{2770}', '\u{2771}'), ('\u{2771}', '\u{2770}'), ('\u{2772}', '\u{2773}'),
+		('\u{2773}', '\u{2772}'), ('\u{2774}', '\u{2775}'), ('\u{2775}', '\u{2774}'), ('\u{27C3}', '\u{27C4}'),
+		('\u{27C4}', '\u{27C3}'), ('\u{27C5}', '\u{27C6}'), ('\u{27C6}', '\u{27C5}'), ('\u{27C8}', '\u{27C9}'),
+		('\u{27C9}', '\u{27C8}'), ('\u{27CB}', '\u{27CD}'), ('\u{27CD}', '\u{27CB}'), ('\u{27D5}', '\u{27D6}'),
+		('\u{27D6}', '\u{27D5}'), ('\u{27DD}', '\u{27DE}'), ('\u{27DE}', '\u{27DD}'), ('\u{27E2}', '\u{27E3}'),
+		('\u{27E3}', '\u{27E2}'), ('\u{27E4}', '\u{27E5}'), ('\u{27E5}', '\u{27E4}'), ('\u{27E6}', '\u{27E7}'),
+		('\u{27E7}', '\u{27E6}'), ('\u{27E8}', '\u{27E9}'), ('\u{27E9}', '\u{27E8}'), ('\u{27EA}', '\u{27EB}'),
+		('\u{27EB}', '\u{27EA}'), ('\u{27EC}', '\u{27ED}'), ('\u{27ED}', '\u{27EC}'), ('\u{27EE}', '\u{27EF}'),
+		('\u{27EF}', '\u{27EE}'), ('\u{2983}', '\u{2984}'), ('\u{2984}', '\u{2983}'), ('\u{2985}', '\u{2986}'),
+		('\u{2986}', '\u{2985}'), ('\u{2987}', '\u{2988}'), ('\u{2988}', '\u{2987}'), ('\u{2989}', '\u{298A}'),
+		('\u{298A}', '\u{2989}'), ('\u{298B}', '\u{298C}'), ('\u{298C}', '\u{298B}'), ('\u{298D}', '\u{2990}'),
+		('\u{298E}', '\u{298F}'), ('\u{2991}', '\u{2992}'), ('\u{2992}', '\u{2991}'), ('\u{2993}', '\u{2994}'),
+		('\u{2994}', '\u{2993}'), ('\u{2995}', '\u{2996}'), ('\u{2996}', '\u{2995}'), ('\u{2997}', '\u{2998}'),
+		('\u{2998}', '\u{2997}'), ('\u{29A4}', '\u{29A5}'), ('\u{29A5}', '\u{29A4}'), ('\u{29A8}', '\u{29A9}'),
+		('\u{29A9}', '\u{29A8}'), ('\u{29AA}', '\u{29AB}'), ('\u{29AB}', '\u{29AA}'), ('\u{29AC}', '\u{29AD}'),
+		('\u{29AD}', '\u{29AC}'), ('\u{29AE}', '\u{29AF}'), ('\u{29AF}', '\u{29AE}'), ('\u{29C0}', '\u{29C1}'),
+		('\u{29C1}', '\u{29C0}'), ('\u{29C4}', '\u{29C5}'), ('\u{29C5}', '\u{29C4}'), ('\u{29CF}', '\u{29D0}'),
+		('\u{29D0}', '\u{29CF}'), ('\u{29D1}', '\u{29D2}'), ('\u{29D2}', '\u{29D1}'), ('\u{29D4}', '\u{29D5}'),
+		('\u{29D5}', '\u{29D4}'), ('\u{29D8}', '\u{29D9}'), ('\u{29D9}', '\u{29D8}'), ('\u{29DA}', '\u{29DB}'),
+		('\u{29DB}', '\u{29DA}'), ('\u{29E8}', '\u{29E9}'), ('\u{29E9}', '\u{29E8}'), ('\u{29F8}', '\u{29F9}'),
+		('\u{29F9}', '\u{29F8}'), ('\u{29FC}', '\u{29FD}'), ('\u{29FD}', '\u{29FC}'), ('\u{2A2B}', '\u{2A2C}'),
+		('\u{2A2C}', '\u{2A2B}'), ('\u{2A2D}', '\u{2A2E}'), ('\u{2A2E}', '\u{2A2D}'), ('\u{2A34}', '\u{2A35}'),
+		('\u{2A35}', '\u{2A34}'), ('\u{2A3C}', '\u{2A3D}'), ('\u{2A3D}', '\u{2A3C}'), ('\u{2A64}', '\u{2A65}'),
+		('\u{2A65}', '\u{2A64}'), ('\u{2A79}', '\u{2A7A}'), ('\u{2A7A}', '\u{2A79}'), ('\u{2A7B}', '\u{2A7C}'),
+		('\u{2A7C}', '\u{2A7B}'), ('\u{2A7D}', '\u{2A7E}'), ('\u{2A7E}', '\u{2A7D}'), ('\u{2A7F}', '\u{2A80}'),
+		('\u{2A80}', '\u{2A7F}'), ('\u{2A81}', '\u{2A82}'), ('\u{2A82}', '\u{2A81}'), ('\u{2A83}', '\u{2A84}'),
+		('\u{2A84}', '\u{2A83}'), ('\u{2A85}', '\u{2A86}'), ('\u{2A86}', '\u{2A85}'), ('\u{2A87}', '\u{2A88}'),
+		('\u{2A88}', '\u{2A87}'), ('\u{2A89}', '\u{2A8A}'), ('\u{2A8A}', '\u{2A89}'), ('\u{2A8B}', '\u{2A8C}'),
+		('\u{2A8C}', '\u{2A8B}'), ('\u{2A8D}', '\u{2A8E}'), ('\u{2A8E}', '\u{2A8D}'), ('\u{2A8F}', '\u{2A90}'),
+		('\u{2A90}', '\u{2A8F}'), ('\u{2A91}', '\u{2A92}'), ('\u{2A92}', '\u{2A91}'), ('\u{2A93}', '\u{2A94}'),
+		('\u{2A94}', '\u{2A93}'), ('\u{2A95}', '\u{2A96}'), ('\u{2A96}', '\u{2A95}'), ('\u{2A97}', '\u{2A98}'),
+		('\u{2A98}', '\u{2A97}'), ('\u{2A99}', '\u{2A9A}'), ('\u{2A9A}', '\u{2A99}'), ('\u{2A9B}', '\u{2A9C}'),
+		('\u{2A9C}', '\u{2A9B}'), ('\u{2A9D}', '\u{2A9E}'), ('\u{2A9E}', '\u{2A9D}'), ('\u{2A9F}', '\u{2AA0}'),
+		('\u{2AA0}', '\u{2A9F}'), ('\u{2AA1}', '\u{2AA2}'), ('\u{2AA2}', '\u{2AA1}'), ('\u{2AA6}', '\u{2AA7}'),
+		('\u{2AA7}', '\u{2AA6}'), ('\u{2AA8}', '\u{2AA9}'), ('\u{2AA9}', '\u{2AA8}'), ('\u{2AAA}', '\u{2AAB}'),
+		('\u{2AAB}', '\u{2AAA}'), ('\u{2AAC}', '\u{2AAD}'), ('\u{2AAD}', '\u{2AAC}'), ('\u{2AAF}', '\u{2AB0}'),
+		('\u{2AB0}', '\u{2AAF}'), ('\u{2AB1}', '\u{2AB2}'), ('\u{2AB2}', '\u{2AB1}'), ('\u{2AB3}', '\u{2AB4}'),
+		('\u{2AB4}', '\u{2AB3}'), ('\u{2AB5}', '\u{2AB6}'), ('\u{2AB6}', '\u{2AB5}'), ('\u{2AB7}', '\u{2AB8}'),
+		('\u{2AB8}', '\u{2AB7}'), ('\u{2AB9}', '\u{2ABA}'), ('\u{2ABA}', '\u{2AB9}'), ('\u{2ABB}', '\u{2ABC}'),
+		('\u{2ABC}', '\u{2ABB}'), ('\u{2ABD}', '\u{2ABE}'), ('\u{2ABE}', '\u{2ABD}'), ('\u{2ABF}', '\u{2AC0}'),
+		('\u{2AC0}', '\u{2ABF}'), ('\u{2AC1}', '\u{2AC2}'), ('\u{2AC2}', '\u{2AC1}'), ('\u{2AC3}', '\u{2AC4}'),
+		('\u{2AC4}', '\u{2AC3}'), ('\u{2AC5}', '\u{2AC6}'), ('\u{2AC6}', '\u{2AC5}'), ('\u{2AC7}', '\u{2AC8}'),
+		('\u{2AC8}', '\u{2AC7}'), ('\u{2AC9}', '\u{2ACA}'), ('\u{2ACA}', '\u{2AC9}'), ('\u{2ACB}', '\u{2ACC}'),
+		('\u{2ACC}', '\u{2ACB}'), ('\u{2ACD}', '\u{2ACE}'), ('\u{2ACE}', '\u{2ACD}'), ('\u{2ACF}', '\u{2AD0}'),
+		('\u{2AD0}', '\u{2ACF}'), ('\u{2AD1}', '\u{2AD2}'), ('\u{2AD2}', '\u{2AD1}'), ('\u{2AD3}', '\u{2AD4}'),
+		('\u{2AD4}', '\u{2AD3}'), ('\u{2AD5}', '\u{2AD6}'), ('\u{2AD6}', '\u{2AD5}'), ('\u{2AEC}', '\u{2AED}'),
+		('\u{2AED}', '\u{2AEC}'), ('\u{2AF7}', '\u{2AF8}'), ('\u{2AF8}', '\u{2AF7}'), ('\u{2AF9}', '\u{2AFA}'),
+		('\u{2AFA}', '\u{2AF9}'), ('\u{2E02}', '\u{2E03}'), ('\u{2E03}', '\u{2E02}'), ('\u{2E04}', '\u{2E05}'),
+		('\u{2E05}', '\u{2E04}'), ('\u{2E09}', '\u{2E0A}'), ('\u{2E0A}', '\u{2E09}'), ('\u{2E0C}', '\u{2E0D}'),
+		('\u{2E0D}', '\u{2E0C}'), ('\u{2E1C}', '\u{2E1D}'), ('\u{2E1D}', '\u{2E1C}'), ('\u{2E20}', '\u{2E21}'),
+		('\u{2E21}', '\u{2E20}'), ('\u{2E22}', '\u{2E23}'), ('\u{2E23}', '\u{2E22}'), ('\u{2E24}', '\u{2E25}'),
+		('\u{2E25}', '\u{2E24}'), ('\u{2E26}', '\u{2E27}'), ('\u{2E27}', '\u{2E26}'), ('\u{2E28}', '\u{2E29}'),
+		('\u{2E29}', '\u{2E28}'), ('\u{2E55}', '\u{2E56}'), ('\u{2E56}', '\u{2E55}'), ('\u{2E57}', '\u{2E58}'),
+		('\u{2E58}', '\u{2E57}'), ('\u{2E59}', '\u{2E5A}'), ('\u{2E5A}', '\u{2E59}'), ('\u{2E5B}', '\u{2E5C}'),
+		('\u{2E5C}', '\u{2E5B}'), ('\u{3008}', '\u{3009}'), ('\u{3009}', '\u{3008}'), ('\u{300A}', '\u{300B}'),
+		('\u{300B}', '\u{300A}'), ('\u{300C}', '\u{300D}'), ('\u{300D}', '\u{300C}'), ('\u{300E}', '\u{300F}'),
+		('\u{300F}', '\u{300E}'), ('\u{3010}', '\u{3011}'), ('\u{3011}', '\u{3010}'), ('\u{3014}', '\u{3015}'),
+		('\u{3015}', '\u{3014}'), ('\u{3016}', '\u{3017}'), ('\u{3017}', '\u{3016}'), ('\u{3018}', '\u{3019}'),
+		('\u{3019}', '\u{3018}'), ('\u{301A}', '\u{301B}'), ('\u{301B}', '\u{301A}'), ('\u{FE59}', '\u{FE5A}'),
+		('\u{FE5A}', '\u{FE59}'), ('\u{FE5B}', '\u{FE5C}'), ('\u{FE5C}', '\u{FE5B}'), ('\u{FE5D}', '\u{FE5E}'),
+		('\u{FE5E}', '\u{FE5D}'), ('\u{FE64}', '\u{FE65}'), ('\u{FE65}', '\u{FE64}'), ('\u{FF08}', '\u{FF09}'),
+		('\u{FF09}', '\u{FF08}'), ('\u{FF1C}', '\u{FF1E}'), ('\u{FF1E}', '\u{FF1C}'), ('\u{FF3B}', '\u{FF3D}'),
+		('\u{FF3D}', '\u{FF3B}'), ('\u{FF5B}', '\u{FF5D}'), ('\u{FF5D}', '\u{FF5B}'), ('\u{FF5F}', '\u{FF60}'),
+		('\u{FF60}', '\u{FF5F}'), ('\u{FF62}', '\u{FF63}'), ('\u{FF63}', '\u{FF62}'),
+	];
+}
+
+pub mod line_break {
+	use crate::text::line_break::LineBreak::{self, *};
+	
+	/// The line break classes of all code points that are not `AL`, after the resolution of `AI`,
+	/// `SA`, `SG`, `XX` and `CJ` as described by the rule LB1. Hangul syllables are omitted, they
+	/// are `H2` or `H3` depending on their position in the block.
+	pub const CLASSES: &[(u32, u32, LineBreak)] = &[
+		(0x0000, 0x0008, CM), (0x0009, 0x0009, BA), (0x000A, 0x000A, LF), (0x000B, 0x000C, BK),
+		(0x000D, 0x000D, CR), (0x000E, 0x001F, CM), (0x0020, 0x0020, SP), (0x0021, 0x0021, EX),
+		(0x0022, 0x0022, QU), (0x0024, 0x0024, PR), (0x0025, 0x0025, PO), (0x0027, 0x0027, QU),
+		(0x0028, 0x0028, OP), (0x0029, 0x0029, CP), (0x002B, 0x002B, PR), (0x002C, 0x002C, IS),
+		(0x002D, 0x002D, HY), (0x002E, 0x002E, IS), (0x002F, 0x002F, SY), (0x0030, 0x0039, NU),
+		(0x003A, 0x003B, IS), (0x003F, 0x003F, EX), (0x005B, 0x005B, OP), (0x005C, 0x005C, PR),
+		(0x005D, 0x005D, CP), (0x007B, 0x007B, OP), (0x007C, 0x007C, BA), (0x007D, 0x007D, CL),
+		(0x007F, 0x0084, CM), (0x0085, 0x0085, NL), (0x0086, 0x009F, CM), (0x00A0, 0x00A0, GL),
+		(0x00A1, 0x00A1, OP), (0x00A2, 0x00A2, PO), (0x00A3, 0x00A5, PR), (0x00AB, 0x00AB, QU),
+		(0x00AD, 0x00AD, BA), (0x00B0, 0x00B0, PO), (0x00B1, 0x00B1, PR), (0x00B4, 0x00B4, BB),
+		(0x00BB, 0x00BB, QU), (0x00BF, 0x00BF, OP), (0x02C8, 0x02C8, BB), (0x02CC, 0x02CC, BB),
+		(0x02DF, 0x02DF, BB), (0x0300, 0x034E, CM), (0x034F, 0x034F, GL), (0x0350, 0x035B, CM),
+		(0x035C, 0x0362, GL), (0x0363, 0x036F, CM), (0x037E, 0x037E, IS), (0x0483, 0x0489, CM),
+		(0x0589, 0x0589, IS), (0x058A, 0x058A, BA), (0x058F, 0x058F, PR), (0x0591, 0x05BD, CM),
+		(0x05BE, 0x05BE, BA), (0x05BF, 0x05BF, CM), (0x05C1, 0x05C2, CM), (0x05C4, 0x05C5, CM),
+		(0x05C6, 0x05C6, EX), (0x05C7, 0x05C7, CM), (0x05D0, 0x05EA, HL), (0x05EF, 0x05F2, HL),
+		(0x0609, 0x060B, PO), (0x060C, 0x060D, IS), (0x0610, 0x061A, CM), (0x061B, 0x061B, EX),
+		(0x061C, 0x061C, CM), (0x061D, 0x061F, EX), (0x064B, 0x065F, CM), (0x0660, 0x0669, NU),
+		(0x066A, 0x066A, PO), (0x066B, 0x066C, NU), (0x0670, 0x0670, CM), (0x06D4, 0x06D4, EX),
+		(0x06D6, 0x06DC, CM), (0x06DF, 0x06E4, CM), (0x06E7, 0x06E8, CM), (0x06EA, 0x06ED, CM),
+		(0x06F0, 0x06F9, NU), (0x0711, 0x0711, CM), (0x0730, 0x074A, CM), (0x07A6, 0x07B0, CM),
+		(0x07C0, 0x07C9, NU), (0x07EB, 0x07F3, CM), (0x07F8, 0x07F8, IS), (0x07F9, 0x07F9, EX),
+		(0x07FD, 0x07FD, CM), (0x07FE, 0x07FF, PR), (0x0816, 0x0819, CM), (0x081B, 0x0823, CM),
+		(0x0825, 0x0827, CM), (0x0829, 0x082D, CM), (0x0859, 0x085B, CM), (0x0898, 0x089F, CM),
+		(0x08CA, 0x08E1, CM), (0x08E3, 0x0903, CM), (0x093A, 0x093C, CM), (0x093E, 0x094F, CM),
+		(0x0951, 0x0957, CM), (0x0962, 0x0963, CM), (0x0964, 0x0965, BA), (0x0966, 0x096F, NU),
+		(0x0981, 0x0983, CM), (0x09BC, 0x09BC, CM), (0x09BE, 0x09C4, CM), (0x09C7, 0x09C8, CM),
+		(0x09CB, 0x09CD, CM), (0x09D7, 0x09D7, CM), (0x09E2, 0x09E3, CM), (0x09E6, 0x09EF, NU),
+		(0x09F2, 0x09F3, PO), (0x09F9, 0x09F9, PO), (0x09FB, 0x09FB, PR), (0x09FE, 0x09FE, CM),
+		(0x0A01, 0x0A03, CM), (0x0A3C, 0x0A3C, CM), (0x0A3E, 0x0A42, CM), (0x0A47, 0x0A48, CM),
+		(0x0A4B, 0x0A4D, CM), (0x0A51, 0x0A51, CM), (0x0A66, 0x0A6F, NU), (0x0A70, 0x0A71, CM),
+		(0x0A75, 0x0A75, CM), (0x0A81, 0x0A83, CM), (0x0ABC, 0x0ABC, CM), (0x0ABE, 0x0AC5, CM),
+		(0x0AC7, 0x0AC9, CM), (0x0ACB, 0x0ACD, CM), (0x0AE2, 0x0AE3, CM), (0x0AE6, 0x0AEF, NU),
+		(0x0AF1, 0x0AF1, PR), (0x0AFA, 0x0AFF, CM), (0x0B01, 0x0B03, CM), (0x0B3C, 0x0B3C, CM),
+		(0x0B3E, 0x0B44, CM), (0x0B47, 0x0B48, CM), (0x0B4B, 0x0B4D, CM), (0x0B55, 0x0B57, CM),
+		(0x0B62, 0x0B63, CM), (0x0B66, 0x0B6F, NU), (0x0B82, 0x0B82, CM), (0x0BBE, 0x0BC2, CM),
+		(0x0BC6, 0x0BC8, CM), (0x0BCA, 0x0BCD, CM), (0x0BD7, 0x0BD7, CM), (0x0BE6, 0x0BEF, NU),
+		(0x0BF9, 0x0BF9, PR), (0x0C00, 0x0C04, CM), (0x0C3C, 0x0C3C, CM), (0x0C3E, 0x0C44, CM),
+		(0x0C46, 0x0C48, CM), (0x0C4A, 0x0C4D, CM), (0x0C55, 0x0C56, CM), (0x0C62, 0x0C63, CM),
+		(0x0C66, 0x0C6F, NU), (0x0C77, 0x0C77, BB), (0x0C81, 0x0C83, CM), (0x0C84, 0x0C84, BB),
+		(0x0CBC, 0x0CBC, CM), (0x0CBE, 0x0CC4, CM), (0x0CC6, 0x0CC8, CM), (0x0CCA, 0x0CCD, CM),
+		(0x0CD5, 0x0CD6, CM), (0x0CE2, 0x0CE3, CM), (0x0CE6, 0x0CEF, NU), (0x0CF3, 0x0CF3, CM),
+		(0x0D00, 0x0D03, CM), (0x0D3B, 0x0D3C, CM), (0x0D3E, 0x0D44, CM), (0x0D46, 0x0D48, CM),
+		(0x0D4A, 0x0D4D, CM), (0x0D57, 0x0D57, CM), (0x0D62, 0x0D63, CM), (0x0D66, 0x0D6F, NU),
+		(0x0D79, 0x0D79, PO), (0x0D81, 0x0D83, CM), (0x0DCA, 0x0DCA, CM), (0x0DCF, 0x0DD4, CM),
+		(0x0DD6, 0x0DD6, CM), (0x0DD8, 0x0DDF, CM), (0x0DE6, 0x0DEF, NU), (0x0DF2, 0x0DF3, CM),
+		(0x0E31, 0x0E31, CM), (0x0E34, 0x0E3A, CM), (0x0E3F, 0x0E3F, PR), (0x0E47, 0x0E4E, CM),
+		(0x0E50, 0x0E59, NU), (0x0E5A, 0x0E5B, BA), (0x0EB1, 0x0EB1, CM), (0x0EB4, 0x0EBC, CM),
+		(0x0EC8, 0x0ECE, CM), (0x0ED0, 0x0ED9, NU), (0x0F01, 0x0F04, BB), (0x0F06, 0x0F07, BB),
+		(0x0F08, 0x0F08, GL), (0x0F09, 0x0F0A, BB), (0x0F0B, 0x0F0B, BA), (0x0F0C, 0x0F0C, GL),
+		(0x0F0D, 0x0F11, EX), (0x0F12, 0x0F12, GL), (0x0F14, 0x0F14, EX), (0x0F18, 0x0F19, CM),
+		(0x0F20, 0x0F29, NU), (0x0F34, 0x0F34, BA), (0x0F35, 0x0F35, CM), (0x0F37, 0x0F37, CM),
+		(0x0F39, 0x0F39, CM), (0x0F3A, 0x0F3A, OP), (0x0F3B, 0x0F3B, CL), (0x0F3C, 0x0F3C, OP),
+		(0x0F3D, 0x0F3D, CL), (0x0F3E, 0x0F3F, CM), (0x0F71, 0x0F7E, CM), (0x0F7F, 0x0F7F, BA),
+		(0x0F80, 0x0F84, CM), (0x0F85, 0x0F85, BA), (0x0F86, 0x0F87, CM), (0x0F8D, 0x0F97, CM),
+		(0x0F99, 0x0FBC, CM), (0x0FBE, 0x0FBF, BA), (0x0FC6, 0x0FC6, CM), (0x0FD0, 0x0FD1, BB),
+		(0x0FD2, 0x0FD2, BA), (0x0FD3, 0x0FD3, BB), (0x0FD9, 0x0FDA, GL), (0x102B, 0x103E, CM),
+		(0x1040, 0x1049, NU), (0x104A, 0x104B, BA), (0x1056, 0x1059, CM), (0x105E, 0x1060, CM),
+		(0x1062, 0x1064, CM), (0x1067, 0x106D, CM), (0x1071, 0x1074, CM), (0x1082, 0x108D, CM),
+		(0x108F, 0x108F, CM), (0x1090, 0x1099, NU), (0x109A, 0x109D, CM), (0x1100, 0x115F, JL),
+		(0x1160, 0x11A7, JV), (0x11A8, 0x11FF, JT), (0x135D, 0x135F, CM), (0x1361, 0x1361, BA),
+		(0x1400, 0x1400, BA), (0x1680, 0x1680, BA), (0x169B, 0x169B, OP), (0x169C, 0x169C, CL),
+		(0x16EB, 0x16ED, BA), (0x1712, 0x1715, CM), (0x1732, 0x1734, CM), (0x1735, 0x1736, BA),
+		(0x1752, 0x1753, CM), (0x1772, 0x1773, CM), (0x17B4, 0x17D3, CM), (0x17D4, 0x17D5, BA),
+		(0x17D6, 0x17D6, NS), (0x17D8, 0x17D8, BA), (0x17DA, 0x17DA, BA), (0x17DB, 0x17DB, PR),
+		(0x17DD, 0x17DD, CM), (0x17E0, 0x17E9, NU), (0x1802, 0x1803, EX), (0x1804, 0x1805, BA),
+		(0x1806, 0x1806, BB), (0x1808, 0x1809, EX), (0x180B, 0x180D, CM), (0x180E, 0x180E, GL),
+		(0x180F, 0x180F, CM), (0x1810, 0x1819, NU), (0x1885, 0x1886, CM), (0x18A9, 0x18A9, CM),
+		(0x1920, 0x192B, CM), (0x1930, 0x193B, CM), (0x1944, 0x1945, EX), (0x1946, 0x194F, NU),
+		(0x19D0, 0x19D9, NU), (0x1A17, 0x1A1B, CM), (0x1A55, 0x1A5E, CM), (0x1A60, 0x1A7C, CM),
+		(0x1A7F, 0x1A7F, CM), (0x1A80, 0x1A89, NU), (0x1A90, 0x1A99, NU), (0x1AB0, 0x1ACE, CM),
+		(0x1B00, 0x1B04, CM), (0x1B34, 0x1B44, CM), (0x1B50, 0x1B59, NU), (0x1B5A, 0x1B5B, BA),
+		(0x1B5D, 0x1B60, BA), (0x1B6B, 0x1B73, CM), (0x1B7D, 0x1B7E, BA), (0x1B80, 0x1B82, CM),
+		(0x1BA1, 0x1BAD, CM), (0x1BB0, 0x1BB9, NU), (0x1BE6, 0x1BF3, CM), (0x1C24, 0x1C37, CM),
+		(0x1C3B, 0x1C3F, BA), (0x1C40, 0x1C49, NU), (0x1C50, 0x1C59, NU), (0x1C7E, 0x1C7F, BA),
+		(0x1CD0, 0x1CD2, CM), (0x1CD4, 0x1CE8, CM), (0x1CED, 0x1CED, CM), (0x1CF4, 0x1CF4, CM),
+		(0x1CF7, 0x1CF9, CM), (0x1DC0, 0x1DCC, CM), (0x1DCD, 0x1DCD, GL), (0x1DCE, 0x1DFB, CM),
+		(0x1DFC, 0x1DFC, GL), (0x1DFD, 0x1DFF, CM), (0x1FFD, 0x1FFD, BB), (0x2000, 0x2006, BA),
+		(0x2007, 0x2007, GL), (0x2008, 0x200A, BA), (0x200B, 0x200B, ZW), (0x200C, 0x200C, CM),
+		(0x200D, 0x200D, ZWJ), (0x200E, 0x200F, CM), (0x2010, 0x2010, BA), (0x2011, 0x2011, GL),
+		(0x2012, 0x2013, BA), (0x2014, 0x2014, B2), (0x2018, 0x2019, QU), (0x201A, 0x201A, OP),
+		(0x201B, 0x201D, QU), (0x201E, 0x201E, OP), (0x201F, 0x201F, QU), (0x2024, 0x2026, IN),
+		(0x2027, 0x2027, BA), (0x2028, 0x2029, BK), (0x202A, 0x202E, CM), (0x202F, 0x202F, GL),
+		(0x2030, 0x2037, PO), (0x2039, 0x203A, QU), (0x203C, 0x203D, NS), (0x2044, 0x2044, IS),
+		(0x2045, 0x2045, OP), (0x2046, 0x2046, CL), (0x2047, 0x2049, NS), (0x2056, 0x2056, BA),
+		(0x2057, 0x2057, PO), (0x2058, 0x205B, BA), (0x205D, 0x205F, BA), (0x2060, 0x2060, WJ),
+		(0x2066, 0x206F, CM), (0x207D, 0x207D, OP), (0x207E, 0x207E, CL), (0x208D, 0x208D, OP),
+		(0x208E, 0x208E, CL), (0x20A0, 0x20A6, PR), (0x20A7, 0x20A7, PO), (0x20A8, 0x20B5, PR),
+		(0x20B6, 0x20B6, PO), (0x20B7, 0x20BA, PR), (0x20BB, 0x20BB, PO), (0x20BC, 0x20BD, PR),
+		(0x20BE, 0x20BE, PO), (0x20BF, 0x20BF, PR), (0x20C0, 0x20C0, PO), (0x20C1, 0x20CF, PR),
+		(0x20D0, 0x20F0, CM), (0x2103, 0x2103, PO), (0x2109, 0x2109, PO), (0x2116, 0x2116, PR),
+		(0x2212, 0x2213, PR), (0x22EF, 0x22EF, IN), (0x2308, 0x2308, OP), (0x2309, 0x2309, CL),
+		(0x230A, 0x230A, OP), (0x230B, 0x230B, CL), (0x231A, 0x231B, ID), (0x2329, 0x2329, OP),
+		(0x232A, 0x232A, CL), (0x23F0, 0x23F3, ID), (0x2600, 0x2603, ID), (0x2614, 0x2615, ID),
+		(0x2618, 0x2618, ID), (0x261A, 0x261C, ID), (0x261D, 0x261D, EB), (0x261E, 0x261F, ID),
+		(0x2639, 0x263B, ID), (0x2668, 0x2668, ID), (0x267F, 0x267F, ID), (0x26BD, 0x26C8, ID),
+		(0x26CD, 0x26CD, ID), (0x26CF, 0x26D1, ID), (0x26D3, 0x26D4, ID), (0x26D8, 0x26D9, ID),
+		(0x26DC, 0x26DC, ID), (0x26DF, 0x26E1, ID), (0x26EA, 0x26EA, ID), (0x26F1, 0x26F5, ID),
+		(0x26F7, 0x26F8, ID), (0x26F9, 0x26F9, EB), (0x26FA, 0x26FA, ID), (0x26FD, 0x2704, ID),
+		(0x2708, 0x2709, ID), (0x270A, 0x270D, EB), (0x275B, 0x2760, QU), (0x2762, 0x2763, EX),
+		(0x2764, 0x2764, ID), (0x2768, 0x2768, OP), (0x2769, 0x2769, CL), (0x276A, 0x276A, OP),
+		(0x276B, 0x276B, CL), (0x276C, 0x276C, OP), (0x276D, 0x276D, CL), (0x276E, 0x276E, OP),
+		(0x276F, 0x276F, CL), (0x2770, 0x2770, OP), (0x2771, 0x2771, CL), (0x2772, 0x2772, OP),
+		(0x2773, 0x2773, CL), (0x2774, 0x2774, OP), (0x2775, 0x2775, CL), (0x27C5, 0x27C5, OP),
+		(0x27C6, 0x27C6, CL), (0x27E6, 0x27E6, OP), (0x27E7, 0x27E7, CL), (0x27E8, 0x27E8, OP),
+		(0x27E9, 0x27E9, CL), (0x27EA, 0x27EA, OP), (0x27EB, 0x27EB, CL), (0x27EC, 0x27EC, OP),
+		(0x27ED, 0x27ED, CL), (0x27EE, 0x27EE, OP), (0x27EF, 0x27EF, CL), (0x2983, 0x2983, OP),
+		(0x2984, 0x2984, CL), (0x2985, 0x2985, OP), (0x2986, 0x2986, CL), (0x2987, 0x2987, OP),
+		(0x2988, 0x2988, CL), (0x2989, 0x2989, OP), (0x298A, 0x298A, CL), (0x298B, 0x298B, OP),
+		(0x298C, 0x298C, CL), (0x298D, 0x298D, OP), (0x298E, 0x298E, CL), (0x298F, 0x298F, OP),
+		(0x2990, 0x2990, CL), (0x2991, 0x2991, OP), (0x2992, 0x2992, CL), (0x2993, 0x2993, OP),
+		(0x2994, 0x2994, CL), (0x2995, 0x2995, OP), (0x2996, 0x2996, CL), (0x2997, 0x2997, OP),
+		(0x2998, 0x2998, CL), (0x29D8, 0x29D8, OP), (0x29D9, 0x29D9, CL), (0x29DA, 0x29DA, OP),
+		(0x29DB, 0x29DB, CL), (0x29FC, 0x29FC, OP), (0x29FD, 0x29FD, CL), (0x2CEF, 0x2CF1, CM),
+		(0x2CF9, 0x2CF9, EX), (0x2CFA, 0x2CFC, BA), (0x2CFE, 0x2CFE, EX), (0x2CFF, 0x2CFF, BA),
+		(0x2D70, 0x2D70, BA), (0x2D7F, 0x2D7F, CM), (0x2DE0, 0x2DFF, CM), (0x2E00, 0x2E0D, QU),
+		(0x2E0E, 0x2E15, BA), (0x2E17, 0x2E17, BA), (0x2E18, 0x2E18, OP), (0x2E19, 0x2E19, BA),
+		(0x2E1C, 0x2E1D, QU), (0x2E20, 0x2E21, QU), (0x2E22, 0x2E22, OP), (0x2E23, 0x2E23, CL),
+		(0x2E24, 0x2E24, OP), (0x2E25, 0x2E25, CL), (0x2E26, 0x2E26, OP), (0x2E27, 0x2E27, CL),
+		(0x2E28, 0x2E28, OP), (0x2E29, 0x2E29, CL), (0x2E2A, 0x2E2D, BA), (0x2E2E, 0x2E2E, EX),
+		(0x2E30, 0x2E31, BA), (0x2E33, 0x2E34, BA), (0x2E3A, 0x2E3B, B2), (0x2E3C, 0x2E3E, BA),
+		(0x2E40, 0x2E41, BA), (0x2E42, 0x2E42, OP), (0x2E43, 0x2E4A, BA), (0x2E4C, 0x2E4C, BA),
+		(0x2E4E, 0x2E4F, BA), (0x2E53, 0x2E54, EX), (0x2E55, 0x2E55, OP), (0x2E56, 0x2E56, CL),
+		(0x2E57, 0x2E57, OP), (0x2E58, 0x2E58, CL), (0x2E59, 0x2E59, OP), (0x2E5A, 0x2E5A, CL),
+		(0x2E5B, 0x2E5B, OP), (0x2E5C, 0x2E5C, CL), (0x2E5D, 0x2E5D, BA), (0x2E80, 0x2E99, ID),
+		(0x2E9B, 0x2EF3, ID), (0x2F00, 0x2FD5, ID), (0x2FF0, 0x2FFB, ID), (0x3000, 0x3000, BA),
+		(0x3001, 0x3002, CL), (0x3003, 0x3004, ID), (0x3005, 0x3005, NS), (0x3006, 0x3007, ID),
+		(0x3008, 0x3008, OP), (0x3009, 0x3009, CL), (0x300A, 0x300A, OP), (0x300B, 0x300B, CL),
+		(0x300C, 0x300C, OP), (0x300D, 0x300D, CL), (0x300E, 0x300E, OP), (0x300F, 0x300F, CL),
+		(0x3010, 0x3010, OP), (0x3011, 0x3011, CL), (0x3012, 0x3013, ID), (0x3014, 0x3014, OP),
+		(0x3015, 0x3015, CL), (0x3016, 0x3016, OP), (0x3017, 0x3017, CL), (0x3018, 0x3018, OP),
+		(0x3019, 0x3019, CL), (0x301A, 0x301A, OP), (0x301B, 0x301B, CL), (0x301C, 0x301C, NS),
+		(0x301D, 0x301D, OP), (0x301E, 0x301F, CL), (0x3020, 0x3029, ID), (0x302A, 0x302F, CM),
+		(0x3030, 0x3034, ID), (0x3035, 0x3035, CM), (0x3036, 0x303A, ID), (0x303B, 0x303C, NS),
+		(0x303D, 0x303F, ID), (0x3041, 0x3041, NS), (0x3042, 0x3042, ID), (0x3043, 0x3043, NS),
+		(0x3044, 0x3044, ID), (0x3045, 0x3045, NS), (0x3046, 0x3046, ID), (0x3047, 0x3047, NS),
+		(0x3048, 0x3048, ID), (0x3049, 0x3049, NS), (0x304A, 0x3062, ID), (0x3063, 0x3063, NS),
+		(0x3064, 0x3082, ID), (0x3083, 0x3083, NS), (0x3084, 0x3084, ID), (0x3085, 0x3085, NS),
+		(0x3086, 0x3086, ID), (0x3087, 0x3087, NS), (0x3088, 0x308D, ID), (0x308E, 0x308E, NS),
+		(0x308F, 0x3094, ID), (0x3095, 0x3096, NS), (0x3099, 0x309A, CM), (0x309B, 0x309E, NS),
+		(0x309F, 0x309F, ID), (0x30A0, 0x30A1, NS), (0x30A2, 0x30A2, ID), (0x30A3, 0x30A3, NS),
+		(0x30A4, 0x30A4, ID), (0x30A5, 0x30A5, NS), (0x30A6, 0x30A6, ID), (0x30A7, 0x30A7, NS),
+		(0x30A8, 0x30A8, ID), (0x30A9, 0x30A9, NS), (0x30AA, 0x30C2, ID), (0x30C3, 0x30C3, NS),
+		(0x30C4, 0x30E2, ID), (0x30E3, 0x30E3, NS), (0x30E4, 0x30E4, ID), (0x30E5, 0x30E5, NS),
+		(0x30E6, 0x30E6, ID), (0x30E7, 0x30E7, NS), (0x30E8, 0x30ED, ID), (0x30EE, 0x30EE, NS),
+		(0x30EF, 0x30F4, ID), (0x30F5, 0x30F6, NS), (0x30F7, 0x30FA, ID), (0x30FB, 0x30FE, NS),
+		(0x30FF, 0x30FF, ID), (0x3105, 0x312F, ID), (0x3131, 0x318E, ID), (0x3190, 0x31E3, ID),
+		(0x31F0, 0x31FF, NS), (0x3200, 0x321E, ID), (0x3220, 0x3247, ID), (0x3250, 0x4DBF, ID),
+		(0x4E00, 0xA014, ID), (0xA015, 0xA015, NS), (0xA016, 0xA48C, ID), (0xA490, 0xA4C6, ID),
+		(0xA4FE, 0xA4FF, BA), (0xA60D, 0xA60D, BA), (0xA60E, 0xA60E, EX), (0xA60F, 0xA60F, BA),
+		(0xA620, 0xA629, NU), (0xA66F, 0xA672, CM), (0xA674, 0xA67D, CM), (0xA69E, 0xA69F, CM),
+		(0xA6F0, 0xA6F1, CM), (0xA6F3, 0xA6F7, BA), (0xA802, 0xA802, CM), (0xA806, 0xA806, CM),
+		(0xA80B, 0xA80B, CM), (0xA823, 0xA827, CM), (0xA82C, 0xA82C, CM), (0xA838, 0xA838, PO),
+		(0xA874, 0xA875, BB), (0xA876, 0xA877, EX), (0xA880, 0xA881, CM), (0xA8B4, 0xA8C5, CM),
+		(0xA8CE, 0xA8CF, BA), (0xA8D0, 0xA8D9, NU), (0xA8E0, 0xA8F1, CM), (0xA8FC, 0xA8FC, BB),
+		(0xA8FF, 0xA8FF, CM), (0xA900, 0xA909, NU), (0xA926, 0xA92D, CM), (0xA92E, 0xA92F, BA),
+		(0xA947, 0xA953, CM), (0xA960, 0xA97C, JL), (0xA980, 0xA983, CM), (0xA9B3, 0xA9C0, CM),
+		(0xA9C7, 0xA9C9, BA), (0xA9D0, 0xA9D9, NU), (0xA9E5, 0xA9E5, CM), (0xA9F0, 0xA9F9, NU),
+		(0xAA29, 0xAA36, CM), (0xAA43, 0xAA43, CM), (0xAA4C, 0xAA4D, CM), (0xAA50, 0xAA59, NU),
+		(0xAA5D, 0xAA5F, BA), (0xAA7B, 0xAA7D, CM), (0xAAB0, 0xAAB0, CM), (0xAAB2, 0xAAB4, CM),
+		(0xAAB7, 0xAAB8, CM), (0xAABE, 0xAABF, CM), (0xAAC1, 0xAAC1, CM), (0xAAEB, 0xAAEF, CM),
+		(0xAAF0, 0xAAF1, BA), (0xAAF5, 0xAAF6, CM), (0xABE3, 0xABEA, CM), (0xABEB, 0xABEB, BA),
+		(0xABEC, 0xABED, CM), (0xABF0, 0xABF9, NU), (0xD7B0, 0xD7C6, JV), (0xD7CB, 0xD7FB, JT),
+		(0xF900, 0xFAFF, ID), (0xFB1D, 0xFB1D, HL), (0xFB1E, 0xFB1E, CM), (0xFB1F, 0xFB28, HL),
+		(0xFB2A, 0xFB36, HL), (0xFB38, 0xFB3C, HL), (0xFB3E, 0xFB3E, HL), (0xFB40, 0xFB41, HL),
+		(0xFB43, 0xFB44, HL), (0xFB46, 0xFB4F, HL), (0xFD3E, 0xFD3E, CL), (0xFD3F, 0xFD3F, OP),
+		(0xFDFC, 0xFDFC, PO), (0xFE00, 0xFE0F, CM), (0xFE10, 0xFE10, IS), (0xFE11, 0xFE12, CL),
+		(0xFE13, 0xFE14, IS), (0xFE15, 0xFE16, EX), (0xFE17, 0xFE17, OP), (0xFE18, 0xFE18, CL),
+		(0xFE19, 0xFE19, IN), (0xFE20, 0xFE2F, CM), (0xFE30, 0xFE34, ID), (0xFE35, 0xFE35, OP),
+		(0xFE36, 0xFE36, CL), (0xFE37, 0xFE37, OP), (0xFE38, 0xFE38, CL), (0xFE39, 0xFE39, OP),
+		(0xFE3A, 0xFE3A, CL), (0xFE3B, 0xFE3B, OP), (0xFE3C, 0xFE3C, CL), (0xFE3D, 0xFE3D, OP),
+		(0xFE3E, 0xFE3E, CL), (0xFE3F, 0xFE3F, OP), (0xFE40, 0xFE40, CL), (0xFE41, 0xFE41, OP),
+		(0xFE42, 0xFE42, CL), (0xFE43, 0xFE43, OP), (0xFE44, 0xFE44, CL), (0xFE45, 0xFE46, ID),
+		(0xFE47, 0xFE47, OP), (0xFE48, 0xFE48, CL), (0xFE49, 0xFE4F, ID), (0xFE50, 0xFE50, CL),
+		(0xFE51, 0xFE51, ID), (0xFE52, 0xFE52, CL), (0xFE54, 0xFE55, NS), (0xFE56, 0xFE57, EX),
+		(0xFE58, 0xFE58, ID), (0xFE59, 0xFE59, OP), (0xFE5A, 0xFE5A, CL), (0xFE5B, 0xFE5B, OP),
+		(0xFE5C, 0xFE5C, CL), (0xFE5D, 0xFE5D, OP), (0xFE5E, 0xFE5E, CL), (0xFE5F, 0xFE66, ID),
+		(0xFE68, 0xFE68, ID), (0xFE69, 0xFE69, PR), (0xFE6A, 0xFE6A, PO), (0xFE6B, 0xFE6B, ID),
+		(0xFEFF, 0xFEFF, WJ), (0xFF01, 0xFF01, EX), (0xFF02, 0xFF03, ID), (0xFF04, 0xFF04, PR),
+		(0xFF05, 0xFF05, PO), (0xFF06, 0xFF07, ID), (0xFF08, 0xFF08, OP), (0xFF09, 0xFF09, CL),
+		(0xFF0A, 0xFF0B, ID), (0xFF0C, 0xFF0C, CL), (0xFF0D, 0xFF0D, ID), (0xFF0E, 0xFF0E, CL),
+		(0xFF0F, 0xFF19, ID), (0xFF1A, 0xFF1B, NS), (0xFF1C, 0xFF1E, ID), (0xFF1F, 0xFF1F, EX),
+		(0xFF20, 0xFF3A, ID), (0xFF3B, 0xFF3B, OP), (0xFF3C, 0xFF3C, ID), (0xFF3D, 0xFF3D, CL),
+		(0xFF3E, 0xFF5A, ID), (0xFF5B, 0xFF5B, OP), (0xFF5C, 0xFF5C, ID), (0xFF5D, 0xFF5D, CL),
+		(0xFF5E, 0xFF5E, ID), (0xFF5F, 0xFF5F, OP), (0xFF60, 0xFF61, CL), (0xFF62, 0xFF62, OP),
+		(0xFF63, 0xFF64, CL), (0xFF65, 0xFF65, NS), (0xFF66, 0xFF66, ID), (0xFF67, 0xFF70, NS),
+		(0xFF71, 0xFF9D, ID), (0xFF9E, 0xFF9F, NS), (0xFFA0, 0xFFBE, ID), (0xFFC2, 0xFFC7, ID),
+		(0xFFCA, 0xFFCF, ID), (0xFFD2, 0xFFD7, ID), (0xFFDA, 0xFFDC, ID), (0xFFE0, 0xFFE0, PO),
+		(0xFFE1, 0xFFE1, PR), (0xFFE2, 0xFFE4, ID), (0xFFE5, 0xFFE6, PR), (0xFFF9, 0xFFFB, CM),
+		(0xFFFC, 0xFFFC, CB), (0x10100, 0x10102, BA), (0x101FD, 0x101FD, CM), (0x102E0, 0x102E0, CM),
+		(0x10376, 0x1037A, CM), (0x1039F, 0x1039F, BA), (0x103D0, 0x103D0, BA), (0x104A0, 0x104A9, NU),
+		(0x10857, 0x10857, BA), (0x1091F, 0x1091F, BA), (0x10A01, 0x10A03, CM), (0x10A05, 0x10A06, CM),
+		(0x10A0C, 0x10A0F, CM), (0x10A38, 0x10A3A, CM), (0x10A3F, 0x10A3F, CM), (0x10A50, 0x10A57, BA),
+		(0x10AE5, 0x10AE6, CM), (0x10AF0, 0x10AF5, BA), (0x10AF6, 0x10AF6, IN), (0x10B39, 0x10B3F, BA),
+		(0x10D24, 0x10D27, CM), (0x10D30, 0x10D39, NU), (0x10EAB, 0x10EAC, CM), (0x10EAD, 0x10EAD, BA),
+		(0x10EFD, 0x10EFF, CM), (0x10F46, 0x10F50, CM), (0x10F82, 0x10F85, CM), (0x11000, 0x11002, CM),
+		(0x11038, 0x11046, CM), (0x11047, 0x11048, BA), (0x11066, 0x1106F, NU), (0x11070, 0x11070, CM),
+		(0x11073, 0x11074, CM), (0x1107F, 0x11082, CM), (0x110B0, 0x110BA, CM), (0x110BE, 0x110C1, BA),
+		(0x110C2, 0x110C2, CM), (0x110F0, 0x110F9, NU), (0x11100, 0x11102, CM), (0x11127, 0x11134, CM),
+		(0x11136, 0x1113F, NU), (0x11140, 0x11143, BA), (0x11145, 0x11146, CM), (0x11173, 0x11173, CM),
+		(0x11175, 0x11175, BB), (0x11180, 0x11182, CM), (0x111B3, 0x111C0, CM), (0x111C5, 0x111C6, BA),
+		(0x111C8, 0x111C8, BA), (0x111C9, 0x111CC, CM), (0x111CE, 0x111CF, CM), (0x111D0, 0x111D9, NU),
+		(0x111DB, 0x111DB, BB), (0x111DD, 0x111DF, BA), (0x1122C, 0x11237, CM), (0x11238, 0x11239, BA),
+		(0x1123B, 0x1123C, BA), (0x1123E, 0x1123E, CM), (0x11241, 0x11241, CM), (0x112A9, 0x112A9, BA),
+		(0x112DF, 0x112EA, CM), (0x112F0, 0x112F9, NU), (0x11300, 0x11303, CM), (0x1133B, 0x1133C, CM),
+		(0x1133E, 0x11344, CM), (0x11347, 0x11348, CM), (0x1134B, 0x1134D, CM), (0x11357, 0x11357, CM),
+		(0x11362, 0x11363, CM), (0x11366, 0x1136C, CM), (0x11370, 0x11374, CM), (0x11435, 0x11446, CM),
+		(0x1144B, 0x1144E, BA), (0x11450, 0x11459, NU), (0x1145A, 0x1145B, BA), (0x1145E, 0x1145E, CM),
+		(0x114B0, 0x114C3, CM), (0x114D0, 0x114D9, NU), (0x115AF, 0x115B5, CM), (0x115B8, 0x115C0, CM),
+		(0x115C1, 0x115C1, BB), (0x115C2, 0x115C3, BA), (0x115C4, 0x115C5, EX), (0x115C9, 0x115D7, BA),
+		(0x115DC, 0x115DD, CM), (0x11630, 0x11640, CM), (0x11641, 0x11642, BA), (0x11650, 0x11659, NU),
+		(0x11660, 0x1166C, BB), (0x116AB, 0x116B7, CM), (0x116C0, 0x116C9, NU), (0x1171D, 0x1172B, CM),
+		(0x11730, 0x11739, NU), (0x1173C, 0x1173E, BA), (0x1182C, 0x1183A, CM), (0x118E0, 0x118E9, NU),
+		(0x11930, 0x11935, CM), (0x11937, 0x11938, CM), (0x1193B, 0x1193E, CM), (0x11940, 0x11940, CM),
+		(0x11942, 0x11943, CM), (0x11944, 0x11946, BA), (0x11950, 0x11959, NU), (0x119D1, 0x119D7, CM),
+		(0x119DA, 0x119E0, CM), (0x119E2, 0x119E2, BB), (0x119E4, 0x119E4, CM), (0x11A01, 0x11A0A, CM),
+		(0x11A33, 0x11A39, CM), (0x11A3B, 0x11A3E, CM), (0x11A3F, 0x11A3F, BB), (0x11A41, 0x11A44, BA),
+		(0x11A45, 0x11A45, BB), (0x11A47, 0x11A47, CM), (0x11A51, 0x11A5B, CM), (0x11A8A, 0x11A99, CM),
+		(0x11A9A, 0x11A9C, BA), (0x11A9E, 0x11AA0, BB), (0x11AA1, 0x11AA2, BA), (0x11B00, 0x11B09, BB),
+		(0x11C2F, 0x11C36, CM), (0x11C38, 0x11C3F, CM), (0x11C41, 0x11C45, BA), (0x11C50, 0x11C59, NU),
+		(0x11C70, 0x11C70, BB), (0x11C71, 0x11C71, EX), (0x11C92, 0x11CA7, CM), (0x11CA9, 0x11CB6, CM),
+		(0x11D31, 0x11D36, CM), (0x11D3A, 0x11D3A, CM), (0x11D3C, 0x11D3D, CM), (0x11D3F, 0x11D45, CM),
+		(0x11D47, 0x11D47, CM), (0x11D50, 0x11D59, NU), (0x11D8A, 0x11D8E, CM), (0x11D90, 0x11D91, CM),
+		(0x11D93, 0x11D97, CM), (0x11DA0, 0x11DA9, NU), (0x11EF3, 0x11EF6, CM), (0x11F00, 0x11F01, CM),
+		(0x11F03, 0x11F03, CM), (0x11F34, 0x11F3A, CM), (0x11F3E, 0x11F42, CM), (0x11F43, 0x11F44, BA),
+		(0x11F45, 0x11F4F, ID), (0x11F50, 0x11F59, NU), (0x11FDD, 0x11FE0, PO), (0x11FFF, 0x11FFF, BA),
+		(0x12470, 0x12474, BA), (0x13258, 0x1325A, OP), (0x1325B, 0x1325D, CL), (0x13282, 0x13282, CL),
+		(0x13286, 0x13286, OP), (0x13287, 0x13287, CL), (0x13288, 0x13288, OP), (0x13289, 0x13289, CL),
+		(0x13379, 0x13379, OP), (0x1337A, 0x1337B, CL), (0x13430, 0x13436, GL), (0x13437, 0x13437, OP),
+		(0x13438, 0x13438, CL), (0x13439, 0x1343B, GL), (0x1343C, 0x1343C, OP), (0x1343D, 0x1343D, CL),
+		(0x1343E, 0x1343E, OP), (0x1343F, 0x1343F, CL), (0x13440, 0x13440, CM), (0x13447, 0x13455, CM),
+		(0x145CE, 0x145CE, OP), (0x145CF, 0x145CF, CL), (0x16A60, 0x16A69, NU), (0x16A6E, 0x16A6F, BA),
+		(0x16AC0, 0x16AC9, NU), (0x16AF0, 0x16AF4, CM), (0x16AF5, 0x16AF5, BA), (0x16B30, 0x16B36, CM),
+		(0x16B37, 0x16B39, BA), (0x16B44, 0x16B44, BA), (0x16B50, 0x16B59, NU), (0x16E97, 0x16E98, BA),
+		(0x16F4F, 0x16F4F, CM), (0x16F51, 0x16F87, CM), (0x16F8F, 0x16F92, CM), (0x16FE0, 0x16FE3, NS),
+		(0x16FE4, 0x16FE4, GL), (0x16FF0, 0x16FF1, CM), (0x17000, 0x187F7, ID), (0x18800, 0x18AFF, ID),
+		(0x18D00, 0x18D08, ID), (0x1B000, 0x1B122, ID), (0x1B132, 0x1B132, NS), (0x1B150, 0x1B152, NS),
+		(0x1B155, 0x1B155, NS), (0x1B164, 0x1B167, NS), (0x1B170, 0x1B2FB, ID), (0x1BC9D, 0x1BC9E, CM),
+		(0x1BC9F, 0x1BC9F, BA), (0x1BCA0, 0x1BCA3, CM), (0x1CF00, 0x1CF2D, CM), (0x1CF30, 0x1CF46, CM),
+		(0x1D165, 0x1D169, CM), (0x1D16D, 0x1D182, CM), (0x1D185, 0x1D18B, CM), (0x1D1AA, 0x1D1AD, CM),
+		(0x1D242, 0x1D244, CM), (0x1D7CE, 0x1D7FF, NU), (0x1DA00, 0x1DA36, CM), (0x1DA3B, 0x1DA6C, CM),
+		(0x1DA75, 0x1DA75, CM), (0x1DA84, 0x1DA84, CM), (0x1DA87, 0x1DA8A, BA), (0x1DA9B, 0x1DA9F, CM),
+		(0x1DAA1, 0x1DAAF, CM), (0x1E000, 0x1E006, CM), (0x1E008, 0x1E018, CM), (0x1E01B, 0x1E021, CM),
+		(0x1E023, 0x1E024, CM), (0x1E026, 0x1E02A, CM), (0x1E08F, 0x1E08F, CM), (0x1E130, 0x1E136, CM),
+		(0x1E140, 0x1E149, NU), (0x1E2AE, 0x1E2AE, CM), (0x1E2EC, 0x1E2EF, CM), (0x1E2F0, 0x1E2F9, NU),
+		(0x1E2FF, 0x1E2FF, PR), (0x1E4EC, 0x1E4EF, CM), (0x1E4F0, 0x1E4F9, NU), (0x1E8D0, 0x1E8D6, CM),
+		(0x1E944, 0x1E94A, CM), (0x1E950, 0x1E959, NU), (0x1E95E, 0x1E95F, OP), (0x1ECAC, 0x1ECAC, PO),
+		(0x1ECB0, 0x1ECB0, PO), (0x1F000, 0x1F0FF, ID), (0x1F10D, 0x1F10F, ID), (0x1F16D, 0x1F16F, ID),
+		(0x1F1AD, 0x1F1E5, ID), (0x1F1E6, 0x1F1FF, RI), (0x1F200, 0x1F384, ID), (0x1F385, 0x1F385, EB),
+		(0x1F386, 0x1F39B, ID), (0x1F39E, 0x1F3B4, ID), (0x1F3B7, 0x1F3BB, ID), (0x1F3BD, 0x1F3C1, ID),
+		(0x1F3C2, 0x1F3C4, EB), (0x1F3C5, 0x1F3C6, ID), (0x1F3C7, 0x1F3C7, EB), (0x1F3C8, 0x1F3C9, ID),
+		(0x1F3CA, 0x1F3CC, EB), (0x1F3CD, 0x1F3FA, ID), (0x1F3FB, 0x1F3FF, EM), (0x1F400, 0x1F441, ID),
+		(0x1F442, 0x1F443, EB), (0x1F444, 0x1F445, ID), (0x1F446, 0x1F450, EB), (0x1F451, 0x1F465, ID),
+		(0x1F466, 0x1F478, EB), (0x1F479, 0x1F47B, ID), (0x1F47C, 0x1F47C, EB), (0x1F47D, 0x1F480, ID),
+		(0x1F481, 0x1F483, EB), (0x1F484, 0x1F484, ID), (0x1F485, 0x1F487, EB), (0x1F488, 0x1F48E, ID),
+		(0x1F48F, 0x1F48F, EB), (0x1F490, 0x1F490, ID), (0x1F491, 0x1F491, EB), (0x1F492, 0x1F49F, ID),
+		(0x1F4A1, 0x1F4A1, ID), (0x1F4A3, 0x1F4A3, ID), (0x1F4A5, 0x1F4A9, ID), (0x1F4AA, 0x1F4AA, EB),
+		(0x1F4AB, 0x1F4AE, ID), (0x1F4B0, 0x1F4B0, ID), (0x1F4B3, 0x1F4FF, ID), (0x1F507, 0x1F516, ID),
+		(0x1F525, 0x1F531, ID), (0x1F54A, 0x1F573, ID), (0x1F574, 0x1F575, EB), (0x1F576, 0x1F579, ID),
+		(0x1F57A, 0x1F57A, EB), (0x1F57B, 0x1F58F, ID), (0x1F590, 0x1F590, EB), (0x1F591, 0x1F594, ID),
+		(0x1F595, 0x1F596, EB), (0x1F597, 0x1F5D3, ID), (0x1F5DC, 0x1F5F3, ID), (0x1F5FA, 0x1F644, ID),
+		(0x1F645, 0x1F647, EB), (0x1F648, 0x1F64A, ID), (0x1F64B, 0x1F64F, EB), (0x1F676, 0x1F678, QU),
+		(0x1F679, 0x1F67B, NS), (0x1F680, 0x1F6A2, ID), (0x1F6A3, 0x1F6A3, EB), (0x1F6A4, 0x1F6B3, ID),
+		(0x1F6B4, 0x1F6B6, EB), (0x1F6B7, 0x1F6BF, ID), (0x1F6C0, 0x1F6C0, EB), (0x1F6C1, 0x1F6CB, ID),
+		(0x1F6CC, 0x1F6CC, EB), (0x1F6CD, 0x1F6FF, ID), (0x1F774, 0x1F77F, ID), (0x1F7D5, 0x1F7FF, ID),
+		(0x1F80C, 0x1F80F, ID), (0x1F848, 0x1F84F, ID), (0x1F85A, 0x1F85F, ID), (0x1F888, 0x1F88F, ID),
+		(0x1F8AE, 0x1F8FF, ID), (0x1F90C, 0x1F90C, EB), (0x1F90D, 0x1F90E, ID), (0x1F90F, 0x1F90F, EB),
+		(0x1F910, 0x1F917, ID), (0x1F918, 0x1F91F, EB), (0x1F920, 0x1F925, ID), (0x1F926, 0x1F926, EB),
+		(0x1F927, 0x1F92F, ID), (0x1F930, 0x1F939, EB), (0x1F93A, 0x1F93B, ID), (0x1F93C, 0x1F93E, EB),
+		(0x1F93F, 0x1F976, ID), (0x1F977, 0x1F977, EB), (0x1F978, 0x1F9B4, ID), (0x1F9B5, 0x1F9B6, EB),
+		(0x1F9B7, 0x1F9B7, ID), (0x1F9B8, 0x1F9B9, EB), (0x1F9BA, 0x1F9BA, ID), (0x1F9BB, 0x1F9BB, EB),
+		(0x1F9BC, 0x1F9CC, ID), (0x1F9CD, 0x1F9CF, EB), (0x1F9D0, 0x1F9D0, ID), (0x1F9D1, 0x1F9DD, EB),
+		(0x1F9DE, 0x1F9FF, ID), (0x1FA54, 0x1FAC2, ID), (0x1FAC3, 0x1FAC5, EB), (0x1FAC6, 0x1FAEF, ID),
+		(0x1FAF0, 0x1FAF8, EB), (0x1FAF9, 0x1FAFF, ID), (0x1FBF0, 0x1FBF9, NU), (0x1FC00, 0x1FFFD, ID),
+		(0x20000, 0x2FFFD, ID), (0x30000, 0x3FFFD, ID), (0xE0001, 0xE0001, CM), (0xE0020, 0xE007F, CM),
+		(0xE0100, 0xE01EF, CM),
+	];
+}
+
+pub mod script {
+	use crate::text::Script::{self, *};
+	
+	/// The scripts of all code points that belong to a script with shaping support.
+	pub const SCRIPTS: &[(u32, u32, Script)] = &[
+		(0x0000, 0x0040, Common), (0x0041, 0x005A, Latin), (0x005B, 0x0060, Common), (0x0061, 0x007A, Latin),
+		(0x007B, 0x00A9, Common), (0x00AA, 0x00AA, Latin), (0x00AB, 0x00B9, Common), (0x00BA, 0x00BA, Latin),
+		(0x00BB, 0x00BF, Common), (0x00C0, 0x00D6, Latin), (0x00D7, 0x00D7, Common), (0x00D8, 0x00F6, Latin),
+		(0x00F7, 0x00F7, Common), (0x00F8, 0x02B8, Latin), (0x02B9, 0x02DF, Common), (0x02E0, 0x02E4, Latin),
+		(0x02E5, 0x02E9, Common), (0x02EA, 0x02EB, Bopomofo), (0x02EC, 0x02FF, Common), (0x0300, 0x036F, Inherited),
+		(0x0370, 0x0373, Greek), (0x0374, 0x0374, Common), (0x0375, 0x0377, Greek), (0x037A, 0x037D, Greek),
+		(0x037E, 0x037E, Common), (0x037F, 0x037F, Greek), (0x0384, 0x0384, Greek), (0x0385, 0x0385, Common),
+		(0x0386, 0x0386, Greek), (0x0387, 0x0387, Common), (0x0388, 0x038A, Greek), (0x038C, 0x038C, Greek),
+		(0x038E, 0x03A1, Greek), (0x03A3, 0x03E1, Greek), (0x03F0, 0x03FF, Greek), (0x0400, 0x0484, Cyrillic),
+		(0x0485, 0x0486, Inherited), (0x0487, 0x052F, Cyrillic), (0x0531, 0x0556, Armenian), (0x0559, 0x058A, Armenian),
+		(0x058D, 0x058F, Armenian), (0x0591, 0x05C7, Hebrew), (0x05D0, 0x05EA, Hebrew), (0x05EF, 0x05F4, Hebrew),
+		(0x0600, 0x0604, Arabic), (0x0605, 0x0605, Common), (0x0606, 0x060B, Arabic), (0x060C, 0x060C, Common),
+		(0x060D, 0x061A, Arabic), (0x061B, 0x061B, Common), (0x061C, 0x061E, Arabic), (0x061F, 0x061F, Common),
+		(0x0620, 0x063F, Arabic), (0x0640, 0x0640, Common), (0x0641, 0x064A, Arabic), (0x064B, 0x0655, Inherited),
+		(0x0656, 0x066F, Arabic), (0x0670, 0x0670, Inherited), (0x0671, 0x06DC, Arabic), (0x06DD, 0x06DD, Common),
+		(0x06DE, 0x06FF, Arabic), (0x0700, 0x070D, Syriac), (0x070F, 0x074A, Syriac), (0x074D, 0x074F, Syriac),
+		(0x0750, 0x077F, Arabic), (0x0780, 0x07B1, Thaana), (0x07C0, 0x07FA, Nko), (0x07FD, 0x07FF, Nko),
+		(0x0860, 0x086A, Syriac), (0x0870, 0x088E, Arabic), (0x0890, 0x0891, Arabic), (0x0898, 0x08E1, Arabic),
+		(0x08E2, 0x08E2, Common), (0x08E3, 0x08FF, Arabic), (0x0900, 0x0950, Devanagari), (0x0951, 0x0954, Inherited),
+		(0x0955, 0x0963, Devanagari), (0x0964, 0x0965, Common), (0x0966, 0x097F, Devanagari), (0x0980, 0x0983, Bengali),
+		(0x0985, 0x098C, Bengali), (0x098F, 0x0990, Bengali), (0x0993, 0x09A8, Bengali), (0x09AA, 0x09B0, Bengali),
+		(0x09B2, 0x09B2, Bengali), (0x09B6, 0x09B9, Bengali), (0x09BC, 0x09C4, Bengali), (0x09C7, 0x09C8, Bengali),
+		(0x09CB, 0x09CE, Bengali), (0x09D7, 0x09D7, Bengali), (0x09DC, 0x09DD, Bengali), (0x09DF, 0x09E3, Bengali),
+		(0x09E6, 0x09FE, Bengali), (0x0A01, 0x0A03, Gurmukhi), (0x0A05, 0x0A0A, Gurmukhi), (0x0A0F, 0x0A10, Gurmukhi),
+		(0x0A13, 0x0A28, Gurmukhi), (0x0A2A, 0x0A30, Gurmukhi), (0x0A32, 0x0A33, Gurmukhi), (0x0A35, 0x0A36, Gurmukhi),
+		(0x0A38, 0x0A39, Gurmukhi), (0x0A3C, 0x0A3C, Gurmukhi), (0x0A3E, 0x0A42, Gurmukhi), (0x0A47, 0x0A48, Gurmukhi),
+		(0x0A4B, 0x0A4D, Gurmukhi), (0x0A51, 0x0A51, Gurmukhi), (0x0A59, 0x0A5C, Gurmukhi), (0x0A5E, 0x0A5E, Gurmukhi),
+		(0x0A66, 0x0A76, Gurmukhi), (0x0A81, 0x0A83, Gujarati), (0x0A85, 0x0A8D, Gujarati), (0x0A8F, 0x0A91, Gujarati),
+		(0x0A93, 0x0AA8, Gujarati), (0x0AAA, 0x0AB0, Gujarati), (0x0AB2, 0x0AB3, Gujarati), (0x0AB5, 0x0AB9, Gujarati),
+		(0x0ABC, 0x0AC5, Gujarati), (0x0AC7, 0x0AC9, Gujarati), (0x0ACB, 0x0ACD, Gujarati), (0x0AD0, 0x0AD0, Gujarati),
+		(0x0AE0, 0x0AE3, Gujarati), (0x0AE6, 0x0AF1, Gujarati), (0x0AF9, 0x0AFF, Gujarati), (0x0B01, 0x0B03, Oriya),
+		(0x0B05, 0x0B0C, Oriya), (0x0B0F, 0x0B10, Oriya), (0x0B13, 0x0B28, Oriya), (0x0B2A, 0x0B30, Oriya),
+		(0x0B32, 0x0B33, Oriya), (0x0B35, 0x0B39, Oriya), (0x0B3C, 0x0B44, Oriya), (0x0B47, 0x0B48, Oriya),
+		(0x0B4B, 0x0B4D, Oriya), (0x0B55, 0x0B57, Oriya), (0x0B5C, 0x0B5D, Oriya), (0x0B5F, 0x0B63, Oriya),
+		(0x0B66, 0x0B77, Oriya), (0x0B82, 0x0B83, Tamil), (0x0B85, 0x0B8A, Tamil), (0x0B8E, 0x0B90, Tamil),
+		(0x0B92, 0x0B95, Tamil), (0x0B99, 0x0B9A, Tamil), (0x0B9C, 0x0B9C, Tamil), (0x0B9E, 0x0B9F, Tamil),
+		(0x0BA3, 0x0BA4, Tamil), (0x0BA8, 0x0BAA, Tamil), (0x0BAE, 0x0BB9, Tamil), (0x0BBE, 0x0BC2, Tamil),
+		(0x0BC6, 0x0BC8, Tamil), (0x0BCA, 0x0BCD, Tamil), (0x0BD0, 0x0BD0, Tamil), (0x0BD7, 0x0BD7, Tamil),
+		(0x0BE6, 0x0BFA, Tamil), (0x0C00, 0x0C0C, Telugu), (0x0C0E, 0x0C10, Telugu), (0x0C12, 0x0C28, Telugu),
+		(0x0C2A, 0x0C39, Telugu), (0x0C3C, 0x0C44, Telugu), (0x0C46, 0x0C48, Telugu), (0x0C4A, 0x0C4D, Telugu),
+		(0x0C55, 0x0C56, Telugu), (0x0C58, 0x0C5A, Telugu), (0x0C5D, 0x0C5D, Telugu), (0x0C60, 0x0C63, Telugu),
+		(0x0C66, 0x0C6F, Telugu), (0x0C77, 0x0C7F, Telugu), (0x0C80, 0x0C8C, Kannada), (0x0C8E, 0x0C90, Kannada),
+		(0x0C92, 0x0CA8, Kannada), (0x0CAA, 0x0CB3, Kannada), (0x0CB5, 0x0CB9, Kannada), (0x0CBC, 0x0CC4, Kannada),
+		(0x0CC6, 0x0CC8, Kannada), (0x0CCA, 0x0CCD, Kannada), (0x0CD5, 0x0CD6, Kannada), (0x0CDD, 0x0CDE, Kannada),
+		(0x0CE0, 0x0CE3, Kannada), (0x0CE6, 0x0CEF, Kannada), (0x0CF1, 0x0CF3, Kannada), (0x0D00, 0x0D0C, Malayalam),
+		(0x0D0E, 0x0D10, Malayalam), (0x0D12, 0x0D44, Malayalam), (0x0D46, 0x0D48, Malayalam), (0x0D4A, 0x0D4F, Malayalam),
+		(0x0D54, 0x0D63, Malayalam), (0x0D66, 0x0D7F, Malayalam), (0x0D81, 0x0D83, Sinhala), (0x0D85, 0x0D96, Sinhala),
+		(0x0D9A, 0x0DB1, Sinhala), (0x0DB3, 0x0DBB, Sinhala), (0x0DBD, 0x0DBD, Sinhala), (0x0DC0, 0x0DC6, Sinhala),
+		(0x0DCA, 0x0DCA, Sinhala), (0x0DCF, 0x0DD4, Sinhala), (0x0DD6, 0x0DD6, Sinhala), (0x0DD8, 0x0DDF, Sinhala),
+		(0x0DE6, 0x0DEF, Sinhala), (0x0DF2, 0x0DF4, Sinhala), (0x0E01, 0x0E3A, Thai), (0x0E3F, 0x0E3F, Common),
+		(0x0E40, 0x0E5B, Thai), (0x0E81, 0x0E82, Lao), (0x0E84, 0x0E84, Lao), (0x0E86, 0x0E8A, Lao),
+		(0x0E8C, 0x0EA3, Lao), (0x0EA5, 0x0EA5, Lao), (0x0EA7, 0x0EBD, Lao), (0x0EC0, 0x0EC4, Lao),
+		(0x0EC6, 0x0EC6, Lao), (0x0EC8, 0x0ECE, Lao), (0x0ED0, 0x0ED9, Lao), (0x0EDC, 0x0EDF, Lao),
+		(0x0F00, 0x0F47, Tibetan), (0x0F49, 0x0F6C, Tibetan), (0x0F71, 0x0F97, Tibetan), (0x0F99, 0x0FBC, Tibetan),
+		(0x0FBE, 0x0FCC, Tibetan), (0x0FCE, 0x0FD4, Tibetan), (0x0FD5, 0x0FD8, Common), (0x0FD9, 0x0FDA, Tibetan),
+		(0x1000, 0x109F, Myanmar), (0x10A0, 0x10C5, Georgian), (0x10C7, 0x10C7, Georgian), (0x10CD, 0x10CD, Georgian),
+		(0x10D0, 0x10FA, Georgian), (0x10FB, 0x10FB, Common), (0x10FC, 0x10FF, Georgian), (0x1100, 0x11FF, Hangul),
+		(0x1200, 0x1248, Ethiopic), (0x124A, 0x124D, Ethiopic), (0x1250, 0x1256, Ethiopic), (0x1258, 0x1258, Ethiopic),
+		(0x125A, 0x125D, Ethiopic), (0x1260, 0x1288, Ethiopic), (0x128A, 0x128D, Ethiopic), (0x1290, 0x12B0, Ethiopic),
+		(0x12B2, 0x12B5, Ethiopic), (0x12B8, 0x12BE, Ethiopic), (0x12C0, 0x12C0, Ethiopic), (0x12C2, 0x12C5, Ethiopic),
+		(0x12C8, 0x12D6, Ethiopic), (0x12D8, 0x1310, Ethiopic), (0x1312, 0x1315, Ethiopic), (0x1318, 0x135A, Ethiopic),
+		(0x135D, 0x137C, Ethiopic), (0x1380, 0x1399, Ethiopic), (0x16EB, 0x16ED, Common), (0x1735, 0x1736, Common),
+		(0x1780, 0x17DD, Khmer), (0x17E0, 0x17E9, Khmer), (0x17F0, 0x17F9, Khmer), (0x1800, 0x1801, Mongolian),
+		(0x1802, 0x1803, Common), (0x1804, 0x1804, Mongolian), (0x1805, 0x1805, Common), (0x1806, 0x1819, Mongolian),
+		(0x1820, 0x1878, Mongolian), (0x1880, 0x18AA, Mongolian), (0x19E0, 0x19FF, Khmer), (0x1AB0, 0x1ACE, Inherited),
+		(0x1C80, 0x1C88, Cyrillic), (0x1C90, 0x1CBA, Georgian), (0x1CBD, 0x1CBF, Georgian), (0x1CD0, 0x1CD2, Inherited),
+		(0x1CD3, 0x1CD3, Common), (0x1CD4, 0x1CE0, Inherited), (0x1CE1, 0x1CE1, Common), (0x1CE2, 0x1CE8, Inherited),
+		(0x1CE9, 0x1CEC, Common), (0x1CED, 0x1CED, Inherited), (0x1CEE, 0x1CF3, Common), (0x1CF4, 0x1CF4, Inherited),
+		(0x1CF5, 0x1CF7, Common), (0x1CF8, 0x1CF9, Inherited), (0x1CFA, 0x1CFA, Common), (0x1D00, 0x1D25, Latin),
+		(0x1D26, 0x1D2A, Greek), (0x1D2B, 0x1D2B, Cyrillic), (0x1D2C, 0x1D5C, Latin), (0x1D5D, 0x1D61, Greek),
+		(0x1D62, 0x1D65, Latin), (0x1D66, 0x1D6A, Greek), (0x1D6B, 0x1D77, Latin), (0x1D78, 0x1D78, Cyrillic),
+		(0x1D79, 0x1DBE, Latin), (0x1DBF, 0x1DBF, Greek), (0x1DC0, 0x1DFF, Inherited), (0x1E00, 0x1EFF, Latin),
+		(0x1F00, 0x1F15, Greek), (0x1F18, 0x1F1D, Greek), (0x1F20, 0x1F45, Greek), (0x1F48, 0x1F4D, Greek),
+		(0x1F50, 0x1F57, Greek), (0x1F59, 0x1F59, Greek), (0x1F5B, 0x1F5B, Greek), (0x1F5D, 0x1F5D, Greek),
+		(0x1F5F, 0x1F7D, Greek), (0x1F80, 0x1FB4, Greek), (0x1FB6, 0x1FC4, Greek), (0x1FC6, 0x1FD3, Greek),
+		(0x1FD6, 0x1FDB, Greek), (0x1FDD, 0x1FEF, Greek), (0x1FF2, 0x1FF4, Greek), (0x1FF6, 0x1FFE, Greek),
+		(0x2000, 0x200B, Common), (0x200C, 0x200D, Inherited), (0x200E, 0x2064, Common), (0x2066, 0x2070, Common),
+		(0x2071, 0x2071, Latin), (0x2074, 0x207E, Common), (0x207F, 0x207F, Latin), (0x2080, 0x208E, Common),
+		(0x2090, 0x209C, Latin), (0x20A0, 0x20C0, Common), (0x20D0, 0x20F0, Inherited), (0x2100, 0x2125, Common),
+		(0x2126, 0x2126, Greek), (0x2127, 0x2129, Common), (0x212A, 0x212B, Latin), (0x212C, 0x2131, Common),
+		(0x2132, 0x2132, Latin), (0x2133, 0x214D, Common), (0x214E, 0x214E, Latin), (0x214F, 0x215F, Common),
+		(0x2160, 0x2188, Latin), (0x2189, 0x218B, Common), (0x2190, 0x2426, Common), (0x2440, 0x244A, Common),
+		(0x2460, 0x27FF, Common), (0x2900, 0x2B73, Common), (0x2B76, 0x2B95, Common), (0x2B97, 0x2BFF, Common),
+		(0x2C60, 0x2C7F, Latin), (0x2D00, 0x2D25, Georgian), (0x2D27, 0x2D27, Georgian), (0x2D2D, 0x2D2D, Georgian),
+		(0x2D80, 0x2D96, Ethiopic), (0x2DA0, 0x2DA6, Ethiopic), (0x2DA8, 0x2DAE, Ethiopic), (0x2DB0, 0x2DB6, Ethiopic),
+		(0x2DB8, 0x2DBE, Ethiopic), (0x2DC0, 0x2DC6, Ethiopic), (0x2DC8, 0x2DCE, Ethiopic), (0x2DD0, 0x2DD6, Ethiopic),
+		(0x2DD8, 0x2DDE, Ethiopic), (0x2DE0, 0x2DFF, Cyrillic), (0x2E00, 0x2E5D, Common), (0x2E80, 0x2E99, Han),
+		(0x2E9B, 0x2EF3, Han), (0x2F00, 0x2FD5, Han), (0x2FF0, 0x2FFB, Common), (0x3000, 0x3004, Common),
+		(0x3005, 0x3005, Han), (0x3006, 0x3006, Common), (0x3007, 0x3007, Han), (0x3008, 0x3020, Common),
+		(0x3021, 0x3029, Han), (0x302A, 0x302D, Inherited), (0x302E, 0x302F, Hangul), (0x3030, 0x3037, Common),
+		(0x3038, 0x303B, Han), (0x303C, 0x303F, Common), (0x3041, 0x3096, Hiragana), (0x3099, 0x309A, Inherited),
+		(0x309B, 0x309C, Common), (0x309D, 0x309F, Hiragana), (0x30A0, 0x30A0, Common), (0x30A1, 0x30FA, Katakana),
+		(0x30FB, 0x30FC, Common), (0x30FD, 0x30FF, Katakana), (0x3105, 0x312F, Bopomofo), (0x3131, 0x318E, Hangul),
+		(0x3190, 0x319F, Common), (0x31A0, 0x31BF, Bopomofo), (0x31C0, 0x31E3, Common), (0x31F0, 0x31FF, Katakana),
+		(0x3200, 0x321E, Hangul), (0x3220, 0x325F, Common), (0x3260, 0x327E, Hangul), (0x327F, 0x32CF, Common),
+		(0x32D0, 0x32FE, Katakana), (0x32FF, 0x32FF, Common), (0x3300, 0x3357, Katakana), (0x3358, 0x33FF, Common),
+		(0x3400, 0x4DBF, Han), (0x4DC0, 0x4DFF, Common), (0x4E00, 0x9FFF, Han), (0xA640, 0xA69F, Cyrillic),
+		(0xA700, 0xA721, Common), (0xA722, 0xA787, Latin), (0xA788, 0xA78A, Common), (0xA78B, 0xA7CA, Latin),
+		(0xA7D0, 0xA7D1, Latin), (0xA7D3, 0xA7D3, Latin), (0xA7D5, 0xA7D9, Latin), (0xA7F2, 0xA7FF, Latin),
+		(0xA830, 0xA839, Common), (0xA8E0, 0xA8FF, Devanagari), (0xA92E, 0xA92E, Common), (0xA960, 0xA97C, Hangul),
+		(0xA9CF, 0xA9CF, Common), (0xA9E0, 0xA9FE, Myanmar), (0xAA60, 0xAA7F, Myanmar), (0xAB01, 0xAB06, Ethiopic),
+		(0xAB09, 0xAB0E, Ethiopic), (0xAB11, 0xAB16, Ethiopic), (0xAB20, 0xAB26, Ethiopic), (0xAB28, 0xAB2E, Ethiopic),
+		(0xAB30, 0xAB5A, Latin), (0xAB5B, 0xAB5B, Common), (0xAB5C, 0xAB64, Latin), (0xAB65, 0xAB65, Greek),
+		(0xAB66, 0xAB69, Latin), (0xAB6A, 0xAB6B, Common), (0xAC00, 0xD7A3, Hangul), (0xD7B0, 0xD7C6, Hangul),
+		(0xD7CB, 0xD7FB, Hangul), (0xF900, 0xFA6D, Han), (0xFA70, 0xFAD9, Han), (0xFB00, 0xFB06, Latin),
+		(0xFB13, 0xFB17, Armenian), (0xFB1D, 0xFB36, Hebrew), (0xFB38, 0xFB3C, Hebrew), (0xFB3E, 0xFB3E, Hebrew),
+		(0xFB40, 0xFB41, Hebrew), (0xFB43, 0xFB44, Hebrew), (0xFB46, 0xFB4F, Hebrew), (0xFB50, 0xFBC2, Arabic),
+		(0xFBD3, 0xFD3D, Arabic), (0xFD3E, 0xFD3F, Common), (0xFD40, 0xFD8F, Arabic), (0xFD92, 0xFDC7, Arabic),
+		(0xFDCF, 0xFDCF, Arabic), (0xFDF0, 0xFDFF, Arabic), (0xFE00, 0xFE0F, Inherited), (0xFE10, 0xFE19, Common),
+		(0xFE20, 0xFE2D, Inherited), (0xFE2E, 0xFE2F, Cyrillic), (0xFE30, 0xFE52, Common), (0xFE54, 0xFE66, Common),
+		(0xFE68, 0xFE6B, Common), (0xFE70, 0xFE74, Arabic), (0xFE76, 0xFEFC, Arabic), (0xFEFF, 0xFEFF, Common),
+		(0xFF01, 0xFF20, Common), (0xFF21, 0xFF3A, Latin), (0xFF3B, 0xFF40, Common), (0xFF41, 0xFF5A, Latin),
+		(0xFF5B, 0xFF65, Common), (0xFF66, 0xFF6F, Katakana), (0xFF70, 0xFF70, Common), (0xFF71, 0xFF9D, Katakana),
+		(0xFF9E, 0xFF9F, Common), (0xFFA0, 0xFFBE, Hangul), (0xFFC2, 0xFFC7, Hangul), (0xFFCA, 0xFFCF, Hangul),
+		(0xFFD2, 0xFFD7, Hangul), (0xFFDA, 0xFFDC, Hangul), (0xFFE0, 0xFFE6, Common), (0xFFE8, 0xFFEE, Common),
+		(0xFFF9, 0xFFFD, Common), (0x10100, 0x10102, Common), (0x10107, 0x10133, Common), (0x10137, 0x1013F, Common),
+		(0x10140, 0x1018E, Greek), (0x10190, 0x1019C, Common), (0x101A0, 0x101A0, Greek), (0x101D0, 0x101FC, Common),
+		(0x101FD, 0x101FD, Inherited), (0x102E0, 0x102E0, Inherited), (0x102E1, 0x102FB, Common), (0x10780, 0x10785, Latin),
+		(0x10787, 0x107B0, Latin), (0x107B2, 0x107BA, Latin), (0x10E60, 0x10E7E, Arabic), (0x10EFD, 0x10EFF, Arabic),
+		(0x111E1, 0x111F4, Sinhala), (0x1133B, 0x1133B, Inherited), (0x11660, 0x1166C, Mongolian), (0x11B00, 0x11B09, Devanagari),
+		(0x11FC0, 0x11FF1, Tamil), (0x11FFF, 0x11FFF, Tamil), (0x16FE2, 0x16FE3, Han), (0x16FF0, 0x16FF1, Han),
+		(0x1AFF0, 0x1AFF3, Katakana), (0x1AFF5, 0x1AFFB, Katakana), (0x1AFFD, 0x1AFFE, Katakana), (0x1B000, 0x1B000, Katakana),
+		(0x1B001, 0x1B11F, Hiragana), (0x1B120, 0x1B122, Katakana), (0x1B132, 0x1B132, Hiragana), (0x1B150, 0x1B152, Hiragana),
+		(0x1B155, 0x1B155, Katakana), (0x1B164, 0x1B167, Katakana), (0x1BCA0, 0x1BCA3, Common), (0x1CF00, 0x1CF2D, Inherited),
+		(0x1CF30, 0x1CF46, Inherited), (0x1CF50, 0x1CFC3, Common), (0x1D000, 0x1D0F5, Common), (0x1D100, 0x1D126, Common),
+		(0x1D129, 0x1D166, Common), (0x1D167, 0x1D169, Inherited), (0x1D16A, 0x1D17A, Common), (0x1D17B, 0x1D182, Inherited),
+		(0x1D183, 0x1D184, Common), (0x1D185, 0x1D18B, Inherited), (0x1D18C, 0x1D1A9, Common), (0x1D1AA, 0x1D1AD, Inherited),
+		(0x1D1AE, 0x1D1EA, Common), (0x1D200, 0x1D245, Greek), (0x1D2C0, 0x1D2D3, Common), (0x1D2E0, 0x1D2F3, Common),
+		(0x1D300, 0x1D356, Common), (0x1D360, 0x1D378, Common), (0x1D400, 0x1D454, Common), (0x1D456, 0x1D49C, Common),
+		(0x1D49E, 0x1D49F, Common), (0x1D4A2, 0x1D4A2, Common), (0x1D4A5, 0x1D4A6, Common), (0x1D4A9, 0x1D4AC, Common),
+		(0x1D4AE, 0x1D4B9, Common), (0x1D4BB, 0x1D4BB, Common), (0x1D4BD, 0x1D4C3, Common), (0x1D4C5, 0x1D505, Common),
+		(0x1D507, 0x1D50A, Common), (0x1D50D, 0x1D514, Common), (0x1D516, 0x1D51C, Common), (0x1D51E, 0x1D539, Common),
+		(0x1D53B, 0x1D53E, Common), (0x1D540, 0x1D544, Common), (0x1D546, 0x1D546, Common), (0x1D54A, 0x1D550, Common),
+		(0x1D552, 0x1D6A5, Common), (0x1D6A8, 0x1D7CB, Common), (0x1D7CE, 0x1D7FF, Common), (0x1DF00, 0x1DF1E, Latin),
+		(0x1DF25, 0x1DF2A, Latin), (0x1E030, 0x1E06D, Cyrillic), (0x1E08F, 0x1E08F, Cyrillic), (0x1E7E0, 0x1E7E6, Ethiopic),
+		(0x1E7E8, 0x1E7EB, Ethiopic), (0x1E7ED, 0x1E7EE, Ethiopic), (0x1E7F0, 0x1E7FE, Ethiopic), (0x1EC71, 0x1ECB4, Common),
+		(0x1ED01, 0x1ED3D, Common), (0x1EE00, 0x1EE03, Arabic), (0x1EE05, 0x1EE1F, Arabic), (0x1EE21, 0x1EE22, Arabic),
+		(0x1EE24, 0x1EE24, Arabic), (0x1EE27, 0x1EE27, Arabic), (0x1EE29, 0x1EE32, Arabic), (0x1EE34, 0x1EE37, Arabic),
+		(0x1EE39, 0x1EE39, Arabic), (0x1EE3B, 0x1EE3B, Arabic), (0x1EE42, 0x1EE42, Arabic), (0x1EE47, 0x1EE47, Arabic),
+		(0x1EE49, 0x1EE49, Arabic), (0x1EE4B, 0x1EE4B, Arabic), (0x1EE4D, 0x1EE4F, Arabic), (0x1EE51, 0x1EE52, Arabic),
+		(0x1EE54, 0x1EE54, Arabic), (0x1EE57, 0x1EE57, Arabic), (0x1EE59, 0x1EE59, Arabic), (0x1EE5B, 0x1EE5B, Arabic),
+		(0x1EE5D, 0x1EE5D, Arabic), (0x1EE5F, 0x1EE5F, Arabic), (0x1EE61, 0x1EE62, Arabic), (0x1EE64, 0x1EE64, Arabic),
+		(0x1EE67, 0x1EE6A, Arabic), (0x1EE6C, 0x1EE72, Arabic), (0x1EE74, 0x1EE77, Arabic), (0x1EE79, 0x1EE7C, Arabic),
+		(0x1EE7E, 0x1EE7E, Arabic), (0x1EE80, 0x1EE89, Arabic), (0x1EE8B, 0x1EE9B, Arabic), (0x1EEA1, 0x1EEA3, Arabic),
+		(0x1EEA5, 0x1EEA9, Arabic), (0x1EEAB, 0x1EEBB, Arabic), (0x1EEF0, 0x1EEF1, Arabic), (0x1F000, 0x1F02B, Common),
+		(0x1F030, 0x1F093, Common), (0x1F0A0, 0x1F0AE, Common), (0x1F0B1, 0x1F0BF, Common), (0x1F0C1, 0x1F0CF, Common),
+		(0x1F0D1, 0x1F0F5, Common), (0x1F100, 0x1F1AD, Common), (0x1F1E6, 0x1F1FF, Common), (0x1F200, 0x1F200, Hiragana),
+		(0x1F201, 0x1F202, Common), (0x1F210, 0x1F23B, Common), (0x1F240, 0x1F248, Common), (0x1F250, 0x1F251, Common),
+		(0x1F260, 0x1F265, Common), (0x1F300, 0x1F6D7, Common), (0x1F6DC, 0x1F6EC, Common), (0x1F6F0, 0x1F6FC, Common),
+		(0x1F700, 0x1F776, Common), (0x1F77B, 0x1F7D9, Common), (0x1F7E0, 0x1F7EB, Common), (0x1F7F0, 0x1F7F0, Common),
+		(0x1F800, 0x1F80B, Common), (0x1F810, 0x1F847, Common), (0x1F850, 0x1F859, Common), (0x1F860, 0x1F887, Common),
+		(0x1F890, 0x1F8AD, Common), (0x1F8B0, 0x1F8B1, Common), (0x1F900, 0x1FA53, Common), (0x1FA60, 0x1FA6D, Common),
+		(0x1FA70, 0x1FA7C, Common), (0x1FA80, 0x1FA88, Common), (0x1FA90, 0x1FABD, Common), (0x1FABF, 0x1FAC5, Common),
+		(0x1FACE, 0x1FADB, Common), (0x1FAE0, 0x1FAE8, Common), (0x1FAF0, 0x1FAF8, Common), (0x1FB00, 0x1FB92, Common),
+		(0x1FB94, 0x1FBCA, Common), (0x1FBF0, 0x1FBF9, Common), (0x20000, 0x2A6DF, Han), (0x2A700, 0x2B739, Han),
+		(0x2B740, 0x2B81D, Han), (0x2B820, 0x2CEA1, Han), (0x2CEB0, 0x2EBE0, Han), (0x2F800, 0x2FA1D, Han),
+		(0x30000, 0x3134A, Han), (0x31350, 0x323AF, Han), (0xE0001, 0xE0001, Common), (0xE0020, 0xE007F, Common),
+		(0xE0100, 0xE01EF, Inherited),
+	];
+}
+
+pub mod shaping {
+	use crate::text::shaping::{Category::{self, *}, Joining::{self, *}};
+	
+	/// The joining types of all code points that are not non-joining or transparent.
+	pub const JOINING_TYPES: &[(u32, u32, Joining)] = &[
+		(0x0620, 0x0620, Dual), (0x0622, 0x0625, Right), (0x0626, 0x0626, Dual), (0x0627, 0x0627, Right),
+		(0x0628, 0x0628, Dual), (0x0629, 0x0629, Right), (0x062A, 0x062E, Dual), (0x062F, 0x0632, Right),
+		(0x0633, 0x063F, Dual), (0x0640, 0x0640, Causing), (0x0641, 0x0647, Dual), (0x0648, 0x0648, Right),
+		(0x0649, 0x064A, Dual), (0x066E, 0x066F, Dual), (0x0671, 0x0673, Right), (0x0675, 0x0677, Right),
+		(0x0678, 0x0687, Dual), (0x0688, 0x0699, Right), (0x069A, 0x06BF, Dual), (0x06C0, 0x06C0, Right),
+		(0x06C1, 0x06C2, Dual), (0x06C3, 0x06CB, Right), (0x06CC, 0x06CC, Dual), (0x06CD, 0x06CD, Right),
+		(0x06CE, 0x06CE, Dual), (0x06CF, 0x06CF, Right), (0x06D0, 0x06D1, Dual), (0x06D2, 0x06D3, Right),
+		(0x06D5, 0x06D5, Right), (0x06EE, 0x06EF, Right), (0x06FA, 0x06FC, Dual), (0x06FF, 0x06FF, Dual),
+		(0x0710, 0x0710, Right), (0x0712, 0x0714, Dual), (0x0715, 0x0719, Right), (0x071A, 0x071D, Dual),
+		(0x071E, 0x071E, Right), (0x071F, 0x0727, Dual), (0x0728, 0x0728, Right), (0x0729, 0x0729, Dual),
+		(0x072A, 0x072A, Right), (0x072B, 0x072B, Dual), (0x072C, 0x072C, Right), (0x072D, 0x072E, Dual),
+		(0x072F, 0x072F, Right), (0x074D, 0x074D, Right), (0x074E, 0x0758, Dual), (0x0759, 0x075B, Right),
+		(0x075C, 0x076A, Dual), (0x076B, 0x076C, Right), (0x076D, 0x0770, Dual), (0x0771, 0x0771, Right),
+		(0x0772, 0x0772, Dual), (0x0773, 0x0774, Right), (0x0775, 0x0777, Dual), (0x0778, 0x0779, Right),
+		(0x077A, 0x077F, Dual), (0x07CA, 0x07EA, Dual), (0x07FA, 0x07FA, Causing), (0x0840, 0x0840, Right),
+		(0x0841, 0x0845, Dual), (0x0846, 0x0847, Right), (0x0848, 0x0848, Dual), (0x0849, 0x0849, Right),
+		(0x084A, 0x0853, Dual), (0x0854, 0x0854, Right), (0x0855, 0x0855, Dual), (0x0856, 0x0858, Right),
+		(0x0860, 0x0860, Dual), (0x0862, 0x0865, Dual), (0x0867, 0x0867, Right), (0x0868, 0x0868, Dual),
+		(0x0869, 0x086A, Right), (0x0870, 0x0882, Right), (0x0883, 0x0885, Causing), (0x0886, 0x0886, Dual),
+		(0x0889, 0x088D, Dual), (0x088E, 0x088E, Right), (0x08A0, 0x08A9, Dual), (0x08AA, 0x08AC, Right),
+		(0x08AE, 0x08AE, Right), (0x08AF, 0x08B0, Dual), (0x08B1, 0x08B2, Right), (0x08B3, 0x08B8, Dual),
+		(0x08B9, 0x08B9, Right), (0x08BA, 0x08C8, Dual), (0x1807, 0x1807, Dual), (0x180A, 0x180A, Causing),
+		(0x1820, 0x1878, Dual), (0x1887, 0x18A8, Dual), (0x18AA, 0x18AA, Dual), (0x200D, 0x200D, Causing),
+		(0xA840, 0xA871, Dual), (0xA872, 0xA872, Left), (0x10AC0, 0x10AC4, Dual), (0x10AC5, 0x10AC5, Right),
+		(0x10AC7, 0x10AC7, Right), (0x10AC9, 0x10ACA, Right), (0x10ACD, 0x10ACD, Left), (0x10ACE, 0x10AD2, Right),
+		(0x10AD3, 0x10AD6, Dual), (0x10AD7, 0x10AD7, Left), (0x10AD8, 0x10ADC, Dual), (0x10ADD, 0x10ADD, Right),
+		(0x10ADE, 0x10AE0, Dual), (0x10AE1, 0x10AE1, Right), (0x10AE4, 0x10AE4, Right), (0x10AEB, 0x10AEE, Dual),
+		(0x10AEF, 0x10AEF, Right), (0x10B80, 0x10B80, Dual), (0x10B81, 0x10B81, Right), (0x10B82, 0x10B82, Dual),
+		(0x10B83, 0x10B85, Right), (0x10B86, 0x10B88, Dual), (0x10B89, 0x10B89, Right), (0x10B8A, 0x10B8B, Dual),
+		(0x10B8C, 0x10B8C, Right), (0x10B8D, 0x10B8D, Dual), (0x10B8E, 0x10B8F, Right), (0x10B90, 0x10B90, Dual),
+		(0x10B91, 0x10B91, Right), (0x10BA9, 0x10BAC, Right), (0x10BAD, 0x10BAE, Dual), (0x10D00, 0x10D00, Left),
+		(0x10D01, 0x10D21, Dual), (0x10D22, 0x10D22, Right), (0x10D23, 0x10D23, Dual), (0x10F30, 0x10F32, Dual),
+		(0x10F33, 0x10F33, Right), (0x10F34, 0x10F44, Dual), (0x10F51, 0x10F53, Dual), (0x10F54, 0x10F54, Right),
+		(0x10F70, 0x10F73, Dual), (0x10F74, 0x10F75, Right), (0x10F76, 0x10F81, Dual), (0x10FB0, 0x10FB0, Dual),
+		(0x10FB2, 0x10FB3, Dual), (0x10FB4, 0x10FB6, Right), (0x10FB8, 0x10FB8, Dual), (0x10FB9, 0x10FBA, Right),
+		(0x10FBB, 0x10FBC, Dual), (0x10FBD, 0x10FBD, Right), (0x10FBE, 0x10FBF, Dual), (0x10FC1, 0x10FC1, Dual),
+		(0x10FC2, 0x10FC3, Right), (0x10FC4, 0x10FC4, Dual), (0x10FC9, 0x10FC9, Right), (0x10FCA, 0x10FCA, Dual),
+		(0x10FCB, 0x10FCB, Left), (0x1E900, 0x1E943, Dual),
+	];
+	
+	/// The general categories of all marks and format characters.
+	pub const CATEGORIES: &[(u32, u32, Category)] = &[
+		(0x00AD, 0x00AD, Cf), (0x0300, 0x036F, Mn), (0x0483, 0x0487, Mn), (0x0488, 0x0489, Me),
+		(0x0591, 0x05BD, Mn), (0x05BF, 0x05BF, Mn), (0x05C1, 0x05C2, Mn), (0x05C4, 0x05C5, Mn),
+		(0x05C7, 0x05C7, Mn), (0x0600, 0x0605, Cf), (0x0610, 0x061A, Mn), (0x061C, 0x061C, Cf),
+		(0x064B, 0x065F, Mn), (0x0670, 0x0670, Mn), (0x06D6, 0x06DC, Mn), (0x06DD, 0x06DD, Cf),
+		(0x06DF, 0x06E4, Mn), (0x06E7, 0x06E8, Mn), (0x06EA, 0x06ED, Mn), (0x070F, 0x070F, Cf),
+		(0x0711, 0x0711, Mn), (0x0730, 0x074A, Mn), (0x07A6, 0x07B0, Mn), (0x07EB, 0x07F3, Mn),
+		(0x07FD, 0x07FD, Mn), (0x0816, 0x0819, Mn), (0x081B, 0x0823, Mn), (0x0825, 0x0827, Mn),
+		(0x0829, 0x082D, Mn), (0x0859, 0x085B, Mn), (0x0890, 0x0891, Cf), (0x0898, 0x089F, Mn),
+		(0x08CA, 0x08E1, Mn), (0x08E2, 0x08E2, Cf), (0x08E3, 0x0902, Mn), (0x0903, 0x0903, Mc),
+		(0x093A, 0x093A, Mn), (0x093B, 0x093B, Mc), (0x093C, 0x093C, Mn), (0x093E, 0x0940, Mc),
+		(0x0941, 0x0948, Mn), (0x0949, 0x094C, Mc), (0x094D, 0x094D, Mn), (0x094E, 0x094F, Mc),
+		(0x0951, 0x0957, Mn), (0x0962, 0x0963, Mn), (0x0981, 0x0981, Mn), (0x0982, 0x0983, Mc),
+		(0x09BC, 0x09BC, Mn), (0x09BE, 0x09C0, Mc), (0x09C1, 0x09C4, Mn), (0x09C7, 0x09C8, Mc),
+		(0x09CB, 0x09CC, Mc), (0x09CD, 0x09CD, Mn), (0x09D7, 0x09D7, Mc), (0x09E2, 0x09E3, Mn),
+		(0x09FE, 0x09FE, Mn), (0x0A01, 0x0A02, Mn), (0x0A03, 0x0A03, Mc), (0x0A3C, 0x0A3C, Mn),
+		(0x0A3E, 0x0A40, Mc), (0x0A41, 0x0A42, Mn), (0x0A47, 0x0A48, Mn), (0x0A4B, 0x0A4D, Mn),
+		(0x0A51, 0x0A51, Mn), (0x0A70, 0x0A71, Mn), (0x0A75, 0x0A75, Mn), (0x0A81, 0x0A82, Mn),
+		(0x0A83, 0x0A83, Mc), (0x0ABC, 0x0ABC, Mn), (0x0ABE, 0x0AC0, Mc), (0x0AC1, 0x0AC5, Mn),
+		(0x0AC7, 0x0AC8, Mn), (0x0AC9, 0x0AC9, Mc), (0x0ACB, 0x0ACC, Mc), (0x0ACD, 0x0ACD, Mn),
+		(0x0AE2, 0x0AE3, Mn), (0x0AFA, 0x0AFF, Mn), (0x0B01, 0x0B01, Mn), (0x0B02, 0x0B03, Mc),
+		(0x0B3C, 0x0B3C, Mn), (0x0B3E, 0x0B3E, Mc), (0x0B3F, 0x0B3F, Mn), (0x0B40, 0x0B40, Mc),
+		(0x0B41, 0x0B44, Mn), (0x0B47, 0x0B48, Mc), (0x0B4B, 0x0B4C, Mc), (0x0B4D, 0x0B4D, Mn),
+		(0x0B55, 0x0B56, Mn), (0x0B57, 0x0B57, Mc), (0x0B62, 0x0B63, Mn), (0x0B82, 0x0B82, Mn),
+		(0x0BBE, 0x0BBF, Mc), (0x0BC0, 0x0BC0, Mn), (0x0BC1, 0x0BC2, Mc), (0x0BC6, 0x0BC8, Mc),
+		(0x0BCA, 0x0BCC, Mc), (0x0BCD, 0x0BCD, Mn), (0x0BD7, 0x0BD7, Mc), (0x0C00, 0x0C00, Mn),
+		(0x0C01, 0x0C03, Mc), (0x0C04, 0x0C04, Mn), (0x0C3C, 0x0C3C, Mn), (0x0C3E, 0x0C40, Mn),
+		(0x0C41, 0x0C44, Mc), (0x0C46, 0x0C48, Mn), (0x0C4A, 0x0C4D, Mn), (0x0C55, 0x0C56, Mn),
+		(0x0C62, 0x0C63, Mn), (0x0C81, 0x0C81, Mn), (0x0C82, 0x0C83, Mc), (0x0CBC, 0x0CBC, Mn),
+		(0x0CBE, 0x0CBE, Mc), (0x0CBF, 0x0CBF, Mn), (0x0CC0, 0x0CC4, Mc), (0x0CC6, 0x0CC6, Mn),
+		(0x0CC7, 0x0CC8, Mc), (0x0CCA, 0x0CCB, Mc), (0x0CCC, 0x0CCD, Mn), (0x0CD5, 0x0CD6, Mc),
+		(0x0CE2, 0x0CE3, Mn), (0x0CF3, 0x0CF3, Mc), (0x0D00, 0x0D01, Mn), (0x0D02, 0x0D03, Mc),
+		(0x0D3B, 0x0D3C, Mn), (0x0D3E, 0x0D40, Mc), (0x0D41, 0x0D44, Mn), (0x0D46, 0x0D48, Mc),
+		(0x0D4A, 0x0D4C, Mc), (0x0D4D, 0x0D4D, Mn), (0x0D57, 0x0D57, Mc), (0x0D62, 0x0D63, Mn),
+		(0x0D81, 0x0D81, Mn), (0x0D82, 0x0D83, Mc), (0x0DCA, 0x0DCA, Mn), (0x0DCF, 0x0DD1, Mc),
+		(0x0DD2, 0x0DD4, Mn), (0x0DD6, 0x0DD6, Mn), (0x0DD8, 0x0DDF, Mc), (0x0DF2, 0x0DF3, Mc),
+		(0x0E31, 0x0E31, Mn), (0x0E34, 0x0E3A, Mn), (0x0E47, 0x0E4E, Mn), (0x0EB1, 0x0EB1, Mn),
+		(0x0EB4, 0x0EBC, Mn), (0x0EC8, 0x0ECE, Mn), (0x0F18, 0x0F19, Mn), (0x0F35, 0x0F35, Mn),
+		(0x0F37, 0x0F37, Mn), (0x0F39, 0x0F39, Mn), (0x0F3E, 0x0F3F, Mc), (0x0F71, 0x0F7E, Mn),
+		(0x0F7F, 0x0F7F, Mc), (0x0F80, 0x0F84, Mn), (0x0F86, 0x0F87, Mn), (0x0F8D, 0x0F97, Mn),
+		(0x0F99, 0x0FBC, Mn), (0x0FC6, 0x0FC6, Mn), (0x102B, 0x102C, Mc), (0x102D, 0x1030, Mn),
+		(0x1031, 0x1031, Mc), (0x1032, 0x1037, Mn), (0x1038, 0x1038, Mc), (0x1039, 0x103A, Mn),
+		(0x103B, 0x103C, Mc), (0x103D, 0x103E, Mn), (0x1056, 0x1057, Mc), (0x1058, 0x1059, Mn),
+		(0x105E, 0x1060, Mn), (0x1062, 0x1064, Mc), (0x1067, 0x106D, Mc), (0x1071, 0x1074, Mn),
+		(0x1082, 0x1082, Mn), (0x1083, 0x1084, Mc), (0x1085, 0x1086, Mn), (0x1087, 0x108C, Mc),
+		(0x108D, 0x108D, Mn), (0x108F, 0x108F, Mc), (0x109A, 0x109C, Mc), (0x109D, 0x109D, Mn),
+		(0x135D, 0x135F, Mn), (0x1712, 0x1714, Mn), (0x1715, 0x1715, Mc), (0x1732, 0x1733, Mn),
+		(0x1734, 0x1734, Mc), (0x1752, 0x1753, Mn), (0x1772, 0x1773, Mn), (0x17B4, 0x17B5, Mn),
+		(0x17B6, 0x17B6, Mc), (0x17B7, 0x17BD, Mn), (0x17BE, 0x17C5, Mc), (0x17C6, 0x17C6, Mn),
+		(0x17C7, 0x17C8, Mc), (0x17C9, 0x17D3, Mn), (0x17DD, 0x17DD, Mn), (0x180B, 0x180D, Mn),
+		(0x180E, 0x180E, Cf), (0x180F, 0x180F, Mn), (0x1885, 0x1886, Mn), (0x18A9, 0x18A9, Mn),
+		(0x1920, 0x1922, Mn), (0x1923, 0x1926, Mc), (0x1927, 0x1928, Mn), (0x1929, 0x192B, Mc),
+		(0x1930, 0x1931, Mc), (0x1932, 0x1932, Mn), (0x1933, 0x1938, Mc), (0x1939, 0x193B, Mn),
+		(0x1A17, 0x1A18, Mn), (0x1A19, 0x1A1A, Mc), (0x1A1B, 0x1A1B, Mn), (0x1A55, 0x1A55, Mc),
+		(0x1A56, 0x1A56, Mn), (0x1A57, 0x1A57, Mc), (0x1A58, 0x1A5E, Mn), (0x1A60, 0x1A60, Mn),
+		(0x1A61, 0x1A61, Mc), (0x1A62, 0x1A62, Mn), (0x1A63, 0x1A64, Mc), (0x1A65, 0x1A6C, Mn),
+		(0x1A6D, 0x1A72, Mc), (0x1A73, 0x1A7C, Mn), (0x1A7F, 0x1A7F, Mn), (0x1AB0, 0x1ABD, Mn),
+		(0x1ABE, 0x1ABE, Me), (0x1ABF, 0x1ACE, Mn), (0x1B00, 0x1B03, Mn), (0x1B04, 0x1B04, Mc),
+		(0x1B34, 0x1B34, Mn), (0x1B35, 0x1B35, Mc), (0x1B36, 0x1B3A, Mn), (0x1B3B, 0x1B3B, Mc),
+		(0x1B3C, 0x1B3C, Mn), (0x1B3D, 0x1B41, Mc), (0x1B42, 0x1B42, Mn), (0x1B43, 0x1B44, Mc),
+		(0x1B6B, 0x1B73, Mn), (0x1B80, 0x1B81, Mn), (0x1B82, 0x1B82, Mc), (0x1BA1, 0x1BA1, Mc),
+		(0x1BA2, 0x1BA5, Mn), (0x1BA6, 0x1BA7, Mc), (0x1BA8, 0x1BA9, Mn), (0x1BAA, 0x1BAA, Mc),
+		(0x1BAB, 0x1BAD, Mn), (0x1BE6, 0x1BE6, Mn), (0x1BE7, 0x1BE7, Mc), (0x1BE8, 0x1BE9, Mn),
+		(0x1BEA, 0x1BEC, Mc), (0x1BED, 0x1BED, Mn), (0x1BEE, 0x1BEE, Mc), (0x1BEF, 0x1BF1, Mn),
+		(0x1BF2, 0x1BF3, Mc), (0x1C24, 0x1C2B, Mc), (0x1C2C, 0x1C33, Mn), (0x1C34, 0x1C35, Mc),
+		(0x1C36, 0x1C37, Mn), (0x1CD0, 0x1CD2, Mn), (0x1CD4, 0x1CE0, Mn), (0x1CE1, 0x1CE1, Mc),
+		(0x1CE2, 0x1CE8, Mn), (0x1CED, 0x1CED, Mn), (0x1CF4, 0x1CF4, Mn), (0x1CF7, 0x1CF7, Mc),
+		(0x1CF8, 0x1CF9, Mn), (0x1DC0, 0x1DFF, Mn), (0x200B, 0x200F, Cf), (0x202A, 0x202E, Cf),
+		(0x2060, 0x2064, Cf), (0x2066, 0x206F, Cf), (0x20D0, 0x20DC, Mn), (0x20DD, 0x20E0, Me),
+		(0x20E1, 0x20E1, Mn), (0x20E2, 0x20E4, Me), (0x20E5, 0x20F0, Mn), (0x2CEF, 0x2CF1, Mn),
+		(0x2D7F, 0x2D7F, Mn), (0x2DE0, 0x2DFF, Mn), (0x302A, 0x302D, Mn), (0x302E, 0x302F, Mc),
+		(0x3099, 0x309A, Mn), (0xA66F, 0xA66F, Mn), (0xA670, 0xA672, Me), (0xA674, 0xA67D, Mn),
+		(0xA69E, 0xA69F, Mn), (0xA6F0, 0xA6F1, Mn), (0xA802, 0xA802, Mn), (0xA806, 0xA806, Mn),
+		(0xA80B, 0xA80B, Mn), (0xA823, 0xA824, Mc), (0xA825, 0xA826, Mn), (0xA827, 0xA827, Mc),
+		(0xA82C, 0xA82C, Mn), (0xA880, 0xA881, Mc), (0xA8B4, 0xA8C3, Mc), (0xA8C4, 0xA8C5, Mn),
+		(0xA8E0, 0xA8F1, Mn), (0xA8FF, 0xA8FF, Mn), (0xA926, 0xA92D, Mn), (0xA947, 0xA951, Mn),
+		(0xA952, 0xA953, Mc), (0xA980, 0xA982, Mn), (0xA983, 0xA983, Mc), (0xA9B3, 0xA9B3, Mn),
+		(0xA9B4, 0xA9B5, Mc), (0xA9B6, 0xA9B9, Mn), (0xA9BA, 0xA9BB, Mc), (0xA9BC, 0xA9BD, Mn),
+		(0xA9BE, 0xA9C0, Mc), (0xA9E5, 0xA9E5, Mn), (0xAA29, 0xAA2E, Mn), (0xAA2F, 0xAA30, Mc),
+		(0xAA31, 0xAA32, Mn), (0xAA33, 0xAA34, Mc), (0xAA35, 0xAA36, Mn), (0xAA43, 0xAA43, Mn),
+		(0xAA4C, 0xAA4C, Mn), (0xAA4D, 0xAA4D, Mc), (0xAA7B, 0xAA7B, Mc), (0xAA7C, 0xAA7C, Mn),
+		(0xAA7D, 0xAA7D, Mc), (0xAAB0, 0xAAB0, Mn), (0xAAB2, 0xAAB4, Mn), (0xAAB7, 0xAAB8, Mn),
+		(0xAABE, 0xAABF, Mn), (0xAAC1, 0xAAC1, Mn), (0xAAEB, 0xAAEB, Mc), (0xAAEC, 0xAAED, Mn),
+		(0xAAEE, 0xAAEF, Mc), (0xAAF5, 0xAAF5, Mc), (0xAAF6, 0xAAF6, Mn), (0xABE3, 0xABE4, Mc),
+		(0xABE5, 0xABE5, Mn), (0xABE6, 0xABE7, Mc), (0xABE8, 0xABE8, Mn), (0xABE9, 0xABEA, Mc),
+		(0xABEC, 0xABEC, Mc), (0xABED, 0xABED, Mn), (0xFB1E, 0xFB1E, Mn), (0xFE00, 0xFE0F, Mn),
+		(0xFE20, 0xFE2F, Mn), (0xFEFF, 0xFEFF, Cf), (0xFFF9, 0xFFFB, Cf), (0x101FD, 0x101FD, Mn),
+		(0x102E0, 0x102E0, Mn), (0x10376, 0x1037A, Mn), (0x10A01, 0x10A03, Mn), (0x10A05, 0x10A06, Mn),
+		(0x10A0C, 0x10A0F, Mn), (0x10A38, 0x10A3A, Mn), (0x10A3F, 0x10A3F, Mn), (0x10AE5, 0x10AE6, Mn),
+		(0x10D24, 0x10D27, Mn), (0x10EAB, 0x10EAC, Mn), (0x10EFD, 0x10EFF, Mn), (0x10F46, 0x10F50, Mn),
+		(0x10F82, 0x10F85, Mn), (0x11000, 0x11000, Mc), (0x11001, 0x11001, Mn), (0x11002, 0x11002, Mc),
+		(0x11038, 0x11046, Mn), (0x11070, 0x11070, Mn), (0x11073, 0x11074, Mn), (0x1107F, 0x11081, Mn),
+		(0x11082, 0x11082, Mc), (0x110B0, 0x110B2, Mc), (0x110B3, 0x110B6, Mn), (0x110B7, 0x110B8, Mc),
+		(0x110B9, 0x110BA, Mn), (0x110BD, 0x110BD, Cf), (0x110C2, 0x110C2, Mn), (0x110CD, 0x110CD, Cf),
+		(0x11100, 0x11102, Mn), (0x11127, 0x1112B, Mn), (0x1112C, 0x1112C, Mc), (0x1112D, 0x11134, Mn),
+		(0x11145, 0x11146, Mc), (0x11173, 0x11173, Mn), (0x11180, 0x11181, Mn), (0x11182, 0x11182, Mc),
+		(0x111B3, 0x111B5, Mc), (0x111B6, 0x111BE, Mn), (0x111BF, 0x111C0, Mc), (0x111C9, 0x111CC, Mn),
+		(0x111CE, 0x111CE, Mc), (0x111CF, 0x111CF, Mn), (0x1122C, 0x1122E, Mc), (0x1122F, 0x11231, Mn),
+		(0x11232, 0x11233, Mc), (0x11234, 0x11234, Mn), (0x11235, 0x11235, Mc), (0x11236, 0x11237, Mn),
+		(0x1123E, 0x1123E, Mn), (0x11241, 0x11241, Mn), (0x112DF, 0x112DF, Mn), (0x112E0, 0x112E2, Mc),
+		(0x112E3, 0x112EA, Mn), (0x11300, 0x11301, Mn), (0x11302, 0x11303, Mc), (0x1133B, 0x1133C, Mn),
+		(0x1133E, 0x1133F, Mc), (0x11340, 0x11340, Mn), (0x11341, 0x11344, Mc), (0x11347, 0x11348, Mc),
+		(0x1134B, 0x1134D, Mc), (0x11357, 0x11357, Mc), (0x11362, 0x11363, Mc), (0x11366, 0x1136C, Mn),
+		(0x11370, 0x11374, Mn), (0x11435, 0x11437, Mc), (0x11438, 0x1143F, Mn), (0x11440, 0x11441, Mc),
+		(0x11442, 0x11444, Mn), (0x11445, 0x11445, Mc), (0x11446, 0x11446, Mn), (0x1145E, 0x1145E, Mn),
+		(0x114B0, 0x114B2, Mc), (0x114B3, 0x114B8, Mn), (0x114B9, 0x114B9, Mc), (0x114BA, 0x114BA, Mn),
+		(0x114BB, 0x114BE, Mc), (0x114BF, 0x114C0, Mn), (0x114C1, 0x114C1, Mc), (0x114C2, 0x114C3, Mn),
+		(0x115AF, 0x115B1, Mc), (0x115B2, 0x115B5, Mn), (0x115B8, 0x115BB, Mc), (0x115BC, 0x115BD, Mn),
+		(0x115BE, 0x115BE, Mc), (0x115BF, 0x115C0, Mn), (0x115DC, 0x115DD, Mn), (0x11630, 0x11632, Mc),
+		(0x11633, 0x1163A, Mn), (0x1163B, 0x1163C, Mc), (0x1163D, 0x1163D, Mn), (0x1163E, 0x1163E, Mc),
+		(0x1163F, 0x11640, Mn), (0x116AB, 0x116AB, Mn), (0x116AC, 0x116AC, Mc), (0x116AD, 0x116AD, Mn),
+		(0x116AE, 0x116AF, Mc), (0x116B0, 0x116B5, Mn), (0x116B6, 0x116B6, Mc), (0x116B7, 0x116B7, Mn),
+		(0x1171D, 0x1171F, Mn), (0x11720, 0x11721, Mc), (0x11722, 0x11725, Mn), (0x11726, 0x11726, Mc),
+		(0x11727, 0x1172B, Mn), (0x1182C, 0x1182E, Mc), (0x1182F, 0x11837, Mn), (0x11838, 0x11838, Mc),
+		(0x11839, 0x1183A, Mn), (0x11930, 0x11935, Mc), (0x11937, 0x11938, Mc), (0x1193B, 0x1193C, Mn),
+		(0x1193D, 0x1193D, Mc), (0x1193E, 0x1193E, Mn), (0x11940, 0x11940, Mc), (0x11942, 0x11942, Mc),
+		(0x11943, 0x11943, Mn), (0x119D1, 0x119D3, Mc), (0x119D4, 0x119D7, Mn), (0x119DA, 0x119DB, Mn),
+		(0x119DC, 0x119DF, Mc), (0x119E0, 0x119E0, Mn), (0x119E4, 0x119E4, Mc), (0x11A01, 0x11A0A, Mn),
+		(0x11A33, 0x11A38, Mn), (0x11A39, 0x11A39, Mc), (0x11A3B, 0x11A3E, Mn), (0x11A47, 0x11A47, Mn),
+		(0x11A51, 0x11A56, Mn), (0x11A57, 0x11A58, Mc), (0x11A59, 0x11A5B, Mn), (0x11A8A, 0x11A96, Mn),
+		(0x11A97, 0x11A97, Mc), (0x11A98, 0x11A99, Mn), (0x11C2F, 0x11C2F, Mc), (0x11C30, 0x11C36, Mn),
+		(0x11C38, 0x11C3D, Mn), (0x11C3E, 0x11C3E, Mc), (0x11C3F, 0x11C3F, Mn), (0x11C92, 0x11CA7, Mn),
+		(0x11CA9, 0x11CA9, Mc), (0x11CAA, 0x11CB0, Mn), (0x11CB1, 0x11CB1, Mc), (0x11CB2, 0x11CB3, Mn),
+		(0x11CB4, 0x11CB4, Mc), (0x11CB5, 0x11CB6, Mn), (0x11D31, 0x11D36, Mn), (0x11D3A, 0x11D3A, Mn),
+		(0x11D3C, 0x11D3D, Mn), (0x11D3F, 0x11D45, Mn), (0x11D47, 0x11D47, Mn), (0x11D8A, 0x11D8E, Mc),
+		(0x11D90, 0x11D91, Mn), (0x11D93, 0x11D94, Mc), (0x11D95, 0x11D95, Mn), (0x11D96, 0x11D96, Mc),
+		(0x11D97, 0x11D97, Mn), (0x11EF3, 0x11EF4, Mn), (0x11EF5, 0x11EF6, Mc), (0x11F00, 0x11F01, Mn),
+		(0x11F03, 0x11F03, Mc), (0x11F34, 0x11F35, Mc), (0x11F36, 0x11F3A, Mn), (0x11F3E, 0x11F3F, Mc),
+		(0x11F40, 0x11F40, Mn), (0x11F41, 0x11F41, Mc), (0x11F42, 0x11F42, Mn), (0x13430, 0x1343F, Cf),
+		(0x13440, 0x13440, Mn), (0x13447, 0x13455, Mn), (0x16AF0, 0x16AF4, Mn), (0x16B30, 0x16B36, Mn),
+		(0x16F4F, 0x16F4F, Mn), (0x16F51, 0x16F87, Mc), (0x16F8F, 0x16F92, Mn), (0x16FE4, 0x16FE4, Mn),
+		(0x16FF0, 0x16FF1, Mc), (0x1BC9D, 0x1BC9E, Mn), (0x1BCA0, 0x1BCA3, Cf), (0x1CF00, 0x1CF2D, Mn),
+		(0x1CF30, 0x1CF46, Mn), (0x1D165, 0x1D166, Mc), (0x1D167, 0x1D169, Mn), (0x1D16D, 0x1D172, Mc),
+		(0x1D173, 0x1D17A, Cf), (0x1D17B, 0x1D182, Mn), (0x1D185, 0x1D18B, Mn), (0x1D1AA, 0x1D1AD, Mn),
+		(0x1D242, 0x1D244, Mn), (0x1DA00, 0x1DA36, Mn), (0x1DA3B, 0x1DA6C, Mn), (0x1DA75, 0x1DA75, Mn),
+		(0x1DA84, 0x1DA84, Mn), (0x1DA9B, 0x1DA9F, Mn), (0x1DAA1, 0x1DAAF, Mn), (0x1E000, 0x1E006, Mn),
+		(0x1E008, 0x1E018, Mn), (0x1E01B, 0x1E021, Mn), (0x1E023, 0x1E024, Mn), (0x1E026, 0x1E02A, Mn),
+		(0x1E08F, 0x1E08F, Mn), (0x1E130, 0x1E136, Mn), (0x1E2AE, 0x1E2AE, Mn), (0x1E2EC, 0x1E2EF, Mn),
+		(0x1E4EC, 0x1E4EF, Mn), (0x1E8D0, 0x1E8D6, Mn), (0x1E944, 0x1E94A, Mn), (0xE0001, 0xE0001, Cf),
+		(0xE0020, 0xE007F, Cf), (0xE0100, 0xE01EF, Mn),
+	];
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn check_ranges<T>(table: &[(u32, u32, T)]) {
+		assert!(table.iter().all(|&(first, last, _)| first <= last));
+		assert!(table.windows(2).all(|w| w[0].1 < w[1].0));
+	}
+	
+	#[test]
+	fn sorted() {
+		check_ranges(bidi::CLASSES);
+		check_ranges(line_break::CLASSES);
+		check_ranges(script::SCRIPTS);
+		check_ranges(shaping::JOINING_TYPES);
+		check_ranges(shaping::CATEGORIES);
+		assert!(bidi::BRACKETS.windows(2).all(|w| w[0].0 < w[1].0));
+		assert!(bidi::MIRRORS.windows(2).all(|w| w[0].0 < w[1].0));
+	}
+}
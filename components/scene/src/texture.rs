@@ -20,7 +20,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use super::*;
+use {
+	super::*,
+	engine_core::BoxedFuture,
+	file_formats::ktx2::Ktx2Texture,
+	vk::VkFormat,
+	std::{io, sync::Arc}
+};
 
 pub type Texture = dyn Source<(TextureDescriptor, Box<dyn TextureReader>)>;
 
@@ -35,3 +41,59 @@ pub struct TextureDescriptor {
 	pub levels: usize,
 	pub cube:   bool
 }
+
+/// Converts a format number as stored in KTX2 files, only core formats are supported.
+pub fn vk_format(format: u32) -> Option<VkFormat> {
+	match format {
+		// the core formats are numbered contiguously
+		1..=184 => {
+			if cfg!(feature = "trace-unsafe") { log::trace!("TRACE-UNSAFE") }
+			Some(unsafe { std::mem::transmute::<u32, VkFormat>(format) })
+		}
+		_ => None
+	}
+}
+
+/// A texture read from a KTX2 file, the levels are decompressed and stored base level first.
+#[derive(Clone, Debug)]
+pub struct Ktx2TextureSource {
+	pub descriptor: TextureDescriptor,
+	pub data:       Arc<[u8]>
+}
+
+impl Ktx2TextureSource {
+	pub fn new(texture: &Ktx2Texture) -> io::Result<Self> {
+		let header = &texture.header;
+		let format = vk_format(header.format)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "unsupported texture format"))?;
+		
+		Ok(Self {
+			descriptor: TextureDescriptor {
+				extent: Vec3(
+					header.pixel_width as usize,
+					header.pixel_height.max(1) as usize,
+					header.pixel_depth.max(1) as usize
+				),
+				format,
+				layers: (header.number_of_array_elements.max(1) * header.number_of_faces.max(1)) as usize,
+				levels: header.levels(),
+				cube:   header.number_of_faces == 6
+			},
+			data: texture.levels.concat().into()
+		})
+	}
+	
+	pub fn read(reader: impl io::Read + io::Seek) -> io::Result<Self> {
+		Self::new(&Ktx2Texture::read(reader)?)
+	}
+}
+
+impl Source<(TextureDescriptor, Box<dyn TextureReader>)> for Ktx2TextureSource {
+	fn open(&self, _read: bool, _write: bool) -> BoxedFuture<io::Result<(TextureDescriptor, Box<dyn TextureReader>)>> {
+		Box::pin(async move {
+			Ok((self.descriptor, Box::new(io::Cursor::new(self.data.clone())) as _))
+		})
+	}
+}
+
+impl TextureReader for io::Cursor<Arc<[u8]>> {}
@@ -20,7 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use {super::*, std::alloc::Allocator};
+use {super::*, engine_core::Time, std::alloc::Allocator};
 
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Transform {
@@ -48,6 +48,15 @@ impl Transform {
 		self.scaling = scaling;
 		self
 	}
+	
+	/// Interpolates between `self` and `other`, `alpha` is usually `Time::alpha`.
+	pub fn interpolate(self, other: Self, alpha: f32) -> Self {
+		Self {
+			translation: Vec3::from(alpha).mix(self.translation, other.translation),
+			rotation:    self.rotation.slerp(other.rotation, alpha),
+			scaling:     self.scaling + (other.scaling - self.scaling) * alpha
+		}
+	}
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
@@ -56,9 +65,16 @@ pub struct LocalTransform(pub Mat4<f32>);
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct GlobalTransform(pub Mat4<f32>);
 
+/// The transform of the previous simulation tick, entities with this component are rendered
+/// with a transform interpolated between the last two ticks.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PreviousTransform(pub Transform);
+
+/// Change of a transform per second.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Velocity(pub Transform);
 
+/// Change of a velocity per second.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Acceleration(pub Transform);
 
@@ -100,16 +116,48 @@ pub struct OrthographicSymmetricCamera {
 	pub planes: Vec2<f32>
 }
 
-pub fn update_velocity(velocity: &mut Velocity, acceleration: &Acceleration) {
-	velocity.0.translation += acceleration.0.translation;
-	velocity.0.scaling += acceleration.0.scaling;
-	velocity.0.rotation.rotate(acceleration.0.rotation);
+/// Applies the fraction `dt` of the rotation `velocity` to `rotation`.
+fn integrate_rotation(rotation: Quat32, velocity: Quat32, dt: f32) -> Quat32 {
+	let axis = Vec3(velocity.0, velocity.1, velocity.2);
+	match axis.len2() <= f32::EPSILON {
+		true  => rotation,
+		false => rotation.rotate_axis(axis, 2.0 * velocity.3.clamp(-1.0, 1.0).acos() * dt)
+	}
+}
+
+pub fn update_velocity(velocity: &mut Velocity, acceleration: &Acceleration, time: &Time) {
+	let dt = time.tick_delta_secs();
+	velocity.0.translation += acceleration.0.translation * dt;
+	velocity.0.scaling += acceleration.0.scaling * dt;
+	velocity.0.rotation = integrate_rotation(velocity.0.rotation, acceleration.0.rotation, dt);
+}
+
+pub fn update_transform(transform: &mut Transform, velocity: &Velocity, time: &Time) {
+	let dt = time.tick_delta_secs();
+	transform.translation += velocity.0.translation * dt;
+	transform.scaling += velocity.0.scaling * dt;
+	transform.rotation = integrate_rotation(transform.rotation, velocity.0.rotation, dt);
+}
+
+/// Must run before each simulation tick, so that `PreviousTransform` holds the state of the
+/// last tick when the tick has completed.
+pub fn store_previous_transform(transform: &Transform, previous: &mut PreviousTransform) {
+	previous.0 = *transform;
 }
 
-pub fn update_transform(transform: &mut Transform, velocity: &Velocity) {
-	transform.translation += velocity.0.translation;
-	transform.scaling += velocity.0.scaling;
-	transform.rotation.rotate(velocity.0.rotation);
+/// Replaces `update_local_transform` for entities with a `PreviousTransform`, runs every frame.
+pub fn interpolate_local_transform(
+	transform:       &Transform,
+	previous:        &PreviousTransform,
+	local_transform: &mut LocalTransform,
+	time:            &Time
+) {
+	let transform = previous.0.interpolate(*transform, time.alpha);
+	local_transform.0 = Mat4::from_transform(
+		transform.translation,
+		transform.rotation,
+		Vec3::from(transform.scaling)
+	);
 }
 
 pub fn update_local_transform(transform: Mutated<Transform>, local_transform: &mut LocalTransform) {
@@ -130,14 +178,130 @@ pub fn update_global_transform<'a, A: Allocator + Clone>(
 	parent_query: &'a mut CachedQuery<&'a GlobalTransform, A>
 ) {
 	global.0 = match parent {
-		Some(parent) => parent_query.get(parent.0).unwrap().0 * local.0,
+		Some(parent) => mul(&parent_query.get(parent.0).unwrap().0, &local.0),
 		None => local.0
 	}
 }
 
+/// Computes the `JointsGlobalTransform` of a skinned entity from its `GlobalTransform`, its
+/// `Joints` and its `JointsInverseBindMatrices`, must run after `update_global_transform`.
+pub fn update_joints_global_transform(
+	global:                &GlobalTransform,
+	joints:                &Joints,
+	inverse_bind_matrices: &JointsInverseBindMatrices,
+	joints_global:         &mut JointsGlobalTransform
+) {
+	assert_eq!(joints.0.len(), inverse_bind_matrices.0.len(), "joint and inverse bind matrix counts differ");
+	
+	let mut transforms = vec![None; joints.0.len()];
+	joints_global.0.clear();
+	joints_global.0.extend((0..joints.0.len()).map(|i| mul(
+		&mul(&global.0, &joint_transform(&joints.0, &mut transforms, i)),
+		&inverse_bind_matrices.0[i]
+	)));
+}
+
+/// The transform of joint `i` relative to the skeleton root, parents may come after their children.
+fn joint_transform(joints: &[(u32, Transform)], transforms: &mut [Option<Mat4<f32>>], i: usize) -> Mat4<f32> {
+	if let Some(transform) = transforms[i] {
+		return transform;
+	}
+	
+	let (parent, transform) = joints[i];
+	let local = Mat4::from_transform(transform.translation, transform.rotation, Vec3::from(transform.scaling));
+	let transform = match parent {
+		u32::MAX => local,
+		parent => mul(&joint_transform(joints, transforms, parent as usize), &local)
+	};
+	
+	transforms[i] = Some(transform);
+	transform
+}
+
+impl JointsGlobalTransform {
+	/// Transforms the positions `src` of vertices that are bound to `joint` only into world space.
+	pub fn transform_positions(&self, joint: usize, src: &[Vec3<f32>], dst: &mut [Vec3<f32>]) {
+		#[cfg(feature = "simd")]
+		self.0[joint].transform_pos_batch_simd(src, dst);
+		#[cfg(not(feature = "simd"))]
+		self.0[joint].transform_pos_batch(src, dst);
+	}
+}
+
+/// `a * b`, with SIMD if the `simd` feature is enabled.
+fn mul(a: &Mat4<f32>, b: &Mat4<f32>) -> Mat4<f32> {
+	#[cfg(feature = "simd")]
+	return a.mul_simd(b);
+	#[cfg(not(feature = "simd"))]
+	return *a * *b;
+}
+
 pub fn propagate_parent_removes(entity: Removed<Entity>, children: &ChildrenAccelStorage, world: &World) {
 	children.for_each_child(entity.0, &mut |child, _| {
 		world.remove_component::<Parent>(child);
 		false
 	})
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, core::f32::consts::FRAC_PI_2, std::time::Duration};
+	
+	fn spin(velocity: Quat32, expected: Quat32) {
+		let time = Time::new(Duration::from_secs(1) / 60);
+		let velocity = Velocity(Transform::new().rotation(velocity));
+		let mut transform = Transform::new().rotation(Vec4(0.0, 0.0, 0.0, 1.0));
+		
+		for _ in 0..60 {
+			update_transform(&mut transform, &velocity, &time);
+		}
+		
+		assert!((transform.rotation - expected).len() < 1e-4, "{:?} != {:?}", transform.rotation, expected);
+		assert!((transform.rotation.len() - 1.0).abs() < 1e-4);
+	}
+	
+	#[test]
+	fn integrate_rotation_y() {
+		spin(Quat32::from_rotation_y(FRAC_PI_2), Quat32::from_rotation_y(FRAC_PI_2));
+	}
+	
+	#[test]
+	fn integrate_rotation_z() {
+		spin(Quat32::from_rotation_z(FRAC_PI_2), Quat32::from_rotation_z(FRAC_PI_2));
+	}
+	
+	fn matrix(transform: Transform) -> Mat4<f32> {
+		Mat4::from_transform(transform.translation, transform.rotation, Vec3::from(transform.scaling))
+	}
+	
+	#[test]
+	fn joints_global_transform() {
+		let root = Transform::new().translation(Vec3(1.0, 2.0, 3.0)).rotation(Quat32::from_rotation_y(0.5)).scaling(2.0);
+		let child = Transform::new().translation(Vec3(0.0, 1.5, 0.0)).rotation(Quat32::from_rotation_x(0.25)).scaling(0.75);
+		let global = GlobalTransform(matrix(Transform::new().translation(Vec3(-4.0, 0.5, 1.0)).rotation(Quat32::from_rotation_y(-1.0)).scaling(3.0)));
+		let inverse_bind_matrices = [!(matrix(root) * matrix(child)), !matrix(root)];
+		
+		// the child joint comes before its parent
+		let mut joints_global = JointsGlobalTransform::default();
+		update_joints_global_transform(
+			&global,
+			&Joints(vec![(1, child), (u32::MAX, root)]),
+			&JointsInverseBindMatrices(inverse_bind_matrices.to_vec()),
+			&mut joints_global
+		);
+		
+		// the SIMD path gives bit-identical results
+		let expected = [
+			global.0 * (matrix(root) * matrix(child)) * inverse_bind_matrices[0],
+			global.0 * matrix(root) * inverse_bind_matrices[1]
+		];
+		assert_eq!(joints_global.0, expected);
+		
+		let src = [Vec3(0.0, 0.0, 0.0), Vec3(1.0, -2.0, 0.5), Vec3(0.25, 3.0, -1.0), Vec3(-7.0, 0.125, 2.0), Vec3(5.0, 5.0, 5.0)];
+		for (joint, matrix) in expected.iter().enumerate() {
+			let mut dst = [Vec3::default(); 5];
+			joints_global.transform_positions(joint, &src, &mut dst);
+			assert_eq!(dst, src.map(|pos| matrix.transform_pos(pos)));
+		}
+	}
+}
@@ -130,7 +130,9 @@ pub struct UiDimensions {
 pub enum UiHorzAlign {
 	Right,
 	Center,
-	Left
+	Left,
+	/// Aligns left and stretches the spaces of wrapped lines to fill the width, only for text.
+	Justify
 }
 
 impl Default for UiHorzAlign {
@@ -142,9 +144,10 @@ impl Default for UiHorzAlign {
 impl Into<f32> for UiHorzAlign {
 	fn into(self) -> f32 {
 		match self {
-			Self::Right  => -1f32,
-			Self::Center => 0f32,
-			Self::Left   => 1f32,
+			Self::Right   => -1f32,
+			Self::Center  => 0f32,
+			Self::Left    => 1f32,
+			Self::Justify => 1f32,
 		}
 	}
 }
@@ -199,16 +202,109 @@ impl Default for UiText {
 	}
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct FmtText(Vec<(TextCtrl, String)>);
+/// Formatted text, a sequence of runs with their own style. Can be parsed from ANSI escape
+/// sequences with [`FmtText::from_ansi`] or from markup with [`FmtText::from_markup`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FmtText {
+	runs:  Vec<(TextCtrl, String)>,
+	links: Vec<String>
+}
+
+impl FmtText {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	/// Appends text, it is merged with the last run if the style is the same. Empty text is
+	/// only added if the run clears the display.
+	pub fn push(&mut self, ctrl: TextCtrl, text: &str) {
+		match self.runs.last_mut() {
+			_ if text.is_empty() && ctrl.clear.is_none() => (),
+			Some((last, last_text)) if *last == ctrl && ctrl.clear.is_none() => last_text.push_str(text),
+			_ => self.runs.push((ctrl, text.to_string()))
+		}
+	}
+	
+	/// Adds the target of a link and returns the index to use for [`TextCtrl::link`].
+	pub fn add_link(&mut self, target: &str) -> usize {
+		self.links.push(target.to_string());
+		self.links.len() - 1
+	}
+	
+	pub fn runs(&self) -> &[(TextCtrl, String)] {
+		&self.runs
+	}
+	
+	pub fn link(&self, index: usize) -> Option<&str> {
+		self.links.get(index).map(String::as_str)
+	}
+	
+	/// Returns the runs with their byte range in the plain text, which is the concatenation
+	/// of all runs and returned by `to_string`.
+	pub fn spans(&self) -> impl Iterator<Item = (std::ops::Range<usize>, &TextCtrl)> {
+		self.runs.iter().scan(0, |offset, (ctrl, text)| {
+			let start = *offset;
+			*offset += text.len();
+			Some((start..*offset, ctrl))
+		})
+	}
+	
+	/// Returns the index of the run that contains the byte offset of the plain text.
+	pub fn run_at(&self, offset: usize) -> Option<usize> {
+		self.spans().position(|(range, _)| range.contains(&offset))
+	}
+	
+	pub fn is_empty(&self) -> bool {
+		self.runs.iter().all(|(_, text)| text.is_empty())
+	}
+}
+
+impl std::fmt::Display for FmtText {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.runs.iter().try_for_each(|(_, text)| f.write_str(text))
+	}
+}
 
+/// The style of a run of formatted text, modeled after the SGR parameters of terminals.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct TextCtrl {
-	reset:    bool,
-	effects:  usize,
-	color_fg: Vec4<f64>,
-	color_bg: Vec4<f64>,
-	alt_font: usize
+	/// The part of the line or display to clear before the text is written.
+	pub clear:    Option<ClearMode>,
+	/// The effects, a combination of the `TextCtrl::BOLD`, ... flags.
+	pub effects:  usize,
+	/// The foreground color, the default color of the text if `None`.
+	pub color_fg: Option<Vec4<f64>>,
+	/// The background color, no background if `None`.
+	pub color_bg: Option<Vec4<f64>>,
+	/// The alternate font, `0` is the primary font.
+	pub alt_font: usize,
+	/// The index of the link target in the [`FmtText`].
+	pub link:     Option<usize>
+}
+
+impl TextCtrl {
+	pub const BOLD:             usize = 0x1;
+	pub const FAINT:            usize = 0x2;
+	pub const ITALIC:           usize = 0x4;
+	pub const UNDERLINE:        usize = 0x8;
+	pub const DOUBLE_UNDERLINE: usize = 0x10;
+	pub const BLINK:            usize = 0x20;
+	pub const RAPID_BLINK:      usize = 0x40;
+	pub const INVERSE:          usize = 0x80;
+	pub const CONCEAL:          usize = 0x100;
+	pub const STRIKETHROUGH:    usize = 0x200;
+	pub const OVERLINE:         usize = 0x400;
+	
+	pub fn has(&self, effects: usize) -> bool {
+		self.effects & effects != 0
+	}
+	
+	pub fn set(&mut self, effects: usize, v: bool) {
+		match v {
+			true =>  self.effects |= effects,
+			false => self.effects &= !effects
+		}
+	}
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -273,7 +369,7 @@ impl UiTransformCache {
 		self.points          = 0f32;
 	}
 	
-	fn to_norm(&self, val: Val) -> f32 {
+	pub(crate) fn to_norm(&self, val: Val) -> f32 {
 		match val {
 			Val::Norm(v)    => v,
 			Val::VWidth(v)  => v * self.viewport_width,
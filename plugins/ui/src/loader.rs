@@ -22,7 +22,7 @@
 
 use {
 	super::{*, scene::*},
-	std::{io, ptr},
+	std::{io, ptr, sync::Mutex},
 	engine_core::*,
 	math::*,
 	ecs::*,
@@ -274,8 +274,14 @@ impl Loader {
 			AllocatorWithLayout::new(&self.device.transfer_buffer_alloc).with_min_align(8)
 		);
 		
-		for ch in ' '..='~' {
-			let glyph: Glyph = match reader.get_glyph(ch) {
+		let mut ids = (' '..='~')
+			.filter_map(|ch| reader.glyph_index(ch))
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids.dedup();
+		
+		for &id in &ids {
+			let glyph: Glyph = match reader.get_glyph(id) {
 				Some(v) => v,
 				None    => continue
 			};
@@ -283,12 +289,8 @@ impl Loader {
 			let extent    = (glyph.metrics.bbox_max - glyph.metrics.bbox_min).map(|v| v as f32);
 			let extent_px = (extent * texel_scale).map(|v| v as u32);
 			
-			glyphs_host.insert(ch as char, HostGlyphData {
-				idx:          if extent.0 == 0f32 || extent.1 == 0f32 { -1 } else { i },
-				advance:      Vec2(glyph.metrics.advance.0 as f32 * scale, glyph.metrics.advance.1 as f32 * scale),
-				kern_advance: (' '..='~')
-					.map(|ch1| (ch1, reader.get_kerning(ch, ch1).unwrap_or(0) as f32 * scale))
-					.collect(),
+			glyphs_host.insert(id, HostGlyphData {
+				idx: if extent.0 == 0f32 || extent.1 == 0f32 { -1 } else { i }
 			});
 			
 			if extent.0 == 0f32 || extent.1 == 0f32 {
@@ -631,9 +633,8 @@ impl Loader {
 			glyphs_host,
 			glyphs_local_offset,
 			glyphs_local_len,
-			ascender:            desc.ascender as f32 * scale,
-			descender:           desc.descender as f32 * scale,
-			line_gap:            desc.line_gap as f32 * scale
+			reader:              Mutex::new(reader),
+			desc
 		}));
 		
 		log::trace!("[RESOURCES] uploaded font #{:04x} to VkBuffer#{:X} @ {:#X}-{:#X}, VkImage#{:X} with VkImageView#{:X}",
@@ -24,12 +24,12 @@
 
 use {
 	super::*,
-	std::{alloc::Allocator, collections::BTreeMap},
+	std::{alloc::Allocator, collections::BTreeMap, sync::Mutex},
 	engine_core::ResourceState,
 	gpgpu::{mem::MappedSubAlloc, misc::AllocatorWithLayout, plugins::batch::*},
 	math::*,
 	ecs::*,
-	::scene::*,
+	::scene::{*, text::{LayoutOptions, TextLayout}},
 	vec_map::VecMap
 };
 
@@ -420,47 +420,32 @@ impl SceneContext {
 	) {
 		buffer.clear();
 		if text.is_empty() { return; }
-		let mut pos = Vec2(0f32, 0f32);
-
-		for line in text.lines() {
-			let len = buffer.len();
-			let mut chars = line.chars().peekable();
-
-			while let Some(ch) = chars.next() {
-				match font.glyphs_host.get(&ch) {
-					Some(v) => {
-						if v.idx != -1 {
-							buffer.push(GlyphVertex { index: v.idx as _, pos });
-						}
-
-						pos.0 += v.advance.0 + *chars.peek()
-							.and_then(|ch| v.kern_advance.get(ch))
-							.unwrap_or(&0f32);
-					}
-					None => {
-						unimplemented!("gen on use")
-					}
-				}
-			}
-
-			let __tmp__ = buffer.len();
-			match align.horz {
-				UiHorzAlign::Left => (),
-				UiHorzAlign::Right => buffer[len..__tmp__].iter_mut()
-					.for_each(|vert| vert.pos.0 -= pos.0),
-				UiHorzAlign::Center => buffer[len..__tmp__].iter_mut()
-					.for_each(|vert| vert.pos.0 -= pos.0 * 0.5f32)
-			}
 
-			pos.0 = 0f32;
-			pos.1 += font.ascender - font.descender + font.line_gap
-		}
+		let options = LayoutOptions::new().align(align.horz);
+		let layout = TextLayout::new(
+			text,
+			&mut **font.reader.lock().expect("font reader poisoned"),
+			&font.desc,
+			&options
+		);
+
+		// the origin is on the baseline of the first line
+		let baseline = layout.lines.first().map_or(0f32, |line| line.baseline);
+		let offset = Vec2(match align.horz {
+			UiHorzAlign::Left | UiHorzAlign::Justify => 0f32,
+			UiHorzAlign::Right => -layout.size.0,
+			UiHorzAlign::Center => layout.size.0 * -0.5f32
+		}, match align.vert {
+			UiVertAlign::Top => -baseline,
+			UiVertAlign::Bottom => -baseline - layout.size.1,
+			UiVertAlign::Center => -baseline - layout.size.1 * 0.5f32
+		});
 
-		match align.vert {
-			UiVertAlign::Top => (),
-			UiVertAlign::Bottom => buffer.iter_mut().for_each(|v| v.pos.1 -= pos.1),
-			UiVertAlign::Center => buffer.iter_mut().for_each(|v| v.pos.1 -= pos.1 * 0.5f32)
-		}
+		// glyphs that are not in the atlas are skipped
+		buffer.extend(layout.glyphs()
+			.filter_map(|glyph| Some((font.glyphs_host.get(&glyph.id)?, glyph)))
+			.filter(|(data, _)| data.idx != -1)
+			.map(|(data, glyph)| GlyphVertex { index: data.idx as _, pos: glyph.pos + offset }));
 	}
 }
 
@@ -630,9 +615,7 @@ pub(crate) struct LocalSdfGenVertex {
 /// Per-glyph data required on the cpu
 #[derive(Debug, Clone)]
 pub(crate) struct HostGlyphData {
-	pub idx:          isize,
-	pub advance:      Vec2<f32>,
-	pub kern_advance: VecMap<char, f32>
+	pub idx: isize
 }
 
 /// A single char of a text in host visible memory
@@ -650,15 +633,28 @@ struct LocalInstanceData {
 	color: Vec4<f32>
 }
 
-#[derive(Debug)]
 pub(crate) struct FontData {
 	pub sdf_image:           VkImage,
 	pub sdf_view:            VkImageView,
 	pub desc_set:            VkDescriptorSet,
-	pub glyphs_host:         VecMap<char, HostGlyphData>,
+	pub glyphs_host:         VecMap<GlyphId, HostGlyphData>,
 	pub glyphs_local_offset: VkDeviceSize,
 	pub glyphs_local_len:    usize,
-	pub ascender:            f32,
-	pub descender:           f32,
-	pub line_gap:            f32
+	/// Used to shape the texts, the glyphs that are not in the atlas are skipped.
+	pub reader:              Mutex<Box<dyn FontReader>>,
+	pub desc:                FontDescriptor
+}
+
+impl std::fmt::Debug for FontData {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("FontData")
+			.field("sdf_image", &self.sdf_image)
+			.field("sdf_view", &self.sdf_view)
+			.field("desc_set", &self.desc_set)
+			.field("glyphs_host", &self.glyphs_host)
+			.field("glyphs_local_offset", &self.glyphs_local_offset)
+			.field("glyphs_local_len", &self.glyphs_local_len)
+			.field("desc", &self.desc)
+			.finish_non_exhaustive()
+	}
 }
\ No newline at end of file
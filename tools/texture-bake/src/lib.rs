@@ -0,0 +1,357 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! CPU texture processing for the asset pipeline: decoding, format conversion, mip chain
+//! generation and KTX2 export.
+
+use {
+	file_formats::{png, jpeg, ktx2::{Ktx2Texture, SupercompressionScheme}},
+	vk::VkFormat::{self, *},
+	std::io::{self, Error, ErrorKind}
+};
+
+/// The supported target formats and their names.
+pub const FORMATS: [(&str, VkFormat); 15] = [
+	("R8_UNORM",            VK_FORMAT_R8_UNORM),
+	("R8_SRGB",             VK_FORMAT_R8_SRGB),
+	("R8G8_UNORM",          VK_FORMAT_R8G8_UNORM),
+	("R8G8_SRGB",           VK_FORMAT_R8G8_SRGB),
+	("R8G8B8A8_UNORM",      VK_FORMAT_R8G8B8A8_UNORM),
+	("R8G8B8A8_SNORM",      VK_FORMAT_R8G8B8A8_SNORM),
+	("R8G8B8A8_SRGB",       VK_FORMAT_R8G8B8A8_SRGB),
+	("B8G8R8A8_UNORM",      VK_FORMAT_B8G8R8A8_UNORM),
+	("B8G8R8A8_SRGB",       VK_FORMAT_B8G8R8A8_SRGB),
+	("R16_UNORM",           VK_FORMAT_R16_UNORM),
+	("R16G16B16A16_UNORM",  VK_FORMAT_R16G16B16A16_UNORM),
+	("R16_SFLOAT",          VK_FORMAT_R16_SFLOAT),
+	("R16G16B16A16_SFLOAT", VK_FORMAT_R16G16B16A16_SFLOAT),
+	("R32_SFLOAT",          VK_FORMAT_R32_SFLOAT),
+	("R32G32B32A32_SFLOAT", VK_FORMAT_R32G32B32A32_SFLOAT)
+];
+
+/// Returns the format with the given name, the `VK_FORMAT_` prefix is optional.
+pub fn parse_format(name: &str) -> Option<VkFormat> {
+	let name = match name.get(..10) {
+		Some(prefix) if prefix.eq_ignore_ascii_case("VK_FORMAT_") => &name[10..],
+		_ => name
+	};
+	FORMATS.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, format)| *format)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Encoding {
+	Unorm8,
+	Snorm8,
+	Srgb8,
+	Unorm16,
+	Sfloat16,
+	Sfloat32
+}
+
+/// Returns the channels in memory order and the encoding of a supported format.
+fn layout(format: VkFormat) -> Option<(&'static [usize], Encoding)> {
+	const R: &[usize] = &[0];
+	const RG: &[usize] = &[0, 1];
+	const RGBA: &[usize] = &[0, 1, 2, 3];
+	const BGRA: &[usize] = &[2, 1, 0, 3];
+	
+	Some(match format {
+		VK_FORMAT_R8_UNORM            => (R, Encoding::Unorm8),
+		VK_FORMAT_R8_SRGB             => (R, Encoding::Srgb8),
+		VK_FORMAT_R8G8_UNORM          => (RG, Encoding::Unorm8),
+		VK_FORMAT_R8G8_SRGB           => (RG, Encoding::Srgb8),
+		VK_FORMAT_R8G8B8A8_UNORM      => (RGBA, Encoding::Unorm8),
+		VK_FORMAT_R8G8B8A8_SNORM      => (RGBA, Encoding::Snorm8),
+		VK_FORMAT_R8G8B8A8_SRGB       => (RGBA, Encoding::Srgb8),
+		VK_FORMAT_B8G8R8A8_UNORM      => (BGRA, Encoding::Unorm8),
+		VK_FORMAT_B8G8R8A8_SRGB       => (BGRA, Encoding::Srgb8),
+		VK_FORMAT_R16_UNORM           => (R, Encoding::Unorm16),
+		VK_FORMAT_R16G16B16A16_UNORM  => (RGBA, Encoding::Unorm16),
+		VK_FORMAT_R16_SFLOAT          => (R, Encoding::Sfloat16),
+		VK_FORMAT_R16G16B16A16_SFLOAT => (RGBA, Encoding::Sfloat16),
+		VK_FORMAT_R32_SFLOAT          => (R, Encoding::Sfloat32),
+		VK_FORMAT_R32G32B32A32_SFLOAT => (RGBA, Encoding::Sfloat32),
+		_ => return None
+	})
+}
+
+pub fn srgb_to_linear(v: f32) -> f32 {
+	if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+}
+
+pub fn linear_to_srgb(v: f32) -> f32 {
+	if v <= 0.003_130_8 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Converts to half precision, rounding to the nearest value.
+pub fn f32_to_f16(v: f32) -> u16 {
+	let bits = v.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let exp = ((bits >> 23) & 0xFF) as i32;
+	let mantissa = bits & 0x7F_FFFF;
+	
+	if exp == 0xFF {
+		return sign | 0x7C00 | if mantissa != 0 { 0x200 } else { 0 };
+	}
+	
+	let exp = exp - 127 + 15;
+	let (half, rem, halfway) = match exp {
+		31.. => return sign | 0x7C00,
+		..=-11 => return sign,
+		-10..=0 => {
+			// subnormal
+			let shift = (14 - exp) as u32;
+			let mantissa = mantissa | 0x80_0000;
+			(mantissa >> shift, mantissa & ((1 << shift) - 1), 1 << (shift - 1))
+		}
+		_ => ((exp as u32) << 10 | mantissa >> 13, mantissa & 0x1FFF, 0x1000)
+	};
+	
+	// an overflow of the mantissa correctly increments the exponent
+	sign | (half + (rem > halfway || (rem == halfway && half & 1 == 1)) as u32) as u16
+}
+
+/// An RGBA image with floating point channels, gray images are expanded to RGB and missing
+/// alpha channels are set to one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+	pub width:  usize,
+	pub height: usize,
+	/// Whether the color channels are sRGB encoded, alpha is always linear.
+	pub srgb:   bool,
+	pub pixels: Vec<[f32; 4]>
+}
+
+impl Image {
+	/// Decodes a PNG or JPEG file. Images are assumed to be sRGB encoded, unless a PNG file
+	/// specifies a gamma of 1.0.
+	pub fn decode(data: &[u8]) -> io::Result<Self> {
+		let (width, height, channels, max, samples, srgb) = if data.starts_with(&png::SIGNATURE) {
+			let image = png::decode(data)?;
+			let samples = match image.bit_depth {
+				16 => image.data.chunks_exact(2).map(|s| u16::from_le_bytes([s[0], s[1]]) as f32).collect::<Vec<_>>(),
+				_  => image.data.iter().map(|s| *s as f32).collect()
+			};
+			let max = if image.bit_depth == 16 { 65535.0 } else { 255.0 };
+			let srgb = image.gamma.is_none_or(|gamma| (gamma - 1.0).abs() > 0.01);
+			(image.width, image.height, image.channels, max, samples, srgb)
+		} else if data.starts_with(&[0xFF, 0xD8]) {
+			let image = jpeg::decode(data)?;
+			let samples = image.data.iter().map(|s| *s as f32).collect();
+			(image.width, image.height, image.channels, 255.0, samples, true)
+		} else {
+			return Err(Error::new(ErrorKind::InvalidData, "unknown image format, expected PNG or JPEG"));
+		};
+		
+		let pixels = samples.chunks_exact(channels).map(|s| match s.len() {
+			1 => [s[0] / max, s[0] / max, s[0] / max, 1.0],
+			2 => [s[0] / max, s[0] / max, s[0] / max, s[1] / max],
+			3 => [s[0] / max, s[1] / max, s[2] / max, 1.0],
+			_ => [s[0] / max, s[1] / max, s[2] / max, s[3] / max]
+		}).collect();
+		
+		Ok(Self { width: width as usize, height: height as usize, srgb, pixels })
+	}
+	
+	/// Returns the image with linear color channels.
+	pub fn to_linear(&self) -> Self {
+		match self.srgb {
+			true => Self {
+				srgb:   false,
+				pixels: self.pixels.iter().map(|p| [srgb_to_linear(p[0]), srgb_to_linear(p[1]), srgb_to_linear(p[2]), p[3]]).collect(),
+				..*self
+			},
+			false => self.clone()
+		}
+	}
+	
+	/// Returns the image with sRGB encoded color channels.
+	pub fn to_srgb(&self) -> Self {
+		match self.srgb {
+			true  => self.clone(),
+			false => Self {
+				srgb:   true,
+				pixels: self.pixels.iter().map(|p| [linear_to_srgb(p[0]), linear_to_srgb(p[1]), linear_to_srgb(p[2]), p[3]]).collect(),
+				..*self
+			}
+		}
+	}
+	
+	/// Halves the size with a box filter. Filtering happens in linear space with premultiplied
+	/// alpha, odd sizes are handled by weighting the source pixels by their coverage.
+	pub fn downsample(&self) -> Self {
+		let linear = self.to_linear();
+		let (width, height) = ((self.width / 2).max(1), (self.height / 2).max(1));
+		let (xw, yw) = (weights(self.width, width), weights(self.height, height));
+		let mut pixels = Vec::with_capacity(width * height);
+		
+		for ys in &yw {
+			for xs in &xw {
+				let mut sum = [0f32; 4];
+				for (y, wy) in ys {
+					for (x, wx) in xs {
+						let p = linear.pixels[y * self.width + x];
+						let w = wx * wy;
+						sum = [sum[0] + p[0] * p[3] * w, sum[1] + p[1] * p[3] * w, sum[2] + p[2] * p[3] * w, sum[3] + p[3] * w];
+					}
+				}
+				
+				pixels.push(match sum[3] > 0.0 {
+					true  => [sum[0] / sum[3], sum[1] / sum[3], sum[2] / sum[3], sum[3]],
+					false => [0.0; 4]
+				});
+			}
+		}
+		
+		let image = Self { width, height, srgb: false, pixels };
+		if self.srgb { image.to_srgb() } else { image }
+	}
+	
+	/// Returns the full mip chain down to 1x1, starting with this image.
+	pub fn mip_chain(self) -> Vec<Self> {
+		let mut levels = vec![self];
+		while let Some(last) = levels.last().filter(|l| l.width > 1 || l.height > 1) {
+			levels.push(last.downsample());
+		}
+		levels
+	}
+	
+	/// Encodes the pixels in a format of `FORMATS`. Color channels are sRGB encoded for sRGB
+	/// formats and linear otherwise.
+	pub fn encode(&self, format: VkFormat) -> io::Result<Vec<u8>> {
+		let (channels, encoding) = layout(format)
+			.ok_or_else(|| Error::new(ErrorKind::InvalidInput, "unsupported texture format"))?;
+		let image = if encoding == Encoding::Srgb8 { self.to_srgb() } else { self.to_linear() };
+		let mut out = Vec::with_capacity(image.pixels.len() * channels.len() * 4);
+		
+		for pixel in &image.pixels {
+			for v in channels.iter().map(|c| pixel[*c]) {
+				match encoding {
+					Encoding::Unorm8 | Encoding::Srgb8 => out.push((v.clamp(0.0, 1.0) * 255.0).round() as u8),
+					Encoding::Snorm8   => out.push(((v.clamp(0.0, 1.0) * 2.0 - 1.0) * 127.0).round() as i8 as u8),
+					Encoding::Unorm16  => out.extend_from_slice(&((v.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes()),
+					Encoding::Sfloat16 => out.extend_from_slice(&f32_to_f16(v).to_le_bytes()),
+					Encoding::Sfloat32 => out.extend_from_slice(&v.to_le_bytes())
+				}
+			}
+		}
+		
+		Ok(out)
+	}
+}
+
+/// Returns the source pixels and their weights for each destination pixel.
+fn weights(src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+	let scale = src as f32 / dst as f32;
+	(0..dst).map(|i| {
+		let (start, end) = (i as f32 * scale, (i + 1) as f32 * scale);
+		(start.floor() as usize..(end.ceil() as usize).min(src))
+			.map(|j| (j, (end.min(j as f32 + 1.0) - start.max(j as f32)) / scale))
+			.collect()
+	}).collect()
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Options {
+	pub format:           VkFormat,
+	/// Treat the color channels of the input as linear, e.g. for normal maps.
+	pub linear:           bool,
+	pub mips:             bool,
+	pub supercompression: SupercompressionScheme
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Self {
+			format:           VK_FORMAT_R8G8B8A8_SRGB,
+			linear:           false,
+			mips:             true,
+			supercompression: SupercompressionScheme::None
+		}
+	}
+}
+
+/// Decodes an image and converts it to a texture.
+pub fn bake(data: &[u8], options: &Options) -> io::Result<Ktx2Texture> {
+	let mut image = Image::decode(data)?;
+	image.srgb &= !options.linear;
+	
+	let (width, height) = (image.width as u32, image.height as u32);
+	let levels = match options.mips {
+		true  => image.mip_chain(),
+		false => vec![image]
+	};
+	
+	let levels = levels.iter().map(|level| level.encode(options.format)).collect::<io::Result<Vec<_>>>()?;
+	let mut texture = Ktx2Texture::new(options.format as u32, width, height, levels)?;
+	texture.header.supercompression_scheme = options.supercompression;
+	texture.key_value_data.push(("KTXwriter".to_string(), b"texture-bake\0".to_vec()));
+	Ok(texture)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn half() {
+		assert_eq!(f32_to_f16(1.0), 0x3C00);
+		assert_eq!(f32_to_f16(-2.0), 0xC000);
+		assert_eq!(f32_to_f16(65504.0), 0x7BFF);
+		assert_eq!(f32_to_f16(1e6), 0x7C00);
+		assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+		assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3C00);
+		assert_eq!(f32_to_f16(f32::NAN) & 0x7E00, 0x7E00);
+	}
+	
+	#[test]
+	fn mip_chain() {
+		let image = Image { width: 5, height: 3, srgb: true, pixels: vec![[1.0, 0.0, 0.5, 1.0]; 15] };
+		let levels = image.mip_chain();
+		let sizes = levels.iter().map(|l| (l.width, l.height)).collect::<Vec<_>>();
+		assert_eq!(sizes, [(5, 3), (2, 1), (1, 1)]);
+		assert!(levels[2].pixels[0].iter().zip([1.0, 0.0, 0.5, 1.0]).all(|(a, b)| (a - b).abs() < 1e-4));
+	}
+	
+	#[test]
+	fn srgb_filtering() {
+		// black and white average to 50% linear intensity, not 50% sRGB
+		let image = Image { width: 2, height: 1, srgb: true, pixels: vec![[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]] };
+		let level = image.downsample();
+		assert_eq!(level.encode(VK_FORMAT_R8_SRGB).unwrap(), [188]);
+		assert_eq!(level.encode(VK_FORMAT_R8_UNORM).unwrap(), [128]);
+		
+		// transparent pixels do not bleed into the color
+		let image = Image { width: 2, height: 1, srgb: false, pixels: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 0.0]] };
+		assert_eq!(image.downsample().pixels, [[1.0, 0.0, 0.0, 0.5]]);
+	}
+	
+	#[test]
+	fn encode() {
+		let image = Image { width: 1, height: 1, srgb: false, pixels: vec![[1.0, 0.5, 0.0, 1.0]] };
+		assert_eq!(image.encode(VK_FORMAT_B8G8R8A8_UNORM).unwrap(), [0, 128, 255, 255]);
+		assert_eq!(image.encode(VK_FORMAT_R8G8B8A8_SNORM).unwrap(), [127, 0, 129, 127]);
+		assert_eq!(image.encode(VK_FORMAT_R16_UNORM).unwrap(), [0xFF, 0xFF]);
+		assert!(image.encode(VK_FORMAT_BC1_RGB_UNORM_BLOCK).is_err());
+		assert_eq!(parse_format("vk_format_r16g16b16a16_sfloat"), Some(VK_FORMAT_R16G16B16A16_SFLOAT));
+	}
+}
@@ -0,0 +1,113 @@
+// MIT License
+//
+// Copyright (c) 2019-2023 Tobias Pfeiffer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use {
+	texture_bake::*,
+	file_formats::ktx2::SupercompressionScheme,
+	std::{fs, path::Path, process::exit}
+};
+
+const HELP: &str = r#"
+texture-bake
+Usage: texture-bake <input file>... [options]
+
+Converts PNG and JPEG images to KTX2 textures. Unless an output file is specified, each texture is
+written next to its input file with a `.ktx2` extension.
+
+Options:
+--help, -h           - display this help page
+--out=<output file>,
+-o=<output file>     - specify the output file, only valid with a single input file
+--format=<format>,
+-f=<format>          - the texture format, e.g. `R8G8B8A8_UNORM`, defaults to `R8G8B8A8_SRGB`
+--formats            - list all supported formats
+--linear             - the input contains linear data, e.g. normal maps, no sRGB conversion is applied
+--no-mips            - only store the base level instead of a full mip chain
+--zstd               - supercompress the levels with zstd
+--zlib               - supercompress the levels with zlib
+"#;
+
+fn main() {
+	let mut files = Vec::new();
+	let mut file_out = None;
+	let mut options = Options::default();
+	
+	for arg in std::env::args().skip(1) {
+		if arg.starts_with('-') {
+			if arg.starts_with("-o=") || arg.starts_with("--out=") {
+				file_out = Some(arg.trim_start_matches("-o=")
+					.trim_start_matches("--out=").to_string());
+			} else if arg.starts_with("-f=") || arg.starts_with("--format=") {
+				let name = arg.trim_start_matches("-f=").trim_start_matches("--format=");
+				options.format = parse_format(name).unwrap_or_else(|| {
+					eprintln!("unsupported format: {}, see `--formats`", name);
+					exit(1);
+				});
+			} else if arg == "--formats" {
+				FORMATS.iter().for_each(|(name, _)| println!("{}", name));
+				return;
+			} else if arg == "--linear" {
+				options.linear = true;
+			} else if arg == "--no-mips" {
+				options.mips = false;
+			} else if arg == "--zstd" {
+				options.supercompression = SupercompressionScheme::Zstd;
+			} else if arg == "--zlib" {
+				options.supercompression = SupercompressionScheme::Zlib;
+			} else if arg == "-h" || arg == "--help" {
+				println!("{}", HELP);
+				return;
+			} else {
+				println!("ignored unknown option: {}", arg)
+			}
+		} else {
+			files.push(arg);
+		}
+	}
+	
+	if files.is_empty() || (file_out.is_some() && files.len() > 1) {
+		eprintln!("expected a single input file with `--out`, or at least one input file, see `--help`");
+		exit(1);
+	}
+	
+	let mut failed = false;
+	for file in &files {
+		let out = file_out.clone().unwrap_or_else(|| Path::new(file)
+			.with_extension("ktx2").to_string_lossy().into_owned());
+		
+		let result = fs::read(file)
+			.and_then(|data| bake(&data, &options))
+			.and_then(|texture| texture.write(std::io::BufWriter::new(fs::File::create(&out)?)));
+		
+		match result {
+			Ok(()) => println!("baked `{}` -> `{}`", file, out),
+			Err(e) => {
+				eprintln!("failed to bake `{}`: {}", file, e);
+				failed = true;
+			}
+		}
+	}
+	
+	if failed {
+		exit(1);
+	}
+}